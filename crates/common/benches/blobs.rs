@@ -0,0 +1,78 @@
+//! Blob put/get throughput for each blob store backend
+
+use std::future::Future;
+use std::pin::Pin;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use tempfile::TempDir;
+use tokio::runtime::Runtime;
+
+use common::peer::BlobsStore;
+
+const SIZES: &[usize] = &[4 * 1024, 1024 * 1024];
+
+type Open = fn(&TempDir) -> Pin<Box<dyn Future<Output = BlobsStore> + '_>>;
+
+/// Backends by name. S3 needs a live endpoint and isn't covered.
+const BACKENDS: &[(&str, Open)] = &[
+    ("legacy_memory", |_| {
+        Box::pin(async { BlobsStore::legacy_memory().await.unwrap() })
+    }),
+    ("legacy_fs", |dir| {
+        Box::pin(async { BlobsStore::legacy_fs(dir.path()).await.unwrap() })
+    }),
+    ("memory", |_| {
+        Box::pin(async { BlobsStore::memory().await.unwrap() })
+    }),
+    ("fs", |dir| {
+        Box::pin(async { BlobsStore::fs(dir.path()).await.unwrap() })
+    }),
+];
+
+/// Distinct contents per call, so puts aren't deduplicated
+fn blob(size: usize, seed: u64) -> Vec<u8> {
+    let mut state = seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1;
+    (0..size)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect()
+}
+
+fn bench_blobs(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    for (name, open) in BACKENDS {
+        let dir = TempDir::new().unwrap();
+        let blobs = runtime.block_on(open(&dir));
+
+        let mut group = c.benchmark_group(format!("blobs/{}", name));
+        for &size in SIZES {
+            group.throughput(Throughput::Bytes(size as u64));
+
+            let mut seed = 0;
+            group.bench_function(BenchmarkId::new("put", size), |b| {
+                b.to_async(&runtime).iter_batched(
+                    || {
+                        seed += 1;
+                        blob(size, seed)
+                    },
+                    |data| async { blobs.put(data).await.unwrap() },
+                    criterion::BatchSize::SmallInput,
+                )
+            });
+
+            let hash = runtime.block_on(blobs.put(blob(size, u64::MAX))).unwrap();
+            group.bench_function(BenchmarkId::new("get", size), |b| {
+                b.to_async(&runtime)
+                    .iter(|| async { blobs.get(&hash).await.unwrap() })
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_blobs);
+criterion_main!(benches);
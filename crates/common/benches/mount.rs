@@ -0,0 +1,114 @@
+//! Mount save/load with a growing number of files, and read latency while
+//! large files are being uploaded
+
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use tempfile::TempDir;
+use tokio::runtime::{Builder, Runtime};
+use uuid::Uuid;
+
+use common::crypto::SecretKey;
+use common::mount::Mount;
+use common::peer::BlobsStore;
+
+const FILE_COUNTS: &[usize] = &[10, 100, 1000];
+
+const UPLOADERS: usize = 4;
+/// Large enough to be chunked, so re-uploading it reuses the same blobs
+const UPLOAD_SIZE: usize = 8 * 1024 * 1024;
+
+/// A mount holding `files` small files spread over ten directories
+async fn mount_with_files(blobs: &BlobsStore, key: &SecretKey, files: usize) -> Mount {
+    let mut mount = Mount::init(Uuid::new_v4(), "bench".to_string(), key, blobs)
+        .await
+        .unwrap();
+    for i in 0..files {
+        let path = PathBuf::from(format!("/dir-{}/file-{}.txt", i % 10, i));
+        let data = format!("contents of file {}", i).into_bytes();
+        mount.add(&path, Cursor::new(data)).await.unwrap();
+    }
+    mount
+}
+
+fn bench_mount(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let dir = TempDir::new().unwrap();
+    let blobs = runtime.block_on(BlobsStore::fs(dir.path())).unwrap();
+    let key = SecretKey::generate();
+
+    let mut group = c.benchmark_group("mount");
+    group.sample_size(20);
+    for &files in FILE_COUNTS {
+        let mount = runtime.block_on(mount_with_files(&blobs, &key, files));
+
+        group.bench_function(BenchmarkId::new("save", files), |b| {
+            b.to_async(&runtime)
+                .iter(|| async { mount.save(&blobs, false).await.unwrap() })
+        });
+
+        let (link, _, _) = runtime.block_on(mount.save(&blobs, false)).unwrap();
+        group.bench_function(BenchmarkId::new("load", files), |b| {
+            b.to_async(&runtime)
+                .iter(|| async { Mount::load(&link, &key, &blobs).await.unwrap() })
+        });
+
+        group.bench_function(BenchmarkId::new("ls_deep", files), |b| {
+            b.to_async(&runtime)
+                .iter(|| async { mount.ls_deep(&PathBuf::from("/")).await.unwrap() })
+        });
+    }
+    group.finish();
+}
+
+/// Time to read a small file while other tasks keep uploading large ones.
+/// Encryption and compression left on the async runtime show up here as
+/// stalls.
+fn bench_cat_during_uploads(c: &mut Criterion) {
+    let runtime = Builder::new_multi_thread()
+        .worker_threads(2)
+        .enable_all()
+        .build()
+        .unwrap();
+    let blobs = runtime.block_on(BlobsStore::memory()).unwrap();
+    let key = SecretKey::generate();
+    let mount = runtime.block_on(mount_with_files(&blobs, &key, 10));
+    let path = PathBuf::from("/dir-0/file-0.txt");
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let mut uploads = Vec::new();
+    for uploader in 0..UPLOADERS {
+        let (blobs, key, stop) = (blobs.clone(), key.clone(), stop.clone());
+        uploads.push(runtime.spawn(async move {
+            let mut mount = Mount::init(Uuid::new_v4(), "uploads".to_string(), &key, &blobs)
+                .await
+                .unwrap();
+            let data = vec![uploader as u8; UPLOAD_SIZE];
+            while !stop.load(Ordering::Relaxed) {
+                mount
+                    .add(&PathBuf::from("/large.bin"), Cursor::new(data.clone()))
+                    .await
+                    .unwrap();
+            }
+        }));
+    }
+
+    let mut group = c.benchmark_group("mount");
+    group.sample_size(20);
+    group.bench_function("cat_during_uploads", |b| {
+        b.to_async(&runtime)
+            .iter(|| async { mount.cat(&path).await.unwrap() })
+    });
+    group.finish();
+
+    stop.store(true, Ordering::Relaxed);
+    for upload in uploads {
+        runtime.block_on(upload).unwrap();
+    }
+}
+
+criterion_group!(benches, bench_mount, bench_cat_during_uploads);
+criterion_main!(benches);
@@ -0,0 +1,234 @@
+//! Registry of the algorithms bucket encryption can use
+//!
+//! Every [`Secret`](super::Secret) names the [`Cipher`] it encrypts with, and
+//! every [`SecretShare`](super::SecretShare) names the [`KeyWrap`] it was
+//! wrapped with, by a one-byte identifier. Identifiers are never reused, so a
+//! peer can always tell which algorithm produced a ciphertext or share, and
+//! data written under an older algorithm stays readable as new ones are added.
+//!
+//! The first algorithm of each kind predates the identifiers: data it
+//! produced carries none, and it's still written that way so that peers which
+//! don't know about identifiers can read it. Other algorithms always write
+//! their identifier.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce, XChaCha20Poly1305, XNonce};
+
+use super::secret::NONCE_SIZE;
+
+/// An algorithm name that isn't in the registry
+#[derive(Debug, thiserror::Error)]
+#[error("unknown algorithm: {0}")]
+pub struct UnknownAlgorithm(pub String);
+
+/// An AEAD cipher for content encryption
+///
+/// All ciphers take a 256-bit key and a 16-byte authentication tag; they
+/// differ in nonce size.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Cipher {
+    /// ChaCha20-Poly1305 with a 96-bit nonce; the original cipher, written
+    /// without an identifier
+    #[default]
+    ChaCha20Poly1305,
+    /// XChaCha20-Poly1305 with a 192-bit nonce, safe to pick at random for
+    /// any number of messages
+    XChaCha20Poly1305,
+}
+
+impl Cipher {
+    /// All registered ciphers
+    pub const ALL: [Cipher; 2] = [Cipher::ChaCha20Poly1305, Cipher::XChaCha20Poly1305];
+
+    /// The identifier written alongside data encrypted with this cipher
+    pub fn id(self) -> u8 {
+        match self {
+            Cipher::ChaCha20Poly1305 => 0,
+            Cipher::XChaCha20Poly1305 => 1,
+        }
+    }
+
+    /// Look up a cipher by its identifier
+    pub fn from_id(id: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|cipher| cipher.id() == id)
+    }
+
+    /// The cipher's name, as used in configuration
+    pub fn name(self) -> &'static str {
+        match self {
+            Cipher::ChaCha20Poly1305 => "chacha20-poly1305",
+            Cipher::XChaCha20Poly1305 => "xchacha20-poly1305",
+        }
+    }
+
+    /// Whether data encrypted with this cipher carries no identifier
+    pub fn is_legacy(self) -> bool {
+        self == Cipher::default()
+    }
+
+    /// Size of the cipher's nonce in bytes
+    pub fn nonce_size(self) -> usize {
+        match self {
+            Cipher::ChaCha20Poly1305 => NONCE_SIZE,
+            Cipher::XChaCha20Poly1305 => 24,
+        }
+    }
+
+    /// Encrypt and authenticate `plaintext`; `nonce` must be
+    /// [`Cipher::nonce_size`] bytes
+    pub(crate) fn seal(
+        self,
+        key: &[u8; 32],
+        nonce: &[u8],
+        plaintext: &[u8],
+    ) -> Result<Vec<u8>, anyhow::Error> {
+        let key = Key::from_slice(key);
+        let result = match self {
+            Cipher::ChaCha20Poly1305 => {
+                ChaCha20Poly1305::new(key).encrypt(Nonce::from_slice(nonce), plaintext)
+            }
+            Cipher::XChaCha20Poly1305 => {
+                XChaCha20Poly1305::new(key).encrypt(XNonce::from_slice(nonce), plaintext)
+            }
+        };
+        result.map_err(|_| anyhow::anyhow!("encrypt error"))
+    }
+
+    /// Authenticate and decrypt `ciphertext`; `nonce` must be
+    /// [`Cipher::nonce_size`] bytes
+    pub(crate) fn open(
+        self,
+        key: &[u8; 32],
+        nonce: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, anyhow::Error> {
+        let key = Key::from_slice(key);
+        let result = match self {
+            Cipher::ChaCha20Poly1305 => {
+                ChaCha20Poly1305::new(key).decrypt(Nonce::from_slice(nonce), ciphertext)
+            }
+            Cipher::XChaCha20Poly1305 => {
+                XChaCha20Poly1305::new(key).decrypt(XNonce::from_slice(nonce), ciphertext)
+            }
+        };
+        result.map_err(|_| anyhow::anyhow!("decrypt error"))
+    }
+}
+
+impl fmt::Display for Cipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Cipher {
+    type Err = UnknownAlgorithm;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|cipher| cipher.name() == s)
+            .ok_or_else(|| UnknownAlgorithm(s.to_string()))
+    }
+}
+
+/// A scheme for wrapping a secret to a peer's public key
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyWrap {
+    /// X25519 ECDH with an ephemeral key, then AES-KW under the shared
+    /// secret; the original scheme, written without an identifier
+    #[default]
+    X25519AesKw,
+    /// X25519 ECDH and ML-KEM-768 encapsulation to the recipient, then
+    /// AES-KW under a key derived from both shared secrets
+    X25519MlKem768,
+}
+
+impl KeyWrap {
+    /// All registered key wrapping schemes
+    pub const ALL: [KeyWrap; 2] = [KeyWrap::X25519AesKw, KeyWrap::X25519MlKem768];
+
+    /// The identifier written at the start of shares wrapped with this scheme
+    pub fn id(self) -> u8 {
+        match self {
+            KeyWrap::X25519AesKw => 0,
+            KeyWrap::X25519MlKem768 => 1,
+        }
+    }
+
+    /// Look up a key wrapping scheme by its identifier
+    pub fn from_id(id: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|wrap| wrap.id() == id)
+    }
+
+    /// The scheme's name, as used in configuration
+    pub fn name(self) -> &'static str {
+        match self {
+            KeyWrap::X25519AesKw => "x25519-aes-kw",
+            KeyWrap::X25519MlKem768 => "x25519-ml-kem-768",
+        }
+    }
+
+    /// Whether shares wrapped with this scheme may carry no identifier
+    pub fn is_legacy(self) -> bool {
+        self == KeyWrap::default()
+    }
+
+    /// Whether shares wrapped with this scheme resist a quantum adversary
+    pub fn is_post_quantum(self) -> bool {
+        match self {
+            KeyWrap::X25519AesKw => false,
+            KeyWrap::X25519MlKem768 => true,
+        }
+    }
+}
+
+impl fmt::Display for KeyWrap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for KeyWrap {
+    type Err = UnknownAlgorithm;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|wrap| wrap.name() == s)
+            .ok_or_else(|| UnknownAlgorithm(s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ids_and_names_roundtrip() {
+        for cipher in Cipher::ALL {
+            assert_eq!(Cipher::from_id(cipher.id()), Some(cipher));
+            assert_eq!(cipher.name().parse::<Cipher>().unwrap(), cipher);
+        }
+        for wrap in KeyWrap::ALL {
+            assert_eq!(KeyWrap::from_id(wrap.id()), Some(wrap));
+            assert_eq!(wrap.name().parse::<KeyWrap>().unwrap(), wrap);
+        }
+        assert_eq!(Cipher::from_id(u8::MAX), None);
+        assert!("aes-256-gcm".parse::<Cipher>().is_err());
+    }
+
+    #[test]
+    fn test_seal_open() {
+        let key = [7u8; 32];
+        for cipher in Cipher::ALL {
+            let nonce = vec![1u8; cipher.nonce_size()];
+            let sealed = cipher.seal(&key, &nonce, b"hello").unwrap();
+            assert_eq!(cipher.open(&key, &nonce, &sealed).unwrap(), b"hello");
+            assert!(cipher.open(&[8u8; 32], &nonce, &sealed).is_err());
+        }
+    }
+}
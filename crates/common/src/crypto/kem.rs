@@ -0,0 +1,216 @@
+//! ML-KEM-768 keys for post-quantum key sharing
+//!
+//! A peer's ML-KEM keypair is derived from its Ed25519 identity, so there is
+//! no second key to store or back up: whoever holds the identity can
+//! decapsulate shares wrapped to it. The encapsulation key is too large to
+//! derive from a public key alone, so peers hand it out on request (see
+//! `peer::share_keys`), over connections that authenticate the identity it
+//! belongs to.
+//!
+//! Shares combine ML-KEM with X25519 (see
+//! [`KeyWrap::X25519MlKem768`](super::KeyWrap::X25519MlKem768)), so they stay
+//! safe as long as either holds.
+
+use std::convert::TryFrom;
+
+use ml_kem::array::Array;
+use ml_kem::kem::Decapsulate;
+use ml_kem::{EncapsulateDeterministic, EncodedSizeUser, KemCore, MlKem768, B32};
+use serde::{Deserialize, Serialize};
+
+use super::keys::{KeyError, SecretKey};
+
+/// Size of an ML-KEM-768 encapsulation key in bytes
+pub const KEM_PUBLIC_KEY_SIZE: usize = 1184;
+/// Size of an ML-KEM-768 ciphertext in bytes
+pub const KEM_CIPHERTEXT_SIZE: usize = 1088;
+/// Size of the secret ML-KEM-768 agrees on, in bytes
+pub const KEM_SHARED_SECRET_SIZE: usize = 32;
+
+/// BLAKE3 contexts deriving a peer's ML-KEM seeds from its identity
+const KEM_D_CONTEXT: &str = "jax 2025 ml-kem-768 seed d";
+const KEM_Z_CONTEXT: &str = "jax 2025 ml-kem-768 seed z";
+
+type EncapsulationKey = <MlKem768 as KemCore>::EncapsulationKey;
+type DecapsulationKey = <MlKem768 as KemCore>::DecapsulationKey;
+
+/// A peer's ML-KEM-768 encapsulation key, to wrap shares to
+///
+/// Serializes as bytes, like [`SecretShare`](super::SecretShare).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct KemPublicKey(Vec<u8>);
+
+impl KemPublicKey {
+    /// The encapsulation key of a peer's identity
+    pub fn of(secret_key: &SecretKey) -> Self {
+        let (_, encapsulation_key) = keypair(secret_key);
+        KemPublicKey(encapsulation_key.as_bytes().to_vec())
+    }
+
+    /// Parse an encapsulation key from a hexadecimal string
+    pub fn from_hex(hex: &str) -> Result<Self, KeyError> {
+        let bytes = hex::decode(hex).map_err(|_| anyhow::anyhow!("kem key hex decode error"))?;
+        Self::try_from(bytes.as_slice())
+    }
+
+    /// Convert the encapsulation key to a hexadecimal string
+    pub fn to_hex(&self) -> String {
+        hex::encode(&self.0)
+    }
+
+    /// Get a reference to the raw encapsulation key bytes
+    pub fn bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Agree on a fresh secret with the key's holder
+    ///
+    /// Returns the ciphertext to send the holder and the secret, which the
+    /// holder recovers with [`decapsulate`].
+    pub(crate) fn encapsulate(&self) -> Result<(Vec<u8>, [u8; KEM_SHARED_SECRET_SIZE]), KeyError> {
+        let encoded = Array::try_from(self.0.as_slice())
+            .map_err(|_| anyhow::anyhow!("invalid kem key size"))?;
+        let encapsulation_key = EncapsulationKey::from_bytes(&encoded);
+
+        let mut m = [0u8; 32];
+        getrandom::getrandom(&mut m)
+            .map_err(|e| anyhow::anyhow!("failed to generate kem randomness: {}", e))?;
+        let (ciphertext, shared) = encapsulation_key
+            .encapsulate_deterministic(&B32::from(m))
+            .map_err(|_| anyhow::anyhow!("kem encapsulation error"))?;
+
+        let mut secret = [0u8; KEM_SHARED_SECRET_SIZE];
+        secret.copy_from_slice(shared.as_slice());
+        Ok((ciphertext.as_slice().to_vec(), secret))
+    }
+}
+
+impl TryFrom<&[u8]> for KemPublicKey {
+    type Error = KeyError;
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if bytes.len() != KEM_PUBLIC_KEY_SIZE {
+            return Err(anyhow::anyhow!(
+                "invalid kem key size, expected {}, got {}",
+                KEM_PUBLIC_KEY_SIZE,
+                bytes.len()
+            )
+            .into());
+        }
+        Ok(KemPublicKey(bytes.to_vec()))
+    }
+}
+
+impl Serialize for KemPublicKey {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for KemPublicKey {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::{Error, SeqAccess, Visitor};
+        use std::fmt;
+
+        struct KemKeyVisitor;
+
+        impl<'de> Visitor<'de> for KemKeyVisitor {
+            type Value = KemPublicKey;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a byte array or sequence of an ML-KEM-768 key")
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                KemPublicKey::try_from(v).map_err(E::custom)
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut bytes = Vec::with_capacity(KEM_PUBLIC_KEY_SIZE);
+                while let Some(byte) = seq.next_element::<u8>()? {
+                    bytes.push(byte);
+                }
+                KemPublicKey::try_from(bytes.as_slice()).map_err(A::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_byte_buf(KemKeyVisitor)
+    }
+}
+
+/// The ML-KEM keypair of a peer's identity
+fn keypair(secret_key: &SecretKey) -> (DecapsulationKey, EncapsulationKey) {
+    let seed = secret_key.to_bytes();
+    let d = B32::from(blake3::derive_key(KEM_D_CONTEXT, &seed));
+    let z = B32::from(blake3::derive_key(KEM_Z_CONTEXT, &seed));
+    MlKem768::generate_deterministic(&d, &z)
+}
+
+/// Recover the secret a ciphertext from [`KemPublicKey::encapsulate`]
+/// agreed on with `secret_key`'s encapsulation key
+///
+/// ML-KEM doesn't fail on a ciphertext meant for another key; it yields an
+/// unrelated secret instead, which the caller's authenticated unwrap rejects.
+pub(crate) fn decapsulate(
+    secret_key: &SecretKey,
+    ciphertext: &[u8],
+) -> Result<[u8; KEM_SHARED_SECRET_SIZE], KeyError> {
+    let ciphertext =
+        Array::try_from(ciphertext).map_err(|_| anyhow::anyhow!("invalid kem ciphertext size"))?;
+    let (decapsulation_key, _) = keypair(secret_key);
+    let shared = decapsulation_key
+        .decapsulate(&ciphertext)
+        .map_err(|_| anyhow::anyhow!("kem decapsulation error"))?;
+
+    let mut secret = [0u8; KEM_SHARED_SECRET_SIZE];
+    secret.copy_from_slice(shared.as_slice());
+    Ok(secret)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_kem_key_is_derived_from_identity() {
+        let secret_key = SecretKey::generate();
+        let kem_key = KemPublicKey::of(&secret_key);
+        assert_eq!(kem_key.bytes().len(), KEM_PUBLIC_KEY_SIZE);
+        assert_eq!(KemPublicKey::of(&secret_key), kem_key);
+        assert_ne!(KemPublicKey::of(&SecretKey::generate()), kem_key);
+        assert_eq!(KemPublicKey::from_hex(&kem_key.to_hex()).unwrap(), kem_key);
+    }
+
+    #[test]
+    fn test_encapsulate_decapsulate() {
+        let secret_key = SecretKey::generate();
+        let (ciphertext, shared) = KemPublicKey::of(&secret_key).encapsulate().unwrap();
+        assert_eq!(ciphertext.len(), KEM_CIPHERTEXT_SIZE);
+        assert_eq!(decapsulate(&secret_key, &ciphertext).unwrap(), shared);
+
+        // Another identity agrees on something else
+        let other = decapsulate(&SecretKey::generate(), &ciphertext).unwrap();
+        assert_ne!(other, shared);
+        assert!(decapsulate(&secret_key, &ciphertext[1..]).is_err());
+    }
+
+    #[test]
+    fn test_kem_key_serde() {
+        let kem_key = KemPublicKey::of(&SecretKey::generate());
+        let encoded = serde_ipld_dagcbor::to_vec(&kem_key).unwrap();
+        let decoded: KemPublicKey = serde_ipld_dagcbor::from_slice(&encoded).unwrap();
+        assert_eq!(decoded, kem_key);
+        assert!(KemPublicKey::try_from(&kem_key.bytes()[1..]).is_err());
+    }
+}
@@ -0,0 +1,251 @@
+//! Peer identities derived from a mnemonic phrase
+//!
+//! A [`Mnemonic`] is a BIP-39 phrase (English wordlist) that can be written
+//! down instead of backing up a key file. The phrase and an optional
+//! passphrase are stretched into a seed as BIP-39 specifies, and identities
+//! are derived from the seed along a [`DerivationPath`] with SLIP-0010, the
+//! Ed25519 flavor of BIP-32. Ed25519 only supports hardened derivation, so
+//! every index of a path is hardened.
+//!
+//! Each device gets its own identity from the same phrase, at
+//! `m/44'/7384'/<device>'` (see [`DerivationPath::device`]), so one phrase
+//! recovers every device without any two of them sharing a key.
+
+use std::fmt;
+use std::str::FromStr;
+
+use hmac::{Hmac, Mac};
+use sha2::Sha512;
+
+use super::keys::{SecretKey, PRIVATE_KEY_SIZE};
+
+/// Number of words in generated phrases (256 bits of entropy)
+pub const MNEMONIC_WORDS: usize = 24;
+
+/// Purpose and coin type of the paths devices derive their identities at
+const PURPOSE: u32 = 44;
+const COIN_TYPE: u32 = 7384;
+
+/// Marks a hardened index
+const HARDENED: u32 = 0x8000_0000;
+/// HMAC key deriving the master key from a seed, per SLIP-0010
+const ED25519_CURVE: &[u8] = b"ed25519 seed";
+
+type HmacSha512 = Hmac<Sha512>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum MnemonicError {
+    #[error("invalid mnemonic: {0}")]
+    InvalidPhrase(String),
+    #[error("invalid derivation path: {0}")]
+    InvalidPath(String),
+}
+
+/// A BIP-39 mnemonic phrase
+#[derive(Clone, PartialEq, Eq)]
+pub struct Mnemonic(bip39::Mnemonic);
+
+impl Mnemonic {
+    /// Generate a new phrase of [`MNEMONIC_WORDS`] words
+    pub fn generate() -> Result<Self, MnemonicError> {
+        let mut entropy = [0u8; 32];
+        getrandom::getrandom(&mut entropy)
+            .map_err(|e| MnemonicError::InvalidPhrase(format!("no randomness: {}", e)))?;
+        bip39::Mnemonic::from_entropy(&entropy)
+            .map(Mnemonic)
+            .map_err(|e| MnemonicError::InvalidPhrase(e.to_string()))
+    }
+
+    /// Parse a phrase, ignoring case and extra whitespace. Fails on unknown
+    /// words or a bad checksum.
+    pub fn parse(phrase: &str) -> Result<Self, MnemonicError> {
+        let normalized = phrase
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>()
+            .join(" ");
+        bip39::Mnemonic::parse_normalized(&normalized)
+            .map(Mnemonic)
+            .map_err(|e| MnemonicError::InvalidPhrase(e.to_string()))
+    }
+
+    /// The words of the phrase, separated by single spaces
+    pub fn phrase(&self) -> String {
+        self.0.to_string()
+    }
+
+    /// Derive the identity at `path` from the phrase and `passphrase`, which
+    /// may be empty
+    pub fn derive_key(&self, passphrase: &str, path: &DerivationPath) -> SecretKey {
+        let seed = self.0.to_seed_normalized(passphrase);
+        derive_slip10(&seed, path)
+    }
+}
+
+// Keep phrases out of logs
+impl fmt::Debug for Mnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Mnemonic(..)")
+    }
+}
+
+/// A path of hardened indices from the seed to an identity, written like
+/// `m/44'/7384'/0'`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivationPath(Vec<u32>);
+
+impl DerivationPath {
+    /// The path of a device's identity
+    pub fn device(device: u32) -> Result<Self, MnemonicError> {
+        if device >= HARDENED {
+            return Err(MnemonicError::InvalidPath(format!(
+                "device {} is out of range",
+                device
+            )));
+        }
+        Ok(DerivationPath(vec![PURPOSE, COIN_TYPE, device]))
+    }
+
+    /// The indices of the path, without the hardened bit
+    pub fn indices(&self) -> &[u32] {
+        &self.0
+    }
+}
+
+impl Default for DerivationPath {
+    /// The path of the first device
+    fn default() -> Self {
+        DerivationPath(vec![PURPOSE, COIN_TYPE, 0])
+    }
+}
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("m")?;
+        for index in &self.0 {
+            write!(f, "/{}'", index)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = MnemonicError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| MnemonicError::InvalidPath(format!("{}: {}", s, reason));
+        let mut parts = s.split('/');
+        if parts.next() != Some("m") {
+            return Err(invalid("must start with m"));
+        }
+        let mut indices = Vec::new();
+        for part in parts {
+            let index = part
+                .strip_suffix('\'')
+                .or_else(|| part.strip_suffix('h'))
+                .ok_or_else(|| invalid("every index must be hardened, like 0'"))?;
+            let index: u32 = index.parse().map_err(|_| invalid("bad index"))?;
+            if index >= HARDENED {
+                return Err(invalid("index out of range"));
+            }
+            indices.push(index);
+        }
+        Ok(DerivationPath(indices))
+    }
+}
+
+/// SLIP-0010 Ed25519 derivation of the key at `path` from a seed
+fn derive_slip10(seed: &[u8], path: &DerivationPath) -> SecretKey {
+    let (mut key, mut chain_code) = hmac_split(ED25519_CURVE, &[seed]);
+    for index in path.indices() {
+        let index = (index | HARDENED).to_be_bytes();
+        (key, chain_code) = hmac_split(&chain_code, &[&[0u8][..], &key[..], &index[..]]);
+    }
+    SecretKey::from(key)
+}
+
+/// HMAC-SHA512 of `data` under `key`, split into a key and a chain code
+fn hmac_split(key: &[u8], data: &[&[u8]]) -> ([u8; PRIVATE_KEY_SIZE], [u8; 32]) {
+    let mut mac = HmacSha512::new_from_slice(key).expect("HMAC takes keys of any size");
+    for part in data {
+        mac.update(part);
+    }
+    let output = mac.finalize().into_bytes();
+    let mut left = [0u8; PRIVATE_KEY_SIZE];
+    let mut right = [0u8; 32];
+    left.copy_from_slice(&output[..32]);
+    right.copy_from_slice(&output[32..]);
+    (left, right)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ABANDON: &str = "abandon abandon abandon abandon abandon abandon \
+                           abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn test_bip39_seed_vector() {
+        let mnemonic = Mnemonic::parse(ABANDON).unwrap();
+        assert_eq!(
+            hex::encode(mnemonic.0.to_seed_normalized("TREZOR")),
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+        );
+    }
+
+    #[test]
+    fn test_slip10_vector() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let master = derive_slip10(&seed, &"m".parse().unwrap());
+        assert_eq!(
+            master.to_hex(),
+            "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7"
+        );
+        let child = derive_slip10(&seed, &"m/0'".parse().unwrap());
+        assert_eq!(
+            child.to_hex(),
+            "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3"
+        );
+    }
+
+    #[test]
+    fn test_devices_get_distinct_recoverable_keys() {
+        let mnemonic = Mnemonic::generate().unwrap();
+        assert_eq!(mnemonic.phrase().split(' ').count(), MNEMONIC_WORDS);
+
+        let first = mnemonic.derive_key("", &DerivationPath::device(0).unwrap());
+        let second = mnemonic.derive_key("", &DerivationPath::device(1).unwrap());
+        assert_ne!(first.public(), second.public());
+
+        // The written-down phrase recovers the same identity
+        let recovered = Mnemonic::parse(&mnemonic.phrase().to_uppercase()).unwrap();
+        assert_eq!(
+            recovered
+                .derive_key("", &DerivationPath::default())
+                .public(),
+            first.public()
+        );
+        // A passphrase gives another identity
+        assert_ne!(
+            mnemonic
+                .derive_key("extra", &DerivationPath::default())
+                .public(),
+            first.public()
+        );
+    }
+
+    #[test]
+    fn test_invalid_phrases_and_paths() {
+        assert!(Mnemonic::parse("abandon abandon abandon").is_err());
+        // Bad checksum
+        assert!(Mnemonic::parse(&ABANDON.replace("about", "abandon")).is_err());
+
+        let path: DerivationPath = "m/44'/7384'/3h".parse().unwrap();
+        assert_eq!(path, DerivationPath::device(3).unwrap());
+        assert_eq!(path.to_string(), "m/44'/7384'/3'");
+        assert!("m/44'/0".parse::<DerivationPath>().is_err());
+        assert!("44'/0'".parse::<DerivationPath>().is_err());
+        assert!(DerivationPath::device(HARDENED).is_err());
+    }
+}
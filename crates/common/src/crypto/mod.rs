@@ -3,7 +3,7 @@
 //! This module provides the cryptographic foundation for JaxBucket's security model:
 //!
 //! - **Identity & Authentication**: Ed25519 keypairs for peer identity
-//! - **Encryption**: AES-256-GCM for content encryption with per-item secrets
+//! - **Encryption**: ChaCha20-Poly1305 for content encryption with per-item secrets
 //! - **Key Sharing**: ECDH-based key sharing using X25519 curve conversion
 //!
 //! # Security Model
@@ -11,9 +11,11 @@
 //! ## Peer Identity
 //! Each peer has an Ed25519 keypair (`SecretKey`/`PublicKey`) that serves as their
 //! identity in the network. This same keypair is used for key sharing.
+//! Identities can be derived from a written-down [`Mnemonic`] instead of
+//! generated, with one [`DerivationPath`] per device.
 //!
 //! ## Content Encryption
-//! Every encrypted item (nodes, data) has its own AES-256-GCM `Secret` key. This provides:
+//! Every encrypted item (nodes, data) has its own `Secret` key. This provides:
 //! - Content-addressed storage (hashes are stable)
 //! - Per-item encryption (no shared secrets across items)
 //! - Forward secrecy (rotating keys doesn't require re-encryption)
@@ -31,12 +33,30 @@
 //! 2. Converting keys to X25519
 //! 3. Performing ECDH with their private key
 //! 4. Using AES-KW to unwrap the secret
+//!
+//! Shares can also be hybrid: wrapped under both X25519 and an ML-KEM-768
+//! key derived from the recipient's identity (see [`KemPublicKey`]), so that shares
+//! recorded today can't be opened by a future quantum computer.
+//!
+//! ## Algorithm Agility
+//! Secrets, shares, and ciphertexts name the algorithms they use by the
+//! identifiers in the [`Cipher`] and [`KeyWrap`] registries, so new algorithms
+//! can be added alongside the original ChaCha20-Poly1305 and X25519 + AES-KW
+//! without making existing buckets unreadable. Data of the original
+//! algorithms carries no identifiers and is written exactly as before.
 
+mod algorithm;
+mod kem;
 mod keys;
+mod mnemonic;
 mod secret;
 mod secret_share;
+pub mod shamir;
 
+pub use algorithm::{Cipher, KeyWrap, UnknownAlgorithm};
 pub use ed25519_dalek::Signature;
+pub use kem::KemPublicKey;
 pub use keys::{PublicKey, SecretKey};
-pub use secret::{Secret, SecretError, BLAKE3_HASH_SIZE};
+pub use mnemonic::{DerivationPath, Mnemonic, MnemonicError, MNEMONIC_WORDS};
+pub use secret::{offload, Secret, SecretError, BLAKE3_HASH_SIZE, OFFLOAD_THRESHOLD};
 pub use secret_share::{SecretShare, SecretShareError};
@@ -1,23 +1,32 @@
-//! Content encryption using ChaCha20-Poly1305
+//! Content encryption using ChaCha20-Poly1305 and its successors
 //!
 //! This module provides symmetric encryption for bucket data. Each encrypted item
 //! (nodes, files) has its own unique `Secret` key, providing:
 //! - **Content-addressed storage**: Encrypted data can be hashed deterministically
 //! - **Per-item encryption**: Compromising one key doesn't affect other items
 //! - **Efficient key rotation**: Can re-encrypt specific items without touching others
+//!
+//! Each `Secret` names the [`Cipher`] it encrypts with. Ciphertexts of the
+//! original ChaCha20-Poly1305 cipher have no header; those of any other
+//! cipher start with [`CIPHER_HEADER_MAGIC`] and the cipher's identifier, so
+//! decryption picks the cipher from the data itself.
 
+use std::fmt;
 use std::io::Read;
 use std::ops::Deref;
 
-use chacha20poly1305::Key;
-use chacha20poly1305::{
-    aead::{Aead, KeyInit},
-    ChaCha20Poly1305, Nonce,
-};
-use serde::{Deserialize, Serialize};
+use serde::de::{Error as _, SeqAccess, Visitor};
+use serde::ser::SerializeTuple;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::algorithm::Cipher;
 
-/// Size of ChaCha20-Poly1305 nonce in bytes
+/// Size of the original ChaCha20-Poly1305 nonce in bytes
 pub const NONCE_SIZE: usize = 12;
+/// Bytes that open the header of a ciphertext that names its cipher
+pub const CIPHER_HEADER_MAGIC: [u8; 3] = *b"JXC";
+/// Size of a ciphertext header: the magic and the cipher identifier
+pub const CIPHER_HEADER_SIZE: usize = CIPHER_HEADER_MAGIC.len() + 1;
 /// Size of ChaCha20-Poly1305 key in bytes (256 bits)
 pub const SECRET_SIZE: usize = 32;
 /// Size of BLAKE3 hash in bytes (256 bits)
@@ -25,6 +34,9 @@ pub const BLAKE3_HASH_SIZE: usize = 32;
 /// Default chunk size for streaming operations
 #[allow(dead_code)]
 pub const CHUNK_SIZE: usize = 4096;
+/// Payloads of at least this many bytes are encrypted and decrypted on the
+/// blocking thread pool rather than on the async runtime
+pub const OFFLOAD_THRESHOLD: usize = 64 * 1024;
 
 /// Errors that can occur during encryption/decryption
 #[derive(Debug, thiserror::Error)]
@@ -37,11 +49,16 @@ pub enum SecretError {
 
 /// A 256-bit symmetric encryption key for content encryption
 ///
-/// Each `Secret` is used to encrypt a single item (node or data blob) using ChaCha20-Poly1305 AEAD.
-/// The encrypted format is: `nonce (12 bytes) || encrypted(hash(32 bytes) || plaintext) || tag (16 bytes)`.
+/// Each `Secret` is used to encrypt a single item (node or data blob) using its [`Cipher`],
+/// ChaCha20-Poly1305 AEAD unless chosen otherwise.
+/// The encrypted format is: `[header (4 bytes)] || nonce || encrypted(hash(32 bytes) || plaintext) || tag (16 bytes)`,
+/// where only ciphers other than ChaCha20-Poly1305 write the header.
 /// The BLAKE3 hash of the plaintext is prepended before encryption to enable content verification
 /// without full decryption (useful for filesystem sync operations).
 ///
+/// Secrets of the original cipher serialize as their 32 key bytes, as they always have;
+/// others serialize as the cipher identifier followed by the key bytes.
+///
 /// # Examples
 ///
 /// ```ignore
@@ -56,34 +73,141 @@ pub enum SecretError {
 /// let recovered = secret.decrypt(&ciphertext)?;
 /// assert_eq!(plaintext, &recovered[..]);
 /// ```
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
-pub struct Secret([u8; SECRET_SIZE]);
+#[derive(Debug, PartialEq, Clone)]
+pub struct Secret {
+    key: [u8; SECRET_SIZE],
+    cipher: Cipher,
+}
 
 impl Default for Secret {
     fn default() -> Self {
-        Secret([0; SECRET_SIZE])
+        Secret::from([0; SECRET_SIZE])
     }
 }
 
 impl Deref for Secret {
     type Target = [u8; SECRET_SIZE];
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.key
     }
 }
 
 impl From<[u8; SECRET_SIZE]> for Secret {
     fn from(bytes: [u8; SECRET_SIZE]) -> Self {
-        Secret(bytes)
+        Secret {
+            key: bytes,
+            cipher: Cipher::default(),
+        }
+    }
+}
+
+/// The identifier and key of a secret whose cipher isn't the original one
+struct TaggedKey<'a>(&'a Secret);
+
+impl Serialize for TaggedKey<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut tuple = serializer.serialize_tuple(1 + SECRET_SIZE)?;
+        tuple.serialize_element(&self.0.cipher.id())?;
+        for byte in &self.0.key {
+            tuple.serialize_element(byte)?;
+        }
+        tuple.end()
+    }
+}
+
+impl Serialize for Secret {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if self.cipher.is_legacy() {
+            serializer.serialize_newtype_struct("Secret", &self.key)
+        } else {
+            serializer.serialize_newtype_struct("Secret", &TaggedKey(self))
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Secret {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct SecretVisitor;
+
+        impl<'de> Visitor<'de> for SecretVisitor {
+            type Value = Secret;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("32 key bytes, optionally preceded by a cipher identifier")
+            }
+
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                // Self-describing formats end the sequence where the data
+                //  does, so the shorter, untagged form reads too
+                deserializer.deserialize_tuple(1 + SECRET_SIZE, self)
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut bytes = Vec::with_capacity(1 + SECRET_SIZE);
+                while let Some(byte) = seq.next_element::<u8>()? {
+                    if bytes.len() == 1 + SECRET_SIZE {
+                        return Err(A::Error::invalid_length(bytes.len() + 1, &"32 or 33 bytes"));
+                    }
+                    bytes.push(byte);
+                }
+                let (cipher, key) = match bytes.len() {
+                    SECRET_SIZE => (Cipher::default(), &bytes[..]),
+                    len if len == 1 + SECRET_SIZE => {
+                        let cipher = Cipher::from_id(bytes[0]).ok_or_else(|| {
+                            A::Error::custom(format!("unknown cipher identifier {}", bytes[0]))
+                        })?;
+                        (cipher, &bytes[1..])
+                    }
+                    len => return Err(A::Error::invalid_length(len, &"32 or 33 bytes")),
+                };
+                let mut buff = [0; SECRET_SIZE];
+                buff.copy_from_slice(key);
+                Ok(Secret::from(buff).with_cipher(cipher))
+            }
+        }
+
+        deserializer.deserialize_newtype_struct("Secret", SecretVisitor)
     }
 }
 
 impl Secret {
-    /// Generate a new random secret using a cryptographically secure RNG
+    /// Generate a new random secret for the original cipher using a
+    /// cryptographically secure RNG
     pub fn generate() -> Self {
+        Self::generate_for(Cipher::default())
+    }
+
+    /// Generate a new random secret that encrypts with `cipher`
+    pub fn generate_for(cipher: Cipher) -> Self {
         let mut buff = [0; SECRET_SIZE];
         getrandom::getrandom(&mut buff).expect("failed to generate random bytes");
-        Self(buff)
+        Self::from(buff).with_cipher(cipher)
+    }
+
+    /// The same key, encrypting with `cipher`
+    pub fn with_cipher(mut self, cipher: Cipher) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
+    /// The cipher this secret encrypts with
+    pub fn cipher(&self) -> Cipher {
+        self.cipher
     }
 
     /// Create a secret from a byte slice
@@ -107,12 +231,13 @@ impl Secret {
 
     /// Get a reference to the secret key bytes
     pub fn bytes(&self) -> &[u8] {
-        self.0.as_ref()
+        self.key.as_ref()
     }
 
-    /// Encrypt data using ChaCha20-Poly1305 AEAD
+    /// Encrypt data using the secret's cipher
     ///
-    /// The output format is: `nonce (12 bytes) || encrypted(hash(32) || plaintext) || auth_tag (16 bytes)`.
+    /// The output format is: `[header (4 bytes)] || nonce || encrypted(hash(32) || plaintext) || auth_tag (16 bytes)`,
+    /// with a header for ciphers other than ChaCha20-Poly1305.
     /// A BLAKE3 hash of the plaintext is computed and prepended to the data before encryption.
     /// A random nonce is generated for each encryption operation.
     ///
@@ -120,6 +245,41 @@ impl Secret {
     ///
     /// Returns an error if encryption fails (should be rare, only on system RNG failure).
     pub fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>, SecretError> {
+        // Generate random nonce
+        let mut nonce_bytes = vec![0u8; self.cipher.nonce_size()];
+        getrandom::getrandom(&mut nonce_bytes)
+            .map_err(|e| anyhow::anyhow!("failed to generate nonce: {}", e))?;
+        self.encrypt_with_nonce(data, &nonce_bytes)
+    }
+
+    /// Encrypt data under a key and nonce derived from the data itself
+    ///
+    /// The key is a BLAKE3 keyed hash of the data under `convergence`, and the
+    /// nonce is derived from the key and the data, so the same data under the
+    /// same convergence secret always yields the same ciphertext. The output
+    /// is in the format of [`Secret::encrypt`] and decrypts with the returned
+    /// secret, which uses the cipher of `convergence`.
+    ///
+    /// Only use this where equal ciphertexts are wanted, since they reveal
+    /// equal plaintexts to anyone holding both.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if encryption fails.
+    pub fn encrypt_convergent(
+        convergence: &Secret,
+        data: &[u8],
+    ) -> Result<(Secret, Vec<u8>), SecretError> {
+        let secret = Secret::from(*blake3::keyed_hash(convergence, data).as_bytes())
+            .with_cipher(convergence.cipher);
+        let nonce_size = secret.cipher.nonce_size();
+        let nonce_hash = blake3::keyed_hash(&secret, data);
+        let nonce_bytes = &nonce_hash.as_bytes()[..nonce_size];
+        let encrypted = secret.encrypt_with_nonce(data, nonce_bytes)?;
+        Ok((secret, encrypted))
+    }
+
+    fn encrypt_with_nonce(&self, data: &[u8], nonce_bytes: &[u8]) -> Result<Vec<u8>, SecretError> {
         // Compute BLAKE3 hash of plaintext
         let plaintext_hash = blake3::hash(data);
 
@@ -128,29 +288,65 @@ impl Secret {
         data_with_hash.extend_from_slice(plaintext_hash.as_bytes());
         data_with_hash.extend_from_slice(data);
 
-        let key = Key::from_slice(self.bytes());
-        let cipher = ChaCha20Poly1305::new(key);
+        let ciphertext = self
+            .cipher
+            .seal(&self.key, nonce_bytes, data_with_hash.as_ref())?;
 
-        // Generate random nonce
-        let mut nonce_bytes = [0u8; NONCE_SIZE];
-        getrandom::getrandom(&mut nonce_bytes)
-            .map_err(|e| anyhow::anyhow!("failed to generate nonce: {}", e))?;
-        let nonce = Nonce::from_slice(&nonce_bytes);
-
-        let ciphertext = cipher
-            .encrypt(nonce, data_with_hash.as_ref())
-            .map_err(|_| anyhow::anyhow!("encrypt error"))?;
-
-        let mut out = Vec::with_capacity(NONCE_SIZE + ciphertext.len());
-        out.extend_from_slice(nonce.as_ref());
+        let mut out = Vec::with_capacity(CIPHER_HEADER_SIZE + nonce_bytes.len() + ciphertext.len());
+        if !self.cipher.is_legacy() {
+            out.extend_from_slice(&CIPHER_HEADER_MAGIC);
+            out.push(self.cipher.id());
+        }
+        out.extend_from_slice(nonce_bytes);
         out.extend_from_slice(ciphertext.as_ref());
 
         Ok(out)
     }
 
-    /// Decrypt data using ChaCha20-Poly1305 AEAD
+    /// Authenticate and decrypt data with the cipher its header names, or
+    /// with ChaCha20-Poly1305 if it has none
     ///
-    /// Expects input in the format: `nonce (12 bytes) || encrypted(hash(32) || plaintext) || auth_tag (16 bytes)`.
+    /// A headerless ciphertext could start with the header's bytes by chance,
+    /// so one that fails to open with the named cipher is tried as headerless.
+    fn open(&self, data: &[u8]) -> Result<Vec<u8>, SecretError> {
+        if let Some((cipher, rest)) = Self::split_header(data) {
+            if let Ok(decrypted) = self.open_with(cipher, rest) {
+                return Ok(decrypted);
+            }
+        }
+        self.open_with(Cipher::default(), data)
+    }
+
+    fn open_with(&self, cipher: Cipher, data: &[u8]) -> Result<Vec<u8>, SecretError> {
+        let nonce_size = cipher.nonce_size();
+        if data.len() < nonce_size {
+            return Err(anyhow::anyhow!("data too short for nonce").into());
+        }
+        Ok(cipher.open(&self.key, &data[..nonce_size], &data[nonce_size..])?)
+    }
+
+    /// The cipher named by a ciphertext's header, and the data after it
+    fn split_header(data: &[u8]) -> Option<(Cipher, &[u8])> {
+        let rest = data.strip_prefix(&CIPHER_HEADER_MAGIC[..])?;
+        let (&id, rest) = rest.split_first()?;
+        let cipher = Cipher::from_id(id).filter(|cipher| !cipher.is_legacy())?;
+        Some((cipher, rest))
+    }
+
+    /// The cipher a ciphertext names in its header, or ChaCha20-Poly1305 if
+    /// it has none
+    ///
+    /// This doesn't authenticate the data, so a headerless ciphertext that
+    /// happens to start with a header reads as another cipher.
+    pub fn cipher_of(data: &[u8]) -> Cipher {
+        Self::split_header(data)
+            .map(|(cipher, _)| cipher)
+            .unwrap_or_default()
+    }
+
+    /// Decrypt data using the cipher it was encrypted with
+    ///
+    /// Expects input in the format: `[header (4 bytes)] || nonce || encrypted(hash(32) || plaintext) || auth_tag (16 bytes)`.
     /// Returns only the plaintext (hash is stripped but verified for integrity).
     ///
     /// # Errors
@@ -161,16 +357,7 @@ impl Secret {
     /// - Decrypted data is too short to contain the hash header
     /// - Hash verification fails (data corruption)
     pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, SecretError> {
-        if data.len() < NONCE_SIZE {
-            return Err(anyhow::anyhow!("data too short for nonce").into());
-        }
-
-        let key = Key::from_slice(self.bytes());
-        let nonce = Nonce::from_slice(&data[..NONCE_SIZE]);
-        let cipher = ChaCha20Poly1305::new(key);
-        let decrypted = cipher
-            .decrypt(nonce, &data[NONCE_SIZE..])
-            .map_err(|_| anyhow::anyhow!("decrypt error"))?;
+        let decrypted = self.open(data)?;
 
         // Extract hash and plaintext
         if decrypted.len() < BLAKE3_HASH_SIZE {
@@ -204,16 +391,7 @@ impl Secret {
         &self,
         data: &[u8],
     ) -> Result<[u8; BLAKE3_HASH_SIZE], SecretError> {
-        if data.len() < NONCE_SIZE {
-            return Err(anyhow::anyhow!("data too short for nonce").into());
-        }
-
-        let key = Key::from_slice(self.bytes());
-        let nonce = Nonce::from_slice(&data[..NONCE_SIZE]);
-        let cipher = ChaCha20Poly1305::new(key);
-        let decrypted = cipher
-            .decrypt(nonce, &data[NONCE_SIZE..])
-            .map_err(|_| anyhow::anyhow!("decrypt error"))?;
+        let decrypted = self.open(data)?;
 
         // Extract just the hash
         if decrypted.len() < BLAKE3_HASH_SIZE {
@@ -226,6 +404,23 @@ impl Secret {
         Ok(hash)
     }
 
+    /// Encrypt data like [`Secret::encrypt`], on the blocking thread pool if
+    /// it's at least [`OFFLOAD_THRESHOLD`] bytes
+    pub async fn encrypt_offloaded(&self, data: Vec<u8>) -> Result<Vec<u8>, SecretError> {
+        let secret = self.clone();
+        offload(data.len(), move || secret.encrypt(&data)).await
+    }
+
+    /// Decrypt data like [`Secret::decrypt`], on the blocking thread pool if
+    /// it's at least [`OFFLOAD_THRESHOLD`] bytes
+    pub async fn decrypt_offloaded<D>(&self, data: D) -> Result<Vec<u8>, SecretError>
+    where
+        D: AsRef<[u8]> + Send + 'static,
+    {
+        let secret = self.clone();
+        offload(data.as_ref().len(), move || secret.decrypt(data.as_ref())).await
+    }
+
     /// Create an encrypted reader from a plaintext reader
     ///
     /// This buffers all data in memory, encrypts it, and returns a reader over the encrypted data.
@@ -261,6 +456,27 @@ impl Secret {
     }
 }
 
+/// Run CPU-heavy work over `len` bytes of data, such as encryption or
+/// compression, on the blocking thread pool so it doesn't stall other tasks
+/// on the async runtime. Work on less than [`OFFLOAD_THRESHOLD`] bytes runs
+/// inline, where it's cheaper than the hand-off.
+///
+/// Callers keep `len` to a chunk at a time, so that one large file doesn't
+/// hold a blocking thread for long.
+pub async fn offload<T, E, F>(len: usize, f: F) -> Result<T, E>
+where
+    F: FnOnce() -> Result<T, E> + Send + 'static,
+    T: Send + 'static,
+    E: From<SecretError> + Send + 'static,
+{
+    if len < OFFLOAD_THRESHOLD {
+        return f();
+    }
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| SecretError::Default(anyhow::anyhow!("offloaded task failed: {}", e)))?
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -352,6 +568,24 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_encrypt_convergent() {
+        let convergence = Secret::generate();
+        let data = b"the same chunk";
+
+        let (secret, encrypted) = Secret::encrypt_convergent(&convergence, data).unwrap();
+        let (again_secret, again) = Secret::encrypt_convergent(&convergence, data).unwrap();
+        assert_eq!(secret, again_secret);
+        assert_eq!(encrypted, again);
+        assert_eq!(secret.decrypt(&encrypted).unwrap(), data);
+
+        // Other data or another convergence secret yield other ciphertexts
+        let (_, other) = Secret::encrypt_convergent(&convergence, b"another chunk").unwrap();
+        assert_ne!(encrypted, other);
+        let (_, other) = Secret::encrypt_convergent(&Secret::generate(), data).unwrap();
+        assert_ne!(encrypted, other);
+    }
+
     #[test]
     fn test_empty_data_encryption() {
         let secret = Secret::generate();
@@ -367,4 +601,91 @@ mod test {
         let expected_hash = blake3::hash(data);
         assert_eq!(hash, *expected_hash.as_bytes());
     }
+
+    #[test]
+    fn test_legacy_format_has_no_header() {
+        let secret = Secret::generate();
+        let data = b"written before ciphers had identifiers";
+
+        let encrypted = secret.encrypt(data).unwrap();
+        assert_eq!(
+            encrypted.len(),
+            NONCE_SIZE + BLAKE3_HASH_SIZE + data.len() + 16
+        );
+        assert_eq!(Secret::cipher_of(&encrypted), Cipher::ChaCha20Poly1305);
+    }
+
+    #[test]
+    fn test_xchacha_encrypt_decrypt() {
+        let secret = Secret::generate_for(Cipher::XChaCha20Poly1305);
+        let data = b"hello world, under a longer nonce";
+
+        let encrypted = secret.encrypt(data).unwrap();
+        assert!(encrypted.starts_with(&CIPHER_HEADER_MAGIC));
+        assert_eq!(Secret::cipher_of(&encrypted), Cipher::XChaCha20Poly1305);
+        assert_eq!(
+            encrypted.len(),
+            CIPHER_HEADER_SIZE + 24 + BLAKE3_HASH_SIZE + data.len() + 16
+        );
+        assert_eq!(secret.decrypt(&encrypted).unwrap(), data);
+        assert_eq!(
+            secret.extract_plaintext_hash(&encrypted).unwrap(),
+            *blake3::hash(data).as_bytes()
+        );
+
+        // Convergent encryption keeps the cipher
+        let (chunk_secret, encrypted) = Secret::encrypt_convergent(&secret, data).unwrap();
+        assert_eq!(chunk_secret.cipher(), Cipher::XChaCha20Poly1305);
+        assert_eq!(Secret::cipher_of(&encrypted), Cipher::XChaCha20Poly1305);
+        assert_eq!(chunk_secret.decrypt(&encrypted).unwrap(), data);
+    }
+
+    #[test]
+    fn test_decrypt_follows_the_data() {
+        // Data written before a key moved to another cipher stays readable
+        let legacy = Secret::generate();
+        let encrypted = legacy.encrypt(b"old").unwrap();
+        let migrated = legacy.clone().with_cipher(Cipher::XChaCha20Poly1305);
+        assert_eq!(migrated.decrypt(&encrypted).unwrap(), b"old");
+
+        let encrypted = migrated.encrypt(b"new").unwrap();
+        assert_eq!(legacy.decrypt(&encrypted).unwrap(), b"new");
+    }
+
+    #[test]
+    fn test_secret_serde() {
+        // Secrets of the original cipher encode as they always have
+        let legacy = Secret::generate();
+        let encoded = serde_ipld_dagcbor::to_vec(&legacy).unwrap();
+        assert_eq!(encoded, serde_ipld_dagcbor::to_vec(&*legacy).unwrap());
+        let decoded: Secret = serde_ipld_dagcbor::from_slice(&encoded).unwrap();
+        assert_eq!(decoded, legacy);
+
+        let tagged = Secret::generate_for(Cipher::XChaCha20Poly1305);
+        let encoded = serde_ipld_dagcbor::to_vec(&tagged).unwrap();
+        let decoded: Secret = serde_ipld_dagcbor::from_slice(&encoded).unwrap();
+        assert_eq!(decoded, tagged);
+        let json = serde_json::to_string(&tagged).unwrap();
+        assert_eq!(serde_json::from_str::<Secret>(&json).unwrap(), tagged);
+
+        // Unknown ciphers and other lengths don't decode
+        let mut unknown = vec![u8::MAX];
+        unknown.extend_from_slice(tagged.bytes());
+        let encoded = serde_ipld_dagcbor::to_vec(&unknown).unwrap();
+        assert!(serde_ipld_dagcbor::from_slice::<Secret>(&encoded).is_err());
+        let encoded = serde_ipld_dagcbor::to_vec(&vec![1u8; 16]).unwrap();
+        assert!(serde_ipld_dagcbor::from_slice::<Secret>(&encoded).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_offloaded_roundtrip() {
+        let secret = Secret::generate();
+        for len in [16, OFFLOAD_THRESHOLD + 1] {
+            let data = vec![7u8; len];
+            let encrypted = secret.encrypt_offloaded(data.clone()).await.unwrap();
+            assert_eq!(secret.decrypt(&encrypted).unwrap(), data);
+            let decrypted = secret.decrypt_offloaded(encrypted).await.unwrap();
+            assert_eq!(decrypted, data);
+        }
+    }
 }
@@ -22,22 +22,50 @@
 //! - **Forward Secrecy**: Ephemeral keys are not stored, so past sessions cannot be decrypted
 //! - **Authentication**: The recipient's public key must be known in advance
 //! - **Integrity**: AES-KW provides authentication of the wrapped key
+//!
+//! # Post-Quantum Shares
+//!
+//! A share recorded today could be opened once X25519 falls to a quantum
+//! computer. Hybrid shares ([`SecretShare::new_hybrid`]) also encapsulate a
+//! secret to the recipient's ML-KEM-768 key (see [`KemPublicKey`]) and wrap
+//! under a key derived from both shared secrets, so they hold as long as
+//! either X25519 or ML-KEM does. They are larger (1162 bytes), and only
+//! peers that know about algorithm identifiers can read them.
+//!
+//! # Algorithm Identifiers
+//!
+//! A share of a ChaCha20-Poly1305 secret wrapped with X25519 and AES-KW is
+//! written in the original 72-byte layout. Any other share starts with the
+//! identifiers of its [`KeyWrap`] and of the shared secret's [`Cipher`], so
+//! the recipient knows how to unwrap it and what the secret encrypts with.
 
 use std::convert::TryFrom;
 
 use aes_kw::KekAes256 as Kek;
 use serde::{Deserialize, Serialize};
 
+use super::algorithm::{Cipher, KeyWrap};
+use super::kem::{self, KemPublicKey, KEM_CIPHERTEXT_SIZE, KEM_SHARED_SECRET_SIZE};
 use super::keys::{KeyError, PublicKey, SecretKey, PUBLIC_KEY_SIZE};
 use super::secret::{Secret, SecretError, SECRET_SIZE};
 
 /// Size of AES Key Wrap padding/nonce in bytes
 pub const KW_NONCE_SIZE: usize = 8;
-/// Total size of a Share in bytes
+/// Size of a share in the original layout, in bytes
 ///
 /// Layout: ephemeral_pubkey (32) || wrapped_secret (40) = 72 bytes
 /// Note: AES-KW adds 8 bytes of padding to the 32-byte secret, resulting in 40 bytes
 pub const SECRET_SHARE_SIZE: usize = PUBLIC_KEY_SIZE + SECRET_SIZE + KW_NONCE_SIZE;
+/// Size of the identifiers that open a share not in the original layout
+pub const SHARE_HEADER_SIZE: usize = 2;
+/// Size of what X25519 + ML-KEM-768 wrapping produces for a secret
+///
+/// Layout: ephemeral_pubkey (32) || kem_ciphertext (1088) || wrapped_secret (40)
+pub const HYBRID_WRAPPED_SIZE: usize =
+    PUBLIC_KEY_SIZE + KEM_CIPHERTEXT_SIZE + SECRET_SIZE + KW_NONCE_SIZE;
+
+/// BLAKE3 context deriving the AES-KW key of a hybrid share
+const HYBRID_KEK_CONTEXT: &str = "jax 2025 x25519 ml-kem-768 share kek";
 
 /// Errors that can occur during share creation or recovery
 #[derive(Debug, thiserror::Error)]
@@ -61,6 +89,12 @@ pub enum SecretShareError {
 /// [ ephemeral_pubkey: 32 bytes ][ wrapped_secret: 40 bytes ]
 /// ```
 ///
+/// or, for shares of other algorithms:
+///
+/// ```text
+/// [ key_wrap_id: 1 byte ][ cipher_id: 1 byte ][ wrapped: depends on the key wrap ]
+/// ```
+///
 /// # Examples
 ///
 /// ```ignore
@@ -75,8 +109,8 @@ pub enum SecretShareError {
 /// let recovered_secret = share.recover(&bob_secret_key)?;
 /// assert_eq!(bucket_secret, recovered_secret);
 /// ```
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
-pub struct SecretShare(pub(crate) [u8; SECRET_SHARE_SIZE]);
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct SecretShare(pub(crate) Vec<u8>);
 
 impl Serialize for SecretShare {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -101,22 +135,14 @@ impl<'de> Deserialize<'de> for SecretShare {
             type Value = SecretShare;
 
             fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                formatter.write_str("a byte array or sequence of SHARE_SIZE")
+                formatter.write_str("a byte array or sequence of a share")
             }
 
             fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
             where
                 E: Error,
             {
-                if v.len() != SECRET_SHARE_SIZE {
-                    return Err(E::invalid_length(
-                        v.len(),
-                        &format!("expected {} bytes", SECRET_SHARE_SIZE).as_str(),
-                    ));
-                }
-                let mut array = [0u8; SECRET_SHARE_SIZE];
-                array.copy_from_slice(v);
-                Ok(SecretShare(array))
+                SecretShare::try_from(v).map_err(E::custom)
             }
 
             fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
@@ -127,15 +153,7 @@ impl<'de> Deserialize<'de> for SecretShare {
                 while let Some(byte) = seq.next_element::<u8>()? {
                     bytes.push(byte);
                 }
-                if bytes.len() != SECRET_SHARE_SIZE {
-                    return Err(A::Error::invalid_length(
-                        bytes.len(),
-                        &format!("expected {} bytes", SECRET_SHARE_SIZE).as_str(),
-                    ));
-                }
-                let mut array = [0u8; SECRET_SHARE_SIZE];
-                array.copy_from_slice(&bytes);
-                Ok(SecretShare(array))
+                SecretShare::try_from(bytes.as_slice()).map_err(A::Error::custom)
             }
         }
 
@@ -146,17 +164,17 @@ impl<'de> Deserialize<'de> for SecretShare {
 
 impl Default for SecretShare {
     fn default() -> Self {
-        SecretShare([0; SECRET_SHARE_SIZE])
+        SecretShare(vec![0; SECRET_SHARE_SIZE])
     }
 }
 
 impl From<[u8; SECRET_SHARE_SIZE]> for SecretShare {
     fn from(bytes: [u8; SECRET_SHARE_SIZE]) -> Self {
-        SecretShare(bytes)
+        SecretShare(bytes.to_vec())
     }
 }
 
-impl From<SecretShare> for [u8; SECRET_SHARE_SIZE] {
+impl From<SecretShare> for Vec<u8> {
     fn from(share: SecretShare) -> Self {
         share.0
     }
@@ -165,17 +183,28 @@ impl From<SecretShare> for [u8; SECRET_SHARE_SIZE] {
 impl TryFrom<&[u8]> for SecretShare {
     type Error = SecretShareError;
     fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
-        if bytes.len() != SECRET_SHARE_SIZE {
+        if bytes.len() == SECRET_SHARE_SIZE {
+            return Ok(SecretShare(bytes.to_vec()));
+        }
+        let Some((&wrap_id, rest)) = bytes.split_first() else {
+            return Err(anyhow::anyhow!("empty share").into());
+        };
+        let wrap = KeyWrap::from_id(wrap_id)
+            .ok_or_else(|| anyhow::anyhow!("unknown key wrap identifier {}", wrap_id))?;
+        let Some((&cipher_id, wrapped)) = rest.split_first() else {
+            return Err(anyhow::anyhow!("share too short for a cipher identifier").into());
+        };
+        Cipher::from_id(cipher_id)
+            .ok_or_else(|| anyhow::anyhow!("unknown cipher identifier {}", cipher_id))?;
+        if wrapped.len() != Self::wrapped_size(wrap) {
             return Err(anyhow::anyhow!(
                 "invalid share size, expected {}, got {}",
-                SECRET_SHARE_SIZE,
+                SHARE_HEADER_SIZE + Self::wrapped_size(wrap),
                 bytes.len()
             )
             .into());
         }
-        let mut share = SecretShare::default();
-        share.0.copy_from_slice(bytes);
-        Ok(share)
+        Ok(SecretShare(bytes.to_vec()))
     }
 }
 
@@ -185,15 +214,47 @@ impl SecretShare {
     /// Accepts both plain hex and "0x"-prefixed hex strings.
     pub fn from_hex(hex: &str) -> Result<Self, SecretShareError> {
         let hex = hex.strip_prefix("0x").unwrap_or(hex);
-        let mut buff = [0; SECRET_SHARE_SIZE];
-        hex::decode_to_slice(hex, &mut buff).map_err(|_| anyhow::anyhow!("hex decode error"))?;
-        Ok(SecretShare::from(buff))
+        let buff = hex::decode(hex).map_err(|_| anyhow::anyhow!("hex decode error"))?;
+        SecretShare::try_from(buff.as_slice())
     }
 
     /// Convert share to hexadecimal string
     #[allow(clippy::wrong_self_convention)]
     pub fn to_hex(&self) -> String {
-        hex::encode(self.0)
+        hex::encode(&self.0)
+    }
+
+    /// Size of what a key wrapping scheme produces for a secret
+    ///
+    /// With the identifiers in front, no share may be [`SECRET_SHARE_SIZE`]
+    /// bytes long, or it would read as a share in the original layout.
+    fn wrapped_size(wrap: KeyWrap) -> usize {
+        match wrap {
+            KeyWrap::X25519AesKw => SECRET_SHARE_SIZE,
+            KeyWrap::X25519MlKem768 => HYBRID_WRAPPED_SIZE,
+        }
+    }
+
+    /// The key wrapping scheme, the shared secret's cipher, and the wrapped
+    /// bytes of the share
+    fn parts(&self) -> (KeyWrap, Cipher, &[u8]) {
+        if self.0.len() == SECRET_SHARE_SIZE {
+            return (KeyWrap::default(), Cipher::default(), self.0.as_slice());
+        }
+        // Shares are only built from validated bytes
+        let wrap = KeyWrap::from_id(self.0[0]).unwrap_or_default();
+        let cipher = Cipher::from_id(self.0[1]).unwrap_or_default();
+        (wrap, cipher, &self.0[SHARE_HEADER_SIZE..])
+    }
+
+    /// The scheme the secret was wrapped with
+    pub fn key_wrap(&self) -> KeyWrap {
+        self.parts().0
+    }
+
+    /// The cipher of the shared secret
+    pub fn cipher(&self) -> Cipher {
+        self.parts().1
     }
 
     /// Create a new share that wraps a secret for a specific recipient
@@ -205,6 +266,9 @@ impl SecretShare {
     /// 4. Uses AES-KW to wrap the secret with the shared secret
     /// 5. Returns a Share containing [ephemeral_pubkey || wrapped_secret]
     ///
+    /// The share is written in the original layout if the secret's cipher is
+    /// the original one, and with identifiers otherwise.
+    ///
     /// # Arguments
     ///
     /// * `secret` - The secret to share (e.g., a bucket encryption key)
@@ -214,6 +278,78 @@ impl SecretShare {
     ///
     /// Returns an error if key conversion or encryption fails.
     pub fn new(secret: &Secret, recipient: &PublicKey) -> Result<Self, SecretShareError> {
+        let (ephemeral_bytes, shared_secret) = Self::ephemeral_ecdh(recipient)?;
+        let wrapped = Self::kw_wrap(shared_secret, secret)?;
+
+        // sanity check we're getting `SHARE_SIZE` bytes here
+        if ephemeral_bytes.len() + wrapped.len() != SECRET_SHARE_SIZE {
+            return Err(anyhow::anyhow!("expected share size is incorrect").into());
+        };
+
+        // Build share: ephemeral_public_key || wrapped_secret
+        let mut share = Vec::with_capacity(SECRET_SHARE_SIZE);
+        share.extend_from_slice(&ephemeral_bytes);
+        share.extend_from_slice(&wrapped);
+
+        Ok(Self::assemble(KeyWrap::X25519AesKw, secret, share))
+    }
+
+    /// Create a new share that wraps a secret for a specific recipient with
+    /// both X25519 and ML-KEM-768
+    ///
+    /// Like [`SecretShare::new`], but the AES-KW key is derived from the X25519
+    /// shared secret together with one encapsulated to the recipient's
+    /// [`KemPublicKey`], so recording the share today and breaking X25519
+    /// later doesn't recover the secret. Returns a Share containing
+    /// [key_wrap_id || cipher_id || ephemeral_pubkey || kem_ciphertext || wrapped_secret].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if key conversion, encapsulation or encryption fails.
+    pub fn new_hybrid(
+        secret: &Secret,
+        recipient: &PublicKey,
+        kem_key: &KemPublicKey,
+    ) -> Result<Self, SecretShareError> {
+        let (ephemeral_bytes, x25519_secret) = Self::ephemeral_ecdh(recipient)?;
+        let (kem_ciphertext, kem_secret) = kem_key.encapsulate()?;
+        let kek = Self::hybrid_kek(
+            &x25519_secret,
+            &kem_secret,
+            &ephemeral_bytes,
+            &kem_ciphertext,
+        );
+        let wrapped = Self::kw_wrap(kek, secret)?;
+
+        let mut share = Vec::with_capacity(HYBRID_WRAPPED_SIZE);
+        share.extend_from_slice(&ephemeral_bytes);
+        share.extend_from_slice(&kem_ciphertext);
+        share.extend_from_slice(&wrapped);
+        if share.len() != HYBRID_WRAPPED_SIZE {
+            return Err(anyhow::anyhow!("expected share size is incorrect").into());
+        }
+
+        Ok(Self::assemble(KeyWrap::X25519MlKem768, secret, share))
+    }
+
+    /// Prefix wrapped bytes with the identifiers of the scheme and cipher,
+    /// unless both are the original ones
+    fn assemble(wrap: KeyWrap, secret: &Secret, wrapped: Vec<u8>) -> Self {
+        if wrap.is_legacy() && secret.cipher().is_legacy() {
+            return SecretShare(wrapped);
+        }
+        let mut share = Vec::with_capacity(SHARE_HEADER_SIZE + wrapped.len());
+        share.push(wrap.id());
+        share.push(secret.cipher().id());
+        share.extend_from_slice(&wrapped);
+        SecretShare(share)
+    }
+
+    /// Generate an ephemeral keypair and perform ECDH with the recipient,
+    /// returning the ephemeral public key and the shared secret
+    fn ephemeral_ecdh(
+        recipient: &PublicKey,
+    ) -> Result<([u8; PUBLIC_KEY_SIZE], [u8; SECRET_SIZE]), SecretShareError> {
         // Generate ephemeral Ed25519 keypair
         let ephemeral_private = SecretKey::generate();
         let ephemeral_public = ephemeral_private.public();
@@ -225,29 +361,69 @@ impl SecretShare {
         // Perform ECDH to get shared secret
         let shared_secret = ephemeral_x25519_private.diffie_hellman(&recipient_x25519_public);
 
-        // Use shared secret as KEK for AES-KW
         // copy the bytes to a fixed array
         let mut shared_secret_bytes = [0; SECRET_SIZE];
         shared_secret_bytes.copy_from_slice(shared_secret.as_bytes());
-        let kek = Kek::from(shared_secret_bytes);
+        Ok((ephemeral_public.to_bytes(), shared_secret_bytes))
+    }
+
+    /// Perform ECDH with the ephemeral public key at the start of `wrapped`
+    fn recipient_ecdh(
+        wrapped: &[u8],
+        recipient_secret: &SecretKey,
+    ) -> Result<[u8; SECRET_SIZE], SecretShareError> {
+        // Extract the ephemeral public key
+        let ephemeral_public_bytes = &wrapped[..PUBLIC_KEY_SIZE];
+        let ephemeral_public = PublicKey::try_from(ephemeral_public_bytes)?;
+
+        // Convert keys to X25519 for ECDH
+        let recipient_x25519_private = recipient_secret.to_x25519();
+        let ephemeral_x25519_public = ephemeral_public.to_x25519()?;
+
+        // Perform ECDH to get same shared secret
+        let shared_secret = recipient_x25519_private.diffie_hellman(&ephemeral_x25519_public);
+        Ok(*shared_secret.as_bytes())
+    }
+
+    /// The AES-KW key of a hybrid share: both shared secrets, bound to the
+    /// ephemeral key and ciphertext that produced them
+    fn hybrid_kek(
+        x25519_secret: &[u8; SECRET_SIZE],
+        kem_secret: &[u8; KEM_SHARED_SECRET_SIZE],
+        ephemeral_bytes: &[u8],
+        kem_ciphertext: &[u8],
+    ) -> [u8; SECRET_SIZE] {
+        let mut hasher = blake3::Hasher::new_derive_key(HYBRID_KEK_CONTEXT);
+        hasher.update(x25519_secret);
+        hasher.update(kem_secret);
+        hasher.update(ephemeral_bytes);
+        hasher.update(kem_ciphertext);
+        *hasher.finalize().as_bytes()
+    }
+
+    fn kw_wrap(kek: [u8; SECRET_SIZE], secret: &Secret) -> Result<Vec<u8>, SecretShareError> {
+        let kek = Kek::from(kek);
         let wrapped = kek
             .wrap_vec(secret.bytes())
             .map_err(|_| anyhow::anyhow!("AES-KW wrap error"))?;
+        Ok(wrapped)
+    }
 
-        // Build share: ephemeral_public_key || wrapped_secret
-        let mut share = SecretShare::default();
-        let ephemeral_bytes = ephemeral_public.to_bytes();
+    fn kw_unwrap(kek: [u8; SECRET_SIZE], wrapped: &[u8]) -> Result<Secret, SecretShareError> {
+        let kek = Kek::from(kek);
 
-        // sanity check we're getting `SHARE_SIZE` bytes here
-        if ephemeral_bytes.len() + wrapped.len() != SECRET_SHARE_SIZE {
-            return Err(anyhow::anyhow!("expected share size is incorrect").into());
-        };
+        // Find the actual length of wrapped data (AES-KW adds padding)
+        let unwrapped = kek
+            .unwrap_vec(wrapped)
+            .map_err(|_| anyhow::anyhow!("AES-KW unwrap error"))?;
 
-        // Copy the bytes in
-        share.0[..PUBLIC_KEY_SIZE].copy_from_slice(&ephemeral_bytes);
-        share.0[PUBLIC_KEY_SIZE..PUBLIC_KEY_SIZE + wrapped.len()].copy_from_slice(&wrapped);
+        if unwrapped.len() != SECRET_SIZE {
+            return Err(anyhow::anyhow!("unwrapped secret has wrong size").into());
+        }
 
-        Ok(share)
+        let mut secret_bytes = [0; SECRET_SIZE];
+        secret_bytes.copy_from_slice(&unwrapped);
+        Ok(Secret::from(secret_bytes))
     }
 
     /// Recover the wrapped secret using the recipient's private key
@@ -256,7 +432,9 @@ impl SecretShare {
     /// 1. Extracts the ephemeral public key from the Share
     /// 2. Converts keys to X25519 for ECDH
     /// 3. Performs ECDH to derive the same shared secret
-    /// 4. Uses AES-KW to unwrap the secret
+    /// 4. For hybrid shares, decapsulates the ML-KEM ciphertext with the
+    ///    recipient's key and combines both shared secrets
+    /// 5. Uses AES-KW to unwrap the secret
     ///
     /// # Arguments
     ///
@@ -274,34 +452,20 @@ impl SecretShare {
     /// If this function returns an error, it means either the Share was created for a different
     /// recipient, the data was corrupted, or an attacker tampered with it.
     pub fn recover(&self, recipient_secret: &SecretKey) -> Result<Secret, SecretShareError> {
-        // Extract the ephemeral public key
-        let ephemeral_public_bytes = &self.0[..PUBLIC_KEY_SIZE];
-        let ephemeral_public = PublicKey::try_from(ephemeral_public_bytes)?;
-
-        // Convert keys to X25519 for ECDH
-        let recipient_x25519_private = recipient_secret.to_x25519();
-        let ephemeral_x25519_public = ephemeral_public.to_x25519()?;
-
-        // Perform ECDH to get same shared secret
-        let shared_secret = recipient_x25519_private.diffie_hellman(&ephemeral_x25519_public);
-
-        // Use shared secret as KEK for AES-KW unwrapping
-        let shared_secret_bytes = *shared_secret.as_bytes();
-        let kek = Kek::from(shared_secret_bytes);
-        let wrapped_data = &self.0[PUBLIC_KEY_SIZE..];
-
-        // Find the actual length of wrapped data (AES-KW adds padding)
-        let unwrapped = kek
-            .unwrap_vec(wrapped_data)
-            .map_err(|_| anyhow::anyhow!("AES-KW unwrap error"))?;
-
-        if unwrapped.len() != SECRET_SIZE {
-            return Err(anyhow::anyhow!("unwrapped secret has wrong size").into());
-        }
-
-        let mut secret_bytes = [0; SECRET_SIZE];
-        secret_bytes.copy_from_slice(&unwrapped);
-        Ok(Secret::from(secret_bytes))
+        let (wrap, cipher, wrapped) = self.parts();
+        let x25519_secret = Self::recipient_ecdh(wrapped, recipient_secret)?;
+        let secret = match wrap {
+            KeyWrap::X25519AesKw => Self::kw_unwrap(x25519_secret, &wrapped[PUBLIC_KEY_SIZE..])?,
+            KeyWrap::X25519MlKem768 => {
+                let (ephemeral_bytes, rest) = wrapped.split_at(PUBLIC_KEY_SIZE);
+                let (kem_ciphertext, kw_wrapped) = rest.split_at(KEM_CIPHERTEXT_SIZE);
+                let kem_secret = kem::decapsulate(recipient_secret, kem_ciphertext)?;
+                let kek =
+                    Self::hybrid_kek(&x25519_secret, &kem_secret, ephemeral_bytes, kem_ciphertext);
+                Self::kw_unwrap(kek, kw_wrapped)?
+            }
+        };
+        Ok(secret.with_cipher(cipher))
     }
 
     /// Get a reference to the raw share bytes
@@ -422,6 +586,65 @@ mod test {
         assert_eq!(share.0, [0u8; SECRET_SHARE_SIZE]);
     }
 
+    #[test]
+    fn test_share_algorithm_identifiers() {
+        let private_key = SecretKey::generate();
+        let public_key = private_key.public();
+
+        // Shares of original secrets keep the original layout
+        let share = SecretShare::new(&Secret::generate(), &public_key).unwrap();
+        assert_eq!(share.bytes().len(), SECRET_SHARE_SIZE);
+        assert_eq!(share.key_wrap(), KeyWrap::X25519AesKw);
+        assert_eq!(share.cipher(), Cipher::ChaCha20Poly1305);
+
+        // Others name their algorithms, and recover the secret's cipher
+        let secret = Secret::generate_for(Cipher::XChaCha20Poly1305);
+        let share = SecretShare::new(&secret, &public_key).unwrap();
+        assert_eq!(share.bytes().len(), SHARE_HEADER_SIZE + SECRET_SHARE_SIZE);
+        assert_eq!(share.cipher(), Cipher::XChaCha20Poly1305);
+        let binary = bincode::serialize(&share).unwrap();
+        let recovered_share: SecretShare = bincode::deserialize(&binary).unwrap();
+        assert_eq!(recovered_share, share);
+        let recovered_secret = recovered_share.recover(&private_key).unwrap();
+        assert_eq!(recovered_secret, secret);
+        assert_eq!(recovered_secret.cipher(), Cipher::XChaCha20Poly1305);
+        let recovered_share = SecretShare::from_hex(&share.to_hex()).unwrap();
+        assert_eq!(recovered_share, share);
+
+        // Unknown identifiers don't parse
+        let mut unknown = share.bytes().to_vec();
+        unknown[0] = u8::MAX;
+        assert!(SecretShare::try_from(unknown.as_slice()).is_err());
+        let mut unknown = share.bytes().to_vec();
+        unknown[1] = u8::MAX;
+        assert!(SecretShare::try_from(unknown.as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_hybrid_share() {
+        let private_key = SecretKey::generate();
+        let public_key = private_key.public();
+        let kem_key = KemPublicKey::of(&private_key);
+        let secret = Secret::generate();
+
+        let share = SecretShare::new_hybrid(&secret, &public_key, &kem_key).unwrap();
+        assert_eq!(share.key_wrap(), KeyWrap::X25519MlKem768);
+        assert_eq!(share.cipher(), Cipher::ChaCha20Poly1305);
+        assert_eq!(share.bytes().len(), SHARE_HEADER_SIZE + HYBRID_WRAPPED_SIZE);
+        assert_eq!(share.recover(&private_key).unwrap(), secret);
+
+        // It survives serialization, and nobody else can recover it
+        let binary = bincode::serialize(&share).unwrap();
+        let recovered_share: SecretShare = bincode::deserialize(&binary).unwrap();
+        assert_eq!(recovered_share.recover(&private_key).unwrap(), secret);
+        assert!(share.recover(&SecretKey::generate()).is_err());
+
+        // Wrapping to another identity's ML-KEM key doesn't work either
+        let other_kem_key = KemPublicKey::of(&SecretKey::generate());
+        let share = SecretShare::new_hybrid(&secret, &public_key, &other_kem_key).unwrap();
+        assert!(share.recover(&private_key).is_err());
+    }
+
     #[test]
     fn test_share_serde_multiple_formats() {
         let secret = Secret::generate();
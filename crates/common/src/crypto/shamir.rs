@@ -0,0 +1,188 @@
+//! Shamir secret sharing over GF(256)
+//!
+//! [`split`] cuts a secret into shards so that any `threshold` of them
+//! reconstruct it with [`combine`], while fewer reveal nothing about it. Each
+//! byte of the secret is the constant term of its own random polynomial of
+//! degree `threshold - 1`, and a shard holds the value of every polynomial at
+//! the shard's index. Arithmetic is in GF(2^8) with the AES polynomial.
+//!
+//! Shards carry no checksum: combining too few shards, or shards of
+//! different secrets, yields a wrong secret without an error. Callers check
+//! the result against something they know, such as a public key.
+
+use serde::{Deserialize, Serialize};
+
+/// One shard of a split secret
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Shard {
+    /// Point the shard's values are taken at, never 0
+    pub index: u8,
+    /// Value of each byte's polynomial at `index`
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ShamirError {
+    #[error("threshold must be between 1 and the number of shards, got {threshold} of {count}")]
+    InvalidThreshold { threshold: u8, count: u8 },
+    #[error("no shards to combine")]
+    NoShards,
+    #[error("shards have different lengths")]
+    LengthMismatch,
+    #[error("shard index {0} is invalid or repeated")]
+    InvalidIndex(u8),
+    #[error("no randomness: {0}")]
+    Random(String),
+}
+
+/// Split `secret` into `count` shards, any `threshold` of which recover it
+pub fn split(secret: &[u8], threshold: u8, count: u8) -> Result<Vec<Shard>, ShamirError> {
+    if threshold == 0 || threshold > count {
+        return Err(ShamirError::InvalidThreshold { threshold, count });
+    }
+
+    // coefficients[i] holds the random coefficients of byte i's polynomial,
+    // for x^1 up to x^degree
+    let degree = threshold as usize - 1;
+    let mut random = vec![0u8; secret.len() * degree];
+    getrandom::getrandom(&mut random).map_err(|e| ShamirError::Random(e.to_string()))?;
+    let coefficients: Vec<&[u8]> = (0..secret.len())
+        .map(|i| &random[i * degree..(i + 1) * degree])
+        .collect();
+
+    Ok((1..=count)
+        .map(|index| Shard {
+            index,
+            data: secret
+                .iter()
+                .zip(&coefficients)
+                .map(|(byte, coefficients)| evaluate(*byte, coefficients, index))
+                .collect(),
+        })
+        .collect())
+}
+
+/// Recover a secret from at least the threshold of its shards
+pub fn combine(shards: &[Shard]) -> Result<Vec<u8>, ShamirError> {
+    let first = shards.first().ok_or(ShamirError::NoShards)?;
+    let len = first.data.len();
+    for (i, shard) in shards.iter().enumerate() {
+        if shard.data.len() != len {
+            return Err(ShamirError::LengthMismatch);
+        }
+        if shard.index == 0 || shards[..i].iter().any(|s| s.index == shard.index) {
+            return Err(ShamirError::InvalidIndex(shard.index));
+        }
+    }
+
+    // Lagrange interpolation at x = 0: secret = sum(y_j * prod(x_m / (x_m - x_j)))
+    // (subtraction is xor in GF(256))
+    let weights: Vec<u8> = shards
+        .iter()
+        .map(|shard| {
+            shards
+                .iter()
+                .filter(|other| other.index != shard.index)
+                .fold(1, |weight, other| {
+                    mul(weight, div(other.index, other.index ^ shard.index))
+                })
+        })
+        .collect();
+
+    Ok((0..len)
+        .map(|i| {
+            shards
+                .iter()
+                .zip(&weights)
+                .fold(0, |secret, (shard, weight)| {
+                    secret ^ mul(shard.data[i], *weight)
+                })
+        })
+        .collect())
+}
+
+/// Value at `x` of the polynomial with constant term `secret`
+fn evaluate(secret: u8, coefficients: &[u8], x: u8) -> u8 {
+    // Horner's method, from the highest degree down to the constant term
+    coefficients
+        .iter()
+        .rev()
+        .chain(std::iter::once(&secret))
+        .fold(0, |acc, coefficient| mul(acc, x) ^ coefficient)
+}
+
+/// Multiplication in GF(2^8) modulo x^8 + x^4 + x^3 + x + 1
+fn mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        let carry = a & 0x80 != 0;
+        a <<= 1;
+        if carry {
+            a ^= 0x1b;
+        }
+        b >>= 1;
+    }
+    product
+}
+
+/// Division in GF(2^8); `b` must not be 0
+fn div(a: u8, b: u8) -> u8 {
+    // b^254 is the inverse of b
+    let mut inverse = 1;
+    for _ in 0..254 {
+        inverse = mul(inverse, b);
+    }
+    mul(a, inverse)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_field_arithmetic() {
+        // From FIPS-197: {57} * {83} = {c1}
+        assert_eq!(mul(0x57, 0x83), 0xc1);
+        for a in 1..=255u8 {
+            assert_eq!(mul(div(1, a), a), 1);
+        }
+    }
+
+    #[test]
+    fn test_any_threshold_of_shards_recovers_the_secret() {
+        let secret = b"thirty-two bytes of bucket key!!".to_vec();
+        let shards = split(&secret, 3, 5).unwrap();
+        assert_eq!(shards.len(), 5);
+
+        for picked in [[0, 1, 2], [0, 2, 4], [1, 3, 4], [4, 3, 2]] {
+            let subset: Vec<Shard> = picked.iter().map(|&i| shards[i].clone()).collect();
+            assert_eq!(combine(&subset).unwrap(), secret);
+        }
+        assert_eq!(combine(&shards).unwrap(), secret);
+
+        // Too few shards give something else
+        assert_ne!(combine(&shards[..2]).unwrap(), secret);
+    }
+
+    #[test]
+    fn test_threshold_of_one_copies_the_secret() {
+        let shards = split(b"key", 1, 3).unwrap();
+        assert!(shards.iter().all(|shard| shard.data == b"key"));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert!(split(b"key", 0, 3).is_err());
+        assert!(split(b"key", 4, 3).is_err());
+        assert!(combine(&[]).is_err());
+
+        let shards = split(b"key", 2, 3).unwrap();
+        assert!(combine(&[shards[0].clone(), shards[0].clone()]).is_err());
+        let mut short = shards[1].clone();
+        short.data.pop();
+        assert!(combine(&[shards[0].clone(), short]).is_err());
+    }
+}
@@ -0,0 +1,442 @@
+//! Running a jax peer inside another application
+//!
+//! [`NodeBuilder`] wires a [`Peer`] up with a sync worker and returns a
+//! [`Node`]: a handle to read and write buckets, control syncing and follow
+//! what happens to the buckets, without HTTP servers, a CLI or config files.
+//! The host brings the identity, the blob store and the bucket log, e.g.
+//! [`MemoryBucketLogProvider`](crate::bucket_log::MemoryBucketLogProvider)
+//! or a provider over its own database.
+//!
+//! ```ignore
+//! let node = NodeBuilder::new(MemoryBucketLogProvider::new())
+//!     .secret_key(secret_key)
+//!     .blobs_store(BlobsStore::fs(&blobs_path).await?)
+//!     .build()
+//!     .await;
+//! let mut events = Box::pin(node.events());
+//!
+//! let bucket_id = node.create_bucket("notes").await?;
+//! node.add(bucket_id, Path::new("/todo.md"), &b"- ship it"[..]).await?;
+//! assert_eq!(node.cat(bucket_id, Path::new("/todo.md")).await?, b"- ship it");
+//! ```
+//!
+//! Jobs peers send (syncing a bucket, downloading its pins) run one at a
+//! time on a worker the node spawns, and are held while syncing is paused.
+//! Nothing is synced on a timer: hosts call [`Node::sync`] when it suits
+//! them, e.g. on start, on a push notification or from a background task.
+
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use futures::Stream;
+use tokio::io::AsyncRead;
+use tokio::sync::{broadcast, mpsc, watch};
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::bucket_log::BucketLogProvider;
+use crate::crypto::SecretKey;
+use crate::linked_data::Link;
+use crate::mount::{Mount, MountError, NodeLink};
+use crate::peer::sync::execute_job;
+use crate::peer::{
+    BlobsStore, ConnectionLimits, Peer, PeerBuilder, PeerError, ProtocolTimeouts, SyncJob,
+    SyncProvider,
+};
+
+/// Events buffered for each subscriber before the oldest are dropped
+const DEFAULT_EVENT_CAPACITY: usize = 256;
+
+/// Something that happened to the node's buckets
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeEvent {
+    /// A version was saved through the node
+    Saved {
+        bucket_id: Uuid,
+        link: Link,
+        height: u64,
+    },
+    /// A sync with a peer moved the bucket's head
+    Updated {
+        bucket_id: Uuid,
+        link: Link,
+        height: u64,
+    },
+    /// A sync job failed
+    SyncFailed {
+        bucket_id: Uuid,
+        job: &'static str,
+        error: String,
+    },
+    /// Syncing was paused
+    SyncPaused,
+    /// Syncing was resumed
+    SyncResumed,
+}
+
+/// Builder of an embedded [`Node`]
+pub struct NodeBuilder<L: BucketLogProvider> {
+    log_provider: L,
+    socket_address: Option<SocketAddr>,
+    secret_key: Option<SecretKey>,
+    blobs_store: Option<BlobsStore>,
+    protocol_timeouts: Option<ProtocolTimeouts>,
+    connection_limits: Option<ConnectionLimits>,
+    event_capacity: usize,
+}
+
+impl<L> NodeBuilder<L>
+where
+    L: BucketLogProvider + Clone + Send + Sync + std::fmt::Debug + 'static,
+    L::Error: std::error::Error + Send + Sync + 'static,
+{
+    /// Start a node keeping its bucket logs in `log_provider`
+    pub fn new(log_provider: L) -> Self {
+        Self {
+            log_provider,
+            socket_address: None,
+            secret_key: None,
+            blobs_store: None,
+            protocol_timeouts: None,
+            connection_limits: None,
+            event_capacity: DEFAULT_EVENT_CAPACITY,
+        }
+    }
+
+    /// Address to accept peers on, an ephemeral port if not set
+    pub fn socket_address(mut self, socket_addr: SocketAddr) -> Self {
+        self.socket_address = Some(socket_addr);
+        self
+    }
+
+    /// Identity of the node, a new one if not set
+    pub fn secret_key(mut self, secret_key: SecretKey) -> Self {
+        self.secret_key = Some(secret_key);
+        self
+    }
+
+    /// Where bucket contents are stored, in memory if not set
+    pub fn blobs_store(mut self, blobs: BlobsStore) -> Self {
+        self.blobs_store = Some(blobs);
+        self
+    }
+
+    pub fn protocol_timeouts(mut self, timeouts: ProtocolTimeouts) -> Self {
+        self.protocol_timeouts = Some(timeouts);
+        self
+    }
+
+    pub fn connection_limits(mut self, limits: ConnectionLimits) -> Self {
+        self.connection_limits = Some(limits);
+        self
+    }
+
+    /// Events buffered for each subscriber of [`Node::events`]
+    pub fn event_capacity(mut self, capacity: usize) -> Self {
+        self.event_capacity = capacity.max(1);
+        self
+    }
+
+    /// Start the node: accept peers and run the sync worker until
+    /// [`Node::shutdown`]
+    pub async fn build(self) -> Node<L> {
+        let (jobs_tx, jobs_rx) = mpsc::unbounded_channel();
+        let mut builder = PeerBuilder::new()
+            .log_provider(self.log_provider)
+            .with_sync_provider(Arc::new(ChannelSync { jobs: jobs_tx }));
+        if let Some(addr) = self.socket_address {
+            builder = builder.socket_address(addr);
+        }
+        if let Some(secret_key) = self.secret_key {
+            builder = builder.secret_key(secret_key);
+        }
+        if let Some(blobs) = self.blobs_store {
+            builder = builder.blobs_store(blobs);
+        }
+        if let Some(timeouts) = self.protocol_timeouts {
+            builder = builder.protocol_timeouts(timeouts);
+        }
+        if let Some(limits) = self.connection_limits {
+            builder = builder.connection_limits(limits);
+        }
+        let peer = builder.build().await;
+
+        let (events, _) = broadcast::channel(self.event_capacity);
+        let (paused, paused_rx) = watch::channel(false);
+        let (shutdown, shutdown_rx) = watch::channel(());
+        tokio::spawn(run_sync(peer.clone(), jobs_rx, paused_rx, events.clone()));
+        let router = tokio::spawn(crate::peer::spawn(peer.clone(), shutdown_rx));
+
+        Node {
+            peer,
+            inner: Arc::new(NodeInner {
+                events,
+                paused,
+                shutdown,
+                router: Mutex::new(Some(router)),
+            }),
+        }
+    }
+}
+
+/// Handle to an embedded jax peer, see the [module docs](self)
+pub struct Node<L: BucketLogProvider> {
+    peer: Peer<L>,
+    inner: Arc<NodeInner>,
+}
+
+struct NodeInner {
+    events: broadcast::Sender<NodeEvent>,
+    paused: watch::Sender<bool>,
+    shutdown: watch::Sender<()>,
+    router: Mutex<Option<JoinHandle<Result<(), PeerError>>>>,
+}
+
+impl<L: BucketLogProvider> Clone for Node<L>
+where
+    L: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            peer: self.peer.clone(),
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<L> Node<L>
+where
+    L: BucketLogProvider + Clone + Send + Sync + std::fmt::Debug + 'static,
+    L::Error: std::error::Error + Send + Sync + 'static,
+{
+    /// The peer underneath, for operations the node doesn't wrap
+    pub fn peer(&self) -> &Peer<L> {
+        &self.peer
+    }
+
+    pub fn secret(&self) -> &SecretKey {
+        self.peer.secret()
+    }
+
+    /// Create a bucket owned by the node
+    pub async fn create_bucket(&self, name: &str) -> Result<Uuid, MountError> {
+        let id = Uuid::new_v4();
+        let mount =
+            Mount::init(id, name.to_string(), self.peer.secret(), self.peer.blobs()).await?;
+        let link = mount.link().await;
+        self.peer
+            .logs()
+            .append(id, name.to_string(), link.clone(), None, 0, false)
+            .await
+            .map_err(|e| MountError::Default(anyhow!("Failed to append to log: {}", e)))?;
+        let _ = self.inner.events.send(NodeEvent::Saved {
+            bucket_id: id,
+            link,
+            height: 0,
+        });
+        Ok(id)
+    }
+
+    /// Buckets the node holds
+    pub async fn buckets(&self) -> Result<Vec<Uuid>, MountError> {
+        self.peer
+            .logs()
+            .list_buckets()
+            .await
+            .map_err(|e| MountError::Default(anyhow!("Failed to list buckets: {}", e)))
+    }
+
+    /// The bucket at its head, to change and [`save`](Node::save)
+    pub async fn mount(&self, bucket_id: Uuid) -> Result<Mount, MountError> {
+        self.peer.mount(bucket_id).await
+    }
+
+    /// Save a mount as the bucket's next version, telling its peers
+    pub async fn save(&self, mount: &Mount) -> Result<Link, MountError> {
+        let link = self.peer.save_mount(mount, false).await?;
+        let inner = mount.inner().await;
+        let _ = self.inner.events.send(NodeEvent::Saved {
+            bucket_id: *inner.manifest().id(),
+            link: link.clone(),
+            height: inner.height(),
+        });
+        Ok(link)
+    }
+
+    /// Write a file, replacing any at `path`, and save the bucket
+    pub async fn add<R>(&self, bucket_id: Uuid, path: &Path, data: R) -> Result<Link, MountError>
+    where
+        R: AsyncRead + Send + Unpin,
+    {
+        let mut mount = self.mount(bucket_id).await?;
+        mount.add_reader(path, data).await?;
+        self.save(&mount).await
+    }
+
+    /// Create a directory and save the bucket
+    pub async fn mkdir(&self, bucket_id: Uuid, path: &Path) -> Result<Link, MountError> {
+        let mut mount = self.mount(bucket_id).await?;
+        mount.mkdir(path).await?;
+        self.save(&mount).await
+    }
+
+    /// Remove a file or directory and save the bucket
+    pub async fn rm(&self, bucket_id: Uuid, path: &Path) -> Result<Link, MountError> {
+        let mut mount = self.mount(bucket_id).await?;
+        mount.rm(path).await?;
+        self.save(&mount).await
+    }
+
+    /// Read a file, at the version the node's role in the bucket may read
+    pub async fn cat(&self, bucket_id: Uuid, path: &Path) -> Result<Vec<u8>, MountError> {
+        self.peer.mount_for_read(bucket_id).await?.cat(path).await
+    }
+
+    /// List a directory, at the version the node's role in the bucket may read
+    pub async fn ls(
+        &self,
+        bucket_id: Uuid,
+        path: &Path,
+    ) -> Result<BTreeMap<PathBuf, NodeLink>, MountError> {
+        self.peer.mount_for_read(bucket_id).await?.ls(path).await
+    }
+
+    /// Ask the bucket's peers for newer versions
+    pub async fn sync(&self, bucket_id: Uuid) -> Result<()> {
+        self.peer.ping(bucket_id).await
+    }
+
+    /// Ask the peers of every bucket for newer versions
+    pub async fn sync_all(&self) -> Result<()> {
+        for bucket_id in self.buckets().await? {
+            self.sync(bucket_id).await?;
+        }
+        Ok(())
+    }
+
+    /// Hold sync jobs until [`resume_sync`](Node::resume_sync)
+    pub fn pause_sync(&self) {
+        if !self.inner.paused.send_replace(true) {
+            let _ = self.inner.events.send(NodeEvent::SyncPaused);
+        }
+    }
+
+    /// Run sync jobs again, starting with those held while paused
+    pub fn resume_sync(&self) {
+        if self.inner.paused.send_replace(false) {
+            let _ = self.inner.events.send(NodeEvent::SyncResumed);
+        }
+    }
+
+    pub fn is_sync_paused(&self) -> bool {
+        *self.inner.paused.borrow()
+    }
+
+    /// Events from now on. A subscriber that falls more than the
+    /// [event capacity](NodeBuilder::event_capacity) behind skips the
+    /// events it missed.
+    pub fn events(&self) -> impl Stream<Item = NodeEvent> + Send + 'static {
+        let rx = self.inner.events.subscribe();
+        futures::stream::unfold(rx, |mut rx| async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) => return Some((event, rx)),
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+
+    /// Stop accepting peers and cancel the sync jobs in flight
+    pub async fn shutdown(&self) -> Result<(), PeerError> {
+        let _ = self.inner.shutdown.send(());
+        let router = self
+            .inner
+            .router
+            .lock()
+            .expect("router lock poisoned")
+            .take();
+        match router {
+            Some(router) => router
+                .await
+                .map_err(|e| PeerError::RouterShutdown(e.into()))?,
+            None => Ok(()),
+        }
+    }
+}
+
+/// Hands the jobs peers send to the node's sync worker
+#[derive(Debug)]
+struct ChannelSync {
+    jobs: mpsc::UnboundedSender<SyncJob>,
+}
+
+#[async_trait]
+impl<L> SyncProvider<L> for ChannelSync
+where
+    L: BucketLogProvider + Clone + Send + Sync + 'static,
+    L::Error: std::error::Error + Send + Sync + 'static,
+{
+    async fn execute(&self, _peer: &Peer<L>, job: SyncJob) -> Result<()> {
+        self.jobs
+            .send(job)
+            .map_err(|_| anyhow!("sync worker has been stopped"))
+    }
+}
+
+/// Run sync jobs one at a time, holding them while syncing is paused, until
+/// the peer shuts down
+async fn run_sync<L>(
+    peer: Peer<L>,
+    mut jobs: mpsc::UnboundedReceiver<SyncJob>,
+    mut paused: watch::Receiver<bool>,
+    events: broadcast::Sender<NodeEvent>,
+) where
+    L: BucketLogProvider + Clone + Send + Sync + 'static,
+    L::Error: std::error::Error + Send + Sync + 'static,
+{
+    loop {
+        let job = tokio::select! {
+            job = jobs.recv() => match job {
+                Some(job) => job,
+                None => return,
+            },
+            _ = peer.cancelled() => return,
+        };
+        tokio::select! {
+            resumed = paused.wait_for(|paused| !paused) => {
+                if resumed.is_err() {
+                    return;
+                }
+            }
+            _ = peer.cancelled() => return,
+        }
+
+        let bucket_id = job.bucket_id();
+        let name = job.name();
+        let before = peer.logs().head(bucket_id, None).await.ok();
+        if let Err(e) = execute_job(&peer, job).await {
+            tracing::warn!(%bucket_id, job = name, "sync job failed: {}", e);
+            let _ = events.send(NodeEvent::SyncFailed {
+                bucket_id,
+                job: name,
+                error: e.to_string(),
+            });
+            continue;
+        }
+        if let Ok((link, height)) = peer.logs().head(bucket_id, None).await {
+            if before.as_ref().map(|(link, _)| link) != Some(&link) {
+                let _ = events.send(NodeEvent::Updated {
+                    bucket_id,
+                    link,
+                    height,
+                });
+            }
+        }
+    }
+}
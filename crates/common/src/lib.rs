@@ -5,6 +5,12 @@ pub mod bucket_log;
  *  - Key-to-key key sharing
  */
 pub mod crypto;
+/**
+ * Facade for running a jax peer as a library
+ *  inside another application, without the
+ *  daemon's servers.
+ */
+pub mod embed;
 /**
  * Internal wrapper around IPLD, renamed to
  *  something a little more down-to-earth.
@@ -0,0 +1,444 @@
+//! Content-defined chunking of large files
+//!
+//! Files of at least [`CHUNKING_THRESHOLD`] bytes are split into chunks at
+//! boundaries picked from their content with a gear rolling hash, so an edit
+//! only moves the boundaries around it. Each chunk is encrypted convergently
+//! (see [`Secret::encrypt_convergent`]) under the file's secret, which new
+//! versions of a file at the same path keep, so chunks an edit didn't touch
+//! keep their blob hashes. Peers syncing the new version already hold those
+//! blobs through the old version's pins and only fetch the changed chunks.
+//!
+//! The data link of a chunked file points to its [`ChunkList`], encrypted
+//! with the file's secret, and its node [`Data`](super::node::Data) is marked
+//! as chunked. Compression, if any, is applied and recorded per chunk.
+//!
+//! Chunking reads the file as an async stream and holds at most one
+//! chunk's worth of it in memory at a time, however large the file, so
+//! uploads can be chunked as they arrive. Blocking readers are read in
+//! place through [`SyncReader`].
+//!
+//! Appending to a chunked file (see [`append_chunked`]) cuts only the
+//! appended bytes into new chunks at the end of its list, so log-style files
+//! grow without their existing chunks being read or rewritten.
+
+use std::io::Read;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use mime::Mime;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncReadExt, ReadBuf};
+
+use crate::crypto::{offload, Secret, SecretError};
+use crate::linked_data::{BlockEncoded, DagCborCodec, Hash, Link, LD_RAW_CODEC};
+use crate::peer::BlobsStore;
+
+use super::{Compression, MountError};
+
+/// Files of at least this many bytes are stored as chunks
+pub const CHUNKING_THRESHOLD: usize = 4 * 1024 * 1024;
+
+const MIN_CHUNK_SIZE: usize = 256 * 1024;
+/// Chunks average 2^20 bytes (1 MiB)
+const AVG_CHUNK_BITS: u32 = 20;
+const MAX_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+/// A chunk of a file's contents
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChunkRef {
+    link: Link,
+    secret: Secret,
+    /// Size of the chunk's contents before compression and encryption
+    size: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    compression: Option<Compression>,
+}
+
+impl ChunkRef {
+    /// Link to the encrypted chunk blob
+    pub fn link(&self) -> &Link {
+        &self.link
+    }
+
+    /// Size of the chunk's contents
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Fetch and decrypt the chunk, off the async runtime
+    ///
+    /// Reading a file chunk by chunk keeps only one chunk in memory.
+    #[allow(clippy::result_large_err)]
+    pub async fn read(&self, blobs: &BlobsStore) -> Result<Vec<u8>, MountError> {
+        let encrypted = blobs.get(&self.link.hash()).await?;
+        let secret = self.secret.clone();
+        let compression = self.compression;
+        offload(encrypted.len(), move || {
+            let plaintext = secret.decrypt(&encrypted)?;
+            match compression {
+                Some(codec) => Ok(codec.decompress(&plaintext)?),
+                None => Ok(plaintext),
+            }
+        })
+        .await
+    }
+}
+
+/// The chunks of a file, in order
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChunkList {
+    chunks: Vec<ChunkRef>,
+}
+
+impl BlockEncoded<DagCborCodec> for ChunkList {}
+
+impl ChunkList {
+    pub fn chunks(&self) -> &[ChunkRef] {
+        &self.chunks
+    }
+
+    /// Size of the file's contents
+    pub fn size(&self) -> u64 {
+        self.chunks.iter().map(|chunk| chunk.size).sum()
+    }
+
+    /// Load and decrypt the chunk list a chunked file's data link points to
+    pub async fn load(
+        blobs: &BlobsStore,
+        link: &Link,
+        secret: &Secret,
+    ) -> Result<Self, MountError> {
+        let encrypted = blobs.get(&link.hash()).await?;
+        let data = secret.decrypt_offloaded(encrypted).await?;
+        Ok(Self::decode(&data)?)
+    }
+
+    /// Encrypt the chunk list with the file's secret and store it, returning
+    /// the file's new data link
+    async fn store(&self, blobs: &BlobsStore, secret: &Secret) -> Result<Link, MountError> {
+        let encrypted = secret.encrypt_offloaded(self.encode()?).await?;
+        let hash = blobs.put(encrypted).await?;
+        Ok(Link::new(LD_RAW_CODEC, hash))
+    }
+
+    /// Fetch, decrypt and concatenate the chunks
+    ///
+    /// Each chunk is decrypted and decompressed off the async runtime.
+    #[allow(clippy::result_large_err)]
+    pub async fn read(&self, blobs: &BlobsStore) -> Result<Vec<u8>, MountError> {
+        let mut data = Vec::with_capacity(self.size() as usize);
+        for chunk in &self.chunks {
+            data.extend(chunk.read(blobs).await?);
+        }
+        Ok(data)
+    }
+
+    /// Fetch and decrypt only as many leading chunks as it takes to cover
+    /// the first `len` bytes of the file, which may return more
+    #[allow(clippy::result_large_err)]
+    pub async fn read_prefix(&self, blobs: &BlobsStore, len: usize) -> Result<Vec<u8>, MountError> {
+        let mut data = Vec::new();
+        for chunk in &self.chunks {
+            if data.len() >= len {
+                break;
+            }
+            data.extend(chunk.read(blobs).await?);
+        }
+        Ok(data)
+    }
+}
+
+/// Store the contents read from `reader` as chunks encrypted under
+/// `secret`, compressing each chunk with `compression` where it pays off.
+///
+/// Only the chunk being cut is buffered, so memory use is bounded by the
+/// maximum chunk size rather than the size of the file. Each chunk is
+/// compressed and encrypted off the async runtime.
+///
+/// Returns the link to the encrypted chunk list and the hashes of every blob
+/// written, all of which must be pinned.
+#[allow(clippy::result_large_err)]
+pub(crate) async fn put_chunked<R: AsyncRead + Unpin>(
+    blobs: &BlobsStore,
+    secret: &Secret,
+    reader: R,
+    compression: Option<Compression>,
+    mime: Option<&Mime>,
+) -> Result<(Link, Vec<Hash>), MountError> {
+    append_chunked(
+        blobs,
+        secret,
+        ChunkList::default(),
+        reader,
+        compression,
+        mime,
+    )
+    .await
+}
+
+/// Store the contents read from `reader` as new chunks at the end of
+/// `list`, the chunk list of a file encrypted under `secret`.
+///
+/// Chunks already in the list are left as they are, so this costs the size
+/// of the appended data and of the list, not of the file. Returns the link
+/// to the extended chunk list and the hashes of every blob written.
+#[allow(clippy::result_large_err)]
+pub(crate) async fn append_chunked<R: AsyncRead + Unpin>(
+    blobs: &BlobsStore,
+    secret: &Secret,
+    mut list: ChunkList,
+    mut reader: R,
+    compression: Option<Compression>,
+    mime: Option<&Mime>,
+) -> Result<(Link, Vec<Hash>), MountError> {
+    let mut hashes = Vec::new();
+    let mut buf = Vec::with_capacity(MAX_CHUNK_SIZE);
+    let mut eof = false;
+    loop {
+        // Top the buffer up to a full chunk's worth, which is all a cut looks at
+        if !eof {
+            let want = (MAX_CHUNK_SIZE - buf.len()) as u64;
+            let read = (&mut reader)
+                .take(want)
+                .read_to_end(&mut buf)
+                .await
+                .map_err(SecretError::Io)?;
+            eof = read < want as usize;
+        }
+        if buf.is_empty() {
+            break;
+        }
+
+        // Cut, compress and encrypt the next chunk off the async runtime
+        let data = std::mem::take(&mut buf);
+        let convergence = secret.clone();
+        let mime = mime.cloned();
+        let (rest, size, chunk_secret, encrypted, chunk_compression) =
+            offload(data.len(), move || {
+                let mut chunk = data;
+                let cut = cut_point(&chunk, MIN_CHUNK_SIZE, AVG_CHUNK_BITS, MAX_CHUNK_SIZE);
+                let rest = chunk.split_off(cut);
+                let compressed = match compression {
+                    Some(codec) => codec
+                        .maybe_compress(&chunk, mime.as_ref())?
+                        .map(|c| (codec, c)),
+                    None => None,
+                };
+                let (chunk_secret, encrypted) = match &compressed {
+                    Some((_, compressed)) => Secret::encrypt_convergent(&convergence, compressed)?,
+                    None => Secret::encrypt_convergent(&convergence, &chunk)?,
+                };
+                let chunk_compression = compressed.map(|(codec, _)| codec);
+                Ok::<_, MountError>((rest, cut, chunk_secret, encrypted, chunk_compression))
+            })
+            .await?;
+        buf = rest;
+
+        let hash = blobs.put(encrypted).await?;
+        hashes.push(hash);
+        list.chunks.push(ChunkRef {
+            link: Link::new(LD_RAW_CODEC, hash),
+            secret: chunk_secret,
+            size: size as u64,
+            compression: chunk_compression,
+        });
+    }
+
+    let link = list.store(blobs, secret).await?;
+    hashes.push(link.hash());
+    Ok((link, hashes))
+}
+
+/// A blocking reader read in place as an async one
+///
+/// Each read blocks the task for as long as the reader does, which is fine
+/// for readers over memory or local files but not for network streams.
+pub(crate) struct SyncReader<R>(pub R);
+
+impl<R: Read + Unpin> AsyncRead for SyncReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let n = self.get_mut().0.read(buf.initialize_unfilled())?;
+        buf.advance(n);
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Length of the first chunk of `data`, between `min_size` and `max_size`
+/// bytes, cutting where the low `avg_bits` bits of a gear hash of the
+/// preceding bytes are zero.
+///
+/// Only the first `max_size` bytes of `data` are looked at, so a stream can
+/// be cut from a buffer holding that much of it.
+fn cut_point(data: &[u8], min_size: usize, avg_bits: u32, max_size: usize) -> usize {
+    let mask = (1u64 << avg_bits) - 1;
+    let end = max_size.min(data.len());
+    let mut hash = 0u64;
+    for (i, byte) in data[..end].iter().enumerate().skip(min_size) {
+        hash = (hash << 1).wrapping_add(GEAR[*byte as usize]);
+        if hash & mask == 0 {
+            return i + 1;
+        }
+    }
+    end
+}
+
+/// Per-byte values of the gear hash, fixed so that every peer cuts the same
+/// data at the same places
+static GEAR: [u64; 256] = gear_table();
+
+const fn gear_table() -> [u64; 256] {
+    // splitmix64
+    let mut table = [0u64; 256];
+    let mut state = 0x6a09_e667_f3bc_c908u64;
+    let mut i = 0;
+    while i < 256 {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        table[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    table
+}
+
+#[cfg(test)]
+mod test {
+    use std::ops::Range;
+
+    use super::*;
+
+    /// Split `data` into chunks as [`cut_point`] cuts them
+    fn boundaries(
+        data: &[u8],
+        min_size: usize,
+        avg_bits: u32,
+        max_size: usize,
+    ) -> Vec<Range<usize>> {
+        let mut chunks = Vec::new();
+        let mut start = 0;
+        while start < data.len() {
+            let cut = start + cut_point(&data[start..], min_size, avg_bits, max_size);
+            chunks.push(start..cut);
+            start = cut;
+        }
+        chunks
+    }
+
+    fn pseudo_random(len: usize, seed: u64) -> Vec<u8> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_boundaries_cover_data() {
+        let data = pseudo_random(200_000, 1);
+        let chunks = boundaries(&data, 1024, 12, 16 * 1024);
+        assert!(chunks.len() > 1);
+        assert_eq!(chunks.first().unwrap().start, 0);
+        assert_eq!(chunks.last().unwrap().end, data.len());
+        for pair in chunks.windows(2) {
+            assert_eq!(pair[0].end, pair[1].start);
+        }
+        for chunk in &chunks[..chunks.len() - 1] {
+            assert!(chunk.len() >= 1024 && chunk.len() <= 16 * 1024);
+        }
+    }
+
+    #[test]
+    fn test_boundaries_survive_edit() {
+        let data = pseudo_random(200_000, 2);
+        let mut edited = data.clone();
+        // Insert a few bytes in the middle
+        edited.splice(100_000..100_000, b"an edit".iter().copied());
+
+        let chunk_set = |data: &[u8]| -> Vec<Vec<u8>> {
+            boundaries(data, 1024, 12, 16 * 1024)
+                .into_iter()
+                .map(|range| data[range].to_vec())
+                .collect()
+        };
+        let before = chunk_set(&data);
+        let after = chunk_set(&edited);
+
+        let unchanged = after.iter().filter(|chunk| before.contains(chunk)).count();
+        // Only the chunks around the edit change
+        assert!(unchanged + 2 >= after.len());
+        assert!(unchanged + 2 >= before.len());
+    }
+
+    /// Hands out at most a few bytes per read, like a slow socket
+    struct Trickle<'a>(&'a [u8]);
+
+    impl AsyncRead for Trickle<'_> {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            let n = buf.remaining().min(self.0.len()).min(1000);
+            buf.put_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_put_chunked_streams_like_slice() {
+        let blobs = BlobsStore::memory().await.unwrap();
+        let secret = Secret::generate();
+        let data = pseudo_random(9 * 1024 * 1024 + 17, 3);
+
+        let (_, from_slice) = put_chunked(&blobs, &secret, &data[..], None, None)
+            .await
+            .unwrap();
+        let (link, streamed) = put_chunked(&blobs, &secret, Trickle(&data), None, None)
+            .await
+            .unwrap();
+
+        // Chunks are cut at the same places however the bytes arrive; only
+        //  the chunk list itself is encrypted with a fresh nonce
+        assert!(from_slice.len() > 2);
+        assert_eq!(
+            from_slice[..from_slice.len() - 1],
+            streamed[..streamed.len() - 1]
+        );
+        let list = ChunkList::load(&blobs, &link, &secret).await.unwrap();
+        assert_eq!(list.size(), data.len() as u64);
+        assert_eq!(list.read(&blobs).await.unwrap(), data);
+    }
+
+    #[tokio::test]
+    async fn test_append_chunked_keeps_existing_chunks() {
+        let blobs = BlobsStore::memory().await.unwrap();
+        let secret = Secret::generate();
+        let first = pseudo_random(5 * 1024 * 1024, 4);
+        let more = pseudo_random(1000, 5);
+
+        let (link, _) = put_chunked(&blobs, &secret, &first[..], None, None)
+            .await
+            .unwrap();
+        let list = ChunkList::load(&blobs, &link, &secret).await.unwrap();
+        let (link, written) = append_chunked(&blobs, &secret, list.clone(), &more[..], None, None)
+            .await
+            .unwrap();
+
+        // One new chunk and the new list, the old chunks untouched
+        assert_eq!(written.len(), 2);
+        let appended = ChunkList::load(&blobs, &link, &secret).await.unwrap();
+        assert_eq!(appended.chunks()[..list.chunks().len()], list.chunks()[..]);
+        assert_eq!(appended.chunks().len(), list.chunks().len() + 1);
+        assert_eq!(appended.read(&blobs).await.unwrap(), [first, more].concat());
+    }
+}
@@ -0,0 +1,179 @@
+//! Comments on files and directories
+//!
+//! Peers attach short [`Comment`]s to a path as it was at a bucket version.
+//! A bucket's comments are kept in one [`Comments`] blob next to the entry
+//! tree, encrypted with the version's secret like the ops log, linked from
+//! the manifest and pinned, so they sync along with the version.
+//!
+//! Unlike the ops log, the comments carry over from one version to the next.
+//! Removed comments leave a tombstone, so merging diverged histories (see
+//! [`Mount::merge_from`](super::Mount::merge_from)) takes the union of both
+//! sides' comments without bringing removed ones back.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::crypto::PublicKey;
+use crate::linked_data::{BlockEncoded, DagCborCodec, Hash};
+
+/// Longest comment body, in bytes
+pub const MAX_COMMENT_LEN: usize = 4 * 1024;
+
+/// A comment on a file or directory
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Comment {
+    pub id: Uuid,
+    /// Absolute path of the file or directory commented on
+    pub path: String,
+    /// Hash of the bucket version the comment refers to
+    pub version: Hash,
+    /// Peer that wrote the comment
+    pub author: PublicKey,
+    pub body: String,
+    /// When the comment was written, in seconds since the Unix epoch
+    pub created_at: i64,
+}
+
+/// The comments of a bucket, and the IDs of those removed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Comments {
+    comments: BTreeMap<Uuid, Comment>,
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    removed: BTreeSet<Uuid>,
+}
+
+impl BlockEncoded<DagCborCodec> for Comments {}
+
+impl Comments {
+    /// Whether there are no comments, nor any removed
+    pub fn is_empty(&self) -> bool {
+        self.comments.is_empty() && self.removed.is_empty()
+    }
+
+    /// Comments on every path, oldest first
+    pub fn all(&self) -> Vec<&Comment> {
+        let mut comments: Vec<_> = self.comments.values().collect();
+        comments.sort_by_key(|c| (c.created_at, c.id));
+        comments
+    }
+
+    /// Comments on one path, oldest first
+    pub fn on_path(&self, path: &str) -> Vec<&Comment> {
+        self.all().into_iter().filter(|c| c.path == path).collect()
+    }
+
+    pub fn get(&self, id: &Uuid) -> Option<&Comment> {
+        self.comments.get(id)
+    }
+
+    /// Add a comment. Comments removed before can't be added back.
+    pub fn insert(&mut self, comment: Comment) {
+        if !self.removed.contains(&comment.id) {
+            self.comments.insert(comment.id, comment);
+        }
+    }
+
+    /// Remove a comment, returning it if there was one
+    pub fn remove(&mut self, id: &Uuid) -> Option<Comment> {
+        let comment = self.comments.remove(id)?;
+        self.removed.insert(*id);
+        Some(comment)
+    }
+
+    /// Add the comments of another history, and drop those it removed
+    pub fn merge(&mut self, other: &Comments) {
+        self.removed.extend(other.removed.iter().copied());
+        for comment in other.comments.values() {
+            self.comments
+                .entry(comment.id)
+                .or_insert_with(|| comment.clone());
+        }
+        let removed = &self.removed;
+        self.comments.retain(|id, _| !removed.contains(id));
+    }
+}
+
+/// Check that a comment body is non-empty and within [`MAX_COMMENT_LEN`]
+pub fn validate_comment(body: &str) -> Result<(), String> {
+    if body.trim().is_empty() {
+        return Err("comment must not be empty".to_string());
+    }
+    if body.len() > MAX_COMMENT_LEN {
+        return Err(format!("comment is longer than {} bytes", MAX_COMMENT_LEN));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::crypto::SecretKey;
+
+    fn comment(path: &str, body: &str, created_at: i64) -> Comment {
+        Comment {
+            id: Uuid::new_v4(),
+            path: path.to_string(),
+            version: Hash::new(b"version"),
+            author: SecretKey::generate().public(),
+            body: body.to_string(),
+            created_at,
+        }
+    }
+
+    #[test]
+    fn test_comments_roundtrip() {
+        let mut comments = Comments::default();
+        comments.insert(comment("/a.txt", "first", 1));
+        let removed = comment("/a.txt", "gone", 2);
+        comments.insert(removed.clone());
+        comments.remove(&removed.id);
+
+        let decoded = Comments::decode(&comments.encode().unwrap()).unwrap();
+        assert_eq!(decoded, comments);
+        assert_eq!(decoded.all().len(), 1);
+    }
+
+    #[test]
+    fn test_on_path_is_oldest_first() {
+        let mut comments = Comments::default();
+        comments.insert(comment("/a.txt", "second", 2));
+        comments.insert(comment("/b.txt", "other", 0));
+        comments.insert(comment("/a.txt", "first", 1));
+
+        let bodies: Vec<_> = comments
+            .on_path("/a.txt")
+            .iter()
+            .map(|c| c.body.as_str())
+            .collect();
+        assert_eq!(bodies, vec!["first", "second"]);
+    }
+
+    #[test]
+    fn test_merge_keeps_removals() {
+        let shared = comment("/a.txt", "shared", 1);
+        let mut local = Comments::default();
+        local.insert(shared.clone());
+        let mut incoming = local.clone();
+
+        local.remove(&shared.id);
+        let added = comment("/b.txt", "added", 2);
+        incoming.insert(added.clone());
+
+        local.merge(&incoming);
+        assert!(local.get(&shared.id).is_none());
+        assert_eq!(local.get(&added.id), Some(&added));
+
+        // Removed comments stay removed when added again
+        local.insert(shared);
+        assert_eq!(local.all().len(), 1);
+    }
+
+    #[test]
+    fn test_validate_comment() {
+        assert!(validate_comment("looks good").is_ok());
+        assert!(validate_comment("  ").is_err());
+        assert!(validate_comment(&"x".repeat(MAX_COMMENT_LEN + 1)).is_err());
+    }
+}
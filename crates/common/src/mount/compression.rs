@@ -0,0 +1,134 @@
+//! Transparent compression of file data before encryption
+//!
+//! Encrypted blobs are indistinguishable from random data, so compression has
+//! to happen before encryption if it is going to happen at all. Buckets opt in
+//! by setting a [`Compression`] in their manifest; each file then records the
+//! codec that was actually applied to its bytes in its node [`Data`](super::node::Data),
+//! so readers can decompress transparently regardless of the bucket's current setting.
+
+use mime::Mime;
+use serde::{Deserialize, Serialize};
+
+/// Default zstd compression level
+const ZSTD_LEVEL: i32 = 3;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CompressionError {
+    #[error("compression i/o error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Compression codec applied to file contents before encryption.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Compression {
+    Zstd,
+}
+
+impl Compression {
+    /// Compress `data` with this codec.
+    pub fn compress(&self, data: &[u8]) -> Result<Vec<u8>, CompressionError> {
+        match self {
+            Compression::Zstd => Ok(zstd::encode_all(data, ZSTD_LEVEL)?),
+        }
+    }
+
+    /// Decompress `data` previously produced by [`Compression::compress`].
+    pub fn decompress(&self, data: &[u8]) -> Result<Vec<u8>, CompressionError> {
+        match self {
+            Compression::Zstd => Ok(zstd::decode_all(data)?),
+        }
+    }
+
+    /// Compress `data` only if it is worth it.
+    ///
+    /// Returns `None` when the content type is already compressed (images,
+    /// video, archives, ...) or when compression would not shrink the data.
+    pub fn maybe_compress(
+        &self,
+        data: &[u8],
+        mime: Option<&Mime>,
+    ) -> Result<Option<Vec<u8>>, CompressionError> {
+        if !is_compressible(mime) {
+            return Ok(None);
+        }
+        let compressed = self.compress(data)?;
+        if compressed.len() < data.len() {
+            Ok(Some(compressed))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Whether content of the given MIME type is likely to benefit from compression.
+fn is_compressible(mime: Option<&Mime>) -> bool {
+    let Some(mime) = mime else {
+        // Unknown types are tried; incompressible data is caught by the size check
+        return true;
+    };
+
+    match mime.type_() {
+        mime::TEXT => true,
+        mime::IMAGE => mime.subtype() == mime::SVG,
+        mime::VIDEO | mime::AUDIO | mime::FONT => false,
+        _ => !matches!(
+            mime.subtype().as_str(),
+            "zip"
+                | "gzip"
+                | "x-gzip"
+                | "zstd"
+                | "x-bzip2"
+                | "x-xz"
+                | "x-7z-compressed"
+                | "x-rar-compressed"
+                | "vnd.rar"
+                | "pdf"
+                | "epub+zip"
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_zstd_roundtrip() {
+        let data = "hello world ".repeat(1000).into_bytes();
+        let compressed = Compression::Zstd.compress(&data).unwrap();
+        assert!(compressed.len() < data.len());
+        let decompressed = Compression::Zstd.decompress(&compressed).unwrap();
+        assert_eq!(decompressed, data);
+    }
+
+    #[test]
+    fn test_maybe_compress_skips_compressed_types() {
+        let data = "a".repeat(4096).into_bytes();
+        let jpeg: Mime = "image/jpeg".parse().unwrap();
+        let zip: Mime = "application/zip".parse().unwrap();
+        let text: Mime = "text/plain".parse().unwrap();
+
+        assert!(Compression::Zstd
+            .maybe_compress(&data, Some(&jpeg))
+            .unwrap()
+            .is_none());
+        assert!(Compression::Zstd
+            .maybe_compress(&data, Some(&zip))
+            .unwrap()
+            .is_none());
+        assert!(Compression::Zstd
+            .maybe_compress(&data, Some(&text))
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn test_maybe_compress_skips_incompressible_data() {
+        let data: Vec<u8> = (0..64u8).collect();
+        assert!(Compression::Zstd
+            .maybe_compress(&data, None)
+            .unwrap()
+            .is_none());
+    }
+}
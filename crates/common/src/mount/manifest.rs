@@ -13,6 +13,10 @@
 //! - **Owners** have an encrypted [`SecretShare`] that they can decrypt with their private key
 //! - **Mirrors** have no individual share; they use [`Manifest::public`] when available
 //! - **Publishing** stores the bucket's secret in plaintext, making it readable by anyone with the manifest
+//! - **Subtree shares** give a peer the secret of one directory's node instead of the bucket's,
+//!   so it reads that directory and nothing above or beside it (see [`SubtreeShare`])
+//! - **Ephemeral shares** wrap the secret for a throwaway key until they expire, so the
+//!   recipient's own identity never appears in the manifest (see [`EphemeralShare`])
 //!
 //! ## Versioning
 //!
@@ -27,10 +31,13 @@ use std::collections::BTreeMap;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::crypto::{PublicKey, Secret, SecretKey, SecretShare, Signature};
+use crate::crypto::{
+    KemPublicKey, PublicKey, Secret, SecretKey, SecretShare, SecretShareError, Signature,
+};
 use crate::linked_data::{BlockEncoded, CodecError, DagCborCodec, Link};
 use crate::version::Version;
 
+use super::compression::Compression;
 use super::principal::{Principal, PrincipalRole};
 
 /// Errors that can occur during manifest operations.
@@ -49,12 +56,18 @@ pub enum ManifestError {
 ///
 /// - **Owners**: Always have `Some(SecretShare)` encrypted to their public key
 /// - **Mirrors**: Always have `None`; use the manifest's `public` secret instead
+///
+/// Owners that announced an ML-KEM key get post-quantum hybrid shares (see
+/// [`SecretShare::new_hybrid`]) each time the bucket's secret is shared.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Share {
     principal: Principal,
     /// The encrypted share of the bucket's secret key.
     /// Only owners have this; mirrors use the manifest's public secret instead.
     share: Option<SecretShare>,
+    /// The principal's ML-KEM key, if its shares are wrapped with it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    kem_key: Option<KemPublicKey>,
 }
 
 impl Share {
@@ -66,6 +79,7 @@ impl Share {
                 identity: public_key,
             },
             share: Some(share),
+            kem_key: None,
         }
     }
 
@@ -80,6 +94,7 @@ impl Share {
                 identity: public_key,
             },
             share: None,
+            kem_key: None,
         }
     }
 
@@ -102,12 +117,32 @@ impl Share {
         &self.principal.role
     }
 
+    /// Get the principal's ML-KEM key, if its shares are wrapped with it.
+    pub fn kem_key(&self) -> Option<&KemPublicKey> {
+        self.kem_key.as_ref()
+    }
+
+    /// Wrap `secret` for this principal: with its ML-KEM key as well as
+    /// X25519 if it has one, and with X25519 alone otherwise.
+    pub fn wrap(&self, secret: &Secret) -> Result<SecretShare, SecretShareError> {
+        match &self.kem_key {
+            Some(kem_key) => SecretShare::new_hybrid(secret, &self.principal.identity, kem_key),
+            None => SecretShare::new(secret, &self.principal.identity),
+        }
+    }
+
     /* Setters */
 
     /// Set the encrypted secret share.
     pub fn set_share(&mut self, share: SecretShare) {
         self.share = Some(share);
     }
+
+    /// Set or clear the principal's ML-KEM key; its shares are hybrid, or
+    /// classic again, from the next time the bucket's secret is shared.
+    pub fn set_kem_key(&mut self, kem_key: Option<KemPublicKey>) {
+        self.kem_key = kem_key;
+    }
 }
 
 /// Map of hex-encoded public keys to their shares.
@@ -115,13 +150,146 @@ impl Share {
 /// Uses `String` keys (hex-encoded [`PublicKey`]) for CBOR serialization compatibility.
 pub type Shares = BTreeMap<String, Share>;
 
+/// A peer's read access to one directory of a bucket.
+///
+/// Every node is encrypted with its own secret, held by its parent, so the
+/// secret of a directory's node opens that directory and everything below it
+/// but nothing else. A subtree share wraps that secret for the peer, which
+/// mounts the directory as the root of a read-only view of the bucket.
+///
+/// A directory's node gets a new secret and link whenever something below it
+/// changes, so owners re-wrap subtree shares on every save. While the path
+/// doesn't exist, e.g. after it was moved away, the share holds nothing.
+///
+/// The path itself is stored in plaintext, like the rest of the manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubtreeShare {
+    identity: PublicKey,
+    /// Absolute path of the shared directory
+    path: String,
+    /// Link to the directory's node in this version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    node: Option<Link>,
+    /// The directory node's secret, encrypted to the peer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    share: Option<SecretShare>,
+}
+
+impl SubtreeShare {
+    /// Create a subtree share of `path`, filled in when the bucket is saved.
+    pub fn new(identity: PublicKey, path: String) -> Self {
+        Self {
+            identity,
+            path,
+            node: None,
+            share: None,
+        }
+    }
+
+    /// Get the peer's public key.
+    pub fn identity(&self) -> &PublicKey {
+        &self.identity
+    }
+
+    /// Get the absolute path of the shared directory.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Get the link to the directory's node, if the path exists in this version.
+    pub fn node(&self) -> Option<&Link> {
+        self.node.as_ref()
+    }
+
+    /// Get the directory node's encrypted secret, if the path exists in this version.
+    pub fn share(&self) -> Option<&SecretShare> {
+        self.share.as_ref()
+    }
+
+    /// Share the directory's node at `node`, wrapping its secret for the peer.
+    pub fn set(&mut self, node: Link, secret: &Secret) -> Result<(), SecretShareError> {
+        self.share = Some(SecretShare::new(secret, &self.identity)?);
+        self.node = Some(node);
+        Ok(())
+    }
+
+    /// Share nothing, while the path doesn't exist.
+    pub fn clear(&mut self) {
+        self.node = None;
+        self.share = None;
+    }
+}
+
+/// Map of hex-encoded public keys to their subtree shares.
+pub type SubtreeShares = BTreeMap<String, SubtreeShare>;
+
+/// Read access for a temporary identity, until it expires.
+///
+/// The identity is a throwaway key generated for one share and handed to the
+/// recipient out of band, so the manifest records that the bucket was shared
+/// but not with whom. The recipient reads the whole bucket with it, like an
+/// owner, but can't write.
+///
+/// Owners re-wrap the bucket's secret for unexpired ephemeral shares on every
+/// save and drop expired ones. Versions saved before the expiry stay readable
+/// with the key; versions saved after it don't.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EphemeralShare {
+    identity: PublicKey,
+    /// Unix time in seconds after which the share is dropped
+    expires_at: i64,
+    /// The bucket's secret, encrypted to the temporary identity
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    share: Option<SecretShare>,
+}
+
+impl EphemeralShare {
+    /// Create an ephemeral share, filled in when the bucket is saved.
+    pub fn new(identity: PublicKey, expires_at: i64) -> Self {
+        Self {
+            identity,
+            expires_at,
+            share: None,
+        }
+    }
+
+    /// Get the temporary identity's public key.
+    pub fn identity(&self) -> &PublicKey {
+        &self.identity
+    }
+
+    /// Get the Unix time in seconds after which the share is dropped.
+    pub fn expires_at(&self) -> i64 {
+        self.expires_at
+    }
+
+    /// Check whether the share expired at Unix time `now`.
+    pub fn is_expired(&self, now: i64) -> bool {
+        now >= self.expires_at
+    }
+
+    /// Get the bucket's encrypted secret, once the bucket was saved.
+    pub fn share(&self) -> Option<&SecretShare> {
+        self.share.as_ref()
+    }
+
+    /// Wrap the bucket's secret for the temporary identity.
+    pub fn set(&mut self, secret: &Secret) -> Result<(), SecretShareError> {
+        self.share = Some(SecretShare::new(secret, &self.identity)?);
+        Ok(())
+    }
+}
+
+/// Map of hex-encoded temporary public keys to their ephemeral shares.
+pub type EphemeralShares = BTreeMap<String, EphemeralShare>;
+
 /// The root metadata structure for a bucket.
 ///
 /// A manifest contains everything needed to access and verify a bucket:
 ///
 /// - **Identity**: Global UUID and human-readable name
 /// - **Access control**: Principal shares for decryption
-/// - **Content pointers**: Links to entry node, pin set, and crdt op log
+/// - **Content pointers**: Links to entry node, pin set, crdt op log and comments
 /// - **Version chain**: Previous link and height for history
 /// - **Publication**: Optional plaintext secret for public access
 ///
@@ -155,6 +323,12 @@ pub struct Manifest {
     version: Version,
     /// Map of principal public keys (hex) to their shares.
     shares: Shares,
+    /// Map of public keys (hex) to their shares of a single directory.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    subtree_shares: SubtreeShares,
+    /// Map of temporary public keys (hex) to their shares, until they expire.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    ephemeral_shares: EphemeralShares,
     /// Link to the root [`Node`](super::Node) of the file tree.
     entry: Link,
     /// Link to the [`Pins`](super::Pins) blob hash set.
@@ -164,12 +338,25 @@ pub struct Manifest {
     /// Optional link to the encrypted path operations log (CRDT).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     ops_log: Option<Link>,
+    /// Optional link to the encrypted [`Comments`](super::Comments) blob.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    comments: Option<Link>,
     /// Plaintext secret for public read access.
     ///
     /// When set, anyone with the manifest can decrypt bucket contents.
     /// Publishing is opt-in per version via `save(publish: true)`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     public: Option<Secret>,
+    /// Compression applied to new file contents before encryption.
+    ///
+    /// Existing files keep whatever compression they were written with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    compression: Option<Compression>,
+    /// Whether to extract image and video metadata from new files.
+    ///
+    /// See [`super::MediaInfo`]; existing files are not revisited.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    extract_media: bool,
     /// Public key of the peer who signed this manifest.
     ///
     /// Set when the manifest is signed via [`Manifest::sign`].
@@ -216,15 +403,21 @@ impl Manifest {
                         identity: owner,
                     },
                     share: Some(share),
+                    kem_key: None,
                 },
             )]),
+            subtree_shares: BTreeMap::new(),
+            ephemeral_shares: BTreeMap::new(),
             entry,
             pins,
             previous: None,
             height,
             version: Version::default(),
             ops_log: None,
+            comments: None,
             public: None,
+            compression: None,
+            extract_media: false,
             author: None,
             signature: None,
         }
@@ -272,6 +465,11 @@ impl Manifest {
         self.ops_log.as_ref()
     }
 
+    /// Get the comments link, if the bucket has any comments.
+    pub fn comments(&self) -> Option<&Link> {
+        self.comments.as_ref()
+    }
+
     /// Get all shares.
     pub fn shares(&self) -> &BTreeMap<String, Share> {
         &self.shares
@@ -290,11 +488,49 @@ impl Manifest {
     /// Get all peer public keys from shares.
     pub fn get_peer_ids(&self) -> Vec<PublicKey> {
         self.shares
-            .iter()
-            .filter_map(|(key_hex, _)| PublicKey::from_hex(key_hex).ok())
+            .keys()
+            .filter_map(|key_hex| PublicKey::from_hex(key_hex).ok())
             .collect()
     }
 
+    /// Get all subtree shares.
+    pub fn subtree_shares(&self) -> &SubtreeShares {
+        &self.subtree_shares
+    }
+
+    /// Get mutable access to subtree shares.
+    pub fn subtree_shares_mut(&mut self) -> &mut SubtreeShares {
+        &mut self.subtree_shares
+    }
+
+    /// Get a peer's subtree share by their public key.
+    pub fn get_subtree_share(&self, public_key: &PublicKey) -> Option<&SubtreeShare> {
+        self.subtree_shares.get(&public_key.to_hex())
+    }
+
+    /// Get the public keys of peers with a subtree share.
+    pub fn get_subtree_peer_ids(&self) -> Vec<PublicKey> {
+        self.subtree_shares
+            .values()
+            .map(|share| *share.identity())
+            .collect()
+    }
+
+    /// Get all ephemeral shares.
+    pub fn ephemeral_shares(&self) -> &EphemeralShares {
+        &self.ephemeral_shares
+    }
+
+    /// Get mutable access to ephemeral shares.
+    pub fn ephemeral_shares_mut(&mut self) -> &mut EphemeralShares {
+        &mut self.ephemeral_shares
+    }
+
+    /// Get the ephemeral share of a temporary identity by its public key.
+    pub fn get_ephemeral_share(&self, public_key: &PublicKey) -> Option<&EphemeralShare> {
+        self.ephemeral_shares.get(&public_key.to_hex())
+    }
+
     /// Get all shares with a specific role.
     pub fn get_shares_by_role(&self, role: PrincipalRole) -> Vec<&Share> {
         self.shares.values().filter(|s| *s.role() == role).collect()
@@ -313,6 +549,16 @@ impl Manifest {
         self.public.as_ref()
     }
 
+    /// Get the compression applied to new file contents.
+    pub fn compression(&self) -> Option<Compression> {
+        self.compression
+    }
+
+    /// Whether image and video metadata is extracted from new files.
+    pub fn extract_media(&self) -> bool {
+        self.extract_media
+    }
+
     /// Get the author (signer's public key) if the manifest is signed.
     pub fn author(&self) -> Option<&PublicKey> {
         self.author.as_ref()
@@ -363,6 +609,21 @@ impl Manifest {
         self.ops_log = None;
     }
 
+    /// Set or clear the comments link.
+    pub fn set_comments(&mut self, link: Option<Link>) {
+        self.comments = link;
+    }
+
+    /// Set the compression applied to new file contents.
+    pub fn set_compression(&mut self, compression: Option<Compression>) {
+        self.compression = compression;
+    }
+
+    /// Set whether image and video metadata is extracted from new files.
+    pub fn set_extract_media(&mut self, extract_media: bool) {
+        self.extract_media = extract_media;
+    }
+
     /// Add a share to the manifest.
     ///
     /// Use [`Share::new_owner`] or [`Share::new_mirror`] to construct the share.
@@ -371,6 +632,18 @@ impl Manifest {
         self.shares.insert(key, share);
     }
 
+    /// Add a subtree share, replacing any the peer already had.
+    pub fn add_subtree_share(&mut self, share: SubtreeShare) {
+        let key = share.identity().to_hex();
+        self.subtree_shares.insert(key, share);
+    }
+
+    /// Add an ephemeral share for a temporary identity.
+    pub fn add_ephemeral_share(&mut self, share: EphemeralShare) {
+        let key = share.identity().to_hex();
+        self.ephemeral_shares.insert(key, share);
+    }
+
     /// Publish the bucket by storing the secret in plaintext.
     ///
     /// **Warning**: Once published, this version's secret is exposed
@@ -560,6 +833,52 @@ mod tests {
         assert!(!decoded.verify_signature().unwrap());
     }
 
+    #[test]
+    fn test_subtree_shares_serialize() {
+        use ipld_core::codec::Codec;
+        use serde_ipld_dagcbor::codec::DagCborCodec;
+
+        let peer = SecretKey::generate().public();
+        let mut manifest = create_test_manifest();
+        let mut subtree_share = SubtreeShare::new(peer, "/clients/acme".to_string());
+        subtree_share
+            .set(Link::default(), &Secret::generate())
+            .unwrap();
+        manifest.add_subtree_share(subtree_share.clone());
+
+        let encoded = DagCborCodec::encode_to_vec(&manifest).unwrap();
+        let decoded: Manifest = DagCborCodec::decode_from_slice(&encoded).unwrap();
+        assert_eq!(decoded.get_subtree_share(&peer), Some(&subtree_share));
+        assert_eq!(decoded.get_subtree_peer_ids(), vec![peer]);
+
+        // Manifests without any are unchanged on the wire
+        let plain = create_test_manifest();
+        let encoded = DagCborCodec::encode_to_vec(&plain).unwrap();
+        let decoded: Manifest = DagCborCodec::decode_from_slice(&encoded).unwrap();
+        assert!(decoded.subtree_shares().is_empty());
+    }
+
+    #[test]
+    fn test_ephemeral_shares_serialize() {
+        use ipld_core::codec::Codec;
+        use serde_ipld_dagcbor::codec::DagCborCodec;
+
+        let temporary = SecretKey::generate();
+        let mut manifest = create_test_manifest();
+        let mut ephemeral_share = EphemeralShare::new(temporary.public(), 1_000);
+        ephemeral_share.set(&Secret::generate()).unwrap();
+        manifest.add_ephemeral_share(ephemeral_share.clone());
+
+        let encoded = DagCborCodec::encode_to_vec(&manifest).unwrap();
+        let decoded: Manifest = DagCborCodec::decode_from_slice(&encoded).unwrap();
+        let share = decoded.get_ephemeral_share(&temporary.public()).unwrap();
+        assert_eq!(share, &ephemeral_share);
+        assert!(!share.is_expired(999));
+        assert!(share.is_expired(1_000));
+        // Not a peer of the bucket
+        assert!(!decoded.get_peer_ids().contains(&temporary.public()));
+    }
+
     #[test]
     fn test_manifest_wrong_key_verification() {
         let secret_key1 = SecretKey::generate();
@@ -15,6 +15,15 @@ impl MaybeMime {
             .and_then(|m| m.as_ref().parse().ok());
         MaybeMime(mime)
     }
+
+    /// Detect the MIME type from a file path's extension, or failing that
+    /// from the file's first bytes (see [`super::sniff`])
+    pub fn detect(path: &Path, head: &[u8]) -> Self {
+        match Self::from_path(path) {
+            MaybeMime(None) => MaybeMime(super::sniff::sniff(head)),
+            known => known,
+        }
+    }
 }
 
 impl Serialize for MaybeMime {
@@ -138,6 +147,21 @@ mod tests {
         assert_eq!(format!("{:?}", maybe_mime_none), "MaybeMime(None)");
     }
 
+    #[test]
+    fn test_detect_prefers_extension() {
+        let png = b"\x89PNG\r\n\x1a\n";
+        let detected = MaybeMime::detect(Path::new("/notes.txt"), png);
+        assert_eq!(
+            detected.0.map(|m| m.to_string()).as_deref(),
+            Some("text/plain")
+        );
+        let detected = MaybeMime::detect(Path::new("/screenshot"), png);
+        assert_eq!(
+            detected.0.map(|m| m.to_string()).as_deref(),
+            Some("image/png")
+        );
+    }
+
     #[test]
     fn test_clone_and_equality() {
         let mime = "application/xml".parse::<Mime>().unwrap();
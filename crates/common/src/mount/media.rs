@@ -0,0 +1,498 @@
+//! Image and video metadata extracted at upload
+//!
+//! Buckets that opt in (see [`super::Manifest::extract_media`]) record the
+//! dimensions, capture date and duration of photos and videos in their
+//! encrypted node [`Data`](super::node::Data), so clients can sort and lay
+//! them out without downloading each file. Only container headers are
+//! parsed: EXIF in JPEG, the image headers of PNG, GIF and WebP, and the
+//! `moov` box of MP4 and QuickTime files. Only the first
+//! [`CHUNKING_THRESHOLD`](super::CHUNKING_THRESHOLD) bytes are looked at, so
+//! the `moov` box of a larger video is only found if it was written at the
+//! front ("fast start").
+
+use std::collections::BTreeMap;
+
+use chrono::NaiveDateTime;
+use mime::Mime;
+use serde::{Deserialize, Serialize};
+
+use crate::linked_data::LinkedData;
+
+/// Metadata keys, as stored in a file's node data
+const WIDTH: &str = "width";
+const HEIGHT: &str = "height";
+const CAPTURED_AT: &str = "captured_at";
+const DURATION_MS: &str = "duration_ms";
+
+/// Seconds between the MP4 epoch (1904-01-01) and the Unix epoch
+const MP4_EPOCH_OFFSET: u64 = 2_082_844_800;
+
+/// What is known about a photo or video
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MediaInfo {
+    /// Width in pixels, as displayed (EXIF rotation applied)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u64>,
+    /// Height in pixels, as displayed (EXIF rotation applied)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<u64>,
+    /// When the photo or video was taken, in seconds since the Unix epoch.
+    /// EXIF dates carry no time zone and are taken to be UTC.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub captured_at: Option<i64>,
+    /// Length of a video or audio track in milliseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+}
+
+impl MediaInfo {
+    /// Extract what the file's headers tell, for the types this understands
+    pub fn extract(mime: Option<&Mime>, head: &[u8]) -> Self {
+        let Some(mime) = mime else {
+            return Self::default();
+        };
+        match (mime.type_(), mime.subtype().as_str()) {
+            (mime::IMAGE, "jpeg") => jpeg(head),
+            (mime::IMAGE, "png") => png(head),
+            (mime::IMAGE, "gif") => gif(head),
+            (mime::IMAGE, "webp") => webp(head),
+            (mime::VIDEO, "mp4" | "quicktime" | "x-m4v") | (mime::AUDIO, "mp4" | "m4a") => {
+                mp4(head)
+            }
+            _ => Self::default(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Read back what [`MediaInfo::to_metadata`] stored
+    pub fn from_metadata(metadata: &BTreeMap<String, LinkedData>) -> Self {
+        let int = |key: &str| match metadata.get(key) {
+            Some(LinkedData::Integer(value)) => Some(*value),
+            _ => None,
+        };
+        Self {
+            width: int(WIDTH).and_then(|v| u64::try_from(v).ok()),
+            height: int(HEIGHT).and_then(|v| u64::try_from(v).ok()),
+            captured_at: int(CAPTURED_AT).and_then(|v| i64::try_from(v).ok()),
+            duration_ms: int(DURATION_MS).and_then(|v| u64::try_from(v).ok()),
+        }
+    }
+
+    /// The metadata entries to store for this file
+    pub fn to_metadata(&self) -> Vec<(String, LinkedData)> {
+        [
+            (WIDTH, self.width.map(i128::from)),
+            (HEIGHT, self.height.map(i128::from)),
+            (CAPTURED_AT, self.captured_at.map(i128::from)),
+            (DURATION_MS, self.duration_ms.map(i128::from)),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| Some((key.to_string(), LinkedData::Integer(value?))))
+        .collect()
+    }
+}
+
+fn be16(b: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(b.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn be32(b: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(b.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn be64(b: &[u8], at: usize) -> Option<u64> {
+    Some(u64::from_be_bytes(b.get(at..at + 8)?.try_into().ok()?))
+}
+
+fn le16(b: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(b.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn le24(b: &[u8], at: usize) -> Option<u32> {
+    let b = b.get(at..at + 3)?;
+    Some(u32::from(b[0]) | u32::from(b[1]) << 8 | u32::from(b[2]) << 16)
+}
+
+fn png(b: &[u8]) -> MediaInfo {
+    MediaInfo {
+        width: be32(b, 16).map(u64::from),
+        height: be32(b, 20).map(u64::from),
+        ..Default::default()
+    }
+}
+
+fn gif(b: &[u8]) -> MediaInfo {
+    MediaInfo {
+        width: le16(b, 6).map(u64::from),
+        height: le16(b, 8).map(u64::from),
+        ..Default::default()
+    }
+}
+
+fn webp(b: &[u8]) -> MediaInfo {
+    let (width, height) = match b.get(12..16) {
+        // Lossy: dimensions follow the frame tag and start code
+        Some(b"VP8 ") => (
+            le16(b, 26).map(|w| w & 0x3fff),
+            le16(b, 28).map(|h| h & 0x3fff),
+        ),
+        // Lossless: 14-bit dimensions minus one, packed after the signature
+        Some(b"VP8L") => match b.get(21..25) {
+            Some(&[b0, b1, b2, b3]) => (
+                Some(1 + (u16::from(b0) | u16::from(b1 & 0x3f) << 8)),
+                Some(1 + (u16::from(b1 >> 6) | u16::from(b2) << 2 | u16::from(b3 & 0x0f) << 10)),
+            ),
+            _ => (None, None),
+        },
+        // Extended: 24-bit canvas dimensions minus one
+        Some(b"VP8X") => {
+            return MediaInfo {
+                width: le24(b, 24).map(|w| u64::from(w) + 1),
+                height: le24(b, 27).map(|h| u64::from(h) + 1),
+                ..Default::default()
+            }
+        }
+        _ => (None, None),
+    };
+    MediaInfo {
+        width: width.map(u64::from),
+        height: height.map(u64::from),
+        ..Default::default()
+    }
+}
+
+fn jpeg(b: &[u8]) -> MediaInfo {
+    let mut info = MediaInfo::default();
+    let mut orientation = None;
+    let mut at = 2;
+    while at + 4 <= b.len() {
+        if b[at] != 0xff {
+            break;
+        }
+        let marker = b[at + 1];
+        // Padding and markers without a length
+        if marker == 0xff {
+            at += 1;
+            continue;
+        }
+        if marker == 0x01 || (0xd0..=0xd9).contains(&marker) {
+            at += 2;
+            continue;
+        }
+        let Some(len) = be16(b, at + 2).map(usize::from) else {
+            break;
+        };
+        let segment = b.get(at + 4..(at + 2 + len).min(b.len())).unwrap_or(&[]);
+        match marker {
+            0xe1 if segment.starts_with(b"Exif\0\0") => {
+                let exif = exif(&segment[6..]);
+                info.captured_at = exif.captured_at;
+                orientation = exif.orientation;
+            }
+            // Start of frame (baseline, progressive, ...), but not DHT, JPG or DAC
+            0xc0..=0xcf if !matches!(marker, 0xc4 | 0xc8 | 0xcc) => {
+                info.height = be16(segment, 1).map(u64::from);
+                info.width = be16(segment, 3).map(u64::from);
+                break;
+            }
+            // Start of scan: the headers are over
+            0xda => break,
+            _ => {}
+        }
+        at += 2 + len;
+    }
+
+    // Orientations 5 to 8 rotate the image a quarter turn
+    if matches!(orientation, Some(5..=8)) {
+        std::mem::swap(&mut info.width, &mut info.height);
+    }
+    info
+}
+
+struct Exif {
+    captured_at: Option<i64>,
+    orientation: Option<u16>,
+}
+
+/// Parse the TIFF structure of an EXIF segment for the orientation and the
+/// capture date, falling back to the modification date
+fn exif(tiff: &[u8]) -> Exif {
+    let little = match tiff.get(..2) {
+        Some(b"II") => true,
+        Some(b"MM") => false,
+        _ => {
+            return Exif {
+                captured_at: None,
+                orientation: None,
+            }
+        }
+    };
+    let u16_at = |at: usize| -> Option<u16> {
+        let bytes = tiff.get(at..at + 2)?.try_into().ok()?;
+        Some(if little {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    };
+    let u32_at = |at: usize| -> Option<u32> {
+        let bytes = tiff.get(at..at + 4)?.try_into().ok()?;
+        Some(if little {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    };
+    // The (tag, value field offset) pairs of an IFD
+    let entries = |ifd: usize| -> Vec<(u16, usize)> {
+        let count = u16_at(ifd).unwrap_or(0) as usize;
+        (0..count)
+            .map(|i| ifd + 2 + i * 12)
+            .filter_map(|entry| Some((u16_at(entry)?, entry + 8)))
+            .collect()
+    };
+    let date_at = |value: usize| -> Option<i64> {
+        let offset = u32_at(value)? as usize;
+        let text = std::str::from_utf8(tiff.get(offset..offset + 19)?).ok()?;
+        let date = NaiveDateTime::parse_from_str(text, "%Y:%m:%d %H:%M:%S").ok()?;
+        Some(date.and_utc().timestamp())
+    };
+
+    let mut orientation = None;
+    let mut modified = None;
+    let mut original = None;
+    let ifd0 = u32_at(4).map(|offset| offset as usize);
+    for (tag, value) in ifd0.map(entries).unwrap_or_default() {
+        match tag {
+            0x0112 => orientation = u16_at(value),
+            0x0132 => modified = date_at(value),
+            0x8769 => {
+                let sub_ifd = u32_at(value).map(|offset| offset as usize);
+                for (tag, value) in sub_ifd.map(entries).unwrap_or_default() {
+                    if tag == 0x9003 {
+                        original = date_at(value);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Exif {
+        captured_at: original.or(modified),
+        orientation,
+    }
+}
+
+/// Iterate the boxes of an ISO base media file, yielding each box's type
+/// and contents
+fn boxes(b: &[u8]) -> impl Iterator<Item = (&[u8], &[u8])> {
+    let mut at = 0;
+    std::iter::from_fn(move || {
+        let size = be32(b, at)? as usize;
+        let kind = b.get(at + 4..at + 8)?;
+        let (header, size) = match size {
+            0 => (8, b.len() - at),
+            1 => (16, usize::try_from(be64(b, at + 8)?).ok()?),
+            size => (8, size),
+        };
+        if size < header {
+            return None;
+        }
+        // A box cut off by the end of what we have still yields its start
+        let contents = b.get(at + header..(at + size).min(b.len()))?;
+        at += size;
+        Some((kind, contents))
+    })
+}
+
+fn find_box<'a>(b: &'a [u8], kind: &[u8]) -> Option<&'a [u8]> {
+    boxes(b)
+        .find(|(k, _)| *k == kind)
+        .map(|(_, contents)| contents)
+}
+
+fn mp4(b: &[u8]) -> MediaInfo {
+    let mut info = MediaInfo::default();
+    let Some(moov) = find_box(b, b"moov") else {
+        return info;
+    };
+
+    if let Some(mvhd) = find_box(moov, b"mvhd") {
+        let (created, timescale, duration) = match mvhd.first() {
+            Some(1) => (be64(mvhd, 4), be32(mvhd, 20), be64(mvhd, 24)),
+            Some(_) => (
+                be32(mvhd, 4).map(u64::from),
+                be32(mvhd, 12),
+                be32(mvhd, 16).map(u64::from),
+            ),
+            None => (None, None, None),
+        };
+        info.captured_at = created
+            .filter(|&t| t > MP4_EPOCH_OFFSET)
+            .and_then(|t| i64::try_from(t - MP4_EPOCH_OFFSET).ok());
+        if let (Some(timescale), Some(duration)) = (timescale, duration) {
+            if timescale > 0 {
+                info.duration_ms = Some(duration.saturating_mul(1000) / u64::from(timescale));
+            }
+        }
+    }
+
+    // The first track with a picture gives the dimensions (16.16 fixed point)
+    for (kind, trak) in boxes(moov) {
+        if kind != b"trak" {
+            continue;
+        }
+        let Some(tkhd) = find_box(trak, b"tkhd") else {
+            continue;
+        };
+        let at = if tkhd.first() == Some(&1) { 88 } else { 76 };
+        let width = be32(tkhd, at).map(|w| u64::from(w >> 16));
+        let height = be32(tkhd, at + 4).map(|h| u64::from(h >> 16));
+        if width.unwrap_or(0) > 0 && height.unwrap_or(0) > 0 {
+            info.width = width;
+            info.height = height;
+            break;
+        }
+    }
+    info
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn extract(mime: &str, head: &[u8]) -> MediaInfo {
+        MediaInfo::extract(Some(&mime.parse().unwrap()), head)
+    }
+
+    /// A big-endian TIFF block with an orientation and, in its EXIF IFD, a
+    /// DateTimeOriginal
+    fn exif_segment(orientation: u16, date: &str) -> Vec<u8> {
+        let mut tiff = b"MM\x00\x2a\x00\x00\x00\x08".to_vec();
+        // IFD0 at 8: two entries, then the EXIF IFD at 38
+        tiff.extend(2u16.to_be_bytes());
+        tiff.extend([0x01, 0x12, 0x00, 0x03, 0, 0, 0, 1]);
+        tiff.extend(orientation.to_be_bytes());
+        tiff.extend([0, 0]);
+        tiff.extend([0x87, 0x69, 0x00, 0x04, 0, 0, 0, 1]);
+        tiff.extend(38u32.to_be_bytes());
+        tiff.extend(0u32.to_be_bytes());
+        // EXIF IFD at 38: DateTimeOriginal stored at 56
+        tiff.extend(1u16.to_be_bytes());
+        tiff.extend([0x90, 0x03, 0x00, 0x02, 0, 0, 0, 20]);
+        tiff.extend(56u32.to_be_bytes());
+        tiff.extend(0u32.to_be_bytes());
+        tiff.extend(date.as_bytes());
+        tiff.push(0);
+
+        let mut segment = b"Exif\0\0".to_vec();
+        segment.extend(tiff);
+        segment
+    }
+
+    fn jpeg_with(exif: Option<Vec<u8>>, width: u16, height: u16) -> Vec<u8> {
+        let mut b = vec![0xff, 0xd8];
+        if let Some(exif) = exif {
+            b.extend([0xff, 0xe1]);
+            b.extend((exif.len() as u16 + 2).to_be_bytes());
+            b.extend(exif);
+        }
+        b.extend([0xff, 0xc0, 0x00, 0x11, 0x08]);
+        b.extend(height.to_be_bytes());
+        b.extend(width.to_be_bytes());
+        b.extend([0x03; 10]);
+        b.extend([0xff, 0xda]);
+        b
+    }
+
+    fn mp4_box(kind: &[u8], contents: &[u8]) -> Vec<u8> {
+        let mut b = ((contents.len() + 8) as u32).to_be_bytes().to_vec();
+        b.extend(kind);
+        b.extend(contents);
+        b
+    }
+
+    #[test]
+    fn test_jpeg_exif() {
+        let photo = jpeg_with(Some(exif_segment(1, "2024:07:01 12:30:00")), 4032, 3024);
+        let info = extract("image/jpeg", &photo);
+        assert_eq!(info.width, Some(4032));
+        assert_eq!(info.height, Some(3024));
+        assert_eq!(info.captured_at, Some(1_719_837_000));
+
+        // Rotated a quarter turn
+        let photo = jpeg_with(Some(exif_segment(6, "2024:07:01 12:30:00")), 4032, 3024);
+        let info = extract("image/jpeg", &photo);
+        assert_eq!((info.width, info.height), (Some(3024), Some(4032)));
+
+        // No EXIF at all
+        let info = extract("image/jpeg", &jpeg_with(None, 640, 480));
+        assert_eq!((info.width, info.captured_at), (Some(640), None));
+    }
+
+    #[test]
+    fn test_image_dimensions() {
+        let png = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR\x00\x00\x03\x20\x00\x00\x02\x58";
+        let info = extract("image/png", png);
+        assert_eq!((info.width, info.height), (Some(800), Some(600)));
+
+        let gif = b"GIF89a\x40\x01\xf0\x00";
+        let info = extract("image/gif", gif);
+        assert_eq!((info.width, info.height), (Some(320), Some(240)));
+
+        let mut webp = b"RIFF\x00\x00\x00\x00WEBPVP8X\x0a\x00\x00\x00\x00\x00\x00\x00".to_vec();
+        webp.extend([0x7f, 0x07, 0x00, 0x37, 0x04, 0x00]);
+        let info = extract("image/webp", &webp);
+        assert_eq!((info.width, info.height), (Some(1920), Some(1080)));
+    }
+
+    #[test]
+    fn test_mp4() {
+        // mvhd v0: created 2024-07-01 12:30:00 UTC, 90 seconds at 600 ticks/s
+        let mut mvhd = vec![0; 4];
+        mvhd.extend(((1_719_837_000 + MP4_EPOCH_OFFSET) as u32).to_be_bytes());
+        mvhd.extend(0u32.to_be_bytes());
+        mvhd.extend(600u32.to_be_bytes());
+        mvhd.extend(54_000u32.to_be_bytes());
+        // tkhd v0 of a 1280x720 track
+        let mut tkhd = vec![0; 76];
+        tkhd.extend((1280u32 << 16).to_be_bytes());
+        tkhd.extend((720u32 << 16).to_be_bytes());
+
+        let mut moov = mp4_box(b"mvhd", &mvhd);
+        moov.extend(mp4_box(b"trak", &mp4_box(b"tkhd", &tkhd)));
+        let mut file = mp4_box(b"ftyp", b"isom\x00\x00\x02\x00");
+        file.extend(mp4_box(b"moov", &moov));
+        file.extend(mp4_box(b"mdat", &[0; 32]));
+
+        let info = extract("video/mp4", &file);
+        assert_eq!(
+            info,
+            MediaInfo {
+                width: Some(1280),
+                height: Some(720),
+                captured_at: Some(1_719_837_000),
+                duration_ms: Some(90_000),
+            }
+        );
+    }
+
+    #[test]
+    fn test_metadata_roundtrip() {
+        let info = MediaInfo {
+            width: Some(10),
+            height: Some(20),
+            captured_at: Some(-5),
+            duration_ms: None,
+        };
+        let metadata = info.to_metadata().into_iter().collect();
+        assert_eq!(MediaInfo::from_metadata(&metadata), info);
+
+        // Garbage and other types yield nothing
+        assert!(extract("image/jpeg", b"\xff\xd8\xff\xe1\xff").is_empty());
+        assert!(extract("text/plain", b"hello").is_empty());
+    }
+}
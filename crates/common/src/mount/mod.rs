@@ -40,24 +40,48 @@
 //! - Each node and file has its own encryption [`Secret`](crate::crypto::Secret)
 //! - Secrets are stored in the parent node's [`NodeLink`]
 //! - The root node's secret is shared with authorized peers via [`Share`](crate::crypto::Share)
+//! - A directory node's secret can be shared instead, granting access to that subtree
+//!   only (see [`SubtreeShare`])
+//! - The root node's secret can also be shared with a throwaway key until it expires,
+//!   keeping the recipient's identity out of the manifest (see [`EphemeralShare`])
 //! - This provides fine-grained access control and efficient key rotation
+//!
+//! ## Bucket References
+//!
+//! A directory entry may reference another bucket instead of a node of this one
+//! (see [`BucketRef`]), at a pinned version or following its head. The bucket
+//! stores nothing of the referenced one, not even a secret: readers resolve the
+//! reference with their own access to it (see `Peer::resolve`).
 
+mod chunks;
+mod comments;
+mod compression;
 mod conflict;
 mod manifest;
 mod maybe_mime;
+mod media;
 mod mount_inner;
 mod node;
 mod path_ops;
 mod pins;
 mod principal;
+mod sniff;
 
+pub use chunks::{ChunkList, ChunkRef, CHUNKING_THRESHOLD};
+pub use comments::{validate_comment, Comment, Comments, MAX_COMMENT_LEN};
+pub use compression::{Compression, CompressionError};
 pub use conflict::{
     conflicts_with_mv_source, operations_conflict, BaseWins, Conflict, ConflictFile,
     ConflictResolver, ForkOnConflict, LastWriteWins, MergeResult, Resolution, ResolvedConflict,
 };
-pub use manifest::{Manifest, ManifestError, Share, Shares};
+pub use manifest::{
+    EphemeralShare, EphemeralShares, Manifest, ManifestError, Share, Shares, SubtreeShare,
+    SubtreeShares,
+};
+pub use media::MediaInfo;
 pub use mount_inner::{Mount, MountError};
-pub use node::{Node, NodeError, NodeLink};
+pub use node::{Attrs, BucketRef, Node, NodeError, NodeLink, MAX_ATTRS_SIZE, MAX_ATTR_KEY_LEN};
 pub use path_ops::{merge_logs, OpId, OpType, PathOpLog, PathOperation};
 pub use pins::Pins;
 pub use principal::{Principal, PrincipalRole};
+pub use sniff::{sniff, SNIFF_LEN};
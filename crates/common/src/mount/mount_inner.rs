@@ -1,21 +1,32 @@
 use std::collections::BTreeMap;
 use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::pin::Pin;
 use std::sync::Arc;
+use std::task::{ready, Context, Poll};
 
+use tokio::io::{AsyncRead, ReadBuf};
 use tokio::sync::Mutex;
 use uuid::Uuid;
 
-use crate::crypto::{PublicKey, Secret, SecretError, SecretKey, SecretShare};
-use crate::linked_data::{BlockEncoded, CodecError, Link};
+use crate::crypto::{
+    offload, KemPublicKey, PublicKey, Secret, SecretError, SecretKey, SecretShare,
+};
+use crate::linked_data::{BlockEncoded, CodecError, Hash, Link};
 use crate::peer::{BlobsStore, BlobsStoreError};
 
+use super::chunks::{self, ChunkList, SyncReader, CHUNKING_THRESHOLD};
+use super::comments::{validate_comment, Comment, Comments};
+use super::compression::{Compression, CompressionError};
 use super::conflict::MergeResult;
-use super::manifest::{Manifest, ManifestError, Share};
-use super::node::{Node, NodeError, NodeLink};
+use super::manifest::{EphemeralShare, Manifest, ManifestError, Share, SubtreeShare};
+use super::maybe_mime::MaybeMime;
+use super::media::MediaInfo;
+use super::node::{validate_attrs, Attrs, BucketRef, Data, Node, NodeError, NodeLink};
 use super::path_ops::{OpType, PathOpLog};
 use super::pins::Pins;
 use super::principal::PrincipalRole;
+use super::sniff::SNIFF_LEN;
 
 pub fn clean_path(path: &Path) -> PathBuf {
     if !path.is_absolute() {
@@ -27,6 +38,34 @@ pub fn clean_path(path: &Path) -> PathBuf {
         .collect::<PathBuf>()
 }
 
+/// Size and BLAKE3 hash of the bytes read through a [`Tallied`] reader
+#[derive(Default)]
+struct Tally {
+    size: u64,
+    hasher: blake3::Hasher,
+}
+
+/// A reader tallying what is read through it, shared so the tally can be
+/// read after the reader has been handed off
+struct Tallied<R>(R, Arc<std::sync::Mutex<Tally>>);
+
+impl<R: AsyncRead + Unpin> AsyncRead for Tallied<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        let before = buf.filled().len();
+        ready!(Pin::new(&mut this.0).poll_read(cx, buf))?;
+        let read = &buf.filled()[before..];
+        let mut tally = this.1.lock().expect("tally lock poisoned");
+        tally.size += read.len() as u64;
+        tally.hasher.update(read);
+        Poll::Ready(Ok(()))
+    }
+}
+
 #[derive(Clone)]
 pub struct MountInner {
     // link to the manifest
@@ -41,10 +80,15 @@ pub struct MountInner {
     pub height: u64,
     // the path operations log (CRDT for conflict resolution)
     pub ops_log: PathOpLog,
+    /// Comments on paths in the bucket, carried over between versions
+    pub comments: Comments,
     // the local peer ID (for recording operations)
     pub peer_id: PublicKey,
     // the secret key for signing manifests
     pub secret_key: SecretKey,
+    /// The shared directory this mount is rooted at, for peers reading the
+    /// bucket through a subtree share
+    pub subtree: Option<PathBuf>,
 }
 
 impl MountInner {
@@ -66,9 +110,15 @@ impl MountInner {
     pub fn ops_log(&self) -> &PathOpLog {
         &self.ops_log
     }
+    pub fn comments(&self) -> &Comments {
+        &self.comments
+    }
     pub fn peer_id(&self) -> &PublicKey {
         &self.peer_id
     }
+    pub fn subtree(&self) -> Option<&Path> {
+        self.subtree.as_deref()
+    }
 }
 
 #[derive(Clone)]
@@ -104,6 +154,30 @@ pub enum MountError {
     ShareNotFound,
     #[error("mirror cannot mount: bucket is not published")]
     MirrorCannotMount,
+    #[error("compression error: {0}")]
+    Compression(#[from] CompressionError),
+    #[error("version {0} is not available")]
+    VersionUnavailable(crate::linked_data::Hash),
+    #[error("bucket head moved to {0}")]
+    HeadMoved(Link),
+    #[error("invalid attributes: {0}")]
+    InvalidAttrs(String),
+    #[error("invalid comment: {0}")]
+    InvalidComment(String),
+    #[error("comment not found: {0}")]
+    CommentNotFound(Uuid),
+    #[error("{} already reads the whole bucket", .0.to_hex())]
+    AlreadyShared(PublicKey),
+    #[error("a subtree share is read-only")]
+    SubtreeReadOnly,
+    #[error("an ephemeral share is read-only")]
+    EphemeralReadOnly,
+    #[error("path is a reference to another bucket: {0}")]
+    BucketReference(PathBuf),
+    #[error("bucket {0} is referenced from itself")]
+    ReferenceCycle(Uuid),
+    #[error("bucket references nest deeper than {0}")]
+    ReferencesTooDeep(usize),
 }
 
 impl Mount {
@@ -137,9 +211,18 @@ impl Mount {
             previous_height,
             manifest_template,
             ops_log,
+            comments,
             secret_key,
         ) = {
             let inner = self.0.lock().await;
+            if inner.subtree.is_some() {
+                return Err(MountError::SubtreeReadOnly);
+            }
+            if inner.manifest.get_share(&inner.peer_id).is_none()
+                && inner.manifest.get_ephemeral_share(&inner.peer_id).is_some()
+            {
+                return Err(MountError::EphemeralReadOnly);
+            }
             (
                 inner.entry.clone(),
                 inner.pins.clone(),
@@ -147,6 +230,7 @@ impl Mount {
                 inner.height,
                 inner.manifest.clone(),
                 inner.ops_log.clone(),
+                inner.comments.clone(),
                 inner.secret_key.clone(),
             )
         };
@@ -174,17 +258,49 @@ impl Mount {
             None
         };
 
+        // Comments are re-encrypted with each version's secret
+        let comments_link = if !comments.is_empty() {
+            let link = Self::_put_comments_in_blobs(&comments, &secret, blobs).await?;
+            pins.insert(link.hash());
+            Some(link)
+        } else {
+            None
+        };
+
         let pins_link = Self::_put_pins_in_blobs(&pins, blobs).await?;
 
-        // Re-encrypt owner shares with the new secret (mirrors stay unchanged)
+        // Re-encrypt owner shares with the new secret, hybrid for owners with
+        //  an ML-KEM key (mirrors stay unchanged)
         let mut manifest = manifest_template;
         for share in manifest.shares_mut().values_mut() {
             if *share.role() == PrincipalRole::Owner {
-                let secret_share = SecretShare::new(&secret, &share.principal().identity)?;
+                let secret_share = share.wrap(&secret)?;
                 share.set_share(secret_share);
             }
         }
 
+        // Re-share the current node of each shared directory, whose secret
+        //  changed if anything below it did
+        for subtree_share in manifest.subtree_shares_mut().values_mut() {
+            let path = Path::new(subtree_share.path());
+            match Self::_get_link_at_path(&entry_node, path, blobs).await {
+                Ok(NodeLink::Dir(link, secret)) => subtree_share.set(link, &secret)?,
+                _ => subtree_share.clear(),
+            }
+        }
+
+        // Drop expired ephemeral shares and re-wrap the new secret for the rest
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs() as i64)
+            .unwrap_or_default();
+        manifest
+            .ephemeral_shares_mut()
+            .retain(|_, ephemeral_share| !ephemeral_share.is_expired(now));
+        for ephemeral_share in manifest.ephemeral_shares_mut().values_mut() {
+            ephemeral_share.set(&secret)?;
+        }
+
         // Update publish state: publish with new secret, or clear stale public secret
         if publish {
             manifest.publish(&secret);
@@ -204,6 +320,7 @@ impl Mount {
         if let Some(ops_link) = ops_log_link {
             manifest.set_ops_log(ops_link);
         }
+        manifest.set_comments(comments_link);
 
         // Sign the manifest with the stored secret key
         manifest.sign(&secret_key)?;
@@ -268,13 +385,56 @@ impl Mount {
                 pins,
                 height: 0,
                 ops_log: PathOpLog::new(),
+                comments: Comments::default(),
                 peer_id: owner.public(),
                 secret_key: owner.clone(),
+                subtree: None,
             })),
             blobs.clone(),
         ))
     }
 
+    /// Start a new bucket owned by `owner` alone, seeded with the current
+    /// contents of this mount.
+    ///
+    /// The fork gets its own id, secret and history. Files and directories
+    /// below the root are carried over as they are, still encrypted with
+    /// their own secrets, so their blobs are reused rather than copied.
+    /// Compression and media settings carry over; shares, comments and the
+    /// ops log don't. The returned mount is at the fork's empty genesis and
+    /// records the contents on its first save.
+    pub async fn fork(
+        &self,
+        id: Uuid,
+        name: String,
+        owner: &SecretKey,
+    ) -> Result<Self, MountError> {
+        let (entry, compression, extract_media) = {
+            let inner = self.0.lock().await;
+            (
+                inner.entry.clone(),
+                inner.manifest.compression(),
+                inner.manifest.extract_media(),
+            )
+        };
+
+        // Pin everything below the root, which is re-encrypted on save
+        let mut pins = Vec::new();
+        for node_link in entry.get_links().values() {
+            pins.extend(Self::reachable(node_link, &self.1).await?);
+        }
+
+        let mut fork = Self::init(id, name, owner, &self.1).await?;
+        fork.set_compression(compression).await;
+        fork.set_extract_media(extract_media).await;
+        {
+            let mut inner = fork.0.lock().await;
+            inner.entry = entry;
+            inner.pins.extend(pins);
+        }
+        Ok(fork)
+    }
+
     pub async fn load(
         link: &Link,
         secret_key: &SecretKey,
@@ -282,26 +442,51 @@ impl Mount {
     ) -> Result<Self, MountError> {
         let public_key = &secret_key.public();
         let manifest = Self::_get_manifest_from_blobs(link, blobs).await?;
-
-        let bucket_share = match manifest.get_share(public_key) {
-            Some(share) => share,
-            None => return Err(MountError::ShareNotFound),
-        };
-
-        // Get the secret based on role
-        let secret = match bucket_share.role() {
-            PrincipalRole::Owner => {
-                // Owners decrypt their individual share
-                let share = bucket_share.share().ok_or(MountError::ShareNotFound)?;
-                share.recover(secret_key)?
+        // Blobs missing from here on are the bucket's to fetch
+        let blobs = &blobs.for_bucket(*manifest.id());
+
+        // Peers with a subtree share see only that directory
+        if manifest.get_share(public_key).is_none() {
+            if let Some(subtree_share) = manifest.get_subtree_share(public_key) {
+                return Self::_load_subtree(
+                    link,
+                    manifest.clone(),
+                    subtree_share,
+                    secret_key,
+                    blobs,
+                )
+                .await;
             }
-            PrincipalRole::Mirror => {
-                // Mirrors use the public secret (if bucket is published)
-                manifest
+        }
+
+        // Get the secret based on role. Temporary identities read with
+        // their ephemeral share, and followers without a share read
+        // published versions with the public secret, like mirrors.
+        let secret = match manifest.get_share(public_key) {
+            None => match manifest.get_ephemeral_share(public_key) {
+                Some(ephemeral_share) => ephemeral_share
+                    .share()
+                    .ok_or(MountError::ShareNotFound)?
+                    .recover(secret_key)?,
+                None => manifest
                     .public()
                     .cloned()
-                    .ok_or(MountError::MirrorCannotMount)?
-            }
+                    .ok_or(MountError::ShareNotFound)?,
+            },
+            Some(bucket_share) => match bucket_share.role() {
+                PrincipalRole::Owner => {
+                    // Owners decrypt their individual share
+                    let share = bucket_share.share().ok_or(MountError::ShareNotFound)?;
+                    share.recover(secret_key)?
+                }
+                PrincipalRole::Mirror => {
+                    // Mirrors use the public secret (if bucket is published)
+                    manifest
+                        .public()
+                        .cloned()
+                        .ok_or(MountError::MirrorCannotMount)?
+                }
+            },
         };
 
         let pins = Self::_get_pins_from_blobs(manifest.pins(), blobs).await?;
@@ -324,6 +509,13 @@ impl Mount {
             PathOpLog::new()
         };
 
+        let comments = match manifest.comments() {
+            Some(comments_link) => {
+                Self::_get_comments_from_blobs(comments_link, &secret, blobs).await?
+            }
+            None => Comments::default(),
+        };
+
         Ok(Mount(
             Arc::new(Mutex::new(MountInner {
                 link: link.clone(),
@@ -332,8 +524,47 @@ impl Mount {
                 pins,
                 height,
                 ops_log,
+                comments,
+                peer_id: secret_key.public(),
+                secret_key: secret_key.clone(),
+                subtree: None,
+            })),
+            blobs.clone(),
+        ))
+    }
+
+    /// Load the read-only view of a peer with a subtree share, rooted at
+    /// the shared directory. The ops log and comments are encrypted with the
+    /// bucket's secret, so the view has neither.
+    async fn _load_subtree(
+        link: &Link,
+        manifest: Manifest,
+        subtree_share: &SubtreeShare,
+        secret_key: &SecretKey,
+        blobs: &BlobsStore,
+    ) -> Result<Self, MountError> {
+        let (node, share) = subtree_share
+            .node()
+            .zip(subtree_share.share())
+            .ok_or_else(|| MountError::PathNotFound(PathBuf::from(subtree_share.path())))?;
+        let secret = share.recover(secret_key)?;
+        let entry = Self::_get_node_from_blobs(&NodeLink::Dir(node.clone(), secret), blobs).await?;
+        let pins = Self::_get_pins_from_blobs(manifest.pins(), blobs).await?;
+        let height = manifest.height();
+        let subtree = PathBuf::from(subtree_share.path());
+
+        Ok(Mount(
+            Arc::new(Mutex::new(MountInner {
+                link: link.clone(),
+                manifest,
+                entry,
+                pins,
+                height,
+                ops_log: PathOpLog::new(),
+                comments: Comments::default(),
                 peer_id: secret_key.public(),
                 secret_key: secret_key.clone(),
+                subtree: Some(subtree),
             })),
             blobs.clone(),
         ))
@@ -355,6 +586,30 @@ impl Mount {
         inner
             .manifest
             .add_share(Share::new_owner(secret_share, peer));
+        // Owners read the whole bucket
+        inner.manifest.subtree_shares_mut().remove(&peer.to_hex());
+        Ok(())
+    }
+
+    /// Record an owner's ML-KEM key, so its shares are wrapped with it as
+    /// well as X25519 from the next save on, or clear it to go back to
+    /// classic shares.
+    ///
+    /// Only set one once every peer of the bucket reads hybrid shares: peers
+    /// that predate them can't read a manifest holding one.
+    pub async fn set_kem_key(
+        &mut self,
+        peer: &PublicKey,
+        kem_key: Option<KemPublicKey>,
+    ) -> Result<(), MountError> {
+        let mut inner = self.0.lock().await;
+        let share = inner
+            .manifest
+            .shares_mut()
+            .get_mut(&peer.to_hex())
+            .filter(|share| *share.role() == PrincipalRole::Owner)
+            .ok_or(MountError::ShareNotFound)?;
+        share.set_kem_key(kem_key);
         Ok(())
     }
 
@@ -363,6 +618,44 @@ impl Mount {
     pub async fn add_mirror(&mut self, peer: PublicKey) {
         let mut inner = self.0.lock().await;
         inner.manifest.add_share(Share::new_mirror(peer));
+        inner.manifest.subtree_shares_mut().remove(&peer.to_hex());
+    }
+
+    /// Share the directory at `path` with a peer, read-only.
+    /// The peer gets the directory node's secret from the next save on, and
+    /// any subtree share it had of another directory is replaced.
+    pub async fn add_subtree_share(
+        &mut self,
+        peer: PublicKey,
+        path: &Path,
+    ) -> Result<(), MountError> {
+        if clean_path(path) == Path::new("") {
+            return Err(MountError::PathNotNode(path.to_path_buf()));
+        }
+        if !self.get(path).await?.is_dir() {
+            return Err(MountError::PathNotNode(path.to_path_buf()));
+        }
+        let mut inner = self.0.lock().await;
+        if inner.manifest.get_share(&peer).is_some() {
+            return Err(MountError::AlreadyShared(peer));
+        }
+        let path = Path::new("/").join(clean_path(path));
+        inner
+            .manifest
+            .add_subtree_share(SubtreeShare::new(peer, path.to_string_lossy().to_string()));
+        Ok(())
+    }
+
+    /// Share this bucket read-only with a new temporary identity until Unix
+    /// time `expires_at`, returning its key for the recipient.
+    /// The identity gets the bucket's secret from the next save on.
+    pub async fn add_ephemeral_share(&mut self, expires_at: i64) -> SecretKey {
+        let temporary = SecretKey::generate();
+        let mut inner = self.0.lock().await;
+        inner
+            .manifest
+            .add_ephemeral_share(EphemeralShare::new(temporary.public(), expires_at));
+        temporary
     }
 
     /// Check if this bucket is published (mirrors can decrypt).
@@ -378,34 +671,250 @@ impl Mount {
         self.save(&self.1, true).await
     }
 
+    /// Get the compression applied to new file contents in this bucket.
+    pub async fn compression(&self) -> Option<Compression> {
+        let inner = self.0.lock().await;
+        inner.manifest.compression()
+    }
+
+    /// Set the compression applied to new file contents in this bucket.
+    ///
+    /// Takes effect for files added after this call and is persisted
+    /// in the manifest on the next save. Existing files are not rewritten.
+    pub async fn set_compression(&mut self, compression: Option<Compression>) {
+        let mut inner = self.0.lock().await;
+        inner.manifest.set_compression(compression);
+    }
+
+    /// Whether image and video metadata is extracted from new files.
+    pub async fn extract_media(&self) -> bool {
+        let inner = self.0.lock().await;
+        inner.manifest.extract_media()
+    }
+
+    /// Set whether image and video metadata is extracted from new files.
+    ///
+    /// See [`MediaInfo`]. Takes effect for files added after this call and is
+    /// persisted in the manifest on the next save.
+    pub async fn set_extract_media(&mut self, extract_media: bool) {
+        let mut inner = self.0.lock().await;
+        inner.manifest.set_extract_media(extract_media);
+    }
+
+    #[allow(clippy::result_large_err)]
     pub async fn add<R>(&mut self, path: &Path, data: R) -> Result<(), MountError>
     where
         R: Read + Send + Sync + 'static + Unpin,
     {
-        let secret = Secret::generate();
+        self.add_reader(path, SyncReader(data)).await
+    }
+
+    /// Add a file at `path` with the contents of an async reader, such as an
+    /// upload still arriving
+    ///
+    /// Large files are chunked as they are read, so at most a chunk's worth
+    /// of the contents is held in memory however large the file.
+    #[allow(clippy::result_large_err)]
+    pub async fn add_reader<R>(&mut self, path: &Path, data: R) -> Result<(), MountError>
+    where
+        R: AsyncRead + Send + Unpin,
+    {
+        use tokio::io::AsyncReadExt;
 
-        let encrypted_reader = secret.encrypt_reader(data)?;
+        // Replacing a directory with a file would drop its whole subtree
+        let existing = self.get(path).await;
+        if matches!(existing, Ok(NodeLink::Dir(..))) {
+            return Err(MountError::PathAlreadyExists(path.to_path_buf()));
+        }
 
-        // TODO (amiller68): this is incredibly dumb
-        use bytes::Bytes;
-        use futures::stream;
-        let encrypted_bytes = {
-            let mut buf = Vec::new();
-            let mut reader = encrypted_reader;
-            reader.read_to_end(&mut buf).map_err(SecretError::Io)?;
-            buf
+        // Read no more than it takes to tell whether the file gets chunked,
+        //  so large files stream through without being held in memory
+        let tally = Arc::new(std::sync::Mutex::new(Tally::default()));
+        let mut data = Tallied(data, tally.clone());
+        let mut buf = Vec::new();
+        (&mut data)
+            .take(CHUNKING_THRESHOLD as u64)
+            .read_to_end(&mut buf)
+            .await
+            .map_err(SecretError::Io)?;
+        let mime = MaybeMime::detect(path, &buf);
+        let codec = self.compression().await;
+        let media = if self.extract_media().await {
+            MediaInfo::extract(mime.0.as_ref(), &buf)
+        } else {
+            MediaInfo::default()
         };
 
-        let stream = Box::pin(stream::once(async move {
-            Ok::<_, std::io::Error>(Bytes::from(encrypted_bytes))
-        }));
+        let (link, secret, compression, chunked, data_hashes) = if buf.len() >= CHUNKING_THRESHOLD {
+            // Keep the secret of the chunked file this replaces, so that
+            //  unchanged chunks keep their hashes
+            let secret = match &existing {
+                Ok(NodeLink::Data(_, secret, data)) if data.is_chunked() => secret.clone(),
+                _ => Secret::generate(),
+            };
+            let reader = AsyncReadExt::chain(std::io::Cursor::new(buf), data);
+            let (link, hashes) =
+                chunks::put_chunked(&self.1, &secret, reader, codec, mime.0.as_ref()).await?;
+            (link, secret, None, true, hashes)
+        } else {
+            let secret = Secret::generate();
 
-        let hash = self.1.put_stream(stream).await?;
+            // Compress before encrypting if the bucket asks for it and it pays
+            //  off, both off the async runtime for larger files
+            let file_secret = secret.clone();
+            let mime = mime.clone();
+            let (encrypted, compression) = offload(buf.len(), move || {
+                let (plaintext, compression) = match codec {
+                    Some(codec) => match codec.maybe_compress(&buf, mime.0.as_ref())? {
+                        Some(compressed) => (compressed, Some(codec)),
+                        None => (buf, None),
+                    },
+                    None => (buf, None),
+                };
+                Ok::<_, MountError>((file_secret.encrypt(&plaintext)?, compression))
+            })
+            .await?;
+
+            let hash = self.1.put(encrypted).await?;
+
+            let link = Link::new(crate::linked_data::LD_RAW_CODEC, hash);
+            (link, secret, compression, false, vec![hash])
+        };
 
-        let link = Link::new(crate::linked_data::LD_RAW_CODEC, hash);
+        let mut node_link = NodeLink::new_data_from_path(link, secret, path);
+        if let NodeLink::Data(_, _, data) = &mut node_link {
+            data.set_mime(mime.0);
+            for (key, value) in media.to_metadata() {
+                data.set_metadata(key, value);
+            }
+            data.set_compression(compression);
+            data.set_chunked(chunked);
+            let tally = tally.lock().expect("tally lock poisoned");
+            data.set_content(
+                Some(tally.size),
+                Some(Hash::from_bytes(*tally.hasher.finalize().as_bytes())),
+            );
+            // Attributes describe the file, not one version of its contents
+            if let Ok(NodeLink::Data(_, _, previous)) = &existing {
+                data.set_attrs(previous.attrs().clone());
+            }
+        }
 
-        let node_link = NodeLink::new_data_from_path(link.clone(), secret, path);
+        self._put_data_at_path(path, node_link, data_hashes).await
+    }
+
+    /// Append `data` to the file at `path`, creating it if it doesn't exist
+    ///
+    /// Made for log-style files: the appended bytes are stored as new chunks
+    /// at the end of the file's chunk list, and existing chunks are neither
+    /// read nor rewritten, so an append costs the size of the appended data
+    /// rather than of the file. Files appended to are always chunked; a file
+    /// that isn't yet is rewritten as one on its first append.
+    #[allow(clippy::result_large_err)]
+    pub async fn append<R>(&mut self, path: &Path, data: R) -> Result<(), MountError>
+    where
+        R: Read + Send + Sync + 'static + Unpin,
+    {
+        let codec = self.compression().await;
 
+        let existing = self.get(path).await;
+        let (secret, list, mut meta, reader): (_, _, _, Box<dyn Read + Send + Sync>) =
+            match existing {
+                Ok(NodeLink::Dir(..) | NodeLink::Bucket(_)) => {
+                    return Err(MountError::PathAlreadyExists(path.to_path_buf()))
+                }
+                Ok(NodeLink::Data(link, secret, meta)) if meta.is_chunked() => {
+                    let list = ChunkList::load(&self.1, &link, &secret).await?;
+                    (secret, list, meta, Box::new(data))
+                }
+                // Carry the contents of a whole file into its first chunks
+                Ok(NodeLink::Data(_, _, meta)) => {
+                    let existing = self.cat(path).await?;
+                    let reader = std::io::Cursor::new(existing).chain(data);
+                    (
+                        Secret::generate(),
+                        ChunkList::default(),
+                        meta,
+                        Box::new(reader),
+                    )
+                }
+                // A new file's type is detected from the start of its first append
+                Err(_) => {
+                    let mut data = data;
+                    let mut head = Vec::new();
+                    (&mut data)
+                        .take(SNIFF_LEN as u64)
+                        .read_to_end(&mut head)
+                        .map_err(SecretError::Io)?;
+                    let mut meta = Data::from_path(path);
+                    meta.set_mime(MaybeMime::detect(path, &head).0);
+                    let reader = std::io::Cursor::new(head).chain(data);
+                    (
+                        Secret::generate(),
+                        ChunkList::default(),
+                        meta,
+                        Box::new(reader),
+                    )
+                }
+            };
+
+        // Chunks already in the list aren't read again, so the new contents
+        //  are only hashed whole when they were all read through here
+        let carried = list.size();
+        let tally = Arc::new(std::sync::Mutex::new(Tally::default()));
+        let mime = meta.mime().cloned();
+        let (link, data_hashes) = chunks::append_chunked(
+            &self.1,
+            &secret,
+            list,
+            Tallied(SyncReader(reader), tally.clone()),
+            codec,
+            mime.as_ref(),
+        )
+        .await?;
+        meta.set_compression(None);
+        meta.set_chunked(true);
+        {
+            let tally = tally.lock().expect("tally lock poisoned");
+            let content_hash =
+                (carried == 0).then(|| Hash::from_bytes(*tally.hasher.finalize().as_bytes()));
+            meta.set_content(Some(carried + tally.size), content_hash);
+        }
+
+        self._put_data_at_path(path, NodeLink::Data(link, secret, meta), data_hashes)
+            .await
+    }
+
+    /// Link a file's node at `path`, pinning its data blobs and recording
+    /// the write in the ops log
+    async fn _put_data_at_path(
+        &mut self,
+        path: &Path,
+        node_link: NodeLink,
+        data_hashes: Vec<Hash>,
+    ) -> Result<(), MountError> {
+        let link = node_link.link().clone();
+        self._relink_at_path(path, node_link).await?;
+
+        let mut inner = self.0.lock().await;
+        inner.pins.extend(data_hashes);
+
+        // Record the add operation in the ops log
+        let peer_id = inner.peer_id;
+        inner
+            .ops_log
+            .record(peer_id, OpType::Add, clean_path(path), Some(link), false);
+
+        Ok(())
+    }
+
+    /// Link `node_link` at `path`, rewriting its ancestors up to the root
+    /// and pinning the nodes this creates
+    async fn _relink_at_path(
+        &mut self,
+        path: &Path,
+        node_link: NodeLink,
+    ) -> Result<(), MountError> {
         let root_node = {
             let inner = self.0.lock().await;
             inner.entry.clone()
@@ -427,25 +936,170 @@ impl Mount {
             None
         };
 
-        // Update inner state
-        {
-            let mut inner = self.0.lock().await;
-            // Track pins: data blob + all created node hashes
-            inner.pins.insert(hash);
-            inner.pins.extend(node_hashes);
+        let mut inner = self.0.lock().await;
+        inner.pins.extend(node_hashes);
+        if let Some(entry) = new_entry {
+            inner.entry = entry;
+        }
+
+        Ok(())
+    }
+
+    /// Get the custom attributes of the file or directory at `path`
+    /// (`/` for the bucket's root)
+    pub async fn attrs(&self, path: &Path) -> Result<Attrs, MountError> {
+        if clean_path(path) == Path::new("") {
+            let inner = self.0.lock().await;
+            return Ok(inner.entry.attrs().clone());
+        }
+
+        match self.get(path).await? {
+            NodeLink::Data(_, _, data) => Ok(data.attrs().clone()),
+            dir @ NodeLink::Dir(..) => Ok(Self::_get_node_from_blobs(&dir, &self.1)
+                .await?
+                .attrs()
+                .clone()),
+            NodeLink::Bucket(_) => Err(MountError::BucketReference(clean_path(path))),
+        }
+    }
 
-            if let Some(entry) = new_entry {
-                inner.entry = entry;
+    /// Set the attributes in `set` and remove those named in `remove` on the
+    /// file or directory at `path`, returning the resulting attributes
+    ///
+    /// File contents aren't touched; the entry and its ancestors are relinked.
+    /// Attribute changes aren't recorded in the ops log, so merging diverged
+    /// histories (see [`Mount::merge_from`]) keeps the local side's attributes.
+    pub async fn set_attrs(
+        &mut self,
+        path: &Path,
+        set: Attrs,
+        remove: &[String],
+    ) -> Result<Attrs, MountError> {
+        let update = |mut attrs: Attrs| -> Result<Attrs, String> {
+            for key in remove {
+                attrs.remove(key);
             }
+            attrs.extend(set);
+            validate_attrs(&attrs)?;
+            Ok(attrs)
+        };
 
-            // Record the add operation in the ops log
-            let peer_id = inner.peer_id;
-            inner
-                .ops_log
-                .record(peer_id, OpType::Add, clean_path(path), Some(link), false);
+        if clean_path(path) == Path::new("") {
+            let mut entry = {
+                let inner = self.0.lock().await;
+                inner.entry.clone()
+            };
+            let attrs = update(entry.attrs().clone()).map_err(MountError::InvalidAttrs)?;
+            entry.set_attrs(attrs.clone());
+            let mut inner = self.0.lock().await;
+            inner.entry = entry;
+            return Ok(attrs);
         }
 
-        Ok(())
+        let (node_link, attrs) = match self.get(path).await? {
+            NodeLink::Data(link, secret, mut data) => {
+                let attrs = update(data.attrs().clone()).map_err(MountError::InvalidAttrs)?;
+                data.set_attrs(attrs.clone());
+                (NodeLink::Data(link, secret, data), attrs)
+            }
+            dir @ NodeLink::Dir(..) => {
+                let mut node = Self::_get_node_from_blobs(&dir, &self.1).await?;
+                let attrs = update(node.attrs().clone()).map_err(MountError::InvalidAttrs)?;
+                node.set_attrs(attrs.clone());
+                let secret = Secret::generate();
+                let link = Self::_put_node_in_blobs(&node, &secret, &self.1).await?;
+                self.0.lock().await.pins.insert(link.hash());
+                (NodeLink::new_dir(link, secret), attrs)
+            }
+            NodeLink::Bucket(_) => return Err(MountError::BucketReference(clean_path(path))),
+        };
+        self._relink_at_path(path, node_link).await?;
+
+        Ok(attrs)
+    }
+
+    /// The comments on paths in this bucket
+    pub async fn comments(&self) -> Comments {
+        self.0.lock().await.comments.clone()
+    }
+
+    /// Comment on the file or directory at `path` as it was at the bucket
+    /// version `version`, as this mount's peer
+    ///
+    /// The path isn't looked up, as it may no longer exist in the current
+    /// version. Comments, like attributes, aren't recorded in the ops log.
+    pub async fn add_comment(
+        &mut self,
+        path: &Path,
+        version: Hash,
+        body: &str,
+    ) -> Result<Comment, MountError> {
+        validate_comment(body).map_err(MountError::InvalidComment)?;
+        let created_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs() as i64)
+            .unwrap_or_default();
+
+        let mut inner = self.0.lock().await;
+        let comment = Comment {
+            id: Uuid::new_v4(),
+            path: Path::new("/")
+                .join(clean_path(path))
+                .to_string_lossy()
+                .to_string(),
+            version,
+            author: inner.peer_id,
+            body: body.to_string(),
+            created_at,
+        };
+        inner.comments.insert(comment.clone());
+        Ok(comment)
+    }
+
+    /// Remove a comment, returning it
+    pub async fn remove_comment(&mut self, id: &Uuid) -> Result<Comment, MountError> {
+        self.0
+            .lock()
+            .await
+            .comments
+            .remove(id)
+            .ok_or(MountError::CommentNotFound(*id))
+    }
+
+    /// Detect and record the MIME type of every file that has none, as
+    /// [`Mount::add`] does for new files, returning the paths updated
+    ///
+    /// Only the first bytes of each file are read. Like attribute changes,
+    /// the updates aren't recorded in the ops log.
+    pub async fn backfill_mime(&mut self) -> Result<Vec<PathBuf>, MountError> {
+        let mut updated = Vec::new();
+        for (path, node_link) in self.ls_deep(Path::new("/")).await? {
+            let NodeLink::Data(link, secret, mut data) = node_link else {
+                continue;
+            };
+            if data.mime().is_some() {
+                continue;
+            }
+
+            let path = Path::new("/").join(path);
+            let head = if data.is_chunked() {
+                ChunkList::load(&self.1, &link, &secret)
+                    .await?
+                    .read_prefix(&self.1, SNIFF_LEN)
+                    .await?
+            } else {
+                self.cat(&path).await?
+            };
+            let MaybeMime(Some(mime)) = MaybeMime::detect(&path, &head) else {
+                continue;
+            };
+
+            data.set_mime(Some(mime));
+            self._relink_at_path(&path, NodeLink::Data(link, secret, data))
+                .await?;
+            updated.push(path);
+        }
+        Ok(updated)
     }
 
     pub async fn rm(&mut self, path: &Path) -> Result<(), MountError> {
@@ -635,6 +1289,7 @@ impl Mount {
     ///
     /// - `PathNotFound` - source path doesn't exist
     /// - `PathAlreadyExists` - destination path already exists
+    /// - `PathNotNode` - an ancestor of the destination is a file
     /// - `MoveIntoSelf` - attempting to move a directory into itself (e.g., /foo -> /foo/bar)
     /// - `Default` - attempting to move the root directory
     pub async fn mv(&mut self, from: &Path, to: &Path) -> Result<(), MountError> {
@@ -682,6 +1337,18 @@ impl Mount {
             return Err(MountError::PathAlreadyExists(to.to_path_buf()));
         }
 
+        // The destination must also be insertable, or the node would be lost
+        // after removing it from its source: none of its ancestors may be a file
+        for ancestor in to_clean.ancestors().skip(1) {
+            if ancestor == Path::new("") {
+                break;
+            }
+            let ancestor = Path::new("/").join(ancestor);
+            if let Ok(NodeLink::Data(..)) = self.get(&ancestor).await {
+                return Err(MountError::PathNotNode(ancestor));
+            }
+        }
+
         // ============================================================
         // STEP 3: Remove the node from its source location
         // ============================================================
@@ -875,6 +1542,7 @@ impl Mount {
     }
 
     #[allow(clippy::await_holding_lock)]
+    #[allow(clippy::result_large_err)]
     pub async fn cat(&self, path: &Path) -> Result<Vec<u8>, MountError> {
         let path = clean_path(path);
 
@@ -902,12 +1570,27 @@ impl Mount {
             .ok_or_else(|| MountError::PathNotFound(path.to_path_buf()))?;
 
         match link {
-            NodeLink::Data(link, secret, _) => {
+            NodeLink::Data(link, secret, data) if data.is_chunked() => {
+                ChunkList::load(&self.1, link, secret)
+                    .await?
+                    .read(&self.1)
+                    .await
+            }
+            NodeLink::Data(link, secret, data) => {
                 let encrypted_data = self.1.get(&link.hash()).await?;
-                let data = secret.decrypt(&encrypted_data)?;
-                Ok(data)
+                let secret = secret.clone();
+                let compression = data.compression();
+                offload(encrypted_data.len(), move || {
+                    let plaintext = secret.decrypt(&encrypted_data)?;
+                    match compression {
+                        Some(codec) => Ok(codec.decompress(&plaintext)?),
+                        None => Ok(plaintext),
+                    }
+                })
+                .await
             }
             NodeLink::Dir(_, _) => Err(MountError::PathNotNode(path.to_path_buf())),
+            NodeLink::Bucket(_) => Err(MountError::BucketReference(path.to_path_buf())),
         }
     }
 
@@ -920,19 +1603,105 @@ impl Mount {
         let root_node = inner.entry.clone();
         drop(inner);
 
-        let (parent_path, file_name) = if let Some(parent) = path.parent() {
-            (
-                parent,
-                path.file_name().unwrap().to_string_lossy().to_string(),
-            )
-        } else {
-            return Err(MountError::PathNotFound(path.to_path_buf()));
+        Self::_get_link_at_path(&root_node, &path, &self.1).await
+    }
+
+    /// Reference another bucket at `path`, like a git submodule
+    ///
+    /// The reference is recorded in the ops log like a file added at `path`.
+    /// Nothing of the referenced bucket is copied or pinned.
+    pub async fn add_reference(
+        &mut self,
+        path: &Path,
+        bucket_ref: BucketRef,
+    ) -> Result<(), MountError> {
+        let bucket_id = *self.0.lock().await.manifest.id();
+        if *bucket_ref.bucket_id() == bucket_id {
+            return Err(MountError::ReferenceCycle(bucket_id));
+        }
+        if clean_path(path) == Path::new("") {
+            return Err(MountError::PathAlreadyExists(PathBuf::from("/")));
+        }
+        match self.get(path).await {
+            Err(MountError::PathNotFound(_)) => {}
+            Ok(_) => return Err(MountError::PathAlreadyExists(path.to_path_buf())),
+            Err(e) => return Err(e),
+        }
+
+        self._put_data_at_path(path, NodeLink::Bucket(bucket_ref), Vec::new())
+            .await
+    }
+
+    /// The first bucket reference on `path`, the path itself included: the
+    /// reference's path and what it references. `None` if the path doesn't
+    /// cross one, or doesn't exist.
+    pub async fn find_reference(
+        &self,
+        path: &Path,
+    ) -> Result<Option<(PathBuf, BucketRef)>, MountError> {
+        let mut node = self.0.lock().await.entry.clone();
+        let mut consumed_path = PathBuf::from("/");
+        for part in clean_path(path).iter() {
+            consumed_path.push(part);
+            let Some(node_link) = node.get_link(&part.to_string_lossy()) else {
+                return Ok(None);
+            };
+            match node_link {
+                NodeLink::Bucket(bucket_ref) => {
+                    return Ok(Some((consumed_path, bucket_ref.clone())))
+                }
+                NodeLink::Dir(..) => node = Self::_get_node_from_blobs(node_link, &self.1).await?,
+                NodeLink::Data(..) => return Ok(None),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Hashes of every blob reachable from a node: the node itself and, for
+    /// directories, everything below it, including the chunks of large files.
+    /// Referenced buckets aren't part of it. The blobs must be in `blobs`.
+    pub async fn reachable(
+        node_link: &NodeLink,
+        blobs: &BlobsStore,
+    ) -> Result<Vec<crate::linked_data::Hash>, MountError> {
+        let mut hashes = Vec::new();
+        let mut queue = vec![node_link.clone()];
+        while let Some(node_link) = queue.pop() {
+            if node_link.is_bucket() {
+                continue;
+            }
+            hashes.push(node_link.link().hash());
+            match &node_link {
+                NodeLink::Dir(..) => {
+                    let node = Self::_get_node_from_blobs(&node_link, blobs).await?;
+                    queue.extend(node.get_links().values().cloned());
+                }
+                NodeLink::Data(link, secret, data) if data.is_chunked() => {
+                    let chunks = ChunkList::load(blobs, link, secret).await?;
+                    hashes.extend(chunks.chunks().iter().map(|chunk| chunk.link().hash()));
+                }
+                NodeLink::Data(..) | NodeLink::Bucket(_) => {}
+            }
+        }
+        Ok(hashes)
+    }
+
+    /// The link at a path relative to `root`
+    async fn _get_link_at_path(
+        root: &Node,
+        path: &Path,
+        blobs: &BlobsStore,
+    ) -> Result<NodeLink, MountError> {
+        let path = path.strip_prefix("/").unwrap_or(path);
+        let (parent_path, file_name) = match (path.parent(), path.file_name()) {
+            (Some(parent), Some(name)) => (parent, name.to_string_lossy().to_string()),
+            _ => return Err(MountError::PathNotFound(path.to_path_buf())),
         };
 
         let parent_node = if parent_path == Path::new("") {
-            root_node
+            root.clone()
         } else {
-            Self::_get_node_at_path(&root_node, parent_path, &self.1).await?
+            Self::_get_node_at_path(root, parent_path, blobs).await?
         };
 
         parent_node
@@ -955,6 +1724,9 @@ impl Mount {
             let next_link = current_node
                 .get_link(&next)
                 .ok_or(MountError::PathNotFound(consumed_path.clone()))?;
+            if next_link.is_bucket() {
+                return Err(MountError::BucketReference(consumed_path));
+            }
             current_node = Self::_get_node_from_blobs(next_link, blobs).await?
         }
         Ok(current_node)
@@ -987,6 +1759,9 @@ impl Mount {
                     NodeLink::Data(..) => {
                         return Err(MountError::PathNotNode(consumed_path.clone()));
                     }
+                    NodeLink::Bucket(_) => {
+                        return Err(MountError::BucketReference(consumed_path.clone()));
+                    }
                 }
                 visited_nodes.push((consumed_path.clone(), node.clone()));
             } else {
@@ -1026,7 +1801,7 @@ impl Mount {
         let hash = link.hash();
         tracing::debug!("_get_bucket_from_blobs: Bucket hash: {}", hash);
 
-        match blobs.stat(&hash).await {
+        match blobs.materialize(&hash).await {
             Ok(true) => {
                 tracing::debug!(
                     "_get_bucket_from_blobs: Bucket hash {} exists in blobs",
@@ -1068,7 +1843,7 @@ impl Mount {
         let hash = link.hash();
         tracing::debug!("_get_pins_from_blobs: Pins hash: {}", hash);
 
-        match blobs.stat(&hash).await {
+        match blobs.materialize(&hash).await {
             Ok(true) => {
                 tracing::debug!("_get_pins_from_blobs: Pins hash {} exists in blobs", hash);
             }
@@ -1105,12 +1880,15 @@ impl Mount {
         blobs: &BlobsStore,
     ) -> Result<Node, MountError> {
         let link = node_link.link();
-        let secret = node_link.secret();
+        // Referenced buckets are resolved by the caller, see `Peer::resolve`
+        let secret = node_link
+            .secret()
+            .ok_or_else(|| MountError::LinkNotFound(link.clone()))?;
         let hash = link.hash();
 
         tracing::debug!("_get_node_from_blobs: Checking for node at hash {}", hash);
 
-        match blobs.stat(&hash).await {
+        match blobs.materialize(&hash).await {
             Ok(true) => {
                 tracing::debug!("_get_node_from_blobs: Node hash {} exists in blobs", hash);
             }
@@ -1139,7 +1917,7 @@ impl Mount {
         );
 
         tracing::debug!("_get_node_from_blobs: Decrypting node data");
-        let data = secret.decrypt(&blob)?;
+        let data = secret.decrypt_offloaded(blob).await?;
         tracing::debug!("_get_node_from_blobs: Decrypted {} bytes", data.len());
 
         let node = Node::decode(&data)?;
@@ -1157,7 +1935,7 @@ impl Mount {
         blobs: &BlobsStore,
     ) -> Result<Link, MountError> {
         let _data = node.encode()?;
-        let data = secret.encrypt(&_data)?;
+        let data = secret.encrypt_offloaded(_data).await?;
         let hash = blobs.put(data).await?;
         // NOTE (amiller68): nodes are always stored as raw
         //  since they are encrypted blobs
@@ -1197,7 +1975,7 @@ impl Mount {
             hash
         );
 
-        match blobs.stat(&hash).await {
+        match blobs.materialize(&hash).await {
             Ok(true) => {
                 tracing::debug!(
                     "_get_ops_log_from_blobs: Ops log hash {} exists in blobs",
@@ -1229,7 +2007,7 @@ impl Mount {
         );
 
         tracing::debug!("_get_ops_log_from_blobs: Decrypting ops log data");
-        let data = secret.decrypt(&blob)?;
+        let data = secret.decrypt_offloaded(blob).await?;
         tracing::debug!("_get_ops_log_from_blobs: Decrypted {} bytes", data.len());
 
         let ops_log = PathOpLog::decode(&data)?;
@@ -1247,7 +2025,7 @@ impl Mount {
         blobs: &BlobsStore,
     ) -> Result<Link, MountError> {
         let _data = ops_log.encode()?;
-        let data = secret.encrypt(&_data)?;
+        let data = secret.encrypt_offloaded(_data).await?;
         let hash = blobs.put(data).await?;
         // Ops log is stored as an encrypted raw blob
         let link = Link::new(crate::linked_data::LD_RAW_CODEC, hash);
@@ -1259,6 +2037,31 @@ impl Mount {
         Ok(link)
     }
 
+    async fn _get_comments_from_blobs(
+        link: &Link,
+        secret: &Secret,
+        blobs: &BlobsStore,
+    ) -> Result<Comments, MountError> {
+        let hash = link.hash();
+        if !blobs.materialize(&hash).await? {
+            return Err(MountError::LinkNotFound(link.clone()));
+        }
+        let blob = blobs.get(&hash).await?;
+        let data = secret.decrypt_offloaded(blob).await?;
+        Ok(Comments::decode(&data)?)
+    }
+
+    async fn _put_comments_in_blobs(
+        comments: &Comments,
+        secret: &Secret,
+        blobs: &BlobsStore,
+    ) -> Result<Link, MountError> {
+        let data = secret.encrypt_offloaded(comments.encode()?).await?;
+        let hash = blobs.put(data).await?;
+        // Comments are stored as an encrypted raw blob, like the ops log
+        Ok(Link::new(crate::linked_data::LD_RAW_CODEC, hash))
+    }
+
     /// Collect all ops from manifest chain back to (but not including) ancestor_link.
     ///
     /// Traverses the manifest chain starting from the current version, collecting
@@ -1509,10 +2312,13 @@ impl Mount {
         // Apply merged state to the entry tree
         self.apply_resolved_state(&merged_ops).await?;
 
-        // Merge the ops_log to include all merged operations
+        // Merge the ops_log to include all merged operations, and take the
+        //  comments of both sides
+        let incoming_comments = incoming.inner().await.comments;
         {
             let mut inner = self.0.lock().await;
             inner.ops_log.merge(&merged_ops);
+            inner.comments.merge(&incoming_comments);
         }
 
         // Save the merged state
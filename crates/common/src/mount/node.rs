@@ -5,11 +5,14 @@ use std::path::Path;
 
 use mime::Mime;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 use crate::crypto::Secret;
-use crate::linked_data::{BlockEncoded, DagCborCodec, Link, LinkedData};
+use crate::linked_data::{BlockEncoded, DagCborCodec, Hash, Link, LinkedData};
 
+use super::compression::Compression;
 use super::maybe_mime::MaybeMime;
+use super::media::MediaInfo;
 
 /**
  * Nodes
@@ -21,6 +24,8 @@ use super::maybe_mime::MaybeMime;
  *   to other nodes, which fall into two categories:
  *  - Data Links: links to terminal nodes in the DAG i.e. actual files
  *  - Dir Links: links to other nodes in the DAG i.e. directories
+ *  Directories may also reference other buckets, like git submodules,
+ *   see `BucketRef`
  * Nodes are always DAG-CBOR encoded, and may be encrypted
  */
 
@@ -36,6 +41,26 @@ pub struct Data {
     // Data Links may have metadata built for them, which are parsed
     //  from the links data at inclusion time
     metadata: Option<BTreeMap<String, LinkedData>>,
+    // Data Links may have been compressed before encryption,
+    //  in which case readers must decompress after decrypting
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    compression: Option<Compression>,
+    // Data Links of large files may point to a chunk list rather
+    //  than the file's contents, see `super::chunks`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    chunked: bool,
+    // Data Links may carry custom attributes set by users and apps
+    //  (tags, labels, app-specific fields), see `Attrs`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    attrs: Attrs,
+    // Data Links record the size and BLAKE3 hash of the file's
+    //  plaintext when it is written whole, so clients can tell whether
+    //  a local copy differs without downloading it. Files written
+    //  before these were recorded, or appended to, have neither
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    size: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    content_hash: Option<Hash>,
 }
 
 impl Default for Data {
@@ -50,6 +75,11 @@ impl Data {
         Self {
             mime: MaybeMime(None),
             metadata: None,
+            compression: None,
+            chunked: false,
+            attrs: Attrs::new(),
+            size: None,
+            content_hash: None,
         }
     }
 
@@ -65,6 +95,11 @@ impl Data {
             } else {
                 Some(metadata)
             },
+            compression: None,
+            chunked: false,
+            attrs: Attrs::new(),
+            size: None,
+            content_hash: None,
         }
     }
 
@@ -79,6 +114,21 @@ impl Data {
         }
     }
 
+    /// Set the MIME type
+    pub fn set_mime(&mut self, mime: Option<Mime>) {
+        self.mime = MaybeMime(mime);
+    }
+
+    /// Record the compression applied to the data before encryption
+    pub fn set_compression(&mut self, compression: Option<Compression>) {
+        self.compression = compression;
+    }
+
+    /// Mark the data link as pointing to a chunk list
+    pub fn set_chunked(&mut self, chunked: bool) {
+        self.chunked = chunked;
+    }
+
     /// Get the MIME type if present
     pub fn mime(&self) -> Option<&Mime> {
         self.mime.0.as_ref()
@@ -88,14 +138,132 @@ impl Data {
     pub fn metadata(&self) -> Option<&BTreeMap<String, LinkedData>> {
         self.metadata.as_ref()
     }
+
+    /// Get the image or video metadata extracted at upload, if any
+    pub fn media(&self) -> Option<MediaInfo> {
+        let media = MediaInfo::from_metadata(self.metadata.as_ref()?);
+        (!media.is_empty()).then_some(media)
+    }
+
+    /// Get the compression applied to the data, if any
+    pub fn compression(&self) -> Option<Compression> {
+        self.compression
+    }
+
+    /// Whether the data link points to a chunk list
+    pub fn is_chunked(&self) -> bool {
+        self.chunked
+    }
+
+    /// Get the custom attributes
+    pub fn attrs(&self) -> &Attrs {
+        &self.attrs
+    }
+
+    /// Replace the custom attributes
+    pub fn set_attrs(&mut self, attrs: Attrs) {
+        self.attrs = attrs;
+    }
+
+    /// Record the size and BLAKE3 hash of the plaintext, or forget them
+    pub fn set_content(&mut self, size: Option<u64>, content_hash: Option<Hash>) {
+        self.size = size;
+        self.content_hash = content_hash;
+    }
+
+    /// Size of the plaintext in bytes, if recorded
+    pub fn size(&self) -> Option<u64> {
+        self.size
+    }
+
+    /// BLAKE3 hash of the plaintext, if recorded
+    pub fn content_hash(&self) -> Option<Hash> {
+        self.content_hash
+    }
+}
+
+/// Custom key-value attributes on a file or directory. They are
+/// stored alongside the rest of the entry, so they are encrypted
+/// with it and travel with the bucket.
+pub type Attrs = BTreeMap<String, String>;
+
+/// Longest attribute key, in bytes
+pub const MAX_ATTR_KEY_LEN: usize = 256;
+
+/// Largest total size of one entry's attributes (keys plus values), in bytes
+pub const MAX_ATTRS_SIZE: usize = 64 * 1024;
+
+/// Check that a set of attributes is within the limits above
+pub fn validate_attrs(attrs: &Attrs) -> Result<(), String> {
+    let mut size = 0;
+    for (key, value) in attrs {
+        if key.is_empty() {
+            return Err("attribute keys must not be empty".to_string());
+        }
+        if key.len() > MAX_ATTR_KEY_LEN {
+            return Err(format!(
+                "attribute key is longer than {} bytes",
+                MAX_ATTR_KEY_LEN
+            ));
+        }
+        size += key.len() + value.len();
+    }
+    if size > MAX_ATTRS_SIZE {
+        return Err(format!("attributes exceed {} bytes", MAX_ATTRS_SIZE));
+    }
+    Ok(())
+}
+
+/// A reference to another bucket mounted at a path, like a git submodule.
+///
+/// The reference records a version of the bucket: readers see that version
+/// if it is pinned, or the bucket's latest version they can read if it is
+/// floating. Nothing of the referenced bucket is stored in this one; readers
+/// need access to both, see `Peer::resolve`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BucketRef {
+    bucket_id: Uuid,
+    // Manifest link of the referenced bucket's version when the
+    //  reference was made
+    version: Link,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    floating: bool,
+}
+
+impl BucketRef {
+    /// Reference `bucket_id` at `version`, following its head if `floating`
+    pub fn new(bucket_id: Uuid, version: Link, floating: bool) -> Self {
+        Self {
+            bucket_id,
+            version,
+            floating,
+        }
+    }
+
+    /// The referenced bucket
+    pub fn bucket_id(&self) -> &Uuid {
+        &self.bucket_id
+    }
+
+    /// The version recorded when the reference was made
+    pub fn version(&self) -> &Link {
+        &self.version
+    }
+
+    /// Whether readers follow the bucket's head rather than the recorded
+    /// version
+    pub fn is_floating(&self) -> bool {
+        self.floating
+    }
 }
 
 // Lastly, we have a node, which is either a data link,
-//  or a link to another node.
+//  a link to another node, or a reference to another bucket.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum NodeLink {
     Data(Link, Secret, Data),
     Dir(Link, Secret),
+    Bucket(BucketRef),
 }
 
 impl NodeLink {
@@ -115,17 +283,23 @@ impl NodeLink {
         NodeLink::Dir(link, secret)
     }
 
+    /// The linked blob, or for bucket references the recorded version of
+    /// the referenced bucket's manifest, which isn't part of this bucket
     pub fn link(&self) -> &Link {
         match self {
             NodeLink::Data(link, _, _) => link,
             NodeLink::Dir(link, _) => link,
+            NodeLink::Bucket(bucket_ref) => bucket_ref.version(),
         }
     }
 
-    pub fn secret(&self) -> &Secret {
+    /// The secret the linked blob is encrypted with, none for bucket
+    /// references
+    pub fn secret(&self) -> Option<&Secret> {
         match self {
-            NodeLink::Data(_, secret, _) => secret,
-            NodeLink::Dir(_, secret) => secret,
+            NodeLink::Data(_, secret, _) => Some(secret),
+            NodeLink::Dir(_, secret) => Some(secret),
+            NodeLink::Bucket(_) => None,
         }
     }
 
@@ -133,7 +307,15 @@ impl NodeLink {
     pub fn data(&self) -> Option<&Data> {
         match self {
             NodeLink::Data(_, _, data) => Some(data),
-            NodeLink::Dir(_, _) => None,
+            NodeLink::Dir(..) | NodeLink::Bucket(_) => None,
+        }
+    }
+
+    /// Get the referenced bucket if this is a bucket reference
+    pub fn bucket_ref(&self) -> Option<&BucketRef> {
+        match self {
+            NodeLink::Bucket(bucket_ref) => Some(bucket_ref),
+            NodeLink::Data(..) | NodeLink::Dir(..) => None,
         }
     }
 
@@ -146,6 +328,11 @@ impl NodeLink {
     pub fn is_data(&self) -> bool {
         matches!(self, NodeLink::Data(_, _, _))
     }
+
+    /// Check if this is a reference to another bucket
+    pub fn is_bucket(&self) -> bool {
+        matches!(self, NodeLink::Bucket(_))
+    }
 }
 
 // And a node is just a map of names to links.
@@ -154,6 +341,10 @@ impl NodeLink {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct Node {
     links: BTreeMap<String, NodeLink>,
+    // Directories keep their custom attributes in their own node,
+    //  so they stay encrypted with the directory's secret
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    attrs: Attrs,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -168,6 +359,7 @@ impl Node {
     pub fn new() -> Self {
         Node {
             links: BTreeMap::new(),
+            attrs: Attrs::new(),
         }
     }
 
@@ -191,6 +383,16 @@ impl Node {
     pub fn size(&self) -> usize {
         self.links.len()
     }
+
+    /// Get the directory's custom attributes
+    pub fn attrs(&self) -> &Attrs {
+        &self.attrs
+    }
+
+    /// Replace the directory's custom attributes
+    pub fn set_attrs(&mut self, attrs: Attrs) {
+        self.attrs = attrs;
+    }
 }
 
 #[cfg(test)]
@@ -208,6 +410,11 @@ mod test {
                 Data {
                     metadata: None,
                     mime: MaybeMime(None),
+                    compression: None,
+                    chunked: false,
+                    attrs: Attrs::new(),
+                    size: None,
+                    content_hash: None,
                 },
             ),
         );
@@ -218,6 +425,39 @@ mod test {
         assert_eq!(node, decoded);
     }
 
+    #[test]
+    fn test_attrs_encode_decode() {
+        let mut data = Data::new();
+        data.set_attrs(Attrs::from([("tag".to_string(), "holiday".to_string())]));
+        let mut node = Node::default();
+        node.insert(
+            "photo.jpg".to_string(),
+            NodeLink::Data(Link::default(), Secret::default(), data),
+        );
+        node.set_attrs(Attrs::from([("album".to_string(), "2024".to_string())]));
+
+        let decoded = Node::decode(&node.encode().unwrap()).unwrap();
+        assert_eq!(node, decoded);
+        assert_eq!(
+            decoded.attrs().get("album").map(String::as_str),
+            Some("2024")
+        );
+
+        // Nodes without attributes encode as they did before attributes existed
+        let empty = Node::default();
+        assert!(!String::from_utf8_lossy(&empty.encode().unwrap()).contains("attrs"));
+    }
+
+    #[test]
+    fn test_validate_attrs() {
+        assert!(validate_attrs(&Attrs::new()).is_ok());
+        assert!(validate_attrs(&Attrs::from([(String::new(), "x".to_string())])).is_err());
+        let long_key = "k".repeat(MAX_ATTR_KEY_LEN + 1);
+        assert!(validate_attrs(&Attrs::from([(long_key, String::new())])).is_err());
+        let big_value = "v".repeat(MAX_ATTRS_SIZE);
+        assert!(validate_attrs(&Attrs::from([("k".to_string(), big_value)])).is_err());
+    }
+
     #[test]
     fn test_data_from_path() {
         use std::path::PathBuf;
@@ -0,0 +1,202 @@
+//! Content-based MIME detection
+//!
+//! Extensions say nothing about files without one, and uploads often lack
+//! them. When the path doesn't give a MIME type, the first bytes of the file
+//! are matched against the signatures of common formats, falling back to
+//! `text/plain` for content that decodes as UTF-8 text.
+
+use mime::Mime;
+
+/// How many leading bytes of a file are enough to sniff its type
+pub const SNIFF_LEN: usize = 8 * 1024;
+
+/// A format recognised by bytes at a fixed offset
+struct Signature {
+    offset: usize,
+    magic: &'static [u8],
+    mime: &'static str,
+}
+
+const fn sig(offset: usize, magic: &'static [u8], mime: &'static str) -> Signature {
+    Signature {
+        offset,
+        magic,
+        mime,
+    }
+}
+
+/// Checked in order, so more specific signatures come before the ones they
+/// share a prefix with
+const SIGNATURES: &[Signature] = &[
+    // Images
+    sig(0, b"\x89PNG\r\n\x1a\n", "image/png"),
+    sig(0, b"\xff\xd8\xff", "image/jpeg"),
+    sig(0, b"GIF87a", "image/gif"),
+    sig(0, b"GIF89a", "image/gif"),
+    sig(8, b"WEBP", "image/webp"),
+    sig(0, b"BM", "image/bmp"),
+    sig(0, b"\x00\x00\x01\x00", "image/x-icon"),
+    sig(0, b"II*\x00", "image/tiff"),
+    sig(0, b"MM\x00*", "image/tiff"),
+    sig(4, b"ftypavif", "image/avif"),
+    sig(4, b"ftypheic", "image/heic"),
+    sig(4, b"ftypheix", "image/heic"),
+    sig(4, b"ftypmif1", "image/heif"),
+    // Audio
+    sig(4, b"ftypM4A", "audio/mp4"),
+    sig(0, b"ID3", "audio/mpeg"),
+    sig(0, b"\xff\xfb", "audio/mpeg"),
+    sig(0, b"\xff\xf3", "audio/mpeg"),
+    sig(0, b"fLaC", "audio/flac"),
+    sig(0, b"OggS", "audio/ogg"),
+    sig(8, b"WAVE", "audio/wav"),
+    // Video
+    sig(4, b"ftypqt", "video/quicktime"),
+    sig(4, b"ftyp", "video/mp4"),
+    sig(8, b"AVI ", "video/x-msvideo"),
+    sig(0, b"\x1a\x45\xdf\xa3", "video/x-matroska"),
+    // Documents and archives
+    sig(0, b"%PDF-", "application/pdf"),
+    sig(0, b"PK\x03\x04", "application/zip"),
+    sig(0, b"\x1f\x8b", "application/gzip"),
+    sig(0, b"BZh", "application/x-bzip2"),
+    sig(0, b"\xfd7zXZ\x00", "application/x-xz"),
+    sig(0, b"7z\xbc\xaf\x27\x1c", "application/x-7z-compressed"),
+    sig(0, b"\x28\xb5\x2f\xfd", "application/zstd"),
+    sig(257, b"ustar", "application/x-tar"),
+    sig(0, b"SQLite format 3\x00", "application/vnd.sqlite3"),
+    // Executables and fonts
+    sig(0, b"\x00asm", "application/wasm"),
+    sig(0, b"\x7fELF", "application/x-executable"),
+    sig(0, b"wOFF", "font/woff"),
+    sig(0, b"wOF2", "font/woff2"),
+    sig(0, b"OTTO", "font/otf"),
+    sig(0, b"\x00\x01\x00\x00\x00", "font/ttf"),
+];
+
+/// Guess the MIME type of a file from its first bytes
+///
+/// Returns `None` for empty content and for binary content of no known
+/// format.
+pub fn sniff(head: &[u8]) -> Option<Mime> {
+    let head = &head[..head.len().min(SNIFF_LEN)];
+    if head.is_empty() {
+        return None;
+    }
+
+    let matched = SIGNATURES
+        .iter()
+        .find(|sig| head.get(sig.offset..sig.offset + sig.magic.len()) == Some(sig.magic));
+    if let Some(sig) = matched {
+        // Matroska files that declare themselves WebM are served as such
+        if sig.mime == "video/x-matroska" && contains(head, b"webm") {
+            return "video/webm".parse().ok();
+        }
+        return sig.mime.parse().ok();
+    }
+
+    sniff_text(head)
+}
+
+/// Markup and plain text, telling apart the kinds a browser renders
+fn sniff_text(head: &[u8]) -> Option<Mime> {
+    let text = match std::str::from_utf8(head) {
+        Ok(text) => text,
+        // A multi-byte character cut off by the end of the head is fine
+        Err(e) if e.error_len().is_none() => std::str::from_utf8(&head[..e.valid_up_to()]).ok()?,
+        Err(_) => return None,
+    };
+    if text.contains('\0') {
+        return None;
+    }
+
+    let start = text.trim_start_matches('\u{feff}').trim_start();
+    let lower = start
+        .get(..start.len().min(64))
+        .unwrap_or(start)
+        .to_ascii_lowercase();
+    let mime = if lower.starts_with("<!doctype html") || lower.starts_with("<html") {
+        mime::TEXT_HTML
+    } else if lower.starts_with("<svg") || (lower.starts_with("<?xml") && start.contains("<svg")) {
+        mime::IMAGE_SVG
+    } else if lower.starts_with("<?xml") {
+        mime::TEXT_XML
+    } else if start.starts_with("%!PS") {
+        return "application/postscript".parse().ok();
+    } else {
+        mime::TEXT_PLAIN
+    };
+    Some(mime)
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sniffed(head: &[u8]) -> Option<String> {
+        sniff(head).map(|mime| mime.to_string())
+    }
+
+    #[test]
+    fn test_sniff_signatures() {
+        assert_eq!(
+            sniffed(b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR").as_deref(),
+            Some("image/png")
+        );
+        assert_eq!(
+            sniffed(b"\xff\xd8\xff\xe0\x00\x10JFIF").as_deref(),
+            Some("image/jpeg")
+        );
+        assert_eq!(
+            sniffed(b"RIFF\x24\x00\x00\x00WEBPVP8 ").as_deref(),
+            Some("image/webp")
+        );
+        assert_eq!(
+            sniffed(b"\x00\x00\x00\x18ftypheic\x00\x00").as_deref(),
+            Some("image/heic")
+        );
+        assert_eq!(
+            sniffed(b"\x00\x00\x00\x20ftypisom\x00\x00").as_deref(),
+            Some("video/mp4")
+        );
+        assert_eq!(
+            sniffed(b"\x1a\x45\xdf\xa3\x01\x00\x00\x00\x42\x82\x84webm").as_deref(),
+            Some("video/webm")
+        );
+        assert_eq!(sniffed(b"%PDF-1.7\n").as_deref(), Some("application/pdf"));
+
+        let mut tar = vec![0u8; 512];
+        tar[257..262].copy_from_slice(b"ustar");
+        assert_eq!(sniffed(&tar).as_deref(), Some("application/x-tar"));
+    }
+
+    #[test]
+    fn test_sniff_text() {
+        assert_eq!(sniffed(b"hello world\n").as_deref(), Some("text/plain"));
+        assert_eq!(
+            sniffed(b"  <!DOCTYPE html><html></html>").as_deref(),
+            Some("text/html")
+        );
+        assert_eq!(
+            sniffed(b"<?xml version=\"1.0\"?><svg></svg>").as_deref(),
+            Some("image/svg+xml")
+        );
+        // Text cut off in the middle of a character is still text
+        assert_eq!(
+            sniffed("caf\u{e9}".as_bytes()[..4].as_ref()).as_deref(),
+            Some("text/plain")
+        );
+    }
+
+    #[test]
+    fn test_sniff_unknown() {
+        assert_eq!(sniffed(b""), None);
+        assert_eq!(sniffed(b"\x00\x9f\x92\x96 not text"), None);
+    }
+}
@@ -1,9 +1,10 @@
 use std::future::IntoFuture;
 use std::ops::Deref;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 use anyhow::anyhow;
+use async_trait::async_trait;
 use bytes::Bytes;
 use futures::Stream;
 use iroh::{Endpoint, NodeId};
@@ -17,7 +18,8 @@ use iroh_blobs::{
     BlobsProtocol, Hash,
 };
 
-use object_store::ObjectStore as ObjStore;
+use object_store::{ObjectStore as ObjStore, StorageClass};
+use uuid::Uuid;
 
 use crate::{
     crypto::PublicKey,
@@ -34,6 +36,27 @@ use crate::{
 #[derive(Clone, Debug)]
 pub struct BlobsStore {
     pub inner: Arc<BlobsProtocol>,
+    /// Fetches missing blobs on read, shared by all clones of the store
+    fetcher: Arc<RwLock<Option<Arc<dyn BlobFetcher>>>>,
+    /// The object store backend, if that's what the store is, for placing
+    ///  blobs in its storage locations
+    object_store: Option<ObjStore>,
+    /// Bucket whose blobs are read through this handle, so the fetcher
+    ///  knows whose peers to ask
+    bucket: Option<Uuid>,
+}
+
+/// Fetches blobs that aren't stored locally when they are read,
+///  so content can be materialized on demand instead of downloaded up front
+#[async_trait]
+pub trait BlobFetcher: Send + Sync + std::fmt::Debug {
+    /// Fetch a missing blob into the store. `blobs` is the handle the
+    ///  blob was read through, scoped to its bucket if it was read from one
+    ///  (see [`BlobsStore::bucket`]).
+    async fn fetch(&self, blobs: &BlobsStore, hash: Hash) -> Result<(), BlobsStoreError>;
+
+    /// Called whenever a blob is read through the store
+    fn accessed(&self, _hash: &Hash) {}
 }
 
 impl Deref for BlobsStore {
@@ -59,6 +82,19 @@ pub enum BlobsStoreError {
     ObjectStore(#[from] object_store::BlobStoreError),
 }
 
+impl BlobsStoreError {
+    /// Storage class of the archived blob a read ran into, whose restore
+    ///  was requested
+    pub fn archived(&self) -> Option<StorageClass> {
+        match self {
+            BlobsStoreError::ObjectStore(object_store::BlobStoreError::Archived {
+                class, ..
+            }) => Some(*class),
+            _ => None,
+        }
+    }
+}
+
 impl BlobsStore {
     /// Legacy: load from filesystem using iroh's FsStore directly.
     pub async fn legacy_fs(path: &Path) -> Result<Self, BlobsStoreError> {
@@ -68,6 +104,9 @@ impl BlobsStore {
         let blobs = BlobsProtocol::new(&store, None);
         Ok(Self {
             inner: Arc::new(blobs),
+            fetcher: Default::default(),
+            object_store: None,
+            bucket: None,
         })
     }
 
@@ -77,19 +116,22 @@ impl BlobsStore {
         let blobs = BlobsProtocol::new(&store, None);
         Ok(Self {
             inner: Arc::new(blobs),
+            fetcher: Default::default(),
+            object_store: None,
+            bucket: None,
         })
     }
 
     /// Local filesystem via ObjectStore (SQLite + local object storage).
     pub async fn fs(data_dir: &Path) -> Result<Self, BlobsStoreError> {
         let store = ObjStore::new_local(data_dir).await?;
-        Ok(Self::from_store(store.into()))
+        Ok(Self::from_object_store(store))
     }
 
     /// In-memory via ObjectStore.
     pub async fn memory() -> Result<Self, BlobsStoreError> {
         let store = ObjStore::new_ephemeral().await?;
-        Ok(Self::from_store(store.into()))
+        Ok(Self::from_object_store(store))
     }
 
     /// S3-backed via ObjectStore.
@@ -103,7 +145,19 @@ impl BlobsStore {
     ) -> Result<Self, BlobsStoreError> {
         let store =
             ObjStore::new_s3(db_path, endpoint, access_key, secret_key, bucket, region).await?;
-        Ok(Self::from_store(store.into()))
+        Ok(Self::from_object_store(store))
+    }
+
+    /// Wrap an ObjectStore, e.g. one with storage locations to place
+    ///  blobs in
+    pub fn from_object_store(store: ObjStore) -> Self {
+        let blobs = BlobsProtocol::new(&store, None);
+        Self {
+            inner: Arc::new(blobs),
+            fetcher: Default::default(),
+            object_store: Some(store),
+            bucket: None,
+        }
     }
 
     /// Wrap an existing iroh-blobs Store (kept for flexibility).
@@ -111,6 +165,9 @@ impl BlobsStore {
         let blobs = BlobsProtocol::new(&store, None);
         Self {
             inner: Arc::new(blobs),
+            fetcher: Default::default(),
+            object_store: None,
+            bucket: None,
         }
     }
 
@@ -120,8 +177,133 @@ impl BlobsStore {
         self.inner.store().blobs()
     }
 
+    /// Set the fetcher used to materialize blobs that aren't stored locally
+    pub fn set_fetcher(&self, fetcher: Arc<dyn BlobFetcher>) {
+        *self.fetcher.write().expect("fetcher lock poisoned") = Some(fetcher);
+    }
+
+    /// A handle to the store for reading a bucket's blobs, through which
+    ///  missing blobs are fetched from that bucket's peers
+    pub fn for_bucket(&self, bucket_id: Uuid) -> Self {
+        Self {
+            bucket: Some(bucket_id),
+            ..self.clone()
+        }
+    }
+
+    /// Bucket this handle reads blobs of, if it's scoped to one
+    pub fn bucket(&self) -> Option<Uuid> {
+        self.bucket
+    }
+
+    fn fetcher(&self) -> Option<Arc<dyn BlobFetcher>> {
+        self.fetcher.read().expect("fetcher lock poisoned").clone()
+    }
+
+    /// Make sure a blob is stored locally, fetching it through the fetcher
+    ///  if one is set. Returns whether the blob is available.
+    pub async fn materialize(&self, hash: &Hash) -> Result<bool, BlobsStoreError> {
+        let Some(fetcher) = self.fetcher() else {
+            return self.stat(hash).await;
+        };
+        if !self.stat(hash).await? {
+            fetcher.fetch(self, *hash).await?;
+            if !self.stat(hash).await? {
+                return Ok(false);
+            }
+        }
+        fetcher.accessed(hash);
+        Ok(true)
+    }
+
+    /// Materialize a blob before reading it, if a fetcher is set, and
+    ///  restore it if it's archived
+    async fn ensure_local(&self, hash: &Hash) -> Result<(), BlobsStoreError> {
+        if self.fetcher().is_some() {
+            self.materialize(hash).await?;
+        }
+        if let Some(store) = &self.object_store {
+            store.ensure_readable(hash).await?;
+        }
+        Ok(())
+    }
+
+    /// Names of the storage locations blobs can be placed in besides the
+    ///  default one. Only object store backends have any.
+    pub fn locations(&self) -> Vec<String> {
+        self.object_store
+            .as_ref()
+            .map(ObjStore::locations)
+            .unwrap_or_default()
+    }
+
+    /// Move a blob to a storage location, or back to the default one with
+    ///  `None`. Returns whether it moved; blobs not stored here are left
+    ///  alone.
+    pub async fn place(
+        &self,
+        hash: &Hash,
+        location: Option<&str>,
+    ) -> Result<bool, BlobsStoreError> {
+        match (&self.object_store, location) {
+            (Some(store), _) => Ok(store.place(hash, location).await?),
+            (None, None) => Ok(false),
+            (None, Some(name)) => {
+                Err(object_store::BlobStoreError::UnknownLocation(name.to_string()).into())
+            }
+        }
+    }
+
+    /// Whether blobs can be moved to other storage classes, which only
+    ///  object stores with S3 storage can
+    pub fn supports_storage_classes(&self) -> bool {
+        self.object_store
+            .as_ref()
+            .is_some_and(ObjStore::supports_storage_classes)
+    }
+
+    /// Storage class of a blob, `None` if it isn't stored in an object store
+    pub async fn storage_class(
+        &self,
+        hash: &Hash,
+    ) -> Result<Option<StorageClass>, BlobsStoreError> {
+        match &self.object_store {
+            Some(store) => Ok(store.storage_class(hash).await?),
+            None => Ok(None),
+        }
+    }
+
+    /// Move a blob to another storage class where it's stored. Returns
+    ///  whether it moved. Archived blobs are restored first, and fail until
+    ///  they are (see [`BlobsStoreError::archived`]).
+    pub async fn set_storage_class(
+        &self,
+        hash: &Hash,
+        class: StorageClass,
+    ) -> Result<bool, BlobsStoreError> {
+        match (&self.object_store, class) {
+            (Some(store), _) => Ok(store.set_storage_class(hash, class).await?),
+            (None, StorageClass::Standard) => Ok(false),
+            (None, _) => Err(object_store::BlobStoreError::StorageClassUnsupported.into()),
+        }
+    }
+
+    /// Delete blobs from the store
+    pub async fn delete(&self, hashes: Vec<Hash>) -> Result<(), BlobsStoreError> {
+        object_store::delete_blobs(self.inner.store(), hashes, false).await?;
+        Ok(())
+    }
+
+    /// Delete blobs from the store, even ones it protects because they were
+    /// written recently. Only for blobs known to be unreferenced.
+    pub async fn force_delete(&self, hashes: Vec<Hash>) -> Result<(), BlobsStoreError> {
+        object_store::delete_blobs(self.inner.store(), hashes, true).await?;
+        Ok(())
+    }
+
     /// Get a blob as bytes
     pub async fn get(&self, hash: &Hash) -> Result<Bytes, BlobsStoreError> {
+        self.ensure_local(hash).await?;
         let bytes = self.blobs().get_bytes(*hash).await?;
         Ok(bytes)
     }
@@ -131,12 +313,14 @@ impl BlobsStore {
         &self,
         hash: &Hash,
     ) -> Result<T, BlobsStoreError> {
+        self.ensure_local(hash).await?;
         let bytes = self.blobs().get_bytes(*hash).await?;
         Ok(T::decode(&bytes)?)
     }
 
     /// Get a blob from the store as a reader
     pub async fn get_reader(&self, hash: Hash) -> Result<Reader, BlobsStoreError> {
+        self.ensure_local(&hash).await?;
         let reader = self.blobs().reader(hash);
         Ok(reader)
     }
@@ -173,6 +357,19 @@ impl BlobsStore {
         Ok(matches!(stat, BlobStatus::Complete { .. }))
     }
 
+    /// Get the size of a complete blob, if present
+    pub async fn size(&self, hash: &Hash) -> Result<Option<u64>, BlobsStoreError> {
+        let stat = self
+            .blobs()
+            .status(*hash)
+            .await
+            .map_err(|err| BlobsStoreError::Default(anyhow!(err)))?;
+        match stat {
+            BlobStatus::Complete { size } => Ok(Some(size)),
+            _ => Ok(None),
+        }
+    }
+
     /// Download a single hash from peers
     ///
     /// This checks if the hash exists locally first, then downloads if needed.
@@ -448,6 +645,18 @@ mod tests {
         assert!(!store.stat(&fake_hash).await.unwrap());
     }
 
+    #[tokio::test]
+    async fn test_size() {
+        let (store, _temp) = setup_test_store().await;
+
+        let data = b"Test data for size";
+        let hash = store.put(data.to_vec()).await.unwrap();
+        assert_eq!(store.size(&hash).await.unwrap(), Some(data.len() as u64));
+
+        let fake_hash = iroh_blobs::Hash::from_bytes([1u8; 32]);
+        assert_eq!(store.size(&fake_hash).await.unwrap(), None);
+    }
+
     #[tokio::test]
     async fn test_large_data() {
         let (store, _temp) = setup_test_store().await;
@@ -498,4 +707,85 @@ mod tests {
         // Should return an error
         assert!(result.is_err());
     }
+
+    /// Fetcher that copies blobs from another store, counting reads
+    #[derive(Debug)]
+    struct CopyFetcher {
+        source: BlobsStore,
+        reads: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl BlobFetcher for CopyFetcher {
+        async fn fetch(&self, blobs: &BlobsStore, hash: Hash) -> Result<(), BlobsStoreError> {
+            if self.source.stat(&hash).await? {
+                let data = self.source.get(&hash).await?;
+                blobs.put(data.to_vec()).await?;
+            }
+            Ok(())
+        }
+
+        fn accessed(&self, _hash: &Hash) {
+            self.reads.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetcher_materializes_missing_blobs() {
+        let (store, _temp) = setup_test_store().await;
+        let (source, _source_temp) = setup_test_store().await;
+
+        let data = b"Fetched on demand";
+        let hash = source.put(data.to_vec()).await.unwrap();
+        assert!(!store.stat(&hash).await.unwrap());
+
+        let fetcher = Arc::new(CopyFetcher {
+            source,
+            reads: Default::default(),
+        });
+        store.set_fetcher(fetcher.clone());
+
+        // Reading a missing blob fetches it
+        assert_eq!(store.get(&hash).await.unwrap().as_ref(), data);
+        assert!(store.stat(&hash).await.unwrap());
+        assert_eq!(fetcher.reads.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        // A blob no one has stays missing
+        let fake_hash = iroh_blobs::Hash::from_bytes([99u8; 32]);
+        assert!(!store.materialize(&fake_hash).await.unwrap());
+
+        // Deleted blobs are fetched again on the next read
+        store.delete(vec![hash]).await.unwrap();
+        assert!(!store.stat(&hash).await.unwrap());
+        assert_eq!(store.get(&hash).await.unwrap().as_ref(), data);
+    }
+
+    /// Fetcher that records the bucket each fetch was for
+    #[derive(Debug, Default)]
+    struct BucketFetcher {
+        buckets: std::sync::Mutex<Vec<Option<Uuid>>>,
+    }
+
+    #[async_trait]
+    impl BlobFetcher for BucketFetcher {
+        async fn fetch(&self, blobs: &BlobsStore, _hash: Hash) -> Result<(), BlobsStoreError> {
+            self.buckets.lock().unwrap().push(blobs.bucket());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetcher_sees_bucket_of_read() {
+        let (store, _temp) = setup_test_store().await;
+        let fetcher = Arc::new(BucketFetcher::default());
+        store.set_fetcher(fetcher.clone());
+        let bucket_id = Uuid::new_v4();
+        let fake_hash = iroh_blobs::Hash::from_bytes([99u8; 32]);
+
+        assert!(!store.materialize(&fake_hash).await.unwrap());
+        let scoped = store.for_bucket(bucket_id);
+        assert!(!scoped.materialize(&fake_hash).await.unwrap());
+        assert_eq!(store.bucket(), None);
+        assert_eq!(*fetcher.buckets.lock().unwrap(), [None, Some(bucket_id)]);
+    }
 }
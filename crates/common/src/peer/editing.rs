@@ -0,0 +1,274 @@
+//! "Someone is editing" indicators
+//!
+//! Owners editing the same file at once end up with conflict copies. To make
+//! that less likely, a peer about to edit a file marks it as being edited:
+//! the mark is recorded locally and sent to every other owner announcing
+//! [`capability::EDITING`](super::protocol::capability::EDITING), and shows
+//! up next to the file when they list it.
+//!
+//! Marks are only a hint. Unlike [leases](super::leases) they never
+//! conflict: any number of owners can mark the same path, and nothing is
+//! refused because of a mark. Every mark expires after its TTL unless its
+//! editor renews it by marking the path again, so an editor that goes away
+//! without clearing its mark doesn't leave it behind. Each peer tracks expiry
+//! on its own clock from the TTL it was sent.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use uuid::Uuid;
+
+use crate::bucket_log::BucketLogProvider;
+use crate::crypto::PublicKey;
+
+use super::leases;
+use super::protocol::bidirectional::BidirectionalHandler;
+use super::protocol::capability;
+use super::protocol::messages::editing::{
+    Editing as EditingHandler, EditingAction, EditingMessage, EditingReplyStatus,
+};
+use super::Peer;
+
+/// TTL of a mark made without one
+pub const DEFAULT_EDITING_TTL: Duration = Duration::from_secs(60);
+
+/// Longest a mark lasts; longer requests are cut down to it
+pub const MAX_EDITING_TTL: Duration = Duration::from_secs(600);
+
+/// A path of a bucket marked as being edited
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditMark {
+    pub bucket_id: Uuid,
+    /// Absolute path being edited
+    pub path: PathBuf,
+    pub editor: PublicKey,
+    expires_at: Instant,
+}
+
+impl EditMark {
+    pub fn new(bucket_id: Uuid, path: PathBuf, editor: PublicKey, ttl: Duration) -> Self {
+        Self {
+            bucket_id,
+            path,
+            editor,
+            expires_at: Instant::now() + ttl.min(MAX_EDITING_TTL),
+        }
+    }
+
+    /// Time left until the mark expires
+    pub fn remaining(&self) -> Duration {
+        self.expires_at.saturating_duration_since(Instant::now())
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.remaining().is_zero()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum EditingError {
+    #[error("only owners of bucket {0} can mark paths as being edited")]
+    NotOwner(Uuid),
+    #[error("invalid path: {0}")]
+    InvalidPath(String),
+    #[error("{0}")]
+    Default(#[from] anyhow::Error),
+}
+
+/// The paths a peer knows are being edited, by itself and by other owners
+#[derive(Debug, Clone, Default)]
+pub struct EditMarks(Arc<Mutex<HashMap<Uuid, Vec<EditMark>>>>);
+
+impl EditMarks {
+    fn buckets(&self) -> std::sync::MutexGuard<'_, HashMap<Uuid, Vec<EditMark>>> {
+        self.0.lock().expect("edit marks lock poisoned")
+    }
+
+    /// Mark a path as being edited by `editor`, renewing its mark if it has
+    /// one
+    pub fn mark(&self, bucket_id: Uuid, path: &Path, editor: PublicKey, ttl: Duration) -> EditMark {
+        let mut buckets = self.buckets();
+        let marks = buckets.entry(bucket_id).or_default();
+        marks.retain(|mark| !(mark.is_expired() || (mark.editor == editor && mark.path == path)));
+        let mark = EditMark::new(bucket_id, path.to_path_buf(), editor, ttl);
+        marks.push(mark.clone());
+        mark
+    }
+
+    /// Drop the mark `editor` has on `path`. Returns false if it had none.
+    pub fn clear(&self, bucket_id: Uuid, path: &Path, editor: &PublicKey) -> bool {
+        let mut buckets = self.buckets();
+        let Some(marks) = buckets.get_mut(&bucket_id) else {
+            return false;
+        };
+        let before = marks.len();
+        marks.retain(|mark| !(mark.editor == *editor && mark.path == path));
+        let cleared = marks.len() < before;
+        if marks.is_empty() {
+            buckets.remove(&bucket_id);
+        }
+        cleared
+    }
+
+    /// Marks on a bucket that haven't expired
+    pub fn active(&self, bucket_id: &Uuid) -> Vec<EditMark> {
+        let mut buckets = self.buckets();
+        let Some(marks) = buckets.get_mut(bucket_id) else {
+            return Vec::new();
+        };
+        marks.retain(|mark| !mark.is_expired());
+        let active = marks.clone();
+        if active.is_empty() {
+            buckets.remove(bucket_id);
+        }
+        active
+    }
+}
+
+/// Check a marked path is absolute and normalize it
+pub fn parse_path(path: &str) -> Result<PathBuf, EditingError> {
+    leases::parse_prefix(path).map_err(|_| {
+        EditingError::InvalidPath(format!("{} is not an absolute path without ..", path))
+    })
+}
+
+/// Mark a path as being edited by us, here and on the other owners of a
+/// bucket
+pub(crate) async fn mark<L>(
+    peer: &Peer<L>,
+    bucket_id: Uuid,
+    path: PathBuf,
+    ttl: Duration,
+) -> Result<EditMark, EditingError>
+where
+    L: BucketLogProvider,
+    L::Error: std::error::Error + Send + Sync + 'static,
+{
+    let us = peer.secret().public();
+    let owners = leases::owners(peer, bucket_id).await?;
+    if !owners.contains(&us) {
+        return Err(EditingError::NotOwner(bucket_id));
+    }
+
+    let mark = peer.editing().mark(bucket_id, &path, us, ttl);
+    let action = EditingAction::Start {
+        ttl_ms: ttl.min(MAX_EDITING_TTL).as_millis() as u64,
+    };
+    broadcast(peer, bucket_id, &path, action, owners).await;
+    Ok(mark)
+}
+
+/// Clear our mark on a path, here and on the other owners of a bucket
+pub(crate) async fn clear<L>(
+    peer: &Peer<L>,
+    bucket_id: Uuid,
+    path: PathBuf,
+) -> Result<bool, EditingError>
+where
+    L: BucketLogProvider,
+    L::Error: std::error::Error + Send + Sync + 'static,
+{
+    let us = peer.secret().public();
+    let cleared = peer.editing().clear(bucket_id, &path, &us);
+    let owners = leases::owners(peer, bucket_id).await?;
+    broadcast(peer, bucket_id, &path, EditingAction::Stop, owners).await;
+    Ok(cleared)
+}
+
+/// Tell the other owners that understand marks about ours. Owners that are
+/// unreachable or predate marks are skipped.
+async fn broadcast<L>(
+    peer: &Peer<L>,
+    bucket_id: Uuid,
+    path: &Path,
+    action: EditingAction,
+    owners: Vec<PublicKey>,
+) where
+    L: BucketLogProvider,
+    L::Error: std::error::Error + Send + Sync + 'static,
+{
+    let us = peer.secret().public();
+    for owner in owners.into_iter().filter(|owner| *owner != us) {
+        match peer.protocol(&owner).await {
+            Ok(protocol) if protocol.supports(capability::EDITING) => {}
+            Ok(_) => continue,
+            Err(e) => {
+                tracing::debug!("Skipping owner {} for edit mark: {}", owner.to_hex(), e);
+                continue;
+            }
+        }
+        let message = EditingMessage {
+            bucket_id,
+            path: path.to_string_lossy().to_string(),
+            action: action.clone(),
+        };
+        match EditingHandler::send(peer, &owner, message).await {
+            Ok(reply) if reply.status == EditingReplyStatus::NotFound => tracing::debug!(
+                "Owner {} did not record our edit mark on bucket {}",
+                owner.to_hex(),
+                bucket_id
+            ),
+            Ok(_) => {}
+            Err(e) => {
+                tracing::debug!("Owner {} did not answer edit mark: {}", owner.to_hex(), e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::crypto::SecretKey;
+
+    fn key() -> PublicKey {
+        SecretKey::generate().public()
+    }
+
+    #[test]
+    fn test_marks_are_shared_and_renewed() {
+        let marks = EditMarks::default();
+        let bucket_id = Uuid::new_v4();
+        let (alice, bob) = (key(), key());
+        let ttl = Duration::from_secs(30);
+        let path = Path::new("/report.docx");
+
+        marks.mark(bucket_id, path, alice, ttl);
+        marks.mark(bucket_id, path, bob, ttl);
+        marks.mark(bucket_id, path, alice, ttl);
+        assert_eq!(marks.active(&bucket_id).len(), 2);
+
+        assert!(marks.clear(bucket_id, path, &alice));
+        assert!(!marks.clear(bucket_id, path, &alice));
+        let active = marks.active(&bucket_id);
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].editor, bob);
+    }
+
+    #[test]
+    fn test_expired_marks_lapse() {
+        let marks = EditMarks::default();
+        let bucket_id = Uuid::new_v4();
+        marks.mark(
+            bucket_id,
+            Path::new("/a.txt"),
+            key(),
+            Duration::from_millis(20),
+        );
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(marks.active(&bucket_id).is_empty());
+    }
+
+    #[test]
+    fn test_ttl_is_capped() {
+        let mark = EditMark::new(
+            Uuid::new_v4(),
+            PathBuf::from("/a.txt"),
+            key(),
+            Duration::from_secs(24 * 3600),
+        );
+        assert!(mark.remaining() <= MAX_EDITING_TTL);
+    }
+}
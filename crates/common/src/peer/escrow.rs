@@ -0,0 +1,528 @@
+//! Social recovery of bucket access through trusted peers
+//!
+//! An owner worried about losing its key can set up recovery for a bucket:
+//! a fresh recovery identity is added to the bucket as an owner, so every
+//! save wraps the bucket secret to it like to any other owner, and its
+//! secret key is split with [Shamir](crate::crypto::shamir) among trustees.
+//! Each trustee announcing [`capability::ESCROW`] keeps its shard, together
+//! with the bucket's peers at the time, and the key itself is forgotten.
+//!
+//! To recover, the owner starts a node with a new identity and asks the
+//! trustees to release their shards to it. A trustee records the request and
+//! only releases its shard once its user approves it, after checking out of
+//! band that the request really comes from the owner. With `threshold`
+//! shards the new node rebuilds the recovery identity, syncs the bucket from
+//! its peers and adds itself as an owner, signing that version with the
+//! recovery identity.
+//!
+//! Trustees hold one shard per bucket: setting recovery up again replaces
+//! the shards of the earlier recovery identity, which stays an owner of the
+//! bucket but can no longer be rebuilt.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::bucket_log::BucketLogProvider;
+use crate::crypto::shamir::{self, ShamirError, Shard};
+use crate::crypto::{KemPublicKey, PublicKey, SecretKey};
+use crate::mount::{Mount, MountError, PrincipalRole};
+
+use super::protocol::bidirectional::BidirectionalHandler;
+use super::protocol::capability;
+use super::protocol::messages::escrow::{
+    Escrow as EscrowHandler, EscrowAction, EscrowMessage, EscrowReplyStatus,
+};
+use super::Peer;
+
+/// Most shards a peer holds for others; deposits beyond it are refused
+pub const MAX_HELD_SHARDS: usize = 1000;
+
+/// Most release requests a peer keeps waiting for approval
+const MAX_RELEASE_REQUESTS: usize = 1000;
+
+/// A shard of a bucket's recovery identity, as held by a trustee
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EscrowShard {
+    pub bucket_id: Uuid,
+    /// Owner that set recovery up
+    pub owner: PublicKey,
+    /// Public key of the recovery identity the shards rebuild
+    pub recovery_key: PublicKey,
+    /// Shards needed to rebuild the recovery identity
+    pub threshold: u8,
+    pub shard: Shard,
+    /// Owners and mirrors of the bucket when recovery was set up, to sync it
+    /// from
+    pub peer_ids: Vec<PublicKey>,
+}
+
+/// A request to release a held shard to a new identity
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReleaseRequest {
+    pub bucket_id: Uuid,
+    /// Identity asking for the shard
+    pub requester: PublicKey,
+    /// Whether our user approved releasing the shard to the requester
+    pub approved: bool,
+}
+
+/// What a trustee does with a request for its shard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Release {
+    /// We hold no shard of the bucket
+    NotFound,
+    /// The request waits for our user's approval
+    Pending,
+    /// The request was approved
+    Released(Box<EscrowShard>),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum EscrowError {
+    #[error("only owners of bucket {0} can set up recovery")]
+    NotOwner(Uuid),
+    #[error("invalid trustees: {0}")]
+    InvalidTrustees(String),
+    #[error("{0}")]
+    Shamir(#[from] ShamirError),
+    #[error("trustee {0} did not take its shard: {1}")]
+    TrusteeFailed(String, String),
+    #[error("the released shards don't rebuild the recovery identity")]
+    BadShards,
+    #[error("mount error: {0}")]
+    Mount(Box<MountError>),
+    #[error("{0}")]
+    Default(#[from] anyhow::Error),
+}
+
+impl From<MountError> for EscrowError {
+    fn from(e: MountError) -> Self {
+        EscrowError::Mount(Box::new(e))
+    }
+}
+
+/// Recovery set up for a bucket
+#[derive(Debug, Clone)]
+pub struct EscrowSetup {
+    pub recovery_key: PublicKey,
+    pub threshold: u8,
+    pub trustees: Vec<PublicKey>,
+}
+
+/// Progress of a recovery
+#[derive(Debug, Clone)]
+pub enum Recovery {
+    /// Fewer than the threshold of trustees released their shard so far
+    Waiting {
+        /// Trustees that released their shard
+        released: Vec<PublicKey>,
+        /// Trustees waiting for their user's approval
+        pending: Vec<PublicKey>,
+        /// Shards needed, once a trustee told us
+        threshold: Option<u8>,
+    },
+    /// The recovery identity was rebuilt
+    Recovered {
+        /// Boxed, the key being much larger than the other variant
+        recovery_key: Box<SecretKey>,
+        /// Owner that set recovery up
+        owner: PublicKey,
+        /// Peers of the bucket to sync it from
+        peer_ids: Vec<PublicKey>,
+    },
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct EscrowState {
+    held: Vec<EscrowShard>,
+    requests: Vec<ReleaseRequest>,
+}
+
+/// Shards a peer holds for other owners and the requests to release them,
+/// kept in a file if the peer was given one
+#[derive(Debug, Clone, Default)]
+pub struct Escrow {
+    state: Arc<Mutex<EscrowState>>,
+    path: Option<PathBuf>,
+}
+
+impl Escrow {
+    /// Escrow kept in `path`, loading what it holds if the file exists
+    pub fn load(path: PathBuf) -> Result<Self, EscrowError> {
+        let state = match std::fs::read(&path) {
+            Ok(bytes) => serde_ipld_dagcbor::from_slice(&bytes)
+                .map_err(|e| anyhow::anyhow!("invalid escrow file: {}", e))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => EscrowState::default(),
+            Err(e) => return Err(anyhow::anyhow!("failed to read escrow file: {}", e).into()),
+        };
+        Ok(Self {
+            state: Arc::new(Mutex::new(state)),
+            path: Some(path),
+        })
+    }
+
+    fn update<T>(&self, f: impl FnOnce(&mut EscrowState) -> T) -> T {
+        let mut state = self.state.lock().expect("escrow lock poisoned");
+        let result = f(&mut state);
+        if let Some(path) = &self.path {
+            if let Err(e) = persist(path, &state) {
+                tracing::error!("Failed to save escrow to {}: {}", path.display(), e);
+            }
+        }
+        result
+    }
+
+    /// Shards we hold for other owners
+    pub fn held(&self) -> Vec<EscrowShard> {
+        self.state
+            .lock()
+            .expect("escrow lock poisoned")
+            .held
+            .clone()
+    }
+
+    /// Requests to release the shards we hold
+    pub fn requests(&self) -> Vec<ReleaseRequest> {
+        self.state
+            .lock()
+            .expect("escrow lock poisoned")
+            .requests
+            .clone()
+    }
+
+    /// Keep a shard, replacing the one we held for its bucket. Returns false
+    /// if we hold too many shards already.
+    pub fn hold(&self, shard: EscrowShard) -> bool {
+        self.update(|state| {
+            state.held.retain(|held| held.bucket_id != shard.bucket_id);
+            state
+                .requests
+                .retain(|request| request.bucket_id != shard.bucket_id);
+            if state.held.len() >= MAX_HELD_SHARDS {
+                return false;
+            }
+            state.held.push(shard);
+            true
+        })
+    }
+
+    /// Stop holding the shard of a bucket. Returns false if we held none.
+    pub fn discard(&self, bucket_id: &Uuid) -> bool {
+        self.update(|state| {
+            let before = state.held.len();
+            state.held.retain(|held| held.bucket_id != *bucket_id);
+            state
+                .requests
+                .retain(|request| request.bucket_id != *bucket_id);
+            state.held.len() < before
+        })
+    }
+
+    /// Approve releasing the shard of a bucket to `requester`. Returns false
+    /// if it didn't ask for it.
+    pub fn approve(&self, bucket_id: &Uuid, requester: &PublicKey) -> bool {
+        self.update(|state| {
+            let request = state
+                .requests
+                .iter_mut()
+                .find(|request| request.bucket_id == *bucket_id && request.requester == *requester);
+            match request {
+                Some(request) => {
+                    request.approved = true;
+                    true
+                }
+                None => false,
+            }
+        })
+    }
+
+    /// Answer `requester` asking for the shard of a bucket, recording the
+    /// request if it is new
+    pub(crate) fn request_release(&self, bucket_id: &Uuid, requester: &PublicKey) -> Release {
+        self.update(|state| {
+            let Some(shard) = state.held.iter().find(|held| held.bucket_id == *bucket_id) else {
+                return Release::NotFound;
+            };
+            let existing = state
+                .requests
+                .iter()
+                .find(|request| request.bucket_id == *bucket_id && request.requester == *requester);
+            match existing {
+                Some(request) if request.approved => Release::Released(Box::new(shard.clone())),
+                Some(_) => Release::Pending,
+                None if state.requests.len() >= MAX_RELEASE_REQUESTS => Release::Pending,
+                None => {
+                    state.requests.push(ReleaseRequest {
+                        bucket_id: *bucket_id,
+                        requester: *requester,
+                        approved: false,
+                    });
+                    Release::Pending
+                }
+            }
+        })
+    }
+}
+
+/// Write the escrow state readable only by the current user
+fn persist(path: &std::path::Path, state: &EscrowState) -> anyhow::Result<()> {
+    use std::io::Write;
+
+    let bytes = serde_ipld_dagcbor::to_vec(state)?;
+    let tmp = path.with_extension("tmp");
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(&tmp)?.write_all(&bytes)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Set up recovery of a mount's bucket with `threshold` of `trustees`,
+/// adding the recovery identity as an owner. Takes effect on the next save.
+pub(crate) async fn set_up<L>(
+    peer: &Peer<L>,
+    mount: &mut Mount,
+    trustees: Vec<PublicKey>,
+    threshold: u8,
+) -> Result<EscrowSetup, EscrowError>
+where
+    L: BucketLogProvider,
+    L::Error: std::error::Error + Send + Sync + 'static,
+{
+    let us = peer.secret().public();
+    let manifest = mount.inner().await.manifest().clone();
+    let bucket_id = *manifest.id();
+    let is_owner = manifest
+        .get_share(&us)
+        .is_some_and(|share| *share.role() == PrincipalRole::Owner);
+    if !is_owner {
+        return Err(EscrowError::NotOwner(bucket_id));
+    }
+
+    let unique: HashSet<_> = trustees.iter().collect();
+    if trustees.is_empty() || unique.len() != trustees.len() || unique.contains(&us) {
+        return Err(EscrowError::InvalidTrustees(
+            "list other peers, each once".to_string(),
+        ));
+    }
+    let count = u8::try_from(trustees.len())
+        .map_err(|_| EscrowError::InvalidTrustees("at most 255 trustees".to_string()))?;
+
+    let recovery = SecretKey::generate();
+    let shards = shamir::split(&recovery.to_bytes(), threshold, count)?;
+    let peer_ids = manifest.get_peer_ids();
+
+    for (trustee, shard) in trustees.iter().zip(shards) {
+        let failed = |reason: String| EscrowError::TrusteeFailed(trustee.to_hex(), reason);
+        let protocol = peer
+            .protocol(trustee)
+            .await
+            .map_err(|e| failed(e.to_string()))?;
+        if !protocol.supports(capability::ESCROW) {
+            return Err(failed("it doesn't hold shards".to_string()));
+        }
+        let message = EscrowMessage {
+            bucket_id,
+            action: EscrowAction::Deposit(EscrowShard {
+                bucket_id,
+                owner: us,
+                recovery_key: recovery.public(),
+                threshold,
+                shard,
+                peer_ids: peer_ids.clone(),
+            }),
+        };
+        match EscrowHandler::send(peer, trustee, message).await {
+            Ok(reply) if reply.status == EscrowReplyStatus::Held => {}
+            Ok(reply) => return Err(failed(format!("{:?}", reply.status))),
+            Err(e) => return Err(failed(e.to_string())),
+        }
+    }
+
+    mount.add_owner(recovery.public()).await?;
+    // Keep owner shares hybrid if they are: no node answers for the
+    // recovery identity (see `share_keys`)
+    let hybrid = manifest
+        .get_share(&us)
+        .is_some_and(|share| share.kem_key().is_some());
+    if hybrid {
+        mount
+            .set_kem_key(&recovery.public(), Some(KemPublicKey::of(&recovery)))
+            .await?;
+    }
+    Ok(EscrowSetup {
+        recovery_key: recovery.public(),
+        threshold,
+        trustees,
+    })
+}
+
+/// Ask `trustees` for their shards of a bucket's recovery identity, and
+/// rebuild it once enough of them released theirs
+///
+/// Unreachable trustees are skipped. Asking again is how a recovery makes
+/// progress: trustees release their shard once their user approves.
+pub(crate) async fn recover<L>(
+    peer: &Peer<L>,
+    bucket_id: Uuid,
+    trustees: &[PublicKey],
+) -> Result<Recovery, EscrowError>
+where
+    L: BucketLogProvider,
+    L::Error: std::error::Error + Send + Sync + 'static,
+{
+    let mut released: Vec<(PublicKey, EscrowShard)> = Vec::new();
+    let mut pending = Vec::new();
+    for trustee in trustees {
+        match peer.protocol(trustee).await {
+            Ok(protocol) if protocol.supports(capability::ESCROW) => {}
+            Ok(_) => continue,
+            Err(e) => {
+                tracing::debug!("Skipping trustee {}: {}", trustee.to_hex(), e);
+                continue;
+            }
+        }
+        let message = EscrowMessage {
+            bucket_id,
+            action: EscrowAction::Release,
+        };
+        match EscrowHandler::send(peer, trustee, message).await {
+            Ok(reply) => match reply.status {
+                EscrowReplyStatus::Released(shard) if shard.bucket_id == bucket_id => {
+                    released.push((*trustee, *shard))
+                }
+                EscrowReplyStatus::Pending => pending.push(*trustee),
+                status => tracing::debug!(
+                    "Trustee {} has no shard of bucket {}: {:?}",
+                    trustee.to_hex(),
+                    bucket_id,
+                    status
+                ),
+            },
+            Err(e) => tracing::debug!("Trustee {} did not answer: {}", trustee.to_hex(), e),
+        }
+    }
+
+    // Shards of an earlier setup may linger on trustees that missed the
+    // latest one; rebuild the identity most trustees released shards of
+    let Some(recovery_key) = released
+        .iter()
+        .map(|(_, shard)| shard.recovery_key)
+        .max_by_key(|key| {
+            released
+                .iter()
+                .filter(|(_, shard)| shard.recovery_key == *key)
+                .count()
+        })
+    else {
+        return Ok(Recovery::Waiting {
+            released: Vec::new(),
+            pending,
+            threshold: None,
+        });
+    };
+    let shards: Vec<EscrowShard> = released
+        .iter()
+        .filter(|(_, shard)| shard.recovery_key == recovery_key)
+        .map(|(_, shard)| shard.clone())
+        .collect();
+    let threshold = shards[0].threshold;
+    if shards.len() < threshold as usize {
+        return Ok(Recovery::Waiting {
+            released: released.into_iter().map(|(trustee, _)| trustee).collect(),
+            pending,
+            threshold: Some(threshold),
+        });
+    }
+
+    let parts: Vec<Shard> = shards.iter().map(|shard| shard.shard.clone()).collect();
+    let bytes: [u8; 32] = shamir::combine(&parts)?
+        .try_into()
+        .map_err(|_| EscrowError::BadShards)?;
+    let key = SecretKey::from(bytes);
+    if key.public() != recovery_key {
+        return Err(EscrowError::BadShards);
+    }
+    Ok(Recovery::Recovered {
+        recovery_key: Box::new(key),
+        owner: shards[0].owner,
+        peer_ids: shards[0].peer_ids.clone(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn shard(bucket_id: Uuid) -> EscrowShard {
+        EscrowShard {
+            bucket_id,
+            owner: SecretKey::generate().public(),
+            recovery_key: SecretKey::generate().public(),
+            threshold: 2,
+            shard: Shard {
+                index: 1,
+                data: vec![1, 2, 3],
+            },
+            peer_ids: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_shards_are_released_once_approved() {
+        let escrow = Escrow::default();
+        let bucket_id = Uuid::new_v4();
+        let requester = SecretKey::generate().public();
+        assert_eq!(
+            escrow.request_release(&bucket_id, &requester),
+            Release::NotFound
+        );
+
+        let held = shard(bucket_id);
+        assert!(escrow.hold(held.clone()));
+        assert_eq!(
+            escrow.request_release(&bucket_id, &requester),
+            Release::Pending
+        );
+        assert_eq!(escrow.requests().len(), 1);
+        assert!(!escrow.approve(&bucket_id, &SecretKey::generate().public()));
+        assert!(escrow.approve(&bucket_id, &requester));
+        assert_eq!(
+            escrow.request_release(&bucket_id, &requester),
+            Release::Released(Box::new(held))
+        );
+
+        // A new deposit drops the requests for the old shard
+        assert!(escrow.hold(shard(bucket_id)));
+        assert_eq!(escrow.held().len(), 1);
+        assert!(escrow.requests().is_empty());
+        assert!(escrow.discard(&bucket_id));
+        assert!(!escrow.discard(&bucket_id));
+    }
+
+    #[test]
+    fn test_escrow_survives_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("escrow.cbor");
+        let bucket_id = Uuid::new_v4();
+        let requester = SecretKey::generate().public();
+
+        let escrow = Escrow::load(path.clone()).unwrap();
+        escrow.hold(shard(bucket_id));
+        escrow.request_release(&bucket_id, &requester);
+        escrow.approve(&bucket_id, &requester);
+
+        let reloaded = Escrow::load(path).unwrap();
+        assert_eq!(reloaded.held(), escrow.held());
+        assert_eq!(reloaded.requests(), escrow.requests());
+    }
+}
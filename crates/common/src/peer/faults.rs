@@ -0,0 +1,199 @@
+//! Fault injection for sync tests
+//!
+//! Every peer holds a [`Faults`] handle, empty unless a test configures it.
+//! It is consulted wherever the peer deals with others: sending a message,
+//! accepting a connection, downloading a blob and running a sync job. A test
+//! can partition peers, slow them down, lose messages and make downloads or
+//! jobs fail, then heal the network and check that sync recovers.
+//!
+//! Random faults are drawn from a generator seeded with [`Faults::seed`], so
+//! a test that injects the same faults in the same order sees the same ones.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::crypto::PublicKey;
+
+use super::sync::SyncJob;
+
+/// Shared handle to the faults injected into a peer
+#[derive(Debug, Clone, Default)]
+pub struct Faults(Arc<Mutex<FaultState>>);
+
+#[derive(Debug, Default)]
+struct FaultState {
+    partitioned: HashSet<PublicKey>,
+    latency: HashMap<PublicKey, Duration>,
+    /// Chance of losing each message, by message name
+    message_loss: HashMap<&'static str, f64>,
+    /// Number of blob downloads still to fail
+    failing_downloads: usize,
+    /// Number of jobs still to fail, by job name
+    failing_jobs: HashMap<&'static str, usize>,
+    rng: u64,
+    injected: u64,
+}
+
+impl Faults {
+    fn state(&self) -> std::sync::MutexGuard<'_, FaultState> {
+        self.0.lock().expect("faults lock poisoned")
+    }
+
+    /// Reseed the generator random faults are drawn from
+    pub fn seed(&self, seed: u64) {
+        self.state().rng = seed;
+    }
+
+    /// Cut the peer off from `peer`: messages and downloads to it fail as if
+    /// it were offline and its connections are turned away. Blobs it fetches
+    /// from us are still served, so partition both sides for a full split.
+    pub fn partition(&self, peer: PublicKey) {
+        self.state().partitioned.insert(peer);
+    }
+
+    /// Undo [`Faults::partition`]
+    pub fn heal(&self, peer: &PublicKey) {
+        self.state().partitioned.remove(peer);
+    }
+
+    /// Delay messages and downloads to `peer` by `latency`
+    pub fn set_latency(&self, peer: PublicKey, latency: Duration) {
+        self.state().latency.insert(peer, latency);
+    }
+
+    /// Lose messages named `message` (e.g. "Ping") with the given chance
+    /// between 0 and 1. Lost messages time out for the sender and never
+    /// reach the recipient.
+    pub fn lose_messages(&self, message: &'static str, chance: f64) {
+        self.state().message_loss.insert(message, chance);
+    }
+
+    /// Fail the next `count` blob downloads, as if the transfer broke off
+    pub fn fail_downloads(&self, count: usize) {
+        self.state().failing_downloads = count;
+    }
+
+    /// Fail the next `count` sync jobs named `job` ("sync_bucket",
+    /// "download_pins" or "ping_peer"), as if the worker crashed running them
+    pub fn fail_jobs(&self, job: &'static str, count: usize) {
+        self.state().failing_jobs.insert(job, count);
+    }
+
+    /// Remove every fault, keeping the generator's state
+    pub fn clear(&self) {
+        let mut state = self.state();
+        let (rng, injected) = (state.rng, state.injected);
+        *state = FaultState {
+            rng,
+            injected,
+            ..Default::default()
+        };
+    }
+
+    /// Number of faults injected so far
+    pub fn injected(&self) -> u64 {
+        self.state().injected
+    }
+
+    /// Whether `peer` is partitioned away
+    pub(crate) fn is_partitioned(&self, peer: &PublicKey) -> bool {
+        let mut state = self.state();
+        let partitioned = state.partitioned.contains(peer);
+        state.injected += partitioned as u64;
+        partitioned
+    }
+
+    /// Wait out the latency to `peer`
+    pub(crate) async fn delay(&self, peer: &PublicKey) {
+        let latency = self.state().latency.get(peer).copied();
+        if let Some(latency) = latency {
+            tokio::time::sleep(latency).await;
+        }
+    }
+
+    /// Whether to lose a message named `message`
+    pub(crate) fn loses(&self, message: &str) -> bool {
+        let mut state = self.state();
+        let Some(&chance) = state.message_loss.get(message) else {
+            return false;
+        };
+        let lost = state.next_f64() < chance;
+        state.injected += lost as u64;
+        lost
+    }
+
+    /// Whether to fail the next blob download
+    pub(crate) fn fails_download(&self) -> bool {
+        let mut state = self.state();
+        if state.failing_downloads == 0 {
+            return false;
+        }
+        state.failing_downloads -= 1;
+        state.injected += 1;
+        true
+    }
+
+    /// Whether to fail running `job`
+    pub(crate) fn fails_job(&self, job: &SyncJob) -> bool {
+        let mut state = self.state();
+        match state.failing_jobs.get_mut(job.name()) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                state.injected += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FaultState {
+    /// A uniform draw in [0, 1) from the seeded generator (splitmix64)
+    fn next_f64(&mut self) -> f64 {
+        self.rng = self.rng.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.rng;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::crypto::SecretKey;
+
+    #[test]
+    fn test_message_loss_is_seeded() {
+        let draws = |seed| {
+            let faults = Faults::default();
+            faults.seed(seed);
+            faults.lose_messages("Ping", 0.5);
+            (0..64).map(|_| faults.loses("Ping")).collect::<Vec<_>>()
+        };
+        assert_eq!(draws(7), draws(7));
+        assert_ne!(draws(7), draws(8));
+        assert!(draws(7).contains(&true) && draws(7).contains(&false));
+    }
+
+    #[test]
+    fn test_partition_and_counted_faults() {
+        let faults = Faults::default();
+        let peer = SecretKey::generate().public();
+        assert!(!faults.is_partitioned(&peer));
+
+        faults.partition(peer);
+        assert!(faults.is_partitioned(&peer));
+        faults.heal(&peer);
+        assert!(!faults.is_partitioned(&peer));
+
+        faults.fail_downloads(2);
+        assert!(faults.fails_download());
+        assert!(faults.fails_download());
+        assert!(!faults.fails_download());
+        assert!(!faults.loses("Ping"));
+        assert_eq!(faults.injected(), 3);
+    }
+}
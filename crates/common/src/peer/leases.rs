@@ -0,0 +1,425 @@
+//! Advisory write leases
+//!
+//! Owners writing to the same bucket fork its history when they commit
+//! concurrently. Cooperating writers avoid that by taking a time-limited
+//! lease on the bucket, or on a path prefix of it, before writing. Acquiring
+//! a lease records it locally and asks every other owner announcing
+//! [`capability::LEASES`](super::protocol::capability::LEASES) to record it
+//! too; if any of them already granted an overlapping lease to someone else,
+//! the acquirer backs out everywhere and is told who holds it.
+//!
+//! Leases are purely advisory: writes are never refused because of one, and
+//! owners that are offline or predate leases are skipped. Every lease
+//! expires after its TTL unless its holder renews it by acquiring it again,
+//! so a holder that disappears can't block the others for long. Each peer
+//! tracks expiry on its own clock from the TTL it was sent.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::anyhow;
+use uuid::Uuid;
+
+use crate::bucket_log::BucketLogProvider;
+use crate::crypto::PublicKey;
+use crate::mount::{Manifest, PrincipalRole};
+
+use super::protocol::bidirectional::BidirectionalHandler;
+use super::protocol::capability;
+use super::protocol::messages::lease::{
+    Lease as LeaseHandler, LeaseAction, LeaseMessage, LeaseReplyStatus,
+};
+use super::Peer;
+
+/// TTL of a lease acquired without one
+pub const DEFAULT_LEASE_TTL: Duration = Duration::from_secs(30);
+
+/// Longest a lease is granted for; longer requests are cut down to it
+pub const MAX_LEASE_TTL: Duration = Duration::from_secs(600);
+
+/// A write lease on a path prefix of a bucket
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lease {
+    pub bucket_id: Uuid,
+    /// Absolute path the lease covers, with everything below it. `/` covers
+    /// the whole bucket.
+    pub prefix: PathBuf,
+    pub holder: PublicKey,
+    expires_at: Instant,
+}
+
+impl Lease {
+    pub fn new(bucket_id: Uuid, prefix: PathBuf, holder: PublicKey, ttl: Duration) -> Self {
+        Self {
+            bucket_id,
+            prefix,
+            holder,
+            expires_at: Instant::now() + ttl.min(MAX_LEASE_TTL),
+        }
+    }
+
+    /// Time left until the lease expires
+    pub fn remaining(&self) -> Duration {
+        self.expires_at.saturating_duration_since(Instant::now())
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.remaining().is_zero()
+    }
+
+    /// Whether the lease covers `prefix` or anything below it
+    pub fn overlaps(&self, prefix: &Path) -> bool {
+        self.prefix.starts_with(prefix) || prefix.starts_with(&self.prefix)
+    }
+}
+
+/// Why a lease could not be acquired
+#[derive(Debug, thiserror::Error)]
+pub enum LeaseError {
+    #[error(
+        "{} of bucket {} is leased to {} for another {:?}",
+        .0.prefix.display(),
+        .0.bucket_id,
+        .0.holder.to_hex(),
+        .0.remaining()
+    )]
+    Held(Lease),
+    #[error("only owners of bucket {0} can lease it")]
+    NotOwner(Uuid),
+    #[error("invalid lease prefix: {0}")]
+    InvalidPrefix(String),
+    #[error("{0}")]
+    Default(#[from] anyhow::Error),
+}
+
+/// The leases a peer knows of, its own and those granted to other owners
+#[derive(Debug, Clone, Default)]
+pub struct Leases(Arc<Mutex<HashMap<Uuid, Vec<Lease>>>>);
+
+impl Leases {
+    fn buckets(&self) -> std::sync::MutexGuard<'_, HashMap<Uuid, Vec<Lease>>> {
+        self.0.lock().expect("leases lock poisoned")
+    }
+
+    /// Grant `holder` a lease, renewing any it holds on the same prefix.
+    /// Fails with the lease another holder has on an overlapping prefix.
+    pub fn grant(
+        &self,
+        bucket_id: Uuid,
+        prefix: &Path,
+        holder: PublicKey,
+        ttl: Duration,
+    ) -> Result<Lease, Lease> {
+        let mut buckets = self.buckets();
+        let leases = buckets.entry(bucket_id).or_default();
+        leases.retain(|lease| !lease.is_expired());
+        if let Some(held) = leases
+            .iter()
+            .find(|lease| lease.holder != holder && lease.overlaps(prefix))
+        {
+            return Err(held.clone());
+        }
+        leases.retain(|lease| !(lease.holder == holder && lease.prefix == prefix));
+        let lease = Lease::new(bucket_id, prefix.to_path_buf(), holder, ttl);
+        leases.push(lease.clone());
+        Ok(lease)
+    }
+
+    /// Drop the lease `holder` has on `prefix`. Returns false if it had none.
+    pub fn release(&self, bucket_id: Uuid, prefix: &Path, holder: &PublicKey) -> bool {
+        let mut buckets = self.buckets();
+        let Some(leases) = buckets.get_mut(&bucket_id) else {
+            return false;
+        };
+        let before = leases.len();
+        leases.retain(|lease| !(lease.holder == *holder && lease.prefix == prefix));
+        let released = leases.len() < before;
+        if leases.is_empty() {
+            buckets.remove(&bucket_id);
+        }
+        released
+    }
+
+    /// Leases on a bucket that haven't expired
+    pub fn active(&self, bucket_id: &Uuid) -> Vec<Lease> {
+        let mut buckets = self.buckets();
+        let Some(leases) = buckets.get_mut(bucket_id) else {
+            return Vec::new();
+        };
+        leases.retain(|lease| !lease.is_expired());
+        let active = leases.clone();
+        if active.is_empty() {
+            buckets.remove(bucket_id);
+        }
+        active
+    }
+}
+
+/// Check a lease prefix is absolute and normalize it
+pub fn parse_prefix(prefix: &str) -> Result<PathBuf, LeaseError> {
+    let path = Path::new(prefix);
+    if !path.is_absolute() {
+        return Err(LeaseError::InvalidPrefix(format!(
+            "{} is not absolute",
+            prefix
+        )));
+    }
+    if path
+        .components()
+        .any(|c| matches!(c, std::path::Component::ParentDir))
+    {
+        return Err(LeaseError::InvalidPrefix(format!(
+            "{} may not contain ..",
+            prefix
+        )));
+    }
+    Ok(path.components().collect())
+}
+
+/// Whether `peer_id` is an owner in the current manifest of a bucket. Fails
+/// if we don't have the bucket.
+pub(crate) async fn is_owner<L: BucketLogProvider>(
+    peer: &Peer<L>,
+    bucket_id: Uuid,
+    peer_id: &PublicKey,
+) -> anyhow::Result<bool> {
+    Ok(owners(peer, bucket_id).await?.contains(peer_id))
+}
+
+/// Owners in the current manifest of a bucket. Fails if we don't have the bucket.
+pub(crate) async fn owners<L: BucketLogProvider>(
+    peer: &Peer<L>,
+    bucket_id: Uuid,
+) -> anyhow::Result<Vec<PublicKey>> {
+    let (head, _) = peer
+        .logs()
+        .head(bucket_id, None)
+        .await
+        .map_err(|e| anyhow!("Failed to get head of bucket {}: {}", bucket_id, e))?;
+    let manifest: Manifest = peer.blobs().get_cbor(&head.hash()).await?;
+    Ok(manifest
+        .get_shares_by_role(PrincipalRole::Owner)
+        .into_iter()
+        .map(|share| share.principal().identity)
+        .collect())
+}
+
+/// Acquire, or renew, a lease for ourselves on the other owners of a bucket
+pub(crate) async fn acquire<L>(
+    peer: &Peer<L>,
+    bucket_id: Uuid,
+    prefix: PathBuf,
+    ttl: Duration,
+) -> Result<Lease, LeaseError>
+where
+    L: BucketLogProvider,
+    L::Error: std::error::Error + Send + Sync + 'static,
+{
+    let us = peer.secret().public();
+    let owners = owners(peer, bucket_id).await?;
+    if !owners.contains(&us) {
+        return Err(LeaseError::NotOwner(bucket_id));
+    }
+
+    let lease = peer
+        .leases()
+        .grant(bucket_id, &prefix, us, ttl)
+        .map_err(LeaseError::Held)?;
+
+    let mut granted = Vec::new();
+    for owner in owners.into_iter().filter(|owner| *owner != us) {
+        let message = LeaseMessage {
+            bucket_id,
+            prefix: prefix.to_string_lossy().to_string(),
+            action: LeaseAction::Acquire {
+                ttl_ms: ttl.min(MAX_LEASE_TTL).as_millis() as u64,
+            },
+        };
+        match send(peer, &owner, message).await {
+            Some(LeaseReplyStatus::Granted) => granted.push(owner),
+            Some(LeaseReplyStatus::Held {
+                prefix: held_prefix,
+                holder,
+                remaining_ms,
+            }) => {
+                tracing::info!(
+                    "Owner {} has {} of bucket {} leased to {}, backing out",
+                    owner.to_hex(),
+                    held_prefix,
+                    bucket_id,
+                    holder.to_hex()
+                );
+                release_on(peer, bucket_id, &prefix, &granted).await;
+                peer.leases().release(bucket_id, &prefix, &us);
+                return Err(LeaseError::Held(Lease::new(
+                    bucket_id,
+                    PathBuf::from(held_prefix),
+                    holder,
+                    Duration::from_millis(remaining_ms),
+                )));
+            }
+            // Owners that can't vouch for the lease don't hold it up
+            Some(status) => tracing::debug!(
+                "Owner {} did not record our lease on bucket {}: {:?}",
+                owner.to_hex(),
+                bucket_id,
+                status
+            ),
+            None => {}
+        }
+    }
+    Ok(lease)
+}
+
+/// Release a lease we hold, locally and on the other owners of a bucket
+pub(crate) async fn release<L>(
+    peer: &Peer<L>,
+    bucket_id: Uuid,
+    prefix: PathBuf,
+) -> Result<bool, LeaseError>
+where
+    L: BucketLogProvider,
+    L::Error: std::error::Error + Send + Sync + 'static,
+{
+    let us = peer.secret().public();
+    let released = peer.leases().release(bucket_id, &prefix, &us);
+    let others: Vec<PublicKey> = owners(peer, bucket_id)
+        .await?
+        .into_iter()
+        .filter(|owner| *owner != us)
+        .collect();
+    release_on(peer, bucket_id, &prefix, &others).await;
+    Ok(released)
+}
+
+async fn release_on<L>(peer: &Peer<L>, bucket_id: Uuid, prefix: &Path, owners: &[PublicKey])
+where
+    L: BucketLogProvider,
+    L::Error: std::error::Error + Send + Sync + 'static,
+{
+    for owner in owners {
+        let message = LeaseMessage {
+            bucket_id,
+            prefix: prefix.to_string_lossy().to_string(),
+            action: LeaseAction::Release,
+        };
+        send(peer, owner, message).await;
+    }
+}
+
+/// Send a lease message to an owner that understands it. Owners that are
+/// unreachable or predate leases give no answer.
+async fn send<L>(
+    peer: &Peer<L>,
+    owner: &PublicKey,
+    message: LeaseMessage,
+) -> Option<LeaseReplyStatus>
+where
+    L: BucketLogProvider,
+    L::Error: std::error::Error + Send + Sync + 'static,
+{
+    match peer.protocol(owner).await {
+        Ok(protocol) if protocol.supports(capability::LEASES) => {}
+        Ok(_) => return None,
+        Err(e) => {
+            tracing::debug!("Skipping owner {} for lease: {}", owner.to_hex(), e);
+            return None;
+        }
+    }
+    match LeaseHandler::send(peer, owner, message).await {
+        Ok(reply) => Some(reply.status),
+        Err(e) => {
+            tracing::debug!("Owner {} did not answer lease: {}", owner.to_hex(), e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::crypto::SecretKey;
+
+    fn key() -> PublicKey {
+        SecretKey::generate().public()
+    }
+
+    #[test]
+    fn test_overlapping_leases_conflict() {
+        let leases = Leases::default();
+        let bucket_id = Uuid::new_v4();
+        let (alice, bob) = (key(), key());
+        let ttl = Duration::from_secs(30);
+
+        leases
+            .grant(bucket_id, Path::new("/docs"), alice, ttl)
+            .unwrap();
+        // Below, above and on the same prefix
+        for prefix in ["/docs/a.txt", "/", "/docs"] {
+            let held = leases
+                .grant(bucket_id, Path::new(prefix), bob, ttl)
+                .unwrap_err();
+            assert_eq!(held.holder, alice);
+        }
+        // Disjoint prefixes, other buckets and renewals are fine
+        leases
+            .grant(bucket_id, Path::new("/docsets"), bob, ttl)
+            .unwrap();
+        leases
+            .grant(Uuid::new_v4(), Path::new("/docs"), bob, ttl)
+            .unwrap();
+        leases
+            .grant(bucket_id, Path::new("/docs"), alice, ttl)
+            .unwrap();
+        assert_eq!(leases.active(&bucket_id).len(), 2);
+
+        assert!(leases.release(bucket_id, Path::new("/docs"), &alice));
+        assert!(!leases.release(bucket_id, Path::new("/docs"), &alice));
+        leases
+            .grant(bucket_id, Path::new("/docs/a.txt"), bob, ttl)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_expired_leases_lapse() {
+        let leases = Leases::default();
+        let bucket_id = Uuid::new_v4();
+        let (alice, bob) = (key(), key());
+
+        leases
+            .grant(bucket_id, Path::new("/"), alice, Duration::from_millis(20))
+            .unwrap();
+        assert!(leases
+            .grant(bucket_id, Path::new("/a"), bob, DEFAULT_LEASE_TTL)
+            .is_err());
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(leases
+            .grant(bucket_id, Path::new("/a"), bob, DEFAULT_LEASE_TTL)
+            .is_ok());
+        let active = leases.active(&bucket_id);
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].holder, bob);
+    }
+
+    #[test]
+    fn test_ttl_is_capped() {
+        let lease = Lease::new(
+            Uuid::new_v4(),
+            PathBuf::from("/"),
+            key(),
+            Duration::from_secs(24 * 3600),
+        );
+        assert!(lease.remaining() <= MAX_LEASE_TTL);
+    }
+
+    #[test]
+    fn test_parse_prefix() {
+        assert_eq!(parse_prefix("/docs/").unwrap(), PathBuf::from("/docs"));
+        assert_eq!(parse_prefix("/").unwrap(), PathBuf::from("/"));
+        assert!(parse_prefix("docs").is_err());
+        assert!(parse_prefix("/docs/../etc").is_err());
+    }
+}
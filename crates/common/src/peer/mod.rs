@@ -2,24 +2,38 @@ use iroh::protocol::Router;
 use tokio::sync::watch::Receiver as WatchReceiver;
 
 mod blobs_store;
+pub mod editing;
+pub mod escrow;
+pub mod faults;
+pub mod leases;
 mod peer_builder;
 mod peer_inner;
 mod protocol;
+pub mod share_keys;
 pub mod sync;
 
-pub use blobs_store::{BlobsStore, BlobsStoreError};
-pub use protocol::{PingReplyStatus, ALPN};
+pub use blobs_store::{BlobFetcher, BlobsStore, BlobsStoreError};
+pub use editing::{EditMark, EditMarks, EditingError};
+pub use escrow::{Escrow, EscrowError, EscrowSetup, EscrowShard, Recovery, ReleaseRequest};
+pub use faults::Faults;
+pub use leases::{Lease, LeaseError, Leases};
+pub use protocol::{
+    capability, ConnectionLimits, PingReplyStatus, ProtocolError, ProtocolInfo, ProtocolMetrics,
+    ProtocolTimeouts, ALPN, PROTOCOL_VERSION,
+};
+pub use protocol::{limits, timeouts};
 pub use sync::{SyncJob, SyncProvider, SyncTarget};
 
 pub use iroh::NodeAddr;
 
 pub use peer_builder::PeerBuilder;
-pub use peer_inner::Peer;
+pub use peer_inner::{Peer, Resolved, MAX_REFERENCE_DEPTH};
 
 /// Spawn the peer with protocol router
 ///
 /// This starts the iroh protocol router for handling incoming connections.
-/// The peer's sync provider is responsible for managing its own background workers.
+/// The peer's sync provider is responsible for managing its own background workers;
+/// on shutdown, requests and downloads in flight are cancelled (see [`Peer::cancelled`]).
 ///
 /// # Arguments
 ///
@@ -51,6 +65,9 @@ where
     let _ = shutdown_rx.changed().await;
     tracing::info!(peer_id = %node_id, "Shutdown signal received, stopping peer");
 
+    // Cancel requests and downloads still in flight
+    peer.cancel();
+
     // Shutdown the router (this closes the endpoint and stops accepting connections)
     router
         .shutdown()
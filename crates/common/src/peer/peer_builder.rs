@@ -9,7 +9,10 @@ pub use super::blobs_store::BlobsStore;
 use crate::bucket_log::BucketLogProvider;
 use crate::crypto::SecretKey;
 
+use super::escrow::Escrow;
+use super::faults::Faults;
 use super::peer_inner::Peer;
+use super::protocol::{ConnectionLimits, ProtocolTimeouts};
 use super::sync::SyncProvider;
 
 /// Marker type for builder state: needs sync provider to be configured
@@ -33,6 +36,14 @@ pub struct PeerBuilder<L: BucketLogProvider, State = ReadyToBuild> {
     log_provider: Option<L>,
     /// Sync provider implementation (trait object for flexibility)
     sync_provider: Option<Arc<dyn SyncProvider<L>>>,
+    /// Timeouts of requests to peers, the defaults if not set
+    protocol_timeouts: Option<ProtocolTimeouts>,
+    /// Limits on requests handled at once, the defaults if not set
+    connection_limits: Option<ConnectionLimits>,
+    /// Faults to inject, none if not set
+    faults: Option<Faults>,
+    /// Shards held for other owners, kept in memory if not set
+    escrow: Option<Escrow>,
     /// State marker (zero-sized type for compile-time guarantees)
     _state: std::marker::PhantomData<State>,
 }
@@ -58,6 +69,28 @@ impl<L: BucketLogProvider, State> PeerBuilder<L, State> {
         self.log_provider = Some(log_provider);
         self
     }
+
+    pub fn protocol_timeouts(mut self, timeouts: ProtocolTimeouts) -> Self {
+        self.protocol_timeouts = Some(timeouts);
+        self
+    }
+
+    pub fn connection_limits(mut self, limits: ConnectionLimits) -> Self {
+        self.connection_limits = Some(limits);
+        self
+    }
+
+    /// Share a fault injection handle with the peer, for tests
+    pub fn faults(mut self, faults: Faults) -> Self {
+        self.faults = Some(faults);
+        self
+    }
+
+    /// Keep the recovery shards held for other owners in this store
+    pub fn escrow(mut self, escrow: Escrow) -> Self {
+        self.escrow = Some(escrow);
+        self
+    }
 }
 
 // Initial construction - starts in NeedsSyncProvider state for explicit configuration
@@ -78,6 +111,10 @@ impl<L: BucketLogProvider> PeerBuilder<L, NeedsSyncProvider> {
             blobs_store: None,
             log_provider: None,
             sync_provider: None,
+            protocol_timeouts: None,
+            connection_limits: None,
+            faults: None,
+            escrow: None,
             _state: std::marker::PhantomData,
         }
     }
@@ -97,6 +134,10 @@ impl<L: BucketLogProvider> PeerBuilder<L, NeedsSyncProvider> {
             blobs_store: self.blobs_store,
             log_provider: self.log_provider,
             sync_provider: self.sync_provider,
+            protocol_timeouts: self.protocol_timeouts,
+            connection_limits: self.connection_limits,
+            faults: self.faults,
+            escrow: self.escrow,
             _state: std::marker::PhantomData,
         }
     }
@@ -155,6 +196,10 @@ impl<L: BucketLogProvider> PeerBuilder<L, ReadyToBuild> {
             secret_key,
             endpoint,
             sync_provider,
+            self.protocol_timeouts.unwrap_or_default(),
+            self.connection_limits.unwrap_or_default(),
+            self.faults.unwrap_or_default(),
+            self.escrow.unwrap_or_default(),
         )
     }
 }
@@ -1,7 +1,10 @@
 use crate::crypto::{PublicKey, SecretKey};
 
+use std::collections::{HashMap, HashSet};
 use std::net::SocketAddr;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
 use anyhow::{anyhow, Result};
 use iroh::{Endpoint, NodeId};
@@ -9,12 +12,42 @@ use uuid::Uuid;
 
 pub use super::blobs_store::BlobsStore;
 
-use crate::bucket_log::BucketLogProvider;
-use crate::linked_data::Link;
+use crate::bucket_log::{BucketLogError, BucketLogProvider};
+use crate::linked_data::{Hash, Link, LD_RAW_CODEC};
 use crate::mount::{Mount, MountError};
 
+use super::editing::{self, EditMark, EditMarks, EditingError};
+use super::escrow::{self, Escrow, EscrowError, EscrowSetup, Recovery};
+use super::faults::Faults;
+use super::leases::{self, Lease, LeaseError, Leases};
+use super::protocol::limits::{Admission, Permit, Rejection};
+use super::protocol::timeouts::Cancellation;
+use super::protocol::{
+    ConnectionLimits, ProtocolError, ProtocolInfo, ProtocolMetrics, ProtocolTimeouts,
+};
+use super::share_keys;
 use super::sync::{PingPeerJob, SyncJob, SyncProvider};
 
+/// How long a read waits for a version it must see to reach this peer
+pub const MIN_VERSION_WAIT: Duration = Duration::from_secs(2);
+const MIN_VERSION_POLL: Duration = Duration::from_millis(50);
+
+/// How many bucket references a path may go through
+pub const MAX_REFERENCE_DEPTH: usize = 8;
+
+/// Where a path ends up after following bucket references, see
+/// [`Peer::resolve`]
+#[derive(Clone)]
+pub struct Resolved {
+    /// Mount of the bucket the path ends up in
+    pub mount: Mount,
+    /// The path within that bucket
+    pub path: PathBuf,
+    /// Where that bucket's root is in the bucket the path started from, `/`
+    /// if the path crossed no reference
+    pub mount_point: PathBuf,
+}
+
 /// Overview of a peer's state, generic over a bucket log provider.
 ///  Provides everything that a peer needs in order to
 ///  load data, interact with peers, and manage buckets.
@@ -26,6 +59,30 @@ pub struct Peer<L: BucketLogProvider> {
     secret_key: SecretKey,
     endpoint: Endpoint,
     sync_provider: Arc<dyn SyncProvider<L>>,
+    /// Published buckets we follow without a share, and the peer we follow
+    /// each one from
+    following: Arc<RwLock<HashMap<Uuid, PublicKey>>>,
+    /// Temporary identities followed buckets were shared with, to read them
+    /// through their ephemeral share
+    ephemeral_keys: Arc<RwLock<HashMap<Uuid, SecretKey>>>,
+    /// Protocol negotiated with each peer we talked to
+    protocols: Arc<RwLock<HashMap<PublicKey, ProtocolInfo>>>,
+    protocol_timeouts: Arc<ProtocolTimeouts>,
+    /// Set on shutdown, cancels requests and downloads in flight
+    cancellation: Cancellation,
+    /// Limits and counters of requests from other peers
+    admission: Arc<Admission>,
+    /// Faults injected by tests, none otherwise
+    faults: Faults,
+    /// The last version of each bucket saved through this peer, with its
+    /// height. Reads see at least this version.
+    saved: Arc<RwLock<HashMap<Uuid, (Link, u64)>>>,
+    /// Advisory write leases we hold or granted to other owners
+    leases: Leases,
+    /// Paths we or other owners are editing
+    editing: EditMarks,
+    /// Recovery shards we hold for other owners
+    escrow: Escrow,
 }
 
 impl<L: BucketLogProvider> Clone for Peer<L>
@@ -40,11 +97,23 @@ where
             secret_key: self.secret_key.clone(),
             endpoint: self.endpoint.clone(),
             sync_provider: self.sync_provider.clone(),
+            following: self.following.clone(),
+            ephemeral_keys: self.ephemeral_keys.clone(),
+            protocols: self.protocols.clone(),
+            protocol_timeouts: self.protocol_timeouts.clone(),
+            cancellation: self.cancellation.clone(),
+            admission: self.admission.clone(),
+            faults: self.faults.clone(),
+            saved: self.saved.clone(),
+            leases: self.leases.clone(),
+            editing: self.editing.clone(),
+            escrow: self.escrow.clone(),
         }
     }
 }
 
 impl<L: BucketLogProvider> Peer<L> {
+    #[allow(clippy::too_many_arguments)]
     pub(super) fn new(
         log_provider: L,
         socket_address: SocketAddr,
@@ -52,6 +121,10 @@ impl<L: BucketLogProvider> Peer<L> {
         secret_key: SecretKey,
         endpoint: Endpoint,
         sync_provider: Arc<dyn SyncProvider<L>>,
+        protocol_timeouts: ProtocolTimeouts,
+        connection_limits: ConnectionLimits,
+        faults: Faults,
+        escrow: Escrow,
     ) -> Peer<L> {
         Self {
             log_provider,
@@ -60,6 +133,17 @@ impl<L: BucketLogProvider> Peer<L> {
             secret_key,
             endpoint,
             sync_provider,
+            following: Arc::new(RwLock::new(HashMap::new())),
+            ephemeral_keys: Arc::new(RwLock::new(HashMap::new())),
+            protocols: Arc::new(RwLock::new(HashMap::new())),
+            protocol_timeouts: Arc::new(protocol_timeouts),
+            cancellation: Cancellation::default(),
+            admission: Arc::new(Admission::new(connection_limits)),
+            faults,
+            saved: Arc::new(RwLock::new(HashMap::new())),
+            leases: Leases::default(),
+            editing: EditMarks::default(),
+            escrow,
         }
     }
 
@@ -91,6 +175,345 @@ impl<L: BucketLogProvider> Peer<L> {
         self.endpoint.node_id()
     }
 
+    // ========================================
+    // Following
+    // ========================================
+
+    /// Follow a published bucket from `source` without holding a share.
+    ///
+    /// Updates to a followed bucket are accepted as long as their authors are
+    /// owners, and its published versions are read with the public secret.
+    pub fn follow(&self, bucket_id: Uuid, source: PublicKey) {
+        self.following
+            .write()
+            .expect("following lock poisoned")
+            .insert(bucket_id, source);
+    }
+
+    /// Follow a bucket from `source` that was shared with the temporary
+    /// identity `key`, reading it through the identity's
+    /// [`EphemeralShare`](crate::mount::EphemeralShare) rather than waiting
+    /// for published versions.
+    pub fn follow_ephemeral(&self, bucket_id: Uuid, source: PublicKey, key: SecretKey) {
+        self.ephemeral_keys
+            .write()
+            .expect("ephemeral keys lock poisoned")
+            .insert(bucket_id, key);
+        self.follow(bucket_id, source);
+    }
+
+    /// The temporary identity a followed bucket was shared with, if any
+    pub fn ephemeral_key(&self, bucket_id: &Uuid) -> Option<SecretKey> {
+        self.ephemeral_keys
+            .read()
+            .expect("ephemeral keys lock poisoned")
+            .get(bucket_id)
+            .cloned()
+    }
+
+    /// Stop following a bucket, forgetting any temporary identity it was
+    /// shared with. Returns false if it was not followed.
+    pub fn unfollow(&self, bucket_id: &Uuid) -> bool {
+        self.ephemeral_keys
+            .write()
+            .expect("ephemeral keys lock poisoned")
+            .remove(bucket_id);
+        self.following
+            .write()
+            .expect("following lock poisoned")
+            .remove(bucket_id)
+            .is_some()
+    }
+
+    /// The peer a bucket is followed from, if we follow it
+    pub fn following(&self, bucket_id: &Uuid) -> Option<PublicKey> {
+        self.following
+            .read()
+            .expect("following lock poisoned")
+            .get(bucket_id)
+            .copied()
+    }
+
+    /// All followed buckets
+    pub fn followed_buckets(&self) -> Vec<Uuid> {
+        self.following
+            .read()
+            .expect("following lock poisoned")
+            .keys()
+            .copied()
+            .collect()
+    }
+
+    // ========================================
+    // Protocol Versions
+    // ========================================
+
+    /// The protocol last negotiated with a peer, if any
+    pub fn peer_protocol(&self, node_id: &PublicKey) -> Option<ProtocolInfo> {
+        self.protocols
+            .read()
+            .expect("protocols lock poisoned")
+            .get(node_id)
+            .cloned()
+    }
+
+    pub(crate) fn record_protocol(&self, node_id: PublicKey, info: ProtocolInfo) {
+        self.protocols
+            .write()
+            .expect("protocols lock poisoned")
+            .insert(node_id, info);
+    }
+
+    /// The protocol a peer speaks, negotiated with it unless recently known
+    ///
+    /// Fails with a [`ProtocolError`] if the peer can't be reached or doesn't
+    /// answer in time; peers that predate negotiation come back as
+    /// [`ProtocolInfo::legacy`].
+    pub async fn protocol(&self, node_id: &PublicKey) -> Result<ProtocolInfo>
+    where
+        L::Error: std::error::Error + Send + Sync + 'static,
+    {
+        super::protocol::version::negotiate(self, node_id).await
+    }
+
+    // ========================================
+    // Write Leases
+    // ========================================
+
+    /// Leases we know of, our own and those of other owners
+    pub fn leases(&self) -> &Leases {
+        &self.leases
+    }
+
+    /// Acquire, or renew, an advisory write lease on `prefix` of a bucket for
+    /// `ttl`, recorded by every reachable owner
+    ///
+    /// Fails with [`LeaseError::Held`] if we or another owner already know
+    /// of an overlapping lease held by someone else.
+    pub async fn acquire_lease(
+        &self,
+        bucket_id: Uuid,
+        prefix: &str,
+        ttl: Duration,
+    ) -> Result<Lease, LeaseError>
+    where
+        L::Error: std::error::Error + Send + Sync + 'static,
+    {
+        let prefix = leases::parse_prefix(prefix)?;
+        leases::acquire(self, bucket_id, prefix, ttl).await
+    }
+
+    /// Release our lease on `prefix` of a bucket. Returns false if we held
+    /// none.
+    pub async fn release_lease(&self, bucket_id: Uuid, prefix: &str) -> Result<bool, LeaseError>
+    where
+        L::Error: std::error::Error + Send + Sync + 'static,
+    {
+        let prefix = leases::parse_prefix(prefix)?;
+        leases::release(self, bucket_id, prefix).await
+    }
+
+    // ========================================
+    // Editing Indicators
+    // ========================================
+
+    /// Paths we know are being edited, by us and by other owners
+    pub fn editing(&self) -> &EditMarks {
+        &self.editing
+    }
+
+    /// Mark `path` of a bucket as being edited by us for `ttl`, telling every
+    /// reachable owner. Marking it again renews the mark.
+    pub async fn mark_editing(
+        &self,
+        bucket_id: Uuid,
+        path: &str,
+        ttl: Duration,
+    ) -> Result<EditMark, EditingError>
+    where
+        L::Error: std::error::Error + Send + Sync + 'static,
+    {
+        let path = editing::parse_path(path)?;
+        editing::mark(self, bucket_id, path, ttl).await
+    }
+
+    /// Clear our mark on `path` of a bucket. Returns false if we had none.
+    pub async fn clear_editing(&self, bucket_id: Uuid, path: &str) -> Result<bool, EditingError>
+    where
+        L::Error: std::error::Error + Send + Sync + 'static,
+    {
+        let path = editing::parse_path(path)?;
+        editing::clear(self, bucket_id, path).await
+    }
+
+    // ========================================
+    // Post-Quantum Shares
+    // ========================================
+
+    /// Make the owner shares of a mount hybrid X25519 + ML-KEM-768 if every
+    /// owner and mirror reads them, and classic otherwise, from its next
+    /// save on. Returns whether they will be hybrid.
+    pub async fn negotiate_shares(&self, mount: &mut Mount) -> Result<bool, MountError>
+    where
+        L::Error: std::error::Error + Send + Sync + 'static,
+    {
+        share_keys::negotiate(self, mount).await
+    }
+
+    // ========================================
+    // Social Recovery
+    // ========================================
+
+    /// Recovery shards we hold for other owners, and requests to release them
+    pub fn escrow(&self) -> &Escrow {
+        &self.escrow
+    }
+
+    /// Add a recovery identity to a mount's bucket and hand its shards to
+    /// `trustees`, any `threshold` of which can rebuild it. Takes effect on
+    /// the next save.
+    pub async fn set_up_escrow(
+        &self,
+        mount: &mut Mount,
+        trustees: Vec<PublicKey>,
+        threshold: u8,
+    ) -> Result<EscrowSetup, EscrowError>
+    where
+        L::Error: std::error::Error + Send + Sync + 'static,
+    {
+        escrow::set_up(self, mount, trustees, threshold).await
+    }
+
+    /// Ask `trustees` to release their shards of a bucket's recovery
+    /// identity to us, rebuilding it once enough of them did
+    pub async fn recover_escrow(
+        &self,
+        bucket_id: Uuid,
+        trustees: &[PublicKey],
+    ) -> Result<Recovery, EscrowError>
+    where
+        L::Error: std::error::Error + Send + Sync + 'static,
+    {
+        escrow::recover(self, bucket_id, trustees).await
+    }
+
+    // ========================================
+    // Connection Limits
+    // ========================================
+
+    /// Counters of requests from other peers
+    pub fn protocol_metrics(&self) -> ProtocolMetrics {
+        self.admission.metrics()
+    }
+
+    /// Admit a request from `peer`, holding its slot until the permit drops
+    pub(crate) fn admit(&self, peer: PublicKey) -> Result<Permit, Rejection> {
+        self.admission.admit(peer)
+    }
+
+    // ========================================
+    // Fault Injection
+    // ========================================
+
+    /// Faults injected into this peer's dealings with others, for tests
+    pub fn faults(&self) -> &Faults {
+        &self.faults
+    }
+
+    // ========================================
+    // Timeouts and Cancellation
+    // ========================================
+
+    pub fn protocol_timeouts(&self) -> &ProtocolTimeouts {
+        &self.protocol_timeouts
+    }
+
+    /// Cancel everything in flight, and every later request, e.g. on shutdown
+    pub(crate) fn cancel(&self) {
+        self.cancellation.cancel();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.is_cancelled()
+    }
+
+    /// Resolves once the peer shuts down. Long running work on the peer's
+    /// behalf (e.g. a sync worker) should stop when it does.
+    pub async fn cancelled(&self) {
+        self.cancellation.cancelled().await
+    }
+
+    /// Run `future` unless it takes longer than `after` or the peer shuts
+    /// down first, failing with a [`ProtocolError`] then
+    pub(crate) async fn bounded<T, F>(
+        &self,
+        operation: impl Fn() -> String,
+        after: std::time::Duration,
+        future: F,
+    ) -> Result<T>
+    where
+        F: std::future::Future<Output = Result<T>>,
+    {
+        self.cancellation.bounded(operation, after, future).await
+    }
+
+    /// Connect to a peer on the JAX protocol. A peer that can't be reached
+    /// within the connect timeout is offline.
+    pub(crate) async fn connect(&self, node_id: &PublicKey) -> Result<iroh::endpoint::Connection> {
+        let operation = || format!("connecting to peer {}", node_id.to_hex());
+        let after = self.protocol_timeouts.connect;
+        self.cancellation
+            .cancellable(operation, async {
+                let offline = |reason: String| ProtocolError::Offline {
+                    peer: node_id.to_hex(),
+                    reason,
+                };
+                if self.faults.is_partitioned(node_id) {
+                    return Err(offline("partitioned by an injected fault".to_string()).into());
+                }
+                self.faults.delay(node_id).await;
+                let connect = self.endpoint.connect(**node_id, super::protocol::ALPN);
+                match tokio::time::timeout(after, connect).await {
+                    Ok(Ok(conn)) => Ok(conn),
+                    Ok(Err(e)) => Err(offline(e.to_string()).into()),
+                    Err(_) => Err(offline(format!("no answer within {:?}", after)).into()),
+                }
+            })
+            .await
+    }
+
+    /// Download a blob from peers within the download timeout
+    pub(crate) async fn download_blob(
+        &self,
+        what: &str,
+        hash: iroh_blobs::Hash,
+        peer_ids: &[PublicKey],
+    ) -> Result<()> {
+        let operation = || format!("download of {} {}", what, hash);
+        self.bounded(operation, self.protocol_timeouts.download, async {
+            let peer_ids: Vec<PublicKey> = peer_ids
+                .iter()
+                .filter(|peer_id| !self.faults.is_partitioned(peer_id))
+                .copied()
+                .collect();
+            if let Some(peer_id) = peer_ids.first() {
+                self.faults.delay(peer_id).await;
+            }
+            if self.faults.fails_download() {
+                return Err(anyhow!(
+                    "Download of {} {} broke off (injected fault)",
+                    what,
+                    hash
+                ));
+            }
+            self.blobs_store
+                .download_hash(hash, peer_ids, &self.endpoint)
+                .await
+                .map_err(|e| anyhow!("Failed to download {} {} from peers: {}", what, hash, e))
+        })
+        .await
+    }
+
     // ========================================
     // Sync Operations (dispatch to backend)
     // ========================================
@@ -108,17 +531,32 @@ impl<L: BucketLogProvider> Peer<L> {
     /// Ping all peers in a bucket's shares
     ///
     /// Dispatches ping jobs to all peers listed in the bucket's current
-    /// manifest shares (except ourselves).
+    /// manifest shares and subtree shares (except ourselves). Followed buckets also ping the
+    /// peer they are followed from, even before we have any of their versions.
     pub async fn ping(&self, bucket_id: Uuid) -> Result<()>
     where
         L::Error: std::error::Error + Send + Sync + 'static,
     {
+        let source = self.following(&bucket_id);
+        if let Some(peer_id) = source {
+            self.dispatch(SyncJob::PingPeer(PingPeerJob { bucket_id, peer_id }))
+                .await?;
+        }
+
         // Get current head link
-        let (head_link, _) = self
-            .logs()
-            .head(bucket_id, None)
-            .await
-            .map_err(|e| anyhow!("Failed to get head for bucket {}: {}", bucket_id, e))?;
+        let head = self.logs().head(bucket_id, None).await;
+        let (head_link, _) = match head {
+            Ok(head) => head,
+            // Nothing synced from the source yet
+            Err(_) if source.is_some() => return Ok(()),
+            Err(e) => {
+                return Err(anyhow!(
+                    "Failed to get head for bucket {}: {}",
+                    bucket_id,
+                    e
+                ))
+            }
+        };
 
         // Load manifest from blobs store
         let manifest: crate::mount::Manifest = self
@@ -131,13 +569,20 @@ impl<L: BucketLogProvider> Peer<L> {
         let our_key = crate::crypto::PublicKey::from(*self.secret().public()).to_hex();
 
         // For each peer in shares, dispatch a ping job
-        for peer_key_hex in manifest.shares().keys() {
+        for peer_key_hex in manifest
+            .shares()
+            .keys()
+            .chain(manifest.subtree_shares().keys())
+        {
             if peer_key_hex == &our_key {
                 continue; // Skip ourselves
             }
 
             let peer_id = crate::crypto::PublicKey::from_hex(peer_key_hex)
                 .map_err(|e| anyhow!("Invalid peer key in shares: {}", e))?;
+            if Some(peer_id) == source {
+                continue; // Already pinged
+            }
 
             // Dispatch ping job
             if let Err(e) = self
@@ -291,15 +736,52 @@ impl<L: BucketLogProvider> Peer<L> {
         Mount::load(&link, &self.secret_key, &self.blobs_store).await
     }
 
+    /// Load mount at the current head of a bucket to write to, if the head
+    /// is still `expected_version`
+    ///
+    /// Writers pass the version they last read, so that a write made
+    /// against an older version fails rather than overwriting the versions
+    /// saved since. Without an `expected_version` this is [`Peer::mount`].
+    ///
+    /// # Errors
+    ///
+    /// As [`Peer::mount`], and [`MountError::HeadMoved`] with the current
+    /// head if it isn't `expected_version`.
+    pub async fn mount_expecting(
+        &self,
+        bucket_id: Uuid,
+        expected_version: Option<Hash>,
+    ) -> Result<Mount, MountError> {
+        let (link, _height) = self
+            .log_provider
+            .head(bucket_id, None)
+            .await
+            .map_err(|e| MountError::Default(anyhow!("Failed to get current head: {}", e)))?;
+
+        if let Some(expected) = expected_version {
+            if link.hash() != expected {
+                return Err(MountError::HeadMoved(link));
+            }
+        }
+
+        Mount::load(&link, &self.secret_key, &self.blobs_store).await
+    }
+
     /// Load mount for reading based on the peer's role in the bucket.
     ///
     /// This method determines the appropriate version to load based on the peer's role:
     /// - **Owners** see HEAD (latest state, including unpublished changes)
+    /// - **Peers with a subtree share** see their directory at HEAD
+    /// - **Temporary identities** of followed buckets see HEAD while their
+    ///   ephemeral share lasts
     /// - **Mirrors** (or unknown roles) see the latest_published version
     ///
     /// This ensures that mirrors only see content that has been explicitly published
     /// to them, while owners always see the most recent state.
     ///
+    /// Owners also see at least the last version saved through this peer,
+    /// see [`Peer::mount_for_read_at_least`].
+    ///
     /// # Arguments
     ///
     /// * `bucket_id` - The UUID of the bucket to load
@@ -315,29 +797,83 @@ impl<L: BucketLogProvider> Peer<L> {
     /// - No published version available (for mirrors)
     /// - Failed to load mount from blobs
     pub async fn mount_for_read(&self, bucket_id: Uuid) -> Result<Mount, MountError> {
+        self.mount_for_read_at_least(bucket_id, None).await
+    }
+
+    /// Load mount for reading like [`Peer::mount_for_read`], at no older a
+    /// version than `min_version`.
+    ///
+    /// Owners are served HEAD unless it's older than the minimum version, or
+    /// a fork of it at the same height, in which case they are served the
+    /// minimum version itself. Without a `min_version` the minimum is the
+    /// last version saved through this peer, so a read right after a write
+    /// sees that write even if a peer's concurrent version took over HEAD.
+    ///
+    /// A `min_version` this peer doesn't hold yet, such as one written on
+    /// another peer, is waited for up to [`MIN_VERSION_WAIT`].
+    ///
+    /// Mirrors can't write, so they read the latest published version
+    /// regardless.
+    ///
+    /// # Errors
+    ///
+    /// As [`Peer::mount_for_read`], and [`MountError::VersionUnavailable`] if
+    /// `min_version` didn't reach this peer in time.
+    pub async fn mount_for_read_at_least(
+        &self,
+        bucket_id: Uuid,
+        min_version: Option<Hash>,
+    ) -> Result<Mount, MountError> {
         use crate::mount::PrincipalRole;
 
         // Get current head link from log
-        let (head_link, _) = self
+        let (head_link, head_height) = self
             .log_provider
             .head(bucket_id, None)
             .await
             .map_err(|e| MountError::Default(anyhow!("Failed to get current head: {}", e)))?;
 
         // Check our role from the HEAD manifest
-        let our_role = Mount::load_manifest(&head_link, &self.blobs_store)
+        let head_manifest = Mount::load_manifest(&head_link, &self.blobs_store)
             .await
-            .ok()
-            .and_then(|m| {
-                m.get_share(&self.secret_key.public())
-                    .map(|s| s.role().clone())
-            });
+            .ok();
+        let our_role = head_manifest.as_ref().and_then(|m| {
+            m.get_share(&self.secret_key.public())
+                .map(|s| s.role().clone())
+        });
+        let subtree = head_manifest
+            .as_ref()
+            .is_some_and(|m| m.get_subtree_share(&self.secret_key.public()).is_some());
+        let ephemeral_key = self.ephemeral_key(&bucket_id).filter(|key| {
+            head_manifest
+                .as_ref()
+                .is_some_and(|m| m.get_ephemeral_share(&key.public()).is_some())
+        });
+
+        // Ephemeral shares are re-wrapped on every version, until they expire
+        if let Some(key) = ephemeral_key.filter(|_| our_role.is_none()) {
+            return Mount::load(&head_link, &key, &self.blobs_store).await;
+        }
 
         match our_role {
             Some(PrincipalRole::Owner) => {
-                // Owners see HEAD (latest state)
-                self.mount(bucket_id).await
+                let floor = match min_version {
+                    Some(hash) => Some(self.min_version(bucket_id, hash).await?),
+                    None => self.saved_version(&bucket_id),
+                };
+                let link = match floor {
+                    Some((link, height))
+                        if link.hash() != head_link.hash() && height >= head_height =>
+                    {
+                        link
+                    }
+                    // Owners see HEAD (latest state)
+                    _ => head_link,
+                };
+                Mount::load(&link, &self.secret_key, &self.blobs_store).await
             }
+            // Subtree shares are re-wrapped on every version
+            None if subtree => Mount::load(&head_link, &self.secret_key, &self.blobs_store).await,
             _ => {
                 // Mirrors (or unknown role) see latest_published
                 let (link, _) = self
@@ -355,6 +891,99 @@ impl<L: BucketLogProvider> Peer<L> {
         }
     }
 
+    /// Follow the bucket references on `path` in `mount` (see
+    /// [`crate::mount::BucketRef`]) to the bucket the path ends up in.
+    ///
+    /// Pinned references are read at their recorded version, floating ones
+    /// at the version [`Peer::mount_for_read`] gives. Either way this peer
+    /// needs access to the referenced bucket.
+    ///
+    /// # Errors
+    ///
+    /// [`MountError::ReferenceCycle`] if the path comes back to a bucket it
+    /// went through, [`MountError::ReferencesTooDeep`] past
+    /// [`MAX_REFERENCE_DEPTH`] references, and as [`Peer::mount_for_read`]
+    /// for the referenced buckets.
+    pub async fn resolve(&self, mount: Mount, path: &Path) -> Result<Resolved, MountError> {
+        let mut mount = mount;
+        let mut path = Path::new("/").join(path);
+        let mut mount_point = PathBuf::from("/");
+        let mut visited = HashSet::from([*mount.inner().await.manifest().id()]);
+        while let Some((prefix, bucket_ref)) = mount.find_reference(&path).await? {
+            let bucket_id = *bucket_ref.bucket_id();
+            if !visited.insert(bucket_id) {
+                return Err(MountError::ReferenceCycle(bucket_id));
+            }
+            if visited.len() > MAX_REFERENCE_DEPTH + 1 {
+                return Err(MountError::ReferencesTooDeep(MAX_REFERENCE_DEPTH));
+            }
+
+            mount = if bucket_ref.is_floating() {
+                self.mount_for_read(bucket_id).await?
+            } else {
+                let version = bucket_ref.version();
+                if !self.blobs_store.stat(&version.hash()).await? {
+                    return Err(MountError::VersionUnavailable(version.hash()));
+                }
+                let manifest = Mount::load_manifest(version, &self.blobs_store).await?;
+                if *manifest.id() != bucket_id {
+                    return Err(MountError::Default(anyhow!(
+                        "version {} is not of bucket {}",
+                        version.hash(),
+                        bucket_id
+                    )));
+                }
+                let key = self
+                    .ephemeral_key(&bucket_id)
+                    .filter(|key| {
+                        manifest.get_share(&self.secret_key.public()).is_none()
+                            && manifest.get_ephemeral_share(&key.public()).is_some()
+                    })
+                    .unwrap_or_else(|| self.secret_key.clone());
+                Mount::load(version, &key, &self.blobs_store).await?
+            };
+            let rest = path.strip_prefix(&prefix).unwrap_or(Path::new(""));
+            path = Path::new("/").join(rest);
+            mount_point.push(prefix.strip_prefix("/").unwrap_or(&prefix));
+        }
+        Ok(Resolved {
+            mount,
+            path,
+            mount_point,
+        })
+    }
+
+    /// The last version of a bucket saved through this peer, and its height
+    pub fn saved_version(&self, bucket_id: &Uuid) -> Option<(Link, u64)> {
+        self.saved
+            .read()
+            .expect("saved lock poisoned")
+            .get(bucket_id)
+            .cloned()
+    }
+
+    /// Wait for a version of a bucket to be held locally and read its height
+    async fn min_version(&self, bucket_id: Uuid, hash: Hash) -> Result<(Link, u64), MountError> {
+        let link = Link::new(LD_RAW_CODEC, hash);
+        let deadline = tokio::time::Instant::now() + MIN_VERSION_WAIT;
+        while !self.blobs_store.stat(&hash).await? {
+            if tokio::time::Instant::now() >= deadline {
+                return Err(MountError::VersionUnavailable(hash));
+            }
+            tokio::time::sleep(MIN_VERSION_POLL).await;
+        }
+
+        let manifest = Mount::load_manifest(&link, &self.blobs_store).await?;
+        if *manifest.id() != bucket_id {
+            return Err(MountError::Default(anyhow!(
+                "version {} is not of bucket {}",
+                hash,
+                bucket_id
+            )));
+        }
+        Ok((link, manifest.height()))
+    }
+
     /// Save a mount and append it to the bucket's log
     ///
     /// This method:
@@ -399,7 +1028,8 @@ impl<L: BucketLogProvider> Peer<L> {
         tracing::info!("SAVE_MOUNT: Found {} shares in manifest", shares.len());
 
         // Append to log
-        self.log_provider
+        if let Err(e) = self
+            .log_provider
             .append(
                 bucket_id,
                 name,
@@ -409,11 +1039,29 @@ impl<L: BucketLogProvider> Peer<L> {
                 is_published,
             )
             .await
-            .map_err(|e| MountError::Default(anyhow!("Failed to append to log: {}", e)))?;
+        {
+            // A concurrent write saved this height first
+            if matches!(e, BucketLogError::Conflict) {
+                if let Ok((head, _)) = self.log_provider.head(bucket_id, None).await {
+                    if head != link {
+                        return Err(MountError::HeadMoved(head));
+                    }
+                }
+            }
+            return Err(MountError::Default(anyhow!(
+                "Failed to append to log: {}",
+                e
+            )));
+        }
+        self.saved
+            .write()
+            .expect("saved lock poisoned")
+            .insert(bucket_id, (link.clone(), height));
 
-        // Dispatch ping jobs for each peer (except ourselves)
+        // Dispatch ping jobs for each peer (except ourselves), including
+        //  those with a subtree share
         let mut notified_count = 0;
-        for (peer_key_hex, _share) in shares.iter() {
+        for peer_key_hex in shares.keys().chain(manifest.subtree_shares().keys()) {
             tracing::info!("SAVE_MOUNT: Checking share for peer: {}", peer_key_hex);
 
             // Parse the peer's public key
@@ -3,14 +3,16 @@ use std::fmt::Debug;
 use anyhow::{anyhow, Result};
 use iroh::endpoint::SendStream;
 use iroh::protocol::AcceptError;
-use iroh::Endpoint;
 use serde::{Deserialize, Serialize};
 
 use crate::bucket_log::BucketLogProvider;
 use crate::crypto::PublicKey;
 use crate::peer::Peer;
 
-use super::{messages::Message, ALPN};
+use super::limits;
+use super::messages::Message;
+use super::timeouts::ProtocolError;
+use super::wire::{self, WireFormat};
 
 // TODO (amiller68): there should be a generic error type
 //  for all the message / replies
@@ -108,8 +110,8 @@ pub trait BidirectionalHandler: Sized {
         L: BucketLogProvider,
         L::Error: std::error::Error + Send + Sync + 'static,
     {
-        let endpoint = peer.endpoint();
-        let response = Self::_handle_send::<L>(endpoint, recipient_node_id, request).await?;
+        let format = WireFormat::for_peer(peer.peer_protocol(recipient_node_id).as_ref());
+        let response = Self::_handle_send::<L>(peer, recipient_node_id, request, format).await?;
         Self::handle_reply(peer, recipient_node_id, &response).await?;
         Ok(response)
     }
@@ -129,6 +131,7 @@ pub trait BidirectionalHandler: Sized {
         peer: &Peer<L>,
         sender_node_id: &PublicKey,
         message: Self::Message,
+        format: WireFormat,
         mut send: SendStream,
     ) -> Result<(), AcceptError>
     where
@@ -138,8 +141,8 @@ pub trait BidirectionalHandler: Sized {
         // Call the handler to get the response
         let reply = Self::handle_message(peer, sender_node_id, &message).await;
 
-        // Serialize the response
-        let reply_bytes = bincode::serialize(&reply).map_err(|e| {
+        // Serialize the response in the format the message came in
+        let reply_bytes = wire::encode_reply(format, &reply).map_err(|e| {
             tracing::error!("Failed to serialize reply: {}", e);
             let err: Box<dyn std::error::Error + Send + Sync> =
                 anyhow!("failed to serialize reply: {}", e).into();
@@ -182,84 +185,103 @@ pub trait BidirectionalHandler: Sized {
     ///  your implementation, you should call the `send` method.
     ///
     /// This is a provided method that handles all the boilerplate:
-    /// - Connects to the peer
+    /// - Connects to the peer within the connect timeout
     /// - Opens a bidirectional stream
-    /// - Serializes and sends the request
+    /// - Serializes and sends the request in `format`
     /// - Receives and deserializes the response
     /// - Returns the response for the caller to handle
-    /// - Error handling
+    /// - Error handling: an unreachable or busy peer, a request that outlives
+    ///   the timeout of its message type and shutdown fail with a
+    ///   [`ProtocolError`](super::ProtocolError)
     ///
     /// If you want automatic response handling, call `handle_response` on the result.
+    #[tracing::instrument(name = "peer.send", skip_all, fields(peer = %recipient_node_id.to_hex()))]
     async fn _handle_send<L>(
-        endpoint: &Endpoint,
+        peer: &Peer<L>,
         recipient_node_id: &PublicKey,
         message: Self::Message,
+        format: WireFormat,
     ) -> Result<Self::Reply>
     where
         L: BucketLogProvider,
     {
         // Connect to the peer
-        let conn = endpoint
-            .connect(**recipient_node_id, ALPN)
-            .await
-            .map_err(|e| {
-                tracing::error!("Failed to connect to peer {:?}: {}", recipient_node_id, e);
-                anyhow!("Failed to connect to peer: {}", e)
-            })?;
+        let conn = peer.connect(recipient_node_id).await.inspect_err(|e| {
+            tracing::error!("Failed to connect to peer {:?}: {}", recipient_node_id, e);
+        })?;
 
         tracing::info!("Connected to peer {:?}", recipient_node_id);
 
-        // Open a bidirectional stream
-        let (mut send, mut recv) = conn.open_bi().await.map_err(|e| {
-            tracing::error!("Failed to open bidirectional stream: {}", e);
-            anyhow!("Failed to open bidirectional stream: {}", e)
-        })?;
+        // Wrap request in Message enum
+        let message = Self::wrap_request(message);
+        let name = message.name();
+        let timeout = peer.protocol_timeouts().for_message(name);
+        let operation = || format!("{} request to peer {}", name, recipient_node_id.to_hex());
+
+        // A lost message never arrives, so the sender only sees it time out
+        if peer.faults().loses(name) {
+            tracing::warn!("Losing {} (injected fault)", operation());
+            return Err(ProtocolError::Timeout {
+                operation: operation(),
+                after: timeout,
+            }
+            .into());
+        }
 
-        tracing::info!(
-            "Opened bidirectional stream with peer {:?}",
-            recipient_node_id
-        );
+        peer.bounded(operation, timeout, async {
+            // Open a bidirectional stream
+            let (mut send, mut recv) = conn.open_bi().await.map_err(|e| {
+                tracing::error!("Failed to open bidirectional stream: {}", e);
+                anyhow!("Failed to open bidirectional stream: {}", e)
+            })?;
 
-        // Wrap request in Message enum and serialize
-        let message = Self::wrap_request(message);
-        let request_bytes = bincode::serialize(&message)
-            .map_err(|e| anyhow!("Failed to serialize request: {}", e))?;
+            tracing::info!(
+                "Opened bidirectional stream with peer {:?}",
+                recipient_node_id
+            );
 
-        tracing::info!(
-            "BIDIRECTIONAL: Serialized request to {} bytes, first byte: {}",
-            request_bytes.len(),
-            request_bytes
-                .first()
-                .map(|b| b.to_string())
-                .unwrap_or_else(|| "none".to_string())
-        );
+            // Serialize the request
+            let request_bytes = wire::encode_message(format, &message, &tracing::Span::current())
+                .map_err(|e| anyhow!("Failed to serialize request: {}", e))?;
 
-        // Send the request
-        send.write_all(&request_bytes)
-            .await
-            .map_err(|e| anyhow!("Failed to write request: {}", e))?;
+            tracing::info!(
+                "BIDIRECTIONAL: Serialized request to {} bytes, first byte: {}",
+                request_bytes.len(),
+                request_bytes
+                    .first()
+                    .map(|b| b.to_string())
+                    .unwrap_or_else(|| "none".to_string())
+            );
 
-        tracing::info!("BIDIRECTIONAL: Sent request");
+            // Send the request
+            send.write_all(&request_bytes)
+                .await
+                .map_err(|e| anyhow!("Failed to write request: {}", e))?;
 
-        send.finish()
-            .map_err(|e| anyhow!("Failed to finish sending request: {}", e))?;
+            tracing::info!("BIDIRECTIONAL: Sent request");
 
-        tracing::info!("BIDIRECTIONAL: Finished sending request");
+            send.finish()
+                .map_err(|e| anyhow!("Failed to finish sending request: {}", e))?;
 
-        // Read the response
-        let response_bytes = recv
-            .read_to_end(1024 * 1024)
-            .await
-            .map_err(|e| anyhow!("Failed to read response: {}", e))?;
+            tracing::info!("BIDIRECTIONAL: Finished sending request");
 
-        tracing::info!("BIDIRECTIONAL: Received response");
+            // Read the response
+            let response_bytes = recv
+                .read_to_end(1024 * 1024)
+                .await
+                .map_err(|e| anyhow!("Failed to read response: {}", e))?;
 
-        // Deserialize the response
-        let response: Self::Reply = bincode::deserialize(&response_bytes)
-            .map_err(|e| anyhow!("Failed to deserialize response: {}", e))?;
+            tracing::info!("BIDIRECTIONAL: Received response");
 
-        tracing::info!("BIDIRECTIONAL: Deserialized response: {:?}", response);
+            // Deserialize the response
+            let response: Self::Reply = wire::decode_reply(format, &response_bytes)
+                .map_err(|e| anyhow!("Failed to deserialize response: {}", e))?;
 
-        Ok(response)
+            tracing::info!("BIDIRECTIONAL: Deserialized response: {:?}", response);
+
+            Ok(response)
+        })
+        .await
+        .map_err(|e| limits::or_busy(e, &conn, recipient_node_id))
     }
 }
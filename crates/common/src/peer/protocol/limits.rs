@@ -0,0 +1,221 @@
+//! Limits on incoming connections and requests
+//!
+//! Every connection on the JAX protocol carries one request, handled in its
+//! own task. To keep a misbehaving peer from spawning handlers without bound,
+//! a connection is only handled if fewer than
+//! [`ConnectionLimits::max_connections`] are in flight in total and fewer
+//! than [`ConnectionLimits::max_requests_per_peer`] from the same peer.
+//! Otherwise it is closed right away with [`BUSY_CODE`], which the sender
+//! sees as [`ProtocolError::Busy`](super::ProtocolError::Busy) and can retry
+//! later.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use iroh::endpoint::{Connection, ConnectionError, VarInt};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::crypto::PublicKey;
+
+use super::ProtocolError;
+
+/// Requests handled at once, from all peers
+pub const DEFAULT_MAX_CONNECTIONS: usize = 256;
+
+/// Requests handled at once from a single peer
+pub const DEFAULT_MAX_REQUESTS_PER_PEER: usize = 16;
+
+/// Application error code connections are closed with when over a limit
+pub const BUSY_CODE: u32 = 429;
+
+/// Limits on requests handled at once
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectionLimits {
+    pub max_connections: usize,
+    pub max_requests_per_peer: usize,
+}
+
+impl Default for ConnectionLimits {
+    fn default() -> Self {
+        Self {
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+            max_requests_per_peer: DEFAULT_MAX_REQUESTS_PER_PEER,
+        }
+    }
+}
+
+/// Counters of the protocol handler
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtocolMetrics {
+    /// Requests being handled now
+    pub in_flight: usize,
+    /// Peers with requests being handled now
+    pub peers_in_flight: usize,
+    /// Requests handled since start
+    pub accepted_total: u64,
+    /// Requests turned away because of `max_connections`
+    pub rejected_busy_total: u64,
+    /// Requests turned away because of `max_requests_per_peer`
+    pub rejected_peer_total: u64,
+}
+
+/// Why a connection was turned away
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Rejection {
+    Busy,
+    PeerLimit,
+}
+
+impl Rejection {
+    pub(crate) fn reason(&self) -> &'static [u8] {
+        match self {
+            Rejection::Busy => b"busy",
+            Rejection::PeerLimit => b"too many requests",
+        }
+    }
+}
+
+/// Admission control of incoming connections, shared by all clones of a peer
+#[derive(Debug)]
+pub(crate) struct Admission {
+    limits: ConnectionLimits,
+    connections: Arc<Semaphore>,
+    per_peer: Mutex<HashMap<PublicKey, usize>>,
+    accepted: AtomicU64,
+    rejected_busy: AtomicU64,
+    rejected_peer: AtomicU64,
+}
+
+/// Held while a request is handled; frees its slots when dropped
+#[derive(Debug)]
+pub(crate) struct Permit {
+    admission: Arc<Admission>,
+    peer: PublicKey,
+    _connection: OwnedSemaphorePermit,
+}
+
+impl Admission {
+    pub(crate) fn new(limits: ConnectionLimits) -> Self {
+        Self {
+            limits,
+            connections: Arc::new(Semaphore::new(limits.max_connections.max(1))),
+            per_peer: Mutex::new(HashMap::new()),
+            accepted: AtomicU64::new(0),
+            rejected_busy: AtomicU64::new(0),
+            rejected_peer: AtomicU64::new(0),
+        }
+    }
+
+    /// Admit a request from `peer` if it is within the limits
+    pub(crate) fn admit(self: &Arc<Self>, peer: PublicKey) -> Result<Permit, Rejection> {
+        let mut per_peer = self.per_peer.lock().expect("admission lock poisoned");
+        let from_peer = per_peer.entry(peer).or_insert(0);
+        if *from_peer >= self.limits.max_requests_per_peer.max(1) {
+            self.rejected_peer.fetch_add(1, Ordering::Relaxed);
+            return Err(Rejection::PeerLimit);
+        }
+        let Ok(connection) = self.connections.clone().try_acquire_owned() else {
+            if *from_peer == 0 {
+                per_peer.remove(&peer);
+            }
+            self.rejected_busy.fetch_add(1, Ordering::Relaxed);
+            return Err(Rejection::Busy);
+        };
+        *from_peer += 1;
+        self.accepted.fetch_add(1, Ordering::Relaxed);
+        Ok(Permit {
+            admission: self.clone(),
+            peer,
+            _connection: connection,
+        })
+    }
+
+    pub(crate) fn metrics(&self) -> ProtocolMetrics {
+        let per_peer = self.per_peer.lock().expect("admission lock poisoned");
+        ProtocolMetrics {
+            in_flight: per_peer.values().sum(),
+            peers_in_flight: per_peer.len(),
+            accepted_total: self.accepted.load(Ordering::Relaxed),
+            rejected_busy_total: self.rejected_busy.load(Ordering::Relaxed),
+            rejected_peer_total: self.rejected_peer.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        let mut per_peer = self
+            .admission
+            .per_peer
+            .lock()
+            .expect("admission lock poisoned");
+        if let Some(from_peer) = per_peer.get_mut(&self.peer) {
+            *from_peer -= 1;
+            if *from_peer == 0 {
+                per_peer.remove(&self.peer);
+            }
+        }
+    }
+}
+
+/// Replace `error` with [`ProtocolError::Busy`] if the peer turned the
+/// connection away, so callers can tell it apart from a failed request
+pub(crate) fn or_busy(error: anyhow::Error, conn: &Connection, peer: &PublicKey) -> anyhow::Error {
+    match busy_error(conn, peer) {
+        Some(busy) => busy.into(),
+        None => error,
+    }
+}
+
+/// The error for a connection the peer closed because it was over a limit
+fn busy_error(conn: &Connection, peer: &PublicKey) -> Option<ProtocolError> {
+    match conn.close_reason()? {
+        ConnectionError::ApplicationClosed(close)
+            if close.error_code == VarInt::from_u32(BUSY_CODE) =>
+        {
+            Some(ProtocolError::Busy {
+                peer: peer.to_hex(),
+                reason: String::from_utf8_lossy(&close.reason).into_owned(),
+            })
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::crypto::SecretKey;
+
+    #[test]
+    fn test_admission_limits() {
+        let admission = Arc::new(Admission::new(ConnectionLimits {
+            max_connections: 3,
+            max_requests_per_peer: 2,
+        }));
+        let noisy = SecretKey::generate().public();
+        let quiet = SecretKey::generate().public();
+
+        let first = admission.admit(noisy).unwrap();
+        let _second = admission.admit(noisy).unwrap();
+        assert_eq!(admission.admit(noisy).unwrap_err(), Rejection::PeerLimit);
+
+        // Other peers still get through, up to the total
+        let _third = admission.admit(quiet).unwrap();
+        assert_eq!(admission.admit(quiet).unwrap_err(), Rejection::Busy);
+
+        let metrics = admission.metrics();
+        assert_eq!(metrics.in_flight, 3);
+        assert_eq!(metrics.peers_in_flight, 2);
+        assert_eq!(metrics.accepted_total, 3);
+        assert_eq!(metrics.rejected_peer_total, 1);
+        assert_eq!(metrics.rejected_busy_total, 1);
+
+        // Finished requests free their slots
+        drop(first);
+        let _fourth = admission.admit(noisy).unwrap();
+        assert_eq!(admission.metrics().in_flight, 3);
+    }
+}
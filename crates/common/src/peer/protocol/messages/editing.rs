@@ -0,0 +1,116 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::bucket_log::BucketLogProvider;
+use crate::crypto::PublicKey;
+use crate::peer::protocol::bidirectional::BidirectionalHandler;
+use crate::peer::protocol::messages::Message;
+use crate::peer::{editing, leases, Peer};
+
+/// What the sender is doing with a path
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum EditingAction {
+    /// The sender is editing the path; record, or renew, its mark for
+    /// `ttl_ms` on our clock
+    Start { ttl_ms: u64 },
+    /// The sender stopped editing the path
+    Stop,
+}
+
+/// Request to record or drop the sender's mark on a path of a bucket (see
+/// [`crate::peer::editing`])
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditingMessage {
+    pub bucket_id: Uuid,
+    /// Absolute path being edited
+    pub path: String,
+    pub action: EditingAction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum EditingReplyStatus {
+    /// We don't have the bucket, the sender isn't one of its owners, or the
+    /// path isn't absolute
+    NotFound,
+    /// The mark is recorded until it expires or is cleared
+    Marked,
+    /// The sender's mark, if it had one, is dropped
+    Cleared,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditingReply {
+    pub bucket_id: Uuid,
+    pub status: EditingReplyStatus,
+}
+
+/// Editing handler: owners record which paths the others are editing
+pub struct Editing;
+
+impl BidirectionalHandler for Editing {
+    type Message = EditingMessage;
+    type Reply = EditingReply;
+
+    fn wrap_request(request: Self::Message) -> Message {
+        Message::Editing(request)
+    }
+
+    async fn handle_message<L: BucketLogProvider>(
+        peer: &Peer<L>,
+        sender_node_id: &PublicKey,
+        request: &EditingMessage,
+    ) -> EditingReply {
+        let bucket_id = request.bucket_id;
+        let not_found = EditingReply {
+            bucket_id,
+            status: EditingReplyStatus::NotFound,
+        };
+        let Ok(path) = editing::parse_path(&request.path) else {
+            return not_found;
+        };
+        match leases::is_owner(peer, bucket_id, sender_node_id).await {
+            Ok(true) => {}
+            Ok(false) => return not_found,
+            Err(e) => {
+                tracing::debug!("No edit mark on bucket {} for peer: {}", bucket_id, e);
+                return not_found;
+            }
+        }
+
+        let status = match request.action {
+            EditingAction::Start { ttl_ms } => {
+                peer.editing().mark(
+                    bucket_id,
+                    &path,
+                    *sender_node_id,
+                    std::time::Duration::from_millis(ttl_ms),
+                );
+                EditingReplyStatus::Marked
+            }
+            EditingAction::Stop => {
+                peer.editing().clear(bucket_id, &path, sender_node_id);
+                EditingReplyStatus::Cleared
+            }
+        };
+        EditingReply { bucket_id, status }
+    }
+
+    async fn handle_reply<L>(
+        _peer: &Peer<L>,
+        recipient_node_id: &PublicKey,
+        reply: &EditingReply,
+    ) -> Result<()>
+    where
+        L: BucketLogProvider,
+        L::Error: std::error::Error + Send + Sync + 'static,
+    {
+        tracing::debug!(
+            "Peer {} answered edit mark on bucket {} with {:?}",
+            recipient_node_id.to_hex(),
+            reply.bucket_id,
+            reply.status
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,119 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::bucket_log::BucketLogProvider;
+use crate::crypto::PublicKey;
+use crate::peer::escrow::{EscrowShard, Release};
+use crate::peer::protocol::bidirectional::BidirectionalHandler;
+use crate::peer::protocol::messages::Message;
+use crate::peer::Peer;
+
+/// What the sender wants done with a shard
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum EscrowAction {
+    /// Keep this shard of the bucket's recovery identity for the sender
+    Deposit(EscrowShard),
+    /// Release our shard of the bucket to the sender, once our user approves
+    Release,
+}
+
+/// Request to hold or release a shard of a bucket's recovery identity (see
+/// [`crate::peer::escrow`])
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EscrowMessage {
+    pub bucket_id: Uuid,
+    pub action: EscrowAction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum EscrowReplyStatus {
+    /// The deposited shard is kept
+    Held,
+    /// The deposit was refused: the sender isn't the owner it names, or we
+    /// hold too many shards
+    Refused,
+    /// We hold no shard of the bucket
+    NotFound,
+    /// The release waits for our user's approval
+    Pending,
+    /// Our shard, released to the sender
+    Released(Box<EscrowShard>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EscrowReply {
+    pub bucket_id: Uuid,
+    pub status: EscrowReplyStatus,
+}
+
+/// Escrow handler: trustees hold shards of recovery identities and release
+/// them to approved requesters
+pub struct Escrow;
+
+impl BidirectionalHandler for Escrow {
+    type Message = EscrowMessage;
+    type Reply = EscrowReply;
+
+    fn wrap_request(request: Self::Message) -> Message {
+        Message::Escrow(request)
+    }
+
+    async fn handle_message<L: BucketLogProvider>(
+        peer: &Peer<L>,
+        sender_node_id: &PublicKey,
+        request: &EscrowMessage,
+    ) -> EscrowReply {
+        let bucket_id = request.bucket_id;
+        let status = match &request.action {
+            EscrowAction::Deposit(shard)
+                if shard.owner != *sender_node_id || shard.bucket_id != bucket_id =>
+            {
+                EscrowReplyStatus::Refused
+            }
+            EscrowAction::Deposit(shard) => {
+                if peer.escrow().hold(shard.clone()) {
+                    tracing::info!(
+                        "Holding a recovery shard of bucket {} for {}",
+                        bucket_id,
+                        sender_node_id.to_hex()
+                    );
+                    EscrowReplyStatus::Held
+                } else {
+                    EscrowReplyStatus::Refused
+                }
+            }
+            EscrowAction::Release => {
+                match peer.escrow().request_release(&bucket_id, sender_node_id) {
+                    Release::NotFound => EscrowReplyStatus::NotFound,
+                    Release::Pending => EscrowReplyStatus::Pending,
+                    Release::Released(shard) => EscrowReplyStatus::Released(shard),
+                }
+            }
+        };
+        EscrowReply { bucket_id, status }
+    }
+
+    async fn handle_reply<L>(
+        _peer: &Peer<L>,
+        recipient_node_id: &PublicKey,
+        reply: &EscrowReply,
+    ) -> Result<()>
+    where
+        L: BucketLogProvider,
+        L::Error: std::error::Error + Send + Sync + 'static,
+    {
+        // Keep shards out of logs
+        let status = match &reply.status {
+            EscrowReplyStatus::Released(_) => "Released".to_string(),
+            status => format!("{:?}", status),
+        };
+        tracing::debug!(
+            "Peer {} answered escrow of bucket {} with {}",
+            recipient_node_id.to_hex(),
+            reply.bucket_id,
+            status
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::bucket_log::BucketLogProvider;
+use crate::crypto::PublicKey;
+use crate::peer::protocol::bidirectional::BidirectionalHandler;
+use crate::peer::protocol::messages::Message;
+use crate::peer::protocol::version::{self, ProtocolInfo};
+use crate::peer::Peer;
+
+/// Handshake announcing the protocol version and capabilities of the sender
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HelloMessage {
+    /// Protocol version of the sender
+    pub version: u32,
+    /// Capabilities of the sender, see [`version::capability`]
+    pub capabilities: Vec<String>,
+}
+
+/// The responder's side of the handshake
+pub type HelloReply = HelloMessage;
+
+/// Hello handler: both sides learn which protocol the other speaks
+pub struct Hello;
+
+impl BidirectionalHandler for Hello {
+    type Message = HelloMessage;
+    type Reply = HelloReply;
+
+    fn wrap_request(request: Self::Message) -> Message {
+        Message::Hello(request)
+    }
+
+    async fn handle_message<L: BucketLogProvider>(
+        _peer: &Peer<L>,
+        _sender_node_id: &PublicKey,
+        _hello: &HelloMessage,
+    ) -> HelloReply {
+        version::hello()
+    }
+
+    /// Remember what the sender speaks, sparing us a handshake of our own
+    async fn handle_message_side_effect<L>(
+        peer: &Peer<L>,
+        sender_node_id: &PublicKey,
+        hello: &HelloMessage,
+        _reply: &HelloReply,
+    ) -> Result<()>
+    where
+        L: BucketLogProvider,
+        L::Error: std::error::Error + Send + Sync + 'static,
+    {
+        peer.record_protocol(
+            *sender_node_id,
+            ProtocolInfo::from_hello(hello.version, hello.capabilities.clone()),
+        );
+        Ok(())
+    }
+
+    async fn handle_reply<L>(
+        peer: &Peer<L>,
+        recipient_node_id: &PublicKey,
+        reply: &HelloReply,
+    ) -> Result<()>
+    where
+        L: BucketLogProvider,
+        L::Error: std::error::Error + Send + Sync + 'static,
+    {
+        tracing::debug!(
+            "Peer {} speaks protocol v{} with capabilities {:?}",
+            recipient_node_id.to_hex(),
+            reply.version,
+            reply.capabilities
+        );
+        peer.record_protocol(
+            *recipient_node_id,
+            ProtocolInfo::from_hello(reply.version, reply.capabilities.clone()),
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::linked_data::Link;
+    use crate::peer::protocol::messages::ping::PingMessage;
+
+    /// The message enum as peers that predate negotiation know it
+    #[derive(Debug, Serialize, Deserialize)]
+    enum LegacyMessage {
+        Ping(PingMessage),
+    }
+
+    #[test]
+    fn test_legacy_peers_decode_pings_but_not_hellos() {
+        let ping = PingMessage {
+            bucket_id: uuid::Uuid::new_v4(),
+            link: Link::default(),
+            height: 3,
+        };
+        let bytes = bincode::serialize(&Message::Ping(ping.clone())).unwrap();
+        assert_eq!(
+            bytes,
+            bincode::serialize(&LegacyMessage::Ping(ping)).unwrap()
+        );
+        assert!(bincode::deserialize::<LegacyMessage>(&bytes).is_ok());
+
+        let hello = bincode::serialize(&Message::Hello(version::hello())).unwrap();
+        assert!(bincode::deserialize::<LegacyMessage>(&hello).is_err());
+    }
+
+    #[test]
+    fn test_protocol_info_from_hello() {
+        let newer = ProtocolInfo::from_hello(
+            version::PROTOCOL_VERSION + 1,
+            vec!["follow".to_string(), "from-the-future".to_string()],
+        );
+        assert_eq!(newer.version, version::PROTOCOL_VERSION);
+        assert!(newer.supports(version::capability::FOLLOW));
+        assert!(!newer.supports(version::capability::TRACE_CONTEXT));
+
+        let legacy = ProtocolInfo::legacy();
+        assert_eq!(legacy.version, version::LEGACY_PROTOCOL_VERSION);
+        assert!(!legacy.supports(version::capability::FOLLOW));
+    }
+}
@@ -0,0 +1,54 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::bucket_log::BucketLogProvider;
+use crate::crypto::{KemPublicKey, PublicKey};
+use crate::peer::protocol::bidirectional::BidirectionalHandler;
+use crate::peer::protocol::messages::Message;
+use crate::peer::Peer;
+
+/// Request for the responder's ML-KEM key, to wrap bucket shares to (see
+/// [`crate::peer::share_keys`])
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KemKeyMessage {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KemKeyReply {
+    /// The ML-KEM-768 key of the responder's identity
+    pub kem_key: KemPublicKey,
+}
+
+/// KemKey handler: peers hand out the ML-KEM key of their identity
+pub struct KemKey;
+
+impl BidirectionalHandler for KemKey {
+    type Message = KemKeyMessage;
+    type Reply = KemKeyReply;
+
+    fn wrap_request(request: Self::Message) -> Message {
+        Message::KemKey(request)
+    }
+
+    async fn handle_message<L: BucketLogProvider>(
+        peer: &Peer<L>,
+        _sender_node_id: &PublicKey,
+        _request: &KemKeyMessage,
+    ) -> KemKeyReply {
+        KemKeyReply {
+            kem_key: KemPublicKey::of(peer.secret()),
+        }
+    }
+
+    async fn handle_reply<L>(
+        _peer: &Peer<L>,
+        recipient_node_id: &PublicKey,
+        _reply: &KemKeyReply,
+    ) -> Result<()>
+    where
+        L: BucketLogProvider,
+        L::Error: std::error::Error + Send + Sync + 'static,
+    {
+        tracing::debug!("Peer {} sent its ML-KEM key", recipient_node_id.to_hex());
+        Ok(())
+    }
+}
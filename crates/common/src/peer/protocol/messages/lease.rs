@@ -0,0 +1,126 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::bucket_log::BucketLogProvider;
+use crate::crypto::PublicKey;
+use crate::peer::leases;
+use crate::peer::protocol::bidirectional::BidirectionalHandler;
+use crate::peer::protocol::messages::Message;
+use crate::peer::Peer;
+
+/// What the sender wants done with its lease
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum LeaseAction {
+    /// Record, or renew, a lease for the sender lasting `ttl_ms` on our clock
+    Acquire { ttl_ms: u64 },
+    /// Drop the sender's lease
+    Release,
+}
+
+/// Request to record or drop the sender's write lease on a path prefix of a
+/// bucket (see [`crate::peer::leases`])
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaseMessage {
+    pub bucket_id: Uuid,
+    /// Absolute path the lease covers
+    pub prefix: String,
+    pub action: LeaseAction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum LeaseReplyStatus {
+    /// We don't have the bucket, the sender isn't one of its owners, or the
+    /// prefix isn't an absolute path
+    NotFound,
+    /// The lease is recorded until it expires or is released
+    Granted,
+    /// Another owner holds an overlapping lease
+    Held {
+        prefix: String,
+        holder: PublicKey,
+        remaining_ms: u64,
+    },
+    /// The sender's lease, if it had one, is dropped
+    Released,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaseReply {
+    pub bucket_id: Uuid,
+    pub status: LeaseReplyStatus,
+}
+
+/// Lease handler: owners record each other's advisory write leases
+pub struct Lease;
+
+impl BidirectionalHandler for Lease {
+    type Message = LeaseMessage;
+    type Reply = LeaseReply;
+
+    fn wrap_request(request: Self::Message) -> Message {
+        Message::Lease(request)
+    }
+
+    async fn handle_message<L: BucketLogProvider>(
+        peer: &Peer<L>,
+        sender_node_id: &PublicKey,
+        request: &LeaseMessage,
+    ) -> LeaseReply {
+        let bucket_id = request.bucket_id;
+        let not_found = LeaseReply {
+            bucket_id,
+            status: LeaseReplyStatus::NotFound,
+        };
+        let Ok(prefix) = leases::parse_prefix(&request.prefix) else {
+            return not_found;
+        };
+        match leases::is_owner(peer, bucket_id, sender_node_id).await {
+            Ok(true) => {}
+            Ok(false) => return not_found,
+            Err(e) => {
+                tracing::debug!("No lease on bucket {} for peer: {}", bucket_id, e);
+                return not_found;
+            }
+        }
+
+        let status = match request.action {
+            LeaseAction::Acquire { ttl_ms } => match peer.leases().grant(
+                bucket_id,
+                &prefix,
+                *sender_node_id,
+                std::time::Duration::from_millis(ttl_ms),
+            ) {
+                Ok(_) => LeaseReplyStatus::Granted,
+                Err(held) => LeaseReplyStatus::Held {
+                    prefix: held.prefix.to_string_lossy().to_string(),
+                    holder: held.holder,
+                    remaining_ms: held.remaining().as_millis() as u64,
+                },
+            },
+            LeaseAction::Release => {
+                peer.leases().release(bucket_id, &prefix, sender_node_id);
+                LeaseReplyStatus::Released
+            }
+        };
+        LeaseReply { bucket_id, status }
+    }
+
+    async fn handle_reply<L>(
+        _peer: &Peer<L>,
+        recipient_node_id: &PublicKey,
+        reply: &LeaseReply,
+    ) -> Result<()>
+    where
+        L: BucketLogProvider,
+        L::Error: std::error::Error + Send + Sync + 'static,
+    {
+        tracing::debug!(
+            "Peer {} answered lease on bucket {} with {:?}",
+            recipient_node_id.to_hex(),
+            reply.bucket_id,
+            reply.status
+        );
+        Ok(())
+    }
+}
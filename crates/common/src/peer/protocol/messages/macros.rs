@@ -38,6 +38,15 @@ macro_rules! register_handlers {
         }
 
         impl Message {
+            /// Name of the message type, e.g. to look up its timeout
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(
+                        Message::$variant(_) => stringify!($variant),
+                    )*
+                }
+            }
+
             /// Dispatch this message to the appropriate handler
             ///
             /// This method is auto-generated by the register_handlers! macro.
@@ -45,6 +54,7 @@ macro_rules! register_handlers {
                 self,
                 peer: &crate::peer::Peer<L>,
                 sender_node_id: &crate::crypto::PublicKey,
+                format: crate::peer::protocol::wire::WireFormat,
                 send: iroh::endpoint::SendStream,
             ) -> Result<(), iroh::protocol::AcceptError>
             where
@@ -55,7 +65,7 @@ macro_rules! register_handlers {
                     $(
                         Message::$variant(message) => {
                             tracing::debug!(concat!("Dispatching ", stringify!($variant), " message"));
-                            <$handler>::_handle_message(peer, sender_node_id, message, send).await
+                            <$handler>::_handle_message(peer, sender_node_id, message, format, send).await
                         }
                     )*
                 }
@@ -1,12 +1,33 @@
 #[macro_use]
 mod macros;
+pub mod editing;
+pub mod escrow;
+pub mod hello;
+pub mod kem_key;
+pub mod lease;
 pub mod ping;
+pub mod sync_from;
 
+pub use editing::Editing;
+pub use escrow::Escrow;
+pub use hello::Hello;
+pub use kem_key::KemKey;
+pub use lease::Lease;
 pub use ping::Ping;
+pub use sync_from::SyncFrom;
 
 // Register all bidirectional message handlers
-// To add a new message type, just add a line here:
+// To add a new message type, just add a line at the end:
 //   NewMessage(NewMessageHandler),
+// Peers speaking bincode decode variants by position, so never reorder or
+// remove them, and only send a new message to peers announcing the
+// capability for it (see `protocol::version` and `protocol::wire`).
 register_handlers! {
     Ping(Ping),
+    Hello(Hello),
+    SyncFrom(SyncFrom),
+    Lease(Lease),
+    Editing(Editing),
+    KemKey(KemKey),
+    Escrow(Escrow),
 }
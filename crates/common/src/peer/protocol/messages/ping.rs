@@ -15,7 +15,8 @@ use crate::peer::Peer;
 pub struct PingMessage {
     /// The bucket ID to check
     pub bucket_id: Uuid,
-    /// The current link the requesting peer has for this bucket
+    /// The current link the requesting peer has for this bucket, or the
+    /// default link if it follows the bucket but has no versions yet
     pub link: Link,
     /// The height of the link we are responding to
     pub height: u64,
@@ -152,7 +153,7 @@ impl BidirectionalHandler for Ping {
                 );
 
                 // Load our manifest to get all peer IDs from shares
-                let peer_ids = match peer.blobs().get_cbor::<Manifest>(&our_link.hash()).await {
+                let mut peer_ids = match peer.blobs().get_cbor::<Manifest>(&our_link.hash()).await {
                     Ok(manifest) => manifest.get_peer_ids(),
                     Err(e) => {
                         tracing::warn!(
@@ -162,6 +163,11 @@ impl BidirectionalHandler for Ping {
                         vec![*sender_node_id]
                     }
                 };
+                // The sender has the version, even if our shares don't list
+                //  it yet (such as an owner recovered since), so it is asked
+                //  first
+                peer_ids.retain(|id| id != sender_node_id);
+                peer_ids.insert(0, *sender_node_id);
 
                 // Dispatch sync job to background worker
                 use crate::peer::sync::{SyncBucketJob, SyncJob, SyncTarget};
@@ -193,6 +199,9 @@ impl BidirectionalHandler for Ping {
                 tracing::debug!("In sync with peer for bucket {}", ping.bucket_id);
                 // All good, nothing to do
             }
+            PingReplyStatus::NotFound if ping.link == Link::default() => {
+                tracing::debug!("Neither we nor the peer have bucket {}", ping.bucket_id);
+            }
             PingReplyStatus::NotFound => {
                 tracing::debug!(
                     "We don't have bucket {} that peer is asking about",
@@ -257,7 +266,7 @@ impl BidirectionalHandler for Ping {
                 );
 
                 // Load our manifest to get all peer IDs from shares
-                let peer_ids = match peer.logs().head(pong.bucket_id, None).await {
+                let mut peer_ids = match peer.logs().head(pong.bucket_id, None).await {
                     Ok((our_link, _)) => {
                         match peer.blobs().get_cbor::<Manifest>(&our_link.hash()).await {
                             Ok(manifest) => manifest.get_peer_ids(),
@@ -278,6 +287,9 @@ impl BidirectionalHandler for Ping {
                         vec![*recipient_node_id]
                     }
                 };
+                // Likewise the recipient has the version
+                peer_ids.retain(|id| id != recipient_node_id);
+                peer_ids.insert(0, *recipient_node_id);
 
                 // Dispatch sync job to background worker
                 use crate::peer::sync::{SyncBucketJob, SyncJob, SyncTarget};
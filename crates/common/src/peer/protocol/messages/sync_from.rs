@@ -0,0 +1,236 @@
+use std::collections::HashSet;
+
+use anyhow::Result;
+use iroh_blobs::Hash;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::bucket_log::BucketLogProvider;
+use crate::crypto::PublicKey;
+use crate::linked_data::Link;
+use crate::mount::{Manifest, Mount};
+use crate::peer::protocol::bidirectional::BidirectionalHandler;
+use crate::peer::protocol::messages::Message;
+use crate::peer::Peer;
+
+/// Most manifests a delta may list; pullers further behind walk the chain
+pub const MAX_DELTA_MANIFESTS: usize = 1000;
+
+/// Most hashes a pin diff may list; larger diffs are left out of the reply
+pub const MAX_PIN_DIFF: usize = 16 * 1024;
+
+/// Request for the versions of a bucket between a version the sender has
+/// and a later one it wants
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncFromMessage {
+    pub bucket_id: Uuid,
+    /// The sender's head
+    pub head: Link,
+    pub height: u64,
+    /// The version the sender wants to sync to
+    pub target: Link,
+}
+
+/// Hashes pinned by the target version but not by the sender's head, and
+/// the other way around
+///
+/// Peers holding a subtree share are only told of added hashes below their
+/// directory.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PinDiff {
+    pub added: Vec<Hash>,
+    pub removed: Vec<Hash>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SyncFromReplyStatus {
+    /// We don't have the bucket or the target version, or the sender may not
+    /// read the bucket
+    NotFound,
+    /// The sender's head is not an ancestor of the target in our log
+    UnknownHead,
+    /// The target is more than [`MAX_DELTA_MANIFESTS`] versions ahead
+    TooFar,
+    /// The manifests after the sender's head, oldest first and ending with
+    /// the target, and the pin diff if we have the pins of both versions
+    Delta {
+        manifests: Vec<Link>,
+        pins: Option<PinDiff>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncFromReply {
+    pub bucket_id: Uuid,
+    pub status: SyncFromReplyStatus,
+}
+
+/// SyncFrom handler: answers with only what a puller is missing, sparing it
+/// a walk of the manifest chain
+pub struct SyncFrom;
+
+impl BidirectionalHandler for SyncFrom {
+    type Message = SyncFromMessage;
+    type Reply = SyncFromReply;
+
+    fn wrap_request(request: Self::Message) -> Message {
+        Message::SyncFrom(request)
+    }
+
+    async fn handle_message<L: BucketLogProvider>(
+        peer: &Peer<L>,
+        sender_node_id: &PublicKey,
+        request: &SyncFromMessage,
+    ) -> SyncFromReply {
+        let status = match delta(peer, sender_node_id, request).await {
+            Ok(status) => status,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to compute delta of bucket {} for peer {}: {}",
+                    request.bucket_id,
+                    sender_node_id.to_hex(),
+                    e
+                );
+                SyncFromReplyStatus::NotFound
+            }
+        };
+        SyncFromReply {
+            bucket_id: request.bucket_id,
+            status,
+        }
+    }
+
+    async fn handle_reply<L>(
+        _peer: &Peer<L>,
+        recipient_node_id: &PublicKey,
+        reply: &SyncFromReply,
+    ) -> Result<()>
+    where
+        L: BucketLogProvider,
+        L::Error: std::error::Error + Send + Sync + 'static,
+    {
+        tracing::debug!(
+            "Peer {} answered sync of bucket {} with {:?}",
+            recipient_node_id.to_hex(),
+            reply.bucket_id,
+            match &reply.status {
+                SyncFromReplyStatus::Delta { manifests, .. } =>
+                    format!("{} manifest(s)", manifests.len()),
+                status => format!("{:?}", status),
+            }
+        );
+        Ok(())
+    }
+}
+
+/// Walk back from the target to the sender's head through our own blobs
+async fn delta<L: BucketLogProvider>(
+    peer: &Peer<L>,
+    sender_node_id: &PublicKey,
+    request: &SyncFromMessage,
+) -> Result<SyncFromReplyStatus> {
+    let bucket_id = request.bucket_id;
+    let in_log = peer
+        .logs()
+        .has(bucket_id, request.target.clone())
+        .await
+        .map(|heights| !heights.is_empty())
+        .unwrap_or(false);
+    if !in_log {
+        return Ok(SyncFromReplyStatus::NotFound);
+    }
+
+    let target: Manifest = peer.blobs().get_cbor(&request.target.hash()).await?;
+    // Only answer peers that could sync the bucket from us anyway
+    let subtree = target.get_share(sender_node_id).is_none()
+        && target.get_subtree_share(sender_node_id).is_some();
+    if !target.is_published() && !target.get_peer_ids().contains(sender_node_id) && !subtree {
+        return Ok(SyncFromReplyStatus::NotFound);
+    }
+
+    let mut manifests = vec![request.target.clone()];
+    let mut current = target.clone();
+    loop {
+        if current.height() <= request.height {
+            return Ok(SyncFromReplyStatus::UnknownHead);
+        }
+        let Some(previous) = current.previous().clone() else {
+            return Ok(SyncFromReplyStatus::UnknownHead);
+        };
+        if previous == request.head {
+            break;
+        }
+        if manifests.len() >= MAX_DELTA_MANIFESTS {
+            return Ok(SyncFromReplyStatus::TooFar);
+        }
+        current = peer.blobs().get_cbor(&previous.hash()).await?;
+        manifests.push(previous);
+    }
+    manifests.reverse();
+
+    let mut pins = pin_diff(peer, &request.head, &target).await;
+    if subtree {
+        pins = match (pins, reachable(peer, sender_node_id, &request.target).await) {
+            (Some(mut diff), Some(reachable)) => {
+                diff.added.retain(|hash| reachable.contains(hash));
+                Some(diff)
+            }
+            // We can't tell what is below the shared directory
+            _ => None,
+        };
+    }
+    Ok(SyncFromReplyStatus::Delta { manifests, pins })
+}
+
+/// Hashes of the blobs below the directory a peer has a subtree share of,
+/// if we can read the target version
+async fn reachable<L: BucketLogProvider>(
+    peer: &Peer<L>,
+    subtree_peer: &PublicKey,
+    target: &Link,
+) -> Option<HashSet<Hash>> {
+    let mount = Mount::load(target, peer.secret(), peer.blobs())
+        .await
+        .ok()?;
+    let inner = mount.inner().await;
+    // Our own view may be a subtree too, rooted elsewhere
+    if inner.subtree().is_some() {
+        return None;
+    }
+    let path = inner
+        .manifest()
+        .get_subtree_share(subtree_peer)?
+        .path()
+        .to_string();
+    let node = mount.get(std::path::Path::new(&path)).await.ok()?;
+    let hashes = Mount::reachable(&node, peer.blobs()).await.ok()?;
+    Some(hashes.into_iter().collect())
+}
+
+/// The pin diff between the sender's head and the target, if we have the
+/// pins of both and the diff isn't too large to send
+async fn pin_diff<L: BucketLogProvider>(
+    peer: &Peer<L>,
+    head: &Link,
+    target: &Manifest,
+) -> Option<PinDiff> {
+    let head: Manifest = peer.blobs().get_cbor(&head.hash()).await.ok()?;
+    let blobs = peer.blobs();
+    let before: HashSet<Hash> = blobs
+        .read_hash_list(head.pins().hash())
+        .await
+        .ok()?
+        .into_iter()
+        .collect();
+    let after: HashSet<Hash> = blobs
+        .read_hash_list(target.pins().hash())
+        .await
+        .ok()?
+        .into_iter()
+        .collect();
+    let diff = PinDiff {
+        added: after.difference(&before).copied().collect(),
+        removed: before.difference(&after).copied().collect(),
+    };
+    (diff.added.len() + diff.removed.len() <= MAX_PIN_DIFF).then_some(diff)
+}
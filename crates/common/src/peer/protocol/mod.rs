@@ -1,25 +1,32 @@
-use anyhow::anyhow;
 use futures::future::BoxFuture;
 use iroh::endpoint::Connection;
 use iroh::protocol::{AcceptError, ProtocolHandler};
+use tracing::Instrument;
 
 use crate::crypto::PublicKey;
 
 use super::peer_inner::Peer;
 
 pub mod bidirectional;
+pub mod limits;
 pub mod messages;
-
-use messages::Message;
+pub mod timeouts;
+pub mod trace_context;
+pub mod version;
+pub mod wire;
 
 // Re-export for external users implementing custom handlers
 #[allow(unused_imports)]
 pub use bidirectional::BidirectionalHandler;
+pub use limits::{ConnectionLimits, ProtocolMetrics};
 pub use messages::ping::{Ping, PingMessage, PingReplyStatus};
+pub use timeouts::{ProtocolError, ProtocolTimeouts};
+pub use version::{capability, ProtocolInfo, PROTOCOL_VERSION};
 
 // TODO ( amiller68): migrate the alpn, idt there's a great
 //  reason to have an iroh prefix, nthis is not a n0 computer project
-/// ALPN identifier for the JAX protocol
+/// ALPN identifier for the JAX protocol. Kept across protocol versions, which
+/// are negotiated in-band (see [`version`]).
 pub const ALPN: &[u8] = b"/iroh-jax/1";
 
 /// Generic connection handler that processes all incoming messages
@@ -28,49 +35,76 @@ pub const ALPN: &[u8] = b"/iroh-jax/1";
 /// - Accepting bidirectional streams
 /// - Reading and deserializing messages
 /// - Dispatching to appropriate handlers
+/// - Timeouts: reading a request is bounded by the request timeout, handling
+///   it by the timeout of its message type, and both stop on shutdown
 /// - Error handling
-async fn handle_connection<L>(peer: Peer<L>, conn: Connection) -> Result<(), AcceptError>
+async fn handle_connection<L>(
+    peer: Peer<L>,
+    sender_node_id: PublicKey,
+    conn: Connection,
+) -> Result<(), AcceptError>
 where
     L: crate::bucket_log::BucketLogProvider,
     L::Error: std::error::Error + Send + Sync + 'static,
 {
-    // determine the sender
-    let sender_node_id: PublicKey = conn
-        .remote_node_id()
-        .map_err(|e| {
-            tracing::error!("failed to get remote node id: {}", e);
-            AcceptError::from(e)
-        })?
-        .into();
-    // Accept bidirectional stream
-    let (send, mut recv) = conn.accept_bi().await.map_err(|e| {
-        tracing::error!("failed to accept bidirectional stream: {}", e);
-        AcceptError::from(e)
-    })?;
-    tracing::debug!("bidirectional stream accepted");
+    // Accept bidirectional stream and read the message (1MB limit for
+    // non-blob data), giving up on senders that never finish it
+    let read_operation = || format!("reading a request from peer {}", sender_node_id.to_hex());
+    let (send, message_bytes) = peer
+        .bounded(read_operation, peer.protocol_timeouts().request, async {
+            let (send, mut recv) = conn.accept_bi().await.map_err(|e| {
+                tracing::error!("failed to accept bidirectional stream: {}", e);
+                anyhow::Error::from(e)
+            })?;
+            tracing::debug!("bidirectional stream accepted");
 
-    // Read message (1MB limit for non-blob data)
-    let message_bytes = recv.read_to_end(1024 * 1024).await.map_err(|e| {
-        tracing::error!("failed to read message: {}", e);
-        AcceptError::from(std::io::Error::other(e))
-    })?;
+            let message_bytes = recv.read_to_end(1024 * 1024).await.map_err(|e| {
+                tracing::error!("failed to read message: {}", e);
+                anyhow::Error::from(e)
+            })?;
+            Ok((send, message_bytes))
+        })
+        .await
+        .map_err(into_accept_error)?;
 
-    // Deserialize message
-    let message: Message = bincode::deserialize(&message_bytes).map_err(|e| {
+    // Deserialize message, in whichever format the sender used
+    let incoming = wire::decode_message(&message_bytes).map_err(|e| {
         tracing::error!("Failed to deserialize message: {}", e);
         tracing::error!(
             "First 20 bytes of received data: {:?}",
             &message_bytes[..message_bytes.len().min(20)]
         );
-        let err: Box<dyn std::error::Error + Send + Sync> =
-            anyhow!("failed to deserialize message: {}", e).into();
-        AcceptError::from(err)
+        AcceptError::from(std::io::Error::other(e))
     })?;
 
-    // Dispatch to appropriate handler
-    message.dispatch(&peer, &sender_node_id, send).await?;
+    let span = tracing::info_span!("peer.handle_message", peer = %sender_node_id.to_hex());
+    trace_context::set_parent(&span, incoming.trace);
+
+    // Dispatch to appropriate handler, within the timeout of the message type
+    let name = incoming.message.name();
+    let handle_operation = || format!("{} from peer {}", name, sender_node_id.to_hex());
+    peer.bounded(
+        handle_operation,
+        peer.protocol_timeouts().for_message(name),
+        async {
+            incoming
+                .message
+                .dispatch(&peer, &sender_node_id, incoming.format, send)
+                .await
+                .map_err(anyhow::Error::from)
+        },
+    )
+    .instrument(span)
+    .await
+    .map_err(|e| {
+        tracing::warn!("failed to handle message: {}", e);
+        into_accept_error(e)
+    })
+}
 
-    Ok(())
+fn into_accept_error(error: anyhow::Error) -> AcceptError {
+    let error: Box<dyn std::error::Error + Send + Sync> = error.into();
+    AcceptError::from(error)
 }
 
 // This allows the router to accept connections for this protocol
@@ -82,6 +116,34 @@ where
     #[allow(refining_impl_trait)]
     fn accept(&self, conn: Connection) -> BoxFuture<'static, Result<(), AcceptError>> {
         let peer = self.clone();
-        Box::pin(handle_connection(peer, conn))
+        Box::pin(async move {
+            // determine the sender
+            let sender_node_id: PublicKey = conn
+                .remote_node_id()
+                .map_err(|e| {
+                    tracing::error!("failed to get remote node id: {}", e);
+                    AcceptError::from(e)
+                })?
+                .into();
+            if peer.faults().is_partitioned(&sender_node_id) {
+                conn.close(0u32.into(), b"partitioned");
+                return Ok(());
+            }
+            // Turn the request away right away if over the limits, so the
+            // handler tasks of a misbehaving peer can't pile up
+            let _permit = match peer.admit(sender_node_id) {
+                Ok(permit) => permit,
+                Err(rejection) => {
+                    tracing::debug!(
+                        "rejecting request from peer {}: {:?}",
+                        sender_node_id.to_hex(),
+                        rejection
+                    );
+                    conn.close(limits::BUSY_CODE.into(), rejection.reason());
+                    return Ok(());
+                }
+            };
+            handle_connection(peer, sender_node_id, conn).await
+        })
     }
 }
@@ -0,0 +1,216 @@
+//! Timeouts and cancellation of peer requests
+//!
+//! Every exchange with a peer is bounded: connecting, the request/response of
+//! each message type, and each blob downloaded during a sync. Everything a
+//! peer has in flight is also cancelled once it shuts down, so a hung peer
+//! can't wedge a sync worker or hold up shutdown. Failures are returned as
+//! [`ProtocolError`]s, which tell a peer that can't be reached from one that
+//! stopped answering.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::watch;
+
+/// How long connecting to a peer may take
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How long a request may take to be answered, unless its message type has
+/// its own timeout
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long downloading a single blob from peers may take
+pub const DEFAULT_DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Timeouts of requests to peers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtocolTimeouts {
+    /// Connecting to a peer
+    pub connect: Duration,
+    /// Sending a request and reading the reply, and on the responder side
+    /// reading and answering a request
+    pub request: Duration,
+    /// Request timeouts of single message types, by variant name (e.g.
+    /// `Ping`), overriding `request`
+    pub messages: HashMap<String, Duration>,
+    /// Downloading one blob during a sync
+    pub download: Duration,
+}
+
+impl Default for ProtocolTimeouts {
+    fn default() -> Self {
+        Self {
+            connect: DEFAULT_CONNECT_TIMEOUT,
+            request: DEFAULT_REQUEST_TIMEOUT,
+            // Answered without any I/O, a peer taking longer is hung
+            messages: HashMap::from([("Hello".to_string(), Duration::from_secs(10))]),
+            download: DEFAULT_DOWNLOAD_TIMEOUT,
+        }
+    }
+}
+
+impl ProtocolTimeouts {
+    /// The request timeout of a message type
+    pub fn for_message(&self, name: &str) -> Duration {
+        self.messages.get(name).copied().unwrap_or(self.request)
+    }
+
+    /// Set the request timeout of a message type
+    pub fn with_message(mut self, name: &str, timeout: Duration) -> Self {
+        self.messages.insert(name.to_string(), timeout);
+        self
+    }
+}
+
+/// Why a request to a peer failed
+#[derive(Debug, thiserror::Error)]
+pub enum ProtocolError {
+    /// The peer could not be reached
+    #[error("peer {peer} is offline: {reason}")]
+    Offline { peer: String, reason: String },
+    /// The peer was reached but did not finish in time
+    #[error("{operation} timed out after {after:?}")]
+    Timeout { operation: String, after: Duration },
+    /// The peer is shutting down
+    #[error("{operation} was cancelled by shutdown")]
+    Cancelled { operation: String },
+    /// The peer turned the request away because it is over its limits
+    #[error("peer {peer} is busy: {reason}")]
+    Busy { peer: String, reason: String },
+}
+
+impl ProtocolError {
+    /// The protocol error an error was caused by, if any
+    pub fn of(error: &anyhow::Error) -> Option<&ProtocolError> {
+        error.chain().find_map(|e| e.downcast_ref())
+    }
+
+    pub fn is_timeout(error: &anyhow::Error) -> bool {
+        matches!(Self::of(error), Some(ProtocolError::Timeout { .. }))
+    }
+
+    pub fn is_offline(error: &anyhow::Error) -> bool {
+        matches!(Self::of(error), Some(ProtocolError::Offline { .. }))
+    }
+
+    pub fn is_busy(error: &anyhow::Error) -> bool {
+        matches!(Self::of(error), Some(ProtocolError::Busy { .. }))
+    }
+}
+
+/// Cancellation signal shared by all clones of a peer
+#[derive(Debug, Clone)]
+pub(crate) struct Cancellation(Arc<watch::Sender<bool>>);
+
+impl Default for Cancellation {
+    fn default() -> Self {
+        Self(Arc::new(watch::Sender::new(false)))
+    }
+}
+
+impl Cancellation {
+    pub(crate) fn cancel(&self) {
+        self.0.send_replace(true);
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        *self.0.borrow()
+    }
+
+    /// Resolves once cancelled
+    pub(crate) async fn cancelled(&self) {
+        let mut rx = self.0.subscribe();
+        // The sender lives as long as self, so this only returns once set
+        let _ = rx.wait_for(|cancelled| *cancelled).await;
+    }
+
+    /// Run `future` unless cancelled first
+    pub(crate) async fn cancellable<T, F>(
+        &self,
+        operation: impl FnOnce() -> String,
+        future: F,
+    ) -> anyhow::Result<T>
+    where
+        F: Future<Output = anyhow::Result<T>>,
+    {
+        tokio::select! {
+            biased;
+            _ = self.cancelled() => Err(ProtocolError::Cancelled { operation: operation() }.into()),
+            result = future => result,
+        }
+    }
+
+    /// Run `future` unless cancelled first or `after` elapses
+    pub(crate) async fn bounded<T, F>(
+        &self,
+        operation: impl Fn() -> String,
+        after: Duration,
+        future: F,
+    ) -> anyhow::Result<T>
+    where
+        F: Future<Output = anyhow::Result<T>>,
+    {
+        let timed = async {
+            match tokio::time::timeout(after, future).await {
+                Ok(result) => result,
+                Err(_) => Err(ProtocolError::Timeout {
+                    operation: operation(),
+                    after,
+                }
+                .into()),
+            }
+        };
+        self.cancellable(&operation, timed).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_bounded_tells_timeouts_from_cancellation() {
+        let cancellation = Cancellation::default();
+        let ok = cancellation
+            .bounded(|| "quick".to_string(), Duration::from_secs(1), async {
+                Ok(1)
+            })
+            .await;
+        assert_eq!(ok.unwrap(), 1);
+
+        let hung = cancellation
+            .bounded(
+                || "hung".to_string(),
+                Duration::from_millis(10),
+                std::future::pending::<anyhow::Result<()>>(),
+            )
+            .await
+            .unwrap_err();
+        assert!(ProtocolError::is_timeout(&hung));
+        assert!(!ProtocolError::is_offline(&hung));
+
+        cancellation.cancel();
+        let cancelled = cancellation
+            .bounded(
+                || "late".to_string(),
+                Duration::from_secs(60),
+                std::future::pending::<anyhow::Result<()>>(),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            ProtocolError::of(&cancelled),
+            Some(ProtocolError::Cancelled { .. })
+        ));
+    }
+
+    #[test]
+    fn test_message_timeouts() {
+        let timeouts = ProtocolTimeouts::default().with_message("Ping", Duration::from_secs(5));
+        assert_eq!(timeouts.for_message("Ping"), Duration::from_secs(5));
+        assert_eq!(timeouts.for_message("Hello"), Duration::from_secs(10));
+        assert_eq!(timeouts.for_message("Invite"), DEFAULT_REQUEST_TIMEOUT);
+    }
+}
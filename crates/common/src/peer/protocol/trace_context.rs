@@ -0,0 +1,126 @@
+//! Trace context propagation across the peer protocol
+//!
+//! The sender's trace context travels as W3C trace context headers. CBOR
+//! frames carry them in a field (see [`super::wire`]); bincode messages have
+//! them appended as a trailer: a magic marker followed by the bincode-encoded
+//! headers. bincode ignores trailing bytes, so peers that predate the trailer
+//! still decode the message, and messages from those peers simply arrive
+//! without one.
+//!
+//! Injection and extraction use the global OpenTelemetry propagator. Until an
+//! application installs one (e.g. when trace export is enabled) no trailer is
+//! written.
+
+use std::collections::HashMap;
+
+use opentelemetry::propagation::{Extractor, Injector};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// Marks the start of the trace context trailer
+const TRAILER_MAGIC: &[u8] = b"\0jax-trace";
+
+struct Carrier(HashMap<String, String>);
+
+impl Injector for Carrier {
+    fn set(&mut self, key: &str, value: String) {
+        self.0.insert(key.to_string(), value);
+    }
+}
+
+impl Extractor for Carrier {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(String::as_str)
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(String::as_str).collect()
+    }
+}
+
+/// The trace context of `span` as W3C trace context headers.
+///
+/// Empty when there is nothing to propagate.
+pub fn inject(span: &tracing::Span) -> HashMap<String, String> {
+    let mut carrier = Carrier(HashMap::new());
+    opentelemetry::global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&span.context(), &mut carrier)
+    });
+    carrier.0
+}
+
+/// Make `span` a child of the trace context in `headers`.
+pub fn set_parent(span: &tracing::Span, headers: HashMap<String, String>) {
+    if headers.is_empty() {
+        return;
+    }
+    let carrier = Carrier(headers);
+    let parent =
+        opentelemetry::global::get_text_map_propagator(|propagator| propagator.extract(&carrier));
+    let _ = span.set_parent(parent);
+}
+
+/// Encode trace context headers as a message trailer.
+///
+/// Returns an empty trailer when there are no headers.
+pub fn encode_trailer(headers: &HashMap<String, String>) -> Vec<u8> {
+    if headers.is_empty() {
+        return Vec::new();
+    }
+
+    match bincode::serialize(headers) {
+        Ok(encoded) => [TRAILER_MAGIC, &encoded].concat(),
+        Err(e) => {
+            tracing::debug!("failed to encode trace context: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Decode the trace context headers of a message trailer.
+///
+/// Returns None for unknown or malformed trailers.
+pub fn decode_trailer(trailer: &[u8]) -> Option<HashMap<String, String>> {
+    let encoded = trailer.strip_prefix(TRAILER_MAGIC)?;
+    bincode::deserialize(encoded).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use opentelemetry::trace::{TraceContextExt, TracerProvider as _};
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+    use opentelemetry_sdk::trace::SdkTracerProvider;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+
+    fn trace_id(span: &tracing::Span) -> opentelemetry::trace::TraceId {
+        span.context().span().span_context().trace_id()
+    }
+
+    #[test]
+    fn test_trailer_round_trip() {
+        opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+        let provider = SdkTracerProvider::builder().build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let sender = tracing::info_span!("send");
+            let trailer = encode_trailer(&inject(&sender));
+            assert!(trailer.starts_with(TRAILER_MAGIC));
+
+            // Peers without trace support still decode the message
+            let bytes = [bincode::serialize("hello").unwrap(), trailer.clone()].concat();
+            assert_eq!(bincode::deserialize::<String>(&bytes).unwrap(), "hello");
+
+            let receiver = tracing::info_span!("receive");
+            set_parent(&receiver, decode_trailer(&trailer).unwrap());
+            assert_eq!(trace_id(&receiver), trace_id(&sender));
+
+            let unrelated = tracing::info_span!("unrelated");
+            assert!(decode_trailer(b"garbage").is_none());
+            set_parent(&unrelated, HashMap::new());
+            assert_ne!(trace_id(&unrelated), trace_id(&sender));
+        });
+    }
+}
@@ -0,0 +1,195 @@
+//! Protocol versions and capabilities
+//!
+//! The ALPN stays `/iroh-jax/1` so peers of every version can connect.
+//! Versions are negotiated in-band instead: before talking to a peer we send
+//! it a [`Hello`](super::messages::hello::Hello) with our version and
+//! capabilities, and it answers with its own. Peers that predate negotiation
+//! (version 1) fail to decode the hello and close the stream, which we take
+//! as version 1 with no capabilities.
+//!
+//! Messages are bincode-encoded enums, so a message variant or field added
+//! later can't be decoded by older peers. New behavior is therefore gated on
+//! a capability, and callers fall back to what the peer supports (see
+//! [`capability`]). The negotiated protocol is cached per peer for
+//! [`NEGOTIATION_TTL`], so upgraded peers are noticed.
+
+use std::collections::BTreeSet;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+
+use crate::bucket_log::BucketLogProvider;
+use crate::crypto::PublicKey;
+use crate::peer::Peer;
+
+use super::bidirectional::BidirectionalHandler;
+use super::limits;
+use super::messages::hello::{Hello, HelloMessage, HelloReply};
+use super::messages::Message;
+use super::wire::{self, WireFormat};
+use super::ProtocolError;
+
+/// Version of the protocol this node speaks
+pub const PROTOCOL_VERSION: u32 = 11;
+
+/// Version of peers that predate version negotiation
+pub const LEGACY_PROTOCOL_VERSION: u32 = 1;
+
+/// How long a negotiated protocol is trusted before asking the peer again
+pub const NEGOTIATION_TTL: Duration = Duration::from_secs(600);
+
+/// Capabilities a peer can announce. Unknown capabilities are ignored, so
+/// newer peers can announce more.
+pub mod capability {
+    /// Answers pings for buckets it is followed on without a share, and
+    /// treats a ping with an empty link as "tell me where you are"
+    pub const FOLLOW: &str = "follow";
+    /// Reads the sender's trace context from the message trailer
+    pub const TRACE_CONTEXT: &str = "trace-context";
+    /// Accepts messages as versioned DAG-CBOR frames (see [`super::super::wire`])
+    pub const CBOR_WIRE: &str = "cbor-wire";
+    /// Answers `SyncFrom` requests with the manifests a puller is missing
+    /// (see [`super::super::messages::sync_from`])
+    pub const DELTA_SYNC: &str = "delta-sync";
+    /// Records other owners' advisory write leases (see
+    /// [`crate::peer::leases`])
+    pub const LEASES: &str = "leases";
+    /// Records which paths other owners are editing (see
+    /// [`crate::peer::editing`])
+    pub const EDITING: &str = "editing";
+    /// Hands out its ML-KEM key and reads shares wrapped with it (see
+    /// [`crate::peer::share_keys`])
+    pub const PQ_SHARES: &str = "pq-shares";
+    /// Holds shards of other owners' recovery identities (see
+    /// [`crate::peer::escrow`])
+    pub const ESCROW: &str = "escrow";
+    /// Reads buckets through a share of a single directory, and serves
+    /// peers holding one only what is below it (see
+    /// [`crate::mount::SubtreeShare`])
+    pub const SUBTREE_SHARES: &str = "subtree-shares";
+    /// Reads buckets through a share of a temporary identity (see
+    /// [`crate::mount::EphemeralShare`])
+    pub const EPHEMERAL_SHARES: &str = "ephemeral-shares";
+    /// Reads buckets whose directories reference other buckets (see
+    /// [`crate::mount::BucketRef`])
+    pub const BUCKET_REFERENCES: &str = "bucket-references";
+
+    /// Everything this node supports
+    pub fn all() -> Vec<String> {
+        vec![
+            FOLLOW.to_string(),
+            TRACE_CONTEXT.to_string(),
+            CBOR_WIRE.to_string(),
+            DELTA_SYNC.to_string(),
+            LEASES.to_string(),
+            EDITING.to_string(),
+            PQ_SHARES.to_string(),
+            ESCROW.to_string(),
+            SUBTREE_SHARES.to_string(),
+            EPHEMERAL_SHARES.to_string(),
+            BUCKET_REFERENCES.to_string(),
+        ]
+    }
+}
+
+/// What a peer told us it speaks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtocolInfo {
+    /// Protocol version used with the peer: the lower of both versions
+    pub version: u32,
+    /// Capabilities the peer announced
+    pub capabilities: BTreeSet<String>,
+    negotiated_at: Instant,
+}
+
+impl ProtocolInfo {
+    pub(crate) fn from_hello(version: u32, capabilities: Vec<String>) -> Self {
+        Self {
+            version: version.min(PROTOCOL_VERSION),
+            capabilities: capabilities.into_iter().collect(),
+            negotiated_at: Instant::now(),
+        }
+    }
+
+    /// A peer that predates negotiation
+    pub fn legacy() -> Self {
+        Self::from_hello(LEGACY_PROTOCOL_VERSION, Vec::new())
+    }
+
+    pub fn supports(&self, capability: &str) -> bool {
+        self.capabilities.contains(capability)
+    }
+
+    pub(crate) fn is_fresh(&self) -> bool {
+        self.negotiated_at.elapsed() < NEGOTIATION_TTL
+    }
+}
+
+/// Our side of the handshake
+pub(crate) fn hello() -> HelloMessage {
+    HelloMessage {
+        version: PROTOCOL_VERSION,
+        capabilities: capability::all(),
+    }
+}
+
+/// Find out which protocol a peer speaks, asking it unless a recent answer
+/// is cached
+///
+/// Fails with a [`ProtocolError`] if the peer can't be reached, is busy or
+/// doesn't answer in time. A peer that rejects the hello is recorded as a
+/// legacy peer.
+pub(crate) async fn negotiate<L>(peer: &Peer<L>, node_id: &PublicKey) -> Result<ProtocolInfo>
+where
+    L: BucketLogProvider,
+    L::Error: std::error::Error + Send + Sync + 'static,
+{
+    if let Some(info) = peer.peer_protocol(node_id).filter(ProtocolInfo::is_fresh) {
+        return Ok(info);
+    }
+
+    let conn = peer.connect(node_id).await?;
+
+    // A peer that doesn't answer in time is hung, and one that turns the
+    // connection away is busy, not legacy: legacy peers close the stream
+    // right away
+    let timeout = peer.protocol_timeouts().for_message("Hello");
+    let operation = || format!("Hello request to peer {}", node_id.to_hex());
+    match peer
+        .bounded(operation, timeout, exchange_hello(&conn))
+        .await
+        .map_err(|e| limits::or_busy(e, &conn, node_id))
+    {
+        Ok(reply) => Hello::handle_reply(peer, node_id, &reply).await?,
+        Err(e) if ProtocolError::of(&e).is_some() => return Err(e),
+        Err(e) => {
+            tracing::debug!(
+                "Peer {} did not answer the hello, assuming protocol v{}: {}",
+                node_id.to_hex(),
+                LEGACY_PROTOCOL_VERSION,
+                e
+            );
+            peer.record_protocol(*node_id, ProtocolInfo::legacy());
+        }
+    }
+    conn.close(0u32.into(), b"negotiated");
+
+    peer.peer_protocol(node_id)
+        .ok_or_else(|| anyhow!("protocol of peer {} was not recorded", node_id.to_hex()))
+}
+
+/// Send our hello over an open connection and read the reply
+///
+/// The hello is always bincode: we don't know yet what else the peer reads.
+async fn exchange_hello(conn: &iroh::endpoint::Connection) -> Result<HelloReply> {
+    let (mut send, mut recv) = conn.open_bi().await?;
+    let request_bytes = wire::encode_message(
+        WireFormat::Bincode,
+        &Message::Hello(hello()),
+        &tracing::Span::none(),
+    )?;
+    send.write_all(&request_bytes).await?;
+    send.finish()?;
+    let reply_bytes = recv.read_to_end(1024 * 1024).await?;
+    Ok(wire::decode_reply(WireFormat::Bincode, &reply_bytes)?)
+}
@@ -0,0 +1,225 @@
+//! Wire format of protocol messages
+//!
+//! Messages used to be raw bincode: compact, but not self-describing, so a
+//! new field or a reordered variant breaks every peer that doesn't know about
+//! it. They are now sent as frames of DAG-CBOR, the encoding manifests use:
+//!
+//! ```text
+//! "jax" <wire version: u8> <DAG-CBOR frame { "body": ..., "trace": {...} }>
+//! ```
+//!
+//! Message variants and fields are encoded by name, so fields added with
+//! `#[serde(default)]` are readable by older peers and ignored by them, and
+//! the version byte leaves room for changing the framing itself.
+//!
+//! Compatibility: bincode messages start with their variant index, a little
+//! endian `u32`, so they never start with the frame magic. Responders accept
+//! both formats and reply in the one they were asked in. Initiators only use
+//! CBOR with peers announcing [`capability::CBOR_WIRE`], and the
+//! [`Hello`](super::messages::hello::Hello) handshake itself is always sent as
+//! bincode so that peers from before this format can answer it.
+
+use std::collections::HashMap;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use super::messages::Message;
+use super::trace_context;
+use super::version::{capability, ProtocolInfo};
+
+/// Marks a framed message
+const FRAME_MAGIC: &[u8] = b"jax";
+
+/// Version of the framing after the magic
+pub const WIRE_VERSION: u8 = 1;
+
+/// How a message or reply is encoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WireFormat {
+    /// Raw bincode, understood by every peer
+    Bincode,
+    /// Versioned DAG-CBOR frames
+    Cbor,
+}
+
+impl WireFormat {
+    /// The format to talk to a peer in, given what it announced. Peers we
+    /// haven't negotiated with get bincode.
+    pub fn for_peer(protocol: Option<&ProtocolInfo>) -> Self {
+        match protocol {
+            Some(protocol) if protocol.supports(capability::CBOR_WIRE) => WireFormat::Cbor,
+            _ => WireFormat::Bincode,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Frame<T> {
+    body: T,
+    /// W3C trace context headers of the sender
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    trace: HashMap<String, String>,
+}
+
+/// A decoded incoming message
+#[derive(Debug)]
+pub struct Incoming {
+    /// Format the message arrived in, and the reply should be sent in
+    pub format: WireFormat,
+    pub message: Message,
+    /// Trace context of the sender, empty if it sent none
+    pub trace: HashMap<String, String>,
+}
+
+fn encode<T: Serialize>(
+    format: WireFormat,
+    body: &T,
+    trace: HashMap<String, String>,
+) -> Result<Vec<u8>, WireError> {
+    match format {
+        WireFormat::Bincode => {
+            let mut bytes = bincode::serialize(body)?;
+            bytes.extend(trace_context::encode_trailer(&trace));
+            Ok(bytes)
+        }
+        WireFormat::Cbor => {
+            let frame = serde_ipld_dagcbor::to_vec(&Frame { body, trace })
+                .map_err(|e| WireError::Cbor(e.to_string()))?;
+            Ok([FRAME_MAGIC, &[WIRE_VERSION], &frame].concat())
+        }
+    }
+}
+
+fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<(WireFormat, Frame<T>), WireError> {
+    let Some(framed) = bytes.strip_prefix(FRAME_MAGIC) else {
+        // bincode ignores trailing bytes; whatever follows the body is the
+        // sender's trace context
+        let mut remaining = bytes;
+        let body = bincode::deserialize_from(&mut remaining)?;
+        let trace = trace_context::decode_trailer(remaining).unwrap_or_default();
+        return Ok((WireFormat::Bincode, Frame { body, trace }));
+    };
+    match framed.split_first() {
+        Some((&WIRE_VERSION, frame)) => {
+            let frame = serde_ipld_dagcbor::from_slice(frame)
+                .map_err(|e| WireError::Cbor(e.to_string()))?;
+            Ok((WireFormat::Cbor, frame))
+        }
+        Some((version, _)) => Err(WireError::UnsupportedVersion(*version)),
+        None => Err(WireError::Truncated),
+    }
+}
+
+/// Encode a message, carrying the trace context of `span`
+pub fn encode_message(
+    format: WireFormat,
+    message: &Message,
+    span: &tracing::Span,
+) -> Result<Vec<u8>, WireError> {
+    encode(format, message, trace_context::inject(span))
+}
+
+/// Decode a message in either format
+pub fn decode_message(bytes: &[u8]) -> Result<Incoming, WireError> {
+    let (format, frame) = decode::<Message>(bytes)?;
+    Ok(Incoming {
+        format,
+        message: frame.body,
+        trace: frame.trace,
+    })
+}
+
+/// Encode a reply in the format of the message it answers
+pub fn encode_reply<T: Serialize>(format: WireFormat, reply: &T) -> Result<Vec<u8>, WireError> {
+    encode(format, reply, HashMap::new())
+}
+
+/// Decode a reply, expecting the format the message was sent in
+pub fn decode_reply<T: DeserializeOwned>(format: WireFormat, bytes: &[u8]) -> Result<T, WireError> {
+    let (got, frame) = decode(bytes)?;
+    if got != format {
+        return Err(WireError::UnexpectedFormat(got));
+    }
+    Ok(frame.body)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum WireError {
+    #[error("bincode error: {0}")]
+    Bincode(#[from] bincode::Error),
+    #[error("cbor error: {0}")]
+    Cbor(String),
+    #[error("unsupported wire version {0}")]
+    UnsupportedVersion(u8),
+    #[error("frame ends before its version")]
+    Truncated,
+    #[error("reply arrived as {0:?}")]
+    UnexpectedFormat(WireFormat),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::linked_data::Link;
+    use crate::peer::protocol::messages::ping::{PingMessage, PingReply};
+
+    fn ping() -> Message {
+        Message::Ping(PingMessage {
+            bucket_id: uuid::Uuid::new_v4(),
+            link: Link::default(),
+            height: 7,
+        })
+    }
+
+    #[test]
+    fn test_round_trip_in_both_formats() {
+        for format in [WireFormat::Bincode, WireFormat::Cbor] {
+            let message = ping();
+            let bytes = encode_message(format, &message, &tracing::Span::none()).unwrap();
+            let incoming = decode_message(&bytes).unwrap();
+            assert_eq!(incoming.format, format);
+            assert_eq!(format!("{:?}", incoming.message), format!("{:?}", message));
+
+            let reply = PingReply::ahead(uuid::Uuid::new_v4(), Link::default(), 3);
+            let bytes = encode_reply(format, &reply).unwrap();
+            let decoded: PingReply = decode_reply(format, &bytes).unwrap();
+            assert_eq!(decoded.status, reply.status);
+        }
+    }
+
+    #[test]
+    fn test_legacy_messages_are_not_framed() {
+        // What peers from before framing send
+        let legacy = bincode::serialize(&ping()).unwrap();
+        assert!(!legacy.starts_with(FRAME_MAGIC));
+        assert_eq!(decode_message(&legacy).unwrap().format, WireFormat::Bincode);
+
+        let mut future = encode_message(WireFormat::Cbor, &ping(), &tracing::Span::none()).unwrap();
+        future[FRAME_MAGIC.len()] = WIRE_VERSION + 1;
+        assert!(matches!(
+            decode_message(&future),
+            Err(WireError::UnsupportedVersion(_))
+        ));
+    }
+
+    #[test]
+    fn test_frames_ignore_unknown_fields() {
+        // A newer peer adding a field to the frame
+        #[derive(Serialize)]
+        struct NewerFrame<'a> {
+            body: &'a Message,
+            trace: HashMap<String, String>,
+            priority: u8,
+        }
+        let message = ping();
+        let frame = serde_ipld_dagcbor::to_vec(&NewerFrame {
+            body: &message,
+            trace: HashMap::new(),
+            priority: 1,
+        })
+        .unwrap();
+        let bytes = [FRAME_MAGIC, &[WIRE_VERSION], &frame].concat();
+        assert!(decode_message(&bytes).is_ok());
+    }
+}
@@ -0,0 +1,104 @@
+//! Negotiating post-quantum shares with the peers of a bucket
+//!
+//! Owner shares can be wrapped with ML-KEM-768 as well as X25519 (see
+//! [`KeyWrap::X25519MlKem768`](crate::crypto::KeyWrap::X25519MlKem768)), so
+//! that a bucket secret recorded today can't be unwrapped by whoever breaks
+//! X25519 later. Wrapping to a peer takes its ML-KEM key, which peers
+//! announcing [`capability::PQ_SHARES`](super::protocol::capability::PQ_SHARES)
+//! hand out on request.
+//!
+//! The choice is all-or-nothing per bucket: every peer holding a share reads
+//! the whole manifest, and peers that predate hybrid shares can't decode one
+//! that contains any. So owners only get ML-KEM keys when every owner and
+//! mirror of the bucket announces the capability and answers with its key;
+//! otherwise all shares stay, or go back to, classic X25519.
+//!
+//! Owners that don't answer keep the ML-KEM key already recorded for them.
+//! Recovery identities (see [`super::escrow`]) rely on this: they have no
+//! node to ask, and whoever rebuilds one reads hybrid shares.
+
+use crate::bucket_log::BucketLogProvider;
+use crate::crypto::{KemPublicKey, PublicKey};
+use crate::mount::{Mount, MountError, PrincipalRole};
+
+use super::protocol::bidirectional::BidirectionalHandler;
+use super::protocol::capability;
+use super::protocol::messages::kem_key::{KemKey as KemKeyHandler, KemKeyMessage};
+use super::Peer;
+
+/// The ML-KEM key of a peer, or None if it doesn't hand one out or can't be
+/// reached
+pub(crate) async fn kem_key<L>(peer: &Peer<L>, node_id: &PublicKey) -> Option<KemPublicKey>
+where
+    L: BucketLogProvider,
+    L::Error: std::error::Error + Send + Sync + 'static,
+{
+    if *node_id == peer.secret().public() {
+        return Some(KemPublicKey::of(peer.secret()));
+    }
+
+    match peer.protocol(node_id).await {
+        Ok(protocol) if protocol.supports(capability::PQ_SHARES) => {}
+        Ok(_) => {
+            tracing::debug!("Peer {} predates hybrid shares", node_id.to_hex());
+            return None;
+        }
+        Err(e) => {
+            tracing::debug!("No ML-KEM key for peer {}: {}", node_id.to_hex(), e);
+            return None;
+        }
+    }
+    match KemKeyHandler::send(peer, node_id, KemKeyMessage {}).await {
+        Ok(reply) => Some(reply.kem_key),
+        Err(e) => {
+            tracing::debug!(
+                "Peer {} did not send its ML-KEM key: {}",
+                node_id.to_hex(),
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Give every owner of a mount an ML-KEM key if all of its peers read hybrid
+/// shares, and clear them otherwise. Takes effect on the next save.
+///
+/// Returns whether owner shares will be hybrid.
+pub(crate) async fn negotiate<L>(peer: &Peer<L>, mount: &mut Mount) -> Result<bool, MountError>
+where
+    L: BucketLogProvider,
+    L::Error: std::error::Error + Send + Sync + 'static,
+{
+    let principals: Vec<(PublicKey, PrincipalRole, Option<KemPublicKey>)> = mount
+        .inner()
+        .await
+        .manifest()
+        .shares()
+        .values()
+        .map(|share| {
+            (
+                share.principal().identity,
+                share.role().clone(),
+                share.kem_key().cloned(),
+            )
+        })
+        .collect();
+
+    let mut owner_keys = Vec::new();
+    let mut hybrid = true;
+    for (identity, role, recorded) in principals {
+        let kem_key = kem_key(peer, &identity).await.or(recorded);
+        hybrid &= kem_key.is_some();
+        if role == PrincipalRole::Owner {
+            owner_keys.push((identity, kem_key));
+        }
+    }
+
+    for (owner, kem_key) in owner_keys {
+        mount
+            .set_kem_key(&owner, kem_key.filter(|_| hybrid))
+            .await?;
+    }
+    Ok(hybrid)
+}
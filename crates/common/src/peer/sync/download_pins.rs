@@ -2,30 +2,148 @@
 //!
 //! This module contains the logic for downloading pinned content from peers.
 
+use std::collections::HashSet;
+
 use anyhow::Result;
+use iroh_blobs::Hash;
+use uuid::Uuid;
 
 use crate::bucket_log::BucketLogProvider;
-use crate::crypto::PublicKey;
-use crate::linked_data::Link;
+use crate::crypto::{PublicKey, SecretKey};
+use crate::linked_data::{BlockEncoded, Link};
+use crate::mount::{ChunkList, Manifest, Node, NodeLink};
 use crate::peer::Peer;
 
 /// Download pins job definition
 #[derive(Debug, Clone)]
 pub struct DownloadPinsJob {
+    pub bucket_id: Uuid,
     pub pins_link: Link,
     pub peer_ids: Vec<PublicKey>,
+    /// Height of the version the pins belong to
+    pub height: u64,
+    /// Whether the pins belong to a version older than the synced head,
+    /// which shallow buckets don't need to download
+    pub historical: bool,
+    /// Hashes pinned since the version the bucket was synced from, if known.
+    /// They are downloaded first: the rest is usually here already.
+    pub added: Option<Vec<Hash>>,
+    /// The shared directory, if we read the bucket through a subtree share:
+    /// only the pins below it are downloaded. Boxed to keep sync jobs small.
+    pub subtree: Option<Box<NodeLink>>,
+}
+
+/// The node of the directory we have a subtree share of in a version, with
+/// its secret, unless we have a share of the whole bucket or none at all
+pub fn subtree_root(manifest: &Manifest, secret_key: &SecretKey) -> Option<NodeLink> {
+    let us = secret_key.public();
+    if manifest.get_share(&us).is_some() {
+        return None;
+    }
+    let subtree_share = manifest.get_subtree_share(&us)?;
+    let secret = subtree_share.share()?.recover(secret_key).ok()?;
+    Some(NodeLink::new_dir(subtree_share.node()?.clone(), secret))
 }
 
 /// Execute a pins download job
 ///
-/// This downloads the hash list from the specified peers.
+/// This downloads the hash list from the specified peers, then every hash
+/// it lists that isn't already here, each within the download timeout.
+#[tracing::instrument(name = "sync.download_pins", skip_all, fields(pins = %job.pins_link.hash()))]
 pub async fn execute<L>(peer: &Peer<L>, job: DownloadPinsJob) -> Result<()>
 where
     L: BucketLogProvider + Clone + Send + Sync + 'static,
     L::Error: std::error::Error + Send + Sync + 'static,
 {
-    peer.blobs()
-        .download_hash_list(job.pins_link.hash(), job.peer_ids, peer.endpoint())
+    let list_hash = job.pins_link.hash();
+    peer.download_blob("pin list", list_hash, &job.peer_ids)
+        .await?;
+    let pinned = peer
+        .blobs()
+        .read_hash_list(list_hash)
         .await
-        .map_err(|e| anyhow::anyhow!("Failed to download pins: {}", e))
+        .map_err(|e| anyhow::anyhow!("Failed to read pins: {}", e))?;
+    if let Some(subtree) = job.subtree {
+        let pinned: HashSet<Hash> = pinned.into_iter().collect();
+        return download_subtree(peer, *subtree, &pinned, &job.peer_ids).await;
+    }
+    // Blobs shared with versions we already have, such as the unchanged
+    // chunks of an edited large file, are not fetched again
+    let mut hashes = Vec::new();
+    for hash in &pinned {
+        if !peer.blobs().stat(hash).await? {
+            hashes.push(*hash);
+        }
+    }
+    match &job.added {
+        Some(added) => {
+            let added: HashSet<&Hash> = added.iter().collect();
+            hashes.sort_by_key(|hash| !added.contains(hash));
+            tracing::info!(
+                "Downloading {} of {} pinned blob(s), {} new",
+                hashes.len(),
+                pinned.len(),
+                added.len()
+            );
+        }
+        None => tracing::info!(
+            "Downloading {} of {} pinned blob(s)",
+            hashes.len(),
+            pinned.len()
+        ),
+    }
+    for hash in hashes {
+        peer.download_blob("pinned blob", hash, &job.peer_ids)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Download the pinned blobs below a shared directory, walking down from its
+/// node since nothing else tells what is below it
+async fn download_subtree<L>(
+    peer: &Peer<L>,
+    root: NodeLink,
+    pinned: &HashSet<Hash>,
+    peer_ids: &[PublicKey],
+) -> Result<()>
+where
+    L: BucketLogProvider + Clone + Send + Sync + 'static,
+    L::Error: std::error::Error + Send + Sync + 'static,
+{
+    let blobs = peer.blobs();
+    let mut downloaded = 0;
+    let mut queue = vec![root];
+    while let Some(node_link) = queue.pop() {
+        let hash = node_link.link().hash();
+        if !pinned.contains(&hash) {
+            continue;
+        }
+        if !blobs.stat(&hash).await? {
+            peer.download_blob("pinned blob", hash, peer_ids).await?;
+            downloaded += 1;
+        }
+        match &node_link {
+            NodeLink::Dir(_, secret) => {
+                let data = secret.decrypt_offloaded(blobs.get(&hash).await?).await?;
+                queue.extend(Node::decode(&data)?.get_links().values().cloned());
+            }
+            NodeLink::Data(link, secret, data) if data.is_chunked() => {
+                let chunks = ChunkList::load(blobs, link, secret).await?;
+                for chunk in chunks.chunks() {
+                    let hash = chunk.link().hash();
+                    if pinned.contains(&hash) && !blobs.stat(&hash).await? {
+                        peer.download_blob("pinned blob", hash, peer_ids).await?;
+                        downloaded += 1;
+                    }
+                }
+            }
+            NodeLink::Data(..) | NodeLink::Bucket(_) => {}
+        }
+    }
+    tracing::info!(
+        "Downloaded {} pinned blob(s) below the shared directory",
+        downloaded
+    );
+    Ok(())
 }
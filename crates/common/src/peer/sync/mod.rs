@@ -6,6 +6,7 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use thiserror::Error;
+use uuid::Uuid;
 
 use crate::bucket_log::BucketLogProvider;
 use crate::linked_data::Link;
@@ -51,11 +52,34 @@ pub enum SyncJob {
 ///
 /// This is a helper function that dispatches to the per-job-type execution logic.
 /// Both synchronous and queued providers can use this.
+impl SyncJob {
+    /// Name of the kind of job
+    pub fn name(&self) -> &'static str {
+        match self {
+            SyncJob::SyncBucket(_) => "sync_bucket",
+            SyncJob::DownloadPins(_) => "download_pins",
+            SyncJob::PingPeer(_) => "ping_peer",
+        }
+    }
+
+    /// Bucket the job is for
+    pub fn bucket_id(&self) -> Uuid {
+        match self {
+            SyncJob::SyncBucket(job) => job.bucket_id,
+            SyncJob::DownloadPins(job) => job.bucket_id,
+            SyncJob::PingPeer(job) => job.bucket_id,
+        }
+    }
+}
+
 pub async fn execute_job<L>(peer: &crate::peer::Peer<L>, job: SyncJob) -> Result<()>
 where
     L: BucketLogProvider + Clone + Send + Sync + 'static,
     L::Error: std::error::Error + Send + Sync + 'static,
 {
+    if peer.faults().fails_job(&job) {
+        anyhow::bail!("{} job failed (injected fault)", job.name());
+    }
     match job {
         SyncJob::DownloadPins(job) => download_pins::execute(peer, job).await,
         SyncJob::SyncBucket(job) => sync_bucket::execute(peer, job).await,
@@ -7,8 +7,9 @@ use uuid::Uuid;
 
 use crate::bucket_log::BucketLogProvider;
 use crate::crypto::PublicKey;
+use crate::linked_data::Link;
 use crate::peer::protocol::bidirectional::BidirectionalHandler;
-use crate::peer::protocol::{Ping, PingMessage};
+use crate::peer::protocol::{capability, Ping, PingMessage, ProtocolError};
 use crate::peer::Peer;
 
 /// Ping peer job definition
@@ -21,7 +22,14 @@ pub struct PingPeerJob {
 /// Execute a ping peer job
 ///
 /// This sends a ping to the specified peer with our current bucket state
-/// and processes the response.
+/// and processes the response. For a followed bucket we have no versions of
+/// yet, the ping carries an empty link so the peer reports where it is; such
+/// pings are only sent to peers announcing [`capability::FOLLOW`].
+#[tracing::instrument(
+    name = "sync.ping_peer",
+    skip_all,
+    fields(bucket_id = %job.bucket_id, peer = %job.peer_id.to_hex())
+)]
 pub async fn execute<L>(peer: &Peer<L>, job: PingPeerJob) -> Result<()>
 where
     L: BucketLogProvider + Clone + Send + Sync + 'static,
@@ -36,6 +44,7 @@ where
     // Get our bucket state
     let (our_link, our_height) = match peer.log_provider().head(job.bucket_id, None).await {
         Ok((link, height)) => (link, height),
+        Err(_) if peer.following(&job.bucket_id).is_some() => (Link::default(), 0),
         Err(e) => {
             tracing::warn!(
                 "Failed to get head for bucket {} when pinging peer {}: {}",
@@ -47,6 +56,24 @@ where
         }
     };
 
+    // Learn what the peer speaks before sending it anything it may not
+    // understand
+    let protocol = match peer.protocol(&job.peer_id).await {
+        Ok(protocol) => protocol,
+        Err(e) => return Err(ping_failed(&job, e)),
+    };
+    // Peers without follow support take an empty link for a bucket they
+    // don't know, so there is nothing to ask them until we have a version
+    if our_link == Link::default() && !protocol.supports(capability::FOLLOW) {
+        tracing::info!(
+            "Peer {} speaks protocol v{} without follow support, not asking it about bucket {}",
+            job.peer_id.to_hex(),
+            protocol.version,
+            job.bucket_id
+        );
+        return Ok(());
+    }
+
     // Construct ping
     let ping = PingMessage {
         bucket_id: job.bucket_id,
@@ -66,19 +93,38 @@ where
             );
             Ok(())
         }
-        Err(e) => {
-            tracing::debug!(
-                "Failed to ping peer {} for bucket {}: {}",
-                job.peer_id.to_hex(),
-                job.bucket_id,
-                e
-            );
-            Err(anyhow::anyhow!(
-                "Ping job failed for bucket {} to peer {}: {}",
-                job.bucket_id,
-                job.peer_id.to_hex(),
-                e
-            ))
+        Err(e) => Err(ping_failed(&job, e)),
+    }
+}
+
+/// Log a failed ping by why it failed: offline peers are expected, peers that
+/// stop answering are not
+fn ping_failed(job: &PingPeerJob, error: anyhow::Error) -> anyhow::Error {
+    let peer_id = job.peer_id.to_hex();
+    match ProtocolError::of(&error) {
+        Some(ProtocolError::Offline { .. }) => {
+            tracing::debug!("Peer {} is offline: {}", peer_id, error)
         }
+        Some(ProtocolError::Timeout { .. }) => {
+            tracing::warn!("Peer {} stopped answering: {}", peer_id, error)
+        }
+        Some(ProtocolError::Cancelled { .. }) => {
+            tracing::debug!("Ping to peer {} cancelled: {}", peer_id, error)
+        }
+        Some(ProtocolError::Busy { .. }) => {
+            tracing::info!("Peer {} is busy: {}", peer_id, error)
+        }
+        None => tracing::debug!(
+            "Failed to ping peer {} for bucket {}: {}",
+            peer_id,
+            job.bucket_id,
+            error
+        ),
     }
+    // The cause stays in the chain for callers checking ProtocolError::of
+    let context = format!(
+        "Ping job failed for bucket {} to peer {}: {}",
+        job.bucket_id, peer_id, error
+    );
+    error.context(context)
 }
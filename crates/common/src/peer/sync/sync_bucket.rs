@@ -3,6 +3,7 @@
 //! This module contains the logic for syncing buckets between peers.
 
 use anyhow::{anyhow, Result};
+use iroh_blobs::Hash;
 use uuid::Uuid;
 
 use crate::bucket_log::BucketLogProvider;
@@ -10,8 +11,12 @@ use crate::crypto::PublicKey;
 use crate::linked_data::Link;
 use crate::mount::Manifest;
 use crate::mount::PrincipalRole;
+use crate::peer::protocol::bidirectional::BidirectionalHandler;
+use crate::peer::protocol::capability;
+use crate::peer::protocol::messages::sync_from::{SyncFrom, SyncFromMessage, SyncFromReplyStatus};
 use crate::peer::Peer;
 
+use super::download_pins::subtree_root;
 use super::{DownloadPinsJob, ProvenanceError, SyncJob};
 
 /// Result of provenance verification for a manifest.
@@ -19,7 +24,8 @@ use super::{DownloadPinsJob, ProvenanceError, SyncJob};
 enum ProvenanceResult {
     /// Manifest is valid - properly signed by an authorized writer
     Valid,
-    /// Our key is not in the manifest's shares (not an error, just skip)
+    /// Our key is not in the manifest's shares and we don't follow the
+    /// bucket (not an error, just skip)
     NotAuthorized,
     /// Manifest is unsigned (allowed during migration)
     UnsignedLegacy,
@@ -49,6 +55,11 @@ pub struct SyncBucketJob {
 /// This is the main entry point for syncing. It handles both cases:
 /// - Updating an existing bucket we already have
 /// - Cloning a new bucket we don't have yet
+#[tracing::instrument(
+    name = "sync.sync_bucket",
+    skip_all,
+    fields(bucket_id = %job.bucket_id, height = job.target.height)
+)]
 pub async fn execute<L>(peer: &Peer<L>, job: SyncBucketJob) -> Result<()>
 where
     L: BucketLogProvider + Clone + Send + Sync + 'static,
@@ -66,6 +77,21 @@ where
 
     let exists: bool = peer.logs().exists(job.bucket_id).await?;
 
+    // Peers that support it tell us what we are missing after our head,
+    //  sparing us the walk down the chain
+    if exists {
+        if let Some(delta) = download_delta(peer, job.bucket_id, &job.target).await? {
+            return apply_verified(
+                peer,
+                job.bucket_id,
+                Some(&delta.base),
+                &delta.manifests,
+                delta.pins_added,
+            )
+            .await;
+        }
+    }
+
     let common_ancestor = if exists {
         // find a common ancestor between our log and the
         //  link the peer advertised to us
@@ -125,16 +151,37 @@ where
     // TODO (amiller68): maybe theres an optimization here in that we should know
     //  we can exit earlier by virtue of finding a common ancestor which is just
     //  our current head
+    apply_verified(peer, job.bucket_id, trusted_base.as_ref(), &manifests, None).await
+}
+
+/// Check we may receive the latest of a validated chain of manifests and
+/// apply it to our log
+async fn apply_verified<L>(
+    peer: &Peer<L>,
+    bucket_id: Uuid,
+    trusted_base: Option<&Manifest>,
+    manifests: &[(Manifest, Link)],
+    head_pins_added: Option<Vec<Hash>>,
+) -> Result<()>
+where
+    L: BucketLogProvider + Clone + Send + Sync + 'static,
+    L::Error: std::error::Error + Send + Sync + 'static,
+{
     if manifests.is_empty() {
         tracing::info!("No new manifests to sync, already up to date");
         return Ok(());
     };
 
-    // Verify provenance of the latest manifest
-    // Use our local manifest as the trusted base (previous)
+    // Verify provenance of the latest manifest against the one before it,
+    // which is our local manifest (the trusted base) if it is the only one.
+    // The rest of the chain was verified as it was downloaded; the latest
+    // may be authored by an owner added since our local manifest.
     let latest_manifest = &manifests.last().unwrap().0;
-    let trusted_base_ref = trusted_base.as_ref();
-    match verify_provenance(peer, latest_manifest, trusted_base_ref)? {
+    let previous = match manifests.len() {
+        n if n >= 2 => Some(&manifests[n - 2].0),
+        _ => trusted_base,
+    };
+    match verify_provenance(peer, latest_manifest, previous)? {
         ProvenanceResult::Valid => {
             tracing::debug!("Provenance verification passed");
         }
@@ -151,11 +198,144 @@ where
     }
 
     // apply the updates to the bucket
-    apply_manifest_chain(peer, job.bucket_id, &manifests).await?;
+    apply_manifest_chain(peer, bucket_id, manifests, head_pins_added).await?;
 
     Ok(())
 }
 
+/// The manifests after our head, as told by a peer that supports delta sync
+struct Delta {
+    /// Our head, the trusted base of the chain
+    base: Manifest,
+    /// Validated manifests after our head, oldest first
+    manifests: Vec<(Manifest, Link)>,
+    /// Hashes pinned by the target but not by our head, if the peer knew
+    pins_added: Option<Vec<Hash>>,
+}
+
+/// Ask the first peer supporting delta sync for the manifests between our
+/// head and the target, and download and validate them
+///
+/// Returns None if no peer could answer, or if our head is not an ancestor
+/// of the target (e.g. we diverged), so the caller walks the chain instead.
+async fn download_delta<L>(
+    peer: &Peer<L>,
+    bucket_id: Uuid,
+    target: &SyncTarget,
+) -> Result<Option<Delta>>
+where
+    L: BucketLogProvider + Clone + Send + Sync + 'static,
+    L::Error: std::error::Error + Send + Sync + 'static,
+{
+    let (head, height) = peer
+        .logs()
+        .head(bucket_id, None)
+        .await
+        .map_err(|e| anyhow!("Failed to get head of bucket {}: {}", bucket_id, e))?;
+    let base: Manifest = peer.blobs().get_cbor(&head.hash()).await?;
+    if head == target.link {
+        return Ok(Some(Delta {
+            base,
+            manifests: Vec::new(),
+            pins_added: None,
+        }));
+    }
+
+    for (i, peer_id) in target.peer_ids.iter().enumerate() {
+        // Only the preferred peer is asked what it speaks; offline peers
+        //  further down would each cost a connect timeout
+        let protocol = if i == 0 {
+            peer.protocol(peer_id).await.ok()
+        } else {
+            peer.peer_protocol(peer_id)
+        };
+        if !protocol.is_some_and(|info| info.supports(capability::DELTA_SYNC)) {
+            continue;
+        }
+        let request = SyncFromMessage {
+            bucket_id,
+            head: head.clone(),
+            height,
+            target: target.link.clone(),
+        };
+        let reply = match SyncFrom::send(peer, peer_id, request).await {
+            Ok(reply) => reply,
+            Err(e) => {
+                tracing::debug!(
+                    "Peer {} could not answer delta sync: {}",
+                    peer_id.to_hex(),
+                    e
+                );
+                continue;
+            }
+        };
+        let (links, pins) = match reply.status {
+            SyncFromReplyStatus::Delta { manifests, pins } => (manifests, pins),
+            SyncFromReplyStatus::NotFound => continue,
+            status => {
+                tracing::info!(
+                    "Peer {} can't sync bucket {} from our head ({:?}), walking the chain",
+                    peer_id.to_hex(),
+                    bucket_id,
+                    status
+                );
+                return Ok(None);
+            }
+        };
+        tracing::info!(
+            "Syncing {} manifest(s) of bucket {} from our head at height {}",
+            links.len(),
+            bucket_id,
+            height
+        );
+        if let Some(pins) = &pins {
+            tracing::debug!(
+                "{} blob(s) pinned and {} unpinned since our head",
+                pins.added.len(),
+                pins.removed.len()
+            );
+        }
+
+        // The answering peer first, it has all of them
+        let mut peer_ids = vec![*peer_id];
+        peer_ids.extend(target.peer_ids.iter().filter(|id| *id != peer_id));
+        let mut manifests = Vec::with_capacity(links.len());
+        let mut previous = head.clone();
+        for link in links {
+            peer.download_blob("manifest", link.hash(), &peer_ids)
+                .await?;
+            let manifest: Manifest = peer.blobs().get_cbor(&link.hash()).await?;
+            // The delta must be an unbroken chain from our head
+            if manifest.previous().as_ref() != Some(&previous) {
+                tracing::warn!(
+                    "Peer {} sent a delta of bucket {} that doesn't chain from our head, walking the chain",
+                    peer_id.to_hex(),
+                    bucket_id
+                );
+                return Ok(None);
+            }
+            previous = link.clone();
+            manifests.push((manifest, link));
+        }
+        if previous != target.link {
+            tracing::warn!(
+                "Peer {} sent a delta of bucket {} that doesn't end at the target, walking the chain",
+                peer_id.to_hex(),
+                bucket_id
+            );
+            return Ok(None);
+        }
+
+        verify_chain(&manifests, Some(&base))?;
+        return Ok(Some(Delta {
+            base,
+            manifests,
+            pins_added: pins.map(|pins| pins.added),
+        }));
+    }
+    Ok(None)
+}
+
 /// Download a chain of manifests from peers and validate provenance
 ///
 /// Walks backwards through the manifest chain via `previous` links.
@@ -191,16 +371,8 @@ where
     // Download manifests walking backwards
     loop {
         // Download the manifest blob from peers
-        peer.blobs()
-            .download_hash(current_link.hash(), peer_ids.to_vec(), peer.endpoint())
-            .await
-            .map_err(|e| {
-                anyhow!(
-                    "Failed to download manifest {:?} from peers: {}",
-                    current_link,
-                    e
-                )
-            })?;
+        peer.download_blob("manifest", current_link.hash(), peer_ids)
+            .await?;
 
         // Read and decode the manifest
         let manifest: Manifest = peer.blobs().get_cbor(&current_link.hash()).await?;
@@ -233,10 +405,21 @@ where
 
     tracing::debug!("Downloaded {} manifests", manifests.len());
 
-    // Validate the chain (walking from oldest to newest)
-    // The first manifest is validated against the trusted_base (common ancestor)
+    verify_chain(&manifests, trusted_base)?;
+
+    Ok(manifests)
+}
+
+/// Validate a chain of manifests, oldest first
+///
+/// The first manifest is validated against `trusted_base` (common ancestor),
+/// each subsequent one against its predecessor.
+fn verify_chain(
+    manifests: &[(Manifest, Link)],
+    trusted_base: Option<&Manifest>,
+) -> Result<(), ProvenanceError> {
     // NOTE: Chain validation only checks author authorization, not receiver authorization.
-    // The receiver check is done separately on the final manifest in execute().
+    // The receiver check is done separately on the final manifest in apply_verified().
     let mut previous: Option<&Manifest> = trusted_base;
 
     for (manifest, link) in manifests.iter() {
@@ -247,7 +430,7 @@ where
         previous = Some(manifest);
     }
 
-    Ok(manifests)
+    Ok(())
 }
 
 /// Find common ancestor by downloading manifests from peers
@@ -299,16 +482,8 @@ where
         // TODO (amiller68): this should build in memory
         //  but for now we just download it
         // Download the manifest from peers
-        peer.blobs()
-            .download_hash(current_link.hash(), peer_ids.to_vec(), peer.endpoint())
-            .await
-            .map_err(|e| {
-                anyhow!(
-                    "Failed to download manifest {:?} from peers: {}",
-                    current_link,
-                    e
-                )
-            })?;
+        peer.download_blob("manifest", current_link.hash(), peer_ids)
+            .await?;
 
         // Read and decode the manifest
         let manifest: Manifest = peer.blobs().get_cbor(&current_link.hash()).await?;
@@ -354,11 +529,18 @@ where
 
 /// Apply a chain of manifests to the log
 ///
-/// Appends each manifest to the log in order, starting from `start_height`.
+/// Appends each manifest to the log in order, oldest first, and queues a
+/// download of each version's pins. Only the newest manifest's pins are the
+/// head pins; the rest are marked historical. `head_pins_added` are the head
+/// pins new since the version we synced from, if known.
+///
+/// If the newest manifest only gives us a subtree share, versions that don't
+/// give us one have nothing for us to download.
 async fn apply_manifest_chain<L>(
     peer: &Peer<L>,
     bucket_id: Uuid,
     manifests: &[(Manifest, Link)],
+    mut head_pins_added: Option<Vec<Hash>>,
 ) -> Result<()>
 where
     L: BucketLogProvider + Clone + Send + Sync + 'static,
@@ -366,7 +548,12 @@ where
 {
     tracing::info!("Applying {} manifests to log", manifests.len(),);
 
-    if let Some((_i, (manifest, link))) = manifests.iter().enumerate().next() {
+    let us = peer.secret().public();
+    let subtree_only = manifests.last().is_some_and(|(manifest, _)| {
+        manifest.get_share(&us).is_none() && manifest.get_subtree_share(&us).is_some()
+    });
+
+    for (i, (manifest, link)) in manifests.iter().enumerate() {
         let previous = manifest.previous().clone();
         let height = manifest.height();
         let is_published = manifest.is_published();
@@ -391,18 +578,31 @@ where
             .await
             .map_err(|e| anyhow!("Failed to append manifest at height {}: {}", height, e))?;
 
+        let historical = i + 1 < manifests.len();
+        let subtree = subtree_root(manifest, peer.secret());
+        if subtree_only && subtree.is_none() {
+            continue;
+        }
         let pins_link = manifest.pins().clone();
         let peer_ids = manifest
             .shares()
             .iter()
             .map(|share| share.1.principal().identity)
             .collect();
-        return peer
-            .dispatch(SyncJob::DownloadPins(DownloadPinsJob {
-                pins_link,
-                peer_ids,
-            }))
-            .await;
+        peer.dispatch(SyncJob::DownloadPins(DownloadPinsJob {
+            bucket_id,
+            pins_link,
+            peer_ids,
+            height,
+            historical,
+            added: if historical {
+                None
+            } else {
+                head_pins_added.take()
+            },
+            subtree: subtree.map(Box::new),
+        }))
+        .await?;
     }
 
     tracing::info!("Successfully applied {} manifests to log", manifests.len());
@@ -477,7 +677,8 @@ fn verify_author(
 /// Verify a manifest's full provenance including receiver authorization.
 ///
 /// Checks that:
-/// 1. Our key is in the manifest's shares (we're authorized to receive it)
+/// 1. Our key is in the manifest's shares or subtree shares, or we follow
+///    the bucket (we're authorized to receive it)
 /// 2. The manifest is properly signed (or unsigned during migration)
 /// 3. The author was in the previous manifest's shares (authorized to make changes)
 /// 4. The author has write permission (Owner role)
@@ -505,7 +706,9 @@ where
     let we_are_authorized = manifest
         .shares()
         .iter()
-        .any(|(key_hex, _)| key_hex == &our_key_hex);
+        .any(|(key_hex, _)| key_hex == &our_key_hex)
+        || manifest.get_subtree_share(&our_pub_key).is_some()
+        || peer.following(manifest.id()).is_some();
 
     if !we_are_authorized {
         return Ok(ProvenanceResult::NotAuthorized);
@@ -5,6 +5,8 @@ mod common;
 use std::io::Cursor;
 use std::path::PathBuf;
 
+use ::common::mount::MountError;
+
 #[tokio::test]
 async fn test_add_and_cat() {
     let (mut mount, _, _, _temp) = common::setup_test_env().await;
@@ -114,3 +116,163 @@ async fn test_nested_operations() {
         .unwrap();
     assert_eq!(data, b"integration");
 }
+
+#[tokio::test]
+async fn test_add_does_not_replace_directory() {
+    let (mut mount, _, _, _temp) = common::setup_test_env().await;
+
+    mount
+        .add(&PathBuf::from("/docs/a.txt"), Cursor::new(b"a".to_vec()))
+        .await
+        .unwrap();
+
+    let result = mount
+        .add(&PathBuf::from("/docs"), Cursor::new(b"b".to_vec()))
+        .await;
+    assert!(matches!(result, Err(MountError::PathAlreadyExists(_))));
+    assert_eq!(
+        mount.cat(&PathBuf::from("/docs/a.txt")).await.unwrap(),
+        b"a"
+    );
+}
+
+#[tokio::test]
+async fn test_add_sniffs_extensionless_files() {
+    let (mut mount, _, _, _temp) = common::setup_test_env().await;
+
+    let files: Vec<(&str, &[u8], Option<&str>)> = vec![
+        (
+            "/screenshot",
+            b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR",
+            Some("image/png"),
+        ),
+        ("/README", b"Read me first\n", Some("text/plain")),
+        (
+            "/page",
+            b"<!doctype html><title>hi</title>",
+            Some("text/html"),
+        ),
+        ("/blob", b"\x00\x9f\x92\x96\x00", None),
+        // The extension wins over the content
+        ("/notes.txt", b"%PDF-1.7\n", Some("text/plain")),
+    ];
+
+    for (path, data, expected_mime) in files {
+        let path = PathBuf::from(path);
+        mount.add(&path, Cursor::new(data.to_vec())).await.unwrap();
+        let link = mount.get(&path).await.unwrap();
+        let mime = link.data().unwrap().mime().map(|m| m.to_string());
+        assert_eq!(mime.as_deref(), expected_mime, "{}", path.display());
+    }
+}
+
+#[tokio::test]
+async fn test_backfill_mime() {
+    let (mut mount, blobs, key, _temp) = common::setup_test_env().await;
+
+    // A file whose type wasn't known when it was created, like those added
+    //  before content sniffing
+    let path = PathBuf::from("/capture");
+    mount.append(&path, Cursor::new(Vec::new())).await.unwrap();
+    mount
+        .append(&path, Cursor::new(b"GIF89a\x01\x00\x01\x00".to_vec()))
+        .await
+        .unwrap();
+    mount
+        .add(
+            &PathBuf::from("/blob"),
+            Cursor::new(b"\x00\x9f\x92".to_vec()),
+        )
+        .await
+        .unwrap();
+    assert!(mount
+        .get(&path)
+        .await
+        .unwrap()
+        .data()
+        .unwrap()
+        .mime()
+        .is_none());
+
+    let updated = mount.backfill_mime().await.unwrap();
+    assert_eq!(updated, vec![path.clone()]);
+    let (link, _, _) = mount.save(&blobs, false).await.unwrap();
+
+    let loaded = ::common::mount::Mount::load(&link, &key, &blobs)
+        .await
+        .unwrap();
+    let link = loaded.get(&path).await.unwrap();
+    assert_eq!(link.data().unwrap().mime().unwrap().as_ref(), "image/gif");
+    assert_eq!(loaded.cat(&path).await.unwrap(), b"GIF89a\x01\x00\x01\x00");
+
+    // Running it again finds nothing left to do
+    let mut loaded = loaded;
+    assert!(loaded.backfill_mime().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_add_extracts_media_when_enabled() {
+    let (mut mount, blobs, key, _temp) = common::setup_test_env().await;
+    let png = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR\x00\x00\x03\x20\x00\x00\x02\x58".to_vec();
+
+    // Off by default
+    let before = PathBuf::from("/before.png");
+    mount.add(&before, Cursor::new(png.clone())).await.unwrap();
+    let link = mount.get(&before).await.unwrap();
+    assert_eq!(link.data().unwrap().media(), None);
+
+    mount.set_extract_media(true).await;
+    let path = PathBuf::from("/photo.png");
+    mount.add(&path, Cursor::new(png)).await.unwrap();
+    let (link, _, _) = mount.save(&blobs, false).await.unwrap();
+
+    // The setting and the metadata survive a reload
+    let loaded = ::common::mount::Mount::load(&link, &key, &blobs)
+        .await
+        .unwrap();
+    assert!(loaded.extract_media().await);
+    let media = loaded
+        .get(&path)
+        .await
+        .unwrap()
+        .data()
+        .unwrap()
+        .media()
+        .unwrap();
+    assert_eq!((media.width, media.height), (Some(800), Some(600)));
+}
+
+#[tokio::test]
+async fn test_add_records_size_and_hash() {
+    let (mut mount, _, _, _temp) = common::setup_test_env().await;
+    let small = b"Hello, world!".to_vec();
+    let large: Vec<u8> = (0..::common::mount::CHUNKING_THRESHOLD as u32 * 2)
+        .map(|i| (i.wrapping_mul(2654435761) >> 24) as u8)
+        .collect();
+
+    // Whole and chunked files alike
+    for (path, data) in [("/small.txt", &small), ("/large.bin", &large)] {
+        let path = PathBuf::from(path);
+        mount.add(&path, Cursor::new(data.clone())).await.unwrap();
+        let link = mount.get(&path).await.unwrap();
+        let meta = link.data().unwrap();
+        assert_eq!(meta.size(), Some(data.len() as u64));
+        assert_eq!(
+            meta.content_hash(),
+            Some(::common::linked_data::Hash::new(data))
+        );
+    }
+
+    // Appending keeps them up to date
+    let path = PathBuf::from("/small.txt");
+    mount
+        .append(&path, Cursor::new(b" Again".to_vec()))
+        .await
+        .unwrap();
+    let link = mount.get(&path).await.unwrap();
+    assert_eq!(link.data().unwrap().size(), Some(19));
+    assert_eq!(
+        link.data().unwrap().content_hash(),
+        Some(::common::linked_data::Hash::new(b"Hello, world! Again"))
+    );
+}
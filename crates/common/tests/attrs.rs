@@ -0,0 +1,124 @@
+//! Integration tests for custom attributes on files and directories
+
+mod common;
+
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use ::common::mount::{Attrs, Mount, MountError, MAX_ATTR_KEY_LEN};
+
+fn attrs(pairs: &[(&str, &str)]) -> Attrs {
+    pairs
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_file_attrs() {
+    let (mut mount, blobs, key, _temp) = common::setup_test_env().await;
+    let path = PathBuf::from("/photos/beach.jpg");
+    mount
+        .add(&path, Cursor::new(b"jpeg bytes".to_vec()))
+        .await
+        .unwrap();
+    assert!(mount.attrs(&path).await.unwrap().is_empty());
+
+    let set = mount
+        .set_attrs(&path, attrs(&[("tag", "holiday"), ("rating", "4")]), &[])
+        .await
+        .unwrap();
+    assert_eq!(set, attrs(&[("tag", "holiday"), ("rating", "4")]));
+
+    // Setting overwrites, removing drops, and the contents are untouched
+    let set = mount
+        .set_attrs(&path, attrs(&[("rating", "5")]), &["tag".to_string()])
+        .await
+        .unwrap();
+    assert_eq!(set, attrs(&[("rating", "5")]));
+    assert_eq!(mount.cat(&path).await.unwrap(), b"jpeg bytes");
+
+    // Replacing the contents keeps the attributes
+    mount
+        .add(&path, Cursor::new(b"edited jpeg".to_vec()))
+        .await
+        .unwrap();
+    assert_eq!(mount.attrs(&path).await.unwrap(), attrs(&[("rating", "5")]));
+
+    let (link, _, _) = mount.save(&blobs, false).await.unwrap();
+    let loaded = Mount::load(&link, &key, &blobs).await.unwrap();
+    assert_eq!(
+        loaded.attrs(&path).await.unwrap(),
+        attrs(&[("rating", "5")])
+    );
+}
+
+#[tokio::test]
+async fn test_dir_and_root_attrs() {
+    let (mut mount, blobs, key, _temp) = common::setup_test_env().await;
+    let dir = PathBuf::from("/projects/jax");
+    let file = dir.join("README.md");
+    mount
+        .add(&file, Cursor::new(b"# jax".to_vec()))
+        .await
+        .unwrap();
+
+    mount
+        .set_attrs(&dir, attrs(&[("color", "blue")]), &[])
+        .await
+        .unwrap();
+    mount
+        .set_attrs(Path::new("/"), attrs(&[("app", "notes")]), &[])
+        .await
+        .unwrap();
+
+    // Directory attributes survive changes to their children
+    mount
+        .add(&dir.join("TODO.md"), Cursor::new(b"- ship".to_vec()))
+        .await
+        .unwrap();
+    assert_eq!(
+        mount.attrs(&dir).await.unwrap(),
+        attrs(&[("color", "blue")])
+    );
+    assert_eq!(mount.cat(&file).await.unwrap(), b"# jax");
+
+    let (link, _, _) = mount.save(&blobs, false).await.unwrap();
+    let loaded = Mount::load(&link, &key, &blobs).await.unwrap();
+    assert_eq!(
+        loaded.attrs(&dir).await.unwrap(),
+        attrs(&[("color", "blue")])
+    );
+    assert_eq!(
+        loaded.attrs(Path::new("/")).await.unwrap(),
+        attrs(&[("app", "notes")])
+    );
+
+    // Moving a directory carries its attributes along
+    let mut loaded = loaded;
+    loaded.mv(&dir, Path::new("/jax")).await.unwrap();
+    assert_eq!(
+        loaded.attrs(Path::new("/jax")).await.unwrap(),
+        attrs(&[("color", "blue")])
+    );
+}
+
+#[tokio::test]
+async fn test_invalid_attrs() {
+    let (mut mount, _, _, _temp) = common::setup_test_env().await;
+    let path = PathBuf::from("/file.txt");
+
+    let result = mount.set_attrs(&path, attrs(&[("tag", "x")]), &[]).await;
+    assert!(matches!(result, Err(MountError::PathNotFound(_))));
+
+    mount
+        .add(&path, Cursor::new(b"hello".to_vec()))
+        .await
+        .unwrap();
+    let long_key = "k".repeat(MAX_ATTR_KEY_LEN + 1);
+    let result = mount
+        .set_attrs(&path, attrs(&[(long_key.as_str(), "x")]), &[])
+        .await;
+    assert!(matches!(result, Err(MountError::InvalidAttrs(_))));
+    assert!(mount.attrs(&path).await.unwrap().is_empty());
+}
@@ -0,0 +1,98 @@
+//! Integration tests for references to other buckets
+
+mod common;
+
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use ::common::mount::{BucketRef, Mount, MountError};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_reference_is_recorded_and_not_followed_by_the_mount() {
+    let (mut mount, blobs, owner_key, _temp) = common::setup_test_env().await;
+    let mut lib = Mount::init(Uuid::new_v4(), "lib".to_string(), &owner_key, &blobs)
+        .await
+        .unwrap();
+    lib.add(
+        &PathBuf::from("/lib.rs"),
+        Cursor::new(b"pub fn f() {}".to_vec()),
+    )
+    .await
+    .unwrap();
+    let (lib_link, _, _) = lib.save(&blobs, false).await.unwrap();
+    let lib_id = *lib.inner().await.manifest().id();
+
+    mount
+        .add_reference(
+            &PathBuf::from("/vendor/lib"),
+            BucketRef::new(lib_id, lib_link.clone(), false),
+        )
+        .await
+        .unwrap();
+    let (link, _, _) = mount.save(&blobs, false).await.unwrap();
+    let mount = Mount::load(&link, &owner_key, &blobs).await.unwrap();
+
+    // Listed under its parent, and found on paths through it
+    let listed = mount.ls(&PathBuf::from("/vendor")).await.unwrap();
+    let node_link = listed.get(&PathBuf::from("vendor/lib")).unwrap();
+    let bucket_ref = node_link.bucket_ref().unwrap();
+    assert_eq!(*bucket_ref.bucket_id(), lib_id);
+    assert_eq!(bucket_ref.version(), &lib_link);
+    assert!(!bucket_ref.is_floating());
+    let (prefix, found) = mount
+        .find_reference(&PathBuf::from("/vendor/lib/lib.rs"))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(prefix, PathBuf::from("/vendor/lib"));
+    assert_eq!(&found, bucket_ref);
+    assert!(mount
+        .find_reference(&PathBuf::from("/vendor"))
+        .await
+        .unwrap()
+        .is_none());
+
+    // The mount itself doesn't read through it
+    assert!(matches!(
+        mount.cat(&PathBuf::from("/vendor/lib")).await,
+        Err(MountError::BucketReference(_))
+    ));
+    assert!(matches!(
+        mount.get(&PathBuf::from("/vendor/lib/lib.rs")).await,
+        Err(MountError::BucketReference(_))
+    ));
+
+    // Nothing of the referenced bucket is reachable from this one
+    let vendor = mount.get(&PathBuf::from("/vendor")).await.unwrap();
+    let reachable = Mount::reachable(&vendor, &blobs).await.unwrap();
+    assert!(reachable.contains(&vendor.link().hash()));
+    assert!(!reachable.contains(&lib_link.hash()));
+}
+
+#[tokio::test]
+async fn test_reference_rejects_self_and_existing_paths() {
+    let (mut mount, blobs, _owner_key, _temp) = common::setup_test_env().await;
+    mount
+        .add(&PathBuf::from("/a.txt"), Cursor::new(b"a".to_vec()))
+        .await
+        .unwrap();
+    let (link, _, _) = mount.save(&blobs, false).await.unwrap();
+    let id = *mount.inner().await.manifest().id();
+
+    assert!(matches!(
+        mount
+            .add_reference(&PathBuf::from("/me"), BucketRef::new(id, link.clone(), true))
+            .await,
+        Err(MountError::ReferenceCycle(cycle)) if cycle == id
+    ));
+    assert!(matches!(
+        mount
+            .add_reference(
+                &PathBuf::from("/a.txt"),
+                BucketRef::new(Uuid::new_v4(), link, true)
+            )
+            .await,
+        Err(MountError::PathAlreadyExists(_))
+    ));
+}
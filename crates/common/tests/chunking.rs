@@ -0,0 +1,172 @@
+//! Integration tests for content-defined chunking of large files
+
+mod common;
+
+use std::collections::HashSet;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use ::common::mount::{ChunkList, Compression, Mount, NodeLink, CHUNKING_THRESHOLD};
+
+fn pseudo_random(len: usize) -> Vec<u8> {
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect()
+}
+
+async fn chunk_hashes(mount: &Mount, path: &Path) -> Vec<::common::linked_data::Hash> {
+    let NodeLink::Data(link, secret, data) = mount.get(path).await.unwrap() else {
+        panic!("not a file");
+    };
+    assert!(data.is_chunked());
+    ChunkList::load(&mount.blobs(), &link, &secret)
+        .await
+        .unwrap()
+        .chunks()
+        .iter()
+        .map(|chunk| chunk.link().hash())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_chunked_add_and_cat() {
+    let (mut mount, blobs, key, _temp) = common::setup_test_env().await;
+
+    let data = pseudo_random(3 * CHUNKING_THRESHOLD);
+    let path = PathBuf::from("/video.bin");
+    mount.add(&path, Cursor::new(data.clone())).await.unwrap();
+    assert!(chunk_hashes(&mount, &path).await.len() > 1);
+    assert_eq!(mount.cat(&path).await.unwrap(), data);
+
+    // Small files are stored whole
+    let small = PathBuf::from("/small.bin");
+    mount
+        .add(&small, Cursor::new(pseudo_random(1024)))
+        .await
+        .unwrap();
+    assert!(!mount
+        .get(&small)
+        .await
+        .unwrap()
+        .data()
+        .unwrap()
+        .is_chunked());
+
+    let (link, _, _) = mount.save(&blobs, false).await.unwrap();
+    let loaded = Mount::load(&link, &key, &blobs).await.unwrap();
+    assert_eq!(loaded.cat(&path).await.unwrap(), data);
+}
+
+#[tokio::test]
+async fn test_chunked_edit_keeps_unchanged_chunks() {
+    let (mut mount, blobs, _, _temp) = common::setup_test_env().await;
+    mount.set_compression(Some(Compression::Zstd)).await;
+
+    let mut data = pseudo_random(3 * CHUNKING_THRESHOLD);
+    let path = PathBuf::from("/disk.img");
+    mount.add(&path, Cursor::new(data.clone())).await.unwrap();
+    let before = chunk_hashes(&mount, &path).await;
+    mount.save(&blobs, false).await.unwrap();
+
+    // Overwrite a few bytes in the middle of the file
+    let middle = data.len() / 2;
+    data[middle..middle + 16].copy_from_slice(b"a small change!!");
+    mount.add(&path, Cursor::new(data.clone())).await.unwrap();
+    let after = chunk_hashes(&mount, &path).await;
+
+    let before: HashSet<_> = before.into_iter().collect();
+    let changed = after.iter().filter(|hash| !before.contains(*hash)).count();
+    assert!((1..=2).contains(&changed));
+    assert_eq!(mount.cat(&path).await.unwrap(), data);
+
+    // The new version pins its chunks, unchanged or not
+    let pins = mount.inner().await.pins;
+    assert!(after.iter().all(|hash| pins.contains(hash)));
+}
+
+#[tokio::test]
+async fn test_append_extends_chunk_list() {
+    let (mut mount, blobs, key, _temp) = common::setup_test_env().await;
+    let path = PathBuf::from("/events.log");
+
+    // Appending to a missing file creates it
+    mount
+        .append(&path, Cursor::new(b"boot\n".to_vec()))
+        .await
+        .unwrap();
+    let first = chunk_hashes(&mount, &path).await;
+    assert_eq!(first.len(), 1);
+
+    // Later appends leave the existing chunks alone
+    mount
+        .append(&path, Cursor::new(b"reading 1\n".to_vec()))
+        .await
+        .unwrap();
+    mount
+        .append(&path, Cursor::new(b"reading 2\n".to_vec()))
+        .await
+        .unwrap();
+    let after = chunk_hashes(&mount, &path).await;
+    assert_eq!(after.len(), 3);
+    assert_eq!(after[..1], first[..]);
+    assert_eq!(
+        mount.cat(&path).await.unwrap(),
+        b"boot\nreading 1\nreading 2\n"
+    );
+    let pins = mount.inner().await.pins;
+    assert!(after.iter().all(|hash| pins.contains(hash)));
+
+    // The size stays known; the hash would take reading the earlier chunks
+    let node = mount.get(&path).await.unwrap();
+    let data = node.data().unwrap();
+    assert_eq!(data.size(), Some(25));
+    assert_eq!(data.content_hash(), None);
+
+    let (link, _, _) = mount.save(&blobs, false).await.unwrap();
+    let loaded = Mount::load(&link, &key, &blobs).await.unwrap();
+    assert_eq!(
+        loaded.cat(&path).await.unwrap(),
+        b"boot\nreading 1\nreading 2\n"
+    );
+}
+
+#[tokio::test]
+async fn test_append_to_whole_file_and_dir() {
+    let (mut mount, _, _, _temp) = common::setup_test_env().await;
+
+    // A file stored whole is carried into the first chunk
+    let path = PathBuf::from("/notes.txt");
+    mount
+        .add(&path, Cursor::new(b"first line\n".to_vec()))
+        .await
+        .unwrap();
+    mount
+        .append(&path, Cursor::new(b"second line\n".to_vec()))
+        .await
+        .unwrap();
+    assert_eq!(chunk_hashes(&mount, &path).await.len(), 1);
+    assert_eq!(
+        mount.cat(&path).await.unwrap(),
+        b"first line\nsecond line\n"
+    );
+    // All of it was read, so it is hashed whole
+    let node = mount.get(&path).await.unwrap();
+    let data = node.data().unwrap();
+    assert_eq!(data.size(), Some(23));
+    assert_eq!(
+        data.content_hash().map(|hash| *hash.as_bytes()),
+        Some(*blake3::hash(b"first line\nsecond line\n").as_bytes())
+    );
+
+    mount.mkdir(Path::new("/logs")).await.unwrap();
+    assert!(mount
+        .append(Path::new("/logs"), Cursor::new(b"x".to_vec()))
+        .await
+        .is_err());
+}
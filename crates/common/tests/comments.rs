@@ -0,0 +1,87 @@
+//! Integration tests for comments on files and directories
+
+mod common;
+
+use std::io::Cursor;
+use std::path::Path;
+
+use ::common::mount::{ConflictFile, Mount, MountError};
+
+#[tokio::test]
+async fn test_comments_persist_across_versions() {
+    let (mut mount, blobs, key, _temp) = common::setup_test_env().await;
+    mount
+        .add(Path::new("/plan.md"), Cursor::new(b"v1".to_vec()))
+        .await
+        .unwrap();
+    let (version, _, _) = mount.save(&blobs, false).await.unwrap();
+
+    let comment = mount
+        .add_comment(Path::new("/plan.md"), version.hash(), "Needs a summary")
+        .await
+        .unwrap();
+    assert_eq!(comment.path, "/plan.md");
+    assert_eq!(comment.author, key.public());
+    let (link, _, _) = mount.save(&blobs, false).await.unwrap();
+
+    // The comments blob is pinned, so peers fetch it with the version
+    let loaded = Mount::load(&link, &key, &blobs).await.unwrap();
+    let inner = loaded.inner().await;
+    let comments_link = inner.manifest().comments().unwrap();
+    assert!(inner.pins().contains(&comments_link.hash()));
+    assert_eq!(loaded.comments().await.all(), vec![&comment]);
+
+    // They carry over to later versions
+    let mut loaded = loaded;
+    loaded
+        .add(Path::new("/other.txt"), Cursor::new(b"other".to_vec()))
+        .await
+        .unwrap();
+    let (link, _, _) = loaded.save(&blobs, false).await.unwrap();
+    let mut reloaded = Mount::load(&link, &key, &blobs).await.unwrap();
+    assert_eq!(reloaded.comments().await.on_path("/plan.md").len(), 1);
+
+    reloaded.remove_comment(&comment.id).await.unwrap();
+    assert!(matches!(
+        reloaded.remove_comment(&comment.id).await,
+        Err(MountError::CommentNotFound(_))
+    ));
+    assert!(matches!(
+        reloaded
+            .add_comment(Path::new("/plan.md"), version.hash(), "")
+            .await,
+        Err(MountError::InvalidComment(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_merge_takes_comments_of_both_sides() {
+    let (mut alice, blobs, _, _temp) = common::setup_test_env().await;
+    let shared = alice
+        .add_comment(Path::new("/"), alice.link().await.hash(), "Welcome")
+        .await
+        .unwrap();
+    let (mut bob, _bob_key) = common::fork_mount(&mut alice, &blobs).await;
+
+    // Alice removes the shared comment while Bob adds one
+    alice.remove_comment(&shared.id).await.unwrap();
+    alice
+        .add(Path::new("/a.txt"), Cursor::new(b"a".to_vec()))
+        .await
+        .unwrap();
+    alice.save(&blobs, false).await.unwrap();
+    let from_bob = bob
+        .add_comment(Path::new("/"), bob.link().await.hash(), "Hi")
+        .await
+        .unwrap();
+    bob.add(Path::new("/b.txt"), Cursor::new(b"b".to_vec()))
+        .await
+        .unwrap();
+    bob.save(&blobs, false).await.unwrap();
+
+    alice
+        .merge_from(&bob, &ConflictFile::new(), &blobs)
+        .await
+        .unwrap();
+    assert_eq!(alice.comments().await.all(), vec![&from_bob]);
+}
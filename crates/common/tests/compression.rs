@@ -0,0 +1,73 @@
+//! Integration tests for transparent compression of file contents
+
+mod common;
+
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use ::common::mount::{Compression, Mount};
+
+#[tokio::test]
+async fn test_compressed_add_and_cat() {
+    let (mut mount, blobs, _, _temp) = common::setup_test_env().await;
+    mount.set_compression(Some(Compression::Zstd)).await;
+
+    let data = "the quick brown fox ".repeat(500).into_bytes();
+    let path = PathBuf::from("/notes.txt");
+    mount.add(&path, Cursor::new(data.clone())).await.unwrap();
+
+    let node_link = mount.get(&path).await.unwrap();
+    assert_eq!(
+        node_link.data().unwrap().compression(),
+        Some(Compression::Zstd)
+    );
+
+    // The stored blob is smaller than the plaintext
+    let stored = blobs.get(&node_link.link().hash()).await.unwrap();
+    assert!(stored.len() < data.len());
+
+    assert_eq!(mount.cat(&path).await.unwrap(), data);
+}
+
+#[tokio::test]
+async fn test_compression_skips_media() {
+    let (mut mount, _, _, _temp) = common::setup_test_env().await;
+    mount.set_compression(Some(Compression::Zstd)).await;
+
+    let data = vec![0u8; 4096];
+    let path = PathBuf::from("/photo.jpg");
+    mount.add(&path, Cursor::new(data.clone())).await.unwrap();
+
+    let node_link = mount.get(&path).await.unwrap();
+    assert_eq!(node_link.data().unwrap().compression(), None);
+    assert_eq!(mount.cat(&path).await.unwrap(), data);
+}
+
+#[tokio::test]
+async fn test_compression_persists_across_load() {
+    let (mut mount, blobs, key, _temp) = common::setup_test_env().await;
+    mount.set_compression(Some(Compression::Zstd)).await;
+
+    let data = "persisted ".repeat(500).into_bytes();
+    let path = PathBuf::from("/doc.md");
+    mount.add(&path, Cursor::new(data.clone())).await.unwrap();
+    let (link, _, _) = mount.save(&blobs, false).await.unwrap();
+
+    let loaded = Mount::load(&link, &key, &blobs).await.unwrap();
+    assert_eq!(loaded.compression().await, Some(Compression::Zstd));
+    assert_eq!(loaded.cat(&path).await.unwrap(), data);
+}
+
+#[tokio::test]
+async fn test_uncompressed_bucket_by_default() {
+    let (mut mount, _, _, _temp) = common::setup_test_env().await;
+    assert_eq!(mount.compression().await, None);
+
+    let data = "plain ".repeat(500).into_bytes();
+    let path = PathBuf::from("/plain.txt");
+    mount.add(&path, Cursor::new(data.clone())).await.unwrap();
+
+    let node_link = mount.get(&path).await.unwrap();
+    assert_eq!(node_link.data().unwrap().compression(), None);
+    assert_eq!(mount.cat(&path).await.unwrap(), data);
+}
@@ -0,0 +1,71 @@
+//! Embedding a node as a library (see `common::embed`)
+
+use std::path::Path;
+
+use futures::StreamExt;
+
+use common::bucket_log::MemoryBucketLogProvider;
+use common::embed::{NodeBuilder, NodeEvent};
+use common::peer::BlobsStore;
+
+#[tokio::test]
+async fn test_embedded_node_reads_and_writes_buckets() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let blobs = BlobsStore::fs(&temp_dir.path().join("blobs"))
+        .await
+        .unwrap();
+    let node = NodeBuilder::new(MemoryBucketLogProvider::new())
+        .blobs_store(blobs)
+        .build()
+        .await;
+    let mut events = Box::pin(node.events());
+
+    let bucket_id = node.create_bucket("notes").await.unwrap();
+    assert_eq!(node.buckets().await.unwrap(), [bucket_id]);
+    let link = node
+        .add(bucket_id, Path::new("/todo.md"), &b"- ship it"[..])
+        .await
+        .unwrap();
+    node.mkdir(bucket_id, Path::new("/drafts")).await.unwrap();
+
+    assert_eq!(
+        node.cat(bucket_id, Path::new("/todo.md")).await.unwrap(),
+        b"- ship it"
+    );
+    let names: Vec<_> = node
+        .ls(bucket_id, Path::new("/"))
+        .await
+        .unwrap()
+        .into_keys()
+        .collect();
+    assert_eq!(names, [Path::new("drafts"), Path::new("todo.md")]);
+
+    // Every save is an event, in order
+    assert!(matches!(
+        events.next().await,
+        Some(NodeEvent::Saved { height: 0, .. })
+    ));
+    assert_eq!(
+        events.next().await,
+        Some(NodeEvent::Saved {
+            bucket_id,
+            link,
+            height: 1
+        })
+    );
+    assert!(matches!(
+        events.next().await,
+        Some(NodeEvent::Saved { height: 2, .. })
+    ));
+
+    // Pausing twice is one pause
+    node.pause_sync();
+    node.pause_sync();
+    assert!(node.is_sync_paused());
+    node.resume_sync();
+    assert!(!node.is_sync_paused());
+    assert_eq!(events.next().await, Some(NodeEvent::SyncPaused));
+    assert_eq!(events.next().await, Some(NodeEvent::SyncResumed));
+
+    node.shutdown().await.unwrap();
+}
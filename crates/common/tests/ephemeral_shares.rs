@@ -0,0 +1,69 @@
+//! Integration tests for sharing a bucket with a temporary identity
+
+mod common;
+
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ::common::mount::{Mount, MountError};
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+#[tokio::test]
+async fn test_temporary_identity_reads_until_its_share_expires() {
+    let (mut mount, blobs, _owner_key, _temp) = common::setup_test_env().await;
+    mount
+        .add(&PathBuf::from("/a.txt"), Cursor::new(b"a".to_vec()))
+        .await
+        .unwrap();
+    let temporary = mount.add_ephemeral_share(now() + 3600).await;
+    let (link, _, _) = mount.save(&blobs, false).await.unwrap();
+
+    let manifest = Mount::load_manifest(&link, &blobs).await.unwrap();
+    assert!(manifest.get_share(&temporary.public()).is_none());
+    assert!(manifest.get_ephemeral_share(&temporary.public()).is_some());
+    let view = Mount::load(&link, &temporary, &blobs).await.unwrap();
+    assert_eq!(view.cat(&PathBuf::from("/a.txt")).await.unwrap(), b"a");
+
+    // Later versions are re-wrapped for it
+    mount
+        .add(&PathBuf::from("/b.txt"), Cursor::new(b"b".to_vec()))
+        .await
+        .unwrap();
+    let (link, _, _) = mount.save(&blobs, false).await.unwrap();
+    let view = Mount::load(&link, &temporary, &blobs).await.unwrap();
+    assert_eq!(view.cat(&PathBuf::from("/b.txt")).await.unwrap(), b"b");
+
+    // The view is read-only
+    let mut view = view;
+    view.add(&PathBuf::from("/c.txt"), Cursor::new(b"no".to_vec()))
+        .await
+        .unwrap();
+    assert!(matches!(
+        view.save(&blobs, false).await,
+        Err(MountError::EphemeralReadOnly)
+    ));
+}
+
+#[tokio::test]
+async fn test_expired_ephemeral_shares_are_dropped_on_save() {
+    let (mut mount, blobs, _owner_key, _temp) = common::setup_test_env().await;
+    let expired = mount.add_ephemeral_share(now() - 1).await;
+    let lasting = mount.add_ephemeral_share(now() + 3600).await;
+    let (link, _, _) = mount.save(&blobs, false).await.unwrap();
+
+    let manifest = Mount::load_manifest(&link, &blobs).await.unwrap();
+    assert!(manifest.get_ephemeral_share(&expired.public()).is_none());
+    assert!(manifest.get_ephemeral_share(&lasting.public()).is_some());
+    assert!(matches!(
+        Mount::load(&link, &expired, &blobs).await,
+        Err(MountError::ShareNotFound)
+    ));
+    assert!(Mount::load(&link, &lasting, &blobs).await.is_ok());
+}
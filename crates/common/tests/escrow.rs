@@ -0,0 +1,40 @@
+//! Integration tests for recovering bucket access from Shamir shards
+
+mod common;
+
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use ::common::crypto::shamir;
+use ::common::crypto::SecretKey;
+use ::common::mount::Mount;
+
+#[tokio::test]
+async fn test_rebuilt_recovery_identity_grants_a_new_owner() {
+    let (mut mount, blobs, _owner_key, _temp) = common::setup_test_env().await;
+    mount
+        .add(
+            &PathBuf::from("/file.txt"),
+            Cursor::new(b"recoverable".to_vec()),
+        )
+        .await
+        .unwrap();
+    let recovery_key = SecretKey::generate();
+    mount.add_owner(recovery_key.public()).await.unwrap();
+    let (link, _, _) = mount.save(&blobs, false).await.unwrap();
+
+    let shards = shamir::split(&recovery_key.to_bytes(), 2, 3).unwrap();
+    let bytes: [u8; 32] = shamir::combine(&shards[1..]).unwrap().try_into().unwrap();
+    let rebuilt = SecretKey::from(bytes);
+    assert_eq!(rebuilt.public(), recovery_key.public());
+
+    // The owner lost its key: the rebuilt identity adds a new one
+    let new_owner = SecretKey::generate();
+    let mut recovered = Mount::load(&link, &rebuilt, &blobs).await.unwrap();
+    recovered.add_owner(new_owner.public()).await.unwrap();
+    let (link, _, _) = recovered.save(&blobs, false).await.unwrap();
+
+    let mount = Mount::load(&link, &new_owner, &blobs).await.unwrap();
+    let data = mount.cat(&PathBuf::from("/file.txt")).await.unwrap();
+    assert_eq!(data, b"recoverable");
+}
@@ -0,0 +1,60 @@
+//! Integration tests for forking a bucket into a new, independent one
+
+mod common;
+
+use std::collections::HashSet;
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use ::common::crypto::SecretKey;
+use ::common::mount::{Compression, Mount};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn test_fork_is_independent_and_reuses_blobs() {
+    let (mut mount, blobs, _owner_key, _temp) = common::setup_test_env().await;
+    mount.set_compression(Some(Compression::Zstd)).await;
+    mount
+        .add(&PathBuf::from("/docs/a.txt"), Cursor::new(b"a".to_vec()))
+        .await
+        .unwrap();
+    let (source_link, _, _) = mount.save(&blobs, false).await.unwrap();
+    let source_file = mount.get(&PathBuf::from("/docs/a.txt")).await.unwrap();
+
+    let forker = SecretKey::generate();
+    let fork_id = Uuid::new_v4();
+    let fork = mount
+        .fork(fork_id, "fork".to_string(), &forker)
+        .await
+        .unwrap();
+    let (fork_link, _, _) = fork.save(&blobs, false).await.unwrap();
+
+    // A new bucket, owned by the forker alone, with the same contents
+    let manifest = Mount::load_manifest(&fork_link, &blobs).await.unwrap();
+    assert_eq!(*manifest.id(), fork_id);
+    assert_eq!(manifest.shares().len(), 1);
+    assert!(manifest.get_share(&forker.public()).is_some());
+    assert_eq!(manifest.height(), 1);
+    assert_eq!(manifest.compression(), Some(Compression::Zstd));
+    let view = Mount::load(&fork_link, &forker, &blobs).await.unwrap();
+    assert_eq!(view.cat(&PathBuf::from("/docs/a.txt")).await.unwrap(), b"a");
+
+    // The file's blob is reused, and pinned by the fork
+    let fork_file = view.get(&PathBuf::from("/docs/a.txt")).await.unwrap();
+    assert_eq!(fork_file.link().hash(), source_file.link().hash());
+    let pins: HashSet<_> = view.inner().await.pins().iter().cloned().collect();
+    assert!(pins.contains(&source_file.link().hash()));
+
+    // The source's owner can't open it, and changes don't cross over
+    let source_manifest = Mount::load_manifest(&source_link, &blobs).await.unwrap();
+    for source_owner in source_manifest.shares().keys() {
+        assert!(!manifest.shares().contains_key(source_owner));
+    }
+    let mut view = view;
+    view.rm(&PathBuf::from("/docs/a.txt")).await.unwrap();
+    view.save(&blobs, false).await.unwrap();
+    assert_eq!(
+        mount.cat(&PathBuf::from("/docs/a.txt")).await.unwrap(),
+        b"a"
+    );
+}
@@ -62,6 +62,25 @@ async fn test_mirror_can_mount_published_bucket() {
     assert_eq!(data, b"published data");
 }
 
+#[tokio::test]
+async fn test_follower_without_share_mounts_published_only() {
+    let (mount, blobs, _, _, _temp) = setup_mount_with_mirror(b"followed data").await;
+    let follower_key = SecretKey::generate();
+
+    // Unpublished versions stay private to shares
+    let (link, _, _) = mount.save(&blobs, false).await.unwrap();
+    let result = Mount::load(&link, &follower_key, &blobs).await;
+    assert!(matches!(result, Err(MountError::ShareNotFound)));
+
+    // Published versions can be read without a share
+    let (link, _, _) = mount.publish().await.unwrap();
+    let follower_mount = Mount::load(&link, &follower_key, &blobs)
+        .await
+        .expect("Follower should be able to mount published bucket");
+    let data = follower_mount.cat(&PathBuf::from(TEST_PATH)).await.unwrap();
+    assert_eq!(data, b"followed data");
+}
+
 #[tokio::test]
 async fn test_owner_can_always_mount() {
     let (mount, blobs, owner_key, _, _temp) = setup_mount_with_mirror(b"owner data").await;
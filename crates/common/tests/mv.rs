@@ -174,3 +174,23 @@ async fn test_mv_into_self() {
         .unwrap();
     assert_eq!(data, b"data");
 }
+
+#[tokio::test]
+async fn test_mv_under_file_keeps_source() {
+    let (mut mount, _, _, _temp) = common::setup_test_env().await;
+
+    mount.mkdir(&PathBuf::from("/dir")).await.unwrap();
+    mount
+        .add(&PathBuf::from("/file.txt"), Cursor::new(b"data".to_vec()))
+        .await
+        .unwrap();
+
+    // A file can't hold children, so the move must fail...
+    let result = mount
+        .mv(&PathBuf::from("/dir"), &PathBuf::from("/file.txt/dir"))
+        .await;
+    assert!(matches!(result, Err(MountError::PathNotNode(_))));
+
+    // ...without losing the source
+    assert!(mount.get(&PathBuf::from("/dir")).await.unwrap().is_dir());
+}
@@ -0,0 +1,73 @@
+//! Integration tests for hybrid X25519 + ML-KEM-768 owner shares
+
+mod common;
+
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use ::common::crypto::{KemPublicKey, KeyWrap, PublicKey, SecretKey};
+use ::common::linked_data::Link;
+use ::common::mount::Mount;
+use ::common::peer::BlobsStore;
+
+async fn key_wrap_of(link: &Link, blobs: &BlobsStore, owner: &PublicKey) -> KeyWrap {
+    let manifest = Mount::load_manifest(link, blobs).await.unwrap();
+    manifest.shares()[&owner.to_hex()]
+        .share()
+        .unwrap()
+        .key_wrap()
+}
+
+#[tokio::test]
+async fn test_owners_with_kem_keys_get_hybrid_shares() {
+    let (mut mount, blobs, owner_key, _temp) = common::setup_test_env().await;
+    mount
+        .add(
+            &PathBuf::from("/file.txt"),
+            Cursor::new(b"pq data".to_vec()),
+        )
+        .await
+        .unwrap();
+    let co_owner_key = SecretKey::generate();
+    mount.add_owner(co_owner_key.public()).await.unwrap();
+
+    for key in [&owner_key, &co_owner_key] {
+        mount
+            .set_kem_key(&key.public(), Some(KemPublicKey::of(key)))
+            .await
+            .unwrap();
+    }
+    let (link, _, _) = mount.save(&blobs, false).await.unwrap();
+
+    for key in [&owner_key, &co_owner_key] {
+        assert_eq!(
+            key_wrap_of(&link, &blobs, &key.public()).await,
+            KeyWrap::X25519MlKem768
+        );
+        let loaded = Mount::load(&link, key, &blobs).await.unwrap();
+        let data = loaded.cat(&PathBuf::from("/file.txt")).await.unwrap();
+        assert_eq!(data, b"pq data");
+    }
+
+    // Clearing the keys goes back to classic shares
+    for key in [&owner_key, &co_owner_key] {
+        mount.set_kem_key(&key.public(), None).await.unwrap();
+    }
+    let (link, _, _) = mount.save(&blobs, false).await.unwrap();
+    assert_eq!(
+        key_wrap_of(&link, &blobs, &co_owner_key.public()).await,
+        KeyWrap::X25519AesKw
+    );
+    Mount::load(&link, &co_owner_key, &blobs).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_mirrors_have_no_kem_key() {
+    let (mut mount, _blobs, _, _temp) = common::setup_test_env().await;
+    let mirror_key = SecretKey::generate();
+    mount.add_mirror(mirror_key.public()).await;
+    assert!(mount
+        .set_kem_key(&mirror_key.public(), Some(KemPublicKey::of(&mirror_key)))
+        .await
+        .is_err());
+}
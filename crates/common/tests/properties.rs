@@ -0,0 +1,290 @@
+//! Property tests for Mount path operations
+//!
+//! Random sequences of add/mkdir/mv/rm/cp are applied both to a mount and to
+//! a plain in-memory model of the tree. After every operation the mount must
+//! agree with the model (same entries from `ls_deep`, `ls` of every directory
+//! and `cat` of every file), whether the operation succeeded or was refused,
+//! and a saved and reloaded mount must agree with it too.
+
+mod common;
+
+use std::collections::BTreeMap;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use ::common::mount::Mount;
+use proptest::prelude::*;
+
+/// Names paths are built from: few, so operations collide often, and
+/// including unicode and spaces
+const NAMES: &[&str] = &["a", "b", "x.txt", "ñ", "日本", "with space", "e\u{301}"];
+
+const MAX_DEPTH: usize = 5;
+
+#[derive(Debug, Clone)]
+struct TestPath {
+    parts: Vec<&'static str>,
+    trailing_slash: bool,
+}
+
+impl TestPath {
+    fn key(&self) -> String {
+        self.parts.join("/")
+    }
+
+    fn path(&self) -> PathBuf {
+        let mut path = format!("/{}", self.key());
+        if self.trailing_slash {
+            path.push('/');
+        }
+        PathBuf::from(path)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Op {
+    Add(TestPath, Vec<u8>),
+    Mkdir(TestPath),
+    Mv(TestPath, TestPath),
+    Rm(TestPath),
+    /// Mount has no copy; clients copy a file by reading and adding it
+    Cp(TestPath, TestPath),
+}
+
+fn test_path() -> impl Strategy<Value = TestPath> {
+    (
+        prop::collection::vec(prop::sample::select(NAMES), 1..=MAX_DEPTH),
+        any::<bool>(),
+    )
+        .prop_map(|(parts, trailing_slash)| TestPath {
+            parts,
+            trailing_slash,
+        })
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        3 => (test_path(), prop::collection::vec(any::<u8>(), 0..64))
+            .prop_map(|(path, data)| Op::Add(path, data)),
+        2 => test_path().prop_map(Op::Mkdir),
+        2 => (test_path(), test_path()).prop_map(|(from, to)| Op::Mv(from, to)),
+        1 => test_path().prop_map(Op::Rm),
+        1 => (test_path(), test_path()).prop_map(|(from, to)| Op::Cp(from, to)),
+    ]
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Entry {
+    File(Vec<u8>),
+    Dir,
+}
+
+/// The tree a mount should hold, keyed by path relative to the root
+#[derive(Debug, Clone, Default)]
+struct Model(BTreeMap<String, Entry>);
+
+impl Model {
+    fn ancestors(key: &str) -> Vec<String> {
+        let parts: Vec<&str> = key.split('/').collect();
+        (1..parts.len()).map(|i| parts[..i].join("/")).collect()
+    }
+
+    /// Whether an entry can be put at `key`: no ancestor may be a file
+    fn can_insert(&self, key: &str) -> bool {
+        Self::ancestors(key)
+            .iter()
+            .all(|ancestor| !self.is_file(ancestor))
+    }
+
+    fn is_file(&self, key: &str) -> bool {
+        matches!(self.0.get(key), Some(Entry::File(_)))
+    }
+
+    fn insert(&mut self, key: &str, entry: Entry) {
+        for ancestor in Self::ancestors(key) {
+            self.0.entry(ancestor).or_insert(Entry::Dir);
+        }
+        self.0.insert(key.to_string(), entry);
+    }
+
+    /// Remove `key` and everything under it, returning the removed entries
+    /// keyed relative to `key`
+    fn remove(&mut self, key: &str) -> BTreeMap<String, Entry> {
+        let prefix = format!("{}/", key);
+        let keys: Vec<String> = self
+            .0
+            .keys()
+            .filter(|k| *k == key || k.starts_with(&prefix))
+            .cloned()
+            .collect();
+        keys.into_iter()
+            .map(|k| {
+                let entry = self.0.remove(&k).unwrap();
+                (k[key.len()..].to_string(), entry)
+            })
+            .collect()
+    }
+
+    /// Apply `op`, returning whether it should succeed. The model is left
+    /// untouched if not.
+    fn apply(&mut self, op: &Op) -> bool {
+        match op {
+            Op::Add(path, data) => {
+                let key = path.key();
+                if !self.can_insert(&key) || self.0.get(&key) == Some(&Entry::Dir) {
+                    return false;
+                }
+                self.insert(&key, Entry::File(data.clone()));
+                true
+            }
+            Op::Mkdir(path) => {
+                let key = path.key();
+                if !self.can_insert(&key) || self.0.contains_key(&key) {
+                    return false;
+                }
+                self.insert(&key, Entry::Dir);
+                true
+            }
+            Op::Mv(from, to) => {
+                let (from, to) = (from.key(), to.key());
+                if !self.0.contains_key(&from)
+                    || self.0.contains_key(&to)
+                    || to == from
+                    || to.starts_with(&format!("{}/", from))
+                {
+                    return false;
+                }
+                let mut moved = self.clone();
+                let subtree = moved.remove(&from);
+                if !moved.can_insert(&to) {
+                    return false;
+                }
+                for (suffix, entry) in subtree {
+                    moved.insert(&format!("{}{}", to, suffix), entry);
+                }
+                *self = moved;
+                true
+            }
+            Op::Rm(path) => {
+                let key = path.key();
+                if !self.0.contains_key(&key) {
+                    return false;
+                }
+                self.remove(&key);
+                true
+            }
+            Op::Cp(from, to) => {
+                let Some(Entry::File(data)) = self.0.get(&from.key()).cloned() else {
+                    return false;
+                };
+                self.apply(&Op::Add(to.clone(), data))
+            }
+        }
+    }
+}
+
+async fn apply(mount: &mut Mount, op: &Op) -> bool {
+    match op {
+        Op::Add(path, data) => mount
+            .add(&path.path(), Cursor::new(data.clone()))
+            .await
+            .is_ok(),
+        Op::Mkdir(path) => mount.mkdir(&path.path()).await.is_ok(),
+        Op::Mv(from, to) => mount.mv(&from.path(), &to.path()).await.is_ok(),
+        Op::Rm(path) => mount.rm(&path.path()).await.is_ok(),
+        Op::Cp(from, to) => match mount.cat(&from.path()).await {
+            Ok(data) => mount.add(&to.path(), Cursor::new(data)).await.is_ok(),
+            Err(_) => false,
+        },
+    }
+}
+
+/// Check that `mount` holds exactly the tree of `model`
+async fn assert_agrees(mount: &Mount, model: &Model) -> Result<(), TestCaseError> {
+    let entries: BTreeMap<String, bool> = mount
+        .ls_deep(Path::new("/"))
+        .await
+        .map_err(|e| TestCaseError::fail(format!("ls_deep failed: {}", e)))?
+        .into_iter()
+        .map(|(path, link)| (path.to_string_lossy().to_string(), link.is_dir()))
+        .collect();
+    let expected: BTreeMap<String, bool> = model
+        .0
+        .iter()
+        .map(|(key, entry)| (key.clone(), *entry == Entry::Dir))
+        .collect();
+    prop_assert_eq!(&entries, &expected);
+
+    for (key, entry) in &model.0 {
+        let path = PathBuf::from(format!("/{}", key));
+        match entry {
+            Entry::File(data) => {
+                let content = mount
+                    .cat(&path)
+                    .await
+                    .map_err(|e| TestCaseError::fail(format!("cat {} failed: {}", key, e)))?;
+                prop_assert_eq!(&content, data, "contents of {}", key);
+            }
+            Entry::Dir => {
+                let children: Vec<String> = mount
+                    .ls(&path)
+                    .await
+                    .map_err(|e| TestCaseError::fail(format!("ls {} failed: {}", key, e)))?
+                    .into_keys()
+                    .map(|path| path.to_string_lossy().to_string())
+                    .collect();
+                let prefix = format!("{}/", key);
+                let expected: Vec<String> = model
+                    .0
+                    .keys()
+                    .filter(|k| {
+                        k.strip_prefix(&prefix)
+                            .is_some_and(|rest| !rest.contains('/'))
+                    })
+                    .cloned()
+                    .collect();
+                prop_assert_eq!(children, expected, "children of {}", key);
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn run(ops: Vec<Op>) -> Result<(), TestCaseError> {
+    let (mut mount, blobs, key, _temp) = common::setup_test_env().await;
+    let mut model = Model::default();
+
+    for op in &ops {
+        let expected = model.clone().apply(op);
+        let succeeded = apply(&mut mount, op).await;
+        prop_assert_eq!(succeeded, expected, "outcome of {:?}", op);
+        model.apply(op);
+        assert_agrees(&mount, &model).await?;
+    }
+
+    let (link, _, _) = mount
+        .save(&blobs, false)
+        .await
+        .map_err(|e| TestCaseError::fail(format!("save failed: {}", e)))?;
+    let loaded = Mount::load(&link, &key, &blobs)
+        .await
+        .map_err(|e| TestCaseError::fail(format!("load failed: {}", e)))?;
+    assert_agrees(&loaded, &model).await
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        cases: 64,
+        failure_persistence: None,
+        ..ProptestConfig::default()
+    })]
+
+    #[test]
+    fn test_path_operations_agree_with_model(ops in prop::collection::vec(op(), 1..24)) {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(run(ops))?;
+    }
+}
@@ -0,0 +1,155 @@
+//! Integration tests for sharing a single directory of a bucket
+
+mod common;
+
+use std::collections::HashSet;
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use ::common::crypto::SecretKey;
+use ::common::mount::{Mount, MountError};
+
+#[tokio::test]
+async fn test_subtree_share_reads_only_its_directory() {
+    let (mut mount, blobs, _owner_key, _temp) = common::setup_test_env().await;
+    mount
+        .add(
+            &PathBuf::from("/clients/acme/report.txt"),
+            Cursor::new(b"for acme".to_vec()),
+        )
+        .await
+        .unwrap();
+    mount
+        .add(
+            &PathBuf::from("/clients/globex/report.txt"),
+            Cursor::new(b"for globex".to_vec()),
+        )
+        .await
+        .unwrap();
+    let client = SecretKey::generate();
+    mount
+        .add_subtree_share(client.public(), &PathBuf::from("/clients/acme"))
+        .await
+        .unwrap();
+    let (link, _, _) = mount.save(&blobs, false).await.unwrap();
+
+    let view = Mount::load(&link, &client, &blobs).await.unwrap();
+    assert_eq!(
+        view.inner().await.subtree(),
+        Some(PathBuf::from("/clients/acme").as_path())
+    );
+    let data = view.cat(&PathBuf::from("/report.txt")).await.unwrap();
+    assert_eq!(data, b"for acme");
+    let names: Vec<PathBuf> = view
+        .ls(&PathBuf::from("/"))
+        .await
+        .unwrap()
+        .into_keys()
+        .collect();
+    assert_eq!(names, vec![PathBuf::from("report.txt")]);
+
+    // The view is read-only
+    let mut view = view;
+    view.add(&PathBuf::from("/new.txt"), Cursor::new(b"no".to_vec()))
+        .await
+        .unwrap();
+    assert!(matches!(
+        view.save(&blobs, false).await,
+        Err(MountError::SubtreeReadOnly)
+    ));
+}
+
+#[tokio::test]
+async fn test_subtree_share_follows_changes_below_it() {
+    let (mut mount, blobs, _owner_key, _temp) = common::setup_test_env().await;
+    mount
+        .add(&PathBuf::from("/shared/a.txt"), Cursor::new(b"a".to_vec()))
+        .await
+        .unwrap();
+    let client = SecretKey::generate();
+    mount
+        .add_subtree_share(client.public(), &PathBuf::from("/shared"))
+        .await
+        .unwrap();
+    mount.save(&blobs, false).await.unwrap();
+
+    // The directory's node gets a new secret, re-shared on save
+    mount
+        .add(&PathBuf::from("/shared/b.txt"), Cursor::new(b"b".to_vec()))
+        .await
+        .unwrap();
+    let (link, _, _) = mount.save(&blobs, false).await.unwrap();
+    let view = Mount::load(&link, &client, &blobs).await.unwrap();
+    assert_eq!(view.cat(&PathBuf::from("/b.txt")).await.unwrap(), b"b");
+
+    // Moved away, the share holds nothing
+    mount
+        .mv(&PathBuf::from("/shared"), &PathBuf::from("/elsewhere"))
+        .await
+        .unwrap();
+    let (link, _, _) = mount.save(&blobs, false).await.unwrap();
+    assert!(matches!(
+        Mount::load(&link, &client, &blobs).await,
+        Err(MountError::PathNotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_reachable_hashes_stay_below_the_directory() {
+    let (mut mount, blobs, _owner_key, _temp) = common::setup_test_env().await;
+    mount
+        .add(
+            &PathBuf::from("/shared/a.txt"),
+            Cursor::new(b"inside".to_vec()),
+        )
+        .await
+        .unwrap();
+    mount
+        .add(
+            &PathBuf::from("/private.txt"),
+            Cursor::new(b"outside".to_vec()),
+        )
+        .await
+        .unwrap();
+    mount.save(&blobs, false).await.unwrap();
+
+    let shared = mount.get(&PathBuf::from("/shared")).await.unwrap();
+    let file = mount.get(&PathBuf::from("/shared/a.txt")).await.unwrap();
+    let private = mount.get(&PathBuf::from("/private.txt")).await.unwrap();
+    let reachable: HashSet<_> = Mount::reachable(&shared, &blobs)
+        .await
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert!(reachable.contains(&shared.link().hash()));
+    assert!(reachable.contains(&file.link().hash()));
+    assert!(!reachable.contains(&private.link().hash()));
+}
+
+#[tokio::test]
+async fn test_subtree_share_needs_a_directory_and_no_full_share() {
+    let (mut mount, _blobs, _owner_key, _temp) = common::setup_test_env().await;
+    mount
+        .add(&PathBuf::from("/file.txt"), Cursor::new(b"x".to_vec()))
+        .await
+        .unwrap();
+    let peer = SecretKey::generate();
+
+    assert!(mount
+        .add_subtree_share(peer.public(), &PathBuf::from("/file.txt"))
+        .await
+        .is_err());
+    assert!(mount
+        .add_subtree_share(peer.public(), &PathBuf::from("/"))
+        .await
+        .is_err());
+
+    mount.mkdir(&PathBuf::from("/dir")).await.unwrap();
+    mount.add_mirror(peer.public()).await;
+    assert!(matches!(
+        mount
+            .add_subtree_share(peer.public(), &PathBuf::from("/dir"))
+            .await,
+        Err(MountError::AlreadyShared(_))
+    ));
+}
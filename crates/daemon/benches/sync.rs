@@ -0,0 +1,75 @@
+//! Sync of buckets holding M blobs between two in-process daemons
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use std::time::{Duration, Instant};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use tokio::runtime::Runtime;
+
+use jax_daemon::http_server::api::v0::bucket::share::ShareRole;
+
+use crate::common::{start_daemons, TestDaemon};
+
+const BLOB_COUNTS: &[usize] = &[10, 100];
+const FILE_SIZE: usize = 16 * 1024;
+
+/// Fill a fresh bucket on the first daemon with `files` files, share it with
+/// the second and time until the second can read them all
+async fn sync_bucket(daemons: &mut [TestDaemon], files: usize, round: u64) -> Duration {
+    let (alice, bob) = daemons.split_at_mut(1);
+    let (alice, bob) = (&mut alice[0], &mut bob[0]);
+
+    let bucket_id = alice.create_bucket(&format!("bench-{}", round)).await;
+    let contents: Vec<Vec<u8>> = (0..files)
+        .map(|i| {
+            let mut data = vec![(i % 251) as u8; FILE_SIZE];
+            data[..8].copy_from_slice(&round.to_le_bytes());
+            data
+        })
+        .collect();
+    for (i, data) in contents.iter().enumerate() {
+        alice
+            .add_file(bucket_id, &format!("/files/{}.bin", i), data)
+            .await;
+    }
+
+    let start = Instant::now();
+    alice.share(bucket_id, bob, ShareRole::Owner).await;
+    for (i, data) in contents.iter().enumerate() {
+        bob.wait_for_file(bucket_id, &format!("/files/{}.bin", i), data)
+            .await;
+    }
+    start.elapsed()
+}
+
+fn bench_sync(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let mut daemons = runtime.block_on(start_daemons(2));
+
+    let mut group = c.benchmark_group("sync");
+    group.sample_size(10);
+    let mut round = 0;
+    for &files in BLOB_COUNTS {
+        group.throughput(Throughput::Bytes((files * FILE_SIZE) as u64));
+        group.bench_function(BenchmarkId::new("shared_bucket", files), |b| {
+            b.iter_custom(|iters| {
+                let mut total = Duration::ZERO;
+                for _ in 0..iters {
+                    round += 1;
+                    total += runtime.block_on(sync_bucket(&mut daemons, files, round));
+                }
+                total
+            })
+        });
+    }
+    group.finish();
+
+    for daemon in daemons {
+        runtime.block_on(daemon.stop());
+    }
+}
+
+criterion_group!(benches, bench_sync);
+criterion_main!(benches);
@@ -0,0 +1,3 @@
+-- Drop blob_refs table and indexes
+DROP INDEX IF EXISTS idx_blob_refs_hash;
+DROP TABLE IF EXISTS blob_refs;
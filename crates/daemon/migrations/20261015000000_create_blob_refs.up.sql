@@ -0,0 +1,19 @@
+-- Create blob_refs table for per-bucket reachability accounting
+CREATE TABLE blob_refs (
+    -- The bucket that references the blob
+    bucket_id TEXT NOT NULL,
+    -- Absolute path of the file within the bucket
+    path TEXT NOT NULL,
+    -- Hash of the (encrypted) blob backing the file
+    hash TEXT NOT NULL,
+    -- Size of the blob in bytes
+    size INTEGER NOT NULL,
+    -- When this reference was last accounted
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    -- One reference per path per bucket
+    PRIMARY KEY (bucket_id, path)
+);
+
+-- Index for finding duplicate references to the same blob
+CREATE INDEX idx_blob_refs_hash ON blob_refs(hash);
@@ -0,0 +1,6 @@
+-- Create settings table for daemon state changed at runtime (e.g. sync pause)
+CREATE TABLE settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,13 @@
+-- Create bucket_sync_policies table for per-bucket sync scheduling
+CREATE TABLE bucket_sync_policies (
+    bucket_id TEXT PRIMARY KEY,
+    -- Seconds between periodic pings, NULL for the daemon default
+    interval_secs INTEGER,
+    -- Skip periodic pings; only manual syncs and peer announcements apply
+    manual_only BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Don't sync the bucket at all
+    paused BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Daily local time window for periodic pings (HH:MM-HH:MM), NULL for always
+    active_hours TEXT,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,4 @@
+-- Drop blob_cache table and the online-only policy flag
+DROP INDEX IF EXISTS idx_blob_cache_last_accessed;
+DROP TABLE IF EXISTS blob_cache;
+ALTER TABLE bucket_sync_policies DROP COLUMN online_only;
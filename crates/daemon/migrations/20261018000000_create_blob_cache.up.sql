@@ -0,0 +1,17 @@
+-- Online-only buckets skip downloading their pins and fetch blobs on read
+ALTER TABLE bucket_sync_policies ADD COLUMN online_only BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Create blob_cache table for blobs fetched on demand for online-only buckets
+CREATE TABLE blob_cache (
+    -- Hash of the fetched blob
+    hash TEXT PRIMARY KEY,
+    -- Size of the blob in bytes
+    size INTEGER NOT NULL,
+    -- When the blob was fetched
+    fetched_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    -- When the blob was last read, used to evict the least recently used first
+    last_accessed TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Index for evicting in order of last access
+CREATE INDEX idx_blob_cache_last_accessed ON blob_cache(last_accessed);
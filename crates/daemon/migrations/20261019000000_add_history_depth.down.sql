@@ -0,0 +1,2 @@
+-- Drop the history depth policy column
+ALTER TABLE bucket_sync_policies DROP COLUMN history_depth;
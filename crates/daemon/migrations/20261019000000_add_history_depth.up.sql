@@ -0,0 +1,2 @@
+-- Number of most recent versions whose content is downloaded, NULL for all
+ALTER TABLE bucket_sync_policies ADD COLUMN history_depth INTEGER;
@@ -0,0 +1,2 @@
+-- Drop followed_buckets table
+DROP TABLE IF EXISTS followed_buckets;
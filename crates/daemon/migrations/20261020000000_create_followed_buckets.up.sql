@@ -0,0 +1,7 @@
+-- Create followed_buckets table for published buckets followed without a share
+CREATE TABLE followed_buckets (
+    bucket_id TEXT PRIMARY KEY,
+    -- Node the bucket is followed from (hex public key)
+    source TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,2 @@
+-- Drop gateway_links table
+DROP TABLE IF EXISTS gateway_links;
@@ -0,0 +1,7 @@
+-- Create gateway_links table for rotatable public gateway links of published buckets
+CREATE TABLE gateway_links (
+    bucket_id TEXT PRIMARY KEY,
+    -- Random path segment the gateway serves the bucket at instead of its id
+    token TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,2 @@
+-- Drop gateway_bucket_policies table
+DROP TABLE IF EXISTS gateway_bucket_policies;
@@ -0,0 +1,11 @@
+-- Create gateway_bucket_policies table for per-bucket gateway serving rules
+CREATE TABLE gateway_bucket_policies (
+    bucket_id TEXT PRIMARY KEY,
+    -- Served when the gateway only serves allowlisted buckets
+    allowed BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Whether directory listings are shown (index files are always served)
+    listings BOOLEAN NOT NULL DEFAULT TRUE,
+    -- Bytes the gateway serves for the bucket per day, NULL for no cap
+    daily_bandwidth_bytes INTEGER,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
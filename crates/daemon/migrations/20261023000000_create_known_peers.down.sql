@@ -0,0 +1,2 @@
+-- Drop known_peers table
+DROP TABLE IF EXISTS known_peers;
@@ -0,0 +1,12 @@
+-- Create known_peers table, the address book of peers dialed on startup
+CREATE TABLE known_peers (
+    -- Hex public key of the peer
+    node_id TEXT PRIMARY KEY,
+    -- Home relay of the peer, NULL if unknown
+    relay_url TEXT,
+    -- Comma separated socket addresses the peer was last reached at
+    direct_addresses TEXT NOT NULL DEFAULT '',
+    -- When the peer was last connected, NULL if never
+    last_seen TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
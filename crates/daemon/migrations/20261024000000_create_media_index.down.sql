@@ -0,0 +1,4 @@
+-- Drop media_index tables and indexes
+DROP TABLE IF EXISTS media_index_versions;
+DROP INDEX IF EXISTS idx_media_index_captured_at;
+DROP TABLE IF EXISTS media_index;
@@ -0,0 +1,27 @@
+-- Create media_index table, the photos of each bucket ordered for a timeline
+CREATE TABLE media_index (
+    -- The bucket the photo is in
+    bucket_id TEXT NOT NULL,
+    -- Absolute path of the photo within the bucket
+    path TEXT NOT NULL,
+    mime_type TEXT NOT NULL,
+    -- When the photo was taken in seconds since the Unix epoch, NULL if unknown
+    captured_at INTEGER,
+    -- Dimensions in pixels as displayed, NULL if unknown
+    width INTEGER,
+    height INTEGER,
+
+    -- One entry per path per bucket
+    PRIMARY KEY (bucket_id, path)
+);
+
+-- Index for paging through the timeline newest first
+CREATE INDEX idx_media_index_captured_at ON media_index(captured_at DESC);
+
+-- The bucket version each bucket's media_index entries were read from
+CREATE TABLE media_index_versions (
+    bucket_id TEXT PRIMARY KEY,
+    -- Hash of the indexed version
+    version TEXT NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
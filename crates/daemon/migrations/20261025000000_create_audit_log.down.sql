@@ -0,0 +1,6 @@
+-- Drop audit_log table, its triggers and indexes
+DROP TRIGGER IF EXISTS audit_log_no_delete;
+DROP TRIGGER IF EXISTS audit_log_no_update;
+DROP INDEX IF EXISTS idx_audit_log_actor;
+DROP INDEX IF EXISTS idx_audit_log_bucket_id;
+DROP TABLE IF EXISTS audit_log;
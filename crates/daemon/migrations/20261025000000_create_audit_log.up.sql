@@ -0,0 +1,34 @@
+-- Create audit_log table, an append-only record of mutating operations
+CREATE TABLE audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    -- When the operation happened in seconds since the Unix epoch
+    at INTEGER NOT NULL,
+    -- Who made the change: "api", "desktop", "fuse", "mobile" or "peer:<hex key>"
+    actor TEXT NOT NULL,
+    -- What was done, e.g. "bucket.add" or "sync"
+    operation TEXT NOT NULL,
+    -- The bucket changed, NULL for daemon-wide operations
+    bucket_id TEXT,
+    -- Path within the bucket, NULL if the operation has none
+    path TEXT,
+    -- Anything else that identifies the change, e.g. the config key set,
+    -- the peer a bucket was shared with or where a path was moved to
+    detail TEXT,
+    -- Hash of the bucket version the operation resulted in, if any
+    version TEXT
+);
+
+-- Indexes for listing a bucket's or an actor's entries newest first
+CREATE INDEX idx_audit_log_bucket_id ON audit_log(bucket_id, id DESC);
+CREATE INDEX idx_audit_log_actor ON audit_log(actor, id DESC);
+
+-- Entries can't be changed or removed once written
+CREATE TRIGGER audit_log_no_update BEFORE UPDATE ON audit_log
+BEGIN
+    SELECT RAISE(ABORT, 'audit log is append-only');
+END;
+
+CREATE TRIGGER audit_log_no_delete BEFORE DELETE ON audit_log
+BEGIN
+    SELECT RAISE(ABORT, 'audit log is append-only');
+END;
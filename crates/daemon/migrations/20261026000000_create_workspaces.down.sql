@@ -0,0 +1,5 @@
+-- Drop workspaces
+DROP TABLE IF EXISTS workspace_shares;
+DROP INDEX IF EXISTS idx_workspace_buckets_workspace;
+DROP TABLE IF EXISTS workspace_buckets;
+DROP TABLE IF EXISTS workspaces;
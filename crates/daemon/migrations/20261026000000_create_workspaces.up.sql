@@ -0,0 +1,24 @@
+-- Create workspaces, local groupings of buckets
+CREATE TABLE workspaces (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- A bucket belongs to at most one workspace
+CREATE TABLE workspace_buckets (
+    bucket_id TEXT PRIMARY KEY,
+    workspace_id TEXT NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    added_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_workspace_buckets_workspace ON workspace_buckets(workspace_id);
+
+-- Peers a workspace is shared with; buckets added later are shared with them too
+CREATE TABLE workspace_shares (
+    workspace_id TEXT NOT NULL REFERENCES workspaces(id) ON DELETE CASCADE,
+    peer_public_key TEXT NOT NULL,
+    role TEXT NOT NULL,
+    shared_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (workspace_id, peer_public_key)
+);
@@ -0,0 +1,3 @@
+-- Drop member policies and pending shares
+DROP TABLE IF EXISTS pending_shares;
+DROP TABLE IF EXISTS bucket_member_policies;
@@ -0,0 +1,20 @@
+-- Per-bucket policies for new members; the node-wide default is a setting
+CREATE TABLE bucket_member_policies (
+    bucket_id TEXT PRIMARY KEY,
+    -- Download the content of the bucket when this node becomes a member
+    auto_pin BOOLEAN NOT NULL DEFAULT TRUE,
+    -- Record members other owners add in the audit log
+    notify_members BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Hold shares made on this node until an owner approves them
+    require_approval BOOLEAN NOT NULL DEFAULT FALSE,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Shares waiting for approval
+CREATE TABLE pending_shares (
+    bucket_id TEXT NOT NULL,
+    peer_public_key TEXT NOT NULL,
+    role TEXT NOT NULL,
+    requested_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (bucket_id, peer_public_key)
+);
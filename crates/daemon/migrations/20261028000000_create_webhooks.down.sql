@@ -0,0 +1,3 @@
+-- Drop webhooks
+DROP INDEX IF EXISTS idx_webhooks_bucket_id;
+DROP TABLE IF EXISTS webhooks;
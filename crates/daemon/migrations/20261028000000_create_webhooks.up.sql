@@ -0,0 +1,18 @@
+-- URLs the daemon POSTs bucket events to
+CREATE TABLE webhooks (
+    id TEXT PRIMARY KEY,
+    url TEXT NOT NULL,
+    -- NULL for events of every bucket
+    bucket_id TEXT,
+    -- Comma-separated event names the webhook is subscribed to
+    events TEXT NOT NULL,
+    -- Key of the HMAC-SHA256 signature sent with each delivery
+    secret TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    -- Outcome of the latest delivery attempt
+    last_delivery_at TIMESTAMP,
+    last_status INTEGER,
+    last_error TEXT
+);
+
+CREATE INDEX idx_webhooks_bucket_id ON webhooks(bucket_id);
@@ -0,0 +1,3 @@
+-- Drop deploy keys
+DROP INDEX IF EXISTS idx_deploy_keys_bucket_id;
+DROP TABLE IF EXISTS deploy_keys;
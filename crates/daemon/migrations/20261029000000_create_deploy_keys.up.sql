@@ -0,0 +1,14 @@
+-- Keys that sign writes to one bucket under a path prefix, for CI pipelines
+CREATE TABLE deploy_keys (
+    id TEXT PRIMARY KEY,
+    bucket_id TEXT NOT NULL,
+    -- Absolute path the key may write under; `/` for the whole bucket
+    path_prefix TEXT NOT NULL,
+    name TEXT,
+    -- Key of the HMAC-SHA256 request signatures
+    secret TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_used_at TIMESTAMP
+);
+
+CREATE INDEX idx_deploy_keys_bucket_id ON deploy_keys(bucket_id);
@@ -0,0 +1,4 @@
+-- Drop backup jobs and their snapshots
+DROP INDEX IF EXISTS idx_backup_snapshots_job_id;
+DROP TABLE IF EXISTS backup_snapshots;
+DROP TABLE IF EXISTS backup_jobs;
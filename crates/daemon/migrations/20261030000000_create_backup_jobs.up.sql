@@ -0,0 +1,32 @@
+-- Local directories backed up into buckets on a schedule
+CREATE TABLE backup_jobs (
+    id TEXT PRIMARY KEY,
+    name TEXT,
+    -- Absolute path of the directory on the daemon's machine
+    source_dir TEXT NOT NULL,
+    bucket_id TEXT NOT NULL,
+    -- Absolute path in the bucket the directory is mirrored to
+    target_path TEXT NOT NULL,
+    -- Cron expression, in local time
+    schedule TEXT NOT NULL,
+    -- Number of snapshots kept
+    retain INTEGER NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    next_run_at TIMESTAMP NOT NULL,
+    -- Outcome of the latest run; NULL error if it succeeded
+    last_run_at TIMESTAMP,
+    last_error TEXT
+);
+
+-- Bucket versions saved by backup runs, newest `retain` per job
+CREATE TABLE backup_snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    job_id TEXT NOT NULL,
+    version TEXT NOT NULL,
+    height INTEGER NOT NULL,
+    files_uploaded INTEGER NOT NULL,
+    files_deleted INTEGER NOT NULL,
+    taken_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX idx_backup_snapshots_job_id ON backup_snapshots(job_id);
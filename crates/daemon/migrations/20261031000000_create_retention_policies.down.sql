@@ -0,0 +1,3 @@
+-- Drop retention policies and the record of pruned versions
+DROP TABLE IF EXISTS pruned_versions;
+DROP TABLE IF EXISTS bucket_retention_policies;
@@ -0,0 +1,20 @@
+-- Per-bucket retention policies: the newest version of how many hours,
+-- days, weeks and months is kept. NULL keeps none of a period, 0 every one
+CREATE TABLE bucket_retention_policies (
+    bucket_id TEXT PRIMARY KEY,
+    hourly INTEGER,
+    daily INTEGER,
+    weekly INTEGER,
+    monthly INTEGER,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Versions whose content retention deleted; their log entries stay
+CREATE TABLE pruned_versions (
+    bucket_id TEXT NOT NULL,
+    height INTEGER NOT NULL,
+    -- Hash of the version's manifest
+    link TEXT NOT NULL,
+    pruned_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (bucket_id, height)
+);
@@ -0,0 +1,3 @@
+-- Drop the activity index
+DROP TABLE IF EXISTS activity_index_versions;
+DROP TABLE IF EXISTS activity_index;
@@ -0,0 +1,29 @@
+-- Paths each bucket version changed compared to the version before it,
+-- newest first for the activity feed
+CREATE TABLE activity_index (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    bucket_id TEXT NOT NULL,
+    -- Hash of the version that made the change
+    version TEXT NOT NULL,
+    height INTEGER NOT NULL,
+    -- Absolute path of the file within the bucket
+    path TEXT NOT NULL,
+    -- added, modified or removed
+    change TEXT NOT NULL,
+    -- Hex public key of the peer that signed the version, NULL if unsigned
+    author TEXT,
+    -- When this node recorded the version, in seconds since the Unix epoch
+    changed_at INTEGER NOT NULL
+);
+
+-- Index for paging through the feed newest first
+CREATE INDEX idx_activity_index_changed_at ON activity_index(changed_at DESC, height DESC);
+CREATE INDEX idx_activity_index_bucket ON activity_index(bucket_id);
+
+-- The bucket versions already compared, including those that couldn't be
+CREATE TABLE activity_index_versions (
+    bucket_id TEXT NOT NULL,
+    version TEXT NOT NULL,
+    indexed_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (bucket_id, version)
+);
@@ -0,0 +1,2 @@
+-- Drop favorites
+DROP TABLE IF EXISTS favorites;
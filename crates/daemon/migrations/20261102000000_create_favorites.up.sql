@@ -0,0 +1,9 @@
+-- Buckets and paths starred by the user of this node
+CREATE TABLE favorites (
+    bucket_id TEXT NOT NULL,
+    -- Absolute path of the starred file or directory, '' for the bucket itself
+    path TEXT NOT NULL DEFAULT '',
+    -- When it was starred, in seconds since the Unix epoch
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY (bucket_id, path)
+);
@@ -0,0 +1,5 @@
+-- Drop drop links and their moderation queue
+DROP INDEX IF EXISTS idx_drop_uploads_link_id;
+DROP TABLE IF EXISTS drop_uploads;
+DROP INDEX IF EXISTS idx_drop_links_bucket_id;
+DROP TABLE IF EXISTS drop_links;
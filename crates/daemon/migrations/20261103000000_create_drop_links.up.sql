@@ -0,0 +1,35 @@
+-- Write-only links anonymous visitors upload files into a bucket directory with
+CREATE TABLE drop_links (
+    id TEXT PRIMARY KEY,
+    -- Unguessable part of the gateway URL, `/drop/<token>`
+    token TEXT NOT NULL UNIQUE,
+    bucket_id TEXT NOT NULL,
+    -- Absolute directory approved uploads are added to
+    directory TEXT NOT NULL,
+    name TEXT,
+    -- Largest file accepted, in bytes
+    max_size INTEGER NOT NULL,
+    -- Comma separated MIME types or `type/*` patterns; NULL accepts any type
+    allowed_types TEXT,
+    -- Siteverify-style endpoint and secret uploads' captcha tokens are checked with
+    captcha_verify_url TEXT,
+    captcha_secret TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at TIMESTAMP
+);
+
+CREATE INDEX idx_drop_links_bucket_id ON drop_links(bucket_id);
+
+-- Files uploaded through a drop link, held until the owner approves or
+-- rejects them
+CREATE TABLE drop_uploads (
+    id TEXT PRIMARY KEY,
+    link_id TEXT NOT NULL REFERENCES drop_links(id) ON DELETE CASCADE,
+    file_name TEXT NOT NULL,
+    mime_type TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    content BLOB NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_drop_uploads_link_id ON drop_uploads(link_id);
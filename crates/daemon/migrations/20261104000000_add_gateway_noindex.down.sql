@@ -0,0 +1,2 @@
+-- Drop the gateway noindex policy column
+ALTER TABLE gateway_bucket_policies DROP COLUMN noindex;
@@ -0,0 +1,2 @@
+-- Ask crawlers not to index the bucket
+ALTER TABLE gateway_bucket_policies ADD COLUMN noindex BOOLEAN NOT NULL DEFAULT FALSE;
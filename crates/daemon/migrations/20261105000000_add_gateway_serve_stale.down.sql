@@ -0,0 +1,2 @@
+-- Drop the gateway stale serving policy column
+ALTER TABLE gateway_bucket_policies DROP COLUMN serve_stale;
@@ -0,0 +1,2 @@
+-- Serve the last fully synced version while a newer one syncs
+ALTER TABLE gateway_bucket_policies ADD COLUMN serve_stale BOOLEAN NOT NULL DEFAULT FALSE;
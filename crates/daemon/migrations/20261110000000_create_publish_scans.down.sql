@@ -0,0 +1,3 @@
+-- Drop publish scan results
+DROP INDEX IF EXISTS idx_publish_scans_bucket_version;
+DROP TABLE IF EXISTS publish_scans;
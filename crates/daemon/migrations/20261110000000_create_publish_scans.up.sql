@@ -0,0 +1,14 @@
+-- Verdicts of the configured scanners on versions about to be published
+CREATE TABLE publish_scans (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    bucket_id TEXT NOT NULL,
+    -- Hash of the version scanned
+    version TEXT NOT NULL,
+    scanner TEXT NOT NULL,
+    -- allowed, vetoed or failed
+    verdict TEXT NOT NULL,
+    detail TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_publish_scans_bucket_version ON publish_scans(bucket_id, version);
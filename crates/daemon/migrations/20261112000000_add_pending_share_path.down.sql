@@ -0,0 +1,2 @@
+-- Drop the directory of pending shares
+ALTER TABLE pending_shares DROP COLUMN path;
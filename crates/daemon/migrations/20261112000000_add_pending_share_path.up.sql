@@ -0,0 +1,2 @@
+-- Directory a pending share is limited to, NULL for the whole bucket
+ALTER TABLE pending_shares ADD COLUMN path TEXT;
@@ -0,0 +1,2 @@
+-- Drop ephemeral_keys table
+DROP TABLE IF EXISTS ephemeral_keys;
@@ -0,0 +1,7 @@
+-- Create ephemeral_keys table for temporary identities followed buckets were shared with
+CREATE TABLE ephemeral_keys (
+    bucket_id TEXT PRIMARY KEY,
+    -- Secret key of the temporary identity (hex)
+    secret_key TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,4 @@
+-- Drop publish pipelines and their builds
+DROP INDEX IF EXISTS idx_publish_builds_bucket;
+DROP TABLE IF EXISTS publish_builds;
+DROP TABLE IF EXISTS bucket_publish_pipelines;
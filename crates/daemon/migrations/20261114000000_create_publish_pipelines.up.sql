@@ -0,0 +1,26 @@
+-- Per-bucket publish pipelines: the configured builder run on publish, the
+-- output directory in the checkout and the template variables, as JSON
+CREATE TABLE bucket_publish_pipelines (
+    bucket_id TEXT PRIMARY KEY,
+    builder TEXT NOT NULL,
+    output TEXT,
+    vars TEXT NOT NULL DEFAULT '{}',
+    -- Bucket the output is published as, created on the first build
+    site_bucket_id TEXT,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Builds run on publish
+CREATE TABLE publish_builds (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    bucket_id TEXT NOT NULL,
+    -- Hash of the version built
+    source_version TEXT NOT NULL,
+    -- Hash of the site bucket's version published, NULL if the build failed
+    site_version TEXT,
+    succeeded BOOLEAN NOT NULL,
+    log TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_publish_builds_bucket ON publish_builds(bucket_id, id);
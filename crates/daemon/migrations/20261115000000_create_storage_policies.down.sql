@@ -0,0 +1,2 @@
+-- Drop storage policies
+DROP TABLE IF EXISTS bucket_storage_policies;
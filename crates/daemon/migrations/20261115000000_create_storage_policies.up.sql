@@ -0,0 +1,7 @@
+-- Per-bucket storage policies: the blob location, by its name in
+-- config.toml's blob_locations, a bucket's blobs are placed in
+CREATE TABLE bucket_storage_policies (
+    bucket_id TEXT PRIMARY KEY,
+    location TEXT NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
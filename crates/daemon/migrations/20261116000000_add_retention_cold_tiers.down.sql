@@ -0,0 +1,3 @@
+-- Drop the cold storage tiers of retention policies
+ALTER TABLE bucket_retention_policies DROP COLUMN archive_after;
+ALTER TABLE bucket_retention_policies DROP COLUMN infrequent_access_after;
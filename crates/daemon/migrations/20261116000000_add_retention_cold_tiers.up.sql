@@ -0,0 +1,4 @@
+-- Days after which blobs only older versions reference move to colder S3
+-- storage classes; NULL never moves them
+ALTER TABLE bucket_retention_policies ADD COLUMN infrequent_access_after INTEGER;
+ALTER TABLE bucket_retention_policies ADD COLUMN archive_after INTEGER;
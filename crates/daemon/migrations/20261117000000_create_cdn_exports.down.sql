@@ -0,0 +1,2 @@
+-- Drop CDN exports
+DROP TABLE IF EXISTS bucket_cdn_exports;
@@ -0,0 +1,11 @@
+-- Per-bucket CDN exports: the target, by its name in config.toml's
+-- cdn_targets, and the prefix in it a bucket's published content is
+-- mirrored below, with the version last written there
+CREATE TABLE bucket_cdn_exports (
+    bucket_id TEXT PRIMARY KEY,
+    target TEXT NOT NULL,
+    prefix TEXT NOT NULL DEFAULT '',
+    exported_version TEXT,
+    exported_at TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,3 @@
+-- Drop remote imports
+DROP TABLE IF EXISTS remote_import_objects;
+DROP TABLE IF EXISTS remote_imports;
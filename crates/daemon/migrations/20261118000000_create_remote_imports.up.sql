@@ -0,0 +1,16 @@
+-- Buckets imported from existing cloud storage: the source URL each was
+-- imported from, and the fingerprint of every object brought in, so that
+-- importing the same source again only copies what changed
+CREATE TABLE remote_imports (
+    bucket_id TEXT PRIMARY KEY,
+    source TEXT NOT NULL UNIQUE,
+    imported_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE remote_import_objects (
+    bucket_id TEXT NOT NULL,
+    path TEXT NOT NULL,
+    fingerprint TEXT NOT NULL,
+    PRIMARY KEY (bucket_id, path)
+);
@@ -0,0 +1,4 @@
+-- Drop sync job claims and cluster leases
+DROP TABLE IF EXISTS cluster_leases;
+DROP INDEX IF EXISTS idx_sync_jobs_finished_at;
+DROP TABLE IF EXISTS sync_jobs;
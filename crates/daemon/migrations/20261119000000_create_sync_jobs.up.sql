@@ -0,0 +1,23 @@
+-- Sync jobs claimed by one of the instances sharing this database, so that
+-- each runs once however many are up. Times are in seconds since the Unix
+-- epoch.
+CREATE TABLE sync_jobs (
+    -- What the job does, such as `pins:<bucket>:<pins hash>`
+    job TEXT PRIMARY KEY,
+    bucket_id TEXT NOT NULL,
+    -- Instance running the job, and until when its claim holds
+    claimed_by TEXT NOT NULL,
+    claimed_until INTEGER NOT NULL,
+    -- When the job finished, kept so that instances handed the same job
+    -- later skip it
+    finished_at INTEGER
+);
+
+CREATE INDEX idx_sync_jobs_finished_at ON sync_jobs(finished_at);
+
+-- Leases held by one instance at a time, such as running the periodic sync
+CREATE TABLE cluster_leases (
+    name TEXT PRIMARY KEY,
+    holder TEXT NOT NULL,
+    expires_at INTEGER NOT NULL
+);
@@ -0,0 +1,269 @@
+//! Activity feed: the paths recently changed across all buckets
+//!
+//! Each version in a bucket's log is compared with the version before it,
+//! and the files it added, modified or removed are recorded in the
+//! `activity_index` table with the version's author and the time this node
+//! recorded it. Directories are compared by node hash, so only the subtrees
+//! a version changed are read. Versions are indexed once, when the feed is
+//! next asked for; those this node can't read, or whose previous version it
+//! can't (e.g. unpublished versions on a mirror, or history not kept
+//! locally), are recorded without changes.
+//!
+//! A file counts as modified when its content changed; moves show up as a
+//! removal and an addition.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use common::linked_data::Link;
+use common::mount::{Mount, MountError, NodeLink};
+use common::peer::Peer;
+
+use crate::database::Database;
+
+/// How a version changed a path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Change {
+    Added,
+    Modified,
+    Removed,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::Added => write!(f, "added"),
+            Change::Modified => write!(f, "modified"),
+            Change::Removed => write!(f, "removed"),
+        }
+    }
+}
+
+impl FromStr for Change {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "added" => Ok(Change::Added),
+            "modified" => Ok(Change::Modified),
+            "removed" => Ok(Change::Removed),
+            other => Err(format!("unknown change: {}", other)),
+        }
+    }
+}
+
+/// A path changed by a bucket version
+#[derive(Debug, Clone)]
+pub struct ActivityEntry {
+    pub bucket_id: Uuid,
+    /// Hash of the version that made the change
+    pub version: String,
+    pub height: u64,
+    pub path: String,
+    pub change: Change,
+    /// Hex public key of the peer that signed the version
+    pub author: Option<String>,
+    /// When this node recorded the version
+    pub changed_at: OffsetDateTime,
+}
+
+/// Compare the versions of a bucket not indexed yet with their previous
+/// versions and record what they changed, returning the number of changes
+pub async fn index_bucket(
+    peer: &Peer<Database>,
+    database: &Database,
+    bucket_id: Uuid,
+) -> Result<usize, ActivityError> {
+    let indexed = database.activity_indexed_versions(&bucket_id).await?;
+    let log = database.get_all_bucket_logs(&bucket_id).await?;
+
+    // The log is oldest first, so a version's previous one was usually
+    //  just loaded
+    let mut last: Option<(Link, Option<Mount>)> = None;
+    let mut recorded = 0;
+    for entry in log {
+        let version = entry.current_link.hash().to_string();
+        if indexed.contains(&version) {
+            continue;
+        }
+
+        let previous = match &entry.previous_link {
+            Some(link) => match last.take() {
+                Some((last_link, mount)) if &last_link == link => Some(mount),
+                _ => Some(load(peer, link).await),
+            },
+            None => None,
+        };
+        let current = load(peer, &entry.current_link).await;
+
+        let changes = match (&current, previous.as_ref()) {
+            (Some(current), None) => diff(None, current).await,
+            (Some(current), Some(Some(previous))) => diff(Some(previous), current).await,
+            _ => Ok(Vec::new()),
+        };
+        let changes = changes.unwrap_or_else(|e| {
+            tracing::debug!(
+                "Could not compare version {} of bucket {}: {}",
+                version,
+                bucket_id,
+                e
+            );
+            Vec::new()
+        });
+
+        let author = match &current {
+            Some(mount) => mount
+                .inner()
+                .await
+                .manifest()
+                .author()
+                .map(|key| key.to_hex()),
+            None => None,
+        };
+        let entries: Vec<ActivityEntry> = changes
+            .into_iter()
+            .map(|(path, change)| ActivityEntry {
+                bucket_id,
+                version: version.clone(),
+                height: entry.height,
+                path: path.to_string_lossy().into_owned(),
+                change,
+                author: author.clone(),
+                changed_at: entry.created_at,
+            })
+            .collect();
+        if database
+            .record_activity(&bucket_id, &version, &entries)
+            .await?
+        {
+            recorded += entries.len();
+        }
+
+        last = Some((entry.current_link, current));
+    }
+
+    Ok(recorded)
+}
+
+/// A version of a bucket, if this node can read it
+async fn load(peer: &Peer<Database>, link: &Link) -> Option<Mount> {
+    Mount::load(link, peer.secret(), peer.blobs()).await.ok()
+}
+
+/// The files `current` added, modified or removed compared to `previous`
+/// (or to an empty bucket)
+async fn diff(
+    previous: Option<&Mount>,
+    current: &Mount,
+) -> Result<Vec<(PathBuf, Change)>, MountError> {
+    if let Some(previous) = previous {
+        let previous_root = previous.inner().await.manifest().entry().hash();
+        let current_root = current.inner().await.manifest().entry().hash();
+        if previous_root == current_root {
+            return Ok(Vec::new());
+        }
+    }
+    let mut changes = Vec::new();
+    diff_dir(previous, Some(current), Path::new("/"), &mut changes).await?;
+    Ok(changes)
+}
+
+/// Compare a directory that exists on either or both sides
+async fn diff_dir(
+    previous: Option<&Mount>,
+    current: Option<&Mount>,
+    path: &Path,
+    changes: &mut Vec<(PathBuf, Change)>,
+) -> Result<(), MountError> {
+    let before = children(previous, path).await?;
+    let after = children(current, path).await?;
+    let names: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+
+    for name in names {
+        let item_path = path.join(name);
+        match (before.get(name), after.get(name)) {
+            (Some(NodeLink::Dir(old, _)), Some(NodeLink::Dir(new, _))) => {
+                if old.hash() != new.hash() {
+                    Box::pin(diff_dir(previous, current, &item_path, changes)).await?;
+                }
+            }
+            (Some(NodeLink::Data(old, _, _)), Some(NodeLink::Data(new, _, _))) => {
+                if old.hash() != new.hash() {
+                    changes.push((item_path, Change::Modified));
+                }
+            }
+            (Some(NodeLink::Bucket(old)), Some(NodeLink::Bucket(new))) => {
+                if old != new {
+                    changes.push((item_path, Change::Modified));
+                }
+            }
+            (old, new) => {
+                // Added, removed, or replaced by a different kind of node
+                match old {
+                    Some(NodeLink::Dir(..)) => {
+                        Box::pin(diff_dir(previous, None, &item_path, changes)).await?
+                    }
+                    Some(NodeLink::Data(..) | NodeLink::Bucket(_)) => {
+                        changes.push((item_path.clone(), Change::Removed))
+                    }
+                    None => {}
+                }
+                match new {
+                    Some(NodeLink::Dir(..)) => {
+                        Box::pin(diff_dir(None, current, &item_path, changes)).await?
+                    }
+                    Some(NodeLink::Data(..) | NodeLink::Bucket(_)) => {
+                        changes.push((item_path, Change::Added))
+                    }
+                    None => {}
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Entries of a directory by name, none for a side it doesn't exist on
+async fn children(
+    mount: Option<&Mount>,
+    path: &Path,
+) -> Result<BTreeMap<String, NodeLink>, MountError> {
+    let Some(mount) = mount else {
+        return Ok(BTreeMap::new());
+    };
+    Ok(mount
+        .ls(path)
+        .await?
+        .into_iter()
+        .filter_map(|(item_path, link)| {
+            let name = item_path.file_name()?.to_string_lossy().into_owned();
+            Some((name, link))
+        })
+        .collect())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ActivityError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_change_round_trip() {
+        for change in [Change::Added, Change::Modified, Change::Removed] {
+            assert_eq!(change.to_string().parse::<Change>().unwrap(), change);
+        }
+        assert!("renamed".parse::<Change>().is_err());
+    }
+}
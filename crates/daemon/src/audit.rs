@@ -0,0 +1,251 @@
+//! Audit log of mutating operations
+//!
+//! Every change the daemon makes on someone's behalf is recorded with who
+//! made it, what it was, the bucket and path it touched and the bucket
+//! version it resulted in. Local changes are attributed to the interface they
+//! came through (the HTTP API, the desktop app, a FUSE mount, the mobile
+//! bindings, a deploy key, a backup job, a retention or a storage policy);
+//! versions pulled in by sync are attributed to the peer that signed them.
+//! Entries live in the `audit_log` table, which triggers keep append-only,
+//! and are served by `/api/v0/audit` and `jax audit`.
+//!
+//! Recording is best effort: a failed insert is logged and never fails the
+//! operation itself.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use common::bucket_log::BucketLogProvider;
+use common::crypto::PublicKey;
+use common::linked_data::Link;
+use common::mount::Mount;
+use common::peer::Peer;
+
+use crate::database::Database;
+use crate::webhooks::Webhooks;
+
+/// Who made a change
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Actor {
+    /// A client of the local HTTP API, e.g. the `jax` CLI
+    Api,
+    /// The desktop app
+    Desktop,
+    /// A FUSE mount
+    Fuse,
+    /// An app embedding the lite node
+    Mobile,
+    /// A CI pipeline writing through the gateway with a deploy key
+    Deploy(Uuid),
+    /// An anonymous visitor uploading through a drop link
+    Drop(Uuid),
+    /// A scheduled backup job
+    Backup(Uuid),
+    /// A bucket's retention policy, applied in the background
+    Retention,
+    /// A bucket's storage policy, applied in the background
+    Storage,
+    /// A bucket's CDN export, written in the background
+    Cdn,
+    /// An ephemeral share that expired, cleaned up in the background
+    Expiry,
+    /// A peer whose version was synced
+    Peer(PublicKey),
+}
+
+impl fmt::Display for Actor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Actor::Api => write!(f, "api"),
+            Actor::Desktop => write!(f, "desktop"),
+            Actor::Fuse => write!(f, "fuse"),
+            Actor::Mobile => write!(f, "mobile"),
+            Actor::Deploy(key_id) => write!(f, "deploy:{}", key_id),
+            Actor::Drop(link_id) => write!(f, "drop:{}", link_id),
+            Actor::Backup(job_id) => write!(f, "backup:{}", job_id),
+            Actor::Retention => write!(f, "retention"),
+            Actor::Storage => write!(f, "storage"),
+            Actor::Cdn => write!(f, "cdn"),
+            Actor::Expiry => write!(f, "expiry"),
+            Actor::Peer(key) => write!(f, "peer:{}", key.to_hex()),
+        }
+    }
+}
+
+/// A change to record
+#[derive(Debug, Clone)]
+pub struct AuditEvent {
+    pub actor: Actor,
+    /// What was done, e.g. `bucket.add`
+    pub operation: &'static str,
+    pub bucket_id: Option<Uuid>,
+    pub path: Option<String>,
+    /// Anything else that identifies the change, e.g. the config key set
+    pub detail: Option<String>,
+    /// Hash of the bucket version the change resulted in
+    pub version: Option<String>,
+}
+
+impl AuditEvent {
+    pub fn new(actor: Actor, operation: &'static str) -> Self {
+        Self {
+            actor,
+            operation,
+            bucket_id: None,
+            path: None,
+            detail: None,
+            version: None,
+        }
+    }
+
+    pub fn bucket(mut self, bucket_id: Uuid) -> Self {
+        self.bucket_id = Some(bucket_id);
+        self
+    }
+
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    pub fn detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    pub fn version(mut self, link: &Link) -> Self {
+        self.version = Some(link.hash().to_string());
+        self
+    }
+}
+
+/// A recorded change
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub id: i64,
+    #[serde(with = "time::serde::rfc3339")]
+    pub at: OffsetDateTime,
+    pub actor: String,
+    pub operation: String,
+    pub bucket_id: Option<Uuid>,
+    pub path: Option<String>,
+    pub detail: Option<String>,
+    pub version: Option<String>,
+}
+
+/// Which entries to list
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    pub bucket_id: Option<Uuid>,
+    /// Exact actor, as displayed (`api`, `peer:<hex>`, ...)
+    pub actor: Option<String>,
+    pub since: Option<OffsetDateTime>,
+    /// Most entries to return, newest first; None returns them all
+    pub limit: Option<u32>,
+}
+
+/// Handle for recording and reading the audit log
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    database: Database,
+    webhooks: Webhooks,
+}
+
+impl AuditLog {
+    pub fn new(database: Database, webhooks: Webhooks) -> Self {
+        Self { database, webhooks }
+    }
+
+    /// Record a change, logging rather than returning a failure, and raise
+    /// the webhook events it amounts to
+    pub async fn record(&self, event: AuditEvent) {
+        if let Err(e) = self.database.insert_audit_entry(&event).await {
+            tracing::warn!(
+                "Failed to record {} by {} in the audit log: {}",
+                event.operation,
+                event.actor,
+                e
+            );
+        }
+        self.webhooks.audited(&event);
+    }
+
+    /// Entries matching `filter`, newest first
+    pub async fn list(&self, filter: &AuditFilter) -> Result<Vec<AuditEntry>, sqlx::Error> {
+        self.database.list_audit_entries(filter).await
+    }
+
+    /// Record the versions of a bucket above `from_height` that a sync
+    /// appended, each attributed to the peer that signed it. Versions signed
+    /// by this node are local saves and are recorded where they were made.
+    /// A bucket new to this node is also recorded as `bucket.join`, by the
+    /// peer it was synced from.
+    pub(crate) async fn record_synced<L>(
+        &self,
+        peer: &Peer<L>,
+        bucket_id: Uuid,
+        from_height: Option<u64>,
+        source: Option<PublicKey>,
+    ) where
+        L: BucketLogProvider + Clone + Send + Sync + 'static,
+        L::Error: std::error::Error + Send + Sync + 'static,
+    {
+        let Ok(height) = peer.logs().height(bucket_id).await else {
+            return;
+        };
+        let own_key = peer.secret().public();
+        if let (None, Some(source)) = (from_height, source) {
+            self.record(AuditEvent::new(Actor::Peer(source), "bucket.join").bucket(bucket_id))
+                .await;
+        }
+        let first = from_height.map_or(0, |h| h + 1);
+        for height in first..=height {
+            let links = match peer.logs().heads(bucket_id, height).await {
+                Ok(links) => links,
+                Err(e) => {
+                    tracing::warn!("Failed to read synced versions of {}: {}", bucket_id, e);
+                    return;
+                }
+            };
+            for link in links {
+                let author = match Mount::load_manifest(&link, peer.blobs()).await {
+                    Ok(manifest) => manifest.author().copied(),
+                    Err(_) => None,
+                };
+                if author == Some(own_key) {
+                    continue;
+                }
+                let Some(author) = author.or(source) else {
+                    continue;
+                };
+                self.record(
+                    AuditEvent::new(Actor::Peer(author), "sync")
+                        .bucket(bucket_id)
+                        .version(&link),
+                )
+                .await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use common::crypto::SecretKey;
+
+    #[test]
+    fn test_actor_display() {
+        assert_eq!(Actor::Api.to_string(), "api");
+        assert_eq!(Actor::Fuse.to_string(), "fuse");
+        let key = SecretKey::generate().public();
+        assert_eq!(
+            Actor::Peer(key).to_string(),
+            format!("peer:{}", key.to_hex())
+        );
+    }
+}
@@ -0,0 +1,543 @@
+//! Scheduled backups of local directories into buckets
+//!
+//! A backup job mirrors a directory on the daemon's machine into a path of a
+//! bucket on a cron schedule (`minute hour day-of-month month day-of-week`,
+//! in local time, or `@hourly`, `@daily`, `@weekly`, `@monthly`). Each run
+//! uploads the files whose size or BLAKE3 hash differs from what the bucket
+//! holds, removes what is gone from the directory and saves the result as
+//! one version, which the job records as a snapshot. Only the job's newest
+//! `retain` snapshots are kept; older ones are forgotten, though the bucket
+//! versions themselves stay in its history. Runs that were due while the
+//! daemon was down happen once on start.
+//!
+//! Jobs are stored in the database and run one at a time by the backup
+//! worker, on schedule or on request (`/api/v0/backup/run`). Runs are
+//! audited as made by `backup:<job id>`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, Local, NaiveDate, TimeZone, Timelike};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use tokio::sync::{mpsc, oneshot};
+use uuid::Uuid;
+
+use common::bucket_log::BucketLogProvider;
+use common::linked_data::Hash;
+use common::mount::{MountError, NodeLink};
+use common::peer::Peer;
+
+use crate::audit::{Actor, AuditEvent, AuditLog};
+use crate::database::Database;
+
+/// How often the worker looks for jobs that are due
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How far ahead a schedule is searched for its next run
+const MAX_LOOKAHEAD_DAYS: u64 = 366 * 5;
+
+/// When a job runs: a cron expression, in local time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    expr: String,
+    minutes: u64,
+    hours: u32,
+    days: u32,
+    months: u16,
+    weekdays: u8,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl Schedule {
+    /// First time strictly after `after` the schedule fires, if any
+    pub fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        let start =
+            after.naive_local().with_second(0)?.with_nanosecond(0)? + chrono::Duration::minutes(1);
+        for date in start.date().iter_days().take(MAX_LOOKAHEAD_DAYS as usize) {
+            if !self.matches_date(date) {
+                continue;
+            }
+            for hour in (0..24).filter(|h| self.hours & (1 << h) != 0) {
+                for minute in (0..60).filter(|m| self.minutes & (1 << m) != 0) {
+                    let candidate = date.and_hms_opt(hour, minute, 0)?;
+                    if candidate < start {
+                        continue;
+                    }
+                    // Times skipped by a DST change never fire
+                    if let Some(time) = Local.from_local_datetime(&candidate).earliest() {
+                        return Some(time);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// With both restricted, either the day of the month or the day of the
+    /// week has to match, as in cron
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        if self.months & (1 << date.month()) == 0 {
+            return false;
+        }
+        let day = self.days & (1 << date.day()) != 0;
+        let weekday = self.weekdays & (1 << date.weekday().num_days_from_sunday()) != 0;
+        match (self.any_day, self.any_weekday) {
+            (false, false) => day || weekday,
+            _ => day && weekday,
+        }
+    }
+}
+
+/// Parse one cron field into a bitset of the values in `min..=max`
+fn parse_field(field: &str, min: u32, max: u32) -> Option<u64> {
+    let mut bits = 0u64;
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().ok().filter(|s| *s > 0)?),
+            None => (item, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (start.parse().ok()?, end.parse().ok()?),
+                // `a/n` runs from a to the end of the range
+                None if item.contains('/') => (range.parse().ok()?, max),
+                None => {
+                    let value = range.parse().ok()?;
+                    (value, value)
+                }
+            },
+        };
+        if start < min || end > max || start > end {
+            return None;
+        }
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1u64 << value;
+        }
+    }
+    Some(bits)
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expr)
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = BackupError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || BackupError::InvalidSchedule(s.to_string());
+        let expr = match s.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            expr => expr,
+        };
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(invalid());
+        };
+        // Sunday is both 0 and 7
+        let weekdays = parse_field(weekday, 0, 7).ok_or_else(invalid)?;
+        let schedule = Self {
+            expr: s.trim().to_string(),
+            minutes: parse_field(minute, 0, 59).ok_or_else(invalid)?,
+            hours: parse_field(hour, 0, 23).ok_or_else(invalid)? as u32,
+            days: parse_field(day, 1, 31).ok_or_else(invalid)? as u32,
+            months: parse_field(month, 1, 12).ok_or_else(invalid)? as u16,
+            weekdays: ((weekdays | weekdays >> 7) & 0x7f) as u8,
+            any_day: day == "*",
+            any_weekday: weekday == "*",
+        };
+        // e.g. `0 0 30 2 *`
+        if schedule.next_after(Local::now()).is_none() {
+            return Err(invalid());
+        }
+        Ok(schedule)
+    }
+}
+
+impl Serialize for Schedule {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Schedule {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// A directory backed up into a bucket on a schedule
+#[derive(Debug, Clone)]
+pub struct BackupJob {
+    pub id: Uuid,
+    pub name: Option<String>,
+    /// Absolute path of the directory on the daemon's machine
+    pub source_dir: PathBuf,
+    pub bucket_id: Uuid,
+    /// Absolute path in the bucket the directory is mirrored to
+    pub target_path: String,
+    pub schedule: Schedule,
+    /// Number of snapshots kept
+    pub retain: u64,
+    pub created_at: OffsetDateTime,
+    pub next_run_at: OffsetDateTime,
+    pub last_run_at: Option<OffsetDateTime>,
+    /// Why the latest run failed, None if it succeeded
+    pub last_error: Option<String>,
+}
+
+impl BackupJob {
+    /// Next time the job is due after now
+    pub fn next_run(schedule: &Schedule) -> OffsetDateTime {
+        schedule
+            .next_after(Local::now())
+            .and_then(|time| OffsetDateTime::from_unix_timestamp(time.timestamp()).ok())
+            // Schedules are checked to fire when parsed
+            .unwrap_or_else(|| OffsetDateTime::now_utc() + Duration::from_secs(86400 * 365))
+    }
+}
+
+/// A version of a bucket saved by a backup run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupSnapshot {
+    pub job_id: Uuid,
+    /// Hash of the bucket version
+    pub version: String,
+    pub height: u64,
+    pub files_uploaded: u64,
+    pub files_deleted: u64,
+    #[serde(with = "time::serde::rfc3339")]
+    pub taken_at: OffsetDateTime,
+}
+
+/// Handle for running backup jobs on request; scheduled runs happen in the
+/// background
+#[derive(Debug, Clone)]
+pub struct Backups {
+    runs: mpsc::UnboundedSender<(Uuid, oneshot::Sender<Result<BackupSnapshot, BackupError>>)>,
+}
+
+impl Backups {
+    /// Start the backup worker, which runs until the peer shuts down
+    pub fn spawn(peer: Peer<Database>, database: Database, audit: AuditLog) -> Self {
+        let (runs, runs_rx) = mpsc::unbounded_channel();
+        tokio::spawn(run_worker(peer, database, audit, runs_rx));
+        Self { runs }
+    }
+
+    /// Run a job now, waiting for it to finish. Its schedule is unchanged.
+    pub async fn run(&self, job_id: Uuid) -> Result<BackupSnapshot, BackupError> {
+        let (reply, result) = oneshot::channel();
+        self.runs
+            .send((job_id, reply))
+            .map_err(|_| BackupError::WorkerStopped)?;
+        result.await.map_err(|_| BackupError::WorkerStopped)?
+    }
+}
+
+async fn run_worker(
+    peer: Peer<Database>,
+    database: Database,
+    audit: AuditLog,
+    mut runs: mpsc::UnboundedReceiver<(Uuid, oneshot::Sender<Result<BackupSnapshot, BackupError>>)>,
+) {
+    let mut check = tokio::time::interval(CHECK_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = check.tick() => {
+                let jobs = match database.list_backup_jobs().await {
+                    Ok(jobs) => jobs,
+                    Err(e) => {
+                        tracing::warn!("Failed to load backup jobs: {}", e);
+                        continue;
+                    }
+                };
+                let now = OffsetDateTime::now_utc();
+                for job in jobs.into_iter().filter(|job| job.next_run_at <= now) {
+                    let result = run_job(&peer, &database, &audit, &job).await;
+                    if let Err(e) = &result {
+                        tracing::warn!("Backup job {} failed: {}", job.id, e);
+                    }
+                    let next_run_at = BackupJob::next_run(&job.schedule);
+                    if let Err(e) = database.schedule_backup_job(&job.id, next_run_at).await {
+                        tracing::warn!("Failed to reschedule backup job {}: {}", job.id, e);
+                    }
+                }
+            }
+
+            Some((job_id, reply)) = runs.recv() => {
+                let result = match database.get_backup_job(&job_id).await {
+                    Ok(Some(job)) => run_job(&peer, &database, &audit, &job).await,
+                    Ok(None) => Err(BackupError::UnknownJob(job_id)),
+                    Err(e) => Err(e.into()),
+                };
+                let _ = reply.send(result);
+            }
+
+            _ = peer.cancelled() => break,
+        }
+    }
+    tracing::debug!("Backup worker shutting down");
+}
+
+/// Run a job and record its outcome and snapshot
+async fn run_job(
+    peer: &Peer<Database>,
+    database: &Database,
+    audit: &AuditLog,
+    job: &BackupJob,
+) -> Result<BackupSnapshot, BackupError> {
+    tracing::info!(
+        "Backing up {} to {}:{}",
+        job.source_dir.display(),
+        job.bucket_id,
+        job.target_path
+    );
+    let result = back_up(peer, database, audit, job).await;
+    let error = result.as_ref().err().map(ToString::to_string);
+    database
+        .record_backup_run(&job.id, error.as_deref())
+        .await?;
+    let snapshot = result?;
+    database
+        .insert_backup_snapshot(&snapshot, job.retain)
+        .await?;
+    Ok(snapshot)
+}
+
+async fn back_up(
+    peer: &Peer<Database>,
+    database: &Database,
+    audit: &AuditLog,
+    job: &BackupJob,
+) -> Result<BackupSnapshot, BackupError> {
+    let mut mount = peer.mount(job.bucket_id).await?;
+    let target = PathBuf::from(&job.target_path);
+
+    // What the bucket holds under the target path, relative to it
+    let existing = if job.target_path == "/" {
+        Some(mount.ls_deep(&target).await?)
+    } else {
+        match mount.get(&target).await {
+            Ok(NodeLink::Dir(..)) => Some(mount.ls_deep(&target).await?),
+            Ok(NodeLink::Data(..) | NodeLink::Bucket(_)) => {
+                return Err(BackupError::TargetNotADirectory(job.target_path.clone()))
+            }
+            Err(_) => None,
+        }
+    };
+    let mut remote = BTreeMap::new();
+    for (path, link) in existing.into_iter().flatten() {
+        let content = link
+            .data()
+            .and_then(|data| data.size().zip(data.content_hash()));
+        remote.insert(path, (link.is_dir(), content));
+    }
+
+    // Hashing reads every file whose size is unchanged
+    let source_dir = job.source_dir.clone();
+    let contents = remote
+        .iter()
+        .filter_map(|(path, (_, content))| content.map(|content| (path.clone(), content)))
+        .collect();
+    let local = tokio::task::spawn_blocking(move || LocalTree::scan(&source_dir, &contents))
+        .await
+        .map_err(|e| BackupError::Io(std::io::Error::other(e)))??;
+
+    // A removed directory takes everything under it along
+    let mut removed: Vec<&PathBuf> = Vec::new();
+    for (path, (is_dir, _)) in &remote {
+        let kept = if *is_dir {
+            local.dirs.contains(path)
+        } else {
+            local.files.contains(path)
+        };
+        if kept || removed.iter().any(|dir| path.starts_with(dir)) {
+            continue;
+        }
+        mount.rm(&target.join(path)).await?;
+        removed.push(path);
+    }
+    for path in &local.changed {
+        let file = fs::File::open(job.source_dir.join(path))?;
+        mount.add(&target.join(path), file).await?;
+    }
+
+    let files_uploaded = local.changed.len() as u64;
+    let files_deleted = removed.len() as u64;
+    if files_uploaded > 0 || files_deleted > 0 {
+        let link = peer.save_mount(&mount, false).await?;
+        audit
+            .record(
+                AuditEvent::new(Actor::Backup(job.id), "backup.run")
+                    .bucket(job.bucket_id)
+                    .path(job.target_path.clone())
+                    .detail(format!(
+                        "{} uploaded, {} deleted",
+                        files_uploaded, files_deleted
+                    ))
+                    .version(&link),
+            )
+            .await;
+    }
+
+    // Unchanged directories snapshot the current head
+    let (link, height) = database
+        .head(job.bucket_id, None)
+        .await
+        .map_err(|e| BackupError::BucketLog(e.to_string()))?;
+    Ok(BackupSnapshot {
+        job_id: job.id,
+        version: link.hash().to_string(),
+        height,
+        files_uploaded,
+        files_deleted,
+        taken_at: OffsetDateTime::now_utc(),
+    })
+}
+
+/// Files and directories of a backed up directory, relative to it, and the
+/// files that differ from the bucket
+#[derive(Debug, Default)]
+struct LocalTree {
+    files: BTreeSet<PathBuf>,
+    dirs: BTreeSet<PathBuf>,
+    changed: Vec<PathBuf>,
+}
+
+impl LocalTree {
+    fn scan(root: &Path, remote: &BTreeMap<PathBuf, (u64, Hash)>) -> std::io::Result<Self> {
+        if !root.is_dir() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("{} is not a directory", root.display()),
+            ));
+        }
+        let mut tree = Self::default();
+        tree.walk(root, Path::new(""), remote)?;
+        Ok(tree)
+    }
+
+    fn walk(
+        &mut self,
+        dir: &Path,
+        relative: &Path,
+        remote: &BTreeMap<PathBuf, (u64, Hash)>,
+    ) -> std::io::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            let path = relative.join(entry.file_name());
+            if file_type.is_dir() {
+                self.walk(&entry.path(), &path, remote)?;
+                self.dirs.insert(path);
+            } else if file_type.is_file() {
+                let unchanged = match remote.get(&path) {
+                    Some((size, hash)) => {
+                        entry.metadata()?.len() == *size && hash_file(&entry.path())? == *hash
+                    }
+                    None => false,
+                };
+                if !unchanged {
+                    self.changed.push(path.clone());
+                }
+                self.files.insert(path);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn hash_file(path: &Path) -> std::io::Result<Hash> {
+    let mut hasher = blake3::Hasher::new();
+    std::io::copy(&mut fs::File::open(path)?, &mut hasher)?;
+    Ok(Hash::from_bytes(*hasher.finalize().as_bytes()))
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BackupError {
+    #[error("Invalid schedule: {0}")]
+    InvalidSchedule(String),
+    #[error("Unknown backup job: {0}")]
+    UnknownJob(Uuid),
+    #[error("{0} exists in the bucket and is not a directory")]
+    TargetNotADirectory(String),
+    #[error("Backup worker has stopped")]
+    WorkerStopped,
+    #[error("Mount error: {0}")]
+    Mount(#[from] MountError),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Bucket log error: {0}")]
+    BucketLog(String),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn local(s: &str) -> DateTime<Local> {
+        let naive = chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+        Local.from_local_datetime(&naive).earliest().unwrap()
+    }
+
+    fn next(schedule: &str, after: &str) -> String {
+        schedule
+            .parse::<Schedule>()
+            .unwrap()
+            .next_after(local(after))
+            .unwrap()
+            .format("%Y-%m-%d %H:%M")
+            .to_string()
+    }
+
+    #[test]
+    fn test_next_after() {
+        assert_eq!(next("@daily", "2026-03-10 14:30"), "2026-03-11 00:00");
+        assert_eq!(next("*/15 * * * *", "2026-03-10 14:30"), "2026-03-10 14:45");
+        assert_eq!(
+            next("0 9-17/4 * * *", "2026-03-10 14:30"),
+            "2026-03-10 17:00"
+        );
+        // 2026-03-10 is a Tuesday
+        assert_eq!(next("30 2 * * 0", "2026-03-10 14:30"), "2026-03-15 02:30");
+        assert_eq!(next("30 2 * * 7", "2026-03-10 14:30"), "2026-03-15 02:30");
+        assert_eq!(next("0 0 1 * *", "2026-03-10 14:30"), "2026-04-01 00:00");
+        // Day of month or day of week, as in cron
+        assert_eq!(next("0 0 20 * 5", "2026-03-10 14:30"), "2026-03-13 00:00");
+        assert_eq!(next("0 0 29 2 *", "2026-03-10 14:30"), "2028-02-29 00:00");
+    }
+
+    #[test]
+    fn test_invalid_schedules() {
+        for schedule in [
+            "",
+            "* * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "0 0 30 2 *",
+            "@yearly",
+        ] {
+            assert!(schedule.parse::<Schedule>().is_err(), "{}", schedule);
+        }
+    }
+}
@@ -0,0 +1,20 @@
+//! `git-remote-jax`: git remote helper for repositories kept in buckets
+//! (see [`jax_daemon::git_remote`])
+//!
+//! Git runs it as `git-remote-jax <remote name> <url>` for `jax://` URLs.
+
+#[tokio::main]
+async fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let Some(url) = args.get(2) else {
+        eprintln!("usage: git-remote-jax <remote> <url>");
+        std::process::exit(1);
+    };
+
+    let input = std::io::stdin().lock();
+    let output = std::io::stdout().lock();
+    if let Err(e) = jax_daemon::git_remote::run_helper(url, input, output).await {
+        eprintln!("fatal: {}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,287 @@
+//! On-demand blob cache for online-only buckets
+//!
+//! After a sync, an online-only bucket keeps its manifest and directory nodes
+//! but skips downloading the rest of its pins. File content is fetched from
+//! the bucket's peers the first time it is read, whether through the API,
+//! `jax bucket cat` or a FUSE mount. Fetched blobs are recorded in the
+//! `blob_cache` table, and the least recently read ones are evicted once the
+//! cache grows beyond `online_cache_max_bytes`.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::anyhow;
+use iroh::Endpoint;
+use tokio::sync::watch;
+use uuid::Uuid;
+
+use common::bucket_log::BucketLogProvider;
+use common::crypto::PublicKey;
+use common::linked_data::Hash;
+use common::mount::Mount;
+use common::peer::{BlobFetcher, BlobsStore, BlobsStoreError, Peer};
+
+use crate::database::Database;
+use crate::reload::RuntimeConfig;
+use crate::sync_policy::SyncPolicies;
+
+/// How often the cache is checked against its size limit
+const EVICT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Minimum time between persisting reads of the same blob
+const TOUCH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Tracks blobs fetched on demand and fetches missing ones from the peers of
+/// the online-only bucket they are read from
+#[derive(Debug, Clone)]
+pub struct BlobCache {
+    inner: Arc<BlobCacheInner>,
+}
+
+#[derive(Debug)]
+struct BlobCacheInner {
+    database: Database,
+    endpoint: Endpoint,
+    node_key: PublicKey,
+    policies: SyncPolicies,
+    /// Cached blobs and when their last read was persisted
+    tracked: Mutex<HashMap<Hash, Instant>>,
+}
+
+/// Size of the cache
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CacheUsage {
+    pub blobs: u64,
+    pub bytes: u64,
+}
+
+impl BlobCache {
+    /// Load the blobs cached by a previous run
+    pub async fn load(
+        database: Database,
+        endpoint: Endpoint,
+        node_key: PublicKey,
+        policies: SyncPolicies,
+    ) -> Result<Self, sqlx::Error> {
+        let now = Instant::now();
+        let tracked = database
+            .list_cached_blobs()
+            .await?
+            .into_iter()
+            .filter_map(|blob| blob.hash.parse().ok())
+            .map(|hash| (hash, now))
+            .collect();
+
+        Ok(Self {
+            inner: Arc::new(BlobCacheInner {
+                database,
+                endpoint,
+                node_key,
+                policies,
+                tracked: Mutex::new(tracked),
+            }),
+        })
+    }
+
+    fn tracked(&self) -> std::sync::MutexGuard<'_, HashMap<Hash, Instant>> {
+        self.inner.tracked.lock().expect("blob cache lock poisoned")
+    }
+
+    /// Number and total size of the cached blobs
+    pub async fn usage(&self) -> Result<CacheUsage, sqlx::Error> {
+        let cached = self.inner.database.list_cached_blobs().await?;
+        Ok(CacheUsage {
+            blobs: cached.len() as u64,
+            bytes: cached.iter().map(|blob| blob.size).sum(),
+        })
+    }
+
+    /// Evict the least recently read blobs until the cache fits in `max_bytes`
+    pub async fn evict(
+        &self,
+        blobs: &BlobsStore,
+        max_bytes: u64,
+    ) -> Result<CacheUsage, BlobCacheError> {
+        let cached = self.inner.database.list_cached_blobs().await?;
+        let mut total: u64 = cached.iter().map(|blob| blob.size).sum();
+
+        let mut evicted = Vec::new();
+        let mut freed = CacheUsage::default();
+        for blob in cached {
+            if total <= max_bytes {
+                break;
+            }
+            total -= blob.size;
+            freed.blobs += 1;
+            freed.bytes += blob.size;
+            evicted.push(blob.hash);
+        }
+        if evicted.is_empty() {
+            return Ok(freed);
+        }
+
+        let hashes: Vec<Hash> = evicted
+            .iter()
+            .filter_map(|hash| hash.parse().ok())
+            .collect();
+        blobs.delete(hashes.clone()).await?;
+        self.inner.database.forget_cached_blobs(&evicted).await?;
+        let mut tracked = self.tracked();
+        for hash in &hashes {
+            tracked.remove(hash);
+        }
+
+        tracing::info!(
+            "Evicted {} cached blobs ({} bytes) from the online-only cache",
+            freed.blobs,
+            freed.bytes
+        );
+        Ok(freed)
+    }
+
+    /// Stop evicting blobs pinned by a bucket that keeps all of its content
+    pub async fn pin(&self, blobs: &BlobsStore, pins: Hash) -> Result<(), BlobCacheError> {
+        if self.tracked().is_empty() {
+            return Ok(());
+        }
+        let pinned: Vec<String> = {
+            let hashes = blobs.read_hash_list(pins).await?;
+            let mut tracked = self.tracked();
+            hashes
+                .into_iter()
+                .filter(|hash| tracked.remove(hash).is_some())
+                .map(|hash| hash.to_string())
+                .collect()
+        };
+        if !pinned.is_empty() {
+            self.inner.database.forget_cached_blobs(&pinned).await?;
+        }
+        Ok(())
+    }
+
+    /// Peers sharing an online-only bucket, which may hold its content
+    async fn peers(&self, blobs: &BlobsStore, bucket_id: Uuid) -> anyhow::Result<Vec<PublicKey>> {
+        let (link, _) = self
+            .inner
+            .database
+            .head(bucket_id, None)
+            .await
+            .map_err(|e| anyhow!("failed to get head of bucket {}: {}", bucket_id, e))?;
+        // Only use manifests we already have, fetching them would recurse
+        if !blobs.stat(&link.hash()).await.unwrap_or(false) {
+            return Err(anyhow!(
+                "manifest of bucket {} isn't stored locally",
+                bucket_id
+            ));
+        }
+        let manifest = Mount::load_manifest(&link, blobs)
+            .await
+            .map_err(|e| anyhow!("failed to load manifest of bucket {}: {}", bucket_id, e))?;
+        let mut peers = Vec::new();
+        for share in manifest.shares().values() {
+            let identity = share.principal().identity;
+            if identity != self.inner.node_key && !peers.contains(&identity) {
+                peers.push(identity);
+            }
+        }
+        Ok(peers)
+    }
+}
+
+#[async_trait::async_trait]
+impl BlobFetcher for BlobCache {
+    async fn fetch(&self, blobs: &BlobsStore, hash: Hash) -> Result<(), BlobsStoreError> {
+        // Blobs read outside of a bucket, such as manifests, and blobs of
+        //  buckets that keep all of their content aren't fetched on demand
+        let Some(bucket_id) = blobs
+            .bucket()
+            .filter(|bucket_id| self.inner.policies.online_only().contains(bucket_id))
+        else {
+            return Ok(());
+        };
+        let peers = self.peers(blobs, bucket_id).await?;
+        if peers.is_empty() {
+            return Err(anyhow!(
+                "bucket {} has no peers to fetch blob {} from",
+                bucket_id,
+                hash
+            )
+            .into());
+        }
+
+        tracing::debug!("Fetching blob {} on demand", hash);
+        blobs
+            .download_hash(hash, peers, &self.inner.endpoint)
+            .await?;
+        let size = blobs.size(&hash).await?.unwrap_or_default();
+        self.inner
+            .database
+            .record_cached_blob(&hash.to_string(), size)
+            .await
+            .map_err(|e| anyhow!("failed to record cached blob {}: {}", hash, e))?;
+        self.tracked().insert(hash, Instant::now());
+        Ok(())
+    }
+
+    fn accessed(&self, hash: &Hash) {
+        let mut tracked = self.tracked();
+        let Some(last) = tracked.get_mut(hash) else {
+            return;
+        };
+        if last.elapsed() < TOUCH_INTERVAL {
+            return;
+        }
+        *last = Instant::now();
+
+        let database = self.inner.database.clone();
+        let hash = hash.to_string();
+        tokio::spawn(async move {
+            if let Err(e) = database.touch_cached_blob(&hash).await {
+                tracing::warn!("Failed to record read of cached blob {}: {}", hash, e);
+            }
+        });
+    }
+}
+
+/// Fetch the directory nodes of an online-only bucket so it can be listed,
+/// leaving file content to be fetched when read
+pub(crate) async fn sync_metadata<L>(peer: &Peer<L>, bucket_id: Uuid) -> anyhow::Result<()>
+where
+    L: BucketLogProvider + Clone + Send + Sync + 'static,
+    L::Error: std::error::Error + Send + Sync + 'static,
+{
+    let mount = peer.mount_for_read(bucket_id).await?;
+    let entries = mount.ls_deep(Path::new("/")).await?;
+    tracing::info!(
+        "Synced metadata of online-only bucket {} ({} entries)",
+        bucket_id,
+        entries.len()
+    );
+    Ok(())
+}
+
+/// Keep the cache within its configured size until the daemon exits
+pub(crate) async fn run_eviction(
+    cache: BlobCache,
+    blobs: BlobsStore,
+    runtime_rx: watch::Receiver<RuntimeConfig>,
+) {
+    let mut interval = tokio::time::interval(EVICT_INTERVAL);
+    loop {
+        interval.tick().await;
+        let max_bytes = runtime_rx.borrow().online_cache_max_bytes;
+        if let Err(e) = cache.evict(&blobs, max_bytes).await {
+            tracing::error!("Failed to evict cached blobs: {}", e);
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BlobCacheError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("blob store error: {0}")]
+    Blobs(#[from] BlobsStoreError),
+}
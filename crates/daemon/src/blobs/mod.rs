@@ -4,11 +4,13 @@
 
 mod setup;
 
+use std::collections::BTreeMap;
 use std::path::Path;
 
 use common::peer::BlobsStore;
 
 use crate::state::BlobStoreConfig;
+use crate::storage_policy::BlobLocationConfig;
 
 /// Wrapper around the legacy BlobsStore.
 ///
@@ -22,9 +24,14 @@ impl Blobs {
     ///
     /// # Arguments
     /// * `config` - Blob store configuration (Legacy, Filesystem, or S3)
+    /// * `locations` - Other storage backends blobs can be placed in, by name
     /// * `jax_dir` - Path to the jax directory (used for legacy blobs and cache)
-    pub async fn setup(config: &BlobStoreConfig, jax_dir: &Path) -> Result<Self, BlobsSetupError> {
-        let store = setup::setup_blobs_store(config, jax_dir).await?;
+    pub async fn setup(
+        config: &BlobStoreConfig,
+        locations: &BTreeMap<String, BlobLocationConfig>,
+        jax_dir: &Path,
+    ) -> Result<Self, BlobsSetupError> {
+        let store = setup::setup_blobs_store(config, locations, jax_dir).await?;
         Ok(Self(store))
     }
 
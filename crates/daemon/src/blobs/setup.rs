@@ -1,10 +1,13 @@
 //! Blob store setup logic.
 
+use std::collections::BTreeMap;
 use std::path::Path;
 
 use common::peer::BlobsStore;
+use object_store::{ObjectStore, ObjectStoreConfig};
 
 use crate::state::BlobStoreConfig;
+use crate::storage_policy::BlobLocationConfig;
 
 use super::BlobsSetupError;
 
@@ -14,12 +17,33 @@ use super::BlobsSetupError;
 /// - Legacy: Uses iroh's FsStore (default, for backwards compatibility)
 /// - Filesystem: Uses SQLite + local filesystem via ObjectStore
 /// - S3: Uses SQLite + S3/MinIO via ObjectStore
+///
+/// Storage locations buckets can be placed in are opened alongside the
+/// ObjectStore modes; the legacy store has no metadata to track them in.
 pub async fn setup_blobs_store(
     config: &BlobStoreConfig,
+    locations: &BTreeMap<String, BlobLocationConfig>,
     jax_dir: &Path,
 ) -> Result<BlobsStore, BlobsSetupError> {
+    let locations = locations
+        .iter()
+        .map(|(name, location)| {
+            let config = location
+                .object_store_config()
+                .map_err(|e| BlobsSetupError::StoreError(format!("location {}: {}", name, e)))?;
+            Ok((name.clone(), config))
+        })
+        .collect::<Result<BTreeMap<_, _>, BlobsSetupError>>()?;
+    let names: Vec<&String> = locations.keys().collect();
+
     match config {
         BlobStoreConfig::Legacy => {
+            if !locations.is_empty() {
+                return Err(BlobsSetupError::StoreError(
+                    "blob locations need the filesystem or s3 blob store".to_string(),
+                ));
+            }
+
             // Use iroh's FsStore for backwards compatibility
             let blobs_path = jax_dir.join("blobs");
             tracing::info!(path = %blobs_path.display(), "Using iroh blob store");
@@ -29,11 +53,23 @@ pub async fn setup_blobs_store(
         }
 
         BlobStoreConfig::Filesystem { path } => {
-            // Use ObjectStore with local filesystem backend
-            tracing::info!(path = %path.display(), "Using SQLite + local filesystem blob store");
-            BlobsStore::fs(path)
-                .await
-                .map_err(|e| BlobsSetupError::StoreError(e.to_string()))
+            // Use ObjectStore with local filesystem backend, laid out as
+            //  ObjectStore::new_local does
+            tracing::info!(
+                path = %path.display(),
+                locations = ?names,
+                "Using SQLite + local filesystem blob store"
+            );
+            let store = ObjectStore::with_locations(
+                &path.join("blobs.db"),
+                ObjectStoreConfig::Local {
+                    path: path.join("objects"),
+                },
+                locations,
+            )
+            .await
+            .map_err(|e| BlobsSetupError::StoreError(e.to_string()))?;
+            Ok(BlobsStore::from_object_store(store))
         }
 
         BlobStoreConfig::S3 { url } => {
@@ -44,22 +80,27 @@ pub async fn setup_blobs_store(
             tracing::info!(
                 endpoint = %s3_config.endpoint,
                 bucket = %s3_config.bucket,
+                locations = ?names,
                 "Using SQLite + S3 blob store"
             );
 
             // SQLite database goes in jax_dir
             let db_path = jax_dir.join("blobs.db");
 
-            BlobsStore::s3(
+            let store = ObjectStore::with_locations(
                 &db_path,
-                &s3_config.endpoint,
-                &s3_config.access_key,
-                &s3_config.secret_key,
-                &s3_config.bucket,
-                None, // Use default region
+                ObjectStoreConfig::S3 {
+                    endpoint: s3_config.endpoint,
+                    access_key: s3_config.access_key,
+                    secret_key: s3_config.secret_key,
+                    bucket: s3_config.bucket,
+                    region: None, // Use default region
+                },
+                locations,
             )
             .await
-            .map_err(|e| BlobsSetupError::StoreError(e.to_string()))
+            .map_err(|e| BlobsSetupError::StoreError(e.to_string()))?;
+            Ok(BlobsStore::from_object_store(store))
         }
     }
 }
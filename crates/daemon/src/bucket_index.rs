@@ -0,0 +1,204 @@
+//! Public bucket index pages
+//!
+//! When the gateway serves the root of a bucket to a browser, and the bucket
+//! has no `index.html` of its own, it shows an index page: the bucket's title
+//! and description, a list of featured files, its README rendered from
+//! markdown, and then the root listing (if listings are on).
+//!
+//! The title, description and featured files are set when publishing
+//! (`jax bucket publish --title ... --description ... --featured /path`) and
+//! stored as attributes of the bucket root, so every gateway serving a
+//! published version shows the same page. Buckets with none of them set and
+//! no README get the plain listing, as before.
+//!
+//! Publishing with `--noindex true` likewise asks crawlers to stay away from
+//! the bucket on every gateway (see [`crate::robots`]).
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use common::mount::{Attrs, Mount};
+
+pub const TITLE_ATTR: &str = "gateway.title";
+pub const DESCRIPTION_ATTR: &str = "gateway.description";
+/// Absolute paths of the featured files, one per line
+pub const FEATURED_ATTR: &str = "gateway.featured";
+/// Set to `true` when the publisher doesn't want the bucket indexed
+pub const NOINDEX_ATTR: &str = "gateway.noindex";
+
+/// Root files shown as the README, in order of preference
+pub const README_NAMES: [&str; 5] = [
+    "README.md",
+    "readme.md",
+    "Readme.md",
+    "README.markdown",
+    "README.txt",
+];
+
+/// What a bucket's index page shows besides its README and listing
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IndexSettings {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub featured: Vec<String>,
+    /// Not shown on the page, only sent to crawlers
+    pub noindex: bool,
+}
+
+impl IndexSettings {
+    pub fn from_attrs(attrs: &Attrs) -> Self {
+        let text = |key: &str| {
+            attrs
+                .get(key)
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        Self {
+            title: text(TITLE_ATTR),
+            description: text(DESCRIPTION_ATTR),
+            featured: attrs
+                .get(FEATURED_ATTR)
+                .map(|paths| {
+                    paths
+                        .lines()
+                        .map(str::trim)
+                        .filter(|path| !path.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            noindex: attrs
+                .get(NOINDEX_ATTR)
+                .is_some_and(|value| value.trim() == "true"),
+        }
+    }
+
+    /// Whether there's nothing to show besides the README and listing
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none() && self.description.is_none() && self.featured.is_empty()
+    }
+}
+
+/// Changes to a bucket's index settings made when publishing. Fields left
+/// out are kept; empty ones are cleared.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, clap::Args)]
+pub struct IndexUpdate {
+    /// Title shown on the bucket's gateway index page (empty to clear)
+    #[arg(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    /// Description shown under the title (empty to clear)
+    #[arg(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// File to feature on the index page; repeat for more, or pass an empty
+    /// path to clear
+    #[arg(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub featured: Option<Vec<String>>,
+
+    /// Ask crawlers not to index the bucket on any gateway
+    #[arg(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub noindex: Option<bool>,
+}
+
+impl IndexUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.description.is_none()
+            && self.featured.is_none()
+            && self.noindex.is_none()
+    }
+
+    /// Attributes of the bucket root to set and remove
+    pub fn to_attrs(&self) -> (Attrs, Vec<String>) {
+        let mut set = Attrs::new();
+        let mut remove = Vec::new();
+        let mut apply = |key: &str, value: Option<String>| match value {
+            Some(value) if !value.trim().is_empty() => {
+                set.insert(key.to_string(), value.trim().to_string());
+            }
+            Some(_) => remove.push(key.to_string()),
+            None => {}
+        };
+        apply(TITLE_ATTR, self.title.clone());
+        apply(DESCRIPTION_ATTR, self.description.clone());
+        apply(
+            FEATURED_ATTR,
+            self.featured.as_ref().map(|paths| {
+                paths
+                    .iter()
+                    .map(|path| path.trim())
+                    .filter(|path| !path.is_empty())
+                    .map(|path| format!("/{}", path.trim_start_matches('/')))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }),
+        );
+        apply(
+            NOINDEX_ATTR,
+            self.noindex.map(|noindex| match noindex {
+                true => "true".to_string(),
+                false => String::new(),
+            }),
+        );
+        (set, remove)
+    }
+}
+
+/// The README at the root of a bucket, if it has one
+pub async fn find_readme(mount: &Mount) -> Option<PathBuf> {
+    let root = Path::new("/");
+    for name in README_NAMES {
+        let path = root.join(name);
+        if matches!(mount.get(&path).await, Ok(node) if !node.is_dir()) {
+            return Some(path);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_update_round_trip() {
+        let update = IndexUpdate {
+            title: Some("  Field notes ".to_string()),
+            description: None,
+            featured: Some(vec!["maps/route.gpx".to_string(), " ".to_string()]),
+            noindex: Some(true),
+        };
+        let (set, remove) = update.to_attrs();
+        assert!(remove.is_empty());
+        assert_eq!(
+            IndexSettings::from_attrs(&set),
+            IndexSettings {
+                title: Some("Field notes".to_string()),
+                description: None,
+                featured: vec!["/maps/route.gpx".to_string()],
+                noindex: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_empty_values_clear() {
+        let update = IndexUpdate {
+            title: Some(String::new()),
+            description: None,
+            featured: Some(vec![]),
+            noindex: Some(false),
+        };
+        let (set, remove) = update.to_attrs();
+        assert!(set.is_empty());
+        assert_eq!(remove, vec![TITLE_ATTR, FEATURED_ATTR, NOINDEX_ATTR]);
+        assert!(IndexUpdate::default().is_empty());
+        assert!(IndexSettings::from_attrs(&Attrs::new()).is_empty());
+    }
+}
@@ -0,0 +1,295 @@
+//! Bucket statistics: file counts, sizes and type breakdown of a version
+//!
+//! Statistics are computed by walking a bucket's tree. Directory nodes are
+//! content addressed, so each directory is summarised once by its hash and a
+//! new version only reads the directories it changed. The totals are cached
+//! per bucket until its head link moves. Caches are kept in memory and only
+//! hold the directories of each bucket's latest counted version.
+//!
+//! Sizes are of the files' plaintext, as recorded at upload. Files written
+//! before sizes were recorded, or appended to, are measured from their
+//! content, and count as empty if it isn't stored locally. The physical size
+//! is the space the bucket's file blobs take up in the local store, counting
+//! each blob once.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::linked_data::Hash;
+use common::mount::{ChunkList, Mount, MountError, NodeLink};
+use common::peer::{BlobsStore, BlobsStoreError};
+
+/// Most largest files kept per bucket
+pub const MAX_LARGEST_FILES: usize = 100;
+
+/// MIME type counted for files that have none recorded
+const UNKNOWN_MIME: &str = "application/octet-stream";
+
+/// Statistics of one version of a bucket
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BucketStats {
+    /// Hash of the counted version
+    pub version: String,
+    pub files: u64,
+    pub directories: u64,
+    /// Total size of the files
+    pub logical_bytes: u64,
+    /// Space their blobs take up locally after deduplication
+    pub physical_bytes: u64,
+    /// Largest files first
+    pub largest_files: Vec<FileSize>,
+    /// Files and bytes per MIME type, most bytes first
+    pub mime_types: Vec<MimeTypeStats>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileSize {
+    pub path: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MimeTypeStats {
+    pub mime_type: String,
+    pub files: u64,
+    pub bytes: u64,
+}
+
+/// The files and subdirectories directly in a directory
+#[derive(Debug)]
+struct DirSummary {
+    files: Vec<FileSummary>,
+    dirs: Vec<(String, Hash)>,
+}
+
+#[derive(Debug)]
+struct FileSummary {
+    name: String,
+    size: u64,
+    mime_type: String,
+    /// Blobs of the file stored locally, with their sizes
+    blobs: Vec<(Hash, u64)>,
+}
+
+#[derive(Debug)]
+struct CachedStats {
+    version: Hash,
+    stats: BucketStats,
+    dirs: HashMap<Hash, Arc<DirSummary>>,
+}
+
+/// Statistics of the buckets they were asked for, cached per head link
+#[derive(Clone, Default)]
+pub struct BucketStatsCache {
+    buckets: Arc<Mutex<HashMap<Uuid, CachedStats>>>,
+}
+
+impl BucketStatsCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Statistics of the version of a bucket `mount` holds, reusing the
+    /// summaries of directories counted for the bucket before
+    pub async fn stats(
+        &self,
+        bucket_id: Uuid,
+        mount: &Mount,
+    ) -> Result<BucketStats, BucketStatsError> {
+        let version = mount.link().await.hash();
+        let known = {
+            let buckets = self.buckets.lock().unwrap();
+            match buckets.get(&bucket_id) {
+                Some(cached) if cached.version == version => return Ok(cached.stats.clone()),
+                Some(cached) => cached.dirs.clone(),
+                None => HashMap::new(),
+            }
+        };
+
+        let mut walk = Walk {
+            mount,
+            blobs: mount.blobs(),
+            known,
+            visited: HashMap::new(),
+        };
+        let root = mount.inner().await.manifest().entry().hash();
+        let mut totals = Totals::default();
+        walk.count(Path::new("/"), root, &mut totals).await?;
+        let stats = totals.finish(version);
+
+        self.buckets.lock().unwrap().insert(
+            bucket_id,
+            CachedStats {
+                version,
+                stats: stats.clone(),
+                dirs: walk.visited,
+            },
+        );
+        Ok(stats)
+    }
+}
+
+struct Walk<'a> {
+    mount: &'a Mount,
+    blobs: BlobsStore,
+    /// Directories summarised for an earlier version
+    known: HashMap<Hash, Arc<DirSummary>>,
+    /// Directories of this version
+    visited: HashMap<Hash, Arc<DirSummary>>,
+}
+
+impl Walk<'_> {
+    async fn count(
+        &mut self,
+        path: &Path,
+        hash: Hash,
+        totals: &mut Totals,
+    ) -> Result<(), BucketStatsError> {
+        let summary = match self
+            .visited
+            .get(&hash)
+            .or_else(|| self.known.get(&hash))
+            .cloned()
+        {
+            Some(summary) => summary,
+            None => Arc::new(self.summarise(path).await?),
+        };
+        self.visited.insert(hash, summary.clone());
+
+        for file in &summary.files {
+            totals.add(path.join(&file.name), file);
+        }
+        for (name, dir_hash) in &summary.dirs {
+            totals.directories += 1;
+            Box::pin(self.count(&path.join(name), *dir_hash, totals)).await?;
+        }
+        Ok(())
+    }
+
+    async fn summarise(&self, path: &Path) -> Result<DirSummary, BucketStatsError> {
+        let mut summary = DirSummary {
+            files: Vec::new(),
+            dirs: Vec::new(),
+        };
+        for (item_path, node_link) in self.mount.ls(path).await? {
+            let name = item_path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            match node_link {
+                NodeLink::Dir(link, _) => summary.dirs.push((name, link.hash())),
+                NodeLink::Data(link, secret, data) => {
+                    let mut blobs = Vec::new();
+                    let stored = self.blobs.size(&link.hash()).await?;
+                    if let Some(size) = stored {
+                        blobs.push((link.hash(), size));
+                    }
+
+                    // Chunked files also take up the space of their chunks
+                    let mut chunks_size = None;
+                    if data.is_chunked() && stored.is_some() {
+                        let list = ChunkList::load(&self.blobs, &link, &secret).await?;
+                        for chunk in list.chunks() {
+                            let hash = chunk.link().hash();
+                            if let Some(size) = self.blobs.size(&hash).await? {
+                                blobs.push((hash, size));
+                            }
+                        }
+                        chunks_size = Some(list.size());
+                    }
+
+                    let size = match (data.size(), chunks_size) {
+                        (Some(size), _) | (None, Some(size)) => size,
+                        (None, None) if stored.is_some() && !data.is_chunked() => {
+                            let abs_path = Path::new("/").join(&item_path);
+                            self.mount.cat(&abs_path).await?.len() as u64
+                        }
+                        (None, None) => 0,
+                    };
+                    summary.files.push(FileSummary {
+                        name,
+                        size,
+                        mime_type: data
+                            .mime()
+                            .map(|mime| mime.to_string())
+                            .unwrap_or_else(|| UNKNOWN_MIME.to_string()),
+                        blobs,
+                    });
+                }
+                // Referenced buckets are counted in their own stats
+                NodeLink::Bucket(_) => {}
+            }
+        }
+        Ok(summary)
+    }
+}
+
+#[derive(Default)]
+struct Totals {
+    files: u64,
+    directories: u64,
+    logical_bytes: u64,
+    blobs: HashMap<Hash, u64>,
+    files_by_size: Vec<(u64, PathBuf)>,
+    mime_types: BTreeMap<String, (u64, u64)>,
+}
+
+impl Totals {
+    fn add(&mut self, path: PathBuf, file: &FileSummary) {
+        self.files += 1;
+        self.logical_bytes += file.size;
+        for (hash, size) in &file.blobs {
+            self.blobs.insert(*hash, *size);
+        }
+        let mime = self.mime_types.entry(file.mime_type.clone()).or_default();
+        mime.0 += 1;
+        mime.1 += file.size;
+        self.files_by_size.push((file.size, path));
+    }
+
+    fn finish(mut self, version: Hash) -> BucketStats {
+        self.files_by_size
+            .sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+        self.files_by_size.truncate(MAX_LARGEST_FILES);
+
+        let mut mime_types: Vec<MimeTypeStats> = self
+            .mime_types
+            .into_iter()
+            .map(|(mime_type, (files, bytes))| MimeTypeStats {
+                mime_type,
+                files,
+                bytes,
+            })
+            .collect();
+        mime_types.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| b.files.cmp(&a.files)));
+
+        BucketStats {
+            version: version.to_string(),
+            files: self.files,
+            directories: self.directories,
+            logical_bytes: self.logical_bytes,
+            physical_bytes: self.blobs.values().sum(),
+            largest_files: self
+                .files_by_size
+                .into_iter()
+                .map(|(size, path)| FileSize {
+                    path: path.to_string_lossy().into_owned(),
+                    size,
+                })
+                .collect(),
+            mime_types,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BucketStatsError {
+    #[error("Mount error: {0}")]
+    Mount(#[from] MountError),
+    #[error("Blobs error: {0}")]
+    Blobs(#[from] BlobsStoreError),
+}
@@ -0,0 +1,555 @@
+//! Mirroring published buckets into plain object storage for a CDN
+//!
+//! `config.toml` names the targets published content can be mirrored into,
+//! `[cdn_targets.<name>]`: an S3 bucket a CDN fronts, or a local directory a
+//! web server serves. A bucket's CDN export names its target and a prefix in
+//! it. Whenever the bucket is published on this node, its latest published
+//! version is decrypted and written there as plain files, one object per
+//! path, with the content type the gateway would serve it with. jax stays the
+//! source of truth: the target is overwritten with whatever is published, and
+//! unpublishing the bucket withdraws it.
+//!
+//! Versions are written under the prefix as `versions/<version>/<path>`,
+//! named by the hash of the bucket version, so their objects never change
+//! and are cached for a year. Once every file is there, `jax-manifest.json`
+//! at the prefix is replaced with one naming the version and listing its
+//! files, which switches the site over in one write; edge rules or CDN
+//! functions resolve paths through it. The manifest isn't cached. The
+//! version it replaced is kept, so pages loaded before the switch still find
+//! their assets, and older ones are deleted.
+//!
+//! Exports run one at a time in the background: after each publish or
+//! unpublish here, every [`CHECK_INTERVAL`] for versions published by peers,
+//! and on request (`/api/v0/bucket/cdn/run`). Buckets with a publish pipeline
+//! export what it built. Exports are audited as `bucket.cdn.export`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Component, Path};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use tokio::sync::{mpsc, oneshot};
+use uuid::Uuid;
+
+use common::bucket_log::BucketLogProvider;
+use common::linked_data::Link;
+use common::mount::{Mount, MountError, NodeLink};
+use common::peer::Peer;
+use object_store::{BlobStoreError, SiteStore};
+
+use crate::audit::{Actor, AuditEvent, AuditLog};
+use crate::database::Database;
+use crate::state::StateError;
+use crate::storage_policy::BlobLocationConfig;
+
+/// How often exports are checked against the latest published versions
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// File at the prefix naming the exported version
+pub const MANIFEST_FILE: &str = "jax-manifest.json";
+
+/// Directory below the prefix holding each exported version
+pub const VERSIONS_DIR: &str = "versions";
+
+/// Cache control of a version's files, which never change
+const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+
+/// Cache control of the manifest, which changes on every export
+const NO_CACHE: &str = "no-cache";
+
+/// A bucket's CDN export
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CdnExport {
+    pub bucket_id: Uuid,
+    /// Target the bucket is exported to, as configured under cdn_targets
+    pub target: String,
+    /// Path in the target the export is written below, empty for its root
+    pub prefix: String,
+    /// Hash of the version last exported, None if nothing is there
+    pub exported_version: Option<String>,
+    /// When it was exported or withdrawn
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub exported_at: Option<OffsetDateTime>,
+}
+
+/// The version a CDN export serves, as written to [`MANIFEST_FILE`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CdnManifest {
+    pub bucket_id: Uuid,
+    pub name: String,
+    /// Hash of the bucket version
+    pub version: String,
+    pub height: u64,
+    #[serde(with = "time::serde::rfc3339")]
+    pub exported_at: OffsetDateTime,
+    /// Directory holding the version's files, relative to the manifest
+    pub root: String,
+    /// Hash of the version this one replaced, whose files are kept
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replaced: Option<String>,
+    /// Every file, by its path in the bucket without the leading slash
+    pub files: BTreeMap<String, CdnFile>,
+}
+
+/// A file of an exported version
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CdnFile {
+    pub content_type: String,
+    pub size: u64,
+}
+
+/// What exporting a bucket wrote
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CdnExportReport {
+    pub bucket_id: Uuid,
+    pub target: String,
+    /// Hash of the version exported, None if the bucket isn't published and
+    /// the export was withdrawn
+    pub version: Option<String>,
+    pub height: Option<u64>,
+    /// Files in the version
+    pub files: u64,
+    /// Files written; the rest were there from an earlier attempt
+    pub uploaded_files: u64,
+    /// Versions deleted from the target
+    pub removed_versions: u64,
+}
+
+/// Key of a path below an export's prefix
+fn key(prefix: &str, path: &str) -> String {
+    if prefix.is_empty() {
+        path.to_string()
+    } else {
+        format!("{}/{}", prefix, path)
+    }
+}
+
+/// A prefix without surrounding slashes, if it stays inside the target
+pub fn normalize_prefix(prefix: &str) -> Option<String> {
+    let prefix = prefix.trim_matches('/');
+    let inside = Path::new(prefix)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+    (inside && !prefix.contains("//")).then(|| prefix.to_string())
+}
+
+/// Content type a file is served with: the type recorded when it was added,
+/// else the one its extension suggests
+fn content_type(path: &Path, node: &NodeLink) -> String {
+    node.data()
+        .and_then(|data| data.mime())
+        .map(|mime| mime.to_string())
+        .unwrap_or_else(|| {
+            mime_guess::from_path(path)
+                .first_or_octet_stream()
+                .to_string()
+        })
+}
+
+/// Handle for exporting buckets on request; checks for versions to export
+/// happen in the background
+#[derive(Debug, Clone)]
+pub struct CdnExports {
+    targets: Arc<BTreeMap<String, BlobLocationConfig>>,
+    jobs: mpsc::UnboundedSender<Job>,
+}
+
+type Reply = oneshot::Sender<Result<CdnExportReport, CdnExportError>>;
+
+/// Work for the export worker, with where to send its outcome if anyone
+/// waits for it
+enum Job {
+    /// Export a bucket's latest published version
+    Export(Uuid, Actor, Option<Reply>),
+    /// Delete what an export wrote, once it's removed or moved elsewhere
+    Withdraw(CdnExport, Reply),
+}
+
+impl CdnExports {
+    /// Start the export worker, which runs until the peer shuts down
+    pub fn spawn(
+        peer: Peer<Database>,
+        database: Database,
+        audit: AuditLog,
+        targets: BTreeMap<String, BlobLocationConfig>,
+    ) -> Self {
+        let targets = Arc::new(targets);
+        let (jobs, jobs_rx) = mpsc::unbounded_channel();
+        tokio::spawn(run_worker(peer, database, audit, targets.clone(), jobs_rx));
+        Self { targets, jobs }
+    }
+
+    /// Names of the configured targets
+    pub fn targets(&self) -> Vec<String> {
+        self.targets.keys().cloned().collect()
+    }
+
+    /// Export a bucket now, waiting for it to finish
+    pub async fn run(&self, bucket_id: Uuid) -> Result<CdnExportReport, CdnExportError> {
+        let (reply, result) = oneshot::channel();
+        self.jobs
+            .send(Job::Export(bucket_id, Actor::Api, Some(reply)))
+            .map_err(|_| CdnExportError::WorkerStopped)?;
+        result.await.map_err(|_| CdnExportError::WorkerStopped)?
+    }
+
+    /// Delete everything an export wrote to its target, waiting for it to
+    /// finish. The export should no longer be stored.
+    pub async fn withdraw(&self, cdn_export: CdnExport) -> Result<CdnExportReport, CdnExportError> {
+        let (reply, result) = oneshot::channel();
+        self.jobs
+            .send(Job::Withdraw(cdn_export, reply))
+            .map_err(|_| CdnExportError::WorkerStopped)?;
+        result.await.map_err(|_| CdnExportError::WorkerStopped)?
+    }
+
+    /// Export a bucket after it was published or unpublished, if it has a
+    /// CDN export
+    pub(crate) fn published(&self, bucket_id: Uuid) {
+        if !self.targets.is_empty() {
+            let _ = self.jobs.send(Job::Export(bucket_id, Actor::Cdn, None));
+        }
+    }
+}
+
+async fn run_worker(
+    peer: Peer<Database>,
+    database: Database,
+    audit: AuditLog,
+    targets: Arc<BTreeMap<String, BlobLocationConfig>>,
+    mut jobs: mpsc::UnboundedReceiver<Job>,
+) {
+    let mut check = tokio::time::interval(CHECK_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = check.tick() => {
+                let exports = match database.list_cdn_exports().await {
+                    Ok(exports) => exports,
+                    Err(e) => {
+                        tracing::warn!("Failed to load CDN exports: {}", e);
+                        continue;
+                    }
+                };
+                for cdn_export in exports {
+                    let latest = match latest(&peer, &database, cdn_export.bucket_id).await {
+                        Ok(latest) => latest.map(|(link, _)| link.hash().to_string()),
+                        Err(_) => continue,
+                    };
+                    if latest == cdn_export.exported_version {
+                        continue;
+                    }
+                    let result = export(&peer, &database, &targets, &cdn_export).await;
+                    finish(&audit, Actor::Cdn, cdn_export.bucket_id, &result).await;
+                }
+            }
+
+            Some(job) = jobs.recv() => match job {
+                Job::Export(bucket_id, actor, reply) => {
+                    let result = match database.cdn_export(&bucket_id).await {
+                        Ok(Some(cdn_export)) => {
+                            export(&peer, &database, &targets, &cdn_export).await
+                        }
+                        Ok(None) => Err(CdnExportError::NotConfigured(bucket_id)),
+                        Err(e) => Err(e.into()),
+                    };
+                    // Publishes of buckets without an export aren't worth a word
+                    if reply.is_some() || !matches!(result, Err(CdnExportError::NotConfigured(_))) {
+                        finish(&audit, actor, bucket_id, &result).await;
+                    }
+                    if let Some(reply) = reply {
+                        let _ = reply.send(result);
+                    }
+                }
+                Job::Withdraw(cdn_export, reply) => {
+                    let result = withdraw(&targets, &cdn_export).await;
+                    finish(&audit, Actor::Api, cdn_export.bucket_id, &result).await;
+                    let _ = reply.send(result);
+                }
+            },
+
+            _ = peer.cancelled() => break,
+        }
+    }
+    tracing::debug!("CDN export worker shutting down");
+}
+
+/// Log and audit an export's outcome
+async fn finish(
+    audit: &AuditLog,
+    actor: Actor,
+    bucket_id: Uuid,
+    result: &Result<CdnExportReport, CdnExportError>,
+) {
+    match result {
+        Ok(report) => audit_export(audit, actor, report).await,
+        Err(e) => tracing::warn!("Failed to export bucket {} to its CDN: {}", bucket_id, e),
+    }
+}
+
+/// The latest published version of what a bucket publishes: its site
+/// bucket if a publish pipeline builds it, else the bucket itself
+async fn latest(
+    peer: &Peer<Database>,
+    database: &Database,
+    bucket_id: Uuid,
+) -> Result<Option<(Link, u64)>, CdnExportError> {
+    let published = match database.publish_pipeline(&bucket_id).await? {
+        Some((_, Some(site_bucket_id))) => site_bucket_id,
+        _ => bucket_id,
+    };
+    peer.logs()
+        .latest_published(published)
+        .await
+        .map_err(|e| CdnExportError::BucketLog(e.to_string()))
+}
+
+/// Open an export's target
+async fn open(
+    targets: &BTreeMap<String, BlobLocationConfig>,
+    cdn_export: &CdnExport,
+) -> Result<SiteStore, CdnExportError> {
+    let config = targets
+        .get(&cdn_export.target)
+        .ok_or_else(|| CdnExportError::UnknownTarget(cdn_export.target.clone()))?;
+    Ok(SiteStore::new(config.object_store_config()?).await?)
+}
+
+/// An empty report of an export, as if it was withdrawn
+fn empty_report(cdn_export: &CdnExport) -> CdnExportReport {
+    CdnExportReport {
+        bucket_id: cdn_export.bucket_id,
+        target: cdn_export.target.clone(),
+        version: None,
+        height: None,
+        files: 0,
+        uploaded_files: 0,
+        removed_versions: 0,
+    }
+}
+
+/// Export a bucket's latest published version to its target, or withdraw
+/// the export if it has none
+pub async fn export(
+    peer: &Peer<Database>,
+    database: &Database,
+    targets: &BTreeMap<String, BlobLocationConfig>,
+    cdn_export: &CdnExport,
+) -> Result<CdnExportReport, CdnExportError> {
+    let Some((link, height)) = latest(peer, database, cdn_export.bucket_id).await? else {
+        let report = withdraw(targets, cdn_export).await?;
+        database
+            .set_cdn_exported(&cdn_export.bucket_id, None)
+            .await?;
+        return Ok(report);
+    };
+    let store = open(targets, cdn_export).await?;
+    let prefix = cdn_export.prefix.as_str();
+    let mut report = empty_report(cdn_export);
+
+    let previous: Option<CdnManifest> = match store.get(&key(prefix, MANIFEST_FILE)).await? {
+        Some(data) => serde_json::from_slice(&data).ok(),
+        None => None,
+    };
+    let mount = Mount::load(&link, peer.secret(), peer.blobs()).await?;
+    let version = link.hash().to_string();
+    let root = format!("{}/{}", VERSIONS_DIR, version);
+    let existing: BTreeSet<String> = store.list(&key(prefix, &root)).await?.into_iter().collect();
+
+    let mut files = BTreeMap::new();
+    for (path, node) in mount.ls_deep(Path::new("/")).await? {
+        if !matches!(node, NodeLink::Data(..)) {
+            continue;
+        }
+        let relative = path.strip_prefix("/").unwrap_or(&path);
+        let Some(name) = relative.to_str() else {
+            continue;
+        };
+        let content_type = content_type(relative, &node);
+        let object = key(prefix, &format!("{}/{}", root, name));
+        let known_size = node.data().and_then(|data| data.size());
+        let size = match known_size.filter(|_| existing.contains(&object)) {
+            Some(size) => size,
+            None => {
+                let data = mount.cat(&Path::new("/").join(relative)).await?;
+                let size = data.len() as u64;
+                store.put(&object, data, &content_type, IMMUTABLE).await?;
+                report.uploaded_files += 1;
+                size
+            }
+        };
+        files.insert(name.to_string(), CdnFile { content_type, size });
+    }
+    report.files = files.len() as u64;
+
+    // Everything is in place; switch over. Exporting the version served
+    // already keeps the one it replaced.
+    let replaced = match previous {
+        Some(previous) if previous.version == version => previous.replaced,
+        previous => previous.map(|previous| previous.version),
+    };
+    let manifest = CdnManifest {
+        bucket_id: cdn_export.bucket_id,
+        name: mount.inner().await.manifest().name().to_string(),
+        version: version.clone(),
+        height,
+        exported_at: OffsetDateTime::now_utc(),
+        root,
+        replaced: replaced.clone(),
+        files,
+    };
+    store
+        .put(
+            &key(prefix, MANIFEST_FILE),
+            serde_json::to_vec_pretty(&manifest)?,
+            "application/json",
+            NO_CACHE,
+        )
+        .await?;
+
+    let mut kept = BTreeSet::from([version.clone()]);
+    kept.extend(replaced);
+    report.removed_versions = remove_versions(&store, prefix, &kept).await?;
+    database
+        .set_cdn_exported(&cdn_export.bucket_id, Some(&version))
+        .await?;
+    tracing::info!(
+        "Exported version {} of bucket {} to {} ({} of {} file(s) written)",
+        version,
+        report.bucket_id,
+        report.target,
+        report.uploaded_files,
+        report.files
+    );
+    report.version = Some(version);
+    report.height = Some(height);
+    Ok(report)
+}
+
+/// Delete the manifest and every version an export wrote
+async fn withdraw(
+    targets: &BTreeMap<String, BlobLocationConfig>,
+    cdn_export: &CdnExport,
+) -> Result<CdnExportReport, CdnExportError> {
+    let store = open(targets, cdn_export).await?;
+    let prefix = cdn_export.prefix.as_str();
+    store.delete(&key(prefix, MANIFEST_FILE)).await?;
+    let mut report = empty_report(cdn_export);
+    report.removed_versions = remove_versions(&store, prefix, &BTreeSet::new()).await?;
+    tracing::info!(
+        "Withdrew the CDN export of bucket {} from {}",
+        report.bucket_id,
+        report.target
+    );
+    Ok(report)
+}
+
+/// Delete the versions below a prefix other than `kept`, returning how many
+async fn remove_versions(
+    store: &SiteStore,
+    prefix: &str,
+    kept: &BTreeSet<String>,
+) -> Result<u64, CdnExportError> {
+    let versions = key(prefix, VERSIONS_DIR);
+    let mut removed = BTreeSet::new();
+    for object in store.list(&versions).await? {
+        let Some(version) = object
+            .strip_prefix(&versions)
+            .and_then(|rest| rest.trim_start_matches('/').split('/').next())
+        else {
+            continue;
+        };
+        if !kept.contains(version) {
+            removed.insert(version.to_string());
+            store.delete(&object).await?;
+        }
+    }
+    Ok(removed.len() as u64)
+}
+
+/// Audit an export
+pub(crate) async fn audit_export(audit: &AuditLog, actor: Actor, report: &CdnExportReport) {
+    let detail = match &report.version {
+        Some(version) => format!("{} to {}", version, report.target),
+        None => format!("withdrawn from {}", report.target),
+    };
+    audit
+        .record(
+            AuditEvent::new(actor, "bucket.cdn.export")
+                .bucket(report.bucket_id)
+                .detail(detail),
+        )
+        .await;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CdnExportError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Bucket log error: {0}")]
+    BucketLog(String),
+    #[error("Mount error: {0}")]
+    Mount(#[from] MountError),
+    #[error("CDN target error: {0}")]
+    Target(#[from] BlobStoreError),
+    #[error("Invalid CDN target: {0}")]
+    Config(#[from] StateError),
+    #[error("Manifest error: {0}")]
+    Manifest(#[from] serde_json::Error),
+    #[error("Unknown CDN target {0} (configure it under cdn_targets in config.toml)")]
+    UnknownTarget(String),
+    #[error("Bucket {0} has no CDN export")]
+    NotConfigured(Uuid),
+    #[error("CDN export worker stopped")]
+    WorkerStopped,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_prefix() {
+        assert_eq!(normalize_prefix("").as_deref(), Some(""));
+        assert_eq!(
+            normalize_prefix("/sites/blog/").as_deref(),
+            Some("sites/blog")
+        );
+        assert_eq!(normalize_prefix("sites/../other"), None);
+        assert_eq!(normalize_prefix("./sites"), None);
+        assert_eq!(normalize_prefix("sites//blog"), None);
+
+        assert_eq!(key("", MANIFEST_FILE), "jax-manifest.json");
+        assert_eq!(
+            key("sites/blog", "versions/abc/index.html"),
+            "sites/blog/versions/abc/index.html"
+        );
+    }
+
+    #[test]
+    fn test_manifest_format() {
+        let manifest = CdnManifest {
+            bucket_id: Uuid::nil(),
+            name: "blog".to_string(),
+            version: "bafkreiabc".to_string(),
+            height: 3,
+            exported_at: OffsetDateTime::UNIX_EPOCH,
+            root: "versions/bafkreiabc".to_string(),
+            replaced: Some("bafkreiold".to_string()),
+            files: BTreeMap::from([(
+                "index.html".to_string(),
+                CdnFile {
+                    content_type: "text/html".to_string(),
+                    size: 12,
+                },
+            )]),
+        };
+        let json = serde_json::to_value(&manifest).unwrap();
+        assert_eq!(json["root"], "versions/bafkreiabc");
+        assert_eq!(json["exported_at"], "1970-01-01T00:00:00Z");
+        assert_eq!(json["files"]["index.html"]["content_type"], "text/html");
+        let parsed: CdnManifest = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, manifest);
+    }
+}
@@ -14,6 +14,10 @@ pub struct Args {
     #[arg(long, global = true)]
     pub config_path: Option<PathBuf>,
 
+    /// Profile of the daemon to talk to (default profile if not set)
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+
     #[command(subcommand)]
     pub command: crate::Command,
 }
@@ -2,4 +2,8 @@ pub mod args;
 pub mod op;
 pub mod ops;
 
-pub use ops::{Bucket, Daemon, Init, Mount, Version};
+pub use ops::{
+    Activity, Audit, Backup, Bucket, Config, Daemon, Escrow, ExportAll, Favorite, Gateway,
+    Identity, Init, Mount, Peer, Profile, Storage, Sync, VerifyManifest, Version, Webhook,
+    Workspace,
+};
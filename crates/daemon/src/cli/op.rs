@@ -11,14 +11,25 @@ pub struct OpContext {
     pub client: ApiClient,
     /// Optional custom config path (defaults to ~/.jax)
     pub config_path: Option<PathBuf>,
+    /// Profile API requests are made as (None for the default profile)
+    pub profile: Option<String>,
 }
 
 impl OpContext {
-    /// Create context with custom remote URL and optional config path
-    pub fn new(remote: Url, config_path: Option<PathBuf>) -> Result<Self, ApiError> {
+    /// Create context with custom remote URL, optional config path and profile
+    pub fn new(
+        remote: Url,
+        config_path: Option<PathBuf>,
+        profile: Option<String>,
+    ) -> Result<Self, ApiError> {
+        let client = match &profile {
+            Some(profile) => ApiClient::with_profile(&remote, profile)?,
+            None => ApiClient::new(&remote)?,
+        };
         Ok(Self {
-            client: ApiClient::new(&remote)?,
+            client,
             config_path,
+            profile,
         })
     }
 }
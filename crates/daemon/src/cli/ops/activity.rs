@@ -0,0 +1,72 @@
+use clap::Args;
+use uuid::Uuid;
+
+use jax_daemon::activity::Change;
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::activity::ActivityRequest;
+
+/// Show the paths recently changed across all buckets, newest first
+#[derive(Args, Debug, Clone)]
+pub struct Activity {
+    /// Only show changes of this bucket
+    #[arg(long)]
+    pub bucket_id: Option<Uuid>,
+
+    /// Number of changes to skip, from a previous page
+    #[arg(long)]
+    pub offset: Option<u32>,
+
+    /// Number of changes to show (default 50, at most 1000)
+    #[arg(long)]
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ActivityError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for Activity {
+    type Error = ActivityError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response = client
+            .call(ActivityRequest {
+                bucket_id: self.bucket_id,
+                offset: self.offset,
+                limit: self.limit,
+            })
+            .await?;
+
+        if response.items.is_empty() {
+            return Ok("No activity".to_string());
+        }
+        let mut lines: Vec<String> = response
+            .items
+            .iter()
+            .map(|item| {
+                let change = match item.change {
+                    Change::Added => "+",
+                    Change::Modified => "~",
+                    Change::Removed => "-",
+                };
+                let mut line = format!(
+                    "{} {} {}:{} (height {})",
+                    item.changed_at, change, item.bucket_name, item.path, item.height
+                );
+                if let Some(author) = &item.author {
+                    line.push_str(&format!(" by {}", &author[..author.len().min(16)]));
+                }
+                line
+            })
+            .collect();
+        if let Some(next_offset) = response.next_offset {
+            lines.push(format!("(more: --offset {})", next_offset));
+        }
+        Ok(lines.join("\n"))
+    }
+}
@@ -0,0 +1,57 @@
+use std::path::PathBuf;
+
+use clap::Args;
+
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::audit::AuditExportRequest;
+
+#[derive(Args, Debug, Clone)]
+pub struct AuditExport {
+    /// File to write the entries to, one JSON object per line; prints them
+    /// if not given
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub filter: AuditExportRequest,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AuditExportError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for AuditExport {
+    type Error = AuditExportError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let client = &ctx.client;
+        let response = self
+            .filter
+            .build_request(client.base_url(), client.http_client())
+            .send()
+            .await
+            .map_err(ApiError::from)?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.map_err(ApiError::from)?;
+            return Err(ApiError::from_response(status, &body).into());
+        }
+        let body = response.text().await.map_err(ApiError::from)?;
+
+        let Some(output) = &self.output else {
+            return Ok(body.trim_end().to_string());
+        };
+        std::fs::write(output, &body)?;
+        Ok(format!(
+            "Exported {} audit entries to {}",
+            body.lines().count(),
+            output.display()
+        ))
+    }
+}
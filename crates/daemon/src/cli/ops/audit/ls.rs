@@ -0,0 +1,45 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::audit::{AuditLsRequest, AuditLsResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum AuditLsError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for AuditLsRequest {
+    type Error = AuditLsError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: AuditLsResponse = client.call(self.clone()).await?;
+
+        if response.entries.is_empty() {
+            return Ok("No audit entries".to_string());
+        }
+        let lines = response
+            .entries
+            .iter()
+            .rev()
+            .map(|entry| {
+                let mut line = format!("{} {} {}", entry.at, entry.actor, entry.operation);
+                if let Some(bucket_id) = entry.bucket_id {
+                    line.push_str(&format!(" bucket={}", bucket_id));
+                }
+                if let Some(path) = &entry.path {
+                    line.push_str(&format!(" path={}", path));
+                }
+                if let Some(detail) = &entry.detail {
+                    line.push_str(&format!(" detail={}", detail));
+                }
+                if let Some(version) = &entry.version {
+                    line.push_str(&format!(" version={}", version));
+                }
+                line
+            })
+            .collect::<Vec<_>>();
+        Ok(lines.join("\n"))
+    }
+}
@@ -0,0 +1,34 @@
+use clap::{Args, Subcommand};
+
+pub mod export;
+pub mod ls;
+
+use crate::cli::op::Op;
+use jax_daemon::http_server::api::v0::audit::AuditLsRequest;
+
+pub use export::AuditExport;
+
+crate::command_enum! {
+    (Ls, AuditLsRequest),
+    (Export, AuditExport),
+}
+
+// Rename the generated Command to AuditCommand for clarity
+pub type AuditCommand = Command;
+
+/// Read the log of changes made to buckets and daemon settings
+#[derive(Args, Debug, Clone)]
+pub struct Audit {
+    #[command(subcommand)]
+    pub command: AuditCommand,
+}
+
+#[async_trait::async_trait]
+impl Op for Audit {
+    type Error = OpError;
+    type Output = OpOutput;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        self.command.execute(ctx).await
+    }
+}
@@ -0,0 +1,35 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::backup::{BackupCreateRequest, BackupJobInfo};
+
+#[derive(Debug, thiserror::Error)]
+pub enum BackupCreateError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for BackupCreateRequest {
+    type Error = BackupCreateError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        // The daemon resolves paths from its own working directory
+        let request = Self {
+            source_dir: std::fs::canonicalize(&self.source_dir)?,
+            ..self.clone()
+        };
+        let job: BackupJobInfo = client.call(request).await?;
+        Ok(format!(
+            "Created backup job {} of {} to {}:{} ({}, next run {})",
+            job.job_id,
+            job.source_dir.display(),
+            job.bucket_id,
+            job.target_path,
+            job.schedule,
+            job.next_run_at
+        ))
+    }
+}
@@ -0,0 +1,50 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::backup::{BackupListRequest, BackupListResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum BackupListError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for BackupListRequest {
+    type Error = BackupListError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: BackupListResponse = client.call(self.clone()).await?;
+
+        if response.jobs.is_empty() {
+            return Ok("No backup jobs found".to_string());
+        }
+        Ok(response
+            .jobs
+            .iter()
+            .map(|job| {
+                let last = match (job.last_run_at, &job.last_error) {
+                    (None, _) => "never".to_string(),
+                    (Some(at), None) => format!("ok at {}", at),
+                    (Some(at), Some(error)) => format!("failed at {}: {}", at, error),
+                };
+                format!(
+                    "{}{} {} -> {}:{} (schedule: {} | retain: {} | next run: {} | last run: {})",
+                    job.job_id,
+                    job.name
+                        .as_ref()
+                        .map(|name| format!(" [{}]", name))
+                        .unwrap_or_default(),
+                    job.source_dir.display(),
+                    job.bucket_id,
+                    job.target_path,
+                    job.schedule,
+                    job.retain,
+                    job.next_run_at,
+                    last
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
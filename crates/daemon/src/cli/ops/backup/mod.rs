@@ -0,0 +1,41 @@
+use clap::{Args, Subcommand};
+
+pub mod create;
+pub mod list;
+pub mod remove;
+pub mod run;
+pub mod snapshots;
+
+use crate::cli::op::Op;
+use jax_daemon::http_server::api::v0::backup::{
+    BackupCreateRequest, BackupListRequest, BackupRemoveRequest, BackupRunRequest,
+    BackupSnapshotsRequest,
+};
+
+crate::command_enum! {
+    (Create, BackupCreateRequest),
+    (List, BackupListRequest),
+    (Remove, BackupRemoveRequest),
+    (Run, BackupRunRequest),
+    (Snapshots, BackupSnapshotsRequest),
+}
+
+// Rename the generated Command to BackupCommand for clarity
+pub type BackupCommand = Command;
+
+/// Manage directories the daemon backs up into buckets on a schedule
+#[derive(Args, Debug, Clone)]
+pub struct Backup {
+    #[command(subcommand)]
+    pub command: BackupCommand,
+}
+
+#[async_trait::async_trait]
+impl Op for Backup {
+    type Error = OpError;
+    type Output = OpOutput;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        self.command.execute(ctx).await
+    }
+}
@@ -0,0 +1,24 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::backup::{BackupJobInfo, BackupRemoveRequest};
+
+#[derive(Debug, thiserror::Error)]
+pub enum BackupRemoveError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for BackupRemoveRequest {
+    type Error = BackupRemoveError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let job: BackupJobInfo = client.call(self.clone()).await?;
+        Ok(format!(
+            "Removed backup job {} ({})",
+            job.job_id,
+            job.source_dir.display()
+        ))
+    }
+}
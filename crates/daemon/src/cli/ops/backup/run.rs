@@ -0,0 +1,24 @@
+use jax_daemon::backup::BackupSnapshot;
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::backup::BackupRunRequest;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BackupRunError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for BackupRunRequest {
+    type Error = BackupRunError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let snapshot: BackupSnapshot = client.call(self.clone()).await?;
+        Ok(format!(
+            "Backed up: {} uploaded, {} deleted (version {}, height {})",
+            snapshot.files_uploaded, snapshot.files_deleted, snapshot.version, snapshot.height
+        ))
+    }
+}
@@ -0,0 +1,38 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::backup::{BackupSnapshotsRequest, BackupSnapshotsResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum BackupSnapshotsError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for BackupSnapshotsRequest {
+    type Error = BackupSnapshotsError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: BackupSnapshotsResponse = client.call(self.clone()).await?;
+
+        if response.snapshots.is_empty() {
+            return Ok("No snapshots found".to_string());
+        }
+        Ok(response
+            .snapshots
+            .iter()
+            .map(|snapshot| {
+                format!(
+                    "{} version {} (height {} | {} uploaded, {} deleted)",
+                    snapshot.taken_at,
+                    snapshot.version,
+                    snapshot.height,
+                    snapshot.files_uploaded,
+                    snapshot.files_deleted
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
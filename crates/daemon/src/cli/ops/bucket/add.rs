@@ -4,6 +4,7 @@ use jax_daemon::http_server::api::v0::bucket::add::AddResponse;
 use reqwest::multipart;
 use std::env;
 use std::path::PathBuf;
+use tokio_util::io::ReaderStream;
 use uuid::Uuid;
 
 #[derive(Args, Debug, Clone)]
@@ -62,14 +63,17 @@ impl crate::cli::op::Op for Add {
             env::current_dir()?.join(&path)
         };
 
-        // Read the file
-        let file_data = std::fs::read(&absolute_path)?;
+        // Stream the file rather than reading it into memory, so its size
+        //  isn't bound by the memory of either end
+        let file = tokio::fs::File::open(&absolute_path).await?;
+        let size = file.metadata().await?.len();
+        let body = reqwest::Body::wrap_stream(ReaderStream::new(file));
 
         // Build multipart form
         let form = multipart::Form::new()
             .text("bucket_id", bucket_id.to_string())
             .text("mount_path", self.mount_path.clone())
-            .part("file", multipart::Part::bytes(file_data));
+            .part("file", multipart::Part::stream_with_length(body, size));
 
         // Send multipart request
         let url = client.base_url().join("/api/v0/bucket/add").unwrap();
@@ -83,7 +87,7 @@ impl crate::cli::op::Op for Add {
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await?;
-            return Err(BucketAddError::Api(ApiError::HttpStatus(status, body)));
+            return Err(BucketAddError::Api(ApiError::from_response(status, &body)));
         }
 
         let response: AddResponse = response.json().await?;
@@ -0,0 +1,95 @@
+use std::io::Read;
+
+use clap::Args;
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::append::AppendResponse;
+use reqwest::multipart;
+use uuid::Uuid;
+
+#[derive(Args, Debug, Clone)]
+pub struct Append {
+    /// Bucket ID (or use --name)
+    #[arg(long, group = "bucket_identifier")]
+    pub bucket_id: Option<Uuid>,
+
+    /// Bucket name (or use --bucket-id)
+    #[arg(long, group = "bucket_identifier")]
+    pub name: Option<String>,
+
+    /// File whose contents to append; stdin if not given
+    #[arg(long)]
+    pub path: Option<String>,
+
+    /// Path in bucket of the file to append to, created if missing
+    #[arg(long)]
+    pub mount_path: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BucketAppendError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("HTTP error: {0}")]
+    Reqwest(#[from] reqwest::Error),
+    #[error("Either --bucket-id or --name must be provided")]
+    NoBucketIdentifier,
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for Append {
+    type Error = BucketAppendError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+
+        // Resolve bucket name to UUID if needed
+        let bucket_id = if let Some(id) = self.bucket_id {
+            id
+        } else if let Some(ref name) = self.name {
+            client.resolve_bucket_name(name).await?
+        } else {
+            return Err(BucketAppendError::NoBucketIdentifier);
+        };
+
+        let data = match &self.path {
+            Some(path) => std::fs::read(path)?,
+            None => {
+                let mut data = Vec::new();
+                std::io::stdin().read_to_end(&mut data)?;
+                data
+            }
+        };
+
+        let form = multipart::Form::new()
+            .text("bucket_id", bucket_id.to_string())
+            .text("mount_path", self.mount_path.clone())
+            .part("file", multipart::Part::bytes(data));
+
+        let url = client.base_url().join("/api/v0/bucket/append").unwrap();
+        let response = client
+            .http_client()
+            .post(url)
+            .multipart(form)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await?;
+            return Err(BucketAppendError::Api(ApiError::from_response(
+                status, &body,
+            )));
+        }
+
+        let response: AppendResponse = response.json().await?;
+        Ok(format!(
+            "Appended {} bytes to {} (bucket link: {})",
+            response.appended,
+            response.mount_path,
+            response.link.hash()
+        ))
+    }
+}
@@ -0,0 +1,21 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::attrs::{AttrsResponse, GetAttrsRequest};
+
+#[derive(Debug, thiserror::Error)]
+pub enum AttrsGetError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for GetAttrsRequest {
+    type Error = AttrsGetError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: AttrsResponse = client.call(self.clone()).await?;
+
+        Ok(super::describe(&response.path, &response.attrs))
+    }
+}
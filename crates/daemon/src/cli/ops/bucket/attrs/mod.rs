@@ -0,0 +1,44 @@
+use clap::{Args, Subcommand};
+
+pub mod get;
+pub mod set;
+
+use crate::cli::op::Op;
+use jax_daemon::http_server::api::v0::bucket::attrs::GetAttrsRequest;
+
+crate::command_enum! {
+    (Get, GetAttrsRequest),
+    (Set, set::Set),
+}
+
+// Rename the generated Command to AttrsCommand for clarity
+pub type AttrsCommand = Command;
+
+/// Read and change custom attributes on files and directories
+#[derive(Args, Debug, Clone)]
+pub struct Attrs {
+    #[command(subcommand)]
+    pub command: AttrsCommand,
+}
+
+#[async_trait::async_trait]
+impl Op for Attrs {
+    type Error = OpError;
+    type Output = OpOutput;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        self.command.execute(ctx).await
+    }
+}
+
+/// Render attributes for display, one `key=value` per line
+fn describe(path: &str, attrs: &common::mount::Attrs) -> String {
+    if attrs.is_empty() {
+        return format!("No attributes on {}", path);
+    }
+    attrs
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
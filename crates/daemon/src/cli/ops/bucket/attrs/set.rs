@@ -0,0 +1,75 @@
+use clap::Args;
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::attrs::{SetAttrsRequest, SetAttrsResponse};
+use uuid::Uuid;
+
+#[derive(Args, Debug, Clone)]
+pub struct Set {
+    /// Bucket ID (or use --name)
+    #[arg(long, group = "bucket_identifier")]
+    pub bucket_id: Option<Uuid>,
+
+    /// Bucket name (or use --bucket-id)
+    #[arg(long, group = "bucket_identifier")]
+    pub name: Option<String>,
+
+    /// Path in bucket of the file or directory (`/` for the bucket itself)
+    #[arg(long)]
+    pub path: String,
+
+    /// Attribute to set, as key=value (repeatable)
+    #[arg(long = "attr", value_parser = parse_attr)]
+    pub attrs: Vec<(String, String)>,
+
+    /// Attribute to remove (repeatable)
+    #[arg(long)]
+    pub remove: Vec<String>,
+
+    /// Fail unless the bucket head is still this version (a bucket link hash)
+    #[arg(long)]
+    pub expected_version: Option<String>,
+}
+
+fn parse_attr(s: &str) -> Result<(String, String), String> {
+    s.split_once('=')
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .ok_or_else(|| format!("expected key=value, got '{}'", s))
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AttrsSetError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+    #[error("Either --bucket-id or --name must be provided")]
+    NoBucketIdentifier,
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for Set {
+    type Error = AttrsSetError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+
+        // Resolve bucket name to UUID if needed
+        let bucket_id = if let Some(id) = self.bucket_id {
+            id
+        } else if let Some(ref name) = self.name {
+            client.resolve_bucket_name(name).await?
+        } else {
+            return Err(AttrsSetError::NoBucketIdentifier);
+        };
+
+        let request = SetAttrsRequest {
+            bucket_id,
+            path: self.path.clone(),
+            attrs: self.attrs.iter().cloned().collect(),
+            remove: self.remove.clone(),
+            expected_version: self.expected_version.clone(),
+        };
+        let response: SetAttrsResponse = client.call(request).await?;
+
+        Ok(super::describe(&response.path, &response.attrs))
+    }
+}
@@ -0,0 +1,81 @@
+use clap::Args;
+use uuid::Uuid;
+
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::backfill_mime::{
+    BackfillMimeRequest, BackfillMimeResponse,
+};
+use jax_daemon::http_server::api::v0::bucket::ListRequest;
+
+/// Detect the MIME type of files that have none, such as extensionless
+/// files added before content sniffing
+#[derive(Args, Debug, Clone)]
+pub struct BackfillMime {
+    /// Bucket ID to backfill; every bucket if not given
+    #[arg(long)]
+    pub bucket_id: Option<Uuid>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BackfillMimeError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for BackfillMime {
+    type Error = BackfillMimeError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+
+        if let Some(bucket_id) = self.bucket_id {
+            let response: BackfillMimeResponse =
+                client.call(BackfillMimeRequest { bucket_id }).await?;
+            return Ok(describe(&response));
+        }
+
+        // Keep going past buckets that fail, e.g. ones this node only mirrors
+        let buckets = client
+            .call(ListRequest {
+                prefix: None,
+                filter: None,
+                sort: None,
+                page: Default::default(),
+                workspace: None,
+                starred: false,
+            })
+            .await?
+            .buckets;
+        let mut lines = Vec::with_capacity(buckets.len());
+        for bucket in buckets {
+            let line = match client
+                .call(BackfillMimeRequest {
+                    bucket_id: bucket.bucket_id,
+                })
+                .await
+            {
+                Ok(response) => describe(&response),
+                Err(e) => format!("{}: failed: {}", bucket.bucket_id, e),
+            };
+            lines.push(format!("{} ({})", line, bucket.name));
+        }
+        if lines.is_empty() {
+            return Ok("No buckets found".to_string());
+        }
+        Ok(lines.join("\n"))
+    }
+}
+
+fn describe(response: &BackfillMimeResponse) -> String {
+    match &response.link {
+        Some(link) => format!(
+            "{}: detected the type of {} files (bucket link: {})",
+            response.bucket_id,
+            response.updated.len(),
+            link.hash()
+        ),
+        None => format!("{}: nothing to backfill", response.bucket_id),
+    }
+}
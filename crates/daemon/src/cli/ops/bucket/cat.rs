@@ -17,6 +17,10 @@ pub struct Cat {
     /// Path in bucket to read
     #[arg(long)]
     pub path: String,
+
+    /// Read at no older a version than this one (a bucket link hash)
+    #[arg(long)]
+    pub min_version: Option<String>,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -51,6 +55,7 @@ impl crate::cli::op::Op for Cat {
             bucket_id,
             path: self.path.clone(),
             at: None,
+            min_version: self.min_version.clone(),
             download: None,
         };
 
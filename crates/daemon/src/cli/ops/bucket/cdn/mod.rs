@@ -0,0 +1,57 @@
+use clap::{Args, Subcommand};
+
+pub mod policy;
+pub mod run;
+
+use crate::cli::op::Op;
+use jax_daemon::cdn_export::CdnExportReport;
+use jax_daemon::http_server::api::v0::bucket::cdn::{
+    CdnGetRequest, CdnResetRequest, CdnRunRequest, CdnSetRequest,
+};
+
+crate::command_enum! {
+    (Get, CdnGetRequest),
+    (Set, CdnSetRequest),
+    (Reset, CdnResetRequest),
+    (Run, CdnRunRequest),
+}
+
+// Rename the generated Command to CdnCommand for clarity
+pub type CdnCommand = Command;
+
+/// Mirror a bucket's published content into plain object storage for a CDN
+#[derive(Args, Debug, Clone)]
+pub struct Cdn {
+    #[command(subcommand)]
+    pub command: CdnCommand,
+}
+
+#[async_trait::async_trait]
+impl Op for Cdn {
+    type Error = OpError;
+    type Output = OpOutput;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        self.command.execute(ctx).await
+    }
+}
+
+/// Render an export for display
+fn describe_export(report: &CdnExportReport) -> String {
+    match (&report.version, report.height) {
+        (Some(version), Some(height)) => format!(
+            "Exported {} at height {} ({}) to {}: {} file(s), {} written, {} old version(s) removed",
+            report.bucket_id,
+            height,
+            version,
+            report.target,
+            report.files,
+            report.uploaded_files,
+            report.removed_versions
+        ),
+        _ => format!(
+            "Withdrew {} from {}: {} version(s) removed",
+            report.bucket_id, report.target, report.removed_versions
+        ),
+    }
+}
@@ -0,0 +1,78 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::cdn::{
+    CdnExportResponse, CdnGetRequest, CdnResetRequest, CdnResetResponse, CdnSetRequest,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum CdnPolicyError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+/// Render a CDN export for display
+fn describe(response: &CdnExportResponse) -> String {
+    let exported = match &response.export {
+        Some(export) => {
+            let place = if export.prefix.is_empty() {
+                export.target.clone()
+            } else {
+                format!("{}:{}", export.target, export.prefix)
+            };
+            match &export.exported_version {
+                Some(version) => format!(
+                    "{}: exported to {} (version {})",
+                    response.bucket_id, place, version
+                ),
+                None => format!(
+                    "{}: exported to {} (nothing written yet)",
+                    response.bucket_id, place
+                ),
+            }
+        }
+        None => format!("{}: no CDN export", response.bucket_id),
+    };
+    if response.targets.is_empty() {
+        format!("{} (no CDN targets configured)", exported)
+    } else {
+        format!("{} (targets: {})", exported, response.targets.join(", "))
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for CdnGetRequest {
+    type Error = CdnPolicyError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: CdnExportResponse = client.call(self.clone()).await?;
+        Ok(describe(&response))
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for CdnSetRequest {
+    type Error = CdnPolicyError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: CdnExportResponse = client.call(self.clone()).await?;
+        Ok(describe(&response))
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for CdnResetRequest {
+    type Error = CdnPolicyError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: CdnResetResponse = client.call(self.clone()).await?;
+        match &response.withdrawal {
+            Some(report) => Ok(super::describe_export(report)),
+            None => Ok(format!("{} has no CDN export", response.bucket_id)),
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use jax_daemon::cdn_export::CdnExportReport;
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::cdn::CdnRunRequest;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CdnRunError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for CdnRunRequest {
+    type Error = CdnRunError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let report: CdnExportReport = client.call(self.clone()).await?;
+        Ok(super::describe_export(&report))
+    }
+}
@@ -0,0 +1,24 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::claim::{ClaimRequest, ClaimResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum BucketClaimError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for ClaimRequest {
+    type Error = BucketClaimError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: ClaimResponse = client.call(self.clone()).await?;
+
+        Ok(format!(
+            "Following bucket {} from node {} as a temporary identity until its share expires.",
+            response.bucket_id, response.node_id
+        ))
+    }
+}
@@ -4,6 +4,7 @@ use uuid::Uuid;
 
 use crate::cli::op::Op;
 use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::HistoryRequest;
 
 use super::clone_state::{CloneConfig, CloneStateError, CloneStateManager, PathHashMap};
 
@@ -20,6 +21,11 @@ pub struct Clone {
     /// Directory to clone into (will be created if it doesn't exist)
     #[arg(long)]
     pub directory: PathBuf,
+
+    /// Only keep the content of the N most recent versions, e.g. 1 for the
+    /// latest version only. Deepen later with `jax bucket fetch`
+    #[arg(long)]
+    pub depth: Option<u64>,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -114,6 +120,16 @@ impl Op for Clone {
             .json()
             .await?;
 
+        // Skip downloading the content of older versions
+        if self.depth.is_some() {
+            client
+                .call(HistoryRequest {
+                    bucket_id,
+                    depth: self.depth,
+                })
+                .await?;
+        }
+
         // Initialize .jax directory with clone state
         let state_manager = CloneStateManager::new(self.directory.clone());
         let config = CloneConfig {
@@ -121,6 +137,7 @@ impl Op for Clone {
             bucket_name: export_result.bucket_name.clone(),
             last_synced_link: export_result.link,
             last_synced_height: export_result.height,
+            depth: self.depth,
         };
 
         state_manager.init(config)?;
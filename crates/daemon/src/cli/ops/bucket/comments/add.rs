@@ -0,0 +1,25 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::comments::{AddCommentRequest, AddCommentResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum CommentsAddError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for AddCommentRequest {
+    type Error = CommentsAddError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: AddCommentResponse = client.call(self.clone()).await?;
+        Ok(format!(
+            "Added comment {} on {} (saved as {})",
+            response.comment.id,
+            response.comment.path,
+            response.link.hash()
+        ))
+    }
+}
@@ -0,0 +1,29 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::comments::{CommentsRequest, CommentsResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum CommentsListError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for CommentsRequest {
+    type Error = CommentsListError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: CommentsResponse = client.call(self.clone()).await?;
+
+        if response.comments.is_empty() {
+            return Ok("No comments found".to_string());
+        }
+        Ok(response
+            .comments
+            .iter()
+            .map(super::describe)
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
@@ -0,0 +1,44 @@
+use clap::{Args, Subcommand};
+
+pub mod add;
+pub mod list;
+pub mod remove;
+
+use crate::cli::op::Op;
+use jax_daemon::http_server::api::v0::bucket::comments::{
+    AddCommentRequest, CommentInfo, CommentsRequest, RemoveCommentRequest,
+};
+
+crate::command_enum! {
+    (List, CommentsRequest),
+    (Add, AddCommentRequest),
+    (Remove, RemoveCommentRequest),
+}
+
+// Rename the generated Command to CommentsCommand for clarity
+pub type CommentsCommand = Command;
+
+/// Comment on files and directories
+#[derive(Args, Debug, Clone)]
+pub struct Comments {
+    #[command(subcommand)]
+    pub command: CommentsCommand,
+}
+
+#[async_trait::async_trait]
+impl Op for Comments {
+    type Error = OpError;
+    type Output = OpOutput;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        self.command.execute(ctx).await
+    }
+}
+
+/// Render a comment for display
+fn describe(comment: &CommentInfo) -> String {
+    format!(
+        "{} (id: {} | by: {} | at: {} | version: {})\n  {}",
+        comment.path, comment.id, comment.author, comment.created_at, comment.version, comment.body
+    )
+}
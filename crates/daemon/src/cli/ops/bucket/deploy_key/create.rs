@@ -0,0 +1,28 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::deploy_key::{
+    DeployKeyCreateRequest, DeployKeyCreateResponse,
+};
+
+use super::describe;
+
+#[derive(Debug, thiserror::Error)]
+pub enum DeployKeyCreateError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for DeployKeyCreateRequest {
+    type Error = DeployKeyCreateError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: DeployKeyCreateResponse = client.call(self.clone()).await?;
+        Ok(format!(
+            "Created deploy key {}\nSigning secret: {}",
+            describe(&response.key),
+            response.secret
+        ))
+    }
+}
@@ -0,0 +1,33 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::deploy_key::{
+    DeployKeyListRequest, DeployKeyListResponse,
+};
+
+use super::describe;
+
+#[derive(Debug, thiserror::Error)]
+pub enum DeployKeyListError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for DeployKeyListRequest {
+    type Error = DeployKeyListError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: DeployKeyListResponse = client.call(self.clone()).await?;
+
+        if response.keys.is_empty() {
+            return Ok("No deploy keys found".to_string());
+        }
+        Ok(response
+            .keys
+            .iter()
+            .map(describe)
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
@@ -0,0 +1,51 @@
+use clap::{Args, Subcommand};
+
+pub mod create;
+pub mod list;
+pub mod revoke;
+
+use crate::cli::op::Op;
+use jax_daemon::http_server::api::v0::bucket::deploy_key::{
+    DeployKeyCreateRequest, DeployKeyInfo, DeployKeyListRequest, DeployKeyRevokeRequest,
+};
+
+crate::command_enum! {
+    (Create, DeployKeyCreateRequest),
+    (List, DeployKeyListRequest),
+    (Revoke, DeployKeyRevokeRequest),
+}
+
+// Rename the generated Command to DeployKeyCommand for clarity
+pub type DeployKeyCommand = Command;
+
+/// Manage keys CI signs writes to a bucket with
+#[derive(Args, Debug, Clone)]
+pub struct DeployKey {
+    #[command(subcommand)]
+    pub command: DeployKeyCommand,
+}
+
+#[async_trait::async_trait]
+impl Op for DeployKey {
+    type Error = OpError;
+    type Output = OpOutput;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        self.command.execute(ctx).await
+    }
+}
+
+/// Render a deploy key for display
+fn describe(key: &DeployKeyInfo) -> String {
+    let name = key
+        .name
+        .as_ref()
+        .map_or_else(String::new, |name| format!(" \"{}\"", name));
+    let last_used = key
+        .last_used_at
+        .map_or_else(|| "never".to_string(), |at| at.to_string());
+    format!(
+        "{}{} (bucket: {} | prefix: {} | last used: {})",
+        key.key_id, name, key.bucket_id, key.path_prefix, last_used
+    )
+}
@@ -0,0 +1,20 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::deploy_key::{DeployKeyInfo, DeployKeyRevokeRequest};
+
+#[derive(Debug, thiserror::Error)]
+pub enum DeployKeyRevokeError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for DeployKeyRevokeRequest {
+    type Error = DeployKeyRevokeError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let key: DeployKeyInfo = client.call(self.clone()).await?;
+        Ok(format!("Revoked deploy key {}", key.key_id))
+    }
+}
@@ -0,0 +1,25 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::drop_link::{
+    DropApproveRequest, DropApproveResponse,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum DropApproveError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for DropApproveRequest {
+    type Error = DropApproveError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: DropApproveResponse = client.call(self.clone()).await?;
+        Ok(format!(
+            "Added {} to bucket {} (new version: {})",
+            response.path, response.bucket_id, response.new_bucket_link
+        ))
+    }
+}
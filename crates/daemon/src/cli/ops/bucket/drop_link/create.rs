@@ -0,0 +1,28 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::drop_link::{
+    DropLinkCreateRequest, DropLinkCreateResponse,
+};
+
+use super::describe;
+
+#[derive(Debug, thiserror::Error)]
+pub enum DropLinkCreateError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for DropLinkCreateRequest {
+    type Error = DropLinkCreateError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: DropLinkCreateResponse = client.call(self.clone()).await?;
+        let mut output = format!("Created drop link {}", describe(&response.link));
+        if let Some(url) = response.url {
+            output.push_str(&format!("\nUpload URL: {}", url));
+        }
+        Ok(output)
+    }
+}
@@ -0,0 +1,33 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::drop_link::{
+    DropLinkListRequest, DropLinkListResponse,
+};
+
+use super::describe;
+
+#[derive(Debug, thiserror::Error)]
+pub enum DropLinkListError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for DropLinkListRequest {
+    type Error = DropLinkListError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: DropLinkListResponse = client.call(self.clone()).await?;
+
+        if response.links.is_empty() {
+            return Ok("No drop links found".to_string());
+        }
+        Ok(response
+            .links
+            .iter()
+            .map(describe)
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
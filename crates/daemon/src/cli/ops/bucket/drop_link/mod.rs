@@ -0,0 +1,84 @@
+use clap::{Args, Subcommand};
+
+pub mod approve;
+pub mod create;
+pub mod list;
+pub mod pending;
+pub mod reject;
+pub mod revoke;
+
+use crate::cli::op::Op;
+use jax_daemon::http_server::api::v0::bucket::drop_link::{
+    DropApproveRequest, DropLinkCreateRequest, DropLinkInfo, DropLinkListRequest,
+    DropLinkRevokeRequest, DropPendingRequest, DropRejectRequest, DropUploadInfo,
+};
+
+crate::command_enum! {
+    (Create, DropLinkCreateRequest),
+    (List, DropLinkListRequest),
+    (Revoke, DropLinkRevokeRequest),
+    (Pending, DropPendingRequest),
+    (Approve, DropApproveRequest),
+    (Reject, DropRejectRequest),
+}
+
+// Rename the generated Command to DropLinkCommand for clarity
+pub type DropLinkCommand = Command;
+
+/// Manage write-only upload links and review what was uploaded through them
+#[derive(Args, Debug, Clone)]
+pub struct DropLink {
+    #[command(subcommand)]
+    pub command: DropLinkCommand,
+}
+
+#[async_trait::async_trait]
+impl Op for DropLink {
+    type Error = OpError;
+    type Output = OpOutput;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        self.command.execute(ctx).await
+    }
+}
+
+/// Render a drop link for display
+fn describe(link: &DropLinkInfo) -> String {
+    let name = link
+        .name
+        .as_ref()
+        .map_or_else(String::new, |name| format!(" \"{}\"", name));
+    let types = if link.allowed_types.is_empty() {
+        "any".to_string()
+    } else {
+        link.allowed_types.join(",")
+    };
+    let expires = link
+        .expires_at
+        .map_or_else(|| "never".to_string(), |at| at.to_string());
+    format!(
+        "{}{} (bucket: {} | directory: {} | max size: {} | types: {} | captcha: {} | expires: {})\n  {}",
+        link.link_id,
+        name,
+        link.bucket_id,
+        link.directory,
+        link.max_size,
+        types,
+        link.captcha,
+        expires,
+        link.url_path
+    )
+}
+
+/// Render a queued upload for display
+fn describe_upload(upload: &DropUploadInfo) -> String {
+    format!(
+        "{} {} ({} bytes, {}) via {} at {}",
+        upload.upload_id,
+        upload.file_name,
+        upload.size,
+        upload.mime_type,
+        upload.link_id,
+        upload.created_at
+    )
+}
@@ -0,0 +1,33 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::drop_link::{
+    DropPendingRequest, DropPendingResponse,
+};
+
+use super::describe_upload;
+
+#[derive(Debug, thiserror::Error)]
+pub enum DropPendingError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for DropPendingRequest {
+    type Error = DropPendingError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: DropPendingResponse = client.call(self.clone()).await?;
+
+        if response.uploads.is_empty() {
+            return Ok("No uploads waiting for review".to_string());
+        }
+        Ok(response
+            .uploads
+            .iter()
+            .map(describe_upload)
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
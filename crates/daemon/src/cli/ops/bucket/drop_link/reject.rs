@@ -0,0 +1,23 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::drop_link::{DropRejectRequest, DropUploadInfo};
+
+#[derive(Debug, thiserror::Error)]
+pub enum DropRejectError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for DropRejectRequest {
+    type Error = DropRejectError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let upload: DropUploadInfo = client.call(self.clone()).await?;
+        Ok(format!(
+            "Discarded {} ({})",
+            upload.file_name, upload.upload_id
+        ))
+    }
+}
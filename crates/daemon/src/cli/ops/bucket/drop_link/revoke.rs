@@ -0,0 +1,20 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::drop_link::{DropLinkInfo, DropLinkRevokeRequest};
+
+#[derive(Debug, thiserror::Error)]
+pub enum DropLinkRevokeError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for DropLinkRevokeRequest {
+    type Error = DropLinkRevokeError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let link: DropLinkInfo = client.call(self.clone()).await?;
+        Ok(format!("Revoked drop link {}", link.link_id))
+    }
+}
@@ -0,0 +1,27 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::editing::{
+    EditingClearRequest, EditingClearResponse,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum EditingClearError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for EditingClearRequest {
+    type Error = EditingClearError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: EditingClearResponse = client.call(self.clone()).await?;
+
+        Ok(if response.cleared {
+            format!("No longer editing {}", self.path)
+        } else {
+            format!("{} was not marked as being edited", self.path)
+        })
+    }
+}
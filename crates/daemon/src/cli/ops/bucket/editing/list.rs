@@ -0,0 +1,32 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::editing::{EditingListRequest, EditingListResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum EditingListError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for EditingListRequest {
+    type Error = EditingListError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: EditingListResponse = client.call(self.clone()).await?;
+
+        if response.editing.is_empty() {
+            return Ok(format!(
+                "Nothing is being edited in bucket {}",
+                response.bucket_id
+            ));
+        }
+        Ok(response
+            .editing
+            .iter()
+            .map(super::describe)
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
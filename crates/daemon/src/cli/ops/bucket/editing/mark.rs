@@ -0,0 +1,21 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::editing::{EditingInfo, EditingMarkRequest};
+
+#[derive(Debug, thiserror::Error)]
+pub enum EditingMarkError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for EditingMarkRequest {
+    type Error = EditingMarkError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let mark: EditingInfo = client.call(self.clone()).await?;
+
+        Ok(format!("Marked {}", super::describe(&mark)))
+    }
+}
@@ -0,0 +1,46 @@
+use clap::{Args, Subcommand};
+
+pub mod clear;
+pub mod list;
+pub mod mark;
+
+use crate::cli::op::Op;
+use jax_daemon::http_server::api::v0::bucket::editing::{
+    EditingClearRequest, EditingInfo, EditingListRequest, EditingMarkRequest,
+};
+
+crate::command_enum! {
+    (Mark, EditingMarkRequest),
+    (Clear, EditingClearRequest),
+    (List, EditingListRequest),
+}
+
+// Rename the generated Command to EditingCommand for clarity
+pub type EditingCommand = Command;
+
+/// Tell other owners which files you are editing
+#[derive(Args, Debug, Clone)]
+pub struct Editing {
+    #[command(subcommand)]
+    pub command: EditingCommand,
+}
+
+#[async_trait::async_trait]
+impl Op for Editing {
+    type Error = OpError;
+    type Output = OpOutput;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        self.command.execute(ctx).await
+    }
+}
+
+/// Render a mark for display
+fn describe(mark: &EditingInfo) -> String {
+    format!(
+        "{} being edited by {} for another {}s",
+        mark.path,
+        mark.editor,
+        mark.remaining_ms.div_ceil(1000)
+    )
+}
@@ -0,0 +1,70 @@
+use clap::Args;
+use std::path::PathBuf;
+
+use crate::cli::op::Op;
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::{HistoryRequest, HistoryResponse};
+
+use super::clone_state::{CloneStateError, CloneStateManager};
+
+#[derive(Args, Debug, Clone)]
+#[command(group(clap::ArgGroup::new("history_depth").required(true)))]
+pub struct Fetch {
+    /// Directory containing the cloned bucket (must have .jax directory)
+    #[arg(long)]
+    pub directory: PathBuf,
+
+    /// Download the content of every version
+    #[arg(long, group = "history_depth")]
+    pub history: bool,
+
+    /// Keep the content of the N most recent versions
+    #[arg(long, group = "history_depth")]
+    pub depth: Option<u64>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum FetchError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+    #[error("Clone state error: {0}")]
+    CloneState(#[from] CloneStateError),
+    #[error("Directory is not a cloned bucket (no .jax directory found)")]
+    NotCloned,
+}
+
+#[async_trait::async_trait]
+impl Op for Fetch {
+    type Error = FetchError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+
+        let state_manager = CloneStateManager::new(self.directory.clone());
+        if !state_manager.is_initialized() {
+            return Err(FetchError::NotCloned);
+        }
+        let mut config = state_manager.read_config()?;
+
+        let depth = if self.history { None } else { self.depth };
+        let response: HistoryResponse = client
+            .call(HistoryRequest {
+                bucket_id: config.bucket_id,
+                depth,
+            })
+            .await?;
+
+        config.depth = response.depth;
+        state_manager.write_config(&config)?;
+
+        let kept = match response.depth {
+            Some(depth) => format!("the latest {} version(s)", depth),
+            None => "all versions".to_string(),
+        };
+        Ok(format!(
+            "Keeping the content of {} of '{}'\nQueued {} older version(s) for download",
+            kept, config.bucket_name, response.queued_versions
+        ))
+    }
+}
@@ -0,0 +1,24 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::fork::{ForkRequest, ForkResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum BucketForkError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for ForkRequest {
+    type Error = BucketForkError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: ForkResponse = client.call(self.clone()).await?;
+
+        Ok(format!(
+            "Forked bucket {} into '{}' (id: {}). New bucket link: {}",
+            response.source_bucket_id, response.name, response.bucket_id, response.new_bucket_link
+        ))
+    }
+}
@@ -0,0 +1,78 @@
+use clap::Args;
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::import_remote::ImportRemoteRequest;
+use jax_daemon::remote_import::RemoteImportReport;
+
+/// Import existing cloud storage into a bucket. The first import of a URL
+/// creates the bucket; importing it again copies only what changed.
+#[derive(Args, Debug, Clone)]
+pub struct ImportRemote {
+    /// Storage to import: s3://bucket/prefix, gs://bucket/prefix,
+    /// az://container/prefix, webdav://host/path, or file:///path below one
+    /// of the import_dirs in the daemon's config.toml. gs:// is read through
+    /// Google Cloud Storage's S3-compatible API and needs HMAC keys as
+    /// -o access_key_id=... -o secret_access_key=...
+    pub url: String,
+
+    /// Name of the bucket created on the first import (defaults to the last
+    /// segment of the URL)
+    #[arg(long)]
+    pub name: Option<String>,
+
+    /// Client option for the storage, as key=value (repeatable), e.g.
+    /// endpoint=http://localhost:9000 or access_key_id=...
+    #[arg(long = "option", short = 'o', value_parser = parse_option)]
+    pub options: Vec<(String, String)>,
+
+    /// Remove files whose objects are gone from the storage
+    #[arg(long)]
+    pub delete: bool,
+}
+
+fn parse_option(s: &str) -> Result<(String, String), String> {
+    s.split_once('=')
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .ok_or_else(|| format!("expected key=value, got '{}'", s))
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ImportRemoteError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for ImportRemote {
+    type Error = ImportRemoteError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let request = ImportRemoteRequest {
+            url: self.url.clone(),
+            name: self.name.clone(),
+            options: self.options.iter().cloned().collect(),
+            delete: self.delete,
+        };
+        let report: RemoteImportReport = client.call(request).await?;
+
+        let mut output = format!(
+            "{} bucket '{}' (id: {}) from {}\n{} of {} object(s) imported ({} bytes)",
+            if report.created { "Created" } else { "Updated" },
+            report.name,
+            report.bucket_id,
+            report.source,
+            report.imported_files,
+            report.objects,
+            report.imported_bytes
+        );
+        if report.removed_files > 0 {
+            output.push_str(&format!(", {} file(s) removed", report.removed_files));
+        }
+        match report.version {
+            Some(version) => output.push_str(&format!("\nNew bucket link: {}", version)),
+            None => output.push_str("\nNothing changed"),
+        }
+        Ok(output)
+    }
+}
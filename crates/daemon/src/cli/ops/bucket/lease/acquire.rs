@@ -0,0 +1,21 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::lease::{LeaseAcquireRequest, LeaseInfo};
+
+#[derive(Debug, thiserror::Error)]
+pub enum LeaseAcquireError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for LeaseAcquireRequest {
+    type Error = LeaseAcquireError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let lease: LeaseInfo = client.call(self.clone()).await?;
+
+        Ok(format!("Leased {}", super::describe(&lease)))
+    }
+}
@@ -0,0 +1,29 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::lease::{LeaseListRequest, LeaseListResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum LeaseListError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for LeaseListRequest {
+    type Error = LeaseListError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: LeaseListResponse = client.call(self.clone()).await?;
+
+        if response.leases.is_empty() {
+            return Ok(format!("No leases on bucket {}", response.bucket_id));
+        }
+        Ok(response
+            .leases
+            .iter()
+            .map(super::describe)
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
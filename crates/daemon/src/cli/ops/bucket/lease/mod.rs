@@ -0,0 +1,46 @@
+use clap::{Args, Subcommand};
+
+pub mod acquire;
+pub mod list;
+pub mod release;
+
+use crate::cli::op::Op;
+use jax_daemon::http_server::api::v0::bucket::lease::{
+    LeaseAcquireRequest, LeaseInfo, LeaseListRequest, LeaseReleaseRequest,
+};
+
+crate::command_enum! {
+    (Acquire, LeaseAcquireRequest),
+    (List, LeaseListRequest),
+    (Release, LeaseReleaseRequest),
+}
+
+// Rename the generated Command to LeaseCommand for clarity
+pub type LeaseCommand = Command;
+
+/// Coordinate writers with advisory write leases on a bucket
+#[derive(Args, Debug, Clone)]
+pub struct Lease {
+    #[command(subcommand)]
+    pub command: LeaseCommand,
+}
+
+#[async_trait::async_trait]
+impl Op for Lease {
+    type Error = OpError;
+    type Output = OpOutput;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        self.command.execute(ctx).await
+    }
+}
+
+/// Render a lease for display
+fn describe(lease: &LeaseInfo) -> String {
+    format!(
+        "{} held by {} for another {}s",
+        lease.prefix,
+        lease.holder,
+        lease.remaining_ms.div_ceil(1000)
+    )
+}
@@ -0,0 +1,25 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::lease::{LeaseReleaseRequest, LeaseReleaseResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum LeaseReleaseError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for LeaseReleaseRequest {
+    type Error = LeaseReleaseError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: LeaseReleaseResponse = client.call(self.clone()).await?;
+
+        Ok(if response.released {
+            format!("Released lease on {}", self.prefix)
+        } else {
+            format!("No lease held on {}", self.prefix)
+        })
+    }
+}
@@ -22,12 +22,29 @@ impl crate::cli::op::Op for ListRequest {
         if response.buckets.is_empty() {
             Ok("No buckets found".to_string())
         } else {
-            let output = response
+            let mut output = response
                 .buckets
                 .iter()
-                .map(|b| format!("{} (id: {} | link: {})", b.name, b.bucket_id, b.link.hash()))
+                .map(|b| {
+                    let star = if b.starred { " *" } else { "" };
+                    format!(
+                        "{} (id: {} | link: {}){}",
+                        b.name,
+                        b.bucket_id,
+                        b.link.hash(),
+                        star
+                    )
+                })
                 .collect::<Vec<_>>()
                 .join("\n");
+            if let Some(cursor) = &response.next_cursor {
+                output.push_str(&format!(
+                    "\n{} of {} buckets, next page: --cursor {}",
+                    response.buckets.len(),
+                    response.total,
+                    cursor
+                ));
+            }
             Ok(output)
         }
     }
@@ -1,6 +1,7 @@
 use clap::Args;
 use jax_daemon::http_server::api::client::ApiError;
-use jax_daemon::http_server::api::v0::bucket::ls::{LsRequest, LsResponse};
+use jax_daemon::http_server::api::v0::bucket::ls::{LsRequest, LsResponse, LsSort};
+use jax_daemon::pagination::PageRequest;
 use uuid::Uuid;
 
 #[derive(Args, Debug, Clone)]
@@ -20,6 +21,25 @@ pub struct Ls {
     /// List recursively
     #[arg(long)]
     pub deep: Option<bool>,
+
+    /// List at no older a version than this one (a bucket link hash)
+    #[arg(long)]
+    pub min_version: Option<String>,
+
+    /// Only list starred files and directories under the path, at any depth
+    #[arg(long)]
+    pub starred: bool,
+
+    /// Only list files and directories whose name contains this, ignoring case
+    #[arg(long)]
+    pub filter: Option<String>,
+
+    /// Sort by path (the default), name, size or type
+    #[arg(long, value_enum)]
+    pub sort: Option<LsSort>,
+
+    #[command(flatten)]
+    pub page: PageRequest,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -52,6 +72,11 @@ impl crate::cli::op::Op for Ls {
             bucket_id,
             path: self.path.clone(),
             deep: self.deep,
+            min_version: self.min_version.clone(),
+            starred: self.starred,
+            filter: self.filter.clone(),
+            sort: self.sort,
+            page: self.page.clone(),
         };
 
         // Call API
@@ -60,15 +85,49 @@ impl crate::cli::op::Op for Ls {
         if response.items.is_empty() {
             Ok("No items found".to_string())
         } else {
-            let output = response
+            let mut output = response
                 .items
                 .iter()
                 .map(|item| {
                     let type_str = if item.is_dir { "dir" } else { "file" };
-                    format!("{} ({}) [{}]", item.path, type_str, item.link.hash())
+                    let star = if item.starred { " *" } else { "" };
+                    let mut line = format!(
+                        "{} ({}) [{}]{}",
+                        item.path,
+                        type_str,
+                        item.link.hash(),
+                        star
+                    );
+                    if !item.editing.is_empty() {
+                        let editors = item
+                            .editing
+                            .iter()
+                            .map(|mark| mark.editor.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        line = format!("{} (being edited by {})", line, editors);
+                    }
+                    if item.attrs.is_empty() {
+                        return line;
+                    }
+                    let attrs = item
+                        .attrs
+                        .iter()
+                        .map(|(key, value)| format!("{}={}", key, value))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    format!("{} {{{}}}", line, attrs)
                 })
                 .collect::<Vec<_>>()
                 .join("\n");
+            if let Some(cursor) = &response.next_cursor {
+                output.push_str(&format!(
+                    "\n{} of {} items, next page: --cursor {}",
+                    response.items.len(),
+                    response.total,
+                    cursor
+                ));
+            }
             Ok(output)
         }
     }
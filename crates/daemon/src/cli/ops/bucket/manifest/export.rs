@@ -0,0 +1,75 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::manifest::ManifestRequest;
+use uuid::Uuid;
+
+#[derive(Args, Debug, Clone)]
+pub struct Export {
+    /// Bucket ID (or use --name)
+    #[arg(long, group = "bucket_identifier")]
+    pub bucket_id: Option<Uuid>,
+
+    /// Bucket name (or use --bucket-id)
+    #[arg(long, group = "bucket_identifier")]
+    pub name: Option<String>,
+
+    /// Version to list (a bucket link hash), instead of the current one
+    #[arg(long)]
+    pub at: Option<String>,
+
+    /// Write the manifest to this file instead of printing it
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ManifestExportError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Either --bucket-id or --name must be provided")]
+    NoBucketIdentifier,
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for Export {
+    type Error = ManifestExportError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+
+        let bucket_id = if let Some(id) = self.bucket_id {
+            id
+        } else if let Some(ref name) = self.name {
+            client.resolve_bucket_name(name).await?
+        } else {
+            return Err(ManifestExportError::NoBucketIdentifier);
+        };
+
+        let response = client
+            .call(ManifestRequest {
+                bucket_id,
+                at: self.at.clone(),
+            })
+            .await?;
+
+        match &self.output {
+            Some(output) => {
+                std::fs::write(output, &response.manifest)?;
+                Ok(format!(
+                    "Wrote a manifest of {} file(s) in version {} (height {}) of {} to {}",
+                    response.files,
+                    response.version,
+                    response.height,
+                    bucket_id,
+                    output.display()
+                ))
+            }
+            None => Ok(response.manifest.trim_end().to_string()),
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use clap::{Args, Subcommand};
+
+pub mod export;
+
+use crate::cli::op::Op;
+
+crate::command_enum! {
+    (Export, export::Export),
+}
+
+// Rename the generated Command to ManifestCommand for clarity
+pub type ManifestCommand = Command;
+
+/// Signed listings of bucket versions for export records, checked with
+/// `jax verify-manifest`
+#[derive(Args, Debug, Clone)]
+pub struct Manifest {
+    #[command(subcommand)]
+    pub command: ManifestCommand,
+}
+
+#[async_trait::async_trait]
+impl Op for Manifest {
+    type Error = OpError;
+    type Output = OpOutput;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        self.command.execute(ctx).await
+    }
+}
@@ -0,0 +1,66 @@
+use clap::{Args, Subcommand};
+
+pub mod approve;
+pub mod pending;
+pub mod policy;
+pub mod reject;
+
+use crate::cli::op::Op;
+use jax_daemon::http_server::api::v0::bucket::member::{
+    MemberApproveRequest, MemberPendingRequest, MemberPolicyGetRequest, MemberPolicyResetRequest,
+    MemberPolicyResponse, MemberPolicySetRequest, MemberRejectRequest,
+};
+
+crate::command_enum! {
+    (Policy, MemberPolicyGetRequest),
+    (SetPolicy, MemberPolicySetRequest),
+    (ResetPolicy, MemberPolicyResetRequest),
+    (Pending, MemberPendingRequest),
+    (Approve, MemberApproveRequest),
+    (Reject, MemberRejectRequest),
+}
+
+// Rename the generated Command to MemberCommand for clarity
+pub type MemberCommand = Command;
+
+/// Decide what happens when members join a bucket and approve held shares
+#[derive(Args, Debug, Clone)]
+pub struct Member {
+    #[command(subcommand)]
+    pub command: MemberCommand,
+}
+
+#[async_trait::async_trait]
+impl Op for Member {
+    type Error = OpError;
+    type Output = OpOutput;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        self.command.execute(ctx).await
+    }
+}
+
+/// Render a member policy for display
+fn describe(response: &MemberPolicyResponse) -> String {
+    let policy = &response.policy;
+    let subject = match (&response.bucket_id, response.is_default) {
+        (Some(bucket_id), false) => bucket_id.to_string(),
+        (Some(bucket_id), true) => format!("{} (default)", bucket_id),
+        (None, _) => "default".to_string(),
+    };
+    format!(
+        "{}: auto-pin {}, notify members {}, require approval {}",
+        subject,
+        on_off(policy.auto_pin),
+        on_off(policy.notify_members),
+        on_off(policy.require_approval)
+    )
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value {
+        "on"
+    } else {
+        "off"
+    }
+}
@@ -0,0 +1,40 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::member::{
+    MemberPendingRequest, MemberPendingResponse,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum MemberPendingError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for MemberPendingRequest {
+    type Error = MemberPendingError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: MemberPendingResponse = client.call(self.clone()).await?;
+
+        if response.shares.is_empty() {
+            return Ok("No shares waiting for approval".to_string());
+        }
+        Ok(response
+            .shares
+            .iter()
+            .map(|share| match &share.path {
+                Some(path) => format!(
+                    "{} {} for {} (requested {})",
+                    share.bucket_id, share.peer_public_key, path, share.requested_at
+                ),
+                None => format!(
+                    "{} {} as {} (requested {})",
+                    share.bucket_id, share.peer_public_key, share.role, share.requested_at
+                ),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
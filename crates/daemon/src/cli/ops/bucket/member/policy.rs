@@ -0,0 +1,57 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::member::{
+    MemberPolicyGetRequest, MemberPolicyResetRequest, MemberPolicyResetResponse,
+    MemberPolicyResponse, MemberPolicySetRequest,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum MemberPolicyError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for MemberPolicyGetRequest {
+    type Error = MemberPolicyError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: MemberPolicyResponse = client.call(self.clone()).await?;
+        Ok(super::describe(&response))
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for MemberPolicySetRequest {
+    type Error = MemberPolicyError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: MemberPolicyResponse = client.call(self.clone()).await?;
+        Ok(super::describe(&response))
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for MemberPolicyResetRequest {
+    type Error = MemberPolicyError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: MemberPolicyResetResponse = client.call(self.clone()).await?;
+        if response.removed {
+            Ok(format!(
+                "{} follows the default member policy again",
+                response.bucket_id
+            ))
+        } else {
+            Ok(format!(
+                "{} already follows the default member policy",
+                response.bucket_id
+            ))
+        }
+    }
+}
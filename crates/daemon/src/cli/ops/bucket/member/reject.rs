@@ -0,0 +1,23 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::member::{MemberRejectRequest, MemberRejectResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum MemberRejectError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for MemberRejectRequest {
+    type Error = MemberRejectError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: MemberRejectResponse = client.call(self.clone()).await?;
+        Ok(format!(
+            "Share of bucket {} with peer {} rejected",
+            response.bucket_id, response.peer_public_key
+        ))
+    }
+}
@@ -1,27 +1,95 @@
 use clap::{Args, Subcommand};
 
 pub mod add;
+pub mod append;
+pub mod attrs;
+pub mod backfill_mime;
 pub mod cat;
+pub mod cdn;
+pub mod claim;
 pub mod clone;
 pub mod clone_state;
+pub mod comments;
 pub mod create;
+pub mod deploy_key;
+pub mod drop_link;
+pub mod editing;
+pub mod fetch;
+pub mod follow;
+pub mod fork;
+pub mod import_remote;
+pub mod lease;
 pub mod list;
 pub mod ls;
+pub mod manifest;
+pub mod member;
+pub mod photos;
+pub mod pipeline;
+pub mod presign;
+pub mod publish;
+pub mod reference;
+pub mod republish;
+pub mod retention;
+pub mod rsync;
+pub mod scans;
 pub mod share;
+pub mod share_ephemeral;
+pub mod shares;
+pub mod stats;
+pub mod storage;
 pub mod sync;
+pub mod unfollow;
+pub mod unpublish;
+pub mod versions;
 
 use crate::cli::op::Op;
-use jax_daemon::http_server::api::v0::bucket::{CreateRequest, ListRequest, ShareRequest};
+use jax_daemon::http_server::api::v0::bucket::{
+    ClaimRequest, CreateRequest, FollowRequest, ForkRequest, ListRequest, PhotosRequest,
+    PresignRequest, PublishRequest, ReferenceRequest, ScansRequest, ShareEphemeralRequest,
+    ShareRequest, SharesRequest, UnfollowRequest, UnpublishRequest, VersionsRequest,
+};
 
 crate::command_enum! {
     (Create, CreateRequest),
+    (Fork, ForkRequest),
+    (ImportRemote, import_remote::ImportRemote),
     (List, ListRequest),
     (Add, add::Add),
+    (Append, append::Append),
+    (Attrs, attrs::Attrs),
+    (BackfillMime, backfill_mime::BackfillMime),
     (Ls, ls::Ls),
+    (Manifest, manifest::Manifest),
+    (Photos, PhotosRequest),
+    (Presign, PresignRequest),
     (Cat, cat::Cat),
+    (Cdn, cdn::Cdn),
+    (Reference, ReferenceRequest),
+    (Comments, comments::Comments),
     (Share, ShareRequest),
+    (ShareEphemeral, ShareEphemeralRequest),
+    (Shares, SharesRequest),
+    (Stats, stats::Stats),
     (Clone, clone::Clone),
+    (DeployKey, deploy_key::DeployKey),
+    (DropLink, drop_link::DropLink),
+    (Editing, editing::Editing),
     (Sync, sync::Sync),
+    (Rsync, rsync::Rsync),
+    (Fetch, fetch::Fetch),
+    (Follow, FollowRequest),
+    (Claim, ClaimRequest),
+    (Lease, lease::Lease),
+    (Member, member::Member),
+    (Unfollow, UnfollowRequest),
+    (Publish, PublishRequest),
+    (Pipeline, pipeline::Pipeline),
+    (Republish, republish::Republish),
+    (Retention, retention::Retention),
+    (Scans, ScansRequest),
+    (Storage, storage::Storage),
+    (Unpublish, UnpublishRequest),
+    (Versions, VersionsRequest),
 }
 
 // Rename the generated Command to BucketCommand for clarity
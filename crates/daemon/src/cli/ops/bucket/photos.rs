@@ -0,0 +1,49 @@
+use time::OffsetDateTime;
+
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::photos::{PhotosRequest, PhotosResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum PhotosError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for PhotosRequest {
+    type Error = PhotosError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: PhotosResponse = client.call(self.clone()).await?;
+
+        if response.items.is_empty() {
+            return Ok("No photos found".to_string());
+        }
+
+        let mut lines: Vec<String> = response
+            .items
+            .iter()
+            .map(|photo| {
+                let taken = photo
+                    .captured_at
+                    .and_then(|secs| OffsetDateTime::from_unix_timestamp(secs).ok())
+                    .map(|date| date.date().to_string())
+                    .unwrap_or_else(|| "-".to_string());
+                let size = match (photo.width, photo.height) {
+                    (Some(width), Some(height)) => format!("{}x{}", width, height),
+                    _ => "-".to_string(),
+                };
+                format!(
+                    "{:<10} {:>11}  {} {}",
+                    taken, size, photo.bucket_id, photo.path
+                )
+            })
+            .collect();
+        if let Some(next_offset) = response.next_offset {
+            lines.push(format!("(more: --offset {})", next_offset));
+        }
+        Ok(lines.join("\n"))
+    }
+}
@@ -0,0 +1,69 @@
+use clap::{Args, Subcommand};
+
+pub mod policy;
+pub mod set;
+
+use crate::cli::op::Op;
+use jax_daemon::http_server::api::v0::bucket::pipeline::{
+    PipelineGetRequest, PipelineResetRequest, PipelineResponse,
+};
+
+crate::command_enum! {
+    (Get, PipelineGetRequest),
+    (Set, set::Set),
+    (Reset, PipelineResetRequest),
+}
+
+// Rename the generated Command to PipelineCommand for clarity
+pub type PipelineCommand = Command;
+
+/// Build buckets with a configured builder when they're published, publishing the output
+#[derive(Args, Debug, Clone)]
+pub struct Pipeline {
+    #[command(subcommand)]
+    pub command: PipelineCommand,
+}
+
+#[async_trait::async_trait]
+impl Op for Pipeline {
+    type Error = OpError;
+    type Output = OpOutput;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        self.command.execute(ctx).await
+    }
+}
+
+/// Render a publish pipeline and its latest builds for display
+fn describe(response: &PipelineResponse) -> String {
+    let mut output = match &response.pipeline {
+        Some(pipeline) => {
+            let mut output = format!("{}: built with {}", response.bucket_id, pipeline.builder);
+            if let Some(dir) = &pipeline.output {
+                output.push_str(&format!(" into {}", dir));
+            }
+            for (name, value) in &pipeline.vars {
+                output.push_str(&format!("\n  {}={}", name, value));
+            }
+            output
+        }
+        None => format!("{}: published as it is", response.bucket_id),
+    };
+    if let Some(site_bucket_id) = response.site_bucket_id {
+        output.push_str(&format!("\nSite bucket: {}", site_bucket_id));
+    }
+    for build in &response.builds {
+        let result = match &build.site_version {
+            Some(site_version) => format!("published {}", site_version),
+            None => "failed".to_string(),
+        };
+        output.push_str(&format!(
+            "\n{} {} {}",
+            build.created_at, build.source_version, result
+        ));
+        if let (false, Some(log)) = (build.succeeded, &build.log) {
+            output.push_str(&format!("\n  {}", log.replace('\n', "\n  ")));
+        }
+    }
+    output
+}
@@ -0,0 +1,38 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::pipeline::{
+    PipelineGetRequest, PipelineResetRequest, PipelineResetResponse, PipelineResponse,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum PipelineError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for PipelineGetRequest {
+    type Error = PipelineError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: PipelineResponse = client.call(self.clone()).await?;
+        Ok(super::describe(&response))
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for PipelineResetRequest {
+    type Error = PipelineError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: PipelineResetResponse = client.call(self.clone()).await?;
+        Ok(if response.removed {
+            format!("{}: publish pipeline removed", response.bucket_id)
+        } else {
+            format!("{}: had no publish pipeline", response.bucket_id)
+        })
+    }
+}
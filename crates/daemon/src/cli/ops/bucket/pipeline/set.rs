@@ -0,0 +1,61 @@
+use clap::Args;
+use uuid::Uuid;
+
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::pipeline::{PipelineResponse, PipelineSetRequest};
+use jax_daemon::publish_pipeline::PublishPipeline;
+
+/// Build the bucket with a configured builder when it's published
+#[derive(Args, Debug, Clone)]
+pub struct Set {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Name of the builder, from `[[publish_builders]]` in the config file
+    #[arg(long)]
+    pub builder: String,
+
+    /// Directory of the checkout the builder writes its output to, e.g.
+    /// `public` (defaults to $JAX_OUTPUT)
+    #[arg(long)]
+    pub output: Option<String>,
+
+    /// Template variable set in the builder's environment, as NAME=value
+    /// (repeatable)
+    #[arg(long = "var", value_parser = parse_var)]
+    pub vars: Vec<(String, String)>,
+}
+
+fn parse_var(s: &str) -> Result<(String, String), String> {
+    s.split_once('=')
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .ok_or_else(|| format!("expected NAME=value, got '{}'", s))
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PipelineSetError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for Set {
+    type Error = PipelineSetError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: PipelineResponse = client
+            .call(PipelineSetRequest {
+                bucket_id: self.bucket_id,
+                pipeline: PublishPipeline {
+                    builder: self.builder.clone(),
+                    output: self.output.clone(),
+                    vars: self.vars.iter().cloned().collect(),
+                },
+            })
+            .await?;
+        Ok(super::describe(&response))
+    }
+}
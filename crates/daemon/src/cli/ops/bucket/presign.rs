@@ -0,0 +1,26 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::presign::{PresignRequest, PresignResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum PresignError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for PresignRequest {
+    type Error = PresignError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: PresignResponse = client.call(self.clone()).await?;
+
+        // Without a configured gateway URL, the path goes on the gateway's host
+        let url = response.url.unwrap_or(response.url_path);
+        Ok(format!(
+            "{}\n(version {}, expires {})",
+            url, response.version, response.expires_at
+        ))
+    }
+}
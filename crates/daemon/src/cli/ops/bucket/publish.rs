@@ -0,0 +1,36 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::publish::{PublishRequest, PublishResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum BucketPublishError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for PublishRequest {
+    type Error = BucketPublishError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: PublishResponse = client.call(self.clone()).await?;
+
+        Ok(format_publication(&response))
+    }
+}
+
+pub(crate) fn format_publication(response: &PublishResponse) -> String {
+    let mut output = format!(
+        "Published bucket {} (link: {})\nGateway path: {}",
+        response.bucket_id, response.new_bucket_link, response.gateway_path
+    );
+    if let Some(site_bucket_id) = &response.site_bucket_id {
+        output.push_str(&format!("\nBuilt into site bucket {}", site_bucket_id));
+    }
+    if let Some(version_path) = &response.version_path {
+        output.push_str(&format!("\nThis version: {}", version_path));
+    }
+    output.push_str(&format!("\nFollow ticket: {}", response.ticket));
+    output
+}
@@ -0,0 +1,32 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::reference::{ReferenceRequest, ReferenceResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum BucketReferenceError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for ReferenceRequest {
+    type Error = BucketReferenceError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: ReferenceResponse = client.call(self.clone()).await?;
+
+        let version = if response.floating {
+            format!("following its head (now {})", response.version)
+        } else {
+            format!("pinned at {}", response.version)
+        };
+        Ok(format!(
+            "Bucket {} referenced at {}, {}. New bucket link: {}",
+            self.target,
+            response.path,
+            version,
+            response.link.hash()
+        ))
+    }
+}
@@ -0,0 +1,50 @@
+use clap::Args;
+use uuid::Uuid;
+
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::publish::{PublishRequest, PublishResponse};
+
+use super::publish::format_publication;
+
+/// Publish the current version of an already published bucket again
+#[derive(Args, Debug, Clone)]
+pub struct Republish {
+    /// Bucket ID to republish
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Revoke the current public link and earlier published versions,
+    /// serving the bucket at a new gateway link
+    #[arg(long)]
+    pub rotate: bool,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RepublishError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for Republish {
+    type Error = RepublishError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: PublishResponse = client
+            .call(PublishRequest {
+                bucket_id: self.bucket_id,
+                rotate: self.rotate,
+                expected_version: None,
+                index: Default::default(),
+            })
+            .await?;
+
+        let mut output = format_publication(&response);
+        if self.rotate {
+            output.push_str("\nThe previous public link no longer works");
+        }
+        Ok(output)
+    }
+}
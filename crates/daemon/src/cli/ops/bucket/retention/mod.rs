@@ -0,0 +1,51 @@
+use clap::{Args, Subcommand};
+
+pub mod policy;
+pub mod run;
+
+use crate::cli::op::Op;
+use jax_daemon::http_server::api::v0::bucket::retention::{
+    RetentionGetRequest, RetentionPolicyResponse, RetentionResetRequest, RetentionRunRequest,
+    RetentionSetRequest,
+};
+
+crate::command_enum! {
+    (Get, RetentionGetRequest),
+    (Set, RetentionSetRequest),
+    (Reset, RetentionResetRequest),
+    (Run, RetentionRunRequest),
+}
+
+// Rename the generated Command to RetentionCommand for clarity
+pub type RetentionCommand = Command;
+
+/// Prune old versions of a bucket, keeping hourly, daily, weekly or monthly ones
+#[derive(Args, Debug, Clone)]
+pub struct Retention {
+    #[command(subcommand)]
+    pub command: RetentionCommand,
+}
+
+#[async_trait::async_trait]
+impl Op for Retention {
+    type Error = OpError;
+    type Output = OpOutput;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        self.command.execute(ctx).await
+    }
+}
+
+/// Render a retention policy for display
+fn describe(response: &RetentionPolicyResponse) -> String {
+    match &response.policy {
+        Some(policy) => format!(
+            "{}: keep {} ({} version(s) pruned)",
+            response.bucket_id, policy, response.pruned_versions
+        ),
+        None => format!(
+            "{}: keeps every version ({} version(s) pruned)",
+            response.bucket_id, response.pruned_versions
+        ),
+    }
+}
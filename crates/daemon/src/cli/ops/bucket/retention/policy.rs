@@ -0,0 +1,54 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::retention::{
+    RetentionGetRequest, RetentionPolicyResponse, RetentionResetRequest, RetentionResetResponse,
+    RetentionSetRequest,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum RetentionPolicyError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for RetentionGetRequest {
+    type Error = RetentionPolicyError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: RetentionPolicyResponse = client.call(self.clone()).await?;
+        Ok(super::describe(&response))
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for RetentionSetRequest {
+    type Error = RetentionPolicyError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: RetentionPolicyResponse = client.call(self.clone()).await?;
+        Ok(super::describe(&response))
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for RetentionResetRequest {
+    type Error = RetentionPolicyError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: RetentionResetResponse = client.call(self.clone()).await?;
+        if response.removed {
+            Ok(format!(
+                "{} keeps every new version again",
+                response.bucket_id
+            ))
+        } else {
+            Ok(format!("{} has no retention policy", response.bucket_id))
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::retention::RetentionRunRequest;
+use jax_daemon::retention::PruneReport;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RetentionRunError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for RetentionRunRequest {
+    type Error = RetentionRunError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let report: PruneReport = client.call(self.clone()).await?;
+        let versions = match (
+            report.pruned_versions.first(),
+            report.pruned_versions.last(),
+        ) {
+            (Some(first), Some(last)) => format!(
+                "{} version(s) between heights {} and {}",
+                report.pruned_versions.len(),
+                first,
+                last
+            ),
+            _ => "no versions".to_string(),
+        };
+        let mut out = format!(
+            "{} {} of {}, {} blob(s) ({} bytes)",
+            if self.dry_run {
+                "Would prune"
+            } else {
+                "Pruned"
+            },
+            versions,
+            report.bucket_id,
+            report.deleted_blobs,
+            report.freed_bytes
+        );
+        if report.transitioned_blobs > 0 {
+            out.push_str(&format!(
+                "; {} {} blob(s) between storage classes",
+                if self.dry_run { "would move" } else { "moved" },
+                report.transitioned_blobs
+            ));
+        }
+        if report.restoring_blobs > 0 {
+            out.push_str(&format!(
+                "; {} archived blob(s) being restored before moving back",
+                report.restoring_blobs
+            ));
+        }
+        Ok(out)
+    }
+}
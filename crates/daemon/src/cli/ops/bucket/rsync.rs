@@ -0,0 +1,365 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use glob::{MatchOptions, Pattern};
+use reqwest::multipart;
+use uuid::Uuid;
+
+use jax_daemon::http_server::api::client::{ApiClient, ApiError};
+use jax_daemon::http_server::api::v0::bucket::delete::DeleteRequest;
+use jax_daemon::http_server::api::v0::bucket::ls::{LsRequest, PathInfo};
+use jax_daemon::integrity::hash_file;
+
+#[derive(Args, Debug, Clone)]
+pub struct Rsync {
+    /// Local directory to upload
+    pub local_dir: PathBuf,
+
+    /// Bucket name or ID, optionally followed by a path in it, e.g. `backups/photos`
+    pub target: String,
+
+    /// Delete files and directories in the bucket that are not in the local directory
+    #[arg(long)]
+    pub delete: bool,
+
+    /// Print what would be uploaded and deleted without changing the bucket
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Only sync files matching this pattern (repeatable). Patterns without a
+    /// `/` match file names, others paths relative to the directory
+    #[arg(long)]
+    pub include: Vec<String>,
+
+    /// Skip files and directories matching this pattern (repeatable), in the
+    /// bucket as well as locally
+    #[arg(long)]
+    pub exclude: Vec<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RsyncError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("HTTP error: {0}")]
+    Reqwest(#[from] reqwest::Error),
+    #[error("Invalid pattern {0}: {1}")]
+    Pattern(String, glob::PatternError),
+    #[error("Not a directory: {0}")]
+    NotADirectory(PathBuf),
+    #[error("{0} exists in the bucket and is not a directory")]
+    TargetNotADirectory(String),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for Rsync {
+    type Error = RsyncError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+
+        if !self.local_dir.is_dir() {
+            return Err(RsyncError::NotADirectory(self.local_dir.clone()));
+        }
+        let filter = Filter::new(&self.include, &self.exclude)?;
+
+        let (bucket, base) = split_target(&self.target);
+        let bucket_id = match bucket.parse::<Uuid>() {
+            Ok(id) => id,
+            Err(_) => client.resolve_bucket_name(bucket).await?,
+        };
+
+        let mut local = LocalTree::default();
+        local.walk(&self.local_dir, Path::new(""), &filter)?;
+        let remote = remote_tree(&mut client, bucket_id, &base).await?;
+
+        // Sizes are compared before hashing, so files whose size changed
+        //  are never read
+        let mut uploads: BTreeMap<String, Vec<(String, PathBuf)>> = BTreeMap::new();
+        let mut unchanged = 0;
+        for (relative, source) in &local.files {
+            if let Some(info) = remote.get(relative) {
+                if !info.is_dir
+                    && info.size == Some(fs::metadata(source)?.len())
+                    && info.content_hash == Some(hash_file(source)?)
+                {
+                    unchanged += 1;
+                    continue;
+                }
+            }
+            let (parent, name) = match relative.rsplit_once('/') {
+                Some((parent, name)) => (join(&base, parent), name),
+                None => (base.clone(), relative.as_str()),
+            };
+            uploads
+                .entry(parent)
+                .or_default()
+                .push((name.to_string(), source.clone()));
+        }
+
+        // A deleted directory takes everything under it along, so with
+        //  includes only files are deleted, one by one
+        let mut deletes: Vec<String> = Vec::new();
+        if self.delete {
+            for (relative, info) in &remote {
+                let kept = if info.is_dir {
+                    local.dirs.contains(relative) || !filter.include.is_empty()
+                } else {
+                    local.files.contains_key(relative)
+                };
+                let covered = deletes
+                    .iter()
+                    .any(|dir| relative.starts_with(&format!("{}/", dir)));
+                if kept || covered || !filter.allows(relative, info.is_dir) {
+                    continue;
+                }
+                deletes.push(relative.clone());
+            }
+        }
+
+        let verb = if self.dry_run { "Would " } else { "" };
+        let mut lines = Vec::new();
+        for path in &deletes {
+            let path = join(&base, path);
+            if !self.dry_run {
+                client
+                    .call(DeleteRequest {
+                        bucket_id,
+                        path: path.clone(),
+                        expected_version: None,
+                    })
+                    .await?;
+            }
+            lines.push(format!("{}delete {}", verb, path));
+        }
+
+        let mut uploaded = 0;
+        for (parent, files) in &uploads {
+            for (name, _) in files {
+                lines.push(format!("{}upload {}", verb, join(parent, name)));
+            }
+            uploaded += files.len();
+            if !self.dry_run {
+                upload(&client, bucket_id, parent, files).await?;
+            }
+        }
+
+        lines.push(format!(
+            "{}{} {} file(s), {} {} file(s) or directories, {} unchanged",
+            verb,
+            if self.dry_run { "upload" } else { "Uploaded" },
+            uploaded,
+            if self.dry_run { "delete" } else { "deleted" },
+            deletes.len(),
+            unchanged
+        ));
+        Ok(lines.join("\n"))
+    }
+}
+
+/// Split `<bucket>/<path>` into the bucket and an absolute path in it
+fn split_target(target: &str) -> (&str, String) {
+    match target.split_once('/') {
+        Some((bucket, path)) => (bucket, format!("/{}", path.trim_matches('/'))),
+        None => (target, "/".to_string()),
+    }
+}
+
+/// Join a path relative to `base` onto it
+fn join(base: &str, relative: &str) -> String {
+    format!("{}/{}", base.trim_end_matches('/'), relative)
+}
+
+/// Include and exclude patterns. Patterns without a `/` match the file or
+/// directory name at any depth, others its path relative to the synced
+/// directory
+struct Filter {
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+}
+
+impl Filter {
+    fn new(include: &[String], exclude: &[String]) -> Result<Self, RsyncError> {
+        let compile = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|p| Pattern::new(p).map_err(|e| RsyncError::Pattern(p.clone(), e)))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok(Self {
+            include: compile(include)?,
+            exclude: compile(exclude)?,
+        })
+    }
+
+    /// Whether a path relative to the synced directory takes part in the
+    /// sync. Includes only narrow down files, so directories are walked
+    /// for the files they may hold
+    fn allows(&self, relative: &str, is_dir: bool) -> bool {
+        if self.exclude.iter().any(|p| Self::matches(p, relative)) {
+            return false;
+        }
+        is_dir || self.include.is_empty() || self.include.iter().any(|p| Self::matches(p, relative))
+    }
+
+    fn matches(pattern: &Pattern, relative: &str) -> bool {
+        let options = MatchOptions {
+            require_literal_separator: true,
+            ..MatchOptions::new()
+        };
+        if pattern.as_str().contains('/') {
+            pattern.matches_with(relative, options)
+        } else {
+            let name = relative.rsplit('/').next().unwrap_or(relative);
+            pattern.matches_with(name, options)
+        }
+    }
+}
+
+/// Files and directories of the local directory that pass the filter,
+/// keyed by their `/`-separated path relative to it
+#[derive(Default)]
+struct LocalTree {
+    files: BTreeMap<String, PathBuf>,
+    dirs: BTreeSet<String>,
+}
+
+impl LocalTree {
+    fn walk(&mut self, dir: &Path, relative: &Path, filter: &Filter) -> std::io::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            let path = relative.join(entry.file_name());
+            let key = path.to_string_lossy().replace('\\', "/");
+            if !filter.allows(&key, file_type.is_dir()) {
+                continue;
+            }
+            if file_type.is_dir() {
+                self.dirs.insert(key);
+                self.walk(&entry.path(), &path, filter)?;
+            } else if file_type.is_file() {
+                self.files.insert(key, entry.path());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Everything under `base` in the bucket, keyed by path relative to it.
+/// Empty if `base` doesn't exist yet
+async fn remote_tree(
+    client: &mut ApiClient,
+    bucket_id: Uuid,
+    base: &str,
+) -> Result<BTreeMap<String, PathInfo>, RsyncError> {
+    if base != "/" {
+        let (parent, name) = base.rsplit_once('/').unwrap_or(("", base));
+        let siblings = client
+            .call(LsRequest {
+                bucket_id,
+                path: Some(if parent.is_empty() { "/" } else { parent }.to_string()),
+                deep: None,
+                min_version: None,
+                starred: false,
+                filter: None,
+                sort: None,
+                page: Default::default(),
+            })
+            .await?;
+        match siblings.items.iter().find(|item| item.name == name) {
+            None => return Ok(BTreeMap::new()),
+            Some(item) if !item.is_dir => {
+                return Err(RsyncError::TargetNotADirectory(base.to_string()))
+            }
+            Some(_) => {}
+        }
+    }
+
+    let listing = client
+        .call(LsRequest {
+            bucket_id,
+            path: Some(base.to_string()),
+            deep: Some(true),
+            min_version: None,
+            starred: false,
+            filter: None,
+            sort: None,
+            page: Default::default(),
+        })
+        .await?;
+    Ok(listing
+        .items
+        .into_iter()
+        .map(|item| (item.path.trim_start_matches('/').to_string(), item))
+        .collect())
+}
+
+/// Upload files into one directory of the bucket in a single request
+async fn upload(
+    client: &ApiClient,
+    bucket_id: Uuid,
+    parent: &str,
+    files: &[(String, PathBuf)],
+) -> Result<(), RsyncError> {
+    let mut form = multipart::Form::new()
+        .text("bucket_id", bucket_id.to_string())
+        .text("mount_path", parent.to_string());
+    for (name, source) in files {
+        let part = multipart::Part::file(source).await?.file_name(name.clone());
+        form = form.part("files", part);
+    }
+
+    let url = client.base_url().join("/api/v0/bucket/add").unwrap();
+    let response = client
+        .http_client()
+        .post(url)
+        .multipart(form)
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await?;
+        return Err(ApiError::from_response(status, &body).into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_split_target() {
+        assert_eq!(split_target("backups"), ("backups", "/".to_string()));
+        assert_eq!(split_target("backups/"), ("backups", "/".to_string()));
+        assert_eq!(
+            split_target("backups/photos/2024/"),
+            ("backups", "/photos/2024".to_string())
+        );
+    }
+
+    #[test]
+    fn test_filter() {
+        let filter = Filter::new(
+            &["*.jpg".to_string(), "docs/*.md".to_string()],
+            &["tmp".to_string(), "*.part.jpg".to_string()],
+        )
+        .unwrap();
+
+        assert!(filter.allows("a.jpg", false));
+        assert!(filter.allows("photos/2024/a.jpg", false));
+        assert!(filter.allows("docs/readme.md", false));
+        assert!(!filter.allows("docs/nested/readme.md", false));
+        assert!(!filter.allows("notes.txt", false));
+        assert!(!filter.allows("a.part.jpg", false));
+
+        // Directories are walked unless excluded
+        assert!(filter.allows("photos", true));
+        assert!(!filter.allows("cache/tmp", true));
+    }
+}
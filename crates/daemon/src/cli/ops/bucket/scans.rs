@@ -0,0 +1,38 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::scans::{ScansRequest, ScansResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum BucketScansError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for ScansRequest {
+    type Error = BucketScansError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: ScansResponse = client.call(self.clone()).await?;
+
+        if response.scans.is_empty() {
+            return Ok(format!("No scans of bucket {}", response.bucket_id));
+        }
+        let lines = response
+            .scans
+            .iter()
+            .map(|scan| {
+                let mut line = format!(
+                    "{}  {}  {}  {}",
+                    scan.created_at, scan.version, scan.result.scanner, scan.result.verdict
+                );
+                if let Some(detail) = &scan.result.detail {
+                    line.push_str(&format!(": {}", detail));
+                }
+                line
+            })
+            .collect::<Vec<_>>();
+        Ok(lines.join("\n"))
+    }
+}
@@ -19,9 +19,19 @@ impl crate::cli::op::Op for ShareRequest {
         let mut client = ctx.client.clone();
         let response: ShareResponse = client.call(self.clone()).await?;
 
-        Ok(format!(
-            "Bucket {} shared with peer {}. New bucket link: {}",
-            response.bucket_id, response.peer_public_key, response.new_bucket_link
-        ))
+        match (response.new_bucket_link, &self.path) {
+            (Some(link), Some(path)) => Ok(format!(
+                "Directory {} of bucket {} shared with peer {}. New bucket link: {}",
+                path, response.bucket_id, response.peer_public_key, link
+            )),
+            (Some(link), None) => Ok(format!(
+                "Bucket {} shared with peer {}. New bucket link: {}",
+                response.bucket_id, response.peer_public_key, link
+            )),
+            (None, _) => Ok(format!(
+                "Sharing bucket {} with peer {} waits for approval (jax bucket member approve)",
+                response.bucket_id, response.peer_public_key
+            )),
+        }
     }
 }
@@ -0,0 +1,31 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::share_ephemeral::{
+    ShareEphemeralRequest, ShareEphemeralResponse,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum BucketShareEphemeralError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for ShareEphemeralRequest {
+    type Error = BucketShareEphemeralError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: ShareEphemeralResponse = client.call(self.clone()).await?;
+
+        Ok(format!(
+            "Bucket {} shared with temporary identity {} until {} (Unix time). New bucket link: {}\n\
+             Hand the recipient this ticket, which holds the identity's key (jax bucket claim):\n{}",
+            response.bucket_id,
+            response.identity,
+            response.expires_at,
+            response.new_bucket_link,
+            response.ticket
+        ))
+    }
+}
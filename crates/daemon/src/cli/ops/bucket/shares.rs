@@ -0,0 +1,40 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::shares::{SharesRequest, SharesResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum BucketSharesError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for SharesRequest {
+    type Error = BucketSharesError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: SharesResponse = client.call(self.clone()).await?;
+
+        if response.shares.is_empty() {
+            return Ok(format!("No shares of bucket {}", response.bucket_id));
+        }
+        let mut lines = response
+            .shares
+            .iter()
+            .map(|share| {
+                let this_node = if share.is_self { " (this node)" } else { "" };
+                format!("{}  {}{}", share.public_key, share.role.as_str(), this_node)
+            })
+            .collect::<Vec<_>>();
+        if let Some(cursor) = &response.next_cursor {
+            lines.push(format!(
+                "{} of {} shares, next page: --cursor {}",
+                response.shares.len(),
+                response.total,
+                cursor
+            ));
+        }
+        Ok(lines.join("\n"))
+    }
+}
@@ -0,0 +1,86 @@
+use clap::Args;
+use uuid::Uuid;
+
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::stats::StatsRequest;
+
+use crate::cli::ops::storage::format_bytes;
+
+/// Show file counts, sizes and the type breakdown of a bucket
+#[derive(Args, Debug, Clone)]
+pub struct Stats {
+    /// Bucket ID (or use --name)
+    #[arg(long, group = "bucket_identifier")]
+    pub bucket_id: Option<Uuid>,
+
+    /// Bucket name (or use --bucket-id)
+    #[arg(long, group = "bucket_identifier")]
+    pub name: Option<String>,
+
+    /// Number of largest files to list (default 10, at most 100)
+    #[arg(long)]
+    pub top: Option<usize>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum StatsError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+    #[error("Either --bucket-id or --name must be provided")]
+    NoBucketIdentifier,
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for Stats {
+    type Error = StatsError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+
+        let bucket_id = if let Some(id) = self.bucket_id {
+            id
+        } else if let Some(ref name) = self.name {
+            client.resolve_bucket_name(name).await?
+        } else {
+            return Err(StatsError::NoBucketIdentifier);
+        };
+
+        let response = client
+            .call(StatsRequest {
+                bucket_id,
+                top: self.top,
+            })
+            .await?;
+        let stats = &response.stats;
+
+        let mut lines = vec![
+            format!("{} ({})", response.name, response.bucket_id),
+            format!("  Version:     {}", stats.version),
+            format!("  Files:       {}", stats.files),
+            format!("  Directories: {}", stats.directories),
+            format!("  Logical:     {}", format_bytes(stats.logical_bytes)),
+            format!("  Physical:    {}", format_bytes(stats.physical_bytes)),
+        ];
+        if !stats.mime_types.is_empty() {
+            lines.push(String::new());
+            lines.push("Types:".to_string());
+            for mime in &stats.mime_types {
+                lines.push(format!(
+                    "  {:>10}  {:>6} files  {}",
+                    format_bytes(mime.bytes),
+                    mime.files,
+                    mime.mime_type
+                ));
+            }
+        }
+        if !stats.largest_files.is_empty() {
+            lines.push(String::new());
+            lines.push("Largest files:".to_string());
+            for file in &stats.largest_files {
+                lines.push(format!("  {:>10}  {}", format_bytes(file.size), file.path));
+            }
+        }
+        Ok(lines.join("\n"))
+    }
+}
@@ -0,0 +1,54 @@
+use clap::{Args, Subcommand};
+
+pub mod policy;
+pub mod run;
+
+use crate::cli::op::Op;
+use jax_daemon::http_server::api::v0::bucket::storage::{
+    StorageGetRequest, StorageResetRequest, StorageRunRequest, StorageSetRequest,
+};
+use jax_daemon::storage_policy::PlacementReport;
+
+crate::command_enum! {
+    (Get, StorageGetRequest),
+    (Set, StorageSetRequest),
+    (Reset, StorageResetRequest),
+    (Run, StorageRunRequest),
+}
+
+// Rename the generated Command to StorageCommand for clarity
+pub type StorageCommand = Command;
+
+/// Keep a bucket's blobs in another storage backend, e.g. a local-only directory
+#[derive(Args, Debug, Clone)]
+pub struct Storage {
+    #[command(subcommand)]
+    pub command: StorageCommand,
+}
+
+#[async_trait::async_trait]
+impl Op for Storage {
+    type Error = OpError;
+    type Output = OpOutput;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        self.command.execute(ctx).await
+    }
+}
+
+/// Render a placement for display
+fn describe_placement(report: &PlacementReport) -> String {
+    let mut out = format!(
+        "Placed {} blob(s) of {} in {}",
+        report.placed_blobs,
+        report.bucket_id,
+        report.location.as_deref().unwrap_or("the main store")
+    );
+    if report.shared_blobs > 0 {
+        out.push_str(&format!(
+            ", left {} shared with buckets placed elsewhere",
+            report.shared_blobs
+        ));
+    }
+    out
+}
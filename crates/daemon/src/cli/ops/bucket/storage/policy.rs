@@ -0,0 +1,63 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::storage::{
+    StorageGetRequest, StoragePolicyResponse, StorageResetRequest, StorageResetResponse,
+    StorageSetRequest,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum StoragePolicyError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+/// Render a storage policy for display
+fn describe(response: &StoragePolicyResponse) -> String {
+    let placed = match &response.location {
+        Some(location) => format!("{}: blobs kept in {}", response.bucket_id, location),
+        None => format!("{}: blobs kept in the main store", response.bucket_id),
+    };
+    if response.locations.is_empty() {
+        format!("{} (no blob locations configured)", placed)
+    } else {
+        format!("{} (locations: {})", placed, response.locations.join(", "))
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for StorageGetRequest {
+    type Error = StoragePolicyError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: StoragePolicyResponse = client.call(self.clone()).await?;
+        Ok(describe(&response))
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for StorageSetRequest {
+    type Error = StoragePolicyError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: StoragePolicyResponse = client.call(self.clone()).await?;
+        Ok(describe(&response))
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for StorageResetRequest {
+    type Error = StoragePolicyError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: StorageResetResponse = client.call(self.clone()).await?;
+        match &response.placement {
+            Some(report) => Ok(super::describe_placement(report)),
+            None => Ok(format!("{} has no storage policy", response.bucket_id)),
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::storage::StorageRunRequest;
+use jax_daemon::storage_policy::PlacementReport;
+
+#[derive(Debug, thiserror::Error)]
+pub enum StorageRunError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for StorageRunRequest {
+    type Error = StorageRunError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let report: PlacementReport = client.call(self.clone()).await?;
+        Ok(super::describe_placement(&report))
+    }
+}
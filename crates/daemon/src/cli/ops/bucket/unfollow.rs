@@ -0,0 +1,25 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::unfollow::{UnfollowRequest, UnfollowResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum BucketUnfollowError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for UnfollowRequest {
+    type Error = BucketUnfollowError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: UnfollowResponse = client.call(self.clone()).await?;
+
+        Ok(if response.removed {
+            format!("Stopped following bucket {}", response.bucket_id)
+        } else {
+            format!("Bucket {} was not followed", response.bucket_id)
+        })
+    }
+}
@@ -0,0 +1,24 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::unpublish::{UnpublishRequest, UnpublishResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum BucketUnpublishError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for UnpublishRequest {
+    type Error = BucketUnpublishError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: UnpublishResponse = client.call(self.clone()).await?;
+
+        Ok(format!(
+            "Unpublished bucket {} (link: {})",
+            response.bucket_id, response.new_bucket_link
+        ))
+    }
+}
@@ -0,0 +1,46 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::versions::{VersionsRequest, VersionsResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum BucketVersionsError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for VersionsRequest {
+    type Error = BucketVersionsError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: VersionsResponse = client.call(self.clone()).await?;
+
+        if response.versions.is_empty() {
+            return Ok(format!("No versions of bucket {}", response.bucket_id));
+        }
+        let mut lines = response
+            .versions
+            .iter()
+            .map(|version| {
+                let published = if version.published { "  published" } else { "" };
+                format!(
+                    "v{}  {}  {}{}",
+                    version.height,
+                    version.created_at,
+                    version.link.hash(),
+                    published
+                )
+            })
+            .collect::<Vec<_>>();
+        if let Some(cursor) = &response.next_cursor {
+            lines.push(format!(
+                "{} of {} versions, next page: --cursor {}",
+                response.versions.len(),
+                response.total,
+                cursor
+            ));
+        }
+        Ok(lines.join("\n"))
+    }
+}
@@ -0,0 +1,20 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::config::{ConfigGetRequest, ConfigGetResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigGetError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for ConfigGetRequest {
+    type Error = ConfigGetError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: ConfigGetResponse = client.call(self.clone()).await?;
+        Ok(response.value)
+    }
+}
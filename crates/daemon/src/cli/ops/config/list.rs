@@ -0,0 +1,26 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::config::{ConfigListRequest, ConfigListResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigListError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for ConfigListRequest {
+    type Error = ConfigListError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: ConfigListResponse = client.call(self.clone()).await?;
+
+        Ok(response
+            .entries
+            .iter()
+            .map(|entry| format!("{} = {}", entry.key, entry.value))
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
@@ -0,0 +1,35 @@
+use clap::{Args, Subcommand};
+
+pub mod get;
+pub mod list;
+pub mod set;
+
+use crate::cli::op::Op;
+use jax_daemon::http_server::api::v0::config::{
+    ConfigGetRequest, ConfigListRequest, ConfigSetRequest,
+};
+
+crate::command_enum! {
+    (Get, ConfigGetRequest),
+    (List, ConfigListRequest),
+    (Set, ConfigSetRequest),
+}
+
+// Rename the generated Command to ConfigCommand for clarity
+pub type ConfigCommand = Command;
+
+#[derive(Args, Debug, Clone)]
+pub struct Config {
+    #[command(subcommand)]
+    pub command: ConfigCommand,
+}
+
+#[async_trait::async_trait]
+impl Op for Config {
+    type Error = OpError;
+    type Output = OpOutput;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        self.command.execute(ctx).await
+    }
+}
@@ -0,0 +1,31 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::config::{ConfigSetRequest, ConfigSetResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigSetError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for ConfigSetRequest {
+    type Error = ConfigSetError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: ConfigSetResponse = client.call(self.clone()).await?;
+
+        let mut output = format!("Set {} = {}", response.key, response.value);
+        if !response.applied.is_empty() {
+            output.push_str(&format!("\nApplied: {}", response.applied.join(", ")));
+        }
+        if !response.restart_required.is_empty() {
+            output.push_str(&format!(
+                "\nRestart the daemon to apply: {}",
+                response.restart_required.join(", ")
+            ));
+        }
+        Ok(output)
+    }
+}
@@ -0,0 +1,103 @@
+//! `jax daemon logs`: query and follow the daemon's structured logs
+
+use std::io::Write;
+
+use clap::Args;
+use futures::StreamExt;
+
+use jax_daemon::http_server::api::client::{ApiClient, ApiError};
+use jax_daemon::http_server::api::v0::logs::{LogFollowRequest, LogQueryRequest};
+use jax_daemon::logs::LogEntry;
+
+/// Entries shown before following when no --lines is given
+const FOLLOW_BACKLOG: usize = 10;
+
+#[derive(Args, Debug, Clone)]
+pub struct LogsArgs {
+    /// Keep printing new entries as they are logged
+    #[arg(short, long)]
+    pub follow: bool,
+
+    #[command(flatten)]
+    pub query: LogQueryRequest,
+}
+
+pub async fn logs(client: &mut ApiClient, args: &LogsArgs) -> Result<String, ApiError> {
+    let mut query = args.query.clone();
+    if args.follow {
+        if query.limit.is_none() {
+            query.limit = Some(FOLLOW_BACKLOG);
+        }
+        if query.until.is_some() {
+            return Err(ApiError::Other(
+                "--until cannot be combined with --follow".to_string(),
+            ));
+        }
+    }
+
+    let response = client.call(query.clone()).await?;
+    let mut output = response
+        .entries
+        .iter()
+        .map(format_entry)
+        .collect::<Vec<_>>()
+        .join("\n");
+    if !args.follow {
+        return Ok(output);
+    }
+
+    if !output.is_empty() {
+        println!("{}", output);
+        output.clear();
+    }
+    follow(client, query).await?;
+    Ok("Daemon log stream ended".to_string())
+}
+
+/// Print entries from the server-sent event stream until the daemon closes it
+async fn follow(client: &ApiClient, query: LogQueryRequest) -> Result<(), ApiError> {
+    let request = LogFollowRequest {
+        level: query.level,
+        contains: query.contains,
+    };
+    let response = request
+        .build_request(client.base_url(), client.http_client())
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        let status = response.status();
+        return Err(ApiError::from_response(status, &response.text().await?));
+    }
+
+    let mut stream = response.bytes_stream();
+    let mut buffer = String::new();
+    let mut stdout = std::io::stdout();
+    while let Some(chunk) = stream.next().await {
+        buffer.push_str(&String::from_utf8_lossy(&chunk?));
+        while let Some(idx) = buffer.find('\n') {
+            let line = buffer.drain(..=idx).collect::<String>();
+            let Some(data) = line.trim_end().strip_prefix("data:") else {
+                continue;
+            };
+            if let Ok(entry) = serde_json::from_str::<LogEntry>(data.trim_start()) {
+                let _ = writeln!(stdout, "{}", format_entry(&entry));
+                let _ = stdout.flush();
+            }
+        }
+    }
+    Ok(())
+}
+
+fn format_entry(entry: &LogEntry) -> String {
+    let mut line = format!(
+        "{} {:>5} {}: {}",
+        entry.timestamp, entry.level, entry.target, entry.message
+    );
+    for (key, value) in &entry.fields {
+        match value {
+            serde_json::Value::String(s) => line.push_str(&format!(" {}={}", key, s)),
+            other => line.push_str(&format!(" {}={}", key, other)),
+        }
+    }
+    line
+}
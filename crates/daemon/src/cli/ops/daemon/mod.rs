@@ -0,0 +1,458 @@
+use std::fs::OpenOptions;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+use clap::{Args, Subcommand};
+use url::Url;
+
+use jax_daemon::http_server::api::client::{ApiClient, ApiError};
+use jax_daemon::http_server::api::v0::admin::{ShutdownRequest, StatusRequest, StatusResponse};
+use jax_daemon::state::{AppState, DAEMON_LOG_FILE_NAME, LOGS_DIR_NAME};
+use jax_daemon::{spawn_service, ServiceConfig};
+
+pub mod logs;
+pub mod pidfile;
+pub mod service;
+
+use logs::LogsArgs;
+use pidfile::{PidFile, PidFileError};
+use service::{InstallServiceArgs, ServiceError};
+
+/// How long `start` waits for a detached daemon to answer on its API port
+const START_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long `stop` waits for the daemon process to exit (covers the SIGTERM grace period)
+const STOP_TIMEOUT: Duration = Duration::from_secs(45);
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Run, start, stop or inspect the local daemon.
+///
+/// Without a subcommand the daemon runs in the foreground.
+#[derive(Args, Debug, Clone)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct Daemon {
+    #[command(subcommand)]
+    pub command: Option<DaemonCommand>,
+
+    #[command(flatten)]
+    pub run: RunArgs,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum DaemonCommand {
+    /// Start the daemon (detached unless --foreground)
+    Start(StartArgs),
+    /// Stop the running daemon
+    Stop,
+    /// Stop the running daemon and start it again detached
+    Restart(RunArgs),
+    /// Show whether the daemon is running and what it is doing
+    Status(StatusRequest),
+    /// Show or follow the daemon's logs
+    Logs(LogsArgs),
+    /// Install a systemd user unit (Linux) or launchd agent (macOS) for the daemon
+    InstallService(InstallServiceArgs),
+    /// Stop and remove the installed system service
+    UninstallService,
+}
+
+#[derive(Args, Debug, Clone, Default)]
+pub struct RunArgs {
+    /// Override API server port (default from config)
+    #[arg(long)]
+    pub api_port: Option<u16>,
+
+    /// Override gateway server port (default from config)
+    #[arg(long)]
+    pub gateway_port: Option<u16>,
+
+    /// Gateway URL for share/download links (e.g., https://gateway.example.com)
+    #[arg(long)]
+    pub gateway_url: Option<String>,
+
+    /// Directory for log files (default: logs/ in the jax directory)
+    #[arg(long)]
+    pub log_dir: Option<PathBuf>,
+
+    /// OTLP/HTTP collector to export traces to, e.g. http://localhost:4318 (default from config)
+    #[arg(long)]
+    pub otlp_endpoint: Option<String>,
+}
+
+impl RunArgs {
+    /// Render the overrides back into command line flags
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(port) = self.api_port {
+            args.extend(["--api-port".to_string(), port.to_string()]);
+        }
+        if let Some(port) = self.gateway_port {
+            args.extend(["--gateway-port".to_string(), port.to_string()]);
+        }
+        if let Some(url) = &self.gateway_url {
+            args.extend(["--gateway-url".to_string(), url.clone()]);
+        }
+        if let Some(log_dir) = &self.log_dir {
+            args.extend([
+                "--log-dir".to_string(),
+                log_dir.to_string_lossy().to_string(),
+            ]);
+        }
+        if let Some(endpoint) = &self.otlp_endpoint {
+            args.extend(["--otlp-endpoint".to_string(), endpoint.clone()]);
+        }
+        args
+    }
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct StartArgs {
+    /// Run in the foreground instead of detaching
+    #[arg(long)]
+    pub foreground: bool,
+
+    #[command(flatten)]
+    pub run: RunArgs,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DaemonError {
+    #[error("state error: {0}")]
+    StateError(#[from] jax_daemon::state::StateError),
+
+    #[error("{0}")]
+    PidFile(#[from] PidFileError),
+
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+
+    #[error("{0}")]
+    Service(#[from] ServiceError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("daemon already running with pid {0}")]
+    AlreadyRunning(u32),
+
+    #[error("daemon is not running")]
+    NotRunning,
+
+    #[error("daemon failed: {0}")]
+    Failed(String),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for Daemon {
+    type Error = DaemonError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        match &self.command {
+            None => run_foreground(ctx, &self.run).await,
+            Some(DaemonCommand::Start(args)) if args.foreground => {
+                run_foreground(ctx, &args.run).await
+            }
+            Some(DaemonCommand::Start(args)) => start_detached(ctx, &args.run).await,
+            Some(DaemonCommand::Stop) => stop(ctx).await,
+            Some(DaemonCommand::Restart(run)) => {
+                let mut output = match stop(ctx).await {
+                    Ok(stopped) => stopped,
+                    Err(DaemonError::NotRunning) => "Daemon was not running".to_string(),
+                    Err(e) => return Err(e),
+                };
+                output.push('\n');
+                output.push_str(&start_detached(ctx, run).await?);
+                Ok(output)
+            }
+            Some(DaemonCommand::Status(req)) => status(ctx, req).await,
+            Some(DaemonCommand::Logs(args)) => {
+                let state = AppState::load(ctx.config_path.clone())?;
+                let mut client = local_client(&state, None)?;
+                Ok(logs::logs(&mut client, args).await?)
+            }
+            Some(DaemonCommand::InstallService(args)) => Ok(service::install(ctx, args)?),
+            Some(DaemonCommand::UninstallService) => Ok(service::uninstall()?),
+        }
+    }
+}
+
+/// Run the daemon in this process until it is shut down
+async fn run_foreground(
+    ctx: &crate::cli::op::OpContext,
+    run: &RunArgs,
+) -> Result<String, DaemonError> {
+    // Load state from config path (or default ~/.jax)
+    let state = AppState::load(ctx.config_path.clone())?;
+
+    // Claim the pidfile for the lifetime of the service
+    let _pidfile = PidFile::acquire(&state.pid_path)?;
+
+    // Load the secret key
+    let secret_key = state.load_key()?;
+
+    // Build node listen address from peer_port if configured
+    let node_listen_addr = state.config.peer_port.map(|port| {
+        format!("0.0.0.0:{}", port)
+            .parse()
+            .expect("Failed to parse peer listen address")
+    });
+
+    let log_level = state.config.log_level()?;
+
+    // Use ports from flags or config
+    let api_port = run.api_port.unwrap_or(state.config.api_port);
+    let gateway_port = run.gateway_port.unwrap_or(state.config.gateway_port);
+
+    // Blob store configuration is read from config.toml (set at init time)
+    let config = ServiceConfig {
+        node_listen_addr,
+        node_secret: Some(secret_key.clone()),
+        blob_store: state.config.blob_store.clone(),
+        blob_locations: state.config.blob_locations.clone(),
+        cdn_targets: state.config.cdn_targets.clone(),
+        jax_dir: state.jax_dir.clone(),
+        api_port,
+        gateway_port,
+        sqlite_path: Some(state.db_path),
+        database_key: state.config.database_key(&secret_key),
+        config_path: Some(state.config_path.clone()),
+        sync_interval: state.config.sync_interval(),
+        periodic_sync: true,
+        online_cache_max_bytes: state.config.online_cache_max_bytes,
+        sync_deferral: state.config.sync_deferral,
+        protocol_timeouts: state.config.protocol_timeouts.timeouts(),
+        connection_limits: state.config.protocol_limits.limits(),
+        log_level,
+        log_dir: Some(
+            run.log_dir
+                .clone()
+                .unwrap_or_else(|| state.jax_dir.join(LOGS_DIR_NAME)),
+        ),
+        otlp_endpoint: run
+            .otlp_endpoint
+            .clone()
+            .or_else(|| state.config.otlp_endpoint.clone()),
+        gateway_url: run.gateway_url.clone(),
+        gateway_tls: state.config.gateway_tls.clone(),
+        reverse_proxy: state.config.reverse_proxy.clone(),
+        publish_scanners: state.config.publish_scanners.clone(),
+        publish_builders: state.config.publish_builders.clone(),
+        upload_limits: state.config.upload_limits.clone(),
+        import_dirs: state.config.import_dirs.clone(),
+        read_replica: state.config.read_replica,
+        cluster: state.config.cluster.clone(),
+    };
+
+    spawn_service(&config).await;
+    Ok("daemon ended".to_string())
+}
+
+/// Re-launch this binary as a detached foreground daemon and wait until it is up
+async fn start_detached(
+    ctx: &crate::cli::op::OpContext,
+    run: &RunArgs,
+) -> Result<String, DaemonError> {
+    let state = AppState::load(ctx.config_path.clone())?;
+    if let Some(pid) = pidfile::running_pid(&state.pid_path)? {
+        return Err(DaemonError::AlreadyRunning(pid));
+    }
+
+    let log_path = state.jax_dir.join(DAEMON_LOG_FILE_NAME);
+    let log = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)?;
+
+    let mut command = std::process::Command::new(std::env::current_exe()?);
+    if let Some(config_path) = &ctx.config_path {
+        command.arg("--config-path").arg(config_path);
+    }
+    command.args(["daemon", "start", "--foreground"]);
+    command.args(run.to_args());
+
+    command
+        .stdin(Stdio::null())
+        .stdout(log.try_clone()?)
+        .stderr(log);
+    // Own process group so the daemon outlives the terminal that started it
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    // Likewise detached from the console that started it
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const DETACHED_PROCESS: u32 = 0x0000_0008;
+        const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+        command.creation_flags(DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP);
+    }
+    let mut child = command.spawn()?;
+
+    let mut client = local_client(&state, run.api_port)?;
+    let deadline = Instant::now() + START_TIMEOUT;
+    loop {
+        if let Some(exit) = child.try_wait()? {
+            return Err(DaemonError::Failed(format!(
+                "daemon exited during startup ({}), see {}",
+                exit,
+                log_path.display()
+            )));
+        }
+        if client.call(StatusRequest::default()).await.is_ok() {
+            break;
+        }
+        if Instant::now() >= deadline {
+            return Err(DaemonError::Failed(format!(
+                "daemon (pid {}) did not become ready within {}s, see {}",
+                child.id(),
+                START_TIMEOUT.as_secs(),
+                log_path.display()
+            )));
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+
+    Ok(format!(
+        "Daemon started (pid {}), logging to {}",
+        child.id(),
+        log_path.display()
+    ))
+}
+
+/// Ask the daemon to shut down over the API, falling back to SIGTERM via the pidfile
+async fn stop(ctx: &crate::cli::op::OpContext) -> Result<String, DaemonError> {
+    let state = AppState::load(ctx.config_path.clone())?;
+    let mut client = local_client(&state, None)?;
+
+    let pid = match client.call(ShutdownRequest::default()).await {
+        Ok(response) => response.pid,
+        Err(_) => {
+            // API unreachable: fall back to signalling the pid we recorded
+            let pid = pidfile::running_pid(&state.pid_path)?.ok_or(DaemonError::NotRunning)?;
+            if !pidfile::terminate(pid) {
+                return Err(DaemonError::Failed(format!(
+                    "could not signal daemon process {}",
+                    pid
+                )));
+            }
+            pid
+        }
+    };
+
+    let deadline = Instant::now() + STOP_TIMEOUT;
+    while pidfile::is_alive(pid) {
+        if Instant::now() >= deadline {
+            return Err(DaemonError::Failed(format!(
+                "daemon (pid {}) did not exit within {}s",
+                pid,
+                STOP_TIMEOUT.as_secs()
+            )));
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+
+    // Clear the pidfile in case the daemon could not remove it itself
+    pidfile::running_pid(&state.pid_path)?;
+
+    Ok(format!("Daemon stopped (pid {})", pid))
+}
+
+async fn status(
+    ctx: &crate::cli::op::OpContext,
+    req: &StatusRequest,
+) -> Result<String, DaemonError> {
+    let state = AppState::load(ctx.config_path.clone())?;
+    let mut client = local_client(&state, None)?;
+
+    let response: StatusResponse = match client.call(req.clone()).await {
+        Ok(response) => response,
+        Err(_) => {
+            return match pidfile::running_pid(&state.pid_path)? {
+                Some(pid) => Ok(format!(
+                    "Daemon process {} is running but its API on port {} is not responding",
+                    pid, state.config.api_port
+                )),
+                None => Ok("Daemon is not running".to_string()),
+            };
+        }
+    };
+
+    let mut output = format!(
+        "Daemon is running (pid {}, version {})\n",
+        response.pid, response.version
+    );
+    output.push_str(&format!(
+        "  Uptime:        {}\n",
+        format_uptime(response.uptime_secs)
+    ));
+    output.push_str(&format!("  API port:      {}\n", response.api_port));
+    output.push_str(&format!("  Gateway port:  {}\n", response.gateway_port));
+    output.push_str(&format!("  Peer ID:       {}\n", response.peer_id));
+    output.push_str(&format!("  Buckets:       {}\n", response.bucket_count));
+    output.push_str(&format!(
+        "  Pending syncs: {}\n",
+        response.pending_sync_jobs
+    ));
+    if let Some(mounts) = response.running_mounts {
+        output.push_str(&format!("  FUSE mounts:   {}\n", mounts));
+    }
+    let protocol = &response.protocol;
+    output.push_str(&format!(
+        "  Requests:      {} in flight, {} handled, {} busy, {} over peer limit\n",
+        protocol.in_flight,
+        protocol.accepted_total,
+        protocol.rejected_busy_total,
+        protocol.rejected_peer_total
+    ));
+
+    if req.verbose {
+        output.push_str(&format!(
+            "  Listening on:  {}\n",
+            response.listen_addrs.join(", ")
+        ));
+        let peers = response.peers.unwrap_or_default();
+        let connected = peers.iter().filter(|p| p.connection != "none").count();
+        output.push_str(&format!(
+            "  Peers:         {} known, {} connected\n",
+            peers.len(),
+            connected
+        ));
+        for peer in peers {
+            let latency = peer
+                .latency_ms
+                .map(|ms| format!(" ({}ms)", ms))
+                .unwrap_or_default();
+            output.push_str(&format!(
+                "    {} {}{}\n",
+                peer.peer_id, peer.connection, latency
+            ));
+        }
+    }
+
+    Ok(output.trim_end().to_string())
+}
+
+/// API client for the daemon configured in the local jax directory
+fn local_client(state: &AppState, api_port: Option<u16>) -> Result<ApiClient, ApiError> {
+    let port = api_port.unwrap_or(state.config.api_port);
+    let url = Url::parse(&format!("http://localhost:{}", port)).expect("valid localhost URL");
+    ApiClient::new(&url)
+}
+
+fn format_uptime(secs: u64) -> String {
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+    let (hours, rem) = (rem / 3_600, rem % 3_600);
+    let (minutes, seconds) = (rem / 60, rem % 60);
+    if days > 0 {
+        format!("{}d {}h {}m", days, hours, minutes)
+    } else if hours > 0 {
+        format!("{}h {}m {}s", hours, minutes, seconds)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
@@ -0,0 +1,199 @@
+//! Pidfile management for the local daemon
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+#[cfg(not(unix))]
+use std::process::{Command, Stdio};
+
+/// A pidfile claimed by the running daemon, removed on drop
+pub struct PidFile {
+    path: PathBuf,
+}
+
+impl PidFile {
+    /// Claim the pidfile for this process.
+    ///
+    /// Fails if another live daemon already holds it; a pidfile left behind
+    /// by a process that no longer exists is replaced. The file is created
+    /// exclusively, so of two daemons starting at once only one claims it.
+    pub fn acquire(path: &Path) -> Result<Self, PidFileError> {
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(path) {
+                Ok(mut file) => {
+                    file.write_all(format!("{}\n", std::process::id()).as_bytes())?;
+                    return Ok(Self {
+                        path: path.to_path_buf(),
+                    });
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+                Err(e) => return Err(e.into()),
+            }
+
+            match read(path) {
+                Ok(Some(pid)) if is_alive(pid) => return Err(PidFileError::AlreadyRunning(pid)),
+                Ok(Some(pid)) => tracing::warn!("Removing stale pidfile for pid {}", pid),
+                Err(PidFileError::Invalid(_)) => {
+                    tracing::warn!("Removing invalid pidfile {}", path.display())
+                }
+                // Removed since we tried to create it
+                Ok(None) => continue,
+                Err(e) => return Err(e),
+            }
+            match fs::remove_file(path) {
+                Ok(()) => {}
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        // Only remove the file if it still belongs to us
+        if matches!(read(&self.path), Ok(Some(pid)) if pid == std::process::id()) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Read the pid recorded in a pidfile, if there is one
+pub fn read(path: &Path) -> Result<Option<u32>, PidFileError> {
+    match fs::read_to_string(path) {
+        // pid 0 would address our own process group when signalled
+        Ok(contents) => match contents.trim().parse::<u32>() {
+            Ok(pid) if pid > 0 => Ok(Some(pid)),
+            _ => Err(PidFileError::Invalid(path.to_path_buf())),
+        },
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Read the pidfile and return the pid only if that process is still alive,
+/// cleaning up the file otherwise.
+pub fn running_pid(path: &Path) -> Result<Option<u32>, PidFileError> {
+    match read(path) {
+        Ok(Some(pid)) if is_alive(pid) => Ok(Some(pid)),
+        Ok(Some(_)) | Err(PidFileError::Invalid(_)) => {
+            let _ = fs::remove_file(path);
+            Ok(None)
+        }
+        Ok(None) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Check whether a process with the given pid exists
+#[cfg(unix)]
+pub fn is_alive(pid: u32) -> bool {
+    // A process we may not signal exists all the same
+    signal(pid, 0) || io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// Send SIGTERM to a process, returning whether the signal was delivered
+#[cfg(unix)]
+pub fn terminate(pid: u32) -> bool {
+    signal(pid, libc::SIGTERM)
+}
+
+#[cfg(unix)]
+fn signal(pid: u32, signal: libc::c_int) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: kill takes no pointers; pid is positive, so it addresses a
+    //  single process and never a process group
+    unsafe { libc::kill(pid, signal) == 0 }
+}
+
+/// Check whether a process with the given pid exists
+#[cfg(not(unix))]
+pub fn is_alive(pid: u32) -> bool {
+    Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {}", pid), "/NH"])
+        .stderr(Stdio::null())
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).contains(&pid.to_string()))
+        .unwrap_or(false)
+}
+
+/// Stop a process, returning whether it was told to
+#[cfg(not(unix))]
+pub fn terminate(pid: u32) -> bool {
+    Command::new("taskkill")
+        .args(["/PID", &pid.to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PidFileError {
+    #[error("daemon already running with pid {0}")]
+    AlreadyRunning(u32),
+
+    #[error("invalid pidfile: {0}")]
+    Invalid(PathBuf),
+
+    #[error("pidfile IO error: {0}")]
+    Io(#[from] io::Error),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_acquire_and_release() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("daemon.pid");
+
+        let pidfile = PidFile::acquire(&path).unwrap();
+        assert_eq!(read(&path).unwrap(), Some(std::process::id()));
+        assert_eq!(running_pid(&path).unwrap(), Some(std::process::id()));
+
+        drop(pidfile);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_stale_pidfile_is_replaced() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("daemon.pid");
+
+        // Above the kernel's maximum pid_max, so it cannot belong to a live process
+        fs::write(&path, "4194304\n").unwrap();
+        assert_eq!(running_pid(&path).unwrap(), None);
+        assert!(!path.exists());
+
+        fs::write(&path, "4194304\n").unwrap();
+        let _pidfile = PidFile::acquire(&path).unwrap();
+        assert_eq!(read(&path).unwrap(), Some(std::process::id()));
+    }
+
+    #[test]
+    fn test_acquire_held_pidfile_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("daemon.pid");
+
+        let _pidfile = PidFile::acquire(&path).unwrap();
+        assert!(matches!(
+            PidFile::acquire(&path),
+            Err(PidFileError::AlreadyRunning(pid)) if pid == std::process::id()
+        ));
+    }
+
+    #[test]
+    fn test_invalid_pidfile() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("daemon.pid");
+
+        fs::write(&path, "not a pid").unwrap();
+        assert!(matches!(read(&path), Err(PidFileError::Invalid(_))));
+        assert_eq!(running_pid(&path).unwrap(), None);
+    }
+}
@@ -0,0 +1,321 @@
+//! System service installation for the daemon
+//!
+//! Generates a systemd user unit on Linux or a launchd agent on macOS that runs
+//! `jax daemon start --foreground` against the current jax directory, so the
+//! daemon comes back after a reboot without the desktop app.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use clap::Args;
+
+use jax_daemon::state::{AppState, DAEMON_LOG_FILE_NAME};
+
+use super::RunArgs;
+
+const SYSTEMD_UNIT_NAME: &str = "jax.service";
+const LAUNCHD_LABEL: &str = "org.jax-protocol.jax";
+
+#[derive(Args, Debug, Clone)]
+pub struct InstallServiceArgs {
+    /// Write the service file without enabling or starting it
+    #[arg(long)]
+    pub no_start: bool,
+
+    /// Print the generated service file instead of installing it
+    #[arg(long)]
+    pub print: bool,
+
+    #[command(flatten)]
+    pub run: RunArgs,
+}
+
+/// Supported service managers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceManager {
+    Systemd,
+    Launchd,
+}
+
+impl ServiceManager {
+    /// The service manager for the platform we are running on
+    pub fn current() -> Result<Self, ServiceError> {
+        if cfg!(target_os = "macos") {
+            Ok(Self::Launchd)
+        } else if cfg!(target_os = "linux") {
+            Ok(Self::Systemd)
+        } else {
+            Err(ServiceError::Unsupported)
+        }
+    }
+
+    /// Where the service file is installed for the current user
+    pub fn service_path(&self) -> Result<PathBuf, ServiceError> {
+        let home = dirs::home_dir().ok_or(ServiceError::NoHomeDirectory)?;
+        Ok(match self {
+            Self::Systemd => home.join(".config/systemd/user").join(SYSTEMD_UNIT_NAME),
+            Self::Launchd => home
+                .join("Library/LaunchAgents")
+                .join(format!("{}.plist", LAUNCHD_LABEL)),
+        })
+    }
+
+    /// Render the service file for a daemon command line
+    pub fn render(&self, program: &[String], log_path: &Path) -> String {
+        match self {
+            Self::Systemd => render_systemd_unit(program),
+            Self::Launchd => render_launchd_plist(program, log_path),
+        }
+    }
+}
+
+pub fn install(
+    ctx: &crate::cli::op::OpContext,
+    args: &InstallServiceArgs,
+) -> Result<String, ServiceError> {
+    let manager = ServiceManager::current()?;
+    let state = AppState::load(ctx.config_path.clone())?;
+
+    // Pin the service to absolute paths; it will not run from this shell
+    let exe = std::env::current_exe()?.canonicalize()?;
+    let jax_dir = state.jax_dir.canonicalize()?;
+    let mut program = vec![
+        exe.to_string_lossy().to_string(),
+        "--config-path".to_string(),
+        jax_dir.to_string_lossy().to_string(),
+        "daemon".to_string(),
+        "start".to_string(),
+        "--foreground".to_string(),
+    ];
+    program.extend(args.run.to_args());
+
+    let contents = manager.render(&program, &jax_dir.join(DAEMON_LOG_FILE_NAME));
+    if args.print {
+        return Ok(contents);
+    }
+
+    let path = manager.service_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, contents)?;
+
+    let mut output = format!("Installed service at {}", path.display());
+    match manager {
+        ServiceManager::Systemd => {
+            run("systemctl", &["--user", "daemon-reload"])?;
+            if !args.no_start {
+                run(
+                    "systemctl",
+                    &["--user", "enable", "--now", SYSTEMD_UNIT_NAME],
+                )?;
+                output.push_str("\nEnabled and started jax.service");
+            }
+            output.push_str("\nTo keep it running while logged out: loginctl enable-linger $USER");
+        }
+        ServiceManager::Launchd => {
+            if !args.no_start {
+                run("launchctl", &["load", "-w", &path.to_string_lossy()])?;
+                output.push_str(&format!("\nLoaded launchd agent {}", LAUNCHD_LABEL));
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+pub fn uninstall() -> Result<String, ServiceError> {
+    let manager = ServiceManager::current()?;
+    let path = manager.service_path()?;
+    if !path.exists() {
+        return Err(ServiceError::NotInstalled(path));
+    }
+
+    // Stopping may fail if the service is already stopped; removal still proceeds
+    match manager {
+        ServiceManager::Systemd => {
+            let _ = run(
+                "systemctl",
+                &["--user", "disable", "--now", SYSTEMD_UNIT_NAME],
+            );
+            fs::remove_file(&path)?;
+            run("systemctl", &["--user", "daemon-reload"])?;
+        }
+        ServiceManager::Launchd => {
+            let _ = run("launchctl", &["unload", "-w", &path.to_string_lossy()]);
+            fs::remove_file(&path)?;
+        }
+    }
+
+    Ok(format!("Removed service at {}", path.display()))
+}
+
+fn run(program: &str, args: &[&str]) -> Result<(), ServiceError> {
+    let status = Command::new(program).args(args).status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(ServiceError::CommandFailed(format!(
+            "{} {} ({})",
+            program,
+            args.join(" "),
+            status
+        )))
+    }
+}
+
+fn render_systemd_unit(program: &[String]) -> String {
+    let exec_start = program
+        .iter()
+        .map(|arg| systemd_quote(arg))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    format!(
+        "[Unit]
+Description=Jax P2P Storage Daemon
+After=network-online.target
+Wants=network-online.target
+
+[Service]
+Type=simple
+ExecStart={}
+ExecReload=/bin/kill -HUP $MAINPID
+Restart=on-failure
+RestartSec=5s
+
+[Install]
+WantedBy=default.target
+",
+        exec_start
+    )
+}
+
+fn render_launchd_plist(program: &[String], log_path: &Path) -> String {
+    let arguments = program
+        .iter()
+        .map(|arg| format!("        <string>{}</string>\n", xml_escape(arg)))
+        .collect::<String>();
+    let log_path = xml_escape(&log_path.to_string_lossy());
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{label}</string>
+    <key>ProgramArguments</key>
+    <array>
+{arguments}    </array>
+    <key>RunAtLoad</key>
+    <true/>
+    <key>KeepAlive</key>
+    <dict>
+        <key>SuccessfulExit</key>
+        <false/>
+    </dict>
+    <key>StandardOutPath</key>
+    <string>{log_path}</string>
+    <key>StandardErrorPath</key>
+    <string>{log_path}</string>
+</dict>
+</plist>
+"#,
+        label = LAUNCHD_LABEL,
+        arguments = arguments,
+        log_path = log_path,
+    )
+}
+
+/// Quote an argument for a systemd `ExecStart=` line
+fn systemd_quote(arg: &str) -> String {
+    if !arg.is_empty()
+        && !arg
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '"' | '\'' | '\\' | '$' | '%' | ';'))
+    {
+        return arg.to_string();
+    }
+    let escaped = arg
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('$', "$$")
+        .replace('%', "%%");
+    format!("\"{}\"", escaped)
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ServiceError {
+    #[error("service installation is only supported on Linux (systemd) and macOS (launchd)")]
+    Unsupported,
+
+    #[error("no home directory found")]
+    NoHomeDirectory,
+
+    #[error("no service installed at {0}")]
+    NotInstalled(PathBuf),
+
+    #[error("command failed: {0}")]
+    CommandFailed(String),
+
+    #[error("state error: {0}")]
+    State(#[from] jax_daemon::state::StateError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn program() -> Vec<String> {
+        [
+            "/usr/local/bin/jax",
+            "--config-path",
+            "/home/me/my jax",
+            "daemon",
+            "start",
+            "--foreground",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+    }
+
+    #[test]
+    fn test_systemd_unit() {
+        let unit = ServiceManager::Systemd.render(&program(), Path::new("/unused"));
+        assert!(unit.contains(
+            "ExecStart=/usr/local/bin/jax --config-path \"/home/me/my jax\" daemon start --foreground\n"
+        ));
+        assert!(unit.contains("ExecReload=/bin/kill -HUP $MAINPID"));
+        assert!(unit.contains("WantedBy=default.target"));
+    }
+
+    #[test]
+    fn test_systemd_quote() {
+        assert_eq!(systemd_quote("plain"), "plain");
+        assert_eq!(systemd_quote(""), "\"\"");
+        assert_eq!(systemd_quote("100%"), "\"100%%\"");
+        assert_eq!(systemd_quote("a\"b"), "\"a\\\"b\"");
+    }
+
+    #[test]
+    fn test_launchd_plist() {
+        let plist = ServiceManager::Launchd.render(&program(), Path::new("/tmp/a&b/daemon.log"));
+        assert!(plist.contains("<string>org.jax-protocol.jax</string>"));
+        assert!(plist.contains("        <string>/home/me/my jax</string>\n"));
+        assert!(plist.contains("<string>/tmp/a&amp;b/daemon.log</string>"));
+        assert!(plist.contains("<key>RunAtLoad</key>"));
+    }
+}
@@ -0,0 +1,23 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::escrow::{EscrowApproveRequest, EscrowApproveResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum EscrowApproveError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for EscrowApproveRequest {
+    type Error = EscrowApproveError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: EscrowApproveResponse = client.call(self.clone()).await?;
+        Ok(format!(
+            "Releasing the shard of bucket {} to {}",
+            response.bucket_id, response.requester
+        ))
+    }
+}
@@ -0,0 +1,43 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::escrow::{EscrowHeldRequest, EscrowHeldResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum EscrowHeldError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for EscrowHeldRequest {
+    type Error = EscrowHeldError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: EscrowHeldResponse = client.call(self.clone()).await?;
+
+        if response.shards.is_empty() {
+            return Ok("No recovery shards held".to_string());
+        }
+        let mut lines = Vec::new();
+        for shard in &response.shards {
+            lines.push(format!(
+                "{} (owner: {}, {} trustees needed)",
+                shard.bucket_id, shard.owner, shard.threshold
+            ));
+            for request in response
+                .requests
+                .iter()
+                .filter(|request| request.bucket_id == shard.bucket_id)
+            {
+                let status = if request.approved {
+                    "approved"
+                } else {
+                    "waiting for approval"
+                };
+                lines.push(format!("  requested by {} ({})", request.requester, status));
+            }
+        }
+        Ok(lines.join("\n"))
+    }
+}
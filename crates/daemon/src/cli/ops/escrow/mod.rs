@@ -0,0 +1,38 @@
+use clap::{Args, Subcommand};
+
+pub mod approve;
+pub mod held;
+pub mod recover;
+pub mod setup;
+
+use crate::cli::op::Op;
+use jax_daemon::http_server::api::v0::escrow::{
+    EscrowApproveRequest, EscrowHeldRequest, EscrowRecoverRequest, EscrowSetupRequest,
+};
+
+crate::command_enum! {
+    (Setup, EscrowSetupRequest),
+    (Held, EscrowHeldRequest),
+    (Approve, EscrowApproveRequest),
+    (Recover, EscrowRecoverRequest),
+}
+
+// Rename the generated Command to EscrowCommand for clarity
+pub type EscrowCommand = Command;
+
+/// Recover bucket access through trusted peers
+#[derive(Args, Debug, Clone)]
+pub struct Escrow {
+    #[command(subcommand)]
+    pub command: EscrowCommand,
+}
+
+#[async_trait::async_trait]
+impl Op for Escrow {
+    type Error = OpError;
+    type Output = OpOutput;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        self.command.execute(ctx).await
+    }
+}
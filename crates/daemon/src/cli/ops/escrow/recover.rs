@@ -0,0 +1,53 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::escrow::{
+    EscrowRecoverRequest, EscrowRecoverResponse, RecoveryState,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum EscrowRecoverError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for EscrowRecoverRequest {
+    type Error = EscrowRecoverError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: EscrowRecoverResponse = client.call(self.clone()).await?;
+        Ok(match response.state {
+            RecoveryState::Waiting => {
+                let needed = response
+                    .threshold
+                    .map(|threshold| threshold.to_string())
+                    .unwrap_or_else(|| "?".to_string());
+                let mut lines = vec![format!(
+                    "Waiting for trustees: {} of {} shards released",
+                    response.released.len(),
+                    needed
+                )];
+                lines.extend(
+                    response
+                        .pending
+                        .iter()
+                        .map(|trustee| format!("  {} waits for its user's approval", trustee)),
+                );
+                lines.push("Run this again once they approved".to_string());
+                lines.join("\n")
+            }
+            RecoveryState::Syncing => format!(
+                "Bucket {} is syncing; run this again to finish recovering it",
+                response.bucket_id
+            ),
+            RecoveryState::Recovered => match response.new_bucket_link {
+                Some(link) => format!(
+                    "Recovered bucket {}, this node is now an owner\nNew link: {}",
+                    response.bucket_id, link
+                ),
+                None => format!("Bucket {} is already recovered", response.bucket_id),
+            },
+        })
+    }
+}
@@ -0,0 +1,27 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::escrow::{EscrowSetupRequest, EscrowSetupResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum EscrowSetupError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for EscrowSetupRequest {
+    type Error = EscrowSetupError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: EscrowSetupResponse = client.call(self.clone()).await?;
+        Ok(format!(
+            "Any {} of {} trustees can now recover bucket {}\nRecovery identity: {}\nNew link: {}",
+            response.threshold,
+            response.trustees.len(),
+            response.bucket_id,
+            response.recovery_key,
+            response.new_bucket_link
+        ))
+    }
+}
@@ -0,0 +1,98 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::Args;
+use jax_daemon::data_export::{ExportProgress, ExportState};
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::export::{ExportStartRequest, ExportStatusRequest};
+
+use crate::cli::ops::storage::format_bytes;
+
+/// How often the export's progress is polled
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Export every bucket this node can access, with shares and history, into
+/// a new directory. The daemon writes the export, so the directory is on
+/// its machine.
+#[derive(Args, Debug, Clone)]
+pub struct ExportAll {
+    /// New or empty directory to export into
+    #[arg(long, short)]
+    pub output: PathBuf,
+
+    /// Export each bucket's blobs as stored, still encrypted, instead of
+    /// decrypted files
+    #[arg(long)]
+    pub encrypted: bool,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ExportAllError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Export failed: {0}")]
+    Failed(String),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for ExportAll {
+    type Error = ExportAllError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+
+        let mut progress = client
+            .call(ExportStartRequest {
+                output_dir: std::path::absolute(&self.output)?,
+                encrypted: self.encrypted,
+            })
+            .await?;
+        let mut reported = None;
+        while progress.state == ExportState::Running {
+            let step = (progress.buckets_done, progress.current_bucket.clone());
+            if let (Some(bucket), false) = (&step.1, reported.as_ref() == Some(&step)) {
+                eprintln!(
+                    "[{}/{}] Exporting {} ({} files, {} written)",
+                    progress.buckets_done + 1,
+                    progress.buckets_total,
+                    bucket,
+                    progress.files_written,
+                    format_bytes(progress.bytes_written)
+                );
+                reported = Some(step);
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+            progress = client
+                .call(ExportStatusRequest {
+                    export_id: progress.export_id,
+                })
+                .await?;
+        }
+
+        if progress.state == ExportState::Failed {
+            return Err(ExportAllError::Failed(progress.error.unwrap_or_default()));
+        }
+        Ok(summary(&progress))
+    }
+}
+
+fn summary(progress: &ExportProgress) -> String {
+    let mut lines = vec![format!(
+        "Exported {} of {} bucket(s) to {}: {} files, {}",
+        progress.buckets_total - progress.skipped.len(),
+        progress.buckets_total,
+        progress.output_dir.display(),
+        progress.files_written,
+        format_bytes(progress.bytes_written)
+    )];
+    for skipped in &progress.skipped {
+        lines.push(format!(
+            "  skipped {} ({}): {}",
+            skipped.name, skipped.bucket_id, skipped.reason
+        ));
+    }
+    lines.join("\n")
+}
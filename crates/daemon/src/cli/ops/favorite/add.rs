@@ -0,0 +1,27 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::favorite::add::{FavoriteAddRequest, FavoriteAddResponse};
+
+use super::describe;
+
+#[derive(Debug, thiserror::Error)]
+pub enum FavoriteAddError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for FavoriteAddRequest {
+    type Error = FavoriteAddError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: FavoriteAddResponse = client.call(self.clone()).await?;
+        let target = describe(&response.bucket_id, response.path.as_deref());
+        if response.added {
+            Ok(format!("Starred {}", target))
+        } else {
+            Ok(format!("Already starred {}", target))
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::favorite::{FavoriteListRequest, FavoriteListResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum FavoriteListError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for FavoriteListRequest {
+    type Error = FavoriteListError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: FavoriteListResponse = client.call(self.clone()).await?;
+
+        if response.favorites.is_empty() {
+            return Ok("No favorites found".to_string());
+        }
+        Ok(response
+            .favorites
+            .iter()
+            .map(|f| {
+                let bucket = f
+                    .bucket_name
+                    .clone()
+                    .unwrap_or_else(|| f.bucket_id.to_string());
+                match &f.path {
+                    Some(path) => format!("{}:{} (id: {})", bucket, path, f.bucket_id),
+                    None => format!("{} (id: {})", bucket, f.bucket_id),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
@@ -0,0 +1,43 @@
+use clap::{Args, Subcommand};
+
+pub mod add;
+pub mod list;
+pub mod remove;
+
+use crate::cli::op::Op;
+use jax_daemon::http_server::api::v0::favorite::{
+    FavoriteAddRequest, FavoriteListRequest, FavoriteRemoveRequest,
+};
+
+crate::command_enum! {
+    (Add, FavoriteAddRequest),
+    (List, FavoriteListRequest),
+    (Remove, FavoriteRemoveRequest),
+}
+
+// Rename the generated Command to FavoriteCommand for clarity
+pub type FavoriteCommand = Command;
+
+/// Star buckets, files and directories
+#[derive(Args, Debug, Clone)]
+pub struct Favorite {
+    #[command(subcommand)]
+    pub command: FavoriteCommand,
+}
+
+#[async_trait::async_trait]
+impl Op for Favorite {
+    type Error = OpError;
+    type Output = OpOutput;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        self.command.execute(ctx).await
+    }
+}
+
+fn describe(bucket_id: &uuid::Uuid, path: Option<&str>) -> String {
+    match path {
+        Some(path) => format!("{} in bucket {}", path, bucket_id),
+        None => format!("bucket {}", bucket_id),
+    }
+}
@@ -0,0 +1,27 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::favorite::remove::{
+    FavoriteRemoveRequest, FavoriteRemoveResponse,
+};
+
+use super::describe;
+
+#[derive(Debug, thiserror::Error)]
+pub enum FavoriteRemoveError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for FavoriteRemoveRequest {
+    type Error = FavoriteRemoveError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: FavoriteRemoveResponse = client.call(self.clone()).await?;
+        Ok(format!(
+            "Unstarred {}",
+            describe(&response.bucket_id, response.path.as_deref())
+        ))
+    }
+}
@@ -0,0 +1,33 @@
+use clap::{Args, Subcommand};
+
+pub mod mode;
+pub mod policy;
+
+use crate::cli::op::Op;
+use jax_daemon::http_server::api::v0::gateway::GatewayModeRequest;
+use policy::Policy;
+
+crate::command_enum! {
+    (Mode, GatewayModeRequest),
+    (Policy, Policy),
+}
+
+// Rename the generated Command to GatewayCommand for clarity
+pub type GatewayCommand = Command;
+
+/// Control which buckets the gateway serves and how
+#[derive(Args, Debug, Clone)]
+pub struct Gateway {
+    #[command(subcommand)]
+    pub command: GatewayCommand,
+}
+
+#[async_trait::async_trait]
+impl Op for Gateway {
+    type Error = OpError;
+    type Output = OpOutput;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        self.command.execute(ctx).await
+    }
+}
@@ -0,0 +1,27 @@
+use jax_daemon::gateway_policy::GatewayMode;
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::gateway::{GatewayModeRequest, GatewayModeResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum GatewayModeError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for GatewayModeRequest {
+    type Error = GatewayModeError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: GatewayModeResponse = client.call(self.clone()).await?;
+
+        let description = match response.mode {
+            GatewayMode::Allowlist => "serves allowlisted buckets only",
+            GatewayMode::Published => "serves every published bucket",
+            GatewayMode::None => "serves no buckets",
+        };
+        Ok(format!("Gateway mode: {} ({})", response.mode, description))
+    }
+}
@@ -0,0 +1,22 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::gateway::policy::{
+    GatewayPolicyGetRequest, GatewayPolicyResponse,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum GatewayPolicyGetError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for GatewayPolicyGetRequest {
+    type Error = GatewayPolicyGetError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: GatewayPolicyResponse = client.call(self.clone()).await?;
+        Ok(super::describe(&response))
+    }
+}
@@ -0,0 +1,28 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::gateway::policy::{
+    GatewayPolicyListRequest, GatewayPolicyListResponse,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum GatewayPolicyListError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for GatewayPolicyListRequest {
+    type Error = GatewayPolicyListError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: GatewayPolicyListResponse = client.call(self.clone()).await?;
+
+        let mut lines = vec![format!("Gateway mode: {}", response.mode)];
+        if response.policies.is_empty() {
+            lines.push("No bucket gateway policies set; all buckets use the defaults".to_string());
+        }
+        lines.extend(response.policies.iter().map(super::describe));
+        Ok(lines.join("\n"))
+    }
+}
@@ -0,0 +1,76 @@
+use clap::{Args, Subcommand};
+
+pub mod get;
+pub mod list;
+pub mod reset;
+pub mod set;
+
+use crate::cli::op::Op;
+use jax_daemon::http_server::api::v0::gateway::policy::{
+    GatewayPolicyGetRequest, GatewayPolicyListRequest, GatewayPolicyResetRequest,
+    GatewayPolicyResponse, GatewayPolicySetRequest,
+};
+
+crate::command_enum! {
+    (Get, GatewayPolicyGetRequest),
+    (List, GatewayPolicyListRequest),
+    (Reset, GatewayPolicyResetRequest),
+    (Set, GatewayPolicySetRequest),
+}
+
+// Rename the generated Command to PolicyCommand for clarity
+pub type PolicyCommand = Command;
+
+/// Show or change how the gateway serves individual buckets
+#[derive(Args, Debug, Clone)]
+pub struct Policy {
+    #[command(subcommand)]
+    pub command: PolicyCommand,
+}
+
+#[async_trait::async_trait]
+impl Op for Policy {
+    type Error = OpError;
+    type Output = OpOutput;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        self.command.execute(ctx).await
+    }
+}
+
+/// Render a bucket's gateway policy for display
+fn describe(response: &GatewayPolicyResponse) -> String {
+    let policy = &response.policy;
+    let mut description = format!(
+        "{}: {}",
+        response.bucket_id,
+        if response.served {
+            "served"
+        } else {
+            "not served"
+        }
+    );
+    if policy.allowed {
+        description.push_str(", allowlisted");
+    }
+    if !policy.listings {
+        description.push_str(", no directory listings");
+    }
+    if policy.noindex {
+        description.push_str(", noindex");
+    }
+    if policy.serve_stale {
+        description.push_str(", serves stale versions while syncing");
+    }
+    match policy.daily_bandwidth_bytes {
+        Some(cap) => description.push_str(&format!(
+            ", {} of {} bytes served today",
+            response.bytes_served_today, cap
+        )),
+        None => description.push_str(&format!(
+            ", {} bytes served today",
+            response.bytes_served_today
+        )),
+    }
+    description
+}
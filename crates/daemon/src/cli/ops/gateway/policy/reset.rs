@@ -0,0 +1,30 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::gateway::policy::{
+    GatewayPolicyResetRequest, GatewayPolicyResetResponse,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum GatewayPolicyResetError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for GatewayPolicyResetRequest {
+    type Error = GatewayPolicyResetError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: GatewayPolicyResetResponse = client.call(self.clone()).await?;
+
+        Ok(if response.removed {
+            format!(
+                "Bucket {} is now served with the default gateway policy",
+                response.bucket_id
+            )
+        } else {
+            format!("Bucket {} had no gateway policy", response.bucket_id)
+        })
+    }
+}
@@ -0,0 +1,72 @@
+use std::path::PathBuf;
+
+use base64::Engine;
+use clap::Args;
+
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::identity::{IdentityExportRequest, IdentityExportResponse};
+
+#[derive(Args, Debug, Clone)]
+pub struct IdentityExport {
+    /// File to write the encrypted bundle to
+    #[arg(short, long)]
+    pub output: PathBuf,
+
+    /// Overwrite the output file if it exists
+    #[arg(long)]
+    pub force: bool,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum IdentityExportError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("{0} already exists (use --force to overwrite)")]
+    OutputExists(PathBuf),
+    #[error("Invalid bundle from daemon: {0}")]
+    Decode(#[from] base64::DecodeError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for IdentityExport {
+    type Error = IdentityExportError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        if self.output.exists() && !self.force {
+            return Err(IdentityExportError::OutputExists(self.output.clone()));
+        }
+
+        let passphrase = super::read_passphrase(true)?;
+        let mut client = ctx.client.clone();
+        let response: IdentityExportResponse =
+            client.call(IdentityExportRequest { passphrase }).await?;
+        let bundle = base64::engine::general_purpose::STANDARD.decode(&response.bundle)?;
+
+        write_private(&self.output, &bundle)?;
+
+        Ok(format!(
+            "Exported identity {} with {} bucket(s) to {}\n\
+             Keep this file and its passphrase safe: they grant full access to your buckets.",
+            response.node_id,
+            response.buckets,
+            self.output.display()
+        ))
+    }
+}
+
+/// Write a file readable only by the current user
+fn write_private(path: &std::path::Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(contents)
+}
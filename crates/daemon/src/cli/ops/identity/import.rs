@@ -0,0 +1,74 @@
+use std::path::PathBuf;
+
+use clap::Args;
+
+use jax_daemon::identity::{IdentityBundle, IdentityError};
+
+#[derive(Args, Debug, Clone)]
+pub struct IdentityImport {
+    /// Bundle written by `jax identity export`
+    pub bundle: PathBuf,
+
+    /// Override the bundled API port
+    #[arg(long)]
+    pub api_port: Option<u16>,
+
+    /// Override the bundled gateway port
+    #[arg(long)]
+    pub gateway_port: Option<u16>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum IdentityImportError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("{0}")]
+    Identity(#[from] IdentityError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for IdentityImport {
+    type Error = IdentityImportError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let data = std::fs::read(&self.bundle)?;
+        let passphrase = super::read_passphrase(false)?;
+
+        let mut bundle = IdentityBundle::decrypt(&data, &passphrase)?;
+        if let Some(port) = self.api_port {
+            bundle.config.api_port = port;
+        }
+        if let Some(port) = self.gateway_port {
+            bundle.config.gateway_port = port;
+        }
+        let node_id = bundle.node_id()?.to_hex();
+        // Buckets nobody else holds a copy of cannot be recovered from peers
+        let shared = bundle
+            .buckets
+            .iter()
+            .filter(|bucket| !bucket.peer_ids.is_empty())
+            .count();
+        let unshared = bundle.buckets.len() - shared;
+
+        let state = bundle.import(ctx.config_path.clone())?;
+
+        let mut output = format!(
+            "Imported identity {} into {}\n\
+             {} bucket(s) will sync from their peers when the daemon starts.",
+            node_id,
+            state.jax_dir.display(),
+            shared
+        );
+        if unshared > 0 {
+            output.push_str(&format!(
+                "\n{} bucket(s) are not shared with any peer and must be copied from the old machine.",
+                unshared
+            ));
+        }
+        output.push_str(
+            "\nStop the daemon on the old machine: two nodes must not share an identity.",
+        );
+        Ok(output)
+    }
+}
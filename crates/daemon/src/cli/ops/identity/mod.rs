@@ -0,0 +1,52 @@
+use clap::{Args, Subcommand};
+
+pub mod export;
+pub mod import;
+
+use crate::cli::op::Op;
+
+pub use export::IdentityExport;
+pub use import::IdentityImport;
+
+crate::command_enum! {
+    (Export, IdentityExport),
+    (Import, IdentityImport),
+}
+
+// Rename the generated Command to IdentityCommand for clarity
+pub type IdentityCommand = Command;
+
+#[derive(Args, Debug, Clone)]
+pub struct Identity {
+    #[command(subcommand)]
+    pub command: IdentityCommand,
+}
+
+#[async_trait::async_trait]
+impl Op for Identity {
+    type Error = OpError;
+    type Output = OpOutput;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        self.command.execute(ctx).await
+    }
+}
+
+/// Environment variable holding the bundle passphrase, for scripted use
+const PASSPHRASE_ENV: &str = "JAX_IDENTITY_PASSPHRASE";
+
+/// Read the bundle passphrase from the environment or the terminal
+fn read_passphrase(confirm: bool) -> std::io::Result<String> {
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV) {
+        return Ok(passphrase);
+    }
+
+    let passphrase = rpassword::prompt_password("Bundle passphrase: ")?;
+    if confirm && rpassword::prompt_password("Repeat passphrase: ")? != passphrase {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "passphrases do not match",
+        ));
+    }
+    Ok(passphrase)
+}
@@ -2,8 +2,14 @@ use std::path::PathBuf;
 
 use clap::{Args, ValueEnum};
 
+use common::crypto::{DerivationPath, Mnemonic, MnemonicError, SecretKey};
 use jax_daemon::state::{AppConfig, AppState, BlobStoreConfig};
 
+/// Environment variable holding the mnemonic, for scripted use
+const MNEMONIC_ENV: &str = "JAX_MNEMONIC";
+/// Environment variable holding the optional BIP-39 passphrase
+const MNEMONIC_PASSPHRASE_ENV: &str = "JAX_MNEMONIC_PASSPHRASE";
+
 /// Blob store backend type for CLI selection
 #[derive(Debug, Clone, Copy, Default, ValueEnum)]
 pub enum BlobStoreType {
@@ -44,6 +50,25 @@ pub struct Init {
     /// Must be an absolute path
     #[arg(long)]
     pub blobs_path: Option<PathBuf>,
+
+    /// Derive the identity from a BIP-39 mnemonic, read from JAX_MNEMONIC or
+    /// the terminal, instead of generating a key
+    #[arg(long, conflicts_with = "new_mnemonic")]
+    pub from_mnemonic: bool,
+
+    /// Generate a new mnemonic, print it once and derive the identity from it
+    #[arg(long)]
+    pub new_mnemonic: bool,
+
+    /// Device to derive the identity of, so each device gets its own key
+    /// from the same mnemonic (path m/44'/7384'/<device>')
+    #[arg(long, conflicts_with = "derivation_path")]
+    pub device: Option<u32>,
+
+    /// Full derivation path of the identity, hardened indices only
+    /// (e.g. m/44'/7384'/0')
+    #[arg(long)]
+    pub derivation_path: Option<DerivationPath>,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -56,6 +81,12 @@ pub enum InitError {
 
     #[error("invalid path: {0}")]
     InvalidPath(String),
+
+    #[error("{0}")]
+    Mnemonic(#[from] MnemonicError),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
 }
 
 impl Init {
@@ -97,6 +128,36 @@ impl Init {
             }
         }
     }
+
+    /// The mnemonic to derive the identity from, if any, and whether it was
+    /// generated here
+    fn mnemonic(&self) -> Result<Option<(Mnemonic, bool)>, InitError> {
+        if self.new_mnemonic {
+            return Ok(Some((Mnemonic::generate()?, true)));
+        }
+        if !self.from_mnemonic {
+            if self.device.is_some() || self.derivation_path.is_some() {
+                return Err(InitError::MissingConfig(
+                    "--device and --derivation-path need --from-mnemonic or --new-mnemonic"
+                        .to_string(),
+                ));
+            }
+            return Ok(None);
+        }
+        let phrase = match std::env::var(MNEMONIC_ENV) {
+            Ok(phrase) => phrase,
+            Err(_) => rpassword::prompt_password("Mnemonic phrase: ")?,
+        };
+        Ok(Some((Mnemonic::parse(&phrase)?, false)))
+    }
+
+    fn derivation_path(&self) -> Result<DerivationPath, InitError> {
+        match (&self.derivation_path, self.device) {
+            (Some(path), _) => Ok(path.clone()),
+            (None, Some(device)) => Ok(DerivationPath::device(device)?),
+            (None, None) => Ok(DerivationPath::default()),
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -116,9 +177,21 @@ impl crate::cli::op::Op for Init {
             gateway_port: self.gateway_port,
             peer_port: self.peer_port,
             blob_store: blob_store.clone(),
+            ..AppConfig::default()
         };
 
-        let state = AppState::init(ctx.config_path.clone(), Some(config))?;
+        let mnemonic = self.mnemonic()?;
+        let path = self.derivation_path()?;
+        let key = match &mnemonic {
+            Some((mnemonic, _)) => {
+                let passphrase = std::env::var(MNEMONIC_PASSPHRASE_ENV).unwrap_or_default();
+                mnemonic.derive_key(&passphrase, &path)
+            }
+            None => SecretKey::generate(),
+        };
+        let node_id = key.public().to_hex();
+
+        let state = AppState::init_with_key(ctx.config_path.clone(), Some(config), key)?;
 
         let peer_port_str = match state.config.peer_port {
             Some(port) => format!("{}", port),
@@ -134,8 +207,9 @@ impl crate::cli::op::Op for Init {
             }
         };
 
-        let output = format!(
+        let mut output = format!(
             "Initialized jax directory at: {}\n\
+             - Identity: {}\n\
              - Database: {}\n\
              - Key: {}\n\
              - Blobs: {}\n\
@@ -145,6 +219,7 @@ impl crate::cli::op::Op for Init {
              - Peer port: {}\n\
              - Blob store: {}",
             state.jax_dir.display(),
+            node_id,
             state.db_path.display(),
             state.key_path.display(),
             state.blobs_path.display(),
@@ -155,6 +230,21 @@ impl crate::cli::op::Op for Init {
             blob_store_str
         );
 
+        match mnemonic {
+            Some((mnemonic, true)) => output.push_str(&format!(
+                "\n\nIdentity derived at {} from this mnemonic:\n\n    {}\n\n\
+                 Write it down and keep it safe: it recovers this identity with\n\
+                 `jax init --from-mnemonic`, and it is not shown again.",
+                path,
+                mnemonic.phrase()
+            )),
+            Some((_, false)) => output.push_str(&format!(
+                "\n\nIdentity derived at {} from the mnemonic.",
+                path
+            )),
+            None => {}
+        }
+
         Ok(output)
     }
 }
@@ -1,11 +1,43 @@
+pub mod activity;
+pub mod audit;
+pub mod backup;
 pub mod bucket;
+pub mod config;
 pub mod daemon;
+pub mod escrow;
+pub mod export_all;
+pub mod favorite;
+pub mod gateway;
+pub mod identity;
 pub mod init;
 pub mod mount;
+pub mod peer;
+pub mod profile;
+pub mod storage;
+pub mod sync;
+pub mod verify_manifest;
 pub mod version;
+pub mod webhook;
+pub mod workspace;
 
+pub use activity::Activity;
+pub use audit::Audit;
+pub use backup::Backup;
 pub use bucket::Bucket;
+pub use config::Config;
 pub use daemon::Daemon;
+pub use escrow::Escrow;
+pub use export_all::ExportAll;
+pub use favorite::Favorite;
+pub use gateway::Gateway;
+pub use identity::Identity;
 pub use init::Init;
 pub use mount::Mount;
+pub use peer::Peer;
+pub use profile::Profile;
+pub use storage::Storage;
+pub use sync::Sync;
+pub use verify_manifest::VerifyManifest;
 pub use version::Version;
+pub use webhook::Webhook;
+pub use workspace::Workspace;
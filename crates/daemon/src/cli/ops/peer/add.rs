@@ -0,0 +1,24 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::peer::{PeerAddRequest, PeerAddResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum PeerAddError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for PeerAddRequest {
+    type Error = PeerAddError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: PeerAddResponse = client.call(self.clone()).await?;
+
+        Ok(format!(
+            "Added peer {} to the address book; it is dialed now and on every start",
+            response.node_id
+        ))
+    }
+}
@@ -0,0 +1,36 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::peer::{PeerListRequest, PeerListResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum PeerListError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for PeerListRequest {
+    type Error = PeerListError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: PeerListResponse = client.call(self.clone()).await?;
+
+        let mut lines = vec![format!("This node: {}", response.own_ticket)];
+        if response.peers.is_empty() {
+            lines.push("No known peers".to_string());
+        }
+        for peer in response.peers {
+            let last_seen = peer
+                .last_seen
+                .map(|seen| format!("last seen {}", seen))
+                .unwrap_or_else(|| "never seen".to_string());
+            let protocol = peer
+                .protocol_version
+                .map(|version| format!(", protocol v{}", version))
+                .unwrap_or_default();
+            lines.push(format!("{} ({}{})", peer.ticket, last_seen, protocol));
+        }
+        Ok(lines.join("\n"))
+    }
+}
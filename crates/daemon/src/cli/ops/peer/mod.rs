@@ -0,0 +1,34 @@
+use clap::{Args, Subcommand};
+
+pub mod add;
+pub mod list;
+pub mod remove;
+
+use crate::cli::op::Op;
+use jax_daemon::http_server::api::v0::peer::{PeerAddRequest, PeerListRequest, PeerRemoveRequest};
+
+crate::command_enum! {
+    (Add, PeerAddRequest),
+    (List, PeerListRequest),
+    (Remove, PeerRemoveRequest),
+}
+
+// Rename the generated Command to PeerCommand for clarity
+pub type PeerCommand = Command;
+
+/// Manage the address book of peers dialed on startup
+#[derive(Args, Debug, Clone)]
+pub struct Peer {
+    #[command(subcommand)]
+    pub command: PeerCommand,
+}
+
+#[async_trait::async_trait]
+impl Op for Peer {
+    type Error = OpError;
+    type Output = OpOutput;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        self.command.execute(ctx).await
+    }
+}
@@ -0,0 +1,25 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::peer::{PeerRemoveRequest, PeerRemoveResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum PeerRemoveError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for PeerRemoveRequest {
+    type Error = PeerRemoveError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: PeerRemoveResponse = client.call(self.clone()).await?;
+
+        Ok(if response.removed {
+            format!("Removed peer {} from the address book", response.node_id)
+        } else {
+            format!("Peer {} was not in the address book", response.node_id)
+        })
+    }
+}
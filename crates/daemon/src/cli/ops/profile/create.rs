@@ -0,0 +1,33 @@
+use clap::Args;
+
+use jax_daemon::profiles;
+use jax_daemon::state::AppState;
+
+use super::ProfileCliError;
+
+/// Create a profile with its own identity and buckets
+#[derive(Args, Debug, Clone)]
+pub struct ProfileCreate {
+    /// Name of the profile (letters, digits, '-' and '_')
+    pub name: String,
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for ProfileCreate {
+    type Error = ProfileCliError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        // Profiles live in an initialized jax directory
+        let base = AppState::load(ctx.config_path.clone())?;
+        let (state, token) = profiles::create(&base.jax_dir, &self.name)?;
+        let node_id = state.load_key()?.public().to_hex();
+
+        Ok(format!(
+            "Created profile {} with node ID {}\n\
+             API token: {}\n\
+             Restart the daemon to serve it, then use `jax --profile {}` or the token as a bearer token.",
+            self.name, node_id, token, self.name
+        ))
+    }
+}
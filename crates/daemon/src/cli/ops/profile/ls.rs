@@ -0,0 +1,40 @@
+use clap::Args;
+
+use jax_daemon::profiles;
+use jax_daemon::state::AppState;
+
+use super::ProfileCliError;
+
+/// List the profiles of the jax directory
+#[derive(Args, Debug, Clone)]
+pub struct ProfileLs {}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for ProfileLs {
+    type Error = ProfileCliError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let base = AppState::load(ctx.config_path.clone())?;
+
+        let mut rows = vec![(None, base.load_key()?.public().to_hex())];
+        for name in profiles::list(&base.jax_dir)? {
+            let state = AppState::load(Some(profiles::profile_dir(&base.jax_dir, &name)))?;
+            rows.push((Some(name), state.load_key()?.public().to_hex()));
+        }
+
+        let lines: Vec<String> = rows
+            .into_iter()
+            .map(|(name, node_id)| {
+                let selected = if name == ctx.profile { "*" } else { " " };
+                format!(
+                    "{} {:<20} {}",
+                    selected,
+                    name.as_deref().unwrap_or("(default)"),
+                    node_id
+                )
+            })
+            .collect();
+        Ok(lines.join("\n"))
+    }
+}
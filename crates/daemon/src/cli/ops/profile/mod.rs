@@ -0,0 +1,41 @@
+use clap::{Args, Subcommand};
+
+pub mod create;
+pub mod ls;
+
+use crate::cli::op::Op;
+
+pub use create::ProfileCreate;
+pub use ls::ProfileLs;
+
+crate::command_enum! {
+    (Create, ProfileCreate),
+    (Ls, ProfileLs),
+}
+
+// Rename the generated Command to ProfileCommand for clarity
+pub type ProfileCommand = Command;
+
+#[derive(Args, Debug, Clone)]
+pub struct Profile {
+    #[command(subcommand)]
+    pub command: ProfileCommand,
+}
+
+#[async_trait::async_trait]
+impl Op for Profile {
+    type Error = OpError;
+    type Output = OpOutput;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        self.command.execute(ctx).await
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ProfileCliError {
+    #[error("{0}")]
+    Profile(#[from] jax_daemon::profiles::ProfileError),
+    #[error("state error: {0}")]
+    State(#[from] jax_daemon::state::StateError),
+}
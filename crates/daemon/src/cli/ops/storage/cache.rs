@@ -0,0 +1,34 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::storage::{CacheRequest, CacheResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum CacheError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for CacheRequest {
+    type Error = CacheError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: CacheResponse = client.call(self.clone()).await?;
+
+        let mut output = format!(
+            "Online-only cache: {} blobs, {} of {}",
+            response.blobs,
+            super::format_bytes(response.bytes),
+            super::format_bytes(response.max_bytes),
+        );
+        if self.evict {
+            output.push_str(&format!(
+                "\nEvicted {} blobs ({})",
+                response.evicted_blobs,
+                super::format_bytes(response.evicted_bytes),
+            ));
+        }
+        Ok(output)
+    }
+}
@@ -0,0 +1,74 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::storage::{DedupReportRequest, DedupReportResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum DedupReportError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for DedupReportRequest {
+    type Error = DedupReportError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: DedupReportResponse = client.call(self.clone()).await?;
+
+        let mut output = String::new();
+        output.push_str(&format!(
+            "Total: {} files, {} logical, {} physical, ratio {:.2}x\n",
+            response.total.file_count,
+            super::format_bytes(response.total.logical_bytes),
+            super::format_bytes(response.total.physical_bytes),
+            response.total.dedup_ratio,
+        ));
+
+        if !response.buckets.is_empty() {
+            output.push('\n');
+            output.push_str(&format!(
+                "{:<36} {:<20} {:>8} {:>12} {:>12} {:>7}\n",
+                "BUCKET ID", "NAME", "FILES", "LOGICAL", "PHYSICAL", "RATIO"
+            ));
+            output.push_str(&"-".repeat(100));
+            output.push('\n');
+            for bucket in &response.buckets {
+                output.push_str(&format!(
+                    "{:<36} {:<20} {:>8} {:>12} {:>12} {:>6.2}x\n",
+                    bucket.bucket_id,
+                    bucket.name,
+                    bucket.stats.file_count,
+                    super::format_bytes(bucket.stats.logical_bytes),
+                    super::format_bytes(bucket.stats.physical_bytes),
+                    bucket.stats.dedup_ratio,
+                ));
+            }
+        }
+
+        if !response.top_duplicates.is_empty() {
+            output.push_str("\nTop duplicate files:\n");
+            for dup in &response.top_duplicates {
+                output.push_str(&format!(
+                    "{} ({} x{}, saves {})\n",
+                    dup.hash,
+                    super::format_bytes(dup.size),
+                    dup.ref_count,
+                    super::format_bytes(dup.saved_bytes),
+                ));
+                for path in &dup.paths {
+                    output.push_str(&format!("  {} {}\n", path.bucket_id, path.path));
+                }
+            }
+        }
+
+        if !response.skipped_buckets.is_empty() {
+            output.push_str(&format!(
+                "\nSkipped {} unreadable bucket(s)\n",
+                response.skipped_buckets.len()
+            ));
+        }
+
+        Ok(output.trim_end().to_string())
+    }
+}
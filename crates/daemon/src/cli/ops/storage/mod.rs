@@ -0,0 +1,47 @@
+use clap::{Args, Subcommand};
+
+pub mod cache;
+pub mod dedup_report;
+
+use crate::cli::op::Op;
+use jax_daemon::http_server::api::v0::storage::{CacheRequest, DedupReportRequest};
+
+crate::command_enum! {
+    (Cache, CacheRequest),
+    (DedupReport, DedupReportRequest),
+}
+
+// Rename the generated Command to StorageCommand for clarity
+pub type StorageCommand = Command;
+
+#[derive(Args, Debug, Clone)]
+pub struct Storage {
+    #[command(subcommand)]
+    pub command: StorageCommand,
+}
+
+#[async_trait::async_trait]
+impl Op for Storage {
+    type Error = OpError;
+    type Output = OpOutput;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        self.command.execute(ctx).await
+    }
+}
+
+/// Render a byte count with a binary unit
+pub(crate) fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
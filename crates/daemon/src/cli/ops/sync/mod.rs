@@ -0,0 +1,92 @@
+use clap::{Args, Subcommand};
+
+pub mod conditions;
+pub mod now;
+pub mod path_status;
+pub mod pause;
+pub mod policy;
+pub mod resume;
+pub mod status;
+
+use crate::cli::op::Op;
+use jax_daemon::http_server::api::v0::sync::{
+    PathStatusRequest, SyncConditionsRequest, SyncNowRequest, SyncPauseRequest, SyncResumeRequest,
+    SyncStatusRequest, SyncStatusResponse,
+};
+use policy::Policy;
+
+crate::command_enum! {
+    (Conditions, SyncConditionsRequest),
+    (Now, SyncNowRequest),
+    (PathStatus, PathStatusRequest),
+    (Pause, SyncPauseRequest),
+    (Policy, Policy),
+    (Resume, SyncResumeRequest),
+    (Status, SyncStatusRequest),
+}
+
+// Rename the generated Command to SyncCommand for clarity
+pub type SyncCommand = Command;
+
+#[derive(Args, Debug, Clone)]
+pub struct Sync {
+    #[command(subcommand)]
+    pub command: SyncCommand,
+}
+
+#[async_trait::async_trait]
+impl Op for Sync {
+    type Error = OpError;
+    type Output = OpOutput;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        self.command.execute(ctx).await
+    }
+}
+
+/// Render the sync status for display
+fn describe(status: &SyncStatusResponse) -> String {
+    use jax_daemon::SyncActivity;
+
+    let state = match (&status.activity, status.paused) {
+        (_, true) => "paused".to_string(),
+        (SyncActivity::Idle, false) => "idle".to_string(),
+        (SyncActivity::Syncing, false) => "syncing".to_string(),
+        (SyncActivity::Error(e), false) => format!("error ({})", e),
+    };
+    let mut description = format!(
+        "Sync: {}\nPending jobs: {}\nParked jobs: {}",
+        state, status.pending_jobs, status.parked_jobs
+    );
+
+    let conditions = &status.conditions;
+    let power = match (conditions.on_battery, conditions.battery_percent) {
+        (true, Some(percent)) => format!("battery ({}%)", percent),
+        (true, None) => "battery".to_string(),
+        (false, _) => "mains".to_string(),
+    };
+    let network = if conditions.metered {
+        "metered"
+    } else {
+        "unmetered"
+    };
+    let source = if status.conditions_reported {
+        "reported"
+    } else {
+        "probed"
+    };
+    description.push_str(&format!(
+        "\nConditions: {}, {} network ({})",
+        power, network, source
+    ));
+    if status.interval_multiplier > 1 {
+        description.push_str(&format!(
+            "\nPeriodic sync slowed {}x on battery",
+            status.interval_multiplier
+        ));
+    }
+    if status.downloads_deferred || status.deferred_jobs > 0 {
+        description.push_str(&format!("\nDeferred downloads: {}", status.deferred_jobs));
+    }
+    description
+}
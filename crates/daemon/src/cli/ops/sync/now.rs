@@ -0,0 +1,28 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::sync::{SyncNowRequest, SyncNowResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum SyncNowError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for SyncNowRequest {
+    type Error = SyncNowError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: SyncNowResponse = client.call(self.clone()).await?;
+
+        Ok(if response.parked {
+            format!(
+                "Syncing is paused; bucket {} will sync once resumed",
+                response.bucket_id
+            )
+        } else {
+            format!("Syncing bucket {} with its peers", response.bucket_id)
+        })
+    }
+}
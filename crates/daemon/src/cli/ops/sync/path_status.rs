@@ -0,0 +1,44 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::sync::path_status::PathSyncState;
+use jax_daemon::http_server::api::v0::sync::{PathStatusRequest, PathStatusResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum PathStatusError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for PathStatusRequest {
+    type Error = PathStatusError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: PathStatusResponse = client.call(self.clone()).await?;
+
+        let lines: Vec<String> = response
+            .statuses
+            .iter()
+            .map(|status| {
+                let state = match status.state {
+                    PathSyncState::Untracked => "untracked",
+                    PathSyncState::Missing => "missing",
+                    PathSyncState::Unavailable => "unavailable",
+                    PathSyncState::Paused => "paused",
+                    PathSyncState::Synced => "synced",
+                    PathSyncState::OnlineOnly => "online-only",
+                    PathSyncState::Downloading => "downloading",
+                };
+                match (&status.bucket_id, &status.path) {
+                    (Some(bucket_id), Some(path)) => format!(
+                        "{:<12} {} ({} {})",
+                        state, status.local_path, bucket_id, path
+                    ),
+                    _ => format!("{:<12} {}", state, status.local_path),
+                }
+            })
+            .collect();
+        Ok(lines.join("\n"))
+    }
+}
@@ -0,0 +1,31 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::sync::policy::{
+    SyncPolicyListRequest, SyncPolicyListResponse,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum SyncPolicyListError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for SyncPolicyListRequest {
+    type Error = SyncPolicyListError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: SyncPolicyListResponse = client.call(self.clone()).await?;
+
+        if response.policies.is_empty() {
+            return Ok("No bucket sync policies set; all buckets use the defaults".to_string());
+        }
+        Ok(response
+            .policies
+            .iter()
+            .map(super::describe)
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
@@ -0,0 +1,66 @@
+use clap::{Args, Subcommand};
+
+pub mod get;
+pub mod list;
+pub mod reset;
+pub mod set;
+
+use crate::cli::op::Op;
+use jax_daemon::http_server::api::v0::sync::policy::{
+    SyncPolicyGetRequest, SyncPolicyListRequest, SyncPolicyResetRequest, SyncPolicyResponse,
+    SyncPolicySetRequest,
+};
+
+crate::command_enum! {
+    (Get, SyncPolicyGetRequest),
+    (List, SyncPolicyListRequest),
+    (Reset, SyncPolicyResetRequest),
+    (Set, SyncPolicySetRequest),
+}
+
+// Rename the generated Command to PolicyCommand for clarity
+pub type PolicyCommand = Command;
+
+/// Show or change per-bucket sync schedules
+#[derive(Args, Debug, Clone)]
+pub struct Policy {
+    #[command(subcommand)]
+    pub command: PolicyCommand,
+}
+
+#[async_trait::async_trait]
+impl Op for Policy {
+    type Error = OpError;
+    type Output = OpOutput;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        self.command.execute(ctx).await
+    }
+}
+
+/// Render a bucket's policy for display
+fn describe(response: &SyncPolicyResponse) -> String {
+    let policy = &response.policy;
+    let schedule = if policy.paused {
+        "paused".to_string()
+    } else if policy.manual_only {
+        "manual only".to_string()
+    } else {
+        let interval = policy
+            .interval_secs
+            .map(|secs| format!("every {}s", secs))
+            .unwrap_or_else(|| format!("every {}s (default)", response.default_interval_secs));
+        match &policy.active_hours {
+            Some(hours) => format!("{}, between {}", interval, hours),
+            None => interval,
+        }
+    };
+    let mut description = format!("{}: {}", response.bucket_id, schedule);
+    if policy.online_only {
+        description.push_str(", online-only");
+    }
+    if let Some(depth) = policy.history_depth {
+        description.push_str(&format!(", content of the latest {} version(s)", depth));
+    }
+    description
+}
@@ -0,0 +1,30 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::sync::policy::{
+    SyncPolicyResetRequest, SyncPolicyResetResponse,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum SyncPolicyResetError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for SyncPolicyResetRequest {
+    type Error = SyncPolicyResetError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: SyncPolicyResetResponse = client.call(self.clone()).await?;
+
+        Ok(if response.removed {
+            format!(
+                "Bucket {} now uses the default schedule",
+                response.bucket_id
+            )
+        } else {
+            format!("Bucket {} had no sync policy", response.bucket_id)
+        })
+    }
+}
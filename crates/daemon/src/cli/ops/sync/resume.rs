@@ -0,0 +1,20 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::sync::{SyncResumeRequest, SyncStatusResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum SyncResumeError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for SyncResumeRequest {
+    type Error = SyncResumeError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: SyncStatusResponse = client.call(self.clone()).await?;
+        Ok(super::describe(&response))
+    }
+}
@@ -0,0 +1,20 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::sync::{SyncStatusRequest, SyncStatusResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum SyncStatusError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for SyncStatusRequest {
+    type Error = SyncStatusError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: SyncStatusResponse = client.call(self.clone()).await?;
+        Ok(super::describe(&response))
+    }
+}
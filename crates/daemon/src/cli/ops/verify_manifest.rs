@@ -0,0 +1,92 @@
+use std::path::PathBuf;
+
+use clap::Args;
+
+use common::crypto::PublicKey;
+use jax_daemon::integrity::{IntegrityError, IntegrityManifest};
+
+/// Check a local directory against a signed manifest exported with
+/// `jax bucket manifest export`. Doesn't need a running daemon.
+#[derive(Args, Debug, Clone)]
+pub struct VerifyManifest {
+    /// Manifest file
+    pub manifest: PathBuf,
+
+    /// Directory holding the bucket's files
+    pub dir: PathBuf,
+
+    /// Require the manifest to be signed by this node (hex public key)
+    #[arg(long)]
+    pub signer: Option<String>,
+
+    /// Don't fail on files in the directory that the manifest doesn't list
+    #[arg(long)]
+    pub ignore_extra: bool,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum VerifyManifestError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("{0}")]
+    Integrity(#[from] IntegrityError),
+    #[error("Invalid signer: {0}")]
+    InvalidSigner(String),
+    #[error("The manifest is signed by {0}, not the expected signer")]
+    WrongSigner(String),
+    #[error("{0}")]
+    Mismatch(String),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for VerifyManifest {
+    type Error = VerifyManifestError;
+    type Output = String;
+
+    async fn execute(&self, _ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let manifest: IntegrityManifest = std::fs::read_to_string(&self.manifest)?.parse()?;
+        manifest.verify()?;
+        if let Some(signer) = &self.signer {
+            let signer = PublicKey::from_hex(signer)
+                .map_err(|e| VerifyManifestError::InvalidSigner(e.to_string()))?;
+            if signer != manifest.signer {
+                return Err(VerifyManifestError::WrongSigner(manifest.signer.to_hex()));
+            }
+        }
+
+        let check = manifest.check_dir(&self.dir)?;
+        let extra = if self.ignore_extra {
+            &[][..]
+        } else {
+            &check.extra[..]
+        };
+        if !check.missing.is_empty() || !check.modified.is_empty() || !extra.is_empty() {
+            let mut lines = vec![format!(
+                "{} does not match the manifest: {} modified, {} missing, {} extra",
+                self.dir.display(),
+                check.modified.len(),
+                check.missing.len(),
+                extra.len()
+            )];
+            for (label, paths) in [
+                ("modified", &check.modified[..]),
+                ("missing", &check.missing[..]),
+                ("extra", extra),
+            ] {
+                lines.extend(paths.iter().map(|path| format!("  {:<9}{}", label, path)));
+            }
+            return Err(VerifyManifestError::Mismatch(lines.join("\n")));
+        }
+
+        Ok(format!(
+            "OK: {} file(s) in {} match version {} (height {}) of bucket {} ({}), signed by {}",
+            check.matched,
+            self.dir.display(),
+            manifest.version,
+            manifest.height,
+            manifest.bucket_name,
+            manifest.bucket_id,
+            manifest.signer.to_hex()
+        ))
+    }
+}
@@ -0,0 +1,33 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::webhook::{WebhookAddRequest, WebhookAddResponse};
+
+use super::describe_events;
+
+#[derive(Debug, thiserror::Error)]
+pub enum WebhookAddError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for WebhookAddRequest {
+    type Error = WebhookAddError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: WebhookAddResponse = client.call(self.clone()).await?;
+        let webhook = response.webhook;
+        let scope = match webhook.bucket_id {
+            Some(bucket_id) => format!("bucket {}", bucket_id),
+            None => "every bucket".to_string(),
+        };
+        Ok(format!(
+            "Added webhook {} for {} ({})\nSigning secret: {}",
+            webhook.webhook_id,
+            scope,
+            describe_events(&webhook.events),
+            response.secret
+        ))
+    }
+}
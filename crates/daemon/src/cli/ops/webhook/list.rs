@@ -0,0 +1,48 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::webhook::{WebhookListRequest, WebhookListResponse};
+
+use super::describe_events;
+
+#[derive(Debug, thiserror::Error)]
+pub enum WebhookListError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for WebhookListRequest {
+    type Error = WebhookListError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: WebhookListResponse = client.call(self.clone()).await?;
+
+        if response.webhooks.is_empty() {
+            return Ok("No webhooks found".to_string());
+        }
+        Ok(response
+            .webhooks
+            .iter()
+            .map(|w| {
+                let bucket = w
+                    .bucket_id
+                    .map_or_else(|| "all".to_string(), |id| id.to_string());
+                let last = match (w.last_status, &w.last_error) {
+                    (Some(status), _) => status.to_string(),
+                    (None, Some(error)) => format!("failed: {}", error),
+                    (None, None) => "never".to_string(),
+                };
+                format!(
+                    "{} {} (buckets: {} | events: {} | last delivery: {})",
+                    w.webhook_id,
+                    w.url,
+                    bucket,
+                    describe_events(&w.events),
+                    last
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
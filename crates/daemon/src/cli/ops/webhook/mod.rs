@@ -0,0 +1,47 @@
+use clap::{Args, Subcommand};
+
+pub mod add;
+pub mod list;
+pub mod remove;
+pub mod test;
+
+use crate::cli::op::Op;
+use jax_daemon::http_server::api::v0::webhook::{
+    WebhookAddRequest, WebhookListRequest, WebhookRemoveRequest, WebhookTestRequest,
+};
+use jax_daemon::webhooks::WebhookEvent;
+
+crate::command_enum! {
+    (Add, WebhookAddRequest),
+    (List, WebhookListRequest),
+    (Remove, WebhookRemoveRequest),
+    (Test, WebhookTestRequest),
+}
+
+// Rename the generated Command to WebhookCommand for clarity
+pub type WebhookCommand = Command;
+
+/// Manage URLs the daemon POSTs bucket events to
+#[derive(Args, Debug, Clone)]
+pub struct Webhook {
+    #[command(subcommand)]
+    pub command: WebhookCommand,
+}
+
+#[async_trait::async_trait]
+impl Op for Webhook {
+    type Error = OpError;
+    type Output = OpOutput;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        self.command.execute(ctx).await
+    }
+}
+
+fn describe_events(events: &[WebhookEvent]) -> String {
+    events
+        .iter()
+        .map(WebhookEvent::as_str)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
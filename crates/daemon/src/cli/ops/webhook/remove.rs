@@ -0,0 +1,23 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::webhook::{WebhookInfo, WebhookRemoveRequest};
+
+#[derive(Debug, thiserror::Error)]
+pub enum WebhookRemoveError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for WebhookRemoveRequest {
+    type Error = WebhookRemoveError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: WebhookInfo = client.call(self.clone()).await?;
+        Ok(format!(
+            "Removed webhook {} ({})",
+            response.webhook_id, response.url
+        ))
+    }
+}
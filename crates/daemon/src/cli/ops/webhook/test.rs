@@ -0,0 +1,24 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::webhook::{WebhookTestRequest, WebhookTestResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum WebhookTestError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for WebhookTestRequest {
+    type Error = WebhookTestError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: WebhookTestResponse = client.call(self.clone()).await?;
+        Ok(match (response.status, response.error) {
+            (Some(status), _) => format!("Ping delivered (status {})", status),
+            (None, Some(error)) => format!("Ping failed: {}", error),
+            (None, None) => "Ping failed".to_string(),
+        })
+    }
+}
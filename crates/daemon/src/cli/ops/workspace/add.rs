@@ -0,0 +1,26 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::workspace::{WorkspaceAddRequest, WorkspaceAddResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum WorkspaceAddError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for WorkspaceAddRequest {
+    type Error = WorkspaceAddError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: WorkspaceAddResponse = client.call(self.clone()).await?;
+
+        let mut lines = vec![format!(
+            "Added bucket {} to workspace {}",
+            response.bucket_id, self.workspace
+        )];
+        lines.extend(response.shares.iter().map(super::describe_share));
+        Ok(lines.join("\n"))
+    }
+}
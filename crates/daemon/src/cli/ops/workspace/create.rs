@@ -0,0 +1,23 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::workspace::{WorkspaceCreateRequest, WorkspaceInfo};
+
+#[derive(Debug, thiserror::Error)]
+pub enum WorkspaceCreateError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for WorkspaceCreateRequest {
+    type Error = WorkspaceCreateError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: WorkspaceInfo = client.call(self.clone()).await?;
+        Ok(format!(
+            "Created workspace {} (id: {})",
+            response.name, response.workspace_id
+        ))
+    }
+}
@@ -0,0 +1,23 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::workspace::{WorkspaceDeleteRequest, WorkspaceInfo};
+
+#[derive(Debug, thiserror::Error)]
+pub enum WorkspaceDeleteError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for WorkspaceDeleteRequest {
+    type Error = WorkspaceDeleteError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: WorkspaceInfo = client.call(self.clone()).await?;
+        Ok(format!(
+            "Deleted workspace {}; its {} bucket(s) were kept",
+            response.name, response.bucket_count
+        ))
+    }
+}
@@ -0,0 +1,34 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::workspace::{WorkspaceListRequest, WorkspaceListResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum WorkspaceListError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for WorkspaceListRequest {
+    type Error = WorkspaceListError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: WorkspaceListResponse = client.call(self.clone()).await?;
+
+        if response.workspaces.is_empty() {
+            return Ok("No workspaces found".to_string());
+        }
+        Ok(response
+            .workspaces
+            .iter()
+            .map(|w| {
+                format!(
+                    "{} (id: {} | buckets: {})",
+                    w.name, w.workspace_id, w.bucket_count
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
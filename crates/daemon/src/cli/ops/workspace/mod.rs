@@ -0,0 +1,53 @@
+use clap::{Args, Subcommand};
+
+pub mod add;
+pub mod create;
+pub mod delete;
+pub mod list;
+pub mod remove;
+pub mod share;
+
+use crate::cli::op::Op;
+use jax_daemon::http_server::api::v0::workspace::{
+    WorkspaceAddRequest, WorkspaceCreateRequest, WorkspaceDeleteRequest, WorkspaceListRequest,
+    WorkspaceRemoveRequest, WorkspaceShareRequest, WorkspaceShareResult,
+};
+
+crate::command_enum! {
+    (Add, WorkspaceAddRequest),
+    (Create, WorkspaceCreateRequest),
+    (Delete, WorkspaceDeleteRequest),
+    (List, WorkspaceListRequest),
+    (Remove, WorkspaceRemoveRequest),
+    (Share, WorkspaceShareRequest),
+}
+
+// Rename the generated Command to WorkspaceCommand for clarity
+pub type WorkspaceCommand = Command;
+
+/// Group buckets into workspaces and share them together
+#[derive(Args, Debug, Clone)]
+pub struct Workspace {
+    #[command(subcommand)]
+    pub command: WorkspaceCommand,
+}
+
+#[async_trait::async_trait]
+impl Op for Workspace {
+    type Error = OpError;
+    type Output = OpOutput;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        self.command.execute(ctx).await
+    }
+}
+
+/// Render the outcome of sharing one bucket of a workspace
+fn describe_share(result: &WorkspaceShareResult) -> String {
+    match (&result.new_bucket_link, &result.error) {
+        (Some(link), _) => format!("{}: shared (link: {})", result.bucket_id, link),
+        (None, None) if result.pending => format!("{}: waiting for approval", result.bucket_id),
+        (None, Some(error)) => format!("{}: failed: {}", result.bucket_id, error),
+        (None, None) => format!("{}: failed", result.bucket_id),
+    }
+}
@@ -0,0 +1,25 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::workspace::{
+    WorkspaceRemoveRequest, WorkspaceRemoveResponse,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum WorkspaceRemoveError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for WorkspaceRemoveRequest {
+    type Error = WorkspaceRemoveError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: WorkspaceRemoveResponse = client.call(self.clone()).await?;
+        Ok(format!(
+            "Removed bucket {} from workspace {}",
+            response.bucket_id, self.workspace
+        ))
+    }
+}
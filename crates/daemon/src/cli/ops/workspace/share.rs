@@ -0,0 +1,26 @@
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::workspace::{WorkspaceShareRequest, WorkspaceShareResponse};
+
+#[derive(Debug, thiserror::Error)]
+pub enum WorkspaceShareError {
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+}
+
+#[async_trait::async_trait]
+impl crate::cli::op::Op for WorkspaceShareRequest {
+    type Error = WorkspaceShareError;
+    type Output = String;
+
+    async fn execute(&self, ctx: &crate::cli::op::OpContext) -> Result<Self::Output, Self::Error> {
+        let mut client = ctx.client.clone();
+        let response: WorkspaceShareResponse = client.call(self.clone()).await?;
+
+        let mut lines = vec![format!(
+            "Workspace {} shared with peer {}",
+            self.workspace, response.peer_public_key
+        )];
+        lines.extend(response.buckets.iter().map(super::describe_share));
+        Ok(lines.join("\n"))
+    }
+}
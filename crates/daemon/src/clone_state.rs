@@ -25,6 +25,9 @@ pub struct CloneConfig {
     pub last_synced_link: Link,
     /// The last synced height
     pub last_synced_height: u64,
+    /// Number of recent versions whose content is kept, None for all
+    #[serde(default)]
+    pub depth: Option<u64>,
 }
 
 /// Mapping of filesystem paths to their content hashes
@@ -0,0 +1,270 @@
+//! Running several daemons on one database
+//!
+//! Daemons that share their database, such as the gateways of a cluster
+//! (see `issues/gateway-clustering`), are all handed the same announcements
+//! and would each run the same bucket syncs and pin downloads. With a
+//! `[cluster]` section in the config, whose `database` can point the
+//! instances at one SQLite file, an instance first claims each of those
+//! jobs in the `sync_jobs` table and skips the ones another instance holds
+//! or finished. A claim lasts `claim_secs` and is renewed while the job
+//! runs, so the job of an instance that stopped is run by the next one
+//! handed it once the claim expired. A failed job is released for any
+//! instance to retry.
+//!
+//! Periodic pings are only sent by the instance holding the
+//! [`PERIODIC_SYNC_LEASE`], which every instance tries to take on each tick
+//! and which lapses when its holder stops renewing it.
+
+use std::future::Future;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use common::peer::SyncJob;
+
+use crate::database::Database;
+
+/// Lease of the instance that sends periodic pings
+pub const PERIODIC_SYNC_LEASE: &str = "periodic_sync";
+
+/// How long finished jobs are remembered, so that instances handed the
+/// same job late skip it
+pub const FINISHED_JOB_RETENTION: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Shortest claim, so that renewals can't spin
+const MIN_CLAIM: Duration = Duration::from_secs(3);
+
+/// The `[cluster]` section of `config.toml`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClusterConfig {
+    /// How long a claim on a sync job holds without being renewed
+    #[serde(default = "default_claim_secs")]
+    pub claim_secs: u64,
+    /// Database shared by the instances, instead of each one's `db.sqlite`.
+    /// The file must exist; an empty one is set up on first start.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub database: Option<PathBuf>,
+}
+
+fn default_claim_secs() -> u64 {
+    300
+}
+
+impl Default for ClusterConfig {
+    fn default() -> Self {
+        Self {
+            claim_secs: default_claim_secs(),
+            database: None,
+        }
+    }
+}
+
+/// This instance's part in a cluster sharing its database
+#[derive(Debug, Clone)]
+pub struct Cluster {
+    database: Database,
+    /// Identifies this instance's claims and leases, new on every start
+    instance: String,
+    claim: Duration,
+}
+
+impl Cluster {
+    pub fn new(database: Database, config: &ClusterConfig) -> Self {
+        Self {
+            database,
+            instance: Uuid::new_v4().to_string(),
+            claim: Duration::from_secs(config.claim_secs).max(MIN_CLAIM),
+        }
+    }
+
+    pub fn instance(&self) -> &str {
+        &self.instance
+    }
+
+    /// Take or renew a lease for `ttl`. Returns whether this instance holds
+    /// it; if the database can't be reached, nobody does.
+    pub async fn lead(&self, lease: &str, ttl: Duration) -> bool {
+        match self
+            .database
+            .acquire_lease(lease, &self.instance, expires_in(ttl))
+            .await
+        {
+            Ok(leads) => leads,
+            Err(e) => {
+                tracing::warn!("Failed to take the {} lease: {}", lease, e);
+                false
+            }
+        }
+    }
+
+    /// Run a sync job unless another instance claimed or finished it,
+    /// keeping the claim while it runs. Returns None if the job was skipped.
+    /// If the claim can't be recorded, the job runs unclaimed rather than
+    /// not at all.
+    pub async fn run<F>(&self, job: &str, bucket_id: Uuid, work: F) -> Option<anyhow::Result<()>>
+    where
+        F: Future<Output = anyhow::Result<()>>,
+    {
+        match self
+            .database
+            .claim_sync_job(job, &bucket_id, &self.instance, expires_in(self.claim))
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => {
+                tracing::debug!("Sync job {} is claimed by another instance, skipping", job);
+                return None;
+            }
+            Err(e) => {
+                tracing::warn!("Failed to claim sync job {}, running it anyway: {}", job, e);
+                return Some(work.await);
+            }
+        }
+
+        tokio::pin!(work);
+        let mut renew = tokio::time::interval(self.claim / 3);
+        renew.tick().await;
+        let result = loop {
+            tokio::select! {
+                result = &mut work => break result,
+                _ = renew.tick() => {
+                    match self
+                        .database
+                        .renew_sync_job(job, &self.instance, expires_in(self.claim))
+                        .await
+                    {
+                        Ok(true) => {}
+                        Ok(false) => tracing::warn!("Lost the claim on sync job {}", job),
+                        Err(e) => {
+                            tracing::warn!("Failed to renew the claim on sync job {}: {}", job, e)
+                        }
+                    }
+                }
+            }
+        };
+
+        let recorded = match &result {
+            Ok(()) => {
+                let forget_before = OffsetDateTime::now_utc().unix_timestamp()
+                    - FINISHED_JOB_RETENTION.as_secs() as i64;
+                self.database
+                    .finish_sync_job(job, &self.instance, forget_before)
+                    .await
+            }
+            Err(_) => self.database.release_sync_job(job, &self.instance).await,
+        };
+        if let Err(e) = recorded {
+            tracing::warn!("Failed to record the end of sync job {}: {}", job, e);
+        }
+        Some(result)
+    }
+}
+
+/// The name a sync job is claimed under, the same on every instance handed
+/// it. Pings aren't claimed, being cheap.
+pub fn job_key(job: &SyncJob) -> Option<String> {
+    match job {
+        SyncJob::SyncBucket(job) => {
+            Some(format!("sync:{}:{}", job.bucket_id, job.target.link.hash()))
+        }
+        SyncJob::DownloadPins(job) => Some(match &job.subtree {
+            Some(subtree) => format!(
+                "pins:{}:{}:{}",
+                job.bucket_id,
+                job.pins_link.hash(),
+                subtree.link().hash()
+            ),
+            None => format!("pins:{}:{}", job.bucket_id, job.pins_link.hash()),
+        }),
+        SyncJob::PingPeer(_) => None,
+    }
+}
+
+/// Seconds since the Unix epoch `after` from now
+fn expires_in(after: Duration) -> i64 {
+    OffsetDateTime::now_utc().unix_timestamp() + after.as_secs() as i64
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    async fn instances() -> (Cluster, Cluster, tempfile::TempDir) {
+        let dir = tempfile::TempDir::new().unwrap();
+        let url = url::Url::parse(&format!(
+            "sqlite://{}",
+            dir.path().join("db.sqlite").display()
+        ))
+        .unwrap();
+        let database = Database::connect(&url, None).await.unwrap();
+        let config = ClusterConfig::default();
+        (
+            Cluster::new(database.clone(), &config),
+            Cluster::new(database, &config),
+            dir,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_jobs_run_once() {
+        let (a, b, _dir) = instances().await;
+        let bucket_id = Uuid::new_v4();
+
+        // Claimed while it runs, and finished afterwards
+        let ran = a
+            .run("pins:1", bucket_id, async {
+                assert!(b.run("pins:1", bucket_id, async { Ok(()) }).await.is_none());
+                Ok(())
+            })
+            .await;
+        assert!(matches!(ran, Some(Ok(()))));
+        assert!(b.run("pins:1", bucket_id, async { Ok(()) }).await.is_none());
+
+        // Failed jobs are released for the others to retry
+        let failed = a
+            .run("pins:2", bucket_id, async {
+                Err(anyhow::anyhow!("offline"))
+            })
+            .await;
+        assert!(matches!(failed, Some(Err(_))));
+        assert!(b.run("pins:2", bucket_id, async { Ok(()) }).await.is_some());
+
+        // The claim of an instance that stopped lapses
+        assert!(a
+            .database
+            .claim_sync_job(
+                "pins:3",
+                &bucket_id,
+                "stopped",
+                expires_in(Duration::ZERO) - 1
+            )
+            .await
+            .unwrap());
+        assert!(b.run("pins:3", bucket_id, async { Ok(()) }).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_lease_held_by_one_instance() {
+        let (a, b, _dir) = instances().await;
+        let ttl = Duration::from_secs(60);
+
+        assert!(a.lead(PERIODIC_SYNC_LEASE, ttl).await);
+        assert!(!b.lead(PERIODIC_SYNC_LEASE, ttl).await);
+        assert!(a.lead(PERIODIC_SYNC_LEASE, ttl).await);
+
+        // Taken over once the holder stops renewing it
+        a.database
+            .acquire_lease(
+                PERIODIC_SYNC_LEASE,
+                a.instance(),
+                expires_in(Duration::ZERO) - 1,
+            )
+            .await
+            .unwrap();
+        assert!(b.lead(PERIODIC_SYNC_LEASE, ttl).await);
+        assert!(!a.lead(PERIODIC_SYNC_LEASE, ttl).await);
+    }
+}
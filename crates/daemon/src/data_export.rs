@@ -0,0 +1,436 @@
+//! Full data exports: everything this node can access, in one directory
+//!
+//! An export writes every bucket the node knows about, with its metadata,
+//! into a new directory on the daemon's machine, for handing a user all of
+//! their data at once. Buckets are written either as decrypted directory
+//! trees or, with `encrypted`, as the blobs of their current version exactly
+//! as stored, which only this node's key can read:
+//!
+//! ```text
+//! <output>/export.json                    node, time, mode and per-bucket summaries
+//! <output>/buckets/<id>/bucket.json       name, version, shares and history
+//! <output>/buckets/<id>/files/...         decrypted files
+//! <output>/buckets/<id>/blobs/<hash>      encrypted blobs (with `encrypted`)
+//! ```
+//!
+//! Decrypted trees are of the version this node reads, so mirrors export the
+//! latest published version and skip buckets that were never published.
+//! Encrypted exports hold the current version's manifest, pins and every
+//! pinned blob present locally. Exports run in the background; their
+//! progress is kept in memory until the daemon restarts.
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use common::bucket_log::BucketLogProvider;
+use common::mount::{Mount, MountError, NodeLink};
+use common::peer::{BlobsStoreError, Peer};
+
+use crate::database::Database;
+
+const EXPORT_FILE_NAME: &str = "export.json";
+const BUCKET_FILE_NAME: &str = "bucket.json";
+
+/// Where an export is in its run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportState {
+    Running,
+    Finished,
+    Failed,
+}
+
+/// Progress of an export, updated as it runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportProgress {
+    pub export_id: Uuid,
+    pub output_dir: PathBuf,
+    pub encrypted: bool,
+    pub state: ExportState,
+    pub buckets_total: usize,
+    pub buckets_done: usize,
+    /// Name of the bucket being written
+    pub current_bucket: Option<String>,
+    /// Files (or blobs, when encrypted) written so far
+    pub files_written: u64,
+    pub bytes_written: u64,
+    /// Buckets that couldn't be exported, with the reason
+    pub skipped: Vec<SkippedBucket>,
+    /// Why the export failed, if it did
+    pub error: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub started_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub finished_at: Option<OffsetDateTime>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedBucket {
+    pub bucket_id: Uuid,
+    pub name: String,
+    pub reason: String,
+}
+
+/// `export.json` at the root of an export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportIndex {
+    /// Public key of the exporting node
+    pub node_id: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub exported_at: OffsetDateTime,
+    pub encrypted: bool,
+    pub buckets: Vec<BucketSummary>,
+    pub skipped: Vec<SkippedBucket>,
+}
+
+/// A bucket in `export.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BucketSummary {
+    pub bucket_id: Uuid,
+    pub name: String,
+    /// Directory of the bucket, relative to the export
+    pub path: String,
+    /// Version exported (a bucket link hash)
+    pub version: String,
+    pub height: u64,
+    pub files: u64,
+    pub bytes: u64,
+}
+
+/// `bucket.json` in a bucket's directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BucketMetadata {
+    pub bucket_id: Uuid,
+    pub name: String,
+    pub version: String,
+    pub height: u64,
+    /// This node's role, if the bucket is shared with it rather than followed
+    pub role: Option<String>,
+    pub shares: Vec<ShareSummary>,
+    /// Every version in the bucket's log, oldest first
+    pub history: Vec<VersionSummary>,
+    /// Blobs of an encrypted export that weren't present locally
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub missing_blobs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareSummary {
+    pub public_key: String,
+    pub role: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionSummary {
+    pub height: u64,
+    pub version: String,
+    pub published: bool,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+/// Handle for starting exports and following their progress
+#[derive(Debug, Clone)]
+pub struct Exports {
+    peer: Peer<Database>,
+    database: Database,
+    progress: Arc<Mutex<HashMap<Uuid, ExportProgress>>>,
+}
+
+impl Exports {
+    pub fn new(peer: Peer<Database>, database: Database) -> Self {
+        Self {
+            peer,
+            database,
+            progress: Default::default(),
+        }
+    }
+
+    /// Start exporting into `output_dir`, which must be an absolute path to
+    /// a new or empty directory
+    pub async fn start(
+        &self,
+        output_dir: PathBuf,
+        encrypted: bool,
+    ) -> Result<ExportProgress, DataExportError> {
+        if !output_dir.is_absolute() {
+            return Err(DataExportError::RelativeOutput(output_dir));
+        }
+        if tokio::fs::try_exists(&output_dir).await?
+            && tokio::fs::read_dir(&output_dir)
+                .await?
+                .next_entry()
+                .await?
+                .is_some()
+        {
+            return Err(DataExportError::OutputNotEmpty(output_dir));
+        }
+        tokio::fs::create_dir_all(&output_dir).await?;
+
+        let progress = ExportProgress {
+            export_id: Uuid::new_v4(),
+            output_dir,
+            encrypted,
+            state: ExportState::Running,
+            buckets_total: 0,
+            buckets_done: 0,
+            current_bucket: None,
+            files_written: 0,
+            bytes_written: 0,
+            skipped: Vec::new(),
+            error: None,
+            started_at: OffsetDateTime::now_utc(),
+            finished_at: None,
+        };
+        self.progress
+            .lock()
+            .expect("export progress lock poisoned")
+            .insert(progress.export_id, progress.clone());
+
+        let exports = self.clone();
+        let export_id = progress.export_id;
+        tokio::spawn(async move {
+            let result = exports.run(export_id).await;
+            exports.update(export_id, |progress| {
+                progress.current_bucket = None;
+                progress.finished_at = Some(OffsetDateTime::now_utc());
+                match result {
+                    Ok(()) => progress.state = ExportState::Finished,
+                    Err(e) => {
+                        tracing::warn!("Export {} failed: {}", export_id, e);
+                        progress.state = ExportState::Failed;
+                        progress.error = Some(e.to_string());
+                    }
+                }
+            });
+        });
+        Ok(progress)
+    }
+
+    /// Progress of an export started since the daemon started
+    pub fn get(&self, export_id: &Uuid) -> Option<ExportProgress> {
+        self.progress
+            .lock()
+            .expect("export progress lock poisoned")
+            .get(export_id)
+            .cloned()
+    }
+
+    fn update(&self, export_id: Uuid, f: impl FnOnce(&mut ExportProgress)) {
+        if let Some(progress) = self
+            .progress
+            .lock()
+            .expect("export progress lock poisoned")
+            .get_mut(&export_id)
+        {
+            f(progress);
+        }
+    }
+
+    async fn run(&self, export_id: Uuid) -> Result<(), DataExportError> {
+        let Some(ExportProgress {
+            output_dir,
+            encrypted,
+            ..
+        }) = self.get(&export_id)
+        else {
+            return Ok(());
+        };
+        let bucket_ids = BucketLogProvider::list_buckets(self.peer.logs())
+            .await
+            .map_err(|e| DataExportError::BucketLog(e.to_string()))?;
+        self.update(export_id, |progress| {
+            progress.buckets_total = bucket_ids.len()
+        });
+
+        let mut index = ExportIndex {
+            node_id: self.peer.secret().public().to_hex(),
+            exported_at: OffsetDateTime::now_utc(),
+            encrypted,
+            buckets: Vec::new(),
+            skipped: Vec::new(),
+        };
+        for bucket_id in bucket_ids {
+            let Some(head) = self.database.get_all_bucket_logs(&bucket_id).await?.pop() else {
+                continue;
+            };
+            self.update(export_id, |progress| {
+                progress.current_bucket = Some(head.name.clone())
+            });
+
+            let dir = output_dir.join("buckets").join(bucket_id.to_string());
+            match self
+                .export_bucket(export_id, bucket_id, &dir, encrypted)
+                .await
+            {
+                Ok(summary) => index.buckets.push(summary),
+                Err(e) => {
+                    tracing::warn!("Export {} skipped bucket {}: {}", export_id, bucket_id, e);
+                    let skipped = SkippedBucket {
+                        bucket_id,
+                        name: head.name.clone(),
+                        reason: e.to_string(),
+                    };
+                    self.update(export_id, |progress| progress.skipped.push(skipped.clone()));
+                    index.skipped.push(skipped);
+                }
+            }
+            self.update(export_id, |progress| progress.buckets_done += 1);
+        }
+
+        let json = serde_json::to_vec_pretty(&index)?;
+        tokio::fs::write(output_dir.join(EXPORT_FILE_NAME), json).await?;
+        Ok(())
+    }
+
+    async fn export_bucket(
+        &self,
+        export_id: Uuid,
+        bucket_id: Uuid,
+        dir: &Path,
+        encrypted: bool,
+    ) -> Result<BucketSummary, DataExportError> {
+        let logs = self.database.get_all_bucket_logs(&bucket_id).await?;
+        let history: Vec<VersionSummary> = logs
+            .iter()
+            .map(|entry| VersionSummary {
+                height: entry.height,
+                version: entry.current_link.hash().to_string(),
+                published: entry.published,
+                created_at: entry.created_at,
+            })
+            .collect();
+
+        let blobs = self.peer.blobs();
+        let (link, mut files, mut bytes, missing_blobs) = if encrypted {
+            let head = logs.last().ok_or(DataExportError::NoVersions)?;
+            let link = head.current_link.clone();
+            let manifest = Mount::load_manifest(&link, blobs).await?;
+
+            let mut hashes =
+                BTreeSet::from([link.hash(), manifest.pins().hash(), manifest.entry().hash()]);
+            hashes.extend(blobs.read_hash_list(manifest.pins().hash()).await?);
+            let blobs_dir = dir.join("blobs");
+            tokio::fs::create_dir_all(&blobs_dir).await?;
+            let (mut files, mut bytes, mut missing) = (0, 0, Vec::new());
+            for hash in hashes {
+                if !blobs.stat(&hash).await? {
+                    missing.push(hash.to_string());
+                    continue;
+                }
+                let data = blobs.get(&hash).await?;
+                tokio::fs::write(blobs_dir.join(hash.to_string()), &data).await?;
+                files += 1;
+                bytes += data.len() as u64;
+                self.count(export_id, data.len() as u64);
+            }
+            (link, files, bytes, missing)
+        } else {
+            let mount = self.peer.mount_for_read(bucket_id).await?;
+            let files_dir = dir.join("files");
+            tokio::fs::create_dir_all(&files_dir).await?;
+            let (mut files, mut bytes) = (0, 0);
+            for (path, node_link) in mount.ls_deep(Path::new("/")).await? {
+                // Names come from the bucket's writers; keep them inside the export
+                if !path
+                    .components()
+                    .all(|component| matches!(component, Component::Normal(_)))
+                {
+                    tracing::warn!("Export {} skipped path {:?}", export_id, path);
+                    continue;
+                }
+                let target = files_dir.join(&path);
+                match node_link {
+                    NodeLink::Dir(..) => tokio::fs::create_dir_all(&target).await?,
+                    NodeLink::Data(..) => {
+                        if let Some(parent) = target.parent() {
+                            tokio::fs::create_dir_all(parent).await?;
+                        }
+                        let data = mount.cat(&Path::new("/").join(&path)).await?;
+                        tokio::fs::write(&target, &data).await?;
+                        files += 1;
+                        bytes += data.len() as u64;
+                        self.count(export_id, data.len() as u64);
+                    }
+                    NodeLink::Bucket(_) => {}
+                }
+            }
+            (mount.link().await, files, bytes, Vec::new())
+        };
+        let manifest = Mount::load_manifest(&link, blobs).await?;
+        let version = logs
+            .iter()
+            .find(|entry| entry.current_link == link)
+            .ok_or(DataExportError::NoVersions)?;
+
+        let own_key = self.peer.secret().public().to_hex();
+        let metadata = BucketMetadata {
+            bucket_id,
+            name: manifest.name().to_string(),
+            version: link.hash().to_string(),
+            height: version.height,
+            role: manifest
+                .shares()
+                .get(&own_key)
+                .map(|share| share.role().to_string().to_lowercase()),
+            shares: manifest
+                .shares()
+                .iter()
+                .map(|(key, share)| ShareSummary {
+                    public_key: key.clone(),
+                    role: share.role().to_string().to_lowercase(),
+                })
+                .collect(),
+            history,
+            missing_blobs,
+        };
+        let json = serde_json::to_vec_pretty(&metadata)?;
+        tokio::fs::write(dir.join(BUCKET_FILE_NAME), &json).await?;
+        files += 1;
+        bytes += json.len() as u64;
+
+        Ok(BucketSummary {
+            bucket_id,
+            name: metadata.name,
+            path: format!("buckets/{}", bucket_id),
+            version: metadata.version,
+            height: metadata.height,
+            files,
+            bytes,
+        })
+    }
+
+    fn count(&self, export_id: Uuid, bytes: u64) {
+        self.update(export_id, |progress| {
+            progress.files_written += 1;
+            progress.bytes_written += bytes;
+        });
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DataExportError {
+    #[error("Output directory must be an absolute path: {0}")]
+    RelativeOutput(PathBuf),
+    #[error("Output directory is not empty: {0}")]
+    OutputNotEmpty(PathBuf),
+    #[error("The bucket has no versions")]
+    NoVersions,
+    #[error("Bucket log error: {0}")]
+    BucketLog(String),
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Mount error: {0}")]
+    Mount(#[from] MountError),
+    #[error("Blob store error: {0}")]
+    Blobs(#[from] BlobsStoreError),
+    #[error("Failed to encode metadata: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
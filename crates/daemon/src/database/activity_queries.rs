@@ -0,0 +1,141 @@
+use std::collections::HashSet;
+
+use sqlx::Row;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::activity::{ActivityEntry, Change};
+use crate::database::Database;
+
+impl Database {
+    /// Versions of a bucket already compared for the activity feed
+    pub async fn activity_indexed_versions(
+        &self,
+        bucket_id: &Uuid,
+    ) -> Result<HashSet<String>, sqlx::Error> {
+        let rows = sqlx::query("SELECT version FROM activity_index_versions WHERE bucket_id = ?1")
+            .bind(bucket_id.to_string())
+            .fetch_all(&**self)
+            .await?;
+        Ok(rows.iter().map(|row| row.get("version")).collect())
+    }
+
+    /// Record the changes of a bucket version, unless it was already
+    /// recorded. Returns whether it was recorded now.
+    pub async fn record_activity(
+        &self,
+        bucket_id: &Uuid,
+        version: &str,
+        entries: &[ActivityEntry],
+    ) -> Result<bool, sqlx::Error> {
+        let bucket_id_str = bucket_id.to_string();
+        let mut tx = self.begin().await?;
+
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO activity_index_versions (bucket_id, version)
+            VALUES (?1, ?2)
+            ON CONFLICT(bucket_id, version) DO NOTHING
+            "#,
+        )
+        .bind(&bucket_id_str)
+        .bind(version)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        if inserted == 0 {
+            return Ok(false);
+        }
+
+        for entry in entries {
+            sqlx::query(
+                r#"
+                INSERT INTO activity_index
+                    (bucket_id, version, height, path, change, author, changed_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                "#,
+            )
+            .bind(&bucket_id_str)
+            .bind(&entry.version)
+            .bind(entry.height as i64)
+            .bind(&entry.path)
+            .bind(entry.change.to_string())
+            .bind(entry.author.as_deref())
+            .bind(entry.changed_at.unix_timestamp())
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Drop the activity of buckets that are no longer known
+    pub async fn prune_activity(&self, keep: &[Uuid]) -> Result<u64, sqlx::Error> {
+        let known = sqlx::query("SELECT DISTINCT bucket_id FROM activity_index_versions")
+            .fetch_all(&**self)
+            .await?;
+
+        let mut removed = 0;
+        for row in known {
+            let bucket_id: String = row.get("bucket_id");
+            if keep.iter().any(|id| id.to_string() == bucket_id) {
+                continue;
+            }
+            removed += sqlx::query("DELETE FROM activity_index WHERE bucket_id = ?1")
+                .bind(&bucket_id)
+                .execute(&**self)
+                .await?
+                .rows_affected();
+            sqlx::query("DELETE FROM activity_index_versions WHERE bucket_id = ?1")
+                .bind(&bucket_id)
+                .execute(&**self)
+                .await?;
+        }
+
+        Ok(removed)
+    }
+
+    /// Page through the changes of one bucket, or of every bucket, newest
+    /// first
+    pub async fn activity_feed(
+        &self,
+        bucket_id: Option<&Uuid>,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<ActivityEntry>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT bucket_id, version, height, path, change, author, changed_at
+            FROM activity_index
+            WHERE ?1 IS NULL OR bucket_id = ?1
+            ORDER BY changed_at DESC, height DESC, bucket_id, path
+            LIMIT ?2 OFFSET ?3
+            "#,
+        )
+        .bind(bucket_id.map(|id| id.to_string()))
+        .bind(limit as i64)
+        .bind(offset as i64)
+        .fetch_all(&**self)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                let bucket_id: String = row.get("bucket_id");
+                let change: String = row.get("change");
+                let changed_at: i64 = row.get("changed_at");
+                Some(ActivityEntry {
+                    bucket_id: Uuid::parse_str(&bucket_id).ok()?,
+                    version: row.get("version"),
+                    height: row.get::<i64, _>("height") as u64,
+                    path: row.get("path"),
+                    change: change.parse::<Change>().ok()?,
+                    author: row.get("author"),
+                    changed_at: OffsetDateTime::from_unix_timestamp(changed_at)
+                        .unwrap_or(OffsetDateTime::UNIX_EPOCH),
+                })
+            })
+            .collect())
+    }
+}
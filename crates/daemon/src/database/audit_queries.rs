@@ -0,0 +1,70 @@
+use sqlx::Row;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::audit::{AuditEntry, AuditEvent, AuditFilter};
+use crate::database::Database;
+
+impl Database {
+    /// Append an entry to the audit log
+    pub async fn insert_audit_entry(&self, event: &AuditEvent) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO audit_log (at, actor, operation, bucket_id, path, detail, version)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            "#,
+        )
+        .bind(OffsetDateTime::now_utc().unix_timestamp())
+        .bind(event.actor.to_string())
+        .bind(event.operation)
+        .bind(event.bucket_id.map(|id| id.to_string()))
+        .bind(event.path.as_deref())
+        .bind(event.detail.as_deref())
+        .bind(event.version.as_deref())
+        .execute(&**self)
+        .await?;
+        Ok(())
+    }
+
+    /// Load the audit log entries matching a filter, newest first
+    pub async fn list_audit_entries(
+        &self,
+        filter: &AuditFilter,
+    ) -> Result<Vec<AuditEntry>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, at, actor, operation, bucket_id, path, detail, version
+            FROM audit_log
+            WHERE (?1 IS NULL OR bucket_id = ?1)
+                AND (?2 IS NULL OR actor = ?2)
+                AND (?3 IS NULL OR at >= ?3)
+            ORDER BY id DESC
+            LIMIT ?4
+            "#,
+        )
+        .bind(filter.bucket_id.map(|id| id.to_string()))
+        .bind(filter.actor.as_deref())
+        .bind(filter.since.map(|since| since.unix_timestamp()))
+        // A negative limit is no limit
+        .bind(filter.limit.map_or(-1, i64::from))
+        .fetch_all(&**self)
+        .await?;
+
+        let mut entries = Vec::with_capacity(rows.len());
+        for row in rows {
+            let at: i64 = row.get("at");
+            let bucket_id: Option<String> = row.get("bucket_id");
+            entries.push(AuditEntry {
+                id: row.get("id"),
+                at: OffsetDateTime::from_unix_timestamp(at).unwrap_or(OffsetDateTime::UNIX_EPOCH),
+                actor: row.get("actor"),
+                operation: row.get("operation"),
+                bucket_id: bucket_id.and_then(|id| Uuid::parse_str(&id).ok()),
+                path: row.get("path"),
+                detail: row.get("detail"),
+                version: row.get("version"),
+            });
+        }
+        Ok(entries)
+    }
+}
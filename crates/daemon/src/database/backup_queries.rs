@@ -0,0 +1,233 @@
+use std::path::{Path, PathBuf};
+
+use sqlx::Row;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::backup::{BackupJob, BackupSnapshot, Schedule};
+use crate::database::Database;
+
+impl Database {
+    /// Create a backup job, first due at `next_run_at`
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_backup_job(
+        &self,
+        name: Option<&str>,
+        source_dir: &Path,
+        bucket_id: &Uuid,
+        target_path: &str,
+        schedule: &Schedule,
+        retain: u64,
+        next_run_at: OffsetDateTime,
+    ) -> Result<BackupJob, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let row = sqlx::query(
+            r#"
+            INSERT INTO backup_jobs
+                (id, name, source_dir, bucket_id, target_path, schedule, retain, next_run_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            RETURNING created_at
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(name)
+        .bind(source_dir.to_string_lossy().to_string())
+        .bind(bucket_id.to_string())
+        .bind(target_path)
+        .bind(schedule.to_string())
+        .bind(retain as i64)
+        .bind(next_run_at)
+        .fetch_one(&**self)
+        .await?;
+
+        Ok(BackupJob {
+            id,
+            name: name.map(str::to_string),
+            source_dir: source_dir.to_path_buf(),
+            bucket_id: *bucket_id,
+            target_path: target_path.to_string(),
+            schedule: schedule.clone(),
+            retain,
+            created_at: row.get("created_at"),
+            next_run_at,
+            last_run_at: None,
+            last_error: None,
+        })
+    }
+
+    /// Backup jobs, oldest first
+    pub async fn list_backup_jobs(&self) -> Result<Vec<BackupJob>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, name, source_dir, bucket_id, target_path, schedule, retain,
+                   created_at, next_run_at, last_run_at, last_error
+            FROM backup_jobs
+            ORDER BY created_at, id
+            "#,
+        )
+        .fetch_all(&**self)
+        .await?;
+
+        Ok(rows.iter().filter_map(backup_job_from_row).collect())
+    }
+
+    pub async fn get_backup_job(&self, id: &Uuid) -> Result<Option<BackupJob>, sqlx::Error> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, name, source_dir, bucket_id, target_path, schedule, retain,
+                   created_at, next_run_at, last_run_at, last_error
+            FROM backup_jobs
+            WHERE id = ?1
+            "#,
+        )
+        .bind(id.to_string())
+        .fetch_optional(&**self)
+        .await?;
+
+        Ok(row.as_ref().and_then(backup_job_from_row))
+    }
+
+    /// Remove a backup job and its snapshots, returning whether it existed
+    pub async fn delete_backup_job(&self, id: &Uuid) -> Result<bool, sqlx::Error> {
+        let mut tx = self.begin().await?;
+        sqlx::query("DELETE FROM backup_snapshots WHERE job_id = ?1")
+            .bind(id.to_string())
+            .execute(&mut *tx)
+            .await?;
+        let result = sqlx::query("DELETE FROM backup_jobs WHERE id = ?1")
+            .bind(id.to_string())
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Set when a backup job is next due
+    pub async fn schedule_backup_job(
+        &self,
+        id: &Uuid,
+        next_run_at: OffsetDateTime,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE backup_jobs SET next_run_at = ?2 WHERE id = ?1")
+            .bind(id.to_string())
+            .bind(next_run_at)
+            .execute(&**self)
+            .await?;
+        Ok(())
+    }
+
+    /// Keep the outcome of a backup job's latest run
+    pub async fn record_backup_run(
+        &self,
+        id: &Uuid,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE backup_jobs
+            SET last_run_at = CURRENT_TIMESTAMP, last_error = ?2
+            WHERE id = ?1
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(error)
+        .execute(&**self)
+        .await?;
+        Ok(())
+    }
+
+    /// Record a snapshot, forgetting the job's snapshots beyond the newest
+    /// `retain`
+    pub async fn insert_backup_snapshot(
+        &self,
+        snapshot: &BackupSnapshot,
+        retain: u64,
+    ) -> Result<(), sqlx::Error> {
+        let job_id = snapshot.job_id.to_string();
+        let mut tx = self.begin().await?;
+        sqlx::query(
+            r#"
+            INSERT INTO backup_snapshots
+                (job_id, version, height, files_uploaded, files_deleted, taken_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+        )
+        .bind(&job_id)
+        .bind(&snapshot.version)
+        .bind(snapshot.height as i64)
+        .bind(snapshot.files_uploaded as i64)
+        .bind(snapshot.files_deleted as i64)
+        .bind(snapshot.taken_at)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            r#"
+            DELETE FROM backup_snapshots
+            WHERE job_id = ?1 AND id NOT IN (
+                SELECT id FROM backup_snapshots WHERE job_id = ?1 ORDER BY id DESC LIMIT ?2
+            )
+            "#,
+        )
+        .bind(&job_id)
+        .bind(retain as i64)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await
+    }
+
+    /// Snapshots of a backup job, newest first
+    pub async fn list_backup_snapshots(
+        &self,
+        job_id: &Uuid,
+    ) -> Result<Vec<BackupSnapshot>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT job_id, version, height, files_uploaded, files_deleted, taken_at
+            FROM backup_snapshots
+            WHERE job_id = ?1
+            ORDER BY id DESC
+            "#,
+        )
+        .bind(job_id.to_string())
+        .fetch_all(&**self)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let height: i64 = row.get("height");
+                let files_uploaded: i64 = row.get("files_uploaded");
+                let files_deleted: i64 = row.get("files_deleted");
+                BackupSnapshot {
+                    job_id: *job_id,
+                    version: row.get("version"),
+                    height: height as u64,
+                    files_uploaded: files_uploaded as u64,
+                    files_deleted: files_deleted as u64,
+                    taken_at: row.get("taken_at"),
+                }
+            })
+            .collect())
+    }
+}
+
+fn backup_job_from_row(row: &sqlx::sqlite::SqliteRow) -> Option<BackupJob> {
+    let id: String = row.get("id");
+    let source_dir: String = row.get("source_dir");
+    let bucket_id: String = row.get("bucket_id");
+    let schedule: String = row.get("schedule");
+    let retain: i64 = row.get("retain");
+    Some(BackupJob {
+        id: Uuid::parse_str(&id).ok()?,
+        name: row.get("name"),
+        source_dir: PathBuf::from(source_dir),
+        bucket_id: Uuid::parse_str(&bucket_id).ok()?,
+        target_path: row.get("target_path"),
+        schedule: schedule.parse().ok()?,
+        retain: retain as u64,
+        created_at: row.get("created_at"),
+        next_run_at: row.get("next_run_at"),
+        last_run_at: row.get("last_run_at"),
+        last_error: row.get("last_error"),
+    })
+}
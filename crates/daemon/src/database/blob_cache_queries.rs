@@ -0,0 +1,69 @@
+use sqlx::Row;
+
+use crate::database::Database;
+
+/// A blob fetched on demand for an online-only bucket
+#[derive(Debug, Clone)]
+pub struct CachedBlob {
+    pub hash: String,
+    pub size: u64,
+}
+
+impl Database {
+    /// List cached blobs, least recently accessed first
+    pub async fn list_cached_blobs(&self) -> Result<Vec<CachedBlob>, sqlx::Error> {
+        let rows = sqlx::query("SELECT hash, size FROM blob_cache ORDER BY last_accessed ASC")
+            .fetch_all(&**self)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| CachedBlob {
+                hash: row.get("hash"),
+                size: row.get::<i64, _>("size") as u64,
+            })
+            .collect())
+    }
+
+    /// Record a blob that was just fetched on demand
+    pub async fn record_cached_blob(&self, hash: &str, size: u64) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO blob_cache (hash, size, fetched_at, last_accessed)
+            VALUES (?1, ?2, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)
+            ON CONFLICT(hash) DO UPDATE SET
+                size = excluded.size,
+                last_accessed = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(hash)
+        .bind(size as i64)
+        .execute(&**self)
+        .await?;
+        Ok(())
+    }
+
+    /// Mark a cached blob as just read
+    pub async fn touch_cached_blob(&self, hash: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE blob_cache SET last_accessed = CURRENT_TIMESTAMP WHERE hash = ?1")
+            .bind(hash)
+            .execute(&**self)
+            .await?;
+        Ok(())
+    }
+
+    /// Stop tracking blobs, e.g. once they are evicted or pinned by a bucket
+    /// that keeps all its content. Returns how many were tracked.
+    pub async fn forget_cached_blobs(&self, hashes: &[String]) -> Result<u64, sqlx::Error> {
+        let mut tx = self.begin().await?;
+        let mut removed = 0;
+        for hash in hashes {
+            removed += sqlx::query("DELETE FROM blob_cache WHERE hash = ?1")
+                .bind(hash)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+        }
+        tx.commit().await?;
+        Ok(removed)
+    }
+}
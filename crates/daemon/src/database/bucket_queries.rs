@@ -1,3 +1,4 @@
+use sqlx::Row;
 use time::OffsetDateTime;
 use uuid::Uuid;
 
@@ -100,6 +101,47 @@ impl Database {
             .collect())
     }
 
+    /// List every bucket from the latest bucket_log entries, for listings
+    /// paged by the caller
+    pub async fn list_all_buckets(
+        &self,
+        prefix: Option<&str>,
+    ) -> Result<Vec<BucketInfo>, sqlx::Error> {
+        let pattern = format!("{}%", prefix.unwrap_or_default());
+
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                bl.bucket_id,
+                bl.name,
+                bl.current_link,
+                MIN(bl.created_at) as created_at
+            FROM bucket_log bl
+            INNER JOIN (
+                SELECT bucket_id, MAX(height) as max_height
+                FROM bucket_log
+                GROUP BY bucket_id
+            ) latest ON bl.bucket_id = latest.bucket_id AND bl.height = latest.max_height
+            WHERE bl.name LIKE ?1
+            GROUP BY bl.bucket_id, bl.name, bl.current_link
+            "#,
+        )
+        .bind(pattern)
+        .fetch_all(&**self)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| BucketInfo {
+                id: Uuid::parse_str(row.get("bucket_id"))
+                    .expect("invalid bucket_id UUID in database"),
+                name: row.get("name"),
+                link: row.get::<DCid, _>("current_link").into(),
+                created_at: row.get("created_at"),
+            })
+            .collect())
+    }
+
     /// Get paginated bucket log entries for a specific bucket
     pub async fn get_bucket_logs(
         &self,
@@ -0,0 +1,111 @@
+use sqlx::Row;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::cdn_export::CdnExport;
+use crate::database::Database;
+
+impl Database {
+    /// A bucket's CDN export, if it has one
+    pub async fn cdn_export(&self, bucket_id: &Uuid) -> Result<Option<CdnExport>, sqlx::Error> {
+        let row = sqlx::query(
+            r#"
+            SELECT bucket_id, target, prefix, exported_version, exported_at
+            FROM bucket_cdn_exports
+            WHERE bucket_id = ?1
+            "#,
+        )
+        .bind(bucket_id.to_string())
+        .fetch_optional(&**self)
+        .await?;
+        Ok(row.as_ref().and_then(cdn_export_from_row))
+    }
+
+    /// All stored CDN exports
+    pub async fn list_cdn_exports(&self) -> Result<Vec<CdnExport>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT bucket_id, target, prefix, exported_version, exported_at
+            FROM bucket_cdn_exports
+            ORDER BY bucket_id
+            "#,
+        )
+        .fetch_all(&**self)
+        .await?;
+        Ok(rows.iter().filter_map(cdn_export_from_row).collect())
+    }
+
+    /// Insert or replace the CDN export of a bucket. Moving it to another
+    /// target or prefix forgets what was exported, so it's written again.
+    pub async fn set_cdn_export(
+        &self,
+        bucket_id: &Uuid,
+        target: &str,
+        prefix: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO bucket_cdn_exports (bucket_id, target, prefix, updated_at)
+            VALUES (?1, ?2, ?3, CURRENT_TIMESTAMP)
+            ON CONFLICT(bucket_id) DO UPDATE SET
+                exported_version = CASE
+                    WHEN target = excluded.target AND prefix = excluded.prefix
+                    THEN exported_version
+                END,
+                exported_at = CASE
+                    WHEN target = excluded.target AND prefix = excluded.prefix
+                    THEN exported_at
+                END,
+                target = excluded.target,
+                prefix = excluded.prefix,
+                updated_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(bucket_id.to_string())
+        .bind(target)
+        .bind(prefix)
+        .execute(&**self)
+        .await?;
+        Ok(())
+    }
+
+    /// Record the version a bucket's target now holds, None once withdrawn
+    pub async fn set_cdn_exported(
+        &self,
+        bucket_id: &Uuid,
+        version: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE bucket_cdn_exports
+            SET exported_version = ?2, exported_at = ?3
+            WHERE bucket_id = ?1
+            "#,
+        )
+        .bind(bucket_id.to_string())
+        .bind(version)
+        .bind(OffsetDateTime::now_utc())
+        .execute(&**self)
+        .await?;
+        Ok(())
+    }
+
+    /// Remove the CDN export of a bucket, returning whether one was set
+    pub async fn delete_cdn_export(&self, bucket_id: &Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM bucket_cdn_exports WHERE bucket_id = ?1")
+            .bind(bucket_id.to_string())
+            .execute(&**self)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+fn cdn_export_from_row(row: &sqlx::sqlite::SqliteRow) -> Option<CdnExport> {
+    Some(CdnExport {
+        bucket_id: Uuid::parse_str(row.get("bucket_id")).ok()?,
+        target: row.get("target"),
+        prefix: row.get("prefix"),
+        exported_version: row.get("exported_version"),
+        exported_at: row.get("exported_at"),
+    })
+}
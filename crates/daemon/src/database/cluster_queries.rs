@@ -0,0 +1,120 @@
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::database::Database;
+
+impl Database {
+    /// Claim a sync job for an instance until `claimed_until`. Returns false
+    /// if another instance holds an unexpired claim on it, or it finished
+    /// already.
+    pub async fn claim_sync_job(
+        &self,
+        job: &str,
+        bucket_id: &Uuid,
+        instance: &str,
+        claimed_until: i64,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO sync_jobs (job, bucket_id, claimed_by, claimed_until)
+            VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT(job) DO UPDATE SET
+                claimed_by = excluded.claimed_by,
+                claimed_until = excluded.claimed_until
+            WHERE sync_jobs.finished_at IS NULL AND sync_jobs.claimed_until < ?5
+            "#,
+        )
+        .bind(job)
+        .bind(bucket_id.to_string())
+        .bind(instance)
+        .bind(claimed_until)
+        .bind(OffsetDateTime::now_utc().unix_timestamp())
+        .execute(&**self)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Extend an instance's claim on a sync job. Returns false if the claim
+    /// was lost to another instance.
+    pub async fn renew_sync_job(
+        &self,
+        job: &str,
+        instance: &str,
+        claimed_until: i64,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE sync_jobs SET claimed_until = ?3
+            WHERE job = ?1 AND claimed_by = ?2 AND finished_at IS NULL
+            "#,
+        )
+        .bind(job)
+        .bind(instance)
+        .bind(claimed_until)
+        .execute(&**self)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record that an instance finished a sync job, and forget the jobs
+    /// that finished before `forget_before`
+    pub async fn finish_sync_job(
+        &self,
+        job: &str,
+        instance: &str,
+        forget_before: i64,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.begin().await?;
+        sqlx::query("UPDATE sync_jobs SET finished_at = ?3 WHERE job = ?1 AND claimed_by = ?2")
+            .bind(job)
+            .bind(instance)
+            .bind(OffsetDateTime::now_utc().unix_timestamp())
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM sync_jobs WHERE finished_at < ?1")
+            .bind(forget_before)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await
+    }
+
+    /// Give up an instance's claim on a sync job, so that any instance can
+    /// run it again
+    pub async fn release_sync_job(&self, job: &str, instance: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "DELETE FROM sync_jobs WHERE job = ?1 AND claimed_by = ?2 AND finished_at IS NULL",
+        )
+        .bind(job)
+        .bind(instance)
+        .execute(&**self)
+        .await?;
+        Ok(())
+    }
+
+    /// Take or renew a lease for an instance until `expires_at`. Returns
+    /// false if another instance holds it and it hasn't expired.
+    pub async fn acquire_lease(
+        &self,
+        name: &str,
+        instance: &str,
+        expires_at: i64,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO cluster_leases (name, holder, expires_at)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT(name) DO UPDATE SET
+                holder = excluded.holder,
+                expires_at = excluded.expires_at
+            WHERE cluster_leases.holder = excluded.holder OR cluster_leases.expires_at < ?4
+            "#,
+        )
+        .bind(name)
+        .bind(instance)
+        .bind(expires_at)
+        .bind(OffsetDateTime::now_utc().unix_timestamp())
+        .execute(&**self)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,109 @@
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::database::Database;
+use crate::deploy::DeployKey;
+
+impl Database {
+    /// Create a deploy key for a bucket
+    pub async fn create_deploy_key(
+        &self,
+        bucket_id: &Uuid,
+        path_prefix: &str,
+        name: Option<&str>,
+        secret: &str,
+    ) -> Result<DeployKey, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let row = sqlx::query(
+            r#"
+            INSERT INTO deploy_keys (id, bucket_id, path_prefix, name, secret)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            RETURNING created_at
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(bucket_id.to_string())
+        .bind(path_prefix)
+        .bind(name)
+        .bind(secret)
+        .fetch_one(&**self)
+        .await?;
+
+        Ok(DeployKey {
+            id,
+            bucket_id: *bucket_id,
+            path_prefix: path_prefix.to_string(),
+            name: name.map(str::to_string),
+            secret: secret.to_string(),
+            created_at: row.get("created_at"),
+            last_used_at: None,
+        })
+    }
+
+    /// Deploy keys, oldest first, optionally of one bucket
+    pub async fn list_deploy_keys(
+        &self,
+        bucket_id: Option<&Uuid>,
+    ) -> Result<Vec<DeployKey>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, bucket_id, path_prefix, name, secret, created_at, last_used_at
+            FROM deploy_keys
+            WHERE ?1 IS NULL OR bucket_id = ?1
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(bucket_id.map(|id| id.to_string()))
+        .fetch_all(&**self)
+        .await?;
+
+        Ok(rows.iter().filter_map(deploy_key_from_row).collect())
+    }
+
+    pub async fn get_deploy_key(&self, id: &Uuid) -> Result<Option<DeployKey>, sqlx::Error> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, bucket_id, path_prefix, name, secret, created_at, last_used_at
+            FROM deploy_keys
+            WHERE id = ?1
+            "#,
+        )
+        .bind(id.to_string())
+        .fetch_optional(&**self)
+        .await?;
+
+        Ok(row.as_ref().and_then(deploy_key_from_row))
+    }
+
+    /// Revoke a deploy key, returning whether it existed
+    pub async fn delete_deploy_key(&self, id: &Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM deploy_keys WHERE id = ?1")
+            .bind(id.to_string())
+            .execute(&**self)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record that a deploy key signed a write
+    pub async fn touch_deploy_key(&self, id: &Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE deploy_keys SET last_used_at = CURRENT_TIMESTAMP WHERE id = ?1")
+            .bind(id.to_string())
+            .execute(&**self)
+            .await?;
+        Ok(())
+    }
+}
+
+fn deploy_key_from_row(row: &sqlx::sqlite::SqliteRow) -> Option<DeployKey> {
+    let id: String = row.get("id");
+    let bucket_id: String = row.get("bucket_id");
+    Some(DeployKey {
+        id: Uuid::parse_str(&id).ok()?,
+        bucket_id: Uuid::parse_str(&bucket_id).ok()?,
+        path_prefix: row.get("path_prefix"),
+        name: row.get("name"),
+        secret: row.get("secret"),
+        created_at: row.get("created_at"),
+        last_used_at: row.get("last_used_at"),
+    })
+}
@@ -0,0 +1,247 @@
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::database::Database;
+use crate::drop_box::{parse_allowed_types, CaptchaConfig, DropLink, DropUpload, NewDropLink};
+
+impl Database {
+    /// Create a drop link with a fresh token
+    pub async fn create_drop_link(
+        &self,
+        link: NewDropLink<'_>,
+        token: &str,
+    ) -> Result<DropLink, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let allowed_types = (!link.allowed_types.is_empty()).then(|| link.allowed_types.join(","));
+        let row = sqlx::query(
+            r#"
+            INSERT INTO drop_links (id, token, bucket_id, directory, name, max_size,
+                allowed_types, captcha_verify_url, captcha_secret, expires_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+            RETURNING created_at
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(token)
+        .bind(link.bucket_id.to_string())
+        .bind(link.directory)
+        .bind(link.name)
+        .bind(link.max_size as i64)
+        .bind(allowed_types)
+        .bind(link.captcha.map(|captcha| captcha.verify_url.as_str()))
+        .bind(link.captcha.map(|captcha| captcha.secret.as_str()))
+        .bind(link.expires_at)
+        .fetch_one(&**self)
+        .await?;
+
+        Ok(DropLink {
+            id,
+            token: token.to_string(),
+            bucket_id: *link.bucket_id,
+            directory: link.directory.to_string(),
+            name: link.name.map(str::to_string),
+            max_size: link.max_size,
+            allowed_types: link.allowed_types.to_vec(),
+            captcha: link.captcha.cloned(),
+            created_at: row.get("created_at"),
+            expires_at: link.expires_at,
+        })
+    }
+
+    /// Drop links, oldest first, optionally of one bucket
+    pub async fn list_drop_links(
+        &self,
+        bucket_id: Option<&Uuid>,
+    ) -> Result<Vec<DropLink>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, token, bucket_id, directory, name, max_size, allowed_types,
+                captcha_verify_url, captcha_secret, created_at, expires_at
+            FROM drop_links
+            WHERE ?1 IS NULL OR bucket_id = ?1
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(bucket_id.map(|id| id.to_string()))
+        .fetch_all(&**self)
+        .await?;
+
+        Ok(rows.iter().filter_map(drop_link_from_row).collect())
+    }
+
+    pub async fn get_drop_link(&self, id: &Uuid) -> Result<Option<DropLink>, sqlx::Error> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, token, bucket_id, directory, name, max_size, allowed_types,
+                captcha_verify_url, captcha_secret, created_at, expires_at
+            FROM drop_links
+            WHERE id = ?1
+            "#,
+        )
+        .bind(id.to_string())
+        .fetch_optional(&**self)
+        .await?;
+
+        Ok(row.as_ref().and_then(drop_link_from_row))
+    }
+
+    /// The link a gateway upload's token belongs to
+    pub async fn get_drop_link_by_token(
+        &self,
+        token: &str,
+    ) -> Result<Option<DropLink>, sqlx::Error> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, token, bucket_id, directory, name, max_size, allowed_types,
+                captcha_verify_url, captcha_secret, created_at, expires_at
+            FROM drop_links
+            WHERE token = ?1
+            "#,
+        )
+        .bind(token)
+        .fetch_optional(&**self)
+        .await?;
+
+        Ok(row.as_ref().and_then(drop_link_from_row))
+    }
+
+    /// Revoke a drop link and discard its queued uploads, returning whether
+    /// it existed
+    pub async fn delete_drop_link(&self, id: &Uuid) -> Result<bool, sqlx::Error> {
+        let mut tx = self.begin().await?;
+        sqlx::query("DELETE FROM drop_uploads WHERE link_id = ?1")
+            .bind(id.to_string())
+            .execute(&mut *tx)
+            .await?;
+        let result = sqlx::query("DELETE FROM drop_links WHERE id = ?1")
+            .bind(id.to_string())
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Put an uploaded file in a link's moderation queue
+    pub async fn queue_drop_upload(
+        &self,
+        link_id: &Uuid,
+        file_name: &str,
+        mime_type: &str,
+        content: &[u8],
+    ) -> Result<DropUpload, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let row = sqlx::query(
+            r#"
+            INSERT INTO drop_uploads (id, link_id, file_name, mime_type, size, content)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            RETURNING created_at
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(link_id.to_string())
+        .bind(file_name)
+        .bind(mime_type)
+        .bind(content.len() as i64)
+        .bind(content)
+        .fetch_one(&**self)
+        .await?;
+
+        Ok(DropUpload {
+            id,
+            link_id: *link_id,
+            file_name: file_name.to_string(),
+            mime_type: mime_type.to_string(),
+            size: content.len() as u64,
+            created_at: row.get("created_at"),
+        })
+    }
+
+    /// Queued uploads, oldest first, optionally of one bucket
+    pub async fn list_drop_uploads(
+        &self,
+        bucket_id: Option<&Uuid>,
+    ) -> Result<Vec<DropUpload>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT u.id, u.link_id, u.file_name, u.mime_type, u.size, u.created_at
+            FROM drop_uploads u
+            JOIN drop_links l ON l.id = u.link_id
+            WHERE ?1 IS NULL OR l.bucket_id = ?1
+            ORDER BY u.created_at, u.id
+            "#,
+        )
+        .bind(bucket_id.map(|id| id.to_string()))
+        .fetch_all(&**self)
+        .await?;
+
+        Ok(rows.iter().filter_map(drop_upload_from_row).collect())
+    }
+
+    /// A queued upload with its contents
+    pub async fn get_drop_upload(
+        &self,
+        id: &Uuid,
+    ) -> Result<Option<(DropUpload, Vec<u8>)>, sqlx::Error> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, link_id, file_name, mime_type, size, created_at, content
+            FROM drop_uploads
+            WHERE id = ?1
+            "#,
+        )
+        .bind(id.to_string())
+        .fetch_optional(&**self)
+        .await?;
+
+        Ok(row.and_then(|row| Some((drop_upload_from_row(&row)?, row.get("content")))))
+    }
+
+    /// Take an upload out of the queue, returning whether it was there
+    pub async fn delete_drop_upload(&self, id: &Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM drop_uploads WHERE id = ?1")
+            .bind(id.to_string())
+            .execute(&**self)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+fn drop_link_from_row(row: &sqlx::sqlite::SqliteRow) -> Option<DropLink> {
+    let id: String = row.get("id");
+    let bucket_id: String = row.get("bucket_id");
+    let max_size: i64 = row.get("max_size");
+    let allowed_types: Option<String> = row.get("allowed_types");
+    let verify_url: Option<String> = row.get("captcha_verify_url");
+    let secret: Option<String> = row.get("captcha_secret");
+    Some(DropLink {
+        id: Uuid::parse_str(&id).ok()?,
+        token: row.get("token"),
+        bucket_id: Uuid::parse_str(&bucket_id).ok()?,
+        directory: row.get("directory"),
+        name: row.get("name"),
+        max_size: max_size.try_into().ok()?,
+        allowed_types: allowed_types
+            .as_deref()
+            .map(parse_allowed_types)
+            .unwrap_or_default(),
+        captcha: verify_url
+            .zip(secret)
+            .map(|(verify_url, secret)| CaptchaConfig { verify_url, secret }),
+        created_at: row.get("created_at"),
+        expires_at: row.get("expires_at"),
+    })
+}
+
+fn drop_upload_from_row(row: &sqlx::sqlite::SqliteRow) -> Option<DropUpload> {
+    let id: String = row.get("id");
+    let link_id: String = row.get("link_id");
+    let size: i64 = row.get("size");
+    Some(DropUpload {
+        id: Uuid::parse_str(&id).ok()?,
+        link_id: Uuid::parse_str(&link_id).ok()?,
+        file_name: row.get("file_name"),
+        mime_type: row.get("mime_type"),
+        size: size.try_into().ok()?,
+        created_at: row.get("created_at"),
+    })
+}
@@ -0,0 +1,189 @@
+//! At-rest encryption of the database with SQLCipher
+//!
+//! Blob content is encrypted by buckets, but the database holds bucket names,
+//! paths of mounts and the media index, and the peer address book in the
+//! clear. With `encrypt_database` set in the config, the daemon opens the
+//! database with SQLCipher under a key derived from the node secret, so
+//! `key.pem` is all it takes to open it and nothing else has to be stored. A
+//! plaintext database is encrypted in place the first time it is opened with
+//! a key.
+//!
+//! SQLite ignores the `key` pragma, so this needs the `sqlcipher` feature;
+//! opening with a key fails without it rather than leaving the file in the
+//! clear.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use common::prelude::SecretKey;
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{ConnectOptions, Connection};
+
+use crate::database::DatabaseSetupError;
+
+/// Context of the key derivation, so the key can't collide with other uses of
+/// the node secret
+const KEY_CONTEXT: &str = "jax-daemon 2026-10-16 database encryption key";
+
+/// Header of every plaintext SQLite file
+const PLAINTEXT_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// Key of an encrypted database
+#[derive(Clone, PartialEq, Eq)]
+pub struct DatabaseKey([u8; 32]);
+
+impl DatabaseKey {
+    /// Derive the database key of a node from its secret
+    pub fn derive(secret: &SecretKey) -> Self {
+        Self(blake3::derive_key(KEY_CONTEXT, &secret.to_bytes()))
+    }
+
+    /// The key as a SQLCipher raw key literal, used without a passphrase KDF
+    pub(crate) fn pragma_value(&self) -> String {
+        format!("\"x'{}'\"", hex::encode(self.0))
+    }
+}
+
+impl fmt::Debug for DatabaseKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("DatabaseKey(..)")
+    }
+}
+
+/// What a database file holds so far
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DatabaseFile {
+    /// Missing or empty, e.g. created by `jax init` but never opened
+    New,
+    Plaintext,
+    /// Anything else, which SQLCipher tells apart from garbage only with the key
+    Encrypted,
+}
+
+impl DatabaseFile {
+    pub(crate) fn inspect(path: &Path) -> Result<Self, std::io::Error> {
+        use std::io::Read;
+
+        let file = match std::fs::File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::New),
+            Err(e) => return Err(e),
+        };
+        let mut header = Vec::with_capacity(PLAINTEXT_HEADER.len());
+        file.take(PLAINTEXT_HEADER.len() as u64)
+            .read_to_end(&mut header)?;
+        Ok(if header.is_empty() {
+            Self::New
+        } else if header == PLAINTEXT_HEADER {
+            Self::Plaintext
+        } else {
+            Self::Encrypted
+        })
+    }
+}
+
+/// Encrypt the plaintext database at `path` in place
+///
+/// The database is exported into an encrypted copy next to it, which then
+/// replaces it, so an interrupted run leaves the plaintext database intact.
+pub(crate) async fn encrypt_in_place(
+    path: &Path,
+    key: &DatabaseKey,
+) -> Result<(), DatabaseSetupError> {
+    let encrypted = sidecar(path, "encrypting");
+    remove_if_exists(&encrypted)?;
+
+    // Attached databases are opened with the flags of the connection, and the
+    // encrypted copy doesn't exist yet
+    let mut conn = SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(true)
+        .connect()
+        .await
+        .map_err(DatabaseSetupError::Unavailable)?;
+    let export = async {
+        // Fold the write-ahead log in, so the export sees every commit
+        sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+            .execute(&mut conn)
+            .await?;
+        sqlx::query(&format!(
+            "ATTACH DATABASE '{}' AS encrypted KEY {}",
+            encrypted.display().to_string().replace('\'', "''"),
+            key.pragma_value()
+        ))
+        .execute(&mut conn)
+        .await?;
+        sqlx::query("SELECT sqlcipher_export('encrypted')")
+            .fetch_all(&mut conn)
+            .await?;
+        sqlx::query("DETACH DATABASE encrypted")
+            .execute(&mut conn)
+            .await?;
+        Ok::<_, sqlx::Error>(())
+    }
+    .await;
+    let closed = conn.close().await;
+    if let Err(e) = export.and(closed) {
+        let _ = std::fs::remove_file(&encrypted);
+        return Err(DatabaseSetupError::Encryption(e));
+    }
+
+    std::fs::rename(&encrypted, path).map_err(DatabaseSetupError::Io)?;
+    remove_if_exists(&sidecar(path, "wal"))?;
+    remove_if_exists(&sidecar(path, "shm"))?;
+    tracing::info!("Encrypted database at {}", path.display());
+    Ok(())
+}
+
+/// `db.sqlite` -> `db.sqlite-<suffix>`, the naming SQLite uses for its own files
+fn sidecar(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push("-");
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+fn remove_if_exists(path: &Path) -> Result<(), DatabaseSetupError> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(DatabaseSetupError::Io(e)),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_key_is_derived_from_secret() {
+        let secret = SecretKey::generate();
+        assert_eq!(DatabaseKey::derive(&secret), DatabaseKey::derive(&secret));
+        assert_ne!(
+            DatabaseKey::derive(&secret),
+            DatabaseKey::derive(&SecretKey::generate())
+        );
+        assert_eq!(
+            format!("{:?}", DatabaseKey::derive(&secret)),
+            "DatabaseKey(..)"
+        );
+    }
+
+    #[test]
+    fn test_inspect_database_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("db.sqlite");
+        let inspect = || DatabaseFile::inspect(&path).unwrap();
+        assert_eq!(inspect(), DatabaseFile::New);
+
+        std::fs::write(&path, b"").unwrap();
+        assert_eq!(inspect(), DatabaseFile::New);
+
+        let mut contents = PLAINTEXT_HEADER.to_vec();
+        contents.extend_from_slice(&[0; 84]);
+        std::fs::write(&path, &contents).unwrap();
+        assert_eq!(inspect(), DatabaseFile::Plaintext);
+
+        std::fs::write(&path, [0x5a; 100]).unwrap();
+        assert_eq!(inspect(), DatabaseFile::Encrypted);
+    }
+}
@@ -0,0 +1,60 @@
+use sqlx::Row;
+use uuid::Uuid;
+
+use common::crypto::SecretKey;
+
+use crate::database::Database;
+
+impl Database {
+    /// Load the temporary identity of every bucket followed through an
+    /// ephemeral share
+    pub async fn list_ephemeral_keys(&self) -> Result<Vec<(Uuid, SecretKey)>, sqlx::Error> {
+        let rows = sqlx::query("SELECT bucket_id, secret_key FROM ephemeral_keys")
+            .fetch_all(&**self)
+            .await?;
+
+        let mut keys = Vec::new();
+        for row in rows {
+            let bucket_id: String = row.get("bucket_id");
+            let secret_key: String = row.get("secret_key");
+            match (
+                Uuid::parse_str(&bucket_id),
+                SecretKey::from_hex(&secret_key),
+            ) {
+                (Ok(bucket_id), Ok(secret_key)) => keys.push((bucket_id, secret_key)),
+                _ => tracing::warn!("Ignoring invalid ephemeral key of bucket {}", bucket_id),
+            }
+        }
+        Ok(keys)
+    }
+
+    /// Record the temporary identity a bucket was shared with, replacing any
+    /// earlier one
+    pub async fn add_ephemeral_key(
+        &self,
+        bucket_id: &Uuid,
+        secret_key: &SecretKey,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO ephemeral_keys (bucket_id, secret_key)
+            VALUES (?1, ?2)
+            ON CONFLICT(bucket_id) DO UPDATE SET secret_key = excluded.secret_key
+            "#,
+        )
+        .bind(bucket_id.to_string())
+        .bind(secret_key.to_hex())
+        .execute(&**self)
+        .await?;
+        Ok(())
+    }
+
+    /// Forget the temporary identity of a bucket. Returns false if it had none.
+    pub async fn remove_ephemeral_key(&self, bucket_id: &Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM ephemeral_keys WHERE bucket_id = ?1")
+            .bind(bucket_id.to_string())
+            .execute(&**self)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::HashSet;
+
+use sqlx::Row;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::database::Database;
+
+/// A starred bucket, or a starred path in one
+#[derive(Debug, Clone)]
+pub struct Favorite {
+    pub bucket_id: Uuid,
+    /// Absolute path of the starred file or directory, None for the bucket
+    pub path: Option<String>,
+    pub created_at: OffsetDateTime,
+}
+
+impl Database {
+    /// Star a bucket, or a path in it. Returns false if it already was.
+    pub async fn add_favorite(
+        &self,
+        bucket_id: &Uuid,
+        path: Option<&str>,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO favorites (bucket_id, path, created_at)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT(bucket_id, path) DO NOTHING
+            "#,
+        )
+        .bind(bucket_id.to_string())
+        .bind(path.unwrap_or(""))
+        .bind(OffsetDateTime::now_utc().unix_timestamp())
+        .execute(&**self)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Unstar a bucket or path. Returns false if it wasn't starred.
+    pub async fn remove_favorite(
+        &self,
+        bucket_id: &Uuid,
+        path: Option<&str>,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM favorites WHERE bucket_id = ?1 AND path = ?2")
+            .bind(bucket_id.to_string())
+            .bind(path.unwrap_or(""))
+            .execute(&**self)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Favorites of one bucket, or of every bucket, most recently starred
+    /// first
+    pub async fn list_favorites(
+        &self,
+        bucket_id: Option<&Uuid>,
+    ) -> Result<Vec<Favorite>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT bucket_id, path, created_at
+            FROM favorites
+            WHERE ?1 IS NULL OR bucket_id = ?1
+            ORDER BY created_at DESC, bucket_id, path
+            "#,
+        )
+        .bind(bucket_id.map(|id| id.to_string()))
+        .fetch_all(&**self)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                let bucket_id: String = row.get("bucket_id");
+                let path: String = row.get("path");
+                let created_at: i64 = row.get("created_at");
+                Some(Favorite {
+                    bucket_id: Uuid::parse_str(&bucket_id).ok()?,
+                    path: (!path.is_empty()).then_some(path),
+                    created_at: OffsetDateTime::from_unix_timestamp(created_at)
+                        .unwrap_or(OffsetDateTime::UNIX_EPOCH),
+                })
+            })
+            .collect())
+    }
+
+    /// IDs of the starred buckets
+    pub async fn starred_buckets(&self) -> Result<HashSet<Uuid>, sqlx::Error> {
+        let rows = sqlx::query("SELECT bucket_id FROM favorites WHERE path = ''")
+            .fetch_all(&**self)
+            .await?;
+        Ok(rows
+            .iter()
+            .filter_map(|row| Uuid::parse_str(row.get("bucket_id")).ok())
+            .collect())
+    }
+
+    /// Starred paths of a bucket
+    pub async fn starred_paths(&self, bucket_id: &Uuid) -> Result<HashSet<String>, sqlx::Error> {
+        let rows = sqlx::query("SELECT path FROM favorites WHERE bucket_id = ?1 AND path != ''")
+            .bind(bucket_id.to_string())
+            .fetch_all(&**self)
+            .await?;
+        Ok(rows.iter().map(|row| row.get("path")).collect())
+    }
+}
@@ -0,0 +1,55 @@
+use sqlx::Row;
+use uuid::Uuid;
+
+use common::crypto::PublicKey;
+
+use crate::database::Database;
+
+impl Database {
+    /// Load all followed buckets and the node each is followed from
+    pub async fn list_followed_buckets(&self) -> Result<Vec<(Uuid, PublicKey)>, sqlx::Error> {
+        let rows = sqlx::query("SELECT bucket_id, source FROM followed_buckets")
+            .fetch_all(&**self)
+            .await?;
+
+        let mut followed = Vec::new();
+        for row in rows {
+            let bucket_id: String = row.get("bucket_id");
+            let source: String = row.get("source");
+            match (Uuid::parse_str(&bucket_id), PublicKey::from_hex(&source)) {
+                (Ok(bucket_id), Ok(source)) => followed.push((bucket_id, source)),
+                _ => tracing::warn!("Ignoring invalid followed bucket {}", bucket_id),
+            }
+        }
+        Ok(followed)
+    }
+
+    /// Record a followed bucket, replacing the node it is followed from
+    pub async fn follow_bucket(
+        &self,
+        bucket_id: &Uuid,
+        source: &PublicKey,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO followed_buckets (bucket_id, source)
+            VALUES (?1, ?2)
+            ON CONFLICT(bucket_id) DO UPDATE SET source = excluded.source
+            "#,
+        )
+        .bind(bucket_id.to_string())
+        .bind(source.to_hex())
+        .execute(&**self)
+        .await?;
+        Ok(())
+    }
+
+    /// Forget a followed bucket. Returns false if it was not followed.
+    pub async fn unfollow_bucket(&self, bucket_id: &Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM followed_buckets WHERE bucket_id = ?1")
+            .bind(bucket_id.to_string())
+            .execute(&**self)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::database::Database;
+use crate::gateway_policy::{GatewayMode, GatewayPolicy};
+
+/// Which buckets the gateway serves
+pub const GATEWAY_MODE_KEY: &str = "gateway_mode";
+
+impl Database {
+    /// The stored gateway mode, the default one if never set
+    pub async fn gateway_mode(&self) -> Result<GatewayMode, sqlx::Error> {
+        Ok(match self.get_setting(GATEWAY_MODE_KEY).await? {
+            Some(mode) => mode.parse().unwrap_or_else(|e| {
+                tracing::warn!("Ignoring stored gateway mode: {}", e);
+                GatewayMode::default()
+            }),
+            None => GatewayMode::default(),
+        })
+    }
+
+    /// Persist the gateway mode
+    pub async fn set_gateway_mode(&self, mode: GatewayMode) -> Result<(), sqlx::Error> {
+        self.set_setting(GATEWAY_MODE_KEY, mode.as_str()).await
+    }
+
+    /// Load all stored bucket gateway policies
+    pub async fn list_gateway_policies(&self) -> Result<HashMap<Uuid, GatewayPolicy>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT bucket_id, allowed, listings, noindex, serve_stale, daily_bandwidth_bytes
+            FROM gateway_bucket_policies
+            "#,
+        )
+        .fetch_all(&**self)
+        .await?;
+
+        let mut policies = HashMap::new();
+        for row in rows {
+            let bucket_id: String = row.get("bucket_id");
+            let Ok(bucket_id) = Uuid::parse_str(&bucket_id) else {
+                tracing::warn!(
+                    "Ignoring gateway policy with invalid bucket id {}",
+                    bucket_id
+                );
+                continue;
+            };
+            let daily_bandwidth_bytes: Option<i64> = row.get("daily_bandwidth_bytes");
+            policies.insert(
+                bucket_id,
+                GatewayPolicy {
+                    allowed: row.get("allowed"),
+                    listings: row.get("listings"),
+                    noindex: row.get("noindex"),
+                    serve_stale: row.get("serve_stale"),
+                    daily_bandwidth_bytes: daily_bandwidth_bytes.map(|bytes| bytes as u64),
+                },
+            );
+        }
+        Ok(policies)
+    }
+
+    /// Insert or replace the gateway policy of a bucket
+    pub async fn set_gateway_policy(
+        &self,
+        bucket_id: &Uuid,
+        policy: &GatewayPolicy,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO gateway_bucket_policies
+                (bucket_id, allowed, listings, noindex, serve_stale, daily_bandwidth_bytes,
+                 updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, CURRENT_TIMESTAMP)
+            ON CONFLICT(bucket_id) DO UPDATE SET
+                allowed = excluded.allowed,
+                listings = excluded.listings,
+                noindex = excluded.noindex,
+                serve_stale = excluded.serve_stale,
+                daily_bandwidth_bytes = excluded.daily_bandwidth_bytes,
+                updated_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(bucket_id.to_string())
+        .bind(policy.allowed)
+        .bind(policy.listings)
+        .bind(policy.noindex)
+        .bind(policy.serve_stale)
+        .bind(policy.daily_bandwidth_bytes.map(|bytes| bytes as i64))
+        .execute(&**self)
+        .await?;
+        Ok(())
+    }
+
+    /// Remove the gateway policy of a bucket, returning whether one was set
+    pub async fn delete_gateway_policy(&self, bucket_id: &Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM gateway_bucket_policies WHERE bucket_id = ?1")
+            .bind(bucket_id.to_string())
+            .execute(&**self)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
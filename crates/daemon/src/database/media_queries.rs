@@ -0,0 +1,145 @@
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::database::Database;
+
+/// A photo in a bucket, as recorded in the media index
+#[derive(Debug, Clone)]
+pub struct MediaEntry {
+    pub bucket_id: Uuid,
+    pub path: String,
+    pub mime_type: String,
+    pub captured_at: Option<i64>,
+    pub width: Option<u64>,
+    pub height: Option<u64>,
+}
+
+impl Database {
+    /// The bucket version the media index of a bucket was last read from
+    pub async fn media_index_version(
+        &self,
+        bucket_id: &Uuid,
+    ) -> Result<Option<String>, sqlx::Error> {
+        let row = sqlx::query("SELECT version FROM media_index_versions WHERE bucket_id = ?1")
+            .bind(bucket_id.to_string())
+            .fetch_optional(&**self)
+            .await?;
+        Ok(row.map(|row| row.get("version")))
+    }
+
+    /// Replace all media index entries of a bucket with those read from
+    /// `version`
+    pub async fn replace_media_index(
+        &self,
+        bucket_id: &Uuid,
+        version: &str,
+        entries: &[MediaEntry],
+    ) -> Result<(), sqlx::Error> {
+        let bucket_id_str = bucket_id.to_string();
+        let mut tx = self.begin().await?;
+
+        sqlx::query("DELETE FROM media_index WHERE bucket_id = ?1")
+            .bind(&bucket_id_str)
+            .execute(&mut *tx)
+            .await?;
+
+        for entry in entries {
+            sqlx::query(
+                r#"
+                INSERT INTO media_index (bucket_id, path, mime_type, captured_at, width, height)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                "#,
+            )
+            .bind(&bucket_id_str)
+            .bind(&entry.path)
+            .bind(&entry.mime_type)
+            .bind(entry.captured_at)
+            .bind(entry.width.map(|w| w as i64))
+            .bind(entry.height.map(|h| h as i64))
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO media_index_versions (bucket_id, version)
+            VALUES (?1, ?2)
+            ON CONFLICT(bucket_id) DO UPDATE SET
+                version = excluded.version,
+                updated_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(&bucket_id_str)
+        .bind(version)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await
+    }
+
+    /// Drop the media index of buckets that are no longer known
+    pub async fn prune_media_index(&self, keep: &[Uuid]) -> Result<u64, sqlx::Error> {
+        let known = sqlx::query("SELECT bucket_id FROM media_index_versions")
+            .fetch_all(&**self)
+            .await?;
+
+        let mut removed = 0;
+        for row in known {
+            let bucket_id: String = row.get("bucket_id");
+            if keep.iter().any(|id| id.to_string() == bucket_id) {
+                continue;
+            }
+            removed += sqlx::query("DELETE FROM media_index WHERE bucket_id = ?1")
+                .bind(&bucket_id)
+                .execute(&**self)
+                .await?
+                .rows_affected();
+            sqlx::query("DELETE FROM media_index_versions WHERE bucket_id = ?1")
+                .bind(&bucket_id)
+                .execute(&**self)
+                .await?;
+        }
+
+        Ok(removed)
+    }
+
+    /// Page through the photos of one bucket, or of every bucket, newest
+    /// capture date first and those without one last
+    pub async fn media_timeline(
+        &self,
+        bucket_id: Option<&Uuid>,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<MediaEntry>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT bucket_id, path, mime_type, captured_at, width, height
+            FROM media_index
+            WHERE ?1 IS NULL OR bucket_id = ?1
+            ORDER BY captured_at IS NULL, captured_at DESC, bucket_id, path
+            LIMIT ?2 OFFSET ?3
+            "#,
+        )
+        .bind(bucket_id.map(|id| id.to_string()))
+        .bind(limit as i64)
+        .bind(offset as i64)
+        .fetch_all(&**self)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let bucket_id: String = row.get("bucket_id");
+                MediaEntry {
+                    bucket_id: Uuid::parse_str(&bucket_id)
+                        .expect("invalid bucket_id UUID in database"),
+                    path: row.get("path"),
+                    mime_type: row.get("mime_type"),
+                    captured_at: row.get("captured_at"),
+                    width: row.get::<Option<i64>, _>("width").map(|w| w as u64),
+                    height: row.get::<Option<i64>, _>("height").map(|h| h as u64),
+                }
+            })
+            .collect())
+    }
+}
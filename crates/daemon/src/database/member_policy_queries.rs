@@ -0,0 +1,186 @@
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::database::Database;
+use crate::member_policy::{MemberPolicy, PendingShare};
+
+/// Member policy of buckets without one of their own, as JSON
+pub const MEMBER_POLICY_DEFAULT_KEY: &str = "member_policy_default";
+
+impl Database {
+    /// The default member policy
+    pub async fn default_member_policy(&self) -> Result<MemberPolicy, sqlx::Error> {
+        let value = self.get_setting(MEMBER_POLICY_DEFAULT_KEY).await?;
+        Ok(value
+            .and_then(|value| match serde_json::from_str(&value) {
+                Ok(policy) => Some(policy),
+                Err(e) => {
+                    tracing::warn!("Ignoring invalid default member policy: {}", e);
+                    None
+                }
+            })
+            .unwrap_or_default())
+    }
+
+    /// Replace the default member policy
+    pub async fn set_default_member_policy(
+        &self,
+        policy: &MemberPolicy,
+    ) -> Result<(), sqlx::Error> {
+        let value = serde_json::to_string(policy).expect("member policy serializes");
+        self.set_setting(MEMBER_POLICY_DEFAULT_KEY, &value).await
+    }
+
+    /// Member policy set for a bucket, if any
+    pub async fn bucket_member_policy(
+        &self,
+        bucket_id: &Uuid,
+    ) -> Result<Option<MemberPolicy>, sqlx::Error> {
+        let row = sqlx::query(
+            r#"
+            SELECT auto_pin, notify_members, require_approval
+            FROM bucket_member_policies
+            WHERE bucket_id = ?1
+            "#,
+        )
+        .bind(bucket_id.to_string())
+        .fetch_optional(&**self)
+        .await?;
+
+        Ok(row.map(|row| MemberPolicy {
+            auto_pin: row.get("auto_pin"),
+            notify_members: row.get("notify_members"),
+            require_approval: row.get("require_approval"),
+        }))
+    }
+
+    /// Member policy that applies to a bucket: its own or the default
+    pub async fn member_policy(&self, bucket_id: &Uuid) -> Result<MemberPolicy, sqlx::Error> {
+        match self.bucket_member_policy(bucket_id).await? {
+            Some(policy) => Ok(policy),
+            None => self.default_member_policy().await,
+        }
+    }
+
+    /// Insert or replace the member policy of a bucket
+    pub async fn set_bucket_member_policy(
+        &self,
+        bucket_id: &Uuid,
+        policy: &MemberPolicy,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO bucket_member_policies
+                (bucket_id, auto_pin, notify_members, require_approval, updated_at)
+            VALUES (?1, ?2, ?3, ?4, CURRENT_TIMESTAMP)
+            ON CONFLICT(bucket_id) DO UPDATE SET
+                auto_pin = excluded.auto_pin,
+                notify_members = excluded.notify_members,
+                require_approval = excluded.require_approval,
+                updated_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(bucket_id.to_string())
+        .bind(policy.auto_pin)
+        .bind(policy.notify_members)
+        .bind(policy.require_approval)
+        .execute(&**self)
+        .await?;
+        Ok(())
+    }
+
+    /// Remove the member policy of a bucket, returning whether one was set
+    pub async fn delete_bucket_member_policy(&self, bucket_id: &Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM bucket_member_policies WHERE bucket_id = ?1")
+            .bind(bucket_id.to_string())
+            .execute(&**self)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Hold a share for approval, replacing the role and path of one already
+    /// pending
+    pub async fn add_pending_share(
+        &self,
+        bucket_id: &Uuid,
+        peer_public_key: &str,
+        role: &str,
+        path: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO pending_shares (bucket_id, peer_public_key, role, path)
+            VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT(bucket_id, peer_public_key) DO UPDATE SET
+                role = excluded.role,
+                path = excluded.path
+            "#,
+        )
+        .bind(bucket_id.to_string())
+        .bind(peer_public_key)
+        .bind(role)
+        .bind(path)
+        .execute(&**self)
+        .await?;
+        Ok(())
+    }
+
+    /// Shares waiting for approval, oldest first, optionally of one bucket
+    pub async fn list_pending_shares(
+        &self,
+        bucket_id: Option<&Uuid>,
+    ) -> Result<Vec<PendingShare>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT bucket_id, peer_public_key, role, path, requested_at
+            FROM pending_shares
+            WHERE ?1 IS NULL OR bucket_id = ?1
+            ORDER BY requested_at
+            "#,
+        )
+        .bind(bucket_id.map(|id| id.to_string()))
+        .fetch_all(&**self)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|row| {
+                let bucket_id: String = row.get("bucket_id");
+                Some(PendingShare {
+                    bucket_id: Uuid::parse_str(&bucket_id).ok()?,
+                    peer_public_key: row.get("peer_public_key"),
+                    role: row.get("role"),
+                    path: row.get("path"),
+                    requested_at: row.get("requested_at"),
+                })
+            })
+            .collect())
+    }
+
+    /// Take a share off the pending list, returning it if it was there
+    pub async fn take_pending_share(
+        &self,
+        bucket_id: &Uuid,
+        peer_public_key: &str,
+    ) -> Result<Option<PendingShare>, sqlx::Error> {
+        let row = sqlx::query(
+            r#"
+            DELETE FROM pending_shares
+            WHERE bucket_id = ?1 AND peer_public_key = ?2
+            RETURNING role, path, requested_at
+            "#,
+        )
+        .bind(bucket_id.to_string())
+        .bind(peer_public_key)
+        .fetch_optional(&**self)
+        .await?;
+
+        Ok(row.map(|row| PendingShare {
+            bucket_id: *bucket_id,
+            peer_public_key: peer_public_key.to_string(),
+            role: row.get("role"),
+            path: row.get("path"),
+            requested_at: row.get("requested_at"),
+        }))
+    }
+}
@@ -1,13 +1,47 @@
+mod activity_queries;
+mod audit_queries;
+mod backup_queries;
+mod blob_cache_queries;
 pub mod bucket_log_provider;
 mod bucket_queries;
+mod cdn_export_queries;
+mod cluster_queries;
+mod deploy_key_queries;
+mod drop_link_queries;
+pub mod encryption;
+mod ephemeral_key_queries;
+mod favorite_queries;
+mod follow_queries;
+mod gateway_policy_queries;
+mod media_queries;
+mod member_policy_queries;
 pub mod models;
+mod peer_queries;
+mod publish_pipeline_queries;
+mod publish_queries;
+mod publish_scan_queries;
+mod remote_import_queries;
+mod replica_queries;
+mod retention_queries;
+mod settings_queries;
 mod sqlite;
+mod storage_policy_queries;
+mod storage_queries;
+mod sync_policy_queries;
 pub mod types;
+mod webhook_queries;
+mod workspace_queries;
 
 use std::ops::Deref;
 
 use sqlx::SqlitePool;
 
+pub use bucket_queries::BucketLogEntry;
+pub use encryption::DatabaseKey;
+pub use media_queries::MediaEntry;
+pub use storage_queries::{BlobRef, DedupStats};
+pub use workspace_queries::Workspace;
+
 #[derive(Clone, Debug)]
 pub struct Database(SqlitePool);
 
@@ -15,9 +49,13 @@ pub struct Database(SqlitePool);
 pub type DatabaseConnection = sqlx::SqliteConnection;
 
 impl Database {
-    pub async fn connect(database_url: &url::Url) -> Result<Self, DatabaseSetupError> {
+    /// Connect to a database, encrypted with `key` if given
+    pub async fn connect(
+        database_url: &url::Url,
+        key: Option<&DatabaseKey>,
+    ) -> Result<Self, DatabaseSetupError> {
         if database_url.scheme() == "sqlite" {
-            let db = sqlite::connect_sqlite(database_url).await?;
+            let db = sqlite::connect_sqlite(database_url, key).await?;
             sqlite::migrate_sqlite(&db).await?;
             return Ok(Database::new(db));
         }
@@ -50,4 +88,16 @@ pub enum DatabaseSetupError {
 
     #[error("requested database type was not recognized: {0}")]
     UnknownDbType(String),
+
+    #[error("database encryption needs a build with the `sqlcipher` feature")]
+    EncryptionUnsupported,
+
+    #[error("the database is encrypted; set `encrypt_database = true` in the config")]
+    Encrypted,
+
+    #[error("unable to open the encrypted database (wrong key?): {0}")]
+    Encryption(sqlx::Error),
+
+    #[error("unable to access the database file: {0}")]
+    Io(std::io::Error),
 }
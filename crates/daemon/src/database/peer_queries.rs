@@ -0,0 +1,107 @@
+use sqlx::Row;
+use time::OffsetDateTime;
+
+use common::crypto::PublicKey;
+
+use crate::database::Database;
+use crate::peers::{KnownPeer, PeerTicket};
+
+impl Database {
+    /// Load the address book
+    pub async fn list_known_peers(&self) -> Result<Vec<KnownPeer>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT node_id, relay_url, direct_addresses, last_seen, created_at
+            FROM known_peers
+            ORDER BY created_at
+            "#,
+        )
+        .fetch_all(&**self)
+        .await?;
+
+        let mut peers = Vec::with_capacity(rows.len());
+        for row in rows {
+            let node_id: String = row.get("node_id");
+            let Ok(node_id) = PublicKey::from_hex(&node_id) else {
+                tracing::warn!("Ignoring known peer with invalid node id {}", node_id);
+                continue;
+            };
+            let relay_url: Option<String> = row.get("relay_url");
+            let direct_addresses: String = row.get("direct_addresses");
+            peers.push(KnownPeer {
+                ticket: PeerTicket {
+                    node_id,
+                    relay_url: relay_url.and_then(|url| url.parse().ok()),
+                    direct_addresses: direct_addresses
+                        .split(',')
+                        .filter_map(|addr| addr.parse().ok())
+                        .collect(),
+                },
+                last_seen: row.get("last_seen"),
+                added_at: row.get("created_at"),
+            });
+        }
+        Ok(peers)
+    }
+
+    /// Add a peer to the address book, or replace its addresses with those of
+    /// the ticket if it is already known
+    pub async fn add_known_peer(&self, ticket: &PeerTicket) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO known_peers (node_id, relay_url, direct_addresses)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT(node_id) DO UPDATE SET
+                relay_url = COALESCE(excluded.relay_url, known_peers.relay_url),
+                direct_addresses = CASE
+                    WHEN excluded.direct_addresses = '' THEN known_peers.direct_addresses
+                    ELSE excluded.direct_addresses
+                END
+            "#,
+        )
+        .bind(ticket.node_id.to_hex())
+        .bind(ticket.relay_url.as_ref().map(|url| url.to_string()))
+        .bind(join_addresses(ticket))
+        .execute(&**self)
+        .await?;
+        Ok(())
+    }
+
+    /// Record where a known peer was just reached
+    pub async fn record_peer_seen(&self, ticket: &PeerTicket) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE known_peers SET
+                relay_url = COALESCE(?2, relay_url),
+                direct_addresses = CASE WHEN ?3 = '' THEN direct_addresses ELSE ?3 END,
+                last_seen = ?4
+            WHERE node_id = ?1
+            "#,
+        )
+        .bind(ticket.node_id.to_hex())
+        .bind(ticket.relay_url.as_ref().map(|url| url.to_string()))
+        .bind(join_addresses(ticket))
+        .bind(OffsetDateTime::now_utc())
+        .execute(&**self)
+        .await?;
+        Ok(())
+    }
+
+    /// Remove a peer from the address book, returning whether it was known
+    pub async fn remove_known_peer(&self, node_id: &PublicKey) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM known_peers WHERE node_id = ?1")
+            .bind(node_id.to_hex())
+            .execute(&**self)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+fn join_addresses(ticket: &PeerTicket) -> String {
+    ticket
+        .direct_addresses
+        .iter()
+        .map(|addr| addr.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
@@ -0,0 +1,158 @@
+use std::collections::BTreeMap;
+
+use sqlx::Row;
+use uuid::Uuid;
+
+use common::linked_data::Hash;
+
+use crate::database::Database;
+use crate::publish_pipeline::{PublishBuild, PublishPipeline};
+
+/// Builds listed per bucket, newest first
+const BUILDS_LISTED: i64 = 20;
+
+impl Database {
+    /// Publish pipeline of a bucket and its site bucket, if a pipeline is set
+    pub async fn publish_pipeline(
+        &self,
+        bucket_id: &Uuid,
+    ) -> Result<Option<(PublishPipeline, Option<Uuid>)>, sqlx::Error> {
+        let row = sqlx::query(
+            r#"
+            SELECT builder, output, vars, site_bucket_id
+            FROM bucket_publish_pipelines
+            WHERE bucket_id = ?1
+            "#,
+        )
+        .bind(bucket_id.to_string())
+        .fetch_optional(&**self)
+        .await?;
+
+        Ok(row.map(|row| {
+            let vars: String = row.get("vars");
+            let vars: BTreeMap<String, String> = serde_json::from_str(&vars).unwrap_or_else(|e| {
+                tracing::warn!(
+                    "Ignoring invalid pipeline variables of {}: {}",
+                    bucket_id,
+                    e
+                );
+                BTreeMap::new()
+            });
+            let site_bucket_id: Option<String> = row.get("site_bucket_id");
+            (
+                PublishPipeline {
+                    builder: row.get("builder"),
+                    output: row.get("output"),
+                    vars,
+                },
+                site_bucket_id.and_then(|id| Uuid::parse_str(&id).ok()),
+            )
+        }))
+    }
+
+    /// Insert or replace the publish pipeline of a bucket, keeping its site
+    /// bucket
+    pub async fn set_publish_pipeline(
+        &self,
+        bucket_id: &Uuid,
+        pipeline: &PublishPipeline,
+    ) -> Result<(), sqlx::Error> {
+        let vars = serde_json::to_string(&pipeline.vars).expect("pipeline variables serialize");
+        sqlx::query(
+            r#"
+            INSERT INTO bucket_publish_pipelines (bucket_id, builder, output, vars, updated_at)
+            VALUES (?1, ?2, ?3, ?4, CURRENT_TIMESTAMP)
+            ON CONFLICT(bucket_id) DO UPDATE SET
+                builder = excluded.builder,
+                output = excluded.output,
+                vars = excluded.vars,
+                updated_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(bucket_id.to_string())
+        .bind(&pipeline.builder)
+        .bind(&pipeline.output)
+        .bind(vars)
+        .execute(&**self)
+        .await?;
+        Ok(())
+    }
+
+    /// Record the bucket a pipeline's output is published as
+    pub async fn set_site_bucket(
+        &self,
+        bucket_id: &Uuid,
+        site_bucket_id: &Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE bucket_publish_pipelines SET site_bucket_id = ?2 WHERE bucket_id = ?1")
+            .bind(bucket_id.to_string())
+            .bind(site_bucket_id.to_string())
+            .execute(&**self)
+            .await?;
+        Ok(())
+    }
+
+    /// Remove the publish pipeline of a bucket, returning whether one was
+    /// set. Its site bucket and builds stay.
+    pub async fn delete_publish_pipeline(&self, bucket_id: &Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM bucket_publish_pipelines WHERE bucket_id = ?1")
+            .bind(bucket_id.to_string())
+            .execute(&**self)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record a build of a version of a bucket
+    pub async fn record_publish_build(
+        &self,
+        bucket_id: &Uuid,
+        source_version: &Hash,
+        site_version: Option<&Hash>,
+        log: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO publish_builds (bucket_id, source_version, site_version, succeeded, log)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            "#,
+        )
+        .bind(bucket_id.to_string())
+        .bind(source_version.to_string())
+        .bind(site_version.map(|hash| hash.to_string()))
+        .bind(site_version.is_some())
+        .bind((!log.is_empty()).then_some(log))
+        .execute(&**self)
+        .await?;
+        Ok(())
+    }
+
+    /// The latest builds of a bucket, newest first
+    pub async fn list_publish_builds(
+        &self,
+        bucket_id: &Uuid,
+    ) -> Result<Vec<PublishBuild>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT source_version, site_version, succeeded, log, created_at
+            FROM publish_builds
+            WHERE bucket_id = ?1
+            ORDER BY id DESC
+            LIMIT ?2
+            "#,
+        )
+        .bind(bucket_id.to_string())
+        .bind(BUILDS_LISTED)
+        .fetch_all(&**self)
+        .await?;
+        Ok(rows
+            .iter()
+            .map(|row| PublishBuild {
+                source_version: row.get("source_version"),
+                site_version: row.get("site_version"),
+                succeeded: row.get("succeeded"),
+                log: row.get("log"),
+                created_at: row.get("created_at"),
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,166 @@
+use sqlx::Row;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use common::linked_data::{Hash, Link};
+
+use crate::database::types::DCid;
+use crate::database::Database;
+
+impl Database {
+    /// The gateway link token of a bucket, if its link was ever rotated
+    pub async fn gateway_link(&self, bucket_id: &Uuid) -> Result<Option<String>, sqlx::Error> {
+        let row = sqlx::query("SELECT token FROM gateway_links WHERE bucket_id = ?1")
+            .bind(bucket_id.to_string())
+            .fetch_optional(&**self)
+            .await?;
+        Ok(row.map(|row| row.get("token")))
+    }
+
+    /// The bucket served at a gateway link token
+    pub async fn bucket_for_gateway_link(&self, token: &str) -> Result<Option<Uuid>, sqlx::Error> {
+        let row = sqlx::query("SELECT bucket_id FROM gateway_links WHERE token = ?1")
+            .bind(token)
+            .fetch_optional(&**self)
+            .await?;
+        Ok(row.and_then(|row| Uuid::parse_str(row.get("bucket_id")).ok()))
+    }
+
+    /// Replace a bucket's gateway link token
+    pub async fn set_gateway_link(&self, bucket_id: &Uuid, token: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO gateway_links (bucket_id, token, created_at)
+            VALUES (?1, ?2, CURRENT_TIMESTAMP)
+            ON CONFLICT(bucket_id) DO UPDATE SET
+                token = excluded.token,
+                created_at = excluded.created_at
+            "#,
+        )
+        .bind(bucket_id.to_string())
+        .bind(token)
+        .execute(&**self)
+        .await?;
+        Ok(())
+    }
+
+    /// Stop serving every published version of a bucket.
+    /// Returns how many versions were published.
+    pub async fn unpublish_versions(&self, bucket_id: &Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE bucket_log SET published = FALSE WHERE bucket_id = ?1 AND published = TRUE",
+        )
+        .bind(bucket_id.to_string())
+        .execute(&**self)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Published versions of a bucket with their heights, newest first
+    pub async fn published_versions(
+        &self,
+        bucket_id: &Uuid,
+    ) -> Result<Vec<(Link, u64)>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT current_link, height FROM bucket_log
+            WHERE bucket_id = ?1 AND published = TRUE
+            ORDER BY height DESC
+            "#,
+        )
+        .bind(bucket_id.to_string())
+        .fetch_all(&**self)
+        .await?;
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let link: Link = row.get::<DCid, _>("current_link").into();
+                (link, row.get::<i64, _>("height") as u64)
+            })
+            .collect())
+    }
+
+    /// The published version of a bucket at this height of its log
+    pub async fn published_version_at_height(
+        &self,
+        bucket_id: &Uuid,
+        height: u64,
+    ) -> Result<Option<Link>, sqlx::Error> {
+        let row = sqlx::query(
+            r#"
+            SELECT current_link FROM bucket_log
+            WHERE bucket_id = ?1 AND height = ?2 AND published = TRUE
+            LIMIT 1
+            "#,
+        )
+        .bind(bucket_id.to_string())
+        .bind(height as i64)
+        .fetch_optional(&**self)
+        .await?;
+        Ok(row.map(|row| row.get::<DCid, _>("current_link").into()))
+    }
+
+    /// The latest version of a bucket published by this node's clock at
+    /// `time`, i.e. recorded at or before it
+    pub async fn published_version_as_of(
+        &self,
+        bucket_id: &Uuid,
+        time: OffsetDateTime,
+    ) -> Result<Option<Link>, sqlx::Error> {
+        // Compared here rather than in SQL, where timestamps are text
+        let rows = sqlx::query(
+            r#"
+            SELECT current_link, created_at FROM bucket_log
+            WHERE bucket_id = ?1 AND published = TRUE
+            ORDER BY height DESC
+            "#,
+        )
+        .bind(bucket_id.to_string())
+        .fetch_all(&**self)
+        .await?;
+        Ok(rows
+            .iter()
+            .find(|row| row.get::<OffsetDateTime, _>("created_at") <= time)
+            .map(|row| row.get::<DCid, _>("current_link").into()))
+    }
+
+    /// Whether a version of a bucket with this hash is published. Versions
+    /// are matched by hash, since links requested by hash carry no codec.
+    pub async fn is_published_version(
+        &self,
+        bucket_id: &Uuid,
+        hash: &Hash,
+    ) -> Result<bool, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT current_link FROM bucket_log WHERE bucket_id = ?1 AND published = TRUE",
+        )
+        .bind(bucket_id.to_string())
+        .fetch_all(&**self)
+        .await?;
+        Ok(rows.iter().any(|row| {
+            let link: Link = row.get::<DCid, _>("current_link").into();
+            link.hash() == *hash
+        }))
+    }
+
+    /// When this node recorded the version of a bucket with this hash, the
+    /// last time anything in that version can have changed
+    pub async fn version_recorded_at(
+        &self,
+        bucket_id: &Uuid,
+        hash: &Hash,
+    ) -> Result<Option<OffsetDateTime>, sqlx::Error> {
+        let rows =
+            sqlx::query("SELECT current_link, created_at FROM bucket_log WHERE bucket_id = ?1")
+                .bind(bucket_id.to_string())
+                .fetch_all(&**self)
+                .await?;
+        Ok(rows
+            .iter()
+            .find(|row| {
+                let link: Link = row.get::<DCid, _>("current_link").into();
+                link.hash() == *hash
+            })
+            .map(|row| row.get("created_at")))
+    }
+}
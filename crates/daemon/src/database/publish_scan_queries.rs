@@ -0,0 +1,130 @@
+use sqlx::Row;
+use uuid::Uuid;
+
+use common::linked_data::{Hash, Link};
+
+use crate::database::types::DCid;
+use crate::database::Database;
+use crate::publish_scan::{PublishScan, ScanResult, Verdict};
+
+impl Database {
+    /// Record the scanners' verdicts on a version of a bucket
+    pub async fn record_publish_scans(
+        &self,
+        bucket_id: &Uuid,
+        version: &Hash,
+        results: &[ScanResult],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.begin().await?;
+        for result in results {
+            sqlx::query(
+                r#"
+                INSERT INTO publish_scans (bucket_id, version, scanner, verdict, detail)
+                VALUES (?1, ?2, ?3, ?4, ?5)
+                "#,
+            )
+            .bind(bucket_id.to_string())
+            .bind(version.to_string())
+            .bind(&result.scanner)
+            .bind(result.verdict.as_str())
+            .bind(&result.detail)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    /// Scan results of a bucket, newest first
+    pub async fn list_publish_scans(
+        &self,
+        bucket_id: &Uuid,
+    ) -> Result<Vec<PublishScan>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT version, scanner, verdict, detail, created_at
+            FROM publish_scans
+            WHERE bucket_id = ?1
+            ORDER BY id DESC
+            "#,
+        )
+        .bind(bucket_id.to_string())
+        .fetch_all(&**self)
+        .await?;
+        Ok(rows.iter().filter_map(publish_scan_from_row).collect())
+    }
+
+    /// The recorded verdicts on a version of a bucket, empty if it was never
+    /// scanned
+    pub async fn publish_scans_of(
+        &self,
+        bucket_id: &Uuid,
+        version: &Hash,
+    ) -> Result<Vec<ScanResult>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT version, scanner, verdict, detail, created_at
+            FROM publish_scans
+            WHERE bucket_id = ?1 AND version = ?2
+            "#,
+        )
+        .bind(bucket_id.to_string())
+        .bind(version.to_string())
+        .fetch_all(&**self)
+        .await?;
+        Ok(rows
+            .iter()
+            .filter_map(publish_scan_from_row)
+            .map(|scan| scan.result)
+            .collect())
+    }
+
+    /// Stop serving the published version of a bucket with this hash.
+    /// Versions are matched by hash, like [`Database::is_published_version`].
+    pub async fn unpublish_version(
+        &self,
+        bucket_id: &Uuid,
+        hash: &Hash,
+    ) -> Result<(), sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT current_link FROM bucket_log WHERE bucket_id = ?1 AND published = TRUE",
+        )
+        .bind(bucket_id.to_string())
+        .fetch_all(&**self)
+        .await?;
+        for row in rows {
+            let stored: DCid = row.get("current_link");
+            let link: Link = stored.into();
+            if link.hash() != *hash {
+                continue;
+            }
+            sqlx::query(
+                "UPDATE bucket_log SET published = FALSE WHERE bucket_id = ?1 AND current_link = ?2",
+            )
+            .bind(bucket_id.to_string())
+            .bind(stored)
+            .execute(&**self)
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+fn publish_scan_from_row(row: &sqlx::sqlite::SqliteRow) -> Option<PublishScan> {
+    let verdict: String = row.get("verdict");
+    let verdict = match verdict.parse::<Verdict>() {
+        Ok(verdict) => verdict,
+        Err(e) => {
+            tracing::warn!("Ignoring publish scan: {}", e);
+            return None;
+        }
+    };
+    Some(PublishScan {
+        version: row.get("version"),
+        result: ScanResult {
+            scanner: row.get("scanner"),
+            verdict,
+            detail: row.get("detail"),
+        },
+        created_at: row.get("created_at"),
+    })
+}
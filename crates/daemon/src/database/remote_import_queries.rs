@@ -0,0 +1,114 @@
+use std::collections::BTreeMap;
+
+use sqlx::Row;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::database::Database;
+use crate::remote_import::RemoteImport;
+
+impl Database {
+    /// The import of a source URL, if it was imported before
+    pub async fn remote_import(&self, source: &str) -> Result<Option<RemoteImport>, sqlx::Error> {
+        let row = sqlx::query(
+            r#"
+            SELECT bucket_id, source, imported_at
+            FROM remote_imports
+            WHERE source = ?1
+            "#,
+        )
+        .bind(source)
+        .fetch_optional(&**self)
+        .await?;
+        Ok(row.as_ref().and_then(remote_import_from_row))
+    }
+
+    /// Record that a bucket is imported from a source URL
+    pub async fn insert_remote_import(
+        &self,
+        bucket_id: &Uuid,
+        source: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("INSERT INTO remote_imports (bucket_id, source) VALUES (?1, ?2)")
+            .bind(bucket_id.to_string())
+            .bind(source)
+            .execute(&**self)
+            .await?;
+        Ok(())
+    }
+
+    /// Record that an import of a bucket finished
+    pub async fn set_remote_imported(&self, bucket_id: &Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE remote_imports SET imported_at = ?2 WHERE bucket_id = ?1")
+            .bind(bucket_id.to_string())
+            .bind(OffsetDateTime::now_utc())
+            .execute(&**self)
+            .await?;
+        Ok(())
+    }
+
+    /// Fingerprints of the objects imported into a bucket, by path
+    pub async fn remote_import_objects(
+        &self,
+        bucket_id: &Uuid,
+    ) -> Result<BTreeMap<String, String>, sqlx::Error> {
+        let rows =
+            sqlx::query("SELECT path, fingerprint FROM remote_import_objects WHERE bucket_id = ?1")
+                .bind(bucket_id.to_string())
+                .fetch_all(&**self)
+                .await?;
+        Ok(rows
+            .iter()
+            .map(|row| (row.get("path"), row.get("fingerprint")))
+            .collect())
+    }
+
+    /// Record the fingerprints of objects now in a bucket
+    pub async fn set_remote_import_objects(
+        &self,
+        bucket_id: &Uuid,
+        objects: &[(String, String)],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.begin().await?;
+        for (path, fingerprint) in objects {
+            sqlx::query(
+                r#"
+                INSERT INTO remote_import_objects (bucket_id, path, fingerprint)
+                VALUES (?1, ?2, ?3)
+                ON CONFLICT(bucket_id, path) DO UPDATE SET fingerprint = excluded.fingerprint
+                "#,
+            )
+            .bind(bucket_id.to_string())
+            .bind(path)
+            .bind(fingerprint)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    /// Forget objects removed from a bucket
+    pub async fn delete_remote_import_objects(
+        &self,
+        bucket_id: &Uuid,
+        paths: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.begin().await?;
+        for path in paths {
+            sqlx::query("DELETE FROM remote_import_objects WHERE bucket_id = ?1 AND path = ?2")
+                .bind(bucket_id.to_string())
+                .bind(path)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await
+    }
+}
+
+fn remote_import_from_row(row: &sqlx::sqlite::SqliteRow) -> Option<RemoteImport> {
+    Some(RemoteImport {
+        bucket_id: Uuid::parse_str(row.get("bucket_id")).ok()?,
+        source: row.get("source"),
+        imported_at: row.get("imported_at"),
+    })
+}
@@ -0,0 +1,52 @@
+use sqlx::Row;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use common::linked_data::Link;
+
+use crate::database::types::DCid;
+use crate::database::Database;
+use crate::replica::SyncedVersion;
+
+/// Latest published entry of each bucket's log; heights are unique per bucket
+const LATEST_PUBLISHED: &str = r#"
+    SELECT bucket_id, name, current_link, height, created_at FROM bucket_log AS entry
+    WHERE published = TRUE
+    AND height = (
+        SELECT MAX(height) FROM bucket_log
+        WHERE bucket_id = entry.bucket_id AND published = TRUE
+    )
+"#;
+
+impl Database {
+    /// The latest published version of every bucket with one, by name
+    pub async fn synced_versions(&self) -> Result<Vec<SyncedVersion>, sqlx::Error> {
+        let rows = sqlx::query(&format!("{} ORDER BY name, bucket_id", LATEST_PUBLISHED))
+            .fetch_all(&**self)
+            .await?;
+        Ok(rows.iter().filter_map(synced_version_from_row).collect())
+    }
+
+    /// The latest published version of a bucket
+    pub async fn synced_version(
+        &self,
+        bucket_id: &Uuid,
+    ) -> Result<Option<SyncedVersion>, sqlx::Error> {
+        let row = sqlx::query(&format!("{} AND bucket_id = ?1", LATEST_PUBLISHED))
+            .bind(bucket_id.to_string())
+            .fetch_optional(&**self)
+            .await?;
+        Ok(row.as_ref().and_then(synced_version_from_row))
+    }
+}
+
+fn synced_version_from_row(row: &sqlx::sqlite::SqliteRow) -> Option<SyncedVersion> {
+    let link: Link = row.get::<DCid, _>("current_link").into();
+    Some(SyncedVersion {
+        bucket_id: Uuid::parse_str(row.get("bucket_id")).ok()?,
+        name: row.get("name"),
+        version: link.hash().to_string(),
+        height: row.get::<i64, _>("height") as u64,
+        synced_at: row.get::<OffsetDateTime, _>("created_at"),
+    })
+}
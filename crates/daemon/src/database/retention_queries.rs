@@ -0,0 +1,180 @@
+use std::collections::BTreeSet;
+
+use sqlx::Row;
+use uuid::Uuid;
+
+use common::linked_data::Link;
+
+use crate::database::Database;
+use crate::retention::{Keep, RetentionPolicy};
+
+impl Database {
+    /// Retention policy of a bucket, if one is set
+    pub async fn retention_policy(
+        &self,
+        bucket_id: &Uuid,
+    ) -> Result<Option<RetentionPolicy>, sqlx::Error> {
+        let row = sqlx::query(
+            r#"
+            SELECT hourly, daily, weekly, monthly, infrequent_access_after, archive_after
+            FROM bucket_retention_policies
+            WHERE bucket_id = ?1
+            "#,
+        )
+        .bind(bucket_id.to_string())
+        .fetch_optional(&**self)
+        .await?;
+
+        Ok(row.as_ref().map(policy_from_row))
+    }
+
+    /// All stored retention policies
+    pub async fn list_retention_policies(
+        &self,
+    ) -> Result<Vec<(Uuid, RetentionPolicy)>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT bucket_id, hourly, daily, weekly, monthly, infrequent_access_after,
+                archive_after
+            FROM bucket_retention_policies
+            ORDER BY bucket_id
+            "#,
+        )
+        .fetch_all(&**self)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|row| {
+                let bucket_id: String = row.get("bucket_id");
+                Some((Uuid::parse_str(&bucket_id).ok()?, policy_from_row(row)))
+            })
+            .collect())
+    }
+
+    /// Insert or replace the retention policy of a bucket
+    pub async fn set_retention_policy(
+        &self,
+        bucket_id: &Uuid,
+        policy: &RetentionPolicy,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO bucket_retention_policies
+                (bucket_id, hourly, daily, weekly, monthly, infrequent_access_after,
+                archive_after, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, CURRENT_TIMESTAMP)
+            ON CONFLICT(bucket_id) DO UPDATE SET
+                hourly = excluded.hourly,
+                daily = excluded.daily,
+                weekly = excluded.weekly,
+                monthly = excluded.monthly,
+                infrequent_access_after = excluded.infrequent_access_after,
+                archive_after = excluded.archive_after,
+                updated_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(bucket_id.to_string())
+        .bind(policy.hourly.map(keep_to_column))
+        .bind(policy.daily.map(keep_to_column))
+        .bind(policy.weekly.map(keep_to_column))
+        .bind(policy.monthly.map(keep_to_column))
+        .bind(policy.infrequent_access_after.map(i64::from))
+        .bind(policy.archive_after.map(i64::from))
+        .execute(&**self)
+        .await?;
+        Ok(())
+    }
+
+    /// Remove the retention policy of a bucket, returning whether one was set.
+    /// Versions already pruned stay pruned.
+    pub async fn delete_retention_policy(&self, bucket_id: &Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM bucket_retention_policies WHERE bucket_id = ?1")
+            .bind(bucket_id.to_string())
+            .execute(&**self)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Heights of the versions of a bucket whose content was pruned
+    pub async fn pruned_versions(&self, bucket_id: &Uuid) -> Result<BTreeSet<u64>, sqlx::Error> {
+        let rows = sqlx::query("SELECT height FROM pruned_versions WHERE bucket_id = ?1")
+            .bind(bucket_id.to_string())
+            .fetch_all(&**self)
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| row.get::<i64, _>("height") as u64)
+            .collect())
+    }
+
+    /// Whether the content of a bucket's version at `height` was pruned
+    pub async fn is_version_pruned(
+        &self,
+        bucket_id: &Uuid,
+        height: u64,
+    ) -> Result<bool, sqlx::Error> {
+        let row = sqlx::query("SELECT 1 FROM pruned_versions WHERE bucket_id = ?1 AND height = ?2")
+            .bind(bucket_id.to_string())
+            .bind(height as i64)
+            .fetch_optional(&**self)
+            .await?;
+        Ok(row.is_some())
+    }
+
+    /// Record versions of a bucket as pruned
+    pub async fn record_pruned_versions(
+        &self,
+        bucket_id: &Uuid,
+        versions: &[(u64, Link)],
+    ) -> Result<(), sqlx::Error> {
+        let bucket_id_str = bucket_id.to_string();
+        let mut tx = self.begin().await?;
+        for (height, link) in versions {
+            sqlx::query(
+                r#"
+                INSERT INTO pruned_versions (bucket_id, height, link)
+                VALUES (?1, ?2, ?3)
+                ON CONFLICT(bucket_id, height) DO NOTHING
+                "#,
+            )
+            .bind(&bucket_id_str)
+            .bind(*height as i64)
+            .bind(link.hash().to_string())
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+}
+
+/// Tiers are stored as the number of periods kept, 0 for forever
+fn keep_to_column(keep: Keep) -> i64 {
+    match keep {
+        Keep::Last(periods) => periods as i64,
+        Keep::Forever => 0,
+    }
+}
+
+fn keep_from_column(value: Option<i64>) -> Option<Keep> {
+    value.map(|periods| match periods {
+        0 => Keep::Forever,
+        periods => Keep::Last(periods as u32),
+    })
+}
+
+fn policy_from_row(row: &sqlx::sqlite::SqliteRow) -> RetentionPolicy {
+    RetentionPolicy {
+        hourly: keep_from_column(row.get("hourly")),
+        daily: keep_from_column(row.get("daily")),
+        weekly: keep_from_column(row.get("weekly")),
+        monthly: keep_from_column(row.get("monthly")),
+        infrequent_access_after: row
+            .get::<Option<i64>, _>("infrequent_access_after")
+            .map(|days| days as u32),
+        archive_after: row
+            .get::<Option<i64>, _>("archive_after")
+            .map(|days| days as u32),
+    }
+}
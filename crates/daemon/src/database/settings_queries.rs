@@ -0,0 +1,62 @@
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::database::Database;
+
+/// Whether syncing is paused globally
+pub const SYNC_PAUSED_KEY: &str = "sync_paused";
+
+/// Bucket that quick-dropped and pasted content goes to
+pub const INBOX_BUCKET_KEY: &str = "inbox_bucket";
+
+impl Database {
+    /// Read a runtime setting, None if it was never set
+    pub async fn get_setting(&self, key: &str) -> Result<Option<String>, sqlx::Error> {
+        let row = sqlx::query("SELECT value FROM settings WHERE key = ?1")
+            .bind(key)
+            .fetch_optional(&**self)
+            .await?;
+        Ok(row.map(|row| row.get("value")))
+    }
+
+    /// Insert or update a runtime setting
+    pub async fn set_setting(&self, key: &str, value: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO settings (key, value, updated_at)
+            VALUES (?1, ?2, CURRENT_TIMESTAMP)
+            ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(key)
+        .bind(value)
+        .execute(&**self)
+        .await?;
+        Ok(())
+    }
+
+    /// Whether syncing was left paused
+    pub async fn sync_paused(&self) -> Result<bool, sqlx::Error> {
+        Ok(self.get_setting(SYNC_PAUSED_KEY).await?.as_deref() == Some("true"))
+    }
+
+    /// Persist the global sync pause switch
+    pub async fn set_sync_paused(&self, paused: bool) -> Result<(), sqlx::Error> {
+        self.set_setting(SYNC_PAUSED_KEY, if paused { "true" } else { "false" })
+            .await
+    }
+
+    /// The designated inbox bucket, if one was chosen
+    pub async fn inbox_bucket(&self) -> Result<Option<Uuid>, sqlx::Error> {
+        Ok(self
+            .get_setting(INBOX_BUCKET_KEY)
+            .await?
+            .and_then(|value| Uuid::parse_str(&value).ok()))
+    }
+
+    /// Designate the inbox bucket, or clear it
+    pub async fn set_inbox_bucket(&self, bucket_id: Option<Uuid>) -> Result<(), sqlx::Error> {
+        let value = bucket_id.map(|id| id.to_string()).unwrap_or_default();
+        self.set_setting(INBOX_BUCKET_KEY, &value).await
+    }
+}
@@ -8,12 +8,16 @@ use sqlx::ConnectOptions;
 use tracing::log::LevelFilter;
 use url::Url;
 
+use crate::database::encryption::{self, DatabaseFile, DatabaseKey};
 use crate::database::DatabaseSetupError;
 
 static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
 
-pub async fn connect_sqlite(url: &Url) -> Result<SqlitePool, DatabaseSetupError> {
-    let connection_options = SqliteConnectOptions::from_url(url)
+pub async fn connect_sqlite(
+    url: &Url,
+    key: Option<&DatabaseKey>,
+) -> Result<SqlitePool, DatabaseSetupError> {
+    let mut connection_options = SqliteConnectOptions::from_url(url)
         .map_err(DatabaseSetupError::Unavailable)?
         .create_if_missing(true)
         .journal_mode(SqliteJournalMode::Wal)
@@ -22,14 +26,42 @@ pub async fn connect_sqlite(url: &Url) -> Result<SqlitePool, DatabaseSetupError>
         .statement_cache_capacity(2_500)
         .synchronous(SqliteSynchronous::Normal);
 
-    SqlitePoolOptions::new()
+    let file =
+        DatabaseFile::inspect(connection_options.get_filename()).map_err(DatabaseSetupError::Io)?;
+    match (key, file) {
+        (Some(_), _) if !cfg!(feature = "sqlcipher") => {
+            return Err(DatabaseSetupError::EncryptionUnsupported)
+        }
+        (Some(key), DatabaseFile::Plaintext) => {
+            encryption::encrypt_in_place(connection_options.get_filename(), key).await?;
+        }
+        (None, DatabaseFile::Encrypted) => return Err(DatabaseSetupError::Encrypted),
+        _ => {}
+    }
+    if let Some(key) = key {
+        connection_options = connection_options.pragma("key", key.pragma_value());
+    }
+
+    let pool = SqlitePoolOptions::new()
         .idle_timeout(Duration::from_secs(90))
         .max_lifetime(Duration::from_secs(1_800))
         .min_connections(1)
         .max_connections(16)
         .connect_with(connection_options)
         .await
-        .map_err(DatabaseSetupError::Unavailable)
+        .map_err(DatabaseSetupError::Unavailable)?;
+
+    if key.is_some() {
+        // SQLite without SQLCipher ignores the key pragma
+        let cipher_version: Option<String> = sqlx::query_scalar("PRAGMA cipher_version")
+            .fetch_optional(&pool)
+            .await
+            .map_err(DatabaseSetupError::Encryption)?;
+        if cipher_version.is_none() {
+            return Err(DatabaseSetupError::EncryptionUnsupported);
+        }
+    }
+    Ok(pool)
 }
 
 pub async fn migrate_sqlite(pool: &SqlitePool) -> Result<(), DatabaseSetupError> {
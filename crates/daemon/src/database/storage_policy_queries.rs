@@ -0,0 +1,67 @@
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::database::Database;
+
+impl Database {
+    /// Location a bucket's blobs are placed in, if it has a storage policy
+    pub async fn storage_policy(&self, bucket_id: &Uuid) -> Result<Option<String>, sqlx::Error> {
+        let row = sqlx::query("SELECT location FROM bucket_storage_policies WHERE bucket_id = ?1")
+            .bind(bucket_id.to_string())
+            .fetch_optional(&**self)
+            .await?;
+        Ok(row.map(|row| row.get("location")))
+    }
+
+    /// All stored storage policies, as the location of each bucket
+    pub async fn list_storage_policies(&self) -> Result<Vec<(Uuid, String)>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT bucket_id, location
+            FROM bucket_storage_policies
+            ORDER BY bucket_id
+            "#,
+        )
+        .fetch_all(&**self)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|row| {
+                let bucket_id: String = row.get("bucket_id");
+                Some((Uuid::parse_str(&bucket_id).ok()?, row.get("location")))
+            })
+            .collect())
+    }
+
+    /// Insert or replace the storage policy of a bucket
+    pub async fn set_storage_policy(
+        &self,
+        bucket_id: &Uuid,
+        location: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO bucket_storage_policies (bucket_id, location, updated_at)
+            VALUES (?1, ?2, CURRENT_TIMESTAMP)
+            ON CONFLICT(bucket_id) DO UPDATE SET
+                location = excluded.location,
+                updated_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(bucket_id.to_string())
+        .bind(location)
+        .execute(&**self)
+        .await?;
+        Ok(())
+    }
+
+    /// Remove the storage policy of a bucket, returning whether one was set
+    pub async fn delete_storage_policy(&self, bucket_id: &Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM bucket_storage_policies WHERE bucket_id = ?1")
+            .bind(bucket_id.to_string())
+            .execute(&**self)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
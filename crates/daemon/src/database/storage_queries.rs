@@ -0,0 +1,188 @@
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::database::Database;
+
+/// A single file reference to a blob within a bucket
+#[derive(Debug, Clone)]
+pub struct BlobRef {
+    pub path: String,
+    pub hash: String,
+    pub size: u64,
+}
+
+/// Logical vs physical byte accounting for a set of blob references
+#[derive(Debug, Clone, Default)]
+pub struct DedupStats {
+    pub file_count: u64,
+    pub logical_bytes: u64,
+    pub physical_bytes: u64,
+}
+
+/// A blob referenced by more than one file
+#[derive(Debug, Clone)]
+pub struct DuplicateBlob {
+    pub hash: String,
+    pub size: u64,
+    pub ref_count: u64,
+    pub paths: Vec<(Uuid, String)>,
+}
+
+impl Database {
+    /// Replace all blob references recorded for a bucket
+    pub async fn replace_blob_refs(
+        &self,
+        bucket_id: &Uuid,
+        refs: &[BlobRef],
+    ) -> Result<(), sqlx::Error> {
+        let bucket_id_str = bucket_id.to_string();
+        let mut tx = self.begin().await?;
+
+        sqlx::query("DELETE FROM blob_refs WHERE bucket_id = ?1")
+            .bind(&bucket_id_str)
+            .execute(&mut *tx)
+            .await?;
+
+        for blob_ref in refs {
+            sqlx::query(
+                r#"
+                INSERT INTO blob_refs (bucket_id, path, hash, size)
+                VALUES (?1, ?2, ?3, ?4)
+                "#,
+            )
+            .bind(&bucket_id_str)
+            .bind(&blob_ref.path)
+            .bind(&blob_ref.hash)
+            .bind(blob_ref.size as i64)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await
+    }
+
+    /// Drop blob references for buckets that are no longer known
+    pub async fn prune_blob_refs(&self, keep: &[Uuid]) -> Result<u64, sqlx::Error> {
+        let known = sqlx::query("SELECT DISTINCT bucket_id FROM blob_refs")
+            .fetch_all(&**self)
+            .await?;
+
+        let mut removed = 0;
+        for row in known {
+            let bucket_id: String = row.get("bucket_id");
+            if keep.iter().any(|id| id.to_string() == bucket_id) {
+                continue;
+            }
+            removed += sqlx::query("DELETE FROM blob_refs WHERE bucket_id = ?1")
+                .bind(&bucket_id)
+                .execute(&**self)
+                .await?
+                .rows_affected();
+        }
+
+        Ok(removed)
+    }
+
+    /// Get dedup stats for a single bucket
+    pub async fn bucket_dedup_stats(&self, bucket_id: &Uuid) -> Result<DedupStats, sqlx::Error> {
+        let bucket_id_str = bucket_id.to_string();
+        let row = sqlx::query(
+            r#"
+            SELECT
+                COUNT(*) AS file_count,
+                COALESCE(SUM(size), 0) AS logical_bytes,
+                COALESCE((
+                    SELECT SUM(size) FROM (
+                        SELECT DISTINCT hash, size FROM blob_refs WHERE bucket_id = ?1
+                    )
+                ), 0) AS physical_bytes
+            FROM blob_refs
+            WHERE bucket_id = ?1
+            "#,
+        )
+        .bind(bucket_id_str)
+        .fetch_one(&**self)
+        .await?;
+
+        Ok(stats_from_row(&row))
+    }
+
+    /// Get dedup stats across every bucket
+    pub async fn total_dedup_stats(&self) -> Result<DedupStats, sqlx::Error> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                COUNT(*) AS file_count,
+                COALESCE(SUM(size), 0) AS logical_bytes,
+                COALESCE((
+                    SELECT SUM(size) FROM (SELECT DISTINCT hash, size FROM blob_refs)
+                ), 0) AS physical_bytes
+            FROM blob_refs
+            "#,
+        )
+        .fetch_one(&**self)
+        .await?;
+
+        Ok(stats_from_row(&row))
+    }
+
+    /// List the blobs with the most bytes saved by deduplication
+    pub async fn top_duplicate_blobs(&self, limit: u32) -> Result<Vec<DuplicateBlob>, sqlx::Error> {
+        let limit_val = limit.min(1000) as i64;
+        let rows = sqlx::query(
+            r#"
+            SELECT hash, MAX(size) AS size, COUNT(*) AS ref_count
+            FROM blob_refs
+            GROUP BY hash
+            HAVING COUNT(*) > 1
+            ORDER BY (COUNT(*) - 1) * MAX(size) DESC, hash ASC
+            LIMIT ?1
+            "#,
+        )
+        .bind(limit_val)
+        .fetch_all(&**self)
+        .await?;
+
+        let mut duplicates = Vec::with_capacity(rows.len());
+        for row in rows {
+            let hash: String = row.get("hash");
+            let paths = sqlx::query(
+                r#"
+                SELECT bucket_id, path
+                FROM blob_refs
+                WHERE hash = ?1
+                ORDER BY bucket_id, path
+                "#,
+            )
+            .bind(&hash)
+            .fetch_all(&**self)
+            .await?
+            .into_iter()
+            .map(|r| {
+                let bucket_id: String = r.get("bucket_id");
+                (
+                    Uuid::parse_str(&bucket_id).expect("invalid bucket_id UUID in database"),
+                    r.get("path"),
+                )
+            })
+            .collect();
+
+            duplicates.push(DuplicateBlob {
+                hash,
+                size: row.get::<i64, _>("size") as u64,
+                ref_count: row.get::<i64, _>("ref_count") as u64,
+                paths,
+            });
+        }
+
+        Ok(duplicates)
+    }
+}
+
+fn stats_from_row(row: &sqlx::sqlite::SqliteRow) -> DedupStats {
+    DedupStats {
+        file_count: row.get::<i64, _>("file_count") as u64,
+        logical_bytes: row.get::<i64, _>("logical_bytes") as u64,
+        physical_bytes: row.get::<i64, _>("physical_bytes") as u64,
+    }
+}
@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::database::Database;
+use crate::sync_policy::SyncPolicy;
+
+impl Database {
+    /// Load all stored bucket sync policies
+    pub async fn list_sync_policies(&self) -> Result<HashMap<Uuid, SyncPolicy>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT bucket_id, interval_secs, manual_only, paused, active_hours, online_only,
+                history_depth
+            FROM bucket_sync_policies
+            "#,
+        )
+        .fetch_all(&**self)
+        .await?;
+
+        let mut policies = HashMap::new();
+        for row in rows {
+            let bucket_id: String = row.get("bucket_id");
+            let Ok(bucket_id) = Uuid::parse_str(&bucket_id) else {
+                tracing::warn!("Ignoring sync policy with invalid bucket id {}", bucket_id);
+                continue;
+            };
+            let active_hours: Option<String> = row.get("active_hours");
+            let active_hours = match active_hours.map(|hours| hours.parse()) {
+                Some(Ok(hours)) => Some(hours),
+                Some(Err(e)) => {
+                    tracing::warn!("Ignoring active hours of bucket {}: {}", bucket_id, e);
+                    None
+                }
+                None => None,
+            };
+            let interval_secs: Option<i64> = row.get("interval_secs");
+            let history_depth: Option<i64> = row.get("history_depth");
+            policies.insert(
+                bucket_id,
+                SyncPolicy {
+                    interval_secs: interval_secs.map(|secs| secs as u64),
+                    manual_only: row.get("manual_only"),
+                    paused: row.get("paused"),
+                    active_hours,
+                    online_only: row.get("online_only"),
+                    history_depth: history_depth.map(|depth| depth as u64),
+                },
+            );
+        }
+        Ok(policies)
+    }
+
+    /// Insert or replace the sync policy of a bucket
+    pub async fn set_sync_policy(
+        &self,
+        bucket_id: &Uuid,
+        policy: &SyncPolicy,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO bucket_sync_policies
+                (bucket_id, interval_secs, manual_only, paused, active_hours, online_only,
+                 history_depth, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, CURRENT_TIMESTAMP)
+            ON CONFLICT(bucket_id) DO UPDATE SET
+                interval_secs = excluded.interval_secs,
+                manual_only = excluded.manual_only,
+                paused = excluded.paused,
+                active_hours = excluded.active_hours,
+                online_only = excluded.online_only,
+                history_depth = excluded.history_depth,
+                updated_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(bucket_id.to_string())
+        .bind(policy.interval_secs.map(|secs| secs as i64))
+        .bind(policy.manual_only)
+        .bind(policy.paused)
+        .bind(policy.active_hours.map(|hours| hours.to_string()))
+        .bind(policy.online_only)
+        .bind(policy.history_depth.map(|depth| depth as i64))
+        .execute(&**self)
+        .await?;
+        Ok(())
+    }
+
+    /// Remove the sync policy of a bucket, returning whether one was set
+    pub async fn delete_sync_policy(&self, bucket_id: &Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM bucket_sync_policies WHERE bucket_id = ?1")
+            .bind(bucket_id.to_string())
+            .execute(&**self)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
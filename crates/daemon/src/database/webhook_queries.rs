@@ -0,0 +1,145 @@
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::database::Database;
+use crate::webhooks::{Webhook, WebhookEvent};
+
+impl Database {
+    /// Register a webhook
+    pub async fn create_webhook(
+        &self,
+        url: &str,
+        bucket_id: Option<&Uuid>,
+        events: &[WebhookEvent],
+        secret: &str,
+    ) -> Result<Webhook, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let row = sqlx::query(
+            r#"
+            INSERT INTO webhooks (id, url, bucket_id, events, secret)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            RETURNING created_at
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(url)
+        .bind(bucket_id.map(|id| id.to_string()))
+        .bind(join_events(events))
+        .bind(secret)
+        .fetch_one(&**self)
+        .await?;
+
+        Ok(Webhook {
+            id,
+            url: url.to_string(),
+            bucket_id: bucket_id.copied(),
+            events: events.to_vec(),
+            secret: secret.to_string(),
+            created_at: row.get("created_at"),
+            last_delivery_at: None,
+            last_status: None,
+            last_error: None,
+        })
+    }
+
+    /// Webhooks in registration order; given a bucket, those that receive
+    /// its events: the bucket's own and the ones for every bucket
+    pub async fn list_webhooks(
+        &self,
+        bucket_id: Option<&Uuid>,
+    ) -> Result<Vec<Webhook>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, url, bucket_id, events, secret, created_at,
+                   last_delivery_at, last_status, last_error
+            FROM webhooks
+            WHERE ?1 IS NULL OR bucket_id IS NULL OR bucket_id = ?1
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(bucket_id.map(|id| id.to_string()))
+        .fetch_all(&**self)
+        .await?;
+
+        Ok(rows.iter().filter_map(webhook_from_row).collect())
+    }
+
+    pub async fn get_webhook(&self, id: &Uuid) -> Result<Option<Webhook>, sqlx::Error> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, url, bucket_id, events, secret, created_at,
+                   last_delivery_at, last_status, last_error
+            FROM webhooks
+            WHERE id = ?1
+            "#,
+        )
+        .bind(id.to_string())
+        .fetch_optional(&**self)
+        .await?;
+
+        Ok(row.as_ref().and_then(webhook_from_row))
+    }
+
+    /// Remove a webhook, returning whether it existed
+    pub async fn delete_webhook(&self, id: &Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM webhooks WHERE id = ?1")
+            .bind(id.to_string())
+            .execute(&**self)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Keep the outcome of the latest delivery attempt to a webhook
+    pub async fn record_webhook_delivery(
+        &self,
+        id: &Uuid,
+        status: Option<u16>,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE webhooks
+            SET last_delivery_at = CURRENT_TIMESTAMP, last_status = ?2, last_error = ?3
+            WHERE id = ?1
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(status.map(i64::from))
+        .bind(error)
+        .execute(&**self)
+        .await?;
+        Ok(())
+    }
+}
+
+fn join_events(events: &[WebhookEvent]) -> String {
+    events
+        .iter()
+        .map(WebhookEvent::as_str)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn webhook_from_row(row: &sqlx::sqlite::SqliteRow) -> Option<Webhook> {
+    let id: String = row.get("id");
+    let bucket_id: Option<String> = row.get("bucket_id");
+    let events: String = row.get("events");
+    let last_status: Option<i64> = row.get("last_status");
+    Some(Webhook {
+        id: Uuid::parse_str(&id).ok()?,
+        url: row.get("url"),
+        bucket_id: match bucket_id {
+            Some(bucket_id) => Some(Uuid::parse_str(&bucket_id).ok()?),
+            None => None,
+        },
+        events: events
+            .split(',')
+            .filter_map(|event| event.parse().ok())
+            .collect(),
+        secret: row.get("secret"),
+        created_at: row.get("created_at"),
+        last_delivery_at: row.get("last_delivery_at"),
+        last_status: last_status.and_then(|status| u16::try_from(status).ok()),
+        last_error: row.get("last_error"),
+    })
+}
@@ -0,0 +1,223 @@
+use sqlx::Row;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::database::Database;
+
+/// A local grouping of buckets
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    pub id: Uuid,
+    pub name: String,
+    pub bucket_count: u64,
+    pub created_at: OffsetDateTime,
+}
+
+impl Database {
+    /// Create an empty workspace
+    pub async fn create_workspace(&self, name: &str) -> Result<Workspace, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let row = sqlx::query(
+            r#"
+            INSERT INTO workspaces (id, name)
+            VALUES (?1, ?2)
+            RETURNING created_at
+            "#,
+        )
+        .bind(id.to_string())
+        .bind(name)
+        .fetch_one(&**self)
+        .await?;
+
+        Ok(Workspace {
+            id,
+            name: name.to_string(),
+            bucket_count: 0,
+            created_at: row.get("created_at"),
+        })
+    }
+
+    /// List workspaces by name, with the number of buckets in each
+    pub async fn list_workspaces(&self) -> Result<Vec<Workspace>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT w.id, w.name, w.created_at, COUNT(wb.bucket_id) AS bucket_count
+            FROM workspaces w
+            LEFT JOIN workspace_buckets wb ON wb.workspace_id = w.id
+            GROUP BY w.id
+            ORDER BY w.name
+            "#,
+        )
+        .fetch_all(&**self)
+        .await?;
+
+        Ok(rows.iter().filter_map(workspace_from_row).collect())
+    }
+
+    /// Find a workspace by name or ID
+    pub async fn get_workspace(&self, name_or_id: &str) -> Result<Option<Workspace>, sqlx::Error> {
+        let row = sqlx::query(
+            r#"
+            SELECT w.id, w.name, w.created_at, COUNT(wb.bucket_id) AS bucket_count
+            FROM workspaces w
+            LEFT JOIN workspace_buckets wb ON wb.workspace_id = w.id
+            WHERE w.name = ?1 OR w.id = ?1
+            GROUP BY w.id
+            "#,
+        )
+        .bind(name_or_id)
+        .fetch_optional(&**self)
+        .await?;
+
+        Ok(row.as_ref().and_then(workspace_from_row))
+    }
+
+    /// Delete a workspace; its buckets are kept and no longer grouped
+    pub async fn delete_workspace(&self, id: &Uuid) -> Result<bool, sqlx::Error> {
+        let mut tx = self.begin().await?;
+        sqlx::query("DELETE FROM workspace_shares WHERE workspace_id = ?1")
+            .bind(id.to_string())
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM workspace_buckets WHERE workspace_id = ?1")
+            .bind(id.to_string())
+            .execute(&mut *tx)
+            .await?;
+        let result = sqlx::query("DELETE FROM workspaces WHERE id = ?1")
+            .bind(id.to_string())
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Put a bucket in a workspace, moving it out of any other
+    pub async fn set_bucket_workspace(
+        &self,
+        bucket_id: &Uuid,
+        workspace_id: &Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO workspace_buckets (bucket_id, workspace_id)
+            VALUES (?1, ?2)
+            ON CONFLICT(bucket_id) DO UPDATE SET
+                workspace_id = excluded.workspace_id,
+                added_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(bucket_id.to_string())
+        .bind(workspace_id.to_string())
+        .execute(&**self)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Take a bucket out of a workspace
+    pub async fn remove_bucket_workspace(
+        &self,
+        bucket_id: &Uuid,
+        workspace_id: &Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let result =
+            sqlx::query("DELETE FROM workspace_buckets WHERE bucket_id = ?1 AND workspace_id = ?2")
+                .bind(bucket_id.to_string())
+                .bind(workspace_id.to_string())
+                .execute(&**self)
+                .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// IDs of the buckets in a workspace
+    pub async fn workspace_bucket_ids(
+        &self,
+        workspace_id: &Uuid,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT bucket_id
+            FROM workspace_buckets
+            WHERE workspace_id = ?1
+            ORDER BY added_at
+            "#,
+        )
+        .bind(workspace_id.to_string())
+        .fetch_all(&**self)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|row| Uuid::parse_str(row.get("bucket_id")).ok())
+            .collect())
+    }
+
+    /// Workspace a bucket belongs to, if any
+    pub async fn bucket_workspace(&self, bucket_id: &Uuid) -> Result<Option<Uuid>, sqlx::Error> {
+        let row = sqlx::query("SELECT workspace_id FROM workspace_buckets WHERE bucket_id = ?1")
+            .bind(bucket_id.to_string())
+            .fetch_optional(&**self)
+            .await?;
+
+        Ok(row.and_then(|row| Uuid::parse_str(row.get("workspace_id")).ok()))
+    }
+
+    /// Record that a workspace is shared with a peer, replacing its role
+    pub async fn add_workspace_share(
+        &self,
+        workspace_id: &Uuid,
+        peer_public_key: &str,
+        role: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO workspace_shares (workspace_id, peer_public_key, role)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT(workspace_id, peer_public_key) DO UPDATE SET role = excluded.role
+            "#,
+        )
+        .bind(workspace_id.to_string())
+        .bind(peer_public_key)
+        .bind(role)
+        .execute(&**self)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Peers a workspace is shared with, as (hex public key, role)
+    pub async fn workspace_shares(
+        &self,
+        workspace_id: &Uuid,
+    ) -> Result<Vec<(String, String)>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT peer_public_key, role
+            FROM workspace_shares
+            WHERE workspace_id = ?1
+            ORDER BY shared_at
+            "#,
+        )
+        .bind(workspace_id.to_string())
+        .fetch_all(&**self)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| (row.get("peer_public_key"), row.get("role")))
+            .collect())
+    }
+}
+
+fn workspace_from_row(row: &sqlx::sqlite::SqliteRow) -> Option<Workspace> {
+    let id: String = row.get("id");
+    let bucket_count: i64 = row.get("bucket_count");
+    Some(Workspace {
+        id: Uuid::parse_str(&id).ok()?,
+        name: row.get("name"),
+        bucket_count: bucket_count as u64,
+        created_at: row.get("created_at"),
+    })
+}
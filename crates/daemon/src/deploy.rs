@@ -0,0 +1,284 @@
+//! Deploy keys: signed writes to a bucket from CI
+//!
+//! A deploy key lets a pipeline push build artifacts or a static site into
+//! one bucket, under one path prefix, without running a node or holding the
+//! daemon's API. Keys are created on the daemon (`jax bucket deploy-key`),
+//! which keeps their secret, and are used against the gateway:
+//!
+//! ```text
+//! PUT    /deploy/<bucket_id>/<path>   write the request body to <path>
+//! DELETE /deploy/<bucket_id>/<path>   remove <path>
+//! ```
+//!
+//! Each request carries the key ID in `X-Jax-Deploy-Key`, the current Unix
+//! time in `X-Jax-Timestamp`, a random nonce unique to the request in
+//! `X-Jax-Nonce`, the hex SHA-256 of the body (of an empty body for DELETE)
+//! in `X-Jax-Content-Sha256`, and in `X-Jax-Signature` `sha256=<hex>`, an
+//! HMAC-SHA256 keyed with the key's secret of:
+//!
+//! ```text
+//! <METHOD>\n<bucket_id>\n<path>\n<timestamp>\n<nonce>\n<content sha256>
+//! ```
+//!
+//! `<path>` is the absolute bucket path, e.g. `/site/index.html`. Requests
+//! more than [`MAX_CLOCK_SKEW`] away from the daemon's clock are refused,
+//! and the gateway remembers the nonces of the requests it accepted for as
+//! long, so a captured request can't be replayed. Writes are audited as made
+//! by `deploy:<key id>`.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+pub const KEY_HEADER: &str = "x-jax-deploy-key";
+pub const TIMESTAMP_HEADER: &str = "x-jax-timestamp";
+pub const NONCE_HEADER: &str = "x-jax-nonce";
+pub const CONTENT_SHA256_HEADER: &str = "x-jax-content-sha256";
+pub const SIGNATURE_HEADER: &str = "x-jax-signature";
+
+/// How far a request's timestamp may be from the daemon's clock
+pub const MAX_CLOCK_SKEW: Duration = Duration::from_secs(300);
+
+/// Longest nonce accepted
+pub const MAX_NONCE_LEN: usize = 64;
+
+/// A key allowed to write to a bucket under a path prefix
+#[derive(Debug, Clone)]
+pub struct DeployKey {
+    pub id: Uuid,
+    pub bucket_id: Uuid,
+    /// Absolute path without a trailing slash, or `/`
+    pub path_prefix: String,
+    pub name: Option<String>,
+    pub secret: String,
+    pub created_at: OffsetDateTime,
+    pub last_used_at: Option<OffsetDateTime>,
+}
+
+impl DeployKey {
+    /// Whether the key may write to an absolute, normalized path
+    pub fn allows(&self, path: &str) -> bool {
+        self.path_prefix == "/"
+            || path == self.path_prefix
+            || path
+                .strip_prefix(&self.path_prefix)
+                .is_some_and(|rest| rest.starts_with('/'))
+    }
+}
+
+/// Result of a deploy request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeployResponse {
+    pub bucket_id: Uuid,
+    pub path: String,
+    pub new_bucket_link: String,
+}
+
+/// An absolute path with no empty, `.` or `..` segments and no trailing
+/// slash, or None if it can't be made into one
+pub fn normalize_path(path: &str) -> Option<String> {
+    let path = path.strip_prefix('/').unwrap_or(path);
+    let path = path.strip_suffix('/').unwrap_or(path);
+    if path.is_empty() {
+        return Some("/".to_string());
+    }
+    if path
+        .split('/')
+        .any(|segment| matches!(segment, "" | "." | ".."))
+    {
+        return None;
+    }
+    Some(format!("/{}", path))
+}
+
+/// Hex SHA-256 of a request body
+pub fn content_sha256(body: &[u8]) -> String {
+    hex::encode(Sha256::digest(body))
+}
+
+/// The string a deploy request's signature covers
+pub fn canonical_request(
+    method: &str,
+    bucket_id: &Uuid,
+    path: &str,
+    timestamp: i64,
+    nonce: &str,
+    content_sha256: &str,
+) -> String {
+    format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        method.to_ascii_uppercase(),
+        bucket_id,
+        path,
+        timestamp,
+        nonce,
+        content_sha256.to_ascii_lowercase()
+    )
+}
+
+fn mac(secret: &str, canonical: &str) -> Hmac<Sha256> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any length");
+    mac.update(canonical.as_bytes());
+    mac
+}
+
+/// Signature of a canonical request, as sent in `X-Jax-Signature`
+pub fn sign_request(secret: &str, canonical: &str) -> String {
+    format!(
+        "sha256={}",
+        hex::encode(mac(secret, canonical).finalize().into_bytes())
+    )
+}
+
+/// Check a signature in constant time
+pub fn verify_request(secret: &str, canonical: &str, signature: &str) -> bool {
+    let Some(signature) = signature
+        .strip_prefix("sha256=")
+        .and_then(|hex| hex::decode(hex).ok())
+    else {
+        return false;
+    };
+    mac(secret, canonical).verify_slice(&signature).is_ok()
+}
+
+/// Whether a request timestamp is within [`MAX_CLOCK_SKEW`] of now
+pub fn is_fresh(timestamp: i64, now: OffsetDateTime) -> bool {
+    now.unix_timestamp().abs_diff(timestamp) <= MAX_CLOCK_SKEW.as_secs()
+}
+
+/// Whether a nonce is one to accept: 1 to [`MAX_NONCE_LEN`] letters, digits,
+/// `-` or `_`
+pub fn is_valid_nonce(nonce: &str) -> bool {
+    (1..=MAX_NONCE_LEN).contains(&nonce.len())
+        && nonce
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_')
+}
+
+/// Nonces of the deploy requests accepted while they are fresh, so that
+/// none is accepted twice
+#[derive(Debug, Clone, Default)]
+pub struct SeenNonces(Arc<Mutex<HashMap<(Uuid, String), i64>>>);
+
+impl SeenNonces {
+    /// Record the nonce of a key's request made at `timestamp`, returning
+    /// false if a request with it was accepted already
+    pub fn insert(&self, key_id: Uuid, nonce: &str, timestamp: i64, now: OffsetDateTime) -> bool {
+        let mut seen = self.0.lock().expect("deploy nonces lock poisoned");
+        // Requests older than this are refused as stale anyway
+        let oldest = now.unix_timestamp() - MAX_CLOCK_SKEW.as_secs() as i64;
+        seen.retain(|_, seen_at| *seen_at >= oldest);
+        seen.insert((key_id, nonce.to_string()), timestamp)
+            .is_none()
+    }
+}
+
+/// Headers of a signed deploy request, for clients
+pub fn signed_headers(
+    key_id: &Uuid,
+    secret: &str,
+    method: &str,
+    bucket_id: &Uuid,
+    path: &str,
+    body: &[u8],
+) -> Vec<(&'static str, String)> {
+    let timestamp = OffsetDateTime::now_utc().unix_timestamp();
+    let nonce = Uuid::new_v4().simple().to_string();
+    let content_sha256 = content_sha256(body);
+    let canonical = canonical_request(method, bucket_id, path, timestamp, &nonce, &content_sha256);
+    vec![
+        (KEY_HEADER, key_id.to_string()),
+        (TIMESTAMP_HEADER, timestamp.to_string()),
+        (NONCE_HEADER, nonce),
+        (CONTENT_SHA256_HEADER, content_sha256),
+        (SIGNATURE_HEADER, sign_request(secret, &canonical)),
+    ]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn key(path_prefix: &str) -> DeployKey {
+        DeployKey {
+            id: Uuid::new_v4(),
+            bucket_id: Uuid::new_v4(),
+            path_prefix: path_prefix.to_string(),
+            name: None,
+            secret: "secret".to_string(),
+            created_at: OffsetDateTime::now_utc(),
+            last_used_at: None,
+        }
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(
+            normalize_path("site/index.html").unwrap(),
+            "/site/index.html"
+        );
+        assert_eq!(normalize_path("/site/").unwrap(), "/site");
+        assert_eq!(normalize_path("/").unwrap(), "/");
+        assert!(normalize_path("/site/../etc").is_none());
+        assert!(normalize_path("/site//index.html").is_none());
+        assert!(normalize_path("/./site").is_none());
+    }
+
+    #[test]
+    fn test_prefix() {
+        let site = key("/site");
+        assert!(site.allows("/site"));
+        assert!(site.allows("/site/index.html"));
+        assert!(!site.allows("/sites/index.html"));
+        assert!(!site.allows("/other"));
+        assert!(key("/").allows("/anything/at/all"));
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let bucket_id = Uuid::new_v4();
+        let canonical = canonical_request("put", &bucket_id, "/a", 1, "n1", &content_sha256(b"a"));
+        let signature = sign_request("secret", &canonical);
+        assert!(verify_request("secret", &canonical, &signature));
+        assert!(!verify_request("other", &canonical, &signature));
+        assert!(!verify_request("secret", &canonical, "sha256=00"));
+        let moved = canonical_request("PUT", &bucket_id, "/b", 1, "n1", &content_sha256(b"a"));
+        assert!(!verify_request("secret", &moved, &signature));
+        let renonced = canonical_request("PUT", &bucket_id, "/a", 1, "n2", &content_sha256(b"a"));
+        assert!(!verify_request("secret", &renonced, &signature));
+    }
+
+    #[test]
+    fn test_nonces_seen_once() {
+        let now = OffsetDateTime::now_utc();
+        let seen = SeenNonces::default();
+        let key_id = Uuid::new_v4();
+        assert!(seen.insert(key_id, "n1", now.unix_timestamp(), now));
+        assert!(!seen.insert(key_id, "n1", now.unix_timestamp(), now));
+        assert!(seen.insert(Uuid::new_v4(), "n1", now.unix_timestamp(), now));
+
+        // Forgotten once its request is stale
+        let later = now + MAX_CLOCK_SKEW + Duration::from_secs(1);
+        assert!(seen.insert(key_id, "n1", later.unix_timestamp(), later));
+
+        assert!(is_valid_nonce("0f3c-a_9"));
+        assert!(!is_valid_nonce(""));
+        assert!(!is_valid_nonce("a\nb"));
+        assert!(!is_valid_nonce(&"a".repeat(MAX_NONCE_LEN + 1)));
+    }
+
+    #[test]
+    fn test_is_fresh() {
+        let now = OffsetDateTime::now_utc();
+        assert!(is_fresh(now.unix_timestamp() - 60, now));
+        assert!(!is_fresh(now.unix_timestamp() - 3600, now));
+        assert!(!is_fresh(now.unix_timestamp() + 3600, now));
+    }
+}
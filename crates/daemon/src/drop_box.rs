@@ -0,0 +1,274 @@
+//! Drop links: write-only uploads into a bucket from anonymous visitors
+//!
+//! A bucket owner creates a drop link for one directory of a bucket
+//! (`jax bucket drop-link create`). Anyone holding the link's token can then
+//! upload files through the gateway, with no other access to the bucket:
+//!
+//! ```text
+//! POST /drop/<token>   multipart form, one or more `file` fields
+//! ```
+//!
+//! Files over the link's size limit, or of a type it doesn't allow, are
+//! refused. A link may require a captcha: the upload then carries the token
+//! the captcha widget produced in a `captcha_token` field (or the
+//! `X-Jax-Captcha` header), which is checked with a siteverify-style endpoint
+//! (hCaptcha, Turnstile and reCAPTCHA all work) before anything is stored.
+//!
+//! Accepted files aren't written to the bucket. They wait in a moderation
+//! queue in the daemon's database until the owner approves them, which adds
+//! them to the link's directory, or rejects them. Queued uploads are audited
+//! as made by `drop:<link id>`.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+pub const CAPTCHA_HEADER: &str = "x-jax-captcha";
+
+/// Largest file a link accepts unless created with another limit
+pub const DEFAULT_MAX_SIZE: u64 = 25 * 1024 * 1024;
+
+/// Largest file any link may accept; queued files are kept in the database
+pub const MAX_SIZE_LIMIT: u64 = 100 * 1024 * 1024;
+
+/// How long the gateway waits on a captcha provider
+const CAPTCHA_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A write-only link into one directory of a bucket
+#[derive(Debug, Clone)]
+pub struct DropLink {
+    pub id: Uuid,
+    pub token: String,
+    pub bucket_id: Uuid,
+    /// Absolute directory without a trailing slash, or `/`
+    pub directory: String,
+    pub name: Option<String>,
+    pub max_size: u64,
+    /// MIME types or `type/*` patterns; empty accepts any type
+    pub allowed_types: Vec<String>,
+    pub captcha: Option<CaptchaConfig>,
+    pub created_at: OffsetDateTime,
+    pub expires_at: Option<OffsetDateTime>,
+}
+
+/// What a new drop link is created with
+#[derive(Debug, Clone)]
+pub struct NewDropLink<'a> {
+    pub bucket_id: &'a Uuid,
+    pub directory: &'a str,
+    pub name: Option<&'a str>,
+    pub max_size: u64,
+    pub allowed_types: &'a [String],
+    pub captcha: Option<&'a CaptchaConfig>,
+    pub expires_at: Option<OffsetDateTime>,
+}
+
+/// Where and how a link's captcha tokens are verified
+#[derive(Debug, Clone)]
+pub struct CaptchaConfig {
+    pub verify_url: String,
+    pub secret: String,
+}
+
+impl DropLink {
+    /// Whether the link has run out at `now`
+    pub fn is_expired(&self, now: OffsetDateTime) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+
+    /// Whether the link accepts files of a MIME type
+    pub fn allows_type(&self, mime_type: &str) -> bool {
+        self.allowed_types.is_empty()
+            || self
+                .allowed_types
+                .iter()
+                .any(|allowed| match allowed.strip_suffix("/*") {
+                    Some(top) => mime_type
+                        .split_once('/')
+                        .is_some_and(|(mime_top, _)| mime_top.eq_ignore_ascii_case(top)),
+                    None => mime_type.eq_ignore_ascii_case(allowed),
+                })
+    }
+
+    /// Bucket path an upload named `file_name` lands at once approved
+    pub fn path_of(&self, file_name: &str) -> String {
+        if self.directory == "/" {
+            format!("/{}", file_name)
+        } else {
+            format!("{}/{}", self.directory, file_name)
+        }
+    }
+}
+
+/// A file waiting in a link's moderation queue
+#[derive(Debug, Clone)]
+pub struct DropUpload {
+    pub id: Uuid,
+    pub link_id: Uuid,
+    pub file_name: String,
+    pub mime_type: String,
+    pub size: u64,
+    pub created_at: OffsetDateTime,
+}
+
+/// What the gateway answers an accepted upload with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DropReceipt {
+    /// Files queued for the owner to review, in upload order
+    pub queued: Vec<QueuedFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedFile {
+    pub file_name: String,
+    pub size: u64,
+}
+
+/// Parse a comma separated list of allowed types, dropping empty entries
+pub fn parse_allowed_types(types: &str) -> Vec<String> {
+    types
+        .split(',')
+        .map(|allowed| allowed.trim().to_ascii_lowercase())
+        .filter(|allowed| !allowed.is_empty())
+        .collect()
+}
+
+/// Whether an allowed type is a MIME type or a `type/*` pattern
+pub fn is_valid_type_pattern(pattern: &str) -> bool {
+    match pattern.split_once('/') {
+        Some((top, sub)) => !top.is_empty() && !sub.is_empty() && top != "*",
+        None => false,
+    }
+}
+
+/// The last segment of an uploaded file's name, or None if nothing usable
+/// is left of it
+pub fn sanitize_file_name(name: &str) -> Option<String> {
+    let name = name.rsplit(['/', '\\']).next().unwrap_or(name);
+    let name: String = name.chars().filter(|c| !c.is_control()).collect();
+    let name = name.trim();
+    if name.is_empty() || name == "." || name == ".." {
+        return None;
+    }
+    Some(name.to_string())
+}
+
+/// A file name not in `taken`, adding ` (2)`, ` (3)`, ... before the
+/// extension of `name` as needed
+pub fn unique_file_name(name: &str, taken: impl Fn(&str) -> bool) -> String {
+    if !taken(name) {
+        return name.to_string();
+    }
+    let (stem, extension) = match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, Some(extension)),
+        _ => (name, None),
+    };
+    (2..)
+        .map(|n| match extension {
+            Some(extension) => format!("{} ({}).{}", stem, n, extension),
+            None => format!("{} ({})", stem, n),
+        })
+        .find(|candidate| !taken(candidate))
+        .expect("some suffix is free")
+}
+
+#[derive(Debug, Deserialize)]
+struct SiteVerifyResponse {
+    success: bool,
+}
+
+/// Check a captcha token with the link's provider
+pub async fn verify_captcha(
+    client: &reqwest::Client,
+    captcha: &CaptchaConfig,
+    token: &str,
+) -> Result<bool, reqwest::Error> {
+    let response: SiteVerifyResponse = client
+        .post(&captcha.verify_url)
+        .timeout(CAPTCHA_TIMEOUT)
+        .form(&[("secret", captcha.secret.as_str()), ("response", token)])
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    Ok(response.success)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn link(allowed_types: &str) -> DropLink {
+        DropLink {
+            id: Uuid::new_v4(),
+            token: "token".to_string(),
+            bucket_id: Uuid::new_v4(),
+            directory: "/inbox".to_string(),
+            name: None,
+            max_size: DEFAULT_MAX_SIZE,
+            allowed_types: parse_allowed_types(allowed_types),
+            captcha: None,
+            created_at: OffsetDateTime::now_utc(),
+            expires_at: None,
+        }
+    }
+
+    #[test]
+    fn test_allows_type() {
+        assert!(link("").allows_type("application/x-msdownload"));
+        let link = link("image/*, application/PDF");
+        assert!(link.allows_type("image/png"));
+        assert!(link.allows_type("application/pdf"));
+        assert!(!link.allows_type("application/zip"));
+        assert!(!link.allows_type("imagefoo"));
+    }
+
+    #[test]
+    fn test_type_patterns() {
+        assert!(is_valid_type_pattern("image/*"));
+        assert!(is_valid_type_pattern("text/plain"));
+        assert!(!is_valid_type_pattern("*/*"));
+        assert!(!is_valid_type_pattern("image"));
+        assert!(!is_valid_type_pattern("/png"));
+    }
+
+    #[test]
+    fn test_sanitize_file_name() {
+        assert_eq!(sanitize_file_name("cv.pdf").as_deref(), Some("cv.pdf"));
+        assert_eq!(
+            sanitize_file_name("../../etc/passwd").as_deref(),
+            Some("passwd")
+        );
+        assert_eq!(
+            sanitize_file_name("C:\\Users\\me\\photo.jpg").as_deref(),
+            Some("photo.jpg")
+        );
+        assert_eq!(sanitize_file_name("uploads/.."), None);
+        assert_eq!(sanitize_file_name("  "), None);
+    }
+
+    #[test]
+    fn test_unique_file_name() {
+        let taken = ["cv.pdf", "cv (2).pdf", "notes"];
+        let taken = |name: &str| taken.contains(&name);
+        assert_eq!(unique_file_name("photo.jpg", taken), "photo.jpg");
+        assert_eq!(unique_file_name("cv.pdf", taken), "cv (3).pdf");
+        assert_eq!(unique_file_name("notes", taken), "notes (2)");
+    }
+
+    #[test]
+    fn test_path_and_expiry() {
+        let mut link = link("");
+        assert_eq!(link.path_of("cv.pdf"), "/inbox/cv.pdf");
+        link.directory = "/".to_string();
+        assert_eq!(link.path_of("cv.pdf"), "/cv.pdf");
+
+        let now = OffsetDateTime::now_utc();
+        assert!(!link.is_expired(now));
+        link.expires_at = Some(now);
+        assert!(link.is_expired(now));
+    }
+}
@@ -0,0 +1,221 @@
+//! Sharing buckets with temporary identities
+//!
+//! An ephemeral share gives read access to a throwaway key instead of the
+//! recipient's node, so the bucket's manifest records that it was shared but
+//! not with whom (see [`common::mount::EphemeralShare`]). The sharer hands the
+//! recipient a ticket, `<bucket-id>@<node-id>#<key>`, out of band; the
+//! recipient claims it, following the bucket from the sharer's node and
+//! reading it with the key. The key is kept in the database until the share
+//! is gone.
+//!
+//! Every [`SWEEP_INTERVAL`] a background task cleans up after expiry:
+//! - buckets this node owns that hold an expired ephemeral share get a new
+//!   version without it, so the key can't read anything saved afterwards,
+//!   audited as `bucket.share.expire`
+//! - buckets followed through an ephemeral share are unfollowed and their
+//!   key forgotten once the share expired or is gone from the synced head,
+//!   audited as `bucket.unfollow`. Versions synced so far are kept.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use uuid::Uuid;
+
+use common::bucket_log::BucketLogProvider;
+use common::crypto::SecretKey;
+use common::mount::{Mount, PrincipalRole};
+use common::peer::Peer;
+
+use crate::audit::{Actor, AuditEvent, AuditLog};
+use crate::database::Database;
+use crate::follow::FollowTicket;
+
+/// How often expired ephemeral shares and keys are cleaned up
+pub const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// What a recipient needs to claim an ephemeral share
+#[derive(Debug, Clone)]
+pub struct EphemeralTicket {
+    /// The bucket and the node to follow it from
+    pub follow: FollowTicket,
+    /// Key of the temporary identity the bucket was shared with
+    pub key: SecretKey,
+}
+
+impl fmt::Display for EphemeralTicket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}#{}", self.follow, self.key.to_hex())
+    }
+}
+
+impl FromStr for EphemeralTicket {
+    type Err = EphemeralError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || EphemeralError::InvalidTicket(s.to_string());
+        let (follow, key) = s.trim().split_once('#').ok_or_else(invalid)?;
+        Ok(Self {
+            follow: follow.parse().map_err(|_| invalid())?,
+            key: SecretKey::from_hex(key).map_err(|_| invalid())?,
+        })
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum EphemeralError {
+    #[error("invalid ticket {0}, expected <bucket-id>@<node-id>#<key>")]
+    InvalidTicket(String),
+}
+
+/// Current Unix time in seconds, as ephemeral shares count it
+pub fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// Drop expired ephemeral shares of the buckets this node owns, and forget
+/// the keys of expired ones it was given
+pub async fn sweep(peer: &Peer<Database>, database: &Database, audit: &AuditLog) {
+    let now = now();
+
+    let bucket_ids = match BucketLogProvider::list_buckets(peer.logs()).await {
+        Ok(bucket_ids) => bucket_ids,
+        Err(e) => {
+            tracing::error!("Failed to list buckets for expired shares: {}", e);
+            Vec::new()
+        }
+    };
+    for bucket_id in bucket_ids {
+        if let Err(e) = expire_shares(peer, audit, bucket_id, now).await {
+            tracing::warn!(
+                "Failed to drop expired ephemeral shares of bucket {}: {}",
+                bucket_id,
+                e
+            );
+        }
+    }
+
+    let keys = match database.list_ephemeral_keys().await {
+        Ok(keys) => keys,
+        Err(e) => {
+            tracing::error!("Failed to load ephemeral keys: {}", e);
+            return;
+        }
+    };
+    for (bucket_id, key) in keys {
+        let Ok((link, _)) = peer.logs().head(bucket_id, None).await else {
+            // Nothing synced yet
+            continue;
+        };
+        let Ok(manifest) = Mount::load_manifest(&link, peer.blobs()).await else {
+            continue;
+        };
+        if manifest
+            .get_ephemeral_share(&key.public())
+            .is_some_and(|share| !share.is_expired(now))
+        {
+            continue;
+        }
+
+        tracing::info!(
+            "Ephemeral share of bucket {} expired, unfollowing it",
+            bucket_id
+        );
+        let released = async {
+            database.unfollow_bucket(&bucket_id).await?;
+            database.remove_ephemeral_key(&bucket_id).await
+        };
+        if let Err(e) = released.await {
+            tracing::warn!(
+                "Failed to forget the ephemeral key of bucket {}: {}",
+                bucket_id,
+                e
+            );
+            continue;
+        }
+        peer.unfollow(&bucket_id);
+        audit
+            .record(AuditEvent::new(Actor::Expiry, "bucket.unfollow").bucket(bucket_id))
+            .await;
+    }
+}
+
+/// Save a new version of an owned bucket without its expired ephemeral
+/// shares, if it holds any
+async fn expire_shares(
+    peer: &Peer<Database>,
+    audit: &AuditLog,
+    bucket_id: Uuid,
+    now: i64,
+) -> anyhow::Result<()> {
+    let (link, _) = peer.logs().head(bucket_id, None).await?;
+    let manifest = Mount::load_manifest(&link, peer.blobs()).await?;
+    let owner = manifest
+        .get_share(&peer.secret().public())
+        .is_some_and(|share| *share.role() == PrincipalRole::Owner);
+    let expired = manifest
+        .ephemeral_shares()
+        .values()
+        .filter(|share| share.is_expired(now))
+        .count();
+    if !owner || expired == 0 {
+        return Ok(());
+    }
+
+    let mount = peer.mount(bucket_id).await?;
+    let link = peer.save_mount(&mount, false).await?;
+    audit
+        .record(
+            AuditEvent::new(Actor::Expiry, "bucket.share.expire")
+                .bucket(bucket_id)
+                .detail(format!("{} ephemeral share(s)", expired))
+                .version(&link),
+        )
+        .await;
+    Ok(())
+}
+
+/// Clean up expired ephemeral shares and keys every [`SWEEP_INTERVAL`] until
+/// the daemon exits
+pub(crate) async fn run(peer: Peer<Database>, database: Database, audit: AuditLog) {
+    let mut interval = tokio::time::interval(SWEEP_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            _ = peer.cancelled() => return,
+        }
+        sweep(&peer, &database, &audit).await;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ticket_roundtrip() {
+        let ticket = EphemeralTicket {
+            follow: FollowTicket {
+                bucket_id: Uuid::new_v4(),
+                node_id: SecretKey::generate().public(),
+            },
+            key: SecretKey::generate(),
+        };
+        let parsed: EphemeralTicket = ticket.to_string().parse().unwrap();
+        assert_eq!(parsed.follow, ticket.follow);
+        assert_eq!(parsed.key.to_hex(), ticket.key.to_hex());
+
+        // A follow ticket alone is not enough
+        assert!(ticket
+            .follow
+            .to_string()
+            .parse::<EphemeralTicket>()
+            .is_err());
+        assert!(format!("{}#zz", ticket.follow)
+            .parse::<EphemeralTicket>()
+            .is_err());
+    }
+}
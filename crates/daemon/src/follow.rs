@@ -0,0 +1,178 @@
+//! Following published buckets without a share
+//!
+//! A follower keeps a read-only copy of a published bucket that stays updated
+//! through peer announcements and periodic pings, reading it with the public
+//! secret of its published versions. A bucket is followed either from a
+//! ticket (`<bucket-id>@<node-id>`) or from a gateway URL
+//! (`https://host/gw/<bucket-id>`, or `/gw/<token>` once the bucket's link
+//! was rotated), whose node is looked up through the gateway's
+//! `/_status/identity` endpoint. Gateways served under a path prefix
+//! (`https://host/jax/gw/<bucket-id>`) work the same.
+
+use std::fmt;
+use std::str::FromStr;
+
+use reqwest::{Client, Url};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use common::crypto::PublicKey;
+
+/// Where to follow a bucket from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FollowTicket {
+    pub bucket_id: Uuid,
+    pub node_id: PublicKey,
+}
+
+impl fmt::Display for FollowTicket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}", self.bucket_id, self.node_id.to_hex())
+    }
+}
+
+impl FromStr for FollowTicket {
+    type Err = FollowError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (bucket_id, node_id) = s
+            .trim()
+            .split_once('@')
+            .ok_or_else(|| FollowError::InvalidTicket(s.to_string()))?;
+        Ok(Self {
+            bucket_id: Uuid::parse_str(bucket_id)
+                .map_err(|_| FollowError::InvalidTicket(s.to_string()))?,
+            node_id: PublicKey::from_hex(node_id)
+                .map_err(|_| FollowError::InvalidTicket(s.to_string()))?,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct IdentityResponse {
+    node_id: String,
+}
+
+#[derive(Deserialize)]
+struct ListingResponse {
+    bucket_id: Uuid,
+}
+
+impl FollowTicket {
+    /// Resolve a ticket or a gateway URL of a bucket
+    pub async fn resolve(target: &str, client: &Client) -> Result<Self, FollowError> {
+        if !target.starts_with("http://") && !target.starts_with("https://") {
+            return target.parse();
+        }
+
+        let url = Url::parse(target).map_err(|_| FollowError::InvalidUrl(target.to_string()))?;
+        let (url, gateway_id) =
+            split_gateway_url(&url).ok_or_else(|| FollowError::InvalidUrl(target.to_string()))?;
+        let bucket_id = match Uuid::parse_str(&gateway_id) {
+            Ok(bucket_id) => bucket_id,
+            // A rotated link, ask the gateway which bucket it serves
+            Err(_) => {
+                let listing_url = url
+                    .join(&format!("gw/{}", gateway_id))
+                    .map_err(|_| FollowError::InvalidUrl(target.to_string()))?;
+                let listing: ListingResponse = client
+                    .get(listing_url)
+                    .header(reqwest::header::ACCEPT, "application/json")
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?;
+                listing.bucket_id
+            }
+        };
+
+        let identity_url = url
+            .join("_status/identity")
+            .map_err(|_| FollowError::InvalidUrl(target.to_string()))?;
+        let identity: IdentityResponse = client
+            .get(identity_url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let node_id = PublicKey::from_hex(&identity.node_id)
+            .map_err(|_| FollowError::InvalidNodeId(identity.node_id))?;
+
+        Ok(Self { bucket_id, node_id })
+    }
+}
+
+/// Split a gateway URL into the gateway's base URL, ending in `/`, and the id
+/// the bucket is served at
+fn split_gateway_url(url: &Url) -> Option<(Url, String)> {
+    let segments: Vec<&str> = url.path_segments()?.collect();
+    let gw = segments.iter().position(|segment| *segment == "gw")?;
+    let gateway_id = segments.get(gw + 1).filter(|id| !id.is_empty())?;
+    let mut base = url.clone();
+    base.set_query(None);
+    base.set_fragment(None);
+    base.set_path(&format!(
+        "/{}",
+        segments[..gw]
+            .iter()
+            .map(|segment| format!("{}/", segment))
+            .collect::<String>()
+    ));
+    Some((base, gateway_id.to_string()))
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum FollowError {
+    #[error("invalid ticket {0}, expected <bucket-id>@<node-id>")]
+    InvalidTicket(String),
+    #[error("invalid gateway URL {0}, expected https://host/gw/<bucket-id>")]
+    InvalidUrl(String),
+    #[error("gateway returned an invalid node id: {0}")]
+    InvalidNodeId(String),
+    #[error("failed to reach gateway: {0}")]
+    Gateway(#[from] reqwest::Error),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use common::crypto::SecretKey;
+
+    #[test]
+    fn test_ticket_roundtrip() {
+        let ticket = FollowTicket {
+            bucket_id: Uuid::new_v4(),
+            node_id: SecretKey::generate().public(),
+        };
+        assert_eq!(ticket.to_string().parse::<FollowTicket>().unwrap(), ticket);
+        assert!("not-a-ticket".parse::<FollowTicket>().is_err());
+        assert!(format!("{}@zz", ticket.bucket_id)
+            .parse::<FollowTicket>()
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_rejects_non_gateway_url() {
+        let client = Client::new();
+        let result = FollowTicket::resolve("https://example.com/buckets/abc", &client).await;
+        assert!(matches!(result, Err(FollowError::InvalidUrl(_))));
+    }
+
+    #[test]
+    fn test_split_gateway_url() {
+        let split = |url: &str| {
+            split_gateway_url(&Url::parse(url).unwrap()).map(|(base, id)| (base.to_string(), id))
+        };
+        assert_eq!(
+            split("https://gw.example/gw/abc/notes?at=1"),
+            Some(("https://gw.example/".to_string(), "abc".to_string()))
+        );
+        assert_eq!(
+            split("https://example.com/jax/gw/abc"),
+            Some(("https://example.com/jax/".to_string(), "abc".to_string()))
+        );
+        assert_eq!(split("https://gw.example/gw/"), None);
+    }
+}
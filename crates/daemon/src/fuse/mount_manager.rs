@@ -10,6 +10,7 @@ use std::sync::Arc;
 use tokio::sync::{broadcast, mpsc, oneshot, RwLock};
 use uuid::Uuid;
 
+use crate::audit::{Actor, AuditEvent, AuditLog};
 use crate::database::models::FuseMount;
 use crate::database::types::MountStatus;
 use crate::database::Database;
@@ -103,19 +104,27 @@ pub struct MountManager {
     db: Database,
     /// Peer for sync integration
     peer: Peer<Database>,
+    /// Audit log for saves made through mounts
+    audit: AuditLog,
     /// Sync event broadcaster
     sync_tx: broadcast::Sender<SyncEvent>,
 }
 
 impl MountManager {
     /// Create a new mount manager
-    pub fn new(db: Database, peer: Peer<Database>, config: MountManagerConfig) -> Self {
+    pub fn new(
+        db: Database,
+        peer: Peer<Database>,
+        audit: AuditLog,
+        config: MountManagerConfig,
+    ) -> Self {
         let (sync_tx, _) = broadcast::channel(config.sync_event_capacity);
 
         Self {
             mounts: RwLock::new(HashMap::new()),
             db,
             peer,
+            audit,
             sync_tx,
         }
     }
@@ -187,8 +196,23 @@ impl MountManager {
                             );
 
                             // Save the merged result
-                            if let Err(e) = self.peer.save_mount(&mount_guard, false).await {
-                                tracing::error!("Failed to save merged mount {}: {}", mount_id, e);
+                            match self.peer.save_mount(&mount_guard, false).await {
+                                Ok(link) => {
+                                    self.audit
+                                        .record(
+                                            AuditEvent::new(Actor::Fuse, "mount.merge")
+                                                .bucket(bucket_id)
+                                                .version(&link),
+                                        )
+                                        .await;
+                                }
+                                Err(e) => {
+                                    tracing::error!(
+                                        "Failed to save merged mount {}: {}",
+                                        mount_id,
+                                        e
+                                    );
+                                }
                             }
                         }
                         Err(e) => {
@@ -404,7 +428,7 @@ impl MountManager {
         );
 
         // Spawn save handler task
-        self.spawn_save_handler(save_rx, mount_arc.clone());
+        self.spawn_save_handler(save_rx, mount_arc.clone(), *mount_config.bucket_id);
 
         // Mount options
         #[cfg(target_os = "linux")]
@@ -513,6 +537,11 @@ impl MountManager {
         Ok(())
     }
 
+    /// Number of mounts currently running
+    pub async fn running_count(&self) -> usize {
+        self.mounts.read().await.len()
+    }
+
     /// Stop all running mounts
     pub async fn stop_all(&self) -> Result<(), MountError> {
         let mount_ids: Vec<Uuid> = {
@@ -577,8 +606,10 @@ impl MountManager {
         &self,
         mut save_rx: mpsc::Receiver<SaveRequest>,
         mount: Arc<RwLock<Mount>>,
+        bucket_id: Uuid,
     ) {
         let peer = self.peer.clone();
+        let audit = self.audit.clone();
 
         tokio::spawn(async move {
             while let Some(request) = save_rx.recv().await {
@@ -593,6 +624,14 @@ impl MountManager {
                             request.mount_id,
                             link.hash()
                         );
+                        audit
+                            .record(
+                                AuditEvent::new(Actor::Fuse, "mount.save")
+                                    .bucket(bucket_id)
+                                    .detail(request.mount_id.to_string())
+                                    .version(&link),
+                            )
+                            .await;
                     }
                     Err(e) => {
                         tracing::error!("Failed to save mount {}: {}", request.mount_id, e);
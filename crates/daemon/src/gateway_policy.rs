@@ -0,0 +1,290 @@
+//! Gateway serving policies
+//!
+//! A gateway operator chooses which buckets the gateway serves: every bucket
+//! with a published version (the default), only buckets on an allowlist, or
+//! none at all. Per bucket, directory listings can be turned off, crawlers can
+//! be asked not to index it, older versions can be served while the latest
+//! syncs and the bytes served per day can be capped. The mode and the
+//! policies are stored in the database and mirrored in [`GatewayPolicies`],
+//! which the gateway handler consults on every request. Bandwidth usage is
+//! only counted in memory and starts over each UTC day and when the daemon
+//! restarts.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, RwLock};
+
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Which buckets the gateway serves
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum GatewayMode {
+    /// Only buckets whose policy allows them
+    Allowlist,
+    /// Any bucket with a published version
+    #[default]
+    Published,
+    /// No buckets
+    None,
+}
+
+impl GatewayMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GatewayMode::Allowlist => "allowlist",
+            GatewayMode::Published => "published",
+            GatewayMode::None => "none",
+        }
+    }
+}
+
+impl fmt::Display for GatewayMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for GatewayMode {
+    type Err = GatewayPolicyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "allowlist" => Ok(GatewayMode::Allowlist),
+            "published" => Ok(GatewayMode::Published),
+            "none" => Ok(GatewayMode::None),
+            _ => Err(GatewayPolicyError::InvalidMode(s.to_string())),
+        }
+    }
+}
+
+/// How the gateway serves a bucket
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GatewayPolicy {
+    /// Serve the bucket when the gateway is in allowlist mode
+    #[serde(default)]
+    pub allowed: bool,
+    /// Show directory listings; index files are served either way
+    #[serde(default = "default_listings")]
+    pub listings: bool,
+    /// Send `X-Robots-Tag: noindex` with everything served for the bucket
+    #[serde(default)]
+    pub noindex: bool,
+    /// Serve the last fully synced published version while a newer one is
+    /// still syncing
+    #[serde(default)]
+    pub serve_stale: bool,
+    /// Bytes served for the bucket per UTC day; None for no cap
+    #[serde(default)]
+    pub daily_bandwidth_bytes: Option<u64>,
+}
+
+fn default_listings() -> bool {
+    true
+}
+
+impl Default for GatewayPolicy {
+    fn default() -> Self {
+        Self {
+            allowed: false,
+            listings: true,
+            noindex: false,
+            serve_stale: false,
+            daily_bandwidth_bytes: None,
+        }
+    }
+}
+
+impl GatewayPolicy {
+    pub fn validate(&self) -> Result<(), GatewayPolicyError> {
+        match self.daily_bandwidth_bytes {
+            Some(0) => Err(GatewayPolicyError::InvalidBandwidth),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    mode: RwLock<GatewayMode>,
+    policies: RwLock<HashMap<Uuid, GatewayPolicy>>,
+    /// Bytes served per bucket on the given day
+    usage: Mutex<HashMap<Uuid, (NaiveDate, u64)>>,
+}
+
+/// In-memory copy of the stored gateway mode and policies, with the bytes
+/// served per bucket today
+#[derive(Debug, Clone, Default)]
+pub struct GatewayPolicies(Arc<Inner>);
+
+impl GatewayPolicies {
+    pub fn new(mode: GatewayMode, policies: HashMap<Uuid, GatewayPolicy>) -> Self {
+        Self(Arc::new(Inner {
+            mode: RwLock::new(mode),
+            policies: RwLock::new(policies),
+            usage: Mutex::new(HashMap::new()),
+        }))
+    }
+
+    pub fn mode(&self) -> GatewayMode {
+        *self.0.mode.read().unwrap()
+    }
+
+    /// Policy for a bucket, the default one if none is set
+    pub fn get(&self, bucket_id: &Uuid) -> GatewayPolicy {
+        self.0
+            .policies
+            .read()
+            .unwrap()
+            .get(bucket_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// All buckets with a policy set
+    pub fn all(&self) -> HashMap<Uuid, GatewayPolicy> {
+        self.0.policies.read().unwrap().clone()
+    }
+
+    pub(crate) fn set_mode(&self, mode: GatewayMode) {
+        *self.0.mode.write().unwrap() = mode;
+    }
+
+    pub(crate) fn set(&self, bucket_id: Uuid, policy: GatewayPolicy) {
+        self.0.policies.write().unwrap().insert(bucket_id, policy);
+    }
+
+    pub(crate) fn remove(&self, bucket_id: &Uuid) {
+        self.0.policies.write().unwrap().remove(bucket_id);
+    }
+
+    /// Whether the gateway serves a bucket at all under the current mode
+    pub fn serves(&self, bucket_id: &Uuid) -> bool {
+        match self.mode() {
+            GatewayMode::Published => true,
+            GatewayMode::Allowlist => self.get(bucket_id).allowed,
+            GatewayMode::None => false,
+        }
+    }
+
+    /// Bytes served for a bucket today
+    pub fn bytes_served_today(&self, bucket_id: &Uuid) -> u64 {
+        self.bytes_served_on(bucket_id, Utc::now().date_naive())
+    }
+
+    /// Whether the bucket used up its daily bandwidth
+    pub fn over_bandwidth(&self, bucket_id: &Uuid) -> bool {
+        self.over_bandwidth_on(bucket_id, Utc::now().date_naive())
+    }
+
+    /// Count bytes served for a bucket
+    pub fn record_served(&self, bucket_id: Uuid, bytes: u64) {
+        self.record_served_on(bucket_id, bytes, Utc::now().date_naive())
+    }
+
+    fn bytes_served_on(&self, bucket_id: &Uuid, today: NaiveDate) -> u64 {
+        match self.0.usage.lock().unwrap().get(bucket_id) {
+            Some((day, bytes)) if *day == today => *bytes,
+            _ => 0,
+        }
+    }
+
+    fn over_bandwidth_on(&self, bucket_id: &Uuid, today: NaiveDate) -> bool {
+        self.get(bucket_id)
+            .daily_bandwidth_bytes
+            .map(|cap| self.bytes_served_on(bucket_id, today) >= cap)
+            .unwrap_or(false)
+    }
+
+    fn record_served_on(&self, bucket_id: Uuid, bytes: u64, today: NaiveDate) {
+        let mut usage = self.0.usage.lock().unwrap();
+        let entry = usage.entry(bucket_id).or_insert((today, 0));
+        if entry.0 != today {
+            *entry = (today, 0);
+        }
+        entry.1 = entry.1.saturating_add(bytes);
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GatewayPolicyError {
+    #[error("invalid gateway mode '{0}' (expected allowlist, published or none)")]
+    InvalidMode(String),
+    #[error("daily bandwidth cap must be at least 1 byte")]
+    InvalidBandwidth,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_serves_by_mode() {
+        let allowed = Uuid::new_v4();
+        let other = Uuid::new_v4();
+        let policies = GatewayPolicies::default();
+        policies.set(
+            allowed,
+            GatewayPolicy {
+                allowed: true,
+                ..GatewayPolicy::default()
+            },
+        );
+
+        assert!(policies.serves(&allowed));
+        assert!(policies.serves(&other));
+
+        policies.set_mode(GatewayMode::Allowlist);
+        assert!(policies.serves(&allowed));
+        assert!(!policies.serves(&other));
+
+        policies.set_mode(GatewayMode::None);
+        assert!(!policies.serves(&allowed));
+
+        for mode in [
+            GatewayMode::Allowlist,
+            GatewayMode::Published,
+            GatewayMode::None,
+        ] {
+            assert_eq!(mode.to_string().parse::<GatewayMode>().unwrap(), mode);
+        }
+        assert!("everything".parse::<GatewayMode>().is_err());
+    }
+
+    #[test]
+    fn test_daily_bandwidth() {
+        let bucket_id = Uuid::new_v4();
+        let day = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap();
+        let next_day = day.succ_opt().unwrap();
+        let policies = GatewayPolicies::default();
+
+        policies.record_served_on(bucket_id, 100, day);
+        assert!(!policies.over_bandwidth_on(&bucket_id, day));
+
+        policies.set(
+            bucket_id,
+            GatewayPolicy {
+                daily_bandwidth_bytes: Some(150),
+                ..GatewayPolicy::default()
+            },
+        );
+        assert!(!policies.over_bandwidth_on(&bucket_id, day));
+        policies.record_served_on(bucket_id, 50, day);
+        assert!(policies.over_bandwidth_on(&bucket_id, day));
+
+        // Usage starts over the next day
+        assert!(!policies.over_bandwidth_on(&bucket_id, next_day));
+        policies.record_served_on(bucket_id, 10, next_day);
+        assert_eq!(policies.bytes_served_on(&bucket_id, next_day), 10);
+
+        assert!(GatewayPolicy {
+            daily_bandwidth_bytes: Some(0),
+            ..GatewayPolicy::default()
+        }
+        .validate()
+        .is_err());
+    }
+}
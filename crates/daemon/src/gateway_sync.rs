@@ -0,0 +1,141 @@
+//! Published versions the gateway is still syncing
+//!
+//! The gateway serves the latest published version of a bucket, which may
+//! still be on its way from the bucket's peers. Until it can be loaded, the
+//! gateway answers 503 with how far along it is: the share of the version's
+//! pinned blobs that are here. Visitors can wait for it with `?wait=<secs>`,
+//! and the operator can have the gateway serve the newest published version
+//! that is completely here in the meantime
+//! (`jax gateway policy set --serve-stale`).
+
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::time::Instant;
+use uuid::Uuid;
+
+use common::bucket_log::BucketLogProvider;
+use common::linked_data::Link;
+use common::mount::Mount;
+use common::peer::{BlobsStore, Peer};
+
+use crate::database::Database;
+
+/// Header with the sync progress of the latest version, in percent, sent
+/// along with an older version served in its place
+pub const SYNC_PROGRESS_HEADER: &str = "x-jax-sync-progress";
+
+/// Longest a request may wait for a version to sync
+pub const MAX_WAIT: Duration = Duration::from_secs(60);
+
+/// How often a waiting request checks on the version
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Most older published versions looked at for one that is completely here
+const MAX_STALE_CANDIDATES: usize = 8;
+
+/// How many of a version's pinned blobs are here
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct SyncProgress {
+    pub fetched: usize,
+    pub total: usize,
+}
+
+impl SyncProgress {
+    /// Share of the pinned blobs fetched, in percent. A version whose pin
+    /// list hasn't arrived is at 0.
+    pub fn percent(&self) -> u8 {
+        if self.total == 0 {
+            return 0;
+        }
+        (self.fetched * 100 / self.total).min(100) as u8
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.total > 0 && self.fetched == self.total
+    }
+}
+
+/// How much of the version at `link` is here
+pub async fn progress(blobs: &BlobsStore, link: &Link) -> SyncProgress {
+    let Ok(manifest) = Mount::load_manifest(link, blobs).await else {
+        return SyncProgress::default();
+    };
+    let Ok(pins) = blobs.read_hash_list(manifest.pins().hash()).await else {
+        return SyncProgress::default();
+    };
+    let mut fetched = 0;
+    for hash in &pins {
+        if blobs.stat(hash).await.unwrap_or(false) {
+            fetched += 1;
+        }
+    }
+    SyncProgress {
+        fetched,
+        total: pins.len(),
+    }
+}
+
+/// Load the latest published version of a bucket, waiting up to `wait` for
+/// it to sync. Fails with how far along it is, or None if this node doesn't
+/// know of a published version.
+pub async fn load_latest_published(
+    peer: &Peer<Database>,
+    bucket_id: Uuid,
+    wait: Duration,
+) -> Result<Mount, Option<SyncProgress>> {
+    let deadline = Instant::now() + wait.min(MAX_WAIT);
+    loop {
+        // A newer version may be published while waiting
+        let link = match peer.logs().latest_published(bucket_id).await {
+            Ok(Some((link, _height))) => Some(link),
+            _ => None,
+        };
+        if let Some(link) = &link {
+            if let Ok(mount) = Mount::load(link, peer.secret(), peer.blobs()).await {
+                return Ok(mount);
+            }
+        }
+        if Instant::now() + POLL_INTERVAL > deadline {
+            return Err(match link {
+                Some(link) => Some(progress(peer.blobs(), &link).await),
+                None => None,
+            });
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// The newest published version of a bucket that is completely here
+pub async fn load_last_complete(
+    peer: &Peer<Database>,
+    database: &Database,
+    bucket_id: Uuid,
+) -> Option<Mount> {
+    let versions = database.published_versions(&bucket_id).await.ok()?;
+    for (link, _height) in versions.into_iter().take(MAX_STALE_CANDIDATES) {
+        if !progress(peer.blobs(), &link).await.is_complete() {
+            continue;
+        }
+        if let Ok(mount) = Mount::load(&link, peer.secret(), peer.blobs()).await {
+            return Some(mount);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_percent() {
+        let progress = |fetched, total| SyncProgress { fetched, total };
+        assert_eq!(progress(0, 0).percent(), 0);
+        assert_eq!(progress(1, 3).percent(), 33);
+        assert_eq!(progress(3, 3).percent(), 100);
+        assert!(progress(3, 3).is_complete());
+        assert!(!progress(2, 3).is_complete());
+        assert!(!progress(0, 0).is_complete());
+    }
+}
@@ -0,0 +1,571 @@
+//! HTTPS for the gateway with certificates from an ACME CA
+//!
+//! Small deployments can serve the gateway over HTTPS without a reverse
+//! proxy by listing the domains it is reached at in `config.toml`:
+//!
+//! ```toml
+//! [gateway_tls]
+//! domains = ["files.example.com"]
+//! contact = "ops@example.com"
+//! ```
+//!
+//! The gateway then also listens on `https_port` (443) and on `http_port`
+//! (80), where it answers ACME HTTP-01 challenges and redirects everything
+//! else to HTTPS. Certificates come from Let's Encrypt, or the ACME directory
+//! set as `directory_url`. The ACME account, the certificate and its key are
+//! kept in `<jax dir>/tls/`, so restarts reuse them, and the certificate is
+//! renewed in the background once two thirds of its lifetime have passed.
+//! Failed orders are retried every hour; the gateway keeps serving the
+//! certificate it has meanwhile.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use instant_acme::{
+    Account, AccountCredentials, AuthorizationStatus, ChallengeType, Identifier, NewAccount,
+    NewOrder, Order, OrderStatus,
+};
+use rustls::server::{ClientHello, ResolvesServerCert};
+use rustls::sign::CertifiedKey;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use tokio::sync::watch;
+use x509_parser::extensions::GeneralName;
+
+pub const TLS_DIR_NAME: &str = "tls";
+const ACCOUNT_FILE_NAME: &str = "account.json";
+const CERT_FILE_NAME: &str = "cert.pem";
+const KEY_FILE_NAME: &str = "key.pem";
+
+/// Where HTTP-01 challenge tokens are fetched from
+pub const ACME_CHALLENGE_PREFIX: &str = "/.well-known/acme-challenge";
+
+pub const LETS_ENCRYPT_DIRECTORY: &str = "https://acme-v02.api.letsencrypt.org/directory";
+
+/// How long to wait before ordering again after an order failed
+const RETRY_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// How often a pending order is checked on
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// How many times a pending order is checked on before giving up
+const POLL_ATTEMPTS: u32 = 60;
+
+/// The `[gateway_tls]` section of `config.toml`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GatewayTlsConfig {
+    /// Domains the gateway is reached at; the certificate covers them all
+    pub domains: Vec<String>,
+    /// Email address the CA sends expiry warnings to
+    #[serde(default)]
+    pub contact: Option<String>,
+    /// ACME directory to order certificates from
+    #[serde(default = "default_directory_url")]
+    pub directory_url: String,
+    /// Port HTTPS is served on
+    #[serde(default = "default_https_port")]
+    pub https_port: u16,
+    /// Port HTTP-01 challenges are answered on; the CA always asks port 80,
+    /// so change it only when port 80 is forwarded here
+    #[serde(default = "default_http_port")]
+    pub http_port: u16,
+}
+
+fn default_directory_url() -> String {
+    LETS_ENCRYPT_DIRECTORY.to_string()
+}
+
+fn default_https_port() -> u16 {
+    443
+}
+
+fn default_http_port() -> u16 {
+    80
+}
+
+impl GatewayTlsConfig {
+    pub fn validate(&self) -> Result<(), GatewayTlsError> {
+        if self.domains.is_empty() {
+            return Err(GatewayTlsError::NoDomains);
+        }
+        for domain in self.normalized_domains() {
+            // HTTP-01 can't prove wildcards, and the domain is only a name
+            let valid = !domain.is_empty()
+                && domain.split('.').count() > 1
+                && domain
+                    .split('.')
+                    .all(|label| !label.is_empty() && !label.starts_with('-'))
+                && domain
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-');
+            if !valid {
+                return Err(GatewayTlsError::InvalidDomain(domain));
+            }
+        }
+        Ok(())
+    }
+
+    /// The configured domains, lowercased
+    fn normalized_domains(&self) -> Vec<String> {
+        self.domains
+            .iter()
+            .map(|domain| domain.trim().to_ascii_lowercase())
+            .collect()
+    }
+}
+
+/// Key authorizations of the HTTP-01 challenges of pending orders, by token
+#[derive(Debug, Clone, Default)]
+pub struct AcmeChallenges(Arc<RwLock<HashMap<String, String>>>);
+
+impl AcmeChallenges {
+    pub fn get(&self, token: &str) -> Option<String> {
+        self.0.read().unwrap().get(token).cloned()
+    }
+
+    fn insert(&self, token: String, key_authorization: String) {
+        self.0.write().unwrap().insert(token, key_authorization);
+    }
+
+    fn remove(&self, token: &str) {
+        self.0.write().unwrap().remove(token);
+    }
+}
+
+/// Hands rustls the current certificate, which renewals swap out
+#[derive(Debug, Default)]
+pub struct CertResolver(RwLock<Option<Arc<CertifiedKey>>>);
+
+impl CertResolver {
+    fn set(&self, key: CertifiedKey) {
+        *self.0.write().unwrap() = Some(Arc::new(key));
+    }
+
+    pub fn has_certificate(&self) -> bool {
+        self.0.read().unwrap().is_some()
+    }
+}
+
+impl ResolvesServerCert for CertResolver {
+    fn resolve(&self, _client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        self.0.read().unwrap().clone()
+    }
+}
+
+/// What the gateway needs to know of a certificate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertificateInfo {
+    /// DNS names the certificate is valid for, lowercased
+    pub domains: Vec<String>,
+    pub not_before: OffsetDateTime,
+    pub not_after: OffsetDateTime,
+}
+
+impl CertificateInfo {
+    /// Read the leaf certificate of a PEM chain
+    pub fn parse(chain_pem: &str) -> Result<Self, GatewayTlsError> {
+        let der = rustls_pemfile::certs(&mut chain_pem.as_bytes())
+            .next()
+            .ok_or(GatewayTlsError::InvalidCertificate(
+                "no certificate".to_string(),
+            ))??;
+        let (_, cert) = x509_parser::parse_x509_certificate(&der)
+            .map_err(|e| GatewayTlsError::InvalidCertificate(e.to_string()))?;
+        let timestamp = |time: i64| {
+            OffsetDateTime::from_unix_timestamp(time)
+                .map_err(|e| GatewayTlsError::InvalidCertificate(e.to_string()))
+        };
+        let domains = match cert.subject_alternative_name() {
+            Ok(Some(names)) => names
+                .value
+                .general_names
+                .iter()
+                .filter_map(|name| match name {
+                    GeneralName::DNSName(name) => Some(name.to_ascii_lowercase()),
+                    _ => None,
+                })
+                .collect(),
+            Ok(None) => Vec::new(),
+            Err(e) => return Err(GatewayTlsError::InvalidCertificate(e.to_string())),
+        };
+        Ok(Self {
+            domains,
+            not_before: timestamp(cert.validity().not_before.timestamp())?,
+            not_after: timestamp(cert.validity().not_after.timestamp())?,
+        })
+    }
+
+    /// When the certificate should be renewed: once two thirds of its
+    /// lifetime have passed
+    pub fn renew_at(&self) -> OffsetDateTime {
+        self.not_after - (self.not_after - self.not_before) / 3
+    }
+
+    /// Whether the certificate is valid for every one of `domains`
+    pub fn covers(&self, domains: &[String]) -> bool {
+        domains.iter().all(|domain| self.domains.contains(domain))
+    }
+}
+
+/// A certificate chain and its key, as rustls serves them
+pub fn certified_key(chain_pem: &str, key_pem: &str) -> Result<CertifiedKey, GatewayTlsError> {
+    let chain = rustls_pemfile::certs(&mut chain_pem.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+    let key = rustls_pemfile::private_key(&mut key_pem.as_bytes())?
+        .ok_or_else(|| GatewayTlsError::InvalidCertificate("no private key".to_string()))?;
+    let key = rustls::crypto::ring::sign::any_supported_type(&key)
+        .map_err(|e| GatewayTlsError::InvalidCertificate(e.to_string()))?;
+    Ok(CertifiedKey::new(chain, key))
+}
+
+/// Keeps a certificate for the configured domains installed
+#[derive(Debug)]
+pub struct GatewayTls {
+    config: GatewayTlsConfig,
+    dir: PathBuf,
+    challenges: AcmeChallenges,
+    resolver: Arc<CertResolver>,
+}
+
+impl GatewayTls {
+    pub fn new(config: GatewayTlsConfig, jax_dir: &Path) -> Self {
+        Self {
+            config,
+            dir: jax_dir.join(TLS_DIR_NAME),
+            challenges: AcmeChallenges::default(),
+            resolver: Arc::new(CertResolver::default()),
+        }
+    }
+
+    pub fn config(&self) -> &GatewayTlsConfig {
+        &self.config
+    }
+
+    pub fn challenges(&self) -> &AcmeChallenges {
+        &self.challenges
+    }
+
+    /// rustls configuration serving the current certificate
+    pub fn server_config(&self) -> Result<Arc<rustls::ServerConfig>, GatewayTlsError> {
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let mut config = rustls::ServerConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()?
+            .with_no_client_auth()
+            .with_cert_resolver(self.resolver.clone());
+        config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+        Ok(Arc::new(config))
+    }
+
+    /// Order certificates as needed until shutdown
+    pub async fn run(self: Arc<Self>, mut shutdown_rx: watch::Receiver<()>) {
+        let mut renew_at = match self.load_stored() {
+            Ok(renew_at) => renew_at,
+            Err(e) => {
+                tracing::warn!("Ignoring stored gateway certificate: {}", e);
+                None
+            }
+        };
+        loop {
+            let now = OffsetDateTime::now_utc();
+            let wait = renew_at
+                .map(|at| Duration::try_from(at - now).unwrap_or(Duration::ZERO))
+                .unwrap_or(Duration::ZERO);
+            tokio::select! {
+                _ = shutdown_rx.changed() => return,
+                _ = tokio::time::sleep(wait) => {}
+            }
+
+            tracing::info!(
+                "Ordering a gateway certificate for {}",
+                self.config.domains.join(", ")
+            );
+            renew_at = match self.issue().await {
+                Ok(info) => {
+                    tracing::info!(
+                        "Installed gateway certificate valid until {}",
+                        info.not_after
+                    );
+                    Some(info.renew_at())
+                }
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to order gateway certificate, retrying in {}s: {}",
+                        RETRY_INTERVAL.as_secs(),
+                        e
+                    );
+                    Some(OffsetDateTime::now_utc() + RETRY_INTERVAL)
+                }
+            };
+        }
+    }
+
+    /// Install the stored certificate if it covers the configured domains,
+    /// returning when to renew it; None if a new one is needed right away
+    fn load_stored(&self) -> Result<Option<OffsetDateTime>, GatewayTlsError> {
+        let cert_path = self.dir.join(CERT_FILE_NAME);
+        let key_path = self.dir.join(KEY_FILE_NAME);
+        if !cert_path.exists() || !key_path.exists() {
+            return Ok(None);
+        }
+        let chain_pem = fs::read_to_string(cert_path)?;
+        let key_pem = fs::read_to_string(key_path)?;
+        let info = CertificateInfo::parse(&chain_pem)?;
+        if !info.covers(&self.config.normalized_domains())
+            || info.not_after <= OffsetDateTime::now_utc()
+        {
+            return Ok(None);
+        }
+        self.resolver.set(certified_key(&chain_pem, &key_pem)?);
+        tracing::info!("Loaded gateway certificate valid until {}", info.not_after);
+        Ok(Some(info.renew_at()))
+    }
+
+    /// Order a certificate for the configured domains, then store and
+    /// install it
+    async fn issue(&self) -> Result<CertificateInfo, GatewayTlsError> {
+        let domains = self.config.normalized_domains();
+        let account = self.account().await?;
+        let identifiers: Vec<_> = domains.iter().cloned().map(Identifier::Dns).collect();
+        let mut order = account
+            .new_order(&NewOrder {
+                identifiers: &identifiers,
+            })
+            .await?;
+
+        let mut tokens = Vec::new();
+        let result = self.prove(&mut order, &mut tokens).await;
+        for token in &tokens {
+            self.challenges.remove(token);
+        }
+        result?;
+
+        let key_pair = rcgen::KeyPair::generate()?;
+        let mut params = rcgen::CertificateParams::new(domains)?;
+        params.distinguished_name = rcgen::DistinguishedName::new();
+        let csr = params.serialize_request(&key_pair)?;
+        order.finalize(csr.der()).await?;
+        let mut chain_pem = None;
+        for _ in 0..POLL_ATTEMPTS {
+            if let Some(certificate) = order.certificate().await? {
+                chain_pem = Some(certificate);
+                break;
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+        let chain_pem = chain_pem.ok_or(GatewayTlsError::Timeout)?;
+        let key_pem = key_pair.serialize_pem();
+
+        let info = CertificateInfo::parse(&chain_pem)?;
+        let key = certified_key(&chain_pem, &key_pem)?;
+        fs::create_dir_all(&self.dir)?;
+        write_file(&self.dir.join(KEY_FILE_NAME), key_pem.as_bytes())?;
+        write_file(&self.dir.join(CERT_FILE_NAME), chain_pem.as_bytes())?;
+        self.resolver.set(key);
+        Ok(info)
+    }
+
+    /// Answer the HTTP-01 challenges of an order's authorizations and wait
+    /// for the order to be ready, noting the tokens handed out in `tokens`
+    async fn prove(
+        &self,
+        order: &mut Order,
+        tokens: &mut Vec<String>,
+    ) -> Result<(), GatewayTlsError> {
+        for authorization in order.authorizations().await? {
+            match authorization.status {
+                AuthorizationStatus::Pending => {}
+                AuthorizationStatus::Valid => continue,
+                status => return Err(GatewayTlsError::Order(format!("{:?}", status))),
+            }
+            let challenge = authorization
+                .challenges
+                .iter()
+                .find(|challenge| challenge.r#type == ChallengeType::Http01)
+                .ok_or(GatewayTlsError::NoHttpChallenge)?;
+            let key_authorization = order.key_authorization(challenge);
+            self.challenges.insert(
+                challenge.token.clone(),
+                key_authorization.as_str().to_string(),
+            );
+            tokens.push(challenge.token.clone());
+            order.set_challenge_ready(&challenge.url).await?;
+        }
+
+        for _ in 0..POLL_ATTEMPTS {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            let state = order.refresh().await?;
+            match state.status {
+                OrderStatus::Ready | OrderStatus::Valid => return Ok(()),
+                OrderStatus::Invalid => {
+                    return Err(GatewayTlsError::Order(format!("{:?}", state.error)))
+                }
+                OrderStatus::Pending | OrderStatus::Processing => {}
+            }
+        }
+        Err(GatewayTlsError::Timeout)
+    }
+
+    /// The stored ACME account, registered on first use
+    async fn account(&self) -> Result<Account, GatewayTlsError> {
+        let path = self.dir.join(ACCOUNT_FILE_NAME);
+        if path.exists() {
+            let credentials: AccountCredentials = serde_json::from_slice(&fs::read(&path)?)?;
+            return Ok(Account::from_credentials(credentials).await?);
+        }
+
+        let contact = self
+            .config
+            .contact
+            .as_ref()
+            .map(|email| format!("mailto:{}", email));
+        let contact: Vec<&str> = contact.iter().map(String::as_str).collect();
+        let (account, credentials) = Account::create(
+            &NewAccount {
+                contact: &contact,
+                terms_of_service_agreed: true,
+                only_return_existing: false,
+            },
+            &self.config.directory_url,
+            None,
+        )
+        .await?;
+        fs::create_dir_all(&self.dir)?;
+        write_file(&path, &serde_json::to_vec(&credentials)?)?;
+        Ok(account)
+    }
+}
+
+/// Replace a file readable only by the current user
+fn write_file(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let tmp = path.with_extension("tmp");
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(&tmp)?.write_all(contents)?;
+    fs::rename(tmp, path)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GatewayTlsError {
+    #[error("gateway_tls needs at least one domain")]
+    NoDomains,
+    #[error("invalid gateway_tls domain '{0}'")]
+    InvalidDomain(String),
+    #[error("invalid certificate: {0}")]
+    InvalidCertificate(String),
+    #[error("ACME error: {0}")]
+    Acme(#[from] instant_acme::Error),
+    #[error("order failed: {0}")]
+    Order(String),
+    #[error("the CA offered no HTTP-01 challenge")]
+    NoHttpChallenge,
+    #[error("timed out waiting on the CA")]
+    Timeout,
+    #[error("key generation failed: {0}")]
+    Key(#[from] rcgen::Error),
+    #[error("TLS error: {0}")]
+    Tls(#[from] rustls::Error),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid account file: {0}")]
+    Account(#[from] serde_json::Error),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn config(domains: &[&str]) -> GatewayTlsConfig {
+        GatewayTlsConfig {
+            domains: domains.iter().map(|domain| domain.to_string()).collect(),
+            contact: None,
+            directory_url: default_directory_url(),
+            https_port: default_https_port(),
+            http_port: default_http_port(),
+        }
+    }
+
+    fn self_signed(domains: &[&str], days: i64) -> (String, String) {
+        let key_pair = rcgen::KeyPair::generate().unwrap();
+        let mut params = rcgen::CertificateParams::new(
+            domains
+                .iter()
+                .map(|domain| domain.to_string())
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+        params.not_before = OffsetDateTime::now_utc() - time::Duration::days(1);
+        params.not_after = params.not_before + time::Duration::days(days);
+        let cert = params.self_signed(&key_pair).unwrap();
+        (cert.pem(), key_pair.serialize_pem())
+    }
+
+    #[test]
+    fn test_validate_domains() {
+        assert!(config(&["files.example.com"]).validate().is_ok());
+        assert!(config(&[]).validate().is_err());
+        assert!(config(&["*.example.com"]).validate().is_err());
+        assert!(config(&["https://example.com"]).validate().is_err());
+        assert!(config(&["localhost"]).validate().is_err());
+        assert_eq!(
+            config(&[" Files.Example.com"]).normalized_domains(),
+            vec!["files.example.com"]
+        );
+    }
+
+    #[test]
+    fn test_config_defaults() {
+        let parsed: GatewayTlsConfig =
+            toml::from_str(r#"domains = ["files.example.com"]"#).unwrap();
+        assert_eq!(parsed, config(&["files.example.com"]));
+    }
+
+    #[test]
+    fn test_certificate_info() {
+        let (chain_pem, key_pem) = self_signed(&["files.example.com", "www.example.com"], 90);
+        let info = CertificateInfo::parse(&chain_pem).unwrap();
+        assert_eq!(info.domains, vec!["files.example.com", "www.example.com"]);
+        assert!(info.covers(&["www.example.com".to_string()]));
+        assert!(!info.covers(&["other.example.com".to_string()]));
+        assert_eq!(info.renew_at(), info.not_before + time::Duration::days(60));
+        assert!(certified_key(&chain_pem, &key_pem).is_ok());
+        assert!(certified_key(&chain_pem, "").is_err());
+    }
+
+    #[test]
+    fn test_load_stored_certificate() {
+        let dir = tempfile::tempdir().unwrap();
+        let tls = GatewayTls::new(config(&["files.example.com"]), dir.path());
+        assert_eq!(tls.load_stored().unwrap(), None);
+
+        // Certificates for other domains are ordered again
+        let (chain_pem, key_pem) = self_signed(&["old.example.com"], 90);
+        fs::create_dir_all(&tls.dir).unwrap();
+        write_file(&tls.dir.join(CERT_FILE_NAME), chain_pem.as_bytes()).unwrap();
+        write_file(&tls.dir.join(KEY_FILE_NAME), key_pem.as_bytes()).unwrap();
+        assert_eq!(tls.load_stored().unwrap(), None);
+        assert!(!tls.resolver.has_certificate());
+
+        let (chain_pem, key_pem) = self_signed(&["files.example.com"], 90);
+        write_file(&tls.dir.join(CERT_FILE_NAME), chain_pem.as_bytes()).unwrap();
+        write_file(&tls.dir.join(KEY_FILE_NAME), key_pem.as_bytes()).unwrap();
+        let renew_at = tls.load_stored().unwrap().unwrap();
+        assert!(renew_at > OffsetDateTime::now_utc());
+        assert!(tls.resolver.has_certificate());
+    }
+
+    #[test]
+    fn test_challenges() {
+        let challenges = AcmeChallenges::default();
+        challenges.insert("token".to_string(), "token.thumbprint".to_string());
+        assert_eq!(challenges.get("token").as_deref(), Some("token.thumbprint"));
+        challenges.remove("token");
+        assert_eq!(challenges.get("token"), None);
+    }
+}
@@ -0,0 +1,243 @@
+//! The remote helper protocol (see `gitremote-helpers(7)`): git writes
+//! commands to the helper's stdin and reads the replies from its stdout
+
+use std::collections::HashSet;
+use std::io::{BufRead, Write};
+
+use super::local::LocalRepo;
+use super::object::ObjectId;
+use super::refs::{render_head, HEAD, PACKED_REFS};
+use super::store::{BucketStore, Snapshot};
+use super::GitRemoteError;
+
+/// Objects uploaded per bucket version when pushing
+const OBJECTS_PER_WRITE: usize = 256;
+
+/// Branch names `HEAD` prefers to point to on the first push
+const DEFAULT_BRANCHES: [&str; 2] = ["refs/heads/main", "refs/heads/master"];
+
+pub struct Helper {
+    store: BucketStore,
+    local: LocalRepo,
+    /// Refs as last listed to git, which pushes are checked against
+    listed: Option<Snapshot>,
+}
+
+impl Helper {
+    pub fn new(store: BucketStore, local: LocalRepo) -> Self {
+        Self {
+            store,
+            local,
+            listed: None,
+        }
+    }
+
+    /// Answer git's commands until it closes stdin or sends a blank line
+    pub async fn run<R: BufRead, W: Write>(
+        &mut self,
+        mut input: R,
+        mut output: W,
+    ) -> Result<(), GitRemoteError> {
+        loop {
+            let Some(line) = read_line(&mut input)? else {
+                return Ok(());
+            };
+            let (command, argument) = line.split_once(' ').unwrap_or((line.as_str(), ""));
+            match command {
+                "" => return Ok(()),
+                "capabilities" => write!(output, "fetch\npush\n\n")?,
+                "list" => {
+                    let snapshot = self.store.snapshot().await?;
+                    for (name, id) in snapshot.refs.iter() {
+                        writeln!(output, "{} {}", id, name)?;
+                    }
+                    if let Some(head) = &snapshot.head {
+                        if snapshot.refs.get(head).is_some() {
+                            writeln!(output, "@{} HEAD", head)?;
+                        }
+                    }
+                    writeln!(output)?;
+                    self.listed = Some(snapshot);
+                }
+                "fetch" => {
+                    let mut wanted = vec![parse_fetch(argument)?];
+                    for line in read_batch(&mut input)? {
+                        let argument = line.strip_prefix("fetch ").unwrap_or(&line);
+                        wanted.push(parse_fetch(argument)?);
+                    }
+                    self.fetch(wanted).await?;
+                    writeln!(output)?;
+                }
+                "push" => {
+                    let mut specs = vec![argument.to_string()];
+                    for line in read_batch(&mut input)? {
+                        specs.push(line.strip_prefix("push ").unwrap_or(&line).to_string());
+                    }
+                    for (dst, result) in self.push(&specs).await? {
+                        match result {
+                            Ok(()) => writeln!(output, "ok {}", dst)?,
+                            Err(reason) => writeln!(output, "error {} {}", dst, reason)?,
+                        }
+                    }
+                    writeln!(output)?;
+                }
+                _ => return Err(GitRemoteError::UnknownCommand(line)),
+            }
+            output.flush()?;
+        }
+    }
+
+    /// Copy the objects reachable from `wanted` that the local repository
+    /// doesn't have yet
+    async fn fetch(&mut self, wanted: Vec<ObjectId>) -> Result<(), GitRemoteError> {
+        let mut pending = wanted;
+        let mut seen = HashSet::new();
+        while let Some(id) = pending.pop() {
+            if !seen.insert(id) || self.local.contains(&id)? {
+                continue;
+            }
+            let object = self.store.read_object(&id).await?;
+            self.local.write(&object)?;
+            pending.extend(object.references()?);
+        }
+        Ok(())
+    }
+
+    /// Update the remote's refs from refspecs, uploading the objects they
+    /// need first. Returns the outcome of each refspec by destination ref.
+    async fn push(
+        &mut self,
+        specs: &[String],
+    ) -> Result<Vec<(String, Result<(), String>)>, GitRemoteError> {
+        let snapshot = match self.listed.take() {
+            Some(snapshot) => snapshot,
+            None => self.store.snapshot().await?,
+        };
+        let mut refs = snapshot.refs.clone();
+        let mut results = Vec::new();
+        let mut tips = Vec::new();
+
+        for spec in specs {
+            let (force, spec) = match spec.strip_prefix('+') {
+                Some(spec) => (true, spec),
+                None => (false, spec.as_str()),
+            };
+            let (src, dst) = spec.split_once(':').unwrap_or((spec, spec));
+            if src.is_empty() {
+                let result = match refs.remove(dst) {
+                    Some(_) => Ok(()),
+                    None => Err("no such ref".to_string()),
+                };
+                results.push((dst.to_string(), result));
+                continue;
+            }
+
+            let new = self.local.resolve(src)?;
+            let result = match refs.get(dst) {
+                Some(old) if old == new || force => Ok(()),
+                Some(_) if dst.starts_with("refs/tags/") => Err("already exists".to_string()),
+                Some(old) if !self.local.contains(&old)? => Err("fetch first".to_string()),
+                Some(old) if !self.local.is_ancestor(&old, &new)? => {
+                    Err("non-fast-forward".to_string())
+                }
+                _ => Ok(()),
+            };
+            if result.is_ok() {
+                refs.set(dst, new);
+                tips.push(new);
+            }
+            results.push((dst.to_string(), result));
+        }
+        if results.iter().all(|(_, result)| result.is_err()) {
+            return Ok(results);
+        }
+
+        let mut have = Vec::new();
+        for (_, id) in snapshot.refs.iter() {
+            if self.local.contains(&id)? {
+                have.push(id);
+            }
+        }
+        let objects = if tips.is_empty() {
+            Vec::new()
+        } else {
+            self.local.objects_between(&tips, &have)?
+        };
+
+        // Objects go first, each batch expecting the version the previous
+        //  one made, so a concurrent push fails this one instead of being
+        //  overwritten by its refs
+        let mut version = snapshot.version.clone();
+        let mut files = Vec::new();
+        for (i, id) in objects.iter().enumerate() {
+            let object = self
+                .local
+                .read(id)?
+                .ok_or(GitRemoteError::MissingObject(*id))?;
+            files.push((id.path(), object.encode()));
+            if files.len() == OBJECTS_PER_WRITE || i + 1 == objects.len() {
+                version = match self.store.write(std::mem::take(&mut files), &version).await {
+                    Err(GitRemoteError::Moved) => return Ok(moved(results)),
+                    result => result?,
+                };
+            }
+        }
+
+        let mut files = vec![(PACKED_REFS.to_string(), refs.render().into_bytes())];
+        if snapshot.head.is_none() {
+            let pushed: Vec<_> = results
+                .iter()
+                .filter(|(dst, result)| result.is_ok() && dst.starts_with("refs/heads/"))
+                .map(|(dst, _)| dst.as_str())
+                .collect();
+            let head = DEFAULT_BRANCHES
+                .into_iter()
+                .find(|branch| pushed.contains(branch))
+                .or_else(|| pushed.first().copied());
+            if let Some(head) = head {
+                files.push((HEAD.to_string(), render_head(head).into_bytes()));
+            }
+        }
+        match self.store.write(files, &version).await {
+            Err(GitRemoteError::Moved) => Ok(moved(results)),
+            result => result.map(|_| results),
+        }
+    }
+}
+
+/// Fail the refs a push would have updated, after the bucket moved under it
+fn moved(results: Vec<(String, Result<(), String>)>) -> Vec<(String, Result<(), String>)> {
+    results
+        .into_iter()
+        .map(|(dst, result)| {
+            let result = result.and(Err("remote changed during push, fetch first".to_string()));
+            (dst, result)
+        })
+        .collect()
+}
+
+/// `<id> <ref name>` of a fetch command
+fn parse_fetch(argument: &str) -> Result<ObjectId, GitRemoteError> {
+    let id = argument.split_whitespace().next().unwrap_or_default();
+    Ok(id.parse()?)
+}
+
+fn read_line<R: BufRead>(input: &mut R) -> Result<Option<String>, GitRemoteError> {
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(line.trim_end_matches(['\r', '\n']).to_string()))
+}
+
+/// The rest of a batch of commands, up to the blank line ending it
+fn read_batch<R: BufRead>(input: &mut R) -> Result<Vec<String>, GitRemoteError> {
+    let mut lines = Vec::new();
+    while let Some(line) = read_line(input)? {
+        if line.is_empty() {
+            break;
+        }
+        lines.push(line);
+    }
+    Ok(lines)
+}
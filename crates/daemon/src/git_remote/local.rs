@@ -0,0 +1,137 @@
+//! The local repository git runs the helper in, driven through `git`
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::PathBuf;
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+use super::object::{Object, ObjectId};
+use super::GitRemoteError;
+
+pub struct LocalRepo {
+    git_dir: PathBuf,
+    /// `git cat-file --batch`, kept running to read objects one by one
+    cat_file: Child,
+    cat_in: ChildStdin,
+    cat_out: BufReader<ChildStdout>,
+}
+
+impl LocalRepo {
+    /// Open the repository git runs the helper in (`$GIT_DIR`, as git sets
+    /// it for remote helpers, or the one of the working directory)
+    pub fn open() -> Result<Self, GitRemoteError> {
+        let git_dir = match std::env::var_os("GIT_DIR") {
+            Some(dir) => PathBuf::from(dir),
+            None => PathBuf::from(git(&["rev-parse", "--git-dir"])?.trim()),
+        };
+        let mut cat_file = Command::new("git")
+            .args(["cat-file", "--batch"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let cat_in = cat_file.stdin.take().expect("stdin is piped");
+        let cat_out = BufReader::new(cat_file.stdout.take().expect("stdout is piped"));
+        Ok(Self {
+            git_dir,
+            cat_file,
+            cat_in,
+            cat_out,
+        })
+    }
+
+    /// An object of the repository, None if it doesn't have it
+    pub fn read(&mut self, id: &ObjectId) -> Result<Option<Object>, GitRemoteError> {
+        writeln!(self.cat_in, "{}", id)?;
+        self.cat_in.flush()?;
+
+        let mut header = String::new();
+        self.cat_out.read_line(&mut header)?;
+        let fields: Vec<_> = header.split_whitespace().collect();
+        match fields.as_slice() {
+            [_, "missing"] => Ok(None),
+            [_, kind, size] => {
+                let size: usize = size
+                    .parse()
+                    .map_err(|_| GitRemoteError::Git(format!("cat-file header: {}", header)))?;
+                // Contents are followed by a newline
+                let mut data = vec![0; size + 1];
+                self.cat_out.read_exact(&mut data)?;
+                data.truncate(size);
+                Ok(Some(Object::new(kind.parse()?, data)))
+            }
+            _ => Err(GitRemoteError::Git(format!("cat-file header: {}", header))),
+        }
+    }
+
+    pub fn contains(&mut self, id: &ObjectId) -> Result<bool, GitRemoteError> {
+        Ok(self.read(id)?.is_some())
+    }
+
+    /// Store an object as a loose object
+    pub fn write(&self, object: &Object) -> Result<(), GitRemoteError> {
+        let path = self.git_dir.join(object.id().path());
+        if path.exists() {
+            return Ok(());
+        }
+        let dir = path.parent().expect("object paths have a directory");
+        std::fs::create_dir_all(dir)?;
+        let mut file = tempfile::NamedTempFile::new_in(dir)?;
+        file.write_all(&object.encode())?;
+        file.persist(&path).map_err(|e| e.error)?;
+        Ok(())
+    }
+
+    /// The object a revision names
+    pub fn resolve(&self, rev: &str) -> Result<ObjectId, GitRemoteError> {
+        Ok(git(&["rev-parse", "--verify", rev])?.trim().parse()?)
+    }
+
+    /// Whether `new` is `old` or one of its descendants
+    pub fn is_ancestor(&self, old: &ObjectId, new: &ObjectId) -> Result<bool, GitRemoteError> {
+        let status = Command::new("git")
+            .args(["merge-base", "--is-ancestor"])
+            .arg(old.to_string())
+            .arg(new.to_string())
+            .status()?;
+        Ok(status.success())
+    }
+
+    /// Objects reachable from `tips` but not from `have`
+    pub fn objects_between(
+        &self,
+        tips: &[ObjectId],
+        have: &[ObjectId],
+    ) -> Result<Vec<ObjectId>, GitRemoteError> {
+        let mut args = vec!["rev-list".to_string(), "--objects".to_string()];
+        args.extend(tips.iter().map(ObjectId::to_string));
+        if !have.is_empty() {
+            args.push("--not".to_string());
+            args.extend(have.iter().map(ObjectId::to_string));
+        }
+        let args: Vec<_> = args.iter().map(String::as_str).collect();
+        git(&args)?
+            .lines()
+            .filter_map(|line| line.split_whitespace().next())
+            .map(|id| Ok(id.parse()?))
+            .collect()
+    }
+}
+
+impl Drop for LocalRepo {
+    fn drop(&mut self) {
+        let _ = self.cat_file.kill();
+        let _ = self.cat_file.wait();
+    }
+}
+
+/// Run git, returning its output
+fn git(args: &[&str]) -> Result<String, GitRemoteError> {
+    let output = Command::new("git").args(args).output()?;
+    if !output.status.success() {
+        return Err(GitRemoteError::Git(format!(
+            "git {}: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -0,0 +1,132 @@
+//! Git remote helper: host git repositories in buckets
+//!
+//! `git-remote-jax` lets git push to and fetch from a repository kept in a
+//! bucket, so a team can share a repository the way it shares files, with
+//! the bucket's encryption and sync. Git runs the helper for remote URLs of
+//! the form:
+//!
+//! ```text
+//! jax://<bucket id or name>/<path of the repository in the bucket>
+//! ```
+//!
+//! e.g. `git clone jax://team/repos/app.git`. The helper talks to the local
+//! daemon's API, at `$JAX_REMOTE` (default `http://localhost:3000`, as for
+//! the `jax` CLI) as the profile in `$JAX_PROFILE` if set.
+//!
+//! Inside the bucket a repository is laid out as git lays out a bare one:
+//! objects are stored as loose objects (see [`object`]) and refs in a
+//! `packed-refs` file next to `HEAD` (see [`refs`]). A push uploads the
+//! objects the remote is missing, then rewrites `packed-refs`, expecting the
+//! bucket version its refs were listed at, so concurrent pushes can't lose
+//! each other's commits: the later one is refused and has to fetch first.
+
+pub mod helper;
+pub mod local;
+pub mod object;
+pub mod refs;
+pub mod store;
+
+use url::Url;
+
+use crate::http_server::api::client::{ApiClient, ApiError};
+use helper::Helper;
+use local::LocalRepo;
+use object::{ObjectError, ObjectId};
+use store::BucketStore;
+
+/// API URL the helper uses unless `$JAX_REMOTE` is set
+pub const DEFAULT_REMOTE: &str = "http://localhost:3000";
+
+#[derive(Debug, thiserror::Error)]
+pub enum GitRemoteError {
+    #[error("Invalid remote URL {0}: expected jax://<bucket>/<path>")]
+    InvalidUrl(String),
+    #[error("API error: {0}")]
+    Api(#[from] ApiError),
+    #[error("URL parse error: {0}")]
+    UrlParse(#[from] url::ParseError),
+    #[error("Unknown bucket: {0}")]
+    UnknownBucket(uuid::Uuid),
+    #[error("Object {0} is missing")]
+    MissingObject(ObjectId),
+    #[error(transparent)]
+    Object(#[from] ObjectError),
+    #[error("Failed to write to the bucket: {0}")]
+    Write(String),
+    #[error("Bucket changed during the push")]
+    Moved,
+    #[error("Git error: {0}")]
+    Git(String),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Unknown command: {0}")]
+    UnknownCommand(String),
+}
+
+/// Where a remote URL points: a bucket and a repository's path in it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteUrl {
+    /// Bucket ID or name
+    pub bucket: String,
+    /// Absolute path without a trailing slash, or `/`
+    pub path: String,
+}
+
+impl RemoteUrl {
+    /// Parse a remote URL as git passes it to the helper: `jax://<bucket>/<path>`,
+    /// or `<bucket>/<path>` for `jax::<bucket>/<path>`
+    pub fn parse(url: &str) -> Result<Self, GitRemoteError> {
+        let invalid = || GitRemoteError::InvalidUrl(url.to_string());
+        let rest = url.strip_prefix("jax://").unwrap_or(url);
+        let (bucket, path) = rest.split_once('/').unwrap_or((rest, ""));
+        if bucket.is_empty() {
+            return Err(invalid());
+        }
+        let segments: Vec<_> = path.split('/').filter(|s| !s.is_empty()).collect();
+        if segments.iter().any(|s| matches!(*s, "." | "..")) {
+            return Err(invalid());
+        }
+        Ok(Self {
+            bucket: bucket.to_string(),
+            path: format!("/{}", segments.join("/")),
+        })
+    }
+}
+
+/// Serve git's commands for the remote at `url`
+pub async fn run_helper<R, W>(url: &str, input: R, output: W) -> Result<(), GitRemoteError>
+where
+    R: std::io::BufRead,
+    W: std::io::Write,
+{
+    let url = RemoteUrl::parse(url)?;
+    let remote = std::env::var("JAX_REMOTE").unwrap_or_else(|_| DEFAULT_REMOTE.to_string());
+    let remote = Url::parse(&remote)?;
+    let client = match std::env::var("JAX_PROFILE") {
+        Ok(profile) => ApiClient::with_profile(&remote, &profile)?,
+        Err(_) => ApiClient::new(&remote)?,
+    };
+
+    let store = BucketStore::open(client, &url).await?;
+    let local = LocalRepo::open()?;
+    Helper::new(store, local).run(input, output).await
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_url() {
+        let url = RemoteUrl::parse("jax://team/repos/app.git/").unwrap();
+        assert_eq!(url.bucket, "team");
+        assert_eq!(url.path, "/repos/app.git");
+        assert_eq!(RemoteUrl::parse("team").unwrap().path, "/");
+        assert_eq!(
+            RemoteUrl::parse("team/app.git").unwrap(),
+            RemoteUrl::parse("jax://team/app.git").unwrap()
+        );
+        assert!(RemoteUrl::parse("jax:///app.git").is_err());
+        assert!(RemoteUrl::parse("jax://team/../app.git").is_err());
+    }
+}
@@ -0,0 +1,237 @@
+//! Object mapping: git objects as files of the bucket
+//!
+//! Each object is stored as git stores a loose object: the zlib-compressed
+//! `<kind> <size>\0<content>` at `objects/<first 2 hex>/<other 38 hex>` under
+//! the repository root. An object's ID is the SHA-1 of its decompressed
+//! file, so objects read back from the bucket are checked against the ID
+//! they were asked for.
+
+use std::fmt;
+use std::str::FromStr;
+
+use sha1::{Digest, Sha1};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ObjectError {
+    #[error("Invalid object ID: {0}")]
+    InvalidId(String),
+    #[error("Unknown object kind: {0}")]
+    UnknownKind(String),
+    #[error("Corrupt object: {0}")]
+    Corrupt(String),
+    #[error("Object {0} does not match its contents")]
+    Mismatch(ObjectId),
+}
+
+/// A git object ID, the SHA-1 of the object
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ObjectId([u8; 20]);
+
+impl ObjectId {
+    /// Path of the object's file, relative to the repository root
+    pub fn path(&self) -> String {
+        let hex = self.to_string();
+        format!("objects/{}/{}", &hex[..2], &hex[2..])
+    }
+}
+
+impl FromStr for ObjectId {
+    type Err = ObjectError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut id = [0u8; 20];
+        hex::decode_to_slice(s, &mut id).map_err(|_| ObjectError::InvalidId(s.to_string()))?;
+        Ok(Self(id))
+    }
+}
+
+impl fmt::Display for ObjectId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.0))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectKind {
+    Commit,
+    Tree,
+    Blob,
+    Tag,
+}
+
+impl ObjectKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ObjectKind::Commit => "commit",
+            ObjectKind::Tree => "tree",
+            ObjectKind::Blob => "blob",
+            ObjectKind::Tag => "tag",
+        }
+    }
+}
+
+impl FromStr for ObjectKind {
+    type Err = ObjectError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "commit" => Ok(ObjectKind::Commit),
+            "tree" => Ok(ObjectKind::Tree),
+            "blob" => Ok(ObjectKind::Blob),
+            "tag" => Ok(ObjectKind::Tag),
+            _ => Err(ObjectError::UnknownKind(s.to_string())),
+        }
+    }
+}
+
+/// A git object and its contents
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Object {
+    pub kind: ObjectKind,
+    pub data: Vec<u8>,
+}
+
+impl Object {
+    pub fn new(kind: ObjectKind, data: Vec<u8>) -> Self {
+        Self { kind, data }
+    }
+
+    fn header(&self) -> String {
+        format!("{} {}\0", self.kind.as_str(), self.data.len())
+    }
+
+    pub fn id(&self) -> ObjectId {
+        let mut hasher = Sha1::new();
+        hasher.update(self.header().as_bytes());
+        hasher.update(&self.data);
+        ObjectId(hasher.finalize().into())
+    }
+
+    /// The object as a loose object file
+    pub fn encode(&self) -> Vec<u8> {
+        let mut raw = self.header().into_bytes();
+        raw.extend_from_slice(&self.data);
+        miniz_oxide::deflate::compress_to_vec_zlib(&raw, 1)
+    }
+
+    /// Read a loose object file, checking that it is the object `id`
+    pub fn decode(id: &ObjectId, file: &[u8]) -> Result<Self, ObjectError> {
+        let raw = miniz_oxide::inflate::decompress_to_vec_zlib(file)
+            .map_err(|e| ObjectError::Corrupt(format!("{}: {:?}", id, e.status)))?;
+        let nul = raw
+            .iter()
+            .position(|b| *b == 0)
+            .ok_or_else(|| ObjectError::Corrupt(format!("{}: no header", id)))?;
+        let header = std::str::from_utf8(&raw[..nul])
+            .map_err(|_| ObjectError::Corrupt(format!("{}: bad header", id)))?;
+        let (kind, size) = header
+            .split_once(' ')
+            .ok_or_else(|| ObjectError::Corrupt(format!("{}: bad header", id)))?;
+        let data = raw[nul + 1..].to_vec();
+        if size.parse::<usize>().ok() != Some(data.len()) {
+            return Err(ObjectError::Corrupt(format!("{}: wrong size", id)));
+        }
+
+        let object = Self::new(kind.parse()?, data);
+        if object.id() != *id {
+            return Err(ObjectError::Mismatch(*id));
+        }
+        Ok(object)
+    }
+
+    /// Objects this one points to: a commit's tree and parents, a tree's
+    /// entries (except submodule commits, which live in other repositories)
+    /// and a tag's target
+    pub fn references(&self) -> Result<Vec<ObjectId>, ObjectError> {
+        match self.kind {
+            ObjectKind::Blob => Ok(Vec::new()),
+            ObjectKind::Commit | ObjectKind::Tag => {
+                let text = String::from_utf8_lossy(&self.data);
+                text.lines()
+                    .take_while(|line| !line.is_empty())
+                    .filter_map(|line| {
+                        let (field, value) = line.split_once(' ')?;
+                        matches!(field, "tree" | "parent" | "object").then_some(value)
+                    })
+                    .map(str::parse)
+                    .collect()
+            }
+            ObjectKind::Tree => {
+                let mut references = Vec::new();
+                let mut rest = self.data.as_slice();
+                while !rest.is_empty() {
+                    let corrupt = || ObjectError::Corrupt(format!("{}: bad tree entry", self.id()));
+                    let nul = rest.iter().position(|b| *b == 0).ok_or_else(corrupt)?;
+                    let id = rest.get(nul + 1..nul + 21).ok_or_else(corrupt)?;
+                    if !rest.starts_with(b"160000 ") {
+                        references.push(ObjectId(id.try_into().map_err(|_| corrupt())?));
+                    }
+                    rest = &rest[nul + 21..];
+                }
+                Ok(references)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ids_match_git() {
+        // `echo hello | git hash-object --stdin`
+        let blob = Object::new(ObjectKind::Blob, b"hello\n".to_vec());
+        assert_eq!(
+            blob.id().to_string(),
+            "ce013625030ba8dba906f756967f9e9ca394464a"
+        );
+        let empty_tree = Object::new(ObjectKind::Tree, Vec::new());
+        assert_eq!(
+            empty_tree.id().to_string(),
+            "4b825dc642cb6eb9a060e54bf8d69288fbee4904"
+        );
+        assert_eq!(
+            blob.id().path(),
+            "objects/ce/013625030ba8dba906f756967f9e9ca394464a"
+        );
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let blob = Object::new(ObjectKind::Blob, b"hello\n".to_vec());
+        let file = blob.encode();
+        assert_eq!(Object::decode(&blob.id(), &file).unwrap(), blob);
+
+        let other = Object::new(ObjectKind::Blob, b"other\n".to_vec());
+        assert!(matches!(
+            Object::decode(&other.id(), &file),
+            Err(ObjectError::Mismatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_references() {
+        let blob = Object::new(ObjectKind::Blob, b"hello\n".to_vec()).id();
+        let submodule: ObjectId = "1111111111111111111111111111111111111111".parse().unwrap();
+        let mut tree = Vec::new();
+        for (mode, name, id) in [("100644", "a.txt", blob), ("160000", "lib", submodule)] {
+            tree.extend_from_slice(format!("{} {}\0", mode, name).as_bytes());
+            tree.extend_from_slice(&id.0);
+        }
+        let tree = Object::new(ObjectKind::Tree, tree);
+        assert_eq!(tree.references().unwrap(), [blob]);
+
+        let commit = Object::new(
+            ObjectKind::Commit,
+            format!(
+                "tree {}\nparent {}\nauthor a <a@a> 0 +0000\n\nparent {}\n",
+                tree.id(),
+                submodule,
+                blob
+            )
+            .into_bytes(),
+        );
+        assert_eq!(commit.references().unwrap(), [tree.id(), submodule]);
+    }
+}
@@ -0,0 +1,98 @@
+//! Ref storage: a repository's branches and tags live in one `packed-refs`
+//! file at its root, in git's format, and its default branch in `HEAD`.
+//! Keeping every ref in one file lets a push update them all in a single
+//! bucket version.
+
+use std::collections::BTreeMap;
+
+use super::object::{ObjectError, ObjectId};
+
+pub const PACKED_REFS: &str = "packed-refs";
+pub const HEAD: &str = "HEAD";
+
+/// Ref names and the objects they point to
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Refs(BTreeMap<String, ObjectId>);
+
+impl Refs {
+    /// Parse a `packed-refs` file. Peeled tag lines (`^<id>`) and comments
+    /// are skipped; peeling is left to git.
+    pub fn parse(text: &str) -> Result<Self, ObjectError> {
+        let mut refs = BTreeMap::new();
+        for line in text.lines() {
+            if line.is_empty() || line.starts_with('#') || line.starts_with('^') {
+                continue;
+            }
+            let (id, name) = line
+                .split_once(' ')
+                .ok_or_else(|| ObjectError::Corrupt(format!("packed-refs line: {}", line)))?;
+            refs.insert(name.to_string(), id.parse()?);
+        }
+        Ok(Self(refs))
+    }
+
+    pub fn render(&self) -> String {
+        let mut text = "# pack-refs with: sorted\n".to_string();
+        for (name, id) in &self.0 {
+            text.push_str(&format!("{} {}\n", id, name));
+        }
+        text
+    }
+
+    pub fn get(&self, name: &str) -> Option<ObjectId> {
+        self.0.get(name).copied()
+    }
+
+    pub fn set(&mut self, name: &str, id: ObjectId) {
+        self.0.insert(name.to_string(), id);
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<ObjectId> {
+        self.0.remove(name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, ObjectId)> {
+        self.0.iter().map(|(name, id)| (name.as_str(), *id))
+    }
+}
+
+/// The ref a `HEAD` file points to
+pub fn parse_head(text: &str) -> Option<String> {
+    text.trim()
+        .strip_prefix("ref: ")
+        .map(|name| name.trim().to_string())
+}
+
+pub fn render_head(name: &str) -> String {
+    format!("ref: {}\n", name)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_packed_refs() {
+        let main: ObjectId = "ce013625030ba8dba906f756967f9e9ca394464a".parse().unwrap();
+        let tag: ObjectId = "4b825dc642cb6eb9a060e54bf8d69288fbee4904".parse().unwrap();
+        let text = format!(
+            "# pack-refs with: peeled fully-peeled sorted\n{} refs/heads/main\n{} refs/tags/v1\n^{}\n",
+            main, tag, main
+        );
+        let refs = Refs::parse(&text).unwrap();
+        assert_eq!(refs.get("refs/heads/main"), Some(main));
+        assert_eq!(refs.get("refs/tags/v1"), Some(tag));
+        assert_eq!(refs.iter().count(), 2);
+        assert_eq!(Refs::parse(&refs.render()).unwrap(), refs);
+        assert!(Refs::parse("not a ref line").is_err());
+    }
+
+    #[test]
+    fn test_head() {
+        assert_eq!(
+            parse_head(&render_head("refs/heads/main")).as_deref(),
+            Some("refs/heads/main")
+        );
+        assert_eq!(parse_head("ce013625030ba8dba906f756967f9e9ca394464a"), None);
+    }
+}
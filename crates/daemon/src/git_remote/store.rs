@@ -0,0 +1,170 @@
+//! A repository in a bucket, read and written through the daemon's API
+
+use base64::Engine;
+use reqwest::{multipart, StatusCode};
+use uuid::Uuid;
+
+use super::object::{Object, ObjectId};
+use super::refs::{parse_head, Refs, HEAD, PACKED_REFS};
+use super::{GitRemoteError, RemoteUrl};
+use crate::http_server::api::client::{ApiClient, ApiError};
+use crate::http_server::api::v0::bucket::add::AddResponse;
+use crate::http_server::api::v0::bucket::cat::CatRequest;
+use crate::http_server::api::v0::bucket::ListRequest;
+
+/// The refs of a repository at one bucket version
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    /// Bucket version the refs were read at
+    pub version: String,
+    pub refs: Refs,
+    /// The ref `HEAD` points to, None for a repository never pushed to
+    pub head: Option<String>,
+}
+
+pub struct BucketStore {
+    client: ApiClient,
+    bucket_id: Uuid,
+    root: String,
+}
+
+impl BucketStore {
+    pub async fn open(mut client: ApiClient, url: &RemoteUrl) -> Result<Self, GitRemoteError> {
+        let bucket_id = match Uuid::parse_str(&url.bucket) {
+            Ok(id) => id,
+            Err(_) => client.resolve_bucket_name(&url.bucket).await?,
+        };
+        Ok(Self {
+            client,
+            bucket_id,
+            root: url.path.clone(),
+        })
+    }
+
+    /// Absolute bucket path of a file of the repository
+    fn path(&self, name: &str) -> String {
+        if self.root == "/" {
+            format!("/{}", name)
+        } else {
+            format!("{}/{}", self.root, name)
+        }
+    }
+
+    /// The bucket's current version
+    async fn version(&mut self) -> Result<String, GitRemoteError> {
+        let buckets = self
+            .client
+            .call(ListRequest {
+                prefix: None,
+                filter: None,
+                sort: None,
+                page: Default::default(),
+                workspace: None,
+                starred: false,
+            })
+            .await?
+            .buckets;
+        buckets
+            .into_iter()
+            .find(|bucket| bucket.bucket_id == self.bucket_id)
+            .map(|bucket| bucket.link.hash().to_string())
+            .ok_or(GitRemoteError::UnknownBucket(self.bucket_id))
+    }
+
+    /// A file of the repository, None if it doesn't exist
+    async fn read(
+        &mut self,
+        name: &str,
+        at: Option<&str>,
+    ) -> Result<Option<Vec<u8>>, GitRemoteError> {
+        let response = self
+            .client
+            .call(CatRequest {
+                bucket_id: self.bucket_id,
+                path: self.path(name),
+                at: at.map(str::to_string),
+                min_version: None,
+                download: None,
+            })
+            .await;
+        match response {
+            Ok(response) => {
+                let data = base64::engine::general_purpose::STANDARD
+                    .decode(&response.content)
+                    .map_err(|e| GitRemoteError::Api(ApiError::Other(e.to_string())))?;
+                Ok(Some(data))
+            }
+            Err(ApiError::HttpStatus(StatusCode::NOT_FOUND, _)) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// The repository's refs at the bucket's current version
+    pub async fn snapshot(&mut self) -> Result<Snapshot, GitRemoteError> {
+        let version = self.version().await?;
+        let refs = match self.read(PACKED_REFS, Some(&version)).await? {
+            Some(text) => Refs::parse(&String::from_utf8_lossy(&text))?,
+            None => Refs::default(),
+        };
+        let head = self
+            .read(HEAD, Some(&version))
+            .await?
+            .and_then(|text| parse_head(&String::from_utf8_lossy(&text)));
+        Ok(Snapshot {
+            version,
+            refs,
+            head,
+        })
+    }
+
+    pub async fn read_object(&mut self, id: &ObjectId) -> Result<Object, GitRemoteError> {
+        let file = self
+            .read(&id.path(), None)
+            .await?
+            .ok_or(GitRemoteError::MissingObject(*id))?;
+        Ok(Object::decode(id, &file)?)
+    }
+
+    /// Write files of the repository in one bucket version, unless the
+    /// bucket has moved on from `expected_version`. Returns the new version.
+    pub async fn write(
+        &mut self,
+        files: Vec<(String, Vec<u8>)>,
+        expected_version: &str,
+    ) -> Result<String, GitRemoteError> {
+        let mut form = multipart::Form::new()
+            .text("bucket_id", self.bucket_id.to_string())
+            .text("mount_path", self.root.clone())
+            .text("expected_version", expected_version.to_string());
+        for (name, data) in files {
+            form = form.part("file", multipart::Part::bytes(data).file_name(name));
+        }
+
+        let url = self.client.base_url().join("/api/v0/bucket/add")?;
+        let response = self
+            .client
+            .http_client()
+            .post(url)
+            .multipart(form)
+            .send()
+            .await
+            .map_err(ApiError::from)?;
+        match response.status() {
+            status if status.is_success() => {
+                let response: AddResponse = response.json().await.map_err(ApiError::from)?;
+                if response.failed_files > 0 {
+                    return Err(GitRemoteError::Write(format!(
+                        "{} file(s) failed to upload",
+                        response.failed_files
+                    )));
+                }
+                Ok(response.bucket_link.hash().to_string())
+            }
+            StatusCode::CONFLICT => Err(GitRemoteError::Moved),
+            status => {
+                let text = response.text().await.map_err(ApiError::from)?;
+                Err(ApiError::from_response(status, &text).into())
+            }
+        }
+    }
+}
@@ -5,6 +5,8 @@ use uuid::Uuid;
 use super::error::ApiError;
 use super::ApiRequest;
 use crate::http_server::api::v0::bucket::list::{ListRequest, ListResponse};
+use crate::pagination::PageRequest;
+use crate::profiles::PROFILE_HEADER;
 
 #[derive(Debug, Clone)]
 pub struct ApiClient {
@@ -14,7 +16,20 @@ pub struct ApiClient {
 
 impl ApiClient {
     pub fn new(remote: &Url) -> Result<Self, ApiError> {
-        let mut default_headers = HeaderMap::new();
+        Self::build(remote, HeaderMap::new())
+    }
+
+    /// Client whose requests are served by a named profile of the daemon
+    /// (see [`crate::profiles`])
+    pub fn with_profile(remote: &Url, profile: &str) -> Result<Self, ApiError> {
+        let mut headers = HeaderMap::new();
+        let value = HeaderValue::from_str(profile)
+            .map_err(|_| ApiError::Other(format!("Invalid profile name: {}", profile)))?;
+        headers.insert(PROFILE_HEADER, value);
+        Self::build(remote, headers)
+    }
+
+    fn build(remote: &Url, mut default_headers: HeaderMap) -> Result<Self, ApiError> {
         default_headers.insert("Content-Type", HeaderValue::from_static("application/json"));
         let client = Client::builder().default_headers(default_headers).build()?;
 
@@ -31,10 +46,8 @@ impl ApiClient {
         if response.status().is_success() {
             Ok(response.json::<T::Response>().await?)
         } else {
-            Err(ApiError::HttpStatus(
-                response.status(),
-                response.text().await?,
-            ))
+            let status = response.status();
+            Err(ApiError::from_response(status, &response.text().await?))
         }
     }
 
@@ -43,7 +56,11 @@ impl ApiClient {
     pub async fn resolve_bucket_name(&mut self, name: &str) -> Result<Uuid, ApiError> {
         let request = ListRequest {
             prefix: Some(name.to_string()),
-            limit: Some(100),
+            filter: None,
+            sort: None,
+            page: PageRequest::first(100),
+            workspace: None,
+            starred: false,
         };
 
         let response: ListResponse = self.call(request).await?;
@@ -54,9 +71,9 @@ impl ApiClient {
             .find(|b| b.name == name)
             .map(|b| b.bucket_id)
             .ok_or_else(|| {
-                ApiError::HttpStatus(
+                ApiError::from_response(
                     reqwest::StatusCode::NOT_FOUND,
-                    format!("Bucket not found: {}", name),
+                    &format!("Bucket not found: {}", name),
                 )
             })
     }
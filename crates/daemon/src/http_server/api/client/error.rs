@@ -1,5 +1,7 @@
 use reqwest::StatusCode;
 
+use crate::http_server::error::{ErrorBody, ErrorCode};
+
 #[derive(Debug, thiserror::Error)]
 pub enum ApiError {
     #[error("HTTP request failed: {0}")]
@@ -7,7 +9,22 @@ pub enum ApiError {
     #[error("URL parse error: {0}")]
     UrlParse(#[from] url::ParseError),
     #[error("HTTP status {0}: {1}")]
-    HttpStatus(StatusCode, String),
+    HttpStatus(StatusCode, ErrorBody),
     #[error("{0}")]
     Other(String),
 }
+
+impl ApiError {
+    /// Error of a response with a failing status, read from its body
+    pub fn from_response(status: StatusCode, body: &str) -> Self {
+        ApiError::HttpStatus(status, ErrorBody::parse(status, body))
+    }
+
+    /// Code of an error answered by the daemon
+    pub fn code(&self) -> Option<ErrorCode> {
+        match self {
+            ApiError::HttpStatus(_, body) => Some(body.code),
+            _ => None,
+        }
+    }
+}
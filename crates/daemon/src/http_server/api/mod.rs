@@ -1,3 +1,4 @@
+use axum::middleware::{self, map_response};
 use axum::Router;
 use http::header::{ACCEPT, CONTENT_TYPE, ORIGIN};
 use http::Method;
@@ -6,6 +7,8 @@ use tower_http::cors::{Any, CorsLayer};
 pub mod client;
 pub mod v0;
 
+use crate::http_server::error::envelope_errors;
+use crate::http_server::replica;
 use crate::ServiceState;
 
 pub fn router(state: ServiceState) -> Router<ServiceState> {
@@ -15,8 +18,17 @@ pub fn router(state: ServiceState) -> Router<ServiceState> {
         .allow_origin(Any)
         .allow_credentials(false);
 
-    Router::new()
-        .nest("/v0", v0::router(state.clone()))
+    let router = Router::new().nest("/v0", v0::router(state.clone()));
+    // Read replicas refuse whatever could write to a bucket
+    let router = if state.is_read_replica() {
+        router.layer(middleware::from_fn(replica::refuse_writes))
+    } else {
+        router
+    };
+
+    router
         .with_state(state)
+        // Every error leaves in the JSON envelope, however it was raised
+        .layer(map_response(envelope_errors))
         .layer(cors_layer)
 }
@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+
+use axum::extract::{Json, State};
+use axum::response::{IntoResponse, Response};
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::activity::{index_bucket, ActivityEntry, ActivityError, Change};
+use crate::http_server::api::client::ApiRequest;
+use crate::http_server::error::ApiError;
+use crate::ServiceState;
+
+/// Default number of changes in a page of the feed
+const DEFAULT_LIMIT: u32 = 50;
+
+/// Most changes in a page of the feed
+const MAX_LIMIT: u32 = 1000;
+
+/// List the paths recently changed across all buckets, newest first
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct ActivityRequest {
+    /// Only list changes of this bucket
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub bucket_id: Option<Uuid>,
+
+    /// Number of changes to skip, from a previous page's `next_offset`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub offset: Option<u32>,
+
+    /// Number of changes to return (default 50, at most 1000)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityResponse {
+    pub items: Vec<ActivityItem>,
+    /// Offset of the next page, if there is one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityItem {
+    pub bucket_id: Uuid,
+    /// Current name of the bucket
+    pub bucket_name: String,
+    pub path: String,
+    pub change: Change,
+    /// Hash of the version that made the change
+    pub version: String,
+    pub height: u64,
+    /// Hex public key of the peer that signed the version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub changed_at: OffsetDateTime,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<ActivityRequest>,
+) -> Result<impl IntoResponse, ActivityApiError> {
+    let offset = req.offset.unwrap_or(0);
+    let limit = req.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    // Bring the index up to date with each bucket's log
+    let buckets = state.database().list_buckets(None, Some(1000)).await?;
+    let names: HashMap<Uuid, String> = buckets
+        .iter()
+        .map(|bucket| (bucket.id, bucket.name.clone()))
+        .collect();
+    match req.bucket_id {
+        Some(bucket_id) => {
+            if !names.contains_key(&bucket_id) {
+                return Err(ActivityApiError::BucketNotFound(bucket_id));
+            }
+            index_bucket(state.peer(), state.database(), bucket_id).await?;
+        }
+        None => {
+            let ids: Vec<Uuid> = names.keys().copied().collect();
+            for bucket_id in &ids {
+                index_bucket(state.peer(), state.database(), *bucket_id).await?;
+            }
+            state.database().prune_activity(&ids).await?;
+        }
+    }
+
+    // Fetch one more than asked for to tell whether there's another page
+    let mut entries = state
+        .database()
+        .activity_feed(req.bucket_id.as_ref(), offset, limit + 1)
+        .await?;
+    let next_offset = (entries.len() > limit as usize).then(|| offset + limit);
+    entries.truncate(limit as usize);
+
+    let items = entries
+        .into_iter()
+        .map(|entry: ActivityEntry| ActivityItem {
+            bucket_name: names.get(&entry.bucket_id).cloned().unwrap_or_default(),
+            bucket_id: entry.bucket_id,
+            path: entry.path,
+            change: entry.change,
+            version: entry.version,
+            height: entry.height,
+            author: entry.author,
+            changed_at: entry.changed_at,
+        })
+        .collect();
+
+    Ok((
+        http::StatusCode::OK,
+        Json(ActivityResponse { items, next_offset }),
+    )
+        .into_response())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ActivityApiError {
+    #[error("Bucket not found: {0}")]
+    BucketNotFound(Uuid),
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("{0}")]
+    Activity(#[from] ActivityError),
+}
+
+impl IntoResponse for ActivityApiError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            ActivityApiError::BucketNotFound(_) => http::StatusCode::NOT_FOUND,
+            _ => http::StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for ActivityRequest {
+    type Response = ActivityResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/activity").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
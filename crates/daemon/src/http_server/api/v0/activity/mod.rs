@@ -0,0 +1,19 @@
+//! Activity feed endpoint
+//!
+//! Pages through the paths recently changed across all buckets, see
+//! [`crate::activity`].
+
+use axum::routing::post;
+use axum::Router;
+
+use crate::ServiceState;
+
+pub mod feed;
+
+pub use feed::{ActivityRequest, ActivityResponse};
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/", post(feed::handler))
+        .with_state(state)
+}
@@ -0,0 +1,104 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::bucket_log::BucketLogProvider;
+
+use super::{bucket_peers, connection, AdminApiError};
+use crate::http_server::api::client::ApiRequest;
+use crate::http_server::api::v0::storage::dedup_report::collect_blob_refs;
+use crate::ServiceState;
+
+/// List every bucket on the node with its size and peers
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct AdminBucketsRequest {
+    /// Walk the buckets to recount their sizes instead of reporting the
+    /// sizes recorded by the last storage report
+    #[arg(long)]
+    #[serde(default)]
+    pub refresh: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminBucketsResponse {
+    pub buckets: Vec<AdminBucket>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminBucket {
+    pub bucket_id: Uuid,
+    pub name: String,
+    /// Hash of the current version
+    pub version: String,
+    pub height: u64,
+    /// Files with content stored locally
+    pub file_count: u64,
+    /// Size of those files
+    pub logical_bytes: u64,
+    /// Space they take up after deduplication
+    pub physical_bytes: u64,
+    /// Peers the bucket is shared with (None for mirrors we can't read yet)
+    pub peer_count: Option<usize>,
+    /// Of those, the peers we are connected to
+    pub connected_peers: Option<usize>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<AdminBucketsRequest>,
+) -> Result<impl IntoResponse, AdminApiError> {
+    let buckets = state.database().list_buckets(None, Some(1000)).await?;
+
+    let mut entries = Vec::with_capacity(buckets.len());
+    for bucket in buckets {
+        if req.refresh {
+            match collect_blob_refs(&state, bucket.id).await {
+                Ok(refs) => {
+                    state
+                        .database()
+                        .replace_blob_refs(&bucket.id, &refs)
+                        .await?
+                }
+                Err(e) => tracing::warn!("Failed to recount size of bucket {}: {}", bucket.id, e),
+            }
+        }
+        let stats = state.database().bucket_dedup_stats(&bucket.id).await?;
+        let height = state.peer().logs().height(bucket.id).await.unwrap_or(0);
+        let peers = bucket_peers(&state, bucket.id).await;
+
+        entries.push(AdminBucket {
+            bucket_id: bucket.id,
+            name: bucket.name,
+            version: bucket.link.hash().to_string(),
+            height,
+            file_count: stats.file_count,
+            logical_bytes: stats.logical_bytes,
+            physical_bytes: stats.physical_bytes,
+            peer_count: peers.as_ref().map(Vec::len),
+            connected_peers: peers.map(|peers| {
+                peers
+                    .iter()
+                    .filter(|peer_id| connection(&state, peer_id).is_some())
+                    .count()
+            }),
+        });
+    }
+
+    Ok((
+        http::StatusCode::OK,
+        Json(AdminBucketsResponse { buckets: entries }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for AdminBucketsRequest {
+    type Response = AdminBucketsResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/admin/buckets").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
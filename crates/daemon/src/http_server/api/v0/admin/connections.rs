@@ -0,0 +1,94 @@
+use std::collections::BTreeMap;
+
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+
+use common::crypto::PublicKey;
+use common::peer::ProtocolMetrics;
+
+use super::{bucket_peers, connection, AdminApiError};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// List the peers the node is connected to
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct AdminConnectionsRequest {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminConnectionsResponse {
+    pub connections: Vec<AdminConnection>,
+    /// Requests from peers handled and turned away
+    pub protocol: ProtocolMetrics,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminConnection {
+    /// Hex public key of the peer
+    pub peer_id: String,
+    /// Connection type: direct, relay or mixed
+    pub connection: String,
+    pub latency_ms: Option<u64>,
+    /// Protocol version negotiated with the peer, None until we talked to it
+    pub protocol_version: Option<u32>,
+    /// Whether the peer is in the address book
+    pub known: bool,
+    /// Local buckets shared with the peer
+    pub shared_buckets: usize,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(_req): Json<AdminConnectionsRequest>,
+) -> Result<impl IntoResponse, AdminApiError> {
+    // Peers we may be connected to: the address book and everyone a bucket
+    // is shared with
+    let mut candidates: BTreeMap<PublicKey, (bool, usize)> = BTreeMap::new();
+    for known in state.database().list_known_peers().await? {
+        candidates.entry(known.ticket.node_id).or_default().0 = true;
+    }
+    for bucket in state.database().list_buckets(None, Some(1000)).await? {
+        for peer_id in bucket_peers(&state, bucket.id).await.unwrap_or_default() {
+            candidates.entry(peer_id).or_default().1 += 1;
+        }
+    }
+
+    let peer = state.peer();
+    let connections = candidates
+        .into_iter()
+        .filter_map(|(peer_id, (known, shared_buckets))| {
+            let connection = connection(&state, &peer_id)?;
+            Some(AdminConnection {
+                peer_id: peer_id.to_hex(),
+                connection: connection.to_string(),
+                latency_ms: peer
+                    .endpoint()
+                    .latency(*peer_id)
+                    .map(|latency| latency.as_millis() as u64),
+                protocol_version: peer.peer_protocol(&peer_id).map(|p| p.version),
+                known,
+                shared_buckets,
+            })
+        })
+        .collect();
+
+    Ok((
+        http::StatusCode::OK,
+        Json(AdminConnectionsResponse {
+            connections,
+            protocol: peer.protocol_metrics(),
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for AdminConnectionsRequest {
+    type Response = AdminConnectionsResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/admin/connections").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,79 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+
+use super::AdminApiError;
+use crate::http_server::api::client::ApiRequest;
+use crate::http_server::api::v0::logs::{run_query, LogQueryRequest, LogsError};
+use crate::logs::LogEntry;
+use crate::{ServiceState, SyncActivity};
+
+const DEFAULT_LIMIT: usize = 50;
+
+/// Show recent errors
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct AdminErrorsRequest {
+    /// Only errors at or after this time (RFC 3339, or relative like 10m, 2h, 1d)
+    #[arg(long)]
+    pub since: Option<String>,
+
+    /// Maximum number of errors to return, most recent kept
+    #[arg(short = 'n', long = "lines")]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminErrorsResponse {
+    /// Error log entries, oldest first (None when the daemon isn't logging
+    /// to a directory)
+    pub entries: Option<Vec<LogEntry>>,
+    /// Error of the last sync job, until a job succeeds
+    pub sync_error: Option<String>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<AdminErrorsRequest>,
+) -> Result<impl IntoResponse, AdminApiError> {
+    let entries = match state.log_dir() {
+        Some(log_dir) => {
+            let log_dir = log_dir.to_path_buf();
+            let query = LogQueryRequest {
+                level: Some("error".to_string()),
+                since: req.since,
+                limit: Some(req.limit.unwrap_or(DEFAULT_LIMIT)),
+                ..LogQueryRequest::default()
+            };
+            let response = tokio::task::spawn_blocking(move || run_query(&log_dir, &query))
+                .await
+                .map_err(|e| LogsError::Io(std::io::Error::other(e)))??;
+            Some(response.entries)
+        }
+        None => None,
+    };
+
+    let sync_error = match state.sync_status().activity() {
+        SyncActivity::Error(error) => Some(error),
+        _ => None,
+    };
+
+    Ok((
+        http::StatusCode::OK,
+        Json(AdminErrorsResponse {
+            entries,
+            sync_error,
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for AdminErrorsRequest {
+    type Response = AdminErrorsResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/admin/errors").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,53 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+
+use super::AdminApiError;
+use crate::http_server::api::client::ApiRequest;
+use crate::{ServiceState, SyncActivity};
+
+/// Show the depth of the sync job queue
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct AdminJobsRequest {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminJobsResponse {
+    /// Jobs queued for the sync worker
+    pub pending: usize,
+    /// Jobs held back while syncing is paused
+    pub parked: usize,
+    /// Pin downloads held back on a metered network
+    pub deferred: usize,
+    pub paused: bool,
+    /// What the sync worker is doing
+    pub activity: SyncActivity,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(_req): Json<AdminJobsRequest>,
+) -> Result<impl IntoResponse, AdminApiError> {
+    let status = state.sync_status();
+    Ok((
+        http::StatusCode::OK,
+        Json(AdminJobsResponse {
+            pending: state.pending_sync_jobs(),
+            parked: status.parked_jobs(),
+            deferred: status.deferred_jobs(),
+            paused: status.is_paused(),
+            activity: status.activity(),
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for AdminJobsRequest {
+    type Response = AdminJobsResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/admin/jobs").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
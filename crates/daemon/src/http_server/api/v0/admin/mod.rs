@@ -0,0 +1,96 @@
+//! Daemon administration API endpoints
+//!
+//! Besides reloading, shutting down and the status summary, the admin group
+//! serves node-wide views for an operator's dashboard: every bucket with its
+//! size and peers, live peer connections, the sync job queue, storage
+//! backend health and recent errors.
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use iroh::endpoint::ConnectionType;
+use iroh::Watcher;
+use uuid::Uuid;
+
+use common::crypto::PublicKey;
+use common::prelude::MountError;
+
+use crate::http_server::api::v0::logs::LogsError;
+use crate::http_server::api::v0::storage::dedup_report::DedupReportError;
+use crate::http_server::error::ApiError;
+use crate::ServiceState;
+
+pub mod buckets;
+pub mod connections;
+pub mod errors;
+pub mod jobs;
+pub mod reload;
+pub mod shutdown;
+pub mod status;
+pub mod storage;
+
+pub use buckets::{AdminBucket, AdminBucketsRequest, AdminBucketsResponse};
+pub use connections::{AdminConnection, AdminConnectionsRequest, AdminConnectionsResponse};
+pub use errors::{AdminErrorsRequest, AdminErrorsResponse};
+pub use jobs::{AdminJobsRequest, AdminJobsResponse};
+pub use reload::{ReloadRequest, ReloadResponse};
+pub use shutdown::{ShutdownRequest, ShutdownResponse};
+pub use status::{PeerStatus, StatusRequest, StatusResponse};
+pub use storage::{AdminStorageRequest, AdminStorageResponse, StorageProbe};
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/reload", post(reload::handler))
+        .route("/shutdown", post(shutdown::handler))
+        .route("/status", post(status::handler))
+        .route("/buckets", post(buckets::handler))
+        .route("/connections", post(connections::handler))
+        .route("/jobs", post(jobs::handler))
+        .route("/storage", post(storage::handler))
+        .route("/errors", post(errors::handler))
+        .with_state(state)
+}
+
+/// Peers a bucket is shared with, other than this node, or None for a
+/// mirror whose share list we can't read yet
+pub(crate) async fn bucket_peers(state: &ServiceState, bucket_id: Uuid) -> Option<Vec<PublicKey>> {
+    let peer = state.peer();
+    let mount = peer.mount_for_read(bucket_id).await.ok()?;
+    let own_key = peer.secret().public();
+    let peer_ids = mount.inner().await.manifest().get_peer_ids();
+    Some(peer_ids.into_iter().filter(|id| *id != own_key).collect())
+}
+
+/// Connection type to a peer (direct, relay or mixed), None if not connected
+pub(crate) fn connection(state: &ServiceState, peer_id: &PublicKey) -> Option<&'static str> {
+    let conn = state.peer().endpoint().conn_type(**peer_id)?.get();
+    match conn {
+        ConnectionType::None => None,
+        conn => Some(status::connection_label(&conn)),
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AdminApiError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Mount error: {0}")]
+    Mount(#[from] MountError),
+    #[error("Storage accounting error: {0}")]
+    Accounting(#[from] DedupReportError),
+    #[error("{0}")]
+    Logs(#[from] LogsError),
+}
+
+impl IntoResponse for AdminApiError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            AdminApiError::Logs(LogsError::InvalidLevel(_) | LogsError::InvalidTime(_)) => {
+                StatusCode::BAD_REQUEST
+            }
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
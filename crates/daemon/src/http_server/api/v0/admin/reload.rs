@@ -0,0 +1,79 @@
+use axum::extract::{Json, State};
+use axum::response::{IntoResponse, Response};
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+
+use crate::http_server::api::client::ApiRequest;
+use crate::http_server::error::ApiError;
+use crate::reload::ReloadError;
+use crate::ServiceState;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct ReloadRequest {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReloadResponse {
+    /// Settings applied to the running daemon
+    pub applied: Vec<String>,
+    /// Settings that changed but only take effect after a restart
+    pub restart_required: Vec<String>,
+    /// Active log level after the reload
+    pub log_level: String,
+    /// Active periodic sync interval after the reload
+    pub sync_interval_secs: u64,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(_req): Json<ReloadRequest>,
+) -> Result<impl IntoResponse, ReloadHandlerError> {
+    let summary = state.reloader().reload()?;
+    let runtime = state.reloader().current();
+
+    Ok((
+        http::StatusCode::OK,
+        Json(ReloadResponse {
+            applied: summary.applied.iter().map(|s| s.to_string()).collect(),
+            restart_required: summary
+                .restart_required
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            log_level: runtime.log_level.to_string().to_lowercase(),
+            sync_interval_secs: runtime.sync_interval.as_secs(),
+        }),
+    )
+        .into_response())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReloadHandlerError {
+    #[error("Reload error: {0}")]
+    Reload(#[from] ReloadError),
+}
+
+impl IntoResponse for ReloadHandlerError {
+    fn into_response(self) -> Response {
+        match self {
+            ReloadHandlerError::Reload(ReloadError::Config(_))
+            | ReloadHandlerError::Reload(ReloadError::NoConfigFile) => {
+                ApiError::new(http::StatusCode::BAD_REQUEST, self.to_string()).into_response()
+            }
+            _ => (
+                http::StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Error: {}", self),
+            )
+                .into_response(),
+        }
+    }
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for ReloadRequest {
+    type Response = ReloadResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/admin/reload").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
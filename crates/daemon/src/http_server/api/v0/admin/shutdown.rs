@@ -0,0 +1,60 @@
+use axum::extract::{Json, State};
+use axum::response::{IntoResponse, Response};
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct ShutdownRequest {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShutdownResponse {
+    /// PID of the daemon process that is shutting down
+    pub pid: u32,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(_req): Json<ShutdownRequest>,
+) -> Result<impl IntoResponse, ShutdownError> {
+    tracing::info!("Shutdown requested via API");
+    if !state.request_shutdown() {
+        return Err(ShutdownError::Unavailable);
+    }
+
+    Ok((
+        http::StatusCode::OK,
+        Json(ShutdownResponse {
+            pid: std::process::id(),
+        }),
+    )
+        .into_response())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ShutdownError {
+    #[error("Service was not started with a shutdown handle")]
+    Unavailable,
+}
+
+impl IntoResponse for ShutdownError {
+    fn into_response(self) -> Response {
+        (
+            http::StatusCode::SERVICE_UNAVAILABLE,
+            format!("Error: {}", self),
+        )
+            .into_response()
+    }
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for ShutdownRequest {
+    type Response = ShutdownResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/admin/shutdown").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,162 @@
+use axum::extract::{Json, State};
+use axum::response::{IntoResponse, Response};
+use iroh::Watcher;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+
+use common::peer::ProtocolMetrics;
+use common::prelude::MountError;
+
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct StatusRequest {
+    /// Include per-peer connection details
+    #[arg(long)]
+    #[serde(default)]
+    pub verbose: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusResponse {
+    pub version: String,
+    pub pid: u32,
+    pub uptime_secs: u64,
+    pub api_port: u16,
+    pub gateway_port: u16,
+    pub peer_id: String,
+    pub listen_addrs: Vec<String>,
+    pub bucket_count: usize,
+    pub pending_sync_jobs: usize,
+    /// Running FUSE mounts (None when built without FUSE support)
+    pub running_mounts: Option<usize>,
+    /// Requests from peers handled and turned away
+    #[serde(default)]
+    pub protocol: ProtocolMetrics,
+    /// Peers shared on local buckets and how we are connected to them (verbose only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peers: Option<Vec<PeerStatus>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerStatus {
+    pub peer_id: String,
+    /// Connection type: direct, relay, mixed or none
+    pub connection: String,
+    pub latency_ms: Option<u64>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<StatusRequest>,
+) -> Result<impl IntoResponse, StatusError> {
+    let peer = state.peer();
+    let buckets = state.database().list_buckets(None, Some(1000)).await?;
+
+    #[cfg(feature = "fuse")]
+    let running_mounts = match state.mount_manager().read().await.as_ref() {
+        Some(manager) => Some(manager.running_count().await),
+        None => None,
+    };
+    #[cfg(not(feature = "fuse"))]
+    let running_mounts = None;
+
+    let peers = if req.verbose {
+        let mut peer_ids = std::collections::BTreeSet::new();
+        for bucket in &buckets {
+            // Mirrors we can't decrypt yet have no readable share list
+            let Ok(mount) = peer.mount_for_read(bucket.id).await else {
+                continue;
+            };
+            for peer_id in mount.inner().await.manifest().get_peer_ids() {
+                let node_id = iroh::PublicKey::from(peer_id);
+                if node_id != peer.id() {
+                    peer_ids.insert(node_id);
+                }
+            }
+        }
+
+        let endpoint = peer.endpoint();
+        Some(
+            peer_ids
+                .into_iter()
+                .map(|peer_id| PeerStatus {
+                    peer_id: peer_id.to_string(),
+                    connection: endpoint
+                        .conn_type(peer_id)
+                        .map(|mut conn| connection_label(&conn.get()))
+                        .unwrap_or("unknown")
+                        .to_string(),
+                    latency_ms: endpoint
+                        .latency(peer_id)
+                        .map(|latency| latency.as_millis() as u64),
+                })
+                .collect(),
+        )
+    } else {
+        None
+    };
+
+    Ok((
+        http::StatusCode::OK,
+        Json(StatusResponse {
+            version: common::prelude::build_info().version,
+            pid: std::process::id(),
+            uptime_secs: state.uptime().as_secs(),
+            api_port: state.api_port(),
+            gateway_port: state.gateway_port(),
+            peer_id: peer.id().to_string(),
+            listen_addrs: peer
+                .endpoint()
+                .bound_sockets()
+                .iter()
+                .map(|addr| addr.to_string())
+                .collect(),
+            bucket_count: buckets.len(),
+            pending_sync_jobs: state.pending_sync_jobs(),
+            running_mounts,
+            protocol: peer.protocol_metrics(),
+            peers,
+        }),
+    )
+        .into_response())
+}
+
+pub(crate) fn connection_label(conn: &iroh::endpoint::ConnectionType) -> &'static str {
+    use iroh::endpoint::ConnectionType;
+    match conn {
+        ConnectionType::Direct(_) => "direct",
+        ConnectionType::Relay(_) => "relay",
+        ConnectionType::Mixed(_, _) => "mixed",
+        ConnectionType::None => "none",
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum StatusError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Mount error: {0}")]
+    Mount(#[from] MountError),
+}
+
+impl IntoResponse for StatusError {
+    fn into_response(self) -> Response {
+        (
+            http::StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Error: {}", self),
+        )
+            .into_response()
+    }
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for StatusRequest {
+    type Response = StatusResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/admin/status").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,93 @@
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+
+use common::linked_data::Hash;
+
+use super::AdminApiError;
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// How long a probe may take before the backend counts as unhealthy
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Check the health of the blob store and database
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct AdminStorageRequest {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminStorageResponse {
+    /// Blob store backend: legacy, filesystem or s3
+    pub backend: String,
+    pub blob_store: StorageProbe,
+    pub database: StorageProbe,
+    /// Bytes of file content stored, as of the last storage report
+    pub logical_bytes: u64,
+    /// Space that content takes up after deduplication
+    pub physical_bytes: u64,
+    /// Size of the cache of content fetched for online-only buckets
+    pub cache_bytes: u64,
+    pub cache_max_bytes: u64,
+}
+
+/// Outcome of a round trip to a storage backend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageProbe {
+    pub healthy: bool,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(_req): Json<AdminStorageRequest>,
+) -> Result<impl IntoResponse, AdminApiError> {
+    // Asking for a blob nobody stores reaches the backend without moving data
+    let blob_store = probe(state.peer().blobs().stat(&Hash::from_bytes([0; 32]))).await;
+    let database = probe(sqlx::query("SELECT 1").execute(&**state.database())).await;
+
+    let totals = state.database().total_dedup_stats().await?;
+    let cache = state.blob_cache().usage().await?;
+
+    Ok((
+        http::StatusCode::OK,
+        Json(AdminStorageResponse {
+            backend: state.blob_backend().to_string(),
+            blob_store,
+            database,
+            logical_bytes: totals.logical_bytes,
+            physical_bytes: totals.physical_bytes,
+            cache_bytes: cache.bytes,
+            cache_max_bytes: state.reloader().current().online_cache_max_bytes,
+        }),
+    )
+        .into_response())
+}
+
+async fn probe<T, E: std::fmt::Display>(check: impl Future<Output = Result<T, E>>) -> StorageProbe {
+    let started = Instant::now();
+    let error = match tokio::time::timeout(PROBE_TIMEOUT, check).await {
+        Ok(Ok(_)) => None,
+        Ok(Err(e)) => Some(e.to_string()),
+        Err(_) => Some(format!("timed out after {}s", PROBE_TIMEOUT.as_secs())),
+    };
+    StorageProbe {
+        healthy: error.is_none(),
+        latency_ms: started.elapsed().as_millis() as u64,
+        error,
+    }
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for AdminStorageRequest {
+    type Response = AdminStorageResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/admin/storage").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
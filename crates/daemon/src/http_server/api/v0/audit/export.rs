@@ -0,0 +1,63 @@
+use axum::extract::{Json, State};
+use axum::http::header;
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{parse_filter, AuditApiError};
+use crate::ServiceState;
+
+/// Export the audit log as newline-delimited JSON
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct AuditExportRequest {
+    /// Only entries of this bucket
+    #[arg(long)]
+    #[serde(default)]
+    pub bucket_id: Option<Uuid>,
+
+    /// Only entries by this actor (api, desktop, fuse, mobile, deploy:<key id>, backup:<job id>, retention or peer:<key>)
+    #[arg(long)]
+    #[serde(default)]
+    pub actor: Option<String>,
+
+    /// Only entries at or after this time (RFC 3339, or relative like 10m, 2h, 1d)
+    #[arg(long)]
+    #[serde(default)]
+    pub since: Option<String>,
+}
+
+impl AuditExportRequest {
+    /// Build the export request; the response body has one JSON
+    /// [`AuditEntry`](crate::audit::AuditEntry) per line, oldest first.
+    pub fn build_request(&self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/audit/export").unwrap();
+        client.post(full_url).json(self)
+    }
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<AuditExportRequest>,
+) -> Result<impl IntoResponse, AuditApiError> {
+    let filter = parse_filter(
+        req.bucket_id,
+        req.actor.as_deref(),
+        req.since.as_deref(),
+        None,
+    )?;
+    let entries = state.audit_log().list(&filter).await?;
+
+    let mut body = String::new();
+    for entry in entries.iter().rev() {
+        body.push_str(&serde_json::to_string(entry)?);
+        body.push('\n');
+    }
+
+    Ok((
+        http::StatusCode::OK,
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        body,
+    )
+        .into_response())
+}
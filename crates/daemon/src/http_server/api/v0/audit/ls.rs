@@ -0,0 +1,68 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{parse_filter, AuditApiError};
+use crate::audit::AuditEntry;
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+const DEFAULT_LIMIT: u32 = 100;
+const MAX_LIMIT: u32 = 10_000;
+
+/// List recent entries of the audit log
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct AuditLsRequest {
+    /// Only entries of this bucket
+    #[arg(long)]
+    #[serde(default)]
+    pub bucket_id: Option<Uuid>,
+
+    /// Only entries by this actor (api, desktop, fuse, mobile, deploy:<key id>, backup:<job id>, retention or peer:<key>)
+    #[arg(long)]
+    #[serde(default)]
+    pub actor: Option<String>,
+
+    /// Only entries at or after this time (RFC 3339, or relative like 10m, 2h, 1d)
+    #[arg(long)]
+    #[serde(default)]
+    pub since: Option<String>,
+
+    /// Maximum number of entries to return, most recent kept
+    #[arg(short = 'n', long = "lines")]
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditLsResponse {
+    /// Matching entries, newest first
+    pub entries: Vec<AuditEntry>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<AuditLsRequest>,
+) -> Result<impl IntoResponse, AuditApiError> {
+    let filter = parse_filter(
+        req.bucket_id,
+        req.actor.as_deref(),
+        req.since.as_deref(),
+        Some(req.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT)),
+    )?;
+    let entries = state.audit_log().list(&filter).await?;
+
+    Ok((http::StatusCode::OK, Json(AuditLsResponse { entries })).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for AuditLsRequest {
+    type Response = AuditLsResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/audit/ls").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,72 @@
+//! Audit log API endpoints
+//!
+//! `ls` lists recent entries of the audit log (see [`crate::audit`]) as
+//! JSON; `export` returns every matching entry as newline-delimited JSON,
+//! oldest first, for archiving or feeding to other tools.
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::audit::AuditFilter;
+use crate::http_server::error::ApiError;
+use crate::ServiceState;
+
+pub mod export;
+pub mod ls;
+
+pub use export::AuditExportRequest;
+pub use ls::{AuditLsRequest, AuditLsResponse};
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/ls", post(ls::handler))
+        .route("/export", post(export::handler))
+        .with_state(state)
+}
+
+/// Build a filter from request parameters
+pub(crate) fn parse_filter(
+    bucket_id: Option<Uuid>,
+    actor: Option<&str>,
+    since: Option<&str>,
+    limit: Option<u32>,
+) -> Result<AuditFilter, AuditApiError> {
+    let since = since
+        .map(|s| {
+            crate::logs::parse_time(s, OffsetDateTime::now_utc())
+                .ok_or_else(|| AuditApiError::InvalidTime(s.to_string()))
+        })
+        .transpose()?;
+    Ok(AuditFilter {
+        bucket_id,
+        actor: actor.filter(|s| !s.is_empty()).map(str::to_string),
+        since,
+        limit,
+    })
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AuditApiError {
+    #[error("Invalid time: {0} (expected RFC 3339 or a relative time like 10m, 2h, 1d)")]
+    InvalidTime(String),
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Failed to encode entries: {0}")]
+    Encode(#[from] serde_json::Error),
+}
+
+impl IntoResponse for AuditApiError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            AuditApiError::InvalidTime(_) => StatusCode::BAD_REQUEST,
+            AuditApiError::Database(_) | AuditApiError::Encode(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
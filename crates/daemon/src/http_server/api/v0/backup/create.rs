@@ -0,0 +1,110 @@
+use std::path::PathBuf;
+
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{BackupApiError, BackupJobInfo};
+use crate::audit::{Actor, AuditEvent};
+use crate::backup::{BackupJob, Schedule};
+use crate::deploy::normalize_path;
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Back up a directory on the daemon's machine into a bucket on a schedule
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct BackupCreateRequest {
+    /// Directory to back up
+    #[arg(long)]
+    pub source_dir: PathBuf,
+
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Path in the bucket to mirror the directory to (default: the root)
+    #[arg(long, default_value = "/")]
+    #[serde(default = "default_target")]
+    pub target_path: String,
+
+    /// Cron expression in local time, e.g. `0 3 * * *`, or @hourly, @daily,
+    /// @weekly, @monthly
+    #[arg(long)]
+    pub schedule: String,
+
+    /// Number of snapshots to keep
+    #[arg(long, default_value_t = default_retain())]
+    #[serde(default = "default_retain")]
+    pub retain: u64,
+
+    /// Label to tell jobs apart
+    #[arg(long)]
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+fn default_target() -> String {
+    "/".to_string()
+}
+
+fn default_retain() -> u64 {
+    7
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<BackupCreateRequest>,
+) -> Result<impl IntoResponse, BackupApiError> {
+    let schedule: Schedule = req.schedule.parse()?;
+    let target_path = normalize_path(&req.target_path)
+        .ok_or_else(|| BackupApiError::InvalidTarget(req.target_path.clone()))?;
+    if !req.source_dir.is_absolute() || !req.source_dir.is_dir() {
+        return Err(BackupApiError::InvalidSource(req.source_dir));
+    }
+    if req.retain == 0 {
+        return Err(BackupApiError::InvalidRetain);
+    }
+    if state
+        .database()
+        .get_bucket_info(&req.bucket_id)
+        .await?
+        .is_none()
+    {
+        return Err(BackupApiError::BucketNotFound(req.bucket_id));
+    }
+
+    let job = state
+        .database()
+        .create_backup_job(
+            req.name.as_deref(),
+            &req.source_dir,
+            &req.bucket_id,
+            &target_path,
+            &schedule,
+            req.retain,
+            BackupJob::next_run(&schedule),
+        )
+        .await?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "backup.create")
+                .bucket(job.bucket_id)
+                .path(job.target_path.clone())
+                .detail(job.id.to_string()),
+        )
+        .await;
+
+    Ok((http::StatusCode::OK, Json(BackupJobInfo::from(job))).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for BackupCreateRequest {
+    type Response = BackupJobInfo;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/backup/create").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,42 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+
+use super::{BackupApiError, BackupJobInfo};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// List backup jobs with when they run next and how their latest run went
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct BackupListRequest {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupListResponse {
+    pub jobs: Vec<BackupJobInfo>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(_req): Json<BackupListRequest>,
+) -> Result<impl IntoResponse, BackupApiError> {
+    let jobs = state
+        .database()
+        .list_backup_jobs()
+        .await?
+        .into_iter()
+        .map(BackupJobInfo::from)
+        .collect();
+
+    Ok((http::StatusCode::OK, Json(BackupListResponse { jobs })).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for BackupListRequest {
+    type Response = BackupListResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/backup/list").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,124 @@
+//! Backup job endpoints
+//!
+//! Backup jobs mirror a directory on the daemon's machine into a bucket on a
+//! schedule and keep their newest versions as snapshots; see
+//! [`crate::backup`] for the schedule format and how runs work.
+
+use std::path::PathBuf;
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::backup::{BackupError, BackupJob, Schedule};
+use crate::http_server::error::ApiError;
+use crate::ServiceState;
+
+pub mod create;
+pub mod list;
+pub mod remove;
+pub mod run;
+pub mod snapshots;
+
+pub use create::BackupCreateRequest;
+pub use list::{BackupListRequest, BackupListResponse};
+pub use remove::BackupRemoveRequest;
+pub use run::BackupRunRequest;
+pub use snapshots::{BackupSnapshotsRequest, BackupSnapshotsResponse};
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/create", post(create::handler))
+        .route("/list", post(list::handler))
+        .route("/remove", post(remove::handler))
+        .route("/run", post(run::handler))
+        .route("/snapshots", post(snapshots::handler))
+        .with_state(state)
+}
+
+/// A backup job and the outcome of its latest run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupJobInfo {
+    pub job_id: Uuid,
+    pub name: Option<String>,
+    pub source_dir: PathBuf,
+    pub bucket_id: Uuid,
+    pub target_path: String,
+    pub schedule: Schedule,
+    pub retain: u64,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub next_run_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_run_at: Option<OffsetDateTime>,
+    /// Why the latest run failed, None if it succeeded
+    pub last_error: Option<String>,
+}
+
+impl From<BackupJob> for BackupJobInfo {
+    fn from(job: BackupJob) -> Self {
+        Self {
+            job_id: job.id,
+            name: job.name,
+            source_dir: job.source_dir,
+            bucket_id: job.bucket_id,
+            target_path: job.target_path,
+            schedule: job.schedule,
+            retain: job.retain,
+            created_at: job.created_at,
+            next_run_at: job.next_run_at,
+            last_run_at: job.last_run_at,
+            last_error: job.last_error,
+        }
+    }
+}
+
+/// Look up a backup job by ID
+async fn find(state: &ServiceState, id: &Uuid) -> Result<BackupJob, BackupApiError> {
+    state
+        .database()
+        .get_backup_job(id)
+        .await?
+        .ok_or(BackupApiError::UnknownJob(*id))
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BackupApiError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Bucket not found: {0}")]
+    BucketNotFound(Uuid),
+    #[error("Invalid source directory: {0}")]
+    InvalidSource(PathBuf),
+    #[error("Invalid target path: {0}")]
+    InvalidTarget(String),
+    #[error("Snapshots to retain must be at least 1")]
+    InvalidRetain,
+    #[error("Unknown backup job: {0}")]
+    UnknownJob(Uuid),
+    #[error("{0}")]
+    Backup(#[from] BackupError),
+}
+
+impl IntoResponse for BackupApiError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            BackupApiError::InvalidSource(_)
+            | BackupApiError::InvalidTarget(_)
+            | BackupApiError::InvalidRetain
+            | BackupApiError::Backup(BackupError::InvalidSchedule(_)) => StatusCode::BAD_REQUEST,
+            BackupApiError::BucketNotFound(_)
+            | BackupApiError::UnknownJob(_)
+            | BackupApiError::Backup(BackupError::UnknownJob(_)) => StatusCode::NOT_FOUND,
+            BackupApiError::Database(_) | BackupApiError::Backup(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
@@ -0,0 +1,47 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{find, BackupApiError, BackupJobInfo};
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Stop backing up a directory and forget the job's snapshots. What it
+/// backed up stays in the bucket.
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct BackupRemoveRequest {
+    /// Backup job ID
+    #[arg(long)]
+    pub job_id: Uuid,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<BackupRemoveRequest>,
+) -> Result<impl IntoResponse, BackupApiError> {
+    let job = find(&state, &req.job_id).await?;
+    state.database().delete_backup_job(&job.id).await?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "backup.remove")
+                .bucket(job.bucket_id)
+                .path(job.target_path.clone())
+                .detail(job.id.to_string()),
+        )
+        .await;
+
+    Ok((http::StatusCode::OK, Json(BackupJobInfo::from(job))).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for BackupRemoveRequest {
+    type Response = BackupJobInfo;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/backup/remove").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,38 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{find, BackupApiError};
+use crate::backup::BackupSnapshot;
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Run a backup job now and wait for it to finish. Its schedule is unchanged.
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct BackupRunRequest {
+    /// Backup job ID
+    #[arg(long)]
+    pub job_id: Uuid,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<BackupRunRequest>,
+) -> Result<impl IntoResponse, BackupApiError> {
+    let job = find(&state, &req.job_id).await?;
+    let snapshot = state.backups().run(job.id).await?;
+
+    Ok((http::StatusCode::OK, Json(snapshot)).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for BackupRunRequest {
+    type Response = BackupSnapshot;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/backup/run").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
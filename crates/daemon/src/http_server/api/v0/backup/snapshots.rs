@@ -0,0 +1,47 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{find, BackupApiError};
+use crate::backup::BackupSnapshot;
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// List the bucket versions a backup job keeps as snapshots, newest first
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct BackupSnapshotsRequest {
+    /// Backup job ID
+    #[arg(long)]
+    pub job_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupSnapshotsResponse {
+    pub snapshots: Vec<BackupSnapshot>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<BackupSnapshotsRequest>,
+) -> Result<impl IntoResponse, BackupApiError> {
+    let job = find(&state, &req.job_id).await?;
+    let snapshots = state.database().list_backup_snapshots(&job.id).await?;
+
+    Ok((
+        http::StatusCode::OK,
+        Json(BackupSnapshotsResponse { snapshots }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for BackupSnapshotsRequest {
+    type Response = BackupSnapshotsResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/backup/snapshots").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -1,12 +1,23 @@
+use axum::extract::multipart::Field;
 use axum::extract::{Multipart, State};
 use axum::response::{IntoResponse, Response};
+use futures::future;
+use futures::TryStreamExt;
 use serde::{Deserialize, Serialize};
-use std::io::Cursor;
+use std::fs::File;
+use std::io::{Seek, SeekFrom};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncWriteExt};
+use tokio_util::io::StreamReader;
 use uuid::Uuid;
 
-use common::prelude::{Link, MountError};
+use common::prelude::{Link, Mount, MountError};
 
+use super::{head_moved, parse_version, stored_mime};
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::error::ApiError;
 use crate::ServiceState;
 
 #[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
@@ -18,6 +29,11 @@ pub struct AddRequest {
     /// Path in bucket where file should be mounted
     #[arg(long)]
     pub mount_path: String,
+
+    /// Fail with 409 unless the bucket head is still this version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub expected_version: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,13 +54,26 @@ pub struct AddResponse {
     pub failed_files: usize,
 }
 
+/// Upload files, streaming each into the bucket as it arrives.
+///
+/// Files sent after the `bucket_id` and `mount_path` fields (as the CLI and
+/// the web UI send them) are chunked and encrypted straight from the request
+/// body, holding at most a chunk of each in memory. Files sent before them
+/// are spooled to temp files until the bucket is known.
+///
+/// Each file is held to its bucket's upload limit as it arrives; a file over
+/// it fails the whole request with 413 before the rest is read.
 pub async fn handler(
     State(state): State<ServiceState>,
     mut multipart: Multipart,
 ) -> Result<impl IntoResponse, AddError> {
     let mut bucket_id: Option<Uuid> = None;
     let mut base_path: Option<String> = None;
-    let mut files: Vec<(String, Vec<u8>)> = Vec::new();
+    let mut expected_version: Option<String> = None;
+    let mut mount: Option<Mount> = None;
+    let mut spooled: Vec<(String, File, usize)> = Vec::new();
+    let limits = state.upload_limits();
+    let mut results = Vec::new();
 
     // Parse multipart form data
     while let Some(field) = multipart.next_field().await.map_err(|e| {
@@ -71,6 +100,12 @@ pub async fn handler(
                     AddError::MultipartError(e.to_string())
                 })?);
             }
+            "expected_version" => {
+                expected_version = Some(field.text().await.map_err(|e| {
+                    tracing::error!("Error reading expected_version field: {}", e);
+                    AddError::MultipartError(e.to_string())
+                })?);
+            }
             "file" | "files" => {
                 // Get filename from the field
                 let filename = field
@@ -78,17 +113,44 @@ pub async fn handler(
                     .map(|s| s.to_string())
                     .unwrap_or_else(|| "unnamed".to_string());
 
-                tracing::info!("Reading file: {}", filename);
-                let file_data = field
-                    .bytes()
-                    .await
-                    .map_err(|e| {
+                let (Some(bucket_id), Some(base_path)) = (bucket_id, base_path.as_deref()) else {
+                    tracing::info!("Spooling file {} until the bucket is known", filename);
+                    let limit = limits.largest();
+                    let (reader, tally) = stream_field(field, limit);
+                    let spooled_file = spool(reader).await;
+                    if tally.over_limit() {
+                        return Err(too_large(&filename, limit));
+                    }
+                    let file = spooled_file.map_err(|e| {
                         tracing::error!("Error reading file data for {}: {}", filename, e);
                         AddError::MultipartError(e.to_string())
-                    })?
-                    .to_vec();
+                    })?;
+                    spooled.push((filename, file, tally.size()));
+                    continue;
+                };
 
-                files.push((filename, file_data));
+                let mount = match &mut mount {
+                    Some(mount) => mount,
+                    None => mount
+                        .insert(load_mount(&state, bucket_id, expected_version.as_deref()).await?),
+                };
+                tracing::info!("Streaming file: {}", filename);
+                let limit = limits.for_bucket(&bucket_id);
+                let (reader, tally) = stream_field(field, limit);
+                let result = add_file(mount, base_path, &filename, reader).await;
+                // An upload cut off halfway or over the limit fails the
+                //  request, not just its file
+                if tally.over_limit() {
+                    return Err(too_large(&filename, limit));
+                }
+                if tally.failed.load(Ordering::Relaxed) {
+                    tracing::error!("Error reading file data for {}", filename);
+                    return Err(AddError::MultipartError(result.error.unwrap_or_default()));
+                }
+                results.push(FileUploadResult {
+                    size: tally.size(),
+                    ..result
+                });
             }
             _ => {
                 tracing::warn!("Ignoring unknown field: {}", field_name);
@@ -101,100 +163,38 @@ pub async fn handler(
     let base_path =
         base_path.ok_or_else(|| AddError::InvalidRequest("mount_path is required".into()))?;
 
-    if files.is_empty() {
+    if results.is_empty() && spooled.is_empty() {
         return Err(AddError::InvalidRequest(
             "At least one file is required".into(),
         ));
     }
 
-    tracing::info!(
-        "Uploading {} file(s) to bucket {} at path {}",
-        files.len(),
-        bucket_id,
-        base_path
-    );
-
-    // Load mount at current head
-    tracing::info!("Loading mount for bucket {}", bucket_id);
-    let mut mount = state.peer().mount(bucket_id).await.map_err(|e| {
-        tracing::error!("Failed to load mount for bucket {}: {}", bucket_id, e);
-        e
-    })?;
-
-    let mut results = Vec::new();
-    let mut successful = 0;
-    let mut failed = 0;
-
-    // Process each file
-    tracing::info!("Processing {} files", files.len());
-    for (idx, (filename, file_data)) in files.iter().enumerate() {
-        tracing::info!("Processing file {}/{}: {}", idx + 1, files.len(), filename);
-
-        // Construct full path
-        let full_path = if base_path == "/" {
-            format!("/{}", filename)
-        } else {
-            format!("{}/{}", base_path.trim_end_matches('/'), filename)
-        };
-        tracing::info!("Full path: {}", full_path);
-
-        let mount_path_buf = PathBuf::from(&full_path);
-
-        // Validate mount path
-        if !mount_path_buf.is_absolute() {
-            tracing::warn!("Path is not absolute: {}", full_path);
-            results.push(FileUploadResult {
-                mount_path: full_path.clone(),
-                mime_type: String::new(),
-                size: file_data.len(),
-                success: false,
-                error: Some("Mount path must be absolute".to_string()),
-            });
-            failed += 1;
-            continue;
-        }
+    let mut mount = match mount {
+        Some(mount) => mount,
+        None => load_mount(&state, bucket_id, expected_version.as_deref()).await?,
+    };
 
-        // Detect MIME type from file extension
-        let mime_type = mime_guess::from_path(&mount_path_buf)
-            .first_or_octet_stream()
-            .to_string();
-
-        let file_size = file_data.len();
-
-        // Try to add file to mount
-        match mount
-            .add(&mount_path_buf, Cursor::new(file_data.clone()))
-            .await
-        {
-            Ok(_) => {
-                tracing::info!(
-                    "✓ Added file {} ({} bytes, {})",
-                    full_path,
-                    file_size,
-                    mime_type
-                );
-                results.push(FileUploadResult {
-                    mount_path: full_path,
-                    mime_type,
-                    size: file_size,
-                    success: true,
-                    error: None,
-                });
-                successful += 1;
-            }
-            Err(e) => {
-                tracing::error!("✗ Failed to add file {}: {}", full_path, e);
-                results.push(FileUploadResult {
-                    mount_path: full_path,
-                    mime_type,
-                    size: file_size,
-                    success: false,
-                    error: Some(e.to_string()),
-                });
-                failed += 1;
-            }
-        }
+    // Files that arrived before the bucket was known
+    let limit = limits.for_bucket(&bucket_id);
+    if let Some((filename, _, _)) = spooled.iter().find(|(_, _, size)| *size as u64 > limit) {
+        return Err(AddError::TooLarge {
+            filename: filename.clone(),
+            limit,
+        });
     }
+    let file_count = spooled.len();
+    for (idx, (filename, file, file_size)) in spooled.into_iter().enumerate() {
+        tracing::info!("Processing file {}/{}: {}", idx + 1, file_count, filename);
+        let file = tokio::fs::File::from_std(file);
+        let result = add_file(&mut mount, &base_path, &filename, file).await;
+        results.push(FileUploadResult {
+            size: file_size,
+            ..result
+        });
+    }
+
+    let successful = results.iter().filter(|result| result.success).count();
+    let failed = results.len() - successful;
 
     let bucket_link = if successful > 0 {
         tracing::info!("Saving mount (at least one file succeeded)");
@@ -211,6 +211,16 @@ pub async fn handler(
     };
 
     tracing::info!("Bucket link: {}", bucket_link);
+    for result in results.iter().filter(|result| result.success) {
+        state
+            .audit(
+                AuditEvent::new(Actor::Api, "bucket.add")
+                    .bucket(bucket_id)
+                    .path(result.mount_path.clone())
+                    .version(&bucket_link),
+            )
+            .await;
+    }
 
     Ok((
         http::StatusCode::OK,
@@ -225,12 +235,180 @@ pub async fn handler(
         .into_response())
 }
 
+/// Load the mount files are added to, at the bucket's current head
+async fn load_mount(
+    state: &ServiceState,
+    bucket_id: Uuid,
+    expected_version: Option<&str>,
+) -> Result<Mount, AddError> {
+    let expected_version = parse_version(expected_version).map_err(AddError::InvalidRequest)?;
+    tracing::info!("Loading mount for bucket {}", bucket_id);
+    let mount = state
+        .peer()
+        .mount_expecting(bucket_id, expected_version)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to load mount for bucket {}: {}", bucket_id, e);
+            e
+        })?;
+    Ok(mount)
+}
+
+/// Add one uploaded file under `base_path`, reporting how it went. The
+/// reported size is left for the caller, which counted the bytes.
+async fn add_file<R>(
+    mount: &mut Mount,
+    base_path: &str,
+    filename: &str,
+    data: R,
+) -> FileUploadResult
+where
+    R: AsyncRead + Send + Unpin,
+{
+    // Construct full path
+    let full_path = if base_path == "/" {
+        format!("/{}", filename)
+    } else {
+        format!("{}/{}", base_path.trim_end_matches('/'), filename)
+    };
+    tracing::info!("Full path: {}", full_path);
+
+    let mount_path_buf = PathBuf::from(&full_path);
+
+    // Validate mount path
+    if !mount_path_buf.is_absolute() {
+        tracing::warn!("Path is not absolute: {}", full_path);
+        return FileUploadResult {
+            mount_path: full_path,
+            mime_type: String::new(),
+            size: 0,
+            success: false,
+            error: Some("Mount path must be absolute".to_string()),
+        };
+    }
+
+    // Detect MIME type from file extension
+    let mime_type = mime_guess::from_path(&mount_path_buf)
+        .first_or_octet_stream()
+        .to_string();
+
+    // Try to add file to mount
+    match mount.add_reader(&mount_path_buf, data).await {
+        Ok(_) => {
+            // The mount falls back to sniffing the contents
+            let mime_type = stored_mime(mount, &mount_path_buf).await;
+            tracing::info!("✓ Added file {} ({})", full_path, mime_type);
+            FileUploadResult {
+                mount_path: full_path,
+                mime_type,
+                size: 0,
+                success: true,
+                error: None,
+            }
+        }
+        Err(e) => {
+            tracing::error!("✗ Failed to add file {}: {}", full_path, e);
+            FileUploadResult {
+                mount_path: full_path,
+                mime_type,
+                size: 0,
+                success: false,
+                error: Some(e.to_string()),
+            }
+        }
+    }
+}
+
+/// What was read of a streamed file field
+#[derive(Default)]
+struct FieldTally {
+    size: AtomicU64,
+    /// Set when the field couldn't be read to its end, e.g. a dropped upload
+    failed: AtomicBool,
+    /// Set when the field grew past its upload limit and was cut off
+    too_large: AtomicBool,
+}
+
+impl FieldTally {
+    fn size(&self) -> usize {
+        self.size.load(Ordering::Relaxed) as usize
+    }
+
+    /// Whether the field was cut off for going over its limit
+    fn over_limit(&self) -> bool {
+        self.too_large.load(Ordering::Relaxed)
+    }
+}
+
+fn too_large(filename: &str, limit: u64) -> AddError {
+    tracing::warn!("{} is over the upload limit of {} bytes", filename, limit);
+    AddError::TooLarge {
+        filename: filename.to_string(),
+        limit,
+    }
+}
+
+/// Read a file field as it arrives, tallying what was read and cutting it
+/// off once it grows past `limit` bytes
+fn stream_field(
+    field: Field<'_>,
+    limit: u64,
+) -> (impl AsyncRead + Send + Unpin + '_, Arc<FieldTally>) {
+    let tally = Arc::new(FieldTally::default());
+    let (read, failed) = (tally.clone(), tally.clone());
+    let stream = field
+        .map_err(move |e| {
+            failed.failed.store(true, Ordering::Relaxed);
+            std::io::Error::other(e)
+        })
+        .and_then(move |chunk| {
+            let size = read.size.fetch_add(chunk.len() as u64, Ordering::Relaxed);
+            if size + chunk.len() as u64 > limit {
+                read.too_large.store(true, Ordering::Relaxed);
+                return future::ready(Err(std::io::Error::other("upload limit exceeded")));
+            }
+            future::ready(Ok(chunk))
+        });
+    (StreamReader::new(Box::pin(stream)), tally)
+}
+
+/// Copy a streamed file to an anonymous temp file, rewound to its start
+async fn spool<R: AsyncRead + Unpin>(mut reader: R) -> std::io::Result<File> {
+    let mut file = tokio::fs::File::from_std(tempfile::tempfile()?);
+    tokio::io::copy(&mut reader, &mut file).await?;
+    file.flush().await?;
+
+    let mut file = file.into_std().await;
+    file.seek(SeekFrom::Start(0))?;
+    Ok(file)
+}
+
+/// Write an uploaded file field to an anonymous temp file as it arrives, so
+/// uploads stream into the mount instead of being held in memory.
+///
+/// Returns the file, rewound to its start, and its size.
+pub(super) async fn spool_field(mut field: Field<'_>) -> anyhow::Result<(File, usize)> {
+    let mut file = tokio::fs::File::from_std(tempfile::tempfile()?);
+    let mut size = 0;
+    while let Some(chunk) = field.chunk().await? {
+        size += chunk.len();
+        file.write_all(&chunk).await?;
+    }
+    file.flush().await?;
+
+    let mut file = file.into_std().await;
+    file.seek(SeekFrom::Start(0))?;
+    Ok((file, size))
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum AddError {
     #[error("Invalid request: {0}")]
     InvalidRequest(String),
     #[error("Multipart error: {0}")]
     MultipartError(String),
+    #[error("{filename} is larger than the upload limit of {limit} bytes")]
+    TooLarge { filename: String, limit: u64 },
     #[error("Mount error: {0}")]
     Mount(#[from] MountError),
 }
@@ -243,6 +421,12 @@ impl IntoResponse for AddError {
                 format!("Bad request: {}", msg),
             )
                 .into_response(),
+            AddError::TooLarge { limit, .. } => {
+                ApiError::new(http::StatusCode::PAYLOAD_TOO_LARGE, self.to_string())
+                    .with_details(serde_json::json!({ "max_bytes": limit }))
+                    .into_response()
+            }
+            AddError::Mount(MountError::HeadMoved(head)) => head_moved(&head).into_response(),
             AddError::Mount(_) => (
                 http::StatusCode::INTERNAL_SERVER_ERROR,
                 "Unexpected error".to_string(),
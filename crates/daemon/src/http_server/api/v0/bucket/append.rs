@@ -0,0 +1,165 @@
+use axum::extract::{Multipart, State};
+use axum::response::{IntoResponse, Response};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use uuid::Uuid;
+
+use common::prelude::{Link, MountError};
+
+use super::add::spool_field;
+use super::{head_moved, parse_version};
+use crate::audit::{Actor, AuditEvent};
+use crate::ServiceState;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppendResponse {
+    pub mount_path: String,
+    pub link: Link,
+    /// Number of bytes appended
+    pub appended: usize,
+}
+
+/// Append to a file, creating it if missing. The appended bytes are stored
+/// as new chunks, so appending to a large log doesn't rewrite it.
+pub async fn handler(
+    State(state): State<ServiceState>,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, AppendError> {
+    let mut bucket_id: Option<Uuid> = None;
+    let mut mount_path: Option<String> = None;
+    let mut expected_version: Option<String> = None;
+    let mut file_data = None;
+
+    // Parse multipart form data
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppendError::MultipartError(e.to_string()))?
+    {
+        let field_name = field.name().unwrap_or("").to_string();
+
+        match field_name.as_str() {
+            "bucket_id" => {
+                let text = field
+                    .text()
+                    .await
+                    .map_err(|e| AppendError::MultipartError(e.to_string()))?;
+                bucket_id = Some(
+                    Uuid::parse_str(&text)
+                        .map_err(|_| AppendError::InvalidRequest("Invalid bucket_id".into()))?,
+                );
+            }
+            "mount_path" => {
+                mount_path = Some(
+                    field
+                        .text()
+                        .await
+                        .map_err(|e| AppendError::MultipartError(e.to_string()))?,
+                );
+            }
+            "expected_version" => {
+                expected_version = Some(
+                    field
+                        .text()
+                        .await
+                        .map_err(|e| AppendError::MultipartError(e.to_string()))?,
+                );
+            }
+            "file" => {
+                file_data = Some(
+                    spool_field(field)
+                        .await
+                        .map_err(|e| AppendError::MultipartError(e.to_string()))?,
+                );
+            }
+            _ => {}
+        }
+    }
+
+    let bucket_id =
+        bucket_id.ok_or_else(|| AppendError::InvalidRequest("bucket_id is required".into()))?;
+    let mount_path =
+        mount_path.ok_or_else(|| AppendError::InvalidRequest("mount_path is required".into()))?;
+    let (file_data, appended) =
+        file_data.ok_or_else(|| AppendError::InvalidRequest("file is required".into()))?;
+    let expected_version =
+        parse_version(expected_version.as_deref()).map_err(AppendError::InvalidRequest)?;
+
+    // Validate mount path
+    let mount_path_buf = PathBuf::from(&mount_path);
+    if !mount_path_buf.is_absolute() {
+        return Err(AppendError::InvalidPath(
+            "Mount path must be absolute".into(),
+        ));
+    }
+
+    let mut mount = state
+        .peer()
+        .mount_expecting(bucket_id, expected_version)
+        .await?;
+    mount.append(&mount_path_buf, file_data).await?;
+    let new_bucket_link = state.peer().save_mount(&mount, false).await?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "bucket.append")
+                .bucket(bucket_id)
+                .path(mount_path.clone())
+                .version(&new_bucket_link),
+        )
+        .await;
+
+    tracing::info!(
+        "APPEND API: Appended {} bytes to {} in bucket {}, new link: {}",
+        appended,
+        mount_path,
+        bucket_id,
+        new_bucket_link.hash()
+    );
+
+    Ok((
+        http::StatusCode::OK,
+        axum::Json(AppendResponse {
+            mount_path,
+            link: new_bucket_link,
+            appended,
+        }),
+    )
+        .into_response())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AppendError {
+    #[error("Invalid path: {0}")]
+    InvalidPath(String),
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+    #[error("Multipart error: {0}")]
+    MultipartError(String),
+    #[error("Mount error: {0}")]
+    Mount(#[from] MountError),
+}
+
+impl IntoResponse for AppendError {
+    fn into_response(self) -> Response {
+        match self {
+            AppendError::InvalidPath(msg)
+            | AppendError::InvalidRequest(msg)
+            | AppendError::MultipartError(msg) => (
+                http::StatusCode::BAD_REQUEST,
+                format!("Bad request: {}", msg),
+            )
+                .into_response(),
+            AppendError::Mount(MountError::PathAlreadyExists(path)) => (
+                http::StatusCode::CONFLICT,
+                format!("Not a file: {}", path.display()),
+            )
+                .into_response(),
+            AppendError::Mount(MountError::HeadMoved(head)) => head_moved(&head).into_response(),
+            AppendError::Mount(_) => (
+                http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Unexpected error".to_string(),
+            )
+                .into_response(),
+        }
+    }
+}
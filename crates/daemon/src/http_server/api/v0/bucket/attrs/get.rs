@@ -0,0 +1,62 @@
+use std::path::PathBuf;
+
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::super::parse_version;
+use super::{AttrsError, AttrsResponse};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Get the custom attributes of a file or directory
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct GetAttrsRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Path in bucket of the file or directory (`/` for the bucket itself)
+    #[arg(long)]
+    pub path: String,
+
+    /// Optional: read at no older a version than this one (a bucket link hash)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub min_version: Option<String>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<GetAttrsRequest>,
+) -> Result<impl IntoResponse, AttrsError> {
+    let min_version =
+        parse_version(req.min_version.as_deref()).map_err(AttrsError::InvalidVersion)?;
+    let mount = state
+        .peer()
+        .mount_for_read_at_least(req.bucket_id, min_version)
+        .await?;
+
+    let attrs = mount.attrs(&PathBuf::from(&req.path)).await?;
+
+    Ok((
+        http::StatusCode::OK,
+        Json(AttrsResponse {
+            path: req.path,
+            attrs,
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for GetAttrsRequest {
+    type Response = AttrsResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/attrs/get").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
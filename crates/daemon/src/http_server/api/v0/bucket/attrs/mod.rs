@@ -0,0 +1,69 @@
+//! Custom attribute endpoints
+//!
+//! Files and directories carry arbitrary key-value attributes (tags, labels,
+//! app-specific fields), stored encrypted with the rest of the bucket.
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use serde::{Deserialize, Serialize};
+
+use common::mount::Attrs;
+use common::prelude::MountError;
+
+use super::head_moved;
+use crate::http_server::error::ApiError;
+use crate::ServiceState;
+
+pub mod get;
+pub mod set;
+
+pub use get::GetAttrsRequest;
+pub use set::{SetAttrsRequest, SetAttrsResponse};
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/get", post(get::handler))
+        .route("/set", post(set::handler))
+        .with_state(state)
+}
+
+/// The attributes of one file or directory
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AttrsResponse {
+    pub path: String,
+    pub attrs: Attrs,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AttrsError {
+    #[error("Invalid version: {0}")]
+    InvalidVersion(String),
+    #[error("Mount error: {0}")]
+    Mount(#[from] MountError),
+}
+
+impl IntoResponse for AttrsError {
+    fn into_response(self) -> Response {
+        match self {
+            AttrsError::InvalidVersion(_)
+            | AttrsError::Mount(MountError::InvalidAttrs(_))
+            | AttrsError::Mount(MountError::PathNotNode(_)) => {
+                (StatusCode::BAD_REQUEST, self.to_string()).into_response()
+            }
+            AttrsError::Mount(MountError::PathNotFound(_)) => {
+                (StatusCode::NOT_FOUND, self.to_string()).into_response()
+            }
+            AttrsError::Mount(MountError::HeadMoved(head)) => head_moved(&head).into_response(),
+            AttrsError::Mount(e @ MountError::VersionUnavailable(_)) => {
+                ApiError::from(e).into_response()
+            }
+            AttrsError::Mount(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Unexpected error".to_string(),
+            )
+                .into_response(),
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use std::path::PathBuf;
+
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::mount::Attrs;
+use common::prelude::Link;
+
+use super::super::parse_version;
+use super::AttrsError;
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Set and remove custom attributes on a file or directory. Attributes not
+/// named in either are left as they are.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetAttrsRequest {
+    pub bucket_id: Uuid,
+    /// Path in bucket of the file or directory (`/` for the bucket itself)
+    pub path: String,
+    /// Attributes to add or overwrite
+    #[serde(default)]
+    pub attrs: Attrs,
+    /// Names of attributes to remove
+    #[serde(default)]
+    pub remove: Vec<String>,
+
+    /// Fail with 409 unless the bucket head is still this version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetAttrsResponse {
+    pub path: String,
+    /// The attributes after the change
+    pub attrs: Attrs,
+    /// The bucket version the change was saved as
+    pub link: Link,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<SetAttrsRequest>,
+) -> Result<impl IntoResponse, AttrsError> {
+    let expected_version =
+        parse_version(req.expected_version.as_deref()).map_err(AttrsError::InvalidVersion)?;
+    let mut mount = state
+        .peer()
+        .mount_expecting(req.bucket_id, expected_version)
+        .await?;
+
+    let attrs = mount
+        .set_attrs(&PathBuf::from(&req.path), req.attrs, &req.remove)
+        .await?;
+
+    let link = state.peer().save_mount(&mount, false).await?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "bucket.attrs.set")
+                .bucket(req.bucket_id)
+                .path(req.path.clone())
+                .version(&link),
+        )
+        .await;
+
+    Ok((
+        http::StatusCode::OK,
+        Json(SetAttrsResponse {
+            path: req.path,
+            attrs,
+            link,
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for SetAttrsRequest {
+    type Response = SetAttrsResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/attrs/set").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
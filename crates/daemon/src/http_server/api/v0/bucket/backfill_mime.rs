@@ -0,0 +1,105 @@
+use axum::extract::{Json, State};
+use axum::response::{IntoResponse, Response};
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::prelude::{Link, MountError};
+
+use super::head_moved;
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Detect and record the MIME type of the files of a bucket that have none,
+/// such as extensionless files added before content sniffing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackfillMimeRequest {
+    pub bucket_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackfillMimeResponse {
+    pub bucket_id: Uuid,
+    /// Paths of the files whose type was detected
+    pub updated: Vec<String>,
+    /// The version the changes were saved as, if there were any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<Link>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<BackfillMimeRequest>,
+) -> Result<impl IntoResponse, BackfillMimeError> {
+    let mut mount = state.peer().mount_expecting(req.bucket_id, None).await?;
+
+    let updated = mount.backfill_mime().await?;
+    let link = if updated.is_empty() {
+        None
+    } else {
+        Some(state.peer().save_mount(&mount, false).await?)
+    };
+    if let Some(link) = &link {
+        state
+            .audit(
+                AuditEvent::new(Actor::Api, "bucket.backfill_mime")
+                    .bucket(req.bucket_id)
+                    .version(link),
+            )
+            .await;
+    }
+
+    tracing::info!(
+        "Backfilled MIME types of {} files in bucket {}",
+        updated.len(),
+        req.bucket_id
+    );
+
+    Ok((
+        http::StatusCode::OK,
+        Json(BackfillMimeResponse {
+            bucket_id: req.bucket_id,
+            updated: updated
+                .iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect(),
+            link,
+        }),
+    )
+        .into_response())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BackfillMimeError {
+    #[error("Mount error: {0}")]
+    Mount(#[from] MountError),
+}
+
+impl IntoResponse for BackfillMimeError {
+    fn into_response(self) -> Response {
+        match self {
+            BackfillMimeError::Mount(MountError::HeadMoved(head)) => {
+                head_moved(&head).into_response()
+            }
+            BackfillMimeError::Mount(MountError::MirrorCannotMount) => {
+                (http::StatusCode::FORBIDDEN, self.to_string()).into_response()
+            }
+            BackfillMimeError::Mount(_) => (
+                http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Unexpected error".to_string(),
+            )
+                .into_response(),
+        }
+    }
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for BackfillMimeRequest {
+    type Response = BackfillMimeResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/backfill-mime").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -1,13 +1,19 @@
+use std::path::{Path, PathBuf};
+
 use axum::extract::{Json, Query, State};
+use axum::http::{HeaderMap, Method};
 use axum::response::{IntoResponse, Response};
 use base64::Engine;
 use reqwest::{Client, RequestBuilder, Url};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use common::prelude::MountError;
+use common::peer::Resolved;
+use common::prelude::{Mount, MountError};
 
 use crate::http_server::api::client::ApiRequest;
+use crate::http_server::conditional;
+use crate::http_server::error::{is_archived, ApiError};
 use crate::ServiceState;
 
 #[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
@@ -25,6 +31,12 @@ pub struct CatRequest {
     #[serde(default)]
     pub at: Option<String>,
 
+    /// Optional: read at no older a version than this one, e.g. the link a
+    /// write returned (defaults to the last version written through this node)
+    #[arg(long)]
+    #[serde(default)]
+    pub min_version: Option<String>,
+
     /// Optional: force download (attachment) instead of inline display
     #[arg(long)]
     #[serde(default)]
@@ -49,24 +61,34 @@ pub async fn handler(
     Ok((http::StatusCode::OK, Json(response)).into_response())
 }
 
-// Query GET handler (for viewing/downloading)
+// Query GET handler (for viewing/downloading), also answering HEAD
 pub async fn handler_get(
     State(state): State<ServiceState>,
+    method: Method,
+    headers: HeaderMap,
     Query(req): Query<CatRequest>,
 ) -> Result<Response, CatError> {
     let is_download = req.download.unwrap_or(false);
-    let cat_response = handle_cat_request(state, req).await?;
+    let (mount, path_buf) = resolve(&state, &req).await?;
+    let node_link = mount.get(&path_buf).await?;
+
+    let bucket_id = *mount.inner().await.manifest().id();
+    let last_modified = conditional::last_modified(&state, bucket_id, &mount).await;
+    if let Some(response) = conditional::not_modified(&headers, last_modified) {
+        return Ok(response);
+    }
 
-    // Decode base64 content back to bytes
-    let content_bytes = base64::engine::general_purpose::STANDARD
-        .decode(&cat_response.content)
-        .map_err(|e| CatError::InvalidPath(format!("Failed to decode content: {}", e)))?;
+    let mime_type = node_link
+        .data()
+        .and_then(|data| data.mime())
+        .map(|mime| mime.to_string())
+        .unwrap_or_else(|| "application/octet-stream".to_string());
 
     // Determine Content-Disposition header (inline for viewing, attachment for download)
     let disposition = if is_download {
         format!(
             "attachment; filename=\"{}\"",
-            std::path::Path::new(&cat_response.path)
+            path_buf
                 .file_name()
                 .and_then(|n| n.to_str())
                 .unwrap_or("download")
@@ -74,61 +96,95 @@ pub async fn handler_get(
     } else {
         format!(
             "inline; filename=\"{}\"",
-            std::path::Path::new(&cat_response.path)
+            path_buf
                 .file_name()
                 .and_then(|n| n.to_str())
                 .unwrap_or("file")
         )
     };
 
-    // Return as binary with appropriate headers
-    Ok((
-        http::StatusCode::OK,
-        [
-            (
-                axum::http::header::CONTENT_TYPE,
-                cat_response.mime_type.as_str(),
-            ),
+    // HEAD is answered from the recorded size, without reading the file
+    let size = node_link.data().and_then(|data| data.size());
+    let mut response = match size {
+        Some(size) if method == Method::HEAD => {
+            conditional::head_response(&mime_type, size, &disposition)
+        }
+        _ => {
+            let data = mount.cat(&path_buf).await?;
             (
-                axum::http::header::CONTENT_DISPOSITION,
-                disposition.as_str(),
-            ),
-        ],
-        content_bytes,
-    )
-        .into_response())
+                http::StatusCode::OK,
+                [
+                    (axum::http::header::CONTENT_TYPE, mime_type.as_str()),
+                    (
+                        axum::http::header::CONTENT_DISPOSITION,
+                        disposition.as_str(),
+                    ),
+                ],
+                data,
+            )
+                .into_response()
+        }
+    };
+    conditional::set_last_modified(&mut response, last_modified);
+    Ok(response)
 }
 
-async fn handle_cat_request(state: ServiceState, req: CatRequest) -> Result<CatResponse, CatError> {
+/// Load the version of the bucket a request reads
+async fn load_mount(state: &ServiceState, req: &CatRequest) -> Result<Mount, CatError> {
     // Load mount - either from specific link or role-based
-    let mount = if let Some(hash_str) = &req.at {
+    if let Some(hash_str) = &req.at {
         // Parse the hash string and create a Link
         match hash_str.parse::<common::linked_data::Hash>() {
             Ok(hash) => {
                 let link = common::linked_data::Link::new(common::linked_data::LD_RAW_CODEC, hash);
-                match common::mount::Mount::load(&link, state.peer().secret(), state.peer().blobs())
-                    .await
-                {
-                    Ok(mount) => mount,
+                match Mount::load(&link, state.peer().secret(), state.peer().blobs()).await {
+                    Ok(mount) => Ok(mount),
                     Err(e) => {
                         tracing::error!("Failed to load mount from link: {}", e);
-                        return Err(CatError::Mount(e));
+                        Err(CatError::Mount(e))
                     }
                 }
             }
-            Err(e) => {
-                return Err(CatError::InvalidPath(format!("Invalid hash format: {}", e)));
-            }
+            Err(e) => Err(CatError::InvalidPath(format!("Invalid hash format: {}", e))),
         }
     } else {
+        let min_version = req
+            .min_version
+            .as_deref()
+            .map(str::parse::<common::linked_data::Hash>)
+            .transpose()
+            .map_err(|e| CatError::InvalidPath(format!("Invalid version: {}", e)))?;
+
         // Load mount based on role (owners see HEAD, mirrors see latest_published)
-        state.peer().mount_for_read(req.bucket_id).await?
-    };
+        Ok(state
+            .peer()
+            .mount_for_read_at_least(req.bucket_id, min_version)
+            .await?)
+    }
+}
 
-    let path_buf = std::path::PathBuf::from(&req.path);
+/// The bucket the requested path ends up in, following bucket references,
+/// and the path within it
+async fn resolve(state: &ServiceState, req: &CatRequest) -> Result<(Mount, PathBuf), CatError> {
+    let mount = load_mount(state, req).await?;
+    let path_buf = PathBuf::from(&req.path);
     if !path_buf.is_absolute() {
         return Err(CatError::InvalidPath("Path must be absolute".into()));
     }
+    let Resolved {
+        mount,
+        path,
+        mount_point,
+    } = state.peer().resolve(mount, &path_buf).await?;
+    // The path of a reference itself is the referenced bucket's root
+    if mount_point != Path::new("/") && path == Path::new("/") {
+        return Err(MountError::BucketReference(path_buf).into());
+    }
+    Ok((mount, path))
+}
+
+async fn handle_cat_request(state: ServiceState, req: CatRequest) -> Result<CatResponse, CatError> {
+    let (mount, path_buf) = resolve(&state, &req).await?;
 
     // Get file data
     let data = mount.cat(&path_buf).await?;
@@ -169,6 +225,23 @@ impl IntoResponse for CatError {
                 format!("Invalid path: {}", msg),
             )
                 .into_response(),
+            CatError::Mount(MountError::PathNotFound(path)) => (
+                http::StatusCode::NOT_FOUND,
+                format!("Path not found: {}", path.display()),
+            )
+                .into_response(),
+            CatError::Mount(e @ MountError::VersionUnavailable(_)) => {
+                ApiError::from(e).into_response()
+            }
+            CatError::Mount(e) if is_archived(&e) => ApiError::from(e).into_response(),
+            CatError::Mount(
+                MountError::BucketReference(_)
+                | MountError::ReferenceCycle(_)
+                | MountError::ReferencesTooDeep(_),
+            ) => (http::StatusCode::BAD_REQUEST, self.to_string()).into_response(),
+            CatError::Mount(MountError::ShareNotFound | MountError::MirrorCannotMount) => {
+                (http::StatusCode::FORBIDDEN, self.to_string()).into_response()
+            }
             CatError::Mount(_) => (
                 http::StatusCode::INTERNAL_SERVER_ERROR,
                 "Unexpected error".to_string(),
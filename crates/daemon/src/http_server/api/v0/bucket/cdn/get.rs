@@ -0,0 +1,55 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::CdnApiError;
+use crate::cdn_export::CdnExport;
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Show where a bucket's published content is mirrored for a CDN
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct CdnGetRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CdnExportResponse {
+    pub bucket_id: Uuid,
+    /// The bucket's CDN export, if it has one
+    pub export: Option<CdnExport>,
+    /// CDN targets configured on this node
+    pub targets: Vec<String>,
+}
+
+impl CdnExportResponse {
+    pub(crate) async fn load(state: &ServiceState, bucket_id: Uuid) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            bucket_id,
+            export: state.database().cdn_export(&bucket_id).await?,
+            targets: state.cdn_exports().targets(),
+        })
+    }
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<CdnGetRequest>,
+) -> Result<impl IntoResponse, CdnApiError> {
+    let response = CdnExportResponse::load(&state, req.bucket_id).await?;
+    Ok((http::StatusCode::OK, Json(response)).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for CdnGetRequest {
+    type Response = CdnExportResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/cdn/get").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
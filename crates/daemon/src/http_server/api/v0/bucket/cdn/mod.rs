@@ -0,0 +1,58 @@
+//! CDN export endpoints (see [`crate::cdn_export`])
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use uuid::Uuid;
+
+use crate::cdn_export::CdnExportError;
+use crate::http_server::error::ApiError;
+use crate::ServiceState;
+
+pub mod get;
+pub mod reset;
+pub mod run;
+pub mod set;
+
+pub use get::{CdnExportResponse, CdnGetRequest};
+pub use reset::{CdnResetRequest, CdnResetResponse};
+pub use run::CdnRunRequest;
+pub use set::CdnSetRequest;
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/get", post(get::handler))
+        .route("/set", post(set::handler))
+        .route("/reset", post(reset::handler))
+        .route("/run", post(run::handler))
+        .with_state(state)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CdnApiError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Bucket not found: {0}")]
+    BucketNotFound(Uuid),
+    #[error("Unknown CDN target {0} (configure it under cdn_targets in config.toml)")]
+    UnknownTarget(String),
+    #[error("Invalid prefix {0}: it must be a relative path without . or ..")]
+    InvalidPrefix(String),
+    #[error("{0}")]
+    Export(#[from] CdnExportError),
+}
+
+impl IntoResponse for CdnApiError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            CdnApiError::UnknownTarget(_) | CdnApiError::InvalidPrefix(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            CdnApiError::BucketNotFound(_)
+            | CdnApiError::Export(CdnExportError::NotConfigured(_)) => StatusCode::NOT_FOUND,
+            CdnApiError::Database(_) | CdnApiError::Export(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
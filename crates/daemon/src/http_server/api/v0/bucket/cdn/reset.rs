@@ -0,0 +1,60 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::CdnApiError;
+use crate::audit::{Actor, AuditEvent};
+use crate::cdn_export::CdnExportReport;
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Stop mirroring a bucket for a CDN and delete what was written to its
+/// target
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct CdnResetRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CdnResetResponse {
+    pub bucket_id: Uuid,
+    /// What was deleted from the target, if the bucket had an export
+    pub withdrawal: Option<CdnExportReport>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<CdnResetRequest>,
+) -> Result<impl IntoResponse, CdnApiError> {
+    let mut withdrawal = None;
+    if let Some(cdn_export) = state.database().cdn_export(&req.bucket_id).await? {
+        state.database().delete_cdn_export(&req.bucket_id).await?;
+        state
+            .audit(AuditEvent::new(Actor::Api, "bucket.cdn.reset").bucket(req.bucket_id))
+            .await;
+        withdrawal = Some(state.cdn_exports().withdraw(cdn_export).await?);
+    }
+
+    Ok((
+        http::StatusCode::OK,
+        Json(CdnResetResponse {
+            bucket_id: req.bucket_id,
+            withdrawal,
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for CdnResetRequest {
+    type Response = CdnResetResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/cdn/reset").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
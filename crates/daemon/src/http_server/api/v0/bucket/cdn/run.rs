@@ -0,0 +1,37 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::CdnApiError;
+use crate::cdn_export::CdnExportReport;
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Export a bucket's latest published version to its CDN target now, or
+/// withdraw the export if the bucket isn't published
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct CdnRunRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<CdnRunRequest>,
+) -> Result<impl IntoResponse, CdnApiError> {
+    let report = state.cdn_exports().run(req.bucket_id).await?;
+    Ok((http::StatusCode::OK, Json(report)).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for CdnRunRequest {
+    type Response = CdnExportReport;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/cdn/run").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,82 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{CdnApiError, CdnExportResponse};
+use crate::audit::{Actor, AuditEvent};
+use crate::cdn_export::normalize_prefix;
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Mirror a bucket's published content into one of the node's CDN targets.
+/// It's exported in the background, or right away with `run`, and again
+/// whenever the bucket is published.
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct CdnSetRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Name of the target, as configured under cdn_targets
+    #[arg(long)]
+    pub target: String,
+
+    /// Path in the target to write below, e.g. sites/blog; its root if empty
+    #[arg(long, default_value = "")]
+    #[serde(default)]
+    pub prefix: String,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<CdnSetRequest>,
+) -> Result<impl IntoResponse, CdnApiError> {
+    if !state.cdn_exports().targets().contains(&req.target) {
+        return Err(CdnApiError::UnknownTarget(req.target));
+    }
+    let prefix =
+        normalize_prefix(&req.prefix).ok_or_else(|| CdnApiError::InvalidPrefix(req.prefix))?;
+    if state
+        .database()
+        .get_bucket_info(&req.bucket_id)
+        .await?
+        .is_none()
+    {
+        return Err(CdnApiError::BucketNotFound(req.bucket_id));
+    }
+
+    let previous = state.database().cdn_export(&req.bucket_id).await?;
+    state
+        .database()
+        .set_cdn_export(&req.bucket_id, &req.target, &prefix)
+        .await?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "bucket.cdn.set")
+                .bucket(req.bucket_id)
+                .detail(format!("{}:{}", req.target, prefix)),
+        )
+        .await;
+    // What was written where the bucket was exported before goes
+    if let Some(previous) = previous {
+        if previous.target != req.target || previous.prefix != prefix {
+            state.cdn_exports().withdraw(previous).await?;
+        }
+    }
+    state.cdn_exports().published(req.bucket_id);
+
+    let response = CdnExportResponse::load(&state, req.bucket_id).await?;
+    Ok((http::StatusCode::OK, Json(response)).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for CdnSetRequest {
+    type Response = CdnExportResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/cdn/set").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,91 @@
+use axum::extract::{Json, State};
+use axum::response::{IntoResponse, Response};
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::audit::{Actor, AuditEvent};
+use crate::ephemeral::{EphemeralError, EphemeralTicket};
+use crate::http_server::api::client::ApiRequest;
+use crate::http_server::error::ApiError;
+use crate::ServiceState;
+
+/// Claim a bucket shared with a temporary identity, following it read-only
+/// with the identity's key
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct ClaimRequest {
+    /// Ticket of the ephemeral share (<bucket-id>@<node-id>#<key>)
+    pub ticket: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaimResponse {
+    pub bucket_id: Uuid,
+    /// Node the bucket is followed from
+    pub node_id: String,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<ClaimRequest>,
+) -> Result<impl IntoResponse, ClaimError> {
+    let EphemeralTicket { follow, key } = req.ticket.parse()?;
+    if follow.node_id == state.peer().secret().public() {
+        return Err(ClaimError::OwnNode);
+    }
+
+    tracing::info!(
+        "CLAIM API: Following bucket {} from {} as a temporary identity",
+        follow.bucket_id,
+        follow.node_id.to_hex()
+    );
+    state
+        .follow_bucket_ephemeral(follow.bucket_id, follow.node_id, key)
+        .await?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "bucket.follow")
+                .bucket(follow.bucket_id)
+                .detail(follow.node_id.to_hex()),
+        )
+        .await;
+
+    Ok((
+        http::StatusCode::OK,
+        Json(ClaimResponse {
+            bucket_id: follow.bucket_id,
+            node_id: follow.node_id.to_hex(),
+        }),
+    )
+        .into_response())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClaimError {
+    #[error("{0}")]
+    Ticket(#[from] EphemeralError),
+    #[error("Cannot claim a bucket shared from this node")]
+    OwnNode,
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+impl IntoResponse for ClaimError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            ClaimError::Ticket(_) | ClaimError::OwnNode => http::StatusCode::BAD_REQUEST,
+            ClaimError::Database(_) => http::StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for ClaimRequest {
+    type Response = ClaimResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/claim").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,126 @@
+use std::path::Path;
+
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::prelude::{Link, Mount};
+
+use super::super::parse_version;
+use super::{CommentInfo, CommentsError};
+use crate::audit::{Actor, AuditEvent};
+use crate::deploy::normalize_path;
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Comment on a file or directory
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct AddCommentRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Path in bucket of the file or directory (`/` for the bucket itself)
+    #[arg(long)]
+    pub path: String,
+
+    /// The comment
+    #[arg(long)]
+    pub body: String,
+
+    /// Bucket version the comment refers to (a bucket link hash); the
+    /// current version if not given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub version: Option<String>,
+
+    /// Fail with 409 unless the bucket head is still this version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub expected_version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddCommentResponse {
+    pub comment: CommentInfo,
+    /// The bucket version the comment was saved as
+    pub link: Link,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<AddCommentRequest>,
+) -> Result<impl IntoResponse, CommentsError> {
+    let path = normalize_path(&req.path).ok_or_else(|| CommentsError::InvalidPath(req.path))?;
+    let version = parse_version(req.version.as_deref()).map_err(CommentsError::InvalidVersion)?;
+    let expected_version =
+        parse_version(req.expected_version.as_deref()).map_err(CommentsError::InvalidVersion)?;
+    let mut mount = state
+        .peer()
+        .mount_expecting(req.bucket_id, expected_version)
+        .await?;
+
+    // The path must exist at the version commented on
+    let version = match version {
+        Some(hash) => {
+            let link = state
+                .database()
+                .get_all_bucket_logs(&req.bucket_id)
+                .await
+                .map_err(|e| CommentsError::BucketLog(e.to_string()))?
+                .into_iter()
+                .find(|entry| entry.current_link.hash() == hash)
+                .map(|entry| entry.current_link)
+                .ok_or_else(|| CommentsError::UnknownVersion(hash.to_string()))?;
+            let at = Mount::load(&link, state.peer().secret(), state.peer().blobs()).await?;
+            check_path(&at, &path).await?;
+            hash
+        }
+        None => {
+            check_path(&mount, &path).await?;
+            mount.link().await.hash()
+        }
+    };
+
+    let comment = mount
+        .add_comment(Path::new(&path), version, &req.body)
+        .await?;
+
+    let link = state.peer().save_mount(&mount, false).await?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "bucket.comments.add")
+                .bucket(req.bucket_id)
+                .path(path)
+                .version(&link),
+        )
+        .await;
+
+    Ok((
+        http::StatusCode::OK,
+        Json(AddCommentResponse {
+            comment: CommentInfo::from(&comment),
+            link,
+        }),
+    )
+        .into_response())
+}
+
+async fn check_path(mount: &Mount, path: &str) -> Result<(), CommentsError> {
+    if path != "/" {
+        mount.get(Path::new(path)).await?;
+    }
+    Ok(())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for AddCommentRequest {
+    type Response = AddCommentResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/comments/add").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,76 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::super::parse_version;
+use super::{CommentInfo, CommentsError};
+use crate::deploy::normalize_path;
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// List the comments of a bucket, oldest first
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct CommentsRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Only list comments on this path
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub path: Option<String>,
+
+    /// Optional: read at no older a version than this one (a bucket link hash)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub min_version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommentsResponse {
+    pub comments: Vec<CommentInfo>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<CommentsRequest>,
+) -> Result<impl IntoResponse, CommentsError> {
+    let min_version =
+        parse_version(req.min_version.as_deref()).map_err(CommentsError::InvalidVersion)?;
+    let path = match req.path.as_deref() {
+        Some(path) => {
+            Some(normalize_path(path).ok_or_else(|| CommentsError::InvalidPath(path.into()))?)
+        }
+        None => None,
+    };
+    let mount = state
+        .peer()
+        .mount_for_read_at_least(req.bucket_id, min_version)
+        .await?;
+
+    let comments = mount.comments().await;
+    let comments = match &path {
+        Some(path) => comments.on_path(path),
+        None => comments.all(),
+    };
+
+    Ok((
+        http::StatusCode::OK,
+        Json(CommentsResponse {
+            comments: comments.into_iter().map(CommentInfo::from).collect(),
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for CommentsRequest {
+    type Response = CommentsResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/comments").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,110 @@
+//! Comment endpoints
+//!
+//! Peers comment on a file or directory as it was at a bucket version. The
+//! comments are stored encrypted with the bucket and sync with its versions
+//! (see [`common::mount::Comments`]); adding or removing one saves a new
+//! version.
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use common::mount::Comment;
+use common::prelude::MountError;
+
+use super::head_moved;
+use crate::http_server::error::ApiError;
+use crate::ServiceState;
+
+pub mod add;
+pub mod list;
+pub mod remove;
+
+pub use add::{AddCommentRequest, AddCommentResponse};
+pub use list::{CommentsRequest, CommentsResponse};
+pub use remove::{RemoveCommentRequest, RemoveCommentResponse};
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/", post(list::handler))
+        .route("/add", post(add::handler))
+        .route("/remove", post(remove::handler))
+        .with_state(state)
+}
+
+/// A comment on a file or directory
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CommentInfo {
+    pub id: Uuid,
+    pub path: String,
+    /// Bucket version the comment refers to
+    pub version: String,
+    /// Public key of the peer that wrote it
+    pub author: String,
+    pub body: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+impl From<&Comment> for CommentInfo {
+    fn from(comment: &Comment) -> Self {
+        CommentInfo {
+            id: comment.id,
+            path: comment.path.clone(),
+            version: comment.version.to_string(),
+            author: comment.author.to_hex(),
+            body: comment.body.clone(),
+            created_at: OffsetDateTime::from_unix_timestamp(comment.created_at)
+                .unwrap_or(OffsetDateTime::UNIX_EPOCH),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CommentsError {
+    #[error("Invalid version: {0}")]
+    InvalidVersion(String),
+    #[error("Invalid path: {0}")]
+    InvalidPath(String),
+    #[error("Version {0} is not of this bucket")]
+    UnknownVersion(String),
+    #[error("Comment {0} was written by another peer")]
+    NotAuthor(Uuid),
+    #[error("Bucket log error: {0}")]
+    BucketLog(String),
+    #[error("Mount error: {0}")]
+    Mount(#[from] MountError),
+}
+
+impl IntoResponse for CommentsError {
+    fn into_response(self) -> Response {
+        match self {
+            CommentsError::InvalidVersion(_)
+            | CommentsError::InvalidPath(_)
+            | CommentsError::Mount(MountError::InvalidComment(_)) => {
+                (StatusCode::BAD_REQUEST, self.to_string()).into_response()
+            }
+            CommentsError::UnknownVersion(_)
+            | CommentsError::Mount(MountError::PathNotFound(_))
+            | CommentsError::Mount(MountError::CommentNotFound(_)) => {
+                (StatusCode::NOT_FOUND, self.to_string()).into_response()
+            }
+            CommentsError::NotAuthor(_) => {
+                (StatusCode::FORBIDDEN, self.to_string()).into_response()
+            }
+            CommentsError::Mount(MountError::HeadMoved(head)) => head_moved(&head).into_response(),
+            CommentsError::Mount(e @ MountError::VersionUnavailable(_)) => {
+                ApiError::from(e).into_response()
+            }
+            CommentsError::BucketLog(_) | CommentsError::Mount(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Unexpected error".to_string(),
+            )
+                .into_response(),
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::prelude::{Link, MountError};
+
+use super::super::parse_version;
+use super::{CommentInfo, CommentsError};
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Remove a comment this peer wrote
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct RemoveCommentRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// ID of the comment
+    #[arg(long)]
+    pub comment_id: Uuid,
+
+    /// Fail with 409 unless the bucket head is still this version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub expected_version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoveCommentResponse {
+    pub comment: CommentInfo,
+    /// The bucket version the removal was saved as
+    pub link: Link,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<RemoveCommentRequest>,
+) -> Result<impl IntoResponse, CommentsError> {
+    let expected_version =
+        parse_version(req.expected_version.as_deref()).map_err(CommentsError::InvalidVersion)?;
+    let mut mount = state
+        .peer()
+        .mount_expecting(req.bucket_id, expected_version)
+        .await?;
+
+    // Only the author of a comment removes it
+    let author = mount
+        .comments()
+        .await
+        .get(&req.comment_id)
+        .map(|comment| comment.author)
+        .ok_or(MountError::CommentNotFound(req.comment_id))?;
+    if author != state.peer().secret().public() {
+        return Err(CommentsError::NotAuthor(req.comment_id));
+    }
+
+    let comment = mount.remove_comment(&req.comment_id).await?;
+    let link = state.peer().save_mount(&mount, false).await?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "bucket.comments.remove")
+                .bucket(req.bucket_id)
+                .path(comment.path.clone())
+                .version(&link),
+        )
+        .await;
+
+    Ok((
+        http::StatusCode::OK,
+        Json(RemoveCommentResponse {
+            comment: CommentInfo::from(&comment),
+            link,
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for RemoveCommentRequest {
+    type Response = RemoveCommentResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/comments/remove").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -6,8 +6,10 @@ use time::OffsetDateTime;
 use uuid::Uuid;
 
 use common::bucket_log::BucketLogProvider;
+use common::mount::Compression;
 use common::prelude::{Mount, MountError};
 
+use crate::audit::{Actor, AuditEvent};
 use crate::http_server::api::client::ApiRequest;
 use crate::ServiceState;
 
@@ -16,6 +18,16 @@ pub struct CreateRequest {
     /// Name of the bucket to create
     #[arg(long)]
     pub name: String,
+
+    /// Compress file contents (zstd) before encryption
+    #[arg(long)]
+    #[serde(default)]
+    pub compress: bool,
+
+    /// Record the dimensions, capture date and duration of photos and videos
+    #[arg(long)]
+    #[serde(default)]
+    pub extract_media: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,7 +60,7 @@ pub async fn handler(
     let blobs = state.node().blobs();
 
     tracing::info!("CREATE BUCKET: Initializing mount for bucket {}", id);
-    let mount = Mount::init(id, req.name.clone(), owner, blobs)
+    let mut mount = Mount::init(id, req.name.clone(), owner, blobs)
         .await
         .map_err(|e| {
             tracing::error!("CREATE BUCKET: Failed to initialize mount: {}", e);
@@ -90,6 +102,28 @@ pub async fn handler(
         id
     );
 
+    // Record the compression and media settings in the first version after genesis
+    if req.compress {
+        mount.set_compression(Some(Compression::Zstd)).await;
+        tracing::info!("CREATE BUCKET: Enabled compression for bucket {}", id);
+    }
+    if req.extract_media {
+        mount.set_extract_media(true).await;
+        tracing::info!("CREATE BUCKET: Enabled media metadata for bucket {}", id);
+    }
+    let link = if req.compress || req.extract_media {
+        state.peer().save_mount(&mount, false).await?
+    } else {
+        genesis_link
+    };
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "bucket.create")
+                .bucket(id)
+                .version(&link),
+        )
+        .await;
+
     tracing::info!(
         "CREATE BUCKET: Bucket '{}' created successfully with ID {}",
         req.name,
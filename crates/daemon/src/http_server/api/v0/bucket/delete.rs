@@ -6,6 +6,8 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use uuid::Uuid;
 
+use super::{head_moved, parse_version};
+use crate::audit::{Actor, AuditEvent};
 use crate::http_server::api::client::ApiRequest;
 use crate::ServiceState;
 
@@ -15,6 +17,10 @@ pub struct DeleteRequest {
     pub bucket_id: Uuid,
     /// Absolute path to the file or directory to delete
     pub path: String,
+
+    /// Fail with 409 unless the bucket head is still this version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_version: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,7 +49,12 @@ pub async fn handler(
     }
 
     // Load mount at current head
-    let mut mount = state.peer().mount(req.bucket_id).await?;
+    let expected_version =
+        parse_version(req.expected_version.as_deref()).map_err(DeleteError::InvalidVersion)?;
+    let mut mount = state
+        .peer()
+        .mount_expecting(req.bucket_id, expected_version)
+        .await?;
     tracing::info!("DELETE API: Loaded mount for bucket {}", req.bucket_id);
 
     // Check if path exists before attempting delete
@@ -61,6 +72,14 @@ pub async fn handler(
 
     // Save mount and update log
     let new_bucket_link = state.peer().save_mount(&mount, false).await?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "bucket.delete")
+                .bucket(req.bucket_id)
+                .path(req.path.clone())
+                .version(&new_bucket_link),
+        )
+        .await;
 
     tracing::info!(
         "DELETE API: Deleted {} from bucket {}, new link: {}",
@@ -81,6 +100,8 @@ pub async fn handler(
 
 #[derive(Debug, thiserror::Error)]
 pub enum DeleteError {
+    #[error("Invalid version: {0}")]
+    InvalidVersion(String),
     #[error("Invalid path: {0}")]
     InvalidPath(String),
     #[error("Path not found: {0}")]
@@ -102,6 +123,12 @@ impl IntoResponse for DeleteError {
                 format!("Path not found: {}", msg),
             )
                 .into_response(),
+            DeleteError::InvalidVersion(msg) => (
+                http::StatusCode::BAD_REQUEST,
+                format!("Invalid version: {}", msg),
+            )
+                .into_response(),
+            DeleteError::Mount(MountError::HeadMoved(head)) => head_moved(&head).into_response(),
             DeleteError::Mount(_) => (
                 http::StatusCode::INTERNAL_SERVER_ERROR,
                 "Unexpected error".to_string(),
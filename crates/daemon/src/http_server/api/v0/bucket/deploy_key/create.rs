@@ -0,0 +1,91 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{DeployKeyApiError, DeployKeyInfo};
+use crate::audit::{Actor, AuditEvent};
+use crate::deploy::normalize_path;
+use crate::http_server::api::client::ApiRequest;
+use crate::webhooks::generate_secret;
+use crate::ServiceState;
+
+/// Create a key CI can sign writes to a bucket with
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct DeployKeyCreateRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Only allow writes under this path (default: the whole bucket)
+    #[arg(long, default_value = "/")]
+    #[serde(default = "default_prefix")]
+    pub path_prefix: String,
+
+    /// Label to tell keys apart, e.g. the pipeline using it
+    #[arg(long)]
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+fn default_prefix() -> String {
+    "/".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeployKeyCreateResponse {
+    #[serde(flatten)]
+    pub key: DeployKeyInfo,
+    /// Key requests are signed with; only returned here
+    pub secret: String,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<DeployKeyCreateRequest>,
+) -> Result<impl IntoResponse, DeployKeyApiError> {
+    let path_prefix = normalize_path(&req.path_prefix)
+        .ok_or_else(|| DeployKeyApiError::InvalidPrefix(req.path_prefix.clone()))?;
+    if state
+        .database()
+        .get_bucket_info(&req.bucket_id)
+        .await?
+        .is_none()
+    {
+        return Err(DeployKeyApiError::BucketNotFound(req.bucket_id));
+    }
+
+    let secret = generate_secret();
+    let key = state
+        .database()
+        .create_deploy_key(&req.bucket_id, &path_prefix, req.name.as_deref(), &secret)
+        .await?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "deploy_key.create")
+                .bucket(key.bucket_id)
+                .path(key.path_prefix.clone())
+                .detail(key.id.to_string()),
+        )
+        .await;
+
+    Ok((
+        http::StatusCode::OK,
+        Json(DeployKeyCreateResponse {
+            key: DeployKeyInfo::from(key),
+            secret,
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for DeployKeyCreateRequest {
+    type Response = DeployKeyCreateResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/deploy-key/create").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,48 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{DeployKeyApiError, DeployKeyInfo};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// List deploy keys and when they were last used
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct DeployKeyListRequest {
+    /// Only keys of this bucket
+    #[arg(long)]
+    #[serde(default)]
+    pub bucket_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeployKeyListResponse {
+    pub keys: Vec<DeployKeyInfo>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<DeployKeyListRequest>,
+) -> Result<impl IntoResponse, DeployKeyApiError> {
+    let keys = state
+        .database()
+        .list_deploy_keys(req.bucket_id.as_ref())
+        .await?
+        .into_iter()
+        .map(DeployKeyInfo::from)
+        .collect();
+
+    Ok((http::StatusCode::OK, Json(DeployKeyListResponse { keys })).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for DeployKeyListRequest {
+    type Response = DeployKeyListResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/deploy-key/list").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
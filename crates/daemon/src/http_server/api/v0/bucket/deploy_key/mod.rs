@@ -0,0 +1,83 @@
+//! Deploy key endpoints (see [`crate::deploy`])
+//!
+//! These manage keys on the daemon's API; the keys themselves are only used
+//! against the gateway's `/deploy` routes.
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::deploy::DeployKey;
+use crate::http_server::error::ApiError;
+use crate::ServiceState;
+
+pub mod create;
+pub mod list;
+pub mod revoke;
+
+pub use create::{DeployKeyCreateRequest, DeployKeyCreateResponse};
+pub use list::{DeployKeyListRequest, DeployKeyListResponse};
+pub use revoke::DeployKeyRevokeRequest;
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/create", post(create::handler))
+        .route("/list", post(list::handler))
+        .route("/revoke", post(revoke::handler))
+        .with_state(state)
+}
+
+/// A deploy key, without its secret
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeployKeyInfo {
+    pub key_id: Uuid,
+    pub bucket_id: Uuid,
+    pub path_prefix: String,
+    pub name: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_used_at: Option<OffsetDateTime>,
+}
+
+impl From<DeployKey> for DeployKeyInfo {
+    fn from(key: DeployKey) -> Self {
+        Self {
+            key_id: key.id,
+            bucket_id: key.bucket_id,
+            path_prefix: key.path_prefix,
+            name: key.name,
+            created_at: key.created_at,
+            last_used_at: key.last_used_at,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DeployKeyApiError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Bucket not found: {0}")]
+    BucketNotFound(Uuid),
+    #[error("Invalid path prefix: {0}")]
+    InvalidPrefix(String),
+    #[error("Unknown deploy key: {0}")]
+    UnknownKey(Uuid),
+}
+
+impl IntoResponse for DeployKeyApiError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            DeployKeyApiError::InvalidPrefix(_) => StatusCode::BAD_REQUEST,
+            DeployKeyApiError::BucketNotFound(_) | DeployKeyApiError::UnknownKey(_) => {
+                StatusCode::NOT_FOUND
+            }
+            DeployKeyApiError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
@@ -0,0 +1,50 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{DeployKeyApiError, DeployKeyInfo};
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Revoke a deploy key. Requests signed with it are refused from then on.
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct DeployKeyRevokeRequest {
+    /// Deploy key ID
+    #[arg(long)]
+    pub key_id: Uuid,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<DeployKeyRevokeRequest>,
+) -> Result<impl IntoResponse, DeployKeyApiError> {
+    let key = state
+        .database()
+        .get_deploy_key(&req.key_id)
+        .await?
+        .ok_or(DeployKeyApiError::UnknownKey(req.key_id))?;
+    state.database().delete_deploy_key(&key.id).await?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "deploy_key.revoke")
+                .bucket(key.bucket_id)
+                .path(key.path_prefix.clone())
+                .detail(key.id.to_string()),
+        )
+        .await;
+
+    Ok((http::StatusCode::OK, Json(DeployKeyInfo::from(key))).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for DeployKeyRevokeRequest {
+    type Response = DeployKeyInfo;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/deploy-key/revoke").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::HashSet;
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::DropLinkApiError;
+use crate::audit::{Actor, AuditEvent};
+use crate::drop_box::unique_file_name;
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Add an upload waiting for review to its link's directory
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct DropApproveRequest {
+    /// Upload ID
+    #[arg(long)]
+    pub upload_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DropApproveResponse {
+    pub upload_id: Uuid,
+    pub bucket_id: Uuid,
+    /// Where the file was added; renamed if the upload's name was taken
+    pub path: String,
+    pub new_bucket_link: String,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<DropApproveRequest>,
+) -> Result<impl IntoResponse, DropLinkApiError> {
+    let (upload, content) = state
+        .database()
+        .get_drop_upload(&req.upload_id)
+        .await?
+        .ok_or(DropLinkApiError::UnknownUpload(req.upload_id))?;
+    let link = state
+        .database()
+        .get_drop_link(&upload.link_id)
+        .await?
+        .ok_or(DropLinkApiError::UnknownLink(upload.link_id))?;
+
+    let mut mount = state.peer().mount(link.bucket_id).await?;
+    // Uploads never replace what's already in the bucket
+    let taken: HashSet<String> = mount
+        .ls(&PathBuf::from(&link.directory))
+        .await
+        .unwrap_or_default()
+        .keys()
+        .filter_map(|path| path.file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .collect();
+    let file_name = unique_file_name(&upload.file_name, |name| taken.contains(name));
+    let path = link.path_of(&file_name);
+    mount
+        .add(&PathBuf::from(&path), Cursor::new(content))
+        .await?;
+    let bucket_link = state.peer().save_mount(&mount, false).await?;
+    state.database().delete_drop_upload(&upload.id).await?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "drop.approve")
+                .bucket(link.bucket_id)
+                .path(path.clone())
+                .version(&bucket_link)
+                .detail(upload.id.to_string()),
+        )
+        .await;
+
+    Ok((
+        http::StatusCode::OK,
+        Json(DropApproveResponse {
+            upload_id: upload.id,
+            bucket_id: link.bucket_id,
+            path,
+            new_bucket_link: bucket_link.hash().to_string(),
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for DropApproveRequest {
+    type Response = DropApproveResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/drop-link/approve").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,176 @@
+use std::time::Duration;
+
+use axum::extract::{Extension, Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use super::{DropLinkApiError, DropLinkInfo};
+use crate::audit::{Actor, AuditEvent};
+use crate::deploy::normalize_path;
+use crate::drop_box::{
+    is_valid_type_pattern, parse_allowed_types, CaptchaConfig, NewDropLink, DEFAULT_MAX_SIZE,
+    MAX_SIZE_LIMIT,
+};
+use crate::http_server::api::client::ApiRequest;
+use crate::http_server::Config;
+use crate::webhooks::generate_secret;
+use crate::ServiceState;
+
+/// Create a link anyone can upload files into a directory of a bucket with,
+/// for review before they're added
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct DropLinkCreateRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Directory approved uploads are added to
+    #[arg(long, default_value = "/")]
+    #[serde(default = "default_directory")]
+    pub directory: String,
+
+    /// Label to tell links apart, e.g. who it was sent to
+    #[arg(long)]
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// Largest file accepted, in bytes (default 25 MiB, at most 100 MiB)
+    #[arg(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_size: Option<u64>,
+
+    /// Comma separated MIME types accepted, e.g. `image/*,application/pdf`
+    /// (default: any)
+    #[arg(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_types: Option<String>,
+
+    /// Siteverify endpoint of the captcha uploads must pass, e.g.
+    /// https://hcaptcha.com/siteverify
+    #[arg(long, requires = "captcha_secret")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub captcha_verify_url: Option<String>,
+
+    /// Secret key of the captcha site
+    #[arg(long, requires = "captcha_verify_url")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub captcha_secret: Option<String>,
+
+    /// Seconds until the link stops accepting uploads (default: never)
+    #[arg(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_in_secs: Option<u64>,
+}
+
+fn default_directory() -> String {
+    "/".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DropLinkCreateResponse {
+    #[serde(flatten)]
+    pub link: DropLinkInfo,
+    /// Full upload URL, when the daemon knows its gateway's public URL
+    pub url: Option<String>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Extension(config): Extension<Config>,
+    Json(req): Json<DropLinkCreateRequest>,
+) -> Result<impl IntoResponse, DropLinkApiError> {
+    let directory = normalize_path(&req.directory).ok_or_else(|| {
+        DropLinkApiError::InvalidRequest(format!("invalid directory {}", req.directory))
+    })?;
+    let max_size = req.max_size.unwrap_or(DEFAULT_MAX_SIZE);
+    if max_size == 0 || max_size > MAX_SIZE_LIMIT {
+        return Err(DropLinkApiError::InvalidRequest(format!(
+            "max size must be 1 to {} bytes",
+            MAX_SIZE_LIMIT
+        )));
+    }
+    let allowed_types = req
+        .allowed_types
+        .as_deref()
+        .map(parse_allowed_types)
+        .unwrap_or_default();
+    if let Some(invalid) = allowed_types.iter().find(|t| !is_valid_type_pattern(t)) {
+        return Err(DropLinkApiError::InvalidRequest(format!(
+            "invalid type {}",
+            invalid
+        )));
+    }
+    let captcha = match (req.captcha_verify_url, req.captcha_secret) {
+        (Some(verify_url), Some(secret)) => {
+            Url::parse(&verify_url).map_err(|e| {
+                DropLinkApiError::InvalidRequest(format!("invalid captcha URL: {}", e))
+            })?;
+            Some(CaptchaConfig { verify_url, secret })
+        }
+        (None, None) => None,
+        _ => {
+            return Err(DropLinkApiError::InvalidRequest(
+                "a captcha needs both a verify URL and a secret".to_string(),
+            ))
+        }
+    };
+    let expires_at = req
+        .expires_in_secs
+        .map(|secs| OffsetDateTime::now_utc() + Duration::from_secs(secs));
+    if state
+        .database()
+        .get_bucket_info(&req.bucket_id)
+        .await?
+        .is_none()
+    {
+        return Err(DropLinkApiError::BucketNotFound(req.bucket_id));
+    }
+
+    let link = state
+        .database()
+        .create_drop_link(
+            NewDropLink {
+                bucket_id: &req.bucket_id,
+                directory: &directory,
+                name: req.name.as_deref(),
+                max_size,
+                allowed_types: &allowed_types,
+                captcha: captcha.as_ref(),
+                expires_at,
+            },
+            &generate_secret(),
+        )
+        .await?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "drop_link.create")
+                .bucket(link.bucket_id)
+                .path(link.directory.clone())
+                .detail(link.id.to_string()),
+        )
+        .await;
+
+    let link = DropLinkInfo::from(link);
+    let url = config
+        .gateway_url
+        .as_ref()
+        .map(|base| format!("{}{}", base.trim_end_matches('/'), link.url_path));
+    Ok((
+        http::StatusCode::OK,
+        Json(DropLinkCreateResponse { link, url }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for DropLinkCreateRequest {
+    type Response = DropLinkCreateResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/drop-link/create").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,48 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{DropLinkApiError, DropLinkInfo};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// List drop links
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct DropLinkListRequest {
+    /// Only links of this bucket
+    #[arg(long)]
+    #[serde(default)]
+    pub bucket_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DropLinkListResponse {
+    pub links: Vec<DropLinkInfo>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<DropLinkListRequest>,
+) -> Result<impl IntoResponse, DropLinkApiError> {
+    let links = state
+        .database()
+        .list_drop_links(req.bucket_id.as_ref())
+        .await?
+        .into_iter()
+        .map(DropLinkInfo::from)
+        .collect();
+
+    Ok((http::StatusCode::OK, Json(DropLinkListResponse { links })).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for DropLinkListRequest {
+    type Response = DropLinkListResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/drop-link/list").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
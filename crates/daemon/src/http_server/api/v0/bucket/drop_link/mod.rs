@@ -0,0 +1,138 @@
+//! Drop link endpoints (see [`crate::drop_box`])
+//!
+//! These manage links and their moderation queue on the daemon's API; the
+//! links themselves are only used against the gateway's `/drop` route.
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use common::prelude::MountError;
+
+use crate::drop_box::{DropLink, DropUpload};
+use crate::http_server::error::ApiError;
+use crate::ServiceState;
+
+pub mod approve;
+pub mod create;
+pub mod list;
+pub mod pending;
+pub mod reject;
+pub mod revoke;
+
+pub use approve::{DropApproveRequest, DropApproveResponse};
+pub use create::{DropLinkCreateRequest, DropLinkCreateResponse};
+pub use list::{DropLinkListRequest, DropLinkListResponse};
+pub use pending::{DropPendingRequest, DropPendingResponse};
+pub use reject::DropRejectRequest;
+pub use revoke::DropLinkRevokeRequest;
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/create", post(create::handler))
+        .route("/list", post(list::handler))
+        .route("/revoke", post(revoke::handler))
+        .route("/pending", post(pending::handler))
+        .route("/approve", post(approve::handler))
+        .route("/reject", post(reject::handler))
+        .with_state(state)
+}
+
+/// A drop link, without its captcha secret
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DropLinkInfo {
+    pub link_id: Uuid,
+    pub bucket_id: Uuid,
+    pub directory: String,
+    pub name: Option<String>,
+    /// Path of the link's upload URL on the gateway
+    pub url_path: String,
+    pub max_size: u64,
+    /// Accepted MIME types or `type/*` patterns; empty accepts any type
+    pub allowed_types: Vec<String>,
+    pub captcha: bool,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub expires_at: Option<OffsetDateTime>,
+}
+
+impl From<DropLink> for DropLinkInfo {
+    fn from(link: DropLink) -> Self {
+        Self {
+            link_id: link.id,
+            bucket_id: link.bucket_id,
+            directory: link.directory,
+            name: link.name,
+            url_path: format!("/drop/{}", link.token),
+            max_size: link.max_size,
+            allowed_types: link.allowed_types,
+            captcha: link.captcha.is_some(),
+            created_at: link.created_at,
+            expires_at: link.expires_at,
+        }
+    }
+}
+
+/// A file waiting for review
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DropUploadInfo {
+    pub upload_id: Uuid,
+    pub link_id: Uuid,
+    pub file_name: String,
+    pub mime_type: String,
+    pub size: u64,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+impl From<DropUpload> for DropUploadInfo {
+    fn from(upload: DropUpload) -> Self {
+        Self {
+            upload_id: upload.id,
+            link_id: upload.link_id,
+            file_name: upload.file_name,
+            mime_type: upload.mime_type,
+            size: upload.size,
+            created_at: upload.created_at,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DropLinkApiError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Mount error: {0}")]
+    Mount(#[from] MountError),
+    #[error("Bucket not found: {0}")]
+    BucketNotFound(Uuid),
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+    #[error("Unknown drop link: {0}")]
+    UnknownLink(Uuid),
+    #[error("Unknown upload: {0}")]
+    UnknownUpload(Uuid),
+}
+
+impl IntoResponse for DropLinkApiError {
+    fn into_response(self) -> Response {
+        if let DropLinkApiError::Mount(e @ MountError::VersionUnavailable(_)) = self {
+            return ApiError::from(e).into_response();
+        }
+        let status = match self {
+            DropLinkApiError::InvalidRequest(_) => StatusCode::BAD_REQUEST,
+            DropLinkApiError::BucketNotFound(_)
+            | DropLinkApiError::UnknownLink(_)
+            | DropLinkApiError::UnknownUpload(_) => StatusCode::NOT_FOUND,
+            DropLinkApiError::Database(_) | DropLinkApiError::Mount(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
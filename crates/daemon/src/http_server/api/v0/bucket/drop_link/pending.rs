@@ -0,0 +1,48 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{DropLinkApiError, DropUploadInfo};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// List uploads waiting for review
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct DropPendingRequest {
+    /// Only uploads into this bucket
+    #[arg(long)]
+    #[serde(default)]
+    pub bucket_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DropPendingResponse {
+    pub uploads: Vec<DropUploadInfo>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<DropPendingRequest>,
+) -> Result<impl IntoResponse, DropLinkApiError> {
+    let uploads = state
+        .database()
+        .list_drop_uploads(req.bucket_id.as_ref())
+        .await?
+        .into_iter()
+        .map(DropUploadInfo::from)
+        .collect();
+
+    Ok((http::StatusCode::OK, Json(DropPendingResponse { uploads })).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for DropPendingRequest {
+    type Response = DropPendingResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/drop-link/pending").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,55 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{DropLinkApiError, DropUploadInfo};
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Discard an upload waiting for review
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct DropRejectRequest {
+    /// Upload ID
+    #[arg(long)]
+    pub upload_id: Uuid,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<DropRejectRequest>,
+) -> Result<impl IntoResponse, DropLinkApiError> {
+    let (upload, _) = state
+        .database()
+        .get_drop_upload(&req.upload_id)
+        .await?
+        .ok_or(DropLinkApiError::UnknownUpload(req.upload_id))?;
+    let link = state
+        .database()
+        .get_drop_link(&upload.link_id)
+        .await?
+        .ok_or(DropLinkApiError::UnknownLink(upload.link_id))?;
+    state.database().delete_drop_upload(&upload.id).await?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "drop.reject")
+                .bucket(link.bucket_id)
+                .path(link.path_of(&upload.file_name))
+                .detail(upload.id.to_string()),
+        )
+        .await;
+
+    Ok((http::StatusCode::OK, Json(DropUploadInfo::from(upload))).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for DropRejectRequest {
+    type Response = DropUploadInfo;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/drop-link/reject").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
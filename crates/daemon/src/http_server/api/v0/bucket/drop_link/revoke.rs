@@ -0,0 +1,51 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{DropLinkApiError, DropLinkInfo};
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Revoke a drop link. Its URL stops accepting uploads and files still
+/// waiting for review are discarded.
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct DropLinkRevokeRequest {
+    /// Drop link ID
+    #[arg(long)]
+    pub link_id: Uuid,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<DropLinkRevokeRequest>,
+) -> Result<impl IntoResponse, DropLinkApiError> {
+    let link = state
+        .database()
+        .get_drop_link(&req.link_id)
+        .await?
+        .ok_or(DropLinkApiError::UnknownLink(req.link_id))?;
+    state.database().delete_drop_link(&link.id).await?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "drop_link.revoke")
+                .bucket(link.bucket_id)
+                .path(link.directory.clone())
+                .detail(link.id.to_string()),
+        )
+        .await;
+
+    Ok((http::StatusCode::OK, Json(DropLinkInfo::from(link))).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for DropLinkRevokeRequest {
+    type Response = DropLinkInfo;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/drop-link/revoke").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
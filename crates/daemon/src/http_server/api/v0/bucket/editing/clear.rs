@@ -0,0 +1,54 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::EditingApiError;
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Clear this daemon's mark on a path it stopped editing
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct EditingClearRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Path that was being edited
+    #[arg(long)]
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditingClearResponse {
+    pub bucket_id: Uuid,
+    /// Whether this daemon had marked the path
+    pub cleared: bool,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<EditingClearRequest>,
+) -> Result<impl IntoResponse, EditingApiError> {
+    let cleared = state.peer().clear_editing(req.bucket_id, &req.path).await?;
+
+    Ok((
+        http::StatusCode::OK,
+        Json(EditingClearResponse {
+            bucket_id: req.bucket_id,
+            cleared,
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for EditingClearRequest {
+    type Response = EditingClearResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/editing/clear").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
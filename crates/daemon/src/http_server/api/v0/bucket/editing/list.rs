@@ -0,0 +1,56 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{EditingApiError, EditingInfo};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// List the paths of a bucket this daemon knows are being edited, by itself
+/// and by other owners
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct EditingListRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditingListResponse {
+    pub bucket_id: Uuid,
+    pub editing: Vec<EditingInfo>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<EditingListRequest>,
+) -> Result<impl IntoResponse, EditingApiError> {
+    let editing = state
+        .peer()
+        .editing()
+        .active(&req.bucket_id)
+        .iter()
+        .map(EditingInfo::from)
+        .collect();
+
+    Ok((
+        http::StatusCode::OK,
+        Json(EditingListResponse {
+            bucket_id: req.bucket_id,
+            editing,
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for EditingListRequest {
+    type Response = EditingListResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/editing/list").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::peer::editing::DEFAULT_EDITING_TTL;
+
+use super::{EditingApiError, EditingInfo};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Mark a path of a bucket as being edited by this daemon, or renew the mark
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct EditingMarkRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Path being edited
+    #[arg(long)]
+    pub path: String,
+
+    /// Seconds until the mark expires unless renewed (at most 600)
+    #[arg(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl_secs: Option<u64>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<EditingMarkRequest>,
+) -> Result<impl IntoResponse, EditingApiError> {
+    let ttl = req
+        .ttl_secs
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_EDITING_TTL);
+    let mark = state
+        .peer()
+        .mark_editing(req.bucket_id, &req.path, ttl)
+        .await?;
+
+    Ok((http::StatusCode::OK, Json(EditingInfo::from(&mark))).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for EditingMarkRequest {
+    type Response = EditingInfo;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/editing/mark").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,72 @@
+//! Editing indicator endpoints (see [`common::peer::editing`])
+//!
+//! Before editing a file, an owner marks it as being edited, renewing the
+//! mark while it edits and clearing it after. The other owners see the mark
+//! in `ls` and can hold off, rather than save conflicting edits. Marks are
+//! only a hint: nothing is refused because of one.
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::peer::{EditMark, EditingError};
+
+use crate::ServiceState;
+
+pub mod clear;
+pub mod list;
+pub mod mark;
+
+pub use clear::{EditingClearRequest, EditingClearResponse};
+pub use list::{EditingListRequest, EditingListResponse};
+pub use mark::EditingMarkRequest;
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/mark", post(mark::handler))
+        .route("/clear", post(clear::handler))
+        .route("/list", post(list::handler))
+        .with_state(state)
+}
+
+/// A path being edited, as reported by the API
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EditingInfo {
+    pub bucket_id: Uuid,
+    pub path: String,
+    /// Public key of the owner editing the path
+    pub editor: String,
+    /// Milliseconds until the mark expires unless renewed
+    pub remaining_ms: u64,
+}
+
+impl From<&EditMark> for EditingInfo {
+    fn from(mark: &EditMark) -> Self {
+        Self {
+            bucket_id: mark.bucket_id,
+            path: mark.path.to_string_lossy().to_string(),
+            editor: mark.editor.to_hex(),
+            remaining_ms: mark.remaining().as_millis() as u64,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum EditingApiError {
+    #[error(transparent)]
+    Editing(#[from] EditingError),
+}
+
+impl IntoResponse for EditingApiError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            EditingApiError::Editing(EditingError::InvalidPath(_)) => StatusCode::BAD_REQUEST,
+            EditingApiError::Editing(EditingError::NotOwner(_)) => StatusCode::FORBIDDEN,
+            EditingApiError::Editing(EditingError::Default(_)) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, self.to_string()).into_response()
+    }
+}
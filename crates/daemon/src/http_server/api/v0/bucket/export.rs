@@ -5,7 +5,8 @@ use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
 use common::bucket_log::BucketLogProvider;
-use common::mount::{MountError, NodeLink};
+use common::linked_data::Hash;
+use common::mount::{ChunkList, MountError, NodeLink};
 use common::prelude::Mount;
 
 use crate::clone_state::PathHashMap;
@@ -110,7 +111,7 @@ async fn export_mount_to_filesystem(
 
     for (path, node_link) in items {
         match node_link {
-            NodeLink::Data(link, secret, _) => {
+            NodeLink::Data(link, secret, data) => {
                 // This is a file - export it
                 let target_path = target_dir.join(&path);
 
@@ -119,23 +120,51 @@ async fn export_mount_to_filesystem(
                     std::fs::create_dir_all(parent)?;
                 }
 
-                // Get encrypted blob
-                let encrypted_data = blobs
-                    .get(&link.hash())
-                    .await
-                    .map_err(|e| ExportError::BlobStore(e.to_string()))?;
-
-                // Extract plaintext hash without full decryption (for hash map)
-                let plaintext_hash = secret
-                    .extract_plaintext_hash(&encrypted_data)
-                    .map_err(|e| ExportError::Decryption(e.to_string()))?;
-
-                // Decrypt and write file
-                let decrypted_data = secret
-                    .decrypt(&encrypted_data)
-                    .map_err(|e| ExportError::Decryption(e.to_string()))?;
-
-                std::fs::write(&target_path, decrypted_data)?;
+                // Chunked files are hashed after reassembly, as are compressed
+                // files below, so the hash matches what lands on disk
+                let (file_data, plaintext_hash) = if data.is_chunked() {
+                    let file_data = ChunkList::load(blobs, &link, &secret)
+                        .await
+                        .map_err(ExportError::Mount)?
+                        .read(blobs)
+                        .await
+                        .map_err(ExportError::Mount)?;
+                    let plaintext_hash = *Hash::new(&file_data).as_bytes();
+                    (file_data, plaintext_hash)
+                } else {
+                    // Get encrypted blob
+                    let encrypted_data = blobs
+                        .get(&link.hash())
+                        .await
+                        .map_err(|e| ExportError::BlobStore(e.to_string()))?;
+
+                    // Decrypt and write file
+                    let decrypted_data = secret
+                        .decrypt_offloaded(encrypted_data.clone())
+                        .await
+                        .map_err(|e| ExportError::Decryption(e.to_string()))?;
+
+                    // Extract plaintext hash without full decryption (for hash map).
+                    // Compressed files are hashed after decompression so the hash
+                    // matches what lands on disk.
+                    match data.compression() {
+                        Some(codec) => {
+                            let file_data = codec
+                                .decompress(&decrypted_data)
+                                .map_err(|e| ExportError::Decryption(e.to_string()))?;
+                            let plaintext_hash = *Hash::new(&file_data).as_bytes();
+                            (file_data, plaintext_hash)
+                        }
+                        None => {
+                            let plaintext_hash = secret
+                                .extract_plaintext_hash(&encrypted_data)
+                                .map_err(|e| ExportError::Decryption(e.to_string()))?;
+                            (decrypted_data, plaintext_hash)
+                        }
+                    }
+                };
+
+                std::fs::write(&target_path, file_data)?;
 
                 // Store mapping: path -> (blob_hash, plaintext_hash)
                 hash_map.insert(path.clone(), link.hash(), plaintext_hash);
@@ -151,6 +180,10 @@ async fn export_mount_to_filesystem(
 
                 tracing::debug!("EXPORT: Created directory {}", path.display());
             }
+            NodeLink::Bucket(_) => {
+                // References to other buckets aren't part of this one's export
+                tracing::debug!("EXPORT: Skipped bucket reference {}", path.display());
+            }
         }
     }
 
@@ -0,0 +1,101 @@
+use std::time::Duration;
+
+use axum::extract::{Json, State};
+use axum::response::{IntoResponse, Response};
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::audit::{Actor, AuditEvent};
+use crate::follow::{FollowError as TargetError, FollowTicket};
+use crate::http_server::api::client::ApiRequest;
+use crate::http_server::error::ApiError;
+use crate::ServiceState;
+
+/// How long to wait for a gateway to report its node id
+const GATEWAY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Follow a published bucket as a read-only mirror, without a share
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct FollowRequest {
+    /// Gateway URL of the bucket (https://host/gw/<bucket-id>) or a ticket
+    /// (<bucket-id>@<node-id>)
+    pub target: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FollowResponse {
+    pub bucket_id: Uuid,
+    /// Node the bucket is followed from
+    pub node_id: String,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<FollowRequest>,
+) -> Result<impl IntoResponse, FollowError> {
+    let client = Client::builder()
+        .timeout(GATEWAY_TIMEOUT)
+        .build()
+        .map_err(TargetError::from)?;
+    let ticket = FollowTicket::resolve(&req.target, &client).await?;
+    if ticket.node_id == state.peer().secret().public() {
+        return Err(FollowError::OwnNode);
+    }
+
+    tracing::info!(
+        "FOLLOW API: Following bucket {} from {}",
+        ticket.bucket_id,
+        ticket.node_id.to_hex()
+    );
+    state
+        .follow_bucket(ticket.bucket_id, ticket.node_id)
+        .await?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "bucket.follow")
+                .bucket(ticket.bucket_id)
+                .detail(ticket.node_id.to_hex()),
+        )
+        .await;
+
+    Ok((
+        http::StatusCode::OK,
+        Json(FollowResponse {
+            bucket_id: ticket.bucket_id,
+            node_id: ticket.node_id.to_hex(),
+        }),
+    )
+        .into_response())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum FollowError {
+    #[error("{0}")]
+    Target(#[from] TargetError),
+    #[error("Cannot follow a bucket from this node")]
+    OwnNode,
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+impl IntoResponse for FollowError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            FollowError::Target(TargetError::Gateway(_)) => http::StatusCode::BAD_GATEWAY,
+            FollowError::Target(_) | FollowError::OwnNode => http::StatusCode::BAD_REQUEST,
+            FollowError::Database(_) => http::StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for FollowRequest {
+    type Response = FollowResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/follow").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,129 @@
+use axum::extract::{Json, State};
+use axum::response::{IntoResponse, Response};
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::bucket_log::BucketLogProvider;
+use common::prelude::MountError;
+
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Fork a bucket this node can read into a new bucket owned by this node
+/// alone, seeded with its current contents
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct ForkRequest {
+    /// Bucket ID to fork
+    pub bucket_id: Uuid,
+
+    /// Name of the new bucket (defaults to "<name> (fork)")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForkResponse {
+    /// ID of the new bucket
+    pub bucket_id: Uuid,
+    pub name: String,
+    /// Bucket it was forked from
+    pub source_bucket_id: Uuid,
+    pub new_bucket_link: String,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<ForkRequest>,
+) -> Result<impl IntoResponse, ForkError> {
+    if req.name.as_deref().is_some_and(str::is_empty) {
+        return Err(ForkError::InvalidName("Name cannot be empty".into()));
+    }
+
+    let source = state.peer().mount_for_read(req.bucket_id).await?;
+    let name = match req.name {
+        Some(name) => name,
+        None => format!("{} (fork)", source.inner().await.manifest().name()),
+    };
+
+    let id = Uuid::new_v4();
+    tracing::info!(
+        "FORK BUCKET: Forking bucket {} into {} ('{}')",
+        req.bucket_id,
+        id,
+        name
+    );
+    let fork = source.fork(id, name.clone(), state.peer().secret()).await?;
+
+    // Genesis first, like a created bucket, then the version with the contents
+    let genesis_link = fork.link().await;
+    state
+        .peer()
+        .logs()
+        .append(id, name.clone(), genesis_link, None, 0, false)
+        .await
+        .map_err(|e| ForkError::SaveMount(format!("Failed to append genesis: {}", e)))?;
+    let new_bucket_link = state.peer().save_mount(&fork, false).await?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "bucket.fork")
+                .bucket(id)
+                .detail(req.bucket_id.to_string())
+                .version(&new_bucket_link),
+        )
+        .await;
+
+    Ok((
+        http::StatusCode::CREATED,
+        Json(ForkResponse {
+            bucket_id: id,
+            name,
+            source_bucket_id: req.bucket_id,
+            new_bucket_link: new_bucket_link.hash().to_string(),
+        }),
+    )
+        .into_response())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ForkError {
+    #[error("Invalid bucket name: {0}")]
+    InvalidName(String),
+    #[error("Failed to save mount: {0}")]
+    SaveMount(String),
+    #[error("Mount error: {0}")]
+    Mount(#[from] MountError),
+}
+
+impl IntoResponse for ForkError {
+    fn into_response(self) -> Response {
+        match self {
+            ForkError::InvalidName(_) => {
+                (http::StatusCode::BAD_REQUEST, self.to_string()).into_response()
+            }
+            ForkError::Mount(MountError::MirrorCannotMount | MountError::ShareNotFound) => {
+                (http::StatusCode::FORBIDDEN, self.to_string()).into_response()
+            }
+            ForkError::SaveMount(_) | ForkError::Mount(_) => {
+                tracing::error!("FORK BUCKET ERROR: {:?}", self);
+                (
+                    http::StatusCode::INTERNAL_SERVER_ERROR,
+                    "Unexpected error".to_string(),
+                )
+                    .into_response()
+            }
+        }
+    }
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for ForkRequest {
+    type Response = ForkResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/fork").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
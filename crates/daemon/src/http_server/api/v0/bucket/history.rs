@@ -0,0 +1,121 @@
+use axum::extract::{Json, State};
+use axum::response::{IntoResponse, Response};
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::bucket_log::BucketLogProvider;
+
+use crate::http_server::api::client::ApiRequest;
+use crate::http_server::error::ApiError;
+use crate::sync_policy::SyncPolicyError;
+use crate::ServiceState;
+
+/// Set how many versions of a bucket's content are kept locally.
+/// Deepening queues downloads of the versions that are now kept.
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct HistoryRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Keep the content of the N most recent versions (default: all versions)
+    #[arg(long)]
+    #[serde(default)]
+    pub depth: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryResponse {
+    pub bucket_id: Uuid,
+    /// Versions whose content is kept, None for all
+    pub depth: Option<u64>,
+    /// Number of older versions queued for download
+    pub queued_versions: u64,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<HistoryRequest>,
+) -> Result<impl IntoResponse, HistoryError> {
+    if !state
+        .peer()
+        .logs()
+        .exists(req.bucket_id)
+        .await
+        .map_err(|e| HistoryError::BucketLog(e.to_string()))?
+    {
+        return Err(HistoryError::BucketNotFound(req.bucket_id));
+    }
+
+    let mut policy = state.sync_policies().get(&req.bucket_id);
+    let previous = policy.history_depth;
+    policy.history_depth = req.depth;
+    policy.validate()?;
+    state.set_sync_policy(req.bucket_id, policy).await?;
+
+    // Download the versions kept now but not before, newest first
+    let head_height = state
+        .database()
+        .height(req.bucket_id)
+        .await
+        .map_err(|e| HistoryError::BucketLog(e.to_string()))?;
+    let oldest_kept = |depth: Option<u64>| {
+        depth
+            .map(|depth| (head_height + 1).saturating_sub(depth))
+            .unwrap_or(0)
+    };
+    let mut queued_versions = 0;
+    for height in (oldest_kept(req.depth)..oldest_kept(previous)).rev() {
+        state
+            .queue_pins_download(req.bucket_id, Some(height))
+            .await
+            .map_err(|e| HistoryError::Queue(e.to_string()))?;
+        queued_versions += 1;
+    }
+
+    Ok((
+        http::StatusCode::OK,
+        Json(HistoryResponse {
+            bucket_id: req.bucket_id,
+            depth: req.depth,
+            queued_versions,
+        }),
+    )
+        .into_response())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum HistoryError {
+    #[error("Bucket not found: {0}")]
+    BucketNotFound(Uuid),
+    #[error("Invalid depth: {0}")]
+    Policy(#[from] SyncPolicyError),
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Bucket log error: {0}")]
+    BucketLog(String),
+    #[error("Failed to queue download: {0}")]
+    Queue(String),
+}
+
+impl IntoResponse for HistoryError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            HistoryError::BucketNotFound(_) => http::StatusCode::NOT_FOUND,
+            HistoryError::Policy(_) => http::StatusCode::BAD_REQUEST,
+            _ => http::StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for HistoryRequest {
+    type Response = HistoryResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/history").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
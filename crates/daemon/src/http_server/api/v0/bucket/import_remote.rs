@@ -0,0 +1,93 @@
+use std::collections::BTreeMap;
+
+use axum::extract::{Json, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+
+use object_store::BlobStoreError;
+
+use crate::http_server::api::client::ApiRequest;
+use crate::http_server::error::ApiError;
+use crate::remote_import::{self, RemoteImportError, RemoteImportReport};
+use crate::ServiceState;
+
+/// Import the objects below a prefix of existing cloud storage into a
+/// bucket, creating it on the first import of the URL and copying only
+/// changed objects after that (see [`crate::remote_import`])
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportRemoteRequest {
+    /// Storage to import, e.g. `s3://bucket/prefix`, `gs://bucket/prefix`,
+    /// `az://container/prefix`, `webdav://host/path`, or `file:///path`
+    /// below one of the daemon's configured `import_dirs`
+    pub url: String,
+    /// Name of the bucket created on the first import (defaults to the last
+    /// segment of the URL)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Client options for the storage, such as credentials or an endpoint
+    #[serde(default)]
+    pub options: BTreeMap<String, String>,
+    /// Remove files whose objects are gone from the storage
+    #[serde(default)]
+    pub delete: bool,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<ImportRemoteRequest>,
+) -> Result<impl IntoResponse, ImportRemoteApiError> {
+    let report = remote_import::import(
+        state.peer(),
+        state.database(),
+        state.audit_log(),
+        state.import_dirs(),
+        &req.url,
+        &req.options,
+        req.name.as_deref(),
+        req.delete,
+    )
+    .await?;
+    let status = if report.created {
+        StatusCode::CREATED
+    } else {
+        StatusCode::OK
+    };
+    Ok((status, Json(report)).into_response())
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct ImportRemoteApiError(#[from] RemoteImportError);
+
+impl IntoResponse for ImportRemoteApiError {
+    fn into_response(self) -> Response {
+        let status = match &self.0 {
+            RemoteImportError::InvalidName
+            | RemoteImportError::Source(BlobStoreError::InvalidConfig(_)) => {
+                StatusCode::BAD_REQUEST
+            }
+            RemoteImportError::LocalNotAllowed(_) => StatusCode::FORBIDDEN,
+            // The storage couldn't be listed or read
+            RemoteImportError::Source(_) => StatusCode::BAD_GATEWAY,
+            RemoteImportError::Database(_)
+            | RemoteImportError::BucketLog(_)
+            | RemoteImportError::Mount(_) => {
+                tracing::error!("IMPORT REMOTE ERROR: {:?}", self);
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for ImportRemoteRequest {
+    type Response = RemoteImportReport;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/import-remote").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
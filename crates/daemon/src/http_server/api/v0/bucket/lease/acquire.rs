@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::peer::leases::DEFAULT_LEASE_TTL;
+
+use super::{LeaseApiError, LeaseInfo};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Acquire, or renew, an advisory write lease on a bucket. Fails with 409
+/// while another owner holds an overlapping lease.
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct LeaseAcquireRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Path prefix to lease; the whole bucket by default
+    #[arg(long, default_value = "/")]
+    #[serde(default = "root")]
+    pub prefix: String,
+
+    /// Seconds until the lease expires unless renewed (at most 600)
+    #[arg(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl_secs: Option<u64>,
+}
+
+fn root() -> String {
+    "/".to_string()
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<LeaseAcquireRequest>,
+) -> Result<impl IntoResponse, LeaseApiError> {
+    let ttl = req
+        .ttl_secs
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_LEASE_TTL);
+    let lease = state
+        .peer()
+        .acquire_lease(req.bucket_id, &req.prefix, ttl)
+        .await?;
+
+    Ok((http::StatusCode::OK, Json(LeaseInfo::from(&lease))).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for LeaseAcquireRequest {
+    type Response = LeaseInfo;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/lease/acquire").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
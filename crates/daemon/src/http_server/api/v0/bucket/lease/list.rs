@@ -0,0 +1,56 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{LeaseApiError, LeaseInfo};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// List the unexpired write leases this daemon knows of on a bucket, its own
+/// and those other owners acquired from it
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct LeaseListRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaseListResponse {
+    pub bucket_id: Uuid,
+    pub leases: Vec<LeaseInfo>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<LeaseListRequest>,
+) -> Result<impl IntoResponse, LeaseApiError> {
+    let leases = state
+        .peer()
+        .leases()
+        .active(&req.bucket_id)
+        .iter()
+        .map(LeaseInfo::from)
+        .collect();
+
+    Ok((
+        http::StatusCode::OK,
+        Json(LeaseListResponse {
+            bucket_id: req.bucket_id,
+            leases,
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for LeaseListRequest {
+    type Response = LeaseListResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/lease/list").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
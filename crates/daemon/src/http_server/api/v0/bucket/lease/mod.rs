@@ -0,0 +1,84 @@
+//! Advisory write lease endpoints (see [`common::peer::leases`])
+//!
+//! Cooperating writers acquire a lease on a bucket, or a path prefix of it,
+//! before writing and release it after, so their commits don't fork the
+//! bucket. Writes are never refused because of a lease.
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::peer::{Lease, LeaseError};
+
+use crate::http_server::error::ApiError;
+use crate::ServiceState;
+
+pub mod acquire;
+pub mod list;
+pub mod release;
+
+pub use acquire::LeaseAcquireRequest;
+pub use list::{LeaseListRequest, LeaseListResponse};
+pub use release::{LeaseReleaseRequest, LeaseReleaseResponse};
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/acquire", post(acquire::handler))
+        .route("/list", post(list::handler))
+        .route("/release", post(release::handler))
+        .with_state(state)
+}
+
+/// A lease as reported by the API
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LeaseInfo {
+    pub bucket_id: Uuid,
+    pub prefix: String,
+    /// Public key of the owner holding the lease
+    pub holder: String,
+    /// Milliseconds until the lease expires
+    pub remaining_ms: u64,
+}
+
+impl From<&Lease> for LeaseInfo {
+    fn from(lease: &Lease) -> Self {
+        Self {
+            bucket_id: lease.bucket_id,
+            prefix: lease.prefix.to_string_lossy().to_string(),
+            holder: lease.holder.to_hex(),
+            remaining_ms: lease.remaining().as_millis() as u64,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum LeaseApiError {
+    #[error(transparent)]
+    Lease(#[from] LeaseError),
+}
+
+impl IntoResponse for LeaseApiError {
+    fn into_response(self) -> Response {
+        match self {
+            // The 409 an acquire gets while someone else holds an
+            // overlapping lease names the holder in its details
+            LeaseApiError::Lease(LeaseError::Held(lease)) => {
+                ApiError::new(StatusCode::CONFLICT, "lease held")
+                    .with_details(LeaseInfo::from(&lease))
+                    .into_response()
+            }
+            LeaseApiError::Lease(e @ LeaseError::InvalidPrefix(_)) => {
+                ApiError::new(StatusCode::BAD_REQUEST, e.to_string()).into_response()
+            }
+            LeaseApiError::Lease(e @ LeaseError::NotOwner(_)) => {
+                ApiError::new(StatusCode::FORBIDDEN, e.to_string()).into_response()
+            }
+            LeaseApiError::Lease(e) => {
+                ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+            }
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::LeaseApiError;
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Release a write lease this daemon holds on a bucket
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct LeaseReleaseRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Path prefix the lease was acquired on
+    #[arg(long, default_value = "/")]
+    #[serde(default = "root")]
+    pub prefix: String,
+}
+
+fn root() -> String {
+    "/".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaseReleaseResponse {
+    pub bucket_id: Uuid,
+    /// Whether this daemon held the lease
+    pub released: bool,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<LeaseReleaseRequest>,
+) -> Result<impl IntoResponse, LeaseApiError> {
+    let released = state
+        .peer()
+        .release_lease(req.bucket_id, &req.prefix)
+        .await?;
+
+    Ok((
+        http::StatusCode::OK,
+        Json(LeaseReleaseResponse {
+            bucket_id: req.bucket_id,
+            released,
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for LeaseReleaseRequest {
+    type Response = LeaseReleaseResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/lease/release").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
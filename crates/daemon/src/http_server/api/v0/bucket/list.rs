@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use axum::extract::{Json, State};
 use axum::response::{IntoResponse, Response};
 use reqwest::{Client, RequestBuilder, Url};
@@ -8,6 +10,7 @@ use uuid::Uuid;
 use common::prelude::Link;
 
 use crate::http_server::api::client::ApiRequest;
+use crate::pagination::{PageError, PageRequest, SortKey, SortOrder, DEFAULT_PAGE_SIZE};
 use crate::ServiceState;
 
 #[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
@@ -17,15 +20,59 @@ pub struct ListRequest {
     #[arg(long)]
     pub prefix: Option<String>,
 
-    /// Optional limit
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Only list buckets whose name contains this, ignoring case
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub filter: Option<String>,
+
+    /// Sort by creation time (newest first by default) or name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long, value_enum)]
+    pub sort: Option<BucketSort>,
+
+    /// Page of the listing, 100 buckets by default
+    #[serde(flatten)]
+    #[command(flatten)]
+    pub page: PageRequest,
+
+    /// Only list buckets in this workspace (name or ID)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     #[arg(long)]
-    pub limit: Option<u32>,
+    pub workspace: Option<String>,
+
+    /// Only list starred buckets
+    #[serde(default)]
+    #[arg(long)]
+    pub starred: bool,
+}
+
+/// Field buckets are listed by
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum BucketSort {
+    #[default]
+    Created,
+    Name,
+}
+
+impl BucketSort {
+    fn as_str(&self) -> &'static str {
+        match self {
+            BucketSort::Created => "created",
+            BucketSort::Name => "name",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ListResponse {
     pub buckets: Vec<BucketInfo>,
+    /// Buckets matching the filters, across all pages
+    #[serde(default)]
+    pub total: u64,
+    /// Pass as `cursor` to list the next page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,27 +82,83 @@ pub struct BucketInfo {
     pub link: Link,
     #[serde(with = "time::serde::rfc3339")]
     pub created_at: OffsetDateTime,
+    /// Whether the bucket is starred, see `/api/v0/favorite`
+    #[serde(default)]
+    pub starred: bool,
 }
 
 pub async fn handler(
     State(state): State<ServiceState>,
     Json(req): Json<ListRequest>,
 ) -> Result<impl IntoResponse, ListError> {
-    // Query buckets from bucket_log
-    let buckets = state
+    let members = match &req.workspace {
+        Some(workspace) => {
+            let workspace = state
+                .database()
+                .get_workspace(workspace)
+                .await
+                .map_err(|e| ListError::Database(e.to_string()))?
+                .ok_or_else(|| ListError::UnknownWorkspace(workspace.clone()))?;
+            let ids = state
+                .database()
+                .workspace_bucket_ids(&workspace.id)
+                .await
+                .map_err(|e| ListError::Database(e.to_string()))?;
+            Some(ids.into_iter().collect::<HashSet<_>>())
+        }
+        None => None,
+    };
+
+    let starred = state
         .database()
-        .list_buckets(req.prefix, req.limit)
+        .starred_buckets()
         .await
         .map_err(|e| ListError::Database(e.to_string()))?;
 
+    let mut buckets = state
+        .database()
+        .list_all_buckets(req.prefix.as_deref())
+        .await
+        .map_err(|e| ListError::Database(e.to_string()))?;
+    if let Some(members) = members {
+        buckets.retain(|b| members.contains(&b.id));
+    }
+    if req.starred {
+        buckets.retain(|b| starred.contains(&b.id));
+    }
+    if let Some(filter) = &req.filter {
+        let filter = filter.to_lowercase();
+        buckets.retain(|b| b.name.to_lowercase().contains(&filter));
+    }
+
+    let sort = req.sort.unwrap_or_default();
+    let order = match sort {
+        BucketSort::Created => SortOrder::Desc,
+        BucketSort::Name => SortOrder::Asc,
+    };
+    let page = req.page.paginate(
+        buckets,
+        sort.as_str(),
+        order,
+        Some(DEFAULT_PAGE_SIZE),
+        |b| match sort {
+            BucketSort::Created => {
+                SortKey::number(b.created_at.unix_timestamp_nanos() as u64, b.id.to_string())
+            }
+            BucketSort::Name => SortKey::text(&b.name, b.id.to_string()),
+        },
+    )?;
+
     // Convert to response format
-    let bucket_infos = buckets
+    let bucket_infos = page
+        .items
         .into_iter()
         .map(|b| BucketInfo {
             bucket_id: b.id,
             name: b.name,
             link: b.link,
             created_at: b.created_at,
+            starred: starred.contains(&b.id),
         })
         .collect();
 
@@ -63,6 +166,8 @@ pub async fn handler(
         http::StatusCode::OK,
         Json(ListResponse {
             buckets: bucket_infos,
+            total: page.total,
+            next_cursor: page.next_cursor,
         }),
     )
         .into_response())
@@ -72,15 +177,31 @@ pub async fn handler(
 pub enum ListError {
     #[error("Database error: {0}")]
     Database(String),
+    #[error("Unknown workspace: {0}")]
+    UnknownWorkspace(String),
+    #[error("Invalid page: {0}")]
+    Page(#[from] PageError),
 }
 
 impl IntoResponse for ListError {
     fn into_response(self) -> Response {
-        (
-            http::StatusCode::INTERNAL_SERVER_ERROR,
-            "unknown server error",
-        )
-            .into_response()
+        match self {
+            ListError::UnknownWorkspace(name) => (
+                http::StatusCode::NOT_FOUND,
+                format!("Unknown workspace: {}", name),
+            )
+                .into_response(),
+            ListError::Page(e) => (
+                http::StatusCode::BAD_REQUEST,
+                format!("Invalid page: {}", e),
+            )
+                .into_response(),
+            ListError::Database(_) => (
+                http::StatusCode::INTERNAL_SERVER_ERROR,
+                "unknown server error",
+            )
+                .into_response(),
+        }
     }
 }
 
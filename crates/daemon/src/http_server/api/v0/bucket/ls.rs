@@ -1,12 +1,21 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
 use axum::extract::{Json, State};
 use axum::response::{IntoResponse, Response};
 use reqwest::{Client, RequestBuilder, Url};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use common::linked_data::Hash;
+use common::mount::{Attrs, MediaInfo, NodeLink};
+use common::peer::Resolved;
 use common::prelude::{Link, MountError};
 
+use super::editing::EditingInfo;
 use crate::http_server::api::client::ApiRequest;
+use crate::http_server::error::{is_archived, ApiError};
+use crate::pagination::{PageError, PageRequest, SortKey, SortOrder};
 use crate::ServiceState;
 
 #[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
@@ -24,11 +33,66 @@ pub struct LsRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[arg(long)]
     pub deep: Option<bool>,
+
+    /// Optional: list at no older a version than this one, e.g. the link a
+    /// write returned (defaults to the last version written through this node)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub min_version: Option<String>,
+
+    /// Only list starred files and directories under the path, at any depth
+    #[serde(default)]
+    #[arg(long)]
+    pub starred: bool,
+
+    /// Only list files and directories whose name contains this, ignoring
+    /// case
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub filter: Option<String>,
+
+    /// Sort by path (the default), name, size or MIME type
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long, value_enum)]
+    pub sort: Option<LsSort>,
+
+    /// Page of the listing; every item is listed without a limit or cursor
+    #[serde(flatten)]
+    #[command(flatten)]
+    pub page: PageRequest,
+}
+
+/// Field items are listed by
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum LsSort {
+    #[default]
+    Path,
+    Name,
+    Size,
+    Type,
+}
+
+impl LsSort {
+    fn as_str(&self) -> &'static str {
+        match self {
+            LsSort::Path => "path",
+            LsSort::Name => "name",
+            LsSort::Size => "size",
+            LsSort::Type => "type",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LsResponse {
     pub items: Vec<PathInfo>,
+    /// Items matching the filters, across all pages
+    #[serde(default)]
+    pub total: u64,
+    /// Pass as `cursor` to list the next page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,6 +102,29 @@ pub struct PathInfo {
     pub link: Link,
     pub is_dir: bool,
     pub mime_type: String,
+    /// Custom attributes set on the file or directory
+    #[serde(default, skip_serializing_if = "Attrs::is_empty")]
+    pub attrs: Attrs,
+    /// Dimensions, capture date and duration of photos and videos, for
+    /// buckets that extract them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub media: Option<MediaInfo>,
+    /// Size of the file's contents in bytes, for files written whole
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    /// BLAKE3 hash of the file's contents, for files written whole
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<Hash>,
+    /// Whether the path is starred, see `/api/v0/favorite`
+    #[serde(default)]
+    pub starred: bool,
+    /// Owners marked as editing the path, see `/api/v0/bucket/editing`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub editing: Vec<EditingInfo>,
+    /// Bucket referenced at the path, listed like a directory, see
+    /// `/api/v0/bucket/reference`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<Uuid>,
 }
 
 #[axum::debug_handler]
@@ -47,67 +134,196 @@ pub async fn handler(
 ) -> Result<impl IntoResponse, LsError> {
     let deep = req.deep.unwrap_or(false);
 
+    let min_version = req
+        .min_version
+        .as_deref()
+        .map(str::parse::<Hash>)
+        .transpose()
+        .map_err(|e| LsError::InvalidVersion(e.to_string()))?;
+
     // Load mount based on role (owners see HEAD, mirrors see latest_published)
-    let mount = state.peer().mount_for_read(req.bucket_id).await?;
+    let mount = state
+        .peer()
+        .mount_for_read_at_least(req.bucket_id, min_version)
+        .await?;
 
+    // Paths in referenced buckets are listed from those buckets
     let path_str = req.path.as_deref().unwrap_or("/");
-    let path_buf = std::path::PathBuf::from(path_str);
+    let Resolved {
+        mount,
+        path: path_buf,
+        mount_point,
+    } = state.peer().resolve(mount, Path::new(path_str)).await?;
+    let bucket_id = *mount.inner().await.manifest().id();
+    let starred = state.database().starred_paths(&bucket_id).await?;
+    let editing = state.peer().editing().active(&bucket_id);
 
-    // List items
-    let items = if deep {
+    // List items; starred items are listed by their path from the root, like
+    //  a shallow listing
+    let items = if req.starred {
+        let listed = Path::new("/").join(&path_buf);
+        let mut items = BTreeMap::new();
+        for starred_path in &starred {
+            let starred_path = Path::new(starred_path);
+            if starred_path == listed || !starred_path.starts_with(&listed) {
+                continue;
+            }
+            match mount.get(starred_path).await {
+                Ok(node_link) => {
+                    let relative = starred_path.strip_prefix("/").unwrap_or(starred_path);
+                    items.insert(relative.to_path_buf(), node_link);
+                }
+                // Starred paths removed since are left out
+                Err(MountError::PathNotFound(_)) => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        items
+    } else if deep {
         mount.ls_deep(&path_buf).await?
     } else {
         mount.ls(&path_buf).await?
     };
+    let deep = deep && !req.starred;
 
-    // Convert to response format
-    let path_infos = items
-        .into_iter()
-        .map(|(path, node_link)| {
-            // Mount returns relative paths, make them absolute
-            let absolute_path = std::path::Path::new("/").join(&path);
-            let path_str = absolute_path.to_string_lossy().to_string();
-            let name = path
-                .file_name()
-                .map(|n| n.to_string_lossy().to_string())
-                .unwrap_or_else(|| path.to_string_lossy().to_string());
-
-            let mime_type = if node_link.is_dir() {
-                "inode/directory".to_string()
-            } else {
-                node_link
-                    .data()
-                    .and_then(|data| data.mime())
-                    .map(|mime| mime.to_string())
-                    .unwrap_or_else(|| "application/octet-stream".to_string())
-            };
-
-            PathInfo {
-                path: path_str,
-                name,
-                link: node_link.link().clone(),
-                is_dir: node_link.is_dir(),
-                mime_type,
+    // Filter and page before looking up anything else about the items
+    let name_of = |path: &Path| {
+        path.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string_lossy().to_string())
+    };
+    let mut items = items.into_iter().collect::<Vec<_>>();
+    if let Some(filter) = &req.filter {
+        let filter = filter.to_lowercase();
+        items.retain(|(path, _)| name_of(path).to_lowercase().contains(&filter));
+    }
+    let sort = req.sort.unwrap_or_default();
+    let page = req.page.paginate(
+        items,
+        sort.as_str(),
+        SortOrder::Asc,
+        None,
+        |(path, node_link)| {
+            let id = path.to_string_lossy().to_string();
+            match sort {
+                LsSort::Path => SortKey::text(id.clone(), id),
+                LsSort::Name => SortKey::text(name_of(path), id),
+                LsSort::Size => SortKey::number(
+                    node_link.data().and_then(|data| data.size()).unwrap_or(0),
+                    id,
+                ),
+                LsSort::Type => SortKey::text(mime_type(node_link), id),
             }
-        })
-        .collect();
+        },
+    )?;
+
+    // Convert to response format
+    let mut path_infos = Vec::with_capacity(page.items.len());
+    for (path, node_link) in page.items {
+        // Mount returns relative paths, make them absolute
+        let absolute_path = Path::new("/").join(&path);
+        let path_str = if deep {
+            absolute_path.to_string_lossy().to_string()
+        } else {
+            mount_point.join(&path).to_string_lossy().to_string()
+        };
+        let name = name_of(&path);
+        let is_dir = node_link.is_dir() || node_link.is_bucket();
+        let mime_type = mime_type(&node_link);
+
+        let full_path = if deep {
+            Path::new("/").join(&path_buf).join(&path)
+        } else {
+            absolute_path.clone()
+        };
+
+        // Directories keep their attributes in their own node; deep listings
+        //  are relative to the listed path rather than the root
+        let attrs = match node_link.data() {
+            Some(data) => data.attrs().clone(),
+            None if node_link.is_bucket() => Attrs::new(),
+            None if deep => mount.attrs(&path_buf.join(&path)).await?,
+            None => mount.attrs(&absolute_path).await?,
+        };
 
-    Ok((http::StatusCode::OK, Json(LsResponse { items: path_infos })).into_response())
+        path_infos.push(PathInfo {
+            path: path_str,
+            name,
+            link: node_link.link().clone(),
+            is_dir,
+            mime_type,
+            attrs,
+            media: node_link.data().and_then(|data| data.media()),
+            size: node_link.data().and_then(|data| data.size()),
+            content_hash: node_link.data().and_then(|data| data.content_hash()),
+            starred: starred.contains(&*full_path.to_string_lossy()),
+            editing: editing
+                .iter()
+                .filter(|mark| mark.path == full_path)
+                .map(EditingInfo::from)
+                .collect(),
+            reference: node_link
+                .bucket_ref()
+                .map(|bucket_ref| *bucket_ref.bucket_id()),
+        });
+    }
+
+    Ok((
+        http::StatusCode::OK,
+        Json(LsResponse {
+            items: path_infos,
+            total: page.total,
+            next_cursor: page.next_cursor,
+        }),
+    )
+        .into_response())
+}
+
+/// MIME type listed for an item; directories and references are
+/// `inode/directory`
+fn mime_type(node_link: &NodeLink) -> String {
+    if node_link.is_dir() || node_link.is_bucket() {
+        return "inode/directory".to_string();
+    }
+    node_link
+        .data()
+        .and_then(|data| data.mime())
+        .map(|mime| mime.to_string())
+        .unwrap_or_else(|| "application/octet-stream".to_string())
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum LsError {
+    #[error("Invalid version: {0}")]
+    InvalidVersion(String),
     #[error("Mount error: {0}")]
     Mount(#[from] MountError),
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Invalid page: {0}")]
+    Page(#[from] PageError),
 }
 
 impl IntoResponse for LsError {
     fn into_response(self) -> Response {
-        (
-            http::StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Error: {}", self),
-        )
-            .into_response()
+        match self {
+            LsError::Mount(e @ MountError::VersionUnavailable(_)) => {
+                return ApiError::from(e).into_response()
+            }
+            LsError::Mount(e) if is_archived(&e) => return ApiError::from(e).into_response(),
+            _ => {}
+        }
+        let status = match self {
+            LsError::InvalidVersion(_)
+            | LsError::Page(_)
+            | LsError::Mount(MountError::ReferenceCycle(_))
+            | LsError::Mount(MountError::ReferencesTooDeep(_)) => http::StatusCode::BAD_REQUEST,
+            LsError::Mount(MountError::ShareNotFound | MountError::MirrorCannotMount) => {
+                http::StatusCode::FORBIDDEN
+            }
+            LsError::Mount(_) | LsError::Database(_) => http::StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError::new(status, self.to_string()).into_response()
     }
 }
 
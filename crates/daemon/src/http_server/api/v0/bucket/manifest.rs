@@ -0,0 +1,155 @@
+use std::path::Path;
+
+use axum::extract::{Json, State};
+use axum::response::{IntoResponse, Response};
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::bucket_log::BucketLogProvider;
+use common::linked_data::{Hash, Link, LD_RAW_CODEC};
+use common::mount::{MountError, NodeLink};
+use common::prelude::Mount;
+
+use crate::http_server::api::client::ApiRequest;
+use crate::http_server::error::ApiError;
+use crate::integrity::{IntegrityManifest, ManifestEntry};
+use crate::ServiceState;
+
+/// Export a signed listing (path, hash, size) of the files of a bucket version
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct ManifestRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Version to list (a bucket link hash), instead of the current one
+    #[arg(long)]
+    #[serde(default)]
+    pub at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestResponse {
+    pub bucket_id: Uuid,
+    pub version: String,
+    pub height: u64,
+    pub files: usize,
+    /// The signed manifest, see [`crate::integrity`]
+    pub manifest: String,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<ManifestRequest>,
+) -> Result<impl IntoResponse, ManifestError> {
+    if !state
+        .peer()
+        .logs()
+        .exists(req.bucket_id)
+        .await
+        .map_err(|e| ManifestError::BucketLog(e.to_string()))?
+    {
+        return Err(ManifestError::BucketNotFound(req.bucket_id));
+    }
+
+    let mount =
+        match super::parse_version(req.at.as_deref()).map_err(ManifestError::InvalidVersion)? {
+            Some(hash) => {
+                let link = Link::new(LD_RAW_CODEC, hash);
+                Mount::load(&link, state.peer().secret(), state.peer().blobs()).await?
+            }
+            None => state.peer().mount_for_read(req.bucket_id).await?,
+        };
+    let version = mount.link().await.hash();
+    let height = state
+        .database()
+        .get_all_bucket_logs(&req.bucket_id)
+        .await?
+        .iter()
+        .find(|entry| entry.current_link.hash() == version)
+        .map(|entry| entry.height)
+        .ok_or(ManifestError::VersionNotFound(version))?;
+    let bucket_name = mount.inner().await.manifest().name().to_string();
+
+    // Sizes and hashes recorded at upload are used as is, files written
+    //  before they were recorded or appended to are read and hashed
+    let mut files = Vec::new();
+    for (path, node_link) in mount.ls_deep(Path::new("/")).await? {
+        let NodeLink::Data(_, _, data) = node_link else {
+            continue;
+        };
+        let path = Path::new("/").join(path);
+        let (size, hash) = match data.size().zip(data.content_hash()) {
+            Some(recorded) => recorded,
+            None => {
+                let content = mount.cat(&path).await?;
+                (content.len() as u64, Hash::new(&content))
+            }
+        };
+        files.push(ManifestEntry {
+            path: path.to_string_lossy().into_owned(),
+            hash,
+            size,
+        });
+    }
+
+    let manifest = IntegrityManifest::sign(
+        state.peer().secret(),
+        req.bucket_id,
+        bucket_name,
+        version,
+        height,
+        files,
+    );
+    Ok((
+        http::StatusCode::OK,
+        Json(ManifestResponse {
+            bucket_id: req.bucket_id,
+            version: version.to_string(),
+            height,
+            files: manifest.files.len(),
+            manifest: manifest.to_string(),
+        }),
+    )
+        .into_response())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ManifestError {
+    #[error("Bucket not found: {0}")]
+    BucketNotFound(Uuid),
+    #[error("Invalid version: {0}")]
+    InvalidVersion(String),
+    #[error("Version {0} is not in the bucket's log")]
+    VersionNotFound(Hash),
+    #[error("Bucket log error: {0}")]
+    BucketLog(String),
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Mount error: {0}")]
+    Mount(#[from] MountError),
+}
+
+impl IntoResponse for ManifestError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            ManifestError::BucketNotFound(_) | ManifestError::VersionNotFound(_) => {
+                http::StatusCode::NOT_FOUND
+            }
+            ManifestError::InvalidVersion(_) => http::StatusCode::BAD_REQUEST,
+            _ => http::StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for ManifestRequest {
+    type Response = ManifestResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/manifest").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,77 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::MemberApiError;
+use crate::http_server::api::client::ApiRequest;
+use crate::http_server::api::v0::bucket::share::{
+    grant_share, ShareError, ShareOutcome, ShareResponse, ShareRole,
+};
+use crate::ServiceState;
+
+/// Approve a pending share, adding the peer to the bucket
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct MemberApproveRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Public key of the peer (hex-encoded)
+    #[arg(long)]
+    pub peer_public_key: String,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<MemberApproveRequest>,
+) -> Result<impl IntoResponse, MemberApiError> {
+    let pending = state
+        .database()
+        .list_pending_shares(Some(&req.bucket_id))
+        .await?
+        .into_iter()
+        .find(|share| share.peer_public_key == req.peer_public_key)
+        .ok_or_else(|| MemberApiError::NotPending(req.bucket_id, req.peer_public_key.clone()))?;
+    let role = ShareRole::parse(&pending.role)
+        .ok_or_else(|| MemberApiError::InvalidRole(pending.role.clone()))?;
+
+    let mount = state
+        .peer()
+        .mount(req.bucket_id)
+        .await
+        .map_err(ShareError::from)?;
+    let link = grant_share(
+        &state,
+        mount,
+        &req.peer_public_key,
+        role,
+        pending.path.as_deref(),
+    )
+    .await?;
+    state
+        .database()
+        .take_pending_share(&req.bucket_id, &req.peer_public_key)
+        .await?;
+
+    Ok((
+        http::StatusCode::OK,
+        Json(ShareResponse::new(
+            req.bucket_id,
+            req.peer_public_key,
+            &ShareOutcome::Shared(link),
+        )),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for MemberApproveRequest {
+    type Response = ShareResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/member/approve").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
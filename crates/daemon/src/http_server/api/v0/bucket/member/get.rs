@@ -0,0 +1,67 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::MemberApiError;
+use crate::http_server::api::client::ApiRequest;
+use crate::member_policy::MemberPolicy;
+use crate::ServiceState;
+
+/// Show the member policy of a bucket, or the default one
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct MemberPolicyGetRequest {
+    /// Bucket ID; leave out for the default policy
+    #[arg(long)]
+    #[serde(default)]
+    pub bucket_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemberPolicyResponse {
+    pub bucket_id: Option<Uuid>,
+    pub policy: MemberPolicy,
+    /// The bucket has no policy of its own and follows the default
+    pub is_default: bool,
+}
+
+impl MemberPolicyResponse {
+    pub(crate) async fn load(
+        state: &ServiceState,
+        bucket_id: Option<Uuid>,
+    ) -> Result<Self, sqlx::Error> {
+        let own = match &bucket_id {
+            Some(bucket_id) => state.database().bucket_member_policy(bucket_id).await?,
+            None => None,
+        };
+        let is_default = own.is_none();
+        let policy = match own {
+            Some(policy) => policy,
+            None => state.database().default_member_policy().await?,
+        };
+        Ok(Self {
+            bucket_id,
+            policy,
+            is_default,
+        })
+    }
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<MemberPolicyGetRequest>,
+) -> Result<impl IntoResponse, MemberApiError> {
+    let response = MemberPolicyResponse::load(&state, req.bucket_id).await?;
+    Ok((http::StatusCode::OK, Json(response)).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for MemberPolicyGetRequest {
+    type Response = MemberPolicyResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/member/policy/get").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
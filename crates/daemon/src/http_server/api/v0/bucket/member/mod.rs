@@ -0,0 +1,61 @@
+//! Member policy and share approval endpoints (see [`crate::member_policy`])
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use uuid::Uuid;
+
+use super::share::ShareError;
+use crate::http_server::error::ApiError;
+use crate::ServiceState;
+
+pub mod approve;
+pub mod get;
+pub mod pending;
+pub mod reject;
+pub mod reset;
+pub mod set;
+
+pub use approve::MemberApproveRequest;
+pub use get::{MemberPolicyGetRequest, MemberPolicyResponse};
+pub use pending::{MemberPendingRequest, MemberPendingResponse};
+pub use reject::{MemberRejectRequest, MemberRejectResponse};
+pub use reset::{MemberPolicyResetRequest, MemberPolicyResetResponse};
+pub use set::MemberPolicySetRequest;
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/policy/get", post(get::handler))
+        .route("/policy/set", post(set::handler))
+        .route("/policy/reset", post(reset::handler))
+        .route("/pending", post(pending::handler))
+        .route("/approve", post(approve::handler))
+        .route("/reject", post(reject::handler))
+        .with_state(state)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum MemberApiError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("No share of bucket {0} with {1} is pending")]
+    NotPending(Uuid, String),
+    #[error("Invalid role: {0}")]
+    InvalidRole(String),
+    #[error(transparent)]
+    Share(#[from] ShareError),
+}
+
+impl IntoResponse for MemberApiError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            MemberApiError::Share(e) => return e.into_response(),
+            MemberApiError::NotPending(_, _) => StatusCode::NOT_FOUND,
+            MemberApiError::Database(_) | MemberApiError::InvalidRole(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
@@ -0,0 +1,45 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::MemberApiError;
+use crate::http_server::api::client::ApiRequest;
+use crate::member_policy::PendingShare;
+use crate::ServiceState;
+
+/// List shares waiting for approval
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct MemberPendingRequest {
+    /// Only list shares of this bucket
+    #[arg(long)]
+    #[serde(default)]
+    pub bucket_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemberPendingResponse {
+    pub shares: Vec<PendingShare>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<MemberPendingRequest>,
+) -> Result<impl IntoResponse, MemberApiError> {
+    let shares = state
+        .database()
+        .list_pending_shares(req.bucket_id.as_ref())
+        .await?;
+    Ok((http::StatusCode::OK, Json(MemberPendingResponse { shares })).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for MemberPendingRequest {
+    type Response = MemberPendingResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/member/pending").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,65 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::MemberApiError;
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Turn down a pending share
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct MemberRejectRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Public key of the peer (hex-encoded)
+    #[arg(long)]
+    pub peer_public_key: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemberRejectResponse {
+    pub bucket_id: Uuid,
+    pub peer_public_key: String,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<MemberRejectRequest>,
+) -> Result<impl IntoResponse, MemberApiError> {
+    state
+        .database()
+        .take_pending_share(&req.bucket_id, &req.peer_public_key)
+        .await?
+        .ok_or_else(|| MemberApiError::NotPending(req.bucket_id, req.peer_public_key.clone()))?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "bucket.share.reject")
+                .bucket(req.bucket_id)
+                .detail(&req.peer_public_key),
+        )
+        .await;
+
+    Ok((
+        http::StatusCode::OK,
+        Json(MemberRejectResponse {
+            bucket_id: req.bucket_id,
+            peer_public_key: req.peer_public_key,
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for MemberRejectRequest {
+    type Response = MemberRejectResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/member/reject").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,59 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::MemberApiError;
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Remove a bucket's member policy so it follows the default one
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct MemberPolicyResetRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemberPolicyResetResponse {
+    pub bucket_id: Uuid,
+    /// Whether the bucket had a policy
+    pub removed: bool,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<MemberPolicyResetRequest>,
+) -> Result<impl IntoResponse, MemberApiError> {
+    let removed = state
+        .database()
+        .delete_bucket_member_policy(&req.bucket_id)
+        .await?;
+    if removed {
+        state
+            .audit(AuditEvent::new(Actor::Api, "bucket.member.policy.reset").bucket(req.bucket_id))
+            .await;
+    }
+
+    Ok((
+        http::StatusCode::OK,
+        Json(MemberPolicyResetResponse {
+            bucket_id: req.bucket_id,
+            removed,
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for MemberPolicyResetRequest {
+    type Response = MemberPolicyResetResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/member/policy/reset").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,78 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{MemberApiError, MemberPolicyResponse};
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::member_policy::MemberPolicy;
+use crate::ServiceState;
+
+/// Replace the member policy of a bucket, or the default one. Options left
+/// out fall back to the defaults.
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct MemberPolicySetRequest {
+    /// Bucket ID; leave out to set the default policy
+    #[arg(long)]
+    #[serde(default)]
+    pub bucket_id: Option<Uuid>,
+
+    /// Only sync metadata when this node becomes a member (online-only)
+    #[arg(long = "no-auto-pin", action = clap::ArgAction::SetFalse)]
+    #[serde(default = "default_auto_pin")]
+    pub auto_pin: bool,
+
+    /// Record members added by other owners in the audit log
+    #[arg(long)]
+    #[serde(default)]
+    pub notify_members: bool,
+
+    /// Hold shares made on this node until an owner approves them
+    #[arg(long)]
+    #[serde(default)]
+    pub require_approval: bool,
+}
+
+fn default_auto_pin() -> bool {
+    true
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<MemberPolicySetRequest>,
+) -> Result<impl IntoResponse, MemberApiError> {
+    let policy = MemberPolicy {
+        auto_pin: req.auto_pin,
+        notify_members: req.notify_members,
+        require_approval: req.require_approval,
+    };
+    let event = AuditEvent::new(Actor::Api, "bucket.member.policy.set");
+    match &req.bucket_id {
+        Some(bucket_id) => {
+            state
+                .database()
+                .set_bucket_member_policy(bucket_id, &policy)
+                .await?;
+            state.audit(event.bucket(*bucket_id)).await;
+        }
+        None => {
+            state.database().set_default_member_policy(&policy).await?;
+            state.audit(event.detail("default")).await;
+        }
+    }
+
+    let response = MemberPolicyResponse::load(&state, req.bucket_id).await?;
+    Ok((http::StatusCode::OK, Json(response)).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for MemberPolicySetRequest {
+    type Response = MemberPolicyResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/member/policy/set").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -8,6 +8,8 @@ use uuid::Uuid;
 
 use common::prelude::{Link, MountError};
 
+use super::{head_moved, parse_version};
+use crate::audit::{Actor, AuditEvent};
 use crate::http_server::api::client::ApiRequest;
 use crate::ServiceState;
 
@@ -15,6 +17,10 @@ use crate::ServiceState;
 pub struct MkdirRequest {
     pub bucket_id: Uuid,
     pub path: String,
+
+    /// Fail with 409 unless the bucket head is still this version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_version: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,13 +36,26 @@ pub async fn handler(
     let path = PathBuf::from(&request.path);
 
     // Load mount
-    let mut mount = state.peer().mount(request.bucket_id).await?;
+    let expected_version =
+        parse_version(request.expected_version.as_deref()).map_err(MkdirError::InvalidVersion)?;
+    let mut mount = state
+        .peer()
+        .mount_expecting(request.bucket_id, expected_version)
+        .await?;
 
     // Create directory
     mount.mkdir(&path).await?;
 
     // Save mount
     let new_link = state.peer().save_mount(&mount, false).await?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "bucket.mkdir")
+                .bucket(request.bucket_id)
+                .path(request.path.clone())
+                .version(&new_link),
+        )
+        .await;
 
     Ok((
         http::StatusCode::OK,
@@ -50,17 +69,27 @@ pub async fn handler(
 
 #[derive(Debug, thiserror::Error)]
 pub enum MkdirError {
+    #[error("Invalid version: {0}")]
+    InvalidVersion(String),
     #[error("Mount error: {0}")]
     Mount(#[from] MountError),
 }
 
 impl IntoResponse for MkdirError {
     fn into_response(self) -> axum::response::Response {
-        (
-            http::StatusCode::INTERNAL_SERVER_ERROR,
-            "Unexpected error".to_string(),
-        )
-            .into_response()
+        match self {
+            MkdirError::InvalidVersion(msg) => (
+                http::StatusCode::BAD_REQUEST,
+                format!("Invalid version: {}", msg),
+            )
+                .into_response(),
+            MkdirError::Mount(MountError::HeadMoved(head)) => head_moved(&head).into_response(),
+            MkdirError::Mount(_) => (
+                http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Unexpected error".to_string(),
+            )
+                .into_response(),
+        }
     }
 }
 
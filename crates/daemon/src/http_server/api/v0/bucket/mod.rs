@@ -1,45 +1,167 @@
-use axum::routing::post;
+use std::path::Path;
+
+use axum::extract::DefaultBodyLimit;
+use axum::routing::{get, post};
 use axum::Router;
+use serde::{Deserialize, Serialize};
+
+use common::linked_data::Hash;
+use common::prelude::{Link, Mount};
 
+use crate::http_server::error::{ApiError, ErrorCode};
 use crate::ServiceState;
 
 pub mod add;
+pub mod append;
+pub mod attrs;
+pub mod backfill_mime;
 pub mod cat;
+pub mod cdn;
+pub mod claim;
+pub mod comments;
 pub mod create;
 pub mod delete;
+pub mod deploy_key;
+pub mod drop_link;
+pub mod editing;
 pub mod export;
+pub mod follow;
+pub mod fork;
+pub mod history;
+pub mod import_remote;
 pub mod latest_published;
+pub mod lease;
 pub mod list;
 pub mod ls;
+pub mod manifest;
+pub mod member;
 pub mod mkdir;
 pub mod mv;
+pub mod photos;
 pub mod ping;
+pub mod pipeline;
+pub mod presign;
 pub mod publish;
+pub mod reference;
 pub mod rename;
+pub mod retention;
+pub mod scans;
 pub mod share;
+pub mod share_ephemeral;
+pub mod shares;
+pub mod stats;
+pub mod storage;
+pub mod unfollow;
+pub mod unpublish;
 pub mod update;
+pub mod versions;
+pub mod zip;
 
 // Re-export for convenience
+pub use claim::ClaimRequest;
 pub use create::CreateRequest;
+pub use follow::FollowRequest;
+pub use fork::ForkRequest;
+pub use history::{HistoryRequest, HistoryResponse};
 pub use list::ListRequest;
+pub use photos::PhotosRequest;
+pub use presign::PresignRequest;
+pub use publish::PublishRequest;
+pub use reference::ReferenceRequest;
+pub use scans::ScansRequest;
 pub use share::ShareRequest;
+pub use share_ephemeral::ShareEphemeralRequest;
+pub use shares::SharesRequest;
+pub use unfollow::UnfollowRequest;
+pub use unpublish::UnpublishRequest;
+pub use versions::VersionsRequest;
 
 pub fn router(state: ServiceState) -> Router<ServiceState> {
     Router::new()
         .route("/", post(create::handler))
         .route("/list", post(list::handler))
-        .route("/add", post(add::handler))
+        // Uploads stream into the bucket with bounded memory and each file
+        //  is checked against its bucket's upload limit instead
+        .route(
+            "/add",
+            post(add::handler).layer(DefaultBodyLimit::disable()),
+        )
         .route("/update", post(update::handler))
+        .route("/append", post(append::handler))
         .route("/rename", post(rename::handler))
         .route("/mv", post(mv::handler))
         .route("/delete", post(delete::handler))
         .route("/mkdir", post(mkdir::handler))
+        .route("/reference", post(reference::handler))
         .route("/ls", post(ls::handler))
         .route("/cat", post(cat::handler).get(cat::handler_get))
         .route("/ping", post(ping::handler))
         .route("/share", post(share::handler))
+        .route("/share-ephemeral", post(share_ephemeral::handler))
+        .route("/shares", post(shares::handler))
         .route("/publish", post(publish::handler))
+        .route("/unpublish", post(unpublish::handler))
+        .route("/scans", post(scans::handler))
         .route("/export", post(export::handler))
+        .route("/history", post(history::handler))
+        .route("/versions", post(versions::handler))
+        .route("/manifest", post(manifest::handler))
+        .route("/follow", post(follow::handler))
+        .route("/unfollow", post(unfollow::handler))
+        .route("/fork", post(fork::handler))
+        .route("/import-remote", post(import_remote::handler))
+        .route("/claim", post(claim::handler))
         .route("/latest-published", post(latest_published::handler))
+        .route("/backfill-mime", post(backfill_mime::handler))
+        .route("/photos", post(photos::handler))
+        .route("/stats", post(stats::handler))
+        .route("/presign", post(presign::handler))
+        .route("/zip", get(zip::handler))
+        .nest("/lease", lease::router(state.clone()))
+        .nest("/editing", editing::router(state.clone()))
+        .nest("/attrs", attrs::router(state.clone()))
+        .nest("/comments", comments::router(state.clone()))
+        .nest("/member", member::router(state.clone()))
+        .nest("/deploy-key", deploy_key::router(state.clone()))
+        .nest("/drop-link", drop_link::router(state.clone()))
+        .nest("/retention", retention::router(state.clone()))
+        .nest("/storage", storage::router(state.clone()))
+        .nest("/cdn", cdn::router(state.clone()))
+        .nest("/pipeline", pipeline::router(state.clone()))
         .with_state(state)
 }
+
+/// Details of the `head_moved` 409 a write gets when the bucket head is no
+/// longer its `expected_version`, so the client can rebase onto
+/// `current_version`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeadMoved {
+    pub current_version: String,
+}
+
+pub(crate) fn head_moved(current: &Link) -> ApiError {
+    ApiError::new(http::StatusCode::CONFLICT, "bucket head moved")
+        .with_code(ErrorCode::HeadMoved)
+        .with_details(HeadMoved {
+            current_version: current.hash().to_string(),
+        })
+}
+
+/// Parse a bucket version hash passed as `min_version` or `expected_version`
+pub(crate) fn parse_version(version: Option<&str>) -> Result<Option<Hash>, String> {
+    version
+        .map(str::parse::<Hash>)
+        .transpose()
+        .map_err(|e| e.to_string())
+}
+
+/// The MIME type recorded for the file at `path`, as served to clients
+pub(crate) async fn stored_mime(mount: &Mount, path: &Path) -> String {
+    mount
+        .get(path)
+        .await
+        .ok()
+        .and_then(|link| link.data().and_then(|data| data.mime().cloned()))
+        .map(|mime| mime.to_string())
+        .unwrap_or_else(|| "application/octet-stream".to_string())
+}
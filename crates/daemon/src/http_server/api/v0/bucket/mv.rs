@@ -6,6 +6,8 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use uuid::Uuid;
 
+use super::{head_moved, parse_version};
+use crate::audit::{Actor, AuditEvent};
 use crate::http_server::api::client::ApiRequest;
 use crate::ServiceState;
 
@@ -17,6 +19,10 @@ pub struct MvRequest {
     pub source_path: String,
     /// New absolute path for the file/directory
     pub dest_path: String,
+
+    /// Fail with 409 unless the bucket head is still this version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_version: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -56,7 +62,12 @@ pub async fn handler(
     }
 
     // Load mount at current head
-    let mut mount = state.peer().mount(req.bucket_id).await?;
+    let expected_version =
+        parse_version(req.expected_version.as_deref()).map_err(MvError::InvalidVersion)?;
+    let mut mount = state
+        .peer()
+        .mount_expecting(req.bucket_id, expected_version)
+        .await?;
     tracing::info!("MV API: Loaded mount for bucket {}", req.bucket_id);
 
     // Perform the move operation
@@ -69,6 +80,15 @@ pub async fn handler(
 
     // Save mount and update log
     let new_bucket_link = state.peer().save_mount(&mount, false).await?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "bucket.mv")
+                .bucket(req.bucket_id)
+                .path(req.source_path.clone())
+                .detail(req.dest_path.clone())
+                .version(&new_bucket_link),
+        )
+        .await;
 
     tracing::info!(
         "MV API: Moved {} to {} in bucket {}, new link: {}",
@@ -91,6 +111,8 @@ pub async fn handler(
 
 #[derive(Debug, thiserror::Error)]
 pub enum MvError {
+    #[error("Invalid version: {0}")]
+    InvalidVersion(String),
     #[error("Invalid path: {0}")]
     InvalidPath(String),
     #[error("Mount error: {0}")]
@@ -124,6 +146,12 @@ impl IntoResponse for MvError {
                 ),
             )
                 .into_response(),
+            MvError::InvalidVersion(msg) => (
+                http::StatusCode::BAD_REQUEST,
+                format!("Invalid version: {}", msg),
+            )
+                .into_response(),
+            MvError::Mount(MountError::HeadMoved(head)) => head_moved(&head).into_response(),
             MvError::Mount(_) => (
                 http::StatusCode::INTERNAL_SERVER_ERROR,
                 "Unexpected error".to_string(),
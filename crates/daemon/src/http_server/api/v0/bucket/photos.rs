@@ -0,0 +1,197 @@
+use std::path::Path;
+
+use axum::extract::{Json, State};
+use axum::response::{IntoResponse, Response};
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::mount::NodeLink;
+use common::prelude::MountError;
+
+use crate::database::MediaEntry;
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Default number of photos in a page of the timeline
+const DEFAULT_LIMIT: u32 = 100;
+
+/// Most photos in a page of the timeline
+const MAX_LIMIT: u32 = 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct PhotosRequest {
+    /// Bucket ID to list photos of (defaults to every bucket)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub bucket_id: Option<Uuid>,
+
+    /// Number of photos to skip, from a previous page's `next_offset`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub offset: Option<u32>,
+
+    /// Number of photos to return (default 100, at most 1000)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhotosResponse {
+    pub items: Vec<PhotoInfo>,
+    /// Offset of the next page, if there is one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<u32>,
+    /// Buckets that could not be read (e.g. unpublished mirrors)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped_buckets: Vec<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhotoInfo {
+    pub bucket_id: Uuid,
+    pub path: String,
+    pub mime_type: String,
+    /// When the photo was taken, in seconds since the Unix epoch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub captured_at: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<u64>,
+}
+
+impl From<MediaEntry> for PhotoInfo {
+    fn from(entry: MediaEntry) -> Self {
+        Self {
+            bucket_id: entry.bucket_id,
+            path: entry.path,
+            mime_type: entry.mime_type,
+            captured_at: entry.captured_at,
+            width: entry.width,
+            height: entry.height,
+        }
+    }
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<PhotosRequest>,
+) -> Result<impl IntoResponse, PhotosError> {
+    let offset = req.offset.unwrap_or(0);
+    let limit = req.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    // Bring the index up to date with each bucket's current version
+    let mut skipped_buckets = Vec::new();
+    match req.bucket_id {
+        Some(bucket_id) => index_bucket(&state, bucket_id).await?,
+        None => {
+            let buckets = state.database().list_buckets(None, Some(1000)).await?;
+            let mut indexed = Vec::with_capacity(buckets.len());
+            for bucket in buckets {
+                match index_bucket(&state, bucket.id).await {
+                    Ok(()) => indexed.push(bucket.id),
+                    Err(PhotosError::Mount(e)) => {
+                        tracing::warn!("Skipping bucket {} in photo timeline: {}", bucket.id, e);
+                        skipped_buckets.push(bucket.id);
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            state.database().prune_media_index(&indexed).await?;
+        }
+    }
+
+    // Fetch one more than asked for to tell whether there's another page
+    let mut entries = state
+        .database()
+        .media_timeline(req.bucket_id.as_ref(), offset, limit + 1)
+        .await?;
+    let next_offset = (entries.len() > limit as usize).then(|| offset + limit);
+    entries.truncate(limit as usize);
+
+    Ok((
+        http::StatusCode::OK,
+        Json(PhotosResponse {
+            items: entries.into_iter().map(PhotoInfo::from).collect(),
+            next_offset,
+            skipped_buckets,
+        }),
+    )
+        .into_response())
+}
+
+/// Re-read the photos of a bucket if it changed since it was last indexed
+async fn index_bucket(state: &ServiceState, bucket_id: Uuid) -> Result<(), PhotosError> {
+    let mount = state.peer().mount_for_read(bucket_id).await?;
+    let version = mount.link().await.hash().to_string();
+    if state
+        .database()
+        .media_index_version(&bucket_id)
+        .await?
+        .as_deref()
+        == Some(&version)
+    {
+        return Ok(());
+    }
+
+    let mut entries = Vec::new();
+    for (path, node_link) in mount.ls_deep(Path::new("/")).await? {
+        let NodeLink::Data(_, _, data) = node_link else {
+            continue;
+        };
+        let Some(mime) = data.mime().filter(|mime| mime.type_() == "image") else {
+            continue;
+        };
+        let media = data.media().unwrap_or_default();
+        entries.push(MediaEntry {
+            bucket_id,
+            path: Path::new("/").join(&path).to_string_lossy().to_string(),
+            mime_type: mime.to_string(),
+            captured_at: media.captured_at,
+            width: media.width,
+            height: media.height,
+        });
+    }
+
+    tracing::debug!(
+        "Indexed {} photos of bucket {} at {}",
+        entries.len(),
+        bucket_id,
+        version
+    );
+    state
+        .database()
+        .replace_media_index(&bucket_id, &version, &entries)
+        .await?;
+    Ok(())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PhotosError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Mount error: {0}")]
+    Mount(#[from] MountError),
+}
+
+impl IntoResponse for PhotosError {
+    fn into_response(self) -> Response {
+        (
+            http::StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Error: {}", self),
+        )
+            .into_response()
+    }
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for PhotosRequest {
+    type Response = PhotosResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/photos").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,64 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::PipelineApiError;
+use crate::http_server::api::client::ApiRequest;
+use crate::publish_pipeline::{PublishBuild, PublishPipeline};
+use crate::ServiceState;
+
+/// Show the publish pipeline of a bucket and its latest builds
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct PipelineGetRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineResponse {
+    pub bucket_id: Uuid,
+    /// None if the bucket is published as it is
+    pub pipeline: Option<PublishPipeline>,
+    /// Bucket the output is published as, once the bucket was built
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub site_bucket_id: Option<Uuid>,
+    /// Newest first
+    pub builds: Vec<PublishBuild>,
+}
+
+impl PipelineResponse {
+    pub(crate) async fn load(state: &ServiceState, bucket_id: Uuid) -> Result<Self, sqlx::Error> {
+        let (pipeline, site_bucket_id) = match state.database().publish_pipeline(&bucket_id).await?
+        {
+            Some((pipeline, site_bucket_id)) => (Some(pipeline), site_bucket_id),
+            None => (None, None),
+        };
+        Ok(Self {
+            bucket_id,
+            pipeline,
+            site_bucket_id,
+            builds: state.database().list_publish_builds(&bucket_id).await?,
+        })
+    }
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<PipelineGetRequest>,
+) -> Result<impl IntoResponse, PipelineApiError> {
+    let response = PipelineResponse::load(&state, req.bucket_id).await?;
+    Ok((http::StatusCode::OK, Json(response)).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for PipelineGetRequest {
+    type Response = PipelineResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/pipeline/get").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,48 @@
+//! Publish pipeline endpoints (see [`crate::publish_pipeline`])
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use uuid::Uuid;
+
+use crate::http_server::error::ApiError;
+use crate::publish_pipeline::PipelineError;
+use crate::ServiceState;
+
+pub mod get;
+pub mod reset;
+pub mod set;
+
+pub use get::{PipelineGetRequest, PipelineResponse};
+pub use reset::{PipelineResetRequest, PipelineResetResponse};
+pub use set::PipelineSetRequest;
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/get", post(get::handler))
+        .route("/set", post(set::handler))
+        .route("/reset", post(reset::handler))
+        .with_state(state)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PipelineApiError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Bucket not found: {0}")]
+    BucketNotFound(Uuid),
+    #[error("{0}")]
+    Pipeline(#[from] PipelineError),
+}
+
+impl IntoResponse for PipelineApiError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            PipelineApiError::Pipeline(_) => StatusCode::BAD_REQUEST,
+            PipelineApiError::BucketNotFound(_) => StatusCode::NOT_FOUND,
+            PipelineApiError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
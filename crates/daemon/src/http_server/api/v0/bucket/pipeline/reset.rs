@@ -0,0 +1,60 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::PipelineApiError;
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Remove a bucket's publish pipeline so it's published as it is. Its site
+/// bucket stays, with what was published last.
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct PipelineResetRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineResetResponse {
+    pub bucket_id: Uuid,
+    /// Whether the bucket had a pipeline
+    pub removed: bool,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<PipelineResetRequest>,
+) -> Result<impl IntoResponse, PipelineApiError> {
+    let removed = state
+        .database()
+        .delete_publish_pipeline(&req.bucket_id)
+        .await?;
+    if removed {
+        state
+            .audit(AuditEvent::new(Actor::Api, "bucket.pipeline.reset").bucket(req.bucket_id))
+            .await;
+    }
+
+    Ok((
+        http::StatusCode::OK,
+        Json(PipelineResetResponse {
+            bucket_id: req.bucket_id,
+            removed,
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for PipelineResetRequest {
+    type Response = PipelineResetResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/pipeline/reset").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,63 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{PipelineApiError, PipelineResponse};
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::publish_pipeline::{PipelineError, PublishPipeline};
+use crate::ServiceState;
+
+/// Replace the publish pipeline of a bucket: the configured builder run when
+/// it's published, whose output is published instead
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineSetRequest {
+    pub bucket_id: Uuid,
+    #[serde(flatten)]
+    pub pipeline: PublishPipeline,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<PipelineSetRequest>,
+) -> Result<impl IntoResponse, PipelineApiError> {
+    req.pipeline.validate()?;
+    if !state.publish_builders().contains(&req.pipeline.builder) {
+        return Err(PipelineError::UnknownBuilder(req.pipeline.builder).into());
+    }
+    if state
+        .database()
+        .get_bucket_info(&req.bucket_id)
+        .await?
+        .is_none()
+    {
+        return Err(PipelineApiError::BucketNotFound(req.bucket_id));
+    }
+
+    state
+        .database()
+        .set_publish_pipeline(&req.bucket_id, &req.pipeline)
+        .await?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "bucket.pipeline.set")
+                .bucket(req.bucket_id)
+                .detail(req.pipeline.builder.clone()),
+        )
+        .await;
+
+    let response = PipelineResponse::load(&state, req.bucket_id).await?;
+    Ok((http::StatusCode::OK, Json(response)).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for PipelineSetRequest {
+    type Response = PipelineResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/pipeline/set").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
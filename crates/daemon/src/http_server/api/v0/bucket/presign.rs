@@ -0,0 +1,151 @@
+use std::time::Duration;
+
+use axum::extract::{Extension, Json, State};
+use axum::response::{IntoResponse, Response};
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use common::prelude::{Mount, MountError};
+
+use crate::deploy::normalize_path;
+use crate::http_server::api::client::ApiRequest;
+use crate::http_server::error::ApiError;
+use crate::http_server::Config;
+use crate::signed_url::{SignedDownload, UrlSigner, DEFAULT_EXPIRES_IN, MAX_EXPIRES_IN};
+use crate::ServiceState;
+
+/// Mint a temporary URL the gateway serves a file at, without authentication
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct PresignRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Absolute path of the file
+    #[arg(long)]
+    pub path: String,
+
+    /// Version hash to serve the file at (defaults to the current version)
+    #[arg(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub at: Option<String>,
+
+    /// Seconds until the URL expires (default 3600, at most 7 days)
+    #[arg(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_in_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PresignResponse {
+    pub bucket_id: Uuid,
+    pub path: String,
+    /// Version the URL serves the file at
+    pub version: String,
+    /// Path and query of the URL on the gateway
+    pub url_path: String,
+    /// Full URL, when the daemon knows its gateway's public URL
+    pub url: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub expires_at: OffsetDateTime,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Extension(config): Extension<Config>,
+    Json(req): Json<PresignRequest>,
+) -> Result<impl IntoResponse, PresignError> {
+    let expires_in = req
+        .expires_in_secs
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_EXPIRES_IN);
+    if expires_in.is_zero() || expires_in > MAX_EXPIRES_IN {
+        return Err(PresignError::InvalidExpiry(MAX_EXPIRES_IN.as_secs()));
+    }
+    // Signed as the gateway will see it
+    let path =
+        normalize_path(&req.path).ok_or_else(|| PresignError::InvalidPath(req.path.clone()))?;
+
+    let mount = match &req.at {
+        Some(hash_str) => {
+            let hash = hash_str
+                .parse::<common::linked_data::Hash>()
+                .map_err(|e| PresignError::InvalidPath(format!("Invalid hash format: {}", e)))?;
+            let link = common::linked_data::Link::new(common::linked_data::LD_RAW_CODEC, hash);
+            Mount::load(&link, state.peer().secret(), state.peer().blobs()).await?
+        }
+        None => state.peer().mount_for_read(req.bucket_id).await?,
+    };
+    let node = mount.get(std::path::Path::new(&path)).await?;
+    if node.is_dir() {
+        return Err(PresignError::InvalidPath(format!(
+            "{} is a directory",
+            path
+        )));
+    }
+
+    let expires_at = OffsetDateTime::now_utc() + expires_in;
+    let download = SignedDownload {
+        bucket_id: req.bucket_id,
+        path,
+        version: mount.inner().await.link().hash(),
+        expires: expires_at.unix_timestamp(),
+    };
+    let signature = UrlSigner::derive(state.peer().secret()).sign(&download);
+    let url_path = download.to_path(&signature);
+    let url = config
+        .gateway_url
+        .as_ref()
+        .map(|base| format!("{}{}", base.trim_end_matches('/'), url_path));
+
+    Ok((
+        http::StatusCode::OK,
+        Json(PresignResponse {
+            bucket_id: req.bucket_id,
+            path: download.path.clone(),
+            version: download.version.to_string(),
+            url_path,
+            url,
+            expires_at,
+        }),
+    )
+        .into_response())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PresignError {
+    #[error("Invalid path: {0}")]
+    InvalidPath(String),
+    #[error("URLs expire after 1 to {0} seconds")]
+    InvalidExpiry(u64),
+    #[error("Mount error: {0}")]
+    Mount(#[from] MountError),
+}
+
+impl IntoResponse for PresignError {
+    fn into_response(self) -> Response {
+        if let PresignError::Mount(e @ MountError::VersionUnavailable(_)) = self {
+            return ApiError::from(e).into_response();
+        }
+        let status = match self {
+            PresignError::InvalidPath(_) | PresignError::InvalidExpiry(_) => {
+                http::StatusCode::BAD_REQUEST
+            }
+            PresignError::Mount(MountError::PathNotFound(_)) => http::StatusCode::NOT_FOUND,
+            PresignError::Mount(_) => http::StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for PresignRequest {
+    type Response = PresignResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/presign").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
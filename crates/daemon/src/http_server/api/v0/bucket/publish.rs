@@ -1,10 +1,15 @@
 use axum::extract::{Json, State};
 use axum::response::{IntoResponse, Response};
-use common::prelude::MountError;
 use reqwest::{Client, RequestBuilder, Url};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::audit::{Actor, AuditEvent};
+use crate::bucket_index::IndexUpdate;
+use crate::follow::FollowTicket;
+use common::prelude::MountError;
+
+use super::{head_moved, parse_version};
 use crate::http_server::api::client::ApiRequest;
 use crate::ServiceState;
 
@@ -13,6 +18,22 @@ pub struct PublishRequest {
     /// Bucket ID to publish
     #[arg(long)]
     pub bucket_id: Uuid,
+
+    /// Revoke the current public link and earlier published versions,
+    /// serving the bucket at a new gateway link
+    #[arg(long)]
+    #[serde(default)]
+    pub rotate: bool,
+
+    /// Fail with 409 unless the bucket head is still this version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub expected_version: Option<String>,
+
+    /// Changes to what the bucket's gateway index page shows
+    #[command(flatten)]
+    #[serde(flatten)]
+    pub index: IndexUpdate,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,31 +41,54 @@ pub struct PublishResponse {
     pub bucket_id: Uuid,
     pub published: bool,
     pub new_bucket_link: String,
+    /// Path the gateway serves the bucket at
+    pub gateway_path: String,
+    /// Path the gateway serves this version at, e.g. `/gw/<bucket-id>/@v3`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_path: Option<String>,
+    /// Ticket other nodes can follow the bucket with
+    pub ticket: String,
+    /// Bucket the publish pipeline built the bucket into, which is what was
+    /// published; `new_bucket_link` and the ticket are of it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub site_bucket_id: Option<Uuid>,
 }
 
 pub async fn handler(
     State(state): State<ServiceState>,
     Json(req): Json<PublishRequest>,
 ) -> Result<impl IntoResponse, PublishError> {
-    tracing::info!("PUBLISH API: Publishing bucket {}", req.bucket_id);
-
-    // Load mount at current head
-    let mount = state.peer().mount(req.bucket_id).await?;
-
-    // Check if already published
-    if mount.is_published().await {
-        tracing::info!("PUBLISH API: Bucket {} is already published", req.bucket_id);
-        // Still return success, just note it's already published
-    }
+    tracing::info!(
+        "PUBLISH API: Publishing bucket {} (rotate: {})",
+        req.bucket_id,
+        req.rotate
+    );
 
     // Save mount with publish=true (grants decryption to all mirrors)
     // This saves to blobs, appends to log, and notifies peers
-    let new_bucket_link = state.peer().save_mount(&mount, true).await?;
+    let expected_version =
+        parse_version(req.expected_version.as_deref()).map_err(PublishError::InvalidVersion)?;
+    let publication = state
+        .publish_bucket(req.bucket_id, req.rotate, expected_version, &req.index)
+        .await?;
+    let event = AuditEvent::new(Actor::Api, "bucket.publish")
+        .bucket(req.bucket_id)
+        .version(&publication.link);
+    let event = match (req.rotate, publication.site_bucket_id) {
+        (true, Some(site_bucket_id)) => {
+            event.detail(format!("rotated, built into {}", site_bucket_id))
+        }
+        (false, Some(site_bucket_id)) => event.detail(format!("built into {}", site_bucket_id)),
+        (true, None) => event.detail("rotated"),
+        (false, None) => event,
+    };
+    state.audit(event).await;
 
     tracing::info!(
-        "PUBLISH API: Bucket {} published, new link: {}",
+        "PUBLISH API: Bucket {} published at {}, new link: {}",
         req.bucket_id,
-        new_bucket_link.hash()
+        publication.gateway_path,
+        publication.link.hash()
     );
 
     Ok((
@@ -52,7 +96,22 @@ pub async fn handler(
         Json(PublishResponse {
             bucket_id: req.bucket_id,
             published: true,
-            new_bucket_link: new_bucket_link.hash().to_string(),
+            new_bucket_link: publication.link.hash().to_string(),
+            version_path: publication.height.map(|height| {
+                format!(
+                    "{}/{}v{}",
+                    publication.gateway_path,
+                    crate::version_url::VERSION_MARKER,
+                    height
+                )
+            }),
+            gateway_path: publication.gateway_path,
+            ticket: FollowTicket {
+                bucket_id: publication.site_bucket_id.unwrap_or(req.bucket_id),
+                node_id: state.peer().secret().public(),
+            }
+            .to_string(),
+            site_bucket_id: publication.site_bucket_id,
         }),
     )
         .into_response())
@@ -60,14 +119,39 @@ pub async fn handler(
 
 #[derive(Debug, thiserror::Error)]
 pub enum PublishError {
-    #[error("Mount error: {0}")]
-    Mount(#[from] MountError),
+    #[error("Invalid version: {0}")]
+    InvalidVersion(String),
+    #[error("Publish error: {0}")]
+    Publish(#[from] crate::publish::PublishError),
 }
 
 impl IntoResponse for PublishError {
     fn into_response(self) -> Response {
         match self {
-            PublishError::Mount(_) => (
+            PublishError::InvalidVersion(msg) => (
+                http::StatusCode::BAD_REQUEST,
+                format!("Invalid version: {}", msg),
+            )
+                .into_response(),
+            PublishError::Publish(crate::publish::PublishError::Mount(MountError::HeadMoved(
+                head,
+            ))) => head_moved(&head).into_response(),
+            PublishError::Publish(crate::publish::PublishError::Mount(
+                MountError::InvalidAttrs(msg),
+            )) => (
+                http::StatusCode::BAD_REQUEST,
+                format!("Invalid index settings: {}", msg),
+            )
+                .into_response(),
+            PublishError::Publish(
+                e @ (crate::publish::PublishError::Vetoed(_)
+                | crate::publish::PublishError::Build(_)),
+            ) => (
+                http::StatusCode::UNPROCESSABLE_ENTITY,
+                format!("Publication {}", e),
+            )
+                .into_response(),
+            PublishError::Publish(_) => (
                 http::StatusCode::INTERNAL_SERVER_ERROR,
                 "Unexpected error".to_string(),
             )
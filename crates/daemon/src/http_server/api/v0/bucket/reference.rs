@@ -0,0 +1,174 @@
+use std::path::PathBuf;
+
+use axum::extract::{Json, State};
+use axum::response::{IntoResponse, Response};
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::bucket_log::BucketLogProvider;
+use common::linked_data::LD_RAW_CODEC;
+use common::mount::BucketRef;
+use common::prelude::{Link, Mount, MountError};
+
+use super::{head_moved, parse_version};
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Reference another bucket at a path, like a git submodule, so readers with
+/// access to both see its contents there
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct ReferenceRequest {
+    /// Bucket ID to add the reference to
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Path of the reference in the bucket
+    #[arg(long)]
+    pub path: String,
+
+    /// Bucket ID to reference
+    #[arg(long)]
+    pub target: Uuid,
+
+    /// Version of the referenced bucket to pin (defaults to its current head)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long, conflicts_with = "floating")]
+    pub version: Option<String>,
+
+    /// Follow the referenced bucket's head instead of pinning a version
+    #[serde(default)]
+    #[arg(long)]
+    pub floating: bool,
+
+    /// Fail with 409 unless the bucket head is still this version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub expected_version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReferenceResponse {
+    pub path: String,
+    /// Version of the referenced bucket recorded in the reference
+    pub version: String,
+    pub floating: bool,
+    pub link: Link,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<ReferenceRequest>,
+) -> Result<impl IntoResponse, ReferenceError> {
+    if req.floating && req.version.is_some() {
+        return Err(ReferenceError::InvalidVersion(
+            "a floating reference doesn't pin a version".to_string(),
+        ));
+    }
+    let pinned = parse_version(req.version.as_deref()).map_err(ReferenceError::InvalidVersion)?;
+    let expected_version =
+        parse_version(req.expected_version.as_deref()).map_err(ReferenceError::InvalidVersion)?;
+
+    // The referenced version has to be one this node holds of the target
+    let version = match pinned {
+        Some(hash) => {
+            let link = Link::new(LD_RAW_CODEC, hash);
+            let manifest = Mount::load_manifest(&link, state.peer().blobs())
+                .await
+                .map_err(|_| ReferenceError::UnknownVersion(hash.to_string()))?;
+            if *manifest.id() != req.target {
+                return Err(ReferenceError::UnknownVersion(hash.to_string()));
+            }
+            link
+        }
+        None => {
+            state
+                .peer()
+                .logs()
+                .head(req.target, None)
+                .await
+                .map_err(|_| ReferenceError::UnknownBucket(req.target))?
+                .0
+        }
+    };
+
+    let mut mount = state
+        .peer()
+        .mount_expecting(req.bucket_id, expected_version)
+        .await?;
+    mount
+        .add_reference(
+            &PathBuf::from(&req.path),
+            BucketRef::new(req.target, version.clone(), req.floating),
+        )
+        .await?;
+    let link = state.peer().save_mount(&mount, false).await?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "bucket.reference")
+                .bucket(req.bucket_id)
+                .path(req.path.clone())
+                .detail(req.target.to_string())
+                .version(&link),
+        )
+        .await;
+
+    Ok((
+        http::StatusCode::OK,
+        Json(ReferenceResponse {
+            path: req.path,
+            version: version.hash().to_string(),
+            floating: req.floating,
+            link,
+        }),
+    )
+        .into_response())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReferenceError {
+    #[error("Invalid version: {0}")]
+    InvalidVersion(String),
+    #[error("Unknown bucket: {0}")]
+    UnknownBucket(Uuid),
+    #[error("Version {0} of the referenced bucket is not on this node")]
+    UnknownVersion(String),
+    #[error("Mount error: {0}")]
+    Mount(#[from] MountError),
+}
+
+impl IntoResponse for ReferenceError {
+    fn into_response(self) -> Response {
+        match self {
+            ReferenceError::InvalidVersion(_)
+            | ReferenceError::Mount(MountError::ReferenceCycle(_))
+            | ReferenceError::Mount(MountError::BucketReference(_))
+            | ReferenceError::Mount(MountError::PathNotNode(_)) => {
+                (http::StatusCode::BAD_REQUEST, self.to_string()).into_response()
+            }
+            ReferenceError::UnknownBucket(_) | ReferenceError::UnknownVersion(_) => {
+                (http::StatusCode::NOT_FOUND, self.to_string()).into_response()
+            }
+            ReferenceError::Mount(MountError::PathAlreadyExists(_)) => {
+                (http::StatusCode::CONFLICT, self.to_string()).into_response()
+            }
+            ReferenceError::Mount(MountError::HeadMoved(head)) => head_moved(&head).into_response(),
+            ReferenceError::Mount(_) => (
+                http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Unexpected error".to_string(),
+            )
+                .into_response(),
+        }
+    }
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for ReferenceRequest {
+    type Response = ReferenceResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/reference").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
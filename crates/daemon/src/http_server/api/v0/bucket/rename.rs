@@ -6,6 +6,8 @@ use std::io::Cursor;
 use std::path::PathBuf;
 use uuid::Uuid;
 
+use super::{head_moved, parse_version};
+use crate::audit::{Actor, AuditEvent};
 use crate::ServiceState;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,6 +18,10 @@ pub struct RenameRequest {
     pub old_path: String,
     /// New absolute path for the file
     pub new_path: String,
+
+    /// Fail with 409 unless the bucket head is still this version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_version: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,7 +61,12 @@ pub async fn handler(
     }
 
     // Load mount at current head
-    let mut mount = state.peer().mount(req.bucket_id).await?;
+    let expected_version =
+        parse_version(req.expected_version.as_deref()).map_err(RenameError::InvalidVersion)?;
+    let mut mount = state
+        .peer()
+        .mount_expecting(req.bucket_id, expected_version)
+        .await?;
     tracing::info!("RENAME API: Loaded mount for bucket {}", req.bucket_id);
 
     // Check if source exists
@@ -102,6 +113,15 @@ pub async fn handler(
 
     // Save mount and update log
     let new_bucket_link = state.peer().save_mount(&mount, false).await?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "bucket.rename")
+                .bucket(req.bucket_id)
+                .path(req.old_path.clone())
+                .detail(req.new_path.clone())
+                .version(&new_bucket_link),
+        )
+        .await;
 
     tracing::info!(
         "RENAME API: Renamed {} to {} in bucket {}, new link: {}",
@@ -124,6 +144,8 @@ pub async fn handler(
 
 #[derive(Debug, thiserror::Error)]
 pub enum RenameError {
+    #[error("Invalid version: {0}")]
+    InvalidVersion(String),
     #[error("Invalid path: {0}")]
     InvalidPath(String),
     #[error("Source not found: {0}")]
@@ -152,6 +174,12 @@ impl IntoResponse for RenameError {
                 format!("Destination exists: {}", msg),
             )
                 .into_response(),
+            RenameError::InvalidVersion(msg) => (
+                http::StatusCode::BAD_REQUEST,
+                format!("Invalid version: {}", msg),
+            )
+                .into_response(),
+            RenameError::Mount(MountError::HeadMoved(head)) => head_moved(&head).into_response(),
             RenameError::Mount(_) => (
                 http::StatusCode::INTERNAL_SERVER_ERROR,
                 "Unexpected error".to_string(),
@@ -0,0 +1,55 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::RetentionApiError;
+use crate::http_server::api::client::ApiRequest;
+use crate::retention::RetentionPolicy;
+use crate::ServiceState;
+
+/// Show the retention policy of a bucket
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct RetentionGetRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionPolicyResponse {
+    pub bucket_id: Uuid,
+    /// None if the bucket keeps every version
+    pub policy: Option<RetentionPolicy>,
+    /// Number of versions pruned so far
+    pub pruned_versions: u64,
+}
+
+impl RetentionPolicyResponse {
+    pub(crate) async fn load(state: &ServiceState, bucket_id: Uuid) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            bucket_id,
+            policy: state.database().retention_policy(&bucket_id).await?,
+            pruned_versions: state.database().pruned_versions(&bucket_id).await?.len() as u64,
+        })
+    }
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<RetentionGetRequest>,
+) -> Result<impl IntoResponse, RetentionApiError> {
+    let response = RetentionPolicyResponse::load(&state, req.bucket_id).await?;
+    Ok((http::StatusCode::OK, Json(response)).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for RetentionGetRequest {
+    type Response = RetentionPolicyResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/retention/get").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
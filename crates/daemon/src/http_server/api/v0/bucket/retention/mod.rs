@@ -0,0 +1,59 @@
+//! Retention policy endpoints (see [`crate::retention`])
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use uuid::Uuid;
+
+use crate::http_server::error::ApiError;
+use crate::retention::{RetentionError, RetentionPolicyError};
+use crate::ServiceState;
+
+pub mod get;
+pub mod reset;
+pub mod run;
+pub mod set;
+
+pub use get::{RetentionGetRequest, RetentionPolicyResponse};
+pub use reset::{RetentionResetRequest, RetentionResetResponse};
+pub use run::RetentionRunRequest;
+pub use set::RetentionSetRequest;
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/get", post(get::handler))
+        .route("/set", post(set::handler))
+        .route("/reset", post(reset::handler))
+        .route("/run", post(run::handler))
+        .with_state(state)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RetentionApiError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Bucket not found: {0}")]
+    BucketNotFound(Uuid),
+    #[error("Bucket {0} has no retention policy")]
+    NoPolicy(Uuid),
+    #[error("{0}")]
+    Policy(#[from] RetentionPolicyError),
+    #[error("{0}")]
+    Retention(#[from] RetentionError),
+}
+
+impl IntoResponse for RetentionApiError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            RetentionApiError::Policy(_) => StatusCode::BAD_REQUEST,
+            RetentionApiError::BucketNotFound(_) | RetentionApiError::NoPolicy(_) => {
+                StatusCode::NOT_FOUND
+            }
+            RetentionApiError::Database(_) | RetentionApiError::Retention(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
@@ -0,0 +1,60 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::RetentionApiError;
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Remove a bucket's retention policy so it keeps every new version.
+/// Versions pruned so far stay pruned.
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct RetentionResetRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionResetResponse {
+    pub bucket_id: Uuid,
+    /// Whether the bucket had a policy
+    pub removed: bool,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<RetentionResetRequest>,
+) -> Result<impl IntoResponse, RetentionApiError> {
+    let removed = state
+        .database()
+        .delete_retention_policy(&req.bucket_id)
+        .await?;
+    if removed {
+        state
+            .audit(AuditEvent::new(Actor::Api, "bucket.retention.reset").bucket(req.bucket_id))
+            .await;
+    }
+
+    Ok((
+        http::StatusCode::OK,
+        Json(RetentionResetResponse {
+            bucket_id: req.bucket_id,
+            removed,
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for RetentionResetRequest {
+    type Response = RetentionResetResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/retention/reset").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
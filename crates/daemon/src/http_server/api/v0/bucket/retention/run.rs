@@ -0,0 +1,59 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::RetentionApiError;
+use crate::audit::Actor;
+use crate::http_server::api::client::ApiRequest;
+use crate::retention::{self, PruneReport};
+use crate::ServiceState;
+
+/// Apply a bucket's retention policy now
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct RetentionRunRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Report what would be pruned and moved without changing anything
+    #[arg(long)]
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<RetentionRunRequest>,
+) -> Result<impl IntoResponse, RetentionApiError> {
+    let policy = state
+        .database()
+        .retention_policy(&req.bucket_id)
+        .await?
+        .ok_or(RetentionApiError::NoPolicy(req.bucket_id))?;
+
+    let report = retention::prune(
+        state.peer(),
+        state.database(),
+        req.bucket_id,
+        &policy,
+        req.dry_run,
+    )
+    .await?;
+    if !req.dry_run {
+        retention::audit_prune(state.audit_log(), Actor::Api, &report).await;
+    }
+
+    Ok((http::StatusCode::OK, Json(report)).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for RetentionRunRequest {
+    type Response = PruneReport;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/retention/run").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,104 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{RetentionApiError, RetentionPolicyResponse};
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::retention::{Keep, RetentionPolicy, RetentionPolicyError};
+use crate::ServiceState;
+
+/// Replace the retention policy of a bucket. Each period keeps the newest
+/// version of the last N of them, or of all of them with `forever`. On S3
+/// blob storage, blobs only old versions reference can move to colder
+/// storage classes instead of, or as well as, pruning versions.
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct RetentionSetRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Hours to keep a version of, or `forever`
+    #[arg(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hourly: Option<Keep>,
+
+    /// Days to keep a version of, or `forever`
+    #[arg(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daily: Option<Keep>,
+
+    /// Weeks to keep a version of, or `forever`
+    #[arg(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weekly: Option<Keep>,
+
+    /// Months to keep a version of, or `forever`
+    #[arg(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monthly: Option<Keep>,
+
+    /// Days until blobs only old versions reference move to infrequent access
+    #[arg(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub infrequent_access_after: Option<u32>,
+
+    /// Days until blobs only old versions reference are archived; reading
+    /// archived blobs takes hours
+    #[arg(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive_after: Option<u32>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<RetentionSetRequest>,
+) -> Result<impl IntoResponse, RetentionApiError> {
+    let policy = RetentionPolicy {
+        hourly: req.hourly,
+        daily: req.daily,
+        weekly: req.weekly,
+        monthly: req.monthly,
+        infrequent_access_after: req.infrequent_access_after,
+        archive_after: req.archive_after,
+    };
+    policy.validate()?;
+    if policy.moves_blobs() && !state.peer().blobs().supports_storage_classes() {
+        return Err(RetentionPolicyError::StorageClassesUnsupported.into());
+    }
+    if state
+        .database()
+        .get_bucket_info(&req.bucket_id)
+        .await?
+        .is_none()
+    {
+        return Err(RetentionApiError::BucketNotFound(req.bucket_id));
+    }
+
+    state
+        .database()
+        .set_retention_policy(&req.bucket_id, &policy)
+        .await?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "bucket.retention.set")
+                .bucket(req.bucket_id)
+                .detail(policy.to_string()),
+        )
+        .await;
+
+    let response = RetentionPolicyResponse::load(&state, req.bucket_id).await?;
+    Ok((http::StatusCode::OK, Json(response)).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for RetentionSetRequest {
+    type Response = RetentionPolicyResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/retention/set").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
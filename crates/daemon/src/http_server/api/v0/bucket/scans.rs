@@ -0,0 +1,65 @@
+use axum::extract::{Json, State};
+use axum::response::{IntoResponse, Response};
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::http_server::api::client::ApiRequest;
+use crate::publish_scan::PublishScan;
+use crate::ServiceState;
+
+/// Results of content scanning on versions of a bucket
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct ScansRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScansResponse {
+    pub bucket_id: Uuid,
+    /// Newest first
+    pub scans: Vec<PublishScan>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<ScansRequest>,
+) -> Result<impl IntoResponse, ScansError> {
+    let scans = state.database().list_publish_scans(&req.bucket_id).await?;
+    Ok((
+        http::StatusCode::OK,
+        Json(ScansResponse {
+            bucket_id: req.bucket_id,
+            scans,
+        }),
+    )
+        .into_response())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ScansError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+impl IntoResponse for ScansError {
+    fn into_response(self) -> Response {
+        (
+            http::StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Error: {}", self),
+        )
+            .into_response()
+    }
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for ScansRequest {
+    type Response = ScansResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/scans").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -1,12 +1,16 @@
 use axum::extract::{Json, State};
 use axum::response::{IntoResponse, Response};
-use common::prelude::MountError;
+use common::prelude::{Mount, MountError};
 use reqwest::{Client, RequestBuilder, Url};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use common::crypto::PublicKey;
+use common::linked_data::Hash;
+use common::prelude::Link;
 
+use super::{head_moved, parse_version};
+use crate::audit::{Actor, AuditEvent};
 use crate::http_server::api::client::ApiRequest;
 use crate::ServiceState;
 
@@ -21,6 +25,23 @@ pub enum ShareRole {
     Mirror,
 }
 
+impl ShareRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ShareRole::Owner => "owner",
+            ShareRole::Mirror => "mirror",
+        }
+    }
+
+    pub fn parse(role: &str) -> Option<Self> {
+        match role {
+            "owner" => Some(ShareRole::Owner),
+            "mirror" => Some(ShareRole::Mirror),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
 pub struct ShareRequest {
     /// Bucket ID to share
@@ -35,13 +56,50 @@ pub struct ShareRequest {
     #[arg(long, default_value = "owner")]
     #[serde(default)]
     pub role: ShareRole,
+
+    /// Share only the directory at this path, read-only, instead of the
+    /// whole bucket (owner role only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub path: Option<String>,
+
+    /// Fail with 409 unless the bucket head is still this version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub expected_version: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ShareResponse {
     pub bucket_id: Uuid,
     pub peer_public_key: String,
-    pub new_bucket_link: String,
+    /// New head of the bucket; None while the share waits for approval
+    pub new_bucket_link: Option<String>,
+    /// The bucket's member policy holds the share until an owner approves it
+    #[serde(default)]
+    pub pending: bool,
+}
+
+impl ShareResponse {
+    pub(crate) fn new(bucket_id: Uuid, peer_public_key: String, outcome: &ShareOutcome) -> Self {
+        Self {
+            bucket_id,
+            peer_public_key,
+            new_bucket_link: match outcome {
+                ShareOutcome::Shared(link) => Some(link.hash().to_string()),
+                ShareOutcome::Pending => None,
+            },
+            pending: matches!(outcome, ShareOutcome::Pending),
+        }
+    }
+}
+
+/// What became of a share
+pub(crate) enum ShareOutcome {
+    /// Saved with this new head
+    Shared(Link),
+    /// Held for approval (see [`crate::member_policy`])
+    Pending,
 }
 
 pub async fn handler(
@@ -54,64 +112,186 @@ pub async fn handler(
         req.peer_public_key,
         req.role
     );
+    if req.path.is_some() && req.role != ShareRole::Owner {
+        return Err(ShareError::InvalidPath(
+            "only the owner role can be given for a directory".to_string(),
+        ));
+    }
 
-    // Parse the peer's public key from hex
-    let peer_public_key = PublicKey::from_hex(&req.peer_public_key)
-        .map_err(|e| ShareError::InvalidPublicKey(e.to_string()))?;
+    let expected_version =
+        parse_version(req.expected_version.as_deref()).map_err(ShareError::InvalidVersion)?;
+    let outcome = share_bucket(
+        &state,
+        req.bucket_id,
+        &req.peer_public_key,
+        req.role,
+        req.path.as_deref(),
+        expected_version,
+    )
+    .await?;
+    let status = match outcome {
+        ShareOutcome::Shared(_) => http::StatusCode::OK,
+        ShareOutcome::Pending => http::StatusCode::ACCEPTED,
+    };
 
-    tracing::info!("SHARE API: Parsed peer public key successfully");
+    Ok((
+        status,
+        Json(ShareResponse::new(
+            req.bucket_id,
+            req.peer_public_key,
+            &outcome,
+        )),
+    )
+        .into_response())
+}
 
+/// Share a bucket, or only the directory at `path`, with a peer, unless the
+/// bucket's member policy holds the share for approval
+///
+/// Also used to share every bucket of a workspace.
+pub(crate) async fn share_bucket(
+    state: &ServiceState,
+    bucket_id: Uuid,
+    peer_public_key: &str,
+    role: ShareRole,
+    path: Option<&str>,
+    expected_version: Option<Hash>,
+) -> Result<ShareOutcome, ShareError> {
     // Load mount at current head
-    let mut mount = state.peer().mount(req.bucket_id).await?;
-    tracing::info!("SHARE API: Loaded mount for bucket {}", req.bucket_id);
+    let mount = state
+        .peer()
+        .mount_expecting(bucket_id, expected_version)
+        .await?;
+    tracing::info!("SHARE API: Loaded mount for bucket {}", bucket_id);
+
+    if state
+        .database()
+        .member_policy(&bucket_id)
+        .await?
+        .require_approval
+    {
+        PublicKey::from_hex(peer_public_key)
+            .map_err(|e| ShareError::InvalidPublicKey(e.to_string()))?;
+        state
+            .database()
+            .add_pending_share(&bucket_id, peer_public_key, role.as_str(), path)
+            .await?;
+        state
+            .audit(
+                AuditEvent::new(Actor::Api, "bucket.share.pending")
+                    .bucket(bucket_id)
+                    .detail(share_detail(peer_public_key, role, path)),
+            )
+            .await;
+        tracing::info!(
+            "SHARE API: Share of bucket {} with peer {} waits for approval",
+            bucket_id,
+            peer_public_key
+        );
+        return Ok(ShareOutcome::Pending);
+    }
+
+    grant_share(state, mount, peer_public_key, role, path)
+        .await
+        .map(ShareOutcome::Shared)
+}
+
+/// Audit detail of a share
+fn share_detail(peer_public_key: &str, role: ShareRole, path: Option<&str>) -> String {
+    match path {
+        Some(path) => format!("{} for {}", peer_public_key, path),
+        None => format!("{} as {:?}", peer_public_key, role),
+    }
+}
+
+/// Add a peer to a loaded bucket, or to the directory at `path` only, and
+/// save it, returning the new head
+pub(crate) async fn grant_share(
+    state: &ServiceState,
+    mut mount: Mount,
+    peer_public_key: &str,
+    role: ShareRole,
+    path: Option<&str>,
+) -> Result<Link, ShareError> {
+    // Parse the peer's public key from hex
+    let public_key = PublicKey::from_hex(peer_public_key)
+        .map_err(|e| ShareError::InvalidPublicKey(e.to_string()))?;
+    let bucket_id = *mount.inner().await.manifest().id();
 
     // Share bucket with peer based on role
-    match req.role {
-        ShareRole::Owner => {
-            mount.add_owner(peer_public_key).await?;
+    match (role, path) {
+        // A directory's secret, read-only (see common::mount::SubtreeShare)
+        (ShareRole::Owner, Some(path)) => {
+            if !path.starts_with('/') {
+                return Err(ShareError::InvalidPath(format!("{} is not absolute", path)));
+            }
+            mount
+                .add_subtree_share(public_key, std::path::Path::new(path))
+                .await?;
+            tracing::info!(
+                "SHARE API: Mount.add_subtree_share() completed for peer {} of {}",
+                peer_public_key,
+                path
+            );
+        }
+        (ShareRole::Owner, None) => {
+            mount.add_owner(public_key).await?;
             tracing::info!(
                 "SHARE API: Mount.add_owner() completed for peer {}",
-                req.peer_public_key
+                peer_public_key
             );
         }
-        ShareRole::Mirror => {
-            mount.add_mirror(peer_public_key).await;
+        (ShareRole::Mirror, _) => {
+            mount.add_mirror(public_key).await;
             tracing::info!(
                 "SHARE API: Mount.add_mirror() completed for peer {}",
-                req.peer_public_key
+                peer_public_key
             );
         }
     }
 
-    tracing::info!("SHARE API: Calling save_mount for bucket {}", req.bucket_id);
+    // Wrap owner shares with ML-KEM too if every peer of the bucket reads them
+    let hybrid = state.peer().negotiate_shares(&mut mount).await?;
+    tracing::info!(
+        "SHARE API: Owner shares of bucket {} are {}",
+        bucket_id,
+        if hybrid { "hybrid" } else { "classic" }
+    );
+
     // Save mount and update log
     let new_bucket_link = state.peer().save_mount(&mount, false).await?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "bucket.share")
+                .bucket(bucket_id)
+                .detail(share_detail(peer_public_key, role, path))
+                .version(&new_bucket_link),
+        )
+        .await;
 
     tracing::info!(
         "SHARE API: Bucket {} shared with peer {} as {:?}, new link: {}",
-        req.bucket_id,
-        req.peer_public_key,
-        req.role,
+        bucket_id,
+        peer_public_key,
+        role,
         new_bucket_link.hash()
     );
 
-    Ok((
-        http::StatusCode::OK,
-        Json(ShareResponse {
-            bucket_id: req.bucket_id,
-            peer_public_key: req.peer_public_key,
-            new_bucket_link: new_bucket_link.hash().to_string(),
-        }),
-    )
-        .into_response())
+    Ok(new_bucket_link)
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum ShareError {
+    #[error("Invalid version: {0}")]
+    InvalidVersion(String),
     #[error("Invalid public key: {0}")]
     InvalidPublicKey(String),
+    #[error("Invalid path: {0}")]
+    InvalidPath(String),
     #[error("Mount error: {0}")]
     Mount(#[from] MountError),
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
 }
 
 impl IntoResponse for ShareError {
@@ -122,7 +302,31 @@ impl IntoResponse for ShareError {
                 format!("Invalid public key: {}", msg),
             )
                 .into_response(),
-            ShareError::Mount(_) => (
+            ShareError::InvalidVersion(msg) => (
+                http::StatusCode::BAD_REQUEST,
+                format!("Invalid version: {}", msg),
+            )
+                .into_response(),
+            ShareError::InvalidPath(msg) => (
+                http::StatusCode::BAD_REQUEST,
+                format!("Invalid path: {}", msg),
+            )
+                .into_response(),
+            ShareError::Mount(MountError::HeadMoved(head)) => head_moved(&head).into_response(),
+            ShareError::Mount(MountError::PathNotFound(path)) => (
+                http::StatusCode::NOT_FOUND,
+                format!("Path not found: {}", path.display()),
+            )
+                .into_response(),
+            ShareError::Mount(MountError::PathNotNode(path)) => (
+                http::StatusCode::BAD_REQUEST,
+                format!("Not a directory: {}", path.display()),
+            )
+                .into_response(),
+            ShareError::Mount(e @ MountError::AlreadyShared(_)) => {
+                (http::StatusCode::CONFLICT, e.to_string()).into_response()
+            }
+            ShareError::Mount(_) | ShareError::Database(_) => (
                 http::StatusCode::INTERNAL_SERVER_ERROR,
                 "Unexpected error".to_string(),
             )
@@ -0,0 +1,156 @@
+use axum::extract::{Json, State};
+use axum::response::{IntoResponse, Response};
+use common::prelude::MountError;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{head_moved, parse_version};
+use crate::audit::{Actor, AuditEvent};
+use crate::ephemeral::{self, EphemeralTicket};
+use crate::follow::FollowTicket;
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// How long an ephemeral share lasts unless asked otherwise: a week
+const DEFAULT_TTL_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Share a bucket read-only with a new temporary identity, so the recipient's
+/// own identity isn't recorded in the bucket
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct ShareEphemeralRequest {
+    /// Bucket ID to share
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Seconds until the share expires (defaults to a week)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub ttl_secs: Option<u64>,
+
+    /// Fail with 409 unless the bucket head is still this version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub expected_version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareEphemeralResponse {
+    pub bucket_id: Uuid,
+    /// Ticket to hand to the recipient, holding the temporary identity's key
+    pub ticket: String,
+    /// Public key of the temporary identity (hex)
+    pub identity: String,
+    /// Unix time in seconds when the share expires
+    pub expires_at: i64,
+    pub new_bucket_link: String,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<ShareEphemeralRequest>,
+) -> Result<impl IntoResponse, ShareEphemeralError> {
+    let ttl_secs = req.ttl_secs.unwrap_or(DEFAULT_TTL_SECS);
+    if ttl_secs == 0 || ttl_secs > i64::MAX as u64 / 2 {
+        return Err(ShareEphemeralError::InvalidTtl(ttl_secs));
+    }
+    let expected_version = parse_version(req.expected_version.as_deref())
+        .map_err(ShareEphemeralError::InvalidVersion)?;
+
+    let mut mount = state
+        .peer()
+        .mount_expecting(req.bucket_id, expected_version)
+        .await?;
+    // Pending shares name their recipient, which an ephemeral share must not
+    if state
+        .database()
+        .member_policy(&req.bucket_id)
+        .await?
+        .require_approval
+    {
+        return Err(ShareEphemeralError::ApprovalRequired);
+    }
+
+    let expires_at = ephemeral::now() + ttl_secs as i64;
+    let key = mount.add_ephemeral_share(expires_at).await;
+    let new_bucket_link = state.peer().save_mount(&mount, false).await?;
+    let identity = key.public().to_hex();
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "bucket.share.ephemeral")
+                .bucket(req.bucket_id)
+                .detail(format!("{} until {}", identity, expires_at))
+                .version(&new_bucket_link),
+        )
+        .await;
+    tracing::info!(
+        "SHARE API: Bucket {} shared with temporary identity {} until {}",
+        req.bucket_id,
+        identity,
+        expires_at
+    );
+
+    let ticket = EphemeralTicket {
+        follow: FollowTicket {
+            bucket_id: req.bucket_id,
+            node_id: state.peer().secret().public(),
+        },
+        key,
+    };
+    Ok((
+        http::StatusCode::OK,
+        Json(ShareEphemeralResponse {
+            bucket_id: req.bucket_id,
+            ticket: ticket.to_string(),
+            identity,
+            expires_at,
+            new_bucket_link: new_bucket_link.hash().to_string(),
+        }),
+    )
+        .into_response())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ShareEphemeralError {
+    #[error("Invalid version: {0}")]
+    InvalidVersion(String),
+    #[error("Invalid TTL: {0} seconds")]
+    InvalidTtl(u64),
+    #[error("The bucket's member policy requires approving shares, which name their recipient")]
+    ApprovalRequired,
+    #[error("Mount error: {0}")]
+    Mount(#[from] MountError),
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+impl IntoResponse for ShareEphemeralError {
+    fn into_response(self) -> Response {
+        match self {
+            ShareEphemeralError::InvalidVersion(_) | ShareEphemeralError::InvalidTtl(_) => {
+                (http::StatusCode::BAD_REQUEST, self.to_string()).into_response()
+            }
+            ShareEphemeralError::ApprovalRequired => {
+                (http::StatusCode::CONFLICT, self.to_string()).into_response()
+            }
+            ShareEphemeralError::Mount(MountError::HeadMoved(head)) => {
+                head_moved(&head).into_response()
+            }
+            ShareEphemeralError::Mount(_) | ShareEphemeralError::Database(_) => (
+                http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Unexpected error".to_string(),
+            )
+                .into_response(),
+        }
+    }
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for ShareEphemeralRequest {
+    type Response = ShareEphemeralResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/share-ephemeral").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,143 @@
+use axum::extract::{Json, State};
+use axum::response::{IntoResponse, Response};
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::bucket_log::BucketLogProvider;
+use common::mount::{MountError, PrincipalRole};
+
+use super::share::ShareRole;
+use crate::http_server::api::client::ApiRequest;
+use crate::http_server::error::ApiError;
+use crate::pagination::{PageError, PageRequest, SortKey, SortOrder, DEFAULT_PAGE_SIZE};
+use crate::ServiceState;
+
+/// List the principals a bucket is shared with, by public key
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct SharesRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Only list principals with this role
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long, value_enum)]
+    pub role: Option<ShareRole>,
+
+    /// Page of the listing, 100 principals by default
+    #[serde(flatten)]
+    #[command(flatten)]
+    pub page: PageRequest,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharesResponse {
+    pub bucket_id: Uuid,
+    pub shares: Vec<ShareInfo>,
+    /// Principals matching the filters, across all pages
+    pub total: u64,
+    /// Pass as `cursor` to list the next page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareInfo {
+    /// Hex public key of the principal
+    pub public_key: String,
+    pub role: ShareRole,
+    /// Whether the principal is this node
+    pub is_self: bool,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<SharesRequest>,
+) -> Result<impl IntoResponse, SharesError> {
+    if !state
+        .peer()
+        .logs()
+        .exists(req.bucket_id)
+        .await
+        .map_err(|e| SharesError::BucketLog(e.to_string()))?
+    {
+        return Err(SharesError::BucketNotFound(req.bucket_id));
+    }
+
+    let mount = state.peer().mount_for_read(req.bucket_id).await?;
+    let self_key = state.peer().secret().public().to_hex();
+    let shares = mount
+        .inner()
+        .await
+        .manifest()
+        .shares()
+        .iter()
+        .map(|(public_key, share)| ShareInfo {
+            public_key: public_key.clone(),
+            role: match share.role() {
+                PrincipalRole::Owner => ShareRole::Owner,
+                PrincipalRole::Mirror => ShareRole::Mirror,
+            },
+            is_self: *public_key == self_key,
+        })
+        .filter(|share| req.role.is_none() || req.role == Some(share.role))
+        .collect::<Vec<_>>();
+
+    let page = req.page.paginate(
+        shares,
+        "public_key",
+        SortOrder::Asc,
+        Some(DEFAULT_PAGE_SIZE),
+        |share| SortKey::text(&share.public_key, &share.public_key),
+    )?;
+
+    Ok((
+        http::StatusCode::OK,
+        Json(SharesResponse {
+            bucket_id: req.bucket_id,
+            shares: page.items,
+            total: page.total,
+            next_cursor: page.next_cursor,
+        }),
+    )
+        .into_response())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SharesError {
+    #[error("Bucket not found: {0}")]
+    BucketNotFound(Uuid),
+    #[error("Invalid page: {0}")]
+    Page(#[from] PageError),
+    #[error("Bucket log error: {0}")]
+    BucketLog(String),
+    #[error("Mount error: {0}")]
+    Mount(#[from] MountError),
+}
+
+impl IntoResponse for SharesError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            SharesError::BucketNotFound(_) => http::StatusCode::NOT_FOUND,
+            SharesError::Page(_) => http::StatusCode::BAD_REQUEST,
+            SharesError::Mount(MountError::ShareNotFound | MountError::MirrorCannotMount) => {
+                http::StatusCode::FORBIDDEN
+            }
+            SharesError::BucketLog(_) | SharesError::Mount(_) => {
+                http::StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for SharesRequest {
+    type Response = SharesResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/shares").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,101 @@
+use axum::extract::{Json, State};
+use axum::response::{IntoResponse, Response};
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::bucket_log::BucketLogProvider;
+use common::mount::MountError;
+
+use crate::bucket_stats::{BucketStats, BucketStatsError, MAX_LARGEST_FILES};
+use crate::http_server::api::client::ApiRequest;
+use crate::http_server::error::ApiError;
+use crate::ServiceState;
+
+/// Default number of largest files to include
+const DEFAULT_TOP_FILES: usize = 10;
+
+/// File counts, sizes and type breakdown of the current version of a bucket
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct StatsRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Number of largest files to include (default 10, at most 100)
+    #[arg(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatsResponse {
+    pub bucket_id: Uuid,
+    pub name: String,
+    #[serde(flatten)]
+    pub stats: BucketStats,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<StatsRequest>,
+) -> Result<impl IntoResponse, StatsError> {
+    if !state
+        .peer()
+        .logs()
+        .exists(req.bucket_id)
+        .await
+        .map_err(|e| StatsError::BucketLog(e.to_string()))?
+    {
+        return Err(StatsError::BucketNotFound(req.bucket_id));
+    }
+
+    let mount = state.peer().mount_for_read(req.bucket_id).await?;
+    let name = mount.inner().await.manifest().name().to_string();
+    let mut stats = state.bucket_stats().stats(req.bucket_id, &mount).await?;
+    stats
+        .largest_files
+        .truncate(req.top.unwrap_or(DEFAULT_TOP_FILES).min(MAX_LARGEST_FILES));
+
+    Ok((
+        http::StatusCode::OK,
+        Json(StatsResponse {
+            bucket_id: req.bucket_id,
+            name,
+            stats,
+        }),
+    )
+        .into_response())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum StatsError {
+    #[error("Bucket not found: {0}")]
+    BucketNotFound(Uuid),
+    #[error("Bucket log error: {0}")]
+    BucketLog(String),
+    #[error("Mount error: {0}")]
+    Mount(#[from] MountError),
+    #[error("{0}")]
+    Stats(#[from] BucketStatsError),
+}
+
+impl IntoResponse for StatsError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            StatsError::BucketNotFound(_) => http::StatusCode::NOT_FOUND,
+            _ => http::StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for StatsRequest {
+    type Response = StatsResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/stats").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,54 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::StorageApiError;
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Show where a bucket's blobs are placed
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct StorageGetRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoragePolicyResponse {
+    pub bucket_id: Uuid,
+    /// Location the bucket's blobs are placed in, None for the main store
+    pub location: Option<String>,
+    /// Locations configured on this node
+    pub locations: Vec<String>,
+}
+
+impl StoragePolicyResponse {
+    pub(crate) async fn load(state: &ServiceState, bucket_id: Uuid) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            bucket_id,
+            location: state.database().storage_policy(&bucket_id).await?,
+            locations: state.peer().blobs().locations(),
+        })
+    }
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<StorageGetRequest>,
+) -> Result<impl IntoResponse, StorageApiError> {
+    let response = StoragePolicyResponse::load(&state, req.bucket_id).await?;
+    Ok((http::StatusCode::OK, Json(response)).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for StorageGetRequest {
+    type Response = StoragePolicyResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/storage/get").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
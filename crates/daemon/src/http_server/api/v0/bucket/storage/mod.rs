@@ -0,0 +1,55 @@
+//! Storage policy endpoints (see [`crate::storage_policy`])
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use uuid::Uuid;
+
+use crate::http_server::error::ApiError;
+use crate::storage_policy::StoragePolicyError;
+use crate::ServiceState;
+
+pub mod get;
+pub mod reset;
+pub mod run;
+pub mod set;
+
+pub use get::{StorageGetRequest, StoragePolicyResponse};
+pub use reset::{StorageResetRequest, StorageResetResponse};
+pub use run::StorageRunRequest;
+pub use set::StorageSetRequest;
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/get", post(get::handler))
+        .route("/set", post(set::handler))
+        .route("/reset", post(reset::handler))
+        .route("/run", post(run::handler))
+        .with_state(state)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum StorageApiError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Bucket not found: {0}")]
+    BucketNotFound(Uuid),
+    #[error("Unknown blob location {0} (configure it under blob_locations in config.toml)")]
+    UnknownLocation(String),
+    #[error("{0}")]
+    Storage(#[from] StoragePolicyError),
+}
+
+impl IntoResponse for StorageApiError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            StorageApiError::UnknownLocation(_) => StatusCode::BAD_REQUEST,
+            StorageApiError::BucketNotFound(_) => StatusCode::NOT_FOUND,
+            StorageApiError::Database(_) | StorageApiError::Storage(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
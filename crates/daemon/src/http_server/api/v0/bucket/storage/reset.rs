@@ -0,0 +1,69 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::StorageApiError;
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::storage_policy::{self, PlacementReport};
+use crate::ServiceState;
+
+/// Remove a bucket's storage policy and move its blobs back to the main
+/// blob store
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct StorageResetRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageResetResponse {
+    pub bucket_id: Uuid,
+    /// Whether the bucket had a policy
+    pub removed: bool,
+    /// Blobs moved back to the main store, if it had one
+    pub placement: Option<PlacementReport>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<StorageResetRequest>,
+) -> Result<impl IntoResponse, StorageApiError> {
+    let removed = state
+        .database()
+        .delete_storage_policy(&req.bucket_id)
+        .await?;
+    let mut placement = None;
+    if removed {
+        state
+            .audit(AuditEvent::new(Actor::Api, "bucket.storage.reset").bucket(req.bucket_id))
+            .await;
+        let report =
+            storage_policy::place(state.peer(), state.database(), req.bucket_id, None).await?;
+        storage_policy::audit_placement(state.audit_log(), Actor::Api, &report).await;
+        placement = Some(report);
+    }
+
+    Ok((
+        http::StatusCode::OK,
+        Json(StorageResetResponse {
+            bucket_id: req.bucket_id,
+            removed,
+            placement,
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for StorageResetRequest {
+    type Response = StorageResetResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/storage/reset").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
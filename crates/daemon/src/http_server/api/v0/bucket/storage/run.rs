@@ -0,0 +1,47 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::StorageApiError;
+use crate::audit::Actor;
+use crate::http_server::api::client::ApiRequest;
+use crate::storage_policy::{self, PlacementReport};
+use crate::ServiceState;
+
+/// Place a bucket's blobs where its storage policy says now, or in the main
+/// blob store if it has none
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct StorageRunRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<StorageRunRequest>,
+) -> Result<impl IntoResponse, StorageApiError> {
+    let location = state.database().storage_policy(&req.bucket_id).await?;
+    let report = storage_policy::place(
+        state.peer(),
+        state.database(),
+        req.bucket_id,
+        location.as_deref(),
+    )
+    .await?;
+    storage_policy::audit_placement(state.audit_log(), Actor::Api, &report).await;
+
+    Ok((http::StatusCode::OK, Json(report)).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for StorageRunRequest {
+    type Response = PlacementReport;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/storage/run").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
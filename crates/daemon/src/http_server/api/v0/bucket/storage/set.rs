@@ -0,0 +1,65 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{StorageApiError, StoragePolicyResponse};
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Place a bucket's blobs in one of the node's blob locations. They are
+/// moved there in the background, or right away with `run`.
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct StorageSetRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Name of the location, as configured under blob_locations
+    #[arg(long)]
+    pub location: String,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<StorageSetRequest>,
+) -> Result<impl IntoResponse, StorageApiError> {
+    if !state.peer().blobs().locations().contains(&req.location) {
+        return Err(StorageApiError::UnknownLocation(req.location));
+    }
+    if state
+        .database()
+        .get_bucket_info(&req.bucket_id)
+        .await?
+        .is_none()
+    {
+        return Err(StorageApiError::BucketNotFound(req.bucket_id));
+    }
+
+    state
+        .database()
+        .set_storage_policy(&req.bucket_id, &req.location)
+        .await?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "bucket.storage.set")
+                .bucket(req.bucket_id)
+                .detail(req.location.clone()),
+        )
+        .await;
+
+    let response = StoragePolicyResponse::load(&state, req.bucket_id).await?;
+    Ok((http::StatusCode::OK, Json(response)).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for StorageSetRequest {
+    type Response = StoragePolicyResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/storage/set").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,71 @@
+use axum::extract::{Json, State};
+use axum::response::{IntoResponse, Response};
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Stop following a bucket. Versions synced so far are kept.
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct UnfollowRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnfollowResponse {
+    pub bucket_id: Uuid,
+    /// Whether the bucket was followed
+    pub removed: bool,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<UnfollowRequest>,
+) -> Result<impl IntoResponse, UnfollowError> {
+    let removed = state.unfollow_bucket(req.bucket_id).await?;
+    if removed {
+        state
+            .audit(AuditEvent::new(Actor::Api, "bucket.unfollow").bucket(req.bucket_id))
+            .await;
+    }
+
+    Ok((
+        http::StatusCode::OK,
+        Json(UnfollowResponse {
+            bucket_id: req.bucket_id,
+            removed,
+        }),
+    )
+        .into_response())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum UnfollowError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+impl IntoResponse for UnfollowError {
+    fn into_response(self) -> Response {
+        (
+            http::StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Error: {}", self),
+        )
+            .into_response()
+    }
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for UnfollowRequest {
+    type Response = UnfollowResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/unfollow").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
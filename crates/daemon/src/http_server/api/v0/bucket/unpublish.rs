@@ -0,0 +1,99 @@
+use axum::extract::{Json, State};
+use axum::response::{IntoResponse, Response};
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::prelude::MountError;
+
+use super::{head_moved, parse_version};
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Stop publishing a bucket: save an unpublished version and stop serving
+/// every earlier published version on the gateway
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct UnpublishRequest {
+    /// Bucket ID to unpublish
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Fail with 409 unless the bucket head is still this version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub expected_version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnpublishResponse {
+    pub bucket_id: Uuid,
+    pub new_bucket_link: String,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<UnpublishRequest>,
+) -> Result<impl IntoResponse, UnpublishError> {
+    tracing::info!("UNPUBLISH API: Unpublishing bucket {}", req.bucket_id);
+
+    let expected_version =
+        parse_version(req.expected_version.as_deref()).map_err(UnpublishError::InvalidVersion)?;
+    let new_bucket_link = state
+        .unpublish_bucket(req.bucket_id, expected_version)
+        .await?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "bucket.unpublish")
+                .bucket(req.bucket_id)
+                .version(&new_bucket_link),
+        )
+        .await;
+
+    Ok((
+        http::StatusCode::OK,
+        Json(UnpublishResponse {
+            bucket_id: req.bucket_id,
+            new_bucket_link: new_bucket_link.hash().to_string(),
+        }),
+    )
+        .into_response())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum UnpublishError {
+    #[error("Invalid version: {0}")]
+    InvalidVersion(String),
+    #[error("Publish error: {0}")]
+    Publish(#[from] crate::publish::PublishError),
+}
+
+impl IntoResponse for UnpublishError {
+    fn into_response(self) -> Response {
+        match self {
+            UnpublishError::InvalidVersion(msg) => (
+                http::StatusCode::BAD_REQUEST,
+                format!("Invalid version: {}", msg),
+            )
+                .into_response(),
+            UnpublishError::Publish(crate::publish::PublishError::Mount(
+                MountError::HeadMoved(head),
+            )) => head_moved(&head).into_response(),
+            UnpublishError::Publish(_) => (
+                http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Unexpected error".to_string(),
+            )
+                .into_response(),
+        }
+    }
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for UnpublishRequest {
+    type Response = UnpublishResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/unpublish").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
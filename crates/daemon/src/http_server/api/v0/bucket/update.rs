@@ -1,12 +1,14 @@
 use axum::extract::{Multipart, State};
 use axum::response::{IntoResponse, Response};
 use serde::{Deserialize, Serialize};
-use std::io::Cursor;
 use std::path::PathBuf;
 use uuid::Uuid;
 
 use common::prelude::{Link, MountError};
 
+use super::add::spool_field;
+use super::{head_moved, parse_version, stored_mime};
+use crate::audit::{Actor, AuditEvent};
 use crate::ServiceState;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,7 +24,8 @@ pub async fn handler(
 ) -> Result<impl IntoResponse, UpdateError> {
     let mut bucket_id: Option<Uuid> = None;
     let mut mount_path: Option<String> = None;
-    let mut file_data: Option<Vec<u8>> = None;
+    let mut expected_version: Option<String> = None;
+    let mut file_data = None;
 
     // Parse multipart form data
     while let Some(field) = multipart
@@ -51,15 +54,20 @@ pub async fn handler(
                         .map_err(|e| UpdateError::MultipartError(e.to_string()))?,
                 );
             }
-            "file" => {
-                file_data = Some(
+            "expected_version" => {
+                expected_version = Some(
                     field
-                        .bytes()
+                        .text()
                         .await
-                        .map_err(|e| UpdateError::MultipartError(e.to_string()))?
-                        .to_vec(),
+                        .map_err(|e| UpdateError::MultipartError(e.to_string()))?,
                 );
             }
+            "file" => {
+                let (file, _) = spool_field(field)
+                    .await
+                    .map_err(|e| UpdateError::MultipartError(e.to_string()))?;
+                file_data = Some(file);
+            }
             _ => {}
         }
     }
@@ -70,6 +78,8 @@ pub async fn handler(
         mount_path.ok_or_else(|| UpdateError::InvalidRequest("mount_path is required".into()))?;
     let file_data =
         file_data.ok_or_else(|| UpdateError::InvalidRequest("file is required".into()))?;
+    let expected_version =
+        parse_version(expected_version.as_deref()).map_err(UpdateError::InvalidRequest)?;
 
     // Validate mount path
     let mount_path_buf = PathBuf::from(&mount_path);
@@ -85,13 +95,11 @@ pub async fn handler(
         mount_path
     );
 
-    // Detect MIME type from file extension
-    let mime_type = mime_guess::from_path(&mount_path_buf)
-        .first_or_octet_stream()
-        .to_string();
-
     // Load mount at current head
-    let mut mount = state.peer().mount(bucket_id).await?;
+    let mut mount = state
+        .peer()
+        .mount_expecting(bucket_id, expected_version)
+        .await?;
 
     // Check if file exists (optional, but good for logging)
     let file_exists = mount.get(&mount_path_buf).await.is_ok();
@@ -107,13 +115,21 @@ pub async fn handler(
     }
 
     // Add the new file content
-    let reader = Cursor::new(file_data);
-    mount.add(&mount_path_buf, reader).await?;
+    mount.add(&mount_path_buf, file_data).await?;
 
     tracing::info!("UPDATE API: Added new content to {}", mount_path);
+    let mime_type = stored_mime(&mount, &mount_path_buf).await;
 
     // Save mount and update log
     let new_bucket_link = state.peer().save_mount(&mount, false).await?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "bucket.update")
+                .bucket(bucket_id)
+                .path(mount_path.clone())
+                .version(&new_bucket_link),
+        )
+        .await;
 
     tracing::info!(
         "UPDATE API: Updated {} in bucket {}, new link: {}",
@@ -155,6 +171,7 @@ impl IntoResponse for UpdateError {
                 format!("Bad request: {}", msg),
             )
                 .into_response(),
+            UpdateError::Mount(MountError::HeadMoved(head)) => head_moved(&head).into_response(),
             UpdateError::Mount(_) => (
                 http::StatusCode::INTERNAL_SERVER_ERROR,
                 "Unexpected error".to_string(),
@@ -0,0 +1,128 @@
+use axum::extract::{Json, State};
+use axum::response::{IntoResponse, Response};
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use common::prelude::Link;
+
+use crate::http_server::api::client::ApiRequest;
+use crate::http_server::error::ApiError;
+use crate::pagination::{PageError, PageRequest, SortKey, SortOrder, DEFAULT_PAGE_SIZE};
+use crate::ServiceState;
+
+/// List the versions in a bucket's log, newest first by default
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct VersionsRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Only list published versions
+    #[serde(default)]
+    #[arg(long)]
+    pub published: bool,
+
+    /// Page of the listing, 100 versions by default
+    #[serde(flatten)]
+    #[command(flatten)]
+    pub page: PageRequest,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionsResponse {
+    pub bucket_id: Uuid,
+    pub versions: Vec<VersionInfo>,
+    /// Versions matching the filters, across all pages
+    pub total: u64,
+    /// Pass as `cursor` to list the next page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionInfo {
+    /// Height of the version in the bucket log
+    pub height: u64,
+    pub link: Link,
+    pub previous_link: Option<Link>,
+    pub published: bool,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<VersionsRequest>,
+) -> Result<impl IntoResponse, VersionsError> {
+    let mut entries = state.database().get_all_bucket_logs(&req.bucket_id).await?;
+    if entries.is_empty() {
+        return Err(VersionsError::BucketNotFound(req.bucket_id));
+    }
+    if req.published {
+        entries.retain(|entry| entry.published);
+    }
+
+    // Forks of the log share a height, told apart by their link
+    let page = req.page.paginate(
+        entries,
+        "height",
+        SortOrder::Desc,
+        Some(DEFAULT_PAGE_SIZE),
+        |entry| SortKey::number(entry.height, entry.current_link.hash().to_string()),
+    )?;
+    let versions = page
+        .items
+        .into_iter()
+        .map(|entry| VersionInfo {
+            height: entry.height,
+            link: entry.current_link,
+            previous_link: entry.previous_link,
+            published: entry.published,
+            created_at: entry.created_at,
+        })
+        .collect();
+
+    Ok((
+        http::StatusCode::OK,
+        Json(VersionsResponse {
+            bucket_id: req.bucket_id,
+            versions,
+            total: page.total,
+            next_cursor: page.next_cursor,
+        }),
+    )
+        .into_response())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum VersionsError {
+    #[error("Bucket not found: {0}")]
+    BucketNotFound(Uuid),
+    #[error("Invalid page: {0}")]
+    Page(#[from] PageError),
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+impl IntoResponse for VersionsError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            VersionsError::BucketNotFound(_) => http::StatusCode::NOT_FOUND,
+            VersionsError::Page(_) => http::StatusCode::BAD_REQUEST,
+            VersionsError::Database(_) => http::StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for VersionsRequest {
+    type Response = VersionsResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/bucket/versions").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,116 @@
+use axum::body::Body;
+use axum::extract::{Query, State};
+use axum::response::{IntoResponse, Response};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::prelude::MountError;
+
+use crate::http_server::error::{is_archived, ApiError};
+use crate::zip_stream;
+use crate::ServiceState;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZipRequest {
+    /// Bucket ID to read from
+    pub bucket_id: Uuid,
+
+    /// Directory to archive, `/` for the whole bucket
+    #[serde(default = "default_path")]
+    pub path: String,
+
+    /// Optional: specific version hash to read from
+    #[serde(default)]
+    pub at: Option<String>,
+
+    /// Optional: read at no older a version than this one
+    #[serde(default)]
+    pub min_version: Option<String>,
+}
+
+fn default_path() -> String {
+    "/".to_string()
+}
+
+/// Stream a directory of the bucket as a zip archive
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Query(req): Query<ZipRequest>,
+) -> Result<Response, ZipError> {
+    let mount = if let Some(hash_str) = &req.at {
+        let hash = hash_str
+            .parse::<common::linked_data::Hash>()
+            .map_err(|e| ZipError::InvalidPath(format!("Invalid hash format: {}", e)))?;
+        let link = common::linked_data::Link::new(common::linked_data::LD_RAW_CODEC, hash);
+        common::mount::Mount::load(&link, state.peer().secret(), state.peer().blobs()).await?
+    } else {
+        let min_version = super::parse_version(req.min_version.as_deref())
+            .map_err(|e| ZipError::InvalidPath(format!("Invalid version: {}", e)))?;
+        state
+            .peer()
+            .mount_for_read_at_least(req.bucket_id, min_version)
+            .await?
+    };
+
+    let path_buf = std::path::PathBuf::from(&req.path);
+    if !path_buf.is_absolute() {
+        return Err(ZipError::InvalidPath("Path must be absolute".into()));
+    }
+    if path_buf != std::path::Path::new("/") && !mount.get(&path_buf).await?.is_dir() {
+        return Err(ZipError::InvalidPath(format!(
+            "{} is not a directory",
+            req.path
+        )));
+    }
+
+    let bucket_name = mount.inner().await.manifest().name().to_string();
+    let name = zip_stream::archive_name(&path_buf, &bucket_name);
+    let archive = zip_stream::archive(mount, &path_buf).await?;
+
+    Ok((
+        http::StatusCode::OK,
+        [
+            (axum::http::header::CONTENT_TYPE, "application/zip"),
+            (
+                axum::http::header::CONTENT_DISPOSITION,
+                &format!("attachment; filename=\"{}\"", name),
+            ),
+        ],
+        Body::from_stream(archive),
+    )
+        .into_response())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ZipError {
+    #[error("Invalid path: {0}")]
+    InvalidPath(String),
+    #[error("Mount error: {0}")]
+    Mount(#[from] MountError),
+}
+
+impl IntoResponse for ZipError {
+    fn into_response(self) -> Response {
+        match self {
+            ZipError::InvalidPath(msg) => (
+                http::StatusCode::BAD_REQUEST,
+                format!("Invalid path: {}", msg),
+            )
+                .into_response(),
+            ZipError::Mount(MountError::PathNotFound(path)) => (
+                http::StatusCode::NOT_FOUND,
+                format!("Path not found: {}", path.display()),
+            )
+                .into_response(),
+            ZipError::Mount(e @ MountError::VersionUnavailable(_)) => {
+                ApiError::from(e).into_response()
+            }
+            ZipError::Mount(e) if is_archived(&e) => ApiError::from(e).into_response(),
+            ZipError::Mount(_) => (
+                http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Unexpected error".to_string(),
+            )
+                .into_response(),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+
+use super::{display_value, ConfigError};
+use crate::http_server::api::client::ApiRequest;
+use crate::state::{AppConfig, StateError};
+use crate::ServiceState;
+
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct ConfigGetRequest {
+    /// Config key (e.g. api_port, log_level, blob_store.type)
+    pub key: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigGetResponse {
+    pub key: String,
+    pub value: String,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<ConfigGetRequest>,
+) -> Result<impl IntoResponse, ConfigError> {
+    let path = state
+        .reloader()
+        .config_path()
+        .ok_or(ConfigError::NoConfigFile)?;
+    let config = AppConfig::read(path)?;
+    let value = config
+        .get(&req.key)?
+        .ok_or_else(|| StateError::UnknownConfigKey(req.key.clone()))?;
+
+    Ok((
+        http::StatusCode::OK,
+        Json(ConfigGetResponse {
+            key: req.key,
+            value: display_value(&value),
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for ConfigGetRequest {
+    type Response = ConfigGetResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/config/get").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
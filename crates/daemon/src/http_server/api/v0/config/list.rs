@@ -0,0 +1,53 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+
+use super::{display_value, ConfigError};
+use crate::http_server::api::client::ApiRequest;
+use crate::state::AppConfig;
+use crate::ServiceState;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct ConfigListRequest {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigListResponse {
+    pub entries: Vec<ConfigEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigEntry {
+    pub key: String,
+    pub value: String,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(_req): Json<ConfigListRequest>,
+) -> Result<impl IntoResponse, ConfigError> {
+    let path = state
+        .reloader()
+        .config_path()
+        .ok_or(ConfigError::NoConfigFile)?;
+    let entries = AppConfig::read(path)?
+        .entries()?
+        .into_iter()
+        .map(|(key, value)| ConfigEntry {
+            key,
+            value: display_value(&value),
+        })
+        .collect();
+
+    Ok((http::StatusCode::OK, Json(ConfigListResponse { entries })).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for ConfigListRequest {
+    type Response = ConfigListResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/config/list").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
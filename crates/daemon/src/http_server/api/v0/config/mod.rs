@@ -0,0 +1,68 @@
+//! Daemon configuration API endpoints
+//!
+//! Reads and writes `config.toml` on behalf of clients, type-checking values
+//! before anything is written and reloading runtime settings afterwards.
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+
+use crate::http_server::error::ApiError;
+use crate::reload::ReloadError;
+use crate::state::StateError;
+use crate::ServiceState;
+
+pub mod get;
+pub mod list;
+pub mod set;
+
+pub use get::{ConfigGetRequest, ConfigGetResponse};
+pub use list::{ConfigEntry, ConfigListRequest, ConfigListResponse};
+pub use set::{ConfigSetRequest, ConfigSetResponse};
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/get", post(get::handler))
+        .route("/list", post(list::handler))
+        .route("/set", post(set::handler))
+        .with_state(state)
+}
+
+/// Render a config value for display (strings without quotes)
+pub(crate) fn display_value(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("Daemon was not started from a config file")]
+    NoConfigFile,
+    #[error("Config error: {0}")]
+    State(#[from] StateError),
+    #[error("Reload error: {0}")]
+    Reload(#[from] ReloadError),
+}
+
+impl IntoResponse for ConfigError {
+    fn into_response(self) -> Response {
+        match self {
+            ConfigError::State(StateError::UnknownConfigKey(_)) => {
+                ApiError::new(StatusCode::NOT_FOUND, self.to_string()).into_response()
+            }
+            ConfigError::NoConfigFile
+            | ConfigError::State(StateError::InvalidConfigValue { .. })
+            | ConfigError::State(StateError::InvalidLogLevel(_)) => {
+                ApiError::new(StatusCode::BAD_REQUEST, self.to_string()).into_response()
+            }
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Error: {}", self),
+            )
+                .into_response(),
+        }
+    }
+}
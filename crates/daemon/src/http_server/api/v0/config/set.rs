@@ -0,0 +1,78 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+
+use super::{display_value, ConfigError};
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::state::{AppConfig, StateError};
+use crate::ServiceState;
+
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct ConfigSetRequest {
+    /// Config key (e.g. api_port, log_level, blob_store.type)
+    pub key: String,
+
+    /// New value; parsed as a TOML literal when it matches the key's type
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigSetResponse {
+    pub key: String,
+    pub value: String,
+    /// Settings applied to the running daemon
+    pub applied: Vec<String>,
+    /// Settings that only take effect after a restart
+    pub restart_required: Vec<String>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<ConfigSetRequest>,
+) -> Result<impl IntoResponse, ConfigError> {
+    let path = state
+        .reloader()
+        .config_path()
+        .ok_or(ConfigError::NoConfigFile)?;
+
+    // Validate against the full config before touching the file
+    let config = AppConfig::read(path)?.with_value(&req.key, &req.value)?;
+    let value = config
+        .get(&req.key)?
+        .ok_or_else(|| StateError::UnknownConfigKey(req.key.clone()))?;
+    config.write(path)?;
+    tracing::info!("Config key {} set to {}", req.key, display_value(&value));
+    // Values may be credentials, so only the key is recorded
+    state
+        .audit(AuditEvent::new(Actor::Api, "config.set").detail(req.key.clone()))
+        .await;
+
+    let summary = state.reloader().reload()?;
+
+    Ok((
+        http::StatusCode::OK,
+        Json(ConfigSetResponse {
+            key: req.key,
+            value: display_value(&value),
+            applied: summary.applied.iter().map(|s| s.to_string()).collect(),
+            restart_required: summary
+                .restart_required
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for ConfigSetRequest {
+    type Response = ConfigSetResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/config/set").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,81 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::crypto::PublicKey;
+
+use super::EscrowApiError;
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Release our recovery shard of a bucket to a peer that asked for it. Check
+/// out of band that the owner really runs that peer first.
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct EscrowApproveRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Public key of the peer that asked for the shard (hex-encoded)
+    #[arg(long)]
+    pub requester: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EscrowApproveResponse {
+    pub bucket_id: Uuid,
+    pub requester: String,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<EscrowApproveRequest>,
+) -> Result<impl IntoResponse, EscrowApiError> {
+    let response = approve_release(&state, Actor::Api, req).await?;
+    Ok((http::StatusCode::OK, Json(response)).into_response())
+}
+
+/// Approve releasing our shard of a bucket to a peer that asked for it. Also
+/// used by the desktop app.
+pub async fn approve_release(
+    state: &ServiceState,
+    actor: Actor,
+    req: EscrowApproveRequest,
+) -> Result<EscrowApproveResponse, EscrowApiError> {
+    let requester = PublicKey::from_hex(&req.requester)
+        .map_err(|e| EscrowApiError::InvalidPublicKey(e.to_string()))?;
+    if !state.peer().escrow().approve(&req.bucket_id, &requester) {
+        return Err(EscrowApiError::RequestNotFound(req.bucket_id));
+    }
+
+    tracing::info!(
+        "ESCROW API: Approved release of the shard of bucket {} to {}",
+        req.bucket_id,
+        requester.to_hex()
+    );
+    state
+        .audit(
+            AuditEvent::new(actor, "escrow.approve")
+                .bucket(req.bucket_id)
+                .detail(requester.to_hex()),
+        )
+        .await;
+
+    Ok(EscrowApproveResponse {
+        bucket_id: req.bucket_id,
+        requester: requester.to_hex(),
+    })
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for EscrowApproveRequest {
+    type Response = EscrowApproveResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/escrow/approve").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
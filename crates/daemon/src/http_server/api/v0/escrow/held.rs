@@ -0,0 +1,83 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::EscrowApiError;
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// List the recovery shards we hold for other owners, and the requests to
+/// release them
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct EscrowHeldRequest {}
+
+/// A shard we hold, without the shard itself
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeldShardInfo {
+    pub bucket_id: Uuid,
+    /// Owner that set recovery up
+    pub owner: String,
+    /// Recovery identity the shard is part of
+    pub recovery_key: String,
+    /// Trustees needed to recover the bucket
+    pub threshold: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseRequestInfo {
+    pub bucket_id: Uuid,
+    /// Identity asking for our shard
+    pub requester: String,
+    pub approved: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EscrowHeldResponse {
+    pub shards: Vec<HeldShardInfo>,
+    pub requests: Vec<ReleaseRequestInfo>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(_req): Json<EscrowHeldRequest>,
+) -> Result<impl IntoResponse, EscrowApiError> {
+    Ok((http::StatusCode::OK, Json(held(&state))).into_response())
+}
+
+/// The shards we hold and the requests to release them, without the shards
+/// themselves. Also used by the desktop app.
+pub fn held(state: &ServiceState) -> EscrowHeldResponse {
+    let escrow = state.peer().escrow();
+    let shards = escrow
+        .held()
+        .into_iter()
+        .map(|held| HeldShardInfo {
+            bucket_id: held.bucket_id,
+            owner: held.owner.to_hex(),
+            recovery_key: held.recovery_key.to_hex(),
+            threshold: held.threshold,
+        })
+        .collect();
+    let requests = escrow
+        .requests()
+        .into_iter()
+        .map(|request| ReleaseRequestInfo {
+            bucket_id: request.bucket_id,
+            requester: request.requester.to_hex(),
+            approved: request.approved,
+        })
+        .collect();
+    EscrowHeldResponse { shards, requests }
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for EscrowHeldRequest {
+    type Response = EscrowHeldResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/escrow/held").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,85 @@
+//! Social recovery of bucket access
+//!
+//! An owner sets up recovery of a bucket with trusted peers (trustees), each
+//! of which holds a shard of a recovery identity added to the bucket as an
+//! owner (see [`common::peer::escrow`]). If the owner loses its key, a new
+//! node asks the trustees for their shards; each trustee's user approves the
+//! request once they've checked, out of band, who is asking. With enough
+//! shards the new node rebuilds the recovery identity and becomes an owner.
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use uuid::Uuid;
+
+use common::crypto::PublicKey;
+use common::mount::MountError;
+use common::peer::EscrowError;
+
+use crate::http_server::error::ApiError;
+use crate::ServiceState;
+
+pub mod approve;
+pub mod held;
+pub mod recover;
+pub mod setup;
+
+pub use approve::{approve_release, EscrowApproveRequest, EscrowApproveResponse};
+pub use held::{held, EscrowHeldRequest, EscrowHeldResponse, HeldShardInfo, ReleaseRequestInfo};
+pub use recover::{recover_bucket, EscrowRecoverRequest, EscrowRecoverResponse, RecoveryState};
+pub use setup::{set_up_recovery, EscrowSetupRequest, EscrowSetupResponse};
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/setup", post(setup::handler))
+        .route("/held", post(held::handler))
+        .route("/approve", post(approve::handler))
+        .route("/recover", post(recover::handler))
+        .with_state(state)
+}
+
+/// Parse hex public keys of peers
+pub(crate) fn parse_keys(keys: &[String]) -> Result<Vec<PublicKey>, EscrowApiError> {
+    keys.iter()
+        .map(|key| {
+            PublicKey::from_hex(key).map_err(|e| EscrowApiError::InvalidPublicKey(e.to_string()))
+        })
+        .collect()
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum EscrowApiError {
+    #[error("Invalid public key: {0}")]
+    InvalidPublicKey(String),
+    #[error("No release of bucket {0} was requested by that peer")]
+    RequestNotFound(Uuid),
+    #[error("{0}")]
+    Escrow(#[from] EscrowError),
+    #[error("Mount error: {0}")]
+    Mount(Box<MountError>),
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+impl From<MountError> for EscrowApiError {
+    fn from(e: MountError) -> Self {
+        EscrowApiError::Mount(Box::new(e))
+    }
+}
+
+impl IntoResponse for EscrowApiError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            EscrowApiError::InvalidPublicKey(_)
+            | EscrowApiError::Escrow(EscrowError::InvalidTrustees(_))
+            | EscrowApiError::Escrow(EscrowError::Shamir(_)) => StatusCode::BAD_REQUEST,
+            EscrowApiError::Escrow(EscrowError::NotOwner(_)) => StatusCode::FORBIDDEN,
+            EscrowApiError::RequestNotFound(_) => StatusCode::NOT_FOUND,
+            EscrowApiError::Escrow(EscrowError::TrusteeFailed(_, _)) => StatusCode::BAD_GATEWAY,
+            EscrowApiError::Escrow(EscrowError::BadShards) => StatusCode::CONFLICT,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
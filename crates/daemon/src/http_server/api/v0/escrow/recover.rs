@@ -0,0 +1,188 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::bucket_log::BucketLogProvider;
+use common::mount::{Mount, PrincipalRole};
+use common::peer::Recovery;
+
+use super::{parse_keys, EscrowApiError};
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Recover access to a bucket whose owner lost its key, from the shards its
+/// trustees release to this node
+///
+/// Call it again until the bucket is recovered: trustees release their shard
+/// once their user approves, and the bucket syncs in the background.
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct EscrowRecoverRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Public key of a trustee of the bucket (hex-encoded); repeat for each
+    #[arg(long = "trustee", required = true)]
+    pub trustees: Vec<String>,
+}
+
+/// How far a recovery got
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RecoveryState {
+    /// Too few trustees released their shard so far
+    Waiting,
+    /// The recovery identity is rebuilt, the bucket is syncing
+    Syncing,
+    /// This node is an owner of the bucket
+    Recovered,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EscrowRecoverResponse {
+    pub bucket_id: Uuid,
+    pub state: RecoveryState,
+    /// Trustees that released their shard, while waiting
+    #[serde(default)]
+    pub released: Vec<String>,
+    /// Trustees whose user has yet to approve, while waiting
+    #[serde(default)]
+    pub pending: Vec<String>,
+    /// Trustees needed, once one of them released its shard
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<u8>,
+    /// New head of the bucket, once recovered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_bucket_link: Option<String>,
+}
+
+impl EscrowRecoverResponse {
+    fn new(bucket_id: Uuid, state: RecoveryState) -> Self {
+        Self {
+            bucket_id,
+            state,
+            released: Vec::new(),
+            pending: Vec::new(),
+            threshold: None,
+            new_bucket_link: None,
+        }
+    }
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<EscrowRecoverRequest>,
+) -> Result<impl IntoResponse, EscrowApiError> {
+    let response = recover_bucket(&state, Actor::Api, req).await?;
+    Ok((http::StatusCode::OK, Json(response)).into_response())
+}
+
+/// Take a recovery one step further. Also used by the desktop app.
+pub async fn recover_bucket(
+    state: &ServiceState,
+    actor: Actor,
+    req: EscrowRecoverRequest,
+) -> Result<EscrowRecoverResponse, EscrowApiError> {
+    let bucket_id = req.bucket_id;
+    let trustees = parse_keys(&req.trustees)?;
+    let us = state.peer().secret().public();
+    let (recovery_key, owner, peer_ids) =
+        match state.peer().recover_escrow(bucket_id, &trustees).await? {
+            Recovery::Waiting {
+                released,
+                pending,
+                threshold,
+            } => {
+                return Ok(EscrowRecoverResponse {
+                    released: released.iter().map(|key| key.to_hex()).collect(),
+                    pending: pending.iter().map(|key| key.to_hex()).collect(),
+                    threshold,
+                    ..EscrowRecoverResponse::new(bucket_id, RecoveryState::Waiting)
+                })
+            }
+            Recovery::Recovered {
+                recovery_key,
+                owner,
+                peer_ids,
+            } => (recovery_key, owner, peer_ids),
+        };
+
+    // Follow the bucket until we have it, preferably from a peer other than
+    // the lost owner
+    let head = match state.peer().logs().head(bucket_id, None).await {
+        Ok((head, _)) => head,
+        Err(_) => {
+            if state.peer().following(&bucket_id).is_none() {
+                let source = peer_ids
+                    .iter()
+                    .find(|peer| **peer != owner && **peer != us)
+                    .copied()
+                    .unwrap_or(owner);
+                tracing::info!(
+                    "ESCROW API: Following bucket {} from {} to recover it",
+                    bucket_id,
+                    source.to_hex()
+                );
+                state.follow_bucket(bucket_id, source).await?;
+            }
+            return Ok(EscrowRecoverResponse::new(
+                bucket_id,
+                RecoveryState::Syncing,
+            ));
+        }
+    };
+    let mut mount = match Mount::load(&head, &recovery_key, state.peer().blobs()).await {
+        Ok(mount) => mount,
+        Err(e) => {
+            tracing::debug!("Bucket {} is not synced yet: {}", bucket_id, e);
+            return Ok(EscrowRecoverResponse::new(
+                bucket_id,
+                RecoveryState::Syncing,
+            ));
+        }
+    };
+
+    let is_owner = mount
+        .inner()
+        .await
+        .manifest()
+        .get_share(&us)
+        .is_some_and(|share| *share.role() == PrincipalRole::Owner);
+    let new_bucket_link = if is_owner {
+        None
+    } else {
+        // Saved as the recovery identity, an owner of the bucket
+        mount.add_owner(us).await?;
+        state.peer().negotiate_shares(&mut mount).await?;
+        let link = state.peer().save_mount(&mount, false).await?;
+        state
+            .audit(
+                AuditEvent::new(actor, "escrow.recover")
+                    .bucket(bucket_id)
+                    .detail(format!("lost owner {}", owner.to_hex()))
+                    .version(&link),
+            )
+            .await;
+        tracing::info!("ESCROW API: Recovered bucket {}", bucket_id);
+        Some(link.hash().to_string())
+    };
+    state.unfollow_bucket(bucket_id).await?;
+
+    Ok(EscrowRecoverResponse {
+        new_bucket_link,
+        ..EscrowRecoverResponse::new(bucket_id, RecoveryState::Recovered)
+    })
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for EscrowRecoverRequest {
+    type Response = EscrowRecoverResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/escrow/recover").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
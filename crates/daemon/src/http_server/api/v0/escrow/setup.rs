@@ -0,0 +1,99 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{parse_keys, EscrowApiError};
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Set up recovery of a bucket with trusted peers
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct EscrowSetupRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Public key of a trusted peer to hold a shard (hex-encoded); repeat
+    /// for each trustee
+    #[arg(long = "trustee", required = true)]
+    pub trustees: Vec<String>,
+
+    /// Trustees needed to recover the bucket
+    #[arg(long)]
+    pub threshold: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EscrowSetupResponse {
+    pub bucket_id: Uuid,
+    /// Public key of the recovery identity added as an owner
+    pub recovery_key: String,
+    pub threshold: u8,
+    pub trustees: Vec<String>,
+    /// New head of the bucket
+    pub new_bucket_link: String,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<EscrowSetupRequest>,
+) -> Result<impl IntoResponse, EscrowApiError> {
+    let response = set_up_recovery(&state, Actor::Api, req).await?;
+    Ok((http::StatusCode::OK, Json(response)).into_response())
+}
+
+/// Set up recovery of a bucket and save it. Also used by the desktop app.
+pub async fn set_up_recovery(
+    state: &ServiceState,
+    actor: Actor,
+    req: EscrowSetupRequest,
+) -> Result<EscrowSetupResponse, EscrowApiError> {
+    let trustees = parse_keys(&req.trustees)?;
+    let mut mount = state.peer().mount_expecting(req.bucket_id, None).await?;
+    let setup = state
+        .peer()
+        .set_up_escrow(&mut mount, trustees, req.threshold)
+        .await?;
+    let new_bucket_link = state.peer().save_mount(&mount, false).await?;
+
+    tracing::info!(
+        "ESCROW API: Recovery of bucket {} set up with {} of {} trustees",
+        req.bucket_id,
+        setup.threshold,
+        setup.trustees.len()
+    );
+    state
+        .audit(
+            AuditEvent::new(actor, "escrow.setup")
+                .bucket(req.bucket_id)
+                .detail(format!(
+                    "{} of {} trustees, recovery key {}",
+                    setup.threshold,
+                    setup.trustees.len(),
+                    setup.recovery_key.to_hex()
+                ))
+                .version(&new_bucket_link),
+        )
+        .await;
+
+    Ok(EscrowSetupResponse {
+        bucket_id: req.bucket_id,
+        recovery_key: setup.recovery_key.to_hex(),
+        threshold: setup.threshold,
+        trustees: setup.trustees.iter().map(|key| key.to_hex()).collect(),
+        new_bucket_link: new_bucket_link.hash().to_string(),
+    })
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for EscrowSetupRequest {
+    type Response = EscrowSetupResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/escrow/setup").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,49 @@
+//! Full data export endpoints
+//!
+//! An export writes every bucket this node can access, with its metadata,
+//! into a directory on the daemon's machine in the background; see
+//! [`crate::data_export`] for the layout.
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use uuid::Uuid;
+
+use crate::data_export::DataExportError;
+use crate::http_server::error::ApiError;
+use crate::ServiceState;
+
+pub mod start;
+pub mod status;
+
+pub use start::ExportStartRequest;
+pub use status::ExportStatusRequest;
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/start", post(start::handler))
+        .route("/status", post(status::handler))
+        .with_state(state)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ExportApiError {
+    #[error("Unknown export: {0}")]
+    UnknownExport(Uuid),
+    #[error("{0}")]
+    Export(#[from] DataExportError),
+}
+
+impl IntoResponse for ExportApiError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            ExportApiError::UnknownExport(_) => StatusCode::NOT_FOUND,
+            ExportApiError::Export(
+                DataExportError::RelativeOutput(_) | DataExportError::OutputNotEmpty(_),
+            ) => StatusCode::BAD_REQUEST,
+            ExportApiError::Export(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
@@ -0,0 +1,44 @@
+use std::path::PathBuf;
+
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+
+use super::ExportApiError;
+use crate::data_export::ExportProgress;
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Start exporting every bucket this node can access into a directory on the
+/// daemon's machine. Follow it with `/api/v0/export/status`.
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct ExportStartRequest {
+    /// New or empty directory to export into (absolute path)
+    #[arg(long)]
+    pub output_dir: PathBuf,
+
+    /// Export the encrypted blobs as stored instead of decrypted files
+    #[arg(long)]
+    #[serde(default)]
+    pub encrypted: bool,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<ExportStartRequest>,
+) -> Result<impl IntoResponse, ExportApiError> {
+    let progress = state.exports().start(req.output_dir, req.encrypted).await?;
+
+    Ok((http::StatusCode::OK, Json(progress)).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for ExportStartRequest {
+    type Response = ExportProgress;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/export/start").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,40 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::ExportApiError;
+use crate::data_export::ExportProgress;
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Progress of an export started since the daemon started
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct ExportStatusRequest {
+    /// Export ID
+    #[arg(long)]
+    pub export_id: Uuid,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<ExportStatusRequest>,
+) -> Result<impl IntoResponse, ExportApiError> {
+    let progress = state
+        .exports()
+        .get(&req.export_id)
+        .ok_or(ExportApiError::UnknownExport(req.export_id))?;
+
+    Ok((http::StatusCode::OK, Json(progress)).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for ExportStatusRequest {
+    type Response = ExportProgress;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/export/status").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,74 @@
+use std::path::Path;
+
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{favorite_path, FavoriteApiError};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Star a bucket, or a file or directory in it
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct FavoriteAddRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Path to star; the bucket itself if not given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FavoriteAddResponse {
+    pub bucket_id: Uuid,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// False if it was already starred
+    pub added: bool,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<FavoriteAddRequest>,
+) -> Result<impl IntoResponse, FavoriteApiError> {
+    let path = favorite_path(&state, req.bucket_id, req.path.as_deref()).await?;
+
+    // Only paths in the current version can be starred
+    if let Some(path) = &path {
+        let mount = state.peer().mount_for_read(req.bucket_id).await?;
+        mount
+            .get(Path::new(path))
+            .await
+            .map_err(|e| FavoriteApiError::from_lookup(path, e))?;
+    }
+
+    let added = state
+        .database()
+        .add_favorite(&req.bucket_id, path.as_deref())
+        .await?;
+
+    Ok((
+        http::StatusCode::OK,
+        Json(FavoriteAddResponse {
+            bucket_id: req.bucket_id,
+            path,
+            added,
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for FavoriteAddRequest {
+    type Response = FavoriteAddResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/favorite/add").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
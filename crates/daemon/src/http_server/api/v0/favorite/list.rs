@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{FavoriteApiError, FavoriteInfo};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// List starred buckets and paths, most recently starred first
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct FavoriteListRequest {
+    /// Only list favorites of this bucket
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub bucket_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FavoriteListResponse {
+    pub favorites: Vec<FavoriteInfo>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<FavoriteListRequest>,
+) -> Result<impl IntoResponse, FavoriteApiError> {
+    let names: HashMap<Uuid, String> = state
+        .database()
+        .list_buckets(None, Some(1000))
+        .await?
+        .into_iter()
+        .map(|bucket| (bucket.id, bucket.name))
+        .collect();
+
+    let favorites = state
+        .database()
+        .list_favorites(req.bucket_id.as_ref())
+        .await?
+        .into_iter()
+        .map(|favorite| FavoriteInfo {
+            bucket_name: names.get(&favorite.bucket_id).cloned(),
+            bucket_id: favorite.bucket_id,
+            path: favorite.path,
+            created_at: favorite.created_at,
+        })
+        .collect();
+
+    Ok((
+        http::StatusCode::OK,
+        Json(FavoriteListResponse { favorites }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for FavoriteListRequest {
+    type Response = FavoriteListResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/favorite/list").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
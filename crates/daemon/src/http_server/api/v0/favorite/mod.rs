@@ -0,0 +1,118 @@
+//! Favorites: starred buckets and paths
+//!
+//! Favorites are kept in the daemon database, so each profile has its own.
+//! A favorite without a path stars the bucket itself. Bucket and path
+//! listings flag what is starred (`starred` in their responses) and can be
+//! limited to it.
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use common::bucket_log::BucketLogProvider;
+use common::mount::MountError;
+
+use crate::deploy::normalize_path;
+use crate::http_server::error::ApiError;
+use crate::ServiceState;
+
+pub mod add;
+pub mod list;
+pub mod remove;
+
+pub use add::FavoriteAddRequest;
+pub use list::{FavoriteListRequest, FavoriteListResponse};
+pub use remove::FavoriteRemoveRequest;
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/add", post(add::handler))
+        .route("/remove", post(remove::handler))
+        .route("/list", post(list::handler))
+        .with_state(state)
+}
+
+/// A starred bucket, or a starred path in one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FavoriteInfo {
+    pub bucket_id: Uuid,
+    /// Current name of the bucket, None if it's no longer known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bucket_name: Option<String>,
+    /// Starred path, None when the bucket itself is starred
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+/// Check that a bucket is known and normalize the path to star in it, None
+/// for the bucket itself
+pub(crate) async fn favorite_path(
+    state: &ServiceState,
+    bucket_id: Uuid,
+    path: Option<&str>,
+) -> Result<Option<String>, FavoriteApiError> {
+    if !state
+        .peer()
+        .logs()
+        .exists(bucket_id)
+        .await
+        .map_err(|e| FavoriteApiError::BucketLog(e.to_string()))?
+    {
+        return Err(FavoriteApiError::BucketNotFound(bucket_id));
+    }
+    let Some(path) = path else {
+        return Ok(None);
+    };
+    let normalized =
+        normalize_path(path).ok_or_else(|| FavoriteApiError::InvalidPath(path.to_string()))?;
+    Ok((normalized != "/").then_some(normalized))
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum FavoriteApiError {
+    #[error("Bucket not found: {0}")]
+    BucketNotFound(Uuid),
+    #[error("Invalid path: {0}")]
+    InvalidPath(String),
+    #[error("Path not found: {0}")]
+    PathNotFound(String),
+    #[error("Not starred")]
+    NotStarred,
+    #[error("Bucket log error: {0}")]
+    BucketLog(String),
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Mount error: {0}")]
+    Mount(#[from] MountError),
+}
+
+impl FavoriteApiError {
+    /// The error for a path that couldn't be looked up in a bucket
+    pub(crate) fn from_lookup(path: &str, e: MountError) -> Self {
+        match e {
+            MountError::PathNotFound(_) | MountError::PathNotNode(_) => {
+                FavoriteApiError::PathNotFound(path.to_string())
+            }
+            e => FavoriteApiError::Mount(e),
+        }
+    }
+}
+
+impl IntoResponse for FavoriteApiError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            FavoriteApiError::BucketNotFound(_)
+            | FavoriteApiError::PathNotFound(_)
+            | FavoriteApiError::NotStarred => StatusCode::NOT_FOUND,
+            FavoriteApiError::InvalidPath(_) => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
@@ -0,0 +1,62 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{favorite_path, FavoriteApiError};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Unstar a bucket, or a file or directory in it
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct FavoriteRemoveRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Path to unstar; the bucket itself if not given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FavoriteRemoveResponse {
+    pub bucket_id: Uuid,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<FavoriteRemoveRequest>,
+) -> Result<impl IntoResponse, FavoriteApiError> {
+    let path = favorite_path(&state, req.bucket_id, req.path.as_deref()).await?;
+    if !state
+        .database()
+        .remove_favorite(&req.bucket_id, path.as_deref())
+        .await?
+    {
+        return Err(FavoriteApiError::NotStarred);
+    }
+
+    Ok((
+        http::StatusCode::OK,
+        Json(FavoriteRemoveResponse {
+            bucket_id: req.bucket_id,
+            path,
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for FavoriteRemoveRequest {
+    type Response = FavoriteRemoveResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/favorite/remove").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,44 @@
+//! Gateway serving API endpoints
+//!
+//! The mode decides which buckets the gateway serves at all; per-bucket
+//! policies under `/policy` allow buckets in allowlist mode, turn off
+//! directory listings and cap daily bandwidth (see [`crate::gateway_policy`]).
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+
+use crate::gateway_policy::GatewayPolicyError;
+use crate::http_server::error::ApiError;
+use crate::ServiceState;
+
+pub mod mode;
+pub mod policy;
+
+pub use mode::{GatewayModeRequest, GatewayModeResponse};
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/mode", post(mode::handler))
+        .nest("/policy", policy::router(state.clone()))
+        .with_state(state)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GatewayApiError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Invalid gateway policy: {0}")]
+    Policy(#[from] GatewayPolicyError),
+}
+
+impl IntoResponse for GatewayApiError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            GatewayApiError::Policy(_) => StatusCode::BAD_REQUEST,
+            GatewayApiError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
@@ -0,0 +1,54 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+
+use super::GatewayApiError;
+use crate::audit::{Actor, AuditEvent};
+use crate::gateway_policy::GatewayMode;
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Show or change which buckets the gateway serves
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct GatewayModeRequest {
+    /// New mode; shows the current one if left out
+    #[serde(default)]
+    pub mode: Option<GatewayMode>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayModeResponse {
+    pub mode: GatewayMode,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<GatewayModeRequest>,
+) -> Result<impl IntoResponse, GatewayApiError> {
+    if let Some(mode) = req.mode {
+        tracing::info!("Gateway mode set to {} via API", mode);
+        state.set_gateway_mode(mode).await?;
+        state
+            .audit(AuditEvent::new(Actor::Api, "gateway.mode").detail(mode.to_string()))
+            .await;
+    }
+
+    Ok((
+        http::StatusCode::OK,
+        Json(GatewayModeResponse {
+            mode: state.gateway_policies().mode(),
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for GatewayModeRequest {
+    type Response = GatewayModeResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/gateway/mode").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,64 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::gateway_policy::{GatewayMode, GatewayPolicy};
+use crate::http_server::api::client::ApiRequest;
+use crate::http_server::api::v0::gateway::GatewayApiError;
+use crate::ServiceState;
+
+/// Show a bucket's gateway policy
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct GatewayPolicyGetRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayPolicyResponse {
+    pub bucket_id: Uuid,
+    pub policy: GatewayPolicy,
+    /// Current gateway mode
+    pub mode: GatewayMode,
+    /// Whether the gateway serves the bucket under the current mode
+    pub served: bool,
+    /// Bytes served for the bucket today (UTC)
+    pub bytes_served_today: u64,
+}
+
+impl GatewayPolicyResponse {
+    pub(crate) fn from_state(state: &ServiceState, bucket_id: Uuid) -> Self {
+        let policies = state.gateway_policies();
+        Self {
+            bucket_id,
+            policy: policies.get(&bucket_id),
+            mode: policies.mode(),
+            served: policies.serves(&bucket_id),
+            bytes_served_today: policies.bytes_served_today(&bucket_id),
+        }
+    }
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<GatewayPolicyGetRequest>,
+) -> Result<impl IntoResponse, GatewayApiError> {
+    Ok((
+        http::StatusCode::OK,
+        Json(GatewayPolicyResponse::from_state(&state, req.bucket_id)),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for GatewayPolicyGetRequest {
+    type Response = GatewayPolicyResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/gateway/policy/get").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,51 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+
+use super::GatewayPolicyResponse;
+use crate::gateway_policy::GatewayMode;
+use crate::http_server::api::client::ApiRequest;
+use crate::http_server::api::v0::gateway::GatewayApiError;
+use crate::ServiceState;
+
+/// List buckets with a gateway policy set
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct GatewayPolicyListRequest {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayPolicyListResponse {
+    pub mode: GatewayMode,
+    pub policies: Vec<GatewayPolicyResponse>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(_req): Json<GatewayPolicyListRequest>,
+) -> Result<impl IntoResponse, GatewayApiError> {
+    let mut bucket_ids: Vec<_> = state.gateway_policies().all().into_keys().collect();
+    bucket_ids.sort();
+    let policies = bucket_ids
+        .into_iter()
+        .map(|bucket_id| GatewayPolicyResponse::from_state(&state, bucket_id))
+        .collect();
+
+    Ok((
+        http::StatusCode::OK,
+        Json(GatewayPolicyListResponse {
+            mode: state.gateway_policies().mode(),
+            policies,
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for GatewayPolicyListRequest {
+    type Response = GatewayPolicyListResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/gateway/policy/list").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,25 @@
+//! Per-bucket gateway policy endpoints (see [`crate::gateway_policy`])
+
+use axum::routing::post;
+use axum::Router;
+
+use crate::ServiceState;
+
+pub mod get;
+pub mod list;
+pub mod reset;
+pub mod set;
+
+pub use get::{GatewayPolicyGetRequest, GatewayPolicyResponse};
+pub use list::{GatewayPolicyListRequest, GatewayPolicyListResponse};
+pub use reset::{GatewayPolicyResetRequest, GatewayPolicyResetResponse};
+pub use set::GatewayPolicySetRequest;
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/get", post(get::handler))
+        .route("/list", post(list::handler))
+        .route("/reset", post(reset::handler))
+        .route("/set", post(set::handler))
+        .with_state(state)
+}
@@ -0,0 +1,79 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::GatewayPolicyResponse;
+use crate::audit::{Actor, AuditEvent};
+use crate::gateway_policy::GatewayPolicy;
+use crate::http_server::api::client::ApiRequest;
+use crate::http_server::api::v0::gateway::GatewayApiError;
+use crate::ServiceState;
+
+/// Replace a bucket's gateway policy. Options left out fall back to the defaults.
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct GatewayPolicySetRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Serve the bucket when the gateway only serves allowlisted buckets
+    #[arg(long)]
+    #[serde(default)]
+    pub allow: bool,
+
+    /// Don't show directory listings (index files are still served)
+    #[arg(long)]
+    #[serde(default)]
+    pub no_listings: bool,
+
+    /// Ask search engines not to index the bucket (X-Robots-Tag: noindex)
+    #[arg(long)]
+    #[serde(default)]
+    pub noindex: bool,
+
+    /// Serve the last fully synced version while a newer one is syncing
+    #[arg(long)]
+    #[serde(default)]
+    pub serve_stale: bool,
+
+    /// Bytes served for the bucket per day (UTC) before requests are refused
+    #[arg(long = "daily-bandwidth")]
+    #[serde(default)]
+    pub daily_bandwidth_bytes: Option<u64>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<GatewayPolicySetRequest>,
+) -> Result<impl IntoResponse, GatewayApiError> {
+    let policy = GatewayPolicy {
+        allowed: req.allow,
+        listings: !req.no_listings,
+        noindex: req.noindex,
+        serve_stale: req.serve_stale,
+        daily_bandwidth_bytes: req.daily_bandwidth_bytes,
+    };
+    policy.validate()?;
+    state.set_gateway_policy(req.bucket_id, policy).await?;
+    state
+        .audit(AuditEvent::new(Actor::Api, "gateway.policy.set").bucket(req.bucket_id))
+        .await;
+
+    Ok((
+        http::StatusCode::OK,
+        Json(GatewayPolicyResponse::from_state(&state, req.bucket_id)),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for GatewayPolicySetRequest {
+    type Response = GatewayPolicyResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/gateway/policy/set").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
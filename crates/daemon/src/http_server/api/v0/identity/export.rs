@@ -0,0 +1,67 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use base64::Engine;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+
+use super::IdentityApiError;
+use crate::http_server::api::client::ApiRequest;
+use crate::identity::IdentityBundle;
+use crate::ServiceState;
+
+/// Shortest passphrase accepted for a bundle
+pub const MIN_PASSPHRASE_LEN: usize = 8;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdentityExportRequest {
+    /// Passphrase the bundle is encrypted with
+    pub passphrase: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdentityExportResponse {
+    /// Base64-encoded encrypted bundle
+    pub bundle: String,
+    pub node_id: String,
+    /// Number of buckets listed in the bundle
+    pub buckets: usize,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<IdentityExportRequest>,
+) -> Result<impl IntoResponse, IdentityApiError> {
+    if req.passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(IdentityApiError::WeakPassphrase);
+    }
+
+    let bundle = IdentityBundle::collect(&state).await?;
+    let buckets = bundle.buckets.len();
+    let node_id = bundle.node_id()?.to_hex();
+
+    // Key derivation is deliberately slow; keep it off the async workers
+    let passphrase = req.passphrase;
+    let encrypted = tokio::task::spawn_blocking(move || bundle.encrypt(&passphrase))
+        .await
+        .map_err(|e| crate::identity::IdentityError::Io(std::io::Error::other(e)))??;
+
+    Ok((
+        http::StatusCode::OK,
+        Json(IdentityExportResponse {
+            bundle: base64::engine::general_purpose::STANDARD.encode(encrypted),
+            node_id,
+            buckets,
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for IdentityExportRequest {
+    type Response = IdentityExportResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/identity/export").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
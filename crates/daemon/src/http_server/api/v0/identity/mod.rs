@@ -0,0 +1,42 @@
+//! Identity API endpoints
+//!
+//! Exports the node identity as a passphrase-encrypted bundle (see
+//! [`crate::identity`]). Importing happens before a daemon exists, so it is
+//! done locally by `jax identity import` rather than over the API.
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+
+use crate::http_server::error::ApiError;
+use crate::identity::IdentityError;
+use crate::ServiceState;
+
+pub mod export;
+
+pub use export::{IdentityExportRequest, IdentityExportResponse, MIN_PASSPHRASE_LEN};
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/export", post(export::handler))
+        .with_state(state)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum IdentityApiError {
+    #[error("Passphrase must be at least {MIN_PASSPHRASE_LEN} characters")]
+    WeakPassphrase,
+    #[error("Identity error: {0}")]
+    Identity(#[from] IdentityError),
+}
+
+impl IntoResponse for IdentityApiError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            IdentityApiError::WeakPassphrase => StatusCode::BAD_REQUEST,
+            IdentityApiError::Identity(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
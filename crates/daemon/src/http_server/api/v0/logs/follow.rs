@@ -0,0 +1,102 @@
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::time::Duration;
+
+use axum::extract::{Query, State};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::IntoResponse;
+use futures::Stream;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+
+use super::{parse_filter, LogsError};
+use crate::logs::{LogEntry, LogFilter, LogTail};
+use crate::ServiceState;
+
+/// How often the log files are checked for new lines
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LogFollowRequest {
+    /// Minimum level to include (error, warn, info, debug, trace)
+    pub level: Option<String>,
+    /// Only entries containing this text (case-insensitive)
+    pub contains: Option<String>,
+}
+
+impl LogFollowRequest {
+    /// Build the streaming request; the response body is a `text/event-stream`
+    /// where each `data:` line is a JSON [`LogEntry`].
+    pub fn build_request(&self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/logs/follow").unwrap();
+        client.get(full_url).query(self)
+    }
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Query(req): Query<LogFollowRequest>,
+) -> Result<impl IntoResponse, LogsError> {
+    let log_dir = state.log_dir().ok_or(LogsError::NoLogDir)?;
+    let filter = parse_filter(req.level.as_deref(), None, None, req.contains.as_deref())?;
+    let tail = LogTail::new(log_dir)?;
+
+    let stream = follow_stream(tail, filter, state.shutdown_signal());
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+struct Follow {
+    tail: LogTail,
+    filter: LogFilter,
+    pending: VecDeque<LogEntry>,
+    shutdown: Option<watch::Receiver<()>>,
+}
+
+fn follow_stream(
+    tail: LogTail,
+    filter: LogFilter,
+    shutdown: Option<watch::Receiver<()>>,
+) -> impl Stream<Item = Result<Event, Infallible>> {
+    let follow = Follow {
+        tail,
+        filter,
+        pending: VecDeque::new(),
+        shutdown,
+    };
+
+    futures::stream::unfold(follow, |mut follow| async move {
+        loop {
+            if let Some(entry) = follow.pending.pop_front() {
+                let event = Event::default()
+                    .json_data(&entry)
+                    .unwrap_or_else(|_| Event::default().comment("unserializable entry"));
+                return Some((Ok(event), follow));
+            }
+
+            // End the stream when the daemon shuts down
+            match follow.shutdown.as_mut() {
+                Some(shutdown) => {
+                    tokio::select! {
+                        _ = tokio::time::sleep(POLL_INTERVAL) => {}
+                        _ = shutdown.changed() => return None,
+                    }
+                }
+                None => tokio::time::sleep(POLL_INTERVAL).await,
+            }
+
+            match follow.tail.poll() {
+                Ok(entries) => {
+                    let filter = &follow.filter;
+                    follow
+                        .pending
+                        .extend(entries.into_iter().filter(|entry| filter.matches(entry)));
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to read log files: {}", e);
+                    return None;
+                }
+            }
+        }
+    })
+}
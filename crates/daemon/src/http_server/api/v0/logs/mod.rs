@@ -0,0 +1,75 @@
+//! Daemon log API endpoints
+//!
+//! Serves the structured log files written by the daemon: `query` searches
+//! history, `follow` streams new entries as server-sent events.
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::Router;
+use time::OffsetDateTime;
+
+use crate::http_server::error::ApiError;
+use crate::logs::{self, LogFilter};
+use crate::ServiceState;
+
+pub mod follow;
+pub mod query;
+
+pub use follow::LogFollowRequest;
+pub use query::{run_query, LogQueryRequest, LogQueryResponse};
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/query", post(query::handler))
+        .route("/follow", get(follow::handler))
+        .with_state(state)
+}
+
+/// Build a filter from request parameters
+pub(crate) fn parse_filter(
+    level: Option<&str>,
+    since: Option<&str>,
+    until: Option<&str>,
+    contains: Option<&str>,
+) -> Result<LogFilter, LogsError> {
+    let now = OffsetDateTime::now_utc();
+    let parse_time =
+        |s: &str| logs::parse_time(s, now).ok_or_else(|| LogsError::InvalidTime(s.to_string()));
+
+    Ok(LogFilter {
+        level: level
+            .map(|level| {
+                level
+                    .parse::<tracing::Level>()
+                    .map_err(|_| LogsError::InvalidLevel(level.to_string()))
+            })
+            .transpose()?,
+        since: since.map(parse_time).transpose()?,
+        until: until.map(parse_time).transpose()?,
+        contains: contains.filter(|s| !s.is_empty()).map(str::to_string),
+    })
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum LogsError {
+    #[error("Daemon is not logging to a directory")]
+    NoLogDir,
+    #[error("Invalid log level: {0}")]
+    InvalidLevel(String),
+    #[error("Invalid time: {0} (expected RFC 3339 or a relative time like 10m, 2h, 1d)")]
+    InvalidTime(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl IntoResponse for LogsError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            LogsError::NoLogDir => StatusCode::NOT_FOUND,
+            LogsError::InvalidLevel(_) | LogsError::InvalidTime(_) => StatusCode::BAD_REQUEST,
+            LogsError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
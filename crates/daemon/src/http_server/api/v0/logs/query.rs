@@ -0,0 +1,78 @@
+use std::path::Path;
+
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+
+use super::{parse_filter, LogsError};
+use crate::http_server::api::client::ApiRequest;
+use crate::logs::{self, LogEntry};
+use crate::ServiceState;
+
+const DEFAULT_LIMIT: usize = 200;
+const MAX_LIMIT: usize = 10_000;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct LogQueryRequest {
+    /// Minimum level to include (error, warn, info, debug, trace)
+    #[arg(long)]
+    pub level: Option<String>,
+
+    /// Only entries at or after this time (RFC 3339, or relative like 10m, 2h, 1d)
+    #[arg(long)]
+    pub since: Option<String>,
+
+    /// Only entries at or before this time (RFC 3339, or relative like 10m, 2h, 1d)
+    #[arg(long)]
+    pub until: Option<String>,
+
+    /// Only entries containing this text (case-insensitive)
+    #[arg(long = "grep")]
+    pub contains: Option<String>,
+
+    /// Maximum number of entries to return, most recent kept
+    #[arg(short = 'n', long = "lines")]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogQueryResponse {
+    /// Matching entries, oldest first
+    pub entries: Vec<LogEntry>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<LogQueryRequest>,
+) -> Result<impl IntoResponse, LogsError> {
+    let log_dir = state.log_dir().ok_or(LogsError::NoLogDir)?.to_path_buf();
+    let response = tokio::task::spawn_blocking(move || run_query(&log_dir, &req))
+        .await
+        .map_err(std::io::Error::other)??;
+
+    Ok((http::StatusCode::OK, Json(response)).into_response())
+}
+
+/// Run a log query against a log directory (blocking; reads the log files)
+pub fn run_query(log_dir: &Path, req: &LogQueryRequest) -> Result<LogQueryResponse, LogsError> {
+    let filter = parse_filter(
+        req.level.as_deref(),
+        req.since.as_deref(),
+        req.until.as_deref(),
+        req.contains.as_deref(),
+    )?;
+    let limit = req.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+    let entries = logs::query(log_dir, &filter, limit)?;
+    Ok(LogQueryResponse { entries })
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for LogQueryRequest {
+    type Response = LogQueryResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/logs/query").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -1,13 +1,46 @@
 use axum::Router;
 
+pub mod activity;
+pub mod admin;
+pub mod audit;
+pub mod backup;
 pub mod bucket;
+pub mod config;
+pub mod escrow;
+pub mod export;
+pub mod favorite;
+pub mod gateway;
+pub mod identity;
+pub mod logs;
 #[cfg(feature = "fuse")]
 pub mod mounts;
+pub mod peer;
+pub mod storage;
+pub mod sync;
+pub mod webhook;
+pub mod workspace;
 
 use crate::ServiceState;
 
 pub fn router(state: ServiceState) -> Router<ServiceState> {
-    let router = Router::new().nest("/bucket", bucket::router(state.clone()));
+    let router = Router::new()
+        .nest("/activity", activity::router(state.clone()))
+        .nest("/admin", admin::router(state.clone()))
+        .nest("/audit", audit::router(state.clone()))
+        .nest("/backup", backup::router(state.clone()))
+        .nest("/bucket", bucket::router(state.clone()))
+        .nest("/config", config::router(state.clone()))
+        .nest("/escrow", escrow::router(state.clone()))
+        .nest("/export", export::router(state.clone()))
+        .nest("/favorite", favorite::router(state.clone()))
+        .nest("/gateway", gateway::router(state.clone()))
+        .nest("/identity", identity::router(state.clone()))
+        .nest("/logs", logs::router(state.clone()))
+        .nest("/peer", peer::router(state.clone()))
+        .nest("/storage", storage::router(state.clone()))
+        .nest("/sync", sync::router(state.clone()))
+        .nest("/webhook", webhook::router(state.clone()))
+        .nest("/workspace", workspace::router(state.clone()));
 
     #[cfg(feature = "fuse")]
     let router = router.nest("/mounts", mounts::router(state.clone()));
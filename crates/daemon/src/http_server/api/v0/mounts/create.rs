@@ -6,6 +6,7 @@ use reqwest::{Client, RequestBuilder, Url};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::audit::{Actor, AuditEvent};
 use crate::database::models::FuseMount;
 use crate::http_server::api::client::ApiRequest;
 use crate::ServiceState;
@@ -84,6 +85,13 @@ pub async fn handler(
             req.cache_ttl_secs,
         )
         .await?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "mount.create")
+                .bucket(*mount.bucket_id)
+                .detail(mount.mount_point.clone()),
+        )
+        .await;
 
     Ok((
         http::StatusCode::CREATED,
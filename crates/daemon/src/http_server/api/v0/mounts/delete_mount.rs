@@ -7,6 +7,7 @@ use reqwest::{Client, RequestBuilder, Url};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::audit::{Actor, AuditEvent};
 use crate::http_server::api::client::ApiRequest;
 use crate::ServiceState;
 
@@ -32,6 +33,11 @@ pub async fn handler(
         .ok_or(DeleteMountError::MountManagerUnavailable)?;
 
     let deleted = mount_manager.delete(&id).await?;
+    if deleted {
+        state
+            .audit(AuditEvent::new(Actor::Api, "mount.delete").detail(id.to_string()))
+            .await;
+    }
 
     Ok((http::StatusCode::OK, Json(DeleteMountResponse { deleted })).into_response())
 }
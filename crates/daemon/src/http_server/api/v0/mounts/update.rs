@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use super::create::MountInfo;
+use crate::audit::{Actor, AuditEvent};
 use crate::http_server::api::client::ApiRequest;
 use crate::ServiceState;
 
@@ -57,6 +58,13 @@ pub async fn handler(
         )
         .await?
         .ok_or(UpdateMountError::NotFound(id))?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "mount.update")
+                .bucket(*mount.bucket_id)
+                .detail(mount.mount_point.clone()),
+        )
+        .await;
 
     Ok((
         http::StatusCode::OK,
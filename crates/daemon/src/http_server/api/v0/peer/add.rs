@@ -0,0 +1,49 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+
+use super::PeerApiError;
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::peers::PeerTicket;
+use crate::ServiceState;
+
+/// Add a peer to the address book and dial it
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct PeerAddRequest {
+    /// Peer ticket (`<node-id>[@<addr>,...]`), as shown by `jax peer list`
+    /// on the other node
+    pub ticket: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerAddResponse {
+    /// Hex public key of the peer
+    pub node_id: String,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<PeerAddRequest>,
+) -> Result<impl IntoResponse, PeerApiError> {
+    let ticket: PeerTicket = req.ticket.parse()?;
+    let node_id = ticket.node_id.to_hex();
+    tracing::info!("Adding peer {} to the address book", node_id);
+    state.add_known_peer(ticket).await?;
+    state
+        .audit(AuditEvent::new(Actor::Api, "peer.add").detail(node_id.clone()))
+        .await;
+
+    Ok((http::StatusCode::OK, Json(PeerAddResponse { node_id })).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for PeerAddRequest {
+    type Response = PeerAddResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/peer/add").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,82 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use super::PeerApiError;
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// List the peers in the address book
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct PeerListRequest {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KnownPeerInfo {
+    /// Hex public key of the peer
+    pub node_id: String,
+    /// Ticket with the addresses the peer was last reached at
+    pub ticket: String,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_seen: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub added_at: OffsetDateTime,
+    /// Protocol version negotiated with the peer, None until we talked to it
+    #[serde(default)]
+    pub protocol_version: Option<u32>,
+    /// Capabilities the peer announced
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerListResponse {
+    /// Ticket other nodes can add this node with
+    pub own_ticket: String,
+    pub peers: Vec<KnownPeerInfo>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(_req): Json<PeerListRequest>,
+) -> Result<impl IntoResponse, PeerApiError> {
+    let peers = state
+        .database()
+        .list_known_peers()
+        .await?
+        .into_iter()
+        .map(|known| {
+            let protocol = state.peer().peer_protocol(&known.ticket.node_id);
+            KnownPeerInfo {
+                node_id: known.ticket.node_id.to_hex(),
+                ticket: known.ticket.to_string(),
+                last_seen: known.last_seen,
+                added_at: known.added_at,
+                protocol_version: protocol.as_ref().map(|p| p.version),
+                capabilities: protocol
+                    .map(|p| p.capabilities.into_iter().collect())
+                    .unwrap_or_default(),
+            }
+        })
+        .collect();
+
+    Ok((
+        http::StatusCode::OK,
+        Json(PeerListResponse {
+            own_ticket: state.own_ticket().to_string(),
+            peers,
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for PeerListRequest {
+    type Response = PeerListResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/peer/list").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
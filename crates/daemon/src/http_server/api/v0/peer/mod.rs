@@ -0,0 +1,51 @@
+//! Peer address book API endpoints
+//!
+//! Known peers are dialed when the daemon starts, so they don't need to be
+//! introduced again after a restart (see [`crate::peers`]).
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+
+use crate::http_server::error::ApiError;
+use crate::peers::PeerError;
+use crate::ServiceState;
+
+pub mod add;
+pub mod list;
+pub mod remove;
+
+pub use add::{PeerAddRequest, PeerAddResponse};
+pub use list::{KnownPeerInfo, PeerListRequest, PeerListResponse};
+pub use remove::{PeerRemoveRequest, PeerRemoveResponse};
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/add", post(add::handler))
+        .route("/list", post(list::handler))
+        .route("/remove", post(remove::handler))
+        .with_state(state)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PeerApiError {
+    #[error("{0}")]
+    Peer(#[from] PeerError),
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+impl IntoResponse for PeerApiError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            PeerApiError::Peer(PeerError::InvalidTicket(_) | PeerError::OwnNode) => {
+                StatusCode::BAD_REQUEST
+            }
+            PeerApiError::Peer(PeerError::Database(_)) | PeerApiError::Database(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
@@ -0,0 +1,59 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+
+use common::crypto::PublicKey;
+
+use super::PeerApiError;
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::peers::PeerError;
+use crate::ServiceState;
+
+/// Remove a peer from the address book
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct PeerRemoveRequest {
+    /// Node ID of the peer (hex-encoded)
+    pub node_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerRemoveResponse {
+    pub node_id: String,
+    /// Whether the peer was in the address book
+    pub removed: bool,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<PeerRemoveRequest>,
+) -> Result<impl IntoResponse, PeerApiError> {
+    let node_id = PublicKey::from_hex(&req.node_id)
+        .map_err(|_| PeerError::InvalidTicket(req.node_id.clone()))?;
+    let removed = state.remove_known_peer(&node_id).await?;
+    if removed {
+        state
+            .audit(AuditEvent::new(Actor::Api, "peer.remove").detail(req.node_id.clone()))
+            .await;
+    }
+
+    Ok((
+        http::StatusCode::OK,
+        Json(PeerRemoveResponse {
+            node_id: req.node_id,
+            removed,
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for PeerRemoveRequest {
+    type Response = PeerRemoveResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/peer/remove").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
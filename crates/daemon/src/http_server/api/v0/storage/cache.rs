@@ -0,0 +1,87 @@
+use axum::extract::{Json, State};
+use axum::response::{IntoResponse, Response};
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+
+use crate::blob_cache::BlobCacheError;
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Show the cache of content fetched on demand for online-only buckets
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct CacheRequest {
+    /// Evict the least recently read content until the cache fits its limit
+    #[arg(long)]
+    #[serde(default)]
+    pub evict: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheResponse {
+    /// Number of cached blobs
+    pub blobs: u64,
+    /// Total size of the cached blobs
+    pub bytes: u64,
+    /// Size the cache is kept within
+    pub max_bytes: u64,
+    /// Number of blobs evicted by this request
+    pub evicted_blobs: u64,
+    /// Bytes freed by this request
+    pub evicted_bytes: u64,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<CacheRequest>,
+) -> Result<impl IntoResponse, CacheError> {
+    let max_bytes = state.reloader().current().online_cache_max_bytes;
+    let evicted = if req.evict {
+        state
+            .blob_cache()
+            .evict(state.peer().blobs(), max_bytes)
+            .await?
+    } else {
+        Default::default()
+    };
+    let usage = state.blob_cache().usage().await?;
+
+    Ok((
+        http::StatusCode::OK,
+        Json(CacheResponse {
+            blobs: usage.blobs,
+            bytes: usage.bytes,
+            max_bytes,
+            evicted_blobs: evicted.blobs,
+            evicted_bytes: evicted.bytes,
+        }),
+    )
+        .into_response())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CacheError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Cache error: {0}")]
+    Cache(#[from] BlobCacheError),
+}
+
+impl IntoResponse for CacheError {
+    fn into_response(self) -> Response {
+        (
+            http::StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Error: {}", self),
+        )
+            .into_response()
+    }
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for CacheRequest {
+    type Response = CacheResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/storage/cache").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
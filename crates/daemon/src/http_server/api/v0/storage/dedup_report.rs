@@ -0,0 +1,226 @@
+use std::path::Path;
+
+use axum::extract::{Json, State};
+use axum::response::{IntoResponse, Response};
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::mount::{ChunkList, NodeLink};
+use common::peer::BlobsStoreError;
+use common::prelude::MountError;
+
+use crate::database::{BlobRef, DedupStats};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Default number of duplicate blobs to include in the report
+const DEFAULT_TOP_DUPLICATES: u32 = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct DedupReportRequest {
+    /// Number of top duplicate files to include (default 10)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[arg(long)]
+    pub top: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DedupReportResponse {
+    pub total: DedupSummary,
+    pub buckets: Vec<BucketDedupInfo>,
+    pub top_duplicates: Vec<DuplicateInfo>,
+    /// Buckets that could not be read (e.g. unpublished mirrors)
+    pub skipped_buckets: Vec<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DedupSummary {
+    pub file_count: u64,
+    pub logical_bytes: u64,
+    pub physical_bytes: u64,
+    pub dedup_ratio: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BucketDedupInfo {
+    pub bucket_id: Uuid,
+    pub name: String,
+    #[serde(flatten)]
+    pub stats: DedupSummary,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateInfo {
+    pub hash: String,
+    pub size: u64,
+    pub ref_count: u64,
+    pub saved_bytes: u64,
+    pub paths: Vec<DuplicatePath>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicatePath {
+    pub bucket_id: Uuid,
+    pub path: String,
+}
+
+impl From<DedupStats> for DedupSummary {
+    fn from(stats: DedupStats) -> Self {
+        let dedup_ratio = if stats.physical_bytes == 0 {
+            1.0
+        } else {
+            stats.logical_bytes as f64 / stats.physical_bytes as f64
+        };
+        Self {
+            file_count: stats.file_count,
+            logical_bytes: stats.logical_bytes,
+            physical_bytes: stats.physical_bytes,
+            dedup_ratio,
+        }
+    }
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<DedupReportRequest>,
+) -> Result<impl IntoResponse, DedupReportError> {
+    let buckets = state.database().list_buckets(None, Some(1000)).await?;
+
+    // Refresh reachability accounting for every bucket we can read
+    let mut skipped_buckets = Vec::new();
+    let mut bucket_stats = Vec::new();
+    for bucket in &buckets {
+        let refs = match collect_blob_refs(&state, bucket.id).await {
+            Ok(refs) => refs,
+            Err(DedupReportError::Mount(e)) => {
+                tracing::warn!("Skipping bucket {} in dedup report: {}", bucket.id, e);
+                skipped_buckets.push(bucket.id);
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        state
+            .database()
+            .replace_blob_refs(&bucket.id, &refs)
+            .await?;
+
+        let stats = state.database().bucket_dedup_stats(&bucket.id).await?;
+        bucket_stats.push(BucketDedupInfo {
+            bucket_id: bucket.id,
+            name: bucket.name.clone(),
+            stats: stats.into(),
+        });
+    }
+
+    let known: Vec<Uuid> = bucket_stats.iter().map(|b| b.bucket_id).collect();
+    state.database().prune_blob_refs(&known).await?;
+
+    let total = state.database().total_dedup_stats().await?.into();
+    let top_duplicates = state
+        .database()
+        .top_duplicate_blobs(req.top.unwrap_or(DEFAULT_TOP_DUPLICATES))
+        .await?
+        .into_iter()
+        .map(|d| DuplicateInfo {
+            saved_bytes: d.size * (d.ref_count - 1),
+            hash: d.hash,
+            size: d.size,
+            ref_count: d.ref_count,
+            paths: d
+                .paths
+                .into_iter()
+                .map(|(bucket_id, path)| DuplicatePath { bucket_id, path })
+                .collect(),
+        })
+        .collect();
+
+    Ok((
+        http::StatusCode::OK,
+        Json(DedupReportResponse {
+            total,
+            buckets: bucket_stats,
+            top_duplicates,
+            skipped_buckets,
+        }),
+    )
+        .into_response())
+}
+
+/// Walk a bucket and collect a reference for every locally available file blob
+pub(crate) async fn collect_blob_refs(
+    state: &ServiceState,
+    bucket_id: Uuid,
+) -> Result<Vec<BlobRef>, DedupReportError> {
+    let mount = state.peer().mount_for_read(bucket_id).await?;
+    let items = mount.ls_deep(Path::new("/")).await?;
+    let blobs = state.peer().blobs();
+
+    let mut refs = Vec::new();
+    for (path, node_link) in items {
+        if node_link.is_dir() || node_link.is_bucket() {
+            continue;
+        }
+        let path = Path::new("/").join(&path).to_string_lossy().to_string();
+        let hash = node_link.link().hash();
+        // Blobs that have not been synced locally take up no physical space
+        let Some(size) = blobs.size(&hash).await? else {
+            continue;
+        };
+        refs.push(BlobRef {
+            path: path.clone(),
+            hash: hash.to_string(),
+            size,
+        });
+
+        // Chunked files also take up the space of their chunks
+        if let NodeLink::Data(link, secret, data) = &node_link {
+            if data.is_chunked() {
+                let list = ChunkList::load(blobs, link, secret).await?;
+                for chunk in list.chunks() {
+                    let hash = chunk.link().hash();
+                    let Some(size) = blobs.size(&hash).await? else {
+                        continue;
+                    };
+                    refs.push(BlobRef {
+                        path: path.clone(),
+                        hash: hash.to_string(),
+                        size,
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(refs)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DedupReportError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Mount error: {0}")]
+    Mount(#[from] MountError),
+    #[error("Blobs error: {0}")]
+    Blobs(#[from] BlobsStoreError),
+}
+
+impl IntoResponse for DedupReportError {
+    fn into_response(self) -> Response {
+        (
+            http::StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Error: {}", self),
+        )
+            .into_response()
+    }
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for DedupReportRequest {
+    type Response = DedupReportResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/storage/dedup-report").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,19 @@
+//! Local storage reporting API endpoints
+
+use axum::routing::post;
+use axum::Router;
+
+use crate::ServiceState;
+
+pub mod cache;
+pub mod dedup_report;
+
+pub use cache::{CacheRequest, CacheResponse};
+pub use dedup_report::{DedupReportRequest, DedupReportResponse};
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/cache", post(cache::handler))
+        .route("/dedup-report", post(dedup_report::handler))
+        .with_state(state)
+}
@@ -0,0 +1,63 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+
+use super::{SyncApiError, SyncStatusResponse};
+use crate::http_server::api::client::ApiRequest;
+use crate::sync_conditions::Conditions;
+use crate::ServiceState;
+
+/// Report the machine's power and network conditions in place of the
+/// daemon's own probe, or clear the report to go back to probing
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct SyncConditionsRequest {
+    /// Running on battery power
+    #[arg(long)]
+    #[serde(default)]
+    pub on_battery: bool,
+
+    /// Battery charge in percent
+    #[arg(long, value_parser = clap::value_parser!(u8).range(0..=100))]
+    #[serde(default)]
+    pub battery_percent: Option<u8>,
+
+    /// Connected through a metered network
+    #[arg(long)]
+    #[serde(default)]
+    pub metered: bool,
+
+    /// Clear the report and go back to probing
+    #[arg(long, conflicts_with_all = ["on_battery", "battery_percent", "metered"])]
+    #[serde(default)]
+    pub clear: bool,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<SyncConditionsRequest>,
+) -> Result<impl IntoResponse, SyncApiError> {
+    let reported = (!req.clear).then_some(Conditions {
+        on_battery: req.on_battery,
+        battery_percent: req.battery_percent.map(|percent| percent.min(100)),
+        metered: req.metered,
+    });
+    tracing::debug!(?reported, "Sync conditions reported via API");
+    state.sync_status().conditions().report(reported);
+
+    Ok((
+        http::StatusCode::OK,
+        Json(SyncStatusResponse::from_state(&state)),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for SyncConditionsRequest {
+    type Response = SyncStatusResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/sync/conditions").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,72 @@
+//! Sync control API endpoints
+//!
+//! Pausing stops periodic pings and parks incoming sync jobs until syncing is
+//! resumed. The switch is stored in the database, so it survives restarts.
+//! Per-bucket schedules are managed under `/policy`, `/path-status` reports
+//! the sync state of files in mounted folders, and `/conditions` takes power
+//! and network conditions from the host (see [`crate::sync_conditions`]).
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+
+use uuid::Uuid;
+
+use crate::http_server::error::ApiError;
+use crate::sync_policy::SyncPolicyError;
+use crate::ServiceState;
+
+pub mod conditions;
+pub mod now;
+pub mod path_status;
+pub mod pause;
+pub mod policy;
+pub mod resume;
+pub mod status;
+
+pub use conditions::SyncConditionsRequest;
+pub use now::{SyncNowRequest, SyncNowResponse};
+pub use path_status::{PathStatusRequest, PathStatusResponse};
+pub use pause::SyncPauseRequest;
+pub use resume::SyncResumeRequest;
+pub use status::{SyncStatusRequest, SyncStatusResponse};
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/conditions", post(conditions::handler))
+        .route("/now", post(now::handler))
+        .route("/path-status", post(path_status::handler))
+        .route("/pause", post(pause::handler))
+        .nest("/policy", policy::router(state.clone()))
+        .route("/resume", post(resume::handler))
+        .route("/status", post(status::handler))
+        .with_state(state)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SyncApiError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Invalid sync policy: {0}")]
+    Policy(#[from] SyncPolicyError),
+    #[error("Syncing bucket {0} is paused")]
+    BucketPaused(Uuid),
+    #[error("Failed to ping bucket peers: {0}")]
+    Ping(String),
+    #[error("Bucket log error: {0}")]
+    BucketLog(String),
+}
+
+impl IntoResponse for SyncApiError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            SyncApiError::Policy(_) => StatusCode::BAD_REQUEST,
+            SyncApiError::BucketPaused(_) => StatusCode::CONFLICT,
+            SyncApiError::Database(_) | SyncApiError::Ping(_) | SyncApiError::BucketLog(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
@@ -0,0 +1,57 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::SyncApiError;
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Sync a bucket now by pinging its peers, regardless of its schedule
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct SyncNowRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncNowResponse {
+    pub bucket_id: Uuid,
+    /// Whether the jobs were parked because syncing is paused globally
+    pub parked: bool,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<SyncNowRequest>,
+) -> Result<impl IntoResponse, SyncApiError> {
+    if state.sync_policies().get(&req.bucket_id).paused {
+        return Err(SyncApiError::BucketPaused(req.bucket_id));
+    }
+    state
+        .peer()
+        .ping(req.bucket_id)
+        .await
+        .map_err(|e| SyncApiError::Ping(e.to_string()))?;
+
+    Ok((
+        http::StatusCode::OK,
+        Json(SyncNowResponse {
+            bucket_id: req.bucket_id,
+            parked: state.sync_status().is_paused(),
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for SyncNowRequest {
+    type Response = SyncNowResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/sync/now").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
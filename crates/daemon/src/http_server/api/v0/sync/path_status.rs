@@ -0,0 +1,264 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::bucket_log::BucketLogProvider;
+use common::mount::{ChunkList, Mount, NodeLink};
+use common::prelude::MountError;
+
+use super::SyncApiError;
+use crate::database::models::FuseMount;
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Report the sync state of local paths inside mounted buckets, for file
+/// manager badges and context menus
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct PathStatusRequest {
+    /// Local paths to query, e.g. files in a mount's folder
+    #[arg(required = true)]
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathStatusResponse {
+    /// One status per requested path, in order
+    pub statuses: Vec<PathStatus>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathStatus {
+    /// The path as requested
+    pub local_path: String,
+    pub state: PathSyncState,
+    /// Mount whose folder holds the path
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mount_id: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bucket_id: Option<Uuid>,
+    /// Path of the file in the bucket
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub is_dir: bool,
+    /// Path the gateway serves the file at, if the bucket is published
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gateway_path: Option<String>,
+}
+
+/// Sync state of a local path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PathSyncState {
+    /// Not inside any mount's folder
+    Untracked,
+    /// Inside a mount's folder but not in the bucket's current version
+    Missing,
+    /// The bucket can't be read, e.g. it hasn't synced yet
+    Unavailable,
+    /// Syncing is paused, for every bucket or this one
+    Paused,
+    /// In the bucket, with its content stored locally
+    Synced,
+    /// In an online-only bucket; its content is fetched when read
+    OnlineOnly,
+    /// In the bucket, with its content still being downloaded
+    Downloading,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<PathStatusRequest>,
+) -> Result<impl IntoResponse, SyncApiError> {
+    let mut mount_points: Vec<(PathBuf, FuseMount)> = FuseMount::list(state.database())
+        .await?
+        .into_iter()
+        .map(|mount| (normalize(Path::new(&mount.mount_point)), mount))
+        .collect();
+    // Nested mount points resolve to the innermost one
+    mount_points.sort_by_key(|(point, _)| std::cmp::Reverse(point.components().count()));
+
+    // Buckets are read once per request, however many paths they hold
+    let mut buckets: HashMap<Uuid, Option<BucketView>> = HashMap::new();
+    let mut statuses = Vec::with_capacity(req.paths.len());
+    for local_path in req.paths {
+        let normalized = normalize(Path::new(&local_path));
+        let Some((relative, mount)) = mount_points.iter().find_map(|(point, mount)| {
+            normalized
+                .strip_prefix(point)
+                .ok()
+                .map(|relative| (relative.to_path_buf(), mount))
+        }) else {
+            statuses.push(PathStatus::untracked(local_path));
+            continue;
+        };
+
+        let bucket_id = *mount.bucket_id;
+        if let Entry::Vacant(entry) = buckets.entry(bucket_id) {
+            entry.insert(BucketView::load(&state, bucket_id).await?);
+        }
+        let bucket_path = Path::new("/").join(&relative);
+        let mut status = PathStatus {
+            local_path,
+            state: PathSyncState::Unavailable,
+            mount_id: Some(*mount.mount_id),
+            bucket_id: Some(bucket_id),
+            path: Some(bucket_path.to_string_lossy().to_string()),
+            is_dir: false,
+            gateway_path: None,
+        };
+        if let Some(view) = &buckets[&bucket_id] {
+            view.describe(&state, &bucket_path, &mut status).await;
+        }
+        statuses.push(status);
+    }
+
+    Ok((http::StatusCode::OK, Json(PathStatusResponse { statuses })).into_response())
+}
+
+impl PathStatus {
+    fn untracked(local_path: String) -> Self {
+        Self {
+            local_path,
+            state: PathSyncState::Untracked,
+            mount_id: None,
+            bucket_id: None,
+            path: None,
+            is_dir: false,
+            gateway_path: None,
+        }
+    }
+}
+
+/// What a status needs to know about a bucket
+struct BucketView {
+    mount: Mount,
+    paused: bool,
+    online_only: bool,
+    gateway_path: Option<String>,
+}
+
+impl BucketView {
+    /// None if the bucket can't be read
+    async fn load(state: &ServiceState, bucket_id: Uuid) -> Result<Option<Self>, SyncApiError> {
+        let mount = match state.peer().mount_for_read(bucket_id).await {
+            Ok(mount) => mount,
+            Err(e) => {
+                tracing::debug!("Bucket {} unavailable for path status: {}", bucket_id, e);
+                return Ok(None);
+            }
+        };
+        let policy = state.sync_policies().get(&bucket_id);
+        let published = state
+            .peer()
+            .logs()
+            .latest_published(bucket_id)
+            .await
+            .map_err(|e| SyncApiError::BucketLog(e.to_string()))?
+            .is_some();
+        let gateway_path = if published {
+            Some(state.gateway_path(bucket_id).await?)
+        } else {
+            None
+        };
+        Ok(Some(Self {
+            mount,
+            paused: policy.paused || state.sync_status().is_paused(),
+            online_only: policy.online_only,
+            gateway_path,
+        }))
+    }
+
+    async fn describe(&self, state: &ServiceState, path: &Path, status: &mut PathStatus) {
+        let node_link = match self.mount.get(path).await {
+            Ok(node_link) => node_link,
+            Err(MountError::PathNotFound(_)) => {
+                status.state = PathSyncState::Missing;
+                return;
+            }
+            Err(e) => {
+                tracing::debug!(
+                    "Failed to resolve {} for path status: {}",
+                    path.display(),
+                    e
+                );
+                return;
+            }
+        };
+        status.is_dir = node_link.is_dir();
+        status.gateway_path = self
+            .gateway_path
+            .as_ref()
+            .map(|gateway| format!("{}{}", gateway, path.display()));
+
+        status.state = if self.paused {
+            PathSyncState::Paused
+        } else if node_link.is_dir()
+            || node_link.is_bucket()
+            || has_content(state, &node_link).await
+        {
+            PathSyncState::Synced
+        } else if self.online_only {
+            PathSyncState::OnlineOnly
+        } else {
+            PathSyncState::Downloading
+        };
+    }
+}
+
+/// Whether every blob of a file is stored locally
+async fn has_content(state: &ServiceState, node_link: &NodeLink) -> bool {
+    let blobs = state.peer().blobs();
+    let link = node_link.link();
+    if !blobs.stat(&link.hash()).await.unwrap_or(false) {
+        return false;
+    }
+    let chunked = node_link.data().is_some_and(|data| data.is_chunked());
+    if !chunked {
+        return true;
+    }
+    let Some(secret) = node_link.secret() else {
+        return false;
+    };
+    let Ok(chunks) = ChunkList::load(blobs, link, secret).await else {
+        return false;
+    };
+    for chunk in chunks.chunks() {
+        if !blobs.stat(&chunk.link().hash()).await.unwrap_or(false) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Drop `.` components and resolve `..` lexically, without touching the
+/// filesystem (a mount's folder may be a FUSE mount that is slow or stopped)
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for PathStatusRequest {
+    type Response = PathStatusResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/sync/path-status").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
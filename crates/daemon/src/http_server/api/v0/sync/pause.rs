@@ -0,0 +1,38 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+
+use super::{SyncApiError, SyncStatusResponse};
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Pause all syncing until resumed, including across restarts
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct SyncPauseRequest {}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(_req): Json<SyncPauseRequest>,
+) -> Result<impl IntoResponse, SyncApiError> {
+    tracing::info!("Sync pause requested via API");
+    state.set_sync_paused(true).await?;
+    state.audit(AuditEvent::new(Actor::Api, "sync.pause")).await;
+
+    Ok((
+        http::StatusCode::OK,
+        Json(SyncStatusResponse::from_state(&state)),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for SyncPauseRequest {
+    type Response = SyncStatusResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/sync/pause").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
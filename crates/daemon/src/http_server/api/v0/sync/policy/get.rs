@@ -0,0 +1,57 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::http_server::api::client::ApiRequest;
+use crate::http_server::api::v0::sync::SyncApiError;
+use crate::sync_policy::SyncPolicy;
+use crate::ServiceState;
+
+/// Show a bucket's sync policy
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct SyncPolicyGetRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncPolicyResponse {
+    pub bucket_id: Uuid,
+    pub policy: SyncPolicy,
+    /// Daemon-wide interval used when the policy sets none
+    pub default_interval_secs: u64,
+}
+
+impl SyncPolicyResponse {
+    pub(crate) fn from_state(state: &ServiceState, bucket_id: Uuid) -> Self {
+        Self {
+            bucket_id,
+            policy: state.sync_policies().get(&bucket_id),
+            default_interval_secs: state.reloader().current().sync_interval.as_secs(),
+        }
+    }
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<SyncPolicyGetRequest>,
+) -> Result<impl IntoResponse, SyncApiError> {
+    Ok((
+        http::StatusCode::OK,
+        Json(SyncPolicyResponse::from_state(&state, req.bucket_id)),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for SyncPolicyGetRequest {
+    type Response = SyncPolicyResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/sync/policy/get").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
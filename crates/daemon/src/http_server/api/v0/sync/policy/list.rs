@@ -0,0 +1,46 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+
+use super::SyncPolicyResponse;
+use crate::http_server::api::client::ApiRequest;
+use crate::http_server::api::v0::sync::SyncApiError;
+use crate::ServiceState;
+
+/// List buckets with a sync policy set
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct SyncPolicyListRequest {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncPolicyListResponse {
+    pub policies: Vec<SyncPolicyResponse>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(_req): Json<SyncPolicyListRequest>,
+) -> Result<impl IntoResponse, SyncApiError> {
+    let mut bucket_ids: Vec<_> = state.sync_policies().all().into_keys().collect();
+    bucket_ids.sort();
+    let policies = bucket_ids
+        .into_iter()
+        .map(|bucket_id| SyncPolicyResponse::from_state(&state, bucket_id))
+        .collect();
+
+    Ok((
+        http::StatusCode::OK,
+        Json(SyncPolicyListResponse { policies }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for SyncPolicyListRequest {
+    type Response = SyncPolicyListResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/sync/policy/list").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
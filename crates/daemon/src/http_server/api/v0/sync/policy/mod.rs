@@ -0,0 +1,25 @@
+//! Per-bucket sync policy endpoints (see [`crate::sync_policy`])
+
+use axum::routing::post;
+use axum::Router;
+
+use crate::ServiceState;
+
+pub mod get;
+pub mod list;
+pub mod reset;
+pub mod set;
+
+pub use get::{SyncPolicyGetRequest, SyncPolicyResponse};
+pub use list::{SyncPolicyListRequest, SyncPolicyListResponse};
+pub use reset::{SyncPolicyResetRequest, SyncPolicyResetResponse};
+pub use set::SyncPolicySetRequest;
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/get", post(get::handler))
+        .route("/list", post(list::handler))
+        .route("/reset", post(reset::handler))
+        .route("/set", post(set::handler))
+        .with_state(state)
+}
@@ -0,0 +1,56 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::http_server::api::v0::sync::SyncApiError;
+use crate::ServiceState;
+
+/// Remove a bucket's sync policy so it follows the daemon defaults
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct SyncPolicyResetRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncPolicyResetResponse {
+    pub bucket_id: Uuid,
+    /// Whether the bucket had a policy
+    pub removed: bool,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<SyncPolicyResetRequest>,
+) -> Result<impl IntoResponse, SyncApiError> {
+    let removed = state.reset_sync_policy(req.bucket_id).await?;
+    if removed {
+        state
+            .audit(AuditEvent::new(Actor::Api, "sync.policy.reset").bucket(req.bucket_id))
+            .await;
+    }
+
+    Ok((
+        http::StatusCode::OK,
+        Json(SyncPolicyResetResponse {
+            bucket_id: req.bucket_id,
+            removed,
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for SyncPolicyResetRequest {
+    type Response = SyncPolicyResetResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/sync/policy/reset").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
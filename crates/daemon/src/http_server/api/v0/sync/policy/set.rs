@@ -0,0 +1,85 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::SyncPolicyResponse;
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::http_server::api::v0::sync::SyncApiError;
+use crate::sync_policy::{ActiveHours, SyncPolicy};
+use crate::ServiceState;
+
+/// Replace a bucket's sync policy. Options left out fall back to the defaults.
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct SyncPolicySetRequest {
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+
+    /// Seconds between periodic syncs (default: the daemon's sync interval)
+    #[arg(long = "interval")]
+    #[serde(default)]
+    pub interval_secs: Option<u64>,
+
+    /// Only sync when asked to (`jax sync now`) or when peers announce updates
+    #[arg(long)]
+    #[serde(default)]
+    pub manual_only: bool,
+
+    /// Don't sync this bucket at all
+    #[arg(long)]
+    #[serde(default)]
+    pub paused: bool,
+
+    /// Only sync periodically within this local time window, e.g. 08:00-18:00
+    #[arg(long)]
+    #[serde(default)]
+    pub active_hours: Option<ActiveHours>,
+
+    /// Keep only metadata locally and fetch file content when it is read
+    #[arg(long)]
+    #[serde(default)]
+    pub online_only: bool,
+
+    /// Only download the content of the N most recent versions (default: all)
+    #[arg(long = "depth")]
+    #[serde(default)]
+    pub history_depth: Option<u64>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<SyncPolicySetRequest>,
+) -> Result<impl IntoResponse, SyncApiError> {
+    let policy = SyncPolicy {
+        interval_secs: req.interval_secs,
+        manual_only: req.manual_only,
+        paused: req.paused,
+        active_hours: req.active_hours,
+        online_only: req.online_only,
+        history_depth: req.history_depth,
+    };
+    policy.validate()?;
+    state.set_sync_policy(req.bucket_id, policy).await?;
+    state
+        .audit(AuditEvent::new(Actor::Api, "sync.policy.set").bucket(req.bucket_id))
+        .await;
+
+    Ok((
+        http::StatusCode::OK,
+        Json(SyncPolicyResponse::from_state(&state, req.bucket_id)),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for SyncPolicySetRequest {
+    type Response = SyncPolicyResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/sync/policy/set").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,74 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+
+use super::SyncApiError;
+use crate::http_server::api::client::ApiRequest;
+use crate::sync_conditions::Conditions;
+use crate::sync_provider::SyncActivity;
+use crate::ServiceState;
+
+/// Show whether syncing is paused and what the sync worker is doing
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct SyncStatusRequest {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncStatusResponse {
+    pub paused: bool,
+    pub activity: SyncActivity,
+    /// Jobs waiting in the queue
+    pub pending_jobs: usize,
+    /// Jobs held back until syncing is resumed
+    pub parked_jobs: usize,
+    /// Pin downloads held back until the network is unmetered
+    pub deferred_jobs: usize,
+    /// Power and network conditions in effect
+    pub conditions: Conditions,
+    /// Whether the conditions were reported by the host rather than probed
+    pub conditions_reported: bool,
+    /// Factor periodic sync intervals are stretched by under these conditions
+    pub interval_multiplier: u32,
+    /// Whether pin downloads are deferred under these conditions
+    pub downloads_deferred: bool,
+}
+
+impl SyncStatusResponse {
+    pub(crate) fn from_state(state: &ServiceState) -> Self {
+        let status = state.sync_status();
+        let conditions = status.conditions().current();
+        let deferral = state.reloader().current().sync_deferral;
+        Self {
+            paused: status.is_paused(),
+            activity: status.activity(),
+            pending_jobs: state.pending_sync_jobs(),
+            parked_jobs: status.parked_jobs(),
+            deferred_jobs: status.deferred_jobs(),
+            conditions,
+            conditions_reported: status.conditions().is_reported(),
+            interval_multiplier: deferral.interval_multiplier(&conditions),
+            downloads_deferred: deferral.defers_downloads(&conditions),
+        }
+    }
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(_req): Json<SyncStatusRequest>,
+) -> Result<impl IntoResponse, SyncApiError> {
+    Ok((
+        http::StatusCode::OK,
+        Json(SyncStatusResponse::from_state(&state)),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for SyncStatusRequest {
+    type Response = SyncStatusResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/sync/status").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,92 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{WebhookApiError, WebhookInfo};
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::webhooks::{generate_secret, WebhookEvent};
+use crate::ServiceState;
+
+/// Register a URL to POST bucket events to
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct WebhookAddRequest {
+    /// http(s) URL to deliver events to
+    #[arg(long)]
+    pub url: String,
+
+    /// Only deliver events of this bucket (default: every bucket)
+    #[arg(long)]
+    #[serde(default)]
+    pub bucket_id: Option<Uuid>,
+
+    /// Event to deliver; repeat for several (default: all of them)
+    #[arg(long = "event", value_enum)]
+    #[serde(default)]
+    pub events: Vec<WebhookEvent>,
+
+    /// Key to sign deliveries with (default: a random one)
+    #[arg(long)]
+    #[serde(default)]
+    pub secret: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookAddResponse {
+    #[serde(flatten)]
+    pub webhook: WebhookInfo,
+    /// Key deliveries are signed with; only returned here
+    pub secret: String,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<WebhookAddRequest>,
+) -> Result<impl IntoResponse, WebhookApiError> {
+    let url = Url::parse(&req.url).map_err(|_| WebhookApiError::InvalidUrl(req.url.clone()))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(WebhookApiError::InvalidUrl(req.url));
+    }
+
+    let mut events = req.events;
+    if events.is_empty() {
+        events = WebhookEvent::ALL.to_vec();
+    }
+    events.sort_by_key(|event| event.as_str());
+    events.dedup();
+    let secret = req
+        .secret
+        .filter(|secret| !secret.is_empty())
+        .unwrap_or_else(generate_secret);
+
+    let webhook = state
+        .database()
+        .create_webhook(url.as_str(), req.bucket_id.as_ref(), &events, &secret)
+        .await?;
+    let mut event = AuditEvent::new(Actor::Api, "webhook.add").detail(url.as_str());
+    if let Some(bucket_id) = req.bucket_id {
+        event = event.bucket(bucket_id);
+    }
+    state.audit(event).await;
+
+    Ok((
+        http::StatusCode::OK,
+        Json(WebhookAddResponse {
+            webhook: WebhookInfo::from(webhook),
+            secret,
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for WebhookAddRequest {
+    type Response = WebhookAddResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/webhook/add").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
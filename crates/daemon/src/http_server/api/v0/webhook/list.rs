@@ -0,0 +1,49 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{WebhookApiError, WebhookInfo};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// List webhooks with the outcome of their latest delivery
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct WebhookListRequest {
+    /// Only webhooks receiving events of this bucket, including the ones for
+    /// every bucket
+    #[arg(long)]
+    #[serde(default)]
+    pub bucket_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookListResponse {
+    pub webhooks: Vec<WebhookInfo>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<WebhookListRequest>,
+) -> Result<impl IntoResponse, WebhookApiError> {
+    let webhooks = state
+        .database()
+        .list_webhooks(req.bucket_id.as_ref())
+        .await?
+        .into_iter()
+        .map(WebhookInfo::from)
+        .collect();
+
+    Ok((http::StatusCode::OK, Json(WebhookListResponse { webhooks })).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for WebhookListRequest {
+    type Response = WebhookListResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/webhook/list").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
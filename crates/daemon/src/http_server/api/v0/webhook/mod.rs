@@ -0,0 +1,98 @@
+//! Webhook endpoints
+//!
+//! Webhooks are URLs the daemon POSTs bucket events to; see
+//! [`crate::webhooks`] for the events, payload and signature. A webhook
+//! registered without a bucket receives the events of every bucket.
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::http_server::error::ApiError;
+use crate::webhooks::{Webhook, WebhookEvent};
+use crate::ServiceState;
+
+pub mod add;
+pub mod list;
+pub mod remove;
+pub mod test;
+
+pub use add::{WebhookAddRequest, WebhookAddResponse};
+pub use list::{WebhookListRequest, WebhookListResponse};
+pub use remove::WebhookRemoveRequest;
+pub use test::{WebhookTestRequest, WebhookTestResponse};
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/add", post(add::handler))
+        .route("/list", post(list::handler))
+        .route("/remove", post(remove::handler))
+        .route("/test", post(test::handler))
+        .with_state(state)
+}
+
+/// A registered webhook, without its secret
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookInfo {
+    pub webhook_id: Uuid,
+    pub url: String,
+    /// None for events of every bucket
+    pub bucket_id: Option<Uuid>,
+    pub events: Vec<WebhookEvent>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_delivery_at: Option<OffsetDateTime>,
+    /// HTTP status of the latest delivery, None if it failed to get one
+    pub last_status: Option<u16>,
+    pub last_error: Option<String>,
+}
+
+impl From<Webhook> for WebhookInfo {
+    fn from(webhook: Webhook) -> Self {
+        Self {
+            webhook_id: webhook.id,
+            url: webhook.url,
+            bucket_id: webhook.bucket_id,
+            events: webhook.events,
+            created_at: webhook.created_at,
+            last_delivery_at: webhook.last_delivery_at,
+            last_status: webhook.last_status,
+            last_error: webhook.last_error,
+        }
+    }
+}
+
+/// Look up a webhook by ID
+async fn find(state: &ServiceState, id: &Uuid) -> Result<Webhook, WebhookApiError> {
+    state
+        .database()
+        .get_webhook(id)
+        .await?
+        .ok_or(WebhookApiError::UnknownWebhook(*id))
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum WebhookApiError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Invalid webhook URL: {0}")]
+    InvalidUrl(String),
+    #[error("Unknown webhook: {0}")]
+    UnknownWebhook(Uuid),
+}
+
+impl IntoResponse for WebhookApiError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            WebhookApiError::InvalidUrl(_) => StatusCode::BAD_REQUEST,
+            WebhookApiError::UnknownWebhook(_) => StatusCode::NOT_FOUND,
+            WebhookApiError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
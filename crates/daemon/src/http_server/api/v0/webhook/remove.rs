@@ -0,0 +1,43 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{find, WebhookApiError, WebhookInfo};
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Stop delivering events to a webhook. Retries already under way still run.
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct WebhookRemoveRequest {
+    /// Webhook ID
+    #[arg(long)]
+    pub webhook_id: Uuid,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<WebhookRemoveRequest>,
+) -> Result<impl IntoResponse, WebhookApiError> {
+    let webhook = find(&state, &req.webhook_id).await?;
+    state.database().delete_webhook(&webhook.id).await?;
+    let mut event = AuditEvent::new(Actor::Api, "webhook.remove").detail(&webhook.url);
+    if let Some(bucket_id) = webhook.bucket_id {
+        event = event.bucket(bucket_id);
+    }
+    state.audit(event).await;
+
+    Ok((http::StatusCode::OK, Json(WebhookInfo::from(webhook))).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for WebhookRemoveRequest {
+    type Response = WebhookInfo;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/webhook/remove").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,57 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{find, WebhookApiError};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Send a `ping` event to a webhook and wait for its response. The delivery
+/// isn't retried.
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct WebhookTestRequest {
+    /// Webhook ID
+    #[arg(long)]
+    pub webhook_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookTestResponse {
+    pub webhook_id: Uuid,
+    /// HTTP status the webhook responded with, if it was a success
+    pub status: Option<u16>,
+    pub error: Option<String>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<WebhookTestRequest>,
+) -> Result<impl IntoResponse, WebhookApiError> {
+    let webhook = find(&state, &req.webhook_id).await?;
+    let (status, error) = match state.webhooks().ping(&webhook).await {
+        Ok(status) => (Some(status), None),
+        Err(e) => (None, Some(e)),
+    };
+
+    Ok((
+        http::StatusCode::OK,
+        Json(WebhookTestResponse {
+            webhook_id: webhook.id,
+            status,
+            error,
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for WebhookTestRequest {
+    type Response = WebhookTestResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/webhook/test").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
@@ -0,0 +1,88 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::share::share_member;
+use super::{find, WorkspaceApiError, WorkspaceShareResult};
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::http_server::api::v0::bucket::share::ShareRole;
+use crate::ServiceState;
+
+/// Put a bucket in a workspace, moving it out of any other, and share it
+/// with the peers the workspace is shared with
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct WorkspaceAddRequest {
+    /// Workspace name or ID
+    #[arg(long)]
+    pub workspace: String,
+
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceAddResponse {
+    pub workspace_id: Uuid,
+    pub bucket_id: Uuid,
+    /// Shares with the workspace's peers
+    pub shares: Vec<WorkspaceShareResult>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<WorkspaceAddRequest>,
+) -> Result<impl IntoResponse, WorkspaceApiError> {
+    let workspace = find(&state, &req.workspace).await?;
+    if state
+        .database()
+        .get_bucket_info(&req.bucket_id)
+        .await?
+        .is_none()
+    {
+        return Err(WorkspaceApiError::UnknownBucket(req.bucket_id));
+    }
+
+    state
+        .database()
+        .set_bucket_workspace(&req.bucket_id, &workspace.id)
+        .await?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "workspace.add")
+                .bucket(req.bucket_id)
+                .detail(&workspace.name),
+        )
+        .await;
+
+    let mut shares = Vec::new();
+    for (peer_public_key, role) in state.database().workspace_shares(&workspace.id).await? {
+        let Some(role) = ShareRole::parse(&role) else {
+            continue;
+        };
+        shares.push(share_member(&state, req.bucket_id, &peer_public_key, role).await);
+    }
+
+    Ok((
+        http::StatusCode::OK,
+        Json(WorkspaceAddResponse {
+            workspace_id: workspace.id,
+            bucket_id: req.bucket_id,
+            shares,
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for WorkspaceAddRequest {
+    type Response = WorkspaceAddResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/workspace/add").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
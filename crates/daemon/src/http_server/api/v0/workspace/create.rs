@@ -0,0 +1,47 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+
+use super::{WorkspaceApiError, WorkspaceInfo};
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Create an empty workspace
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct WorkspaceCreateRequest {
+    /// Name of the workspace
+    #[arg(long)]
+    pub name: String,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<WorkspaceCreateRequest>,
+) -> Result<impl IntoResponse, WorkspaceApiError> {
+    let name = req.name.trim();
+    if name.is_empty() {
+        return Err(WorkspaceApiError::InvalidName(req.name));
+    }
+    if state.database().get_workspace(name).await?.is_some() {
+        return Err(WorkspaceApiError::AlreadyExists(name.to_string()));
+    }
+
+    let workspace = state.database().create_workspace(name).await?;
+    state
+        .audit(AuditEvent::new(Actor::Api, "workspace.create").detail(name))
+        .await;
+
+    Ok((http::StatusCode::OK, Json(WorkspaceInfo::from(workspace))).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for WorkspaceCreateRequest {
+    type Response = WorkspaceInfo;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/workspace/create").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
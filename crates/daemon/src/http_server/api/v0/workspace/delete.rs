@@ -0,0 +1,41 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+
+use super::{find, WorkspaceApiError, WorkspaceInfo};
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Delete a workspace. Its buckets are kept; they just no longer belong to
+/// a workspace.
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct WorkspaceDeleteRequest {
+    /// Workspace name or ID
+    #[arg(long)]
+    pub workspace: String,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<WorkspaceDeleteRequest>,
+) -> Result<impl IntoResponse, WorkspaceApiError> {
+    let workspace = find(&state, &req.workspace).await?;
+    state.database().delete_workspace(&workspace.id).await?;
+    state
+        .audit(AuditEvent::new(Actor::Api, "workspace.delete").detail(&workspace.name))
+        .await;
+
+    Ok((http::StatusCode::OK, Json(WorkspaceInfo::from(workspace))).into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for WorkspaceDeleteRequest {
+    type Response = WorkspaceInfo;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/workspace/delete").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
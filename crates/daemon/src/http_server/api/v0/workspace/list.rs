@@ -0,0 +1,46 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+
+use super::{WorkspaceApiError, WorkspaceInfo};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// List workspaces
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct WorkspaceListRequest {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceListResponse {
+    pub workspaces: Vec<WorkspaceInfo>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(_req): Json<WorkspaceListRequest>,
+) -> Result<impl IntoResponse, WorkspaceApiError> {
+    let workspaces = state
+        .database()
+        .list_workspaces()
+        .await?
+        .into_iter()
+        .map(WorkspaceInfo::from)
+        .collect();
+
+    Ok((
+        http::StatusCode::OK,
+        Json(WorkspaceListResponse { workspaces }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for WorkspaceListRequest {
+    type Response = WorkspaceListResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/workspace/list").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
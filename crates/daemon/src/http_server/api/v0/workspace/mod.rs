@@ -0,0 +1,106 @@
+//! Workspace endpoints
+//!
+//! A workspace is a named group of buckets kept in the daemon's database.
+//! A bucket belongs to at most one workspace. Sharing a workspace shares
+//! every bucket in it with the peer, and buckets added to the workspace
+//! later are shared with that peer too.
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::database::Workspace;
+use crate::http_server::error::ApiError;
+use crate::ServiceState;
+
+pub mod add;
+pub mod create;
+pub mod delete;
+pub mod list;
+pub mod remove;
+pub mod share;
+
+pub use add::{WorkspaceAddRequest, WorkspaceAddResponse};
+pub use create::WorkspaceCreateRequest;
+pub use delete::WorkspaceDeleteRequest;
+pub use list::{WorkspaceListRequest, WorkspaceListResponse};
+pub use remove::{WorkspaceRemoveRequest, WorkspaceRemoveResponse};
+pub use share::{WorkspaceShareRequest, WorkspaceShareResponse, WorkspaceShareResult};
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/create", post(create::handler))
+        .route("/list", post(list::handler))
+        .route("/delete", post(delete::handler))
+        .route("/add", post(add::handler))
+        .route("/remove", post(remove::handler))
+        .route("/share", post(share::handler))
+        .with_state(state)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceInfo {
+    pub workspace_id: Uuid,
+    pub name: String,
+    pub bucket_count: u64,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+impl From<Workspace> for WorkspaceInfo {
+    fn from(workspace: Workspace) -> Self {
+        Self {
+            workspace_id: workspace.id,
+            name: workspace.name,
+            bucket_count: workspace.bucket_count,
+            created_at: workspace.created_at,
+        }
+    }
+}
+
+/// Look up a workspace by name or ID
+async fn find(state: &ServiceState, workspace: &str) -> Result<Workspace, WorkspaceApiError> {
+    state
+        .database()
+        .get_workspace(workspace)
+        .await?
+        .ok_or_else(|| WorkspaceApiError::UnknownWorkspace(workspace.to_string()))
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum WorkspaceApiError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Invalid workspace name: {0}")]
+    InvalidName(String),
+    #[error("Workspace already exists: {0}")]
+    AlreadyExists(String),
+    #[error("Unknown workspace: {0}")]
+    UnknownWorkspace(String),
+    #[error("Unknown bucket: {0}")]
+    UnknownBucket(Uuid),
+    #[error("Bucket {0} is not in workspace {1}")]
+    NotMember(Uuid, String),
+    #[error("Invalid public key: {0}")]
+    InvalidPublicKey(String),
+}
+
+impl IntoResponse for WorkspaceApiError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            WorkspaceApiError::InvalidName(_) | WorkspaceApiError::InvalidPublicKey(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            WorkspaceApiError::AlreadyExists(_) => StatusCode::CONFLICT,
+            WorkspaceApiError::UnknownWorkspace(_)
+            | WorkspaceApiError::UnknownBucket(_)
+            | WorkspaceApiError::NotMember(_, _) => StatusCode::NOT_FOUND,
+            WorkspaceApiError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError::new(status, self.to_string()).into_response()
+    }
+}
@@ -0,0 +1,68 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{find, WorkspaceApiError};
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::ServiceState;
+
+/// Take a bucket out of a workspace. Peers it was shared with keep access.
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct WorkspaceRemoveRequest {
+    /// Workspace name or ID
+    #[arg(long)]
+    pub workspace: String,
+
+    /// Bucket ID
+    #[arg(long)]
+    pub bucket_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceRemoveResponse {
+    pub workspace_id: Uuid,
+    pub bucket_id: Uuid,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<WorkspaceRemoveRequest>,
+) -> Result<impl IntoResponse, WorkspaceApiError> {
+    let workspace = find(&state, &req.workspace).await?;
+    let removed = state
+        .database()
+        .remove_bucket_workspace(&req.bucket_id, &workspace.id)
+        .await?;
+    if !removed {
+        return Err(WorkspaceApiError::NotMember(req.bucket_id, workspace.name));
+    }
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "workspace.remove")
+                .bucket(req.bucket_id)
+                .detail(&workspace.name),
+        )
+        .await;
+
+    Ok((
+        http::StatusCode::OK,
+        Json(WorkspaceRemoveResponse {
+            workspace_id: workspace.id,
+            bucket_id: req.bucket_id,
+        }),
+    )
+        .into_response())
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for WorkspaceRemoveRequest {
+    type Response = WorkspaceRemoveResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/workspace/remove").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
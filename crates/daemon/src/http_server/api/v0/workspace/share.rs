@@ -0,0 +1,135 @@
+use axum::extract::{Json, State};
+use axum::response::IntoResponse;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::crypto::PublicKey;
+
+use super::{find, WorkspaceApiError};
+use crate::audit::{Actor, AuditEvent};
+use crate::http_server::api::client::ApiRequest;
+use crate::http_server::api::v0::bucket::share::{share_bucket, ShareOutcome, ShareRole};
+use crate::ServiceState;
+
+/// Share every bucket of a workspace with a peer. Buckets added to the
+/// workspace later are shared with the peer as well.
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Args)]
+pub struct WorkspaceShareRequest {
+    /// Workspace name or ID
+    #[arg(long)]
+    pub workspace: String,
+
+    /// Public key of the peer to share with (hex-encoded)
+    #[arg(long)]
+    pub peer_public_key: String,
+
+    /// Role for the peer (owner or mirror, defaults to owner)
+    #[arg(long, default_value = "owner")]
+    #[serde(default)]
+    pub role: ShareRole,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceShareResponse {
+    pub workspace_id: Uuid,
+    pub peer_public_key: String,
+    pub buckets: Vec<WorkspaceShareResult>,
+}
+
+/// Outcome of sharing one bucket of a workspace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceShareResult {
+    pub bucket_id: Uuid,
+    pub new_bucket_link: Option<String>,
+    /// Held for approval by the bucket's member policy
+    #[serde(default)]
+    pub pending: bool,
+    pub error: Option<String>,
+}
+
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Json(req): Json<WorkspaceShareRequest>,
+) -> Result<impl IntoResponse, WorkspaceApiError> {
+    PublicKey::from_hex(&req.peer_public_key)
+        .map_err(|e| WorkspaceApiError::InvalidPublicKey(e.to_string()))?;
+    let workspace = find(&state, &req.workspace).await?;
+
+    state
+        .database()
+        .add_workspace_share(&workspace.id, &req.peer_public_key, req.role.as_str())
+        .await?;
+    state
+        .audit(
+            AuditEvent::new(Actor::Api, "workspace.share").detail(format!(
+                "{} with {} as {:?}",
+                workspace.name, req.peer_public_key, req.role
+            )),
+        )
+        .await;
+
+    let bucket_ids = state.database().workspace_bucket_ids(&workspace.id).await?;
+    let mut buckets = Vec::with_capacity(bucket_ids.len());
+    for bucket_id in bucket_ids {
+        buckets.push(share_member(&state, bucket_id, &req.peer_public_key, req.role).await);
+    }
+
+    Ok((
+        http::StatusCode::OK,
+        Json(WorkspaceShareResponse {
+            workspace_id: workspace.id,
+            peer_public_key: req.peer_public_key,
+            buckets,
+        }),
+    )
+        .into_response())
+}
+
+/// Share one bucket of a workspace, keeping going if it fails (e.g. a
+/// bucket this node only mirrors)
+pub(crate) async fn share_member(
+    state: &ServiceState,
+    bucket_id: Uuid,
+    peer_public_key: &str,
+    role: ShareRole,
+) -> WorkspaceShareResult {
+    match share_bucket(state, bucket_id, peer_public_key, role, None, None).await {
+        Ok(ShareOutcome::Shared(link)) => WorkspaceShareResult {
+            bucket_id,
+            new_bucket_link: Some(link.hash().to_string()),
+            pending: false,
+            error: None,
+        },
+        Ok(ShareOutcome::Pending) => WorkspaceShareResult {
+            bucket_id,
+            new_bucket_link: None,
+            pending: true,
+            error: None,
+        },
+        Err(e) => {
+            tracing::warn!(
+                "Failed to share bucket {} of a workspace with {}: {}",
+                bucket_id,
+                peer_public_key,
+                e
+            );
+            WorkspaceShareResult {
+                bucket_id,
+                new_bucket_link: None,
+                pending: false,
+                error: Some(e.to_string()),
+            }
+        }
+    }
+}
+
+// Client implementation - builds request for this operation
+impl ApiRequest for WorkspaceShareRequest {
+    type Response = WorkspaceShareResponse;
+
+    fn build_request(self, base_url: &Url, client: &Client) -> RequestBuilder {
+        let full_url = base_url.join("/api/v0/workspace/share").unwrap();
+        client.post(full_url).json(&self)
+    }
+}
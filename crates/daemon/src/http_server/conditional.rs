@@ -0,0 +1,107 @@
+//! HEAD and conditional responses for the gateway and the API
+//!
+//! Everything a response serves is read from one version of a bucket, so
+//! its `Last-Modified` is the time this node recorded that version, and an
+//! `If-Modified-Since` at or after it gets a 304. HEAD of a file that is
+//! served as it is stored is answered from the size recorded in its node,
+//! without reading the file's blobs.
+
+use std::time::SystemTime;
+
+use axum::body::Body;
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum_extra::headers::{HeaderMapExt, IfModifiedSince, LastModified};
+use uuid::Uuid;
+
+use common::mount::Mount;
+
+use crate::ServiceState;
+
+/// When the version `mount` was loaded from was recorded, if this node
+/// has it in the bucket's log
+pub async fn last_modified(
+    state: &ServiceState,
+    bucket_id: Uuid,
+    mount: &Mount,
+) -> Option<SystemTime> {
+    let hash = mount.inner().await.link().hash();
+    match state
+        .database()
+        .version_recorded_at(&bucket_id, &hash)
+        .await
+    {
+        Ok(recorded_at) => recorded_at.map(SystemTime::from),
+        Err(e) => {
+            tracing::warn!("Failed to look up when {} was recorded: {}", hash, e);
+            None
+        }
+    }
+}
+
+/// A 304 if the request's `If-Modified-Since` is no older than
+/// `last_modified`
+pub fn not_modified(headers: &HeaderMap, last_modified: Option<SystemTime>) -> Option<Response> {
+    let since = headers.typed_get::<IfModifiedSince>()?;
+    let last_modified = last_modified?;
+    if since.is_modified(last_modified) {
+        return None;
+    }
+    let mut response = StatusCode::NOT_MODIFIED.into_response();
+    set_last_modified(&mut response, Some(last_modified));
+    Some(response)
+}
+
+/// Mark a response with when its content was last modified
+pub fn set_last_modified(response: &mut Response, last_modified: Option<SystemTime>) {
+    if let Some(last_modified) = last_modified {
+        response
+            .headers_mut()
+            .typed_insert(LastModified::from(last_modified));
+    }
+}
+
+/// Response to HEAD of a file of `content_length` bytes
+pub fn head_response(content_type: &str, content_length: u64, disposition: &str) -> Response {
+    let mut response = Body::empty().into_response();
+    let headers = response.headers_mut();
+    if let Ok(content_type) = HeaderValue::from_str(content_type) {
+        headers.insert(header::CONTENT_TYPE, content_type);
+    }
+    if let Ok(disposition) = HeaderValue::from_str(disposition) {
+        headers.insert(header::CONTENT_DISPOSITION, disposition);
+    }
+    // Set ahead of the empty body, so it isn't taken from it
+    headers.insert(header::CONTENT_LENGTH, HeaderValue::from(content_length));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_not_modified() {
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let mut headers = HeaderMap::new();
+        assert!(not_modified(&headers, Some(modified)).is_none());
+
+        headers.typed_insert(IfModifiedSince::from(modified));
+        let response = not_modified(&headers, Some(modified)).unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert!(response.headers().contains_key(header::LAST_MODIFIED));
+        // Unknown modification times are always served
+        assert!(not_modified(&headers, None).is_none());
+
+        let newer = modified + Duration::from_secs(1);
+        assert!(not_modified(&headers, Some(newer)).is_none());
+    }
+
+    #[test]
+    fn test_head_response() {
+        let response = head_response("text/plain", 42, "inline; filename=\"a.txt\"");
+        assert_eq!(response.headers()[header::CONTENT_LENGTH], "42");
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "text/plain");
+    }
+}
@@ -1,7 +1,10 @@
 use std::net::SocketAddr;
+use std::sync::Arc;
 
 use url::Url;
 
+use crate::reverse_proxy::ReverseProxy;
+
 #[derive(Debug, Clone)]
 pub struct Config {
     // Listen address
@@ -16,10 +19,16 @@ pub struct Config {
     // External gateway URL for generating share/download links
     #[allow(unused)]
     pub gateway_url: Option<String>,
+    // Proxies trusted to forward client addresses, and the path prefix
+    pub reverse_proxy: Arc<ReverseProxy>,
 }
 
 impl Config {
-    pub fn new(listen_addr: SocketAddr, gateway_url: Option<String>) -> Self {
+    pub fn new(
+        listen_addr: SocketAddr,
+        gateway_url: Option<String>,
+        reverse_proxy: Arc<ReverseProxy>,
+    ) -> Self {
         let hostname = Url::parse(&format!("http://localhost:{}", listen_addr.port())).unwrap();
         tracing::info!(
             "Creating HTTP server Config: listen_addr={}, gateway_url={:?}",
@@ -31,6 +40,7 @@ impl Config {
             hostname,
             log_level: tracing::Level::INFO,
             gateway_url,
+            reverse_proxy,
         }
     }
 }
@@ -0,0 +1,270 @@
+//! Signed writes from CI on the gateway (see [`crate::deploy`])
+
+use std::fs::File;
+use std::io::{Seek, SeekFrom};
+use std::path::PathBuf;
+
+use axum::body::Body;
+use axum::extract::{DefaultBodyLimit, Path, State};
+use axum::http::{header, HeaderMap, Method, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::put;
+use axum::{Json, Router};
+use futures::StreamExt;
+use sha2::{Digest, Sha256};
+use time::OffsetDateTime;
+use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
+
+use common::prelude::MountError;
+
+use crate::audit::{Actor, AuditEvent};
+use crate::deploy::{
+    canonical_request, is_fresh, is_valid_nonce, normalize_path, verify_request, DeployKey,
+    DeployResponse, CONTENT_SHA256_HEADER, KEY_HEADER, NONCE_HEADER, SIGNATURE_HEADER,
+    TIMESTAMP_HEADER,
+};
+use crate::ServiceState;
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route(
+            "/:bucket_id/*path",
+            put(write_handler).delete(delete_handler),
+        )
+        // Bodies are held to their bucket's upload limit as they are spooled
+        .layer(DefaultBodyLimit::disable())
+        .with_state(state)
+}
+
+/// Write the request body to a path of the bucket, replacing any file there
+pub async fn write_handler(
+    State(state): State<ServiceState>,
+    Path((bucket_id, path)): Path<(Uuid, String)>,
+    headers: HeaderMap,
+    body: Body,
+) -> Result<impl IntoResponse, DeployError> {
+    let (key, path, content_sha256) =
+        authorize(&state, &Method::PUT, bucket_id, &path, &headers).await?;
+    let limit = state.upload_limits().for_bucket(&bucket_id);
+    let file = spool_body(body, &headers, &content_sha256, limit).await?;
+
+    let mount_path = PathBuf::from(&path);
+    let mut mount = state.peer().mount(bucket_id).await?;
+    let replaced = mount.get(&mount_path).await.is_ok();
+    mount.add(&mount_path, file).await?;
+    let link = state.peer().save_mount(&mount, false).await?;
+
+    let operation = if replaced {
+        "bucket.update"
+    } else {
+        "bucket.add"
+    };
+    finish(&state, &key, operation, path, &link).await
+}
+
+/// Remove a path of the bucket
+pub async fn delete_handler(
+    State(state): State<ServiceState>,
+    Path((bucket_id, path)): Path<(Uuid, String)>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, DeployError> {
+    let (key, path, content_sha256) =
+        authorize(&state, &Method::DELETE, bucket_id, &path, &headers).await?;
+    if content_sha256 != crate::deploy::content_sha256(b"") {
+        return Err(DeployError::ContentMismatch);
+    }
+
+    let mount_path = PathBuf::from(&path);
+    let mut mount = state.peer().mount(bucket_id).await?;
+    if mount.get(&mount_path).await.is_err() {
+        return Err(DeployError::PathNotFound(path));
+    }
+    mount.rm(&mount_path).await?;
+    let link = state.peer().save_mount(&mount, false).await?;
+
+    finish(&state, &key, "bucket.delete", path, &link).await
+}
+
+/// Check a request's key, freshness, nonce, scope and signature, returning
+/// the key, the normalized bucket path and the claimed body hash
+async fn authorize(
+    state: &ServiceState,
+    method: &Method,
+    bucket_id: Uuid,
+    path: &str,
+    headers: &HeaderMap,
+) -> Result<(DeployKey, String, String), DeployError> {
+    let header = |name: &'static str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .ok_or(name)
+    };
+    let key_id = header(KEY_HEADER).map_err(DeployError::MissingHeader)?;
+    let key_id = Uuid::parse_str(key_id).map_err(|_| DeployError::Unauthorized)?;
+    let timestamp: i64 = header(TIMESTAMP_HEADER)
+        .map_err(DeployError::MissingHeader)?
+        .parse()
+        .map_err(|_| DeployError::Stale)?;
+    let nonce = header(NONCE_HEADER).map_err(DeployError::MissingHeader)?;
+    if !is_valid_nonce(nonce) {
+        return Err(DeployError::Unauthorized);
+    }
+    let content_sha256 = header(CONTENT_SHA256_HEADER)
+        .map_err(DeployError::MissingHeader)?
+        .to_ascii_lowercase();
+    let signature = header(SIGNATURE_HEADER).map_err(DeployError::MissingHeader)?;
+
+    let path = normalize_path(path).ok_or_else(|| DeployError::InvalidPath(path.to_string()))?;
+    if path == "/" {
+        return Err(DeployError::InvalidPath(path));
+    }
+
+    // Unknown keys and bad signatures are indistinguishable to the caller
+    let key = state
+        .database()
+        .get_deploy_key(&key_id)
+        .await?
+        .ok_or(DeployError::Unauthorized)?;
+    let canonical = canonical_request(
+        method.as_str(),
+        &bucket_id,
+        &path,
+        timestamp,
+        nonce,
+        &content_sha256,
+    );
+    if !verify_request(&key.secret, &canonical, signature) {
+        return Err(DeployError::Unauthorized);
+    }
+    let now = OffsetDateTime::now_utc();
+    if !is_fresh(timestamp, now) {
+        return Err(DeployError::Stale);
+    }
+    if !state.deploy_nonces().insert(key.id, nonce, timestamp, now) {
+        return Err(DeployError::Replayed);
+    }
+    if key.bucket_id != bucket_id || !key.allows(&path) {
+        return Err(DeployError::Forbidden(path));
+    }
+
+    Ok((key, path, content_sha256))
+}
+
+/// Write a request body to a temp file, checking it against its claimed hash
+/// and cutting it off once it grows past `limit` bytes
+async fn spool_body(
+    body: Body,
+    headers: &HeaderMap,
+    expected_sha256: &str,
+    limit: u64,
+) -> Result<File, DeployError> {
+    let content_length = headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+    if content_length.is_some_and(|length| length > limit) {
+        return Err(DeployError::TooLarge(limit));
+    }
+
+    let io_error = |e: std::io::Error| DeployError::Body(e.to_string());
+    let mut file = tokio::fs::File::from_std(tempfile::tempfile().map_err(io_error)?);
+    let mut hasher = Sha256::new();
+    let mut size = 0u64;
+    let mut stream = body.into_data_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| DeployError::Body(e.to_string()))?;
+        size += chunk.len() as u64;
+        if size > limit {
+            return Err(DeployError::TooLarge(limit));
+        }
+        hasher.update(&chunk);
+        file.write_all(&chunk).await.map_err(io_error)?;
+    }
+    if hex::encode(hasher.finalize()) != expected_sha256 {
+        return Err(DeployError::ContentMismatch);
+    }
+    file.flush().await.map_err(io_error)?;
+
+    let mut file = file.into_std().await;
+    file.seek(SeekFrom::Start(0)).map_err(io_error)?;
+    Ok(file)
+}
+
+async fn finish(
+    state: &ServiceState,
+    key: &DeployKey,
+    operation: &'static str,
+    path: String,
+    link: &common::linked_data::Link,
+) -> Result<Response, DeployError> {
+    state
+        .audit(
+            AuditEvent::new(Actor::Deploy(key.id), operation)
+                .bucket(key.bucket_id)
+                .path(path.clone())
+                .version(link),
+        )
+        .await;
+    if let Err(e) = state.database().touch_deploy_key(&key.id).await {
+        tracing::warn!("Failed to record use of deploy key {}: {}", key.id, e);
+    }
+
+    Ok((
+        StatusCode::OK,
+        Json(DeployResponse {
+            bucket_id: key.bucket_id,
+            path,
+            new_bucket_link: link.hash().to_string(),
+        }),
+    )
+        .into_response())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DeployError {
+    #[error("Missing header: {0}")]
+    MissingHeader(&'static str),
+    #[error("Invalid deploy key or signature")]
+    Unauthorized,
+    #[error("Request timestamp is too far from the server's clock")]
+    Stale,
+    #[error("Request was sent already")]
+    Replayed,
+    #[error("Deploy key may not write to {0}")]
+    Forbidden(String),
+    #[error("Invalid path: {0}")]
+    InvalidPath(String),
+    #[error("Body does not match its content hash")]
+    ContentMismatch,
+    #[error("Failed to read body: {0}")]
+    Body(String),
+    #[error("Body is larger than the upload limit of {0} bytes")]
+    TooLarge(u64),
+    #[error("Path not found: {0}")]
+    PathNotFound(String),
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Mount error: {0}")]
+    Mount(#[from] MountError),
+}
+
+impl IntoResponse for DeployError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            DeployError::MissingHeader(_)
+            | DeployError::Unauthorized
+            | DeployError::Stale
+            | DeployError::Replayed => StatusCode::UNAUTHORIZED,
+            DeployError::Forbidden(_) => StatusCode::FORBIDDEN,
+            DeployError::InvalidPath(_) | DeployError::ContentMismatch | DeployError::Body(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            DeployError::PathNotFound(_) => StatusCode::NOT_FOUND,
+            DeployError::TooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            DeployError::Database(_) | DeployError::Mount(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, format!("Error: {}", self)).into_response()
+    }
+}
@@ -0,0 +1,185 @@
+//! Anonymous uploads through drop links on the gateway (see
+//! [`crate::drop_box`])
+//!
+//! Only `POST` is routed: a drop link can't list or read anything, not even
+//! the files uploaded through it.
+
+use axum::extract::multipart::Field;
+use axum::extract::{DefaultBodyLimit, Multipart, Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use time::OffsetDateTime;
+
+use crate::audit::{Actor, AuditEvent};
+use crate::drop_box::{
+    sanitize_file_name, verify_captcha, DropLink, DropReceipt, QueuedFile, CAPTCHA_HEADER,
+    MAX_SIZE_LIMIT,
+};
+use crate::ServiceState;
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/:token", post(upload_handler))
+        .layer(DefaultBodyLimit::max(MAX_SIZE_LIMIT as usize))
+        .with_state(state)
+}
+
+/// Queue the files of a multipart upload for the link owner to review
+pub async fn upload_handler(
+    State(state): State<ServiceState>,
+    Path(token): Path<String>,
+    headers: HeaderMap,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, DropError> {
+    let link = state
+        .database()
+        .get_drop_link_by_token(&token)
+        .await?
+        .ok_or(DropError::UnknownLink)?;
+    if link.is_expired(OffsetDateTime::now_utc()) {
+        return Err(DropError::Expired);
+    }
+
+    let mut captcha_token = headers
+        .get(CAPTCHA_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let mut files = Vec::new();
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| DropError::Multipart(e.to_string()))?
+    {
+        match field.name().unwrap_or("") {
+            "captcha_token" => {
+                let text = field
+                    .text()
+                    .await
+                    .map_err(|e| DropError::Multipart(e.to_string()))?;
+                captcha_token = Some(text);
+            }
+            "file" | "files" => {
+                let raw_name = field.file_name().unwrap_or("").to_string();
+                let file_name = sanitize_file_name(&raw_name)
+                    .ok_or_else(|| DropError::InvalidFileName(raw_name.clone()))?;
+                let mime_type = mime_guess::from_path(&file_name)
+                    .first_or_octet_stream()
+                    .to_string();
+                if !link.allows_type(&mime_type) {
+                    return Err(DropError::TypeNotAllowed(file_name, mime_type));
+                }
+                let content = read_limited(field, &link, &file_name).await?;
+                files.push((file_name, mime_type, content));
+            }
+            name => {
+                tracing::debug!("Ignoring unknown drop upload field: {}", name);
+            }
+        }
+    }
+    if files.is_empty() {
+        return Err(DropError::NoFiles);
+    }
+
+    // Checked once the whole form is read, as the token may come last
+    if let Some(captcha) = &link.captcha {
+        let token = captcha_token
+            .filter(|token| !token.is_empty())
+            .ok_or(DropError::CaptchaRequired)?;
+        let passed = verify_captcha(&reqwest::Client::new(), captcha, &token)
+            .await
+            .map_err(|e| DropError::CaptchaUnavailable(e.to_string()))?;
+        if !passed {
+            return Err(DropError::CaptchaFailed);
+        }
+    }
+
+    let mut queued = Vec::with_capacity(files.len());
+    for (file_name, mime_type, content) in files {
+        let upload = state
+            .database()
+            .queue_drop_upload(&link.id, &file_name, &mime_type, &content)
+            .await?;
+        state
+            .audit(
+                AuditEvent::new(Actor::Drop(link.id), "drop.upload")
+                    .bucket(link.bucket_id)
+                    .path(link.path_of(&upload.file_name))
+                    .detail(upload.id.to_string()),
+            )
+            .await;
+        queued.push(QueuedFile {
+            file_name: upload.file_name,
+            size: upload.size,
+        });
+    }
+
+    Ok((StatusCode::ACCEPTED, Json(DropReceipt { queued })))
+}
+
+/// Read an uploaded file into memory, refusing it once it passes the link's
+/// size limit
+async fn read_limited(
+    mut field: Field<'_>,
+    link: &DropLink,
+    file_name: &str,
+) -> Result<Vec<u8>, DropError> {
+    let mut content = Vec::new();
+    while let Some(chunk) = field
+        .chunk()
+        .await
+        .map_err(|e| DropError::Multipart(e.to_string()))?
+    {
+        if (content.len() + chunk.len()) as u64 > link.max_size {
+            return Err(DropError::TooLarge(file_name.to_string(), link.max_size));
+        }
+        content.extend_from_slice(&chunk);
+    }
+    Ok(content)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DropError {
+    #[error("Unknown drop link")]
+    UnknownLink,
+    #[error("Drop link has expired")]
+    Expired,
+    #[error("Invalid upload: {0}")]
+    Multipart(String),
+    #[error("No files uploaded")]
+    NoFiles,
+    #[error("Invalid file name: {0:?}")]
+    InvalidFileName(String),
+    #[error("{0} is larger than the {1} bytes this link accepts")]
+    TooLarge(String, u64),
+    #[error("{0} is of type {1}, which this link doesn't accept")]
+    TypeNotAllowed(String, String),
+    #[error("This link requires a captcha")]
+    CaptchaRequired,
+    #[error("Captcha verification failed")]
+    CaptchaFailed,
+    #[error("Captcha provider unavailable: {0}")]
+    CaptchaUnavailable(String),
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+impl IntoResponse for DropError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            DropError::UnknownLink => StatusCode::NOT_FOUND,
+            DropError::Expired => StatusCode::GONE,
+            DropError::Multipart(_)
+            | DropError::NoFiles
+            | DropError::InvalidFileName(_)
+            | DropError::CaptchaRequired => StatusCode::BAD_REQUEST,
+            DropError::TooLarge(..) => StatusCode::PAYLOAD_TOO_LARGE,
+            DropError::TypeNotAllowed(..) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            DropError::CaptchaFailed => StatusCode::FORBIDDEN,
+            DropError::CaptchaUnavailable(_) => StatusCode::BAD_GATEWAY,
+            DropError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, format!("Error: {}", self)).into_response()
+    }
+}
@@ -0,0 +1,341 @@
+//! Error responses of the HTTP API
+//!
+//! Every API error is answered with the same JSON envelope:
+//!
+//! ```json
+//! {"error": {"code": "not_found", "message": "Bucket not found: ...", "retryable": false}}
+//! ```
+//!
+//! `code` is machine-readable and stable (see [`ErrorCode`]), `message` is
+//! for people, `details` carries what a client needs to act on some errors
+//! (the head to rebase onto for `head_moved`, the holder for a held lease),
+//! and `retryable` says whether the same request may succeed later as is,
+//! or after rebasing.
+//!
+//! Handlers return [`ApiError`], or their own error types converted into
+//! it. Responses that don't come with the envelope, such as rejected
+//! request bodies, are wrapped in it by [`envelope_errors`] on the way out,
+//! their code following from their status.
+
+use axum::body::{to_bytes, Body};
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use serde::{Deserialize, Serialize};
+
+use common::mount::MountError;
+
+/// Most bytes of an error body read to wrap it in the envelope
+const MAX_WRAPPED_BODY: usize = 64 * 1024;
+
+/// What went wrong, for clients to branch on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// The request is malformed or one of its values is invalid
+    InvalidRequest,
+    /// The request needs credentials it didn't have
+    Unauthorized,
+    /// The node may not do this, or the caller may not ask for it
+    Forbidden,
+    /// The bucket, path or other thing named doesn't exist
+    NotFound,
+    /// The request conflicts with the current state, e.g. a path that
+    /// already exists or a held lease
+    Conflict,
+    /// The bucket head is no longer the `expected_version`; rebase onto
+    /// `details.current_version` and retry
+    HeadMoved,
+    /// The request body is too large
+    PayloadTooLarge,
+    /// The request body isn't in a supported format
+    UnsupportedMediaType,
+    /// The request is well-formed but refused, e.g. a publish vetoed by
+    /// content scanning
+    Unprocessable,
+    /// Too many requests; retry later
+    RateLimited,
+    /// The node failed unexpectedly
+    Internal,
+    /// A peer or upstream service the request depends on failed
+    BadGateway,
+    /// The node can't serve the request right now; retry later
+    Unavailable,
+    /// The version asked for hasn't synced yet; retry later
+    VersionUnavailable,
+    /// The content is in archive storage and being restored, which takes
+    /// hours (`details.restore_time`); retry then
+    Archived,
+    /// A peer or upstream service didn't answer in time
+    Timeout,
+    /// A code this client doesn't know yet
+    #[serde(other)]
+    Unknown,
+}
+
+impl ErrorCode {
+    /// Code of a status, for errors without a more specific one
+    pub fn for_status(status: StatusCode) -> Self {
+        match status {
+            StatusCode::UNAUTHORIZED => ErrorCode::Unauthorized,
+            StatusCode::FORBIDDEN => ErrorCode::Forbidden,
+            StatusCode::NOT_FOUND | StatusCode::GONE => ErrorCode::NotFound,
+            StatusCode::CONFLICT => ErrorCode::Conflict,
+            StatusCode::PAYLOAD_TOO_LARGE => ErrorCode::PayloadTooLarge,
+            StatusCode::UNSUPPORTED_MEDIA_TYPE => ErrorCode::UnsupportedMediaType,
+            StatusCode::UNPROCESSABLE_ENTITY => ErrorCode::Unprocessable,
+            StatusCode::TOO_MANY_REQUESTS => ErrorCode::RateLimited,
+            StatusCode::BAD_GATEWAY => ErrorCode::BadGateway,
+            StatusCode::SERVICE_UNAVAILABLE => ErrorCode::Unavailable,
+            StatusCode::GATEWAY_TIMEOUT => ErrorCode::Timeout,
+            status if status.is_client_error() => ErrorCode::InvalidRequest,
+            _ => ErrorCode::Internal,
+        }
+    }
+
+    /// Whether a request failing with this code may succeed when retried
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            ErrorCode::HeadMoved
+                | ErrorCode::RateLimited
+                | ErrorCode::BadGateway
+                | ErrorCode::Unavailable
+                | ErrorCode::VersionUnavailable
+                | ErrorCode::Archived
+                | ErrorCode::Timeout
+        )
+    }
+}
+
+/// Body of an error response, inside the envelope
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorBody {
+    pub code: ErrorCode,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+    #[serde(default)]
+    pub retryable: bool,
+}
+
+impl std::fmt::Display for ErrorBody {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl ErrorBody {
+    /// Read an error response body: the envelope, or the text of a server
+    /// that doesn't send one
+    pub fn parse(status: StatusCode, body: &str) -> Self {
+        match serde_json::from_str::<ErrorEnvelope>(body) {
+            Ok(envelope) => envelope.error,
+            Err(_) => ApiError::new(status, body.trim()).body,
+        }
+    }
+}
+
+/// JSON body of every API error response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorEnvelope {
+    pub error: ErrorBody,
+}
+
+/// An error response of the API
+#[derive(Debug, Clone)]
+pub struct ApiError {
+    status: StatusCode,
+    body: ErrorBody,
+}
+
+impl ApiError {
+    /// Error with the code of its status
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        let code = ErrorCode::for_status(status);
+        Self {
+            status,
+            body: ErrorBody {
+                code,
+                message: message.into(),
+                details: None,
+                retryable: code.is_retryable(),
+            },
+        }
+    }
+
+    /// Use a more specific code than the status gives
+    pub fn with_code(mut self, code: ErrorCode) -> Self {
+        self.body.code = code;
+        self.body.retryable = code.is_retryable();
+        self
+    }
+
+    /// Attach what a client needs to act on the error
+    pub fn with_details(mut self, details: impl Serialize) -> Self {
+        self.body.details = serde_json::to_value(details).ok();
+        self
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn body(&self) -> &ErrorBody {
+        &self.body
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, axum::Json(ErrorEnvelope { error: self.body })).into_response()
+    }
+}
+
+/// Whether reading a bucket failed on an archived blob, which [`ApiError`]
+/// answers with a 503 saying how long its restore takes
+pub fn is_archived(e: &MountError) -> bool {
+    matches!(e, MountError::BlobsStore(blobs) if blobs.archived().is_some())
+}
+
+/// The status and code of errors reading or writing a bucket, shared by
+/// the handlers that mount one
+impl From<MountError> for ApiError {
+    fn from(e: MountError) -> Self {
+        if let MountError::BlobsStore(blobs) = &e {
+            if let Some(class) = blobs.archived() {
+                return ApiError::new(StatusCode::SERVICE_UNAVAILABLE, e.to_string())
+                    .with_code(ErrorCode::Archived)
+                    .with_details(serde_json::json!({
+                        "storage_class": class,
+                        "restore_time": class.restore_time(),
+                    }));
+            }
+        }
+        match e {
+            MountError::HeadMoved(head) => super::api::v0::bucket::head_moved(&head),
+            MountError::PathNotFound(_)
+            | MountError::LinkNotFound(_)
+            | MountError::CommentNotFound(_) => ApiError::new(StatusCode::NOT_FOUND, e.to_string()),
+            MountError::PathAlreadyExists(_) | MountError::AlreadyShared(_) => {
+                ApiError::new(StatusCode::CONFLICT, e.to_string())
+            }
+            MountError::VersionUnavailable(_) => {
+                ApiError::new(StatusCode::SERVICE_UNAVAILABLE, e.to_string())
+                    .with_code(ErrorCode::VersionUnavailable)
+            }
+            MountError::ShareNotFound
+            | MountError::MirrorCannotMount
+            | MountError::SubtreeReadOnly
+            | MountError::EphemeralReadOnly => ApiError::new(StatusCode::FORBIDDEN, e.to_string()),
+            MountError::InvalidAttrs(_)
+            | MountError::InvalidComment(_)
+            | MountError::BucketReference(_)
+            | MountError::ReferenceCycle(_)
+            | MountError::ReferencesTooDeep(_) => {
+                ApiError::new(StatusCode::BAD_REQUEST, e.to_string())
+            }
+            e => ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        }
+    }
+}
+
+/// Wrap API error responses that aren't in the envelope yet, such as
+/// rejected request bodies or errors of handlers answering with text
+pub async fn envelope_errors(response: Response) -> Response {
+    let status = response.status();
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    if !(status.is_client_error() || status.is_server_error()) || is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let text = match to_bytes(body, MAX_WRAPPED_BODY).await {
+        Ok(bytes) => String::from_utf8_lossy(&bytes).trim().to_string(),
+        Err(_) => String::new(),
+    };
+    let message = match text.strip_prefix("Error: ") {
+        Some(message) => message.to_string(),
+        None if text.is_empty() => status
+            .canonical_reason()
+            .unwrap_or("Request failed")
+            .to_string(),
+        None => text,
+    };
+    let body = serde_json::to_vec(&ErrorEnvelope {
+        error: ApiError::new(status, message).body,
+    })
+    .expect("error bodies serialize");
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    Response::from_parts(parts, Body::from(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn read(response: Response) -> (StatusCode, ErrorBody) {
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), MAX_WRAPPED_BODY)
+            .await
+            .unwrap();
+        let envelope: ErrorEnvelope = serde_json::from_slice(&bytes).unwrap();
+        (status, envelope.error)
+    }
+
+    #[tokio::test]
+    async fn test_text_errors_are_wrapped() {
+        let response = (StatusCode::NOT_FOUND, "Error: Bucket not found: x").into_response();
+        let (status, body) = read(envelope_errors(response).await).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body.code, ErrorCode::NotFound);
+        assert_eq!(body.message, "Bucket not found: x");
+        assert!(!body.retryable);
+
+        let response = StatusCode::SERVICE_UNAVAILABLE.into_response();
+        let (_, body) = read(envelope_errors(response).await).await;
+        assert_eq!(body.code, ErrorCode::Unavailable);
+        assert_eq!(body.message, "Service Unavailable");
+        assert!(body.retryable);
+    }
+
+    #[tokio::test]
+    async fn test_envelopes_and_successes_pass_through() {
+        let error = ApiError::new(StatusCode::CONFLICT, "moved")
+            .with_code(ErrorCode::HeadMoved)
+            .with_details(serde_json::json!({"current_version": "abc"}));
+        let (status, body) = read(envelope_errors(error.into_response()).await).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body.code, ErrorCode::HeadMoved);
+        assert!(body.retryable);
+        assert_eq!(body.details.unwrap()["current_version"], "abc");
+
+        let response = envelope_errors((StatusCode::OK, "fine").into_response()).await;
+        let bytes = to_bytes(response.into_body(), MAX_WRAPPED_BODY)
+            .await
+            .unwrap();
+        assert_eq!(&bytes[..], b"fine");
+    }
+
+    #[test]
+    fn test_parse_reads_envelopes_and_text() {
+        let body = ErrorBody::parse(
+            StatusCode::BAD_REQUEST,
+            r#"{"error": {"code": "some_new_code", "message": "nope"}}"#,
+        );
+        assert_eq!(body.code, ErrorCode::Unknown);
+        assert_eq!(body.message, "nope");
+
+        let body = ErrorBody::parse(StatusCode::BAD_GATEWAY, "upstream down\n");
+        assert_eq!(body.code, ErrorCode::BadGateway);
+        assert_eq!(body.message, "upstream down");
+        assert!(body.retryable);
+    }
+}
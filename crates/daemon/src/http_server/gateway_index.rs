@@ -1,10 +1,12 @@
 use askama::Template;
 use askama_axum::IntoResponse;
 use axum::extract::State;
+use axum::Extension;
 use uuid::Uuid;
 
 use common::bucket_log::BucketLogProvider;
 
+use crate::reverse_proxy::RequestOrigin;
 use crate::ServiceState;
 
 /// Bucket display info for the gateway homepage
@@ -20,13 +22,18 @@ pub struct BucketDisplayInfo {
 #[derive(Template)]
 #[template(path = "pages/gateway/index.html")]
 pub struct GatewayIndexTemplate {
+    /// Path prefix the gateway is served under, empty when there is none
+    pub base_path: String,
     pub node_id: String,
     pub buckets: Vec<BucketDisplayInfo>,
 }
 
 /// Root page handler for the gateway.
 /// Displays the gateway's public identity and available buckets.
-pub async fn handler(State(state): State<ServiceState>) -> askama_axum::Response {
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Extension(origin): Extension<RequestOrigin>,
+) -> askama_axum::Response {
     let node_id = state.peer().id().to_string();
 
     // List all buckets
@@ -40,6 +47,14 @@ pub async fn handler(State(state): State<ServiceState>) -> askama_axum::Response
     // Only include buckets that have a published version
     let mut buckets = Vec::new();
     for b in db_buckets {
+        // Only list buckets the gateway serves
+        if !state.gateway_policies().serves(&b.id) {
+            continue;
+        }
+        // Buckets with a rotated link are only reachable through that link
+        if matches!(state.database().gateway_link(&b.id).await, Ok(Some(_))) {
+            continue;
+        }
         // Only show buckets that have been published
         match state.peer().logs().latest_published(b.id).await {
             Ok(Some((link, _height))) => {
@@ -64,7 +79,11 @@ pub async fn handler(State(state): State<ServiceState>) -> askama_axum::Response
         }
     }
 
-    let template = GatewayIndexTemplate { node_id, buckets };
+    let template = GatewayIndexTemplate {
+        base_path: origin.base_path,
+        node_id,
+        buckets,
+    };
 
     template.into_response()
 }
@@ -9,6 +9,7 @@ mod data_source;
 mod identity;
 mod liveness;
 mod readiness;
+mod replica;
 mod version;
 
 use crate::ServiceState;
@@ -18,17 +19,31 @@ use crate::ServiceState;
 const HEALTHCHECK_REQUEST_SIZE_LIMIT: usize = 1_024;
 
 pub fn router(state: ServiceState) -> Router<ServiceState> {
-    let cors_layer = CorsLayer::new()
-        .allow_methods(vec![Method::GET])
-        .allow_headers(vec![ACCEPT, ORIGIN])
-        .allow_origin(Any)
-        .allow_credentials(false);
+    with_layers(routes(), state)
+}
+
+/// Health endpoints of the API server, which also lists how fresh a read
+/// replica's buckets are
+pub fn api_router(state: ServiceState) -> Router<ServiceState> {
+    with_layers(routes().route("/replica", get(replica::handler)), state)
+}
 
+fn routes() -> Router<ServiceState> {
     Router::new()
         .route("/livez", get(liveness::handler))
         .route("/readyz", get(readiness::handler))
         .route("/version", get(version::handler))
         .route("/identity", get(identity::handler))
+}
+
+fn with_layers(routes: Router<ServiceState>, state: ServiceState) -> Router<ServiceState> {
+    let cors_layer = CorsLayer::new()
+        .allow_methods(vec![Method::GET])
+        .allow_headers(vec![ACCEPT, ORIGIN])
+        .allow_origin(Any)
+        .allow_credentials(false);
+
+    routes
         .with_state(state)
         .layer(cors_layer)
         .layer(RequestBodyLimitLayer::new(HEALTHCHECK_REQUEST_SIZE_LIMIT))
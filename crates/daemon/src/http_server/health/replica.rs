@@ -0,0 +1,57 @@
+use axum::extract::State;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use http::StatusCode;
+use serde::Serialize;
+
+use crate::replica::SyncedVersion;
+use crate::ServiceState;
+
+#[derive(Serialize)]
+pub struct ReplicaResponse {
+    pub status: &'static str,
+    /// Latest published version synced of each bucket the gateway serves
+    pub buckets: Vec<BucketStaleness>,
+}
+
+#[derive(Serialize)]
+pub struct BucketStaleness {
+    #[serde(flatten)]
+    pub synced: SyncedVersion,
+    /// Seconds since the version was synced
+    pub age_secs: u64,
+}
+
+/// How fresh a read replica's buckets are (see [`crate::replica`])
+#[tracing::instrument(skip(state))]
+pub async fn handler(State(state): State<ServiceState>) -> Response {
+    if !state.is_read_replica() {
+        let msg = serde_json::json!({"status": "failure", "message": "not a read replica"});
+        return (StatusCode::NOT_FOUND, Json(msg)).into_response();
+    }
+    let synced = match state.database().synced_versions().await {
+        Ok(synced) => synced,
+        Err(e) => {
+            tracing::error!("Failed to list synced versions: {}", e);
+            let msg = serde_json::json!({"status": "failure", "message": "failed to list buckets"});
+            return (StatusCode::SERVICE_UNAVAILABLE, Json(msg)).into_response();
+        }
+    };
+    let policies = state.gateway_policies();
+    let buckets = synced
+        .into_iter()
+        .filter(|synced| policies.serves(&synced.bucket_id))
+        .map(|synced| BucketStaleness {
+            age_secs: synced.age_secs(),
+            synced,
+        })
+        .collect();
+    (
+        StatusCode::OK,
+        Json(ReplicaResponse {
+            status: "ok",
+            buckets,
+        }),
+    )
+        .into_response()
+}
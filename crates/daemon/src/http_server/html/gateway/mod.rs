@@ -1,12 +1,26 @@
 use askama::Template;
+use axum::body::HttpBody;
 use axum::extract::{Path, Query, State};
+use axum::http::Method;
 use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use futures::StreamExt;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::sync::LazyLock;
-use uuid::Uuid;
 
 use common::mount::NodeLink;
+use common::peer::Resolved;
+
+use crate::bucket_index::{find_readme, IndexSettings};
+use crate::gateway_sync::{self, SyncProgress, SYNC_PROGRESS_HEADER};
+use crate::http_server::conditional;
+use crate::image_variants::{ImageVariants, VariantParams};
+use crate::reverse_proxy::RequestOrigin;
+use crate::robots::{sitemap_xml, NOINDEX, ROBOTS_TAG_HEADER, SITEMAP_NAME};
+use crate::version_url::{VersionSelector, VERSION_MARKER};
+
+mod render;
 
 /// Format a byte count into a human-readable string (e.g., "1.50 KB")
 fn format_bytes(bytes: usize) -> String {
@@ -38,15 +52,91 @@ static MARKDOWN_LINK_REGEX: LazyLock<Regex> =
 pub struct GatewayQuery {
     #[serde(default)]
     pub at: Option<String>,
-    /// If true, serve the raw file with Content-Disposition: attachment
-    #[serde(default)]
-    pub download: Option<bool>,
+    /// Serve the raw file with Content-Disposition: attachment, or a
+    /// directory as a zip archive
+    #[serde(default, deserialize_with = "deserialize_download")]
+    pub download: Option<Download>,
     /// If true, show the file in viewer UI even if it's HTML/Markdown
     #[serde(default)]
     pub view: Option<bool>,
     /// If true, recursively list all files under the path (deep listing)
     #[serde(default)]
     pub deep: Option<bool>,
+    /// Whether to render text files (markdown to HTML, code highlighted) or
+    /// serve them as plain text. Defaults to rendering for clients that
+    /// accept HTML.
+    #[serde(default, deserialize_with = "deserialize_flag")]
+    pub render: Option<bool>,
+    /// Width to scale an image down to
+    #[serde(default)]
+    pub w: Option<u32>,
+    /// Height to scale an image down to
+    #[serde(default)]
+    pub h: Option<u32>,
+    /// Format asked for a scaled image (`png`, `webp` or `jpeg`); scaled
+    /// images are currently always PNG
+    #[serde(default)]
+    pub format: Option<String>,
+    /// Seconds to wait for a bucket that is still syncing, up to
+    /// [`gateway_sync::MAX_WAIT`]
+    #[serde(default)]
+    pub wait: Option<u64>,
+}
+
+impl GatewayQuery {
+    /// The image variant asked for, if any
+    fn variant(&self) -> Result<VariantParams, String> {
+        let params = VariantParams {
+            width: self.w,
+            height: self.h,
+            format: self.format.as_deref().map(str::parse).transpose()?,
+        };
+        params.validate()?;
+        Ok(params)
+    }
+}
+
+/// Read a query flag given as `1`/`0` or `true`/`false`
+fn deserialize_flag<'de, D>(deserializer: D) -> Result<Option<bool>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = Option::<String>::deserialize(deserializer)?;
+    match value.as_deref() {
+        None => Ok(None),
+        Some("1") | Some("true") => Ok(Some(true)),
+        Some("0") | Some("false") => Ok(Some(false)),
+        Some(other) => Err(serde::de::Error::custom(format!(
+            "expected 1, 0, true or false, got {}",
+            other
+        ))),
+    }
+}
+
+/// What `?download=` asked for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Download {
+    /// The file itself, as an attachment (`true` or `1`)
+    File,
+    /// A directory as a zip archive (`zip`)
+    Zip,
+}
+
+/// Read `?download=` given as `zip`, `1`/`0` or `true`/`false`
+fn deserialize_download<'de, D>(deserializer: D) -> Result<Option<Download>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = Option::<String>::deserialize(deserializer)?;
+    match value.as_deref() {
+        None | Some("0") | Some("false") => Ok(None),
+        Some("1") | Some("true") => Ok(Some(Download::File)),
+        Some("zip") => Ok(Some(Download::Zip)),
+        Some(other) => Err(serde::de::Error::custom(format!(
+            "expected zip, 1, 0, true or false, got {}",
+            other
+        ))),
+    }
 }
 
 /// Path segment for breadcrumb navigation
@@ -65,24 +155,48 @@ pub struct FileDisplayInfo {
     pub is_dir: bool,
 }
 
+/// What the index page at a bucket's root shows above its listing
+#[derive(Debug, Clone)]
+pub struct IndexPage {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub featured: Vec<FileDisplayInfo>,
+    /// Sanitized HTML of the README
+    pub readme_html: Option<String>,
+}
+
 /// Template for directory explorer
 #[derive(Template)]
 #[template(path = "pages/gateway/explorer.html")]
 pub struct GatewayExplorerTemplate {
+    /// Path prefix the gateway is served under, empty when there is none
+    pub base_path: String,
     pub bucket_id: String,
+    /// Bucket id or rotated link token the gateway serves the bucket at,
+    /// with the version segment when a version was named in the path
+    pub gateway_id: String,
     pub bucket_id_short: String,
     pub bucket_name: String,
     pub bucket_link: String,
     pub bucket_link_short: String,
     pub path_segments: Vec<PathSegment>,
     pub items: Vec<FileDisplayInfo>,
+    /// Shown at the bucket root, when it has anything to show
+    pub index: Option<IndexPage>,
+    /// Whether the listing is shown; only index pages are served without it
+    pub listings: bool,
 }
 
 /// Template for file viewer
 #[derive(Template)]
 #[template(path = "pages/gateway/viewer.html")]
 pub struct GatewayViewerTemplate {
+    /// Path prefix the gateway is served under, empty when there is none
+    pub base_path: String,
     pub bucket_id: String,
+    /// Bucket id or rotated link token the gateway serves the bucket at,
+    /// with the version segment when a version was named in the path
+    pub gateway_id: String,
     pub bucket_id_short: String,
     pub bucket_name: String,
     pub bucket_link: String,
@@ -92,11 +206,16 @@ pub struct GatewayViewerTemplate {
     pub mime_type: String,
     pub size_formatted: String,
     pub content: String,
+    /// Highlighted HTML of a code file, shown instead of `content`
+    pub highlighted: Option<String>,
+    /// Whether the file is text that can be shown plain
+    pub is_text: bool,
     pub back_url: String,
 }
 
 #[derive(Debug, Serialize)]
 pub struct DirectoryListing {
+    pub bucket_id: String,
     pub path: String,
     pub entries: Vec<DirectoryEntry>,
 }
@@ -111,38 +230,69 @@ pub struct DirectoryEntry {
 /// Handler for bucket root requests (no file path)
 pub async fn root_handler(
     state: State<ServiceState>,
-    Path(bucket_id): Path<Uuid>,
+    Path(gateway_id): Path<String>,
     query: Query<GatewayQuery>,
+    method: Method,
+    origin: Extension<RequestOrigin>,
     headers: axum::http::HeaderMap,
 ) -> Response {
     // Delegate to main handler with "/" as the path
-    handler(state, Path((bucket_id, "/".to_string())), query, headers).await
+    handler(
+        state,
+        Path((gateway_id, "/".to_string())),
+        query,
+        method,
+        origin,
+        headers,
+    )
+    .await
 }
 
 pub async fn handler(
     State(state): State<ServiceState>,
-    Path((bucket_id, file_path)): Path<(Uuid, String)>,
+    Path((gateway_id, file_path)): Path<(String, String)>,
     Query(query): Query<GatewayQuery>,
+    method: Method,
+    Extension(origin): Extension<RequestOrigin>,
     headers: axum::http::HeaderMap,
 ) -> Response {
-    // Extract host from request headers, fallback to localhost
-    let host = headers
-        .get(axum::http::header::HOST)
-        .and_then(|h| h.to_str().ok())
-        .map(|h| {
-            // Check if the host includes a scheme
-            if h.starts_with("http://") || h.starts_with("https://") {
-                h.to_string()
-            } else {
-                // Assume https for production, http for localhost
-                if h.contains("localhost") || h.starts_with("127.0.0.1") {
-                    format!("http://{}", h)
-                } else {
-                    format!("https://{}", h)
-                }
+    let head = method == Method::HEAD;
+
+    // Buckets are served at their id, or at their link token once rotated
+    let bucket_id = match state.resolve_gateway_id(&gateway_id).await {
+        Ok(Some(bucket_id)) => bucket_id,
+        Ok(None) => return not_found_response("bucket"),
+        Err(e) => {
+            tracing::error!("Failed to resolve gateway link {}: {}", gateway_id, e);
+            return error_response("Failed to resolve bucket");
+        }
+    };
+
+    // The operator decides which buckets are served, and how much of them
+    let policies = state.gateway_policies();
+    if !policies.serves(&bucket_id) {
+        return not_found_response("bucket");
+    }
+    if policies.over_bandwidth(&bucket_id) {
+        return bandwidth_exceeded_response();
+    }
+    let policy = policies.get(&bucket_id);
+
+    // Links are absolute, through any reverse proxy and its path prefix
+    let host = origin.base_url;
+
+    // Published versions can be named in the path: /gw/<id>/@v3/path.
+    // Pages of such a version link within it.
+    let (version, file_path, gateway_id) = match VersionSelector::split_path(&file_path) {
+        Some((selector, rest)) => match selector.parse::<VersionSelector>() {
+            Ok(version) => {
+                let gateway_id = format!("{}/{}{}", gateway_id, VERSION_MARKER, selector);
+                (Some(version), rest, gateway_id)
             }
-        })
-        .unwrap_or_else(|| "http://localhost".to_string());
+            Err(e) => return bad_request_response(&e.to_string()),
+        },
+        None => (None, file_path, gateway_id),
+    };
 
     // Ensure path is absolute
     let absolute_path = if file_path.starts_with('/') {
@@ -151,57 +301,166 @@ pub async fn handler(
         format!("/{}", file_path)
     };
 
-    // Load mount - either from specific link or latest published version
-    // Gateways always show the last published version, never HEAD
-    let mount = if let Some(hash_str) = &query.at {
-        // Parse the hash string and create a Link
-        match hash_str.parse::<common::linked_data::Hash>() {
+    // Set when an older version is served while the latest is syncing
+    let mut stale_progress = None;
+
+    // A version asked for by name or hash, resolved against the bucket log
+    let pinned = match (version, &query.at) {
+        (Some(version), _) => {
+            let database = state.database();
+            let found = match version {
+                VersionSelector::Height(height) => {
+                    database
+                        .published_version_at_height(&bucket_id, height)
+                        .await
+                }
+                VersionSelector::AsOf(time) => {
+                    database.published_version_as_of(&bucket_id, time).await
+                }
+            };
+            match found {
+                Ok(Some(link)) => Some(link),
+                Ok(None) => return not_found_response("version"),
+                Err(e) => {
+                    tracing::error!("Failed to resolve version {}: {}", version, e);
+                    return error_response("Failed to load historical version");
+                }
+            }
+        }
+        (None, Some(hash_str)) => match hash_str.parse::<common::linked_data::Hash>() {
             Ok(hash) => {
-                let link = common::linked_data::Link::new(common::linked_data::LD_RAW_CODEC, hash);
-                match common::mount::Mount::load(&link, state.peer().secret(), state.peer().blobs())
+                // Only published versions of this bucket are served
+                match state
+                    .database()
+                    .is_published_version(&bucket_id, &hash)
                     .await
                 {
-                    Ok(mount) => mount,
+                    Ok(true) => {}
+                    Ok(false) => return not_found_response("version"),
                     Err(e) => {
-                        tracing::error!("Failed to load mount from link: {}", e);
+                        tracing::error!("Failed to check published version: {}", e);
                         return error_response("Failed to load historical version");
                     }
                 }
+                Some(common::linked_data::Link::new(
+                    common::linked_data::LD_RAW_CODEC,
+                    hash,
+                ))
             }
             Err(e) => {
                 tracing::error!("Failed to parse hash: {}", e);
                 return error_response("Invalid hash format");
             }
+        },
+        (None, None) => None,
+    };
+
+    // Load mount - either from specific link or latest published version
+    // Gateways always show the last published version, never HEAD
+    let mount = if let Some(link) = &pinned {
+        match common::mount::Mount::load(link, state.peer().secret(), state.peer().blobs()).await {
+            Ok(mount) => mount,
+            Err(e) => {
+                tracing::error!("Failed to load mount from link: {}", e);
+                return error_response("Failed to load historical version");
+            }
         }
     } else {
-        // Always use latest published version
-        use common::bucket_log::BucketLogProvider;
-        match state.peer().logs().latest_published(bucket_id).await {
-            Ok(Some((published_link, _height))) => {
-                match common::mount::Mount::load(
-                    &published_link,
-                    state.peer().secret(),
-                    state.peer().blobs(),
-                )
-                .await
-                {
-                    Ok(mount) => mount,
-                    Err(_e) => {
-                        return syncing_response();
+        // Always use latest published version, waiting for it if asked to
+        let wait = std::time::Duration::from_secs(query.wait.unwrap_or(0));
+        match gateway_sync::load_latest_published(state.peer(), bucket_id, wait).await {
+            Ok(mount) => mount,
+            Err(progress) => {
+                let stale = match progress {
+                    Some(_) if policy.serve_stale => {
+                        gateway_sync::load_last_complete(state.peer(), state.database(), bucket_id)
+                            .await
+                    }
+                    _ => None,
+                };
+                match stale {
+                    Some(mount) => {
+                        stale_progress = progress;
+                        mount
                     }
+                    None => return syncing_response(progress, wants_json(&headers)),
                 }
             }
-            _ => {
-                // No published version available
-                return syncing_response();
-            }
         }
     };
 
+    // Versions synced from peers are scanned before they are first served
+    match state.may_serve(bucket_id, &mount).await {
+        Ok(true) => {}
+        Ok(false) => return blocked_response(),
+        Err(e) => {
+            tracing::error!("Failed to check scans of bucket {}: {}", bucket_id, e);
+            return error_response("Failed to load bucket");
+        }
+    }
+
     let path_buf = std::path::PathBuf::from(&absolute_path);
 
+    // Crawlers are kept away if the operator or the publisher asks for it
+    let noindex = policy.noindex
+        || match mount.attrs(std::path::Path::new("/")).await {
+            Ok(attrs) => IndexSettings::from_attrs(&attrs).noindex,
+            Err(e) => {
+                tracing::warn!("Failed to read bucket root attributes: {}", e);
+                false
+            }
+        };
+
+    // Buckets without a sitemap of their own get one listing their files
+    if absolute_path == format!("/{}", SITEMAP_NAME) && mount.get(&path_buf).await.is_err() {
+        if noindex {
+            return not_found_response("sitemap");
+        }
+        return sitemap_response(&state, bucket_id, &mount, &host, &gateway_id).await;
+    }
+
+    // Paths in referenced buckets are served from those buckets, which have
+    //  to be published and pass their own scans. The page still shows this
+    //  bucket.
+    let bucket_mount = mount.clone();
+    let Resolved {
+        mount,
+        path: path_buf,
+        mount_point,
+    } = match state.peer().resolve(mount, &path_buf).await {
+        Ok(resolved) => resolved,
+        Err(e) => {
+            tracing::warn!("Failed to resolve path {}: {}", absolute_path, e);
+            return not_found_response(&format!("Path not found: {}", absolute_path));
+        }
+    };
+    let served_id = *mount.inner().await.manifest().id();
+    if served_id != bucket_id {
+        let served_version = mount.link().await;
+        match state
+            .database()
+            .is_published_version(&served_id, &served_version.hash())
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => return not_found_response(&format!("Path not found: {}", absolute_path)),
+            Err(e) => {
+                tracing::error!("Failed to check published version: {}", e);
+                return error_response("Failed to load bucket");
+            }
+        }
+        match state.may_serve(served_id, &mount).await {
+            Ok(true) => {}
+            Ok(false) => return blocked_response(),
+            Err(e) => {
+                tracing::error!("Failed to check scans of bucket {}: {}", served_id, e);
+                return error_response("Failed to load bucket");
+            }
+        }
+    }
+
     // Handle root path specially - it's always a directory
-    let is_root = absolute_path == "/";
+    let is_root = path_buf == std::path::Path::new("/");
 
     // Try to get the node to determine if it's a file or directory
     // Root path doesn't need mount.get() - it's implicitly a directory
@@ -220,12 +479,20 @@ pub async fn handler(
     // Check if it's a directory (None means root, which is always a directory)
     let is_directory = match &node_link {
         None => true, // Root is always a directory
-        Some(NodeLink::Dir(_, _)) => true,
+        Some(NodeLink::Dir(_, _) | NodeLink::Bucket(_)) => true,
         Some(NodeLink::Data(_, _, _)) => false,
     };
 
+    // Clients holding this version already needn't fetch it again
+    let last_modified = conditional::last_modified(&state, served_id, &mount).await;
+    if let Some(mut response) = conditional::not_modified(&headers, last_modified) {
+        set_robots_tag(&mut response, noindex);
+        set_sync_progress(&mut response, stale_progress);
+        return response;
+    }
+
     // Get bucket metadata from mount
-    let inner = mount.inner().await;
+    let inner = bucket_mount.inner().await;
     let bucket_name = inner.manifest().name().to_string();
     let bucket_id_str = bucket_id.to_string();
     let bucket_id_short = format!(
@@ -240,20 +507,60 @@ pub async fn handler(
         &bucket_link[bucket_link.len() - 8..]
     );
 
-    if is_directory {
+    // Directories can be downloaded whole, zipped as they're sent
+    if is_directory && query.download == Some(Download::Zip) {
+        if !policy.listings {
+            return listings_disabled_response();
+        }
+        let name = crate::zip_stream::archive_name(&path_buf, &bucket_name);
+        // The archive's length isn't known until it has been built
+        if head {
+            let empty = futures::stream::empty::<Result<axum::body::Bytes, std::io::Error>>();
+            let mut response = zip_response(&name, axum::body::Body::from_stream(empty));
+            conditional::set_last_modified(&mut response, last_modified);
+            set_robots_tag(&mut response, noindex);
+            set_sync_progress(&mut response, stale_progress);
+            return response;
+        }
+        let mut response = match crate::zip_stream::archive(mount.clone(), &path_buf).await {
+            Ok(archive) => {
+                // Streamed archives have no size up front, so count them as sent
+                let policies = policies.clone();
+                let archive = archive.inspect(move |chunk| {
+                    if let Ok(chunk) = chunk {
+                        policies.record_served(bucket_id, chunk.len() as u64);
+                    }
+                });
+                zip_response(&name, axum::body::Body::from_stream(archive))
+            }
+            Err(e) => {
+                tracing::error!("Failed to list {} for zip: {}", absolute_path, e);
+                return error_response("Failed to list directory");
+            }
+        };
+        conditional::set_last_modified(&mut response, last_modified);
+        set_robots_tag(&mut response, noindex);
+        set_sync_progress(&mut response, stale_progress);
+        return response;
+    }
+
+    let mut response = if is_directory {
         handle_directory(
             &mount,
             &path_buf,
+            &mount_point,
             &absolute_path,
             &query,
             &headers,
             &host,
-            &bucket_id,
+            &origin.base_path,
+            &gateway_id,
             &bucket_id_str,
             &bucket_id_short,
             &bucket_name,
             &bucket_link,
             &bucket_link_short,
+            policy.listings,
         )
         .await
     } else {
@@ -264,32 +571,100 @@ pub async fn handler(
             &query,
             &headers,
             &host,
-            &bucket_id,
+            &origin.base_path,
+            &gateway_id,
             &bucket_id_str,
             &bucket_id_short,
             &bucket_name,
             &bucket_link,
             &bucket_link_short,
             node_link.unwrap(),
+            state.image_variants(),
+            head,
         )
         .await
+    };
+
+    if response.status().is_success() {
+        conditional::set_last_modified(&mut response, last_modified);
+        // Count what was served against the bucket's daily bandwidth
+        if !head {
+            if let Some(bytes) = response.body().size_hint().exact() {
+                policies.record_served(bucket_id, bytes);
+            }
+        }
     }
+    set_robots_tag(&mut response, noindex);
+    set_sync_progress(&mut response, stale_progress);
+    response
+}
+
+/// The path a listed item is linked at: shallow listings list paths of the
+/// bucket listed, which is mounted at `mount_point` if it's a referenced one,
+/// deep listings paths relative to the listed directory
+fn listed_path(mount_point: &std::path::Path, path: &std::path::Path, deep: bool) -> String {
+    if deep {
+        format!("/{}", path.display())
+    } else {
+        mount_point.join(path).display().to_string()
+    }
+}
+
+/// A sitemap of the files in a published version of a bucket
+async fn sitemap_response(
+    state: &ServiceState,
+    bucket_id: uuid::Uuid,
+    mount: &common::mount::Mount,
+    host: &str,
+    gateway_id: &str,
+) -> Response {
+    let entries = match mount.ls_deep(std::path::Path::new("/")).await {
+        Ok(entries) => entries,
+        Err(e) => {
+            tracing::error!("Failed to list bucket for sitemap: {}", e);
+            return error_response("Failed to list bucket");
+        }
+    };
+    let base = match url::Url::parse(&format!(
+        "{}/gw/{}/",
+        host.trim_end_matches('/'),
+        gateway_id
+    )) {
+        Ok(base) => base,
+        Err(_) => return bad_request_response("Invalid Host header"),
+    };
+    let paths = entries
+        .into_iter()
+        .filter(|(_, node)| !node.is_dir())
+        .filter_map(|(path, _)| path.to_str().map(str::to_string));
+    let last_modified = conditional::last_modified(state, bucket_id, mount).await;
+    let mut response = (
+        axum::http::StatusCode::OK,
+        [(axum::http::header::CONTENT_TYPE, "application/xml")],
+        sitemap_xml(&base, paths, last_modified),
+    )
+        .into_response();
+    conditional::set_last_modified(&mut response, last_modified);
+    response
 }
 
 #[allow(clippy::too_many_arguments)]
 async fn handle_directory(
     mount: &common::mount::Mount,
     path_buf: &std::path::Path,
+    mount_point: &std::path::Path,
     absolute_path: &str,
     query: &GatewayQuery,
     headers: &axum::http::HeaderMap,
     host: &str,
-    bucket_id: &Uuid,
+    base_path: &str,
+    gateway_id: &str,
     bucket_id_str: &str,
     bucket_id_short: &str,
     bucket_name: &str,
     bucket_link: &str,
     bucket_link_short: &str,
+    listings: bool,
 ) -> Response {
     // Check for index file first (unless JSON is explicitly requested)
     if !wants_json(headers) {
@@ -303,27 +678,31 @@ async fn handle_directory(
                 }
             };
 
-            // Convert the index_path to string for URL rewriting
+            // Convert the index_path to string for URL rewriting, as a path of
+            //  the bucket served if it's in a referenced one
+            let index_path = mount_point.join(index_path.strip_prefix("/").unwrap_or(&index_path));
             let index_path_str = index_path.to_str().unwrap_or(absolute_path);
 
             // Handle different mime types
-            let (final_content, final_mime_type) = if index_mime_type == "text/markdown" {
-                // Convert markdown to HTML
-                let content_str = String::from_utf8_lossy(&file_data);
-                let html = markdown_to_html(&content_str);
-                // Apply URL rewriting to the generated HTML
-                let rewritten = rewrite_relative_urls(&html, index_path_str, bucket_id, host);
-                (rewritten.into_bytes(), "text/html; charset=utf-8")
-            } else if index_mime_type == "text/html" {
-                // Apply URL rewriting to HTML
-                let content_str = String::from_utf8_lossy(&file_data);
-                let rewritten =
-                    rewrite_relative_urls(&content_str, index_path_str, bucket_id, host);
-                (rewritten.into_bytes(), "text/html; charset=utf-8")
-            } else {
-                // Serve text/plain as-is
-                (file_data, "text/plain; charset=utf-8")
-            };
+            let render_index = query.render.unwrap_or_else(|| accepts_html(headers));
+            let (final_content, final_mime_type) =
+                if index_mime_type == "text/markdown" && render_index {
+                    // Convert markdown to HTML
+                    let content_str = String::from_utf8_lossy(&file_data);
+                    let html = render::markdown_page(&content_str);
+                    // Apply URL rewriting to the generated HTML
+                    let rewritten = rewrite_relative_urls(&html, index_path_str, gateway_id, host);
+                    (rewritten.into_bytes(), "text/html; charset=utf-8")
+                } else if index_mime_type == "text/html" {
+                    // Apply URL rewriting to HTML
+                    let content_str = String::from_utf8_lossy(&file_data);
+                    let rewritten =
+                        rewrite_relative_urls(&content_str, index_path_str, gateway_id, host);
+                    (rewritten.into_bytes(), "text/html; charset=utf-8")
+                } else {
+                    // Serve text/plain as-is
+                    (file_data, "text/plain; charset=utf-8")
+                };
 
             return (
                 axum::http::StatusCode::OK,
@@ -334,9 +713,21 @@ async fn handle_directory(
         }
     }
 
+    // Browsers get the bucket's index page at its root
+    let index = if absolute_path == "/" && !wants_json(headers) {
+        index_page(mount, gateway_id, host).await
+    } else {
+        None
+    };
+    if !listings && index.is_none() {
+        return listings_disabled_response();
+    }
+
     // List directory contents (deep or shallow based on query param)
     let wants_deep = query.deep.unwrap_or(false);
-    let items_map = if wants_deep {
+    let items_map = if !listings {
+        Default::default()
+    } else if wants_deep {
         match mount.ls_deep(path_buf).await {
             Ok(items) => items,
             Err(e) => {
@@ -366,7 +757,7 @@ async fn handle_directory(
                     .to_string();
 
                 let mime_type = match &node_link {
-                    NodeLink::Dir(_, _) => "inode/directory".to_string(),
+                    NodeLink::Dir(_, _) | NodeLink::Bucket(_) => "inode/directory".to_string(),
                     NodeLink::Data(_, _, data) => data
                         .mime()
                         .map(|m| m.to_string())
@@ -375,13 +766,14 @@ async fn handle_directory(
 
                 DirectoryEntry {
                     name,
-                    path: format!("/{}", path.display()),
+                    path: listed_path(mount_point, &path, wants_deep),
                     mime_type,
                 }
             })
             .collect();
 
         let listing = DirectoryListing {
+            bucket_id: bucket_id_str.to_string(),
             path: absolute_path.to_string(),
             entries,
         };
@@ -405,7 +797,7 @@ async fn handle_directory(
                 .to_string();
 
             let (mime_type, is_dir) = match &node_link {
-                NodeLink::Dir(_, _) => ("inode/directory".to_string(), true),
+                NodeLink::Dir(_, _) | NodeLink::Bucket(_) => ("inode/directory".to_string(), true),
                 NodeLink::Data(_, _, data) => (
                     data.mime()
                         .map(|m| m.to_string())
@@ -416,7 +808,7 @@ async fn handle_directory(
 
             FileDisplayInfo {
                 name,
-                path: format!("/{}", path.display()),
+                path: listed_path(mount_point, &path, wants_deep),
                 mime_type,
                 is_dir,
             }
@@ -424,13 +816,17 @@ async fn handle_directory(
         .collect();
 
     let template = GatewayExplorerTemplate {
+        base_path: base_path.to_string(),
         bucket_id: bucket_id_str.to_string(),
+        gateway_id: gateway_id.to_string(),
         bucket_id_short: bucket_id_short.to_string(),
         bucket_name: bucket_name.to_string(),
         bucket_link: bucket_link.to_string(),
         bucket_link_short: bucket_link_short.to_string(),
         path_segments: build_path_segments(absolute_path),
         items,
+        index,
+        listings,
     };
 
     match template.render() {
@@ -455,17 +851,20 @@ async fn handle_file(
     query: &GatewayQuery,
     headers: &axum::http::HeaderMap,
     host: &str,
-    bucket_id: &Uuid,
+    base_path: &str,
+    gateway_id: &str,
     bucket_id_str: &str,
     bucket_id_short: &str,
     bucket_name: &str,
     bucket_link: &str,
     bucket_link_short: &str,
     node_link: NodeLink,
+    images: &ImageVariants,
+    head: bool,
 ) -> Response {
     // Handle file - extract metadata from the node_link
-    let file_metadata_data = match &node_link {
-        NodeLink::Data(_, _, metadata) => metadata.clone(),
+    let (source_hash, file_metadata_data) = match &node_link {
+        NodeLink::Data(link, _, metadata) => (link.hash(), metadata.clone()),
         _ => unreachable!("Already checked is_directory"),
     };
 
@@ -481,19 +880,79 @@ async fn handle_file(
         .unwrap_or("file")
         .to_string();
 
-    // Check if raw download is requested
-    let wants_download = query.download.unwrap_or(false);
+    // Check if raw download is requested (a zip of a file is just the file)
+    let wants_download = query.download.is_some();
     let wants_view = query.view.unwrap_or(false);
 
+    // Scaled images are served from the cache once made
+    let variant = match query.variant() {
+        Ok(variant) => variant,
+        Err(message) => return bad_request_response(&message),
+    };
+    let wants_variant =
+        variant.is_requested() && mime_type.starts_with("image/") && !wants_download;
+
+    let is_html = mime_type == "text/html";
+    let is_markdown = mime_type == "text/markdown";
+    let is_text = render::is_text(&mime_type);
+    let wants_render = query.render.unwrap_or_else(|| accepts_html(headers));
+    // Other text is served plain unless rendering was asked for
+    let wants_plain = is_text && !is_html && !wants_render && !wants_view;
+
+    // HEAD of a file served as stored is answered from its recorded size;
+    // anything else is built as for GET to learn its length
+    if head && !wants_variant {
+        let raw = if wants_download {
+            Some((mime_type.as_str(), "attachment"))
+        } else if wants_plain && !wants_json(headers) {
+            Some(("text/plain; charset=utf-8", "inline"))
+        } else {
+            None
+        };
+        if let (Some((content_type, disposition)), Some(size)) = (raw, file_metadata_data.size()) {
+            return conditional::head_response(
+                content_type,
+                size,
+                &format!("{}; filename=\"{}\"", disposition, filename),
+            );
+        }
+    }
+
+    if wants_variant {
+        if let Some(data) = images.get(&source_hash, &variant).await {
+            return image_response("image/png", &filename, data);
+        }
+    }
+
     // Read file data
     let file_data = match mount.cat(path_buf).await {
         Ok(data) => data,
         Err(e) => {
+            if let common::mount::MountError::BlobsStore(blobs) = &e {
+                if let Some(class) = blobs.archived() {
+                    return archived_response(class);
+                }
+            }
             tracing::error!("Failed to read file: {}", e);
             return error_response("Failed to read file");
         }
     };
 
+    if wants_variant {
+        return match images
+            .create(&source_hash, &variant, file_data.clone())
+            .await
+        {
+            Ok(Some(data)) => image_response("image/png", &filename, data),
+            // Images that can't be scaled are served as they are
+            Ok(None) => image_response(&mime_type, &filename, file_data),
+            Err(e) => {
+                tracing::warn!("Failed to scale image {}: {}", absolute_path, e);
+                image_response(&mime_type, &filename, file_data)
+            }
+        };
+    }
+
     // Calculate size from actual data
     let size_formatted = format_bytes(file_data.len());
 
@@ -531,20 +990,35 @@ async fn handle_file(
             .into_response();
     }
 
-    // For HTML and Markdown files, render directly (unless ?view=true)
-    let is_html = mime_type == "text/html";
-    let is_markdown = mime_type == "text/markdown";
+    if wants_plain {
+        return (
+            axum::http::StatusCode::OK,
+            [
+                (
+                    axum::http::header::CONTENT_TYPE,
+                    "text/plain; charset=utf-8",
+                ),
+                (
+                    axum::http::header::CONTENT_DISPOSITION,
+                    &format!("inline; filename=\"{}\"", filename),
+                ),
+            ],
+            file_data,
+        )
+            .into_response();
+    }
 
+    // For HTML and Markdown files, render directly (unless ?view=true)
     if (is_html || is_markdown) && !wants_view {
         // Render the file directly
         let (final_content, final_mime_type) = if is_markdown {
             let content_str = String::from_utf8_lossy(&file_data);
-            let html = markdown_to_html(&content_str);
-            let rewritten = rewrite_relative_urls(&html, absolute_path, bucket_id, host);
+            let html = render::markdown_page(&content_str);
+            let rewritten = rewrite_relative_urls(&html, absolute_path, gateway_id, host);
             (rewritten.into_bytes(), "text/html; charset=utf-8")
         } else {
             let content_str = String::from_utf8_lossy(&file_data);
-            let rewritten = rewrite_relative_urls(&content_str, absolute_path, bucket_id, host);
+            let rewritten = rewrite_relative_urls(&content_str, absolute_path, gateway_id, host);
             (rewritten.into_bytes(), "text/html; charset=utf-8")
         };
 
@@ -563,22 +1037,30 @@ async fn handle_file(
     }
 
     // Render file viewer UI
-    let content = if mime_type.starts_with("text/")
-        || mime_type == "application/json"
-        || mime_type == "application/xml"
-        || mime_type == "application/javascript"
-    {
+    let content = if is_text {
         // Text content - show as text
         String::from_utf8_lossy(&file_data).to_string()
     } else {
         // Binary content - show hex dump
         to_hex_dump(&file_data, 1024)
     };
+    let highlighted = if is_text {
+        render::Syntax::for_path(absolute_path).map(|syntax| render::highlight(&content, syntax))
+    } else {
+        None
+    };
 
-    let back_url = format!("/gw/{}{}", bucket_id, get_parent_path(absolute_path));
+    let back_url = format!(
+        "{}/gw/{}{}",
+        base_path,
+        gateway_id,
+        get_parent_path(absolute_path)
+    );
 
     let template = GatewayViewerTemplate {
+        base_path: base_path.to_string(),
         bucket_id: bucket_id_str.to_string(),
+        gateway_id: gateway_id.to_string(),
         bucket_id_short: bucket_id_short.to_string(),
         bucket_name: bucket_name.to_string(),
         bucket_link: bucket_link.to_string(),
@@ -588,6 +1070,8 @@ async fn handle_file(
         mime_type,
         size_formatted,
         content,
+        highlighted,
+        is_text,
         back_url,
     };
 
@@ -605,6 +1089,26 @@ async fn handle_file(
     }
 }
 
+/// Ask crawlers not to index a response
+fn set_robots_tag(response: &mut Response, noindex: bool) {
+    if noindex {
+        response.headers_mut().insert(
+            axum::http::HeaderName::from_static(ROBOTS_TAG_HEADER),
+            axum::http::HeaderValue::from_static(NOINDEX),
+        );
+    }
+}
+
+/// Tell clients served an older version how far along the latest one is
+fn set_sync_progress(response: &mut Response, stale_progress: Option<SyncProgress>) {
+    if let Some(progress) = stale_progress {
+        response.headers_mut().insert(
+            axum::http::HeaderName::from_static(SYNC_PROGRESS_HEADER),
+            axum::http::HeaderValue::from(u16::from(progress.percent())),
+        );
+    }
+}
+
 fn error_response(message: &str) -> Response {
     (
         axum::http::StatusCode::INTERNAL_SERVER_ERROR,
@@ -613,11 +1117,131 @@ fn error_response(message: &str) -> Response {
         .into_response()
 }
 
-fn syncing_response() -> Response {
+fn bad_request_response(message: &str) -> Response {
+    (
+        axum::http::StatusCode::BAD_REQUEST,
+        format!("Bad request: {}", message),
+    )
+        .into_response()
+}
+
+fn image_response(mime_type: &str, filename: &str, data: Vec<u8>) -> Response {
+    (
+        axum::http::StatusCode::OK,
+        [
+            (axum::http::header::CONTENT_TYPE, mime_type),
+            (
+                axum::http::header::CONTENT_DISPOSITION,
+                &format!("inline; filename=\"{}\"", filename),
+            ),
+        ],
+        data,
+    )
+        .into_response()
+}
+
+fn listings_disabled_response() -> Response {
+    (
+        axum::http::StatusCode::FORBIDDEN,
+        "Directory listings are disabled for this bucket",
+    )
+        .into_response()
+}
+
+/// A version content scanning vetoed; the gateway serves the previous
+/// published version on the next request
+fn blocked_response() -> Response {
+    (
+        axum::http::StatusCode::FORBIDDEN,
+        "This version was blocked by content scanning",
+    )
+        .into_response()
+}
+
+fn zip_response(filename: &str, body: axum::body::Body) -> Response {
+    (
+        axum::http::StatusCode::OK,
+        [
+            (axum::http::header::CONTENT_TYPE, "application/zip"),
+            (
+                axum::http::header::CONTENT_DISPOSITION,
+                &format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+#[derive(Debug, Serialize)]
+struct SyncingResponse {
+    status: &'static str,
+    /// Share of the latest version's pinned blobs fetched, in percent
+    progress_percent: u8,
+    fetched: usize,
+    total: usize,
+}
+
+/// 503 for a bucket whose latest published version is still syncing, with
+/// how far along it is when this node knows of the version
+fn syncing_response(progress: Option<SyncProgress>, json: bool) -> Response {
+    let progress = progress.unwrap_or_default();
+    let headers = [
+        (axum::http::header::RETRY_AFTER, "5".to_string()),
+        (
+            axum::http::HeaderName::from_static(SYNC_PROGRESS_HEADER),
+            progress.percent().to_string(),
+        ),
+    ];
+    if json {
+        let body = SyncingResponse {
+            status: "syncing",
+            progress_percent: progress.percent(),
+            fetched: progress.fetched,
+            total: progress.total,
+        };
+        return (
+            axum::http::StatusCode::SERVICE_UNAVAILABLE,
+            headers,
+            axum::Json(body),
+        )
+            .into_response();
+    }
+    let message = if progress.total == 0 {
+        "Bucket is still syncing. Please try again in a moment.".to_string()
+    } else {
+        format!(
+            "Bucket is still syncing ({}% fetched). Please try again in a moment, \
+             or add ?wait=<seconds> to wait for it.",
+            progress.percent()
+        )
+    };
+    (
+        axum::http::StatusCode::SERVICE_UNAVAILABLE,
+        headers,
+        message,
+    )
+        .into_response()
+}
+
+/// 503 for a file in archive storage, whose restore reading it requested
+fn archived_response(class: object_store::StorageClass) -> Response {
     (
         axum::http::StatusCode::SERVICE_UNAVAILABLE,
-        [(axum::http::header::RETRY_AFTER, "5")],
-        "Bucket is still syncing. Please try again in a moment.",
+        [(axum::http::header::RETRY_AFTER, "3600")],
+        format!(
+            "This file is in archive storage. It is being restored, which takes {}; \
+             please try again then.",
+            class.restore_time()
+        ),
+    )
+        .into_response()
+}
+
+fn bandwidth_exceeded_response() -> Response {
+    (
+        axum::http::StatusCode::TOO_MANY_REQUESTS,
+        "This bucket has used up its bandwidth for today. Please try again tomorrow.",
     )
         .into_response()
 }
@@ -634,7 +1258,7 @@ fn not_found_response(message: &str) -> Response {
 fn rewrite_relative_urls(
     content: &str,
     current_path: &str,
-    bucket_id: &Uuid,
+    gateway_id: &str,
     host: &str,
 ) -> String {
     let current_dir = if current_path == "/" {
@@ -651,14 +1275,14 @@ fn rewrite_relative_urls(
     let content = HTML_ATTR_REGEX.replace_all(content, |caps: &regex::Captures| {
         let attr = &caps["attr"];
         let url = &caps["url"];
-        let absolute_url = resolve_relative_url(url, &current_dir, bucket_id, host);
+        let absolute_url = resolve_relative_url(url, &current_dir, gateway_id, host);
         format!(r#"{}="{}""#, attr, absolute_url)
     });
 
     // Rewrite Markdown links
     let content = MARKDOWN_LINK_REGEX.replace_all(&content, |caps: &regex::Captures| {
         let url = &caps["url"];
-        let absolute_url = resolve_relative_url(url, &current_dir, bucket_id, host);
+        let absolute_url = resolve_relative_url(url, &current_dir, gateway_id, host);
         format!("]({})", absolute_url)
     });
 
@@ -669,7 +1293,7 @@ fn rewrite_relative_urls(
 fn resolve_relative_url(
     relative_url: &str,
     current_dir: &str,
-    bucket_id: &Uuid,
+    gateway_id: &str,
     host: &str,
 ) -> String {
     let path = if let Some(stripped) = relative_url.strip_prefix("./") {
@@ -711,47 +1335,83 @@ fn resolve_relative_url(
     format!(
         "{}/gw/{}/{}",
         host.trim_end_matches('/'),
-        bucket_id,
+        gateway_id,
         normalized_str
     )
 }
 
-/// Converts markdown content to HTML
-fn markdown_to_html(markdown: &str) -> String {
-    use pulldown_cmark::{html, Options, Parser};
+/// The index page of a bucket root, or None if the bucket sets no index
+/// settings and has no README
+async fn index_page(
+    mount: &common::mount::Mount,
+    gateway_id: &str,
+    host: &str,
+) -> Option<IndexPage> {
+    let root = std::path::Path::new("/");
+    let settings = match mount.attrs(root).await {
+        Ok(attrs) => IndexSettings::from_attrs(&attrs),
+        Err(e) => {
+            tracing::warn!("Failed to read bucket root attributes: {}", e);
+            IndexSettings::default()
+        }
+    };
 
-    let mut options = Options::empty();
-    options.insert(Options::ENABLE_TABLES);
-    options.insert(Options::ENABLE_STRIKETHROUGH);
-    options.insert(Options::ENABLE_TASKLISTS);
+    let readme_html = match find_readme(mount).await {
+        Some(path) => match mount.cat(&path).await {
+            Ok(data) => {
+                let text = String::from_utf8_lossy(&data);
+                let is_markdown = path
+                    .extension()
+                    .is_some_and(|extension| extension == "md" || extension == "markdown");
+                let html = if is_markdown {
+                    render::markdown_to_html(&text)
+                } else {
+                    format!("<pre>{}</pre>", render::escape_html(&text))
+                };
+                let path = path.to_str().unwrap_or("/");
+                Some(rewrite_relative_urls(&html, path, gateway_id, host))
+            }
+            Err(e) => {
+                tracing::warn!("Failed to read README {}: {}", path.display(), e);
+                None
+            }
+        },
+        None => None,
+    };
+    if settings.is_empty() && readme_html.is_none() {
+        return None;
+    }
 
-    let parser = Parser::new_ext(markdown, options);
-    let mut html_output = String::new();
-    html::push_html(&mut html_output, parser);
+    // Featured files that have since moved away are left out
+    let mut featured = Vec::new();
+    for path in &settings.featured {
+        let Ok(node) = mount.get(std::path::Path::new(path)).await else {
+            continue;
+        };
+        let name = path.rsplit('/').next().unwrap_or(path).to_string();
+        let (mime_type, is_dir) = match &node {
+            NodeLink::Dir(_, _) | NodeLink::Bucket(_) => ("inode/directory".to_string(), true),
+            NodeLink::Data(_, _, data) => (
+                data.mime()
+                    .map(|m| m.to_string())
+                    .unwrap_or_else(|| "application/octet-stream".to_string()),
+                false,
+            ),
+        };
+        featured.push(FileDisplayInfo {
+            name,
+            path: path.clone(),
+            mime_type,
+            is_dir,
+        });
+    }
 
-    // Wrap in basic HTML structure
-    format!(
-        r#"<!DOCTYPE html>
-<html>
-<head>
-    <meta charset="UTF-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <style>
-        body {{ font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", Roboto, sans-serif; max-width: 800px; margin: 40px auto; padding: 0 20px; line-height: 1.6; }}
-        img {{ max-width: 100%; height: auto; }}
-        code {{ background: #f4f4f4; padding: 2px 6px; border-radius: 3px; }}
-        pre {{ background: #f4f4f4; padding: 12px; border-radius: 5px; overflow-x: auto; }}
-        table {{ border-collapse: collapse; width: 100%; }}
-        th, td {{ border: 1px solid #ddd; padding: 8px; text-align: left; }}
-        th {{ background-color: #f4f4f4; }}
-    </style>
-</head>
-<body>
-{}
-</body>
-</html>"#,
-        html_output
-    )
+    Some(IndexPage {
+        title: settings.title,
+        description: settings.description,
+        featured,
+        readme_html,
+    })
 }
 
 /// Attempts to find an index file in a directory
@@ -778,6 +1438,14 @@ async fn find_index_file(
     None
 }
 
+/// Check if the Accept header lists HTML, as browsers' do
+fn accepts_html(headers: &axum::http::HeaderMap) -> bool {
+    headers
+        .get(axum::http::header::ACCEPT)
+        .and_then(|h| h.to_str().ok())
+        .is_some_and(|accept| accept.contains("text/html"))
+}
+
 /// Check if the Accept header indicates JSON is preferred
 fn wants_json(headers: &axum::http::HeaderMap) -> bool {
     headers
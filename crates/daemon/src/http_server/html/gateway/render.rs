@@ -0,0 +1,517 @@
+//! Rendering text files for the gateway
+//!
+//! Markdown is rendered to a styled HTML page. Raw HTML in it is shown as
+//! text and links with schemes other than http(s) and mailto are dropped, so
+//! a published document can't run script on the gateway's origin. Code is
+//! highlighted with a small tokenizer for common languages; anything it
+//! doesn't know is shown unhighlighted.
+
+use pulldown_cmark::{CodeBlockKind, CowStr, Event, Options, Parser, Tag, TagEnd};
+
+/// Query value linking a rendered page to its plain text
+pub const PLAIN_QUERY: &str = "?render=0";
+
+/// CSS for the classes [`highlight`] emits
+pub const HIGHLIGHT_CSS: &str = ".hl-keyword { color: #8250df; font-weight: 600; } \
+.hl-string { color: #0a7f3f; } \
+.hl-comment { color: #6e7781; font-style: italic; } \
+.hl-number { color: #0550ae; }";
+
+/// Whether a file is text the gateway can render or show plain
+pub fn is_text(mime_type: &str) -> bool {
+    mime_type.starts_with("text/")
+        || mime_type == "application/json"
+        || mime_type == "application/xml"
+        || mime_type == "application/javascript"
+        || mime_type == "application/toml"
+}
+
+/// Render markdown to a standalone, styled HTML page, with a link to the
+/// plain text
+pub fn markdown_page(markdown: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <style>
+        body {{ font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", Roboto, sans-serif; max-width: 800px; margin: 40px auto; padding: 0 20px; line-height: 1.6; }}
+        img {{ max-width: 100%; height: auto; }}
+        code {{ background: #f4f4f4; padding: 2px 6px; border-radius: 3px; }}
+        pre {{ background: #f4f4f4; padding: 12px; border-radius: 5px; overflow-x: auto; }}
+        pre code {{ padding: 0; }}
+        table {{ border-collapse: collapse; width: 100%; }}
+        th, td {{ border: 1px solid #ddd; padding: 8px; text-align: left; }}
+        th {{ background-color: #f4f4f4; }}
+        .render-toggle {{ text-align: right; font-size: 0.85em; }}
+        {}
+    </style>
+</head>
+<body>
+<nav class="render-toggle"><a href="{}">Plain text</a></nav>
+{}
+</body>
+</html>"#,
+        HIGHLIGHT_CSS,
+        PLAIN_QUERY,
+        markdown_to_html(markdown)
+    )
+}
+
+/// Render markdown to sanitized HTML, highlighting fenced code
+pub fn markdown_to_html(markdown: &str) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TASKLISTS);
+
+    let mut events = Vec::new();
+    // Text of the fenced code block being read, with its language
+    let mut code: Option<(Option<Syntax>, String)> = None;
+    for event in Parser::new_ext(markdown, options) {
+        match event {
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let syntax = match kind {
+                    CodeBlockKind::Fenced(info) => Syntax::for_name(&info),
+                    CodeBlockKind::Indented => None,
+                };
+                code = Some((syntax, String::new()));
+            }
+            Event::Text(text) if code.is_some() => {
+                if let Some((_, buffer)) = code.as_mut() {
+                    buffer.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some((syntax, buffer)) = code.take() {
+                    let body = match syntax {
+                        Some(syntax) => highlight(&buffer, syntax),
+                        None => escape_html(&buffer),
+                    };
+                    events.push(Event::Html(
+                        format!("<pre><code>{}</code></pre>\n", body).into(),
+                    ));
+                }
+            }
+            // Raw HTML is shown as written, not interpreted
+            Event::Html(html) | Event::InlineHtml(html) => events.push(Event::Text(html)),
+            Event::Start(Tag::Link {
+                link_type,
+                dest_url,
+                title,
+                id,
+            }) => events.push(Event::Start(Tag::Link {
+                link_type,
+                dest_url: safe_url(dest_url),
+                title,
+                id,
+            })),
+            Event::Start(Tag::Image {
+                link_type,
+                dest_url,
+                title,
+                id,
+            }) => events.push(Event::Start(Tag::Image {
+                link_type,
+                dest_url: safe_url(dest_url),
+                title,
+                id,
+            })),
+            event => events.push(event),
+        }
+    }
+
+    let mut html = String::new();
+    pulldown_cmark::html::push_html(&mut html, events.into_iter());
+    html
+}
+
+/// Keep relative URLs and http(s) and mailto links, dropping other schemes
+/// such as `javascript:`
+fn safe_url(url: CowStr<'_>) -> CowStr<'_> {
+    let scheme_end = url.find(|c: char| !(c.is_ascii_alphanumeric() || "+-.".contains(c)));
+    let scheme = match scheme_end {
+        Some(end) if url[end..].starts_with(':') => Some(url[..end].to_ascii_lowercase()),
+        _ => None,
+    };
+    match scheme.as_deref() {
+        None | Some("http") | Some("https") | Some("mailto") => url,
+        Some(_) => CowStr::Borrowed("#"),
+    }
+}
+
+/// How to tokenize a language for highlighting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Syntax {
+    keywords: &'static [&'static str],
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    quotes: &'static [char],
+}
+
+const RUST: Syntax = Syntax {
+    keywords: &[
+        "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+        "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut",
+        "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true",
+        "type", "unsafe", "use", "where", "while",
+    ],
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"'],
+};
+
+const PYTHON: Syntax = Syntax {
+    keywords: &[
+        "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del",
+        "elif", "else", "except", "False", "finally", "for", "from", "global", "if", "import",
+        "in", "is", "lambda", "None", "nonlocal", "not", "or", "pass", "raise", "return", "True",
+        "try", "while", "with", "yield",
+    ],
+    line_comments: &["#"],
+    block_comment: None,
+    quotes: &['"', '\''],
+};
+
+const JAVASCRIPT: Syntax = Syntax {
+    keywords: &[
+        "async",
+        "await",
+        "break",
+        "case",
+        "catch",
+        "class",
+        "const",
+        "continue",
+        "default",
+        "delete",
+        "do",
+        "else",
+        "export",
+        "extends",
+        "false",
+        "finally",
+        "for",
+        "function",
+        "if",
+        "import",
+        "in",
+        "instanceof",
+        "interface",
+        "let",
+        "new",
+        "null",
+        "return",
+        "switch",
+        "this",
+        "throw",
+        "true",
+        "try",
+        "type",
+        "typeof",
+        "undefined",
+        "var",
+        "void",
+        "while",
+        "yield",
+    ],
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"', '\'', '`'],
+};
+
+const GO: Syntax = Syntax {
+    keywords: &[
+        "break",
+        "case",
+        "chan",
+        "const",
+        "continue",
+        "default",
+        "defer",
+        "else",
+        "false",
+        "for",
+        "func",
+        "go",
+        "goto",
+        "if",
+        "import",
+        "interface",
+        "map",
+        "nil",
+        "package",
+        "range",
+        "return",
+        "select",
+        "struct",
+        "switch",
+        "true",
+        "type",
+        "var",
+    ],
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"', '`'],
+};
+
+/// C and the languages that look like it closely enough
+const C_LIKE: Syntax = Syntax {
+    keywords: &[
+        "break",
+        "case",
+        "catch",
+        "char",
+        "class",
+        "const",
+        "continue",
+        "default",
+        "do",
+        "double",
+        "else",
+        "enum",
+        "extends",
+        "false",
+        "final",
+        "float",
+        "for",
+        "if",
+        "import",
+        "int",
+        "long",
+        "namespace",
+        "new",
+        "null",
+        "nullptr",
+        "package",
+        "private",
+        "protected",
+        "public",
+        "return",
+        "short",
+        "static",
+        "struct",
+        "switch",
+        "this",
+        "throw",
+        "true",
+        "try",
+        "typedef",
+        "unsigned",
+        "using",
+        "void",
+        "while",
+    ],
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"', '\''],
+};
+
+const SHELL: Syntax = Syntax {
+    keywords: &[
+        "case", "do", "done", "elif", "else", "esac", "export", "fi", "for", "function", "if",
+        "in", "local", "return", "then", "until", "while",
+    ],
+    line_comments: &["#"],
+    block_comment: None,
+    quotes: &['"', '\''],
+};
+
+const SQL: Syntax = Syntax {
+    keywords: &[
+        "AND", "AS", "BY", "CREATE", "DELETE", "DROP", "FROM", "GROUP", "INDEX", "INSERT", "INTO",
+        "JOIN", "KEY", "LEFT", "LIMIT", "NOT", "NULL", "ON", "OR", "ORDER", "PRIMARY", "SELECT",
+        "SET", "TABLE", "UPDATE", "VALUES", "WHERE", "and", "as", "by", "create", "delete", "drop",
+        "from", "group", "index", "insert", "into", "join", "key", "left", "limit", "not", "null",
+        "on", "or", "order", "primary", "select", "set", "table", "update", "values", "where",
+    ],
+    line_comments: &["--"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['\''],
+};
+
+/// JSON, TOML and YAML: strings, numbers, literals and comments
+const DATA: Syntax = Syntax {
+    keywords: &["true", "false", "null"],
+    line_comments: &["#"],
+    block_comment: None,
+    quotes: &['"', '\''],
+};
+
+impl Syntax {
+    /// The syntax of a file, by its extension
+    pub fn for_path(path: &str) -> Option<Syntax> {
+        let extension = std::path::Path::new(path).extension()?.to_str()?;
+        Self::for_name(extension)
+    }
+
+    /// The syntax for a language name or file extension, as used to tag
+    /// fenced code blocks
+    pub fn for_name(name: &str) -> Option<Syntax> {
+        let name = name.split_whitespace().next()?.to_ascii_lowercase();
+        let syntax = match name.as_str() {
+            "rs" | "rust" => RUST,
+            "py" | "python" => PYTHON,
+            "js" | "mjs" | "cjs" | "jsx" | "javascript" | "ts" | "tsx" | "typescript" => JAVASCRIPT,
+            "go" | "golang" => GO,
+            "c" | "h" | "cc" | "cpp" | "hpp" | "cxx" | "c++" | "java" | "cs" | "csharp" | "kt"
+            | "kotlin" | "swift" => C_LIKE,
+            "sh" | "bash" | "zsh" | "shell" => SHELL,
+            "sql" => SQL,
+            "json" | "toml" | "yaml" | "yml" | "ini" => DATA,
+            _ => return None,
+        };
+        Some(syntax)
+    }
+}
+
+/// Highlight code as HTML, escaping it, with spans of the `hl-*` classes
+/// [`HIGHLIGHT_CSS`] styles
+pub fn highlight(code: &str, syntax: Syntax) -> String {
+    let mut html = String::with_capacity(code.len() * 2);
+    let mut rest = code;
+    while let Some(c) = rest.chars().next() {
+        let (class, len) = if let Some(len) = comment_len(rest, &syntax) {
+            (Some("hl-comment"), len)
+        } else if syntax.quotes.contains(&c) {
+            (Some("hl-string"), string_len(rest, c))
+        } else if c.is_ascii_digit() {
+            (Some("hl-number"), word_len(rest))
+        } else if c.is_alphabetic() || c == '_' {
+            let len = word_len(rest);
+            let keyword = syntax.keywords.contains(&&rest[..len]);
+            (keyword.then_some("hl-keyword"), len)
+        } else {
+            (None, c.len_utf8())
+        };
+
+        let (token, tail) = rest.split_at(len);
+        match class {
+            Some(class) => {
+                html.push_str(&format!(
+                    "<span class=\"{}\">{}</span>",
+                    class,
+                    escape_html(token)
+                ));
+            }
+            None => html.push_str(&escape_html(token)),
+        }
+        rest = tail;
+    }
+    html
+}
+
+/// Length of the comment `code` starts with, if any
+fn comment_len(code: &str, syntax: &Syntax) -> Option<usize> {
+    if let Some((open, close)) = syntax.block_comment {
+        if let Some(body) = code.strip_prefix(open) {
+            return Some(
+                body.find(close)
+                    .map(|end| open.len() + end + close.len())
+                    .unwrap_or(code.len()),
+            );
+        }
+    }
+    syntax
+        .line_comments
+        .iter()
+        .any(|start| code.starts_with(start))
+        .then(|| code.find('\n').unwrap_or(code.len()))
+}
+
+/// Length of the string `code` starts with, up to its closing quote or the
+/// end of the line
+fn string_len(code: &str, quote: char) -> usize {
+    let mut escaped = false;
+    for (i, c) in code.char_indices().skip(1) {
+        match c {
+            '\\' if !escaped => escaped = true,
+            '\n' if quote != '`' => return i,
+            c if c == quote && !escaped => return i + c.len_utf8(),
+            _ => escaped = false,
+        }
+    }
+    code.len()
+}
+
+fn word_len(code: &str) -> usize {
+    code.find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+        .unwrap_or(code.len())
+}
+
+/// Escape text for HTML element content and attribute values
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_raw_html_is_escaped() {
+        let html = markdown_to_html("# Title\n\n<script>alert(1)</script>\n\nHi <b>there</b>");
+        assert!(html.contains("<h1>Title</h1>"));
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(!html.contains("<b>"));
+    }
+
+    #[test]
+    fn test_unsafe_links_are_dropped() {
+        let html = markdown_to_html(
+            "[a](javascript:alert(1)) [b](https://example.com) [c](./notes.md) ![d](data:image/png;base64,AA)",
+        );
+        assert!(!html.contains("javascript:"));
+        assert!(!html.contains("data:"));
+        assert!(html.contains(r#"href="https://example.com""#));
+        assert!(html.contains(r#"href="./notes.md""#));
+    }
+
+    #[test]
+    fn test_fenced_code_is_highlighted() {
+        let html = markdown_to_html("```rust\nfn main() { let s = \"<hi>\"; }\n```\n");
+        assert!(html.contains(r#"<span class="hl-keyword">fn</span>"#));
+        assert!(html.contains(r#"<span class="hl-string">&quot;&lt;hi&gt;&quot;</span>"#));
+
+        // Unknown languages are escaped but not highlighted
+        let html = markdown_to_html("```brainfuck\n<+>\n```\n");
+        assert!(html.contains("&lt;+&gt;"));
+        assert!(!html.contains("hl-"));
+    }
+
+    #[test]
+    fn test_highlight() {
+        let html = highlight(
+            "# count\nfor i in range(10):  # loop\n    print('it''s')\n",
+            Syntax::for_path("/scripts/count.py").unwrap(),
+        );
+        assert!(html.starts_with(r#"<span class="hl-comment"># count</span>"#));
+        assert!(html.contains(r#"<span class="hl-keyword">for</span> i"#));
+        assert!(html.contains(r#"<span class="hl-number">10</span>"#));
+        assert!(html.contains(r#"<span class="hl-comment"># loop</span>"#));
+        assert!(html.contains(r#"<span class="hl-string">&#39;it&#39;</span>"#));
+
+        let html = highlight(
+            "/* a */ let x = \"a\\\"b\"; // done",
+            Syntax::for_name("ts").unwrap(),
+        );
+        assert!(html.starts_with(r#"<span class="hl-comment">/* a */</span>"#));
+        assert!(html.contains(r#"<span class="hl-string">&quot;a\&quot;b&quot;</span>"#));
+        assert!(html.ends_with(r#"<span class="hl-comment">// done</span>"#));
+
+        assert!(Syntax::for_path("notes.txt").is_none());
+    }
+}
@@ -1,34 +1,52 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
 use axum::body::Body;
-use axum::extract::DefaultBodyLimit;
+use axum::extract::{DefaultBodyLimit, Request};
 use axum::http::{header, StatusCode, Uri};
+use axum::middleware;
 use axum::response::{IntoResponse, Response};
 use axum::routing::get;
 use axum::{Extension, Router};
-use http::header::{ACCEPT, ORIGIN};
+use http::header::{ACCEPT, IF_MODIFIED_SINCE, ORIGIN};
 use http::Method;
 use rust_embed::RustEmbed;
 use tokio::sync::watch;
+use tower::ServiceExt;
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::trace::TraceLayer;
 use tower_http::trace::{DefaultOnFailure, DefaultOnResponse};
 use tower_http::LatencyUnit;
 
 pub mod api;
+mod conditional;
 mod config;
+mod deploy;
+mod drop_box;
+pub mod error;
 mod gateway_index;
 mod handlers;
 mod html;
+mod proxy;
+mod replica;
+mod robots;
+mod signed_download;
+mod tls;
 
 pub use config::Config;
 
+use crate::drop_box::CAPTCHA_HEADER as DROP_CAPTCHA_HEADER;
+use crate::gateway_tls::{GatewayTls, GatewayTlsError, ACME_CHALLENGE_PREFIX};
+use crate::http_server::error::ApiError;
+use crate::profiles::{ProfileError, Profiles};
+use crate::signed_url::SIGNED_URL_PREFIX;
+use crate::telemetry;
 use crate::ServiceState;
 
 const API_PREFIX: &str = "/api";
 const STATUS_PREFIX: &str = "/_status";
 
-/// Maximum upload size in bytes (500 MB)
-pub const MAX_UPLOAD_SIZE_BYTES: usize = 500 * 1024 * 1024;
-
 #[derive(RustEmbed)]
 #[folder = "static"]
 struct StaticAssets;
@@ -66,14 +84,19 @@ async fn static_handler(uri: Uri) -> impl IntoResponse {
 }
 
 /// Run the API HTTP server (private, serves /_status + /api routes).
+///
+/// Requests that select one of `profiles` are served by that profile's state
+/// (see [`crate::profiles`]), all others by `state`.
 pub async fn run_api(
     config: Config,
     state: ServiceState,
+    profiles: Profiles,
     mut shutdown_rx: watch::Receiver<()>,
 ) -> Result<(), HttpServerError> {
     let listen_addr = config.listen_addr;
     let log_level = config.log_level;
     let trace_layer = TraceLayer::new_for_http()
+        .make_span_with(telemetry::http_request_span)
         .on_response(
             DefaultOnResponse::new()
                 .include_headers(false)
@@ -82,36 +105,88 @@ pub async fn run_api(
         )
         .on_failure(DefaultOnFailure::new().latency_unit(LatencyUnit::Micros));
 
-    let router = Router::new()
-        .nest(STATUS_PREFIX, health::router(state.clone()))
-        .nest(API_PREFIX, api::router(state.clone()))
-        .fallback(handlers::not_found_handler)
-        .layer(DefaultBodyLimit::max(MAX_UPLOAD_SIZE_BYTES))
-        .layer(Extension(config.clone()))
-        .with_state(state)
-        .layer(trace_layer);
+    let default_router = api_routes(&config, state);
+    let router = if profiles.is_empty() {
+        default_router
+    } else {
+        route_profiles(&config, default_router, profiles)
+    }
+    .layer(trace_layer);
+    let router = proxy::resolve_origins(router, config.reverse_proxy.clone());
 
     tracing::info!(addr = ?listen_addr, "API server listening");
     let listener = tokio::net::TcpListener::bind(listen_addr).await?;
 
-    axum::serve(listener, router)
-        .with_graceful_shutdown(async move {
-            let _ = shutdown_rx.changed().await;
-        })
-        .await?;
+    axum::serve(
+        listener,
+        router.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(async move {
+        let _ = shutdown_rx.changed().await;
+    })
+    .await?;
 
     Ok(())
 }
 
-/// Run the gateway HTTP server (public, serves /_status + /gw + / + /static routes).
+/// Routes of the API server for one profile's state.
+///
+/// Request bodies are limited to the configured `upload_limits.max_bytes`,
+/// except for `bucket/add`, which checks each file against its bucket's limit
+/// as it streams in.
+fn api_routes(config: &Config, state: ServiceState) -> Router {
+    let body_limit = usize::try_from(state.upload_limits().max_bytes).unwrap_or(usize::MAX);
+    Router::new()
+        .nest(STATUS_PREFIX, health::api_router(state.clone()))
+        .nest(API_PREFIX, api::router(state.clone()))
+        .fallback(handlers::not_found_handler)
+        .layer(DefaultBodyLimit::max(body_limit))
+        .layer(Extension(config.clone()))
+        .with_state(state)
+}
+
+/// Hand each request to the routes of the profile it selects
+fn route_profiles(config: &Config, default_router: Router, profiles: Profiles) -> Router {
+    let routers: Arc<HashMap<String, Router>> = Arc::new(
+        profiles
+            .iter()
+            .map(|(name, state)| (name.clone(), api_routes(config, state.clone())))
+            .collect(),
+    );
+    Router::new().fallback(move |request: Request| {
+        let router = match profiles.select(request.headers()) {
+            Ok(None) => Ok(default_router.clone()),
+            Ok(Some(name)) => Ok(routers[&name].clone()),
+            Err(e) => Err(e),
+        };
+        async move {
+            match router {
+                Ok(router) => match router.oneshot(request).await {
+                    Ok(response) => response,
+                    Err(never) => match never {},
+                },
+                Err(e @ ProfileError::UnknownToken) => {
+                    ApiError::new(StatusCode::UNAUTHORIZED, e.to_string()).into_response()
+                }
+                Err(e) => ApiError::new(StatusCode::NOT_FOUND, e.to_string()).into_response(),
+            }
+        }
+    })
+}
+
+/// Run the gateway HTTP server (public, serves /_status + /gw + /deploy + /dl + /drop + / + /static routes).
+/// With `tls`, the same routes are also served over HTTPS.
+/// Read replicas serve neither /deploy nor /drop (see [`crate::replica`]).
 pub async fn run_gateway(
     config: Config,
     state: ServiceState,
+    tls: Option<Arc<GatewayTls>>,
     mut shutdown_rx: watch::Receiver<()>,
 ) -> Result<(), HttpServerError> {
     let listen_addr = config.listen_addr;
     let log_level = config.log_level;
     let trace_layer = TraceLayer::new_for_http()
+        .make_span_with(telemetry::http_request_span)
         .on_response(
             DefaultOnResponse::new()
                 .include_headers(false)
@@ -122,10 +197,10 @@ pub async fn run_gateway(
 
     tracing::info!("Static files embedded in binary");
 
-    // Gateway CORS (GET only) for gateway routes
+    // Gateway CORS (GET and HEAD only) for gateway routes
     let gateway_cors = CorsLayer::new()
-        .allow_methods(vec![Method::GET])
-        .allow_headers(vec![ACCEPT, ORIGIN])
+        .allow_methods(vec![Method::GET, Method::HEAD])
+        .allow_headers(vec![ACCEPT, ORIGIN, IF_MODIFIED_SINCE])
         .allow_origin(Any)
         .allow_credentials(false);
 
@@ -134,27 +209,103 @@ pub async fn run_gateway(
         .route("/:bucket_id", get(html::gateway::root_handler))
         .route("/:bucket_id/", get(html::gateway::root_handler))
         .route("/:bucket_id/*file_path", get(html::gateway::handler))
-        .with_state(state.clone())
-        .layer(gateway_cors);
+        .with_state(state.clone());
+    // Read replicas say how fresh what they serve is
+    let gateway_routes = if state.is_read_replica() {
+        gateway_routes.layer(middleware::from_fn_with_state(
+            state.clone(),
+            replica::synced_headers,
+        ))
+    } else {
+        gateway_routes
+    }
+    .layer(gateway_cors);
+
+    // Drop links are posted to from forms on other sites
+    let drop_cors = CorsLayer::new()
+        .allow_methods(vec![Method::POST])
+        .allow_headers(vec![
+            ACCEPT,
+            ORIGIN,
+            header::CONTENT_TYPE,
+            header::HeaderName::from_static(DROP_CAPTCHA_HEADER),
+        ])
+        .allow_origin(Any)
+        .allow_credentials(false);
 
     let router = Router::new()
         .nest(STATUS_PREFIX, health::router(state.clone()))
         .nest("/gw", gateway_routes)
+        .nest(SIGNED_URL_PREFIX, signed_download::router(state.clone()));
+    // Deploys and drops write to buckets, which read replicas never do
+    let router = if state.is_read_replica() {
+        router
+    } else {
+        router
+            .nest("/deploy", deploy::router(state.clone()))
+            .nest("/drop", drop_box::router(state.clone()).layer(drop_cors))
+    };
+    let router = router
         .route("/", get(gateway_index::handler))
+        .route("/robots.txt", get(robots::handler))
         .route("/static/*path", get(static_handler))
         .fallback(handlers::not_found_handler)
         .layer(Extension(config.clone()))
         .with_state(state)
         .layer(trace_layer);
 
+    let Some(gateway_tls) = tls else {
+        let router = proxy::resolve_origins(router, config.reverse_proxy.clone());
+        tracing::info!(addr = ?listen_addr, "Gateway server listening");
+        let listener = tokio::net::TcpListener::bind(listen_addr).await?;
+        axum::serve(
+            listener,
+            router.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown(async move {
+            let _ = shutdown_rx.changed().await;
+        })
+        .await?;
+        return Ok(());
+    };
+
+    // The same routes over HTTPS, with challenges answered on plain HTTP
+    let router = router.nest(
+        ACME_CHALLENGE_PREFIX,
+        tls::challenge_router(gateway_tls.challenges().clone()),
+    );
+    let router = proxy::resolve_origins(router, config.reverse_proxy.clone());
+    let https_addr = SocketAddr::new(listen_addr.ip(), gateway_tls.config().https_port);
+    let http_addr = SocketAddr::new(listen_addr.ip(), gateway_tls.config().http_port);
+    let https = tls::serve_https(
+        https_addr,
+        router.clone(),
+        &gateway_tls,
+        shutdown_rx.clone(),
+    );
+    // Unless challenges are asked for on the gateway's own port
+    let redirect_rx = shutdown_rx.clone();
+    let redirect = async {
+        if http_addr.port() == listen_addr.port() {
+            return Ok(());
+        }
+        tls::serve_redirect(http_addr, &gateway_tls, redirect_rx).await
+    };
+
     tracing::info!(addr = ?listen_addr, "Gateway server listening");
     let listener = tokio::net::TcpListener::bind(listen_addr).await?;
-
-    axum::serve(listener, router)
+    let plain = async move {
+        axum::serve(
+            listener,
+            router.into_make_service_with_connect_info::<SocketAddr>(),
+        )
         .with_graceful_shutdown(async move {
             let _ = shutdown_rx.changed().await;
         })
         .await?;
+        Ok::<_, HttpServerError>(())
+    };
+    tokio::try_join!(plain, https, redirect)?;
 
     Ok(())
 }
@@ -165,4 +316,6 @@ mod health;
 pub enum HttpServerError {
     #[error("an error occurred running the HTTP server: {0}")]
     ServingFailed(#[from] std::io::Error),
+    #[error("gateway TLS error: {0}")]
+    Tls(#[from] GatewayTlsError),
 }
@@ -0,0 +1,53 @@
+//! Resolving requests made through a reverse proxy (see [`crate::reverse_proxy`])
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::uri::PathAndQuery;
+use axum::http::Uri;
+use axum::middleware::{self, Next};
+use axum::response::Response;
+use axum::Router;
+
+use crate::reverse_proxy::ReverseProxy;
+
+/// Wrap `router` so that every request carries its
+/// [`crate::reverse_proxy::RequestOrigin`] and is routed without the path
+/// prefix. Serve the result with the connection's address as `ConnectInfo`.
+pub fn resolve_origins(router: Router, reverse_proxy: Arc<ReverseProxy>) -> Router {
+    // Outside the router, so that the stripped path is the one routed on
+    Router::new()
+        .fallback_service(router)
+        .layer(middleware::from_fn_with_state(
+            reverse_proxy,
+            resolve_origin,
+        ))
+}
+
+async fn resolve_origin(
+    State(reverse_proxy): State<Arc<ReverseProxy>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    let origin = reverse_proxy.origin(peer, request.headers());
+
+    if let Some(path) = reverse_proxy.strip_prefix(request.uri().path()) {
+        let path_and_query = match request.uri().query() {
+            Some(query) => format!("{}?{}", path, query),
+            None => path.to_string(),
+        };
+        let mut parts = request.uri().clone().into_parts();
+        parts.path_and_query = path_and_query.parse::<PathAndQuery>().ok();
+        if let Ok(uri) = Uri::from_parts(parts) {
+            *request.uri_mut() = uri;
+        }
+    }
+
+    request.extensions_mut().insert(origin);
+    next.run(request).await
+}
@@ -0,0 +1,64 @@
+//! Refusing writes and advertising staleness on a read replica (see
+//! [`crate::replica`])
+
+use axum::extract::{Request, State};
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+use crate::http_server::error::ApiError;
+use crate::replica;
+use crate::ServiceState;
+
+/// Refuse API requests a read replica doesn't answer, as they could write to
+/// a bucket
+pub async fn refuse_writes(request: Request, next: Next) -> Response {
+    if replica::allows(request.uri().path()) {
+        return next.run(request).await;
+    }
+    ApiError::new(
+        StatusCode::FORBIDDEN,
+        "this node is a read replica and never writes to a bucket",
+    )
+    .into_response()
+}
+
+/// Mark gateway responses for a bucket with the latest published version of
+/// it synced here, and when it was synced
+pub async fn synced_headers(
+    State(state): State<ServiceState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let gateway_id = request
+        .uri()
+        .path()
+        .trim_start_matches('/')
+        .split('/')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    let mut response = next.run(request).await;
+
+    let bucket_id = match state.resolve_gateway_id(&gateway_id).await {
+        Ok(Some(bucket_id)) => bucket_id,
+        Ok(None) => return response,
+        Err(e) => {
+            tracing::warn!("Failed to resolve gateway link {}: {}", gateway_id, e);
+            return response;
+        }
+    };
+    if !state.gateway_policies().serves(&bucket_id) {
+        return response;
+    }
+    match state.database().synced_version(&bucket_id).await {
+        Ok(Some(synced)) => synced.set_headers(response.headers_mut()),
+        Ok(None) => {}
+        Err(e) => tracing::warn!(
+            "Failed to look up the synced version of {}: {}",
+            bucket_id,
+            e
+        ),
+    }
+    response
+}
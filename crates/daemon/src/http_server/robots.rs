@@ -0,0 +1,61 @@
+//! The gateway's robots.txt (see [`crate::robots`])
+
+use axum::extract::State;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+
+use common::bucket_log::BucketLogProvider;
+use common::mount::Mount;
+
+use crate::bucket_index::IndexSettings;
+use crate::reverse_proxy::RequestOrigin;
+use crate::robots::robots_txt;
+use crate::ServiceState;
+
+/// Point crawlers at the sitemaps of the buckets listed on the gateway's
+/// homepage, leaving out those that shouldn't be indexed
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Extension(origin): Extension<RequestOrigin>,
+) -> Response {
+    let buckets = state
+        .database()
+        .list_buckets(None, None)
+        .await
+        .unwrap_or_default();
+
+    let policies = state.gateway_policies();
+    let mut gateway_ids = Vec::new();
+    for bucket in buckets {
+        if !policies.serves(&bucket.id) || policies.get(&bucket.id).noindex {
+            continue;
+        }
+        // Rotated links aren't given away
+        if matches!(state.database().gateway_link(&bucket.id).await, Ok(Some(_))) {
+            continue;
+        }
+        let Ok(Some((link, _height))) = state.peer().logs().latest_published(bucket.id).await
+        else {
+            continue;
+        };
+        let noindex = match Mount::load(&link, state.peer().secret(), state.peer().blobs()).await {
+            Ok(mount) => match mount.attrs(std::path::Path::new("/")).await {
+                Ok(attrs) => IndexSettings::from_attrs(&attrs).noindex,
+                Err(_) => false,
+            },
+            // Not synced yet; its sitemap will answer once it is
+            Err(_) => false,
+        };
+        if !noindex {
+            gateway_ids.push(bucket.id.to_string());
+        }
+    }
+
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+        robots_txt(&origin.base_url, &origin.base_path, &gateway_ids),
+    )
+        .into_response()
+}
@@ -0,0 +1,203 @@
+//! Pre-signed downloads on the gateway (see [`crate::signed_url`])
+
+use std::path::PathBuf;
+
+use axum::extract::{Path, Query, State};
+use axum::http::{header, HeaderMap, Method, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use serde::Deserialize;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use common::linked_data::{Hash, Link, LD_RAW_CODEC};
+use common::prelude::{Mount, MountError};
+
+use super::conditional;
+use super::error::is_archived;
+use crate::deploy::normalize_path;
+use crate::signed_url::{SignedDownload, UrlSigner};
+use crate::ServiceState;
+
+pub fn router(state: ServiceState) -> Router<ServiceState> {
+    Router::new()
+        .route("/:bucket_id/*path", get(handler))
+        .with_state(state)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SignedQuery {
+    pub version: String,
+    pub expires: i64,
+    pub sig: String,
+}
+
+/// Serve the file a signed URL grants, as an attachment
+pub async fn handler(
+    State(state): State<ServiceState>,
+    Path((bucket_id, path)): Path<(Uuid, String)>,
+    Query(query): Query<SignedQuery>,
+    method: Method,
+    headers: HeaderMap,
+) -> Result<Response, SignedDownloadError> {
+    let path = normalize_path(&path).ok_or(SignedDownloadError::Unauthorized)?;
+    let version = query
+        .version
+        .parse::<Hash>()
+        .map_err(|_| SignedDownloadError::Unauthorized)?;
+    let download = SignedDownload {
+        bucket_id,
+        path,
+        version,
+        expires: query.expires,
+    };
+    if !UrlSigner::derive(state.peer().secret()).verify(&download, &query.sig) {
+        return Err(SignedDownloadError::Unauthorized);
+    }
+    let now = OffsetDateTime::now_utc();
+    if download.is_expired(now) {
+        return Err(SignedDownloadError::Expired);
+    }
+
+    let link = Link::new(LD_RAW_CODEC, download.version);
+    let mount = Mount::load(&link, state.peer().secret(), state.peer().blobs()).await?;
+    let mount_path = PathBuf::from(&download.path);
+    let node = mount.get(&mount_path).await?;
+    let Some(data) = node.data() else {
+        return Err(SignedDownloadError::Mount(MountError::PathNotFound(
+            mount_path,
+        )));
+    };
+
+    let last_modified = conditional::last_modified(&state, bucket_id, &mount).await;
+    if let Some(response) = conditional::not_modified(&headers, last_modified) {
+        return Ok(response);
+    }
+
+    let mime_type = data
+        .mime()
+        .map(|mime| mime.to_string())
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+    let filename = mount_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("download");
+    let disposition = content_disposition(filename);
+    // Caches may keep the file only for as long as the URL works
+    let cache_control = format!(
+        "private, max-age={}",
+        download.expires - now.unix_timestamp()
+    );
+
+    let mut response = match data.size() {
+        Some(size) if method == Method::HEAD => {
+            conditional::head_response(&mime_type, size, &disposition)
+        }
+        _ => {
+            let content = mount.cat(&mount_path).await?;
+            (
+                StatusCode::OK,
+                [
+                    (header::CONTENT_TYPE, mime_type.as_str()),
+                    (header::CONTENT_DISPOSITION, disposition.as_str()),
+                ],
+                content,
+            )
+                .into_response()
+        }
+    };
+    if let Ok(value) = cache_control.parse() {
+        response.headers_mut().insert(header::CACHE_CONTROL, value);
+    }
+    conditional::set_last_modified(&mut response, last_modified);
+    Ok(response)
+}
+
+/// An attachment `Content-Disposition` for a file name. The quoted
+/// `filename` is an ASCII fallback for old clients; `filename*` carries the
+/// name itself, percent-encoded as in RFC 5987.
+fn content_disposition(filename: &str) -> String {
+    let fallback: String = filename
+        .chars()
+        .map(|c| match c {
+            '"' | '\\' => '_',
+            c if c.is_ascii() && !c.is_ascii_control() => c,
+            _ => '_',
+        })
+        .collect();
+    let mut encoded = String::new();
+    for byte in filename.bytes() {
+        match byte {
+            b'a'..=b'z'
+            | b'A'..=b'Z'
+            | b'0'..=b'9'
+            | b'!'
+            | b'#'
+            | b'$'
+            | b'&'
+            | b'+'
+            | b'-'
+            | b'.'
+            | b'^'
+            | b'_'
+            | b'`'
+            | b'|'
+            | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    format!(
+        "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+        fallback, encoded
+    )
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SignedDownloadError {
+    #[error("Invalid download link")]
+    Unauthorized,
+    #[error("Download link has expired")]
+    Expired,
+    #[error("Mount error: {0}")]
+    Mount(#[from] MountError),
+}
+
+impl IntoResponse for SignedDownloadError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            SignedDownloadError::Unauthorized => StatusCode::FORBIDDEN,
+            SignedDownloadError::Expired => StatusCode::GONE,
+            SignedDownloadError::Mount(MountError::PathNotFound(_)) => StatusCode::NOT_FOUND,
+            SignedDownloadError::Mount(MountError::VersionUnavailable(_)) => {
+                StatusCode::SERVICE_UNAVAILABLE
+            }
+            SignedDownloadError::Mount(ref e) if is_archived(e) => StatusCode::SERVICE_UNAVAILABLE,
+            SignedDownloadError::Mount(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, format!("Error: {}", self)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_content_disposition_escapes_name() {
+        assert_eq!(
+            content_disposition("report.pdf"),
+            "attachment; filename=\"report.pdf\"; filename*=UTF-8''report.pdf"
+        );
+        assert_eq!(
+            content_disposition("a\"b;c.txt"),
+            "attachment; filename=\"a_b;c.txt\"; filename*=UTF-8''a%22b%3Bc.txt"
+        );
+        let disposition = content_disposition("résumé.txt");
+        assert_eq!(
+            disposition,
+            "attachment; filename=\"r_sum_.txt\"; filename*=UTF-8''r%C3%A9sum%C3%A9.txt"
+        );
+        assert!(axum::http::HeaderValue::from_str(&disposition).is_ok());
+    }
+}
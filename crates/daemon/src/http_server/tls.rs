@@ -0,0 +1,139 @@
+//! Serving the gateway over HTTPS (see [`crate::gateway_tls`])
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use axum::extract::{Path, State};
+use axum::http::{header, HeaderMap, StatusCode, Uri};
+use axum::response::{IntoResponse, Redirect, Response};
+use axum::routing::get;
+use axum::Router;
+use axum_server::tls_rustls::RustlsConfig;
+use tokio::sync::watch;
+
+use super::HttpServerError;
+use crate::gateway_tls::{AcmeChallenges, GatewayTls, ACME_CHALLENGE_PREFIX};
+
+/// How long HTTPS connections get to finish on shutdown
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(10);
+
+/// Answers HTTP-01 challenges under [`ACME_CHALLENGE_PREFIX`]
+pub fn challenge_router<S>(challenges: AcmeChallenges) -> Router<S> {
+    Router::new()
+        .route("/:token", get(challenge_handler))
+        .with_state(challenges)
+}
+
+async fn challenge_handler(
+    State(challenges): State<AcmeChallenges>,
+    Path(token): Path<String>,
+) -> Response {
+    match challenges.get(&token) {
+        Some(key_authorization) => (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "application/octet-stream")],
+            key_authorization,
+        )
+            .into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Serve `router` over HTTPS until shutdown
+pub async fn serve_https(
+    addr: SocketAddr,
+    router: Router,
+    tls: &GatewayTls,
+    mut shutdown_rx: watch::Receiver<()>,
+) -> Result<(), HttpServerError> {
+    let config = RustlsConfig::from_config(tls.server_config()?);
+    let handle = axum_server::Handle::new();
+    let shutdown = handle.clone();
+    tokio::spawn(async move {
+        let _ = shutdown_rx.changed().await;
+        shutdown.graceful_shutdown(Some(SHUTDOWN_GRACE));
+    });
+
+    tracing::info!(addr = ?addr, "Gateway HTTPS server listening");
+    axum_server::bind_rustls(addr, config)
+        .handle(handle)
+        .serve(router.into_make_service_with_connect_info::<SocketAddr>())
+        .await?;
+    Ok(())
+}
+
+/// Serve plain HTTP until shutdown, answering challenges and redirecting
+/// everything else to HTTPS
+pub async fn serve_redirect(
+    addr: SocketAddr,
+    tls: &GatewayTls,
+    mut shutdown_rx: watch::Receiver<()>,
+) -> Result<(), HttpServerError> {
+    let https_port = tls.config().https_port;
+    let router = Router::new()
+        .nest(
+            ACME_CHALLENGE_PREFIX,
+            challenge_router(tls.challenges().clone()),
+        )
+        .fallback(move |headers: HeaderMap, uri: Uri| async move {
+            match https_location(&headers, &uri, https_port) {
+                Some(location) => Redirect::permanent(&location).into_response(),
+                None => (StatusCode::BAD_REQUEST, "Missing Host header").into_response(),
+            }
+        });
+
+    tracing::info!(addr = ?addr, "Gateway HTTP redirect listening");
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, router)
+        .with_graceful_shutdown(async move {
+            let _ = shutdown_rx.changed().await;
+        })
+        .await?;
+    Ok(())
+}
+
+/// Where a plain HTTP request is redirected to
+fn https_location(headers: &HeaderMap, uri: &Uri, https_port: u16) -> Option<String> {
+    let host = headers.get(header::HOST)?.to_str().ok()?;
+    // Drop the port of the HTTP listener, keeping IPv6 literals whole
+    let host = match host.rsplit_once(':') {
+        Some((name, port)) if !name.is_empty() && port.chars().all(|c| c.is_ascii_digit()) => name,
+        _ => host,
+    };
+    if host.is_empty() {
+        return None;
+    }
+    let path = uri.path_and_query().map(|p| p.as_str()).unwrap_or("/");
+    Some(match https_port {
+        443 => format!("https://{}{}", host, path),
+        port => format!("https://{}:{}{}", host, port, path),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn location(host: &str, uri: &str, https_port: u16) -> Option<String> {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::HOST, host.parse().unwrap());
+        https_location(&headers, &uri.parse().unwrap(), https_port)
+    }
+
+    #[test]
+    fn test_https_location() {
+        assert_eq!(
+            location("files.example.com", "/gw/abc/a.txt?download=true", 443).as_deref(),
+            Some("https://files.example.com/gw/abc/a.txt?download=true")
+        );
+        assert_eq!(
+            location("files.example.com:8080", "/", 8443).as_deref(),
+            Some("https://files.example.com:8443/")
+        );
+        assert_eq!(
+            location("[::1]:80", "/", 443).as_deref(),
+            Some("https://[::1]/")
+        );
+        assert!(https_location(&HeaderMap::new(), &"/".parse().unwrap(), 443).is_none());
+    }
+}
@@ -0,0 +1,330 @@
+//! Identity bundles for moving a node to another machine
+//!
+//! A bundle holds the node's secret key, its config and the buckets it knows
+//! about, encrypted with a passphrase. Importing it on a fresh machine
+//! recreates the jax directory with the same identity; since bucket shares
+//! are addressed to the node's key, the buckets are then synced back from the
+//! peers they are shared with, without re-sharing.
+//!
+//! Format: `BUNDLE_MAGIC || version (1 byte) || salt (16 bytes) || ciphertext`,
+//! where the ciphertext is the JSON bundle encrypted with a [`Secret`] derived
+//! from the passphrase with Argon2id.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::bucket_log::BucketLogProvider;
+use common::crypto::{PublicKey, Secret, SecretKey};
+use common::linked_data::Link;
+use common::mount::Manifest;
+use common::peer::sync::{SyncBucketJob, SyncJob, SyncTarget};
+
+use crate::state::{AppConfig, AppState, StateError};
+use crate::ServiceState;
+
+/// Marks the start of an identity bundle
+pub const BUNDLE_MAGIC: &[u8] = b"JAXIDENT";
+/// Buckets from an imported bundle that have not been synced yet
+pub const IMPORTED_BUCKETS_FILE_NAME: &str = "imported_buckets.json";
+const BUNDLE_VERSION: u8 = 1;
+const SALT_SIZE: usize = 16;
+/// Upper bound on the buckets listed in a bundle
+const MAX_BUCKETS: u32 = 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdentityBundle {
+    /// PEM-encoded node secret key
+    pub secret_key: String,
+    pub config: AppConfig,
+    pub buckets: Vec<BucketRef>,
+}
+
+/// A bucket the node had when the bundle was exported
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BucketRef {
+    pub bucket_id: Uuid,
+    pub name: String,
+    pub link: Link,
+    pub height: u64,
+    /// Hex-encoded keys of the other peers the bucket is shared with
+    pub peer_ids: Vec<String>,
+}
+
+impl IdentityBundle {
+    /// Collect the identity, config and buckets of a running daemon
+    pub async fn collect(state: &ServiceState) -> Result<Self, IdentityError> {
+        let peer = state.peer();
+        let own_key = peer.secret().public();
+
+        let config = match state.reloader().config_path() {
+            Some(path) => AppConfig::read(path)?,
+            None => AppConfig {
+                api_port: state.api_port(),
+                gateway_port: state.gateway_port(),
+                ..AppConfig::default()
+            },
+        };
+
+        let mut buckets = Vec::new();
+        for bucket in state
+            .database()
+            .list_buckets(None, Some(MAX_BUCKETS))
+            .await?
+        {
+            let (_, height) = state
+                .database()
+                .head(bucket.id, None)
+                .await
+                .map_err(|e| IdentityError::BucketLog(e.to_string()))?;
+            let peer_ids = match peer.blobs().get_cbor::<Manifest>(&bucket.link.hash()).await {
+                Ok(manifest) => manifest
+                    .get_peer_ids()
+                    .into_iter()
+                    .filter(|key| *key != own_key)
+                    .map(|key| key.to_hex())
+                    .collect(),
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to load manifest for bucket {}, exporting without peers: {}",
+                        bucket.id,
+                        e
+                    );
+                    Vec::new()
+                }
+            };
+            buckets.push(BucketRef {
+                bucket_id: bucket.id,
+                name: bucket.name,
+                link: bucket.link,
+                height,
+                peer_ids,
+            });
+        }
+
+        Ok(Self {
+            secret_key: peer.secret().to_pem(),
+            config,
+            buckets,
+        })
+    }
+
+    /// Encrypt the bundle with a passphrase
+    pub fn encrypt(&self, passphrase: &str) -> Result<Vec<u8>, IdentityError> {
+        let salt = Secret::generate().bytes()[..SALT_SIZE].to_vec();
+        let ciphertext = derive_secret(passphrase, &salt)?
+            .encrypt(&serde_json::to_vec(self)?)
+            .map_err(|e| IdentityError::Encrypt(e.to_string()))?;
+
+        Ok([BUNDLE_MAGIC, &[BUNDLE_VERSION], &salt, &ciphertext].concat())
+    }
+
+    /// Decrypt a bundle produced by [`IdentityBundle::encrypt`]
+    pub fn decrypt(data: &[u8], passphrase: &str) -> Result<Self, IdentityError> {
+        let rest = data
+            .strip_prefix(BUNDLE_MAGIC)
+            .ok_or(IdentityError::NotABundle)?;
+        let (&version, rest) = rest.split_first().ok_or(IdentityError::NotABundle)?;
+        if version != BUNDLE_VERSION {
+            return Err(IdentityError::UnsupportedVersion(version));
+        }
+        if rest.len() < SALT_SIZE {
+            return Err(IdentityError::NotABundle);
+        }
+        let (salt, ciphertext) = rest.split_at(SALT_SIZE);
+
+        let plaintext = derive_secret(passphrase, salt)?
+            .decrypt(ciphertext)
+            .map_err(|_| IdentityError::Decrypt)?;
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+
+    /// Node public key of the bundled identity
+    pub fn node_id(&self) -> Result<PublicKey, IdentityError> {
+        Ok(self.key()?.public())
+    }
+
+    fn key(&self) -> Result<SecretKey, IdentityError> {
+        SecretKey::from_pem(&self.secret_key).map_err(|e| IdentityError::InvalidKey(e.to_string()))
+    }
+
+    /// Create a new jax directory from the bundle. The bundled buckets are
+    /// synced from their peers the next time the daemon starts.
+    pub fn import(self, custom_path: Option<PathBuf>) -> Result<AppState, IdentityError> {
+        let key = self.key()?;
+        let state = AppState::init_with_key(custom_path, Some(self.config), key)?;
+        if !self.buckets.is_empty() {
+            write_imported_buckets(&state.jax_dir, &self.buckets)?;
+        }
+        Ok(state)
+    }
+}
+
+fn derive_secret(passphrase: &str, salt: &[u8]) -> Result<Secret, IdentityError> {
+    let mut key = [0u8; 32];
+    argon2::Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| IdentityError::Kdf(e.to_string()))?;
+    Secret::from_slice(&key).map_err(|e| IdentityError::Kdf(e.to_string()))
+}
+
+fn write_imported_buckets(jax_dir: &Path, buckets: &[BucketRef]) -> Result<(), IdentityError> {
+    let path = jax_dir.join(IMPORTED_BUCKETS_FILE_NAME);
+    if buckets.is_empty() {
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+            _ => return Ok(()),
+        }
+    }
+    fs::write(path, serde_json::to_vec_pretty(buckets)?)?;
+    Ok(())
+}
+
+/// Queue sync jobs for imported buckets that are not local yet.
+///
+/// Runs at daemon startup; buckets are dropped from the list once present,
+/// so ones whose peers are offline are retried on the next start.
+pub async fn sync_imported_buckets(state: ServiceState, jax_dir: PathBuf) {
+    let path = jax_dir.join(IMPORTED_BUCKETS_FILE_NAME);
+    let buckets: Vec<BucketRef> = match fs::read(&path) {
+        Ok(bytes) => match serde_json::from_slice(&bytes) {
+            Ok(buckets) => buckets,
+            Err(e) => {
+                tracing::warn!("Ignoring unreadable {}: {}", path.display(), e);
+                return;
+            }
+        },
+        Err(_) => return,
+    };
+
+    let mut pending = Vec::new();
+    for bucket in buckets {
+        if state.database().head(bucket.bucket_id, None).await.is_ok() {
+            continue;
+        }
+
+        let peer_ids: Vec<PublicKey> = bucket
+            .peer_ids
+            .iter()
+            .filter_map(|id| PublicKey::from_hex(id).ok())
+            .collect();
+        if peer_ids.is_empty() {
+            tracing::warn!(
+                "Imported bucket {} ({}) is not shared with any peer and cannot be synced",
+                bucket.name,
+                bucket.bucket_id
+            );
+            continue;
+        }
+
+        tracing::info!(
+            "Syncing imported bucket {} ({})",
+            bucket.name,
+            bucket.bucket_id
+        );
+        let job = SyncJob::SyncBucket(SyncBucketJob {
+            bucket_id: bucket.bucket_id,
+            target: SyncTarget {
+                link: bucket.link.clone(),
+                height: bucket.height,
+                peer_ids,
+            },
+        });
+        if let Err(e) = state.peer().dispatch(job).await {
+            tracing::error!("Failed to dispatch sync job: {}", e);
+        }
+        pending.push(bucket);
+    }
+
+    if let Err(e) = write_imported_buckets(&jax_dir, &pending) {
+        tracing::warn!("Failed to update {}: {}", path.display(), e);
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum IdentityError {
+    #[error("not a jax identity bundle")]
+    NotABundle,
+    #[error("unsupported identity bundle version {0}")]
+    UnsupportedVersion(u8),
+    #[error("wrong passphrase or corrupted bundle")]
+    Decrypt,
+    #[error("failed to derive key from passphrase: {0}")]
+    Kdf(String),
+    #[error("failed to encrypt bundle: {0}")]
+    Encrypt(String),
+    #[error("invalid secret key in bundle: {0}")]
+    InvalidKey(String),
+    #[error("invalid bundle contents: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    State(#[from] StateError),
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("bucket log error: {0}")]
+    BucketLog(String),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn bundle() -> IdentityBundle {
+        IdentityBundle {
+            secret_key: SecretKey::generate().to_pem(),
+            config: AppConfig::default(),
+            buckets: vec![BucketRef {
+                bucket_id: Uuid::new_v4(),
+                name: "photos".to_string(),
+                link: Link::default(),
+                height: 3,
+                peer_ids: vec![SecretKey::generate().public().to_hex()],
+            }],
+        }
+    }
+
+    #[test]
+    fn test_bundle_round_trip() {
+        let bundle = bundle();
+        let encrypted = bundle.encrypt("correct horse").unwrap();
+        assert!(encrypted.starts_with(BUNDLE_MAGIC));
+
+        let decrypted = IdentityBundle::decrypt(&encrypted, "correct horse").unwrap();
+        assert_eq!(decrypted.secret_key, bundle.secret_key);
+        assert_eq!(decrypted.buckets, bundle.buckets);
+
+        assert!(matches!(
+            IdentityBundle::decrypt(&encrypted, "wrong"),
+            Err(IdentityError::Decrypt)
+        ));
+        assert!(matches!(
+            IdentityBundle::decrypt(b"not a bundle", "correct horse"),
+            Err(IdentityError::NotABundle)
+        ));
+    }
+
+    #[test]
+    fn test_import_records_buckets() {
+        let dir = tempfile::tempdir().unwrap();
+        let jax_dir = dir.path().join("jax");
+        let bundle = bundle();
+        let node_id = bundle.node_id().unwrap();
+
+        let state = bundle.clone().import(Some(jax_dir.clone())).unwrap();
+        assert_eq!(state.load_key().unwrap().public(), node_id);
+
+        let pending: Vec<BucketRef> =
+            serde_json::from_slice(&fs::read(jax_dir.join(IMPORTED_BUCKETS_FILE_NAME)).unwrap())
+                .unwrap();
+        assert_eq!(pending, bundle.buckets);
+
+        assert!(matches!(
+            bundle.import(Some(jax_dir)),
+            Err(IdentityError::State(StateError::AlreadyInitialized))
+        ));
+    }
+}
@@ -0,0 +1,406 @@
+//! Resized variants of images served by the gateway
+//!
+//! `?w=`, `?h=` and `?format=` on a gateway image return a variant scaled
+//! down to fit the requested box, keeping its aspect ratio. Images are never
+//! scaled up. Variants are cached on disk under the jax directory, named by
+//! the hash of the source blob and the requested size, so each is computed
+//! once per version of a file. The least recently served variants are
+//! removed once the cache grows beyond [`MAX_CACHE_BYTES`].
+//!
+//! Only PNG is decoded and encoded. Variants are always PNG, whatever format
+//! was asked for, and the response's Content-Type says so. Sources in other
+//! formats are served unchanged.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use common::linked_data::Hash;
+
+pub mod png;
+
+/// Directory of the cache, under the jax directory
+pub const IMAGE_VARIANTS_DIR: &str = "image-variants";
+
+/// Largest width or height a variant can be asked for
+pub const MAX_DIMENSION: u32 = 4096;
+
+/// Largest source image decoded, in pixels
+pub const MAX_SOURCE_PIXELS: u64 = 40_000_000;
+
+/// Size the cache is kept under
+pub const MAX_CACHE_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Decoded 8-bit RGBA image
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    /// Rows of RGBA pixels, top to bottom
+    pub pixels: Vec<u8>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ImageError {
+    #[error("unsupported image: {0}")]
+    Unsupported(String),
+    #[error("invalid image: {0}")]
+    Invalid(String),
+}
+
+/// Formats a variant can be asked for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Webp,
+    Jpeg,
+}
+
+impl std::str::FromStr for ImageFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "png" => Ok(Self::Png),
+            "webp" => Ok(Self::Webp),
+            "jpeg" | "jpg" => Ok(Self::Jpeg),
+            other => Err(format!(
+                "unknown image format {}; expected png, webp or jpeg",
+                other
+            )),
+        }
+    }
+}
+
+/// Size of a requested variant
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VariantParams {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub format: Option<ImageFormat>,
+}
+
+impl VariantParams {
+    /// Whether any variant was asked for
+    pub fn is_requested(&self) -> bool {
+        self.width.is_some() || self.height.is_some() || self.format.is_some()
+    }
+
+    /// Check the requested size is within bounds
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, value) in [("w", self.width), ("h", self.height)] {
+            if let Some(value) = value {
+                if value == 0 || value > MAX_DIMENSION {
+                    return Err(format!("{} must be between 1 and {}", name, MAX_DIMENSION));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Size of the variant of a `width` x `height` image: the largest that
+    /// fits the requested box without growing the image
+    pub fn target_size(&self, width: u32, height: u32) -> (u32, u32) {
+        let scale_w = self.width.map_or(1.0, |w| w as f64 / width as f64);
+        let scale_h = self.height.map_or(1.0, |h| h as f64 / height as f64);
+        let scale = scale_w.min(scale_h).min(1.0);
+        (
+            ((width as f64 * scale).round() as u32).max(1),
+            ((height as f64 * scale).round() as u32).max(1),
+        )
+    }
+
+    /// Name of the cached variant of a source blob. Variants are all PNG, so
+    /// the requested format doesn't change it.
+    fn file_name(&self, source: &Hash) -> String {
+        format!(
+            "{}-{}x{}.png",
+            source,
+            self.width.unwrap_or(0),
+            self.height.unwrap_or(0)
+        )
+    }
+}
+
+/// Scale an image to `width` x `height`, averaging the source pixels each
+/// target pixel covers. Colors are weighted by alpha so transparent pixels
+/// don't darken edges.
+pub fn resize(image: &Image, width: u32, height: u32) -> Image {
+    let (src_w, src_h) = (image.width as usize, image.height as usize);
+    let (dst_w, dst_h) = (width as usize, height as usize);
+    // Source range [start, end) covered by each target row or column
+    let span = |i: usize, src: usize, dst: usize| {
+        let start = i * src / dst;
+        let end = ((i + 1) * src / dst).max(start + 1).min(src);
+        start..end
+    };
+
+    let mut pixels = Vec::with_capacity(dst_w * dst_h * 4);
+    for y in 0..dst_h {
+        let rows = span(y, src_h, dst_h);
+        for x in 0..dst_w {
+            let columns = span(x, src_w, dst_w);
+            let (mut r, mut g, mut b, mut a, mut count) = (0u64, 0u64, 0u64, 0u64, 0u64);
+            for sy in rows.clone() {
+                let row = &image.pixels[sy * src_w * 4..(sy + 1) * src_w * 4];
+                for pixel in row[columns.start * 4..columns.end * 4].chunks_exact(4) {
+                    let alpha = pixel[3] as u64;
+                    r += pixel[0] as u64 * alpha;
+                    g += pixel[1] as u64 * alpha;
+                    b += pixel[2] as u64 * alpha;
+                    a += alpha;
+                    count += 1;
+                }
+            }
+            match std::num::NonZeroU64::new(a) {
+                Some(weight) => pixels.extend_from_slice(&[
+                    (r / weight) as u8,
+                    (g / weight) as u8,
+                    (b / weight) as u8,
+                    (a / count) as u8,
+                ]),
+                None => pixels.extend_from_slice(&[0, 0, 0, 0]),
+            }
+        }
+    }
+
+    Image {
+        width,
+        height,
+        pixels,
+    }
+}
+
+/// Make the variant of an image. Returns None for sources that can't be
+/// decoded, which are served as they are.
+pub fn make_variant(source: &[u8], params: &VariantParams) -> Result<Option<Vec<u8>>, ImageError> {
+    if !png::is_png(source) {
+        return Ok(None);
+    }
+    let image = match png::decode(source) {
+        Ok(image) => image,
+        Err(ImageError::Unsupported(reason)) => {
+            tracing::debug!("Serving image unchanged: {}", reason);
+            return Ok(None);
+        }
+        Err(e) => return Err(e),
+    };
+    let (width, height) = params.target_size(image.width, image.height);
+    let image = if (width, height) == (image.width, image.height) {
+        image
+    } else {
+        resize(&image, width, height)
+    };
+    Ok(Some(png::encode(&image)))
+}
+
+/// On-disk cache of image variants
+#[derive(Debug, Clone)]
+pub struct ImageVariants {
+    inner: Arc<ImageVariantsInner>,
+}
+
+#[derive(Debug)]
+struct ImageVariantsInner {
+    dir: PathBuf,
+    max_bytes: u64,
+    /// Serializes eviction
+    evicting: tokio::sync::Mutex<()>,
+}
+
+impl ImageVariants {
+    pub fn new(dir: PathBuf) -> Self {
+        Self::with_max_bytes(dir, MAX_CACHE_BYTES)
+    }
+
+    pub fn with_max_bytes(dir: PathBuf, max_bytes: u64) -> Self {
+        Self {
+            inner: Arc::new(ImageVariantsInner {
+                dir,
+                max_bytes,
+                evicting: tokio::sync::Mutex::new(()),
+            }),
+        }
+    }
+
+    /// A cached variant, marking it as recently served
+    pub async fn get(&self, source: &Hash, params: &VariantParams) -> Option<Vec<u8>> {
+        let path = self.inner.dir.join(params.file_name(source));
+        let data = tokio::fs::read(&path).await.ok()?;
+        tokio::task::spawn_blocking(move || {
+            if let Ok(file) = std::fs::File::options().write(true).open(&path) {
+                let _ = file.set_modified(SystemTime::now());
+            }
+        });
+        Some(data)
+    }
+
+    /// Make the variant of a source and cache it. Returns None for sources
+    /// that can't be decoded.
+    pub async fn create(
+        &self,
+        source: &Hash,
+        params: &VariantParams,
+        data: Vec<u8>,
+    ) -> Result<Option<Vec<u8>>, ImageError> {
+        let make_params = *params;
+        let variant = tokio::task::spawn_blocking(move || make_variant(&data, &make_params))
+            .await
+            .map_err(|e| ImageError::Invalid(e.to_string()))??;
+        let Some(variant) = variant else {
+            return Ok(None);
+        };
+
+        // Failing to cache only costs recomputing the variant
+        if let Err(e) = self.store(&params.file_name(source), &variant).await {
+            tracing::warn!("Failed to cache image variant: {}", e);
+        }
+        Ok(Some(variant))
+    }
+
+    async fn store(&self, name: &str, variant: &[u8]) -> std::io::Result<()> {
+        tokio::fs::create_dir_all(&self.inner.dir).await?;
+        // Written aside and renamed, so readers never see part of a variant
+        let partial = self.inner.dir.join(format!(".{}.partial", name));
+        tokio::fs::write(&partial, variant).await?;
+        tokio::fs::rename(&partial, self.inner.dir.join(name)).await?;
+        self.evict().await
+    }
+
+    /// Remove the least recently served variants while the cache is over
+    /// its limit
+    async fn evict(&self) -> std::io::Result<()> {
+        let _guard = self.inner.evicting.lock().await;
+        let mut entries = Vec::new();
+        let mut total = 0;
+        let mut dir = tokio::fs::read_dir(&self.inner.dir).await?;
+        while let Some(entry) = dir.next_entry().await? {
+            // Variants still being written are left to their writer
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let metadata = entry.metadata().await?;
+            if metadata.is_file() {
+                total += metadata.len();
+                let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                entries.push((modified, metadata.len(), entry.path()));
+            }
+        }
+        if total <= self.inner.max_bytes {
+            return Ok(());
+        }
+
+        entries.sort();
+        for (_, size, path) in entries {
+            if total <= self.inner.max_bytes {
+                break;
+            }
+            tokio::fs::remove_file(&path).await?;
+            total -= size;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn solid(width: u32, height: u32, rgba: [u8; 4]) -> Image {
+        Image {
+            width,
+            height,
+            pixels: rgba.repeat((width * height) as usize),
+        }
+    }
+
+    #[test]
+    fn test_target_size_keeps_aspect_and_never_grows() {
+        let params = |width, height| VariantParams {
+            width,
+            height,
+            format: None,
+        };
+        assert_eq!(params(Some(400), None).target_size(1600, 1200), (400, 300));
+        assert_eq!(params(None, Some(300)).target_size(1600, 1200), (400, 300));
+        assert_eq!(
+            params(Some(400), Some(100)).target_size(1600, 1200),
+            (133, 100)
+        );
+        assert_eq!(
+            params(Some(4000), None).target_size(1600, 1200),
+            (1600, 1200)
+        );
+        assert_eq!(params(Some(1), None).target_size(1000, 10), (1, 1));
+        assert!(params(Some(0), None).validate().is_err());
+        assert!(params(Some(MAX_DIMENSION + 1), None).validate().is_err());
+    }
+
+    #[test]
+    fn test_resize_averages_weighted_by_alpha() {
+        // Left half opaque red, right half transparent
+        let mut image = solid(4, 2, [255, 0, 0, 255]);
+        for y in 0..2 {
+            for x in 2..4 {
+                let i = (y * 4 + x) * 4;
+                image.pixels[i..i + 4].copy_from_slice(&[0, 0, 0, 0]);
+            }
+        }
+        let resized = resize(&image, 1, 1);
+        assert_eq!(resized.pixels, vec![255, 0, 0, 127]);
+
+        let resized = resize(&solid(5, 3, [10, 20, 30, 255]), 2, 2);
+        assert_eq!(resized.pixels, [10, 20, 30, 255].repeat(4));
+    }
+
+    #[test]
+    fn test_make_variant() {
+        let source = png::encode(&solid(40, 20, [1, 2, 3, 255]));
+        let params = VariantParams {
+            width: Some(10),
+            height: None,
+            format: Some(ImageFormat::Webp),
+        };
+        let variant = make_variant(&source, &params).unwrap().unwrap();
+        let decoded = png::decode(&variant).unwrap();
+        assert_eq!((decoded.width, decoded.height), (10, 5));
+
+        // Formats that can't be decoded are left alone
+        assert!(make_variant(b"\xff\xd8\xff\xe0", &params)
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cache_evicts_least_recently_served() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = png::encode(&solid(64, 64, [9, 9, 9, 255]));
+        let hashes: Vec<Hash> = (0..3u8).map(|i| Hash::new([i])).collect();
+        let params = VariantParams {
+            width: Some(16),
+            ..Default::default()
+        };
+        let size = make_variant(&source, &params).unwrap().unwrap().len() as u64;
+        let variants = ImageVariants::with_max_bytes(dir.path().to_path_buf(), size * 2);
+
+        for hash in &hashes[..2] {
+            variants
+                .create(hash, &params, source.clone())
+                .await
+                .unwrap()
+                .unwrap();
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        // Serving the first makes the second the oldest
+        assert!(variants.get(&hashes[0], &params).await.is_some());
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        variants
+            .create(&hashes[2], &params, source.clone())
+            .await
+            .unwrap();
+
+        assert!(variants.get(&hashes[0], &params).await.is_some());
+        assert!(variants.get(&hashes[1], &params).await.is_none());
+        assert!(variants.get(&hashes[2], &params).await.is_some());
+    }
+}
@@ -0,0 +1,375 @@
+//! Minimal PNG codec for resizing
+//!
+//! Decodes non-interlaced PNGs of any color type and bit depth to 8-bit RGBA,
+//! and encodes RGBA back to PNG. Ancillary chunks other than `tRNS` are
+//! dropped.
+
+use miniz_oxide::deflate::compress_to_vec_zlib;
+use miniz_oxide::inflate::decompress_to_vec_zlib_with_limit;
+
+use super::{Image, ImageError, MAX_SOURCE_PIXELS};
+
+const SIGNATURE: &[u8; 8] = b"\x89PNG\r\n\x1a\n";
+
+/// Whether data starts like a PNG
+pub fn is_png(data: &[u8]) -> bool {
+    data.starts_with(SIGNATURE)
+}
+
+struct Header {
+    width: u32,
+    height: u32,
+    bit_depth: u8,
+    color_type: u8,
+}
+
+impl Header {
+    fn channels(&self) -> usize {
+        match self.color_type {
+            2 => 3,
+            4 => 2,
+            6 => 4,
+            _ => 1,
+        }
+    }
+
+    /// Bytes in a row of pixels, without its filter byte
+    fn stride(&self) -> usize {
+        (self.width as usize * self.channels() * self.bit_depth as usize).div_ceil(8)
+    }
+
+    /// Distance to the corresponding byte of the previous pixel, for filters
+    fn filter_distance(&self) -> usize {
+        (self.channels() * self.bit_depth as usize).div_ceil(8)
+    }
+}
+
+/// Decode a PNG to RGBA
+pub fn decode(data: &[u8]) -> Result<Image, ImageError> {
+    if !is_png(data) {
+        return Err(ImageError::Unsupported("not a PNG".to_string()));
+    }
+
+    let mut header = None;
+    let mut palette: &[u8] = &[];
+    let mut transparency: &[u8] = &[];
+    let mut compressed = Vec::new();
+    let mut rest = &data[SIGNATURE.len()..];
+    while rest.len() >= 12 {
+        let length = u32::from_be_bytes(rest[0..4].try_into().unwrap()) as usize;
+        let kind = &rest[4..8];
+        let Some(body) = rest.get(8..8 + length) else {
+            return Err(ImageError::Invalid("truncated chunk".to_string()));
+        };
+        match kind {
+            b"IHDR" if body.len() == 13 => {
+                if body[12] != 0 {
+                    return Err(ImageError::Unsupported("interlaced PNG".to_string()));
+                }
+                header = Some(Header {
+                    width: u32::from_be_bytes(body[0..4].try_into().unwrap()),
+                    height: u32::from_be_bytes(body[4..8].try_into().unwrap()),
+                    bit_depth: body[8],
+                    color_type: body[9],
+                });
+            }
+            b"PLTE" => palette = body,
+            b"tRNS" => transparency = body,
+            b"IDAT" => compressed.extend_from_slice(body),
+            b"IEND" => break,
+            _ => {}
+        }
+        rest = &rest[(12 + length).min(rest.len())..];
+    }
+
+    let header = header.ok_or_else(|| ImageError::Invalid("missing IHDR".to_string()))?;
+    let valid_depth = match header.color_type {
+        0 => matches!(header.bit_depth, 1 | 2 | 4 | 8 | 16),
+        3 => matches!(header.bit_depth, 1 | 2 | 4 | 8),
+        2 | 4 | 6 => matches!(header.bit_depth, 8 | 16),
+        _ => false,
+    };
+    if !valid_depth {
+        return Err(ImageError::Invalid(format!(
+            "color type {} with bit depth {}",
+            header.color_type, header.bit_depth
+        )));
+    }
+    if header.width == 0
+        || header.height == 0
+        || header.width as u64 * header.height as u64 > MAX_SOURCE_PIXELS
+    {
+        return Err(ImageError::Unsupported(format!(
+            "{}x{} image",
+            header.width, header.height
+        )));
+    }
+
+    let stride = header.stride();
+    let expected = (stride + 1) * header.height as usize;
+    let mut raw = decompress_to_vec_zlib_with_limit(&compressed, expected)
+        .map_err(|e| ImageError::Invalid(format!("image data: {:?}", e.status)))?;
+    if raw.len() < expected {
+        return Err(ImageError::Invalid("truncated image data".to_string()));
+    }
+    unfilter(&header, &mut raw)?;
+
+    let mut pixels = Vec::with_capacity(header.width as usize * header.height as usize * 4);
+    for row in raw.chunks_exact(stride + 1) {
+        let row = &row[1..];
+        for x in 0..header.width as usize {
+            pixels.extend_from_slice(&pixel(&header, row, x, palette, transparency));
+        }
+    }
+
+    Ok(Image {
+        width: header.width,
+        height: header.height,
+        pixels,
+    })
+}
+
+/// Undo the per-row filters in place, leaving each row's filter byte
+fn unfilter(header: &Header, raw: &mut [u8]) -> Result<(), ImageError> {
+    let stride = header.stride();
+    let distance = header.filter_distance();
+    let mut previous = vec![0u8; stride];
+    for row in raw.chunks_exact_mut(stride + 1) {
+        let (filter, row) = row.split_first_mut().unwrap();
+        for i in 0..stride {
+            let left = if i >= distance { row[i - distance] } else { 0 };
+            let up = previous[i];
+            let up_left = if i >= distance {
+                previous[i - distance]
+            } else {
+                0
+            };
+            let predicted = match *filter {
+                0 => 0,
+                1 => left,
+                2 => up,
+                3 => ((left as u16 + up as u16) / 2) as u8,
+                4 => paeth(left, up, up_left),
+                other => return Err(ImageError::Invalid(format!("filter type {}", other))),
+            };
+            row[i] = row[i].wrapping_add(predicted);
+        }
+        previous.copy_from_slice(row);
+    }
+    Ok(())
+}
+
+fn paeth(left: u8, up: u8, up_left: u8) -> u8 {
+    let estimate = left as i16 + up as i16 - up_left as i16;
+    let (to_left, to_up, to_up_left) = (
+        (estimate - left as i16).abs(),
+        (estimate - up as i16).abs(),
+        (estimate - up_left as i16).abs(),
+    );
+    if to_left <= to_up && to_left <= to_up_left {
+        left
+    } else if to_up <= to_up_left {
+        up
+    } else {
+        up_left
+    }
+}
+
+/// Read sample `index` of a row at the header's bit depth
+fn sample(header: &Header, row: &[u8], index: usize) -> u16 {
+    match header.bit_depth {
+        16 => u16::from_be_bytes([row[index * 2], row[index * 2 + 1]]),
+        8 => row[index] as u16,
+        depth => {
+            let bit = index * depth as usize;
+            let shift = 8 - depth as usize - bit % 8;
+            ((row[bit / 8] >> shift) & ((1 << depth) - 1)) as u16
+        }
+    }
+}
+
+/// Scale a sample to 8 bits
+fn to_u8(header: &Header, value: u16) -> u8 {
+    match header.bit_depth {
+        16 => (value >> 8) as u8,
+        8 => value as u8,
+        depth => (value as u32 * 255 / ((1 << depth) - 1)) as u8,
+    }
+}
+
+/// RGBA of pixel `x` of a row
+fn pixel(header: &Header, row: &[u8], x: usize, palette: &[u8], transparency: &[u8]) -> [u8; 4] {
+    let channels = header.channels();
+    let at = |channel: usize| sample(header, row, x * channels + channel);
+    // Color key of gray and RGB images without an alpha channel
+    let key = |channel: usize| {
+        transparency
+            .get(channel * 2..channel * 2 + 2)
+            .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+    };
+    match header.color_type {
+        0 => {
+            let gray = at(0);
+            let alpha = if key(0) == Some(gray) { 0 } else { 255 };
+            let gray = to_u8(header, gray);
+            [gray, gray, gray, alpha]
+        }
+        2 => {
+            let (r, g, b) = (at(0), at(1), at(2));
+            let keyed = key(0) == Some(r) && key(1) == Some(g) && key(2) == Some(b);
+            [
+                to_u8(header, r),
+                to_u8(header, g),
+                to_u8(header, b),
+                if keyed { 0 } else { 255 },
+            ]
+        }
+        3 => {
+            let index = at(0) as usize;
+            let color = palette.get(index * 3..index * 3 + 3).unwrap_or(&[0, 0, 0]);
+            let alpha = transparency.get(index).copied().unwrap_or(255);
+            [color[0], color[1], color[2], alpha]
+        }
+        4 => {
+            let gray = to_u8(header, at(0));
+            [gray, gray, gray, to_u8(header, at(1))]
+        }
+        _ => [
+            to_u8(header, at(0)),
+            to_u8(header, at(1)),
+            to_u8(header, at(2)),
+            to_u8(header, at(3)),
+        ],
+    }
+}
+
+/// Encode RGBA as a PNG, dropping the alpha channel if the image is opaque
+pub fn encode(image: &Image) -> Vec<u8> {
+    let opaque = image.pixels.chunks_exact(4).all(|pixel| pixel[3] == 255);
+    let channels = if opaque { 3 } else { 4 };
+    let stride = image.width as usize * channels;
+
+    // Paeth filter every row; it suits photos and costs little on the rest
+    let mut raw = Vec::with_capacity((stride + 1) * image.height as usize);
+    let mut previous = vec![0u8; stride];
+    let mut current = Vec::with_capacity(stride);
+    for row in image.pixels.chunks_exact(image.width as usize * 4) {
+        current.clear();
+        for pixel in row.chunks_exact(4) {
+            current.extend_from_slice(&pixel[..channels]);
+        }
+        raw.push(4);
+        for i in 0..stride {
+            let left = if i >= channels {
+                current[i - channels]
+            } else {
+                0
+            };
+            let up_left = if i >= channels {
+                previous[i - channels]
+            } else {
+                0
+            };
+            raw.push(current[i].wrapping_sub(paeth(left, previous[i], up_left)));
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&image.width.to_be_bytes());
+    header.extend_from_slice(&image.height.to_be_bytes());
+    header.extend_from_slice(&[8, if opaque { 2 } else { 6 }, 0, 0, 0]);
+
+    let mut png = SIGNATURE.to_vec();
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"IDAT", &compress_to_vec_zlib(&raw, 6));
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], body: &[u8]) {
+    png.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(body);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn gradient(width: u32, height: u32, alpha: u8) -> Image {
+        let mut pixels = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                pixels.extend_from_slice(&[(x * 10) as u8, (y * 10) as u8, 128, alpha]);
+            }
+        }
+        Image {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for alpha in [255, 100] {
+            let image = gradient(13, 7, alpha);
+            let decoded = decode(&encode(&image)).unwrap();
+            assert_eq!(decoded, image);
+        }
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"IEND"), 0xAE42_6082);
+    }
+
+    #[test]
+    fn test_decodes_palette_with_transparency() {
+        // 3x1, 2-bit palette: red, transparent green, blue
+        let mut png = SIGNATURE.to_vec();
+        write_chunk(&mut png, b"IHDR", &[0, 0, 0, 3, 0, 0, 0, 1, 2, 3, 0, 0, 0]);
+        write_chunk(&mut png, b"PLTE", &[255, 0, 0, 0, 255, 0, 0, 0, 255]);
+        write_chunk(&mut png, b"tRNS", &[255, 0]);
+        write_chunk(
+            &mut png,
+            b"IDAT",
+            &compress_to_vec_zlib(&[0, 0b0001_1000], 6),
+        );
+        write_chunk(&mut png, b"IEND", &[]);
+
+        let image = decode(&png).unwrap();
+        assert_eq!((image.width, image.height), (3, 1));
+        assert_eq!(
+            image.pixels,
+            vec![255, 0, 0, 255, 0, 255, 0, 0, 0, 0, 255, 255]
+        );
+    }
+
+    #[test]
+    fn test_rejects_other_formats() {
+        assert!(matches!(
+            decode(b"\xff\xd8\xff\xe0 jpeg"),
+            Err(ImageError::Unsupported(_))
+        ));
+    }
+}
@@ -0,0 +1,421 @@
+//! Content integrity manifests: signed listings of a bucket version
+//!
+//! An integrity manifest lists every file of one bucket version with the
+//! BLAKE3 hash and size of its plaintext, and is signed with the exporting
+//! node's key. It is plain text so it can be read, diffed and archived as
+//! an export record, and checked against a directory without a daemon or
+//! the bucket's keys (`jax verify-manifest`):
+//!
+//! ```text
+//! jax integrity manifest v1
+//! bucket: 550e8400-e29b-41d4-a716-446655440000
+//! name: reports
+//! version: 5d3c...
+//! height: 12
+//! exported: 2026-10-16T09:30:00Z
+//! signer: 2gx9...
+//! files: 2
+//!
+//! af1349b9...  1024  /2026/q3.pdf
+//! 9e107d9d...  87  /README.md
+//!
+//! signature: 4f1a...
+//! ```
+//!
+//! File lines are `<hash>  <size>  <path>`, sorted by path. The signature is
+//! an Ed25519 signature by `signer` of every byte before the `signature:`
+//! line. `%`, line feeds and carriage returns in names and paths are written
+//! as `%25`, `%0A` and `%0D`.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use common::crypto::{PublicKey, SecretKey, Signature};
+use common::linked_data::Hash;
+
+const HEADER: &str = "jax integrity manifest v1";
+
+/// A file of a bucket version, as listed in an integrity manifest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    /// Absolute path in the bucket
+    pub path: String,
+    /// BLAKE3 hash of the plaintext
+    pub hash: Hash,
+    pub size: u64,
+}
+
+/// A signed listing of the files of a bucket version
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntegrityManifest {
+    pub bucket_id: Uuid,
+    pub bucket_name: String,
+    /// Hash of the bucket version's link
+    pub version: Hash,
+    pub height: u64,
+    pub exported_at: OffsetDateTime,
+    pub signer: PublicKey,
+    pub files: Vec<ManifestEntry>,
+    pub signature: Signature,
+}
+
+impl IntegrityManifest {
+    /// Sign a listing of a bucket version, exported now
+    pub fn sign(
+        secret: &SecretKey,
+        bucket_id: Uuid,
+        bucket_name: String,
+        version: Hash,
+        height: u64,
+        mut files: Vec<ManifestEntry>,
+    ) -> Self {
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        // Whole seconds, so the manifest reads the same after a round trip
+        let now = OffsetDateTime::now_utc();
+        let exported_at = now.replace_nanosecond(0).unwrap_or(now);
+        let mut manifest = Self {
+            bucket_id,
+            bucket_name,
+            version,
+            height,
+            exported_at,
+            signer: secret.public(),
+            files,
+            signature: Signature::from_bytes(&[0; 64]),
+        };
+        manifest.signature = secret.sign(manifest.body().as_bytes());
+        manifest
+    }
+
+    /// Check the signature against the manifest's signer
+    pub fn verify(&self) -> Result<(), IntegrityError> {
+        self.signer
+            .verify(self.body().as_bytes(), &self.signature)
+            .map_err(|_| IntegrityError::BadSignature)
+    }
+
+    /// Compare a directory holding the bucket's files against the manifest
+    pub fn check_dir(&self, dir: &Path) -> Result<DirCheck, IntegrityError> {
+        let mut local = BTreeMap::new();
+        walk(dir, "", &mut local)?;
+
+        let mut check = DirCheck::default();
+        for entry in &self.files {
+            let Some(file) = local.remove(&entry.path) else {
+                check.missing.push(entry.path.clone());
+                continue;
+            };
+            if fs::metadata(&file)?.len() == entry.size && hash_file(&file)? == entry.hash {
+                check.matched += 1;
+            } else {
+                check.modified.push(entry.path.clone());
+            }
+        }
+        check.extra = local.into_keys().collect();
+        Ok(check)
+    }
+
+    /// Everything the signature covers
+    fn body(&self) -> String {
+        let mut body = format!(
+            "{}\nbucket: {}\nname: {}\nversion: {}\nheight: {}\nexported: {}\nsigner: {}\nfiles: {}\n\n",
+            HEADER,
+            self.bucket_id,
+            escape(&self.bucket_name),
+            self.version,
+            self.height,
+            self.exported_at
+                .format(&Rfc3339)
+                .unwrap_or_else(|_| self.exported_at.unix_timestamp().to_string()),
+            self.signer.to_hex(),
+            self.files.len(),
+        );
+        for entry in &self.files {
+            body.push_str(&format!(
+                "{}  {}  {}\n",
+                entry.hash,
+                entry.size,
+                escape(&entry.path)
+            ));
+        }
+        body.push('\n');
+        body
+    }
+}
+
+impl fmt::Display for IntegrityManifest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}signature: {}",
+            self.body(),
+            hex::encode(self.signature.to_bytes())
+        )
+    }
+}
+
+impl FromStr for IntegrityManifest {
+    type Err = IntegrityError;
+
+    /// Parse a manifest without checking its signature
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = Lines {
+            lines: s.lines(),
+            number: 0,
+        };
+        if lines.next()? != HEADER {
+            return Err(lines.error(format!("expected `{}`", HEADER)));
+        }
+        let bucket_id = lines.parse("bucket")?;
+        let bucket_name = unescape(lines.field("name")?);
+        let version = lines.parse("version")?;
+        let height = lines.parse("height")?;
+        let exported_at = OffsetDateTime::parse(lines.field("exported")?, &Rfc3339)
+            .map_err(|e| lines.error(format!("invalid exported: {}", e)))?;
+        let signer = PublicKey::from_hex(lines.field("signer")?)
+            .map_err(|e| lines.error(format!("invalid signer: {}", e)))?;
+        let count: usize = lines.parse("files")?;
+        lines.blank()?;
+
+        let mut files = Vec::with_capacity(count);
+        for _ in 0..count {
+            let line = lines.next()?;
+            let mut parts = line.splitn(3, "  ");
+            let (Some(hash), Some(size), Some(path)) = (parts.next(), parts.next(), parts.next())
+            else {
+                return Err(lines.error("expected `<hash>  <size>  <path>`".to_string()));
+            };
+            let path = unescape(path);
+            if !path.starts_with('/') {
+                return Err(lines.error(format!("path isn't absolute: {}", path)));
+            }
+            files.push(ManifestEntry {
+                path,
+                hash: hash
+                    .parse()
+                    .map_err(|e| lines.error(format!("invalid hash: {}", e)))?,
+                size: size
+                    .parse()
+                    .map_err(|e| lines.error(format!("invalid size: {}", e)))?,
+            });
+        }
+        lines.blank()?;
+
+        let signature = lines.field("signature")?;
+        let signature = hex::decode(signature)
+            .ok()
+            .and_then(|bytes| <[u8; 64]>::try_from(bytes).ok())
+            .map(|bytes| Signature::from_bytes(&bytes))
+            .ok_or_else(|| lines.error("invalid signature".to_string()))?;
+        if lines.lines.any(|line| !line.trim().is_empty()) {
+            return Err(lines.error("unexpected content after the signature".to_string()));
+        }
+
+        Ok(Self {
+            bucket_id,
+            bucket_name,
+            version,
+            height,
+            exported_at,
+            signer,
+            files,
+            signature,
+        })
+    }
+}
+
+/// Lines of a manifest being parsed, tracking the line number for errors
+struct Lines<'a> {
+    lines: std::str::Lines<'a>,
+    number: usize,
+}
+
+impl<'a> Lines<'a> {
+    fn next(&mut self) -> Result<&'a str, IntegrityError> {
+        self.number += 1;
+        self.lines
+            .next()
+            .ok_or_else(|| self.error("unexpected end of manifest".to_string()))
+    }
+
+    fn blank(&mut self) -> Result<(), IntegrityError> {
+        if !self.next()?.is_empty() {
+            return Err(self.error("expected an empty line".to_string()));
+        }
+        Ok(())
+    }
+
+    /// The value of a `<name>: <value>` line
+    fn field(&mut self, name: &str) -> Result<&'a str, IntegrityError> {
+        let line = self.next()?;
+        line.strip_prefix(name)
+            .and_then(|rest| rest.strip_prefix(": "))
+            .ok_or_else(|| self.error(format!("expected `{}: ...`", name)))
+    }
+
+    fn parse<T>(&mut self, name: &str) -> Result<T, IntegrityError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        let value = self.field(name)?;
+        value
+            .parse()
+            .map_err(|e| self.error(format!("invalid {}: {}", name, e)))
+    }
+
+    fn error(&self, message: String) -> IntegrityError {
+        IntegrityError::Parse(self.number, message)
+    }
+}
+
+/// Result of comparing a directory against a manifest
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DirCheck {
+    /// Files whose size and hash match
+    pub matched: usize,
+    /// Listed files that aren't in the directory
+    pub missing: Vec<String>,
+    /// Listed files whose size or hash differ
+    pub modified: Vec<String>,
+    /// Files in the directory that aren't listed
+    pub extra: Vec<String>,
+}
+
+/// BLAKE3 hash of a local file's contents
+pub fn hash_file(path: &Path) -> std::io::Result<Hash> {
+    let mut hasher = blake3::Hasher::new();
+    std::io::copy(&mut fs::File::open(path)?, &mut hasher)?;
+    Ok(Hash::from_bytes(*hasher.finalize().as_bytes()))
+}
+
+/// Collect the regular files under `dir` by their bucket path
+fn walk(
+    dir: &Path,
+    prefix: &str,
+    files: &mut BTreeMap<String, std::path::PathBuf>,
+) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = format!("{}/{}", prefix, entry.file_name().to_string_lossy());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            walk(&entry.path(), &path, files)?;
+        } else if file_type.is_file() {
+            files.insert(path, entry.path());
+        }
+    }
+    Ok(())
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\n', "%0A")
+        .replace('\r', "%0D")
+}
+
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(at) = rest.find('%') {
+        unescaped.push_str(&rest[..at]);
+        let (replacement, len) = match rest.get(at..at + 3) {
+            Some("%25") => ("%", 3),
+            Some("%0A") => ("\n", 3),
+            Some("%0D") => ("\r", 3),
+            _ => ("%", 1),
+        };
+        unescaped.push_str(replacement);
+        rest = &rest[at + len..];
+    }
+    unescaped.push_str(rest);
+    unescaped
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum IntegrityError {
+    #[error("Invalid manifest at line {0}: {1}")]
+    Parse(usize, String),
+    #[error("The manifest's signature doesn't match its signer")]
+    BadSignature,
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn entry(path: &str, data: &[u8]) -> ManifestEntry {
+        ManifestEntry {
+            path: path.to_string(),
+            hash: Hash::new(data),
+            size: data.len() as u64,
+        }
+    }
+
+    fn manifest() -> IntegrityManifest {
+        IntegrityManifest::sign(
+            &SecretKey::generate(),
+            Uuid::new_v4(),
+            "100% reports".to_string(),
+            Hash::new(b"version"),
+            3,
+            vec![
+                entry("/b/two.txt", b"two"),
+                entry("/one two.txt", b"one"),
+                entry("/odd\nname%0A", b"odd"),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let manifest = manifest();
+        assert_eq!(manifest.files[0].path, "/b/two.txt");
+
+        let text = manifest.to_string();
+        assert!(text.contains("name: 100%25 reports\n"));
+        assert!(text.contains("  3  /odd%0Aname%250A\n"));
+        let parsed: IntegrityManifest = text.parse().unwrap();
+        assert_eq!(parsed, manifest);
+        parsed.verify().unwrap();
+    }
+
+    #[test]
+    fn test_tampering_breaks_signature() {
+        let text = manifest().to_string();
+        let tampered = text.replacen("  3  /one two.txt", "  4  /one two.txt", 1);
+        let parsed: IntegrityManifest = tampered.parse().unwrap();
+        assert!(matches!(parsed.verify(), Err(IntegrityError::BadSignature)));
+
+        let truncated = text.replace("files: 3", "files: 4");
+        assert!(matches!(
+            truncated.parse::<IntegrityManifest>(),
+            Err(IntegrityError::Parse(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_check_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("b")).unwrap();
+        fs::write(dir.path().join("b/two.txt"), b"2").unwrap();
+        fs::write(dir.path().join("one two.txt"), b"one").unwrap();
+        fs::write(dir.path().join("extra.txt"), b"extra").unwrap();
+
+        let check = manifest().check_dir(dir.path()).unwrap();
+        assert_eq!(check.matched, 1);
+        assert_eq!(check.modified, vec!["/b/two.txt"]);
+        assert_eq!(check.missing, vec!["/odd\nname%0A"]);
+        assert_eq!(check.extra, vec!["/extra.txt"]);
+    }
+}
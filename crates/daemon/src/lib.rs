@@ -1,24 +1,70 @@
 // Service modules (daemon functionality)
+pub mod activity;
+pub mod audit;
+pub mod backup;
+pub mod blob_cache;
 pub(crate) mod blobs;
+pub mod bucket_index;
+pub mod bucket_stats;
+pub mod cdn_export;
 pub mod clone_state;
+pub mod cluster;
+pub mod data_export;
 pub(crate) mod database;
+pub mod deploy;
+pub mod drop_box;
+pub mod ephemeral;
+pub mod follow;
 #[cfg(feature = "fuse")]
 pub mod fuse;
+pub mod gateway_policy;
+pub mod gateway_sync;
+pub mod gateway_tls;
+pub mod git_remote;
 pub mod http_server;
+pub mod identity;
+pub mod image_variants;
+pub mod integrity;
+#[cfg(feature = "lite")]
+pub mod lite;
+pub mod logs;
+pub mod member_policy;
+pub mod pagination;
+pub mod peers;
 pub mod process;
+pub mod profiles;
+pub mod publish;
+pub mod publish_pipeline;
+pub mod publish_scan;
+pub mod reload;
+pub mod remote_import;
+pub mod replica;
+pub mod retention;
+pub mod reverse_proxy;
+pub mod robots;
 pub mod service_config;
 pub mod service_state;
+pub mod signed_url;
+pub mod storage_policy;
+pub mod sync_conditions;
+pub mod sync_policy;
 pub(crate) mod sync_provider;
+pub mod telemetry;
+pub mod version_url;
+pub mod webhooks;
+pub mod zip_stream;
 
 // App state (configuration, paths)
 pub mod state;
 
 // Re-exports for consumers (Tauri, etc.)
-pub use database::Database;
+pub use database::{Database, DatabaseKey, DatabaseSetupError};
 pub use process::{spawn_service, start_service, ShutdownHandle};
 pub use service_config::Config as ServiceConfig;
 pub use service_state::State as ServiceState;
 pub use state::{AppConfig, AppState, BlobStoreConfig, StateError};
+pub use sync_policy::{SyncPolicies, SyncPolicy};
+pub use sync_provider::{SyncActivity, SyncStatus};
 
 // Re-exports for mount management
 pub use database::models::FuseMount;
@@ -0,0 +1,365 @@
+//! Lite profile for mobile shells
+//!
+//! The full daemon runs the API and gateway servers, FUSE mounts and a timer
+//! that pings bucket peers, which is more than an iOS or Android app that may
+//! be suspended at any moment can carry. [`LiteNode`] runs only the peer and
+//! the sync worker, pings peers only when the host asks for it (from a
+//! BGTaskScheduler or WorkManager job via [`LiteNode::background_sync`]), and
+//! exposes a small blocking API of plain types for FFI bindings to wrap (see
+//! the `jax-mobile-ffi` crate).
+//!
+//! Build it without the default features: `--no-default-features --features lite`.
+
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use common::bucket_log::BucketLogProvider;
+use common::crypto::PublicKey;
+use common::prelude::MountError;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::audit::{Actor, AuditEvent};
+use crate::process::{start_lite_service, ShutdownHandle};
+use crate::service_state::StateSetupError;
+use crate::state::{AppState, StateError};
+use crate::sync_conditions::Conditions;
+use crate::{ServiceConfig, ServiceState, SyncActivity};
+
+/// How often a background sync checks whether the sync queue has drained
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// A bucket the node holds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiteBucket {
+    pub id: String,
+    pub name: String,
+    /// Hash of the bucket's current version
+    pub version: String,
+}
+
+/// An entry of a bucket directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiteEntry {
+    pub name: String,
+    pub path: String,
+    pub is_dir: bool,
+    pub mime_type: Option<String>,
+}
+
+/// What a background sync got through
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiteSyncReport {
+    /// Buckets whose peers were pinged
+    pub buckets_pinged: u32,
+    /// Sync jobs still queued when the budget ran out
+    pub jobs_remaining: u32,
+    /// Whether the sync queue drained within the budget
+    pub finished: bool,
+}
+
+/// A jax node without the daemon's servers, for mobile shells
+///
+/// Calls block on the node's own runtime, so they must not be made from an
+/// async context.
+pub struct LiteNode {
+    runtime: tokio::runtime::Runtime,
+    state: ServiceState,
+    shutdown: ShutdownHandle,
+}
+
+impl LiteNode {
+    /// Start a node from an initialized jax directory
+    pub fn start(jax_dir: String) -> Result<Self, LiteError> {
+        let app_state = AppState::load(Some(PathBuf::from(jax_dir)))?;
+        let secret_key = app_state.load_key()?;
+
+        let config = ServiceConfig {
+            node_listen_addr: None,
+            node_secret: Some(secret_key.clone()),
+            blob_store: app_state.config.blob_store.clone(),
+            blob_locations: app_state.config.blob_locations.clone(),
+            cdn_targets: app_state.config.cdn_targets.clone(),
+            jax_dir: app_state.jax_dir.clone(),
+            api_port: app_state.config.api_port,
+            gateway_port: app_state.config.gateway_port,
+            sqlite_path: Some(app_state.db_path.clone()),
+            database_key: app_state.config.database_key(&secret_key),
+            config_path: None,
+            sync_interval: app_state.config.sync_interval(),
+            periodic_sync: false,
+            online_cache_max_bytes: app_state.config.online_cache_max_bytes,
+            sync_deferral: app_state.config.sync_deferral,
+            protocol_timeouts: app_state.config.protocol_timeouts.timeouts(),
+            connection_limits: app_state.config.protocol_limits.limits(),
+            log_level: app_state.config.log_level()?,
+            log_dir: None,
+            otlp_endpoint: None,
+            gateway_url: None,
+            gateway_tls: None,
+            reverse_proxy: None,
+            publish_scanners: Vec::new(),
+            publish_builders: Vec::new(),
+            upload_limits: app_state.config.upload_limits.clone(),
+            import_dirs: Vec::new(),
+            read_replica: false,
+            cluster: None,
+        };
+
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .enable_all()
+            .build()
+            .map_err(LiteError::Runtime)?;
+        let (state, shutdown) = runtime.block_on(start_lite_service(&config))?;
+        Ok(Self {
+            runtime,
+            state,
+            shutdown,
+        })
+    }
+
+    /// Hex public key of the node
+    pub fn node_id(&self) -> String {
+        self.state.peer().secret().public().to_hex()
+    }
+
+    pub fn list_buckets(&self) -> Result<Vec<LiteBucket>, LiteError> {
+        let buckets = self
+            .runtime
+            .block_on(self.state.database().list_buckets(None, Some(1000)))?;
+        Ok(buckets
+            .into_iter()
+            .map(|bucket| LiteBucket {
+                id: bucket.id.to_string(),
+                name: bucket.name,
+                version: bucket.link.hash().to_string(),
+            })
+            .collect())
+    }
+
+    /// List a directory of a bucket
+    pub fn ls(&self, bucket_id: String, path: String) -> Result<Vec<LiteEntry>, LiteError> {
+        let bucket_id = parse_bucket_id(&bucket_id)?;
+        self.runtime.block_on(async {
+            let mount = self.state.peer().mount_for_read(bucket_id).await?;
+            let dir = Path::new("/").join(&path);
+            let items = mount.ls(&dir).await?;
+            Ok(items
+                .into_iter()
+                .map(|(item_path, node_link)| LiteEntry {
+                    name: item_path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                        .unwrap_or_default(),
+                    path: Path::new("/")
+                        .join(&item_path)
+                        .to_string_lossy()
+                        .to_string(),
+                    is_dir: node_link.is_dir(),
+                    mime_type: node_link
+                        .data()
+                        .and_then(|data| data.mime())
+                        .map(|mime| mime.to_string()),
+                })
+                .collect())
+        })
+    }
+
+    /// Read a file of a bucket
+    pub fn cat(&self, bucket_id: String, path: String) -> Result<Vec<u8>, LiteError> {
+        let bucket_id = parse_bucket_id(&bucket_id)?;
+        self.runtime.block_on(async {
+            let mount = self.state.peer().mount_for_read(bucket_id).await?;
+            Ok(mount.cat(&Path::new("/").join(&path)).await?)
+        })
+    }
+
+    /// Write a file to a bucket and save it, returning the new version's hash
+    pub fn add(&self, bucket_id: String, path: String, data: Vec<u8>) -> Result<String, LiteError> {
+        let bucket_id = parse_bucket_id(&bucket_id)?;
+        self.runtime.block_on(async {
+            let mut mount = self.state.peer().mount(bucket_id).await?;
+            mount
+                .add(&Path::new("/").join(&path), Cursor::new(data))
+                .await?;
+            let link = self.state.peer().save_mount(&mount, false).await?;
+            self.state
+                .audit(
+                    AuditEvent::new(Actor::Mobile, "bucket.add")
+                        .bucket(bucket_id)
+                        .path(path)
+                        .version(&link),
+                )
+                .await;
+            Ok(link.hash().to_string())
+        })
+    }
+
+    /// Share a bucket with a peer as an owner, or as a mirror that can only
+    /// read what is published, returning the new version's hash
+    pub fn share(
+        &self,
+        bucket_id: String,
+        peer_public_key: String,
+        mirror: bool,
+    ) -> Result<String, LiteError> {
+        let bucket_id = parse_bucket_id(&bucket_id)?;
+        let peer_public_key = PublicKey::from_hex(&peer_public_key)
+            .map_err(|e| LiteError::InvalidPublicKey(e.to_string()))?;
+        self.runtime.block_on(async {
+            let mut mount = self.state.peer().mount(bucket_id).await?;
+            if mirror {
+                mount.add_mirror(peer_public_key).await;
+            } else {
+                mount.add_owner(peer_public_key).await?;
+            }
+            self.state.peer().negotiate_shares(&mut mount).await?;
+            let link = self.state.peer().save_mount(&mount, false).await?;
+            self.state
+                .audit(
+                    AuditEvent::new(Actor::Mobile, "bucket.share")
+                        .bucket(bucket_id)
+                        .detail(peer_public_key.to_hex())
+                        .version(&link),
+                )
+                .await;
+            Ok(link.hash().to_string())
+        })
+    }
+
+    /// Ping a bucket's peers now, e.g. when the user opens it
+    pub fn sync_bucket(&self, bucket_id: String) -> Result<(), LiteError> {
+        let bucket_id = parse_bucket_id(&bucket_id)?;
+        self.runtime.block_on(async {
+            self.state
+                .peer()
+                .ping(bucket_id)
+                .await
+                .map_err(|e| LiteError::Sync(e.to_string()))
+        })
+    }
+
+    /// Ping the peers of every bucket and run sync jobs until the queue
+    /// drains or `budget_secs` runs out
+    ///
+    /// This is the hook for the OS's background scheduling: call it from a
+    /// BGAppRefreshTask or WorkManager job with the time the OS grants.
+    /// Buckets whose policy is paused are skipped, and nothing runs while
+    /// syncing is paused.
+    pub fn background_sync(&self, budget_secs: u64) -> Result<LiteSyncReport, LiteError> {
+        let deadline = Instant::now() + Duration::from_secs(budget_secs);
+        self.runtime.block_on(async {
+            if self.state.sync_status().is_paused() {
+                return Ok(LiteSyncReport {
+                    buckets_pinged: 0,
+                    jobs_remaining: self.state.sync_status().parked_jobs() as u32,
+                    finished: false,
+                });
+            }
+
+            let peer = self.state.peer();
+            let mut bucket_ids = BucketLogProvider::list_buckets(peer.logs())
+                .await
+                .map_err(|e| LiteError::Sync(e.to_string()))?;
+            for bucket_id in peer.followed_buckets() {
+                if !bucket_ids.contains(&bucket_id) {
+                    bucket_ids.push(bucket_id);
+                }
+            }
+
+            let mut buckets_pinged = 0;
+            for bucket_id in bucket_ids {
+                if self.state.sync_policies().get(&bucket_id).paused {
+                    continue;
+                }
+                match peer.ping(bucket_id).await {
+                    Ok(()) => buckets_pinged += 1,
+                    Err(e) => {
+                        tracing::warn!("Failed to ping peers for bucket {}: {}", bucket_id, e)
+                    }
+                }
+            }
+
+            // Pings queue their jobs and replies queue more, so the queue
+            // counts as drained once it has stayed empty for a poll
+            let mut idle_polls = 0;
+            while Instant::now() < deadline {
+                tokio::time::sleep(DRAIN_POLL_INTERVAL).await;
+                let idle = self.state.pending_sync_jobs() == 0
+                    && self.state.sync_status().activity() != SyncActivity::Syncing;
+                idle_polls = if idle { idle_polls + 1 } else { 0 };
+                if idle_polls >= 2 {
+                    return Ok(LiteSyncReport {
+                        buckets_pinged,
+                        jobs_remaining: 0,
+                        finished: true,
+                    });
+                }
+            }
+            Ok(LiteSyncReport {
+                buckets_pinged,
+                jobs_remaining: self.state.pending_sync_jobs() as u32,
+                finished: false,
+            })
+        })
+    }
+
+    /// Report the device's power and network conditions, e.g. from battery
+    /// and connectivity callbacks, so pin downloads wait for an unmetered
+    /// network (see [`crate::sync_conditions`])
+    pub fn set_conditions(&self, on_battery: bool, battery_percent: Option<u8>, metered: bool) {
+        self.state
+            .sync_status()
+            .conditions()
+            .report(Some(Conditions {
+                on_battery,
+                battery_percent,
+                metered,
+            }));
+    }
+
+    /// Stop the node, e.g. before the app is terminated
+    pub fn shutdown(self) {
+        let Self {
+            runtime,
+            state: _,
+            shutdown,
+        } = self;
+        shutdown.shutdown();
+        runtime.block_on(shutdown.wait());
+    }
+}
+
+fn parse_bucket_id(bucket_id: &str) -> Result<Uuid, LiteError> {
+    Uuid::from_str(bucket_id).map_err(|_| LiteError::InvalidBucketId(bucket_id.to_string()))
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum LiteError {
+    #[error("State error: {0}")]
+    State(#[from] StateError),
+    #[error("Failed to start node: {0}")]
+    Setup(#[from] StateSetupError),
+    #[error("Failed to start runtime: {0}")]
+    Runtime(std::io::Error),
+    #[error("Invalid bucket ID: {0}")]
+    InvalidBucketId(String),
+    #[error("Invalid public key: {0}")]
+    InvalidPublicKey(String),
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Mount error: {0}")]
+    Mount(String),
+    #[error("Sync error: {0}")]
+    Sync(String),
+}
+
+// Mount errors are large, and bindings only need their message
+impl From<MountError> for LiteError {
+    fn from(e: MountError) -> Self {
+        LiteError::Mount(e.to_string())
+    }
+}
@@ -0,0 +1,425 @@
+//! Structured log sink and queries over it
+//!
+//! When the daemon logs to a directory it also writes one JSON object per line
+//! to daily `jax.json.YYYY-MM-DD` files. Those files are what the log API and
+//! `jax daemon logs` read: the day in the file name lets time-range queries skip
+//! whole files, and each line is filtered by level, time and text.
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use time::format_description::well_known::Rfc3339;
+use time::{Date, OffsetDateTime};
+use tracing::Subscriber;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// File name prefix of the structured log files inside the log directory
+pub const JSON_LOG_PREFIX: &str = "jax.json";
+
+/// Create a layer that writes JSON log lines to daily files in `log_dir`.
+///
+/// The returned guard must be kept alive for buffered lines to be flushed.
+pub fn json_file_layer<S>(
+    log_dir: &Path,
+) -> (
+    impl Layer<S> + Send + Sync + 'static,
+    tracing_appender::non_blocking::WorkerGuard,
+)
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let appender = tracing_appender::rolling::daily(log_dir, JSON_LOG_PREFIX);
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+    let layer = tracing_subscriber::fmt::layer()
+        .json()
+        .with_current_span(false)
+        .with_span_list(false)
+        .with_writer(writer);
+    (layer, guard)
+}
+
+/// A single structured log line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogEntry {
+    /// RFC 3339 timestamp
+    pub timestamp: String,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    /// Structured fields other than the message
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub fields: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Raw line layout written by the JSON layer
+#[derive(Deserialize)]
+struct RawEntry {
+    timestamp: String,
+    level: String,
+    #[serde(default)]
+    target: String,
+    #[serde(default)]
+    fields: serde_json::Map<String, serde_json::Value>,
+}
+
+impl LogEntry {
+    /// Parse one line of a JSON log file
+    pub fn parse(line: &str) -> Option<Self> {
+        let raw: RawEntry = serde_json::from_str(line).ok()?;
+        let mut fields = raw.fields;
+        let message = match fields.remove("message") {
+            Some(serde_json::Value::String(message)) => message,
+            Some(other) => other.to_string(),
+            None => String::new(),
+        };
+        Some(Self {
+            timestamp: raw.timestamp,
+            level: raw.level,
+            target: raw.target,
+            message,
+            fields,
+        })
+    }
+
+    fn time(&self) -> Option<OffsetDateTime> {
+        OffsetDateTime::parse(&self.timestamp, &Rfc3339).ok()
+    }
+}
+
+/// Which log entries to return
+#[derive(Debug, Clone, Default)]
+pub struct LogFilter {
+    /// Minimum severity (e.g. WARN includes WARN and ERROR)
+    pub level: Option<tracing::Level>,
+    pub since: Option<OffsetDateTime>,
+    pub until: Option<OffsetDateTime>,
+    /// Case-insensitive text to look for in the message, target and fields
+    pub contains: Option<String>,
+}
+
+impl LogFilter {
+    pub fn matches(&self, entry: &LogEntry) -> bool {
+        if let Some(min_level) = self.level {
+            match entry.level.parse::<tracing::Level>() {
+                // More verbose levels compare greater
+                Ok(level) if level <= min_level => {}
+                _ => return false,
+            }
+        }
+
+        if self.since.is_some() || self.until.is_some() {
+            let Some(time) = entry.time() else {
+                return false;
+            };
+            if self.since.is_some_and(|since| time < since) {
+                return false;
+            }
+            if self.until.is_some_and(|until| time > until) {
+                return false;
+            }
+        }
+
+        if let Some(needle) = &self.contains {
+            let needle = needle.to_lowercase();
+            let in_fields = !entry.fields.is_empty()
+                && serde_json::Value::Object(entry.fields.clone())
+                    .to_string()
+                    .to_lowercase()
+                    .contains(&needle);
+            if !entry.message.to_lowercase().contains(&needle)
+                && !entry.target.to_lowercase().contains(&needle)
+                && !in_fields
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Whether a file covering `day` can contain matching entries
+    fn covers_day(&self, day: Date) -> bool {
+        // Files roll over on UTC days
+        let utc_day = |t: OffsetDateTime| t.to_offset(time::UtcOffset::UTC).date();
+        self.since.is_none_or(|since| day >= utc_day(since))
+            && self.until.is_none_or(|until| day <= utc_day(until))
+    }
+}
+
+/// Parse a time bound given either as RFC 3339 or relative to `now` (`30s`, `10m`, `2h`, `1d`)
+pub fn parse_time(s: &str, now: OffsetDateTime) -> Option<OffsetDateTime> {
+    if let Ok(time) = OffsetDateTime::parse(s, &Rfc3339) {
+        return Some(time);
+    }
+    let split = s.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = s.split_at(split);
+    let amount: i64 = amount.parse().ok()?;
+    let ago = match unit {
+        "s" => time::Duration::seconds(amount),
+        "m" => time::Duration::minutes(amount),
+        "h" => time::Duration::hours(amount),
+        "d" => time::Duration::days(amount),
+        _ => return None,
+    };
+    now.checked_sub(ago)
+}
+
+/// List the JSON log files in a directory, oldest first
+pub fn log_files(log_dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let entries = match std::fs::read_dir(log_dir) {
+        Ok(entries) => entries,
+        // Nothing has been logged yet
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(files),
+        Err(e) => return Err(e),
+    };
+    for entry in entries {
+        let path = entry?.path();
+        let is_json_log = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with(JSON_LOG_PREFIX));
+        if is_json_log && path.is_file() {
+            files.push(path);
+        }
+    }
+    // Daily suffixes (YYYY-MM-DD) sort chronologically
+    files.sort();
+    Ok(files)
+}
+
+/// The day a rolled log file covers, from its `YYYY-MM-DD` suffix
+fn file_day(path: &Path) -> Option<Date> {
+    let name = path.file_name()?.to_str()?;
+    let suffix = name.strip_prefix(JSON_LOG_PREFIX)?.strip_prefix('.')?;
+    let format = time::format_description::parse("[year]-[month]-[day]").ok()?;
+    Date::parse(suffix, &format).ok()
+}
+
+/// Return the most recent `limit` entries matching `filter`, oldest first.
+pub fn query(log_dir: &Path, filter: &LogFilter, limit: usize) -> io::Result<Vec<LogEntry>> {
+    let mut entries = VecDeque::with_capacity(limit.min(1024));
+    if limit == 0 {
+        return Ok(Vec::new());
+    }
+
+    for path in log_files(log_dir)? {
+        if file_day(&path).is_some_and(|day| !filter.covers_day(day)) {
+            continue;
+        }
+        let reader = BufReader::new(File::open(&path)?);
+        for line in reader.lines() {
+            let Some(entry) = LogEntry::parse(&line?) else {
+                continue;
+            };
+            if !filter.matches(&entry) {
+                continue;
+            }
+            if entries.len() == limit {
+                entries.pop_front();
+            }
+            entries.push_back(entry);
+        }
+    }
+
+    Ok(entries.into())
+}
+
+/// Follows the JSON log files, returning lines as they are appended
+#[derive(Debug)]
+pub struct LogTail {
+    log_dir: PathBuf,
+    file: Option<PathBuf>,
+    offset: u64,
+    partial: String,
+}
+
+impl LogTail {
+    /// Start following from the current end of the newest log file
+    pub fn new(log_dir: &Path) -> io::Result<Self> {
+        let file = log_files(log_dir)?.pop();
+        let offset = match &file {
+            Some(path) => std::fs::metadata(path)?.len(),
+            None => 0,
+        };
+        Ok(Self {
+            log_dir: log_dir.to_path_buf(),
+            file,
+            offset,
+            partial: String::new(),
+        })
+    }
+
+    /// Read entries appended since the last poll, moving on to newer files after rotation
+    pub fn poll(&mut self) -> io::Result<Vec<LogEntry>> {
+        let mut entries = Vec::new();
+        loop {
+            if let Some(path) = &self.file {
+                self.read_new(&path.clone(), &mut entries)?;
+            }
+
+            let newest = log_files(&self.log_dir)?.pop();
+            if newest.is_none() || newest == self.file {
+                break;
+            }
+            self.file = newest;
+            self.offset = 0;
+            self.partial.clear();
+        }
+        Ok(entries)
+    }
+
+    fn read_new(&mut self, path: &Path, entries: &mut Vec<LogEntry>) -> io::Result<()> {
+        let mut file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        if file.metadata()?.len() < self.offset {
+            // Truncated underneath us; start over
+            self.offset = 0;
+            self.partial.clear();
+        }
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut chunk = String::new();
+        self.offset += file.read_to_string(&mut chunk)? as u64;
+
+        self.partial.push_str(&chunk);
+        // Only consume complete lines; keep a trailing partial write for next time
+        let complete = match self.partial.rfind('\n') {
+            Some(idx) => self.partial.drain(..=idx).collect::<String>(),
+            None => return Ok(()),
+        };
+        entries.extend(complete.lines().filter_map(LogEntry::parse));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Write;
+
+    use super::*;
+
+    fn line(timestamp: &str, level: &str, message: &str) -> String {
+        format!(
+            r#"{{"timestamp":"{}","level":"{}","fields":{{"message":"{}","bucket":"b1"}},"target":"jax_daemon::test"}}"#,
+            timestamp, level, message
+        )
+    }
+
+    fn write_lines(path: &Path, lines: &[String]) {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap();
+        for l in lines {
+            writeln!(file, "{}", l).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_parse_entry() {
+        let entry = LogEntry::parse(&line("2026-10-15T10:00:00Z", "INFO", "hello")).unwrap();
+        assert_eq!(entry.level, "INFO");
+        assert_eq!(entry.message, "hello");
+        assert_eq!(entry.target, "jax_daemon::test");
+        assert_eq!(entry.fields.get("bucket").unwrap(), "b1");
+        assert!(LogEntry::parse("not json").is_none());
+    }
+
+    #[test]
+    fn test_parse_time() {
+        let now = OffsetDateTime::parse("2026-10-15T12:00:00Z", &Rfc3339).unwrap();
+        let expect = |s: &str| OffsetDateTime::parse(s, &Rfc3339).unwrap();
+        assert_eq!(parse_time("10m", now), Some(expect("2026-10-15T11:50:00Z")));
+        assert_eq!(parse_time("1d", now), Some(expect("2026-10-14T12:00:00Z")));
+        assert_eq!(
+            parse_time("2026-10-15T08:00:00+02:00", now),
+            Some(expect("2026-10-15T06:00:00Z"))
+        );
+        assert_eq!(parse_time("10", now), None);
+        assert_eq!(parse_time("5w", now), None);
+    }
+
+    #[test]
+    fn test_query_filters() {
+        let dir = tempfile::tempdir().unwrap();
+        write_lines(
+            &dir.path().join("jax.json.2026-10-14"),
+            &[line("2026-10-14T23:00:00Z", "ERROR", "old failure")],
+        );
+        write_lines(
+            &dir.path().join("jax.json.2026-10-15"),
+            &[
+                line("2026-10-15T10:00:00Z", "DEBUG", "noisy"),
+                line("2026-10-15T10:01:00Z", "WARN", "sync slow"),
+                line("2026-10-15T10:02:00Z", "ERROR", "sync failed"),
+            ],
+        );
+        // Non-JSON text logs in the same directory are ignored
+        write_lines(&dir.path().join("jax.log.2026-10-15"), &["plain".into()]);
+
+        let all = query(dir.path(), &LogFilter::default(), 100).unwrap();
+        assert_eq!(all.len(), 4);
+        assert_eq!(all[0].message, "old failure");
+
+        let warn = LogFilter {
+            level: Some(tracing::Level::WARN),
+            ..Default::default()
+        };
+        assert_eq!(query(dir.path(), &warn, 100).unwrap().len(), 3);
+
+        let today = LogFilter {
+            since: Some(OffsetDateTime::parse("2026-10-15T00:00:00Z", &Rfc3339).unwrap()),
+            contains: Some("SYNC".into()),
+            ..Default::default()
+        };
+        let found = query(dir.path(), &today, 100).unwrap();
+        assert_eq!(found.len(), 2);
+
+        // The limit keeps the newest entries
+        let last = query(dir.path(), &LogFilter::default(), 1).unwrap();
+        assert_eq!(last[0].message, "sync failed");
+    }
+
+    #[test]
+    fn test_tail_follows_appends_and_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let day1 = dir.path().join("jax.json.2026-10-15");
+        write_lines(&day1, &[line("2026-10-15T10:00:00Z", "INFO", "before")]);
+
+        let mut tail = LogTail::new(dir.path()).unwrap();
+        assert!(tail.poll().unwrap().is_empty());
+
+        write_lines(&day1, &[line("2026-10-15T10:00:01Z", "INFO", "after")]);
+        let new = tail.poll().unwrap();
+        assert_eq!(new.len(), 1);
+        assert_eq!(new[0].message, "after");
+
+        // Partial lines wait until they are complete
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&day1)
+            .unwrap();
+        write!(file, r#"{{"timestamp":"2026-10-15T10:00:02Z","#).unwrap();
+        assert!(tail.poll().unwrap().is_empty());
+        writeln!(
+            file,
+            r#""level":"INFO","fields":{{"message":"split"}},"target":"t"}}"#
+        )
+        .unwrap();
+        assert_eq!(tail.poll().unwrap()[0].message, "split");
+
+        let day2 = dir.path().join("jax.json.2026-10-16");
+        write_lines(&day2, &[line("2026-10-16T00:00:01Z", "INFO", "rotated")]);
+        let new = tail.poll().unwrap();
+        assert_eq!(new.len(), 1);
+        assert_eq!(new[0].message, "rotated");
+    }
+}
@@ -2,14 +2,34 @@
 mod cli;
 
 use clap::{Parser, Subcommand};
-use cli::{args::Args, op::Op, Bucket, Daemon, Init, Mount, Version};
+use cli::{
+    args::Args, op::Op, Activity, Audit, Backup, Bucket, Config, Daemon, Escrow, ExportAll,
+    Favorite, Gateway, Identity, Init, Mount, Peer, Profile, Storage, Sync, VerifyManifest,
+    Version, Webhook, Workspace,
+};
 
 command_enum! {
+    (Activity, Activity),
+    (Audit, Audit),
+    (Backup, Backup),
     (Bucket, Bucket),
+    (Config, Config),
     (Daemon, Daemon),
+    (Escrow, Escrow),
+    (ExportAll, ExportAll),
+    (Favorite, Favorite),
+    (Gateway, Gateway),
+    (Identity, Identity),
     (Init, Init),
     (Mount, Mount),
+    (Peer, Peer),
+    (Profile, Profile),
+    (Storage, Storage),
+    (Sync, Sync),
+    (VerifyManifest, VerifyManifest),
     (Version, Version),
+    (Webhook, Webhook),
+    (Workspace, Workspace),
 }
 
 #[tokio::main]
@@ -17,7 +37,7 @@ async fn main() {
     let args = Args::parse();
 
     // Build context - always has API client initialized
-    let ctx = match cli::op::OpContext::new(args.remote, args.config_path) {
+    let ctx = match cli::op::OpContext::new(args.remote, args.config_path, args.profile) {
         Ok(ctx) => ctx,
         Err(e) => {
             eprintln!("Error: Failed to create API client: {}", e);
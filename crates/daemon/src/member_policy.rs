@@ -0,0 +1,166 @@
+//! Policies for new bucket members
+//!
+//! A member policy decides what happens when a principal joins a bucket,
+//! whether it was shared directly or through a workspace:
+//! - `auto_pin`: when this node becomes a member, download the bucket's
+//!   content; otherwise the bucket starts out online-only (see
+//!   [`crate::sync_policy`]). A bucket shared with this node has no policy of
+//!   its own yet, so this comes from the default policy unless one was set for
+//!   the bucket ID ahead of time.
+//! - `notify_members`: members added by other owners show up in this node's
+//!   audit log as `bucket.member.added` when their version is synced. Every
+//!   member is pinged when a share is saved, so existing members learn of
+//!   the change right away.
+//! - `require_approval`: shares made on this node are held as pending until
+//!   an owner approves them.
+//!
+//! The default policy is a setting and per-bucket policies override it; both
+//! are stored in the database. They are only consulted when sharing and
+//! after a bucket sync, so they aren't mirrored in memory.
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use common::bucket_log::BucketLogProvider;
+use common::peer::Peer;
+use common::prelude::Mount;
+
+use crate::audit::{Actor, AuditEvent, AuditLog};
+use crate::database::Database;
+use crate::sync_policy::SyncPolicies;
+
+/// What happens when a principal joins a bucket
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemberPolicy {
+    /// Download the bucket's content when this node becomes a member
+    #[serde(default = "default_auto_pin")]
+    pub auto_pin: bool,
+    /// Record members added by other owners in the audit log
+    #[serde(default)]
+    pub notify_members: bool,
+    /// Hold shares made on this node until an owner approves them
+    #[serde(default)]
+    pub require_approval: bool,
+}
+
+fn default_auto_pin() -> bool {
+    true
+}
+
+impl Default for MemberPolicy {
+    fn default() -> Self {
+        Self {
+            auto_pin: true,
+            notify_members: false,
+            require_approval: false,
+        }
+    }
+}
+
+/// A share waiting for an owner's approval
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingShare {
+    pub bucket_id: Uuid,
+    pub peer_public_key: String,
+    /// `owner` or `mirror`
+    pub role: String,
+    /// The directory shared, if not the whole bucket
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub requested_at: OffsetDateTime,
+}
+
+/// Apply the member policy of a bucket after a sync appended versions above
+/// `from_height`, None if the bucket is new to this node
+pub(crate) async fn on_synced<L>(
+    peer: &Peer<L>,
+    database: &Database,
+    sync_policies: &SyncPolicies,
+    audit: &AuditLog,
+    bucket_id: Uuid,
+    from_height: Option<u64>,
+) where
+    L: BucketLogProvider + Clone + Send + Sync + 'static,
+    L::Error: std::error::Error + Send + Sync + 'static,
+{
+    let policy = match database.member_policy(&bucket_id).await {
+        Ok(policy) => policy,
+        Err(e) => {
+            tracing::warn!("Failed to load member policy of {}: {}", bucket_id, e);
+            return;
+        }
+    };
+
+    let Some(from_height) = from_height else {
+        // This node just became a member; its pins download runs after this
+        if !policy.auto_pin && !sync_policies.get(&bucket_id).online_only {
+            let mut sync_policy = sync_policies.get(&bucket_id);
+            sync_policy.online_only = true;
+            if let Err(e) = database.set_sync_policy(&bucket_id, &sync_policy).await {
+                tracing::warn!("Failed to make bucket {} online-only: {}", bucket_id, e);
+                return;
+            }
+            sync_policies.set(bucket_id, sync_policy);
+            tracing::info!(
+                "Joined bucket {} online-only per its member policy",
+                bucket_id
+            );
+        }
+        return;
+    };
+
+    if policy.notify_members {
+        record_added_members(peer, audit, bucket_id, from_height).await;
+    }
+}
+
+/// Record the principals the synced head has that the head at `from_height`
+/// didn't, attributed to the head's author
+async fn record_added_members<L>(
+    peer: &Peer<L>,
+    audit: &AuditLog,
+    bucket_id: Uuid,
+    from_height: u64,
+) where
+    L: BucketLogProvider + Clone + Send + Sync + 'static,
+    L::Error: std::error::Error + Send + Sync + 'static,
+{
+    let (Ok((previous, _)), Ok((head, _))) = (
+        peer.logs().head(bucket_id, Some(from_height)).await,
+        peer.logs().head(bucket_id, None).await,
+    ) else {
+        return;
+    };
+    if previous == head {
+        return;
+    }
+    let (Ok(previous), Ok(manifest)) = (
+        Mount::load_manifest(&previous, peer.blobs()).await,
+        Mount::load_manifest(&head, peer.blobs()).await,
+    ) else {
+        return;
+    };
+
+    let known: BTreeSet<&String> = previous.shares().keys().collect();
+    let own_key = peer.secret().public().to_hex();
+    let Some(author) = manifest.author().copied() else {
+        return;
+    };
+    for (key, share) in manifest.shares() {
+        if known.contains(key) || *key == own_key {
+            continue;
+        }
+        audit
+            .record(
+                AuditEvent::new(Actor::Peer(author), "bucket.member.added")
+                    .bucket(bucket_id)
+                    .detail(format!("{} as {:?}", key, share.role()))
+                    .version(&head),
+            )
+            .await;
+    }
+}
@@ -0,0 +1,300 @@
+//! Cursor pagination for list endpoints
+//!
+//! List endpoints take a [`PageRequest`] (`cursor`, `limit`, `order`) and
+//! return a page of their items along with the number of items matching
+//! their filters (`total`) and, when more are left, a `next_cursor` to pass
+//! back for the next page.
+//!
+//! Cursors are opaque: they hold the sort key of the last item returned,
+//! which is the value sorted by followed by a key unique to the item, so
+//! items added or removed between requests don't make pages skip or repeat
+//! the others. A cursor only continues the listing it came from; passing it
+//! with another sort field or order is an error.
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+/// Items per page when an endpoint pages by default and no limit is given
+pub const DEFAULT_PAGE_SIZE: u32 = 100;
+
+/// Most items returned in one page
+pub const MAX_PAGE_SIZE: u32 = 1000;
+
+/// Direction a listing is sorted in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+/// Which page of a listing to return
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::Args)]
+pub struct PageRequest {
+    /// Continue after the page that returned this `next_cursor`
+    #[arg(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+
+    /// Items per page (at most 1000)
+    #[arg(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+
+    /// Sort order (defaults depend on the sort field)
+    #[arg(long, value_enum)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<SortOrder>,
+}
+
+/// Where an item sorts: the value sorted by, then a key unique to the item
+/// breaking ties
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct SortKey {
+    value: SortValue,
+    id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(untagged)]
+enum SortValue {
+    Number(u64),
+    Text(String),
+}
+
+impl SortKey {
+    /// Sort by a text value
+    pub fn text(value: impl Into<String>, id: impl Into<String>) -> Self {
+        Self {
+            value: SortValue::Text(value.into()),
+            id: id.into(),
+        }
+    }
+
+    /// Sort by a number
+    pub fn number(value: u64, id: impl Into<String>) -> Self {
+        Self {
+            value: SortValue::Number(value),
+            id: id.into(),
+        }
+    }
+}
+
+/// Contents of a cursor
+#[derive(Debug, Serialize, Deserialize)]
+struct Cursor {
+    sort: String,
+    order: SortOrder,
+    after: SortKey,
+}
+
+/// One page of a listing
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Items matching the listing's filters, across all pages
+    pub total: u64,
+    /// Cursor for the next page, if there are items left
+    pub next_cursor: Option<String>,
+}
+
+impl PageRequest {
+    /// First page of at most `limit` items
+    pub fn first(limit: u32) -> Self {
+        Self {
+            limit: Some(limit),
+            ..Default::default()
+        }
+    }
+
+    /// Sort `items` by `key` and return the requested page of them.
+    /// `sort` names the sort field, so cursors of other listings are
+    /// refused; `order` and `limit` apply when the request leaves them out,
+    /// no limit returning every item after the cursor.
+    pub fn paginate<T>(
+        &self,
+        items: Vec<T>,
+        sort: &str,
+        order: SortOrder,
+        limit: Option<u32>,
+        key: impl Fn(&T) -> SortKey,
+    ) -> Result<Page<T>, PageError> {
+        let order = self.order.unwrap_or(order);
+        let limit = self
+            .limit
+            .or(limit)
+            .map(|limit| limit.clamp(1, MAX_PAGE_SIZE) as usize);
+        let after = self
+            .cursor
+            .as_deref()
+            .map(|cursor| decode(cursor, sort, order))
+            .transpose()?;
+
+        let total = items.len() as u64;
+        let mut keyed = items
+            .into_iter()
+            .map(|item| (key(&item), item))
+            .collect::<Vec<_>>();
+        keyed.sort_by(|(a, _), (b, _)| a.cmp(b));
+        if order == SortOrder::Desc {
+            keyed.reverse();
+        }
+        if let Some(after) = after {
+            keyed.retain(|(key, _)| match order {
+                SortOrder::Asc => *key > after,
+                SortOrder::Desc => *key < after,
+            });
+        }
+
+        let more = limit.is_some_and(|limit| keyed.len() > limit);
+        if let Some(limit) = limit {
+            keyed.truncate(limit);
+        }
+        let next_cursor = match keyed.last() {
+            Some((key, _)) if more => Some(encode(sort, order, key)),
+            _ => None,
+        };
+        Ok(Page {
+            items: keyed.into_iter().map(|(_, item)| item).collect(),
+            total,
+            next_cursor,
+        })
+    }
+}
+
+fn encode(sort: &str, order: SortOrder, after: &SortKey) -> String {
+    let cursor = Cursor {
+        sort: sort.to_string(),
+        order,
+        after: after.clone(),
+    };
+    let json = serde_json::to_vec(&cursor).expect("cursors serialize");
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(json)
+}
+
+fn decode(cursor: &str, sort: &str, order: SortOrder) -> Result<SortKey, PageError> {
+    let decoded: Cursor = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(cursor)
+        .ok()
+        .and_then(|json| serde_json::from_slice(&json).ok())
+        .ok_or(PageError::InvalidCursor)?;
+    if decoded.sort != sort || decoded.order != order {
+        return Err(PageError::CursorMismatch);
+    }
+    Ok(decoded.after)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PageError {
+    #[error("invalid cursor")]
+    InvalidCursor,
+    #[error("the cursor continues a listing with another sort field or order")]
+    CursorMismatch,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names<'a>(page: &Page<(&'a str, u64)>) -> Vec<&'a str> {
+        page.items.iter().map(|(name, _)| *name).collect()
+    }
+
+    fn all_pages(items: &[(&'static str, u64)], request: PageRequest) -> Vec<&'static str> {
+        let mut request = request;
+        let mut seen = Vec::new();
+        loop {
+            let page = request
+                .paginate(
+                    items.to_vec(),
+                    "size",
+                    SortOrder::Asc,
+                    None,
+                    |(name, size)| SortKey::number(*size, *name),
+                )
+                .unwrap();
+            assert_eq!(page.total, items.len() as u64);
+            seen.extend(names(&page));
+            match page.next_cursor {
+                Some(cursor) => request.cursor = Some(cursor),
+                None => return seen,
+            }
+        }
+    }
+
+    #[test]
+    fn test_pages_cover_every_item_once() {
+        let items = [("c", 2), ("a", 1), ("e", 2), ("b", 2), ("d", 0)];
+        assert_eq!(
+            all_pages(&items, PageRequest::first(2)),
+            vec!["d", "a", "b", "c", "e"]
+        );
+        let desc = PageRequest {
+            order: Some(SortOrder::Desc),
+            ..PageRequest::first(3)
+        };
+        assert_eq!(all_pages(&items, desc), vec!["e", "c", "b", "a", "d"]);
+        assert_eq!(all_pages(&items, PageRequest::default()).len(), 5);
+    }
+
+    #[test]
+    fn test_cursor_survives_changes() {
+        let request = PageRequest::first(2);
+        let key = |(name, _): &(&str, u64)| SortKey::text(*name, *name);
+        let first = request
+            .paginate(
+                vec![("a", 0), ("b", 0), ("c", 0)],
+                "name",
+                SortOrder::Asc,
+                None,
+                key,
+            )
+            .unwrap();
+        assert_eq!(names(&first), vec!["a", "b"]);
+
+        // Removing an item already returned doesn't repeat any
+        let next = PageRequest {
+            cursor: first.next_cursor,
+            ..request
+        };
+        let second = next
+            .paginate(
+                vec![("b", 0), ("c", 0), ("d", 0)],
+                "name",
+                SortOrder::Asc,
+                None,
+                key,
+            )
+            .unwrap();
+        assert_eq!(names(&second), vec!["c", "d"]);
+        assert_eq!(second.next_cursor, None);
+    }
+
+    #[test]
+    fn test_cursor_must_match_the_listing() {
+        let key = |(name, _): &(&str, u64)| SortKey::text(*name, *name);
+        let first = PageRequest::first(1)
+            .paginate(vec![("a", 0), ("b", 0)], "name", SortOrder::Asc, None, key)
+            .unwrap();
+        let next = PageRequest {
+            cursor: first.next_cursor,
+            ..Default::default()
+        };
+        assert!(matches!(
+            next.paginate(vec![("a", 0)], "size", SortOrder::Asc, None, key),
+            Err(PageError::CursorMismatch)
+        ));
+        assert!(matches!(
+            next.paginate(vec![("a", 0)], "name", SortOrder::Desc, None, key),
+            Err(PageError::CursorMismatch)
+        ));
+        let garbage = PageRequest {
+            cursor: Some("not a cursor".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            garbage.paginate(vec![("a", 0)], "name", SortOrder::Asc, None, key),
+            Err(PageError::InvalidCursor)
+        ));
+    }
+}
@@ -0,0 +1,244 @@
+//! Peer address book
+//!
+//! Peers are known by their node id, but reaching one without discovery needs
+//! its addresses too. The address book keeps the peers added with a ticket
+//! (`<node-id>[@<addr>,...]`, where each address is a socket address or the
+//! peer's relay URL) along with where they were last reached. On startup the
+//! daemon hands every known address to the endpoint and dials each peer, and
+//! a background task records the address of peers it is connected to.
+
+use std::fmt;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::time::Duration;
+
+use iroh::endpoint::ConnectionType;
+use iroh::{NodeAddr, RelayUrl, Watcher};
+use time::OffsetDateTime;
+
+use common::crypto::PublicKey;
+use common::peer::{Peer, ALPN};
+
+use crate::database::Database;
+
+/// How long to wait for a known peer to answer a dial
+const DIAL_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How often the addresses of connected peers are recorded
+const REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+
+/// A peer and the addresses it can be reached at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerTicket {
+    pub node_id: PublicKey,
+    pub relay_url: Option<RelayUrl>,
+    pub direct_addresses: Vec<SocketAddr>,
+}
+
+impl PeerTicket {
+    /// The ticket of a node, as reported by its endpoint
+    pub fn from_node_addr(node_addr: NodeAddr) -> Self {
+        Self {
+            node_id: node_addr.node_id.into(),
+            relay_url: node_addr.relay_url,
+            direct_addresses: node_addr.direct_addresses.into_iter().collect(),
+        }
+    }
+
+    pub fn node_addr(&self) -> NodeAddr {
+        NodeAddr::from_parts(
+            *self.node_id,
+            self.relay_url.clone(),
+            self.direct_addresses.iter().copied(),
+        )
+    }
+}
+
+impl fmt::Display for PeerTicket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.node_id.to_hex())?;
+        let addresses: Vec<String> = self
+            .direct_addresses
+            .iter()
+            .map(|addr| addr.to_string())
+            .chain(self.relay_url.iter().map(|url| url.to_string()))
+            .collect();
+        if !addresses.is_empty() {
+            write!(f, "@{}", addresses.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for PeerTicket {
+    type Err = PeerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || PeerError::InvalidTicket(s.to_string());
+        let (node_id, addresses) = match s.trim().split_once('@') {
+            Some((node_id, addresses)) => (node_id, Some(addresses)),
+            None => (s.trim(), None),
+        };
+        let mut ticket = Self {
+            node_id: PublicKey::from_hex(node_id).map_err(|_| invalid())?,
+            relay_url: None,
+            direct_addresses: Vec::new(),
+        };
+        for address in addresses.into_iter().flat_map(|a| a.split(',')) {
+            if let Ok(addr) = address.parse::<SocketAddr>() {
+                ticket.direct_addresses.push(addr);
+            } else {
+                ticket.relay_url = Some(address.parse().map_err(|_| invalid())?);
+            }
+        }
+        Ok(ticket)
+    }
+}
+
+/// An entry of the address book
+#[derive(Debug, Clone)]
+pub struct KnownPeer {
+    pub ticket: PeerTicket,
+    /// When the peer was last connected, None if never
+    pub last_seen: Option<OffsetDateTime>,
+    pub added_at: OffsetDateTime,
+}
+
+/// Where we are connected to a peer, if we are
+fn connected_ticket(peer: &Peer<Database>, node_id: PublicKey) -> Option<PeerTicket> {
+    let conn_type = peer.endpoint().conn_type(*node_id)?.get();
+    let (direct, relay_url) = match conn_type {
+        ConnectionType::Direct(addr) => (Some(addr), None),
+        ConnectionType::Relay(url) => (None, Some(url)),
+        ConnectionType::Mixed(addr, url) => (Some(addr), Some(url)),
+        ConnectionType::None => return None,
+    };
+    Some(PeerTicket {
+        node_id,
+        relay_url,
+        direct_addresses: direct.into_iter().collect(),
+    })
+}
+
+/// Hand a peer's addresses to the endpoint and dial it, recording where it
+/// was reached
+pub(crate) async fn dial(peer: &Peer<Database>, database: &Database, ticket: &PeerTicket) {
+    let node_id = ticket.node_id;
+    if let Err(e) = peer
+        .endpoint()
+        .add_node_addr_with_source(ticket.node_addr(), "address-book")
+    {
+        tracing::debug!(
+            "Could not add addresses of peer {}: {}",
+            node_id.to_hex(),
+            e
+        );
+    }
+
+    let connection = tokio::time::timeout(
+        DIAL_TIMEOUT,
+        peer.endpoint().connect(ticket.node_addr(), ALPN),
+    )
+    .await;
+    match connection {
+        Ok(Ok(connection)) => {
+            tracing::info!("Reconnected to known peer {}", node_id.to_hex());
+            let seen = connected_ticket(peer, node_id).unwrap_or_else(|| ticket.clone());
+            if let Err(e) = database.record_peer_seen(&seen).await {
+                tracing::warn!("Failed to record peer {}: {}", node_id.to_hex(), e);
+            }
+            connection.close(0u32.into(), b"dialed");
+        }
+        Ok(Err(e)) => {
+            tracing::info!("Known peer {} is unreachable: {}", node_id.to_hex(), e);
+        }
+        Err(_) => {
+            tracing::info!("Timed out dialing known peer {}", node_id.to_hex());
+        }
+    }
+}
+
+/// Dial every peer in the address book, e.g. on startup
+pub(crate) async fn reconnect_known_peers(peer: Peer<Database>, database: Database) {
+    let known = match database.list_known_peers().await {
+        Ok(known) => known,
+        Err(e) => {
+            tracing::error!("Failed to load known peers: {}", e);
+            return;
+        }
+    };
+    tracing::info!("Reconnecting to {} known peer(s)", known.len());
+    for known_peer in known {
+        let peer = peer.clone();
+        let database = database.clone();
+        tokio::spawn(async move { dial(&peer, &database, &known_peer.ticket).await });
+    }
+}
+
+/// Periodically record where connected known peers are reached, so the
+/// address book stays current across restarts
+pub(crate) async fn run_refresh(peer: Peer<Database>, database: Database) {
+    let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        let known = match database.list_known_peers().await {
+            Ok(known) => known,
+            Err(e) => {
+                tracing::warn!("Failed to load known peers: {}", e);
+                continue;
+            }
+        };
+        for known_peer in known {
+            let Some(seen) = connected_ticket(&peer, known_peer.ticket.node_id) else {
+                continue;
+            };
+            if let Err(e) = database.record_peer_seen(&seen).await {
+                tracing::warn!("Failed to record peer {}: {}", seen.node_id.to_hex(), e);
+            }
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PeerError {
+    #[error("invalid peer ticket {0}, expected <node-id>[@<addr>,...]")]
+    InvalidTicket(String),
+    #[error("cannot add this node to its own address book")]
+    OwnNode,
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use common::crypto::SecretKey;
+
+    #[test]
+    fn test_ticket_roundtrip() {
+        let node_id = SecretKey::generate().public();
+        let bare = PeerTicket {
+            node_id,
+            relay_url: None,
+            direct_addresses: Vec::new(),
+        };
+        assert_eq!(bare.to_string(), node_id.to_hex());
+        assert_eq!(bare.to_string().parse::<PeerTicket>().unwrap(), bare);
+
+        let full = PeerTicket {
+            node_id,
+            relay_url: Some("https://relay.example.com".parse().unwrap()),
+            direct_addresses: vec![
+                "192.168.1.10:4433".parse().unwrap(),
+                "[::1]:4433".parse().unwrap(),
+            ],
+        };
+        assert_eq!(full.to_string().parse::<PeerTicket>().unwrap(), full);
+
+        assert!("not-a-node".parse::<PeerTicket>().is_err());
+        assert!(format!("{}@not an address", node_id.to_hex())
+            .parse::<PeerTicket>()
+            .is_err());
+    }
+}
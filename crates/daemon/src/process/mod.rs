@@ -2,6 +2,7 @@ pub mod utils;
 
 use std::net::SocketAddr;
 use std::str::FromStr;
+use std::sync::Arc;
 use std::time::Duration;
 
 use futures::future::join_all;
@@ -9,11 +10,16 @@ use tokio::sync::watch;
 use tokio::time::timeout;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
-use tracing_subscriber::{EnvFilter, Layer};
 
 const FINAL_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
 
+use crate::gateway_tls::GatewayTls;
 use crate::http_server;
+use crate::logs;
+use crate::profiles;
+use crate::reload;
+use crate::reverse_proxy::ReverseProxy;
+use crate::telemetry::{self, TracerGuard};
 use crate::{ServiceConfig, ServiceState};
 
 /// Handle for gracefully shutting down the daemon service.
@@ -53,23 +59,36 @@ impl ShutdownHandle {
 /// Returns guards that must be kept alive for the duration of the program.
 fn init_logging(
     service_config: &ServiceConfig,
-) -> Vec<tracing_appender::non_blocking::WorkerGuard> {
+) -> (
+    Vec<tracing_appender::non_blocking::WorkerGuard>,
+    Option<TracerGuard>,
+) {
     use tracing_subscriber::fmt::format::FmtSpan;
 
     let mut guards = Vec::new();
 
+    // Trace export (if an OTLP endpoint is set)
+    let (otlp_layer, tracer_guard) = match &service_config.otlp_endpoint {
+        Some(endpoint) => match telemetry::otlp_layer(endpoint) {
+            Ok((layer, guard)) => (Some(layer), Some(guard)),
+            Err(e) => {
+                eprintln!("Warning: Failed to set up trace export: {}", e);
+                (None, None)
+            }
+        },
+        None => (None, None),
+    };
+
+    // Shared filter so the log level can be changed on config reload
+    let filter = reload::reloadable_log_filter(service_config.log_level);
+
     // Stdout layer
     let (stdout_writer, stdout_guard) = tracing_appender::non_blocking(std::io::stdout());
     guards.push(stdout_guard);
 
-    let stdout_env_filter = EnvFilter::builder()
-        .with_default_directive(service_config.log_level.into())
-        .from_env_lossy();
-
     let stdout_layer = tracing_subscriber::fmt::layer()
         .compact()
-        .with_writer(stdout_writer)
-        .with_filter(stdout_env_filter);
+        .with_writer(stdout_writer);
 
     // File layer (if log_dir is set)
     if let Some(log_dir) = &service_config.log_dir {
@@ -85,28 +104,39 @@ fn init_logging(
         let (file_writer, file_guard) = tracing_appender::non_blocking(file_appender);
         guards.push(file_guard);
 
-        let file_env_filter = EnvFilter::builder()
-            .with_default_directive(service_config.log_level.into())
-            .from_env_lossy();
-
         let file_layer = tracing_subscriber::fmt::layer()
             .with_writer(file_writer)
             .with_ansi(false)
-            .with_span_events(FmtSpan::CLOSE)
-            .with_filter(file_env_filter);
+            .with_span_events(FmtSpan::CLOSE);
+
+        // Structured copy of the same events, read by the log query API
+        let (json_layer, json_guard) = logs::json_file_layer(log_dir);
+        guards.push(json_guard);
 
         tracing_subscriber::registry()
+            .with(filter)
+            .with(otlp_layer)
             .with(stdout_layer)
             .with(file_layer)
+            .with(json_layer)
             .init();
     } else {
-        tracing_subscriber::registry().with(stdout_layer).init();
+        tracing_subscriber::registry()
+            .with(filter)
+            .with(otlp_layer)
+            .with(stdout_layer)
+            .init();
     }
 
     utils::register_panic_logger();
     utils::report_build_info();
+    if let Some(endpoint) = &service_config.otlp_endpoint {
+        if tracer_guard.is_some() {
+            tracing::info!("Exporting traces to {}", endpoint);
+        }
+    }
 
-    guards
+    (guards, tracer_guard)
 }
 
 /// Create service state from config, exiting on error.
@@ -146,6 +176,7 @@ async fn shutdown_and_join(
 pub async fn start_service(service_config: &ServiceConfig) -> (ServiceState, ShutdownHandle) {
     let (graceful_waiter, shutdown_tx, shutdown_rx) = utils::graceful_shutdown_blocker();
     let state = create_state(service_config).await;
+    state.set_shutdown_sender(shutdown_tx.clone());
 
     let mut handles = Vec::new();
 
@@ -159,15 +190,41 @@ pub async fn start_service(service_config: &ServiceConfig) -> (ServiceState, Shu
     });
     handles.push(peer_handle);
 
+    // Sync buckets listed in an imported identity bundle
+    tokio::spawn(crate::identity::sync_imported_buckets(
+        state.clone(),
+        service_config.jax_dir.clone(),
+    ));
+
+    // Start the peers of other profiles, served on the same API port
+    let profiles = profiles::start(service_config, &shutdown_tx, &mut handles).await;
+
+    // Behind a reverse proxy, trust its forwarded headers and path prefix
+    let reverse_proxy = match &service_config.reverse_proxy {
+        Some(proxy_config) => match ReverseProxy::new(proxy_config) {
+            Ok(reverse_proxy) => reverse_proxy,
+            Err(e) => {
+                tracing::error!("Ignoring the reverse proxy configuration: {}", e);
+                ReverseProxy::default()
+            }
+        },
+        None => ReverseProxy::default(),
+    };
+    let reverse_proxy = Arc::new(reverse_proxy);
+
     // Spawn API server
     let api_port = service_config.api_port;
     let api_addr = SocketAddr::from_str(&format!("0.0.0.0:{}", api_port))
         .expect("Failed to parse API listen address");
     let api_state = state.clone();
-    let api_config = http_server::Config::new(api_addr, service_config.gateway_url.clone());
+    let api_config = http_server::Config::new(
+        api_addr,
+        service_config.gateway_url.clone(),
+        reverse_proxy.clone(),
+    );
     let api_rx = shutdown_rx.clone();
     let api_handle = tokio::spawn(async move {
-        if let Err(e) = http_server::run_api(api_config, api_state, api_rx).await {
+        if let Err(e) = http_server::run_api(api_config, api_state, profiles, api_rx).await {
             tracing::error!("API server error: {}", e);
         }
     });
@@ -178,15 +235,38 @@ pub async fn start_service(service_config: &ServiceConfig) -> (ServiceState, Shu
     let gw_addr = SocketAddr::from_str(&format!("0.0.0.0:{}", gw_port))
         .expect("Failed to parse gateway listen address");
     let gw_state = state.clone();
-    let gw_config = http_server::Config::new(gw_addr, service_config.gateway_url.clone());
+    let gw_config =
+        http_server::Config::new(gw_addr, service_config.gateway_url.clone(), reverse_proxy);
+    // Over HTTPS too when domains are configured, keeping their certificate
+    let gw_tls = match &service_config.gateway_tls {
+        Some(tls_config) => match tls_config.validate() {
+            Ok(()) => Some(Arc::new(GatewayTls::new(
+                tls_config.clone(),
+                &service_config.jax_dir,
+            ))),
+            Err(e) => {
+                tracing::error!("Not serving the gateway over HTTPS: {}", e);
+                None
+            }
+        },
+        None => None,
+    };
+    if let Some(tls) = &gw_tls {
+        handles.push(tokio::spawn(tls.clone().run(shutdown_rx.clone())));
+    }
     let gw_rx = shutdown_rx.clone();
     let gw_handle = tokio::spawn(async move {
-        if let Err(e) = http_server::run_gateway(gw_config, gw_state, gw_rx).await {
+        if let Err(e) = http_server::run_gateway(gw_config, gw_state, gw_tls, gw_rx).await {
             tracing::error!("Gateway server error: {}", e);
         }
     });
     handles.push(gw_handle);
 
+    // Reload configuration on SIGHUP
+    let reloader = state.reloader().clone();
+    let reload_rx = shutdown_rx.clone();
+    tokio::spawn(reload::reload_on_sighup(reloader, reload_rx));
+
     tracing::info!(
         "Running: Peer + API on port {} + Gateway on port {}",
         api_port,
@@ -221,6 +301,39 @@ pub async fn start_service(service_config: &ServiceConfig) -> (ServiceState, Shu
     (state.clone(), handle)
 }
 
+/// Create state and spawn only the peer: no API or gateway server, FUSE
+/// mounts or config reloading. Used by the lite profile for mobile shells.
+pub async fn start_lite_service(
+    service_config: &ServiceConfig,
+) -> Result<(ServiceState, ShutdownHandle), crate::service_state::StateSetupError> {
+    let (graceful_waiter, shutdown_tx, shutdown_rx) = utils::graceful_shutdown_blocker();
+    let state = ServiceState::from_config(service_config).await?;
+    state.set_shutdown_sender(shutdown_tx.clone());
+
+    let peer = state.peer().clone();
+    let peer_handle = tokio::spawn(async move {
+        if let Err(e) = common::peer::spawn(peer, shutdown_rx).await {
+            tracing::error!("Peer error: {}", e);
+        }
+    });
+
+    tokio::spawn(crate::identity::sync_imported_buckets(
+        state.clone(),
+        service_config.jax_dir.clone(),
+    ));
+
+    tracing::info!("Running: Peer (lite)");
+
+    let handle = ShutdownHandle {
+        graceful_waiter,
+        handles: vec![peer_handle],
+        shutdown_tx,
+        #[cfg(feature = "fuse")]
+        state: state.clone(),
+    };
+    Ok((state, handle))
+}
+
 /// Spawns the daemon service: P2P peer + API server + gateway server.
 /// Blocks until shutdown signal is received. Use for CLI binary usage.
 pub async fn spawn_service(service_config: &ServiceConfig) {
@@ -9,6 +9,7 @@ use tokio::task::JoinHandle;
 const REQUEST_GRACE_PERIOD: Duration = Duration::from_secs(10);
 
 /// Spawns a task that listens for SIGINT and SIGTERM and sends a shutdown signal via a watch.
+/// The task also finishes when shutdown is requested through the returned sender.
 ///
 /// Returns the join handle, the sender (for programmatic shutdown), and the receiver.
 pub fn graceful_shutdown_blocker() -> (JoinHandle<()>, watch::Sender<()>, watch::Receiver<()>) {
@@ -17,9 +18,13 @@ pub fn graceful_shutdown_blocker() -> (JoinHandle<()>, watch::Sender<()>, watch:
 
     let (tx, rx) = tokio::sync::watch::channel(());
     let signal_tx = tx.clone();
+    let mut requested_rx = rx.clone();
 
     let handle = tokio::spawn(async move {
         tokio::select! {
+            _ = requested_rx.changed() => {
+                tracing::debug!("gracefully exiting on shutdown request");
+            }
             _ = sigint.recv() => {
                 tracing::debug!("gracefully exiting immediately on SIGINT");
             }
@@ -0,0 +1,286 @@
+//! Named profiles in one daemon
+//!
+//! A profile is a separate identity: its own key, database and blob store,
+//! and so its own set of buckets. Each lives in `profiles/<name>/` of the jax
+//! directory, laid out like the jax directory itself. The daemon runs a peer
+//! for every profile next to the default one and serves them all on its API
+//! port. A request picks its profile with the `X-Jax-Profile` header (what
+//! `jax --profile` sends) or by presenting the profile's API token as a
+//! bearer token; requests with neither go to the default profile. The gateway
+//! only serves the default profile.
+//!
+//! Profiles are created with `jax profile create` and picked up the next
+//! time the daemon starts.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use http::header::AUTHORIZATION;
+use http::HeaderMap;
+use tokio::sync::watch;
+
+use common::crypto::Secret;
+
+use crate::state::{AppState, StateError};
+use crate::{ServiceConfig, ServiceState};
+
+/// Directory of the jax directory profiles are kept in
+pub const PROFILES_DIR_NAME: &str = "profiles";
+/// File of a profile directory holding its API token
+pub const TOKEN_FILE_NAME: &str = "api_token";
+/// Header a request names its profile with
+pub const PROFILE_HEADER: &str = "x-jax-profile";
+
+/// Directory of a profile
+pub fn profile_dir(jax_dir: &Path, name: &str) -> PathBuf {
+    jax_dir.join(PROFILES_DIR_NAME).join(name)
+}
+
+/// Create a profile with a fresh key, returning its state and API token
+pub fn create(jax_dir: &Path, name: &str) -> Result<(AppState, String), ProfileError> {
+    validate_name(name)?;
+    let dir = profile_dir(jax_dir, name);
+    if dir.exists() {
+        return Err(ProfileError::AlreadyExists(name.to_string()));
+    }
+    let state = AppState::init(Some(dir.clone()), None)?;
+    let token = hex::encode(Secret::generate().bytes());
+    write_private(&dir.join(TOKEN_FILE_NAME), token.as_bytes())?;
+    Ok((state, token))
+}
+
+/// Write a file readable only by the current user
+fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(contents)
+}
+
+/// Names of the profiles of a jax directory, sorted
+pub fn list(jax_dir: &Path) -> Result<Vec<String>, ProfileError> {
+    let dir = jax_dir.join(PROFILES_DIR_NAME);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut names = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        if let Some(name) = entry.file_name().to_str() {
+            if validate_name(name).is_ok() {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// API token of a profile
+pub fn read_token(jax_dir: &Path, name: &str) -> Result<String, ProfileError> {
+    let path = profile_dir(jax_dir, name).join(TOKEN_FILE_NAME);
+    if !path.exists() {
+        return Err(ProfileError::Unknown(name.to_string()));
+    }
+    Ok(fs::read_to_string(path)?.trim().to_string())
+}
+
+/// Profile names are used as directory names and header values
+fn validate_name(name: &str) -> Result<(), ProfileError> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(ProfileError::InvalidName(name.to_string()))
+    }
+}
+
+/// The profiles a daemon runs, by name and by API token
+#[derive(Clone, Default)]
+pub struct Profiles {
+    states: Arc<BTreeMap<String, ServiceState>>,
+    tokens: Arc<HashMap<String, String>>,
+}
+
+impl Profiles {
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ServiceState> {
+        self.states.get(name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &ServiceState)> {
+        self.states.iter()
+    }
+
+    /// Name of the profile a request selects, None for the default profile
+    pub fn select(&self, headers: &HeaderMap) -> Result<Option<String>, ProfileError> {
+        if let Some(name) = headers.get(PROFILE_HEADER) {
+            let name = name.to_str().unwrap_or_default();
+            if !self.states.contains_key(name) {
+                return Err(ProfileError::Unknown(name.to_string()));
+            }
+            return Ok(Some(name.to_string()));
+        }
+        let token = headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        match token {
+            Some(token) => match self.tokens.get(token.trim()) {
+                Some(name) => Ok(Some(name.clone())),
+                None => Err(ProfileError::UnknownToken),
+            },
+            None => Ok(None),
+        }
+    }
+}
+
+/// Start a peer for every profile of the daemon's jax directory
+///
+/// A profile that fails to start is logged and left out rather than
+/// keeping the daemon from starting.
+pub(crate) async fn start(
+    base: &ServiceConfig,
+    shutdown_tx: &watch::Sender<()>,
+    handles: &mut Vec<tokio::task::JoinHandle<()>>,
+) -> Profiles {
+    let names = match list(&base.jax_dir) {
+        Ok(names) => names,
+        Err(e) => {
+            tracing::error!("Failed to list profiles: {}", e);
+            return Profiles::default();
+        }
+    };
+
+    let mut states = BTreeMap::new();
+    let mut tokens = HashMap::new();
+    for name in names {
+        let (config, token) = match profile_config(base, &name) {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                tracing::error!("Failed to load profile {}: {}", name, e);
+                continue;
+            }
+        };
+        let state = match ServiceState::from_config(&config).await {
+            Ok(state) => state,
+            Err(e) => {
+                tracing::error!("Failed to start profile {}: {}", name, e);
+                continue;
+            }
+        };
+        state.set_shutdown_sender(shutdown_tx.clone());
+
+        let peer = state.peer().clone();
+        let peer_rx = shutdown_tx.subscribe();
+        handles.push(tokio::spawn(async move {
+            if let Err(e) = common::peer::spawn(peer, peer_rx).await {
+                tracing::error!("Peer error: {}", e);
+            }
+        }));
+        tokio::spawn(crate::identity::sync_imported_buckets(
+            state.clone(),
+            config.jax_dir.clone(),
+        ));
+
+        tracing::info!("Running profile {} as {}", name, state.peer().id());
+        tokens.insert(token, name.clone());
+        states.insert(name, state);
+    }
+
+    Profiles {
+        states: Arc::new(states),
+        tokens: Arc::new(tokens),
+    }
+}
+
+/// Service config of a profile: its own identity and stores, the daemon's
+/// ports and logging
+fn profile_config(
+    base: &ServiceConfig,
+    name: &str,
+) -> Result<(ServiceConfig, String), ProfileError> {
+    let state = AppState::load(Some(profile_dir(&base.jax_dir, name)))?;
+    let secret_key = state.load_key()?;
+    let token = read_token(&base.jax_dir, name)?;
+    let config = ServiceConfig {
+        node_listen_addr: None,
+        node_secret: Some(secret_key.clone()),
+        blob_store: state.config.blob_store.clone(),
+        blob_locations: state.config.blob_locations.clone(),
+        cdn_targets: state.config.cdn_targets.clone(),
+        jax_dir: state.jax_dir.clone(),
+        api_port: base.api_port,
+        gateway_port: base.gateway_port,
+        sqlite_path: Some(state.db_path.clone()),
+        database_key: state.config.database_key(&secret_key),
+        config_path: Some(state.config_path.clone()),
+        sync_interval: state.config.sync_interval(),
+        periodic_sync: base.periodic_sync,
+        online_cache_max_bytes: state.config.online_cache_max_bytes,
+        sync_deferral: state.config.sync_deferral,
+        protocol_timeouts: state.config.protocol_timeouts.timeouts(),
+        connection_limits: state.config.protocol_limits.limits(),
+        log_level: base.log_level,
+        log_dir: base.log_dir.clone(),
+        otlp_endpoint: None,
+        gateway_url: base.gateway_url.clone(),
+        gateway_tls: None,
+        reverse_proxy: None,
+        publish_scanners: Vec::new(),
+        publish_builders: Vec::new(),
+        upload_limits: state.config.upload_limits.clone(),
+        import_dirs: state.config.import_dirs.clone(),
+        read_replica: state.config.read_replica,
+        cluster: state.config.cluster.clone(),
+    };
+    Ok((config, token))
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ProfileError {
+    #[error("Invalid profile name: {0} (use letters, digits, '-' and '_')")]
+    InvalidName(String),
+    #[error("Profile already exists: {0}")]
+    AlreadyExists(String),
+    #[error("Unknown profile: {0}")]
+    Unknown(String),
+    #[error("API token does not belong to a profile")]
+    UnknownToken,
+    #[error("State error: {0}")]
+    State(#[from] StateError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_profile_names() {
+        assert!(validate_name("work").is_ok());
+        assert!(validate_name("side-project_2").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("../default").is_err());
+        assert!(validate_name("a b").is_err());
+    }
+}
@@ -0,0 +1,74 @@
+//! Publishing buckets, unpublishing and gateway link rotation
+//!
+//! Publishing a version exposes its secret so mirrors, followers and the
+//! gateway can read it. The gateway serves a bucket's latest published version
+//! at `/gw/<bucket-id>` until its link is rotated, and from then on only at
+//! `/gw/<token>`, leaving the bucket off the gateway index. Unpublishing saves
+//! a new unpublished version and marks every earlier version as unpublished
+//! locally, so the gateway stops serving the bucket. Peers keep the versions
+//! they already synced.
+//!
+//! Versions are scanned by the configured content scanners before they are
+//! published (see [`crate::publish_scan`]). Buckets with a publish pipeline
+//! are built first, and the output is published instead (see
+//! [`crate::publish_pipeline`]).
+
+use common::crypto::Secret;
+use common::linked_data::Link;
+use common::mount::MountError;
+
+use crate::publish_pipeline::BuildError;
+use crate::publish_scan::{describe_vetoes, ScanResult};
+
+/// Length of a gateway link token in bytes
+const TOKEN_SIZE: usize = 20;
+
+/// A newly published version of a bucket
+#[derive(Debug, Clone)]
+pub struct Publication {
+    pub link: Link,
+    /// Path the gateway serves the bucket at, e.g. `/gw/<bucket-id>`
+    pub gateway_path: String,
+    /// Height of the version in the bucket log, which the gateway also
+    /// serves it at (`<gateway_path>/@v<height>`)
+    pub height: Option<u64>,
+    /// Bucket the version belongs to, if the bucket published was built
+    /// into it by its publish pipeline
+    pub site_bucket_id: Option<uuid::Uuid>,
+}
+
+/// Generate a random gateway link token. Tokens are longer than a UUID so
+/// they never parse as a bucket id.
+pub(crate) fn new_gateway_token() -> String {
+    Secret::generate().bytes()[..TOKEN_SIZE]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PublishError {
+    #[error("mount error: {0}")]
+    Mount(#[from] MountError),
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("vetoed by content scanning: {}", describe_vetoes(.0))]
+    Vetoed(Vec<ScanResult>),
+    #[error("publish pipeline: {0}")]
+    Build(#[from] BuildError),
+    #[error("bucket log error: {0}")]
+    BucketLog(String),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_gateway_token_is_not_a_bucket_id() {
+        let token = new_gateway_token();
+        assert_eq!(token.len(), TOKEN_SIZE * 2);
+        assert!(uuid::Uuid::parse_str(&token).is_err());
+        assert_ne!(token, new_gateway_token());
+    }
+}
@@ -0,0 +1,418 @@
+//! Build pipelines run when buckets are published
+//!
+//! Operators register builders in the config file (`[[publish_builders]]`),
+//! commands such as a static site generator, and buckets opt into one with a
+//! publish pipeline (`jax bucket pipeline set`). Publishing such a bucket
+//! doesn't publish its contents: the version is exported to a temporary
+//! checkout, the builder runs there, and the tree it writes to its output
+//! directory is published instead, as the bucket's site bucket.
+//!
+//! The builder runs in the checkout with a cleared environment: `PATH`,
+//! `HOME` set to the temporary directory, the pipeline's template variables,
+//! and `JAX_BUCKET_ID`, `JAX_BUCKET_NAME`, `JAX_VERSION`, `JAX_HEIGHT` and
+//! `JAX_OUTPUT`, the directory to write the output to unless the pipeline
+//! names one in the checkout (e.g. `public`). A builder that exits with an
+//! error or times out fails the publish, with the end of its output as the
+//! reason.
+//!
+//! The site bucket is created, owned by this node alone, the first time the
+//! bucket is built, and every build replaces its contents. The gateway
+//! serves it at the source bucket's path too, and content scanners, link
+//! rotation and index settings apply to it rather than the source, which
+//! stays unpublished. Every build is recorded with the versions it was
+//! built from and published as (`jax bucket pipeline get`).
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use common::linked_data::Link;
+use common::mount::{Mount, MountError};
+
+use crate::publish_scan::export;
+
+/// Time a builder gets unless configured otherwise
+const DEFAULT_TIMEOUT_SECS: u64 = 600;
+
+/// Most bytes of a builder's output kept with its build
+const MAX_LOG_LEN: usize = 4096;
+
+/// Prefix of the variables set by the pipeline itself
+const RESERVED_VAR_PREFIX: &str = "JAX_";
+
+/// A builder as configured in the config file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BuilderConfig {
+    /// Name pipelines refer to the builder by
+    pub name: String,
+    /// Program and arguments to run in the checkout
+    pub command: Vec<String>,
+    /// Seconds the builder gets before the build counts as failed
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_timeout_secs() -> u64 {
+    DEFAULT_TIMEOUT_SECS
+}
+
+/// How a bucket is built when it's published
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PublishPipeline {
+    /// Name of the configured builder to run
+    pub builder: String,
+    /// Directory of the checkout the builder writes its output to, relative
+    /// to it; `JAX_OUTPUT` if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// Template variables set in the builder's environment
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub vars: BTreeMap<String, String>,
+}
+
+impl PublishPipeline {
+    pub fn validate(&self) -> Result<(), PipelineError> {
+        if let Some(output) = &self.output {
+            let relative = Path::new(output);
+            if relative.as_os_str().is_empty()
+                || !relative
+                    .components()
+                    .all(|component| matches!(component, Component::Normal(_)))
+            {
+                return Err(PipelineError::InvalidOutput(output.clone()));
+            }
+        }
+        for name in self.vars.keys() {
+            let valid = name
+                .chars()
+                .next()
+                .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                && !name.starts_with(RESERVED_VAR_PREFIX);
+            if !valid {
+                return Err(PipelineError::InvalidVar(name.clone()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A recorded build of a bucket
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublishBuild {
+    /// Hash of the version built
+    pub source_version: String,
+    /// Hash of the site bucket's version published, if the build succeeded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub site_version: Option<String>,
+    pub succeeded: bool,
+    /// End of the builder's output
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+/// The version a build is of
+#[derive(Debug, Clone)]
+pub struct BuildSource {
+    pub bucket_id: Uuid,
+    pub name: String,
+    pub version: Link,
+    pub height: u64,
+}
+
+/// A finished build, whose output lives as long as it does
+#[derive(Debug)]
+pub struct Build {
+    /// Removed with the build
+    _dir: tempfile::TempDir,
+    output: PathBuf,
+    /// End of the builder's output
+    pub log: String,
+}
+
+impl Build {
+    /// Directory holding the output tree
+    pub fn output(&self) -> &Path {
+        &self.output
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Builder {
+    name: String,
+    command: Vec<String>,
+    timeout: Duration,
+}
+
+/// The builders configured on this node
+#[derive(Debug, Clone, Default)]
+pub struct PublishBuilders {
+    builders: Arc<Vec<Builder>>,
+}
+
+impl PublishBuilders {
+    pub fn new(configs: &[BuilderConfig]) -> Result<Self, PublishBuilderError> {
+        let mut builders: Vec<Builder> = Vec::with_capacity(configs.len());
+        for config in configs {
+            if config.command.is_empty() {
+                return Err(PublishBuilderError::EmptyCommand(config.name.clone()));
+            }
+            if builders.iter().any(|builder| builder.name == config.name) {
+                return Err(PublishBuilderError::Duplicate(config.name.clone()));
+            }
+            builders.push(Builder {
+                name: config.name.clone(),
+                command: config.command.clone(),
+                timeout: Duration::from_secs(config.timeout_secs),
+            });
+        }
+        Ok(Self {
+            builders: Arc::new(builders),
+        })
+    }
+
+    /// Whether a builder of this name is configured
+    pub fn contains(&self, name: &str) -> bool {
+        self.builders.iter().any(|builder| builder.name == name)
+    }
+
+    /// Run a pipeline's builder on the version of a bucket in `mount`
+    pub async fn build(
+        &self,
+        pipeline: &PublishPipeline,
+        mount: &Mount,
+        source: &BuildSource,
+    ) -> Result<Build, BuildError> {
+        let builder = self
+            .builders
+            .iter()
+            .find(|builder| builder.name == pipeline.builder)
+            .ok_or_else(|| BuildError::UnknownBuilder(pipeline.builder.clone()))?;
+
+        let dir = tempfile::tempdir()?;
+        let checkout = dir.path().join("checkout");
+        let output_dir = dir.path().join("output");
+        fs::create_dir_all(&checkout)?;
+        fs::create_dir_all(&output_dir)?;
+        export(mount, &checkout)
+            .await
+            .map_err(|e| BuildError::Checkout(e.to_string()))?;
+
+        let mut command = tokio::process::Command::new(&builder.command[0]);
+        command
+            .args(&builder.command[1..])
+            .current_dir(&checkout)
+            .env_clear()
+            .envs(&pipeline.vars)
+            .env("HOME", dir.path())
+            .env("JAX_BUCKET_ID", source.bucket_id.to_string())
+            .env("JAX_BUCKET_NAME", &source.name)
+            .env("JAX_VERSION", source.version.hash().to_string())
+            .env("JAX_HEIGHT", source.height.to_string())
+            .env("JAX_OUTPUT", &output_dir)
+            .kill_on_drop(true);
+        if let Some(path) = std::env::var_os("PATH") {
+            command.env("PATH", path);
+        }
+        let output = match tokio::time::timeout(builder.timeout, command.output()).await {
+            Ok(output) => {
+                output.map_err(|e| BuildError::Run(builder.command[0].clone(), e.to_string()))?
+            }
+            Err(_) => return Err(BuildError::TimedOut(builder.timeout.as_secs())),
+        };
+        let mut log = String::from_utf8_lossy(&output.stdout).to_string();
+        log.push_str(&String::from_utf8_lossy(&output.stderr));
+        let log = tail(log.trim());
+        if !output.status.success() {
+            return Err(BuildError::Failed(log));
+        }
+
+        let output = match &pipeline.output {
+            Some(output) => checkout.join(output),
+            None => output_dir,
+        };
+        if !output.is_dir() {
+            return Err(BuildError::NoOutput(
+                pipeline.output.clone().unwrap_or_default(),
+            ));
+        }
+        Ok(Build {
+            _dir: dir,
+            output,
+            log,
+        })
+    }
+}
+
+/// Replace the contents of a site bucket with an output tree, returning the
+/// number of files added
+pub async fn fill_site(site: &mut Mount, output: &Path) -> Result<u64, BuildError> {
+    for path in site.ls(Path::new("/")).await?.into_keys() {
+        site.rm(&Path::new("/").join(path)).await?;
+    }
+    let root = output.to_path_buf();
+    let files = tokio::task::spawn_blocking(move || {
+        let mut files = Vec::new();
+        walk(&root, Path::new(""), &mut files).map(|_| files)
+    })
+    .await
+    .map_err(|e| BuildError::Io(std::io::Error::other(e)))??;
+    for path in &files {
+        let file = fs::File::open(output.join(path))?;
+        site.add(&Path::new("/").join(path), file).await?;
+    }
+    Ok(files.len() as u64)
+}
+
+/// Files under `dir`, relative to the output root. Links aren't followed, so
+/// nothing outside the output is published.
+fn walk(dir: &Path, relative: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let path = relative.join(entry.file_name());
+        if file_type.is_dir() {
+            walk(&entry.path(), &path, files)?;
+        } else if file_type.is_file() {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Keep the end of a builder's output, where errors are, cut at a character
+/// boundary
+fn tail(log: &str) -> String {
+    if log.len() <= MAX_LOG_LEN {
+        return log.to_string();
+    }
+    let mut start = log.len() - MAX_LOG_LEN;
+    while !log.is_char_boundary(start) {
+        start += 1;
+    }
+    format!("...{}", &log[start..])
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PublishBuilderError {
+    #[error("publish builder {0} has an empty command")]
+    EmptyCommand(String),
+    #[error("publish builder {0} is configured twice")]
+    Duplicate(String),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PipelineError {
+    #[error("no publish builder named {0} is configured")]
+    UnknownBuilder(String),
+    #[error("output {0} is not a directory inside the checkout")]
+    InvalidOutput(String),
+    #[error(
+        "invalid template variable {0}: names are letters, digits and _, not starting with JAX_"
+    )]
+    InvalidVar(String),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BuildError {
+    #[error("no publish builder named {0} is configured")]
+    UnknownBuilder(String),
+    #[error("checking out the version: {0}")]
+    Checkout(String),
+    #[error("running {0}: {1}")]
+    Run(String, String),
+    #[error("build timed out after {0}s")]
+    TimedOut(u64),
+    #[error("build failed: {0}")]
+    Failed(String),
+    #[error("build wrote no output directory {0}")]
+    NoOutput(String),
+    #[error("{0}")]
+    Mount(#[from] MountError),
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn pipeline(output: Option<&str>, vars: &[(&str, &str)]) -> PublishPipeline {
+        PublishPipeline {
+            builder: "hugo".to_string(),
+            output: output.map(str::to_string),
+            vars: vars
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_pipeline_validation() {
+        assert!(pipeline(None, &[]).validate().is_ok());
+        assert!(
+            pipeline(Some("public"), &[("SITE_TITLE", "Notes"), ("_env", "prod")])
+                .validate()
+                .is_ok()
+        );
+        for output in ["", "/srv/www", "../out", "public/../.."] {
+            assert!(
+                matches!(
+                    pipeline(Some(output), &[]).validate(),
+                    Err(PipelineError::InvalidOutput(_))
+                ),
+                "{}",
+                output
+            );
+        }
+        for name in ["", "1ST", "SITE-TITLE", "JAX_VERSION"] {
+            assert!(
+                matches!(
+                    pipeline(None, &[(name, "x")]).validate(),
+                    Err(PipelineError::InvalidVar(_))
+                ),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_builder_config() {
+        let config = |name: &str, command: &[&str]| BuilderConfig {
+            name: name.to_string(),
+            command: command.iter().map(|s| s.to_string()).collect(),
+            timeout_secs: DEFAULT_TIMEOUT_SECS,
+        };
+        let builders = PublishBuilders::new(&[config("hugo", &["hugo", "--minify"])]).unwrap();
+        assert!(builders.contains("hugo"));
+        assert!(!builders.contains("jekyll"));
+        assert!(matches!(
+            PublishBuilders::new(&[config("hugo", &[])]),
+            Err(PublishBuilderError::EmptyCommand(_))
+        ));
+        assert!(matches!(
+            PublishBuilders::new(&[config("hugo", &["hugo"]), config("hugo", &["zola"])]),
+            Err(PublishBuilderError::Duplicate(_))
+        ));
+    }
+
+    #[test]
+    fn test_tail_keeps_the_end() {
+        assert_eq!(tail("short"), "short");
+        let log = format!("{}error: missing layout", "é".repeat(MAX_LOG_LEN));
+        let kept = tail(&log);
+        assert!(kept.starts_with("..."));
+        assert!(kept.ends_with("error: missing layout"));
+        assert!(kept.len() <= MAX_LOG_LEN + 3);
+    }
+}
@@ -0,0 +1,431 @@
+//! Content scanning before publish
+//!
+//! Operators register scanners in the config file (`[[publish_scanners]]`)
+//! that inspect a version before it is published and can veto it:
+//! - a command, run with the version's files exported to a temporary
+//!   directory given as its last argument. Exit status 0 allows the version;
+//!   any other vetoes it, with its output as the reason.
+//! - an HTTP endpoint, POSTed the version as a zip archive. A 2xx response
+//!   allows it; a 4xx vetoes it, with the body as the reason.
+//!
+//! Both get the bucket and version: commands in the `JAX_BUCKET_ID` and
+//! `JAX_VERSION` environment variables, endpoints in the `X-Jax-Bucket-Id`
+//! and `X-Jax-Version` headers. A scanner that fails or times out vetoes the
+//! version too, so nothing unscanned is published.
+//!
+//! Scanners run when a bucket is published (or republished) on this node,
+//! and on a gateway the first time it serves a published version synced from
+//! a peer, which is unpublished locally if vetoed. Every result is recorded
+//! with the version it was for (`jax bucket scans`).
+
+use std::fmt;
+use std::path::{Component, Path};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use common::linked_data::Link;
+use common::mount::{Mount, MountError, NodeLink};
+
+/// Header with the id of the bucket sent to HTTP scanners
+pub const BUCKET_HEADER: &str = "x-jax-bucket-id";
+
+/// Header with the hash of the version sent to HTTP scanners
+pub const VERSION_HEADER: &str = "x-jax-version";
+
+/// Time a scanner gets unless configured otherwise
+const DEFAULT_TIMEOUT_SECS: u64 = 300;
+
+/// Most bytes of a scanner's output kept as the reason for its verdict
+const MAX_DETAIL_LEN: usize = 1024;
+
+/// A scanner as configured in the config file. Exactly one of `command` and
+/// `url` is set.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScannerConfig {
+    /// Name the scanner's results are recorded under
+    pub name: String,
+    /// Program and arguments to run; the export directory is appended
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<Vec<String>>,
+    /// Endpoint to POST the version's zip archive to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Seconds the scanner gets before it counts as failed
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_timeout_secs() -> u64 {
+    DEFAULT_TIMEOUT_SECS
+}
+
+/// What a scanner made of a version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verdict {
+    Allowed,
+    Vetoed,
+    /// The scanner could not be run or answered with an error; counts as a
+    /// veto
+    Failed,
+}
+
+impl Verdict {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Verdict::Allowed => "allowed",
+            Verdict::Vetoed => "vetoed",
+            Verdict::Failed => "failed",
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Verdict {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "allowed" => Ok(Verdict::Allowed),
+            "vetoed" => Ok(Verdict::Vetoed),
+            "failed" => Ok(Verdict::Failed),
+            other => Err(format!("unknown scan verdict {}", other)),
+        }
+    }
+}
+
+/// One scanner's verdict on a version
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScanResult {
+    pub scanner: String,
+    pub verdict: Verdict,
+    /// Why the scanner vetoed the version or failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl ScanResult {
+    fn new(scanner: &str, verdict: Verdict, detail: impl Into<String>) -> Self {
+        let detail = truncate(detail.into().trim());
+        Self {
+            scanner: scanner.to_string(),
+            verdict,
+            detail: (!detail.is_empty()).then_some(detail),
+        }
+    }
+}
+
+/// Whether every scanner allowed a version
+pub fn all_allowed(results: &[ScanResult]) -> bool {
+    results
+        .iter()
+        .all(|result| result.verdict == Verdict::Allowed)
+}
+
+/// The scanners that didn't allow a version and why, for error messages
+pub fn describe_vetoes(results: &[ScanResult]) -> String {
+    results
+        .iter()
+        .filter(|result| result.verdict != Verdict::Allowed)
+        .map(|result| {
+            format!(
+                "{}: {} ({})",
+                result.scanner,
+                result.verdict,
+                result.detail.as_deref().unwrap_or("no reason given")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// A recorded scan result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublishScan {
+    /// Hash of the version scanned
+    pub version: String,
+    #[serde(flatten)]
+    pub result: ScanResult,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+#[derive(Debug, Clone)]
+enum ScannerKind {
+    Command(Vec<String>),
+    Http(url::Url),
+}
+
+#[derive(Debug, Clone)]
+struct Scanner {
+    name: String,
+    kind: ScannerKind,
+    timeout: Duration,
+}
+
+/// The scanners configured on this node
+#[derive(Debug, Clone, Default)]
+pub struct PublishScanners {
+    scanners: Arc<Vec<Scanner>>,
+    client: reqwest::Client,
+}
+
+impl PublishScanners {
+    pub fn new(configs: &[ScannerConfig]) -> Result<Self, PublishScanError> {
+        let scanners = configs
+            .iter()
+            .map(|config| {
+                let kind = match (&config.command, &config.url) {
+                    (Some(command), None) if command.is_empty() => {
+                        return Err(PublishScanError::EmptyCommand(config.name.clone()))
+                    }
+                    (Some(command), None) => ScannerKind::Command(command.clone()),
+                    (None, Some(url)) => ScannerKind::Http(url.parse().map_err(|_| {
+                        PublishScanError::InvalidUrl(config.name.clone(), url.clone())
+                    })?),
+                    _ => return Err(PublishScanError::NoTarget(config.name.clone())),
+                };
+                Ok(Scanner {
+                    name: config.name.clone(),
+                    kind,
+                    timeout: Duration::from_secs(config.timeout_secs),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            scanners: Arc::new(scanners),
+            client: reqwest::Client::new(),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scanners.is_empty()
+    }
+
+    /// Run every scanner on the version of a bucket in `mount`, recorded as
+    /// `version`
+    pub async fn scan(&self, mount: &Mount, bucket_id: Uuid, version: &Link) -> Vec<ScanResult> {
+        let mut results = Vec::with_capacity(self.scanners.len());
+        for scanner in self.scanners.iter() {
+            let scan = async {
+                match &scanner.kind {
+                    ScannerKind::Command(command) => {
+                        scan_with_command(&scanner.name, command, mount, bucket_id, version).await
+                    }
+                    ScannerKind::Http(url) => {
+                        scan_with_http(&scanner.name, &self.client, url, mount, bucket_id, version)
+                            .await
+                    }
+                }
+            };
+            let result = match tokio::time::timeout(scanner.timeout, scan).await {
+                Ok(Ok(result)) => result,
+                Ok(Err(e)) => ScanResult::new(&scanner.name, Verdict::Failed, e),
+                Err(_) => ScanResult::new(
+                    &scanner.name,
+                    Verdict::Failed,
+                    format!("timed out after {}s", scanner.timeout.as_secs()),
+                ),
+            };
+            if result.verdict != Verdict::Allowed {
+                tracing::warn!(
+                    "Scanner {} {} version {} of bucket {}: {}",
+                    result.scanner,
+                    result.verdict,
+                    version.hash(),
+                    bucket_id,
+                    result.detail.as_deref().unwrap_or("no reason given")
+                );
+            }
+            results.push(result);
+        }
+        results
+    }
+}
+
+async fn scan_with_command(
+    name: &str,
+    command: &[String],
+    mount: &Mount,
+    bucket_id: Uuid,
+    version: &Link,
+) -> Result<ScanResult, String> {
+    let dir = tempfile::tempdir().map_err(|e| format!("creating export directory: {}", e))?;
+    export(mount, dir.path())
+        .await
+        .map_err(|e| format!("exporting version: {}", e))?;
+    let output = tokio::process::Command::new(&command[0])
+        .args(&command[1..])
+        .arg(dir.path())
+        .env("JAX_BUCKET_ID", bucket_id.to_string())
+        .env("JAX_VERSION", version.hash().to_string())
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| format!("running {}: {}", command[0], e))?;
+    if output.status.success() {
+        return Ok(ScanResult::new(name, Verdict::Allowed, ""));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let detail = if stdout.trim().is_empty() {
+        String::from_utf8_lossy(&output.stderr).to_string()
+    } else {
+        stdout.to_string()
+    };
+    Ok(ScanResult::new(name, Verdict::Vetoed, detail))
+}
+
+async fn scan_with_http(
+    name: &str,
+    client: &reqwest::Client,
+    url: &url::Url,
+    mount: &Mount,
+    bucket_id: Uuid,
+    version: &Link,
+) -> Result<ScanResult, String> {
+    let archive = crate::zip_stream::archive(mount.clone(), Path::new("/"))
+        .await
+        .map_err(|e| format!("archiving version: {}", e))?;
+    let response = client
+        .post(url.clone())
+        .header(reqwest::header::CONTENT_TYPE, "application/zip")
+        .header(BUCKET_HEADER, bucket_id.to_string())
+        .header(VERSION_HEADER, version.hash().to_string())
+        .body(reqwest::Body::wrap_stream(archive))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    Ok(if status.is_success() {
+        ScanResult::new(name, Verdict::Allowed, "")
+    } else if status.is_client_error() {
+        ScanResult::new(name, Verdict::Vetoed, body)
+    } else {
+        ScanResult::new(name, Verdict::Failed, format!("{}: {}", status, body))
+    })
+}
+
+/// Write the files of a version under `dir`
+pub(crate) async fn export(mount: &Mount, dir: &Path) -> Result<(), ExportError> {
+    for (path, node) in mount.ls_deep(Path::new("/")).await? {
+        // Paths in a bucket never leave it, but make sure
+        let relative = path.strip_prefix("/").unwrap_or(&path);
+        if relative
+            .components()
+            .any(|component| !matches!(component, Component::Normal(_)))
+        {
+            continue;
+        }
+        let target = dir.join(relative);
+        match node {
+            NodeLink::Dir(..) => tokio::fs::create_dir_all(&target).await?,
+            NodeLink::Data(..) => {
+                if let Some(parent) = target.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                let data = mount.cat(&Path::new("/").join(relative)).await?;
+                tokio::fs::write(&target, data).await?;
+            }
+            // Referenced buckets are scanned when they are published
+            NodeLink::Bucket(_) => {}
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum ExportError {
+    #[error("{0}")]
+    Mount(#[from] MountError),
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Keep the start of a scanner's output, cut at a character boundary
+fn truncate(detail: &str) -> String {
+    if detail.len() <= MAX_DETAIL_LEN {
+        return detail.to_string();
+    }
+    let mut end = MAX_DETAIL_LEN;
+    while !detail.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}...", &detail[..end])
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PublishScanError {
+    #[error("publish scanner {0} needs either a command or a url")]
+    NoTarget(String),
+    #[error("publish scanner {0} has an empty command")]
+    EmptyCommand(String),
+    #[error("publish scanner {0} has an invalid url: {1}")]
+    InvalidUrl(String, String),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn config(command: Option<&[&str]>, url: Option<&str>) -> ScannerConfig {
+        ScannerConfig {
+            name: "test".to_string(),
+            command: command.map(|command| command.iter().map(|s| s.to_string()).collect()),
+            url: url.map(str::to_string),
+            timeout_secs: DEFAULT_TIMEOUT_SECS,
+        }
+    }
+
+    #[test]
+    fn test_scanner_config() {
+        assert!(PublishScanners::new(&[config(Some(&["clamscan", "-r"]), None)]).is_ok());
+        assert!(PublishScanners::new(&[config(None, Some("http://localhost:3310/scan"))]).is_ok());
+        assert!(matches!(
+            PublishScanners::new(&[config(None, None)]),
+            Err(PublishScanError::NoTarget(_))
+        ));
+        assert!(matches!(
+            PublishScanners::new(&[config(Some(&["scan"]), Some("http://localhost/"))]),
+            Err(PublishScanError::NoTarget(_))
+        ));
+        assert!(matches!(
+            PublishScanners::new(&[config(Some(&[]), None)]),
+            Err(PublishScanError::EmptyCommand(_))
+        ));
+        assert!(matches!(
+            PublishScanners::new(&[config(None, Some("not a url"))]),
+            Err(PublishScanError::InvalidUrl(..))
+        ));
+    }
+
+    #[test]
+    fn test_verdicts() {
+        let allowed = ScanResult::new("a", Verdict::Allowed, "  ");
+        assert_eq!(allowed.detail, None);
+        let vetoed = ScanResult::new("b", Verdict::Vetoed, "Eicar-Signature FOUND\n");
+        assert_eq!(vetoed.detail.as_deref(), Some("Eicar-Signature FOUND"));
+        assert!(all_allowed(std::slice::from_ref(&allowed)));
+        assert!(!all_allowed(&[allowed.clone(), vetoed.clone()]));
+        assert!(all_allowed(&[]));
+        assert_eq!(
+            describe_vetoes(&[allowed, vetoed]),
+            "b: vetoed (Eicar-Signature FOUND)"
+        );
+        assert_eq!("failed".parse::<Verdict>().unwrap(), Verdict::Failed);
+        assert_eq!(
+            truncate(&"é".repeat(MAX_DETAIL_LEN)).len(),
+            MAX_DETAIL_LEN + 3
+        );
+    }
+}
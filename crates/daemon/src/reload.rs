@@ -0,0 +1,304 @@
+//! Hot-reloading of daemon configuration
+//!
+//! Only settings that can change safely while the daemon is running are
+//! applied (log level, periodic sync interval, online-only cache size, sync
+//! deferral). Settings that would require rebinding sockets or reopening stores
+//! are reported back as needing a restart.
+//! A reload is triggered by SIGHUP or by `POST /api/v0/admin/reload`.
+
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::sync::watch;
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+use crate::state::{AppConfig, StateError};
+use crate::sync_conditions::SyncDeferral;
+
+/// Handle to the global log filter, set when the daemon owns the tracing subscriber
+static LOG_FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// Build the log filter for a given default level (RUST_LOG directives still apply)
+fn log_filter(level: tracing::Level) -> EnvFilter {
+    EnvFilter::builder()
+        .with_default_directive(level.into())
+        .from_env_lossy()
+}
+
+/// Create a log filter layer whose level can be changed on reload.
+pub(crate) fn reloadable_log_filter(level: tracing::Level) -> reload::Layer<EnvFilter, Registry> {
+    let (layer, handle) = reload::Layer::new(log_filter(level));
+    let _ = LOG_FILTER.set(handle);
+    layer
+}
+
+/// Settings that can be changed without restarting the daemon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuntimeConfig {
+    pub log_level: tracing::Level,
+    pub sync_interval: Duration,
+    pub online_cache_max_bytes: u64,
+    pub sync_deferral: SyncDeferral,
+}
+
+/// What a reload changed
+#[derive(Debug, Clone, Default)]
+pub struct ReloadSummary {
+    /// Settings that were applied to the running daemon
+    pub applied: Vec<&'static str>,
+    /// Settings that changed on disk but only take effect after a restart
+    pub restart_required: Vec<&'static str>,
+}
+
+/// Re-reads the config file and pushes runtime settings to running components
+#[derive(Debug, Clone)]
+pub struct ConfigReloader {
+    inner: Arc<ReloaderInner>,
+}
+
+#[derive(Debug)]
+struct ReloaderInner {
+    config_path: Option<PathBuf>,
+    /// Config file contents at startup, used to detect restart-only changes
+    startup: Option<AppConfig>,
+    runtime_tx: watch::Sender<RuntimeConfig>,
+}
+
+impl ConfigReloader {
+    pub fn new(config_path: Option<PathBuf>, runtime: RuntimeConfig) -> Self {
+        let startup = config_path
+            .as_deref()
+            .and_then(|path| AppConfig::read(path).ok());
+        let (runtime_tx, _) = watch::channel(runtime);
+
+        Self {
+            inner: Arc::new(ReloaderInner {
+                config_path,
+                startup,
+                runtime_tx,
+            }),
+        }
+    }
+
+    /// Path of the config file the daemon was started from, if any
+    pub fn config_path(&self) -> Option<&std::path::Path> {
+        self.inner.config_path.as_deref()
+    }
+
+    /// The currently active runtime settings
+    pub fn current(&self) -> RuntimeConfig {
+        *self.inner.runtime_tx.borrow()
+    }
+
+    /// Subscribe to runtime setting changes
+    pub fn subscribe(&self) -> watch::Receiver<RuntimeConfig> {
+        self.inner.runtime_tx.subscribe()
+    }
+
+    /// Re-read the config file and apply any reloadable changes.
+    pub fn reload(&self) -> Result<ReloadSummary, ReloadError> {
+        let path = self
+            .inner
+            .config_path
+            .as_deref()
+            .ok_or(ReloadError::NoConfigFile)?;
+        let config = AppConfig::read(path)?;
+        let runtime = RuntimeConfig {
+            log_level: config.log_level()?,
+            sync_interval: config.sync_interval(),
+            online_cache_max_bytes: config.online_cache_max_bytes,
+            sync_deferral: config.sync_deferral,
+        };
+
+        let previous = self.current();
+        let mut summary = ReloadSummary::default();
+
+        if runtime.log_level != previous.log_level {
+            if let Some(handle) = LOG_FILTER.get() {
+                handle.reload(log_filter(runtime.log_level))?;
+                summary.applied.push("log_level");
+            } else {
+                summary.restart_required.push("log_level");
+            }
+        }
+        if runtime.sync_interval != previous.sync_interval {
+            summary.applied.push("sync_interval_secs");
+        }
+        if runtime.online_cache_max_bytes != previous.online_cache_max_bytes {
+            summary.applied.push("online_cache_max_bytes");
+        }
+        if runtime.sync_deferral != previous.sync_deferral {
+            summary.applied.push("sync_deferral");
+        }
+        self.inner.runtime_tx.send_replace(runtime);
+
+        if let Some(startup) = &self.inner.startup {
+            if config.api_port != startup.api_port {
+                summary.restart_required.push("api_port");
+            }
+            if config.gateway_port != startup.gateway_port {
+                summary.restart_required.push("gateway_port");
+            }
+            if config.peer_port != startup.peer_port {
+                summary.restart_required.push("peer_port");
+            }
+            if config.blob_store != startup.blob_store {
+                summary.restart_required.push("blob_store");
+            }
+            if config.blob_locations != startup.blob_locations {
+                summary.restart_required.push("blob_locations");
+            }
+            if config.cdn_targets != startup.cdn_targets {
+                summary.restart_required.push("cdn_targets");
+            }
+            if config.encrypt_database != startup.encrypt_database {
+                summary.restart_required.push("encrypt_database");
+            }
+            if config.otlp_endpoint != startup.otlp_endpoint {
+                summary.restart_required.push("otlp_endpoint");
+            }
+            if config.gateway_tls != startup.gateway_tls {
+                summary.restart_required.push("gateway_tls");
+            }
+            if config.reverse_proxy != startup.reverse_proxy {
+                summary.restart_required.push("reverse_proxy");
+            }
+            if config.publish_scanners != startup.publish_scanners {
+                summary.restart_required.push("publish_scanners");
+            }
+            if config.publish_builders != startup.publish_builders {
+                summary.restart_required.push("publish_builders");
+            }
+            if config.upload_limits != startup.upload_limits {
+                summary.restart_required.push("upload_limits");
+            }
+            if config.import_dirs != startup.import_dirs {
+                summary.restart_required.push("import_dirs");
+            }
+            if config.read_replica != startup.read_replica {
+                summary.restart_required.push("read_replica");
+            }
+            if config.cluster != startup.cluster {
+                summary.restart_required.push("cluster");
+            }
+        }
+
+        tracing::info!(
+            applied = ?summary.applied,
+            restart_required = ?summary.restart_required,
+            "configuration reloaded"
+        );
+        Ok(summary)
+    }
+}
+
+/// Reload configuration every time the process receives SIGHUP, until shutdown.
+pub async fn reload_on_sighup(reloader: ConfigReloader, mut shutdown_rx: watch::Receiver<()>) {
+    let mut sighup = match signal(SignalKind::hangup()) {
+        Ok(sighup) => sighup,
+        Err(e) => {
+            tracing::error!("Failed to install SIGHUP handler: {}", e);
+            return;
+        }
+    };
+
+    loop {
+        tokio::select! {
+            _ = sighup.recv() => {
+                tracing::info!("Reloading configuration on SIGHUP");
+                if let Err(e) = reloader.reload() {
+                    tracing::error!("Failed to reload configuration: {}", e);
+                }
+            }
+            _ = shutdown_rx.changed() => break,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReloadError {
+    #[error("daemon was not started from a config file")]
+    NoConfigFile,
+    #[error("invalid config: {0}")]
+    Config(#[from] StateError),
+    #[error("failed to update log filter: {0}")]
+    LogFilter(#[from] reload::Error),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn write_config(dir: &tempfile::TempDir, config: &AppConfig) -> PathBuf {
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, toml::to_string_pretty(config).unwrap()).unwrap();
+        path
+    }
+
+    fn runtime(config: &AppConfig) -> RuntimeConfig {
+        RuntimeConfig {
+            log_level: config.log_level().unwrap(),
+            sync_interval: config.sync_interval(),
+            online_cache_max_bytes: config.online_cache_max_bytes,
+            sync_deferral: config.sync_deferral,
+        }
+    }
+
+    #[test]
+    fn test_reload_applies_sync_interval() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = AppConfig::default();
+        let path = write_config(&dir, &config);
+        let reloader = ConfigReloader::new(Some(path), runtime(&config));
+        let rx = reloader.subscribe();
+
+        config.sync_interval_secs = 5;
+        write_config(&dir, &config);
+
+        let summary = reloader.reload().unwrap();
+        assert_eq!(summary.applied, vec!["sync_interval_secs"]);
+        assert!(summary.restart_required.is_empty());
+        assert!(rx.has_changed().unwrap());
+        assert_eq!(reloader.current().sync_interval, Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_reload_reports_restart_required() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = AppConfig::default();
+        let path = write_config(&dir, &config);
+        let reloader = ConfigReloader::new(Some(path), runtime(&config));
+
+        config.api_port = 6001;
+        write_config(&dir, &config);
+
+        let summary = reloader.reload().unwrap();
+        assert!(summary.applied.is_empty());
+        assert_eq!(summary.restart_required, vec!["api_port"]);
+    }
+
+    #[test]
+    fn test_reload_rejects_invalid_log_level() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = AppConfig::default();
+        let path = write_config(&dir, &config);
+        let reloader = ConfigReloader::new(Some(path), runtime(&config));
+
+        config.log_level = "loud".to_string();
+        write_config(&dir, &config);
+
+        assert!(matches!(
+            reloader.reload(),
+            Err(ReloadError::Config(StateError::InvalidLogLevel(_)))
+        ));
+        assert_eq!(reloader.current(), runtime(&AppConfig::default()));
+    }
+
+    #[test]
+    fn test_reload_without_config_file() {
+        let reloader = ConfigReloader::new(None, runtime(&AppConfig::default()));
+        assert!(matches!(reloader.reload(), Err(ReloadError::NoConfigFile)));
+    }
+}
@@ -0,0 +1,316 @@
+//! Importing existing cloud storage into buckets
+//!
+//! An import copies every object below a prefix of S3, Google Cloud Storage,
+//! Azure Blob Storage or a WebDAV server into a bucket, at the same paths;
+//! see [`RemoteSource`] for the URLs and client options each takes. Local
+//! directories can be imported as `file://` URLs only from below one of the
+//! `import_dirs` in the config, so that the API can't be used to import the
+//! node's keys or other files of its host. The
+//! first import of a source URL creates a bucket for it. Importing the same
+//! URL again imports into that bucket, copying only the objects whose ETag
+//! (or size and modification time) changed since, and with `delete` removes
+//! the files whose objects are gone.
+//!
+//! Objects are streamed into the bucket, never held in memory whole, and
+//! the bucket is saved every [`BATCH_SIZE`] objects, so an interrupted
+//! import picks up where it stopped when run again. Imports are audited as
+//! `bucket.import`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use tokio_util::io::StreamReader;
+use uuid::Uuid;
+
+use common::bucket_log::BucketLogProvider;
+use common::mount::{Mount, MountError};
+use common::peer::Peer;
+use object_store::{BlobStoreError, RemoteObject, RemoteSource};
+
+use crate::audit::{Actor, AuditEvent, AuditLog};
+use crate::database::Database;
+
+/// Objects imported between saves of the bucket
+pub const BATCH_SIZE: usize = 100;
+
+/// A bucket imported from a source URL
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemoteImport {
+    pub bucket_id: Uuid,
+    pub source: String,
+    /// When an import last finished
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub imported_at: Option<OffsetDateTime>,
+}
+
+/// What importing a source did
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteImportReport {
+    pub bucket_id: Uuid,
+    pub name: String,
+    pub source: String,
+    /// Whether the bucket was created by this import
+    pub created: bool,
+    /// Objects below the source's prefix
+    pub objects: u64,
+    /// Objects copied, being new or changed
+    pub imported_files: u64,
+    /// Bytes copied
+    pub imported_bytes: u64,
+    /// Files removed since their objects are gone
+    pub removed_files: u64,
+    /// Hash of the bucket version the import saved, None if nothing changed
+    pub version: Option<String>,
+}
+
+/// A source URL without the trailing slash, which names the same prefix
+pub fn normalize_source(url: &str) -> String {
+    url.trim_end_matches('/').to_string()
+}
+
+/// Name of a bucket created for a source: the last segment of its URL
+fn default_name(source: &str) -> String {
+    let path = source.split_once("://").map_or(source, |(_, rest)| rest);
+    path.rsplit('/')
+        .find(|segment| !segment.is_empty())
+        .unwrap_or(path)
+        .to_string()
+}
+
+/// The paths objects are imported at. Objects whose path is a directory of
+/// other objects, such as the empty markers some tools leave for folders,
+/// are skipped.
+fn file_paths<T>(objects: &BTreeMap<String, T>) -> BTreeSet<String> {
+    objects
+        .keys()
+        .filter(|path| {
+            let dir = format!("{}/", path);
+            !objects
+                .range(dir.clone()..)
+                .next()
+                .is_some_and(|(next, _)| next.starts_with(&dir))
+        })
+        .cloned()
+        .collect()
+}
+
+/// The URL to open a source with: a `file://` source resolved to the
+/// directory it names, which must be below one of `import_dirs`
+fn resolve_local(source: &str, import_dirs: &[PathBuf]) -> Result<String, RemoteImportError> {
+    let Some(path) = source.strip_prefix("file://") else {
+        return Ok(source.to_string());
+    };
+    let refused = || RemoteImportError::LocalNotAllowed(path.to_string());
+    // Resolved, so that neither `..` nor symlinks lead out of an import
+    //  directory
+    let path = std::fs::canonicalize(path).map_err(|_| refused())?;
+    let allowed = import_dirs
+        .iter()
+        .filter_map(|dir| std::fs::canonicalize(dir).ok())
+        .any(|dir| path.starts_with(dir));
+    if !allowed {
+        return Err(refused());
+    }
+    Ok(format!("file://{}", path.display()))
+}
+
+/// Import the objects below a source URL into its bucket, creating one
+/// named `name` if the source wasn't imported before. `file://` sources
+/// must be below one of `import_dirs`.
+#[allow(clippy::too_many_arguments)]
+pub async fn import(
+    peer: &Peer<Database>,
+    database: &Database,
+    audit: &AuditLog,
+    import_dirs: &[PathBuf],
+    source: &str,
+    options: &BTreeMap<String, String>,
+    name: Option<&str>,
+    delete: bool,
+) -> Result<RemoteImportReport, RemoteImportError> {
+    let source = normalize_source(source);
+    let remote = RemoteSource::open(&resolve_local(&source, import_dirs)?, options)?;
+    let listed = remote.list().await?;
+
+    let (mut mount, created) = match database.remote_import(&source).await? {
+        Some(existing) => (peer.mount(existing.bucket_id).await?, false),
+        None => {
+            let name = name.map_or_else(|| default_name(&source), str::to_string);
+            if name.is_empty() {
+                return Err(RemoteImportError::InvalidName);
+            }
+            let bucket_id = Uuid::new_v4();
+            let mount = Mount::init(bucket_id, name.clone(), peer.secret(), peer.blobs()).await?;
+            let genesis_link = mount.link().await;
+            peer.logs()
+                .append(bucket_id, name, genesis_link, None, 0, false)
+                .await
+                .map_err(|e| RemoteImportError::BucketLog(e.to_string()))?;
+            database.insert_remote_import(&bucket_id, &source).await?;
+            (mount, true)
+        }
+    };
+    let (bucket_id, name) = {
+        let inner = mount.inner().await;
+        (*inner.manifest().id(), inner.manifest().name().to_string())
+    };
+    let mut report = RemoteImportReport {
+        bucket_id,
+        name,
+        source: source.clone(),
+        created,
+        objects: listed.len() as u64,
+        imported_files: 0,
+        imported_bytes: 0,
+        removed_files: 0,
+        version: None,
+    };
+
+    let known = database.remote_import_objects(&bucket_id).await?;
+    let objects: BTreeMap<String, RemoteObject> = listed
+        .into_iter()
+        .map(|object| (object.path.clone(), object))
+        .collect();
+    let paths = file_paths(&objects);
+
+    let mut saved = None;
+    let mut batch = Vec::new();
+    for path in &paths {
+        let object = &objects[path];
+        if known.get(path) == Some(&object.fingerprint) {
+            continue;
+        }
+        let stream = remote.stream(path).await?;
+        mount
+            .add_reader(&Path::new("/").join(path), StreamReader::new(stream))
+            .await?;
+        report.imported_files += 1;
+        report.imported_bytes += object.size;
+        batch.push((path.clone(), object.fingerprint.clone()));
+        if batch.len() >= BATCH_SIZE {
+            saved = Some(peer.save_mount(&mount, false).await?);
+            database
+                .set_remote_import_objects(&bucket_id, &batch)
+                .await?;
+            batch.clear();
+        }
+    }
+
+    let mut removed = Vec::new();
+    if delete {
+        for path in known.keys().filter(|path| !paths.contains(*path)) {
+            match mount.rm(&Path::new("/").join(path)).await {
+                Ok(()) => report.removed_files += 1,
+                // Removed from the bucket already
+                Err(MountError::PathNotFound(_)) => {}
+                Err(e) => return Err(e.into()),
+            }
+            removed.push(path.clone());
+        }
+    }
+
+    if !batch.is_empty() || report.removed_files > 0 {
+        saved = Some(peer.save_mount(&mount, false).await?);
+    }
+    database
+        .set_remote_import_objects(&bucket_id, &batch)
+        .await?;
+    database
+        .delete_remote_import_objects(&bucket_id, &removed)
+        .await?;
+    database.set_remote_imported(&bucket_id).await?;
+
+    let mut event = AuditEvent::new(Actor::Api, "bucket.import")
+        .bucket(bucket_id)
+        .detail(format!(
+            "{}: {} imported, {} removed",
+            source, report.imported_files, report.removed_files
+        ));
+    if let Some(link) = &saved {
+        event = event.version(link);
+        report.version = Some(link.hash().to_string());
+    }
+    audit.record(event).await;
+    tracing::info!(
+        "Imported {} into bucket {} ({} of {} object(s) copied, {} file(s) removed)",
+        source,
+        bucket_id,
+        report.imported_files,
+        report.objects,
+        report.removed_files
+    );
+    Ok(report)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RemoteImportError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Bucket log error: {0}")]
+    BucketLog(String),
+    #[error("Mount error: {0}")]
+    Mount(Box<MountError>),
+    #[error("Remote storage error: {0}")]
+    Source(#[from] BlobStoreError),
+    #[error("Bucket name cannot be empty")]
+    InvalidName,
+    #[error("Local directory {0} is not below one of the import_dirs in config.toml")]
+    LocalNotAllowed(String),
+}
+
+impl From<MountError> for RemoteImportError {
+    fn from(e: MountError) -> Self {
+        RemoteImportError::Mount(Box::new(e))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_default_name() {
+        assert_eq!(default_name("s3://photos/2024/trips"), "trips");
+        assert_eq!(default_name("s3://photos"), "photos");
+        assert_eq!(default_name("file:///srv/archive"), "archive");
+        assert_eq!(normalize_source("gs://site/www/"), "gs://site/www");
+    }
+
+    #[test]
+    fn test_folder_markers_skipped() {
+        let objects = BTreeMap::from(
+            ["docs", "docs/a.txt", "docs-old", "notes/b.txt"]
+                .map(|path| (path.to_string(), "etag".to_string())),
+        );
+        let paths: Vec<_> = file_paths(&objects).into_iter().collect();
+        assert_eq!(paths, ["docs-old", "docs/a.txt", "notes/b.txt"]);
+    }
+
+    #[test]
+    fn test_local_sources_confined_to_import_dirs() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let allowed = dir.path().join("imports");
+        std::fs::create_dir_all(allowed.join("archive")).unwrap();
+        let import_dirs = [allowed.clone()];
+
+        let archive = std::fs::canonicalize(allowed.join("archive")).unwrap();
+        assert_eq!(
+            resolve_local(&format!("file://{}", archive.display()), &import_dirs).unwrap(),
+            format!("file://{}", archive.display())
+        );
+        for outside in [
+            format!("file://{}", dir.path().display()),
+            format!("file://{}/archive/../..", allowed.display()),
+            format!("file://{}/missing", allowed.display()),
+        ] {
+            assert!(matches!(
+                resolve_local(&outside, &import_dirs),
+                Err(RemoteImportError::LocalNotAllowed(_))
+            ));
+        }
+        assert!(resolve_local(&format!("file://{}", archive.display()), &[]).is_err());
+        assert_eq!(resolve_local("s3://photos", &[]).unwrap(), "s3://photos");
+    }
+}
@@ -0,0 +1,164 @@
+//! Read replicas
+//!
+//! A node with `read_replica = true` is an edge copy of other nodes' published
+//! buckets. It syncs the buckets it follows or is shared into like any other
+//! node, but only downloads the content of their published versions, and it
+//! never writes to a bucket: API requests that could are refused with 403,
+//! and its gateway takes no deploys or drops.
+//!
+//! What a replica serves is only as fresh as its last sync, so it says how
+//! fresh. Gateway responses for a bucket carry the latest published version
+//! synced here and when it was synced, and the API server's
+//! `/_status/replica` lists the same for every bucket the gateway serves.
+
+use axum::http::{HeaderMap, HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// Header with the hash of the latest published version synced here, as
+/// taken by the gateway's `?at=`
+pub const SYNCED_VERSION_HEADER: &str = "x-jax-synced-version";
+
+/// Header with the height of that version in the bucket's log
+pub const SYNCED_HEIGHT_HEADER: &str = "x-jax-synced-height";
+
+/// Header with when that version was synced here, in RFC 3339
+pub const SYNCED_AT_HEADER: &str = "x-jax-synced-at";
+
+/// API sections a replica answers in full, below `/api`. None of them writes
+/// to a bucket.
+const READ_SECTIONS: &[&str] = &[
+    "/v0/activity",
+    "/v0/admin",
+    "/v0/audit",
+    "/v0/config",
+    "/v0/export",
+    "/v0/favorite",
+    "/v0/gateway",
+    "/v0/identity",
+    "/v0/logs",
+    "/v0/peer",
+    "/v0/storage",
+    "/v0/sync",
+    "/v0/webhook",
+];
+
+/// Bucket routes a replica answers, those that only read or choose what is
+/// synced
+const READ_BUCKET_ROUTES: &[&str] = &[
+    "/v0/bucket/attrs/get",
+    "/v0/bucket/cat",
+    "/v0/bucket/cdn/get",
+    "/v0/bucket/comments",
+    "/v0/bucket/deploy-key/list",
+    "/v0/bucket/drop-link/list",
+    "/v0/bucket/editing/list",
+    "/v0/bucket/export",
+    "/v0/bucket/follow",
+    "/v0/bucket/history",
+    "/v0/bucket/latest-published",
+    "/v0/bucket/lease/list",
+    "/v0/bucket/list",
+    "/v0/bucket/ls",
+    "/v0/bucket/manifest",
+    "/v0/bucket/member/policy/get",
+    "/v0/bucket/photos",
+    "/v0/bucket/ping",
+    "/v0/bucket/pipeline/get",
+    "/v0/bucket/presign",
+    "/v0/bucket/retention/get",
+    "/v0/bucket/scans",
+    "/v0/bucket/shares",
+    "/v0/bucket/stats",
+    "/v0/bucket/storage/get",
+    "/v0/bucket/unfollow",
+    "/v0/bucket/versions",
+    "/v0/bucket/zip",
+];
+
+/// Whether a replica answers requests to an API path below `/api`. Routes
+/// not listed here, including ones added later, are refused.
+pub fn allows(path: &str) -> bool {
+    let path = path.trim_end_matches('/');
+    READ_BUCKET_ROUTES.contains(&path)
+        || READ_SECTIONS.iter().any(|section| {
+            path.strip_prefix(section)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+}
+
+/// The latest published version of a bucket synced to this node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncedVersion {
+    pub bucket_id: Uuid,
+    pub name: String,
+    /// Hash of the version
+    pub version: String,
+    pub height: u64,
+    /// When this node recorded the version
+    #[serde(with = "time::serde::rfc3339")]
+    pub synced_at: OffsetDateTime,
+}
+
+impl SyncedVersion {
+    /// Seconds since the version was synced
+    pub fn age_secs(&self) -> u64 {
+        (OffsetDateTime::now_utc() - self.synced_at)
+            .whole_seconds()
+            .max(0) as u64
+    }
+
+    /// Mark a gateway response with the version and when it was synced
+    pub fn set_headers(&self, headers: &mut HeaderMap) {
+        let synced_at = self.synced_at.format(&Rfc3339).unwrap_or_default();
+        for (name, value) in [
+            (SYNCED_VERSION_HEADER, self.version.clone()),
+            (SYNCED_HEIGHT_HEADER, self.height.to_string()),
+            (SYNCED_AT_HEADER, synced_at),
+        ] {
+            if let Ok(value) = HeaderValue::from_str(&value) {
+                headers.insert(HeaderName::from_static(name), value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replica_allows_reads_only() {
+        assert!(allows("/v0/bucket/ls"));
+        assert!(allows("/v0/bucket/cat/"));
+        assert!(allows("/v0/sync/status"));
+        assert!(allows("/v0/gateway/policy/set"));
+
+        assert!(!allows("/v0/bucket"));
+        assert!(!allows("/v0/bucket/add"));
+        assert!(!allows("/v0/bucket/comments/add"));
+        assert!(!allows("/v0/bucket/publish"));
+        assert!(!allows("/v0/syncing"));
+        assert!(!allows("/v0/backup/run"));
+        assert!(!allows("/v0/workspace/create"));
+    }
+
+    #[test]
+    fn test_synced_version_headers() {
+        let synced = SyncedVersion {
+            bucket_id: Uuid::new_v4(),
+            name: "site".to_string(),
+            version: "bafkreiabc".to_string(),
+            height: 7,
+            synced_at: OffsetDateTime::UNIX_EPOCH,
+        };
+        let mut headers = HeaderMap::new();
+        synced.set_headers(&mut headers);
+        assert_eq!(headers[SYNCED_VERSION_HEADER], "bafkreiabc");
+        assert_eq!(headers[SYNCED_HEIGHT_HEADER], "7");
+        assert_eq!(headers[SYNCED_AT_HEADER], "1970-01-01T00:00:00Z");
+        assert!(synced.age_secs() > 0);
+    }
+}
@@ -0,0 +1,777 @@
+//! Retention policies pruning the history of buckets
+//!
+//! A retention policy keeps the newest version of each of the last N hours,
+//! days, weeks and months, in local time, or of every one of them: keeping
+//! hourly versions for a day, daily ones for a month and monthly ones
+//! forever is `{"hourly": 24, "daily": 30, "monthly": "forever"}`. A version
+//! is kept if any period keeps it, and the head and the latest published
+//! version are always kept. Versions are dated by when this node logged
+//! them, which for synced versions is when they arrived.
+//!
+//! Pruning a version deletes the blobs of its tree (directory nodes, files
+//! and their chunks) that no kept version of the bucket references, nor the
+//! pins of any other bucket. The version's manifest and log entry stay, so
+//! the chain still verifies and syncs, but its content can't be read here
+//! anymore. Pruned versions are recorded in the database and left alone
+//! afterwards, and their pins aren't downloaded again. Only this node's copy
+//! is pruned: peers keep theirs, and since every version's pins list all
+//! blobs the bucket ever held, downloading the pins of a new head fetches
+//! pruned blobs again.
+//!
+//! On S3 blob storage, a policy can also move what versions it keeps to
+//! colder storage classes: blobs that neither the head nor the latest
+//! published version references go to infrequent access
+//! `infrequent_access_after` days after the first version without them, and
+//! to the archive class `archive_after` days after. Blobs other buckets
+//! reference stay in the standard class, and blobs a newer version
+//! references again move back to it. Archived blobs have to be restored
+//! before they can be read, which takes hours: reading one requests the
+//! restore and fails with a 503 saying so until it's done. A policy can move
+//! blobs without pruning anything.
+//!
+//! Policies are stored in the database and applied every
+//! [`RETENTION_INTERVAL`] by a background task, or on request
+//! (`/api/v0/bucket/retention/run`). Pruning is audited as
+//! `bucket.retention.prune`, moving blobs between storage classes as
+//! `bucket.retention.transition`.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, Local, Timelike};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use common::bucket_log::BucketLogProvider;
+use common::linked_data::{Hash, Link};
+use common::mount::{ChunkList, Mount, MountError, NodeLink};
+use common::peer::{BlobsStoreError, Peer};
+use object_store::StorageClass;
+
+use crate::audit::{Actor, AuditEvent, AuditLog};
+use crate::database::{BucketLogEntry, Database};
+
+/// How often retention policies are applied
+pub const RETENTION_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// How many periods a retention policy keeps a version of
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Keep {
+    /// The last N periods, counting the current one
+    Last(u32),
+    Forever,
+}
+
+impl fmt::Display for Keep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Keep::Last(periods) => write!(f, "{}", periods),
+            Keep::Forever => write!(f, "forever"),
+        }
+    }
+}
+
+impl FromStr for Keep {
+    type Err = RetentionPolicyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "forever" => Ok(Keep::Forever),
+            _ => s
+                .parse()
+                .ok()
+                .filter(|periods| *periods > 0)
+                .map(Keep::Last)
+                .ok_or_else(|| RetentionPolicyError::InvalidKeep(s.to_string())),
+        }
+    }
+}
+
+/// A number of periods, or `"forever"`
+impl Serialize for Keep {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Keep::Last(periods) => serializer.serialize_u32(*periods),
+            Keep::Forever => serializer.serialize_str("forever"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Keep {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Periods(u32),
+            Word(String),
+        }
+        match Repr::deserialize(deserializer)? {
+            Repr::Periods(periods) => Ok(Keep::Last(periods)),
+            Repr::Word(word) => word.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+/// Which versions of a bucket are kept, by period, and when blobs only old
+/// versions reference move to colder storage
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hourly: Option<Keep>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daily: Option<Keep>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weekly: Option<Keep>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monthly: Option<Keep>,
+    /// Days until blobs only old versions reference move to the
+    /// infrequent-access storage class
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub infrequent_access_after: Option<u32>,
+    /// Days until they move to the archive storage class
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive_after: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Period {
+    Hour,
+    Day,
+    Week,
+    Month,
+}
+
+impl Period {
+    /// Number of the period a local time falls in, counting up over time
+    fn index(self, time: DateTime<Local>) -> i64 {
+        let days = time.num_days_from_ce() as i64;
+        match self {
+            Period::Hour => days * 24 + time.hour() as i64,
+            Period::Day => days,
+            // Day 1 of the common era is a Monday
+            Period::Week => (days - 1).div_euclid(7),
+            Period::Month => time.year() as i64 * 12 + time.month0() as i64,
+        }
+    }
+}
+
+impl RetentionPolicy {
+    pub fn validate(&self) -> Result<(), RetentionPolicyError> {
+        let tiers = self.tiers();
+        if tiers.is_empty() && !self.moves_blobs() {
+            return Err(RetentionPolicyError::Empty);
+        }
+        if tiers.iter().any(|(_, keep)| *keep == Keep::Last(0)) {
+            return Err(RetentionPolicyError::InvalidKeep("0".to_string()));
+        }
+        if let (Some(infrequent), Some(archive)) =
+            (self.infrequent_access_after, self.archive_after)
+        {
+            if archive <= infrequent {
+                return Err(RetentionPolicyError::ArchiveBeforeInfrequentAccess);
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the policy prunes versions, rather than only moving blobs
+    pub fn prunes(&self) -> bool {
+        !self.tiers().is_empty()
+    }
+
+    /// Whether the policy moves blobs to colder storage classes
+    pub fn moves_blobs(&self) -> bool {
+        self.infrequent_access_after.is_some() || self.archive_after.is_some()
+    }
+
+    /// Storage class of a blob that stopped being current `age` ago, or is
+    /// current with `None`
+    pub fn storage_class(&self, age: Option<time::Duration>) -> StorageClass {
+        let Some(days) = age.map(|age| age.whole_days()) else {
+            return StorageClass::Standard;
+        };
+        let reached = |after: Option<u32>| after.is_some_and(|after| days >= after as i64);
+        if reached(self.archive_after) {
+            StorageClass::Archive
+        } else if reached(self.infrequent_access_after) {
+            StorageClass::InfrequentAccess
+        } else {
+            StorageClass::Standard
+        }
+    }
+
+    fn tiers(&self) -> Vec<(Period, Keep)> {
+        [
+            (Period::Hour, self.hourly),
+            (Period::Day, self.daily),
+            (Period::Week, self.weekly),
+            (Period::Month, self.monthly),
+        ]
+        .into_iter()
+        .filter_map(|(period, keep)| Some((period, keep?)))
+        .collect()
+    }
+
+    /// Heights of the versions the policy keeps at `now`, given the height
+    /// and time of every version
+    pub fn kept(&self, versions: &[(u64, OffsetDateTime)], now: DateTime<Local>) -> BTreeSet<u64> {
+        let mut newest_first: Vec<(u64, DateTime<Local>)> = versions
+            .iter()
+            .filter_map(|(height, at)| {
+                let at = DateTime::from_timestamp(at.unix_timestamp(), 0)?;
+                Some((*height, at.with_timezone(&Local)))
+            })
+            .collect();
+        newest_first.sort_by(|a, b| b.1.cmp(&a.1).then(b.0.cmp(&a.0)));
+
+        let mut kept = BTreeSet::new();
+        for (period, keep) in self.tiers() {
+            let current = period.index(now);
+            let mut seen = HashSet::new();
+            for (height, at) in &newest_first {
+                let index = period.index(*at);
+                if let Keep::Last(periods) = keep {
+                    if current - index >= periods as i64 {
+                        break;
+                    }
+                }
+                if seen.insert(index) {
+                    kept.insert(*height);
+                }
+            }
+        }
+        kept
+    }
+}
+
+impl fmt::Display for RetentionPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tiers: Vec<String> = [
+            ("hourly", self.hourly),
+            ("daily", self.daily),
+            ("weekly", self.weekly),
+            ("monthly", self.monthly),
+        ]
+        .into_iter()
+        .filter_map(|(name, keep)| Some(format!("{} {}", name, keep?)))
+        .chain(
+            [
+                ("infrequent access", self.infrequent_access_after),
+                ("archive", self.archive_after),
+            ]
+            .into_iter()
+            .filter_map(|(class, days)| Some(format!("{} after {} days", class, days?))),
+        )
+        .collect();
+        f.write_str(&tiers.join(", "))
+    }
+}
+
+/// What applying a retention policy pruned and moved, or would
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PruneReport {
+    pub bucket_id: Uuid,
+    /// Heights of the versions pruned, oldest first
+    pub pruned_versions: Vec<u64>,
+    /// Blobs deleted from this node
+    pub deleted_blobs: u64,
+    pub freed_bytes: u64,
+    /// Blobs moved to another storage class
+    #[serde(default)]
+    pub transitioned_blobs: u64,
+    /// Archived blobs that can't move back to the standard class until
+    /// their restore is done
+    #[serde(default)]
+    pub restoring_blobs: u64,
+}
+
+/// Apply a bucket's retention policy: prune the versions it doesn't keep,
+/// then move the blobs of the rest between storage classes. With `dry_run`,
+/// only report what would be pruned and moved.
+pub async fn prune(
+    peer: &Peer<Database>,
+    database: &Database,
+    bucket_id: Uuid,
+    policy: &RetentionPolicy,
+    dry_run: bool,
+) -> Result<PruneReport, RetentionError> {
+    let mut report = PruneReport {
+        bucket_id,
+        pruned_versions: Vec::new(),
+        deleted_blobs: 0,
+        freed_bytes: 0,
+        transitioned_blobs: 0,
+        restoring_blobs: 0,
+    };
+    let logs = database.get_all_bucket_logs(&bucket_id).await?;
+    if logs.is_empty() {
+        return Ok(report);
+    }
+    if policy.prunes() {
+        prune_versions(peer, database, &logs, policy, dry_run, &mut report).await?;
+    }
+    if policy.moves_blobs() {
+        transition(peer, database, &logs, policy, dry_run, &mut report).await?;
+    }
+    Ok(report)
+}
+
+/// Prune the versions a policy doesn't keep
+async fn prune_versions(
+    peer: &Peer<Database>,
+    database: &Database,
+    logs: &[BucketLogEntry],
+    policy: &RetentionPolicy,
+    dry_run: bool,
+    report: &mut PruneReport,
+) -> Result<(), RetentionError> {
+    let bucket_id = report.bucket_id;
+    let Some(head) = logs.last() else {
+        return Ok(());
+    };
+
+    let versions: Vec<(u64, OffsetDateTime)> = logs
+        .iter()
+        .map(|entry| (entry.height, entry.created_at))
+        .collect();
+    let mut keep = policy.kept(&versions, Local::now());
+    keep.insert(head.height);
+    if let Some(published) = logs.iter().rev().find(|entry| entry.published) {
+        keep.insert(published.height);
+    }
+    let pruned = database.pruned_versions(&bucket_id).await?;
+    let (prunable, kept): (Vec<_>, Vec<_>) = logs
+        .iter()
+        .filter(|entry| !pruned.contains(&entry.height))
+        .partition(|entry| !keep.contains(&entry.height));
+    if prunable.is_empty() {
+        return Ok(());
+    }
+
+    // The head is always readable; kept versions that aren't here have
+    //  nothing here to protect
+    let mut protected = version_blobs(peer, &head.current_link).await?;
+    for entry in kept.iter().filter(|entry| entry.height != head.height) {
+        match version_blobs(peer, &entry.current_link).await {
+            Ok(hashes) => protected.extend(hashes),
+            Err(e) => tracing::debug!(
+                "Version {} of bucket {} isn't here, not protecting its blobs: {}",
+                entry.height,
+                bucket_id,
+                e
+            ),
+        }
+    }
+    protected.extend(other_buckets_pins(peer, bucket_id).await?);
+
+    let mut doomed = HashSet::new();
+    for entry in &prunable {
+        match version_blobs(peer, &entry.current_link).await {
+            Ok(hashes) => doomed.extend(hashes.into_iter().filter(|h| !protected.contains(h))),
+            Err(e) => tracing::debug!(
+                "Version {} of bucket {} isn't here, nothing to prune: {}",
+                entry.height,
+                bucket_id,
+                e
+            ),
+        }
+    }
+    let mut present = Vec::new();
+    for hash in doomed {
+        if let Some(size) = peer.blobs().size(&hash).await? {
+            report.freed_bytes += size;
+            present.push(hash);
+        }
+    }
+    report.deleted_blobs = present.len() as u64;
+    report.pruned_versions = prunable.iter().map(|entry| entry.height).collect();
+    if dry_run {
+        return Ok(());
+    }
+
+    if !present.is_empty() {
+        // Nothing kept references these, so don't let the store hold on to
+        //  ones it wrote recently
+        peer.blobs().force_delete(present).await?;
+    }
+    let pruned: Vec<(u64, Link)> = prunable
+        .iter()
+        .map(|entry| (entry.height, entry.current_link.clone()))
+        .collect();
+    database.record_pruned_versions(&bucket_id, &pruned).await?;
+    tracing::info!(
+        "Pruned {} version(s) of bucket {}, deleting {} blob(s) ({} bytes)",
+        report.pruned_versions.len(),
+        bucket_id,
+        report.deleted_blobs,
+        report.freed_bytes
+    );
+    Ok(())
+}
+
+/// Move the blobs of the versions left to the storage class the policy puts
+/// them in, by how long ago they stopped being current: since the version
+/// after the newest one referencing them was logged
+async fn transition(
+    peer: &Peer<Database>,
+    database: &Database,
+    logs: &[BucketLogEntry],
+    policy: &RetentionPolicy,
+    dry_run: bool,
+    report: &mut PruneReport,
+) -> Result<(), RetentionError> {
+    let bucket_id = report.bucket_id;
+    let mut pruned = database.pruned_versions(&bucket_id).await?;
+    // A dry run hasn't pruned what it reports
+    pruned.extend(report.pruned_versions.iter().copied());
+    let published = logs.iter().rev().find(|entry| entry.published);
+
+    // When each blob stopped being current, None for current ones
+    let mut superseded: HashMap<Hash, Option<OffsetDateTime>> = HashMap::new();
+    for (entry, next) in logs.iter().zip(logs.iter().skip(1).map(Some).chain([None])) {
+        if pruned.contains(&entry.height) {
+            continue;
+        }
+        let current = next.is_none() || published.is_some_and(|p| p.height == entry.height);
+        let since = if current {
+            None
+        } else {
+            next.map(|next| next.created_at)
+        };
+        let hashes = match version_blobs(peer, &entry.current_link).await {
+            Ok(hashes) => hashes,
+            Err(e) => {
+                tracing::debug!(
+                    "Version {} of bucket {} isn't here, nothing to move: {}",
+                    entry.height,
+                    bucket_id,
+                    e
+                );
+                continue;
+            }
+        };
+        for hash in hashes {
+            let at = superseded.entry(hash).or_insert(since);
+            *at = match (*at, since) {
+                (Some(a), Some(b)) => Some(a.max(b)),
+                _ => None,
+            };
+        }
+    }
+
+    // Content other buckets reference stays in the standard class
+    let shared = other_buckets_pins(peer, bucket_id).await?;
+    let now = OffsetDateTime::now_utc();
+    for (hash, since) in superseded {
+        let class = if shared.contains(&hash) {
+            StorageClass::Standard
+        } else {
+            policy.storage_class(since.map(|since| now - since))
+        };
+        if dry_run {
+            if peer
+                .blobs()
+                .storage_class(&hash)
+                .await?
+                .is_some_and(|current| current != class)
+            {
+                report.transitioned_blobs += 1;
+            }
+            continue;
+        }
+        match peer.blobs().set_storage_class(&hash, class).await {
+            Ok(true) => report.transitioned_blobs += 1,
+            Ok(false) => {}
+            Err(e) if e.archived().is_some() => report.restoring_blobs += 1,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    if report.transitioned_blobs > 0 && !dry_run {
+        tracing::info!(
+            "Moved {} blob(s) of bucket {} between storage classes",
+            report.transitioned_blobs,
+            bucket_id
+        );
+    }
+    Ok(())
+}
+
+/// Blobs of a version's tree: its directory nodes, files and their chunks.
+/// Manifests, pins and ops logs aren't part of it.
+async fn version_blobs(peer: &Peer<Database>, link: &Link) -> Result<HashSet<Hash>, MountError> {
+    let blobs = peer.blobs();
+    let manifest = Mount::load_manifest(link, blobs).await?;
+    let mount = Mount::load(link, peer.secret(), blobs).await?;
+
+    let mut hashes = HashSet::from([manifest.entry().hash()]);
+    for node_link in mount.ls_deep(Path::new("/")).await?.values() {
+        // Referenced buckets keep their versions under their own policies
+        if node_link.is_bucket() {
+            continue;
+        }
+        hashes.insert(node_link.link().hash());
+        if let NodeLink::Data(link, secret, data) = node_link {
+            if data.is_chunked() {
+                let list = ChunkList::load(blobs, link, secret).await?;
+                hashes.extend(list.chunks().iter().map(|chunk| chunk.link().hash()));
+            }
+        }
+    }
+    Ok(hashes)
+}
+
+/// Every blob other buckets ever referenced, as far as their pins are here
+async fn other_buckets_pins(
+    peer: &Peer<Database>,
+    bucket_id: Uuid,
+) -> Result<HashSet<Hash>, RetentionError> {
+    let bucket_ids = BucketLogProvider::list_buckets(peer.logs())
+        .await
+        .map_err(|e| RetentionError::BucketLog(e.to_string()))?;
+
+    let mut hashes = HashSet::new();
+    for other in bucket_ids.into_iter().filter(|id| *id != bucket_id) {
+        let Ok((link, _)) = peer.logs().head(other, None).await else {
+            continue;
+        };
+        let Ok(manifest) = Mount::load_manifest(&link, peer.blobs()).await else {
+            continue;
+        };
+        if let Ok(pins) = peer.blobs().read_hash_list(manifest.pins().hash()).await {
+            hashes.extend(pins);
+        }
+    }
+    Ok(hashes)
+}
+
+/// Apply every stored retention policy every [`RETENTION_INTERVAL`] until
+/// the daemon exits
+pub(crate) async fn run(peer: Peer<Database>, database: Database, audit: AuditLog) {
+    let mut interval = tokio::time::interval(RETENTION_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            _ = peer.cancelled() => return,
+        }
+        let policies = match database.list_retention_policies().await {
+            Ok(policies) => policies,
+            Err(e) => {
+                tracing::error!("Failed to load retention policies: {}", e);
+                continue;
+            }
+        };
+        for (bucket_id, policy) in policies {
+            match prune(&peer, &database, bucket_id, &policy, false).await {
+                Ok(report) => audit_prune(&audit, Actor::Retention, &report).await,
+                Err(e) => tracing::warn!(
+                    "Failed to apply the retention policy of bucket {}: {}",
+                    bucket_id,
+                    e
+                ),
+            }
+        }
+    }
+}
+
+/// Audit a prune that pruned or moved anything
+pub(crate) async fn audit_prune(audit: &AuditLog, actor: Actor, report: &PruneReport) {
+    if report.transitioned_blobs > 0 {
+        audit
+            .record(
+                AuditEvent::new(actor.clone(), "bucket.retention.transition")
+                    .bucket(report.bucket_id)
+                    .detail(format!(
+                        "{} blob(s), {} being restored",
+                        report.transitioned_blobs, report.restoring_blobs
+                    )),
+            )
+            .await;
+    }
+    let (Some(first), Some(last)) = (
+        report.pruned_versions.first(),
+        report.pruned_versions.last(),
+    ) else {
+        return;
+    };
+    audit
+        .record(
+            AuditEvent::new(actor, "bucket.retention.prune")
+                .bucket(report.bucket_id)
+                .detail(format!(
+                    "{} version(s) between heights {} and {}, {} blob(s), {} bytes",
+                    report.pruned_versions.len(),
+                    first,
+                    last,
+                    report.deleted_blobs,
+                    report.freed_bytes
+                )),
+        )
+        .await;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RetentionPolicyError {
+    #[error(
+        "A retention policy needs to keep hourly, daily, weekly or monthly versions, \
+         or move old blobs to colder storage"
+    )]
+    Empty,
+    #[error("Blobs can only be archived after they move to infrequent access")]
+    ArchiveBeforeInfrequentAccess,
+    #[error("Moving blobs to colder storage classes needs an S3 blob store")]
+    StorageClassesUnsupported,
+    #[error("Invalid number of periods to keep: {0} (a positive number or `forever`)")]
+    InvalidKeep(String),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RetentionError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Bucket log error: {0}")]
+    BucketLog(String),
+    #[error("Mount error: {0}")]
+    Mount(#[from] MountError),
+    #[error("Blobs error: {0}")]
+    Blobs(#[from] BlobsStoreError),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(s: &str) -> DateTime<Local> {
+        let naive = chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+        Local.from_local_datetime(&naive).earliest().unwrap()
+    }
+
+    fn versions(times: &[&str]) -> Vec<(u64, OffsetDateTime)> {
+        times
+            .iter()
+            .enumerate()
+            .map(|(height, s)| {
+                let time = OffsetDateTime::from_unix_timestamp(at(s).timestamp()).unwrap();
+                (height as u64, time)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_kept() {
+        let history = versions(&[
+            "2026-01-15 12:00",
+            "2026-01-20 12:00",
+            "2026-02-10 09:00",
+            "2026-03-09 08:00",
+            "2026-03-10 08:10",
+            "2026-03-10 08:50",
+            "2026-03-10 09:30",
+            "2026-03-10 09:40",
+        ]);
+        let now = at("2026-03-10 10:00");
+
+        let hourly = RetentionPolicy {
+            hourly: Some(Keep::Last(2)),
+            ..Default::default()
+        };
+        // Hours 09 and 10 are the last two; 10 has no versions
+        assert_eq!(hourly.kept(&history, now), BTreeSet::from([7]));
+
+        let daily = RetentionPolicy {
+            daily: Some(Keep::Last(2)),
+            ..Default::default()
+        };
+        assert_eq!(daily.kept(&history, now), BTreeSet::from([3, 7]));
+
+        let monthly = RetentionPolicy {
+            monthly: Some(Keep::Forever),
+            ..Default::default()
+        };
+        assert_eq!(monthly.kept(&history, now), BTreeSet::from([1, 2, 7]));
+
+        // Tiers add up
+        let combined = RetentionPolicy {
+            hourly: Some(Keep::Last(3)),
+            daily: Some(Keep::Last(2)),
+            monthly: Some(Keep::Forever),
+            ..Default::default()
+        };
+        assert_eq!(
+            combined.kept(&history, now),
+            BTreeSet::from([1, 2, 3, 5, 7])
+        );
+    }
+
+    #[test]
+    fn test_weeks_start_on_monday() {
+        // 2026-03-09 is a Monday
+        let monday = at("2026-03-09 00:10");
+        let sunday = at("2026-03-08 23:50");
+        assert_eq!(
+            Period::Week.index(monday),
+            Period::Week.index(at("2026-03-15 23:00"))
+        );
+        assert_eq!(Period::Week.index(monday) - Period::Week.index(sunday), 1);
+    }
+
+    #[test]
+    fn test_policy_serde() {
+        let policy: RetentionPolicy =
+            serde_json::from_str(r#"{"hourly": 24, "daily": 30, "monthly": "forever"}"#).unwrap();
+        assert_eq!(policy.hourly, Some(Keep::Last(24)));
+        assert_eq!(policy.weekly, None);
+        assert_eq!(policy.monthly, Some(Keep::Forever));
+        assert_eq!(policy.to_string(), "hourly 24, daily 30, monthly forever");
+        assert_eq!(
+            serde_json::to_string(&policy).unwrap(),
+            r#"{"hourly":24,"daily":30,"monthly":"forever"}"#
+        );
+
+        assert!(RetentionPolicy::default().validate().is_err());
+        assert!(serde_json::from_str::<RetentionPolicy>(r#"{"daily": "always"}"#).is_err());
+        assert!("0".parse::<Keep>().is_err());
+    }
+
+    #[test]
+    fn test_storage_classes() {
+        let policy: RetentionPolicy =
+            serde_json::from_str(r#"{"infrequent_access_after": 30, "archive_after": 180}"#)
+                .unwrap();
+        policy.validate().unwrap();
+        assert!(!policy.prunes());
+        assert_eq!(
+            policy.to_string(),
+            "infrequent access after 30 days, archive after 180 days"
+        );
+
+        let days = |days: i64| Some(time::Duration::days(days));
+        assert_eq!(policy.storage_class(None), StorageClass::Standard);
+        assert_eq!(policy.storage_class(days(29)), StorageClass::Standard);
+        assert_eq!(
+            policy.storage_class(days(30)),
+            StorageClass::InfrequentAccess
+        );
+        assert_eq!(policy.storage_class(days(400)), StorageClass::Archive);
+
+        let archive_only = RetentionPolicy {
+            archive_after: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(archive_only.storage_class(days(0)), StorageClass::Archive);
+
+        let backwards = RetentionPolicy {
+            infrequent_access_after: Some(90),
+            archive_after: Some(30),
+            ..Default::default()
+        };
+        assert!(matches!(
+            backwards.validate(),
+            Err(RetentionPolicyError::ArchiveBeforeInfrequentAccess)
+        ));
+    }
+}
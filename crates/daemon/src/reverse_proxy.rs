@@ -0,0 +1,391 @@
+//! Serving behind a reverse proxy
+//!
+//! A daemon behind nginx, Caddy or a load balancer only sees connections from
+//! the proxy, over plain HTTP, to whatever host it was proxied to. Proxies
+//! listed in `config.toml` are trusted to tell it better:
+//!
+//! ```toml
+//! [reverse_proxy]
+//! trusted_proxies = ["127.0.0.1", "10.0.0.0/8"]
+//! path_prefix = "/jax"
+//! ```
+//!
+//! Requests from a trusted proxy are taken to come from the client named in
+//! `X-Forwarded-For`, over the scheme in `X-Forwarded-Proto`, to the host in
+//! `X-Forwarded-Host`. These headers are ignored from anyone else, so clients
+//! can't claim another address. The client address is logged with each
+//! request, ready for anything that limits requests per client.
+//!
+//! With a `path_prefix`, the API and gateway are served under it, e.g. the
+//! gateway at `https://example.com/jax/gw/<bucket-id>`. The prefix is stripped
+//! from incoming paths (proxies that strip it themselves work too) and put back
+//! on the links the gateway generates.
+
+use std::net::{IpAddr, Ipv6Addr};
+use std::str::FromStr;
+
+use http::{header, HeaderMap};
+use serde::{Deserialize, Serialize};
+
+pub const FORWARDED_FOR_HEADER: &str = "x-forwarded-for";
+pub const FORWARDED_PROTO_HEADER: &str = "x-forwarded-proto";
+pub const FORWARDED_HOST_HEADER: &str = "x-forwarded-host";
+
+/// The `[reverse_proxy]` section of `config.toml`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReverseProxyConfig {
+    /// Addresses (`10.0.0.1`) or ranges (`10.0.0.0/8`) of the proxies whose
+    /// forwarded headers are trusted
+    #[serde(default)]
+    pub trusted_proxies: Vec<String>,
+    /// Path the daemon is served under, e.g. `/jax`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path_prefix: Option<String>,
+}
+
+/// Where a request came from and the URL it was made to, as resolved for
+/// each request by the HTTP servers
+#[derive(Debug, Clone)]
+pub struct RequestOrigin {
+    /// The client, past any trusted proxies; unknown when the connection's
+    /// address isn't
+    pub client_ip: Option<IpAddr>,
+    /// Scheme, host and path prefix the request was made to, e.g.
+    /// `https://example.com/jax`
+    pub base_url: String,
+    /// The path prefix alone, empty when there is none
+    pub base_path: String,
+}
+
+/// A parsed [`ReverseProxyConfig`]
+#[derive(Debug, Clone, Default)]
+pub struct ReverseProxy {
+    trusted: Vec<IpRange>,
+    /// Without a trailing slash, empty when there is none
+    path_prefix: String,
+}
+
+impl ReverseProxy {
+    pub fn new(config: &ReverseProxyConfig) -> Result<Self, ReverseProxyError> {
+        let trusted = config
+            .trusted_proxies
+            .iter()
+            .map(|proxy| proxy.parse())
+            .collect::<Result<_, _>>()?;
+        let path_prefix = match &config.path_prefix {
+            Some(prefix) => normalize_prefix(prefix)?,
+            None => String::new(),
+        };
+        Ok(Self {
+            trusted,
+            path_prefix,
+        })
+    }
+
+    pub fn path_prefix(&self) -> &str {
+        &self.path_prefix
+    }
+
+    pub fn is_trusted(&self, ip: IpAddr) -> bool {
+        let ip = canonical(ip);
+        self.trusted.iter().any(|range| range.contains(ip))
+    }
+
+    /// Resolve where a request from `peer` came from. Without the peer's
+    /// address no forwarded headers are trusted.
+    pub fn origin(&self, peer: Option<IpAddr>, headers: &HeaderMap) -> RequestOrigin {
+        let trusted = peer.is_some_and(|peer| self.is_trusted(peer));
+        let forwarded = |name| trusted.then(|| first_value(headers, name)).flatten();
+
+        let host = forwarded(FORWARDED_HOST_HEADER)
+            .or_else(|| headers.get(header::HOST).and_then(|h| h.to_str().ok()))
+            .unwrap_or("localhost");
+        let scheme =
+            forwarded(FORWARDED_PROTO_HEADER).filter(|proto| matches!(*proto, "http" | "https"));
+        let base_url = match scheme {
+            Some(scheme) => format!("{}://{}", scheme, host),
+            // Hosts sometimes carry their scheme
+            None if host.starts_with("http://") || host.starts_with("https://") => host.to_string(),
+            // Assume https for production, http for localhost
+            None if host.contains("localhost") || host.starts_with("127.0.0.1") => {
+                format!("http://{}", host)
+            }
+            None => format!("https://{}", host),
+        };
+
+        RequestOrigin {
+            client_ip: peer.map(|peer| self.client_ip(peer, headers)),
+            base_url: format!("{}{}", base_url.trim_end_matches('/'), self.path_prefix),
+            base_path: self.path_prefix.clone(),
+        }
+    }
+
+    /// The client behind `peer`: the peer itself, unless it is a trusted
+    /// proxy, then the nearest address in `X-Forwarded-For` that isn't one
+    pub fn client_ip(&self, peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+        let mut client = canonical(peer);
+        if !self.is_trusted(client) {
+            return client;
+        }
+        // Each proxy appends the address it got the request from
+        let hops: Vec<&str> = headers
+            .get_all(FORWARDED_FOR_HEADER)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .collect();
+        for hop in hops.into_iter().rev() {
+            let Some(ip) = parse_hop(hop) else {
+                break;
+            };
+            client = ip;
+            if !self.is_trusted(ip) {
+                break;
+            }
+        }
+        client
+    }
+
+    /// `path` without the path prefix, if it starts with it
+    pub fn strip_prefix<'a>(&self, path: &'a str) -> Option<&'a str> {
+        if self.path_prefix.is_empty() {
+            return None;
+        }
+        match path.strip_prefix(self.path_prefix.as_str())? {
+            "" => Some("/"),
+            rest if rest.starts_with('/') => Some(rest),
+            _ => None,
+        }
+    }
+}
+
+/// An address or CIDR range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct IpRange {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpRange {
+    fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix_len as u32);
+                let mask = mask.unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix_len as u32);
+                let mask = mask.unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for IpRange {
+    type Err = ReverseProxyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ReverseProxyError::InvalidProxy(s.to_string());
+        let (network, prefix_len) = match s.trim().split_once('/') {
+            Some((network, len)) => (network, Some(len)),
+            None => (s.trim(), None),
+        };
+        let network = canonical(network.parse().map_err(|_| invalid())?);
+        let max_len = if network.is_ipv4() { 32 } else { 128 };
+        let prefix_len = match prefix_len {
+            Some(len) => len.parse().ok().filter(|len| *len <= max_len),
+            None => Some(max_len),
+        }
+        .ok_or_else(invalid)?;
+        Ok(Self {
+            network,
+            prefix_len,
+        })
+    }
+}
+
+/// IPv4 clients of dual-stack listeners show up as mapped IPv6 addresses
+fn canonical(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => v6
+            .to_ipv4_mapped()
+            .map(IpAddr::V4)
+            .unwrap_or(IpAddr::V6(v6)),
+        ip => ip,
+    }
+}
+
+/// An address in `X-Forwarded-For`, which some proxies write with a port
+fn parse_hop(hop: &str) -> Option<IpAddr> {
+    let hop = hop.trim();
+    if let Ok(ip) = hop.parse() {
+        return Some(canonical(ip));
+    }
+    if let Ok(addr) = hop.parse::<std::net::SocketAddr>() {
+        return Some(canonical(addr.ip()));
+    }
+    let bracketed = hop.strip_prefix('[')?.strip_suffix(']')?;
+    bracketed
+        .parse::<Ipv6Addr>()
+        .ok()
+        .map(|ip| canonical(ip.into()))
+}
+
+/// The first of a header's comma separated values, the one set by the proxy
+/// nearest the client
+fn first_value<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    let value = headers.get(name)?.to_str().ok()?;
+    let first = value.split(',').next()?.trim();
+    (!first.is_empty()).then_some(first)
+}
+
+/// `/jax/` and `jax` both become `/jax`, `/` becomes no prefix at all
+fn normalize_prefix(prefix: &str) -> Result<String, ReverseProxyError> {
+    let trimmed = prefix.trim().trim_matches('/');
+    if trimmed.is_empty() {
+        return Ok(String::new());
+    }
+    let valid = trimmed.split('/').all(|segment| {
+        !segment.is_empty()
+            && segment != "."
+            && segment != ".."
+            && segment
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-._~".contains(c))
+    });
+    if !valid {
+        return Err(ReverseProxyError::InvalidPrefix(prefix.to_string()));
+    }
+    Ok(format!("/{}", trimmed))
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReverseProxyError {
+    #[error("invalid trusted proxy {0}, expected an address or a CIDR range")]
+    InvalidProxy(String),
+    #[error("invalid path prefix {0}")]
+    InvalidPrefix(String),
+}
+
+#[cfg(test)]
+mod test {
+    use http::HeaderValue;
+
+    use super::*;
+
+    fn proxy(trusted: &[&str], path_prefix: Option<&str>) -> ReverseProxy {
+        ReverseProxy::new(&ReverseProxyConfig {
+            trusted_proxies: trusted.iter().map(|p| p.to_string()).collect(),
+            path_prefix: path_prefix.map(str::to_string),
+        })
+        .unwrap()
+    }
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(*name, HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_trusted_ranges() {
+        let proxy = proxy(&["10.0.0.0/8", "192.168.1.7", "fd00::/8"], None);
+        assert!(proxy.is_trusted(ip("10.20.30.40")));
+        assert!(proxy.is_trusted(ip("192.168.1.7")));
+        assert!(!proxy.is_trusted(ip("192.168.1.8")));
+        assert!(proxy.is_trusted(ip("fd12::1")));
+        assert!(!proxy.is_trusted(ip("2001:db8::1")));
+        // IPv4 peers of an IPv6 listener
+        assert!(proxy.is_trusted(ip("::ffff:10.1.2.3")));
+
+        assert!(proxy.is_trusted(ip("10.0.0.1")));
+        assert!("0.0.0.0/0"
+            .parse::<IpRange>()
+            .unwrap()
+            .contains(ip("8.8.8.8")));
+        for invalid in ["10.0.0.0/33", "proxy.local", "::1/129", "10.0.0.1/"] {
+            assert!(invalid.parse::<IpRange>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_client_ip() {
+        let proxy = proxy(&["10.0.0.0/8"], None);
+        let forwarded = headers(&[(FORWARDED_FOR_HEADER, "203.0.113.9, 10.0.0.2")]);
+
+        // Through two trusted proxies
+        assert_eq!(
+            proxy.client_ip(ip("10.0.0.1"), &forwarded),
+            ip("203.0.113.9")
+        );
+        // Anyone else can't claim an address
+        assert_eq!(
+            proxy.client_ip(ip("198.51.100.1"), &forwarded),
+            ip("198.51.100.1")
+        );
+        // Addresses the client sent itself are left of the first untrusted hop
+        let spoofed = headers(&[(FORWARDED_FOR_HEADER, "1.2.3.4, 203.0.113.9")]);
+        assert_eq!(proxy.client_ip(ip("10.0.0.1"), &spoofed), ip("203.0.113.9"));
+        // Repeated headers and ports
+        let split = headers(&[
+            (FORWARDED_FOR_HEADER, "[2001:db8::7]:4711"),
+            (FORWARDED_FOR_HEADER, "10.0.0.3:80"),
+        ]);
+        assert_eq!(proxy.client_ip(ip("10.0.0.1"), &split), ip("2001:db8::7"));
+        // A garbled hop stops the walk at the last trusted proxy
+        let garbled = headers(&[(FORWARDED_FOR_HEADER, "unknown, 10.0.0.5")]);
+        assert_eq!(proxy.client_ip(ip("10.0.0.1"), &garbled), ip("10.0.0.5"));
+    }
+
+    #[test]
+    fn test_origin() {
+        let proxy = proxy(&["127.0.0.1"], Some("/jax/"));
+        let forwarded = headers(&[
+            ("host", "127.0.0.1:8080"),
+            (FORWARDED_PROTO_HEADER, "https"),
+            (FORWARDED_HOST_HEADER, "files.example.com"),
+            (FORWARDED_FOR_HEADER, "203.0.113.9"),
+        ]);
+
+        let origin = proxy.origin(Some(ip("127.0.0.1")), &forwarded);
+        assert_eq!(origin.base_url, "https://files.example.com/jax");
+        assert_eq!(origin.base_path, "/jax");
+        assert_eq!(origin.client_ip, Some(ip("203.0.113.9")));
+
+        let origin = proxy.origin(Some(ip("198.51.100.1")), &forwarded);
+        assert_eq!(origin.base_url, "http://127.0.0.1:8080/jax");
+        assert_eq!(origin.client_ip, Some(ip("198.51.100.1")));
+
+        let direct = ReverseProxy::default();
+        let origin = direct.origin(None, &headers(&[("host", "gw.example")]));
+        assert_eq!(origin.base_url, "https://gw.example");
+        assert_eq!(origin.base_path, "");
+        assert_eq!(origin.client_ip, None);
+    }
+
+    #[test]
+    fn test_strip_prefix() {
+        let proxy = proxy(&[], Some("jax"));
+        assert_eq!(proxy.path_prefix(), "/jax");
+        assert_eq!(proxy.strip_prefix("/jax"), Some("/"));
+        assert_eq!(proxy.strip_prefix("/jax/gw/abc"), Some("/gw/abc"));
+        assert_eq!(proxy.strip_prefix("/jaxx/gw"), None);
+        assert_eq!(proxy.strip_prefix("/gw/abc"), None);
+        assert_eq!(ReverseProxy::default().strip_prefix("/jax"), None);
+
+        assert_eq!(normalize_prefix("/").unwrap(), "");
+        assert_eq!(normalize_prefix("/a/b/").unwrap(), "/a/b");
+        assert!(normalize_prefix("/a//b").is_err());
+        assert!(normalize_prefix("/../b").is_err());
+        assert!(normalize_prefix("/a?b").is_err());
+    }
+}
@@ -0,0 +1,136 @@
+//! Crawler controls for published buckets
+//!
+//! The gateway answers `/robots.txt` itself. It keeps crawlers out of the
+//! routes that only serve whoever holds a link (signed downloads, drop links
+//! and deploys) and points them at a sitemap for each bucket it lists:
+//! `/gw/<id>/sitemap.xml`, generated from the published version unless the
+//! bucket has a `sitemap.xml` of its own.
+//!
+//! A bucket can be kept out of search results by the gateway operator
+//! (`jax gateway policy set --noindex`) or by its publisher
+//! (`jax bucket publish --noindex true`). Everything served for it then
+//! carries `X-Robots-Tag: noindex` and it has no sitemap. Its URLs are not
+//! disallowed in robots.txt, as a crawler that can't fetch a page never
+//! learns that the page mustn't be indexed.
+
+use std::time::SystemTime;
+
+use chrono::{DateTime, Utc};
+use url::Url;
+
+use crate::signed_url::SIGNED_URL_PREFIX;
+
+pub const ROBOTS_TAG_HEADER: &str = "x-robots-tag";
+pub const NOINDEX: &str = "noindex";
+
+/// Name of the sitemap at the root of a bucket
+pub const SITEMAP_NAME: &str = "sitemap.xml";
+
+/// Most URLs one sitemap may list
+pub const SITEMAP_MAX_URLS: usize = 50_000;
+
+/// The gateway's robots.txt. `host` is the URL the gateway was reached at,
+/// ending in the path prefix it is served under (`base_path`), `gateway_ids`
+/// the buckets to list sitemaps for.
+pub fn robots_txt(host: &str, base_path: &str, gateway_ids: &[String]) -> String {
+    let host = host.trim_end_matches('/');
+    let mut robots = String::from("User-agent: *\n");
+    for prefix in [SIGNED_URL_PREFIX, "/drop", "/deploy"] {
+        robots.push_str(&format!("Disallow: {}{}/\n", base_path, prefix));
+    }
+    if !gateway_ids.is_empty() {
+        robots.push('\n');
+    }
+    for gateway_id in gateway_ids {
+        robots.push_str(&format!(
+            "Sitemap: {}/gw/{}/{}\n",
+            host, gateway_id, SITEMAP_NAME
+        ));
+    }
+    robots
+}
+
+/// A sitemap of a bucket served at `base` (ending in `/`), listing its root
+/// and `paths` relative to it. Only the first [`SITEMAP_MAX_URLS`] are kept.
+pub fn sitemap_xml<I>(base: &Url, paths: I, last_modified: Option<SystemTime>) -> String
+where
+    I: IntoIterator<Item = String>,
+{
+    let lastmod = last_modified.map(|time| {
+        DateTime::<Utc>::from(time)
+            .format("%Y-%m-%dT%H:%M:%S+00:00")
+            .to_string()
+    });
+    let urls = std::iter::once(base.clone()).chain(paths.into_iter().map(|path| {
+        let mut url = base.clone();
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments.pop_if_empty().extend(path.split('/'));
+        }
+        url
+    }));
+
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+    for url in urls.take(SITEMAP_MAX_URLS) {
+        xml.push_str("  <url><loc>");
+        xml.push_str(&escape_xml(url.as_str()));
+        xml.push_str("</loc>");
+        if let Some(lastmod) = &lastmod {
+            xml.push_str(&format!("<lastmod>{}</lastmod>", lastmod));
+        }
+        xml.push_str("</url>\n");
+    }
+    xml.push_str("</urlset>\n");
+    xml
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_robots_txt() {
+        let robots = robots_txt("https://gw.example/", "", &["abc".to_string()]);
+        assert!(robots.starts_with("User-agent: *\n"));
+        assert!(robots.contains("Disallow: /dl/\n"));
+        assert!(robots.contains("Disallow: /drop/\n"));
+        assert!(robots.ends_with("\nSitemap: https://gw.example/gw/abc/sitemap.xml\n"));
+        assert!(!robots_txt("https://gw.example", "", &[]).contains("Sitemap"));
+
+        let robots = robots_txt("https://example.com/jax", "/jax", &["abc".to_string()]);
+        assert!(robots.contains("Disallow: /jax/dl/\n"));
+        assert!(robots.ends_with("\nSitemap: https://example.com/jax/gw/abc/sitemap.xml\n"));
+    }
+
+    #[test]
+    fn test_sitemap_xml() {
+        let base = Url::parse("https://gw.example/gw/abc/").unwrap();
+        let recorded = SystemTime::UNIX_EPOCH + Duration::from_secs(1_790_000_000);
+        let xml = sitemap_xml(
+            &base,
+            vec!["notes/a b.md".to_string(), "q&a.html".to_string()],
+            Some(recorded),
+        );
+        assert!(xml.contains("<loc>https://gw.example/gw/abc/</loc>"));
+        assert!(xml.contains("<loc>https://gw.example/gw/abc/notes/a%20b.md</loc>"));
+        assert!(xml.contains("<loc>https://gw.example/gw/abc/q&amp;a.html</loc>"));
+        assert!(xml.contains("<lastmod>2026-09-21T"));
+
+        let many = (0..SITEMAP_MAX_URLS + 10).map(|i| format!("{}.txt", i));
+        let xml = sitemap_xml(&base, many, None);
+        assert_eq!(xml.matches("<url>").count(), SITEMAP_MAX_URLS);
+        assert!(!xml.contains("<lastmod>"));
+    }
+}
@@ -1,9 +1,20 @@
+use std::collections::BTreeMap;
 use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::time::Duration;
 
+use common::peer::{ConnectionLimits, ProtocolTimeouts};
 use common::prelude::SecretKey;
 
-use crate::state::BlobStoreConfig;
+use crate::cluster::ClusterConfig;
+use crate::database::DatabaseKey;
+use crate::gateway_tls::GatewayTlsConfig;
+use crate::publish_pipeline::BuilderConfig;
+use crate::publish_scan::ScannerConfig;
+use crate::reverse_proxy::ReverseProxyConfig;
+use crate::state::{BlobStoreConfig, UploadLimitsConfig};
+use crate::storage_policy::BlobLocationConfig;
+use crate::sync_conditions::SyncDeferral;
 
 #[derive(Debug)]
 pub struct Config {
@@ -18,6 +29,12 @@ pub struct Config {
     // blob store configuration
     /// Blob storage backend configuration
     pub blob_store: BlobStoreConfig,
+    /// Other storage backends buckets can be placed in, by name,
+    ///  if empty then every blob is kept in `blob_store`
+    pub blob_locations: BTreeMap<String, BlobLocationConfig>,
+    /// Object storage published buckets can be mirrored into for a CDN,
+    ///  by name
+    pub cdn_targets: BTreeMap<String, BlobLocationConfig>,
     /// Path to the jax directory (absolute path, used for legacy blobs and cache)
     pub jax_dir: PathBuf,
 
@@ -31,16 +48,69 @@ pub struct Config {
     /// a path to a sqlite database, if not set then an
     ///  in-memory database will be used
     pub sqlite_path: Option<PathBuf>,
+    /// Key to encrypt the database with at rest,
+    ///  if not set then the database is plaintext
+    pub database_key: Option<DatabaseKey>,
+
+    /// Path to the config file, re-read on reload.
+    ///  if not set then configuration cannot be hot-reloaded
+    pub config_path: Option<PathBuf>,
+    /// Interval between periodic sync pings to bucket peers
+    pub sync_interval: Duration,
+    /// Whether to ping bucket peers every `sync_interval`; hosts that
+    ///  schedule syncs themselves (the lite profile) turn this off
+    pub periodic_sync: bool,
+    /// Size limit of the on-demand cache of online-only buckets
+    pub online_cache_max_bytes: u64,
+    /// How syncing adapts to battery power and metered networks
+    pub sync_deferral: SyncDeferral,
+    /// Timeouts of requests to peers
+    pub protocol_timeouts: ProtocolTimeouts,
+    /// Limits on requests from peers handled at once
+    pub connection_limits: ConnectionLimits,
 
     // logging
     pub log_level: tracing::Level,
     /// Directory for log files (optional, logs to stdout only if not set)
     pub log_dir: Option<PathBuf>,
+    /// OTLP/HTTP collector to export tracing spans to (e.g. http://localhost:4318),
+    ///  if not set then spans are not exported
+    pub otlp_endpoint: Option<String>,
 
     // url configuration
     /// External gateway URL (e.g., "https://gateway.example.com")
     /// Used for generating share/download links
     pub gateway_url: Option<String>,
+    /// Domains to serve the gateway over HTTPS for,
+    ///  if not set then the gateway only speaks plain HTTP
+    pub gateway_tls: Option<GatewayTlsConfig>,
+    /// Proxies trusted to forward client addresses and the path prefix
+    ///  the servers are reached under, if not set then neither is honored
+    pub reverse_proxy: Option<ReverseProxyConfig>,
+
+    // publishing
+    /// Scanners that can veto publishing a version,
+    ///  if empty then versions are published unscanned
+    pub publish_scanners: Vec<ScannerConfig>,
+    /// Builders publish pipelines can run,
+    ///  if empty then buckets are published as they are
+    pub publish_builders: Vec<BuilderConfig>,
+
+    // uploads
+    /// Largest uploads accepted, overall and per bucket
+    pub upload_limits: UploadLimitsConfig,
+
+    // imports
+    /// Local directories that can be imported as `file://` URLs
+    pub import_dirs: Vec<PathBuf>,
+
+    // replicas
+    /// Sync published buckets only and never write to a bucket
+    pub read_replica: bool,
+
+    // clustering
+    /// Claim sync jobs in the database shared with other instances
+    pub cluster: Option<ClusterConfig>,
 }
 
 // TODO (amiller68): real error handling
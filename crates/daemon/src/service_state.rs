@@ -1,25 +1,81 @@
-#[cfg(feature = "fuse")]
-use std::sync::Arc;
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+use std::time::Instant;
 
+use tokio::sync::watch;
 #[cfg(feature = "fuse")]
 use tokio::sync::RwLock;
 use url::Url;
 
+use crate::audit::{AuditEvent, AuditLog};
+use crate::backup::Backups;
+use crate::blob_cache::BlobCache;
 use crate::blobs::{Blobs, BlobsSetupError};
+use crate::bucket_index::IndexUpdate;
+use crate::bucket_stats::BucketStatsCache;
+use crate::cdn_export::CdnExports;
+use crate::cluster::Cluster;
+use crate::data_export::Exports;
 use crate::database::{Database, DatabaseSetupError};
+use crate::deploy::SeenNonces;
 #[cfg(feature = "fuse")]
 use crate::fuse::{MountManager, MountManagerConfig};
+use crate::gateway_policy::{GatewayMode, GatewayPolicies, GatewayPolicy};
+use crate::image_variants::{ImageVariants, IMAGE_VARIANTS_DIR};
+use crate::peers::{PeerError, PeerTicket};
+use crate::publish::{Publication, PublishError};
+use crate::publish_pipeline::{
+    fill_site, BuildError, BuildSource, PublishBuilderError, PublishBuilders, PublishPipeline,
+};
+use crate::publish_scan::{all_allowed, PublishScanError, PublishScanners};
+use crate::reload::{ConfigReloader, RuntimeConfig};
 use crate::service_config::Config;
-use crate::sync_provider::{QueuedSyncConfig, QueuedSyncProvider};
+use crate::state::UploadLimitsConfig;
+use crate::sync_policy::{SyncPolicies, SyncPolicy};
+use crate::sync_provider::{QueuedSyncConfig, QueuedSyncProvider, SyncStatus};
+use crate::webhooks::Webhooks;
+
+use common::bucket_log::BucketLogProvider;
+use common::crypto::{PublicKey, SecretKey};
+use common::linked_data::{Hash, Link};
+use common::mount::Mount;
+use common::peer::sync::download_pins::subtree_root;
+use common::peer::sync::DownloadPinsJob;
+use common::peer::{Escrow, EscrowError, Peer, PeerBuilder, SyncJob};
 
-use common::crypto::SecretKey;
-use common::peer::{Peer, PeerBuilder};
+/// File in the jax directory keeping the recovery shards we hold for others
+pub const ESCROW_FILE: &str = "escrow.cbor";
 
 /// Main service state - orchestrates all components
 #[derive(Clone)]
 pub struct State {
     database: Database,
     peer: Peer<Database>,
+    reloader: ConfigReloader,
+    sync_provider: QueuedSyncProvider,
+    sync_status: SyncStatus,
+    sync_policies: SyncPolicies,
+    gateway_policies: GatewayPolicies,
+    blob_cache: BlobCache,
+    audit: AuditLog,
+    webhooks: Webhooks,
+    backups: Backups,
+    exports: Exports,
+    cdn_exports: CdnExports,
+    bucket_stats: BucketStatsCache,
+    image_variants: ImageVariants,
+    publish_scanners: PublishScanners,
+    publish_builders: PublishBuilders,
+    upload_limits: Arc<UploadLimitsConfig>,
+    import_dirs: Arc<Vec<PathBuf>>,
+    deploy_nonces: SeenNonces,
+    read_replica: bool,
+    started_at: Instant,
+    api_port: u16,
+    gateway_port: u16,
+    log_dir: Option<std::path::PathBuf>,
+    blob_backend: &'static str,
+    shutdown_tx: Arc<OnceLock<watch::Sender<()>>>,
     #[cfg(feature = "fuse")]
     mount_manager: Arc<RwLock<Option<MountManager>>>,
 }
@@ -41,7 +97,8 @@ impl State {
             None => Url::parse("sqlite::memory:").map_err(|_| StateSetupError::InvalidDatabaseUrl),
         }?;
         tracing::info!("Database URL: {:?}", sqlite_database_url);
-        let database = Database::connect(&sqlite_database_url).await?;
+        let database =
+            Database::connect(&sqlite_database_url, config.database_key.as_ref()).await?;
 
         // 2. Setup node secret
         let node_secret = config
@@ -51,7 +108,8 @@ impl State {
 
         // 3. Setup blobs store using the new blobs module
         tracing::debug!("ServiceState::from_config - loading blobs store");
-        let blobs = Blobs::setup(&config.blob_store, &config.jax_dir).await?;
+        let blobs =
+            Blobs::setup(&config.blob_store, &config.blob_locations, &config.jax_dir).await?;
         tracing::debug!("ServiceState::from_config - blobs store loaded successfully");
 
         // 4. Build peer from the database as the log provider
@@ -61,34 +119,170 @@ impl State {
         let (sync_provider, job_receiver) = QueuedSyncProvider::new(QueuedSyncConfig::default());
 
         let mut peer_builder = PeerBuilder::new()
-            .with_sync_provider(std::sync::Arc::new(sync_provider))
+            .with_sync_provider(Arc::new(sync_provider.clone()))
             .log_provider(database.clone())
             .blobs_store(blobs.into_inner())
-            .secret_key(node_secret.clone());
+            .secret_key(node_secret.clone())
+            .protocol_timeouts(config.protocol_timeouts.clone())
+            .connection_limits(config.connection_limits)
+            .escrow(Escrow::load(config.jax_dir.join(ESCROW_FILE))?);
 
         if let Some(addr) = config.node_listen_addr {
             peer_builder = peer_builder.socket_address(addr);
         }
 
         let peer = peer_builder.build().await;
+        for (bucket_id, source) in database.list_followed_buckets().await? {
+            peer.follow(bucket_id, source);
+        }
+        for (bucket_id, key) in database.list_ephemeral_keys().await? {
+            if let Some(source) = peer.following(&bucket_id) {
+                peer.follow_ephemeral(bucket_id, source, key);
+            }
+        }
 
         // Log the bound addresses
         let bound_addrs = peer.endpoint().bound_sockets();
         tracing::info!("Node id: {} (with JAX protocol)", peer.id());
         tracing::info!("Peer listening on: {:?}", bound_addrs);
 
+        // Runtime settings that can be changed by reloading the config file
+        let reloader = ConfigReloader::new(
+            config.config_path.clone(),
+            RuntimeConfig {
+                log_level: config.log_level,
+                sync_interval: config.sync_interval,
+                online_cache_max_bytes: config.online_cache_max_bytes,
+                sync_deferral: config.sync_deferral,
+            },
+        );
+
         // Spawn the worker for the queued sync provider
         // The worker is managed outside the peer, like the database
         let peer_for_worker = peer.clone();
         let job_stream = job_receiver.into_async();
+        let runtime_rx = reloader.subscribe();
+        // Syncing stays paused across restarts
+        let sync_status = SyncStatus::new(database.sync_paused().await?);
+        let worker_status = sync_status.clone();
+        let sync_policies = SyncPolicies::new(database.list_sync_policies().await?);
+        let worker_policies = sync_policies.clone();
+        let gateway_policies = GatewayPolicies::new(
+            database.gateway_mode().await?,
+            database.list_gateway_policies().await?,
+        );
+
+        // Online-only buckets fetch their content on demand through the cache
+        let blob_cache = BlobCache::load(
+            database.clone(),
+            peer.endpoint().clone(),
+            peer.secret().public(),
+            sync_policies.clone(),
+        )
+        .await?;
+        peer.blobs().set_fetcher(Arc::new(blob_cache.clone()));
+        tokio::spawn(crate::blob_cache::run_eviction(
+            blob_cache.clone(),
+            peer.blobs().clone(),
+            reloader.subscribe(),
+        ));
+        let worker_cache = blob_cache.clone();
+        let webhooks = Webhooks::spawn(database.clone());
+        let worker_webhooks = webhooks.clone();
+        let audit = AuditLog::new(database.clone(), webhooks.clone());
+        let worker_audit = audit.clone();
+        let backups = Backups::spawn(peer.clone(), database.clone(), audit.clone());
+        let exports = Exports::new(peer.clone(), database.clone());
+        let cdn_exports = CdnExports::spawn(
+            peer.clone(),
+            database.clone(),
+            audit.clone(),
+            config.cdn_targets.clone(),
+        );
+        tokio::spawn(crate::retention::run(
+            peer.clone(),
+            database.clone(),
+            audit.clone(),
+        ));
+        tokio::spawn(crate::storage_policy::run(
+            peer.clone(),
+            database.clone(),
+            audit.clone(),
+        ));
+        tokio::spawn(crate::ephemeral::run(
+            peer.clone(),
+            database.clone(),
+            audit.clone(),
+        ));
+        let worker_database = database.clone();
+        let periodic_sync = config.periodic_sync;
+        let read_replica = config.read_replica;
+        let cluster = config.cluster.as_ref().map(|cluster| {
+            let cluster = Cluster::new(database.clone(), cluster);
+            tracing::info!(
+                "Sharing sync jobs as cluster instance {}",
+                cluster.instance()
+            );
+            cluster
+        });
+
+        // Peers in the address book are dialed right away, without waiting
+        // for discovery or a re-introduction
+        tokio::spawn(crate::peers::reconnect_known_peers(
+            peer.clone(),
+            database.clone(),
+        ));
+        tokio::spawn(crate::peers::run_refresh(peer.clone(), database.clone()));
+        tokio::spawn(crate::sync_conditions::run_probe(
+            sync_status.conditions().clone(),
+        ));
         tokio::spawn(async move {
-            crate::sync_provider::run_worker(peer_for_worker, job_stream).await;
+            crate::sync_provider::run_worker(
+                peer_for_worker,
+                job_stream,
+                runtime_rx,
+                worker_status,
+                worker_policies,
+                worker_cache,
+                worker_audit,
+                worker_webhooks,
+                worker_database,
+                periodic_sync,
+                read_replica,
+                cluster,
+            )
+            .await;
         });
 
         // Create the initial state
         let state = Self {
             database: database.clone(),
             peer: peer.clone(),
+            reloader,
+            sync_provider,
+            sync_status,
+            sync_policies,
+            gateway_policies,
+            blob_cache,
+            audit,
+            webhooks,
+            backups,
+            exports,
+            cdn_exports,
+            bucket_stats: BucketStatsCache::new(),
+            image_variants: ImageVariants::new(config.jax_dir.join(IMAGE_VARIANTS_DIR)),
+            publish_scanners: PublishScanners::new(&config.publish_scanners)?,
+            publish_builders: PublishBuilders::new(&config.publish_builders)?,
+            upload_limits: Arc::new(config.upload_limits.clone()),
+            import_dirs: Arc::new(config.import_dirs.clone()),
+            deploy_nonces: SeenNonces::default(),
+            read_replica: config.read_replica,
+            started_at: Instant::now(),
+            api_port: config.api_port,
+            gateway_port: config.gateway_port,
+            log_dir: config.log_dir.clone(),
+            blob_backend: config.blob_store.kind(),
+            shutdown_tx: Arc::new(OnceLock::new()),
             #[cfg(feature = "fuse")]
             mount_manager: Arc::new(RwLock::new(None)),
         };
@@ -96,7 +290,12 @@ impl State {
         // Initialize mount manager with fuse feature
         #[cfg(feature = "fuse")]
         {
-            let mount_manager = MountManager::new(database, peer, MountManagerConfig::default());
+            let mount_manager = MountManager::new(
+                database,
+                peer,
+                state.audit.clone(),
+                MountManagerConfig::default(),
+            );
             *state.mount_manager.write().await = Some(mount_manager);
         }
 
@@ -116,6 +315,560 @@ impl State {
         &self.database
     }
 
+    pub fn reloader(&self) -> &ConfigReloader {
+        &self.reloader
+    }
+
+    /// Time since the service state was created
+    pub fn uptime(&self) -> std::time::Duration {
+        self.started_at.elapsed()
+    }
+
+    pub fn api_port(&self) -> u16 {
+        self.api_port
+    }
+
+    pub fn gateway_port(&self) -> u16 {
+        self.gateway_port
+    }
+
+    /// Directory the daemon writes log files to, if file logging is enabled
+    pub fn log_dir(&self) -> Option<&std::path::Path> {
+        self.log_dir.as_deref()
+    }
+
+    /// Kind of blob store backend (`legacy`, `filesystem` or `s3`)
+    pub fn blob_backend(&self) -> &'static str {
+        self.blob_backend
+    }
+
+    /// Number of sync jobs waiting to be processed
+    pub fn pending_sync_jobs(&self) -> usize {
+        self.sync_provider.pending_jobs()
+    }
+
+    /// Sync worker activity and the pause switch
+    pub fn sync_status(&self) -> &SyncStatus {
+        &self.sync_status
+    }
+
+    /// Pause or resume all syncing, persisting the switch across restarts
+    pub async fn set_sync_paused(&self, paused: bool) -> Result<(), sqlx::Error> {
+        self.database.set_sync_paused(paused).await?;
+        self.sync_status.set_paused(paused);
+        Ok(())
+    }
+
+    /// Per-bucket sync policies, as used by the sync worker
+    pub fn sync_policies(&self) -> &SyncPolicies {
+        &self.sync_policies
+    }
+
+    /// Store a bucket's sync policy and apply it to the sync worker
+    pub async fn set_sync_policy(
+        &self,
+        bucket_id: uuid::Uuid,
+        policy: SyncPolicy,
+    ) -> Result<(), sqlx::Error> {
+        let was_online_only = self.sync_policies.get(&bucket_id).online_only;
+        let online_only = policy.online_only;
+        self.database.set_sync_policy(&bucket_id, &policy).await?;
+        self.sync_policies.set(bucket_id, policy);
+        if was_online_only && !online_only {
+            self.download_content(bucket_id).await;
+        }
+        Ok(())
+    }
+
+    /// Drop a bucket's sync policy so it follows the daemon defaults again
+    pub async fn reset_sync_policy(&self, bucket_id: uuid::Uuid) -> Result<bool, sqlx::Error> {
+        let was_online_only = self.sync_policies.get(&bucket_id).online_only;
+        let removed = self.database.delete_sync_policy(&bucket_id).await?;
+        self.sync_policies.remove(&bucket_id);
+        if was_online_only {
+            self.download_content(bucket_id).await;
+        }
+        Ok(removed)
+    }
+
+    /// Gateway mode and per-bucket gateway policies, as used by the gateway
+    pub fn gateway_policies(&self) -> &GatewayPolicies {
+        &self.gateway_policies
+    }
+
+    /// Store which buckets the gateway serves and apply it right away
+    pub async fn set_gateway_mode(&self, mode: GatewayMode) -> Result<(), sqlx::Error> {
+        self.database.set_gateway_mode(mode).await?;
+        self.gateway_policies.set_mode(mode);
+        Ok(())
+    }
+
+    /// Store a bucket's gateway policy and apply it right away
+    pub async fn set_gateway_policy(
+        &self,
+        bucket_id: uuid::Uuid,
+        policy: GatewayPolicy,
+    ) -> Result<(), sqlx::Error> {
+        self.database
+            .set_gateway_policy(&bucket_id, &policy)
+            .await?;
+        self.gateway_policies.set(bucket_id, policy);
+        Ok(())
+    }
+
+    /// Drop a bucket's gateway policy so it is served with the defaults
+    pub async fn reset_gateway_policy(&self, bucket_id: uuid::Uuid) -> Result<bool, sqlx::Error> {
+        let removed = self.database.delete_gateway_policy(&bucket_id).await?;
+        self.gateway_policies.remove(&bucket_id);
+        Ok(removed)
+    }
+
+    /// Log of mutating operations
+    pub fn audit_log(&self) -> &AuditLog {
+        &self.audit
+    }
+
+    /// Record a change in the audit log
+    pub async fn audit(&self, event: AuditEvent) {
+        self.audit.record(event).await
+    }
+
+    pub fn webhooks(&self) -> &Webhooks {
+        &self.webhooks
+    }
+
+    /// Scheduled backup jobs, run by the backup worker
+    pub fn backups(&self) -> &Backups {
+        &self.backups
+    }
+
+    /// Full data exports and their progress
+    pub fn exports(&self) -> &Exports {
+        &self.exports
+    }
+
+    /// Mirrors of published buckets for CDNs
+    pub fn cdn_exports(&self) -> &CdnExports {
+        &self.cdn_exports
+    }
+
+    /// Statistics of buckets, cached per head link
+    pub fn bucket_stats(&self) -> &BucketStatsCache {
+        &self.bucket_stats
+    }
+
+    /// Resized images served by the gateway
+    pub fn image_variants(&self) -> &ImageVariants {
+        &self.image_variants
+    }
+
+    /// Builders configured for publish pipelines
+    pub fn publish_builders(&self) -> &PublishBuilders {
+        &self.publish_builders
+    }
+
+    /// Largest uploads accepted, overall and per bucket
+    pub fn upload_limits(&self) -> &UploadLimitsConfig {
+        &self.upload_limits
+    }
+
+    /// Local directories that can be imported as `file://` URLs
+    pub fn import_dirs(&self) -> &[PathBuf] {
+        &self.import_dirs
+    }
+
+    /// Nonces of the deploy requests accepted recently
+    pub fn deploy_nonces(&self) -> &SeenNonces {
+        &self.deploy_nonces
+    }
+
+    /// Whether this node is a read replica, which never writes to a bucket
+    /// (see [`crate::replica`])
+    pub fn is_read_replica(&self) -> bool {
+        self.read_replica
+    }
+
+    /// Add a peer to the address book and dial it in the background
+    pub async fn add_known_peer(&self, ticket: PeerTicket) -> Result<(), PeerError> {
+        if ticket.node_id == self.peer.secret().public() {
+            return Err(PeerError::OwnNode);
+        }
+        self.database.add_known_peer(&ticket).await?;
+        let peer = self.peer.clone();
+        let database = self.database.clone();
+        tokio::spawn(async move { crate::peers::dial(&peer, &database, &ticket).await });
+        Ok(())
+    }
+
+    /// Remove a peer from the address book, returning whether it was known
+    pub async fn remove_known_peer(&self, node_id: &PublicKey) -> Result<bool, sqlx::Error> {
+        self.database.remove_known_peer(node_id).await
+    }
+
+    /// Ticket other nodes can add this node to their address book with
+    pub fn own_ticket(&self) -> PeerTicket {
+        PeerTicket::from_node_addr(self.peer.endpoint().node_addr())
+    }
+
+    /// Cache of content fetched on demand for online-only buckets
+    pub fn blob_cache(&self) -> &BlobCache {
+        &self.blob_cache
+    }
+
+    /// Queue a download of all of a bucket's content at its current version,
+    /// e.g. once it stops being online-only
+    async fn download_content(&self, bucket_id: uuid::Uuid) {
+        if let Err(e) = self.queue_pins_download(bucket_id, None).await {
+            tracing::warn!("Failed to queue content download of {}: {}", bucket_id, e);
+        }
+    }
+
+    /// Queue a download of the content of a bucket's version at `height`,
+    /// or of its head if None
+    pub async fn queue_pins_download(
+        &self,
+        bucket_id: uuid::Uuid,
+        height: Option<u64>,
+    ) -> anyhow::Result<()> {
+        let head_height = self.database.height(bucket_id).await?;
+        let (link, height) = self.database.head(bucket_id, height).await?;
+        let manifest = Mount::load_manifest(&link, self.peer.blobs()).await?;
+        let job = SyncJob::DownloadPins(DownloadPinsJob {
+            bucket_id,
+            pins_link: manifest.pins().clone(),
+            peer_ids: manifest
+                .shares()
+                .values()
+                .map(|share| share.principal().identity)
+                .collect(),
+            height,
+            historical: height < head_height,
+            added: None,
+            subtree: subtree_root(&manifest, self.peer.secret()).map(Box::new),
+        });
+        self.peer.dispatch(job).await
+    }
+
+    /// Follow a published bucket from `source` without a share and ping the
+    /// source right away to start syncing it
+    pub async fn follow_bucket(
+        &self,
+        bucket_id: uuid::Uuid,
+        source: PublicKey,
+    ) -> Result<(), sqlx::Error> {
+        self.database.follow_bucket(&bucket_id, &source).await?;
+        self.peer.follow(bucket_id, source);
+        if let Err(e) = self.peer.ping(bucket_id).await {
+            tracing::warn!(
+                "Failed to ping source of followed bucket {}: {}",
+                bucket_id,
+                e
+            );
+        }
+        Ok(())
+    }
+
+    /// Follow a bucket shared with the temporary identity `key` from
+    /// `source`, reading it through the identity's ephemeral share, and ping
+    /// the source right away to start syncing it
+    pub async fn follow_bucket_ephemeral(
+        &self,
+        bucket_id: uuid::Uuid,
+        source: PublicKey,
+        key: SecretKey,
+    ) -> Result<(), sqlx::Error> {
+        self.database.add_ephemeral_key(&bucket_id, &key).await?;
+        self.database.follow_bucket(&bucket_id, &source).await?;
+        self.peer.follow_ephemeral(bucket_id, source, key);
+        if let Err(e) = self.peer.ping(bucket_id).await {
+            tracing::warn!(
+                "Failed to ping source of followed bucket {}: {}",
+                bucket_id,
+                e
+            );
+        }
+        Ok(())
+    }
+
+    /// Stop following a bucket, forgetting any temporary identity it was
+    /// shared with. Versions synced so far are kept.
+    /// Returns false if the bucket was not followed.
+    pub async fn unfollow_bucket(&self, bucket_id: uuid::Uuid) -> Result<bool, sqlx::Error> {
+        self.database.remove_ephemeral_key(&bucket_id).await?;
+        let removed = self.database.unfollow_bucket(&bucket_id).await?;
+        self.peer.unfollow(&bucket_id);
+        Ok(removed)
+    }
+
+    /// Publish the current version of a bucket, applying any changes to its
+    /// gateway index settings. Rotating first unpublishes every earlier
+    /// version and moves the bucket to a new gateway link. Buckets with a
+    /// publish pipeline are built, and the output is published as their site
+    /// bucket instead. Buckets with a CDN export are exported afterwards.
+    pub async fn publish_bucket(
+        &self,
+        bucket_id: uuid::Uuid,
+        rotate: bool,
+        expected_version: Option<Hash>,
+        index: &IndexUpdate,
+    ) -> Result<Publication, PublishError> {
+        let mount = self
+            .peer
+            .mount_expecting(bucket_id, expected_version)
+            .await?;
+        let Some((pipeline, site_bucket_id)) = self.database.publish_pipeline(&bucket_id).await?
+        else {
+            let publication = self.publish_mount(bucket_id, mount, rotate, index).await?;
+            self.cdn_exports.published(bucket_id);
+            return Ok(publication);
+        };
+
+        let source_version = mount.link().await.hash();
+        let (site_bucket_id, site, log) = match self
+            .build_site(bucket_id, &mount, &pipeline, site_bucket_id)
+            .await
+        {
+            Ok(built) => built,
+            Err(e) => {
+                let log = match &e {
+                    PublishError::Build(BuildError::Failed(log)) => log.clone(),
+                    e => e.to_string(),
+                };
+                self.database
+                    .record_publish_build(&bucket_id, &source_version, None, &log)
+                    .await?;
+                return Err(e);
+            }
+        };
+        let published = self
+            .publish_mount(site_bucket_id, site, rotate, index)
+            .await;
+        let (site_version, log) = match &published {
+            Ok(publication) => (Some(publication.link.hash()), log),
+            Err(e) => (None, e.to_string()),
+        };
+        self.database
+            .record_publish_build(&bucket_id, &source_version, site_version.as_ref(), &log)
+            .await?;
+        let publication = published?;
+        self.cdn_exports.published(bucket_id);
+        Ok(Publication {
+            site_bucket_id: Some(site_bucket_id),
+            ..publication
+        })
+    }
+
+    /// Build a bucket with its publish pipeline into its site bucket, which
+    /// is created on the first build. Returns the site bucket, its mount
+    /// holding the output, and the end of the builder's output.
+    async fn build_site(
+        &self,
+        bucket_id: uuid::Uuid,
+        source: &Mount,
+        pipeline: &PublishPipeline,
+        site_bucket_id: Option<uuid::Uuid>,
+    ) -> Result<(uuid::Uuid, Mount, String), PublishError> {
+        let (name, height) = {
+            let inner = source.inner().await;
+            (inner.manifest().name().to_string(), inner.height())
+        };
+        let build_source = BuildSource {
+            bucket_id,
+            name: name.clone(),
+            version: source.link().await,
+            height,
+        };
+        let build = self
+            .publish_builders
+            .build(pipeline, source, &build_source)
+            .await?;
+
+        // Site buckets deleted since are created again
+        let existing = match site_bucket_id {
+            Some(id) => self.database.get_bucket_info(&id).await?.map(|_| id),
+            None => None,
+        };
+        let (site_bucket_id, mut site) = match existing {
+            Some(id) => (id, self.peer.mount(id).await?),
+            None => {
+                let id = uuid::Uuid::new_v4();
+                let site_name = format!("{} (site)", name);
+                let site =
+                    Mount::init(id, site_name.clone(), self.peer.secret(), self.peer.blobs())
+                        .await?;
+                self.peer
+                    .logs()
+                    .append(id, site_name, site.link().await, None, 0, false)
+                    .await
+                    .map_err(|e| PublishError::BucketLog(e.to_string()))?;
+                self.database.set_site_bucket(&bucket_id, &id).await?;
+                (id, site)
+            }
+        };
+        fill_site(&mut site, build.output()).await?;
+        Ok((site_bucket_id, site, build.log))
+    }
+
+    /// Scan and publish a bucket's version in `mount`
+    async fn publish_mount(
+        &self,
+        bucket_id: uuid::Uuid,
+        mut mount: Mount,
+        rotate: bool,
+        index: &IndexUpdate,
+    ) -> Result<Publication, PublishError> {
+        if !index.is_empty() {
+            let (set, remove) = index.to_attrs();
+            mount
+                .set_attrs(std::path::Path::new("/"), set, &remove)
+                .await?;
+        }
+        // Scanners see the version as it will be published
+        let head = mount.link().await;
+        let scans = self.publish_scanners.scan(&mount, bucket_id, &head).await;
+        if !all_allowed(&scans) {
+            self.database
+                .record_publish_scans(&bucket_id, &head.hash(), &scans)
+                .await?;
+            return Err(PublishError::Vetoed(scans));
+        }
+        if rotate {
+            self.database.unpublish_versions(&bucket_id).await?;
+            let token = crate::publish::new_gateway_token();
+            self.database.set_gateway_link(&bucket_id, &token).await?;
+        }
+        let link = self.peer.save_mount(&mount, true).await?;
+        // Recorded under the published version, so the gateway here doesn't
+        // scan it again
+        if !scans.is_empty() {
+            self.database
+                .record_publish_scans(&bucket_id, &link.hash(), &scans)
+                .await?;
+        }
+        Ok(Publication {
+            link,
+            gateway_path: self.gateway_path(bucket_id).await?,
+            height: self.peer.logs().height(bucket_id).await.ok(),
+            site_bucket_id: None,
+        })
+    }
+
+    /// Whether the gateway may serve a published version of a bucket.
+    /// Versions no scanner has seen, like those synced from peers, are
+    /// scanned first, and unpublished on this node if vetoed.
+    pub async fn may_serve(
+        &self,
+        bucket_id: uuid::Uuid,
+        mount: &Mount,
+    ) -> Result<bool, sqlx::Error> {
+        if self.publish_scanners.is_empty() {
+            return Ok(true);
+        }
+        let link = mount.link().await;
+        let recorded = self
+            .database
+            .publish_scans_of(&bucket_id, &link.hash())
+            .await?;
+        if !recorded.is_empty() {
+            return Ok(all_allowed(&recorded));
+        }
+        let scans = self.publish_scanners.scan(mount, bucket_id, &link).await;
+        self.database
+            .record_publish_scans(&bucket_id, &link.hash(), &scans)
+            .await?;
+        if all_allowed(&scans) {
+            return Ok(true);
+        }
+        self.database
+            .unpublish_version(&bucket_id, &link.hash())
+            .await?;
+        Ok(false)
+    }
+
+    /// Save an unpublished version of a bucket and stop serving its earlier
+    /// published versions, or those of its site bucket, withdrawing any CDN
+    /// export
+    pub async fn unpublish_bucket(
+        &self,
+        bucket_id: uuid::Uuid,
+        expected_version: Option<Hash>,
+    ) -> Result<Link, PublishError> {
+        let mount = self
+            .peer
+            .mount_expecting(bucket_id, expected_version)
+            .await?;
+        let link = self.peer.save_mount(&mount, false).await?;
+        self.database.unpublish_versions(&bucket_id).await?;
+        if let Some((_, Some(site_bucket_id))) = self.database.publish_pipeline(&bucket_id).await? {
+            if self
+                .database
+                .get_bucket_info(&site_bucket_id)
+                .await?
+                .is_some()
+            {
+                let site = self.peer.mount(site_bucket_id).await?;
+                self.peer.save_mount(&site, false).await?;
+                self.database.unpublish_versions(&site_bucket_id).await?;
+            }
+        }
+        self.cdn_exports.published(bucket_id);
+        Ok(link)
+    }
+
+    /// Path the gateway serves a bucket at
+    pub async fn gateway_path(&self, bucket_id: uuid::Uuid) -> Result<String, sqlx::Error> {
+        let id = match self.database.gateway_link(&bucket_id).await? {
+            Some(token) => token,
+            None => bucket_id.to_string(),
+        };
+        Ok(format!("/gw/{}", id))
+    }
+
+    /// Resolve the id in a gateway path to a bucket. Buckets with a rotated
+    /// link are no longer served at their bucket id.
+    pub async fn resolve_gateway_id(&self, id: &str) -> Result<Option<uuid::Uuid>, sqlx::Error> {
+        if let Some(bucket_id) = self.database.bucket_for_gateway_link(id).await? {
+            return Ok(Some(bucket_id));
+        }
+        let Ok(bucket_id) = uuid::Uuid::parse_str(id) else {
+            return Ok(None);
+        };
+        // Buckets built on publish are served as their site bucket
+        let bucket_id = match self.database.publish_pipeline(&bucket_id).await? {
+            Some((_, Some(site_bucket_id))) => site_bucket_id,
+            _ => bucket_id,
+        };
+        if self.database.gateway_link(&bucket_id).await?.is_some() {
+            return Ok(None);
+        }
+        Ok(Some(bucket_id))
+    }
+
+    /// Whether the peer is connected to a relay, i.e. reachable by other peers
+    pub fn is_online(&self) -> bool {
+        self.peer.endpoint().node_addr().relay_url.is_some()
+    }
+
+    /// Register the sender used to shut the service down
+    pub(crate) fn set_shutdown_sender(&self, tx: watch::Sender<()>) {
+        let _ = self.shutdown_tx.set(tx);
+    }
+
+    /// Ask the service to shut down gracefully.
+    /// Returns false if the service was not started with a shutdown handle.
+    pub fn request_shutdown(&self) -> bool {
+        match self.shutdown_tx.get() {
+            Some(tx) => tx.send(()).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Receiver that changes when the service begins shutting down, so
+    /// long-lived responses can end instead of holding up graceful shutdown.
+    pub fn shutdown_signal(&self) -> Option<watch::Receiver<()>> {
+        self.shutdown_tx.get().map(|tx| tx.subscribe())
+    }
+
     /// Get the mount manager (only available with fuse feature)
     #[cfg(feature = "fuse")]
     pub fn mount_manager(&self) -> &Arc<RwLock<Option<MountManager>>> {
@@ -139,10 +892,18 @@ impl AsRef<Database> for State {
 pub enum StateSetupError {
     #[error("Database path does not exist")]
     DatabasePathDoesNotExist,
-    #[error("Database setup error")]
+    #[error("Database setup error: {0}")]
     DatabaseSetupError(#[from] DatabaseSetupError),
     #[error("Invalid database URL")]
     InvalidDatabaseUrl,
     #[error("Blobs setup error: {0}")]
     BlobsSetupError(#[from] BlobsSetupError),
+    #[error("Failed to load settings: {0}")]
+    Settings(#[from] sqlx::Error),
+    #[error("Invalid publish scanner: {0}")]
+    PublishScanners(#[from] PublishScanError),
+    #[error("Invalid publish builder: {0}")]
+    PublishBuilders(#[from] PublishBuilderError),
+    #[error("Failed to load escrow: {0}")]
+    Escrow(#[from] EscrowError),
 }
@@ -0,0 +1,180 @@
+//! Pre-signed download URLs
+//!
+//! `POST /api/v0/bucket/presign` mints a URL for one file at one version of
+//! a bucket that anyone can fetch from the gateway until it expires, with no
+//! API access and whether or not the bucket is published:
+//!
+//! ```text
+//! /dl/<bucket_id>/<path>?version=<hash>&expires=<unix time>&sig=<hex>
+//! ```
+//!
+//! `sig` is an HMAC-SHA256 of
+//!
+//! ```text
+//! GET\n<bucket_id>\n<path>\n<version>\n<expires>
+//! ```
+//!
+//! keyed with a key derived from the node secret, so the daemon that minted
+//! a URL can check it without storing anything, and rotating the node key
+//! revokes every URL. `<path>` is the absolute bucket path.
+
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use time::OffsetDateTime;
+use url::{Position, Url};
+use uuid::Uuid;
+
+use common::linked_data::Hash;
+use common::prelude::SecretKey;
+
+/// Prefix of the gateway route serving signed URLs
+pub const SIGNED_URL_PREFIX: &str = "/dl";
+
+/// How long a URL is valid unless asked otherwise
+pub const DEFAULT_EXPIRES_IN: Duration = Duration::from_secs(60 * 60);
+
+/// Longest a URL can be valid for
+pub const MAX_EXPIRES_IN: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Context of the key derivation, so the key can't collide with other uses of
+/// the node secret
+const KEY_CONTEXT: &str = "jax-daemon 2026-10-16 signed download URL key";
+
+/// What a signed URL grants: one file at one version, until it expires
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedDownload {
+    pub bucket_id: Uuid,
+    /// Absolute path of the file in the bucket
+    pub path: String,
+    pub version: Hash,
+    /// Unix time the URL stops working at
+    pub expires: i64,
+}
+
+impl SignedDownload {
+    fn canonical(&self) -> String {
+        format!(
+            "GET\n{}\n{}\n{}\n{}",
+            self.bucket_id, self.path, self.version, self.expires
+        )
+    }
+
+    /// Whether the URL has run out at `now`
+    pub fn is_expired(&self, now: OffsetDateTime) -> bool {
+        now.unix_timestamp() >= self.expires
+    }
+
+    /// Path and query of the signed URL on the gateway
+    pub fn to_path(&self, signature: &str) -> String {
+        let mut url = Url::parse("http://localhost").expect("valid base URL");
+        url.path_segments_mut()
+            .expect("base URL has a path")
+            .pop_if_empty()
+            .push(SIGNED_URL_PREFIX.trim_start_matches('/'))
+            .push(&self.bucket_id.to_string())
+            .extend(self.path.trim_start_matches('/').split('/'));
+        url.query_pairs_mut()
+            .append_pair("version", &self.version.to_string())
+            .append_pair("expires", &self.expires.to_string())
+            .append_pair("sig", signature);
+        url[Position::BeforePath..].to_string()
+    }
+}
+
+/// Signs and checks download URLs with a key derived from the node secret
+#[derive(Clone)]
+pub struct UrlSigner([u8; 32]);
+
+impl UrlSigner {
+    /// Derive the URL key of a node from its secret
+    pub fn derive(secret: &SecretKey) -> Self {
+        Self(blake3::derive_key(KEY_CONTEXT, &secret.to_bytes()))
+    }
+
+    fn mac(&self, download: &SignedDownload) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.0).expect("HMAC takes keys of any length");
+        mac.update(download.canonical().as_bytes());
+        mac
+    }
+
+    /// Hex signature of a download, as carried in `sig`
+    pub fn sign(&self, download: &SignedDownload) -> String {
+        hex::encode(self.mac(download).finalize().into_bytes())
+    }
+
+    /// Check a signature in constant time
+    pub fn verify(&self, download: &SignedDownload, signature: &str) -> bool {
+        let Ok(signature) = hex::decode(signature) else {
+            return false;
+        };
+        self.mac(download).verify_slice(&signature).is_ok()
+    }
+}
+
+impl std::fmt::Debug for UrlSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("UrlSigner(..)")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn download() -> SignedDownload {
+        SignedDownload {
+            bucket_id: Uuid::new_v4(),
+            path: "/reports/q3 final.pdf".to_string(),
+            version: Hash::from_bytes([7; 32]),
+            expires: 1_900_000_000,
+        }
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let signer = UrlSigner::derive(&SecretKey::generate());
+        let download = download();
+        let signature = signer.sign(&download);
+        assert!(signer.verify(&download, &signature));
+
+        // Nothing the signature covers can be changed
+        let other_path = SignedDownload {
+            path: "/reports/q4.pdf".to_string(),
+            ..download.clone()
+        };
+        assert!(!signer.verify(&other_path, &signature));
+        let later = SignedDownload {
+            expires: download.expires + 1,
+            ..download.clone()
+        };
+        assert!(!signer.verify(&later, &signature));
+        assert!(!signer.verify(&download, "not hex"));
+
+        // Nor verified by another node
+        let other = UrlSigner::derive(&SecretKey::generate());
+        assert!(!other.verify(&download, &signature));
+    }
+
+    #[test]
+    fn test_to_path() {
+        let download = download();
+        let path = download.to_path("abcd");
+        assert!(path.starts_with(&format!(
+            "/dl/{}/reports/q3%20final.pdf?version=",
+            download.bucket_id
+        )));
+        assert!(path.ends_with("&expires=1900000000&sig=abcd"));
+    }
+
+    #[test]
+    fn test_expiry() {
+        let download = download();
+        let before = OffsetDateTime::from_unix_timestamp(download.expires - 1).unwrap();
+        let at = OffsetDateTime::from_unix_timestamp(download.expires).unwrap();
+        assert!(!download.is_expired(before));
+        assert!(download.is_expired(at));
+    }
+}
@@ -1,15 +1,35 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
 use std::{fs, path::PathBuf};
 
+use common::peer::limits::{DEFAULT_MAX_CONNECTIONS, DEFAULT_MAX_REQUESTS_PER_PEER};
+use common::peer::timeouts::{
+    DEFAULT_CONNECT_TIMEOUT, DEFAULT_DOWNLOAD_TIMEOUT, DEFAULT_REQUEST_TIMEOUT,
+};
+use common::peer::{ConnectionLimits, ProtocolTimeouts};
 use common::prelude::SecretKey;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::cluster::ClusterConfig;
+use crate::database::DatabaseKey;
+use crate::gateway_tls::GatewayTlsConfig;
+use crate::publish_pipeline::BuilderConfig;
+use crate::publish_scan::ScannerConfig;
+use crate::reverse_proxy::ReverseProxyConfig;
+use crate::storage_policy::BlobLocationConfig;
+use crate::sync_conditions::SyncDeferral;
 
 pub const APP_NAME: &str = "jax";
 pub const CONFIG_FILE_NAME: &str = "config.toml";
 pub const DB_FILE_NAME: &str = "db.sqlite";
 pub const KEY_FILE_NAME: &str = "key.pem";
 pub const BLOBS_DIR_NAME: &str = "blobs";
+pub const PID_FILE_NAME: &str = "daemon.pid";
+pub const DAEMON_LOG_FILE_NAME: &str = "daemon.log";
+pub const LOGS_DIR_NAME: &str = "logs";
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AppConfig {
     /// Port for the API HTTP server (private, mutation/RPC)
     #[serde(default = "default_api_port")]
@@ -23,6 +43,217 @@ pub struct AppConfig {
     /// Blob storage backend configuration (set at init time)
     #[serde(default)]
     pub blob_store: BlobStoreConfig,
+    /// Storage backends besides `blob_store` that buckets' storage policies
+    /// can place their blobs in, by name (see `crate::storage_policy`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub blob_locations: BTreeMap<String, BlobLocationConfig>,
+    /// Object storage, configured like blob locations, that buckets' CDN
+    /// exports mirror published content into, by name (see
+    /// `crate::cdn_export`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub cdn_targets: BTreeMap<String, BlobLocationConfig>,
+    /// Log level for the daemon (trace, debug, info, warn, error). Reloadable.
+    #[serde(default = "default_log_level")]
+    pub log_level: String,
+    /// Seconds between periodic sync pings to bucket peers. Reloadable.
+    #[serde(default = "default_sync_interval_secs")]
+    pub sync_interval_secs: u64,
+    /// Most bytes of on-demand content kept for online-only buckets before the
+    /// least recently read is evicted. Reloadable.
+    #[serde(default = "default_online_cache_max_bytes")]
+    pub online_cache_max_bytes: u64,
+    /// How syncing adapts to battery power and metered networks. Reloadable.
+    #[serde(default)]
+    pub sync_deferral: SyncDeferral,
+    /// Encrypt the database at rest with a key derived from the node secret
+    /// (needs a build with the `sqlcipher` feature). A plaintext database is
+    /// encrypted on the next start.
+    #[serde(default)]
+    pub encrypt_database: bool,
+    /// OTLP/HTTP collector to export traces to (e.g. http://localhost:4318)
+    #[serde(default)]
+    pub otlp_endpoint: Option<String>,
+    /// Timeouts of requests to peers
+    #[serde(default)]
+    pub protocol_timeouts: ProtocolTimeoutsConfig,
+    /// Limits on requests from peers handled at once
+    #[serde(default)]
+    pub protocol_limits: ProtocolLimitsConfig,
+    /// Serve the gateway over HTTPS for these domains, with certificates
+    /// from an ACME CA
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gateway_tls: Option<GatewayTlsConfig>,
+    /// Reverse proxies in front of the daemon and the path prefix they
+    /// serve it under
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reverse_proxy: Option<ReverseProxyConfig>,
+    /// Scanners that inspect versions before they are published and can veto
+    /// them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub publish_scanners: Vec<ScannerConfig>,
+    /// Builders buckets' publish pipelines can run, such as static site
+    /// generators
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub publish_builders: Vec<BuilderConfig>,
+    /// Largest uploads accepted, for all buckets and for single ones
+    #[serde(default)]
+    pub upload_limits: UploadLimitsConfig,
+    /// Local directories that can be imported into buckets as `file://`
+    /// URLs (see `crate::remote_import`); any other local path is refused,
+    /// so API callers can't import the node's own files
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub import_dirs: Vec<PathBuf>,
+    /// Run as a read replica, which syncs published buckets but never writes
+    /// to one (see `crate::replica`)
+    #[serde(default)]
+    pub read_replica: bool,
+    /// Share sync jobs with the other daemons on this database, each run
+    /// by one of them (see `crate::cluster`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cluster: Option<ClusterConfig>,
+}
+
+/// Timeouts of requests to peers, in seconds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProtocolTimeoutsConfig {
+    /// Connecting to a peer
+    #[serde(default = "default_connect_timeout_secs")]
+    pub connect_secs: u64,
+    /// Sending a request to a peer and reading its reply, unless the message
+    /// type has its own timeout
+    #[serde(default = "default_request_timeout_secs")]
+    pub request_secs: u64,
+    /// Downloading a single blob during a sync
+    #[serde(default = "default_download_timeout_secs")]
+    pub download_secs: u64,
+    /// Request timeouts of single message types (e.g. `Ping = 10`)
+    #[serde(default)]
+    pub messages: BTreeMap<String, u64>,
+}
+
+fn default_connect_timeout_secs() -> u64 {
+    DEFAULT_CONNECT_TIMEOUT.as_secs()
+}
+
+fn default_request_timeout_secs() -> u64 {
+    DEFAULT_REQUEST_TIMEOUT.as_secs()
+}
+
+fn default_download_timeout_secs() -> u64 {
+    DEFAULT_DOWNLOAD_TIMEOUT.as_secs()
+}
+
+impl Default for ProtocolTimeoutsConfig {
+    fn default() -> Self {
+        Self {
+            connect_secs: default_connect_timeout_secs(),
+            request_secs: default_request_timeout_secs(),
+            download_secs: default_download_timeout_secs(),
+            messages: BTreeMap::new(),
+        }
+    }
+}
+
+impl ProtocolTimeoutsConfig {
+    /// The configured timeouts, on top of the built-in per-message ones
+    pub fn timeouts(&self) -> ProtocolTimeouts {
+        let secs = |secs: u64| Duration::from_secs(secs.max(1));
+        let mut timeouts = ProtocolTimeouts {
+            connect: secs(self.connect_secs),
+            request: secs(self.request_secs),
+            download: secs(self.download_secs),
+            ..ProtocolTimeouts::default()
+        };
+        for (message, timeout) in &self.messages {
+            timeouts = timeouts.with_message(message, secs(*timeout));
+        }
+        timeouts
+    }
+}
+
+/// Limits on requests from peers handled at once
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProtocolLimitsConfig {
+    /// Requests handled at once, from all peers
+    #[serde(default = "default_max_connections")]
+    pub max_connections: usize,
+    /// Requests handled at once from a single peer
+    #[serde(default = "default_max_requests_per_peer")]
+    pub max_requests_per_peer: usize,
+}
+
+fn default_max_connections() -> usize {
+    DEFAULT_MAX_CONNECTIONS
+}
+
+fn default_max_requests_per_peer() -> usize {
+    DEFAULT_MAX_REQUESTS_PER_PEER
+}
+
+impl Default for ProtocolLimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: default_max_connections(),
+            max_requests_per_peer: default_max_requests_per_peer(),
+        }
+    }
+}
+
+impl ProtocolLimitsConfig {
+    pub fn limits(&self) -> ConnectionLimits {
+        ConnectionLimits {
+            max_connections: self.max_connections.max(1),
+            max_requests_per_peer: self.max_requests_per_peer.max(1),
+        }
+    }
+}
+
+/// Largest uploads accepted, in bytes.
+///
+/// The limit applies to each file uploaded through `bucket/add` and to each
+/// file written through the deploy endpoint, which both know their bucket,
+/// and to the whole body of other API requests.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UploadLimitsConfig {
+    /// Limit of buckets without their own
+    #[serde(default = "default_max_upload_bytes")]
+    pub max_bytes: u64,
+    /// Limits of single buckets by id, lower or higher than `max_bytes`
+    /// (e.g. `"<bucket id>" = 2147483648`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub buckets: BTreeMap<Uuid, u64>,
+}
+
+fn default_max_upload_bytes() -> u64 {
+    500 * 1024 * 1024
+}
+
+impl Default for UploadLimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_bytes: default_max_upload_bytes(),
+            buckets: BTreeMap::new(),
+        }
+    }
+}
+
+impl UploadLimitsConfig {
+    /// Largest upload accepted into a bucket
+    pub fn for_bucket(&self, bucket_id: &Uuid) -> u64 {
+        self.buckets
+            .get(bucket_id)
+            .copied()
+            .unwrap_or(self.max_bytes)
+    }
+
+    /// Largest upload accepted into any bucket, for uploads that arrive
+    /// before it is known which bucket they are for
+    pub fn largest(&self) -> u64 {
+        self.buckets
+            .values()
+            .copied()
+            .fold(self.max_bytes, u64::max)
+    }
 }
 
 fn default_api_port() -> u16 {
@@ -33,6 +264,18 @@ fn default_gateway_port() -> u16 {
     8080
 }
 
+fn default_log_level() -> String {
+    "debug".to_string()
+}
+
+fn default_sync_interval_secs() -> u64 {
+    60
+}
+
+fn default_online_cache_max_bytes() -> u64 {
+    10 * 1024 * 1024 * 1024
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
@@ -40,13 +283,31 @@ impl Default for AppConfig {
             gateway_port: default_gateway_port(),
             peer_port: None,
             blob_store: BlobStoreConfig::default(),
+            blob_locations: BTreeMap::new(),
+            cdn_targets: BTreeMap::new(),
+            log_level: default_log_level(),
+            sync_interval_secs: default_sync_interval_secs(),
+            online_cache_max_bytes: default_online_cache_max_bytes(),
+            sync_deferral: SyncDeferral::default(),
+            encrypt_database: false,
+            otlp_endpoint: None,
+            protocol_timeouts: ProtocolTimeoutsConfig::default(),
+            protocol_limits: ProtocolLimitsConfig::default(),
+            gateway_tls: None,
+            reverse_proxy: None,
+            publish_scanners: Vec::new(),
+            publish_builders: Vec::new(),
+            upload_limits: UploadLimitsConfig::default(),
+            import_dirs: Vec::new(),
+            read_replica: false,
+            cluster: None,
         }
     }
 }
 
 /// Configuration for the blob storage backend.
 /// This determines where blob data is stored (legacy iroh, local filesystem, or S3).
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum BlobStoreConfig {
     /// Legacy iroh FsStore (default, for backwards compatibility)
@@ -77,6 +338,15 @@ pub struct S3Config {
 }
 
 impl BlobStoreConfig {
+    /// Name of the backend, as written in the config's `type` field
+    pub fn kind(&self) -> &'static str {
+        match self {
+            BlobStoreConfig::Legacy => "legacy",
+            BlobStoreConfig::Filesystem { .. } => "filesystem",
+            BlobStoreConfig::S3 { .. } => "s3",
+        }
+    }
+
     /// Parse S3 URL into components.
     /// Format: s3://access_key:secret_key@host:port/bucket
     pub fn parse_s3_url(url: &str) -> Result<S3Config, StateError> {
@@ -132,6 +402,8 @@ pub struct AppState {
     pub blobs_path: PathBuf,
     /// Path to the config file
     pub config_path: PathBuf,
+    /// Path to the daemon pidfile (present while a daemon is running)
+    pub pid_path: PathBuf,
     /// Loaded configuration
     pub config: AppConfig,
 }
@@ -155,15 +427,25 @@ impl AppState {
         Ok(jax_dir.exists())
     }
 
-    /// Initialize a new jax state directory
+    /// Initialize a new jax state directory with a freshly generated key
     pub fn init(
         custom_path: Option<PathBuf>,
         config: Option<AppConfig>,
+    ) -> Result<Self, StateError> {
+        Self::init_with_key(custom_path, config, SecretKey::generate())
+    }
+
+    /// Initialize a new jax state directory with an existing key
+    pub fn init_with_key(
+        custom_path: Option<PathBuf>,
+        config: Option<AppConfig>,
+        key: SecretKey,
     ) -> Result<Self, StateError> {
         let jax_dir = Self::jax_dir(custom_path)?;
 
-        // Create jax directory if it doesn't exist
-        if jax_dir.exists() {
+        // The directory may already exist without a state in it (e.g. the
+        // desktop app writes logs there before onboarding)
+        if jax_dir.join(KEY_FILE_NAME).exists() || jax_dir.join(CONFIG_FILE_NAME).exists() {
             return Err(StateError::AlreadyInitialized);
         }
 
@@ -173,22 +455,21 @@ impl AppState {
         let blobs_path = jax_dir.join(BLOBS_DIR_NAME);
         fs::create_dir_all(&blobs_path)?;
 
-        // Generate and save key
-        let key = SecretKey::generate();
+        // Save key
         let key_path = jax_dir.join(KEY_FILE_NAME);
         fs::write(&key_path, key.to_pem())?;
 
         // Create config (use provided or default)
         let config = config.unwrap_or_default();
         let config_path = jax_dir.join(CONFIG_FILE_NAME);
-        let config_toml = toml::to_string_pretty(&config)?;
-        fs::write(&config_path, config_toml)?;
+        config.write(&config_path)?;
 
         // Create empty database (just touch the file, it will be initialized by the service)
         let db_path = jax_dir.join(DB_FILE_NAME);
         fs::write(&db_path, "")?;
 
         Ok(Self {
+            pid_path: jax_dir.join(PID_FILE_NAME),
             jax_dir,
             db_path,
             key_path,
@@ -207,15 +488,11 @@ impl AppState {
         }
 
         // Load paths
-        let db_path = jax_dir.join(DB_FILE_NAME);
         let key_path = jax_dir.join(KEY_FILE_NAME);
         let blobs_path = jax_dir.join(BLOBS_DIR_NAME);
         let config_path = jax_dir.join(CONFIG_FILE_NAME);
 
         // Verify all required files/directories exist
-        if !db_path.exists() {
-            return Err(StateError::MissingFile("db.sqlite".to_string()));
-        }
         if !key_path.exists() {
             return Err(StateError::MissingFile("key.pem".to_string()));
         }
@@ -227,10 +504,20 @@ impl AppState {
         }
 
         // Load config
-        let config_toml = fs::read_to_string(&config_path)?;
-        let config: AppConfig = toml::from_str(&config_toml)?;
+        let config = AppConfig::read(&config_path)?;
+
+        // The instances of a cluster may share a database outside of their
+        // jax directories
+        let db_path = match config.cluster.as_ref().and_then(|c| c.database.clone()) {
+            Some(path) => path,
+            None => jax_dir.join(DB_FILE_NAME),
+        };
+        if !db_path.exists() {
+            return Err(StateError::MissingFile(db_path.display().to_string()));
+        }
 
         Ok(Self {
+            pid_path: jax_dir.join(PID_FILE_NAME),
             jax_dir,
             db_path,
             key_path,
@@ -248,6 +535,133 @@ impl AppState {
     }
 }
 
+impl AppConfig {
+    /// Read and parse a config file without touching the rest of the jax directory
+    pub fn read(path: &std::path::Path) -> Result<Self, StateError> {
+        let config_toml = fs::read_to_string(path)?;
+        Ok(toml::from_str(&config_toml)?)
+    }
+
+    /// Parse the configured log level
+    pub fn log_level(&self) -> Result<tracing::Level, StateError> {
+        self.log_level
+            .parse()
+            .map_err(|_| StateError::InvalidLogLevel(self.log_level.clone()))
+    }
+
+    /// The configured periodic sync interval
+    pub fn sync_interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.sync_interval_secs.max(1))
+    }
+
+    /// Key to open the database with, if it is encrypted at rest
+    pub fn database_key(&self, secret: &SecretKey) -> Option<DatabaseKey> {
+        self.encrypt_database.then(|| DatabaseKey::derive(secret))
+    }
+
+    /// Write the config to a file
+    pub fn write(&self, path: &std::path::Path) -> Result<(), StateError> {
+        fs::write(path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Flatten the config into dotted `key = value` pairs
+    pub fn entries(&self) -> Result<Vec<(String, toml::Value)>, StateError> {
+        let table = toml::Table::try_from(self)?;
+        let mut entries = Vec::new();
+        flatten_table("", &table, &mut entries);
+        Ok(entries)
+    }
+
+    /// Look up a single dotted key (e.g. `api_port` or `blob_store.type`)
+    pub fn get(&self, key: &str) -> Result<Option<toml::Value>, StateError> {
+        Ok(self
+            .entries()?
+            .into_iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v))
+    }
+
+    /// Return a copy of the config with `key` set to `raw`.
+    ///
+    /// The raw value is parsed as a TOML literal when that type-checks against
+    /// the field, and as a plain string otherwise. Unknown keys and values of
+    /// the wrong type are rejected rather than silently ignored.
+    pub fn with_value(&self, key: &str, raw: &str) -> Result<Self, StateError> {
+        let path: Vec<&str> = key.split('.').collect();
+        if path.iter().any(|segment| segment.is_empty()) {
+            return Err(StateError::UnknownConfigKey(key.to_string()));
+        }
+
+        let mut candidates = Vec::new();
+        if let Ok(mut literal) = toml::from_str::<toml::Table>(&format!("v = {}", raw)) {
+            if let Some(value) = literal.remove("v") {
+                candidates.push(value);
+            }
+        }
+        candidates.push(toml::Value::String(raw.to_string()));
+
+        let base = toml::Table::try_from(self)?;
+        let mut last_error = None;
+        for value in candidates {
+            let mut table = base.clone();
+            set_path(&mut table, &path, value.clone());
+
+            let config: AppConfig = match table.try_into() {
+                Ok(config) => config,
+                Err(e) => {
+                    last_error = Some(e.to_string());
+                    continue;
+                }
+            };
+            // Keys that serde ignored or dropped don't exist on the config
+            if config.get(key)?.as_ref() != Some(&value) {
+                last_error = None;
+                continue;
+            }
+            config.log_level()?;
+            return Ok(config);
+        }
+
+        match last_error {
+            Some(reason) => Err(StateError::InvalidConfigValue {
+                key: key.to_string(),
+                reason,
+            }),
+            None => Err(StateError::UnknownConfigKey(key.to_string())),
+        }
+    }
+}
+
+fn flatten_table(prefix: &str, table: &toml::Table, entries: &mut Vec<(String, toml::Value)>) {
+    for (k, v) in table {
+        let key = if prefix.is_empty() {
+            k.clone()
+        } else {
+            format!("{}.{}", prefix, k)
+        };
+        match v {
+            toml::Value::Table(inner) => flatten_table(&key, inner, entries),
+            _ => entries.push((key, v.clone())),
+        }
+    }
+}
+
+fn set_path(table: &mut toml::Table, path: &[&str], value: toml::Value) {
+    let (last, parents) = path.split_last().expect("config key path is never empty");
+    let mut current = table;
+    for segment in parents {
+        let entry = current
+            .entry(segment.to_string())
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+        if !entry.is_table() {
+            *entry = toml::Value::Table(toml::Table::new());
+        }
+        current = entry.as_table_mut().expect("entry was just made a table");
+    }
+    current.insert(last.to_string(), value);
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum StateError {
     #[error("jax directory not initialized. Run 'cli init' first")]
@@ -265,6 +679,15 @@ pub enum StateError {
     #[error("invalid key: {0}")]
     InvalidKey(String),
 
+    #[error("invalid log level: {0}")]
+    InvalidLogLevel(String),
+
+    #[error("unknown config key: {0}")]
+    UnknownConfigKey(String),
+
+    #[error("invalid value for config key {key}: {reason}")]
+    InvalidConfigValue { key: String, reason: String },
+
     #[error("invalid S3 URL: {0}")]
     InvalidS3Url(String),
 
@@ -277,3 +700,137 @@ pub enum StateError {
     #[error("TOML deserialization error: {0}")]
     TomlDe(#[from] toml::de::Error),
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_config_get() {
+        let config = AppConfig::default();
+        assert_eq!(
+            config.get("api_port").unwrap(),
+            Some(toml::Value::Integer(5001))
+        );
+        assert_eq!(
+            config.get("blob_store.type").unwrap(),
+            Some(toml::Value::String("legacy".to_string()))
+        );
+        assert_eq!(config.get("nope").unwrap(), None);
+    }
+
+    #[test]
+    fn test_config_with_value_type_checks() {
+        let config = AppConfig::default();
+
+        let updated = config.with_value("api_port", "6001").unwrap();
+        assert_eq!(updated.api_port, 6001);
+
+        let updated = config.with_value("log_level", "info").unwrap();
+        assert_eq!(updated.log_level, "info");
+
+        let updated = config.with_value("peer_port", "4444").unwrap();
+        assert_eq!(updated.peer_port, Some(4444));
+
+        assert!(matches!(
+            config.with_value("api_port", "not-a-port"),
+            Err(StateError::InvalidConfigValue { .. })
+        ));
+        assert!(matches!(
+            config.with_value("api_port", "70000"),
+            Err(StateError::InvalidConfigValue { .. })
+        ));
+        assert!(matches!(
+            config.with_value("log_level", "loud"),
+            Err(StateError::InvalidLogLevel(_))
+        ));
+    }
+
+    #[test]
+    fn test_config_with_value_rejects_unknown_keys() {
+        let config = AppConfig::default();
+        assert!(matches!(
+            config.with_value("api_prot", "6001"),
+            Err(StateError::UnknownConfigKey(_))
+        ));
+        assert!(matches!(
+            config.with_value("blob_store.bogus", "x"),
+            Err(StateError::UnknownConfigKey(_))
+        ));
+        assert!(matches!(
+            config.with_value("api_port.", "1"),
+            Err(StateError::UnknownConfigKey(_))
+        ));
+    }
+
+    #[test]
+    fn test_config_with_value_blob_store_variant() {
+        let config = AppConfig::default();
+        // Switching variants without the required fields is rejected
+        assert!(matches!(
+            config.with_value("blob_store.type", "filesystem"),
+            Err(StateError::InvalidConfigValue { .. })
+        ));
+    }
+
+    #[test]
+    fn test_config_protocol_timeouts() {
+        let config = AppConfig::default()
+            .with_value("protocol_timeouts.messages.Ping", "5")
+            .unwrap()
+            .with_value("protocol_timeouts.request_secs", "20")
+            .unwrap();
+        let timeouts = config.protocol_timeouts.timeouts();
+        assert_eq!(timeouts.for_message("Ping"), Duration::from_secs(5));
+        assert_eq!(timeouts.for_message("Hello"), Duration::from_secs(10));
+        assert_eq!(timeouts.for_message("Other"), Duration::from_secs(20));
+        assert_eq!(timeouts.connect, DEFAULT_CONNECT_TIMEOUT);
+    }
+
+    #[test]
+    fn test_config_protocol_limits() {
+        let config = AppConfig::default()
+            .with_value("protocol_limits.max_requests_per_peer", "4")
+            .unwrap();
+        let limits = config.protocol_limits.limits();
+        assert_eq!(limits.max_requests_per_peer, 4);
+        assert_eq!(limits.max_connections, DEFAULT_MAX_CONNECTIONS);
+    }
+
+    #[test]
+    fn test_config_upload_limits() {
+        let bucket_id = Uuid::new_v4();
+        let config = AppConfig::default()
+            .with_value("upload_limits.max_bytes", "1024")
+            .unwrap()
+            .with_value(&format!("upload_limits.buckets.{}", bucket_id), "4096")
+            .unwrap();
+        let limits = &config.upload_limits;
+        assert_eq!(limits.for_bucket(&bucket_id), 4096);
+        assert_eq!(limits.for_bucket(&Uuid::new_v4()), 1024);
+        assert_eq!(limits.largest(), 4096);
+
+        assert!(matches!(
+            config.with_value("upload_limits.buckets.not-a-bucket", "1"),
+            Err(StateError::InvalidConfigValue { .. })
+        ));
+    }
+
+    #[test]
+    fn test_init_with_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let jax_dir = dir.path().join("jax");
+        // Logs written before onboarding don't count as a state
+        fs::create_dir_all(jax_dir.join(LOGS_DIR_NAME)).unwrap();
+
+        let key = SecretKey::generate();
+        let state = AppState::init_with_key(Some(jax_dir.clone()), None, key.clone()).unwrap();
+        assert_eq!(state.load_key().unwrap().to_hex(), key.to_hex());
+        assert!(AppState::load(Some(jax_dir.clone())).is_ok());
+
+        assert!(matches!(
+            AppState::init(Some(jax_dir), None),
+            Err(StateError::AlreadyInitialized)
+        ));
+    }
+}
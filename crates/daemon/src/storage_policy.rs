@@ -0,0 +1,283 @@
+//! Storage policies placing the blobs of buckets in other storage backends
+//!
+//! Besides the main blob store, `config.toml` can name other backends blobs
+//! can live in, `[blob_locations.<name>]`: a local directory or an S3
+//! bucket. A bucket's storage policy names the location its blobs are kept
+//! in, e.g. a sensitive bucket in a local directory while the main store is
+//! S3, or an archive bucket in an S3 bucket whose lifecycle rules move
+//! objects to a cold storage class. Buckets without a policy keep their
+//! blobs in the main store, and removing a policy moves them back there.
+//!
+//! Locations need the filesystem or S3 blob store, whose metadata records
+//! where each blob is, so reads, deletes and peers fetching a blob follow it
+//! to its location. Placing a bucket moves the blobs it ever referenced (its
+//! pins) and the manifests of its versions. Blobs referenced by another
+//! bucket placed elsewhere, such as a fork sharing its origin's content, are
+//! left where they are.
+//!
+//! New blobs are written to the main store and placed afterwards, every
+//! [`PLACEMENT_INTERVAL`] by a background task or on request
+//! (`/api/v0/bucket/storage/run`). Content that must never reach a backend
+//! shouldn't be written on a node whose main store is that backend. Placing
+//! is audited as `bucket.storage.place`.
+
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::bucket_log::BucketLogProvider;
+use common::linked_data::{Hash, Link};
+use common::mount::{Mount, MountError};
+use common::peer::{BlobsStoreError, Peer};
+use object_store::ObjectStoreConfig;
+
+use crate::audit::{Actor, AuditEvent, AuditLog};
+use crate::database::Database;
+use crate::state::{BlobStoreConfig, StateError};
+
+/// How often storage policies are applied
+pub const PLACEMENT_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// A storage backend, besides the main blob store, that buckets can be
+/// placed in
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BlobLocationConfig {
+    /// A local directory
+    Filesystem {
+        /// Absolute path the blobs are stored under
+        path: PathBuf,
+    },
+
+    /// S3-compatible object storage
+    S3 {
+        /// S3 URL in the blob store's format:
+        /// s3://access_key:secret_key@endpoint/bucket
+        url: String,
+    },
+}
+
+impl BlobLocationConfig {
+    /// Object storage the location's blobs are kept in
+    pub fn object_store_config(&self) -> Result<ObjectStoreConfig, StateError> {
+        match self {
+            BlobLocationConfig::Filesystem { path } => {
+                Ok(ObjectStoreConfig::Local { path: path.clone() })
+            }
+            BlobLocationConfig::S3 { url } => {
+                let s3 = BlobStoreConfig::parse_s3_url(url)?;
+                Ok(ObjectStoreConfig::S3 {
+                    endpoint: s3.endpoint,
+                    access_key: s3.access_key,
+                    secret_key: s3.secret_key,
+                    bucket: s3.bucket,
+                    region: None,
+                })
+            }
+        }
+    }
+}
+
+/// What placing a bucket's blobs moved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlacementReport {
+    pub bucket_id: Uuid,
+    /// Location the blobs were placed in, None for the main store
+    pub location: Option<String>,
+    /// Blobs moved there; ones already there or not stored here aren't
+    /// counted
+    pub placed_blobs: u64,
+    /// Blobs left where they are because a bucket placed elsewhere
+    /// references them too
+    pub shared_blobs: u64,
+}
+
+/// Place a bucket's blobs in a location, or in the main store with `None`
+pub async fn place(
+    peer: &Peer<Database>,
+    database: &Database,
+    bucket_id: Uuid,
+    location: Option<&str>,
+) -> Result<PlacementReport, StoragePolicyError> {
+    let mut report = PlacementReport {
+        bucket_id,
+        location: location.map(str::to_string),
+        placed_blobs: 0,
+        shared_blobs: 0,
+    };
+    let logs = database.get_all_bucket_logs(&bucket_id).await?;
+    let Some(head) = logs.last() else {
+        return Ok(report);
+    };
+
+    let mut hashes: HashSet<Hash> = logs.iter().map(|entry| entry.current_link.hash()).collect();
+    hashes.extend(pins(peer, &head.current_link).await?);
+    let shared = pinned_elsewhere(peer, database, bucket_id, location).await?;
+
+    for hash in hashes {
+        if shared.contains(&hash) {
+            report.shared_blobs += 1;
+        } else if peer.blobs().place(&hash, location).await? {
+            report.placed_blobs += 1;
+        }
+    }
+    if report.placed_blobs > 0 {
+        tracing::info!(
+            "Placed {} blob(s) of bucket {} in {}",
+            report.placed_blobs,
+            bucket_id,
+            location.unwrap_or("the main store")
+        );
+    }
+    Ok(report)
+}
+
+/// Every blob a version's bucket ever referenced, and the list of them
+async fn pins(peer: &Peer<Database>, link: &Link) -> Result<HashSet<Hash>, StoragePolicyError> {
+    let manifest = Mount::load_manifest(link, peer.blobs()).await?;
+    let list = manifest.pins().hash();
+    let mut hashes: HashSet<Hash> = peer
+        .blobs()
+        .read_hash_list(list)
+        .await?
+        .into_iter()
+        .collect();
+    hashes.insert(list);
+    Ok(hashes)
+}
+
+/// Blobs referenced by other buckets that aren't placed in `location`, as
+/// far as their pins are here
+async fn pinned_elsewhere(
+    peer: &Peer<Database>,
+    database: &Database,
+    bucket_id: Uuid,
+    location: Option<&str>,
+) -> Result<HashSet<Hash>, StoragePolicyError> {
+    let policies: HashMap<Uuid, String> = database
+        .list_storage_policies()
+        .await?
+        .into_iter()
+        .collect();
+    let bucket_ids = BucketLogProvider::list_buckets(peer.logs())
+        .await
+        .map_err(|e| StoragePolicyError::BucketLog(e.to_string()))?;
+
+    let mut hashes = HashSet::new();
+    for other in bucket_ids.into_iter().filter(|id| *id != bucket_id) {
+        if policies.get(&other).map(String::as_str) == location {
+            continue;
+        }
+        let Ok((link, _)) = peer.logs().head(other, None).await else {
+            continue;
+        };
+        if let Ok(pinned) = pins(peer, &link).await {
+            hashes.extend(pinned);
+        }
+    }
+    Ok(hashes)
+}
+
+/// Apply every stored storage policy every [`PLACEMENT_INTERVAL`] until the
+/// daemon exits
+pub(crate) async fn run(peer: Peer<Database>, database: Database, audit: AuditLog) {
+    let mut interval = tokio::time::interval(PLACEMENT_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            _ = peer.cancelled() => return,
+        }
+        let policies = match database.list_storage_policies().await {
+            Ok(policies) => policies,
+            Err(e) => {
+                tracing::error!("Failed to load storage policies: {}", e);
+                continue;
+            }
+        };
+        for (bucket_id, location) in policies {
+            match place(&peer, &database, bucket_id, Some(&location)).await {
+                Ok(report) => audit_placement(&audit, Actor::Storage, &report).await,
+                Err(e) => tracing::warn!(
+                    "Failed to apply the storage policy of bucket {}: {}",
+                    bucket_id,
+                    e
+                ),
+            }
+        }
+    }
+}
+
+/// Audit a placement that moved anything
+pub(crate) async fn audit_placement(audit: &AuditLog, actor: Actor, report: &PlacementReport) {
+    if report.placed_blobs == 0 {
+        return;
+    }
+    audit
+        .record(
+            AuditEvent::new(actor, "bucket.storage.place")
+                .bucket(report.bucket_id)
+                .detail(format!(
+                    "{} blob(s) to {}",
+                    report.placed_blobs,
+                    report.location.as_deref().unwrap_or("the main store")
+                )),
+        )
+        .await;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum StoragePolicyError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Bucket log error: {0}")]
+    BucketLog(String),
+    #[error("Mount error: {0}")]
+    Mount(#[from] MountError),
+    #[error("Blobs error: {0}")]
+    Blobs(#[from] BlobsStoreError),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_location_config() {
+        let locations: HashMap<String, BlobLocationConfig> = toml::from_str(
+            r#"
+            [local]
+            type = "filesystem"
+            path = "/var/lib/jax/sensitive"
+
+            [archive]
+            type = "s3"
+            url = "s3://key:secret@s3.example.com/jax-archive"
+            "#,
+        )
+        .unwrap();
+
+        match locations["local"].object_store_config().unwrap() {
+            ObjectStoreConfig::Local { path } => {
+                assert_eq!(path, PathBuf::from("/var/lib/jax/sensitive"))
+            }
+            other => panic!("expected a local store, got {:?}", other),
+        }
+        match locations["archive"].object_store_config().unwrap() {
+            ObjectStoreConfig::S3 {
+                endpoint, bucket, ..
+            } => {
+                assert_eq!(endpoint, "https://s3.example.com");
+                assert_eq!(bucket, "jax-archive");
+            }
+            other => panic!("expected an S3 store, got {:?}", other),
+        }
+
+        let bad = BlobLocationConfig::S3 {
+            url: "https://s3.example.com/jax-archive".to_string(),
+        };
+        assert!(bad.object_store_config().is_err());
+    }
+}
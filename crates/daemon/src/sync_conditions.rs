@@ -0,0 +1,404 @@
+//! Power and network conditions that syncing adapts to
+//!
+//! On battery, the periodic checker spreads pings out by
+//! [`SyncDeferral::battery_interval_multiplier`]. On a metered network (a
+//! phone hotspot, a capped plan), the worker holds back pin downloads until the
+//! network is unmetered again; bucket metadata keeps syncing, and online-only
+//! buckets still fetch what is read. Both are on by default and set in the
+//! `[sync_deferral]` section of the config, which can also override metered
+//! detection.
+//!
+//! Conditions are probed from the platform every [`PROBE_INTERVAL`] (power
+//! supplies and NetworkManager on Linux, `pmset` on macOS). Hosts that know
+//! better, such as the desktop app or a mobile shell, report them through
+//! `POST /api/v0/sync/conditions` instead, which takes precedence over the
+//! probe until cleared.
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+
+/// How often the platform is probed for conditions
+pub const PROBE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Power and network state of the machine
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Conditions {
+    /// Running on battery power
+    #[serde(default)]
+    pub on_battery: bool,
+    /// Battery charge in percent, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub battery_percent: Option<u8>,
+    /// Connected through a metered network
+    #[serde(default)]
+    pub metered: bool,
+}
+
+/// Override of metered network detection
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MeteredOverride {
+    /// Use the detected or reported state
+    #[default]
+    Auto,
+    /// Always treat the network as metered
+    Always,
+    /// Never treat the network as metered
+    Never,
+}
+
+/// How syncing adapts to conditions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncDeferral {
+    /// Spread out periodic pings while on battery
+    #[serde(default = "default_true")]
+    pub on_battery: bool,
+    /// How many times longer the interval between pings is on battery
+    #[serde(default = "default_battery_interval_multiplier")]
+    pub battery_interval_multiplier: u32,
+    /// Hold back pin downloads on a metered network
+    #[serde(default = "default_true")]
+    pub defer_downloads_on_metered: bool,
+    /// Override metered network detection
+    #[serde(default)]
+    pub metered: MeteredOverride,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_battery_interval_multiplier() -> u32 {
+    4
+}
+
+impl Default for SyncDeferral {
+    fn default() -> Self {
+        Self {
+            on_battery: true,
+            battery_interval_multiplier: default_battery_interval_multiplier(),
+            defer_downloads_on_metered: true,
+            metered: MeteredOverride::Auto,
+        }
+    }
+}
+
+impl SyncDeferral {
+    /// Factor to stretch periodic ping intervals by
+    pub fn interval_multiplier(&self, conditions: &Conditions) -> u32 {
+        if self.on_battery && conditions.on_battery {
+            self.battery_interval_multiplier.max(1)
+        } else {
+            1
+        }
+    }
+
+    /// Whether the network counts as metered
+    pub fn is_metered(&self, conditions: &Conditions) -> bool {
+        match self.metered {
+            MeteredOverride::Auto => conditions.metered,
+            MeteredOverride::Always => true,
+            MeteredOverride::Never => false,
+        }
+    }
+
+    /// Whether pin downloads are held back
+    pub fn defers_downloads(&self, conditions: &Conditions) -> bool {
+        self.defer_downloads_on_metered && self.is_metered(conditions)
+    }
+}
+
+/// Current conditions, shared between the probe, hosts and the sync worker
+#[derive(Debug, Clone)]
+pub struct SyncConditions {
+    current: Arc<watch::Sender<Conditions>>,
+    sources: Arc<Mutex<Sources>>,
+}
+
+#[derive(Debug, Default)]
+struct Sources {
+    probed: Conditions,
+    reported: Option<Conditions>,
+}
+
+impl Default for SyncConditions {
+    fn default() -> Self {
+        Self {
+            current: Arc::new(watch::channel(Conditions::default()).0),
+            sources: Arc::default(),
+        }
+    }
+}
+
+impl SyncConditions {
+    /// The conditions in effect: reported ones if any, else probed ones
+    pub fn current(&self) -> Conditions {
+        *self.current.borrow()
+    }
+
+    /// Subscribe to changes in the conditions in effect
+    pub fn subscribe(&self) -> watch::Receiver<Conditions> {
+        self.current.subscribe()
+    }
+
+    /// Whether a host has reported conditions
+    pub fn is_reported(&self) -> bool {
+        self.sources.lock().unwrap().reported.is_some()
+    }
+
+    /// Report conditions from the host, replacing probed ones, or go back to
+    /// probing with None
+    pub fn report(&self, reported: Option<Conditions>) {
+        let mut sources = self.sources.lock().unwrap();
+        sources.reported = reported;
+        self.update(&sources);
+    }
+
+    fn set_probed(&self, probed: Conditions) {
+        let mut sources = self.sources.lock().unwrap();
+        sources.probed = probed;
+        self.update(&sources);
+    }
+
+    fn update(&self, sources: &Sources) {
+        let conditions = sources.reported.unwrap_or(sources.probed);
+        self.current.send_if_modified(|current| {
+            let changed = *current != conditions;
+            *current = conditions;
+            changed
+        });
+    }
+}
+
+/// Probe the platform for conditions every [`PROBE_INTERVAL`]
+pub(crate) async fn run_probe(conditions: SyncConditions) {
+    let mut interval = tokio::time::interval(PROBE_INTERVAL);
+    loop {
+        interval.tick().await;
+        let probed = probe().await;
+        if probed != conditions.sources.lock().unwrap().probed {
+            tracing::info!(
+                on_battery = probed.on_battery,
+                battery_percent = ?probed.battery_percent,
+                metered = probed.metered,
+                "Power or network conditions changed"
+            );
+        }
+        conditions.set_probed(probed);
+    }
+}
+
+/// Read the conditions of this machine. Anything that can't be read counts as
+/// mains power on an unmetered network.
+pub async fn probe() -> Conditions {
+    if cfg!(target_os = "macos") {
+        command_output("pmset", &["-g", "batt"])
+            .await
+            .map(|output| parse_pmset(&output))
+            .unwrap_or_default()
+    } else if cfg!(target_os = "linux") {
+        let mut conditions = read_power_supplies(Path::new("/sys/class/power_supply"));
+        conditions.metered =
+            command_output("nmcli", &["-t", "-f", "GENERAL.METERED", "dev", "show"])
+                .await
+                .map(|output| parse_nmcli_metered(&output))
+                .unwrap_or(false);
+        conditions
+    } else {
+        Conditions::default()
+    }
+}
+
+async fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = tokio::process::Command::new(program)
+        .args(args)
+        .output()
+        .await
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Read batteries from a Linux `power_supply` class directory
+fn read_power_supplies(dir: &Path) -> Conditions {
+    let mut conditions = Conditions::default();
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return conditions;
+    };
+    for entry in entries.flatten() {
+        let read = |name: &str| {
+            std::fs::read_to_string(entry.path().join(name))
+                .map(|value| value.trim().to_string())
+                .unwrap_or_default()
+        };
+        if read("type") != "Battery" || read("scope") == "Device" {
+            continue;
+        }
+        if read("status") == "Discharging" {
+            conditions.on_battery = true;
+        }
+        if let Ok(percent) = read("capacity").parse::<u8>() {
+            conditions.battery_percent = Some(percent.min(100));
+        }
+    }
+    conditions
+}
+
+/// Parse `pmset -g batt`, e.g. "Now drawing from 'Battery Power'" followed by
+/// " -InternalBattery-0 (id=1234)\t85%; discharging; 4:10 remaining"
+fn parse_pmset(output: &str) -> Conditions {
+    let on_battery = output.contains("'Battery Power'");
+    let battery_percent = output.lines().find_map(|line| {
+        let (before, _) = line.split_once('%')?;
+        before
+            .rsplit(|c: char| !c.is_ascii_digit())
+            .next()?
+            .parse::<u8>()
+            .ok()
+    });
+    Conditions {
+        on_battery,
+        battery_percent,
+        metered: false,
+    }
+}
+
+/// Parse `nmcli -t -f GENERAL.METERED dev show`, one "GENERAL.METERED:yes"
+/// line per device. NetworkManager guesses hotspots as "yes (guessed)".
+fn parse_nmcli_metered(output: &str) -> bool {
+    output
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .any(|(_, value)| value.starts_with("yes"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_pmset() {
+        let on_battery = "Now drawing from 'Battery Power'\n \
+            -InternalBattery-0 (id=4653155)\t85%; discharging; 4:10 remaining present: true\n";
+        assert_eq!(
+            parse_pmset(on_battery),
+            Conditions {
+                on_battery: true,
+                battery_percent: Some(85),
+                metered: false,
+            }
+        );
+
+        let charging = "Now drawing from 'AC Power'\n \
+            -InternalBattery-0 (id=4653155)\t100%; charged; 0:00 remaining present: true\n";
+        let conditions = parse_pmset(charging);
+        assert!(!conditions.on_battery);
+        assert_eq!(conditions.battery_percent, Some(100));
+
+        assert_eq!(
+            parse_pmset("Now drawing from 'AC Power'\n"),
+            Conditions::default()
+        );
+    }
+
+    #[test]
+    fn test_parse_nmcli_metered() {
+        assert!(parse_nmcli_metered(
+            "GENERAL.METERED:no\nGENERAL.METERED:yes (guessed)\nGENERAL.METERED:unknown\n"
+        ));
+        assert!(!parse_nmcli_metered(
+            "GENERAL.METERED:no (guessed)\nGENERAL.METERED:unknown\n"
+        ));
+        assert!(!parse_nmcli_metered(""));
+    }
+
+    #[test]
+    fn test_read_power_supplies() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |supply: &str, name: &str, value: &str| {
+            let path = dir.path().join(supply);
+            std::fs::create_dir_all(&path).unwrap();
+            std::fs::write(path.join(name), format!("{}\n", value)).unwrap();
+        };
+        write("AC", "type", "Mains");
+        write("AC", "online", "0");
+        write("BAT0", "type", "Battery");
+        write("BAT0", "status", "Discharging");
+        write("BAT0", "capacity", "42");
+        // A wireless mouse's battery says nothing about the machine
+        write("hidpp_battery_0", "type", "Battery");
+        write("hidpp_battery_0", "scope", "Device");
+        write("hidpp_battery_0", "status", "Discharging");
+        write("hidpp_battery_0", "capacity", "7");
+
+        let conditions = read_power_supplies(dir.path());
+        assert!(conditions.on_battery);
+        assert_eq!(conditions.battery_percent, Some(42));
+
+        write("BAT0", "status", "Charging");
+        assert!(!read_power_supplies(dir.path()).on_battery);
+        assert_eq!(
+            read_power_supplies(&dir.path().join("missing")),
+            Conditions::default()
+        );
+    }
+
+    #[test]
+    fn test_deferral() {
+        let deferral = SyncDeferral::default();
+        let mains = Conditions::default();
+        let battery_hotspot = Conditions {
+            on_battery: true,
+            battery_percent: Some(30),
+            metered: true,
+        };
+        assert_eq!(deferral.interval_multiplier(&mains), 1);
+        assert_eq!(deferral.interval_multiplier(&battery_hotspot), 4);
+        assert!(!deferral.defers_downloads(&mains));
+        assert!(deferral.defers_downloads(&battery_hotspot));
+
+        let overridden = SyncDeferral {
+            on_battery: false,
+            metered: MeteredOverride::Never,
+            ..SyncDeferral::default()
+        };
+        assert_eq!(overridden.interval_multiplier(&battery_hotspot), 1);
+        assert!(!overridden.defers_downloads(&battery_hotspot));
+
+        let always = SyncDeferral {
+            metered: MeteredOverride::Always,
+            ..SyncDeferral::default()
+        };
+        assert!(always.defers_downloads(&mains));
+    }
+
+    #[test]
+    fn test_reported_conditions_take_precedence() {
+        let conditions = SyncConditions::default();
+        let mut rx = conditions.subscribe();
+        let metered = Conditions {
+            metered: true,
+            ..Conditions::default()
+        };
+
+        conditions.set_probed(metered);
+        assert!(rx.has_changed().unwrap());
+        assert_eq!(*rx.borrow_and_update(), metered);
+
+        conditions.report(Some(Conditions::default()));
+        assert!(conditions.is_reported());
+        assert_eq!(conditions.current(), Conditions::default());
+        // Probes don't override the host
+        conditions.set_probed(metered);
+        assert_eq!(conditions.current(), Conditions::default());
+
+        conditions.report(None);
+        assert_eq!(conditions.current(), metered);
+    }
+}
@@ -0,0 +1,404 @@
+//! Per-bucket sync policies
+//!
+//! By default every bucket's peers are pinged at the daemon's sync interval.
+//! A policy can give a bucket its own interval, limit periodic syncing to a
+//! daily window of local time, leave the bucket to manual syncs only, or pause
+//! it. An online-only bucket keeps syncing its metadata but fetches file
+//! content on demand (see [`crate::blob_cache`]), and a shallow bucket only
+//! downloads the content of its most recent versions. Policies are stored in
+//! the database and mirrored in [`SyncPolicies`] so the sync worker can
+//! consult them without a query per tick.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+use uuid::Uuid;
+
+/// Shortest per-bucket interval accepted
+pub const MIN_INTERVAL_SECS: u64 = 5;
+
+/// How a bucket is synced by the periodic checker
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncPolicy {
+    /// Seconds between periodic pings; None uses the daemon's sync interval
+    #[serde(default)]
+    pub interval_secs: Option<u64>,
+    /// Never ping periodically; updates announced by peers and manual syncs
+    /// still apply
+    #[serde(default)]
+    pub manual_only: bool,
+    /// Don't sync this bucket at all; sync jobs for it are dropped
+    #[serde(default)]
+    pub paused: bool,
+    /// Only ping periodically within this daily window of local time
+    #[serde(default)]
+    pub active_hours: Option<ActiveHours>,
+    /// Don't download the bucket's content after a sync; blobs are fetched
+    /// from peers when read and evicted when the cache is full
+    #[serde(default)]
+    pub online_only: bool,
+    /// Only download the content of this many of the most recent versions;
+    /// None keeps the content of every version
+    #[serde(default)]
+    pub history_depth: Option<u64>,
+}
+
+impl SyncPolicy {
+    /// Interval between periodic pings, given the daemon default
+    pub fn interval(&self, default: Duration) -> Duration {
+        self.interval_secs
+            .map(|secs| Duration::from_secs(secs.max(MIN_INTERVAL_SECS)))
+            .unwrap_or(default)
+    }
+
+    /// Whether the periodic checker may ping the bucket at local time `now`
+    pub fn allows_periodic(&self, now: NaiveTime) -> bool {
+        !self.paused
+            && !self.manual_only
+            && self
+                .active_hours
+                .as_ref()
+                .map(|hours| hours.contains(now))
+                .unwrap_or(true)
+    }
+
+    /// Whether the content of the version at `height` is kept, given the
+    /// height of the bucket's head
+    pub fn keeps_version(&self, head_height: u64, height: u64) -> bool {
+        self.history_depth
+            .map(|depth| head_height.saturating_sub(height) < depth)
+            .unwrap_or(true)
+    }
+
+    pub fn validate(&self) -> Result<(), SyncPolicyError> {
+        if self.history_depth == Some(0) {
+            return Err(SyncPolicyError::InvalidDepth);
+        }
+        match self.interval_secs {
+            Some(secs) if secs < MIN_INTERVAL_SECS => Err(SyncPolicyError::IntervalTooShort),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A daily window of local time, e.g. `08:00-18:00`.
+/// A window whose end is before its start wraps past midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActiveHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl ActiveHours {
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+impl fmt::Display for ActiveHours {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}-{}",
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M")
+        )
+    }
+}
+
+impl FromStr for ActiveHours {
+    type Err = SyncPolicyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || SyncPolicyError::InvalidActiveHours(s.to_string());
+        let (start, end) = s.split_once('-').ok_or_else(invalid)?;
+        let parse = |t: &str| NaiveTime::parse_from_str(t.trim(), "%H:%M").map_err(|_| invalid());
+        let hours = Self {
+            start: parse(start)?,
+            end: parse(end)?,
+        };
+        if hours.start == hours.end {
+            return Err(invalid());
+        }
+        Ok(hours)
+    }
+}
+
+impl Serialize for ActiveHours {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ActiveHours {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// In-memory copy of the stored policies, shared with the sync worker
+#[derive(Debug, Clone)]
+pub struct SyncPolicies(Arc<watch::Sender<HashMap<Uuid, SyncPolicy>>>);
+
+impl Default for SyncPolicies {
+    fn default() -> Self {
+        Self::new(HashMap::new())
+    }
+}
+
+impl SyncPolicies {
+    pub fn new(policies: HashMap<Uuid, SyncPolicy>) -> Self {
+        Self(Arc::new(watch::channel(policies).0))
+    }
+
+    /// Policy for a bucket, the default one if none is set
+    pub fn get(&self, bucket_id: &Uuid) -> SyncPolicy {
+        self.0.borrow().get(bucket_id).cloned().unwrap_or_default()
+    }
+
+    /// All buckets with a policy set
+    pub fn all(&self) -> HashMap<Uuid, SyncPolicy> {
+        self.0.borrow().clone()
+    }
+
+    pub(crate) fn set(&self, bucket_id: Uuid, policy: SyncPolicy) {
+        self.0.send_modify(|policies| {
+            policies.insert(bucket_id, policy);
+        });
+    }
+
+    pub(crate) fn remove(&self, bucket_id: &Uuid) {
+        self.0.send_modify(|policies| {
+            policies.remove(bucket_id);
+        });
+    }
+
+    /// Buckets whose content is fetched on demand
+    pub fn online_only(&self) -> Vec<Uuid> {
+        self.0
+            .borrow()
+            .iter()
+            .filter(|(_, policy)| policy.online_only)
+            .map(|(bucket_id, _)| *bucket_id)
+            .collect()
+    }
+
+    /// Subscribe to policy changes
+    pub fn subscribe(&self) -> watch::Receiver<HashMap<Uuid, SyncPolicy>> {
+        self.0.subscribe()
+    }
+
+    /// How often the periodic checker has to wake up: the shortest interval
+    /// in use, so every bucket is pinged on time
+    pub fn tick_interval(&self, default: Duration) -> Duration {
+        self.0
+            .borrow()
+            .values()
+            .filter(|policy| !policy.paused && !policy.manual_only)
+            .map(|policy| policy.interval(default))
+            .fold(default, Duration::min)
+    }
+}
+
+/// Tracks when each bucket was last pinged by the periodic checker
+#[derive(Debug, Default)]
+pub(crate) struct PingSchedule {
+    last_ping: HashMap<Uuid, Instant>,
+    /// Factor intervals are stretched by, e.g. on battery (0 counts as 1)
+    interval_multiplier: u32,
+}
+
+impl PingSchedule {
+    pub(crate) fn set_interval_multiplier(&mut self, multiplier: u32) {
+        self.interval_multiplier = multiplier;
+    }
+
+    /// Whether the bucket should be pinged now. Records the ping if so.
+    pub(crate) fn due(
+        &mut self,
+        bucket_id: Uuid,
+        policy: &SyncPolicy,
+        default_interval: Duration,
+        now: Instant,
+        local_time: NaiveTime,
+    ) -> bool {
+        if !policy.allows_periodic(local_time) {
+            return false;
+        }
+        // Allow a little slack so ticks that land just short of the interval
+        // don't push the ping back by a whole tick
+        let interval = policy.interval(default_interval) * self.interval_multiplier.max(1);
+        let slack = interval / 10;
+        let due = self
+            .last_ping
+            .get(&bucket_id)
+            .map(|last| now.duration_since(*last) + slack >= interval)
+            .unwrap_or(true);
+        if due {
+            self.last_ping.insert(bucket_id, now);
+        }
+        due
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SyncPolicyError {
+    #[error("sync interval must be at least {MIN_INTERVAL_SECS} seconds")]
+    IntervalTooShort,
+    #[error("invalid active hours '{0}' (expected HH:MM-HH:MM)")]
+    InvalidActiveHours(String),
+    #[error("history depth must be at least 1")]
+    InvalidDepth,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn time(s: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(s, "%H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_active_hours() {
+        let day: ActiveHours = "08:00-18:00".parse().unwrap();
+        assert!(day.contains(time("08:00")));
+        assert!(day.contains(time("12:30")));
+        assert!(!day.contains(time("18:00")));
+        assert!(!day.contains(time("23:00")));
+        assert_eq!(day.to_string(), "08:00-18:00");
+
+        let night: ActiveHours = "22:00-06:00".parse().unwrap();
+        assert!(night.contains(time("23:30")));
+        assert!(night.contains(time("05:59")));
+        assert!(!night.contains(time("12:00")));
+
+        assert!("08:00".parse::<ActiveHours>().is_err());
+        assert!("8am-6pm".parse::<ActiveHours>().is_err());
+        assert!("08:00-08:00".parse::<ActiveHours>().is_err());
+    }
+
+    #[test]
+    fn test_ping_schedule() {
+        let default = Duration::from_secs(60);
+        let bucket_id = Uuid::new_v4();
+        let noon = time("12:00");
+        let start = Instant::now();
+        let mut schedule = PingSchedule::default();
+
+        let policy = SyncPolicy {
+            interval_secs: Some(300),
+            ..SyncPolicy::default()
+        };
+        assert!(schedule.due(bucket_id, &policy, default, start, noon));
+        assert!(!schedule.due(
+            bucket_id,
+            &policy,
+            default,
+            start + Duration::from_secs(60),
+            noon
+        ));
+        assert!(schedule.due(
+            bucket_id,
+            &policy,
+            default,
+            start + Duration::from_secs(300),
+            noon
+        ));
+
+        let manual = SyncPolicy {
+            manual_only: true,
+            ..SyncPolicy::default()
+        };
+        assert!(!schedule.due(Uuid::new_v4(), &manual, default, start, noon));
+
+        let office = SyncPolicy {
+            active_hours: Some("09:00-17:00".parse().unwrap()),
+            ..SyncPolicy::default()
+        };
+        assert!(!schedule.due(Uuid::new_v4(), &office, default, start, time("20:00")));
+        assert!(schedule.due(Uuid::new_v4(), &office, default, start, noon));
+
+        // On battery the interval stretches
+        schedule.set_interval_multiplier(4);
+        assert!(!schedule.due(
+            bucket_id,
+            &policy,
+            default,
+            start + Duration::from_secs(600),
+            noon
+        ));
+        assert!(schedule.due(
+            bucket_id,
+            &policy,
+            default,
+            start + Duration::from_secs(1500),
+            noon
+        ));
+    }
+
+    #[test]
+    fn test_keeps_version() {
+        let full = SyncPolicy::default();
+        assert!(full.keeps_version(10, 0));
+
+        let shallow = SyncPolicy {
+            history_depth: Some(1),
+            ..SyncPolicy::default()
+        };
+        assert!(shallow.keeps_version(10, 10));
+        assert!(!shallow.keeps_version(10, 9));
+
+        let recent = SyncPolicy {
+            history_depth: Some(3),
+            ..SyncPolicy::default()
+        };
+        assert!(recent.keeps_version(10, 8));
+        assert!(!recent.keeps_version(10, 7));
+
+        assert!(SyncPolicy {
+            history_depth: Some(0),
+            ..SyncPolicy::default()
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn test_tick_interval() {
+        let default = Duration::from_secs(60);
+        let policies = SyncPolicies::default();
+        assert_eq!(policies.tick_interval(default), default);
+
+        policies.set(
+            Uuid::new_v4(),
+            SyncPolicy {
+                interval_secs: Some(10),
+                paused: true,
+                ..SyncPolicy::default()
+            },
+        );
+        assert_eq!(policies.tick_interval(default), default);
+
+        policies.set(
+            Uuid::new_v4(),
+            SyncPolicy {
+                interval_secs: Some(20),
+                ..SyncPolicy::default()
+            },
+        );
+        assert_eq!(policies.tick_interval(default), Duration::from_secs(20));
+    }
+}
@@ -3,10 +3,28 @@
 //! This module provides the app-specific implementation of `SyncProvider` using
 //! a flume channel-based job queue with a background worker.
 
+use std::collections::HashMap;
+use std::sync::Arc;
+
 use anyhow::Result;
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
+use common::peer::sync::DownloadPinsJob;
 use common::peer::{SyncJob, SyncProvider};
+use tokio::sync::watch;
+use tracing::Instrument;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::audit::AuditLog;
+use crate::blob_cache::{self, BlobCache};
+use crate::cluster::{self, Cluster, PERIODIC_SYNC_LEASE};
+use crate::database::Database;
+use crate::reload::RuntimeConfig;
+use crate::sync_conditions::SyncConditions;
+use crate::sync_policy::{PingSchedule, SyncPolicies};
+use crate::webhooks::Webhooks;
 
 /// Configuration for the queued sync provider
 #[derive(Debug, Clone)]
@@ -24,6 +42,151 @@ impl Default for QueuedSyncConfig {
     }
 }
 
+/// A queued sync job and the trace context it was queued from
+///
+/// The worker runs the job in a span parented to that context, so a trace that
+/// starts at an API request or protocol message continues through the queue.
+/// Only the context is kept (not the span itself) so the enqueuing span can
+/// close before the job runs.
+#[derive(Debug)]
+pub struct QueuedJob {
+    pub job: SyncJob,
+    pub trace_context: opentelemetry::Context,
+}
+
+/// What the sync worker is doing, for status displays
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "state", content = "error", rename_all = "snake_case")]
+pub enum SyncActivity {
+    /// Waiting for jobs
+    #[default]
+    Idle,
+    /// Running a job
+    Syncing,
+    /// The last job failed; cleared by the next successful one
+    Error(String),
+}
+
+/// Sync status shared between the worker and the rest of the daemon
+#[derive(Debug, Clone)]
+pub struct SyncStatus {
+    activity: Arc<watch::Sender<SyncActivity>>,
+    paused: Arc<watch::Sender<bool>>,
+    parked: Arc<watch::Sender<usize>>,
+    deferred: Arc<watch::Sender<usize>>,
+    conditions: SyncConditions,
+}
+
+impl Default for SyncStatus {
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+impl SyncStatus {
+    pub fn new(paused: bool) -> Self {
+        Self {
+            activity: Arc::new(watch::channel(SyncActivity::Idle).0),
+            paused: Arc::new(watch::channel(paused).0),
+            parked: Arc::new(watch::channel(0).0),
+            deferred: Arc::new(watch::channel(0).0),
+            conditions: SyncConditions::default(),
+        }
+    }
+
+    /// Subscribe to changes in worker activity
+    pub fn subscribe(&self) -> watch::Receiver<SyncActivity> {
+        self.activity.subscribe()
+    }
+
+    pub fn activity(&self) -> SyncActivity {
+        self.activity.borrow().clone()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        *self.paused.borrow()
+    }
+
+    /// Pause or resume syncing. This only affects the running worker; use
+    /// `ServiceState::set_sync_paused` to also persist the switch.
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.send_replace(paused);
+    }
+
+    /// Subscribe to the pause switch
+    pub fn subscribe_paused(&self) -> watch::Receiver<bool> {
+        self.paused.subscribe()
+    }
+
+    /// Number of jobs held back while syncing is paused
+    pub fn parked_jobs(&self) -> usize {
+        *self.parked.borrow()
+    }
+
+    /// Number of pin downloads held back on a metered network
+    pub fn deferred_jobs(&self) -> usize {
+        *self.deferred.borrow()
+    }
+
+    /// Power and network conditions the worker adapts to
+    pub fn conditions(&self) -> &SyncConditions {
+        &self.conditions
+    }
+}
+
+/// Maximum number of non-bucket jobs held back while paused
+const MAX_PARKED_JOBS: usize = 1000;
+
+/// Jobs received while syncing is paused, replayed on resume
+///
+/// Only the newest target is kept per bucket. Ping jobs are dropped, since the
+/// worker pings every bucket's peers again when syncing resumes.
+#[derive(Debug, Default)]
+struct ParkedJobs {
+    buckets: HashMap<Uuid, QueuedJob>,
+    other: Vec<QueuedJob>,
+}
+
+impl ParkedJobs {
+    fn park(&mut self, queued: QueuedJob) {
+        match &queued.job {
+            SyncJob::SyncBucket(job) => {
+                let newer = self
+                    .buckets
+                    .get(&job.bucket_id)
+                    .map(|parked| match &parked.job {
+                        SyncJob::SyncBucket(parked) => job.target.height >= parked.target.height,
+                        _ => true,
+                    })
+                    .unwrap_or(true);
+                if newer {
+                    self.buckets.insert(job.bucket_id, queued);
+                }
+            }
+            SyncJob::PingPeer(_) => {
+                tracing::debug!("Syncing is paused, dropping ping job");
+            }
+            SyncJob::DownloadPins(_) => {
+                if self.other.len() < MAX_PARKED_JOBS {
+                    self.other.push(queued);
+                } else {
+                    tracing::warn!("Too many parked jobs, dropping pins download");
+                }
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.buckets.len() + self.other.len()
+    }
+
+    fn take(&mut self) -> Vec<QueuedJob> {
+        let mut jobs: Vec<QueuedJob> = self.buckets.drain().map(|(_, job)| job).collect();
+        jobs.append(&mut self.other);
+        jobs
+    }
+}
+
 /// Queue-based implementation of SyncProvider
 ///
 /// This implementation uses a flume channel to queue sync jobs and processes
@@ -31,7 +194,7 @@ impl Default for QueuedSyncConfig {
 /// blocking protocol handlers.
 #[derive(Debug, Clone)]
 pub struct QueuedSyncProvider {
-    tx: flume::Sender<SyncJob>,
+    tx: flume::Sender<QueuedJob>,
 }
 
 impl QueuedSyncProvider {
@@ -53,6 +216,11 @@ impl QueuedSyncProvider {
 
         (Self { tx }, JobReceiver { rx })
     }
+
+    /// Number of jobs waiting in the queue
+    pub fn pending_jobs(&self) -> usize {
+        self.tx.len()
+    }
 }
 
 #[async_trait]
@@ -63,7 +231,11 @@ where
 {
     async fn execute(&self, _peer: &common::peer::Peer<L>, job: SyncJob) -> Result<()> {
         tracing::debug!("Queueing job for background execution: {:?}", job);
-        self.tx.try_send(job).map_err(|e| match e {
+        let queued = QueuedJob {
+            job,
+            trace_context: tracing::Span::current().context(),
+        };
+        self.tx.try_send(queued).map_err(|e| match e {
             flume::TrySendError::Full(_) => {
                 anyhow::anyhow!("job queue is full - worker may be overloaded")
             }
@@ -80,19 +252,30 @@ where
 /// in a worker task.
 #[derive(Debug)]
 pub struct JobReceiver {
-    rx: flume::Receiver<SyncJob>,
+    rx: flume::Receiver<QueuedJob>,
 }
 
 impl JobReceiver {
     /// Convert to an async stream for use in tokio::select!
-    pub fn into_async(self) -> flume::r#async::RecvStream<'static, SyncJob> {
+    pub fn into_async(self) -> flume::r#async::RecvStream<'static, QueuedJob> {
         self.rx.into_stream()
     }
 }
 
 /// Run the background worker for queued sync jobs
 ///
-/// This function processes jobs from the queue and also runs periodic ping scheduling.
+/// This function processes jobs from the queue and also runs periodic ping scheduling,
+/// following each bucket's [`SyncPolicy`](crate::sync_policy::SyncPolicy).
+/// While syncing is paused, periodic pings stop and incoming jobs are parked;
+/// on resume the parked jobs run and every bucket's peers are pinged.
+/// Periodic pings are spread out on battery and pin downloads are deferred on
+/// a metered network (see [`crate::sync_conditions`]).
+/// With `published_only`, as on a read replica (see [`crate::replica`]), only
+/// the pins of published versions are downloaded.
+/// In a `cluster`, bucket syncs and pin downloads are claimed before they
+/// run, and only the instance holding the periodic sync lease pings peers
+/// (see [`crate::cluster`]).
+/// The worker stops once the peer shuts down (see `common::peer::spawn`).
 /// It should be spawned in a background task.
 ///
 /// # Example
@@ -105,39 +288,151 @@ impl JobReceiver {
 ///     .await;
 ///
 /// tokio::spawn(async move {
-///     run_worker(peer, job_receiver.into_async()).await;
+///     run_worker(peer, job_receiver.into_async(), reloader.subscribe(), status, policies, cache, audit, webhooks, database, true, false, None)
+///         .await;
 /// });
 /// ```
+#[allow(clippy::too_many_arguments)]
 pub async fn run_worker<L>(
     peer: common::peer::Peer<L>,
-    mut job_stream: flume::r#async::RecvStream<'static, SyncJob>,
+    mut job_stream: flume::r#async::RecvStream<'static, QueuedJob>,
+    mut runtime_rx: watch::Receiver<RuntimeConfig>,
+    status: SyncStatus,
+    policies: SyncPolicies,
+    cache: BlobCache,
+    audit: AuditLog,
+    webhooks: Webhooks,
+    database: Database,
+    periodic: bool,
+    published_only: bool,
+    cluster: Option<Cluster>,
 ) where
     L: common::bucket_log::BucketLogProvider + Clone + Send + Sync + 'static,
     L::Error: std::error::Error + Send + Sync + 'static,
 {
     use futures::StreamExt;
-    use tokio::time::{interval, Duration};
+    use tokio::time::interval;
 
     tracing::info!("Starting background job worker for peer {}", peer.id());
 
-    // Create interval timer for periodic pings (every 5 seconds)
-    let mut ping_interval = interval(Duration::from_secs(60));
+    // Create interval timer for periodic pings. It ticks at the shortest
+    // interval in use; each tick only pings the buckets that are due.
+    // On battery, both stretch by the deferral's multiplier.
+    let runtime = *runtime_rx.borrow_and_update();
+    let mut sync_interval = runtime.sync_interval;
+    let mut deferral = runtime.sync_deferral;
+    let mut conditions_rx = status.conditions.subscribe();
+    let mut conditions = *conditions_rx.borrow_and_update();
+    let mut multiplier = deferral.interval_multiplier(&conditions);
+    let mut policies_rx = policies.subscribe();
+    policies_rx.borrow_and_update();
+    let mut tick_interval = policies.tick_interval(sync_interval) * multiplier;
+    let mut ping_interval = interval(tick_interval);
     ping_interval.tick().await; // Skip first immediate tick
+    let mut schedule = PingSchedule::default();
+    schedule.set_interval_multiplier(multiplier);
+    // Pin downloads held back on a metered network
+    let mut deferred = ParkedJobs::default();
+
+    let mut paused_rx = status.subscribe_paused();
+    let mut paused = *paused_rx.borrow_and_update();
+    if paused {
+        tracing::info!("Syncing is paused");
+    }
+    let mut parked = ParkedJobs::default();
 
     loop {
         tokio::select! {
             // Process incoming jobs from the queue
-            Some(job) = job_stream.next() => {
-                use common::peer::sync::execute_job;
-                if let Err(e) = execute_job(&peer, job).await {
-                    tracing::error!("Job execution failed: {}", e);
+            Some(queued) = job_stream.next() => {
+                if paused {
+                    parked.park(queued);
+                    status.parked.send_replace(parked.len());
+                    continue;
+                }
+                if matches!(queued.job, SyncJob::DownloadPins(_))
+                    && deferral.defers_downloads(&conditions)
+                {
+                    tracing::debug!("Network is metered, deferring pins download");
+                    deferred.park(queued);
+                    status.deferred.send_replace(deferred.len());
+                    continue;
+                }
+                run_job(
+                    &peer, queued, &status, &policies, &cache, &audit, &webhooks, &database,
+                    published_only, cluster.as_ref(),
+                )
+                .await;
+            }
+
+            // Periodic ping scheduler, unless the host schedules syncs
+            _ = ping_interval.tick(), if periodic && !paused => {
+                if leads_periodic_sync(cluster.as_ref(), tick_interval).await {
+                    tracing::debug!("Running periodic ping scheduler");
+                    schedule_periodic_pings(&peer, &policies, &mut schedule, sync_interval).await;
                 }
             }
 
-            // Periodic ping scheduler
-            _ = ping_interval.tick() => {
-                tracing::info!("Running periodic ping scheduler");
-                schedule_periodic_pings(&peer).await;
+            // Sync paused or resumed
+            Ok(()) = paused_rx.changed() => {
+                paused = *paused_rx.borrow_and_update();
+                if paused {
+                    tracing::info!("Syncing paused");
+                    continue;
+                }
+
+                let jobs = parked.take();
+                tracing::info!("Syncing resumed, running {} parked jobs", jobs.len());
+                let mut jobs = jobs.into_iter();
+                while let Some(queued) = jobs.next() {
+                    run_job(
+                    &peer, queued, &status, &policies, &cache, &audit, &webhooks, &database,
+                    published_only, cluster.as_ref(),
+                )
+                .await;
+                    status.parked.send_replace(jobs.len());
+                    // Paused again or shut down while catching up
+                    if *paused_rx.borrow() || peer.is_cancelled() {
+                        break;
+                    }
+                }
+                jobs.for_each(|queued| parked.park(queued));
+                status.parked.send_replace(parked.len());
+
+                if periodic
+                    && !*paused_rx.borrow()
+                    && leads_periodic_sync(cluster.as_ref(), tick_interval).await
+                {
+                    schedule_periodic_pings(&peer, &policies, &mut schedule, sync_interval).await;
+                    ping_interval.reset();
+                }
+            }
+
+            // Sync interval or deferral changed on config reload
+            Ok(()) = runtime_rx.changed() => {
+                let runtime = *runtime_rx.borrow_and_update();
+                if runtime.sync_interval != sync_interval {
+                    tracing::info!("Periodic sync interval changed to {:?}", runtime.sync_interval);
+                    sync_interval = runtime.sync_interval;
+                }
+                deferral = runtime.sync_deferral;
+            }
+
+            // Power or network conditions changed
+            Ok(()) = conditions_rx.changed() => {
+                conditions = *conditions_rx.borrow_and_update();
+            }
+
+            // Bucket sync policies changed
+            Ok(()) = policies_rx.changed() => {
+                policies_rx.borrow_and_update();
+            }
+
+            // The peer shut down; requests of the job in flight are
+            // cancelled along with it
+            _ = peer.cancelled() => {
+                tracing::info!("Peer shut down, stopping worker");
+                break;
             }
 
             // Stream closed (all senders dropped)
@@ -146,35 +441,338 @@ pub async fn run_worker<L>(
                 break;
             }
         }
+
+        // Release deferred downloads once the network is no longer metered
+        if !paused && deferred.len() > 0 && !deferral.defers_downloads(&conditions) {
+            let jobs = deferred.take();
+            tracing::info!(
+                "Network no longer metered, running {} deferred downloads",
+                jobs.len()
+            );
+            status.deferred.send_replace(0);
+            for queued in jobs {
+                run_job(
+                    &peer,
+                    queued,
+                    &status,
+                    &policies,
+                    &cache,
+                    &audit,
+                    &webhooks,
+                    &database,
+                    published_only,
+                    cluster.as_ref(),
+                )
+                .await;
+            }
+        }
+
+        // Follow the shortest interval in use, stretched on battery
+        let new_multiplier = deferral.interval_multiplier(&conditions);
+        if new_multiplier != multiplier {
+            tracing::info!("Periodic sync intervals stretched by {}", new_multiplier);
+            multiplier = new_multiplier;
+            schedule.set_interval_multiplier(multiplier);
+        }
+        let new_tick = policies.tick_interval(sync_interval) * multiplier;
+        if new_tick != tick_interval {
+            tracing::debug!("Periodic ping tick changed to {:?}", new_tick);
+            tick_interval = new_tick;
+            ping_interval = interval(tick_interval);
+            ping_interval.tick().await;
+        }
     }
 
     tracing::info!("Background job worker shutting down for peer {}", peer.id());
 }
 
-/// Schedule periodic pings to all peers in all buckets
-///
-/// This is app-specific scheduling logic - calls peer.ping_bucket_peers()
-/// for each bucket on a timer.
-async fn schedule_periodic_pings<L>(peer: &common::peer::Peer<L>)
+/// Run a single job in a span parented to its enqueuing context.
+/// Jobs for buckets whose policy is paused are dropped, and online-only
+/// buckets only sync their metadata instead of downloading their pins.
+/// Historical pins are skipped for versions beyond a bucket's history depth,
+/// and pins for versions its retention policy pruned. With `published_only`,
+/// pins of versions that aren't published are skipped too.
+/// Versions a bucket sync appends are recorded in the audit log, and the
+/// bucket's member policy is applied (see [`crate::member_policy`]).
+/// Failed bucket syncs and pins downloads raise `sync.failed` webhooks.
+/// In a cluster, jobs claimed or finished by another instance are skipped.
+#[allow(clippy::too_many_arguments)]
+async fn run_job<L>(
+    peer: &common::peer::Peer<L>,
+    queued: QueuedJob,
+    status: &SyncStatus,
+    policies: &SyncPolicies,
+    cache: &BlobCache,
+    audit: &AuditLog,
+    webhooks: &Webhooks,
+    database: &Database,
+    published_only: bool,
+    cluster: Option<&Cluster>,
+) where
+    L: common::bucket_log::BucketLogProvider + Clone + Send + Sync + 'static,
+    L::Error: std::error::Error + Send + Sync + 'static,
+{
+    use common::peer::sync::execute_job;
+
+    let QueuedJob { job, trace_context } = queued;
+    let bucket_id = match &job {
+        SyncJob::SyncBucket(job) => Some(job.bucket_id),
+        SyncJob::PingPeer(job) => Some(job.bucket_id),
+        SyncJob::DownloadPins(job) => Some(job.bucket_id),
+    };
+    if let Some(bucket_id) = bucket_id.filter(|id| policies.get(id).paused) {
+        tracing::debug!("Syncing bucket {} is paused, dropping job", bucket_id);
+        return;
+    }
+    if let SyncJob::DownloadPins(job) = &job {
+        if job.historical && !keeps_pins(peer, policies, job).await {
+            tracing::debug!(
+                "Skipping pins of bucket {} at height {} beyond its history depth",
+                job.bucket_id,
+                job.height
+            );
+            return;
+        }
+        if published_only
+            && !matches!(
+                database
+                    .published_version_at_height(&job.bucket_id, job.height)
+                    .await,
+                Ok(Some(_))
+            )
+        {
+            tracing::debug!(
+                "Skipping pins of bucket {} at height {}, which is not published",
+                job.bucket_id,
+                job.height
+            );
+            return;
+        }
+        if database
+            .is_version_pruned(&job.bucket_id, job.height)
+            .await
+            .unwrap_or(false)
+        {
+            tracing::debug!(
+                "Skipping pins of bucket {} at height {} pruned by its retention policy",
+                job.bucket_id,
+                job.height
+            );
+            return;
+        }
+    }
+    // Peers being offline isn't worth a webhook
+    let reports_failure = !matches!(job, SyncJob::PingPeer(_));
+    let claim = cluster.zip(cluster::job_key(&job)).zip(bucket_id);
+    let job_span = tracing::info_span!(parent: None, "sync.job");
+    let _ = job_span.set_parent(trace_context);
+    let work = async {
+        status.activity.send_replace(SyncActivity::Syncing);
+        match job {
+            SyncJob::DownloadPins(job) if policies.get(&job.bucket_id).online_only => {
+                blob_cache::sync_metadata(peer, job.bucket_id)
+                    .instrument(job_span)
+                    .await
+            }
+            SyncJob::DownloadPins(job) => {
+                let pins = job.pins_link.hash();
+                let result = execute_job(peer, SyncJob::DownloadPins(job))
+                    .instrument(job_span)
+                    .await;
+                if result.is_ok() {
+                    // Content the bucket keeps must not be evicted as cache
+                    if let Err(e) = cache.pin(peer.blobs(), pins).await {
+                        tracing::warn!("Failed to release pinned blobs from the cache: {}", e);
+                    }
+                }
+                result
+            }
+            SyncJob::SyncBucket(job) => {
+                let bucket_id = job.bucket_id;
+                let source = job.target.peer_ids.first().copied();
+                let from_height = peer.logs().height(bucket_id).await.ok();
+                let result = execute_job(peer, SyncJob::SyncBucket(job))
+                    .instrument(job_span)
+                    .await;
+                if result.is_ok() {
+                    audit
+                        .record_synced(peer, bucket_id, from_height, source)
+                        .await;
+                    crate::member_policy::on_synced(
+                        peer,
+                        database,
+                        policies,
+                        audit,
+                        bucket_id,
+                        from_height,
+                    )
+                    .await;
+                }
+                result
+            }
+            job => execute_job(peer, job).instrument(job_span).await,
+        }
+    };
+    let result = match claim {
+        Some(((cluster, key), bucket_id)) => match cluster.run(&key, bucket_id, work).await {
+            Some(result) => result,
+            None => return,
+        },
+        None => work.await,
+    };
+    let activity = match result {
+        Ok(()) => SyncActivity::Idle,
+        Err(e) => {
+            tracing::error!("Job execution failed: {}", e);
+            if let Some(bucket_id) = bucket_id.filter(|_| reports_failure) {
+                webhooks.sync_failed(bucket_id, e.to_string());
+            }
+            SyncActivity::Error(e.to_string())
+        }
+    };
+    status.activity.send_replace(activity);
+}
+
+/// Whether this instance sends the periodic pings: always, unless it is
+/// part of a cluster whose periodic sync lease another instance holds. The
+/// lease outlasts two ticks, so that it stays with its holder.
+async fn leads_periodic_sync(
+    cluster: Option<&Cluster>,
+    tick_interval: std::time::Duration,
+) -> bool {
+    match cluster {
+        Some(cluster) => cluster.lead(PERIODIC_SYNC_LEASE, tick_interval * 2).await,
+        None => true,
+    }
+}
+
+/// Whether the historical pins of a job are within its bucket's history depth.
+/// Online-only buckets only ever sync the metadata of their head.
+async fn keeps_pins<L>(
+    peer: &common::peer::Peer<L>,
+    policies: &SyncPolicies,
+    job: &DownloadPinsJob,
+) -> bool
 where
     L: common::bucket_log::BucketLogProvider + Clone + Send + Sync + 'static,
     L::Error: std::error::Error + Send + Sync + 'static,
 {
-    // Get all bucket IDs
-    let bucket_ids = match peer.logs().list_buckets().await {
+    let policy = policies.get(&job.bucket_id);
+    if policy.online_only {
+        return false;
+    }
+    if policy.history_depth.is_none() {
+        return true;
+    }
+    let head_height = peer
+        .logs()
+        .height(job.bucket_id)
+        .await
+        .unwrap_or(job.height);
+    policy.keeps_version(head_height, job.height)
+}
+
+/// Schedule periodic pings to all peers in the buckets that are due
+///
+/// This is app-specific scheduling logic - calls peer.ping() for each bucket
+/// whose sync policy allows a periodic ping now.
+async fn schedule_periodic_pings<L>(
+    peer: &common::peer::Peer<L>,
+    policies: &SyncPolicies,
+    schedule: &mut PingSchedule,
+    default_interval: std::time::Duration,
+) where
+    L: common::bucket_log::BucketLogProvider + Clone + Send + Sync + 'static,
+    L::Error: std::error::Error + Send + Sync + 'static,
+{
+    // Get all bucket IDs, including followed buckets not synced yet
+    let mut bucket_ids = match peer.logs().list_buckets().await {
         Ok(ids) => ids,
         Err(e) => {
             tracing::error!("Failed to list buckets for periodic pings: {}", e);
             return;
         }
     };
+    for bucket_id in peer.followed_buckets() {
+        if !bucket_ids.contains(&bucket_id) {
+            bucket_ids.push(bucket_id);
+        }
+    }
 
-    tracing::debug!("Scheduling periodic pings for {} buckets", bucket_ids.len());
+    let now = std::time::Instant::now();
+    let local_time = chrono::Local::now().time();
+    let due: Vec<Uuid> = bucket_ids
+        .into_iter()
+        .filter(|bucket_id| {
+            schedule.due(
+                *bucket_id,
+                &policies.get(bucket_id),
+                default_interval,
+                now,
+                local_time,
+            )
+        })
+        .collect();
+    if due.is_empty() {
+        return;
+    }
+
+    tracing::info!("Scheduling periodic pings for {} buckets", due.len());
 
-    // For each bucket, ping all peers in shares
-    for bucket_id in bucket_ids {
+    // For each bucket, ping all peers in shares and the source of followed
+    // buckets
+    for bucket_id in due {
         if let Err(e) = peer.ping(bucket_id).await {
             tracing::warn!("Failed to ping peers for bucket {}: {}", bucket_id, e);
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use common::crypto::SecretKey;
+    use common::linked_data::Link;
+    use common::peer::sync::{PingPeerJob, SyncBucketJob, SyncTarget};
+
+    fn queued(job: SyncJob) -> QueuedJob {
+        QueuedJob {
+            job,
+            trace_context: opentelemetry::Context::new(),
+        }
+    }
+
+    fn sync_bucket(bucket_id: Uuid, height: u64) -> QueuedJob {
+        queued(SyncJob::SyncBucket(SyncBucketJob {
+            bucket_id,
+            target: SyncTarget {
+                link: Link::default(),
+                height,
+                peer_ids: vec![SecretKey::generate().public()],
+            },
+        }))
+    }
+
+    #[test]
+    fn test_parked_jobs_keep_newest_target_per_bucket() {
+        let bucket_id = Uuid::new_v4();
+        let mut parked = ParkedJobs::default();
+        parked.park(sync_bucket(bucket_id, 3));
+        parked.park(sync_bucket(bucket_id, 5));
+        parked.park(sync_bucket(bucket_id, 4));
+        parked.park(sync_bucket(Uuid::new_v4(), 1));
+        parked.park(queued(SyncJob::PingPeer(PingPeerJob {
+            bucket_id,
+            peer_id: SecretKey::generate().public(),
+        })));
+        assert_eq!(parked.len(), 2);
+
+        let jobs = parked.take();
+        assert_eq!(parked.len(), 0);
+        let height = jobs.iter().find_map(|queued| match &queued.job {
+            SyncJob::SyncBucket(job) if job.bucket_id == bucket_id => Some(job.target.height),
+            _ => None,
+        });
+        assert_eq!(height, Some(5));
+    }
+}
@@ -0,0 +1,113 @@
+//! OpenTelemetry trace export
+//!
+//! When an OTLP endpoint is configured, tracing spans (HTTP requests, sync
+//! jobs, peer protocol messages) are exported over OTLP/HTTP and the W3C trace
+//! context propagator is installed, so traces continue across the API, the
+//! sync job queue and the peer protocol (see `common::peer::protocol::trace_context`).
+
+use opentelemetry::propagation::Extractor;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
+use tracing::Subscriber;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use crate::reverse_proxy::RequestOrigin;
+
+/// Service name reported on exported spans
+const SERVICE_NAME: &str = "jax-daemon";
+/// Path of the trace signal on an OTLP/HTTP collector
+const TRACES_PATH: &str = "/v1/traces";
+
+/// Flushes and shuts down the exporter when dropped
+#[derive(Debug)]
+pub struct TracerGuard {
+    provider: SdkTracerProvider,
+}
+
+impl Drop for TracerGuard {
+    fn drop(&mut self) {
+        if let Err(e) = self.provider.shutdown() {
+            eprintln!("Warning: failed to flush trace exporter: {}", e);
+        }
+    }
+}
+
+/// Create a layer exporting spans to the OTLP/HTTP collector at `endpoint`
+/// (e.g. `http://localhost:4318`), and install the trace context propagator.
+///
+/// The returned guard must be kept alive for spans to be exported.
+pub fn otlp_layer<S>(
+    endpoint: &str,
+) -> Result<(impl Layer<S> + Send + Sync + 'static, TracerGuard), TelemetryError>
+where
+    S: Subscriber + for<'a> LookupSpan<'a> + Send + Sync,
+{
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_endpoint(traces_url(endpoint))
+        .build()?;
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name(SERVICE_NAME).build())
+        .build();
+
+    opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let layer = tracing_opentelemetry::layer().with_tracer(provider.tracer(SERVICE_NAME));
+    Ok((layer, TracerGuard { provider }))
+}
+
+/// Full traces URL for a collector endpoint, accepting either the base URL or the traces URL
+fn traces_url(endpoint: &str) -> String {
+    let endpoint = endpoint.trim_end_matches('/');
+    if endpoint.ends_with(TRACES_PATH) {
+        endpoint.to_string()
+    } else {
+        format!("{}{}", endpoint, TRACES_PATH)
+    }
+}
+
+struct HeaderExtractor<'a>(&'a http::HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|key| key.as_str()).collect()
+    }
+}
+
+/// Span for an incoming HTTP request, continuing the caller's trace if it sent
+/// a `traceparent` header. Records the client's address, as seen past any
+/// trusted reverse proxies.
+pub(crate) fn http_request_span<B>(request: &http::Request<B>) -> tracing::Span {
+    let client_ip = request
+        .extensions()
+        .get::<RequestOrigin>()
+        .and_then(|origin| origin.client_ip);
+    let span = tracing::info_span!(
+        "http.request",
+        method = %request.method(),
+        uri = %request.uri(),
+        version = ?request.version(),
+        client_ip = client_ip.map(tracing::field::display),
+    );
+    let parent = opentelemetry::global::get_text_map_propagator(|propagator| {
+        propagator.extract(&HeaderExtractor(request.headers()))
+    });
+    let _ = span.set_parent(parent);
+    span
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TelemetryError {
+    #[error("failed to build OTLP exporter: {0}")]
+    Exporter(#[from] opentelemetry_otlp::ExporterBuildError),
+}
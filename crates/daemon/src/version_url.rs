@@ -0,0 +1,140 @@
+//! Friendly version URLs
+//!
+//! Besides `?at=<hash>`, the gateway serves published versions of a bucket at
+//! a version segment right after the bucket id, resolved against the bucket
+//! log:
+//!
+//! - `/gw/<id>/@v3/path` - the version at height 3 of the log
+//! - `/gw/<id>/@2024-01-01/path` - the latest version published by the end of
+//!   that day (UTC)
+//! - `/gw/<id>/@2024-01-01T12:00:00Z/path` - the latest version published by
+//!   that time
+//!
+//! Only published versions are served; pages of a version link within it.
+
+use std::fmt;
+use std::str::FromStr;
+
+use time::format_description::well_known::Rfc3339;
+use time::{Date, Month, OffsetDateTime};
+
+/// Marks the version segment of a gateway path
+pub const VERSION_MARKER: char = '@';
+
+/// A published version, as named in a gateway path
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionSelector {
+    /// The version at this height of the bucket log
+    Height(u64),
+    /// The latest version published up to this time
+    AsOf(OffsetDateTime),
+}
+
+impl VersionSelector {
+    /// Split the version segment off a path within a bucket, returning the
+    /// selector as written and the rest of the path (absolute)
+    pub fn split_path(path: &str) -> Option<(&str, String)> {
+        let path = path.trim_start_matches('/');
+        let path = path.strip_prefix(VERSION_MARKER)?;
+        let (selector, rest) = match path.split_once('/') {
+            Some((selector, rest)) => (selector, rest),
+            None => (path, ""),
+        };
+        Some((selector, format!("/{}", rest)))
+    }
+}
+
+impl FromStr for VersionSelector {
+    type Err = VersionSelectorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || VersionSelectorError(s.to_string());
+        if let Some(height) = s.strip_prefix('v') {
+            return height.parse().map(Self::Height).map_err(|_| invalid());
+        }
+        if let Ok(time) = OffsetDateTime::parse(s, &Rfc3339) {
+            return Ok(Self::AsOf(time));
+        }
+        // A date covers the whole day
+        let date = parse_date(s).ok_or_else(invalid)?;
+        let end_of_day = date
+            .with_hms_nano(23, 59, 59, 999_999_999)
+            .map_err(|_| invalid())?;
+        Ok(Self::AsOf(end_of_day.assume_utc()))
+    }
+}
+
+impl fmt::Display for VersionSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Height(height) => write!(f, "v{}", height),
+            Self::AsOf(time) => match time.format(&Rfc3339) {
+                Ok(time) => f.write_str(&time),
+                Err(_) => Err(fmt::Error),
+            },
+        }
+    }
+}
+
+/// `YYYY-MM-DD`
+fn parse_date(s: &str) -> Option<Date> {
+    let mut parts = s.splitn(3, '-');
+    let (year, month, day) = (parts.next()?, parts.next()?, parts.next()?);
+    if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+        return None;
+    }
+    let month = Month::try_from(month.parse::<u8>().ok()?).ok()?;
+    Date::from_calendar_date(year.parse().ok()?, month, day.parse().ok()?).ok()
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("invalid version {0}, expected v<height>, a date (YYYY-MM-DD) or an RFC 3339 time")]
+pub struct VersionSelectorError(String);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn utc(hour: u8, minute: u8, second: u8, nanos: u32) -> OffsetDateTime {
+        Date::from_calendar_date(2024, Month::January, 1)
+            .unwrap()
+            .with_hms_nano(hour, minute, second, nanos)
+            .unwrap()
+            .assume_utc()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            "v3".parse::<VersionSelector>().unwrap(),
+            VersionSelector::Height(3)
+        );
+        assert_eq!(
+            "2024-01-01".parse::<VersionSelector>().unwrap(),
+            VersionSelector::AsOf(utc(23, 59, 59, 999_999_999))
+        );
+        assert_eq!(
+            "2024-01-01T12:00:00+02:00"
+                .parse::<VersionSelector>()
+                .unwrap(),
+            VersionSelector::AsOf(utc(10, 0, 0, 0))
+        );
+        for invalid in ["v", "v-1", "3", "2024-1-1", "2024-02-30", "latest"] {
+            assert!(invalid.parse::<VersionSelector>().is_err(), "{}", invalid);
+        }
+        assert_eq!(VersionSelector::Height(7).to_string(), "v7");
+    }
+
+    #[test]
+    fn test_split_path() {
+        assert_eq!(
+            VersionSelector::split_path("@v3/docs/a.md"),
+            Some(("v3", "/docs/a.md".to_string()))
+        );
+        assert_eq!(
+            VersionSelector::split_path("/@2024-01-01"),
+            Some(("2024-01-01", "/".to_string()))
+        );
+        assert_eq!(VersionSelector::split_path("/docs/@v3"), None);
+    }
+}
@@ -0,0 +1,400 @@
+//! Webhooks: HTTP callbacks on bucket events
+//!
+//! Users register URLs, for one bucket or for all of them, that the daemon
+//! POSTs a JSON [`WebhookPayload`] to when an event they subscribed to
+//! happens:
+//! - `version.committed`: a bucket has a new version, saved on this node or
+//!   pulled in by sync
+//! - `share.accepted`: a principal joined a bucket, either shared from this
+//!   node or this node receiving a bucket shared with it
+//! - `sync.failed`: syncing a bucket or downloading its content failed
+//!
+//! Events are taken from the audit log as entries are recorded (see
+//! [`crate::audit`]), except sync failures, which the sync worker reports.
+//! Each request carries the event in `X-Jax-Event`, a delivery ID in
+//! `X-Jax-Delivery` and an HMAC-SHA256 of the body keyed with the webhook's
+//! secret in `X-Jax-Signature`, as `sha256=<hex>`. A delivery that fails or
+//! gets a non-2xx response is retried with backoff, and the outcome of the
+//! latest attempt is kept with the webhook. Deliveries aren't persisted, so
+//! retries still pending when the daemon stops are lost.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use time::OffsetDateTime;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use common::crypto::Secret;
+
+use crate::audit::AuditEvent;
+use crate::database::Database;
+
+/// Time to wait for a webhook to respond
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Delays before retrying a failed delivery; one attempt more than delays
+const RETRY_DELAYS: [Duration; 3] = [
+    Duration::from_secs(1),
+    Duration::from_secs(10),
+    Duration::from_secs(60),
+];
+
+/// Audit operations that add a principal to a bucket
+const SHARE_OPERATIONS: [&str; 2] = ["bucket.share", "bucket.join"];
+
+/// Something a webhook can be subscribed to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, clap::ValueEnum)]
+pub enum WebhookEvent {
+    /// A bucket has a new version
+    #[serde(rename = "version.committed")]
+    #[value(name = "version.committed")]
+    VersionCommitted,
+    /// A principal joined a bucket
+    #[serde(rename = "share.accepted")]
+    #[value(name = "share.accepted")]
+    ShareAccepted,
+    /// Syncing a bucket failed
+    #[serde(rename = "sync.failed")]
+    #[value(name = "sync.failed")]
+    SyncFailed,
+    /// A test delivery, sent to one webhook on request
+    #[serde(rename = "ping")]
+    #[value(name = "ping")]
+    Ping,
+}
+
+impl WebhookEvent {
+    /// Events a webhook is subscribed to when none are given
+    pub const ALL: [WebhookEvent; 3] = [
+        WebhookEvent::VersionCommitted,
+        WebhookEvent::ShareAccepted,
+        WebhookEvent::SyncFailed,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::VersionCommitted => "version.committed",
+            WebhookEvent::ShareAccepted => "share.accepted",
+            WebhookEvent::SyncFailed => "sync.failed",
+            WebhookEvent::Ping => "ping",
+        }
+    }
+}
+
+impl fmt::Display for WebhookEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for WebhookEvent {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "version.committed" => Ok(WebhookEvent::VersionCommitted),
+            "share.accepted" => Ok(WebhookEvent::ShareAccepted),
+            "sync.failed" => Ok(WebhookEvent::SyncFailed),
+            "ping" => Ok(WebhookEvent::Ping),
+            _ => Err(format!("unknown webhook event: {}", s)),
+        }
+    }
+}
+
+/// A registered webhook
+#[derive(Debug, Clone)]
+pub struct Webhook {
+    pub id: Uuid,
+    pub url: String,
+    /// None for events of every bucket
+    pub bucket_id: Option<Uuid>,
+    pub events: Vec<WebhookEvent>,
+    pub secret: String,
+    pub created_at: OffsetDateTime,
+    pub last_delivery_at: Option<OffsetDateTime>,
+    /// HTTP status of the latest delivery, None if it got no response
+    pub last_status: Option<u16>,
+    pub last_error: Option<String>,
+}
+
+impl Webhook {
+    /// Whether an event should be delivered to this webhook
+    pub fn wants(&self, payload: &WebhookPayload) -> bool {
+        self.events.contains(&payload.event)
+            && (self.bucket_id.is_none() || self.bucket_id == payload.bucket_id)
+    }
+}
+
+/// Body POSTed to a webhook
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookPayload {
+    /// Delivery ID, the same across retries
+    pub id: Uuid,
+    pub event: WebhookEvent,
+    #[serde(with = "time::serde::rfc3339")]
+    pub at: OffsetDateTime,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bucket_id: Option<Uuid>,
+    /// Who made the change, as displayed in the audit log
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+    /// Audit operation behind the event, e.g. `bucket.add` or `sync`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Hash of the bucket version
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Why a sync failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl WebhookPayload {
+    pub fn new(event: WebhookEvent) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            event,
+            at: OffsetDateTime::now_utc(),
+            bucket_id: None,
+            actor: None,
+            operation: None,
+            path: None,
+            detail: None,
+            version: None,
+            error: None,
+        }
+    }
+
+    fn from_audit(event: WebhookEvent, audit: &AuditEvent) -> Self {
+        Self {
+            bucket_id: audit.bucket_id,
+            actor: Some(audit.actor.to_string()),
+            operation: Some(audit.operation.to_string()),
+            path: audit.path.clone(),
+            detail: audit.detail.clone(),
+            version: audit.version.clone(),
+            ..Self::new(event)
+        }
+    }
+}
+
+/// Signature of a delivery body, as sent in `X-Jax-Signature`
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// A random secret for a new webhook
+pub fn generate_secret() -> String {
+    hex::encode(Secret::generate().bytes())
+}
+
+/// Handle for raising webhook events; delivery happens in the background
+#[derive(Debug, Clone)]
+pub struct Webhooks {
+    database: Database,
+    client: reqwest::Client,
+    events: mpsc::UnboundedSender<WebhookPayload>,
+}
+
+impl Webhooks {
+    /// Start the delivery worker, which runs until every handle is dropped
+    pub fn spawn(database: Database) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(DELIVERY_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        let (events, events_rx) = mpsc::unbounded_channel();
+        let webhooks = Self {
+            database,
+            client,
+            events,
+        };
+        tokio::spawn(run_dispatcher(
+            webhooks.database.clone(),
+            webhooks.client.clone(),
+            events_rx,
+        ));
+        webhooks
+    }
+
+    /// Deliver an event to the webhooks subscribed to it
+    pub fn send(&self, payload: WebhookPayload) {
+        // The worker only stops once every handle is gone
+        let _ = self.events.send(payload);
+    }
+
+    /// Raise the events an audited change amounts to
+    pub(crate) fn audited(&self, event: &AuditEvent) {
+        if event.version.is_some() {
+            self.send(WebhookPayload::from_audit(
+                WebhookEvent::VersionCommitted,
+                event,
+            ));
+        }
+        if SHARE_OPERATIONS.contains(&event.operation) {
+            self.send(WebhookPayload::from_audit(
+                WebhookEvent::ShareAccepted,
+                event,
+            ));
+        }
+    }
+
+    /// Raise `sync.failed` for a bucket
+    pub(crate) fn sync_failed(&self, bucket_id: Uuid, error: impl Into<String>) {
+        self.send(WebhookPayload {
+            bucket_id: Some(bucket_id),
+            error: Some(error.into()),
+            ..WebhookPayload::new(WebhookEvent::SyncFailed)
+        });
+    }
+
+    /// Send a `ping` to a webhook once, without retrying, returning the
+    /// status it responded with
+    pub async fn ping(&self, webhook: &Webhook) -> Result<u16, String> {
+        let payload = WebhookPayload {
+            bucket_id: webhook.bucket_id,
+            ..WebhookPayload::new(WebhookEvent::Ping)
+        };
+        let result = attempt(&self.client, webhook, &payload).await;
+        record_outcome(&self.database, webhook, &result).await;
+        result
+    }
+}
+
+/// Fan events out to the webhooks subscribed to them
+async fn run_dispatcher(
+    database: Database,
+    client: reqwest::Client,
+    mut events: mpsc::UnboundedReceiver<WebhookPayload>,
+) {
+    while let Some(payload) = events.recv().await {
+        let webhooks = match database.list_webhooks(payload.bucket_id.as_ref()).await {
+            Ok(webhooks) => webhooks,
+            Err(e) => {
+                tracing::warn!("Failed to load webhooks for {}: {}", payload.event, e);
+                continue;
+            }
+        };
+        for webhook in webhooks.into_iter().filter(|w| w.wants(&payload)) {
+            tokio::spawn(deliver(
+                database.clone(),
+                client.clone(),
+                webhook,
+                payload.clone(),
+            ));
+        }
+    }
+    tracing::debug!("Webhook dispatcher shutting down");
+}
+
+/// Deliver an event to one webhook, retrying with backoff
+async fn deliver(
+    database: Database,
+    client: reqwest::Client,
+    webhook: Webhook,
+    payload: WebhookPayload,
+) {
+    let mut delays = RETRY_DELAYS.iter();
+    loop {
+        let result = attempt(&client, &webhook, &payload).await;
+        record_outcome(&database, &webhook, &result).await;
+        let Err(e) = result else {
+            return;
+        };
+        let Some(delay) = delays.next() else {
+            tracing::warn!(
+                "Giving up delivering {} {} to {}: {}",
+                payload.event,
+                payload.id,
+                webhook.url,
+                e
+            );
+            return;
+        };
+        tracing::debug!(
+            "Delivering {} to {} failed, retrying in {:?}: {}",
+            payload.event,
+            webhook.url,
+            delay,
+            e
+        );
+        tokio::time::sleep(*delay).await;
+    }
+}
+
+/// POST a payload once, returning the response status if it was a success
+async fn attempt(
+    client: &reqwest::Client,
+    webhook: &Webhook,
+    payload: &WebhookPayload,
+) -> Result<u16, String> {
+    let body = serde_json::to_vec(payload).expect("webhook payload serializes");
+    let response = client
+        .post(&webhook.url)
+        .header("Content-Type", "application/json")
+        .header("X-Jax-Event", payload.event.as_str())
+        .header("X-Jax-Delivery", payload.id.to_string())
+        .header("X-Jax-Signature", sign(&webhook.secret, &body))
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let status = response.status();
+    if status.is_success() {
+        Ok(status.as_u16())
+    } else {
+        Err(format!("responded with {}", status))
+    }
+}
+
+async fn record_outcome(database: &Database, webhook: &Webhook, result: &Result<u16, String>) {
+    let (status, error) = match result {
+        Ok(status) => (Some(*status), None),
+        Err(e) => (None, Some(e.as_str())),
+    };
+    if let Err(e) = database
+        .record_webhook_delivery(&webhook.id, status, error)
+        .await
+    {
+        tracing::warn!("Failed to record delivery to webhook {}: {}", webhook.id, e);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_event_names_round_trip() {
+        for event in WebhookEvent::ALL.into_iter().chain([WebhookEvent::Ping]) {
+            assert_eq!(event.as_str().parse::<WebhookEvent>(), Ok(event));
+            assert_eq!(
+                serde_json::to_string(&event).unwrap(),
+                format!("\"{}\"", event)
+            );
+        }
+        assert!("version".parse::<WebhookEvent>().is_err());
+    }
+
+    #[test]
+    fn test_sign() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
@@ -0,0 +1,389 @@
+//! Zip archives of a bucket directory, built while they are sent
+//!
+//! `?download=zip` on a gateway directory and `GET /api/v0/bucket/zip`
+//! stream the directory's subtree as a zip archive. Entries are stored
+//! uncompressed (most bucket content is already compressed, and storing
+//! lets each header be written before its data), one file read from the
+//! mount at a time, so only the file being sent is held in memory and never
+//! the whole archive. Archives past 4 GiB or 65535 entries use the zip64
+//! extensions.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use axum::body::Bytes;
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use futures::channel::mpsc;
+use futures::{SinkExt, Stream};
+
+use common::mount::{Mount, MountError, NodeLink};
+
+/// Chunks queued ahead of the client before reading the next file waits
+const CHANNEL_CAPACITY: usize = 4;
+
+const LOCAL_HEADER_SIGNATURE: u32 = 0x0403_4b50;
+const CENTRAL_HEADER_SIGNATURE: u32 = 0x0201_4b50;
+const END_SIGNATURE: u32 = 0x0605_4b50;
+const ZIP64_END_SIGNATURE: u32 = 0x0606_4b50;
+const ZIP64_LOCATOR_SIGNATURE: u32 = 0x0706_4b50;
+const ZIP64_EXTRA_ID: u16 = 0x0001;
+
+/// Names are UTF-8
+const FLAG_UTF8: u16 = 1 << 11;
+/// Stored, not compressed
+const METHOD_STORED: u16 = 0;
+const VERSION_DEFAULT: u16 = 20;
+const VERSION_ZIP64: u16 = 45;
+/// Made by Unix, so the external attributes carry file modes
+const MADE_BY_UNIX: u16 = 3 << 8;
+
+/// Stand-in for a size or offset moved into the zip64 extra field
+const ZIP64_MARKER: u32 = u32::MAX;
+
+/// Writes the framing of a zip archive of stored entries
+///
+/// Each call returns the bytes to send for an entry, ahead of its data;
+/// [`ZipWriter::finish`] returns the central directory that ends the
+/// archive.
+#[derive(Debug)]
+pub struct ZipWriter {
+    offset: u64,
+    entries: Vec<Entry>,
+    time: u16,
+    date: u16,
+}
+
+#[derive(Debug)]
+struct Entry {
+    name: String,
+    crc: u32,
+    size: u64,
+    offset: u64,
+    is_dir: bool,
+}
+
+impl ZipWriter {
+    /// Start an archive whose entries are all marked modified at `modified`
+    pub fn new(modified: DateTime<Utc>) -> Self {
+        let (time, date) = dos_time(modified);
+        Self {
+            offset: 0,
+            entries: Vec::new(),
+            time,
+            date,
+        }
+    }
+
+    /// Header of a file whose contents are `data`, which must be sent
+    /// right after it
+    pub fn file(&mut self, name: &str, data: &[u8]) -> Vec<u8> {
+        self.entry(name.to_string(), crc32(data), data.len() as u64, false)
+    }
+
+    /// Entry of an empty directory, or one whose files follow
+    pub fn directory(&mut self, name: &str) -> Vec<u8> {
+        self.entry(format!("{}/", name.trim_end_matches('/')), 0, 0, true)
+    }
+
+    fn entry(&mut self, name: String, crc: u32, size: u64, is_dir: bool) -> Vec<u8> {
+        let zip64 = size >= ZIP64_MARKER as u64;
+        let mut header = Vec::with_capacity(30 + name.len() + 20);
+        put_u32(&mut header, LOCAL_HEADER_SIGNATURE);
+        put_u16(&mut header, version_needed(zip64));
+        put_u16(&mut header, FLAG_UTF8);
+        put_u16(&mut header, METHOD_STORED);
+        put_u16(&mut header, self.time);
+        put_u16(&mut header, self.date);
+        put_u32(&mut header, crc);
+        if zip64 {
+            put_u32(&mut header, ZIP64_MARKER);
+            put_u32(&mut header, ZIP64_MARKER);
+        } else {
+            put_u32(&mut header, size as u32);
+            put_u32(&mut header, size as u32);
+        }
+        put_u16(&mut header, name.len() as u16);
+        put_u16(&mut header, if zip64 { 20 } else { 0 });
+        header.extend_from_slice(name.as_bytes());
+        if zip64 {
+            put_u16(&mut header, ZIP64_EXTRA_ID);
+            put_u16(&mut header, 16);
+            put_u64(&mut header, size);
+            put_u64(&mut header, size);
+        }
+
+        self.entries.push(Entry {
+            name,
+            crc,
+            size,
+            offset: self.offset,
+            is_dir,
+        });
+        self.offset += header.len() as u64 + size;
+        header
+    }
+
+    /// Central directory and end records, the last bytes of the archive
+    pub fn finish(self) -> Vec<u8> {
+        let mut out = Vec::new();
+        let directory_offset = self.offset;
+
+        for entry in &self.entries {
+            let mut extra = Vec::new();
+            if entry.size >= ZIP64_MARKER as u64 {
+                put_u64(&mut extra, entry.size);
+                put_u64(&mut extra, entry.size);
+            }
+            if entry.offset >= ZIP64_MARKER as u64 {
+                put_u64(&mut extra, entry.offset);
+            }
+            let zip64 = !extra.is_empty();
+            let size = entry.size.min(ZIP64_MARKER as u64) as u32;
+            let mode: u32 = if entry.is_dir { 0o040755 } else { 0o100644 };
+            // MS-DOS directory attribute, for readers that ignore modes
+            let dos_attributes = if entry.is_dir { 0x10 } else { 0 };
+
+            put_u32(&mut out, CENTRAL_HEADER_SIGNATURE);
+            put_u16(&mut out, MADE_BY_UNIX | VERSION_ZIP64);
+            put_u16(&mut out, version_needed(zip64));
+            put_u16(&mut out, FLAG_UTF8);
+            put_u16(&mut out, METHOD_STORED);
+            put_u16(&mut out, self.time);
+            put_u16(&mut out, self.date);
+            put_u32(&mut out, entry.crc);
+            put_u32(&mut out, size);
+            put_u32(&mut out, size);
+            put_u16(&mut out, entry.name.len() as u16);
+            put_u16(&mut out, if zip64 { extra.len() as u16 + 4 } else { 0 });
+            put_u16(&mut out, 0); // comment length
+            put_u16(&mut out, 0); // disk number
+            put_u16(&mut out, 0); // internal attributes
+            put_u32(&mut out, (mode << 16) | dos_attributes);
+            put_u32(&mut out, entry.offset.min(ZIP64_MARKER as u64) as u32);
+            out.extend_from_slice(entry.name.as_bytes());
+            if zip64 {
+                put_u16(&mut out, ZIP64_EXTRA_ID);
+                put_u16(&mut out, extra.len() as u16);
+                out.extend_from_slice(&extra);
+            }
+        }
+
+        let directory_size = out.len() as u64;
+        let count = self.entries.len() as u64;
+        let needs_zip64 = count >= u16::MAX as u64
+            || directory_offset >= ZIP64_MARKER as u64
+            || directory_size >= ZIP64_MARKER as u64;
+
+        if needs_zip64 {
+            let end_offset = directory_offset + directory_size;
+            put_u32(&mut out, ZIP64_END_SIGNATURE);
+            put_u64(&mut out, 44); // size of the rest of this record
+            put_u16(&mut out, MADE_BY_UNIX | VERSION_ZIP64);
+            put_u16(&mut out, VERSION_ZIP64);
+            put_u32(&mut out, 0); // this disk
+            put_u32(&mut out, 0); // disk of the central directory
+            put_u64(&mut out, count);
+            put_u64(&mut out, count);
+            put_u64(&mut out, directory_size);
+            put_u64(&mut out, directory_offset);
+
+            put_u32(&mut out, ZIP64_LOCATOR_SIGNATURE);
+            put_u32(&mut out, 0); // disk of the zip64 end record
+            put_u64(&mut out, end_offset);
+            put_u32(&mut out, 1); // total disks
+        }
+
+        let count = count.min(u16::MAX as u64) as u16;
+        put_u32(&mut out, END_SIGNATURE);
+        put_u16(&mut out, 0); // this disk
+        put_u16(&mut out, 0); // disk of the central directory
+        put_u16(&mut out, count);
+        put_u16(&mut out, count);
+        put_u32(&mut out, directory_size.min(ZIP64_MARKER as u64) as u32);
+        put_u32(&mut out, directory_offset.min(ZIP64_MARKER as u64) as u32);
+        put_u16(&mut out, 0); // comment length
+        out
+    }
+}
+
+/// Stream a zip archive of everything under `dir` in `mount`
+///
+/// The directory is listed up front, so a missing path is an error before
+/// anything is sent. Files are then read one at a time as the client takes
+/// the archive; a file failing to read ends the stream with an error, which
+/// cuts the response short.
+pub async fn archive(
+    mount: Mount,
+    dir: &Path,
+) -> Result<impl Stream<Item = Result<Bytes, io::Error>> + Send + 'static, MountError> {
+    let entries = mount.ls_deep(dir).await?;
+    let dir = dir.to_path_buf();
+    let (mut tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+
+    tokio::spawn(async move {
+        if let Err(e) = write_archive(&mount, &dir, entries, &mut tx).await {
+            tracing::warn!("Zip archive of {} failed: {}", dir.display(), e);
+            let _ = tx.send(Err(e)).await;
+        }
+    });
+
+    Ok(rx)
+}
+
+async fn write_archive(
+    mount: &Mount,
+    dir: &Path,
+    entries: impl IntoIterator<Item = (PathBuf, NodeLink)>,
+    tx: &mut mpsc::Sender<Result<Bytes, io::Error>>,
+) -> Result<(), io::Error> {
+    // The client went away; stop reading files for it
+    let gone = |_| io::Error::new(io::ErrorKind::BrokenPipe, "client disconnected");
+
+    let mut zip = ZipWriter::new(Utc::now());
+    for (relative, link) in entries {
+        let name = relative.to_string_lossy().replace('\\', "/");
+        match link {
+            NodeLink::Dir(_, _) => {
+                tx.send(Ok(zip.directory(&name).into()))
+                    .await
+                    .map_err(gone)?;
+            }
+            NodeLink::Data(_, _, _) => {
+                let data = mount
+                    .cat(&dir.join(&relative))
+                    .await
+                    .map_err(|e| io::Error::other(e.to_string()))?;
+                tx.send(Ok(zip.file(&name, &data).into()))
+                    .await
+                    .map_err(gone)?;
+                tx.send(Ok(data.into())).await.map_err(gone)?;
+            }
+            // Referenced buckets aren't part of this one's archive
+            NodeLink::Bucket(_) => {}
+        }
+    }
+    tx.send(Ok(zip.finish().into())).await.map_err(gone)?;
+    Ok(())
+}
+
+/// File name for an archive of `path`, or of a whole bucket named
+/// `bucket_name`
+pub fn archive_name(path: &Path, bucket_name: &str) -> String {
+    let stem = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(bucket_name);
+    let stem = if stem.is_empty() { "bucket" } else { stem };
+    format!("{}.zip", stem.replace('"', ""))
+}
+
+fn version_needed(zip64: bool) -> u16 {
+    if zip64 {
+        VERSION_ZIP64
+    } else {
+        VERSION_DEFAULT
+    }
+}
+
+/// MS-DOS time and date fields of a timestamp (2 second resolution, from
+/// 1980)
+fn dos_time(at: DateTime<Utc>) -> (u16, u16) {
+    let year = at.year().clamp(1980, 2107) as u16;
+    let time = ((at.hour() as u16) << 11) | ((at.minute() as u16) << 5) | (at.second() as u16 / 2);
+    let date = ((year - 1980) << 9) | ((at.month() as u16) << 5) | at.day() as u16;
+    (time, date)
+}
+
+/// CRC-32 (IEEE) of `data`, as zip entries record it
+pub fn crc32(data: &[u8]) -> u32 {
+    static TABLE: std::sync::LazyLock<[u32; 256]> = std::sync::LazyLock::new(|| {
+        let mut table = [0u32; 256];
+        for (i, slot) in table.iter_mut().enumerate() {
+            let mut crc = i as u32;
+            for _ in 0..8 {
+                crc = if crc & 1 == 1 {
+                    (crc >> 1) ^ 0xEDB8_8320
+                } else {
+                    crc >> 1
+                };
+            }
+            *slot = crc;
+        }
+        table
+    });
+
+    let mut crc = !0u32;
+    for &byte in data {
+        crc = TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+fn put_u16(out: &mut Vec<u8>, value: u16) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u64(out: &mut Vec<u8>, value: u64) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u16_at(data: &[u8], at: usize) -> u16 {
+        u16::from_le_bytes(data[at..at + 2].try_into().unwrap())
+    }
+
+    fn u32_at(data: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes(data[at..at + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn test_archive_layout() {
+        let mut zip = ZipWriter::new(Utc::now());
+        let mut archive = zip.directory("docs");
+        let header = zip.file("docs/a.txt", b"hello");
+        archive.extend_from_slice(&header);
+        archive.extend_from_slice(b"hello");
+        let directory_offset = archive.len();
+        archive.extend_from_slice(&zip.finish());
+
+        // Local headers in order, each followed by its data
+        assert_eq!(u32_at(&archive, 0), LOCAL_HEADER_SIGNATURE);
+        assert_eq!(&archive[30..35], b"docs/");
+        let file_at = 35;
+        assert_eq!(u32_at(&archive, file_at), LOCAL_HEADER_SIGNATURE);
+        assert_eq!(u32_at(&archive, file_at + 14), crc32(b"hello"));
+        assert_eq!(u32_at(&archive, file_at + 22), 5);
+        assert_eq!(&archive[file_at + 30..file_at + 40], b"docs/a.txt");
+        assert_eq!(&archive[file_at + 40..file_at + 45], b"hello");
+
+        // The end record points back at the central directory
+        let end = archive.len() - 22;
+        assert_eq!(u32_at(&archive, end), END_SIGNATURE);
+        assert_eq!(u16_at(&archive, end + 10), 2);
+        assert_eq!(u32_at(&archive, end + 16) as usize, directory_offset);
+        assert_eq!(u32_at(&archive, directory_offset), CENTRAL_HEADER_SIGNATURE);
+        // The second central entry records where the file's header is
+        let second = directory_offset + 46 + "docs/".len();
+        assert_eq!(u32_at(&archive, second + 42) as usize, file_at);
+    }
+
+    #[test]
+    fn test_archive_name() {
+        assert_eq!(archive_name(Path::new("/photos/2024"), "b"), "2024.zip");
+        assert_eq!(archive_name(Path::new("/"), "notes"), "notes.zip");
+        assert_eq!(archive_name(Path::new("/"), ""), "bucket.zip");
+    }
+}
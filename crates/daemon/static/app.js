@@ -1,3 +1,14 @@
+// Message of a failed API response: the `message` of its error envelope,
+// or its text when it has none
+async function errorMessage(response) {
+  const text = await response.text();
+  try {
+    return JSON.parse(text).error.message || text;
+  } catch (_) {
+    return text;
+  }
+}
+
 // Bucket Creation Module
 const BucketCreation = {
   init(apiUrl) {
@@ -33,7 +44,7 @@ const BucketCreation = {
           );
           setTimeout(() => window.location.reload(), 1000);
         } else {
-          const error = await response.text();
+          const error = await errorMessage(response);
           this.showStatus(status, "Failed to create bucket: " + error, "error");
         }
       } catch (error) {
@@ -100,7 +111,7 @@ const FileUpload = {
           );
           // setTimeout(() => window.location.reload(), 1000);
         } else {
-          const error = await response.text();
+          const error = await errorMessage(response);
           this.showStatus(status, "Upload failed: " + error, "error");
         }
       } catch (error) {
@@ -170,7 +181,7 @@ const FileRename = {
           );
           setTimeout(() => window.location.reload(), 1000);
         } else {
-          const error = await response.text();
+          const error = await errorMessage(response);
           this.showStatus(status, "Rename failed: " + error, "error");
         }
       } catch (error) {
@@ -257,7 +268,7 @@ const NewFile = {
             window.location.href = `/buckets/${bucketId}/edit?path=${encodeURIComponent(path)}`;
           }, 500);
         } else {
-          const error = await response.text();
+          const error = await errorMessage(response);
           this.showStatus(status, "Failed to create file: " + error, "error");
         }
       } catch (error) {
@@ -311,7 +322,7 @@ const FileDelete = {
           );
           setTimeout(() => window.location.reload(), 1000);
         } else {
-          const error = await response.text();
+          const error = await errorMessage(response);
           this.showStatus(status, "Delete failed: " + error, "error");
         }
       } catch (error) {
@@ -383,7 +394,7 @@ const FileMove = {
           );
           setTimeout(() => window.location.reload(), 1000);
         } else {
-          const error = await response.text();
+          const error = await errorMessage(response);
           this.showStatus(status, "Move failed: " + error, "error");
         }
       } catch (error) {
@@ -167,7 +167,7 @@ export function initInlineEditor(bucketId, filePath, isMarkdown) {
             });
 
             if (!response.ok) {
-                const errorText = await response.text();
+                const errorText = await errorMessage(response);
                 throw new Error(errorText || 'Failed to save file');
             }
 
@@ -268,7 +268,7 @@ async function createFile(path, content) {
     });
 
     if (!response.ok) {
-        const error = await response.text();
+        const error = await errorMessage(response);
         throw new Error(error || 'Failed to create file');
     }
 }
@@ -287,7 +287,7 @@ async function updateFile(path, content) {
     });
 
     if (!response.ok) {
-        const error = await response.text();
+        const error = await errorMessage(response);
         throw new Error(error || 'Failed to update file');
     }
 }
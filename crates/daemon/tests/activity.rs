@@ -0,0 +1,120 @@
+//! The activity feed of changes across buckets
+
+mod common;
+
+use jax_daemon::activity::Change;
+use jax_daemon::http_server::api::v0::activity::ActivityRequest;
+use jax_daemon::http_server::api::v0::bucket::delete::DeleteRequest;
+use jax_daemon::http_server::api::v0::bucket::mv::MvRequest;
+
+use crate::common::TestDaemon;
+
+#[tokio::test]
+async fn test_activity_lists_changes_newest_first() {
+    let mut daemon = TestDaemon::start().await;
+    let notes = daemon.create_bucket("notes").await;
+    let other = daemon.create_bucket("other").await;
+
+    daemon.add_file(notes, "/a.txt", b"one").await;
+    daemon.add_file(notes, "/docs/b.txt", b"b").await;
+    daemon.add_file(other, "/x.txt", b"x").await;
+    daemon.add_file(notes, "/a.txt", b"two").await;
+    daemon
+        .client
+        .call(DeleteRequest {
+            bucket_id: notes,
+            path: "/docs/b.txt".to_string(),
+            expected_version: None,
+        })
+        .await
+        .unwrap();
+    daemon
+        .client
+        .call(MvRequest {
+            bucket_id: notes,
+            source_path: "/a.txt".to_string(),
+            dest_path: "/c.txt".to_string(),
+            expected_version: None,
+        })
+        .await
+        .unwrap();
+
+    let request = ActivityRequest {
+        bucket_id: Some(notes),
+        offset: None,
+        limit: None,
+    };
+    let feed = daemon.client.call(request.clone()).await.unwrap();
+    let changes: Vec<_> = feed
+        .items
+        .iter()
+        .map(|item| (item.path.as_str(), item.change))
+        .collect();
+    assert_eq!(
+        changes,
+        vec![
+            ("/a.txt", Change::Removed),
+            ("/c.txt", Change::Added),
+            ("/docs/b.txt", Change::Removed),
+            ("/a.txt", Change::Modified),
+            ("/docs/b.txt", Change::Added),
+            ("/a.txt", Change::Added),
+        ]
+    );
+    assert!(feed.next_offset.is_none());
+    let node_id = daemon.node_id();
+    assert!(feed.items.iter().all(|item| item.bucket_name == "notes"
+        && item.bucket_id == notes
+        && item.author.as_deref() == Some(node_id.as_str())));
+    assert!(feed
+        .items
+        .windows(2)
+        .all(|pair| pair[0].height >= pair[1].height));
+
+    // Asking again doesn't record the versions twice
+    let again = daemon.client.call(request).await.unwrap();
+    assert_eq!(again.items.len(), 6);
+
+    // Across every bucket, a page at a time
+    let page = daemon
+        .client
+        .call(ActivityRequest {
+            bucket_id: None,
+            offset: None,
+            limit: Some(4),
+        })
+        .await
+        .unwrap();
+    assert_eq!(page.items.len(), 4);
+    assert_eq!(page.next_offset, Some(4));
+    let rest = daemon
+        .client
+        .call(ActivityRequest {
+            bucket_id: None,
+            offset: page.next_offset,
+            limit: Some(4),
+        })
+        .await
+        .unwrap();
+    assert_eq!(rest.items.len(), 3);
+    assert!(rest.next_offset.is_none());
+    assert!(page
+        .items
+        .iter()
+        .chain(&rest.items)
+        .any(|item| item.bucket_name == "other" && item.path == "/x.txt"));
+}
+
+#[tokio::test]
+async fn test_activity_of_unknown_bucket() {
+    let mut daemon = TestDaemon::start().await;
+    assert!(daemon
+        .client
+        .call(ActivityRequest {
+            bucket_id: Some(uuid::Uuid::new_v4()),
+            offset: None,
+            limit: None,
+        })
+        .await
+        .is_err());
+}
@@ -0,0 +1,85 @@
+//! Admin dashboard endpoints
+
+mod common;
+
+use jax_daemon::http_server::api::v0::admin::{
+    AdminBucketsRequest, AdminConnectionsRequest, AdminErrorsRequest, AdminJobsRequest,
+    AdminStorageRequest,
+};
+use jax_daemon::http_server::api::v0::bucket::share::ShareRole;
+
+use crate::common::{assert_converged, start_daemons, wait_for};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_dashboard_reports_node() {
+    let mut daemons = start_daemons(2).await;
+    let bucket_id = daemons[0].create_bucket("dashboard").await;
+    daemons[0].add_file(bucket_id, "/hello.txt", b"hello").await;
+    let (alice, bob) = daemons.split_at_mut(1);
+    alice[0].share(bucket_id, &bob[0], ShareRole::Owner).await;
+    let head = assert_converged(&mut daemons, bucket_id).await;
+
+    let client = daemons[0].client.clone();
+    let bob_id = daemons[1].node_id();
+    wait_for("bob to show up as a connection", || {
+        let mut client = client.clone();
+        let bob_id = bob_id.clone();
+        async move {
+            client
+                .call(AdminConnectionsRequest::default())
+                .await
+                .is_ok_and(|response| {
+                    response
+                        .connections
+                        .iter()
+                        .any(|conn| conn.peer_id == bob_id && conn.shared_buckets == 1)
+                })
+        }
+    })
+    .await;
+
+    let buckets = daemons[0]
+        .client
+        .call(AdminBucketsRequest { refresh: true })
+        .await
+        .unwrap()
+        .buckets;
+    assert_eq!(buckets.len(), 1);
+    let bucket = &buckets[0];
+    assert_eq!(bucket.bucket_id, bucket_id);
+    assert_eq!(bucket.version, head.hash().to_string());
+    assert_eq!(bucket.file_count, 1);
+    // Sizes are of the encrypted blobs
+    assert!(bucket.logical_bytes > 5);
+    assert_eq!(bucket.peer_count, Some(1));
+
+    let jobs = daemons[0]
+        .client
+        .call(AdminJobsRequest::default())
+        .await
+        .unwrap();
+    assert!(!jobs.paused);
+    assert_eq!(jobs.parked, 0);
+
+    let storage = daemons[0]
+        .client
+        .call(AdminStorageRequest::default())
+        .await
+        .unwrap();
+    assert_eq!(storage.backend, "legacy");
+    assert!(storage.blob_store.healthy, "{:?}", storage.blob_store);
+    assert!(storage.database.healthy, "{:?}", storage.database);
+    assert_eq!(storage.logical_bytes, bucket.logical_bytes);
+
+    // Test daemons don't log to files
+    let errors = daemons[0]
+        .client
+        .call(AdminErrorsRequest::default())
+        .await
+        .unwrap();
+    assert!(errors.entries.is_none());
+
+    for daemon in daemons {
+        daemon.stop().await;
+    }
+}
@@ -0,0 +1,122 @@
+//! Audit log of mutating operations
+
+mod common;
+
+use jax_daemon::audit::AuditEntry;
+use jax_daemon::http_server::api::v0::audit::{AuditExportRequest, AuditLsRequest};
+use jax_daemon::http_server::api::v0::bucket::share::ShareRole;
+
+use crate::common::{assert_converged, start_daemons, wait_for, TestDaemon};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_api_changes_are_recorded() {
+    let mut daemon = TestDaemon::start().await;
+    let bucket_id = daemon.create_bucket("audited").await;
+    let link = daemon.add_file(bucket_id, "/a.txt", b"hello").await;
+
+    let response = daemon
+        .client
+        .call(AuditLsRequest {
+            bucket_id: Some(bucket_id),
+            ..AuditLsRequest::default()
+        })
+        .await
+        .unwrap();
+    let operations: Vec<_> = response
+        .entries
+        .iter()
+        .map(|entry| entry.operation.as_str())
+        .collect();
+    assert_eq!(operations, ["bucket.add", "bucket.create"]);
+    let added = &response.entries[0];
+    assert_eq!(added.actor, "api");
+    assert_eq!(added.path.as_deref(), Some("/a.txt"));
+    assert_eq!(added.version, Some(link.hash().to_string()));
+
+    // Exports run oldest first, one entry per line
+    let body = AuditExportRequest {
+        bucket_id: Some(bucket_id),
+        ..AuditExportRequest::default()
+    }
+    .build_request(daemon.client.base_url(), daemon.client.http_client())
+    .send()
+    .await
+    .unwrap()
+    .text()
+    .await
+    .unwrap();
+    let exported: Vec<AuditEntry> = body
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(exported.len(), 2);
+    assert_eq!(exported[0].operation, "bucket.create");
+    assert_eq!(exported[1], *added);
+
+    // Entries can't be rewritten or removed
+    let database = daemon.state.database();
+    assert!(sqlx::query("DELETE FROM audit_log")
+        .execute(&**database)
+        .await
+        .is_err());
+    assert!(sqlx::query("UPDATE audit_log SET actor = 'someone else'")
+        .execute(&**database)
+        .await
+        .is_err());
+
+    daemon.stop().await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_synced_versions_are_attributed_to_peer() {
+    let mut daemons = start_daemons(2).await;
+    let bucket_id = daemons[0].create_bucket("shared").await;
+    daemons[0]
+        .add_file(bucket_id, "/hello.txt", b"hello from alice")
+        .await;
+    let (alice, bob) = daemons.split_at_mut(1);
+    alice[0].share(bucket_id, &bob[0], ShareRole::Owner).await;
+    let head = assert_converged(&mut daemons, bucket_id).await;
+
+    let actor = format!("peer:{}", daemons[0].node_id());
+    let client = daemons[1].client.clone();
+    let request = AuditLsRequest {
+        bucket_id: Some(bucket_id),
+        actor: Some(actor.clone()),
+        ..AuditLsRequest::default()
+    };
+    wait_for("synced head to be audited", || {
+        let mut client = client.clone();
+        let request = request.clone();
+        let head = head.hash().to_string();
+        async move {
+            client.call(request).await.is_ok_and(|response| {
+                response
+                    .entries
+                    .iter()
+                    .any(|entry| entry.version.as_ref() == Some(&head))
+            })
+        }
+    })
+    .await;
+
+    // Bob joining the bucket is attributed to Alice too
+    let response = daemons[1].client.call(request).await.unwrap();
+    assert!(response
+        .entries
+        .iter()
+        .all(|entry| entry.actor == actor
+            && matches!(entry.operation.as_str(), "sync" | "bucket.join")));
+    assert_eq!(
+        response
+            .entries
+            .iter()
+            .filter(|entry| entry.operation == "bucket.join")
+            .count(),
+        1
+    );
+
+    for daemon in daemons {
+        daemon.stop().await;
+    }
+}
@@ -0,0 +1,138 @@
+//! Scheduled backups of a local directory into a bucket
+
+mod common;
+
+use std::fs;
+
+use jax_daemon::http_server::api::v0::backup::{
+    BackupCreateRequest, BackupListRequest, BackupRemoveRequest, BackupRunRequest,
+    BackupSnapshotsRequest,
+};
+
+use crate::common::TestDaemon;
+
+#[tokio::test]
+async fn test_backup_mirrors_directory() {
+    let mut daemon = TestDaemon::start().await;
+    let bucket_id = daemon.create_bucket("backups").await;
+
+    let source = tempfile::tempdir().unwrap();
+    fs::write(source.path().join("a.txt"), b"a").unwrap();
+    fs::create_dir(source.path().join("notes")).unwrap();
+    fs::write(source.path().join("notes/b.txt"), b"b").unwrap();
+
+    let job = daemon
+        .client
+        .call(BackupCreateRequest {
+            source_dir: source.path().to_path_buf(),
+            bucket_id,
+            target_path: "/home/".to_string(),
+            schedule: "@daily".to_string(),
+            retain: 2,
+            name: Some("home".to_string()),
+        })
+        .await
+        .unwrap();
+    assert_eq!(job.target_path, "/home");
+    assert!(job.last_run_at.is_none());
+
+    let snapshot = daemon
+        .client
+        .call(BackupRunRequest { job_id: job.job_id })
+        .await
+        .unwrap();
+    assert_eq!(snapshot.files_uploaded, 2);
+    assert_eq!(snapshot.files_deleted, 0);
+    assert_eq!(daemon.cat(bucket_id, "/home/a.txt").await.unwrap(), b"a");
+    assert_eq!(
+        daemon.cat(bucket_id, "/home/notes/b.txt").await.unwrap(),
+        b"b"
+    );
+
+    // Nothing changed, nothing uploaded
+    let unchanged = daemon
+        .client
+        .call(BackupRunRequest { job_id: job.job_id })
+        .await
+        .unwrap();
+    assert_eq!(unchanged.files_uploaded, 0);
+    assert_eq!(unchanged.files_deleted, 0);
+    assert_eq!(unchanged.version, snapshot.version);
+
+    // Changes and removals are mirrored
+    fs::write(source.path().join("a.txt"), b"a2").unwrap();
+    fs::remove_dir_all(source.path().join("notes")).unwrap();
+    let changed = daemon
+        .client
+        .call(BackupRunRequest { job_id: job.job_id })
+        .await
+        .unwrap();
+    assert_eq!(changed.files_uploaded, 1);
+    assert_eq!(changed.files_deleted, 1);
+    assert_eq!(daemon.cat(bucket_id, "/home/a.txt").await.unwrap(), b"a2");
+    assert!(daemon.cat(bucket_id, "/home/notes/b.txt").await.is_err());
+
+    // Only the newest `retain` snapshots are kept
+    let snapshots = daemon
+        .client
+        .call(BackupSnapshotsRequest { job_id: job.job_id })
+        .await
+        .unwrap()
+        .snapshots;
+    assert_eq!(snapshots.len(), 2);
+    assert_eq!(snapshots[0].version, changed.version);
+
+    let jobs = daemon.client.call(BackupListRequest {}).await.unwrap().jobs;
+    assert_eq!(jobs.len(), 1);
+    assert!(jobs[0].last_run_at.is_some());
+    assert!(jobs[0].last_error.is_none());
+
+    // A failed run is recorded on the job
+    drop(source);
+    assert!(daemon
+        .client
+        .call(BackupRunRequest { job_id: job.job_id })
+        .await
+        .is_err());
+    let jobs = daemon.client.call(BackupListRequest {}).await.unwrap().jobs;
+    assert!(jobs[0].last_error.is_some());
+
+    daemon
+        .client
+        .call(BackupRemoveRequest { job_id: job.job_id })
+        .await
+        .unwrap();
+    let jobs = daemon.client.call(BackupListRequest {}).await.unwrap().jobs;
+    assert!(jobs.is_empty());
+}
+
+#[tokio::test]
+async fn test_backup_rejects_invalid_jobs() {
+    let mut daemon = TestDaemon::start().await;
+    let bucket_id = daemon.create_bucket("backups").await;
+    let source = tempfile::tempdir().unwrap();
+
+    let request = BackupCreateRequest {
+        source_dir: source.path().to_path_buf(),
+        bucket_id,
+        target_path: "/".to_string(),
+        schedule: "@daily".to_string(),
+        retain: 7,
+        name: None,
+    };
+
+    // Never fires
+    let mut invalid = request.clone();
+    invalid.schedule = "0 0 31 2 *".to_string();
+    assert!(daemon.client.call(invalid).await.is_err());
+
+    let mut invalid = request.clone();
+    invalid.source_dir = source.path().join("missing");
+    assert!(daemon.client.call(invalid).await.is_err());
+
+    let mut invalid = request.clone();
+    invalid.retain = 0;
+    assert!(daemon.client.call(invalid).await.is_err());
+
+    assert!(daemon.client.call(request).await.is_ok());
+}
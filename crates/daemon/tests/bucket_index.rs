@@ -0,0 +1,108 @@
+//! Index pages of published buckets on the gateway
+
+mod common;
+
+use jax_daemon::bucket_index::IndexUpdate;
+use jax_daemon::http_server::api::v0::bucket::PublishRequest;
+use jax_daemon::http_server::api::v0::gateway::policy::GatewayPolicySetRequest;
+
+use crate::common::{wait_for, TestDaemon};
+
+const HTML: &str = "text/html,application/xhtml+xml;q=0.9,*/*;q=0.8";
+
+async fn get_html(daemon: &TestDaemon, path: &str) -> (reqwest::StatusCode, String) {
+    let response = reqwest::Client::new()
+        .get(daemon.gateway_url.join(path).unwrap())
+        .header(reqwest::header::ACCEPT, HTML)
+        .send()
+        .await
+        .unwrap();
+    (response.status(), response.text().await.unwrap())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_bucket_root_index_page() {
+    let mut daemon = TestDaemon::start().await;
+    let bucket_id = daemon.create_bucket("field-notes").await;
+    daemon
+        .add_file(
+            bucket_id,
+            "/README.md",
+            b"# Welcome\n\nSee [the route](maps/route.gpx).\n",
+        )
+        .await;
+    daemon
+        .add_file(bucket_id, "/maps/route.gpx", b"<gpx></gpx>")
+        .await;
+    daemon
+        .client
+        .call(PublishRequest {
+            bucket_id,
+            rotate: false,
+            expected_version: None,
+            index: IndexUpdate {
+                title: Some("Field Notes 2026".to_string()),
+                description: Some("Routes and photos from the trip".to_string()),
+                featured: Some(vec!["/maps/route.gpx".to_string(), "/gone.txt".to_string()]),
+                noindex: None,
+            },
+        })
+        .await
+        .unwrap();
+    let livez = daemon.gateway_url.join("/_status/livez").unwrap();
+    wait_for("gateway to come up", || {
+        let livez = livez.clone();
+        async move { reqwest::get(livez).await.is_ok() }
+    })
+    .await;
+
+    let root = format!("/gw/{}/", bucket_id);
+    let (status, body) = get_html(&daemon, &root).await;
+    assert!(status.is_success());
+    assert!(body.contains("<title>Field Notes 2026 - Gateway</title>"));
+    assert!(body.contains("Routes and photos from the trip"));
+    assert!(body.contains("<h1>Welcome</h1>"));
+    // README links point into the bucket, featured files that are gone are left out
+    assert!(body.contains(&format!("/gw/{}/maps/route.gpx", bucket_id)));
+    assert!(!body.contains("gone.txt"));
+    // The listing follows
+    assert!(body.contains("README.md"));
+
+    // The index is shown without listings; other directories aren't
+    daemon
+        .client
+        .call(GatewayPolicySetRequest {
+            bucket_id,
+            allow: false,
+            no_listings: true,
+            noindex: false,
+            serve_stale: false,
+            daily_bandwidth_bytes: None,
+        })
+        .await
+        .unwrap();
+    let (status, body) = get_html(&daemon, &root).await;
+    assert!(status.is_success());
+    assert!(body.contains("Field Notes 2026"));
+    assert!(!body.contains("Breadcrumb"));
+    let (status, _) = get_html(&daemon, &format!("/gw/{}/maps/", bucket_id)).await;
+    assert!(!status.is_success());
+
+    // Publishing again keeps the settings unless they're changed or cleared
+    daemon
+        .client
+        .call(PublishRequest {
+            bucket_id,
+            rotate: false,
+            expected_version: None,
+            index: IndexUpdate {
+                title: Some(String::new()),
+                ..Default::default()
+            },
+        })
+        .await
+        .unwrap();
+    let (_, body) = get_html(&daemon, &root).await;
+    assert!(!body.contains("Field Notes 2026"));
+    assert!(body.contains("Routes and photos from the trip"));
+}
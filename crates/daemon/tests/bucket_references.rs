@@ -0,0 +1,134 @@
+//! References to other buckets, read through like directories
+
+mod common;
+
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::ls::LsRequest;
+use jax_daemon::http_server::api::v0::bucket::ReferenceRequest;
+use uuid::Uuid;
+
+use crate::common::TestDaemon;
+
+fn reference(bucket_id: Uuid, path: &str, target: Uuid, floating: bool) -> ReferenceRequest {
+    ReferenceRequest {
+        bucket_id,
+        path: path.to_string(),
+        target,
+        version: None,
+        floating,
+        expected_version: None,
+    }
+}
+
+fn status(result: Result<impl std::fmt::Debug, ApiError>) -> reqwest::StatusCode {
+    match result {
+        Err(ApiError::HttpStatus(status, _)) => status,
+        other => panic!("expected an HTTP error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_pinned_and_floating_references_are_read_through() {
+    let mut daemon = TestDaemon::start().await;
+    let app = daemon.create_bucket("app").await;
+    let lib = daemon.create_bucket("lib").await;
+    daemon.add_file(lib, "/src/lib.rs", b"v1").await;
+
+    daemon
+        .client
+        .call(reference(app, "/vendor/pinned", lib, false))
+        .await
+        .unwrap();
+    daemon
+        .client
+        .call(reference(app, "/vendor/floating", lib, true))
+        .await
+        .unwrap();
+    daemon.add_file(lib, "/src/lib.rs", b"v2").await;
+
+    // Pinned references keep reading the version recorded, floating ones the
+    //  referenced bucket's latest
+    assert_eq!(
+        daemon.cat(app, "/vendor/pinned/src/lib.rs").await.unwrap(),
+        b"v1"
+    );
+    assert_eq!(
+        daemon
+            .cat(app, "/vendor/floating/src/lib.rs")
+            .await
+            .unwrap(),
+        b"v2"
+    );
+
+    // Listed like directories, and listed through at the outer paths
+    let items = daemon
+        .client
+        .call(LsRequest {
+            bucket_id: app,
+            path: Some("/vendor".to_string()),
+            deep: None,
+            min_version: None,
+            starred: false,
+            filter: None,
+            sort: None,
+            page: Default::default(),
+        })
+        .await
+        .unwrap()
+        .items;
+    assert_eq!(items.len(), 2);
+    assert!(items
+        .iter()
+        .all(|item| item.is_dir && item.reference == Some(lib)));
+    let items = daemon
+        .client
+        .call(LsRequest {
+            bucket_id: app,
+            path: Some("/vendor/pinned/src".to_string()),
+            deep: None,
+            min_version: None,
+            starred: false,
+            filter: None,
+            sort: None,
+            page: Default::default(),
+        })
+        .await
+        .unwrap()
+        .items;
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].path, "/vendor/pinned/src/lib.rs");
+    assert_eq!(items[0].reference, None);
+
+    // A reference isn't a file
+    assert_eq!(
+        status(daemon.cat(app, "/vendor/pinned").await),
+        reqwest::StatusCode::BAD_REQUEST
+    );
+}
+
+#[tokio::test]
+async fn test_reference_cycles_are_rejected() {
+    let mut daemon = TestDaemon::start().await;
+    let a = daemon.create_bucket("a").await;
+    let b = daemon.create_bucket("b").await;
+
+    assert_eq!(
+        status(daemon.client.call(reference(a, "/a", a, true)).await),
+        reqwest::StatusCode::BAD_REQUEST
+    );
+
+    daemon
+        .client
+        .call(reference(a, "/b", b, true))
+        .await
+        .unwrap();
+    daemon
+        .client
+        .call(reference(b, "/a", a, true))
+        .await
+        .unwrap();
+    assert_eq!(
+        status(daemon.cat(a, "/b/a/b/file.txt").await),
+        reqwest::StatusCode::BAD_REQUEST
+    );
+}
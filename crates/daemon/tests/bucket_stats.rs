@@ -0,0 +1,110 @@
+//! Bucket statistics of the current version
+
+mod common;
+
+use jax_daemon::http_server::api::v0::bucket::stats::StatsRequest;
+
+use crate::common::TestDaemon;
+
+#[tokio::test]
+async fn test_stats_count_files_sizes_and_types() {
+    let mut daemon = TestDaemon::start().await;
+    let bucket_id = daemon.create_bucket("overview").await;
+    daemon
+        .add_file(bucket_id, "/docs/readme.txt", b"hello world")
+        .await;
+    daemon
+        .add_file(bucket_id, "/docs/notes/todo.txt", b"todo")
+        .await;
+    daemon
+        .add_file(bucket_id, "/site/index.html", b"<html></html>")
+        .await;
+    daemon
+        .add_file(bucket_id, "/site/copy.html", b"<html></html>")
+        .await;
+    let version = daemon.add_file(bucket_id, "/log.txt", b"one").await;
+    // Appended files have no recorded size, so they're read
+    daemon.append_file(bucket_id, "/log.txt", b" two").await;
+
+    let stats = daemon
+        .client
+        .call(StatsRequest {
+            bucket_id,
+            top: Some(2),
+        })
+        .await
+        .unwrap();
+    assert_eq!(stats.name, "overview");
+    assert_ne!(stats.stats.version, version.hash().to_string());
+    assert_eq!(stats.stats.files, 5);
+    assert_eq!(stats.stats.directories, 3);
+    assert_eq!(stats.stats.logical_bytes, 11 + 4 + 13 + 13 + 7);
+    assert!(stats.stats.physical_bytes > 0);
+
+    let largest: Vec<_> = stats
+        .stats
+        .largest_files
+        .iter()
+        .map(|file| (file.path.as_str(), file.size))
+        .collect();
+    assert_eq!(
+        largest,
+        vec![("/site/copy.html", 13), ("/site/index.html", 13)]
+    );
+
+    let html = stats
+        .stats
+        .mime_types
+        .iter()
+        .find(|mime| mime.mime_type == "text/html")
+        .unwrap();
+    assert_eq!((html.files, html.bytes), (2, 26));
+    let text = stats
+        .stats
+        .mime_types
+        .iter()
+        .find(|mime| mime.mime_type == "text/plain")
+        .unwrap();
+    assert_eq!((text.files, text.bytes), (3, 22));
+    assert_eq!(stats.stats.mime_types[0].mime_type, "text/html");
+}
+
+#[tokio::test]
+async fn test_stats_follow_new_versions() {
+    let mut daemon = TestDaemon::start().await;
+    let bucket_id = daemon.create_bucket("growing").await;
+    daemon.add_file(bucket_id, "/a/one.txt", b"one").await;
+    daemon.add_file(bucket_id, "/b/two.txt", b"two").await;
+
+    let request = StatsRequest {
+        bucket_id,
+        top: None,
+    };
+    let before = daemon.client.call(request.clone()).await.unwrap();
+    assert_eq!(before.stats.files, 2);
+    let again = daemon.client.call(request.clone()).await.unwrap();
+    assert_eq!(again.stats.version, before.stats.version);
+    assert_eq!(again.stats.files, 2);
+
+    // Only /b changes; the stats still count the whole tree
+    let link = daemon.add_file(bucket_id, "/b/three.txt", b"three").await;
+    let after = daemon.client.call(request).await.unwrap();
+    assert_eq!(after.stats.version, link.hash().to_string());
+    assert_eq!(after.stats.files, 3);
+    assert_eq!(after.stats.directories, 2);
+    assert_eq!(after.stats.logical_bytes, 3 + 3 + 5);
+    assert!(after.stats.physical_bytes > before.stats.physical_bytes);
+}
+
+#[tokio::test]
+async fn test_stats_of_unknown_bucket() {
+    let mut daemon = TestDaemon::start().await;
+    assert!(daemon
+        .client
+        .call(StatsRequest {
+            bucket_id: uuid::Uuid::new_v4(),
+            top: None,
+        })
+        .await
+        .is_err());
+}
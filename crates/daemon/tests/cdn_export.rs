@@ -0,0 +1,216 @@
+//! CDN exports mirroring a bucket's published content into a target
+
+mod common;
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use reqwest::StatusCode;
+
+use jax_daemon::cdn_export::{CdnManifest, MANIFEST_FILE, VERSIONS_DIR};
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::cdn::{
+    CdnGetRequest, CdnResetRequest, CdnRunRequest, CdnSetRequest,
+};
+use jax_daemon::http_server::api::v0::bucket::{PublishRequest, UnpublishRequest};
+use jax_daemon::storage_policy::BlobLocationConfig;
+use uuid::Uuid;
+
+use crate::common::TestDaemon;
+
+/// The manifest an export wrote, if any
+fn manifest(site: &Path) -> Option<CdnManifest> {
+    let data = std::fs::read(site.join(MANIFEST_FILE)).ok()?;
+    Some(serde_json::from_slice(&data).unwrap())
+}
+
+/// Whether a directory holds any file, at any depth
+fn has_files(dir: &Path) -> bool {
+    std::fs::read_dir(dir).unwrap().any(|entry| {
+        let path = entry.unwrap().path();
+        !path.is_dir() || has_files(&path)
+    })
+}
+
+/// Versions an export holds; deleting files leaves their directories
+fn versions(site: &Path) -> usize {
+    std::fs::read_dir(site.join(VERSIONS_DIR))
+        .map(|entries| {
+            entries
+                .filter(|entry| has_files(&entry.as_ref().unwrap().path()))
+                .count()
+        })
+        .unwrap_or(0)
+}
+
+async fn publish(daemon: &mut TestDaemon, bucket_id: Uuid) -> String {
+    daemon
+        .client
+        .call(PublishRequest {
+            bucket_id,
+            rotate: false,
+            expected_version: None,
+            index: Default::default(),
+        })
+        .await
+        .unwrap()
+        .new_bucket_link
+}
+
+#[tokio::test]
+async fn test_cdn_export_mirrors_published_versions() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let target = dir.path().join("www");
+    let site = target.join("sites/blog");
+    let location = target.clone();
+    let mut daemon = TestDaemon::start_with(|config| {
+        config.cdn_targets = BTreeMap::from([(
+            "www".to_string(),
+            BlobLocationConfig::Filesystem { path: location },
+        )]);
+    })
+    .await;
+
+    let bucket_id = daemon.create_bucket("blog").await;
+    daemon
+        .add_file(bucket_id, "/index.html", b"<h1>Hello</h1>")
+        .await;
+    daemon
+        .add_file(bucket_id, "/css/site.css", b"h1 { color: red }")
+        .await;
+
+    // Only configured targets and prefixes inside them can be chosen
+    for (target, prefix) in [("cloudfront", ""), ("www", "../escape")] {
+        match daemon
+            .client
+            .call(CdnSetRequest {
+                bucket_id,
+                target: target.to_string(),
+                prefix: prefix.to_string(),
+            })
+            .await
+        {
+            Err(ApiError::HttpStatus(status, _)) => assert_eq!(status, StatusCode::BAD_REQUEST),
+            other => panic!("expected a 400, got {:?}", other.map(|_| ())),
+        }
+    }
+    let response = daemon
+        .client
+        .call(CdnSetRequest {
+            bucket_id,
+            target: "www".to_string(),
+            prefix: "/sites/blog/".to_string(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(response.targets, ["www"]);
+    let export = response.export.unwrap();
+    assert_eq!(export.prefix, "sites/blog");
+
+    // Nothing is published, so nothing is written
+    let report = daemon
+        .client
+        .call(CdnRunRequest { bucket_id })
+        .await
+        .unwrap();
+    assert_eq!(report.version, None);
+    assert!(manifest(&site).is_none());
+
+    // Publishing writes the version's files and switches the manifest to it
+    let first = publish(&mut daemon, bucket_id).await;
+    let report = daemon
+        .client
+        .call(CdnRunRequest { bucket_id })
+        .await
+        .unwrap();
+    assert_eq!(report.version.as_deref(), Some(first.as_str()));
+    assert_eq!(report.files, 2);
+    let written = manifest(&site).unwrap();
+    assert_eq!(written.version, first);
+    assert_eq!(written.name, "blog");
+    assert_eq!(written.files["index.html"].content_type, "text/html");
+    assert_eq!(written.files["css/site.css"].content_type, "text/css");
+    assert_eq!(written.files["css/site.css"].size, 17);
+    assert_eq!(
+        std::fs::read(site.join(&written.root).join("index.html")).unwrap(),
+        b"<h1>Hello</h1>"
+    );
+
+    // The version it replaces is kept, older ones are deleted
+    daemon
+        .add_file(bucket_id, "/about.html", b"<p>About</p>")
+        .await;
+    let second = publish(&mut daemon, bucket_id).await;
+    daemon
+        .client
+        .call(CdnRunRequest { bucket_id })
+        .await
+        .unwrap();
+    assert_eq!(manifest(&site).unwrap().version, second);
+    assert_eq!(versions(&site), 2);
+    daemon
+        .add_file(bucket_id, "/index.html", b"<h1>Hi</h1>")
+        .await;
+    let third = publish(&mut daemon, bucket_id).await;
+    daemon
+        .client
+        .call(CdnRunRequest { bucket_id })
+        .await
+        .unwrap();
+    let written = manifest(&site).unwrap();
+    assert_eq!(written.version, third);
+    assert_eq!(written.replaced.as_deref(), Some(second.as_str()));
+    assert_eq!(versions(&site), 2);
+    assert!(!site
+        .join(VERSIONS_DIR)
+        .join(&first)
+        .join("index.html")
+        .exists());
+
+    let export = daemon
+        .client
+        .call(CdnGetRequest { bucket_id })
+        .await
+        .unwrap()
+        .export
+        .unwrap();
+    assert_eq!(export.exported_version.as_deref(), Some(third.as_str()));
+
+    // Unpublishing withdraws the export
+    daemon
+        .client
+        .call(UnpublishRequest {
+            bucket_id,
+            expected_version: None,
+        })
+        .await
+        .unwrap();
+    let report = daemon
+        .client
+        .call(CdnRunRequest { bucket_id })
+        .await
+        .unwrap();
+    assert_eq!(report.version, None);
+    assert!(manifest(&site).is_none());
+    assert_eq!(versions(&site), 0);
+
+    // Removing the export deletes what is left
+    publish(&mut daemon, bucket_id).await;
+    daemon
+        .client
+        .call(CdnRunRequest { bucket_id })
+        .await
+        .unwrap();
+    let response = daemon
+        .client
+        .call(CdnResetRequest { bucket_id })
+        .await
+        .unwrap();
+    assert!(response.withdrawal.is_some());
+    assert!(manifest(&site).is_none());
+    assert_eq!(versions(&site), 0);
+    match daemon.client.call(CdnRunRequest { bucket_id }).await {
+        Err(ApiError::HttpStatus(status, _)) => assert_eq!(status, StatusCode::NOT_FOUND),
+        other => panic!("expected a 404, got {:?}", other.map(|_| ())),
+    }
+}
@@ -0,0 +1,201 @@
+//! Comments on files, synced with the bucket
+
+mod common;
+
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::comments::{
+    AddCommentRequest, CommentInfo, CommentsRequest, RemoveCommentRequest,
+};
+use jax_daemon::http_server::api::v0::bucket::share::ShareRole;
+
+use crate::common::{assert_converged, start_daemons, wait_for, TestDaemon};
+
+fn add(bucket_id: uuid::Uuid, path: &str, body: &str) -> AddCommentRequest {
+    AddCommentRequest {
+        bucket_id,
+        path: path.to_string(),
+        body: body.to_string(),
+        version: None,
+        expected_version: None,
+    }
+}
+
+async fn comments(
+    daemon: &mut TestDaemon,
+    bucket_id: uuid::Uuid,
+    path: Option<&str>,
+    min_version: Option<String>,
+) -> Vec<CommentInfo> {
+    daemon
+        .client
+        .call(CommentsRequest {
+            bucket_id,
+            path: path.map(str::to_string),
+            min_version,
+        })
+        .await
+        .unwrap()
+        .comments
+}
+
+/// Wait until a peer lists `count` comments of a bucket. Heads converge
+/// before the comments blob is downloaded, so listing may fail until then.
+async fn wait_for_comments(daemon: &mut TestDaemon, bucket_id: uuid::Uuid, count: usize) {
+    let client = daemon.client.clone();
+    wait_for(&format!("{} comments to sync", count), || {
+        let mut client = client.clone();
+        async move {
+            client
+                .call(CommentsRequest {
+                    bucket_id,
+                    path: None,
+                    min_version: None,
+                })
+                .await
+                .is_ok_and(|response| response.comments.len() == count)
+        }
+    })
+    .await;
+}
+
+fn status(result: Result<impl std::fmt::Debug, ApiError>) -> reqwest::StatusCode {
+    match result {
+        Err(ApiError::HttpStatus(status, _)) => status,
+        other => panic!("expected an HTTP error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_comments_are_added_listed_and_removed() {
+    let mut daemon = TestDaemon::start().await;
+    let bucket_id = daemon.create_bucket("review").await;
+    let first = daemon.add_file(bucket_id, "/plan.md", b"v1").await;
+    daemon.add_file(bucket_id, "/notes.txt", b"notes").await;
+
+    let on_plan = daemon
+        .client
+        .call(add(bucket_id, "plan.md", "Intro reads well"))
+        .await
+        .unwrap();
+    assert_eq!(on_plan.comment.path, "/plan.md");
+    // A comment on an earlier version of the file
+    let mut earlier = add(bucket_id, "/plan.md", "The first draft was shorter");
+    earlier.version = Some(first.hash().to_string());
+    let earlier = daemon.client.call(earlier).await.unwrap();
+    assert_eq!(earlier.comment.version, first.hash().to_string());
+    daemon
+        .client
+        .call(add(bucket_id, "/notes.txt", "Move to docs"))
+        .await
+        .unwrap();
+
+    let all = comments(&mut daemon, bucket_id, None, None).await;
+    assert_eq!(all.len(), 3);
+    let on_path = comments(&mut daemon, bucket_id, Some("/plan.md"), None).await;
+    assert_eq!(on_path.len(), 2);
+    assert!(on_path.contains(&on_plan.comment));
+    assert!(on_path.contains(&earlier.comment));
+
+    // Comments carry over to versions saved since
+    daemon.add_file(bucket_id, "/other.txt", b"other").await;
+    assert_eq!(comments(&mut daemon, bucket_id, None, None).await.len(), 3);
+
+    let removed = daemon
+        .client
+        .call(RemoveCommentRequest {
+            bucket_id,
+            comment_id: on_plan.comment.id,
+            expected_version: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(removed.comment.id, on_plan.comment.id);
+    assert_eq!(comments(&mut daemon, bucket_id, None, None).await.len(), 2);
+
+    assert_eq!(
+        status(
+            daemon
+                .client
+                .call(RemoveCommentRequest {
+                    bucket_id,
+                    comment_id: on_plan.comment.id,
+                    expected_version: None,
+                })
+                .await
+        ),
+        reqwest::StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        status(
+            daemon
+                .client
+                .call(add(bucket_id, "/missing.txt", "Where is it?"))
+                .await
+        ),
+        reqwest::StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        status(daemon.client.call(add(bucket_id, "/plan.md", "  ")).await),
+        reqwest::StatusCode::BAD_REQUEST
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_comments_sync_to_peer() {
+    let mut daemons = start_daemons(2).await;
+
+    let bucket_id = daemons[0].create_bucket("shared-review").await;
+    daemons[0].add_file(bucket_id, "/draft.md", b"draft").await;
+    let (alice, bob) = daemons.split_at_mut(1);
+    alice[0].share(bucket_id, &bob[0], ShareRole::Owner).await;
+
+    let from_alice = daemons[0]
+        .client
+        .call(add(bucket_id, "/draft.md", "Needs a summary"))
+        .await
+        .unwrap();
+    let head = assert_converged(&mut daemons, bucket_id).await;
+    wait_for_comments(&mut daemons[1], bucket_id, 1).await;
+
+    let seen = comments(
+        &mut daemons[1],
+        bucket_id,
+        Some("/draft.md"),
+        Some(head.hash().to_string()),
+    )
+    .await;
+    assert_eq!(seen, vec![from_alice.comment.clone()]);
+
+    // Bob replies, but can't remove Alice's comment
+    daemons[1]
+        .client
+        .call(add(bucket_id, "/draft.md", "Added one"))
+        .await
+        .unwrap();
+    assert_eq!(
+        status(
+            daemons[1]
+                .client
+                .call(RemoveCommentRequest {
+                    bucket_id,
+                    comment_id: from_alice.comment.id,
+                    expected_version: None,
+                })
+                .await
+        ),
+        reqwest::StatusCode::FORBIDDEN
+    );
+
+    let head = assert_converged(&mut daemons, bucket_id).await;
+    wait_for_comments(&mut daemons[0], bucket_id, 2).await;
+    let seen = comments(
+        &mut daemons[0],
+        bucket_id,
+        None,
+        Some(head.hash().to_string()),
+    )
+    .await;
+    assert_eq!(seen.len(), 2);
+    let reply = seen.iter().find(|c| c.body == "Added one").unwrap();
+    assert_eq!(reply.author, daemons[1].node_id());
+}
@@ -0,0 +1,420 @@
+//! Multi-daemon test harness
+//!
+//! Starts full daemons (peer + API + gateway) in-process, each with its own
+//! temp dir, in-memory database and ephemeral ports, and drives them through
+//! the API client like the CLI would.
+#![allow(dead_code)]
+
+use std::future::Future;
+use std::net::{Ipv4Addr, SocketAddr, TcpListener};
+use std::time::Duration;
+
+use reqwest::multipart;
+use tempfile::TempDir;
+use url::Url;
+use uuid::Uuid;
+
+use common::linked_data::Link;
+use common::peer::{ConnectionLimits, ProtocolTimeouts};
+use jax_daemon::http_server::api::client::{ApiClient, ApiError};
+use jax_daemon::http_server::api::v0::bucket::add::AddResponse;
+use jax_daemon::http_server::api::v0::bucket::append::AppendResponse;
+use jax_daemon::http_server::api::v0::bucket::cat::CatRequest;
+use jax_daemon::http_server::api::v0::bucket::share::ShareRole;
+use jax_daemon::http_server::api::v0::bucket::{CreateRequest, ListRequest, ShareRequest};
+use jax_daemon::http_server::api::v0::peer::PeerAddRequest;
+use jax_daemon::peers::PeerTicket;
+use jax_daemon::{BlobStoreConfig, ServiceConfig, ServiceState, ShutdownHandle};
+
+/// How long [`wait_for`] waits before failing the test
+pub const CONVERGENCE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Sync interval of test daemons, short so tests converge quickly
+const SYNC_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A daemon running in this process
+pub struct TestDaemon {
+    pub state: ServiceState,
+    pub client: ApiClient,
+    pub gateway_url: Url,
+    handle: ShutdownHandle,
+    _dir: TempDir,
+}
+
+impl TestDaemon {
+    /// Start a daemon on ephemeral ports in a fresh temp dir
+    pub async fn start() -> Self {
+        Self::start_in(TempDir::new().unwrap()).await
+    }
+
+    /// Start a daemon on ephemeral ports in a prepared temp dir, e.g. one
+    /// with profiles created in it
+    pub async fn start_in(dir: TempDir) -> Self {
+        Self::start_configured(dir, |_| {}).await
+    }
+
+    /// Start a daemon on ephemeral ports, adjusting its config first
+    pub async fn start_with(configure: impl FnOnce(&mut ServiceConfig)) -> Self {
+        Self::start_configured(TempDir::new().unwrap(), configure).await
+    }
+
+    async fn start_configured(dir: TempDir, configure: impl FnOnce(&mut ServiceConfig)) -> Self {
+        // Logs of every daemon go to the test output, filtered by RUST_LOG
+        let _ = tracing_subscriber::fmt()
+            .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+            .with_test_writer()
+            .try_init();
+
+        let api_port = free_port();
+        let gateway_port = free_port();
+        let mut config = ServiceConfig {
+            node_listen_addr: None,
+            node_secret: None,
+            blob_store: BlobStoreConfig::Legacy,
+            blob_locations: Default::default(),
+            cdn_targets: Default::default(),
+            jax_dir: dir.path().to_path_buf(),
+            api_port,
+            gateway_port,
+            sqlite_path: None,
+            database_key: None,
+            config_path: None,
+            sync_interval: SYNC_INTERVAL,
+            periodic_sync: true,
+            online_cache_max_bytes: 64 * 1024 * 1024,
+            sync_deferral: Default::default(),
+            protocol_timeouts: ProtocolTimeouts::default(),
+            connection_limits: ConnectionLimits::default(),
+            log_level: tracing::Level::INFO,
+            log_dir: None,
+            otlp_endpoint: None,
+            gateway_url: None,
+            gateway_tls: None,
+            reverse_proxy: None,
+            publish_scanners: Vec::new(),
+            publish_builders: Vec::new(),
+            upload_limits: Default::default(),
+            import_dirs: Vec::new(),
+            read_replica: false,
+            cluster: None,
+        };
+        configure(&mut config);
+        let (state, handle) = jax_daemon::start_service(&config).await;
+        // Keep tests independent of the machine's battery and network
+        state
+            .sync_status()
+            .conditions()
+            .report(Some(Default::default()));
+
+        let api_url = Url::parse(&format!("http://127.0.0.1:{}", api_port)).unwrap();
+        let client = ApiClient::new(&api_url).unwrap();
+        let gateway_url = Url::parse(&format!("http://127.0.0.1:{}", gateway_port)).unwrap();
+        let daemon = Self {
+            state,
+            client,
+            gateway_url,
+            handle,
+            _dir: dir,
+        };
+
+        // The servers are spawned; wait until the API answers
+        let client = daemon.client.clone();
+        wait_for("API to come up", || {
+            let mut client = client.clone();
+            async move {
+                client
+                    .call(ListRequest {
+                        prefix: None,
+                        filter: None,
+                        sort: None,
+                        page: Default::default(),
+                        workspace: None,
+                        starred: false,
+                    })
+                    .await
+                    .is_ok()
+            }
+        })
+        .await;
+        daemon
+    }
+
+    /// Hex public key of the daemon's peer
+    pub fn node_id(&self) -> String {
+        self.state.peer().secret().public().to_hex()
+    }
+
+    /// Ticket reaching the daemon's peer over loopback
+    pub fn ticket(&self) -> PeerTicket {
+        let direct_addresses = self
+            .state
+            .peer()
+            .endpoint()
+            .bound_sockets()
+            .into_iter()
+            .filter(|addr| addr.is_ipv4())
+            .map(|addr| SocketAddr::new(Ipv4Addr::LOCALHOST.into(), addr.port()))
+            .collect();
+        PeerTicket {
+            node_id: self.state.peer().secret().public(),
+            relay_url: None,
+            direct_addresses,
+        }
+    }
+
+    /// Add `other` to this daemon's address book
+    pub async fn add_peer(&mut self, other: &TestDaemon) {
+        self.client
+            .call(PeerAddRequest {
+                ticket: other.ticket().to_string(),
+            })
+            .await
+            .unwrap();
+    }
+
+    pub async fn create_bucket(&mut self, name: &str) -> Uuid {
+        self.client
+            .call(CreateRequest {
+                name: name.to_string(),
+                compress: false,
+                extract_media: false,
+            })
+            .await
+            .unwrap()
+            .bucket_id
+    }
+
+    /// Share a bucket with another daemon
+    pub async fn share(&mut self, bucket_id: Uuid, other: &TestDaemon, role: ShareRole) {
+        self.client
+            .call(ShareRequest {
+                bucket_id,
+                peer_public_key: other.node_id(),
+                role,
+                path: None,
+                expected_version: None,
+            })
+            .await
+            .unwrap();
+    }
+
+    /// Upload `data` to `path` in a bucket
+    pub async fn add_file(&mut self, bucket_id: Uuid, path: &str, data: &[u8]) -> Link {
+        let (dir, name) = path.rsplit_once('/').expect("path must be absolute");
+        let dir = if dir.is_empty() { "/" } else { dir };
+        let form = multipart::Form::new()
+            .text("bucket_id", bucket_id.to_string())
+            .text("mount_path", dir.to_string())
+            .part(
+                "file",
+                multipart::Part::bytes(data.to_vec()).file_name(name.to_string()),
+            );
+        let url = self.client.base_url().join("/api/v0/bucket/add").unwrap();
+        let response = self
+            .client
+            .http_client()
+            .post(url)
+            .multipart(form)
+            .send()
+            .await
+            .unwrap();
+        assert!(
+            response.status().is_success(),
+            "add failed: {}",
+            response.text().await.unwrap()
+        );
+        response.json::<AddResponse>().await.unwrap().bucket_link
+    }
+
+    /// Append `data` to the file at `path` in a bucket
+    pub async fn append_file(&mut self, bucket_id: Uuid, path: &str, data: &[u8]) -> Link {
+        let form = multipart::Form::new()
+            .text("bucket_id", bucket_id.to_string())
+            .text("mount_path", path.to_string())
+            .part("file", multipart::Part::bytes(data.to_vec()));
+        let url = self
+            .client
+            .base_url()
+            .join("/api/v0/bucket/append")
+            .unwrap();
+        let response = self
+            .client
+            .http_client()
+            .post(url)
+            .multipart(form)
+            .send()
+            .await
+            .unwrap();
+        assert!(
+            response.status().is_success(),
+            "append failed: {}",
+            response.text().await.unwrap()
+        );
+        response.json::<AppendResponse>().await.unwrap().link
+    }
+
+    /// Read a file of a bucket
+    pub async fn cat(&mut self, bucket_id: Uuid, path: &str) -> Result<Vec<u8>, ApiError> {
+        let response = self
+            .client
+            .call(CatRequest {
+                bucket_id,
+                path: path.to_string(),
+                at: None,
+                min_version: None,
+                download: None,
+            })
+            .await?;
+        Ok(
+            base64::Engine::decode(&base64::engine::general_purpose::STANDARD, response.content)
+                .unwrap(),
+        )
+    }
+
+    /// Read a file of a bucket at no older a version than `min_version`
+    pub async fn cat_at_least(
+        &mut self,
+        bucket_id: Uuid,
+        path: &str,
+        min_version: &Link,
+    ) -> Result<Vec<u8>, ApiError> {
+        let response = self
+            .client
+            .call(CatRequest {
+                bucket_id,
+                path: path.to_string(),
+                at: None,
+                min_version: Some(min_version.hash().to_string()),
+                download: None,
+            })
+            .await?;
+        Ok(
+            base64::Engine::decode(&base64::engine::general_purpose::STANDARD, response.content)
+                .unwrap(),
+        )
+    }
+
+    /// Wait until a file of a bucket reads back as `expected`. Heads
+    /// converge before content is downloaded, so reads may fail until then.
+    pub async fn wait_for_file(&mut self, bucket_id: Uuid, path: &str, expected: &[u8]) {
+        let client = self.client.clone();
+        wait_for(&format!("{} to sync", path), || {
+            let mut client = client.clone();
+            async move {
+                client
+                    .call(CatRequest {
+                        bucket_id,
+                        path: path.to_string(),
+                        at: None,
+                        min_version: None,
+                        download: None,
+                    })
+                    .await
+                    .is_ok_and(|response| {
+                        base64::Engine::decode(
+                            &base64::engine::general_purpose::STANDARD,
+                            response.content,
+                        )
+                        .is_ok_and(|content| content == expected)
+                    })
+            }
+        })
+        .await;
+    }
+
+    /// The daemon's head of a bucket, if it has the bucket
+    pub async fn head(&mut self, bucket_id: Uuid) -> Option<Link> {
+        self.client
+            .call(ListRequest {
+                prefix: None,
+                filter: None,
+                sort: None,
+                page: Default::default(),
+                workspace: None,
+                starred: false,
+            })
+            .await
+            .ok()?
+            .buckets
+            .into_iter()
+            .find(|bucket| bucket.bucket_id == bucket_id)
+            .map(|bucket| bucket.link)
+    }
+
+    /// Shut the daemon down and wait for it to stop
+    pub async fn stop(self) {
+        self.handle.shutdown();
+        self.handle.wait().await;
+    }
+}
+
+/// Start `n` daemons that know each other's addresses
+pub async fn start_daemons(n: usize) -> Vec<TestDaemon> {
+    let mut daemons = Vec::with_capacity(n);
+    for _ in 0..n {
+        daemons.push(TestDaemon::start().await);
+    }
+    for i in 0..n {
+        for j in 0..n {
+            if i != j {
+                let (a, b) = if i < j {
+                    let (left, right) = daemons.split_at_mut(j);
+                    (&mut left[i], &right[0])
+                } else {
+                    let (left, right) = daemons.split_at_mut(i);
+                    (&mut right[0], &left[j])
+                };
+                a.add_peer(b).await;
+            }
+        }
+    }
+    daemons
+}
+
+/// Poll `check` until it holds, failing the test after [`CONVERGENCE_TIMEOUT`]
+pub async fn wait_for<F, Fut>(what: &str, mut check: F)
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = bool>,
+{
+    let deadline = tokio::time::Instant::now() + CONVERGENCE_TIMEOUT;
+    while !check().await {
+        if tokio::time::Instant::now() > deadline {
+            panic!("timed out waiting for {}", what);
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+}
+
+/// Wait until every daemon has the same head of a bucket
+pub async fn assert_converged(daemons: &mut [TestDaemon], bucket_id: Uuid) -> Link {
+    let mut converged = None;
+    let deadline = tokio::time::Instant::now() + CONVERGENCE_TIMEOUT;
+    loop {
+        let mut heads = Vec::with_capacity(daemons.len());
+        for daemon in daemons.iter_mut() {
+            heads.push(daemon.head(bucket_id).await);
+        }
+        if let Some(Some(head)) = heads.first() {
+            if heads.iter().all(|h| h.as_ref() == Some(head)) {
+                converged = Some(head.clone());
+            }
+        }
+        if let Some(head) = converged {
+            return head;
+        }
+        if tokio::time::Instant::now() > deadline {
+            panic!("bucket {} did not converge: {:?}", bucket_id, heads);
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+}
+
+/// A port nothing listens on right now
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
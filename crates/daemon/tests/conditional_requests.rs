@@ -0,0 +1,93 @@
+//! HEAD and If-Modified-Since on the gateway and the API
+
+mod common;
+
+use reqwest::{header, Method, StatusCode};
+
+use jax_daemon::http_server::api::v0::bucket::PublishRequest;
+
+use crate::common::{wait_for, TestDaemon};
+
+async fn request(
+    method: Method,
+    url: reqwest::Url,
+    if_modified_since: Option<&str>,
+) -> reqwest::Response {
+    let mut request = reqwest::Client::new().request(method, url);
+    if let Some(since) = if_modified_since {
+        request = request.header(header::IF_MODIFIED_SINCE, since);
+    }
+    request.send().await.unwrap()
+}
+
+fn header_of(response: &reqwest::Response, name: header::HeaderName) -> String {
+    response
+        .headers()
+        .get(name)
+        .map(|value| value.to_str().unwrap().to_string())
+        .unwrap_or_default()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_head_and_conditional_requests() {
+    let mut daemon = TestDaemon::start().await;
+    let bucket_id = daemon.create_bucket("files").await;
+    let data = b"some report contents".repeat(100);
+    daemon.add_file(bucket_id, "/report.txt", &data).await;
+    daemon
+        .client
+        .call(PublishRequest {
+            bucket_id,
+            rotate: false,
+            expected_version: None,
+            index: Default::default(),
+        })
+        .await
+        .unwrap();
+    let livez = daemon.gateway_url.join("/_status/livez").unwrap();
+    wait_for("gateway to come up", || {
+        let livez = livez.clone();
+        async move { reqwest::get(livez).await.is_ok() }
+    })
+    .await;
+
+    let url = daemon
+        .gateway_url
+        .join(&format!("/gw/{}/report.txt?download=true", bucket_id))
+        .unwrap();
+    let head = request(Method::HEAD, url.clone(), None).await;
+    assert_eq!(head.status(), StatusCode::OK);
+    assert_eq!(
+        header_of(&head, header::CONTENT_LENGTH),
+        data.len().to_string()
+    );
+    assert_eq!(header_of(&head, header::CONTENT_TYPE), "text/plain");
+    let last_modified = header_of(&head, header::LAST_MODIFIED);
+    assert!(!last_modified.is_empty());
+
+    let get = request(Method::GET, url.clone(), None).await;
+    assert_eq!(header_of(&get, header::LAST_MODIFIED), last_modified);
+    assert_eq!(get.bytes().await.unwrap(), data);
+
+    // The version hasn't changed since the client last fetched it
+    let cached = request(Method::GET, url.clone(), Some(&last_modified)).await;
+    assert_eq!(cached.status(), StatusCode::NOT_MODIFIED);
+    assert!(cached.bytes().await.unwrap().is_empty());
+    let stale = request(Method::GET, url, Some("Sat, 29 Oct 1994 19:43:31 GMT")).await;
+    assert_eq!(stale.status(), StatusCode::OK);
+
+    // The API's binary cat answers HEAD the same way
+    let mut url = daemon.client.base_url().join("/api/v0/bucket/cat").unwrap();
+    url.query_pairs_mut()
+        .append_pair("bucket_id", &bucket_id.to_string())
+        .append_pair("path", "/report.txt");
+    let head = request(Method::HEAD, url.clone(), None).await;
+    assert_eq!(head.status(), StatusCode::OK);
+    assert_eq!(
+        header_of(&head, header::CONTENT_LENGTH),
+        data.len().to_string()
+    );
+    let last_modified = header_of(&head, header::LAST_MODIFIED);
+    let cached = request(Method::GET, url, Some(&last_modified)).await;
+    assert_eq!(cached.status(), StatusCode::NOT_MODIFIED);
+}
@@ -0,0 +1,149 @@
+//! Full data exports of every bucket
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use uuid::Uuid;
+
+use jax_daemon::data_export::{BucketMetadata, ExportIndex, ExportProgress, ExportState};
+use jax_daemon::http_server::api::client::ApiClient;
+use jax_daemon::http_server::api::v0::export::{ExportStartRequest, ExportStatusRequest};
+
+use crate::common::{wait_for, TestDaemon};
+
+async fn export(client: &ApiClient, output_dir: &Path, encrypted: bool) -> ExportProgress {
+    let mut client = client.clone();
+    let started = client
+        .call(ExportStartRequest {
+            output_dir: output_dir.to_path_buf(),
+            encrypted,
+        })
+        .await
+        .unwrap();
+    let export_id = started.export_id;
+    wait_for("the export to finish", || {
+        let mut client = client.clone();
+        async move {
+            client
+                .call(ExportStatusRequest { export_id })
+                .await
+                .is_ok_and(|progress| progress.state != ExportState::Running)
+        }
+    })
+    .await;
+    client
+        .call(ExportStatusRequest { export_id })
+        .await
+        .unwrap()
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> T {
+    serde_json::from_slice(&fs::read(path).unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn test_export_all_decrypted() {
+    let mut daemon = TestDaemon::start().await;
+    let photos = daemon.create_bucket("photos").await;
+    daemon.add_file(photos, "/2026/beach.jpg", b"sand").await;
+    let notes = daemon.create_bucket("notes").await;
+    let head = daemon.add_file(notes, "/todo.txt", b"export").await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("export");
+    let progress = export(&daemon.client, &output, false).await;
+    assert_eq!(
+        progress.state,
+        ExportState::Finished,
+        "{:?}",
+        progress.error
+    );
+    assert_eq!(progress.buckets_total, 2);
+    assert_eq!(progress.buckets_done, 2);
+    assert!(progress.skipped.is_empty());
+    assert!(progress.files_written >= 2);
+
+    let index: ExportIndex = read_json(&output.join("export.json"));
+    assert_eq!(index.node_id, daemon.node_id());
+    assert!(!index.encrypted);
+    assert_eq!(index.buckets.len(), 2);
+
+    let bucket_dir = output.join("buckets").join(photos.to_string());
+    assert_eq!(
+        fs::read(bucket_dir.join("files/2026/beach.jpg")).unwrap(),
+        b"sand"
+    );
+    let bucket_dir = output.join("buckets").join(notes.to_string());
+    assert_eq!(
+        fs::read(bucket_dir.join("files/todo.txt")).unwrap(),
+        b"export"
+    );
+
+    let metadata: BucketMetadata = read_json(&bucket_dir.join("bucket.json"));
+    assert_eq!(metadata.name, "notes");
+    assert_eq!(metadata.version, head.hash().to_string());
+    assert_eq!(metadata.history.len() as u64, metadata.height + 1);
+    assert_eq!(metadata.role.as_deref(), Some("owner"));
+    assert!(metadata
+        .shares
+        .iter()
+        .any(|share| share.public_key == daemon.node_id() && share.role == "owner"));
+}
+
+#[tokio::test]
+async fn test_export_all_encrypted() {
+    let mut daemon = TestDaemon::start().await;
+    let bucket_id = daemon.create_bucket("secrets").await;
+    let head = daemon.add_file(bucket_id, "/key.txt", b"plaintext").await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let progress = export(&daemon.client, dir.path(), true).await;
+    assert_eq!(
+        progress.state,
+        ExportState::Finished,
+        "{:?}",
+        progress.error
+    );
+
+    let bucket_dir = dir.path().join("buckets").join(bucket_id.to_string());
+    assert!(!bucket_dir.join("files").exists());
+    let blobs: Vec<_> = fs::read_dir(bucket_dir.join("blobs"))
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    assert!(blobs.contains(&head.hash().to_string()));
+    // Nothing is written in the clear
+    for blob in &blobs {
+        let data = fs::read(bucket_dir.join("blobs").join(blob)).unwrap();
+        assert!(!data.windows(9).any(|window| window == b"plaintext"));
+    }
+    let metadata: BucketMetadata = read_json(&bucket_dir.join("bucket.json"));
+    assert!(metadata.missing_blobs.is_empty());
+}
+
+#[tokio::test]
+async fn test_export_all_rejects_bad_output() {
+    let mut daemon = TestDaemon::start().await;
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("existing.txt"), b"data").unwrap();
+
+    for output_dir in [dir.path().to_path_buf(), "relative/export".into()] {
+        assert!(daemon
+            .client
+            .call(ExportStartRequest {
+                output_dir,
+                encrypted: false,
+            })
+            .await
+            .is_err());
+    }
+    assert!(daemon
+        .client
+        .call(ExportStatusRequest {
+            export_id: Uuid::new_v4(),
+        })
+        .await
+        .is_err());
+}
@@ -0,0 +1,76 @@
+//! At-rest encryption of the database, run with `--features sqlcipher`
+#![cfg(feature = "sqlcipher")]
+
+use std::path::Path;
+
+use common::prelude::SecretKey;
+use jax_daemon::{Database, DatabaseKey, DatabaseSetupError};
+use url::Url;
+
+fn url(path: &Path) -> Url {
+    Url::parse(&format!("sqlite://{}", path.display())).unwrap()
+}
+
+fn header(path: &Path) -> Vec<u8> {
+    std::fs::read(path).unwrap()[..16].to_vec()
+}
+
+async fn peer_count(database: &Database) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM known_peers")
+        .fetch_one(&**database)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_plaintext_database_is_encrypted_in_place() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("db.sqlite");
+    let key = DatabaseKey::derive(&SecretKey::generate());
+
+    let database = Database::connect(&url(&path), None).await.unwrap();
+    sqlx::query(
+        "INSERT INTO known_peers (node_id, relay_url) VALUES ('peer', 'https://secret.example')",
+    )
+    .execute(&*database)
+    .await
+    .unwrap();
+    database.close().await;
+    assert_eq!(header(&path), b"SQLite format 3\0");
+
+    // Opening with a key encrypts what is there
+    let database = Database::connect(&url(&path), Some(&key)).await.unwrap();
+    assert_eq!(peer_count(&database).await, 1);
+    database.close().await;
+    assert_ne!(header(&path), b"SQLite format 3\0");
+    let contents = std::fs::read(&path).unwrap();
+    assert!(!contents
+        .windows(b"secret.example".len())
+        .any(|window| window == b"secret.example"));
+
+    let reopened = Database::connect(&url(&path), Some(&key)).await.unwrap();
+    assert_eq!(peer_count(&reopened).await, 1);
+    reopened.close().await;
+
+    // Without the key, or with another node's, it can't be opened
+    assert!(matches!(
+        Database::connect(&url(&path), None).await,
+        Err(DatabaseSetupError::Encrypted)
+    ));
+    let other = DatabaseKey::derive(&SecretKey::generate());
+    assert!(Database::connect(&url(&path), Some(&other)).await.is_err());
+}
+
+#[tokio::test]
+async fn test_new_database_is_encrypted() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("db.sqlite");
+    // `jax init` creates the file empty
+    std::fs::write(&path, b"").unwrap();
+    let key = DatabaseKey::derive(&SecretKey::generate());
+
+    let database = Database::connect(&url(&path), Some(&key)).await.unwrap();
+    assert_eq!(peer_count(&database).await, 0);
+    database.close().await;
+    assert_ne!(header(&path), b"SQLite format 3\0");
+}
@@ -0,0 +1,267 @@
+//! Deploy keys: signed writes to a bucket from CI through the gateway
+
+mod common;
+
+use reqwest::{Method, StatusCode};
+use uuid::Uuid;
+
+use jax_daemon::deploy::{signed_headers, DeployResponse, SIGNATURE_HEADER};
+use jax_daemon::http_server::api::v0::audit::AuditLsRequest;
+use jax_daemon::http_server::api::v0::bucket::deploy_key::{
+    DeployKeyCreateRequest, DeployKeyListRequest, DeployKeyRevokeRequest,
+};
+
+use crate::common::{wait_for, TestDaemon};
+
+/// Send a deploy request signed with a key, optionally tampering with it
+async fn deploy(
+    daemon: &TestDaemon,
+    method: Method,
+    (key_id, secret): (Uuid, &str),
+    bucket_id: Uuid,
+    path: &str,
+    body: &[u8],
+    tamper: impl FnOnce(&mut Vec<(&'static str, String)>),
+) -> reqwest::Response {
+    let mut headers = signed_headers(&key_id, secret, method.as_str(), &bucket_id, path, body);
+    tamper(&mut headers);
+    let url = daemon
+        .gateway_url
+        .join(&format!("/deploy/{}{}", bucket_id, path))
+        .unwrap();
+    let mut request = reqwest::Client::new()
+        .request(method, url)
+        .body(body.to_vec());
+    for (name, value) in headers {
+        request = request.header(name, value);
+    }
+    request.send().await.unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_deploy_key_writes_under_its_prefix() {
+    let mut daemon = TestDaemon::start().await;
+    let bucket_id = daemon.create_bucket("site").await;
+    let gateway = daemon.gateway_url.join("/_status/livez").unwrap();
+    wait_for("gateway to come up", || {
+        let gateway = gateway.clone();
+        async move { reqwest::get(gateway).await.is_ok() }
+    })
+    .await;
+
+    let created = daemon
+        .client
+        .call(DeployKeyCreateRequest {
+            bucket_id,
+            path_prefix: "/site/".to_string(),
+            name: Some("ci".to_string()),
+        })
+        .await
+        .unwrap();
+    assert_eq!(created.key.path_prefix, "/site");
+    let key = (created.key.key_id, created.secret.as_str());
+
+    let response = deploy(
+        &daemon,
+        Method::PUT,
+        key,
+        bucket_id,
+        "/site/index.html",
+        b"<h1>v1</h1>",
+        |_| {},
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let written: DeployResponse = response.json().await.unwrap();
+    assert_eq!(written.path, "/site/index.html");
+    assert_eq!(
+        daemon.cat(bucket_id, "/site/index.html").await.unwrap(),
+        b"<h1>v1</h1>"
+    );
+
+    // Writing again replaces the file
+    let response = deploy(
+        &daemon,
+        Method::PUT,
+        key,
+        bucket_id,
+        "/site/index.html",
+        b"<h1>v2</h1>",
+        |_| {},
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        daemon.cat(bucket_id, "/site/index.html").await.unwrap(),
+        b"<h1>v2</h1>"
+    );
+
+    // The same signed request is accepted only once
+    let signed = signed_headers(
+        &key.0,
+        key.1,
+        "PUT",
+        &bucket_id,
+        "/site/index.html",
+        b"<h1>v3</h1>",
+    );
+    for expected in [StatusCode::OK, StatusCode::UNAUTHORIZED] {
+        let response = deploy(
+            &daemon,
+            Method::PUT,
+            key,
+            bucket_id,
+            "/site/index.html",
+            b"<h1>v3</h1>",
+            |headers| *headers = signed.clone(),
+        )
+        .await;
+        assert_eq!(response.status(), expected);
+    }
+
+    // Outside the prefix
+    let response = deploy(
+        &daemon,
+        Method::PUT,
+        key,
+        bucket_id,
+        "/secrets.txt",
+        b"x",
+        |_| {},
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // Signed with the wrong secret
+    let response = deploy(
+        &daemon,
+        Method::PUT,
+        (key.0, "not the secret"),
+        bucket_id,
+        "/site/a.html",
+        b"x",
+        |_| {},
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Signature of a different request
+    let response = deploy(
+        &daemon,
+        Method::PUT,
+        key,
+        bucket_id,
+        "/site/a.html",
+        b"x",
+        |headers| {
+            let other = signed_headers(&key.0, key.1, "PUT", &bucket_id, "/site/b.html", b"x");
+            let signature = other
+                .into_iter()
+                .find(|(name, _)| *name == SIGNATURE_HEADER);
+            headers.retain(|(name, _)| *name != SIGNATURE_HEADER);
+            headers.push(signature.unwrap());
+        },
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Body that doesn't match the signed hash
+    let url = daemon
+        .gateway_url
+        .join(&format!("/deploy/{}/site/a.html", bucket_id))
+        .unwrap();
+    let mut request = reqwest::Client::new().put(url).body("tampered");
+    for (name, value) in signed_headers(&key.0, key.1, "PUT", &bucket_id, "/site/a.html", b"x") {
+        request = request.header(name, value);
+    }
+    assert_eq!(
+        request.send().await.unwrap().status(),
+        StatusCode::BAD_REQUEST
+    );
+    assert!(daemon.cat(bucket_id, "/site/a.html").await.is_err());
+
+    let response = deploy(
+        &daemon,
+        Method::DELETE,
+        key,
+        bucket_id,
+        "/site/index.html",
+        b"",
+        |_| {},
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(daemon.cat(bucket_id, "/site/index.html").await.is_err());
+
+    // Writes are attributed to the key
+    let entries = daemon
+        .client
+        .call(AuditLsRequest {
+            bucket_id: Some(bucket_id),
+            ..AuditLsRequest::default()
+        })
+        .await
+        .unwrap()
+        .entries;
+    let actor = format!("deploy:{}", key.0);
+    let operations: Vec<_> = entries
+        .iter()
+        .filter(|entry| entry.actor == actor)
+        .map(|entry| entry.operation.as_str())
+        .collect();
+    assert_eq!(
+        operations,
+        [
+            "bucket.delete",
+            "bucket.update",
+            "bucket.update",
+            "bucket.add"
+        ]
+    );
+
+    let keys = daemon
+        .client
+        .call(DeployKeyListRequest {
+            bucket_id: Some(bucket_id),
+        })
+        .await
+        .unwrap()
+        .keys;
+    assert_eq!(keys.len(), 1);
+    assert!(keys[0].last_used_at.is_some());
+
+    // Revoked keys are refused
+    daemon
+        .client
+        .call(DeployKeyRevokeRequest { key_id: key.0 })
+        .await
+        .unwrap();
+    let response = deploy(
+        &daemon,
+        Method::PUT,
+        key,
+        bucket_id,
+        "/site/index.html",
+        b"<h1>v3</h1>",
+        |_| {},
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    daemon.stop().await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_deploy_key_needs_existing_bucket() {
+    let mut daemon = TestDaemon::start().await;
+    let error = daemon
+        .client
+        .call(DeployKeyCreateRequest {
+            bucket_id: Uuid::new_v4(),
+            path_prefix: "/".to_string(),
+            name: None,
+        })
+        .await;
+    assert!(error.is_err());
+    daemon.stop().await;
+}
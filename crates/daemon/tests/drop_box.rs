@@ -0,0 +1,194 @@
+//! Drop links: anonymous write-only uploads through the gateway, held for
+//! review
+
+mod common;
+
+use axum::routing::post;
+use axum::{Form, Json, Router};
+use reqwest::multipart::{Form as Multipart, Part};
+use reqwest::StatusCode;
+use serde_json::json;
+
+use jax_daemon::drop_box::DropReceipt;
+use jax_daemon::http_server::api::v0::bucket::drop_link::{
+    DropApproveRequest, DropLinkCreateRequest, DropLinkRevokeRequest, DropPendingRequest,
+    DropRejectRequest,
+};
+
+use crate::common::{wait_for, TestDaemon};
+
+fn create_request(bucket_id: uuid::Uuid) -> DropLinkCreateRequest {
+    DropLinkCreateRequest {
+        bucket_id,
+        directory: "/inbox/".to_string(),
+        name: Some("applications".to_string()),
+        max_size: Some(16),
+        allowed_types: Some("text/*, application/pdf".to_string()),
+        captcha_verify_url: None,
+        captcha_secret: None,
+        expires_in_secs: None,
+    }
+}
+
+async fn upload(
+    daemon: &TestDaemon,
+    url_path: &str,
+    files: &[(&str, &[u8])],
+    captcha_token: Option<&str>,
+) -> reqwest::Response {
+    let mut form = Multipart::new();
+    for (name, data) in files {
+        form = form.part(
+            "file",
+            Part::bytes(data.to_vec()).file_name(name.to_string()),
+        );
+    }
+    if let Some(token) = captcha_token {
+        form = form.text("captcha_token", token.to_string());
+    }
+    reqwest::Client::new()
+        .post(daemon.gateway_url.join(url_path).unwrap())
+        .multipart(form)
+        .send()
+        .await
+        .unwrap()
+}
+
+async fn wait_for_gateway(daemon: &TestDaemon) {
+    let livez = daemon.gateway_url.join("/_status/livez").unwrap();
+    wait_for("gateway to come up", || {
+        let livez = livez.clone();
+        async move { reqwest::get(livez).await.is_ok() }
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_drop_link_uploads_wait_for_review() {
+    let mut daemon = TestDaemon::start().await;
+    let bucket_id = daemon.create_bucket("hiring").await;
+    daemon
+        .add_file(bucket_id, "/inbox/cv.txt", b"already here")
+        .await;
+    wait_for_gateway(&daemon).await;
+
+    let created = daemon.client.call(create_request(bucket_id)).await.unwrap();
+    assert_eq!(created.link.directory, "/inbox");
+    let url_path = created.link.url_path.clone();
+
+    let response = upload(&daemon, &url_path, &[("cv.txt", b"my cv")], None).await;
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    let receipt: DropReceipt = response.json().await.unwrap();
+    assert_eq!(receipt.queued.len(), 1);
+    assert_eq!(receipt.queued[0].size, 5);
+    // Nothing reaches the bucket before it's approved
+    assert_eq!(
+        daemon.cat(bucket_id, "/inbox/cv.txt").await.unwrap(),
+        b"already here"
+    );
+
+    // Limits are enforced, and the link gives no read access
+    let response = upload(&daemon, &url_path, &[("setup.exe", b"MZ")], None).await;
+    assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    let response = upload(&daemon, &url_path, &[("big.txt", &[b'a'; 17])], None).await;
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    let response = upload(&daemon, "/drop/not-a-token", &[("cv.txt", b"x")], None).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = reqwest::get(daemon.gateway_url.join(&url_path).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+    let response = upload(&daemon, &url_path, &[("spam.txt", b"buy now")], None).await;
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    let pending = daemon
+        .client
+        .call(DropPendingRequest {
+            bucket_id: Some(bucket_id),
+        })
+        .await
+        .unwrap()
+        .uploads;
+    assert_eq!(pending.len(), 2);
+    assert_eq!(pending[0].file_name, "cv.txt");
+    assert_eq!(pending[1].file_name, "spam.txt");
+
+    // Approved uploads never replace existing files
+    let approved = daemon
+        .client
+        .call(DropApproveRequest {
+            upload_id: pending[0].upload_id,
+        })
+        .await
+        .unwrap();
+    assert_eq!(approved.path, "/inbox/cv (2).txt");
+    assert_eq!(
+        daemon.cat(bucket_id, "/inbox/cv (2).txt").await.unwrap(),
+        b"my cv"
+    );
+    daemon
+        .client
+        .call(DropRejectRequest {
+            upload_id: pending[1].upload_id,
+        })
+        .await
+        .unwrap();
+    assert!(daemon.cat(bucket_id, "/inbox/spam.txt").await.is_err());
+    let pending = daemon
+        .client
+        .call(DropPendingRequest::default())
+        .await
+        .unwrap()
+        .uploads;
+    assert!(pending.is_empty());
+
+    // Revoked links stop accepting uploads
+    daemon
+        .client
+        .call(DropLinkRevokeRequest {
+            link_id: created.link.link_id,
+        })
+        .await
+        .unwrap();
+    let response = upload(&daemon, &url_path, &[("late.txt", b"late")], None).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_drop_link_captcha() {
+    // A stand-in captcha provider accepting the token "human"
+    let provider = Router::new().route(
+        "/siteverify",
+        post(|Form(form): Form<Vec<(String, String)>>| async move {
+            let passed = form.contains(&("secret".to_string(), "site-secret".to_string()))
+                && form.contains(&("response".to_string(), "human".to_string()));
+            Json(json!({ "success": passed }))
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let verify_url = format!("http://{}/siteverify", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, provider).await });
+
+    let mut daemon = TestDaemon::start().await;
+    let bucket_id = daemon.create_bucket("contest").await;
+    wait_for_gateway(&daemon).await;
+    let created = daemon
+        .client
+        .call(DropLinkCreateRequest {
+            captcha_verify_url: Some(verify_url),
+            captcha_secret: Some("site-secret".to_string()),
+            ..create_request(bucket_id)
+        })
+        .await
+        .unwrap();
+    assert!(created.link.captcha);
+    let url_path = created.link.url_path;
+
+    let entry: &[(&str, &[u8])] = &[("entry.txt", b"my entry")];
+    let response = upload(&daemon, &url_path, entry, None).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = upload(&daemon, &url_path, entry, Some("robot")).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = upload(&daemon, &url_path, entry, Some("human")).await;
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+}
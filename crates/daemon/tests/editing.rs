@@ -0,0 +1,119 @@
+//! "Someone is editing" marks shared between owners of a bucket
+
+mod common;
+
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::editing::{
+    EditingClearRequest, EditingListRequest, EditingMarkRequest,
+};
+use jax_daemon::http_server::api::v0::bucket::ls::LsRequest;
+use jax_daemon::http_server::api::v0::bucket::share::ShareRole;
+
+use crate::common::{assert_converged, start_daemons, TestDaemon};
+
+async fn editors(daemon: &mut TestDaemon, bucket_id: uuid::Uuid, path: &str) -> Vec<String> {
+    daemon
+        .client
+        .call(LsRequest {
+            bucket_id,
+            path: None,
+            deep: None,
+            min_version: None,
+            starred: false,
+            filter: None,
+            sort: None,
+            page: Default::default(),
+        })
+        .await
+        .unwrap()
+        .items
+        .into_iter()
+        .find(|item| item.path == path)
+        .unwrap()
+        .editing
+        .into_iter()
+        .map(|mark| mark.editor)
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_owners_see_what_the_others_edit() {
+    let mut daemons = start_daemons(2).await;
+
+    let bucket_id = daemons[0].create_bucket("office").await;
+    daemons[0]
+        .add_file(bucket_id, "/report.docx", b"draft")
+        .await;
+    let (alice, bob) = daemons.split_at_mut(1);
+    alice[0].share(bucket_id, &bob[0], ShareRole::Owner).await;
+    assert_converged(&mut daemons, bucket_id).await;
+    daemons[1]
+        .wait_for_file(bucket_id, "/report.docx", b"draft")
+        .await;
+
+    // Alice marks the report; Bob sees it in ls
+    let mark = daemons[0]
+        .client
+        .call(EditingMarkRequest {
+            bucket_id,
+            path: "/report.docx".to_string(),
+            ttl_secs: None,
+        })
+        .await
+        .unwrap();
+    let alice_id = daemons[0].node_id();
+    assert_eq!(mark.editor, alice_id);
+    assert_eq!(
+        editors(&mut daemons[1], bucket_id, "/report.docx").await,
+        vec![alice_id.clone()]
+    );
+
+    // Marks don't conflict: Bob can mark it too
+    daemons[1]
+        .client
+        .call(EditingMarkRequest {
+            bucket_id,
+            path: "/report.docx".to_string(),
+            ttl_secs: None,
+        })
+        .await
+        .unwrap();
+    let listed = daemons[0]
+        .client
+        .call(EditingListRequest { bucket_id })
+        .await
+        .unwrap();
+    assert_eq!(listed.editing.len(), 2);
+
+    // Once Alice is done, Bob only sees his own mark
+    let cleared = daemons[0]
+        .client
+        .call(EditingClearRequest {
+            bucket_id,
+            path: "/report.docx".to_string(),
+        })
+        .await
+        .unwrap();
+    assert!(cleared.cleared);
+    assert_eq!(
+        editors(&mut daemons[1], bucket_id, "/report.docx").await,
+        vec![daemons[1].node_id()]
+    );
+
+    let invalid = daemons[0]
+        .client
+        .call(EditingMarkRequest {
+            bucket_id,
+            path: "report.docx".to_string(),
+            ttl_secs: None,
+        })
+        .await;
+    assert!(matches!(
+        invalid,
+        Err(ApiError::HttpStatus(status, _)) if status == reqwest::StatusCode::BAD_REQUEST
+    ));
+
+    for daemon in daemons {
+        daemon.stop().await;
+    }
+}
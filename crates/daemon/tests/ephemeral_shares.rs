@@ -0,0 +1,138 @@
+//! Sharing a bucket with a temporary identity between daemons
+
+mod common;
+
+use std::time::Duration;
+
+use jax_daemon::http_server::api::v0::bucket::claim::ClaimRequest;
+use jax_daemon::http_server::api::v0::bucket::share_ephemeral::ShareEphemeralRequest;
+use jax_daemon::{ephemeral, ServiceState};
+
+use ::common::bucket_log::BucketLogProvider;
+use ::common::crypto::PublicKey;
+use ::common::mount::Mount;
+
+use crate::common::{start_daemons, wait_for};
+
+/// Whether the head of a bucket on a daemon still holds the temporary
+/// identity's share
+async fn holds_share(state: &ServiceState, bucket_id: uuid::Uuid, identity: &str) -> bool {
+    let identity = PublicKey::from_hex(identity).unwrap();
+    let (link, _) = state.database().head(bucket_id, None).await.unwrap();
+    Mount::load_manifest(&link, state.peer().blobs())
+        .await
+        .unwrap()
+        .get_ephemeral_share(&identity)
+        .is_some()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_temporary_identity_reads_without_being_recorded() {
+    let mut daemons = start_daemons(2).await;
+
+    let bucket_id = daemons[0].create_bucket("handout").await;
+    daemons[0]
+        .add_file(bucket_id, "/slides.pdf", b"slides")
+        .await;
+    let response = daemons[0]
+        .client
+        .call(ShareEphemeralRequest {
+            bucket_id,
+            ttl_secs: Some(3600),
+            expected_version: None,
+        })
+        .await
+        .unwrap();
+
+    daemons[1]
+        .client
+        .call(ClaimRequest {
+            ticket: response.ticket.clone(),
+        })
+        .await
+        .unwrap();
+    daemons[1]
+        .wait_for_file(bucket_id, "/slides.pdf", b"slides")
+        .await;
+
+    // The bucket knows the temporary identity, not the recipient
+    let bob = PublicKey::from_hex(&daemons[1].node_id()).unwrap();
+    let manifest = daemons[0]
+        .state
+        .peer()
+        .mount(bucket_id)
+        .await
+        .unwrap()
+        .inner()
+        .await
+        .manifest()
+        .clone();
+    assert!(manifest.get_share(&bob).is_none());
+    assert!(manifest.get_subtree_share(&bob).is_none());
+    assert!(holds_share(&daemons[0].state, bucket_id, &response.identity).await);
+
+    // A plain follow ticket doesn't claim anything
+    let follow_ticket = response.ticket.split('#').next().unwrap().to_string();
+    assert!(daemons[1]
+        .client
+        .call(ClaimRequest {
+            ticket: follow_ticket
+        })
+        .await
+        .is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_expired_shares_are_dropped_and_keys_forgotten() {
+    let mut daemons = start_daemons(2).await;
+
+    let bucket_id = daemons[0].create_bucket("handout").await;
+    daemons[0].add_file(bucket_id, "/a.txt", b"a").await;
+    let response = daemons[0]
+        .client
+        .call(ShareEphemeralRequest {
+            bucket_id,
+            ttl_secs: Some(2),
+            expected_version: None,
+        })
+        .await
+        .unwrap();
+    daemons[1]
+        .client
+        .call(ClaimRequest {
+            ticket: response.ticket.clone(),
+        })
+        .await
+        .unwrap();
+    daemons[1].wait_for_file(bucket_id, "/a.txt", b"a").await;
+
+    // Past the expiry, the owner saves a version without the share
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    let owner = &daemons[0].state;
+    ephemeral::sweep(owner.peer(), owner.database(), owner.audit_log()).await;
+    assert!(!holds_share(owner, bucket_id, &response.identity).await);
+
+    // Once the recipient synced it, it forgets the key and stops following
+    let recipient = daemons[1].state.clone();
+    recipient.peer().ping(bucket_id).await.unwrap();
+    wait_for("the version without the share to sync", || {
+        let recipient = recipient.clone();
+        let identity = response.identity.clone();
+        async move { !holds_share(&recipient, bucket_id, &identity).await }
+    })
+    .await;
+    ephemeral::sweep(
+        recipient.peer(),
+        recipient.database(),
+        recipient.audit_log(),
+    )
+    .await;
+    assert!(recipient.peer().following(&bucket_id).is_none());
+    assert!(recipient.peer().ephemeral_key(&bucket_id).is_none());
+    assert!(recipient
+        .database()
+        .list_ephemeral_keys()
+        .await
+        .unwrap()
+        .is_empty());
+}
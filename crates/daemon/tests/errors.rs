@@ -0,0 +1,59 @@
+//! The error envelope of API responses
+
+mod common;
+
+use reqwest::StatusCode;
+
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::error::{ErrorCode, ErrorEnvelope};
+
+use crate::common::TestDaemon;
+
+#[tokio::test]
+async fn test_errors_share_the_envelope() {
+    let mut daemon = TestDaemon::start().await;
+    let bucket_id = daemon.create_bucket("errors").await;
+
+    // Handler errors
+    match daemon.cat(bucket_id, "/missing.txt").await {
+        Err(ApiError::HttpStatus(status, body)) => {
+            assert_eq!(status, StatusCode::NOT_FOUND);
+            assert_eq!(body.code, ErrorCode::NotFound);
+            assert!(body.message.contains("missing.txt"), "{}", body.message);
+            assert!(!body.retryable);
+        }
+        other => panic!("expected a 404, got {:?}", other.map(|_| ())),
+    }
+
+    // Requests rejected before reaching a handler
+    let url = daemon.client.base_url().join("/api/v0/bucket/ls").unwrap();
+    let http = reqwest::Client::new();
+    for (content_type, body, status, code) in [
+        (
+            "application/json",
+            "{",
+            StatusCode::BAD_REQUEST,
+            ErrorCode::InvalidRequest,
+        ),
+        (
+            "text/plain",
+            "{}",
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ErrorCode::UnsupportedMediaType,
+        ),
+    ] {
+        let response = http
+            .post(url.clone())
+            .header("Content-Type", content_type)
+            .body(body)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), status);
+        let envelope: ErrorEnvelope = response.json().await.unwrap();
+        assert_eq!(envelope.error.code, code);
+        assert!(!envelope.error.message.is_empty());
+    }
+
+    daemon.stop().await;
+}
@@ -0,0 +1,169 @@
+//! Recovering bucket access from shards held by trusted peers
+
+mod common;
+
+use ::common::linked_data::Link;
+use ::common::mount::Mount;
+use ::common::peer::BlobsStore;
+use jax_daemon::http_server::api::v0::bucket::share::ShareRole;
+use jax_daemon::http_server::api::v0::escrow::{
+    EscrowApproveRequest, EscrowHeldRequest, EscrowRecoverRequest, EscrowSetupRequest,
+    RecoveryState,
+};
+
+use crate::common::{assert_converged, start_daemons, wait_for, TestDaemon};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_trustees_recover_a_lost_owner() {
+    let mut daemons = start_daemons(4).await;
+    let mut dave = daemons.pop().unwrap();
+    let carol = daemons.pop().unwrap();
+
+    let bucket_id = daemons[0].create_bucket("diary").await;
+    daemons[0]
+        .add_file(bucket_id, "/notes.txt", b"dear diary")
+        .await;
+    let (alice, bob) = daemons.split_at_mut(1);
+    alice[0].share(bucket_id, &bob[0], ShareRole::Mirror).await;
+
+    // Bob and Carol each take a shard, either alone falls short
+    let trustees = vec![daemons[1].node_id(), carol.node_id()];
+    let setup = daemons[0]
+        .client
+        .call(EscrowSetupRequest {
+            bucket_id,
+            trustees: trustees.clone(),
+            threshold: 2,
+        })
+        .await
+        .unwrap();
+    assert_eq!(setup.trustees, trustees);
+    let held = carol
+        .client
+        .clone()
+        .call(EscrowHeldRequest {})
+        .await
+        .unwrap();
+    assert_eq!(held.shards.len(), 1);
+    assert_eq!(held.shards[0].owner, daemons[0].node_id());
+    let head = assert_converged(&mut daemons, bucket_id).await;
+    // Bob is all Dave can get the content from once Alice is gone
+    let blobs = daemons[1].state.peer().blobs().clone();
+    wait_for("Bob to mirror the bucket's content", || {
+        let blobs = blobs.clone();
+        let head = head.clone();
+        async move { holds_pins(&blobs, &head).await }
+    })
+    .await;
+
+    // Alice loses her key; Dave is her new node
+    let alice = daemons.remove(0);
+    let bob = daemons.pop().unwrap();
+    alice.stop().await;
+
+    let recover = EscrowRecoverRequest {
+        bucket_id,
+        trustees: trustees.clone(),
+    };
+    let waiting = dave.client.call(recover.clone()).await.unwrap();
+    assert_eq!(waiting.state, RecoveryState::Waiting);
+    assert_eq!(waiting.pending.len(), 2);
+
+    // Only Bob approves so far: still too few shards
+    approve(&bob, bucket_id, &dave).await;
+    let waiting = dave.client.call(recover.clone()).await.unwrap();
+    assert_eq!(waiting.state, RecoveryState::Waiting);
+    assert_eq!(waiting.released, vec![bob.node_id()]);
+    assert_eq!(waiting.threshold, Some(2));
+
+    approve(&carol, bucket_id, &dave).await;
+    let client = dave.client.clone();
+    wait_for("Dave to recover the bucket", || {
+        let mut client = client.clone();
+        let recover = recover.clone();
+        async move {
+            client
+                .call(recover)
+                .await
+                .is_ok_and(|response| response.state == RecoveryState::Recovered)
+        }
+    })
+    .await;
+
+    dave.wait_for_file(bucket_id, "/notes.txt", b"dear diary")
+        .await;
+    // Dave writes as an owner, and Bob takes his versions
+    let head = dave.add_file(bucket_id, "/after.txt", b"recovered").await;
+    let mut synced = [bob, dave];
+    assert_eq!(assert_converged(&mut synced, bucket_id).await, head);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_unapproved_requests_get_nothing() {
+    let mut daemons = start_daemons(3).await;
+    let bucket_id = daemons[0].create_bucket("vault").await;
+    let trustee = daemons[1].node_id();
+    daemons[0]
+        .client
+        .call(EscrowSetupRequest {
+            bucket_id,
+            trustees: vec![trustee.clone()],
+            threshold: 1,
+        })
+        .await
+        .unwrap();
+
+    let response = daemons[2]
+        .client
+        .call(EscrowRecoverRequest {
+            bucket_id,
+            trustees: vec![trustee],
+        })
+        .await
+        .unwrap();
+    assert_eq!(response.state, RecoveryState::Waiting);
+    assert!(response.released.is_empty());
+
+    // Approving a peer that never asked fails
+    let stranger = daemons[0].node_id();
+    let result = daemons[1]
+        .client
+        .call(EscrowApproveRequest {
+            bucket_id,
+            requester: stranger,
+        })
+        .await;
+    assert!(result.is_err());
+}
+
+async fn approve(trustee: &TestDaemon, bucket_id: uuid::Uuid, requester: &TestDaemon) {
+    trustee
+        .client
+        .clone()
+        .call(EscrowApproveRequest {
+            bucket_id,
+            requester: requester.node_id(),
+        })
+        .await
+        .unwrap();
+}
+
+/// Whether every blob a version pins is in `blobs`
+async fn holds_pins(blobs: &BlobsStore, version: &Link) -> bool {
+    let Ok(manifest) = Mount::load_manifest(version, blobs).await else {
+        return false;
+    };
+    let list = manifest.pins().hash();
+    if !blobs.stat(&list).await.unwrap_or(false) {
+        return false;
+    }
+    let Ok(pinned) = blobs.read_hash_list(list).await else {
+        return false;
+    };
+    for hash in pinned {
+        if !blobs.stat(&hash).await.unwrap_or(false) {
+            return false;
+        }
+    }
+    true
+}
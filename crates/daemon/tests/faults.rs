@@ -0,0 +1,111 @@
+//! Sync scenarios under injected faults: partitions, lost messages and
+//! transfers that break off
+
+mod common;
+
+use std::time::Duration;
+
+use jax_daemon::http_server::api::v0::bucket::share::ShareRole;
+
+use crate::common::{assert_converged, start_daemons, TestDaemon};
+
+/// Start two daemons sharing a bucket with a file both can read
+async fn shared_pair() -> (Vec<TestDaemon>, uuid::Uuid) {
+    let mut daemons = start_daemons(2).await;
+    let bucket_id = daemons[0].create_bucket("shared").await;
+    daemons[0].add_file(bucket_id, "/a.txt", b"first").await;
+    let (alice, bob) = daemons.split_at_mut(1);
+    alice[0].share(bucket_id, &bob[0], ShareRole::Owner).await;
+    assert_converged(&mut daemons, bucket_id).await;
+    daemons[1]
+        .wait_for_file(bucket_id, "/a.txt", b"first")
+        .await;
+    (daemons, bucket_id)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_partition_then_heal() {
+    let (mut daemons, bucket_id) = shared_pair().await;
+    let alice = daemons[0].state.peer().secret().public();
+    let bob = daemons[1].state.peer().secret().public();
+
+    daemons[0].state.peer().faults().partition(bob);
+    daemons[1].state.peer().faults().partition(alice);
+    let head = daemons[0].add_file(bucket_id, "/b.txt", b"second").await;
+
+    // Several sync intervals pass without bob seeing the edit
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    assert_ne!(daemons[1].head(bucket_id).await, Some(head.clone()));
+    assert!(daemons[0].state.peer().faults().injected() > 0);
+
+    daemons[0].state.peer().faults().heal(&bob);
+    daemons[1].state.peer().faults().heal(&alice);
+    assert_eq!(assert_converged(&mut daemons, bucket_id).await, head);
+    daemons[1]
+        .wait_for_file(bucket_id, "/b.txt", b"second")
+        .await;
+
+    for daemon in daemons {
+        daemon.stop().await;
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_read_waits_for_min_version() {
+    let (mut daemons, bucket_id) = shared_pair().await;
+    let alice = daemons[0].state.peer().secret().public();
+    let bob = daemons[1].state.peer().secret().public();
+
+    daemons[0].state.peer().faults().partition(bob);
+    daemons[1].state.peer().faults().partition(alice);
+    let head = daemons[0].add_file(bucket_id, "/b.txt", b"second").await;
+
+    // Bob can't serve a version he hasn't got, however long he waits
+    assert!(daemons[1]
+        .cat_at_least(bucket_id, "/a.txt", &head)
+        .await
+        .is_err());
+
+    daemons[0].state.peer().faults().heal(&bob);
+    daemons[1].state.peer().faults().heal(&alice);
+    assert_converged(&mut daemons, bucket_id).await;
+    daemons[1]
+        .wait_for_file(bucket_id, "/b.txt", b"second")
+        .await;
+    assert_eq!(
+        daemons[1]
+            .cat_at_least(bucket_id, "/b.txt", &head)
+            .await
+            .unwrap(),
+        b"second"
+    );
+
+    for daemon in daemons {
+        daemon.stop().await;
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_lost_messages_and_broken_transfers_recover() {
+    let (mut daemons, bucket_id) = shared_pair().await;
+    let faults = daemons[0].state.peer().faults().clone();
+    let bob_faults = daemons[1].state.peer().faults().clone();
+
+    // Lose most announcements, break off bob's first downloads and crash
+    // his first sync job; retries on later intervals must still get there
+    faults.seed(42);
+    faults.lose_messages("Ping", 0.75);
+    bob_faults.fail_downloads(2);
+    bob_faults.fail_jobs("sync_bucket", 1);
+    daemons[0].add_file(bucket_id, "/c.txt", b"third").await;
+
+    assert_converged(&mut daemons, bucket_id).await;
+    daemons[1]
+        .wait_for_file(bucket_id, "/c.txt", b"third")
+        .await;
+    assert!(bob_faults.injected() > 0);
+
+    for daemon in daemons {
+        daemon.stop().await;
+    }
+}
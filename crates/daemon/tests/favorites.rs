@@ -0,0 +1,175 @@
+//! Favorites: starred buckets and paths
+
+mod common;
+
+use jax_daemon::http_server::api::v0::bucket::ls::LsRequest;
+use jax_daemon::http_server::api::v0::bucket::ListRequest;
+use jax_daemon::http_server::api::v0::favorite::{
+    FavoriteAddRequest, FavoriteListRequest, FavoriteRemoveRequest,
+};
+
+use crate::common::TestDaemon;
+
+fn ls(bucket_id: uuid::Uuid, path: &str, deep: bool, starred: bool) -> LsRequest {
+    LsRequest {
+        bucket_id,
+        path: Some(path.to_string()),
+        deep: Some(deep),
+        min_version: None,
+        starred,
+        filter: None,
+        sort: None,
+        page: Default::default(),
+    }
+}
+
+#[tokio::test]
+async fn test_starred_buckets_are_flagged_and_filtered() {
+    let mut daemon = TestDaemon::start().await;
+    let docs = daemon.create_bucket("docs").await;
+    let photos = daemon.create_bucket("photos").await;
+
+    let added = daemon
+        .client
+        .call(FavoriteAddRequest {
+            bucket_id: docs,
+            path: None,
+        })
+        .await
+        .unwrap();
+    assert!(added.added);
+    // Starring twice is a no-op
+    let again = daemon
+        .client
+        .call(FavoriteAddRequest {
+            bucket_id: docs,
+            path: Some("/".to_string()),
+        })
+        .await
+        .unwrap();
+    assert!(!again.added);
+    assert_eq!(again.path, None);
+
+    let list = |starred| ListRequest {
+        prefix: None,
+        filter: None,
+        sort: None,
+        page: Default::default(),
+        workspace: None,
+        starred,
+    };
+    let all = daemon.client.call(list(false)).await.unwrap().buckets;
+    assert_eq!(all.len(), 2);
+    for bucket in &all {
+        assert_eq!(bucket.starred, bucket.bucket_id == docs);
+    }
+    let starred = daemon.client.call(list(true)).await.unwrap().buckets;
+    assert_eq!(
+        starred.iter().map(|b| b.bucket_id).collect::<Vec<_>>(),
+        vec![docs]
+    );
+
+    daemon
+        .client
+        .call(FavoriteRemoveRequest {
+            bucket_id: docs,
+            path: None,
+        })
+        .await
+        .unwrap();
+    assert!(daemon
+        .client
+        .call(list(true))
+        .await
+        .unwrap()
+        .buckets
+        .is_empty());
+    // Nothing left to unstar
+    assert!(daemon
+        .client
+        .call(FavoriteRemoveRequest {
+            bucket_id: docs,
+            path: None,
+        })
+        .await
+        .is_err());
+    assert!(daemon
+        .client
+        .call(FavoriteAddRequest {
+            bucket_id: photos,
+            path: Some("/missing.txt".to_string()),
+        })
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_starred_paths_are_flagged_and_filtered() {
+    let mut daemon = TestDaemon::start().await;
+    let bucket_id = daemon.create_bucket("notes").await;
+    daemon
+        .add_file(bucket_id, "/docs/readme.txt", b"hello")
+        .await;
+    daemon
+        .add_file(bucket_id, "/docs/deep/todo.txt", b"todo")
+        .await;
+    daemon.add_file(bucket_id, "/other.txt", b"other").await;
+
+    for path in ["docs/deep/todo.txt", "/other.txt"] {
+        daemon
+            .client
+            .call(FavoriteAddRequest {
+                bucket_id,
+                path: Some(path.to_string()),
+            })
+            .await
+            .unwrap();
+    }
+
+    let favorites = daemon
+        .client
+        .call(FavoriteListRequest {
+            bucket_id: Some(bucket_id),
+        })
+        .await
+        .unwrap()
+        .favorites;
+    let mut paths: Vec<_> = favorites.iter().filter_map(|f| f.path.clone()).collect();
+    paths.sort();
+    assert_eq!(paths, vec!["/docs/deep/todo.txt", "/other.txt"]);
+    assert!(favorites
+        .iter()
+        .all(|f| f.bucket_name.as_deref() == Some("notes")));
+
+    // Deep listings below the root flag paths from the root
+    let listed = daemon
+        .client
+        .call(ls(bucket_id, "/docs", true, false))
+        .await
+        .unwrap()
+        .items;
+    let flagged: Vec<_> = listed
+        .iter()
+        .filter(|item| item.starred)
+        .map(|item| item.name.as_str())
+        .collect();
+    assert_eq!(flagged, vec!["todo.txt"]);
+
+    let starred = daemon
+        .client
+        .call(ls(bucket_id, "/docs", false, true))
+        .await
+        .unwrap()
+        .items;
+    assert_eq!(starred.len(), 1);
+    assert_eq!(starred[0].path, "/docs/deep/todo.txt");
+    assert!(starred[0].starred);
+
+    let everywhere = daemon
+        .client
+        .call(ls(bucket_id, "/", false, true))
+        .await
+        .unwrap()
+        .items;
+    assert_eq!(everywhere.len(), 2);
+}
@@ -0,0 +1,115 @@
+//! Forking a bucket into a new, independent one
+
+mod common;
+
+use jax_daemon::http_server::api::v0::bucket::claim::ClaimRequest;
+use jax_daemon::http_server::api::v0::bucket::share_ephemeral::ShareEphemeralRequest;
+use jax_daemon::http_server::api::v0::bucket::ForkRequest;
+
+use ::common::mount::PrincipalRole;
+
+use crate::common::{start_daemons, TestDaemon};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fork_of_a_shared_template_is_private() {
+    let mut daemons = start_daemons(2).await;
+
+    let template = daemons[0].create_bucket("template").await;
+    daemons[0]
+        .add_file(template, "/src/main.rs", b"fn main() {}")
+        .await;
+    let ticket = daemons[0]
+        .client
+        .call(ShareEphemeralRequest {
+            bucket_id: template,
+            ttl_secs: Some(3600),
+            expected_version: None,
+        })
+        .await
+        .unwrap()
+        .ticket;
+    daemons[1]
+        .client
+        .call(ClaimRequest { ticket })
+        .await
+        .unwrap();
+    daemons[1]
+        .wait_for_file(template, "/src/main.rs", b"fn main() {}")
+        .await;
+
+    let fork = daemons[1]
+        .client
+        .call(ForkRequest {
+            bucket_id: template,
+            name: None,
+        })
+        .await
+        .unwrap();
+    assert_ne!(fork.bucket_id, template);
+    assert_eq!(fork.source_bucket_id, template);
+    assert_eq!(fork.name, "template (fork)");
+    assert_eq!(
+        daemons[1]
+            .cat(fork.bucket_id, "/src/main.rs")
+            .await
+            .unwrap(),
+        b"fn main() {}"
+    );
+
+    // Owned by the forker alone, and writable
+    let manifest = daemons[1]
+        .state
+        .peer()
+        .mount(fork.bucket_id)
+        .await
+        .unwrap()
+        .inner()
+        .await
+        .manifest()
+        .clone();
+    assert_eq!(manifest.shares().len(), 1);
+    let share = manifest.shares().get(&daemons[1].node_id()).unwrap();
+    assert_eq!(*share.role(), PrincipalRole::Owner);
+    daemons[1]
+        .add_file(fork.bucket_id, "/src/lib.rs", b"")
+        .await;
+
+    // The template is untouched, and its owner doesn't know the fork
+    assert!(daemons[0].cat(template, "/src/lib.rs").await.is_err());
+    assert!(daemons[0].head(fork.bucket_id).await.is_none());
+}
+
+#[tokio::test]
+async fn test_fork_names_and_unknown_buckets() {
+    let mut daemon = TestDaemon::start().await;
+    let bucket_id = daemon.create_bucket("notes").await;
+    daemon.add_file(bucket_id, "/a.txt", b"a").await;
+
+    let fork = daemon
+        .client
+        .call(ForkRequest {
+            bucket_id,
+            name: Some("my notes".to_string()),
+        })
+        .await
+        .unwrap();
+    assert_eq!(fork.name, "my notes");
+    assert_eq!(daemon.cat(fork.bucket_id, "/a.txt").await.unwrap(), b"a");
+
+    assert!(daemon
+        .client
+        .call(ForkRequest {
+            bucket_id,
+            name: Some(String::new()),
+        })
+        .await
+        .is_err());
+    assert!(daemon
+        .client
+        .call(ForkRequest {
+            bucket_id: uuid::Uuid::new_v4(),
+            name: None,
+        })
+        .await
+        .is_err());
+}
@@ -0,0 +1,90 @@
+//! Scaled image variants on the gateway
+
+mod common;
+
+use reqwest::StatusCode;
+
+use jax_daemon::http_server::api::v0::bucket::PublishRequest;
+use jax_daemon::image_variants::{png, Image, IMAGE_VARIANTS_DIR};
+
+use crate::common::{wait_for, TestDaemon};
+
+async fn get(daemon: &TestDaemon, path: &str) -> (StatusCode, String, Vec<u8>) {
+    let url = daemon.gateway_url.join(path).unwrap();
+    let response = reqwest::get(url).await.unwrap();
+    let status = response.status();
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .map(|value| value.to_str().unwrap().to_string())
+        .unwrap_or_default();
+    (
+        status,
+        content_type,
+        response.bytes().await.unwrap().to_vec(),
+    )
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_images_are_scaled_and_cached() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let variants_dir = dir.path().join(IMAGE_VARIANTS_DIR);
+    let mut daemon = TestDaemon::start_in(dir).await;
+    let bucket_id = daemon.create_bucket("gallery").await;
+    let photo = png::encode(&Image {
+        width: 64,
+        height: 32,
+        pixels: [200, 100, 50, 255].repeat(64 * 32),
+    });
+    daemon.add_file(bucket_id, "/photo.png", &photo).await;
+    let jpeg = b"\xff\xd8\xff\xe0 not really a jpeg".to_vec();
+    daemon.add_file(bucket_id, "/photo.jpg", &jpeg).await;
+    daemon
+        .client
+        .call(PublishRequest {
+            bucket_id,
+            rotate: false,
+            expected_version: None,
+            index: Default::default(),
+        })
+        .await
+        .unwrap();
+    let livez = daemon.gateway_url.join("/_status/livez").unwrap();
+    wait_for("gateway to come up", || {
+        let livez = livez.clone();
+        async move { reqwest::get(livez).await.is_ok() }
+    })
+    .await;
+
+    let scaled_path = format!("/gw/{}/photo.png?w=16&format=webp", bucket_id);
+    let (status, content_type, body) = get(&daemon, &scaled_path).await;
+    assert_eq!(status, StatusCode::OK);
+    // Scaled images are PNG whatever format was asked for
+    assert_eq!(content_type, "image/png");
+    let scaled = png::decode(&body).unwrap();
+    assert_eq!((scaled.width, scaled.height), (16, 8));
+    assert_eq!(&scaled.pixels[..4], &[200, 100, 50, 255]);
+
+    // The variant is cached by the file's blob and the size asked for
+    let (_, _, again) = get(&daemon, &scaled_path).await;
+    assert_eq!(again, body);
+    let cached = std::fs::read_dir(&variants_dir).unwrap().count();
+    assert_eq!(cached, 1);
+
+    // Images are never scaled up
+    let (_, _, body) = get(&daemon, &format!("/gw/{}/photo.png?h=100", bucket_id)).await;
+    let unscaled = png::decode(&body).unwrap();
+    assert_eq!((unscaled.width, unscaled.height), (64, 32));
+
+    // Formats that can't be decoded are served unchanged
+    let (status, content_type, body) =
+        get(&daemon, &format!("/gw/{}/photo.jpg?w=16", bucket_id)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type, "image/jpeg");
+    assert_eq!(body, jpeg);
+
+    for bad in ["w=0", "h=100000", "format=gif"] {
+        let (status, _, _) = get(&daemon, &format!("/gw/{}/photo.png?{}", bucket_id, bad)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", bad);
+    }
+}
@@ -0,0 +1,79 @@
+//! Rendering text files on the gateway
+
+mod common;
+
+use jax_daemon::http_server::api::v0::bucket::PublishRequest;
+
+use crate::common::{wait_for, TestDaemon};
+
+const HTML: &str = "text/html,application/xhtml+xml;q=0.9,*/*;q=0.8";
+
+async fn get(daemon: &TestDaemon, path: &str, accept: Option<&str>) -> (String, String) {
+    let url = daemon.gateway_url.join(path).unwrap();
+    let mut request = reqwest::Client::new().get(url);
+    if let Some(accept) = accept {
+        request = request.header(reqwest::header::ACCEPT, accept);
+    }
+    let response = request.send().await.unwrap();
+    assert!(response.status().is_success(), "GET {} failed", path);
+    let content_type = response.headers()[reqwest::header::CONTENT_TYPE]
+        .to_str()
+        .unwrap()
+        .to_string();
+    (content_type, response.text().await.unwrap())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_text_files_render_or_serve_plain() {
+    let mut daemon = TestDaemon::start().await;
+    let bucket_id = daemon.create_bucket("docs").await;
+    let readme = "# Notes\n\n<script>alert(1)</script>\n\n[bad](javascript:alert(1))\n";
+    daemon
+        .add_file(bucket_id, "/README.md", readme.as_bytes())
+        .await;
+    daemon
+        .add_file(bucket_id, "/main.rs", b"fn main() {}\n")
+        .await;
+    daemon
+        .client
+        .call(PublishRequest {
+            bucket_id,
+            rotate: false,
+            expected_version: None,
+            index: Default::default(),
+        })
+        .await
+        .unwrap();
+    let livez = daemon.gateway_url.join("/_status/livez").unwrap();
+    wait_for("gateway to come up", || {
+        let livez = livez.clone();
+        async move { reqwest::get(livez).await.is_ok() }
+    })
+    .await;
+
+    // Browsers get sanitized markdown with a link to the plain text
+    let readme_path = format!("/gw/{}/README.md", bucket_id);
+    let (content_type, body) = get(&daemon, &readme_path, Some(HTML)).await;
+    assert!(content_type.starts_with("text/html"));
+    assert!(body.contains("<h1>Notes</h1>"));
+    assert!(!body.contains("<script>"));
+    assert!(!body.contains("javascript:"));
+    assert!(body.contains(r#"href="?render=0""#));
+
+    // Other clients get the text unless they ask for rendering
+    let (content_type, body) = get(&daemon, &readme_path, None).await;
+    assert!(content_type.starts_with("text/plain"));
+    assert_eq!(body, readme);
+    let (content_type, _) = get(&daemon, &format!("{}?render=1", readme_path), None).await;
+    assert!(content_type.starts_with("text/html"));
+    let (content_type, body) = get(&daemon, &format!("{}?render=0", readme_path), Some(HTML)).await;
+    assert!(content_type.starts_with("text/plain"));
+    assert_eq!(body, readme);
+
+    // Code is highlighted in the viewer
+    let code_path = format!("/gw/{}/main.rs", bucket_id);
+    let (_, body) = get(&daemon, &code_path, Some(HTML)).await;
+    assert!(body.contains(r#"<span class="hl-keyword">fn</span>"#));
+    let (_, body) = get(&daemon, &format!("{}?render=false", code_path), Some(HTML)).await;
+    assert_eq!(body, "fn main() {}\n");
+}
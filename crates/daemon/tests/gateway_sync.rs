@@ -0,0 +1,144 @@
+//! Buckets still syncing on the gateway: progress, long-polling and serving
+//! the last fully synced version
+
+mod common;
+
+use std::time::{Duration, Instant};
+
+use reqwest::StatusCode;
+
+use ::common::bucket_log::BucketLogProvider;
+use ::common::mount::Mount;
+use jax_daemon::http_server::api::v0::bucket::PublishRequest;
+use jax_daemon::http_server::api::v0::gateway::policy::GatewayPolicySetRequest;
+
+use crate::common::{wait_for, TestDaemon};
+
+async fn publish(daemon: &mut TestDaemon, bucket_id: uuid::Uuid) {
+    daemon
+        .client
+        .call(PublishRequest {
+            bucket_id,
+            rotate: false,
+            expected_version: None,
+            index: Default::default(),
+        })
+        .await
+        .unwrap();
+}
+
+fn sync_progress(response: &reqwest::Response) -> Option<u8> {
+    response
+        .headers()
+        .get("x-jax-sync-progress")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_syncing_progress_and_wait() {
+    let mut daemon = TestDaemon::start().await;
+    let bucket_id = daemon.create_bucket("site").await;
+    daemon.add_file(bucket_id, "/a.txt", b"one").await;
+    let livez = daemon.gateway_url.join("/_status/livez").unwrap();
+    wait_for("gateway to come up", || {
+        let livez = livez.clone();
+        async move { reqwest::get(livez).await.is_ok() }
+    })
+    .await;
+    let file_url = |query: &str| {
+        daemon
+            .gateway_url
+            .join(&format!("/gw/{}/a.txt?download=true{}", bucket_id, query))
+            .unwrap()
+    };
+
+    // No published version has arrived yet
+    let response = reqwest::Client::new()
+        .get(file_url(""))
+        .header("accept", "application/json")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(sync_progress(&response), Some(0));
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["status"], "syncing");
+
+    // Waiting gives up after the time asked for
+    let started = Instant::now();
+    let response = reqwest::get(file_url("&wait=1")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert!(started.elapsed() >= Duration::from_millis(500));
+
+    // Or answers once the version is here
+    let waiting = tokio::spawn(reqwest::get(file_url("&wait=30")));
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    publish(&mut daemon, bucket_id).await;
+    let response = waiting.await.unwrap().unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.bytes().await.unwrap().as_ref(), b"one");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_serve_stale_while_syncing() {
+    let mut daemon = TestDaemon::start().await;
+    let bucket_id = daemon.create_bucket("site").await;
+    daemon.add_file(bucket_id, "/a.txt", b"one").await;
+    publish(&mut daemon, bucket_id).await;
+    daemon.add_file(bucket_id, "/b.txt", b"two").await;
+    publish(&mut daemon, bucket_id).await;
+    let livez = daemon.gateway_url.join("/_status/livez").unwrap();
+    wait_for("gateway to come up", || {
+        let livez = livez.clone();
+        async move { reqwest::get(livez).await.is_ok() }
+    })
+    .await;
+
+    // The latest version's root directory hasn't arrived
+    let peer = daemon.state.peer();
+    let (latest, _) = peer
+        .logs()
+        .latest_published(bucket_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let manifest = Mount::load_manifest(&latest, peer.blobs()).await.unwrap();
+    peer.blobs()
+        .force_delete(vec![manifest.entry().hash()])
+        .await
+        .unwrap();
+
+    let get = |path: &str| {
+        reqwest::get(
+            daemon
+                .gateway_url
+                .join(&format!("/gw/{}{}?download=true", bucket_id, path))
+                .unwrap(),
+        )
+    };
+    let response = get("/a.txt").await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let progress = sync_progress(&response).unwrap();
+    assert!(progress > 0 && progress < 100, "{}", progress);
+    assert!(response.text().await.unwrap().contains("% fetched"));
+
+    // The operator serves the previous version meanwhile
+    daemon
+        .client
+        .call(GatewayPolicySetRequest {
+            bucket_id,
+            allow: false,
+            no_listings: false,
+            noindex: false,
+            serve_stale: true,
+            daily_bandwidth_bytes: None,
+        })
+        .await
+        .unwrap();
+    let response = get("/a.txt").await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(sync_progress(&response), Some(progress));
+    assert_eq!(response.bytes().await.unwrap().as_ref(), b"one");
+    assert_eq!(get("/b.txt").await.unwrap().status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,128 @@
+//! Zip downloads of directories from the gateway and the API
+
+mod common;
+
+use reqwest::StatusCode;
+
+use jax_daemon::http_server::api::v0::bucket::PublishRequest;
+use jax_daemon::zip_stream::crc32;
+
+use crate::common::{wait_for, TestDaemon};
+
+/// Names and contents of the entries of a stored (uncompressed) archive,
+/// read from its local headers
+fn entries(archive: &[u8]) -> Vec<(String, Vec<u8>)> {
+    let u16_at = |at: usize| u16::from_le_bytes(archive[at..at + 2].try_into().unwrap());
+    let u32_at = |at: usize| u32::from_le_bytes(archive[at..at + 4].try_into().unwrap());
+
+    let mut entries = Vec::new();
+    let mut at = 0;
+    while u32_at(at) == 0x0403_4b50 {
+        assert_eq!(u16_at(at + 8), 0, "entries are stored");
+        let crc = u32_at(at + 14);
+        let size = u32_at(at + 18) as usize;
+        let name_len = u16_at(at + 26) as usize;
+        let extra_len = u16_at(at + 28) as usize;
+        let name = String::from_utf8(archive[at + 30..at + 30 + name_len].to_vec()).unwrap();
+        let data_at = at + 30 + name_len + extra_len;
+        let data = archive[data_at..data_at + size].to_vec();
+        assert_eq!(crc32(&data), crc, "{}", name);
+        entries.push((name, data));
+        at = data_at + size;
+    }
+    // The central directory follows the last entry
+    assert_eq!(u32_at(at), 0x0201_4b50);
+    entries
+}
+
+async fn get(url: reqwest::Url) -> (StatusCode, String, Vec<u8>) {
+    let response = reqwest::get(url).await.unwrap();
+    let status = response.status();
+    let disposition = response
+        .headers()
+        .get(reqwest::header::CONTENT_DISPOSITION)
+        .map(|value| value.to_str().unwrap().to_string())
+        .unwrap_or_default();
+    (
+        status,
+        disposition,
+        response.bytes().await.unwrap().to_vec(),
+    )
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_directories_download_as_zip() {
+    let mut daemon = TestDaemon::start().await;
+    let bucket_id = daemon.create_bucket("site").await;
+    daemon
+        .add_file(bucket_id, "/docs/intro.md", b"# Intro")
+        .await;
+    daemon
+        .add_file(bucket_id, "/docs/guides/setup.txt", b"run it")
+        .await;
+    daemon
+        .add_file(bucket_id, "/index.html", b"<p>hi</p>")
+        .await;
+    daemon
+        .client
+        .call(PublishRequest {
+            bucket_id,
+            rotate: false,
+            expected_version: None,
+            index: Default::default(),
+        })
+        .await
+        .unwrap();
+    let livez = daemon.gateway_url.join("/_status/livez").unwrap();
+    wait_for("gateway to come up", || {
+        let livez = livez.clone();
+        async move { reqwest::get(livez).await.is_ok() }
+    })
+    .await;
+
+    let url = daemon
+        .gateway_url
+        .join(&format!("/gw/{}/docs?download=zip", bucket_id))
+        .unwrap();
+    let (status, disposition, archive) = get(url).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(disposition, "attachment; filename=\"docs.zip\"");
+    assert_eq!(
+        entries(&archive),
+        vec![
+            ("guides/".to_string(), vec![]),
+            ("guides/setup.txt".to_string(), b"run it".to_vec()),
+            ("intro.md".to_string(), b"# Intro".to_vec()),
+        ]
+    );
+
+    // The API zips the head of the bucket, the root by default
+    let mut url = daemon.client.base_url().join("/api/v0/bucket/zip").unwrap();
+    url.query_pairs_mut()
+        .append_pair("bucket_id", &bucket_id.to_string());
+    let (status, disposition, archive) = get(url).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(disposition, "attachment; filename=\"site.zip\"");
+    let names: Vec<String> = entries(&archive)
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            "docs/",
+            "docs/guides/",
+            "docs/guides/setup.txt",
+            "docs/intro.md",
+            "index.html"
+        ]
+    );
+
+    // Files can't be zipped through the API
+    let mut url = daemon.client.base_url().join("/api/v0/bucket/zip").unwrap();
+    url.query_pairs_mut()
+        .append_pair("bucket_id", &bucket_id.to_string())
+        .append_pair("path", "/index.html");
+    let (status, _, _) = get(url).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
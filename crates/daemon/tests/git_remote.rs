@@ -0,0 +1,170 @@
+//! git-remote-jax: pushing to and fetching from repositories in buckets
+
+mod common;
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use tempfile::TempDir;
+
+use crate::common::TestDaemon;
+
+/// Runs git with the helper on its PATH, against one daemon
+struct Git {
+    api_url: String,
+    path: String,
+}
+
+impl Git {
+    fn new(daemon: &TestDaemon) -> Self {
+        let helper = PathBuf::from(env!("CARGO_BIN_EXE_git-remote-jax"));
+        let path = format!(
+            "{}:{}",
+            helper.parent().unwrap().display(),
+            std::env::var("PATH").unwrap_or_default()
+        );
+        Self {
+            api_url: daemon.client.base_url().to_string(),
+            path,
+        }
+    }
+
+    /// Run git in `dir`, returning whether it succeeded and its output
+    async fn try_run(&self, dir: &Path, args: &[&str]) -> (bool, String) {
+        let mut command = Command::new("git");
+        command
+            .args(args)
+            .current_dir(dir)
+            .env("PATH", &self.path)
+            .env("JAX_REMOTE", &self.api_url)
+            .env("GIT_CONFIG_NOSYSTEM", "1")
+            .env("GIT_CONFIG_GLOBAL", "/dev/null")
+            .env("GIT_AUTHOR_NAME", "Test")
+            .env("GIT_AUTHOR_EMAIL", "test@example.com")
+            .env("GIT_COMMITTER_NAME", "Test")
+            .env("GIT_COMMITTER_EMAIL", "test@example.com");
+        let output = tokio::task::spawn_blocking(move || command.output().unwrap())
+            .await
+            .unwrap();
+        let text = format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        (output.status.success(), text)
+    }
+
+    async fn run(&self, dir: &Path, args: &[&str]) -> String {
+        let (success, output) = self.try_run(dir, args).await;
+        assert!(success, "git {} failed: {}", args.join(" "), output);
+        output
+    }
+
+    async fn commit(&self, dir: &Path, file: &str, contents: &str) {
+        std::fs::write(dir.join(file), contents).unwrap();
+        self.run(dir, &["add", file]).await;
+        self.run(dir, &["commit", "-q", "-m", contents]).await;
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_push_clone_and_fetch() {
+    let mut daemon = TestDaemon::start().await;
+    let bucket_id = daemon.create_bucket("repos").await;
+    let git = Git::new(&daemon);
+    let url = format!("jax://{}/team/app.git", bucket_id);
+    let dir = TempDir::new().unwrap();
+
+    let alice = dir.path().join("alice");
+    std::fs::create_dir(&alice).unwrap();
+    git.run(&alice, &["init", "-q", "-b", "main"]).await;
+    git.commit(&alice, "README.md", "hello").await;
+    git.run(&alice, &["remote", "add", "origin", &url]).await;
+    git.run(&alice, &["push", "-q", "-u", "origin", "main"])
+        .await;
+
+    // The repository sits in the bucket as git lays it out
+    let packed_refs = daemon
+        .cat(bucket_id, "/team/app.git/packed-refs")
+        .await
+        .unwrap();
+    assert!(String::from_utf8(packed_refs)
+        .unwrap()
+        .contains("refs/heads/main"));
+    assert_eq!(
+        daemon.cat(bucket_id, "/team/app.git/HEAD").await.unwrap(),
+        b"ref: refs/heads/main\n"
+    );
+
+    // Clones check out the default branch
+    git.run(dir.path(), &["clone", "-q", &url, "bob"]).await;
+    let bob = dir.path().join("bob");
+    assert_eq!(
+        std::fs::read_to_string(bob.join("README.md")).unwrap(),
+        "hello"
+    );
+
+    // Later pushes only need the new objects, and fetches bring them over
+    git.commit(&alice, "README.md", "hello again").await;
+    git.run(&alice, &["push", "-q", "origin", "main"]).await;
+    git.run(&bob, &["pull", "-q", "--ff-only"]).await;
+    assert_eq!(
+        std::fs::read_to_string(bob.join("README.md")).unwrap(),
+        "hello again"
+    );
+    assert_eq!(
+        git.run(&bob, &["rev-parse", "HEAD"]).await,
+        git.run(&alice, &["rev-parse", "HEAD"]).await
+    );
+
+    // Pushes that would drop commits are refused unless forced
+    git.commit(&alice, "a.txt", "alice's change").await;
+    git.run(&alice, &["push", "-q", "origin", "main"]).await;
+    git.commit(&bob, "b.txt", "bob's change").await;
+    let (pushed, output) = git.try_run(&bob, &["push", "origin", "main"]).await;
+    assert!(!pushed, "diverged push went through: {}", output);
+    git.run(&bob, &["pull", "-q", "--no-rebase", "--no-edit"])
+        .await;
+    git.run(&bob, &["push", "-q", "origin", "main"]).await;
+    git.run(&alice, &["pull", "-q", "--ff-only"]).await;
+    assert!(alice.join("b.txt").exists());
+
+    // Tags and other branches, and deleting them
+    git.run(&alice, &["tag", "v1"]).await;
+    git.run(&alice, &["push", "-q", "origin", "v1", "main:feature"])
+        .await;
+    let remote = git.run(&bob, &["ls-remote", "origin"]).await;
+    assert!(remote.contains("refs/tags/v1"));
+    assert!(remote.contains("refs/heads/feature"));
+    git.run(&alice, &["push", "-q", "origin", ":feature"]).await;
+    let remote = git.run(&bob, &["ls-remote", "origin"]).await;
+    assert!(!remote.contains("refs/heads/feature"));
+    assert!(remote.contains("refs/heads/main"));
+
+    daemon.stop().await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_buckets_are_found_by_name() {
+    let mut daemon = TestDaemon::start().await;
+    daemon.create_bucket("named").await;
+    let git = Git::new(&daemon);
+    let dir = TempDir::new().unwrap();
+
+    git.run(dir.path(), &["init", "-q", "-b", "main"]).await;
+    git.commit(dir.path(), "a.txt", "a").await;
+    git.run(dir.path(), &["push", "-q", "jax://named/app.git", "main"])
+        .await;
+    let remote = git
+        .run(dir.path(), &["ls-remote", "jax://named/app.git"])
+        .await;
+    assert!(remote.contains("refs/heads/main"));
+    assert!(remote.contains("HEAD"));
+
+    let (listed, _) = git
+        .try_run(dir.path(), &["ls-remote", "jax://missing/app.git"])
+        .await;
+    assert!(!listed);
+
+    daemon.stop().await;
+}
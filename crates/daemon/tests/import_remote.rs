@@ -0,0 +1,134 @@
+//! Importing existing storage into buckets and re-importing what changed
+
+mod common;
+
+use std::collections::BTreeMap;
+
+use reqwest::StatusCode;
+
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::import_remote::ImportRemoteRequest;
+
+use crate::common::TestDaemon;
+
+fn request(url: &str) -> ImportRemoteRequest {
+    ImportRemoteRequest {
+        url: url.to_string(),
+        name: None,
+        options: BTreeMap::new(),
+        delete: false,
+    }
+}
+
+#[tokio::test]
+async fn test_import_remote_copies_only_changes() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let source = dir.path().join("archive");
+    std::fs::create_dir_all(source.join("photos/2024")).unwrap();
+    std::fs::write(source.join("readme.txt"), b"old notes").unwrap();
+    std::fs::write(source.join("photos/2024/beach.jpg"), b"jpeg bytes").unwrap();
+    let url = format!("file://{}/", source.display());
+    let import_dir = dir.path().to_path_buf();
+    let mut daemon = TestDaemon::start_with(|config| config.import_dirs = vec![import_dir]).await;
+
+    // The first import creates a bucket named after the URL
+    let report = daemon.client.call(request(&url)).await.unwrap();
+    assert!(report.created);
+    assert_eq!(report.name, "archive");
+    assert_eq!(report.objects, 2);
+    assert_eq!(report.imported_files, 2);
+    assert_eq!(report.imported_bytes, 19);
+    let bucket_id = report.bucket_id;
+    assert_eq!(
+        daemon
+            .cat(bucket_id, "/photos/2024/beach.jpg")
+            .await
+            .unwrap(),
+        b"jpeg bytes"
+    );
+    assert_eq!(
+        daemon.cat(bucket_id, "/readme.txt").await.unwrap(),
+        b"old notes"
+    );
+    let first = daemon.head(bucket_id).await.unwrap();
+    assert_eq!(report.version, Some(first.hash().to_string()));
+
+    // Nothing changed, nothing is copied or saved
+    let report = daemon.client.call(request(&url)).await.unwrap();
+    assert!(!report.created);
+    assert_eq!(report.bucket_id, bucket_id);
+    assert_eq!(report.imported_files, 0);
+    assert_eq!(report.version, None);
+    assert_eq!(daemon.head(bucket_id).await.unwrap(), first);
+
+    // Changed and new objects are copied into the same bucket; removed ones
+    //  stay unless asked to go
+    std::fs::write(source.join("readme.txt"), b"new notes!").unwrap();
+    std::fs::write(source.join("photos/2024/hike.jpg"), b"more jpeg").unwrap();
+    std::fs::remove_file(source.join("photos/2024/beach.jpg")).unwrap();
+    let report = daemon.client.call(request(&url)).await.unwrap();
+    assert_eq!(report.bucket_id, bucket_id);
+    assert_eq!(report.objects, 2);
+    assert_eq!(report.imported_files, 2);
+    assert_eq!(report.removed_files, 0);
+    assert_eq!(
+        daemon.cat(bucket_id, "/readme.txt").await.unwrap(),
+        b"new notes!"
+    );
+    assert_eq!(
+        daemon
+            .cat(bucket_id, "/photos/2024/hike.jpg")
+            .await
+            .unwrap(),
+        b"more jpeg"
+    );
+    assert!(daemon
+        .cat(bucket_id, "/photos/2024/beach.jpg")
+        .await
+        .is_ok());
+
+    let mut delete = request(&url);
+    delete.delete = true;
+    let report = daemon.client.call(delete).await.unwrap();
+    assert_eq!(report.imported_files, 0);
+    assert_eq!(report.removed_files, 1);
+    assert!(daemon
+        .cat(bucket_id, "/photos/2024/beach.jpg")
+        .await
+        .is_err());
+    assert_eq!(
+        daemon.cat(bucket_id, "/readme.txt").await.unwrap(),
+        b"new notes!"
+    );
+}
+
+#[tokio::test]
+async fn test_import_remote_rejects_bad_sources() {
+    let mut daemon = TestDaemon::start().await;
+
+    let mut unknown_option = request("s3://bucket/prefix");
+    unknown_option
+        .options
+        .insert("no_such_option".to_string(), "x".to_string());
+    let gcs_without_keys = request("gs://site/www");
+    for bad in [
+        request("ftp://example.com/files"),
+        unknown_option,
+        gcs_without_keys,
+    ] {
+        match daemon.client.call(bad).await {
+            Err(ApiError::HttpStatus(status, _)) => assert_eq!(status, StatusCode::BAD_REQUEST),
+            other => panic!("expected a 400, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    // Local directories outside of the configured import directories, such
+    //  as the node's own, can't be imported
+    let outside = tempfile::TempDir::new().unwrap();
+    std::fs::write(outside.path().join("secret.key"), b"key").unwrap();
+    let outside = format!("file://{}", outside.path().display());
+    match daemon.client.call(request(&outside)).await {
+        Err(ApiError::HttpStatus(status, _)) => assert_eq!(status, StatusCode::FORBIDDEN),
+        other => panic!("expected a 403, got {:?}", other.map(|_| ())),
+    }
+}
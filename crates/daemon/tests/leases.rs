@@ -0,0 +1,87 @@
+//! Advisory write leases between owners of a bucket
+
+mod common;
+
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::lease::{
+    LeaseAcquireRequest, LeaseInfo, LeaseListRequest, LeaseReleaseRequest,
+};
+use jax_daemon::http_server::api::v0::bucket::share::ShareRole;
+use jax_daemon::http_server::error::ErrorCode;
+
+use crate::common::{assert_converged, start_daemons, TestDaemon};
+
+async fn acquire(
+    daemon: &mut TestDaemon,
+    bucket_id: uuid::Uuid,
+    prefix: &str,
+) -> Result<(), LeaseInfo> {
+    let request = LeaseAcquireRequest {
+        bucket_id,
+        prefix: prefix.to_string(),
+        ttl_secs: None,
+    };
+    match daemon.client.call(request).await {
+        Ok(lease) => {
+            assert_eq!(lease.holder, daemon.node_id());
+            Ok(())
+        }
+        Err(ApiError::HttpStatus(status, body)) if status == reqwest::StatusCode::CONFLICT => {
+            assert_eq!(body.code, ErrorCode::Conflict);
+            Err(serde_json::from_value(body.details.unwrap()).unwrap())
+        }
+        Err(e) => panic!("acquiring {} failed: {}", prefix, e),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_owners_serialize_on_leases() {
+    let mut daemons = start_daemons(2).await;
+
+    let bucket_id = daemons[0].create_bucket("leased").await;
+    let (alice, bob) = daemons.split_at_mut(1);
+    alice[0].share(bucket_id, &bob[0], ShareRole::Owner).await;
+    assert_converged(&mut daemons, bucket_id).await;
+
+    // Alice leases /docs; Bob can't lease anything overlapping it
+    acquire(&mut daemons[0], bucket_id, "/docs").await.unwrap();
+    let alice_id = daemons[0].node_id();
+    for prefix in ["/docs/notes.txt", "/"] {
+        let held = acquire(&mut daemons[1], bucket_id, prefix)
+            .await
+            .unwrap_err();
+        assert_eq!(held.holder, alice_id);
+        assert_eq!(held.prefix, "/docs");
+    }
+
+    // Disjoint prefixes don't conflict, and Alice learns of Bob's lease
+    acquire(&mut daemons[1], bucket_id, "/photos")
+        .await
+        .unwrap();
+    let listed = daemons[0]
+        .client
+        .call(LeaseListRequest { bucket_id })
+        .await
+        .unwrap();
+    let mut prefixes: Vec<_> = listed.leases.iter().map(|l| l.prefix.as_str()).collect();
+    prefixes.sort();
+    assert_eq!(prefixes, ["/docs", "/photos"]);
+
+    // Once Alice releases, Bob gets the lease
+    let released = daemons[0]
+        .client
+        .call(LeaseReleaseRequest {
+            bucket_id,
+            prefix: "/docs".to_string(),
+        })
+        .await
+        .unwrap();
+    assert!(released.released);
+    acquire(&mut daemons[1], bucket_id, "/docs/notes.txt")
+        .await
+        .unwrap();
+
+    for daemon in daemons {
+        daemon.stop().await;
+    }
+}
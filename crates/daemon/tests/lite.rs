@@ -0,0 +1,27 @@
+//! Lite profile node, driven through its blocking API
+#![cfg(feature = "lite")]
+
+use jax_daemon::lite::LiteNode;
+use jax_daemon::AppState;
+
+#[test]
+fn test_lite_node_runs_without_servers() {
+    let dir = tempfile::tempdir().unwrap();
+    let jax_dir = dir.path().join("jax");
+    let app_state = AppState::init(Some(jax_dir.clone()), None).unwrap();
+    let key = app_state.load_key().unwrap();
+
+    let node = LiteNode::start(jax_dir.to_string_lossy().to_string()).unwrap();
+    assert_eq!(node.node_id(), key.public().to_hex());
+    assert!(node.list_buckets().unwrap().is_empty());
+    assert!(node
+        .ls("not-a-bucket".to_string(), "/".to_string())
+        .is_err());
+
+    // With nothing to sync the queue drains right away
+    let report = node.background_sync(5).unwrap();
+    assert_eq!(report.buckets_pinged, 0);
+    assert!(report.finished);
+
+    node.shutdown();
+}
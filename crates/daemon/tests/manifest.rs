@@ -0,0 +1,90 @@
+//! Signed integrity manifests of bucket versions
+
+mod common;
+
+use std::fs;
+
+use jax_daemon::http_server::api::v0::bucket::manifest::ManifestRequest;
+use jax_daemon::integrity::IntegrityManifest;
+
+use crate::common::TestDaemon;
+
+#[tokio::test]
+async fn test_manifest_matches_bucket_contents() {
+    let mut daemon = TestDaemon::start().await;
+    let bucket_id = daemon.create_bucket("records").await;
+    let first = daemon.add_file(bucket_id, "/report.txt", b"q3").await;
+    daemon.add_file(bucket_id, "/data/log.txt", b"one").await;
+    // Appended files have no recorded hash, so they're read
+    daemon
+        .append_file(bucket_id, "/data/log.txt", b" two")
+        .await;
+
+    let response = daemon
+        .client
+        .call(ManifestRequest {
+            bucket_id,
+            at: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(response.files, 2);
+    let manifest: IntegrityManifest = response.manifest.parse().unwrap();
+    manifest.verify().unwrap();
+    assert_eq!(manifest.bucket_id, bucket_id);
+    assert_eq!(manifest.bucket_name, "records");
+    assert_eq!(manifest.version.to_string(), response.version);
+    assert_eq!(manifest.height, response.height);
+
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("data")).unwrap();
+    fs::write(dir.path().join("report.txt"), b"q3").unwrap();
+    fs::write(dir.path().join("data/log.txt"), b"one two").unwrap();
+    let check = manifest.check_dir(dir.path()).unwrap();
+    assert_eq!(check.matched, 2);
+    assert!(check.modified.is_empty() && check.missing.is_empty() && check.extra.is_empty());
+
+    fs::write(dir.path().join("report.txt"), b"q4").unwrap();
+    let check = manifest.check_dir(dir.path()).unwrap();
+    assert_eq!(check.modified, vec!["/report.txt"]);
+
+    // An earlier version
+    let response = daemon
+        .client
+        .call(ManifestRequest {
+            bucket_id,
+            at: Some(first.hash().to_string()),
+        })
+        .await
+        .unwrap();
+    let manifest: IntegrityManifest = response.manifest.parse().unwrap();
+    assert_eq!(manifest.version, first.hash());
+    assert_eq!(manifest.height, 1);
+    assert_eq!(manifest.files.len(), 1);
+    assert_eq!(manifest.files[0].path, "/report.txt");
+}
+
+#[tokio::test]
+async fn test_manifest_rejects_unknown_versions() {
+    let mut daemon = TestDaemon::start().await;
+    let bucket_id = daemon.create_bucket("records").await;
+    let other = daemon.create_bucket("other").await;
+    let link = daemon.add_file(other, "/a.txt", b"a").await;
+
+    assert!(daemon
+        .client
+        .call(ManifestRequest {
+            bucket_id,
+            at: Some(link.hash().to_string()),
+        })
+        .await
+        .is_err());
+    assert!(daemon
+        .client
+        .call(ManifestRequest {
+            bucket_id,
+            at: Some("not a hash".to_string()),
+        })
+        .await
+        .is_err());
+}
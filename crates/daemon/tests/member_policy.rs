@@ -0,0 +1,148 @@
+//! Member policies: held shares, auto-pin and member notifications
+
+mod common;
+
+use uuid::Uuid;
+
+use jax_daemon::http_server::api::client::ApiClient;
+use jax_daemon::http_server::api::v0::audit::AuditLsRequest;
+use jax_daemon::http_server::api::v0::bucket::member::{
+    MemberApproveRequest, MemberPendingRequest, MemberPolicySetRequest,
+};
+use jax_daemon::http_server::api::v0::bucket::share::{ShareRequest, ShareRole};
+use jax_daemon::http_server::api::v0::sync::policy::SyncPolicyGetRequest;
+
+use crate::common::{assert_converged, start_daemons, wait_for};
+
+fn policy(bucket_id: Option<Uuid>) -> MemberPolicySetRequest {
+    MemberPolicySetRequest {
+        bucket_id,
+        auto_pin: true,
+        notify_members: false,
+        require_approval: false,
+    }
+}
+
+/// Share and approve, as a bucket that requires approval needs
+async fn share_approved(client: &mut ApiClient, bucket_id: Uuid, peer_public_key: String) {
+    let response = client
+        .call(ShareRequest {
+            bucket_id,
+            peer_public_key: peer_public_key.clone(),
+            role: ShareRole::Owner,
+            path: None,
+            expected_version: None,
+        })
+        .await
+        .unwrap();
+    assert!(response.pending);
+    assert!(response.new_bucket_link.is_none());
+
+    let pending = client
+        .call(MemberPendingRequest {
+            bucket_id: Some(bucket_id),
+        })
+        .await
+        .unwrap()
+        .shares;
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].peer_public_key, peer_public_key);
+
+    let approved = client
+        .call(MemberApproveRequest {
+            bucket_id,
+            peer_public_key,
+        })
+        .await
+        .unwrap();
+    assert!(approved.new_bucket_link.is_some());
+    assert!(client
+        .call(MemberPendingRequest::default())
+        .await
+        .unwrap()
+        .shares
+        .is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_member_policies() {
+    let mut daemons = start_daemons(3).await;
+    let bob_id = daemons[1].node_id();
+    let carol_id = daemons[2].node_id();
+
+    // Alice holds shares for approval; Bob joins buckets online-only
+    let bucket_id = daemons[0].create_bucket("team").await;
+    daemons[0]
+        .client
+        .call(MemberPolicySetRequest {
+            require_approval: true,
+            ..policy(None)
+        })
+        .await
+        .unwrap();
+    daemons[1]
+        .client
+        .call(MemberPolicySetRequest {
+            auto_pin: false,
+            ..policy(None)
+        })
+        .await
+        .unwrap();
+    daemons[1]
+        .client
+        .call(MemberPolicySetRequest {
+            auto_pin: false,
+            notify_members: true,
+            ..policy(Some(bucket_id))
+        })
+        .await
+        .unwrap();
+
+    share_approved(&mut daemons[0].client, bucket_id, bob_id).await;
+    assert_converged(&mut daemons[..2], bucket_id).await;
+
+    let client = daemons[1].client.clone();
+    wait_for("bob to join online-only", || {
+        let mut client = client.clone();
+        async move {
+            client
+                .call(SyncPolicyGetRequest { bucket_id })
+                .await
+                .is_ok_and(|response| response.policy.online_only)
+        }
+    })
+    .await;
+
+    // Bob hears about Carol joining through Alice
+    share_approved(&mut daemons[0].client, bucket_id, carol_id.clone()).await;
+    let client = daemons[1].client.clone();
+    let alice = format!("peer:{}", daemons[0].node_id());
+    wait_for("bob to record carol joining", || {
+        let mut client = client.clone();
+        let alice = alice.clone();
+        let carol_id = carol_id.clone();
+        async move {
+            client
+                .call(AuditLsRequest {
+                    bucket_id: Some(bucket_id),
+                    ..AuditLsRequest::default()
+                })
+                .await
+                .is_ok_and(|response| {
+                    response.entries.iter().any(|entry| {
+                        entry.operation == "bucket.member.added"
+                            && entry.actor == alice
+                            && entry
+                                .detail
+                                .as_deref()
+                                .is_some_and(|detail| detail.starts_with(&carol_id))
+                    })
+                })
+        }
+    })
+    .await;
+
+    for daemon in daemons {
+        daemon.stop().await;
+    }
+}
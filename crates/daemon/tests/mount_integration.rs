@@ -13,7 +13,7 @@ use jax_daemon::{Database, FuseMount, MountStatus};
 /// Create an in-memory test database
 async fn setup_test_db() -> Database {
     let db_url = url::Url::parse("sqlite::memory:").unwrap();
-    Database::connect(&db_url).await.unwrap()
+    Database::connect(&db_url, None).await.unwrap()
 }
 
 #[tokio::test]
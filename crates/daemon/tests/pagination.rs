@@ -0,0 +1,188 @@
+//! Cursor pagination, filtering and sorting of list endpoints
+
+mod common;
+
+use reqwest::StatusCode;
+use uuid::Uuid;
+
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::list::BucketSort;
+use jax_daemon::http_server::api::v0::bucket::ls::{LsRequest, LsSort};
+use jax_daemon::http_server::api::v0::bucket::share::ShareRole;
+use jax_daemon::http_server::api::v0::bucket::{ListRequest, SharesRequest, VersionsRequest};
+use jax_daemon::pagination::{PageRequest, SortOrder};
+
+use crate::common::{start_daemons, TestDaemon};
+
+fn ls(bucket_id: Uuid, sort: Option<LsSort>, page: PageRequest) -> LsRequest {
+    LsRequest {
+        bucket_id,
+        path: Some("/".to_string()),
+        deep: None,
+        min_version: None,
+        starred: false,
+        filter: None,
+        sort,
+        page,
+    }
+}
+
+#[tokio::test]
+async fn test_ls_pages_through_every_item() {
+    let mut daemon = TestDaemon::start().await;
+    let bucket_id = daemon.create_bucket("files").await;
+    for (name, size) in [
+        ("c.txt", 3),
+        ("a.txt", 1),
+        ("e.txt", 5),
+        ("b.txt", 2),
+        ("d.md", 4),
+    ] {
+        daemon
+            .add_file(bucket_id, &format!("/{}", name), &vec![b'x'; size])
+            .await;
+    }
+
+    // Without a limit or cursor every item is listed, as before
+    let all = daemon
+        .client
+        .call(ls(bucket_id, None, PageRequest::default()))
+        .await
+        .unwrap();
+    assert_eq!(all.items.len(), 5);
+    assert_eq!(all.total, 5);
+    assert_eq!(all.next_cursor, None);
+
+    // Pages by size, largest first, cover every item once
+    let mut request = ls(
+        bucket_id,
+        Some(LsSort::Size),
+        PageRequest {
+            order: Some(SortOrder::Desc),
+            ..PageRequest::first(2)
+        },
+    );
+    let mut names = Vec::new();
+    loop {
+        let page = daemon.client.call(request.clone()).await.unwrap();
+        assert_eq!(page.total, 5);
+        assert!(page.items.len() <= 2);
+        names.extend(page.items.into_iter().map(|item| item.name));
+        match page.next_cursor {
+            Some(cursor) => request.page.cursor = Some(cursor),
+            None => break,
+        }
+    }
+    assert_eq!(names, ["e.txt", "d.md", "c.txt", "b.txt", "a.txt"]);
+
+    // Filters apply before paging, and count in the total
+    let mut filtered = ls(bucket_id, None, PageRequest::first(1));
+    filtered.filter = Some(".TXT".to_string());
+    let page = daemon.client.call(filtered).await.unwrap();
+    assert_eq!(page.total, 4);
+    assert_eq!(page.items[0].name, "a.txt");
+
+    // Cursors only continue the listing they came from
+    let mut mismatched = ls(bucket_id, Some(LsSort::Name), PageRequest::default());
+    mismatched.page.cursor = request.page.cursor;
+    assert!(matches!(
+        daemon.client.call(mismatched).await,
+        Err(ApiError::HttpStatus(StatusCode::BAD_REQUEST, _))
+    ));
+}
+
+#[tokio::test]
+async fn test_buckets_versions_and_shares_are_paged() {
+    let mut daemons = start_daemons(2).await;
+    let (first, rest) = daemons.split_first_mut().unwrap();
+    let other = &rest[0];
+    for name in ["gamma", "alpha", "beta"] {
+        first.create_bucket(name).await;
+    }
+
+    let list = |filter: Option<&str>, page| ListRequest {
+        prefix: None,
+        filter: filter.map(str::to_string),
+        sort: Some(BucketSort::Name),
+        page,
+        workspace: None,
+        starred: false,
+    };
+    let page = first
+        .client
+        .call(list(None, PageRequest::first(2)))
+        .await
+        .unwrap();
+    assert_eq!(page.total, 3);
+    let names: Vec<_> = page.buckets.iter().map(|b| b.name.as_str()).collect();
+    assert_eq!(names, ["alpha", "beta"]);
+    let next = PageRequest {
+        cursor: page.next_cursor,
+        ..PageRequest::first(2)
+    };
+    let page = first.client.call(list(None, next)).await.unwrap();
+    assert_eq!(page.buckets.len(), 1);
+    assert_eq!(page.buckets[0].name, "gamma");
+    assert_eq!(page.next_cursor, None);
+    let page = first
+        .client
+        .call(list(Some("ET"), PageRequest::default()))
+        .await
+        .unwrap();
+    assert_eq!(page.total, 1);
+    assert_eq!(page.buckets[0].name, "beta");
+
+    // Versions, newest first
+    let bucket_id = first.create_bucket("history").await;
+    for i in 0..3 {
+        first
+            .add_file(bucket_id, "/file.txt", format!("{}", i).as_bytes())
+            .await;
+    }
+    let versions = first
+        .client
+        .call(VersionsRequest {
+            bucket_id,
+            published: false,
+            page: PageRequest::first(2),
+        })
+        .await
+        .unwrap();
+    assert_eq!(versions.total, 4);
+    let heights: Vec<_> = versions.versions.iter().map(|v| v.height).collect();
+    assert_eq!(heights, [3, 2]);
+    let versions = first
+        .client
+        .call(VersionsRequest {
+            bucket_id,
+            published: false,
+            page: PageRequest {
+                cursor: versions.next_cursor,
+                ..PageRequest::first(2)
+            },
+        })
+        .await
+        .unwrap();
+    let heights: Vec<_> = versions.versions.iter().map(|v| v.height).collect();
+    assert_eq!(heights, [1, 0]);
+    assert_eq!(versions.next_cursor, None);
+
+    // Shares, filtered by role
+    first.add_peer(other).await;
+    first.share(bucket_id, other, ShareRole::Mirror).await;
+    let shares = |role| SharesRequest {
+        bucket_id,
+        role,
+        page: PageRequest::default(),
+    };
+    let all = first.client.call(shares(None)).await.unwrap();
+    assert_eq!(all.total, 2);
+    let mirrors = first
+        .client
+        .call(shares(Some(ShareRole::Mirror)))
+        .await
+        .unwrap();
+    assert_eq!(mirrors.total, 1);
+    assert_eq!(mirrors.shares[0].public_key, other.node_id());
+    assert!(!mirrors.shares[0].is_self);
+}
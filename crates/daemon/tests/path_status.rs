@@ -0,0 +1,88 @@
+//! Sync status of local paths in mounted folders
+
+mod common;
+
+use jax_daemon::http_server::api::v0::bucket::PublishRequest;
+use jax_daemon::http_server::api::v0::sync::path_status::{PathStatus, PathSyncState};
+use jax_daemon::http_server::api::v0::sync::{PathStatusRequest, SyncPauseRequest};
+use jax_daemon::FuseMount;
+
+use crate::common::TestDaemon;
+
+async fn statuses(daemon: &mut TestDaemon, paths: &[&str]) -> Vec<PathStatus> {
+    daemon
+        .client
+        .call(PathStatusRequest {
+            paths: paths.iter().map(|path| path.to_string()).collect(),
+        })
+        .await
+        .unwrap()
+        .statuses
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_path_status_in_mounted_folder() {
+    let mut daemon = TestDaemon::start().await;
+    let bucket_id = daemon.create_bucket("docs").await;
+    daemon
+        .add_file(bucket_id, "/notes/todo.txt", b"buy milk")
+        .await;
+    // Only the configuration matters; the folder doesn't need to be mounted
+    let mount = FuseMount::create(
+        bucket_id,
+        "/home/me/Jax/docs",
+        false,
+        false,
+        None,
+        None,
+        daemon.state.database(),
+    )
+    .await
+    .unwrap();
+
+    let found = statuses(
+        &mut daemon,
+        &[
+            "/home/me/Jax/docs/notes/todo.txt",
+            "/home/me/Jax/docs/notes",
+            "/home/me/Jax/docs/notes/./../notes/draft.txt",
+            "/home/me/Documents/todo.txt",
+        ],
+    )
+    .await;
+    assert_eq!(found[0].state, PathSyncState::Synced);
+    assert_eq!(found[0].bucket_id, Some(bucket_id));
+    assert_eq!(found[0].mount_id, Some(*mount.mount_id));
+    assert_eq!(found[0].path.as_deref(), Some("/notes/todo.txt"));
+    assert!(!found[0].is_dir);
+    assert_eq!(found[0].gateway_path, None);
+    assert_eq!(found[1].state, PathSyncState::Synced);
+    assert!(found[1].is_dir);
+    assert_eq!(found[2].state, PathSyncState::Missing);
+    assert_eq!(found[2].path.as_deref(), Some("/notes/draft.txt"));
+    assert_eq!(found[3].state, PathSyncState::Untracked);
+    assert_eq!(found[3].bucket_id, None);
+
+    // Publishing gives files a gateway path to copy
+    daemon
+        .client
+        .call(PublishRequest {
+            bucket_id,
+            rotate: false,
+            expected_version: None,
+            index: Default::default(),
+        })
+        .await
+        .unwrap();
+    let found = statuses(&mut daemon, &["/home/me/Jax/docs/notes/todo.txt"]).await;
+    assert_eq!(
+        found[0].gateway_path,
+        Some(format!("/gw/{}/notes/todo.txt", bucket_id))
+    );
+
+    daemon.client.call(SyncPauseRequest {}).await.unwrap();
+    let found = statuses(&mut daemon, &["/home/me/Jax/docs/notes/todo.txt"]).await;
+    assert_eq!(found[0].state, PathSyncState::Paused);
+
+    daemon.stop().await;
+}
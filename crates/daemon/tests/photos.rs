@@ -0,0 +1,145 @@
+//! Photo timeline across buckets
+
+mod common;
+
+use jax_daemon::http_server::api::v0::bucket::photos::{PhotosRequest, PhotosResponse};
+use jax_daemon::http_server::api::v0::bucket::CreateRequest;
+
+use crate::common::TestDaemon;
+
+/// A JPEG with an EXIF DateTimeOriginal and a frame header
+fn jpeg(date: &str, width: u16, height: u16) -> Vec<u8> {
+    // Big-endian TIFF block: IFD0 points at the EXIF IFD, which holds the date
+    let mut tiff = b"MM\x00\x2a\x00\x00\x00\x08".to_vec();
+    tiff.extend(1u16.to_be_bytes());
+    tiff.extend([0x87, 0x69, 0x00, 0x04, 0, 0, 0, 1]);
+    tiff.extend(26u32.to_be_bytes());
+    tiff.extend(0u32.to_be_bytes());
+    tiff.extend(1u16.to_be_bytes());
+    tiff.extend([0x90, 0x03, 0x00, 0x02, 0, 0, 0, 20]);
+    tiff.extend(44u32.to_be_bytes());
+    tiff.extend(0u32.to_be_bytes());
+    tiff.extend(date.as_bytes());
+    tiff.push(0);
+
+    let mut b = vec![0xff, 0xd8, 0xff, 0xe1];
+    b.extend((tiff.len() as u16 + 8).to_be_bytes());
+    b.extend(b"Exif\0\0");
+    b.extend(tiff);
+    b.extend([0xff, 0xc0, 0x00, 0x11, 0x08]);
+    b.extend(height.to_be_bytes());
+    b.extend(width.to_be_bytes());
+    b.extend([0x03; 10]);
+    b.extend([0xff, 0xda]);
+    b
+}
+
+async fn photos(daemon: &mut TestDaemon, request: PhotosRequest) -> PhotosResponse {
+    daemon.client.call(request).await.unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_photo_timeline_across_buckets() {
+    let mut daemon = TestDaemon::start().await;
+    let camera = daemon
+        .client
+        .call(CreateRequest {
+            name: "camera".to_string(),
+            compress: false,
+            extract_media: true,
+        })
+        .await
+        .unwrap()
+        .bucket_id;
+    let scans = daemon.create_bucket("scans").await;
+
+    daemon
+        .add_file(
+            camera,
+            "/2023/beach.jpg",
+            &jpeg("2023:08:14 10:00:00", 40, 30),
+        )
+        .await;
+    daemon
+        .add_file(
+            camera,
+            "/2024/snow.jpg",
+            &jpeg("2024:01:02 09:00:00", 30, 40),
+        )
+        .await;
+    daemon.add_file(camera, "/notes.txt", b"not a photo").await;
+    // Without media extraction the photo has no capture date and goes last
+    daemon
+        .add_file(scans, "/receipt.jpg", &jpeg("2024:05:05 12:00:00", 10, 10))
+        .await;
+
+    let all = photos(
+        &mut daemon,
+        PhotosRequest {
+            bucket_id: None,
+            offset: None,
+            limit: None,
+        },
+    )
+    .await;
+    let paths: Vec<_> = all.items.iter().map(|p| p.path.as_str()).collect();
+    assert_eq!(paths, ["/2024/snow.jpg", "/2023/beach.jpg", "/receipt.jpg"]);
+    assert_eq!(all.items[0].bucket_id, camera);
+    assert_eq!(all.items[0].captured_at, Some(1_704_186_000));
+    assert_eq!(
+        (all.items[0].width, all.items[0].height),
+        (Some(30), Some(40))
+    );
+    assert_eq!(all.items[2].captured_at, None);
+    assert_eq!(all.next_offset, None);
+
+    // Pages follow on from each other
+    let first = photos(
+        &mut daemon,
+        PhotosRequest {
+            bucket_id: None,
+            offset: None,
+            limit: Some(2),
+        },
+    )
+    .await;
+    assert_eq!(first.items.len(), 2);
+    assert_eq!(first.next_offset, Some(2));
+    let second = photos(
+        &mut daemon,
+        PhotosRequest {
+            bucket_id: None,
+            offset: first.next_offset,
+            limit: Some(2),
+        },
+    )
+    .await;
+    assert_eq!(second.items.len(), 1);
+    assert_eq!(second.items[0].path, "/receipt.jpg");
+    assert_eq!(second.next_offset, None);
+
+    // A new photo shows up once the bucket changes
+    daemon
+        .add_file(
+            camera,
+            "/2025/park.jpg",
+            &jpeg("2025:03:01 08:00:00", 40, 30),
+        )
+        .await;
+    let camera_only = photos(
+        &mut daemon,
+        PhotosRequest {
+            bucket_id: Some(camera),
+            offset: None,
+            limit: None,
+        },
+    )
+    .await;
+    let paths: Vec<_> = camera_only.items.iter().map(|p| p.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        ["/2025/park.jpg", "/2024/snow.jpg", "/2023/beach.jpg"]
+    );
+
+    daemon.stop().await;
+}
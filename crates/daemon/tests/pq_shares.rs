@@ -0,0 +1,42 @@
+//! Hybrid X25519 + ML-KEM-768 shares negotiated between daemons
+
+mod common;
+
+use ::common::crypto::KeyWrap;
+use ::common::mount::Mount;
+use jax_daemon::http_server::api::v0::bucket::share::ShareRole;
+
+use crate::common::{assert_converged, start_daemons};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_owners_get_hybrid_shares() {
+    let mut daemons = start_daemons(3).await;
+
+    let bucket_id = daemons[0].create_bucket("vault").await;
+    daemons[0]
+        .add_file(bucket_id, "/keys.txt", b"long-lived secret")
+        .await;
+    let (alice, others) = daemons.split_at_mut(1);
+    alice[0]
+        .share(bucket_id, &others[0], ShareRole::Owner)
+        .await;
+    alice[0]
+        .share(bucket_id, &others[1], ShareRole::Mirror)
+        .await;
+    let head = assert_converged(&mut daemons, bucket_id).await;
+
+    // Every peer reads hybrid shares, so both owners got one
+    let manifest = Mount::load_manifest(&head, daemons[1].state.peer().blobs())
+        .await
+        .unwrap();
+    for owner in [daemons[0].node_id(), daemons[1].node_id()] {
+        let share = manifest.shares()[&owner].share().unwrap();
+        assert_eq!(share.key_wrap(), KeyWrap::X25519MlKem768);
+    }
+    assert!(manifest.shares()[&daemons[2].node_id()].share().is_none());
+
+    // And Bob can unwrap his
+    daemons[1]
+        .wait_for_file(bucket_id, "/keys.txt", b"long-lived secret")
+        .await;
+}
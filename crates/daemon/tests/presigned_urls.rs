@@ -0,0 +1,87 @@
+//! Pre-signed download URLs served by the gateway
+
+mod common;
+
+use std::time::Duration;
+
+use reqwest::StatusCode;
+
+use jax_daemon::http_server::api::v0::bucket::PresignRequest;
+
+use crate::common::{wait_for, TestDaemon};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_presigned_urls() {
+    let mut daemon = TestDaemon::start().await;
+    // Never published: the gateway doesn't otherwise serve it
+    let bucket_id = daemon.create_bucket("private").await;
+    daemon
+        .add_file(bucket_id, "/reports/q3.pdf", b"first draft")
+        .await;
+    let livez = daemon.gateway_url.join("/_status/livez").unwrap();
+    wait_for("gateway to come up", || {
+        let livez = livez.clone();
+        async move { reqwest::get(livez).await.is_ok() }
+    })
+    .await;
+
+    let presign = |path: &str, expires_in_secs: Option<u64>| PresignRequest {
+        bucket_id,
+        path: path.to_string(),
+        at: None,
+        expires_in_secs,
+    };
+    let signed = daemon
+        .client
+        .call(presign("/reports/q3.pdf", None))
+        .await
+        .unwrap();
+    assert!(signed.url.is_none());
+
+    // Later writes don't change what the URL serves
+    daemon
+        .add_file(bucket_id, "/reports/q3.pdf", b"final version")
+        .await;
+    let url = daemon.gateway_url.join(&signed.url_path).unwrap();
+    let response = reqwest::get(url.clone()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()[reqwest::header::CONTENT_DISPOSITION],
+        "attachment; filename=\"q3.pdf\"; filename*=UTF-8''q3.pdf"
+    );
+    assert_eq!(response.bytes().await.unwrap().as_ref(), b"first draft");
+
+    // Tampering with anything signed invalidates the URL
+    let tampered = signed.url_path.replace("q3.pdf", "q4.pdf");
+    let response = reqwest::get(daemon.gateway_url.join(&tampered).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let unsigned = daemon
+        .gateway_url
+        .join(&format!("/gw/{}/reports/q3.pdf", bucket_id))
+        .unwrap();
+    let response = reqwest::get(unsigned).await.unwrap();
+    assert!(!response.status().is_success());
+
+    // Expired URLs are gone
+    let short = daemon
+        .client
+        .call(presign("/reports/q3.pdf", Some(1)))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    let response = reqwest::get(daemon.gateway_url.join(&short.url_path).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::GONE);
+
+    // Directories, missing files and out of range expiries are refused
+    assert!(daemon.client.call(presign("/reports", None)).await.is_err());
+    assert!(daemon.client.call(presign("/missing", None)).await.is_err());
+    assert!(daemon
+        .client
+        .call(presign("/reports/q3.pdf", Some(30 * 24 * 60 * 60)))
+        .await
+        .is_err());
+}
@@ -0,0 +1,87 @@
+//! Named profiles served by one daemon
+
+mod common;
+
+use reqwest::StatusCode;
+use tempfile::TempDir;
+
+use jax_daemon::http_server::api::client::{ApiClient, ApiError, ApiRequest};
+use jax_daemon::http_server::api::v0::admin::StatusRequest;
+use jax_daemon::http_server::api::v0::bucket::list::ListResponse;
+use jax_daemon::http_server::api::v0::bucket::{CreateRequest, ListRequest};
+use jax_daemon::profiles;
+
+use crate::common::TestDaemon;
+
+fn list_request() -> ListRequest {
+    ListRequest {
+        prefix: None,
+        filter: None,
+        sort: None,
+        page: Default::default(),
+        workspace: None,
+        starred: false,
+    }
+}
+
+async fn bucket_names(client: &mut ApiClient) -> Vec<String> {
+    let response = client.call(list_request()).await.unwrap();
+    response.buckets.into_iter().map(|b| b.name).collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_profiles_are_isolated() {
+    let dir = TempDir::new().unwrap();
+    let (_, token) = profiles::create(dir.path(), "work").unwrap();
+    let mut daemon = TestDaemon::start_in(dir).await;
+    let mut work = ApiClient::with_profile(daemon.client.base_url(), "work").unwrap();
+
+    daemon.create_bucket("personal").await;
+    work.call(CreateRequest {
+        name: "job".to_string(),
+        compress: false,
+        extract_media: false,
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(bucket_names(&mut daemon.client).await, ["personal"]);
+    assert_eq!(bucket_names(&mut work).await, ["job"]);
+
+    // Each profile is its own identity
+    let default_id = daemon
+        .client
+        .call(StatusRequest::default())
+        .await
+        .unwrap()
+        .peer_id;
+    let work_id = work.call(StatusRequest::default()).await.unwrap().peer_id;
+    assert_ne!(default_id, work_id);
+
+    // The profile's API token selects it too
+    let response = list_request()
+        .build_request(daemon.client.base_url(), daemon.client.http_client())
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    let listed: ListResponse = response.json().await.unwrap();
+    assert_eq!(listed.buckets.len(), 1);
+    assert_eq!(listed.buckets[0].name, "job");
+
+    let response = list_request()
+        .build_request(daemon.client.base_url(), daemon.client.http_client())
+        .bearer_auth("not-a-token")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let mut unknown = ApiClient::with_profile(daemon.client.base_url(), "play").unwrap();
+    match unknown.call(list_request()).await {
+        Err(ApiError::HttpStatus(status, _)) => assert_eq!(status, StatusCode::NOT_FOUND),
+        other => panic!("expected 404 for an unknown profile, got {:?}", other),
+    }
+
+    daemon.stop().await;
+}
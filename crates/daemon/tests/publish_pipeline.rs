@@ -0,0 +1,188 @@
+//! Publish pipelines building buckets into the site they publish
+
+mod common;
+
+use reqwest::StatusCode;
+
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::pipeline::{PipelineGetRequest, PipelineSetRequest};
+use jax_daemon::http_server::api::v0::bucket::PublishRequest;
+use jax_daemon::publish_pipeline::{BuilderConfig, PublishPipeline};
+
+use crate::common::{wait_for, TestDaemon};
+
+fn publish_request(bucket_id: uuid::Uuid) -> PublishRequest {
+    PublishRequest {
+        bucket_id,
+        rotate: false,
+        expected_version: None,
+        index: Default::default(),
+    }
+}
+
+/// Fills the template in the checkout's page.tmpl into the output, failing
+/// without one
+fn template_builder() -> BuilderConfig {
+    BuilderConfig {
+        name: "template".to_string(),
+        command: vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            r#"sed "s/{{title}}/$SITE_TITLE/" page.tmpl > "$JAX_OUTPUT/index.html""#.to_string(),
+        ],
+        timeout_secs: 30,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_publish_builds_and_publishes_the_output() {
+    let mut daemon = TestDaemon::start_with(|config| {
+        config.publish_builders = vec![template_builder()];
+    })
+    .await;
+    let bucket_id = daemon.create_bucket("notes").await;
+    daemon
+        .add_file(bucket_id, "/page.tmpl", b"<h1>{{title}}</h1>")
+        .await;
+    daemon
+        .client
+        .call(PipelineSetRequest {
+            bucket_id,
+            pipeline: PublishPipeline {
+                builder: "template".to_string(),
+                output: None,
+                vars: [("SITE_TITLE".to_string(), "Notes".to_string())].into(),
+            },
+        })
+        .await
+        .unwrap();
+
+    let publication = daemon
+        .client
+        .call(publish_request(bucket_id))
+        .await
+        .unwrap();
+    let site_bucket_id = publication.site_bucket_id.unwrap();
+    assert_ne!(site_bucket_id, bucket_id);
+    assert_eq!(
+        daemon.cat(site_bucket_id, "/index.html").await.unwrap(),
+        b"<h1>Notes</h1>"
+    );
+    assert!(daemon.cat(site_bucket_id, "/page.tmpl").await.is_err());
+
+    // The gateway serves the site at the source bucket's path too
+    let livez = daemon.gateway_url.join("/_status/livez").unwrap();
+    wait_for("gateway to come up", || {
+        let livez = livez.clone();
+        async move { reqwest::get(livez).await.is_ok() }
+    })
+    .await;
+    for id in [bucket_id, site_bucket_id] {
+        let response = reqwest::get(
+            daemon
+                .gateway_url
+                .join(&format!("/gw/{}/index.html?download=true", id))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.text().await.unwrap(), "<h1>Notes</h1>");
+    }
+
+    // Publishing again rebuilds into the same site bucket
+    daemon
+        .add_file(bucket_id, "/page.tmpl", b"<h2>{{title}}</h2>")
+        .await;
+    let republication = daemon
+        .client
+        .call(publish_request(bucket_id))
+        .await
+        .unwrap();
+    assert_eq!(republication.site_bucket_id, Some(site_bucket_id));
+    assert_eq!(
+        daemon.cat(site_bucket_id, "/index.html").await.unwrap(),
+        b"<h2>Notes</h2>"
+    );
+
+    let builds = daemon
+        .client
+        .call(PipelineGetRequest { bucket_id })
+        .await
+        .unwrap()
+        .builds;
+    assert_eq!(builds.len(), 2);
+    assert!(builds.iter().all(|build| build.succeeded));
+    assert_eq!(
+        builds[0].site_version.as_deref(),
+        Some(republication.new_bucket_link.as_str())
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_failed_build_fails_the_publish() {
+    let mut daemon = TestDaemon::start_with(|config| {
+        config.publish_builders = vec![template_builder()];
+    })
+    .await;
+    let bucket_id = daemon.create_bucket("notes").await;
+    daemon
+        .add_file(bucket_id, "/readme.md", b"no template")
+        .await;
+
+    // Unknown builders are refused up front
+    let err = daemon
+        .client
+        .call(PipelineSetRequest {
+            bucket_id,
+            pipeline: PublishPipeline {
+                builder: "hugo".to_string(),
+                output: None,
+                vars: Default::default(),
+            },
+        })
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        ApiError::HttpStatus(StatusCode::BAD_REQUEST, _)
+    ));
+
+    daemon
+        .client
+        .call(PipelineSetRequest {
+            bucket_id,
+            pipeline: PublishPipeline {
+                builder: "template".to_string(),
+                output: None,
+                vars: Default::default(),
+            },
+        })
+        .await
+        .unwrap();
+    let err = daemon
+        .client
+        .call(publish_request(bucket_id))
+        .await
+        .unwrap_err();
+    let ApiError::HttpStatus(status, error) = err else {
+        panic!("unexpected error {}", err);
+    };
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let message = error.message;
+    assert!(message.contains("page.tmpl"), "{}", message);
+
+    let response = daemon
+        .client
+        .call(PipelineGetRequest { bucket_id })
+        .await
+        .unwrap();
+    assert_eq!(response.site_bucket_id, None);
+    assert_eq!(response.builds.len(), 1);
+    assert!(!response.builds[0].succeeded);
+    assert!(response.builds[0]
+        .log
+        .as_deref()
+        .unwrap()
+        .contains("page.tmpl"));
+}
@@ -0,0 +1,97 @@
+//! Content scanners vetoing publication
+
+mod common;
+
+use reqwest::StatusCode;
+
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::{PublishRequest, ScansRequest};
+use jax_daemon::publish_scan::{ScannerConfig, Verdict};
+
+use crate::common::{wait_for, TestDaemon};
+
+fn publish_request(bucket_id: uuid::Uuid) -> PublishRequest {
+    PublishRequest {
+        bucket_id,
+        rotate: false,
+        expected_version: None,
+        index: Default::default(),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_scanner_vetoes_publish() {
+    let mut daemon = TestDaemon::start_with(|config| {
+        // Vetoes versions with a file mentioning EICAR; the export directory
+        // is the script's $0
+        config.publish_scanners = vec![ScannerConfig {
+            name: "grep".to_string(),
+            command: Some(vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                r#"if grep -rq EICAR "$0"; then echo "infected: $JAX_BUCKET_ID"; exit 1; fi"#
+                    .to_string(),
+            ]),
+            url: None,
+            timeout_secs: 30,
+        }];
+    })
+    .await;
+    let bucket_id = daemon.create_bucket("site").await;
+    daemon.add_file(bucket_id, "/docs/a.txt", b"clean").await;
+    daemon
+        .client
+        .call(publish_request(bucket_id))
+        .await
+        .unwrap();
+
+    daemon
+        .add_file(bucket_id, "/docs/b.txt", b"EICAR test")
+        .await;
+    let err = daemon
+        .client
+        .call(publish_request(bucket_id))
+        .await
+        .unwrap_err();
+    let ApiError::HttpStatus(status, error) = err else {
+        panic!("unexpected error {}", err);
+    };
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let message = error.message;
+    assert!(message.contains("grep: vetoed"), "{}", message);
+    assert!(message.contains(&bucket_id.to_string()), "{}", message);
+
+    // Both verdicts are recorded, newest first
+    let scans = daemon
+        .client
+        .call(ScansRequest { bucket_id })
+        .await
+        .unwrap()
+        .scans;
+    assert_eq!(scans.len(), 2);
+    assert_eq!(scans[0].result.verdict, Verdict::Vetoed);
+    assert_eq!(scans[1].result.verdict, Verdict::Allowed);
+    assert_ne!(scans[0].version, scans[1].version);
+
+    // The gateway keeps serving the version that passed
+    let livez = daemon.gateway_url.join("/_status/livez").unwrap();
+    wait_for("gateway to come up", || {
+        let livez = livez.clone();
+        async move { reqwest::get(livez).await.is_ok() }
+    })
+    .await;
+    let get = |path: &str| {
+        reqwest::get(
+            daemon
+                .gateway_url
+                .join(&format!("/gw/{}{}?download=true", bucket_id, path))
+                .unwrap(),
+        )
+    };
+    let response = get("/docs/a.txt").await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        get("/docs/b.txt").await.unwrap().status(),
+        StatusCode::NOT_FOUND
+    );
+}
@@ -0,0 +1,113 @@
+//! Read replicas: syncing published buckets, refusing writes and saying how
+//! fresh what they serve is
+
+mod common;
+
+use reqwest::StatusCode;
+
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::share::ShareRole;
+use jax_daemon::http_server::api::v0::bucket::{CreateRequest, PublishRequest};
+use jax_daemon::http_server::error::ErrorCode;
+
+use crate::common::{wait_for, TestDaemon};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_replica_serves_published_buckets_with_staleness() {
+    let mut origin = TestDaemon::start().await;
+    let mut replica = TestDaemon::start_with(|config| config.read_replica = true).await;
+    origin.add_peer(&replica).await;
+    replica.add_peer(&origin).await;
+
+    let bucket_id = origin.create_bucket("site").await;
+    origin.add_file(bucket_id, "/index.txt", b"hello").await;
+    origin.share(bucket_id, &replica, ShareRole::Mirror).await;
+    let published = origin
+        .client
+        .call(PublishRequest {
+            bucket_id,
+            rotate: false,
+            expected_version: None,
+            index: Default::default(),
+        })
+        .await
+        .unwrap();
+
+    // The replica's gateway serves the published version once synced
+    let file_url = replica
+        .gateway_url
+        .join(&format!("/gw/{}/index.txt?download=true", bucket_id))
+        .unwrap();
+    wait_for("the replica to serve the bucket", || {
+        let file_url = file_url.clone();
+        async move {
+            match reqwest::get(file_url).await {
+                Ok(response) => response.status() == StatusCode::OK,
+                Err(_) => false,
+            }
+        }
+    })
+    .await;
+    let response = reqwest::get(file_url).await.unwrap();
+    let headers = response.headers();
+    assert_eq!(
+        headers["x-jax-synced-version"],
+        published.new_bucket_link.as_str()
+    );
+    let height: u64 = headers["x-jax-synced-height"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(headers.contains_key("x-jax-synced-at"));
+    assert_eq!(response.bytes().await.unwrap().as_ref(), b"hello");
+
+    // And lists it with its staleness on the API server
+    let status_url = replica.client.base_url().join("/_status/replica").unwrap();
+    let status: serde_json::Value = reqwest::get(status_url)
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let buckets = status["buckets"].as_array().unwrap();
+    assert_eq!(buckets.len(), 1);
+    assert_eq!(buckets[0]["bucket_id"], bucket_id.to_string());
+    assert_eq!(buckets[0]["version"], published.new_bucket_link.as_str());
+    assert_eq!(buckets[0]["height"], height);
+    assert!(buckets[0]["age_secs"].is_u64());
+
+    // Reads are answered, writes are refused
+    assert!(replica.head(bucket_id).await.is_some());
+    match replica
+        .client
+        .call(CreateRequest {
+            name: "local".to_string(),
+            compress: false,
+            extract_media: false,
+        })
+        .await
+    {
+        Err(ApiError::HttpStatus(status, body)) => {
+            assert_eq!(status, StatusCode::FORBIDDEN);
+            assert_eq!(body.code, ErrorCode::Forbidden);
+        }
+        other => panic!("expected a 403, got {:?}", other.map(|_| ())),
+    }
+    let deploy_url = replica
+        .gateway_url
+        .join(&format!("/deploy/{}/index.txt", bucket_id))
+        .unwrap();
+    let response = reqwest::Client::new()
+        .put(deploy_url)
+        .body("nope")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // Other nodes aren't replicas
+    let status_url = origin.client.base_url().join("/_status/replica").unwrap();
+    let response = reqwest::get(status_url).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
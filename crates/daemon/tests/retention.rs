@@ -0,0 +1,175 @@
+//! Retention policies pruning old versions of a bucket
+
+mod common;
+
+use reqwest::StatusCode;
+use uuid::Uuid;
+
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::cat::CatRequest;
+use jax_daemon::http_server::api::v0::bucket::retention::{
+    RetentionGetRequest, RetentionResetRequest, RetentionRunRequest, RetentionSetRequest,
+};
+use jax_daemon::retention::Keep;
+
+use crate::common::TestDaemon;
+
+fn hourly(bucket_id: Uuid, keep: Option<Keep>) -> RetentionSetRequest {
+    RetentionSetRequest {
+        bucket_id,
+        hourly: keep,
+        daily: None,
+        weekly: None,
+        monthly: None,
+        infrequent_access_after: None,
+        archive_after: None,
+    }
+}
+
+#[tokio::test]
+async fn test_retention_prunes_old_versions() {
+    let mut daemon = TestDaemon::start().await;
+    let bucket_id = daemon.create_bucket("history").await;
+    let first = daemon.add_file(bucket_id, "/a.txt", b"one").await;
+    daemon.add_file(bucket_id, "/b.txt", b"b").await;
+    daemon.add_file(bucket_id, "/a.txt", b"two").await;
+
+    // Every version is from this hour, so only the head is kept
+    let policy = daemon
+        .client
+        .call(hourly(bucket_id, Some(Keep::Last(1))))
+        .await
+        .unwrap();
+    assert_eq!(policy.policy.unwrap().hourly, Some(Keep::Last(1)));
+
+    let dry_run = daemon
+        .client
+        .call(RetentionRunRequest {
+            bucket_id,
+            dry_run: true,
+        })
+        .await
+        .unwrap();
+    assert_eq!(dry_run.pruned_versions, vec![0, 1, 2]);
+    assert!(dry_run.deleted_blobs > 0);
+
+    let report = daemon
+        .client
+        .call(RetentionRunRequest {
+            bucket_id,
+            dry_run: false,
+        })
+        .await
+        .unwrap();
+    assert_eq!(report.pruned_versions, dry_run.pruned_versions);
+    assert_eq!(report.deleted_blobs, dry_run.deleted_blobs);
+
+    // The head is intact, the pruned versions can't be read anymore
+    assert_eq!(daemon.cat(bucket_id, "/a.txt").await.unwrap(), b"two");
+    assert_eq!(daemon.cat(bucket_id, "/b.txt").await.unwrap(), b"b");
+    assert!(daemon
+        .client
+        .call(CatRequest {
+            bucket_id,
+            path: "/a.txt".to_string(),
+            at: Some(first.hash().to_string()),
+            min_version: None,
+            download: None,
+        })
+        .await
+        .is_err());
+
+    // Pruned versions are left alone afterwards
+    let again = daemon
+        .client
+        .call(RetentionRunRequest {
+            bucket_id,
+            dry_run: false,
+        })
+        .await
+        .unwrap();
+    assert!(again.pruned_versions.is_empty());
+    let policy = daemon
+        .client
+        .call(RetentionGetRequest { bucket_id })
+        .await
+        .unwrap();
+    assert_eq!(policy.pruned_versions, 3);
+
+    let reset = daemon
+        .client
+        .call(RetentionResetRequest { bucket_id })
+        .await
+        .unwrap();
+    assert!(reset.removed);
+    assert!(daemon
+        .client
+        .call(RetentionRunRequest {
+            bucket_id,
+            dry_run: false,
+        })
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_retention_keeps_shared_blobs() {
+    let mut daemon = TestDaemon::start().await;
+    let pruned = daemon.create_bucket("pruned").await;
+    let other = daemon.create_bucket("other").await;
+    daemon.add_file(pruned, "/shared.txt", b"shared").await;
+    daemon.add_file(other, "/copy.txt", b"shared").await;
+    daemon.add_file(pruned, "/shared.txt", b"changed").await;
+
+    daemon
+        .client
+        .call(hourly(pruned, Some(Keep::Last(1))))
+        .await
+        .unwrap();
+    let report = daemon
+        .client
+        .call(RetentionRunRequest {
+            bucket_id: pruned,
+            dry_run: false,
+        })
+        .await
+        .unwrap();
+    assert_eq!(report.pruned_versions, vec![0, 1]);
+
+    // Another bucket still holds the same content
+    assert_eq!(daemon.cat(other, "/copy.txt").await.unwrap(), b"shared");
+}
+
+#[tokio::test]
+async fn test_retention_rejects_invalid_policies() {
+    let mut daemon = TestDaemon::start().await;
+    let bucket_id = daemon.create_bucket("history").await;
+
+    assert!(daemon.client.call(hourly(bucket_id, None)).await.is_err());
+    assert!(daemon
+        .client
+        .call(hourly(Uuid::new_v4(), Some(Keep::Forever)))
+        .await
+        .is_err());
+    assert!(daemon
+        .client
+        .call(hourly(bucket_id, Some(Keep::Forever)))
+        .await
+        .is_ok());
+
+    // Moving blobs to colder storage classes needs S3 storage
+    match daemon
+        .client
+        .call(RetentionSetRequest {
+            archive_after: Some(90),
+            ..hourly(bucket_id, None)
+        })
+        .await
+    {
+        Err(ApiError::HttpStatus(status, body)) => {
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert!(body.message.contains("S3"));
+        }
+        other => panic!("expected a 400, got {:?}", other.map(|_| ())),
+    }
+}
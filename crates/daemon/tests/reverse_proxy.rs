@@ -0,0 +1,94 @@
+//! Serving the gateway behind a reverse proxy, under a path prefix
+
+mod common;
+
+use jax_daemon::http_server::api::v0::bucket::PublishRequest;
+use jax_daemon::reverse_proxy::ReverseProxyConfig;
+
+use crate::common::{wait_for, TestDaemon};
+
+/// GET `path` as a proxy on this machine would forward it
+async fn get_forwarded(daemon: &TestDaemon, path: &str) -> reqwest::Response {
+    reqwest::Client::new()
+        .get(daemon.gateway_url.join(path).unwrap())
+        .header("accept", "text/html")
+        .header("x-forwarded-for", "203.0.113.9")
+        .header("x-forwarded-proto", "https")
+        .header("x-forwarded-host", "files.example.com")
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_path_prefix_and_forwarded_headers() {
+    let mut daemon = TestDaemon::start_with(|config| {
+        config.reverse_proxy = Some(ReverseProxyConfig {
+            trusted_proxies: vec!["127.0.0.1".to_string(), "::1".to_string()],
+            path_prefix: Some("/jax/".to_string()),
+        });
+    })
+    .await;
+    let bucket_id = daemon.create_bucket("docs").await;
+    daemon.add_file(bucket_id, "/notes.md", b"# Notes").await;
+    daemon
+        .client
+        .call(PublishRequest {
+            bucket_id,
+            rotate: false,
+            expected_version: None,
+            index: Default::default(),
+        })
+        .await
+        .unwrap();
+    let livez = daemon.gateway_url.join("/jax/_status/livez").unwrap();
+    wait_for("gateway to come up", || {
+        let livez = livez.clone();
+        async move {
+            reqwest::get(livez)
+                .await
+                .is_ok_and(|response| response.status().is_success())
+        }
+    })
+    .await;
+
+    // Links in the gateway's pages keep the prefix
+    let response = get_forwarded(&daemon, &format!("/jax/gw/{}/", bucket_id)).await;
+    assert!(response.status().is_success());
+    let html = response.text().await.unwrap();
+    assert!(html.contains(r#"href="/jax/static/style.css""#));
+    assert!(html.contains(&format!(
+        r#"href="/jax/gw/{}/notes.md?view=true""#,
+        bucket_id
+    )));
+
+    let response = get_forwarded(&daemon, "/jax/").await;
+    assert!(response.status().is_success());
+    let html = response.text().await.unwrap();
+    assert!(html.contains(&format!(r#"href="/jax/gw/{}""#, bucket_id)));
+
+    // Absolute URLs are built from the forwarded scheme and host
+    let response = get_forwarded(&daemon, "/jax/robots.txt").await;
+    let robots = response.text().await.unwrap();
+    assert!(robots.contains("Disallow: /jax/dl/"));
+    assert!(robots.contains(&format!(
+        "Sitemap: https://files.example.com/jax/gw/{}/sitemap.xml",
+        bucket_id
+    )));
+
+    // Proxies that strip the prefix themselves are served too
+    let response = reqwest::get(
+        daemon
+            .gateway_url
+            .join(&format!("/gw/{}/notes.md", bucket_id))
+            .unwrap(),
+    )
+    .await
+    .unwrap();
+    assert!(response.status().is_success());
+    assert_eq!(response.bytes().await.unwrap().as_ref(), b"# Notes");
+
+    // The API is served under the prefix as well
+    let api_livez = daemon.client.base_url().join("/jax/_status/livez").unwrap();
+    assert!(reqwest::get(api_livez).await.unwrap().status().is_success());
+}
@@ -0,0 +1,116 @@
+//! robots.txt, sitemaps and noindex for buckets on the gateway
+
+mod common;
+
+use jax_daemon::bucket_index::IndexUpdate;
+use jax_daemon::http_server::api::v0::bucket::PublishRequest;
+use jax_daemon::http_server::api::v0::gateway::policy::{
+    GatewayPolicyResetRequest, GatewayPolicySetRequest,
+};
+
+use crate::common::{wait_for, TestDaemon};
+
+async fn publish(daemon: &mut TestDaemon, bucket_id: uuid::Uuid, noindex: Option<bool>) {
+    daemon
+        .client
+        .call(PublishRequest {
+            bucket_id,
+            rotate: false,
+            expected_version: None,
+            index: IndexUpdate {
+                noindex,
+                ..Default::default()
+            },
+        })
+        .await
+        .unwrap();
+}
+
+async fn get(daemon: &TestDaemon, path: &str) -> reqwest::Response {
+    reqwest::get(daemon.gateway_url.join(path).unwrap())
+        .await
+        .unwrap()
+}
+
+fn robots_tag(response: &reqwest::Response) -> Option<&str> {
+    response
+        .headers()
+        .get("x-robots-tag")
+        .and_then(|value| value.to_str().ok())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_robots_and_sitemaps() {
+    let mut daemon = TestDaemon::start().await;
+    let bucket_id = daemon.create_bucket("site").await;
+    daemon
+        .add_file(bucket_id, "/index.html", b"<p>hi</p>")
+        .await;
+    daemon
+        .add_file(bucket_id, "/posts/first post.md", b"# First")
+        .await;
+    publish(&mut daemon, bucket_id, None).await;
+    let livez = daemon.gateway_url.join("/_status/livez").unwrap();
+    wait_for("gateway to come up", || {
+        let livez = livez.clone();
+        async move { reqwest::get(livez).await.is_ok() }
+    })
+    .await;
+
+    let sitemap_path = format!("/gw/{}/sitemap.xml", bucket_id);
+    let robots = get(&daemon, "/robots.txt").await.text().await.unwrap();
+    assert!(robots.contains("Disallow: /dl/"));
+    assert!(robots.contains(&format!(
+        "Sitemap: {}",
+        daemon.gateway_url.join(&sitemap_path).unwrap()
+    )));
+
+    let response = get(&daemon, &sitemap_path).await;
+    assert!(response.status().is_success());
+    assert_eq!(robots_tag(&response), None);
+    let sitemap = response.text().await.unwrap();
+    assert!(sitemap.contains(&format!("/gw/{}/index.html</loc>", bucket_id)));
+    assert!(sitemap.contains(&format!("/gw/{}/posts/first%20post.md</loc>", bucket_id)));
+    assert!(!sitemap.contains("/posts/</loc>"));
+
+    // The operator keeps the bucket out of search results
+    daemon
+        .client
+        .call(GatewayPolicySetRequest {
+            bucket_id,
+            allow: false,
+            no_listings: false,
+            noindex: true,
+            serve_stale: false,
+            daily_bandwidth_bytes: None,
+        })
+        .await
+        .unwrap();
+    let response = get(&daemon, &format!("/gw/{}/index.html", bucket_id)).await;
+    assert!(response.status().is_success());
+    assert_eq!(robots_tag(&response), Some("noindex"));
+    assert_eq!(get(&daemon, &sitemap_path).await.status(), 404);
+    let robots = get(&daemon, "/robots.txt").await.text().await.unwrap();
+    assert!(!robots.contains("Sitemap:"));
+
+    // So can the publisher, on every gateway
+    daemon
+        .client
+        .call(GatewayPolicyResetRequest { bucket_id })
+        .await
+        .unwrap();
+    publish(&mut daemon, bucket_id, Some(true)).await;
+    let response = get(&daemon, &format!("/gw/{}/", bucket_id)).await;
+    assert_eq!(robots_tag(&response), Some("noindex"));
+    publish(&mut daemon, bucket_id, Some(false)).await;
+    let response = get(&daemon, &format!("/gw/{}/", bucket_id)).await;
+    assert_eq!(robots_tag(&response), None);
+
+    // A sitemap of the bucket's own is served as it is
+    daemon
+        .add_file(bucket_id, "/sitemap.xml", b"<urlset>mine</urlset>")
+        .await;
+    publish(&mut daemon, bucket_id, None).await;
+    let sitemap = get(&daemon, &sitemap_path).await.text().await.unwrap();
+    assert!(sitemap.contains("mine"));
+}
@@ -0,0 +1,109 @@
+//! Storage policies placing a bucket's blobs in another blob location
+
+mod common;
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use reqwest::StatusCode;
+
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::storage::{
+    StorageGetRequest, StorageResetRequest, StorageRunRequest, StorageSetRequest,
+};
+use jax_daemon::storage_policy::BlobLocationConfig;
+use jax_daemon::BlobStoreConfig;
+
+use crate::common::TestDaemon;
+
+/// Number of blobs stored under an object storage directory
+fn stored_blobs(objects: &Path) -> usize {
+    std::fs::read_dir(objects.join("data"))
+        .map(|entries| entries.count())
+        .unwrap_or(0)
+}
+
+#[tokio::test]
+async fn test_storage_policy_places_bucket_blobs() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let main = dir.path().join("main");
+    let local = dir.path().join("local");
+    let location = local.clone();
+    let mut daemon = TestDaemon::start_with(|config| {
+        config.blob_store = BlobStoreConfig::Filesystem { path: main.clone() };
+        config.blob_locations = BTreeMap::from([(
+            "local".to_string(),
+            BlobLocationConfig::Filesystem { path: location },
+        )]);
+    })
+    .await;
+
+    let bucket_id = daemon.create_bucket("sensitive").await;
+    daemon
+        .add_file(bucket_id, "/secret.txt", b"for local eyes only")
+        .await;
+    let policy = daemon
+        .client
+        .call(StorageGetRequest { bucket_id })
+        .await
+        .unwrap();
+    assert_eq!(policy.location, None);
+    assert_eq!(policy.locations, ["local"]);
+
+    // Only configured locations can be chosen
+    match daemon
+        .client
+        .call(StorageSetRequest {
+            bucket_id,
+            location: "glacier".to_string(),
+        })
+        .await
+    {
+        Err(ApiError::HttpStatus(status, _)) => assert_eq!(status, StatusCode::BAD_REQUEST),
+        other => panic!("expected a 400, got {:?}", other.map(|_| ())),
+    }
+
+    let policy = daemon
+        .client
+        .call(StorageSetRequest {
+            bucket_id,
+            location: "local".to_string(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(policy.location.as_deref(), Some("local"));
+    let report = daemon
+        .client
+        .call(StorageRunRequest { bucket_id })
+        .await
+        .unwrap();
+    assert!(report.placed_blobs > 0);
+    assert_eq!(report.shared_blobs, 0);
+    assert_eq!(stored_blobs(&local), report.placed_blobs as usize);
+
+    // Reads follow the blobs, and placing again moves nothing
+    assert_eq!(
+        daemon.cat(bucket_id, "/secret.txt").await.unwrap(),
+        b"for local eyes only"
+    );
+    let again = daemon
+        .client
+        .call(StorageRunRequest { bucket_id })
+        .await
+        .unwrap();
+    assert_eq!(again.placed_blobs, 0);
+
+    // Resetting moves them back to the main store
+    let reset = daemon
+        .client
+        .call(StorageResetRequest { bucket_id })
+        .await
+        .unwrap();
+    assert!(reset.removed);
+    assert_eq!(reset.placement.unwrap().placed_blobs, report.placed_blobs);
+    assert_eq!(stored_blobs(&local), 0);
+    assert_eq!(
+        daemon.cat(bucket_id, "/secret.txt").await.unwrap(),
+        b"for local eyes only"
+    );
+}
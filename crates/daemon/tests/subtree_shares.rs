@@ -0,0 +1,95 @@
+//! Sharing a single directory of a bucket between daemons
+
+mod common;
+
+use std::path::Path;
+
+use jax_daemon::http_server::api::v0::bucket::share::{ShareRequest, ShareRole};
+
+use crate::common::start_daemons;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_peer_syncs_only_the_shared_directory() {
+    let mut daemons = start_daemons(2).await;
+
+    let bucket_id = daemons[0].create_bucket("clients").await;
+    daemons[0]
+        .add_file(bucket_id, "/clients/acme/report.txt", b"for acme")
+        .await;
+    daemons[0]
+        .add_file(bucket_id, "/clients/globex/report.txt", b"for globex")
+        .await;
+    let bob = daemons[1].node_id();
+    let response = daemons[0]
+        .client
+        .call(ShareRequest {
+            bucket_id,
+            peer_public_key: bob,
+            role: ShareRole::Owner,
+            path: Some("/clients/acme".to_string()),
+            expected_version: None,
+        })
+        .await
+        .unwrap();
+    assert!(response.new_bucket_link.is_some());
+
+    // Bob sees the shared directory as the root of the bucket
+    daemons[1]
+        .wait_for_file(bucket_id, "/report.txt", b"for acme")
+        .await;
+
+    // And never downloads what is beside it
+    let globex = daemons[0]
+        .state
+        .peer()
+        .mount(bucket_id)
+        .await
+        .unwrap()
+        .get(Path::new("/clients/globex/report.txt"))
+        .await
+        .unwrap()
+        .link()
+        .hash();
+    assert!(!daemons[1].state.peer().blobs().stat(&globex).await.unwrap());
+
+    // Changes below the directory reach Bob
+    daemons[0]
+        .add_file(bucket_id, "/clients/acme/invoice.txt", b"due")
+        .await;
+    daemons[1]
+        .wait_for_file(bucket_id, "/invoice.txt", b"due")
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_only_directories_can_be_shared() {
+    let mut daemons = start_daemons(2).await;
+
+    let bucket_id = daemons[0].create_bucket("clients").await;
+    daemons[0].add_file(bucket_id, "/notes.txt", b"notes").await;
+    let bob = daemons[1].node_id();
+
+    for (path, role) in [
+        ("/notes.txt", ShareRole::Owner),
+        ("/missing", ShareRole::Owner),
+        ("/", ShareRole::Owner),
+        ("/notes.txt", ShareRole::Mirror),
+    ] {
+        let result = daemons[0]
+            .client
+            .call(ShareRequest {
+                bucket_id,
+                peer_public_key: bob.clone(),
+                role,
+                path: Some(path.to_string()),
+                expected_version: None,
+            })
+            .await;
+        assert!(result.is_err(), "sharing {} as {:?} succeeded", path, role);
+    }
+
+    // Nothing was shared, so Bob never gets the bucket
+    let mut bob = daemons.pop().unwrap();
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    assert!(bob.head(bucket_id).await.is_none());
+}
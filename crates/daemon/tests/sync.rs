@@ -0,0 +1,231 @@
+//! Multi-daemon sync scenarios, driven through the API
+
+mod common;
+
+use jax_daemon::http_server::api::client::ApiError;
+use jax_daemon::http_server::api::v0::bucket::attrs::{GetAttrsRequest, SetAttrsRequest};
+use jax_daemon::http_server::api::v0::bucket::ls::LsRequest;
+use jax_daemon::http_server::api::v0::bucket::mkdir::MkdirRequest;
+use jax_daemon::http_server::api::v0::bucket::share::ShareRole;
+use jax_daemon::http_server::api::v0::bucket::HeadMoved;
+use jax_daemon::http_server::error::ErrorCode;
+
+use crate::common::{assert_converged, start_daemons};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_shared_bucket_syncs_to_peer() {
+    let mut daemons = start_daemons(2).await;
+
+    let bucket_id = daemons[0].create_bucket("shared").await;
+    daemons[0]
+        .add_file(bucket_id, "/hello.txt", b"hello from alice")
+        .await;
+    let (alice, bob) = daemons.split_at_mut(1);
+    alice[0].share(bucket_id, &bob[0], ShareRole::Owner).await;
+
+    assert_converged(&mut daemons, bucket_id).await;
+    daemons[1]
+        .wait_for_file(bucket_id, "/hello.txt", b"hello from alice")
+        .await;
+
+    // Edits from the other owner flow back
+    daemons[1]
+        .add_file(bucket_id, "/reply.txt", b"hello from bob")
+        .await;
+    assert_converged(&mut daemons, bucket_id).await;
+    daemons[0]
+        .wait_for_file(bucket_id, "/reply.txt", b"hello from bob")
+        .await;
+    assert_eq!(
+        daemons[0].cat(bucket_id, "/hello.txt").await.unwrap(),
+        b"hello from alice"
+    );
+
+    for daemon in daemons {
+        daemon.stop().await;
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_appended_log_syncs_to_peer() {
+    let mut daemons = start_daemons(2).await;
+
+    let bucket_id = daemons[0].create_bucket("logs").await;
+    let (alice, bob) = daemons.split_at_mut(1);
+    alice[0].share(bucket_id, &bob[0], ShareRole::Owner).await;
+
+    daemons[0]
+        .append_file(bucket_id, "/sensor.log", b"t=0 ok\n")
+        .await;
+    assert_converged(&mut daemons, bucket_id).await;
+    daemons[1]
+        .wait_for_file(bucket_id, "/sensor.log", b"t=0 ok\n")
+        .await;
+
+    // Appends from either owner extend the same log
+    daemons[1]
+        .append_file(bucket_id, "/sensor.log", b"t=1 ok\n")
+        .await;
+    assert_converged(&mut daemons, bucket_id).await;
+    daemons[0]
+        .wait_for_file(bucket_id, "/sensor.log", b"t=0 ok\nt=1 ok\n")
+        .await;
+
+    for daemon in daemons {
+        daemon.stop().await;
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_attrs_sync_to_peer() {
+    let mut daemons = start_daemons(2).await;
+
+    let bucket_id = daemons[0].create_bucket("tagged").await;
+    daemons[0]
+        .add_file(bucket_id, "/photos/beach.jpg", b"jpeg")
+        .await;
+    let (alice, bob) = daemons.split_at_mut(1);
+    alice[0].share(bucket_id, &bob[0], ShareRole::Owner).await;
+
+    let set = |path: &str, key: &str, value: &str| SetAttrsRequest {
+        bucket_id,
+        path: path.to_string(),
+        attrs: [(key.to_string(), value.to_string())].into(),
+        remove: Vec::new(),
+        expected_version: None,
+    };
+    daemons[0]
+        .client
+        .call(set("/photos/beach.jpg", "tag", "holiday"))
+        .await
+        .unwrap();
+    daemons[0]
+        .client
+        .call(set("/photos", "album", "summer"))
+        .await
+        .unwrap();
+    let head = assert_converged(&mut daemons, bucket_id).await;
+    daemons[1]
+        .wait_for_file(bucket_id, "/photos/beach.jpg", b"jpeg")
+        .await;
+
+    // Bob sees both through get and ls
+    let got = daemons[1]
+        .client
+        .call(GetAttrsRequest {
+            bucket_id,
+            path: "/photos/beach.jpg".to_string(),
+            min_version: Some(head.hash().to_string()),
+        })
+        .await
+        .unwrap();
+    assert_eq!(got.attrs.get("tag").map(String::as_str), Some("holiday"));
+    let listed = daemons[1]
+        .client
+        .call(LsRequest {
+            bucket_id,
+            path: None,
+            deep: Some(true),
+            min_version: Some(head.hash().to_string()),
+            starred: false,
+            filter: None,
+            sort: None,
+            page: Default::default(),
+        })
+        .await
+        .unwrap();
+    let attrs_of = |path: &str| {
+        listed
+            .items
+            .iter()
+            .find(|item| item.path == path)
+            .map(|item| item.attrs.clone())
+            .unwrap()
+    };
+    assert_eq!(
+        attrs_of("/photos").get("album").map(String::as_str),
+        Some("summer")
+    );
+    assert_eq!(
+        attrs_of("/photos/beach.jpg").get("tag").map(String::as_str),
+        Some("holiday")
+    );
+
+    // Attributes on a missing path are a 404
+    let missing = daemons[1]
+        .client
+        .call(set("/nope.txt", "tag", "x"))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        missing,
+        ApiError::HttpStatus(status, _) if status == reqwest::StatusCode::NOT_FOUND
+    ));
+
+    for daemon in daemons {
+        daemon.stop().await;
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_stale_expected_version_conflicts() {
+    let mut daemons = start_daemons(1).await;
+    let daemon = &mut daemons[0];
+
+    let bucket_id = daemon.create_bucket("occ").await;
+    let base = daemon.add_file(bucket_id, "/a.txt", b"a").await;
+
+    // First writer against `base` wins
+    let mkdir = |path: &str| MkdirRequest {
+        bucket_id,
+        path: path.to_string(),
+        expected_version: Some(base.hash().to_string()),
+    };
+    let won = daemon.client.call(mkdir("/first")).await.unwrap();
+
+    // Second writer against `base` is told where the head moved
+    match daemon.client.call(mkdir("/second")).await {
+        Err(ApiError::HttpStatus(status, body)) => {
+            assert_eq!(status, reqwest::StatusCode::CONFLICT);
+            assert_eq!(body.code, ErrorCode::HeadMoved);
+            assert!(body.retryable);
+            let moved: HeadMoved = serde_json::from_value(body.details.unwrap()).unwrap();
+            assert_eq!(moved.current_version, won.link.hash().to_string());
+        }
+        other => panic!("expected a conflict, got {:?}", other.map(|r| r.path)),
+    }
+    assert_eq!(daemon.head(bucket_id).await, Some(won.link));
+
+    for daemon in daemons {
+        daemon.stop().await;
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "forked heads at the same height hit the bucket_log (bucket_id, height) unique constraint"]
+async fn test_concurrent_edits_converge() {
+    let mut daemons = start_daemons(2).await;
+
+    let bucket_id = daemons[0].create_bucket("concurrent").await;
+    let (alice, bob) = daemons.split_at_mut(1);
+    alice[0].share(bucket_id, &bob[0], ShareRole::Owner).await;
+    assert_converged(&mut daemons, bucket_id).await;
+
+    // Both owners edit before hearing from each other
+    daemons[0]
+        .add_file(bucket_id, "/a.txt", b"from alice")
+        .await;
+    daemons[1].add_file(bucket_id, "/b.txt", b"from bob").await;
+
+    assert_converged(&mut daemons, bucket_id).await;
+    for daemon in daemons.iter_mut() {
+        daemon
+            .wait_for_file(bucket_id, "/a.txt", b"from alice")
+            .await;
+        daemon.wait_for_file(bucket_id, "/b.txt", b"from bob").await;
+    }
+
+    for daemon in daemons {
+        daemon.stop().await;
+    }
+}
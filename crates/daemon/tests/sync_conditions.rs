@@ -0,0 +1,90 @@
+//! Sync deferral on battery power and metered networks
+
+mod common;
+
+use jax_daemon::http_server::api::v0::bucket::share::ShareRole;
+use jax_daemon::http_server::api::v0::sync::{SyncConditionsRequest, SyncStatusRequest};
+
+use crate::common::{assert_converged, start_daemons, wait_for, TestDaemon};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reported_conditions() {
+    let mut daemon = TestDaemon::start().await;
+
+    let status = daemon
+        .client
+        .call(SyncConditionsRequest {
+            on_battery: true,
+            battery_percent: Some(35),
+            metered: true,
+            clear: false,
+        })
+        .await
+        .unwrap();
+    assert!(status.conditions_reported);
+    assert!(status.conditions.on_battery);
+    assert_eq!(status.conditions.battery_percent, Some(35));
+    assert_eq!(status.interval_multiplier, 4);
+    assert!(status.downloads_deferred);
+
+    let status = daemon
+        .client
+        .call(SyncConditionsRequest {
+            clear: true,
+            ..SyncConditionsRequest::default()
+        })
+        .await
+        .unwrap();
+    assert!(!status.conditions_reported);
+
+    daemon.stop().await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_downloads_wait_for_unmetered_network() {
+    let mut daemons = start_daemons(2).await;
+    daemons[1]
+        .client
+        .call(SyncConditionsRequest {
+            metered: true,
+            ..SyncConditionsRequest::default()
+        })
+        .await
+        .unwrap();
+
+    let bucket_id = daemons[0].create_bucket("shared").await;
+    daemons[0]
+        .add_file(bucket_id, "/hello.txt", b"hello from alice")
+        .await;
+    let (alice, bob) = daemons.split_at_mut(1);
+    alice[0].share(bucket_id, &bob[0], ShareRole::Owner).await;
+
+    // Metadata still syncs while the content waits
+    assert_converged(&mut daemons, bucket_id).await;
+    let client = daemons[1].client.clone();
+    wait_for("pins download to be deferred", || {
+        let mut client = client.clone();
+        async move {
+            client
+                .call(SyncStatusRequest {})
+                .await
+                .is_ok_and(|status| status.deferred_jobs > 0)
+        }
+    })
+    .await;
+
+    daemons[1]
+        .client
+        .call(SyncConditionsRequest::default())
+        .await
+        .unwrap();
+    daemons[1]
+        .wait_for_file(bucket_id, "/hello.txt", b"hello from alice")
+        .await;
+    let status = daemons[1].client.call(SyncStatusRequest {}).await.unwrap();
+    assert_eq!(status.deferred_jobs, 0);
+
+    for daemon in daemons {
+        daemon.stop().await;
+    }
+}
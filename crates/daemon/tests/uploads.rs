@@ -0,0 +1,131 @@
+//! Uploads through `bucket/add`, streamed into the bucket as they arrive
+
+mod common;
+
+use reqwest::{multipart, StatusCode};
+use uuid::Uuid;
+
+use jax_daemon::http_server::api::v0::bucket::add::AddResponse;
+use jax_daemon::http_server::error::{ErrorCode, ErrorEnvelope};
+
+use crate::common::TestDaemon;
+
+fn pseudo_random(len: usize) -> Vec<u8> {
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_large_uploads_stream_into_the_bucket() {
+    let mut daemon = TestDaemon::start().await;
+    let bucket_id = daemon.create_bucket("uploads").await;
+
+    // Several chunks' worth, cut and encrypted as the body arrives
+    let large = pseudo_random(9 * 1024 * 1024 + 123);
+    daemon.add_file(bucket_id, "/large.bin", &large).await;
+    assert_eq!(daemon.cat(bucket_id, "/large.bin").await.unwrap(), large);
+}
+
+/// Post an add form, files before or after the bucket fields
+async fn post_add(daemon: &TestDaemon, form: multipart::Form) -> reqwest::Response {
+    let url = daemon.client.base_url().join("/api/v0/bucket/add").unwrap();
+    daemon
+        .client
+        .http_client()
+        .post(url)
+        .multipart(form)
+        .send()
+        .await
+        .unwrap()
+}
+
+fn add_form(bucket_id: Uuid, name: &str, data: Vec<u8>) -> multipart::Form {
+    multipart::Form::new()
+        .text("bucket_id", bucket_id.to_string())
+        .text("mount_path", "/")
+        .part(
+            "file",
+            multipart::Part::bytes(data).file_name(name.to_string()),
+        )
+}
+
+#[tokio::test]
+async fn test_files_before_the_bucket_fields_are_spooled() {
+    let mut daemon = TestDaemon::start().await;
+    let bucket_id = daemon.create_bucket("uploads").await;
+
+    let form = multipart::Form::new()
+        .part(
+            "file",
+            multipart::Part::bytes(b"sent first".to_vec()).file_name("first.txt"),
+        )
+        .text("bucket_id", bucket_id.to_string())
+        .text("mount_path", "/docs")
+        .part(
+            "file",
+            multipart::Part::bytes(b"sent last".to_vec()).file_name("last.txt"),
+        );
+    let response = post_add(&daemon, form).await;
+    assert!(response.status().is_success());
+    let added: AddResponse = response.json().await.unwrap();
+    assert_eq!(added.successful_files, 2);
+    let sizes: Vec<_> = added.files.iter().map(|file| file.size).collect();
+    assert_eq!(sizes, [9, 10]);
+
+    assert_eq!(
+        daemon.cat(bucket_id, "/docs/first.txt").await.unwrap(),
+        b"sent first"
+    );
+    assert_eq!(
+        daemon.cat(bucket_id, "/docs/last.txt").await.unwrap(),
+        b"sent last"
+    );
+}
+
+#[tokio::test]
+async fn test_uploads_over_the_limit_are_refused() {
+    let mut daemon = TestDaemon::start_with(|config| {
+        config.upload_limits.max_bytes = 16 * 1024;
+    })
+    .await;
+    let bucket_id = daemon.create_bucket("limited").await;
+
+    daemon.add_file(bucket_id, "/small.bin", &[1; 1024]).await;
+
+    // Streamed files are cut off once they pass the limit (bodies kept small
+    //  enough to be sent whole before the early answer)
+    let response = post_add(
+        &daemon,
+        add_form(bucket_id, "large.bin", vec![2; 48 * 1024]),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    let envelope: ErrorEnvelope = response.json().await.unwrap();
+    assert_eq!(envelope.error.code, ErrorCode::PayloadTooLarge);
+    assert_eq!(envelope.error.details.unwrap()["max_bytes"], 16 * 1024);
+
+    // And so are files spooled before the bucket is known
+    let form = multipart::Form::new()
+        .part(
+            "file",
+            multipart::Part::bytes(vec![3; 48 * 1024]).file_name("early.bin"),
+        )
+        .text("bucket_id", bucket_id.to_string())
+        .text("mount_path", "/");
+    let response = post_add(&daemon, form).await;
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+    assert!(daemon.cat(bucket_id, "/large.bin").await.is_err());
+    assert!(daemon.cat(bucket_id, "/early.bin").await.is_err());
+    assert_eq!(
+        daemon.cat(bucket_id, "/small.bin").await.unwrap(),
+        vec![1; 1024]
+    );
+}
@@ -0,0 +1,80 @@
+//! Serving published versions at friendly version URLs (`@v3`, `@2024-01-01`)
+
+mod common;
+
+use reqwest::StatusCode;
+
+use jax_daemon::http_server::api::v0::bucket::PublishRequest;
+
+use crate::common::{wait_for, TestDaemon};
+
+async fn publish(daemon: &mut TestDaemon, bucket_id: uuid::Uuid) -> String {
+    daemon
+        .client
+        .call(PublishRequest {
+            bucket_id,
+            rotate: false,
+            expected_version: None,
+            index: Default::default(),
+        })
+        .await
+        .unwrap()
+        .version_path
+        .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_version_urls() {
+    let mut daemon = TestDaemon::start().await;
+    let bucket_id = daemon.create_bucket("site").await;
+    daemon.add_file(bucket_id, "/a.txt", b"one").await;
+    let first = publish(&mut daemon, bucket_id).await;
+    daemon.add_file(bucket_id, "/a.txt", b"two").await;
+    let second = publish(&mut daemon, bucket_id).await;
+    assert_ne!(first, second);
+    assert!(first.starts_with(&format!("/gw/{}/@v", bucket_id)));
+    let livez = daemon.gateway_url.join("/_status/livez").unwrap();
+    wait_for("gateway to come up", || {
+        let livez = livez.clone();
+        async move { reqwest::get(livez).await.is_ok() }
+    })
+    .await;
+    let get = |path: String| reqwest::get(daemon.gateway_url.join(&path).unwrap());
+
+    // Versions by height
+    let response = get(format!("{}/a.txt?download=true", first)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.bytes().await.unwrap().as_ref(), b"one");
+    let response = get(format!("{}/a.txt?download=true", second))
+        .await
+        .unwrap();
+    assert_eq!(response.bytes().await.unwrap().as_ref(), b"two");
+
+    // Pages of a version link within it
+    let response = reqwest::Client::new()
+        .get(daemon.gateway_url.join(&format!("{}/", first)).unwrap())
+        .header("accept", "text/html")
+        .send()
+        .await
+        .unwrap();
+    let html = response.text().await.unwrap();
+    assert!(html.contains(&format!(r#"href="{}/a.txt?view=true""#, first)));
+
+    // Versions by date: the latest published by then
+    let response = get(format!("/gw/{}/@2999-01-01/a.txt?download=true", bucket_id))
+        .await
+        .unwrap();
+    assert_eq!(response.bytes().await.unwrap().as_ref(), b"two");
+    let response = get(format!("/gw/{}/@2000-01-01/a.txt", bucket_id))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // Heights of unpublished versions, and versions that aren't versions
+    let response = get(format!("/gw/{}/@v0/a.txt", bucket_id)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = get(format!("/gw/{}/@latest/a.txt", bucket_id))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,217 @@
+//! Webhooks: signed HTTP callbacks on bucket events
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use axum::Router;
+use uuid::Uuid;
+
+use jax_daemon::http_server::api::v0::bucket::share::ShareRole;
+use jax_daemon::http_server::api::v0::webhook::{
+    WebhookAddRequest, WebhookListRequest, WebhookRemoveRequest, WebhookTestRequest,
+};
+use jax_daemon::webhooks::{sign, WebhookEvent, WebhookPayload};
+
+use crate::common::{assert_converged, start_daemons, wait_for};
+
+const SECRET: &str = "s3cret";
+
+/// A delivery as received: the path it was POSTed to and its payload
+type Delivery = (String, WebhookPayload);
+
+/// Collects the deliveries POSTed to `/<name>`, checking their signature.
+/// `/flaky` fails the first delivery it gets.
+#[derive(Clone, Default)]
+struct Receiver {
+    deliveries: Arc<Mutex<Vec<Delivery>>>,
+    flaky_failed: Arc<Mutex<Option<Uuid>>>,
+}
+
+impl Receiver {
+    async fn start() -> (Self, String) {
+        let receiver = Self::default();
+        let router = Router::new()
+            .route("/:name", post(receive))
+            .with_state(receiver.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        (receiver, url)
+    }
+
+    fn received(&self, name: &str, event: WebhookEvent) -> Vec<WebhookPayload> {
+        self.deliveries
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(path, payload)| path == name && payload.event == event)
+            .map(|(_, payload)| payload.clone())
+            .collect()
+    }
+}
+
+async fn receive(
+    State(receiver): State<Receiver>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+    body: axum::body::Bytes,
+) -> StatusCode {
+    let signature = headers["x-jax-signature"].to_str().unwrap();
+    assert_eq!(signature, sign(SECRET, &body));
+    let payload: WebhookPayload = serde_json::from_slice(&body).unwrap();
+    assert_eq!(headers["x-jax-event"], payload.event.as_str());
+    assert_eq!(headers["x-jax-delivery"], payload.id.to_string().as_str());
+
+    if name == "flaky" {
+        let mut failed = receiver.flaky_failed.lock().unwrap();
+        if failed.is_none() {
+            *failed = Some(payload.id);
+            return StatusCode::INTERNAL_SERVER_ERROR;
+        }
+    }
+    receiver.deliveries.lock().unwrap().push((name, payload));
+    StatusCode::OK
+}
+
+fn webhook(url: String, bucket_id: Option<Uuid>, events: Vec<WebhookEvent>) -> WebhookAddRequest {
+    WebhookAddRequest {
+        url,
+        bucket_id,
+        events,
+        secret: Some(SECRET.to_string()),
+    }
+}
+
+async fn wait_for_delivery(receiver: &Receiver, name: &str, event: WebhookEvent, bucket_id: Uuid) {
+    let what = format!("{} delivery to {}", event, name);
+    wait_for(&what, || {
+        let delivered = receiver
+            .received(name, event)
+            .iter()
+            .any(|payload| payload.bucket_id == Some(bucket_id));
+        async move { delivered }
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_webhook_deliveries() {
+    let mut daemons = start_daemons(2).await;
+    let (receiver, url) = Receiver::start().await;
+
+    let all = daemons[0]
+        .client
+        .call(webhook(format!("{}/all", url), None, vec![]))
+        .await
+        .unwrap();
+    assert_eq!(all.secret, SECRET);
+    assert_eq!(all.webhook.events.len(), 3);
+
+    // Pings are delivered right away and answered synchronously
+    let ping = daemons[0]
+        .client
+        .call(WebhookTestRequest {
+            webhook_id: all.webhook.webhook_id,
+        })
+        .await
+        .unwrap();
+    assert_eq!(ping.status, Some(200));
+    assert_eq!(receiver.received("all", WebhookEvent::Ping).len(), 1);
+
+    let bucket_id = daemons[0].create_bucket("hooked").await;
+    let other = daemons[0].create_bucket("other").await;
+    let flaky = daemons[0]
+        .client
+        .call(webhook(
+            format!("{}/flaky", url),
+            Some(bucket_id),
+            vec![WebhookEvent::VersionCommitted],
+        ))
+        .await
+        .unwrap();
+    daemons[1]
+        .client
+        .call(webhook(
+            format!("{}/joined", url),
+            None,
+            vec![WebhookEvent::ShareAccepted],
+        ))
+        .await
+        .unwrap();
+
+    let link = daemons[0]
+        .add_file(bucket_id, "/report.txt", b"quarterly numbers")
+        .await;
+    daemons[0].add_file(other, "/skip.txt", b"not hooked").await;
+    wait_for_delivery(&receiver, "all", WebhookEvent::VersionCommitted, bucket_id).await;
+    let committed = receiver.received("all", WebhookEvent::VersionCommitted);
+    let added = committed
+        .iter()
+        .find(|payload| payload.operation.as_deref() == Some("bucket.add"))
+        .expect("an add was delivered");
+    assert_eq!(added.version, Some(link.hash().to_string()));
+    assert_eq!(added.actor.as_deref(), Some("api"));
+
+    // The failed delivery is retried with the same ID; other buckets'
+    // versions never reach the bucket's webhook
+    wait_for_delivery(
+        &receiver,
+        "flaky",
+        WebhookEvent::VersionCommitted,
+        bucket_id,
+    )
+    .await;
+    let retried = *receiver.flaky_failed.lock().unwrap();
+    let flaky_deliveries = receiver.received("flaky", WebhookEvent::VersionCommitted);
+    assert_eq!(Some(flaky_deliveries[0].id), retried);
+    assert!(flaky_deliveries
+        .iter()
+        .all(|payload| payload.bucket_id == Some(bucket_id)));
+    let listed = daemons[0]
+        .client
+        .call(WebhookListRequest {
+            bucket_id: Some(other),
+        })
+        .await
+        .unwrap()
+        .webhooks;
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].webhook_id, all.webhook.webhook_id);
+
+    // Sharing fires on the sharing node, and on the node that joins
+    let (alice, bob) = daemons.split_at_mut(1);
+    alice[0].share(bucket_id, &bob[0], ShareRole::Owner).await;
+    assert_converged(&mut daemons, bucket_id).await;
+    wait_for_delivery(&receiver, "all", WebhookEvent::ShareAccepted, bucket_id).await;
+    wait_for_delivery(&receiver, "joined", WebhookEvent::ShareAccepted, bucket_id).await;
+    let joined = receiver.received("joined", WebhookEvent::ShareAccepted);
+    assert_eq!(joined[0].operation.as_deref(), Some("bucket.join"));
+    assert_eq!(
+        joined[0].actor,
+        Some(format!("peer:{}", daemons[0].node_id()))
+    );
+
+    daemons[0]
+        .client
+        .call(WebhookRemoveRequest {
+            webhook_id: flaky.webhook.webhook_id,
+        })
+        .await
+        .unwrap();
+    let webhooks = daemons[0]
+        .client
+        .call(WebhookListRequest::default())
+        .await
+        .unwrap()
+        .webhooks;
+    assert_eq!(webhooks.len(), 1);
+    assert_eq!(webhooks[0].last_status, Some(200));
+
+    for daemon in daemons {
+        daemon.stop().await;
+    }
+}
@@ -0,0 +1,130 @@
+//! Workspaces: grouped buckets shared together
+
+mod common;
+
+use uuid::Uuid;
+
+use jax_daemon::http_server::api::client::ApiClient;
+use jax_daemon::http_server::api::v0::bucket::share::ShareRole;
+use jax_daemon::http_server::api::v0::bucket::ListRequest;
+use jax_daemon::http_server::api::v0::workspace::{
+    WorkspaceAddRequest, WorkspaceCreateRequest, WorkspaceListRequest, WorkspaceRemoveRequest,
+    WorkspaceShareRequest,
+};
+
+use crate::common::{assert_converged, start_daemons};
+
+async fn bucket_ids(client: &mut ApiClient, workspace: Option<&str>) -> Vec<Uuid> {
+    let mut ids: Vec<Uuid> = client
+        .call(ListRequest {
+            prefix: None,
+            filter: None,
+            sort: None,
+            page: Default::default(),
+            workspace: workspace.map(str::to_string),
+            starred: false,
+        })
+        .await
+        .unwrap()
+        .buckets
+        .into_iter()
+        .map(|b| b.bucket_id)
+        .collect();
+    ids.sort();
+    ids
+}
+
+async fn add(client: &mut ApiClient, workspace: &str, bucket_id: Uuid) -> usize {
+    client
+        .call(WorkspaceAddRequest {
+            workspace: workspace.to_string(),
+            bucket_id,
+        })
+        .await
+        .unwrap()
+        .shares
+        .len()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_workspace_shares_member_buckets() {
+    let mut daemons = start_daemons(2).await;
+    let bob_id = daemons[1].node_id();
+    let client = &mut daemons[0].client;
+
+    client
+        .call(WorkspaceCreateRequest {
+            name: "team".to_string(),
+        })
+        .await
+        .unwrap();
+    let docs = daemons[0].create_bucket("docs").await;
+    let photos = daemons[0].create_bucket("photos").await;
+    let private = daemons[0].create_bucket("private").await;
+    let client = &mut daemons[0].client;
+    assert_eq!(add(client, "team", docs).await, 0);
+    assert_eq!(add(client, "team", photos).await, 0);
+
+    let mut members = vec![docs, photos];
+    members.sort();
+    assert_eq!(bucket_ids(client, Some("team")).await, members);
+    assert_eq!(bucket_ids(client, None).await.len(), 3);
+
+    let workspaces = client
+        .call(WorkspaceListRequest::default())
+        .await
+        .unwrap()
+        .workspaces;
+    assert_eq!(workspaces.len(), 1);
+    assert_eq!(workspaces[0].bucket_count, 2);
+
+    let shared = client
+        .call(WorkspaceShareRequest {
+            workspace: "team".to_string(),
+            peer_public_key: bob_id,
+            role: ShareRole::Owner,
+        })
+        .await
+        .unwrap();
+    assert_eq!(shared.buckets.len(), 2);
+    assert!(shared.buckets.iter().all(|b| b.error.is_none()));
+    assert_converged(&mut daemons, docs).await;
+    assert_converged(&mut daemons, photos).await;
+
+    // Buckets added later reach the workspace's peers too
+    let notes = daemons[0].create_bucket("notes").await;
+    assert_eq!(add(&mut daemons[0].client, "team", notes).await, 1);
+    assert_converged(&mut daemons, notes).await;
+    assert!(daemons[1].head(private).await.is_none());
+
+    daemons[0]
+        .client
+        .call(WorkspaceRemoveRequest {
+            workspace: "team".to_string(),
+            bucket_id: notes,
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        bucket_ids(&mut daemons[0].client, Some("team")).await,
+        members
+    );
+
+    // Filtering by an unknown workspace is an error rather than no buckets
+    assert!(daemons[0]
+        .client
+        .call(ListRequest {
+            prefix: None,
+            filter: None,
+            sort: None,
+            page: Default::default(),
+            workspace: Some("nope".to_string()),
+            starred: false,
+        })
+        .await
+        .is_err());
+
+    for daemon in daemons {
+        daemon.stop().await;
+    }
+}
@@ -7,16 +7,24 @@ use std::io::Cursor;
 use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
 use time::OffsetDateTime;
+use tokio::io::AsyncWriteExt;
 use uuid::Uuid;
 
 use common::linked_data::{Hash, Link};
-use common::mount::Mount;
-use jax_daemon::ServiceState;
+use common::mount::{ChunkList, Mount, NodeLink};
+use jax_daemon::audit::{Actor, AuditEvent};
+use jax_daemon::bucket_index::IndexUpdate;
+use jax_daemon::http_server::api::v0::bucket::photos::{PhotosRequest, PhotosResponse};
+use jax_daemon::http_server::error::ErrorBody;
+use jax_daemon::{ServiceState, SyncPolicy};
 
 use crate::AppState;
 
+/// Event reporting the progress of [`download_paths`]
+pub const DOWNLOAD_PROGRESS_EVENT: &str = "download-progress";
+
 /// Bucket information returned to the frontend
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BucketInfo {
@@ -36,6 +44,9 @@ pub struct FileEntry {
     pub is_dir: bool,
     pub mime_type: String,
     pub link_hash: String,
+    /// Public keys of owners marked as editing the file right now
+    #[serde(default)]
+    pub editing: Vec<String>,
 }
 
 /// Result of reading a file with cat
@@ -142,7 +153,7 @@ pub async fn create_bucket(state: State<'_, AppState>, name: String) -> Result<B
 
     if !response.status().is_success() {
         let status = response.status();
-        let body = response.text().await.unwrap_or_default();
+        let body = ErrorBody::parse(status, &response.text().await.unwrap_or_default());
         return Err(format!("Failed to create bucket ({}): {}", status, body));
     }
 
@@ -177,11 +188,19 @@ pub async fn delete_bucket(state: State<'_, AppState>, bucket_id: String) -> Res
         .await
         .map_err(|e| e.to_string())?;
 
-    service
+    let link = service
         .peer()
         .save_mount(&mount, false)
         .await
         .map_err(|e| e.to_string())?;
+    service
+        .audit(
+            AuditEvent::new(Actor::Desktop, "bucket.delete")
+                .bucket(bucket_uuid)
+                .path("/")
+                .version(&link),
+        )
+        .await;
 
     Ok(())
 }
@@ -206,6 +225,7 @@ pub async fn ls(
         .ls(&PathBuf::from(&path))
         .await
         .map_err(|e| e.to_string())?;
+    let editing = service.peer().editing().active(&bucket_uuid);
 
     Ok(items
         .into_iter()
@@ -224,6 +244,11 @@ pub async fn ls(
             } else {
                 format!("{}/{}", path.trim_end_matches('/'), name)
             };
+            let editing = editing
+                .iter()
+                .filter(|mark| mark.path == Path::new(&full_path))
+                .map(|mark| mark.editor.to_hex())
+                .collect();
 
             FileEntry {
                 path: full_path,
@@ -231,6 +256,7 @@ pub async fn ls(
                 is_dir,
                 mime_type,
                 link_hash,
+                editing,
             }
         })
         .collect())
@@ -295,11 +321,19 @@ pub async fn add_file(
         .await
         .map_err(|e| e.to_string())?;
 
-    service
+    let link = service
         .peer()
         .save_mount(&mount, false)
         .await
         .map_err(|e| e.to_string())?;
+    service
+        .audit(
+            AuditEvent::new(Actor::Desktop, "bucket.add")
+                .bucket(bucket_uuid)
+                .path(path)
+                .version(&link),
+        )
+        .await;
 
     Ok(())
 }
@@ -329,11 +363,19 @@ pub async fn update_file(
         .await
         .map_err(|e| e.to_string())?;
 
-    service
+    let link = service
         .peer()
         .save_mount(&mount, false)
         .await
         .map_err(|e| e.to_string())?;
+    service
+        .audit(
+            AuditEvent::new(Actor::Desktop, "bucket.update")
+                .bucket(bucket_uuid)
+                .path(path)
+                .version(&link),
+        )
+        .await;
 
     Ok(())
 }
@@ -360,11 +402,20 @@ pub async fn rename_path(
         .await
         .map_err(|e| e.to_string())?;
 
-    service
+    let link = service
         .peer()
         .save_mount(&mount, false)
         .await
         .map_err(|e| e.to_string())?;
+    service
+        .audit(
+            AuditEvent::new(Actor::Desktop, "bucket.rename")
+                .bucket(bucket_uuid)
+                .path(old_path)
+                .detail(new_path)
+                .version(&link),
+        )
+        .await;
 
     Ok(())
 }
@@ -391,11 +442,20 @@ pub async fn move_path(
         .await
         .map_err(|e| e.to_string())?;
 
-    service
+    let link = service
         .peer()
         .save_mount(&mount, false)
         .await
         .map_err(|e| e.to_string())?;
+    service
+        .audit(
+            AuditEvent::new(Actor::Desktop, "bucket.mv")
+                .bucket(bucket_uuid)
+                .path(source_path)
+                .detail(dest_path)
+                .version(&link),
+        )
+        .await;
 
     Ok(())
 }
@@ -434,13 +494,63 @@ pub async fn share_bucket(
 
     if !response.status().is_success() {
         let status = response.status();
-        let body = response.text().await.unwrap_or_default();
+        let body = ErrorBody::parse(status, &response.text().await.unwrap_or_default());
         return Err(format!("Failed to share bucket ({}): {}", status, body));
     }
 
     Ok(())
 }
 
+/// Share a bucket read-only with a new temporary identity until `ttl_secs`
+/// from now, returning the ticket to hand to the recipient (uses HTTP like
+/// [`share_bucket`])
+#[tauri::command]
+pub async fn share_bucket_ephemeral(
+    state: State<'_, AppState>,
+    bucket_id: String,
+    ttl_secs: u64,
+) -> Result<String, String> {
+    let base_url = get_daemon_url(&state).await?;
+    let url = format!("{}/api/v0/bucket/share-ephemeral", base_url);
+
+    let bucket_uuid = parse_bucket_id(&bucket_id)?;
+
+    #[derive(Serialize)]
+    struct ShareEphemeralRequest {
+        bucket_id: Uuid,
+        ttl_secs: u64,
+    }
+
+    #[derive(Deserialize)]
+    struct ShareEphemeralResponse {
+        ticket: String,
+    }
+
+    let client = reqwest::Client::new();
+    let response = client
+        .post(&url)
+        .json(&ShareEphemeralRequest {
+            bucket_id: bucket_uuid,
+            ttl_secs,
+        })
+        .send()
+        .await
+        .map_err(|e| format!("Failed to connect to daemon: {}", e))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = ErrorBody::parse(status, &response.text().await.unwrap_or_default());
+        return Err(format!("Failed to share bucket ({}): {}", status, body));
+    }
+
+    let share_response: ShareEphemeralResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    Ok(share_response.ticket)
+}
+
 /// Check if the current HEAD of a bucket is published
 #[tauri::command]
 pub async fn is_published(state: State<'_, AppState>, bucket_id: String) -> Result<bool, String> {
@@ -458,21 +568,71 @@ pub async fn is_published(state: State<'_, AppState>, bucket_id: String) -> Resu
 
 /// Publish a bucket
 #[tauri::command]
-pub async fn publish_bucket(state: State<'_, AppState>, bucket_id: String) -> Result<(), String> {
+pub async fn publish_bucket(
+    state: State<'_, AppState>,
+    bucket_id: String,
+) -> Result<String, String> {
     let service = get_service(&state).await?;
     let bucket_uuid = parse_bucket_id(&bucket_id)?;
 
-    let mount = service
-        .peer()
-        .mount(bucket_uuid)
+    let publication = service
+        .publish_bucket(bucket_uuid, false, None, &IndexUpdate::default())
         .await
         .map_err(|e| e.to_string())?;
+    service
+        .audit(
+            AuditEvent::new(Actor::Desktop, "bucket.publish")
+                .bucket(bucket_uuid)
+                .version(&publication.link),
+        )
+        .await;
+
+    Ok(publication.gateway_path)
+}
+
+/// Revoke a bucket's public link and earlier published versions, publishing
+/// the current version at a new gateway path
+#[tauri::command]
+pub async fn rotate_public_link(
+    state: State<'_, AppState>,
+    bucket_id: String,
+) -> Result<String, String> {
+    let service = get_service(&state).await?;
+    let bucket_uuid = parse_bucket_id(&bucket_id)?;
 
+    let publication = service
+        .publish_bucket(bucket_uuid, true, None, &IndexUpdate::default())
+        .await
+        .map_err(|e| e.to_string())?;
     service
-        .peer()
-        .save_mount(&mount, true)
+        .audit(
+            AuditEvent::new(Actor::Desktop, "bucket.publish")
+                .bucket(bucket_uuid)
+                .detail("rotated")
+                .version(&publication.link),
+        )
+        .await;
+
+    Ok(publication.gateway_path)
+}
+
+/// Stop publishing a bucket on the gateway
+#[tauri::command]
+pub async fn unpublish_bucket(state: State<'_, AppState>, bucket_id: String) -> Result<(), String> {
+    let service = get_service(&state).await?;
+    let bucket_uuid = parse_bucket_id(&bucket_id)?;
+
+    let link = service
+        .unpublish_bucket(bucket_uuid, None)
         .await
         .map_err(|e| e.to_string())?;
+    service
+        .audit(
+            AuditEvent::new(Actor::Desktop, "bucket.unpublish")
+                .bucket(bucket_uuid)
+                .version(&link),
+        )
+        .await;
 
     Ok(())
 }
@@ -513,7 +673,7 @@ pub async fn ping_peer(
 
     if !response.status().is_success() {
         let status = response.status();
-        let body = response.text().await.unwrap_or_default();
+        let body = ErrorBody::parse(status, &response.text().await.unwrap_or_default());
         return Err(format!("Failed to ping peer ({}): {}", status, body));
     }
 
@@ -542,6 +702,8 @@ pub async fn upload_native_files(
         .await
         .map_err(|e| e.to_string())?;
 
+    let limit = service.upload_limits().for_bucket(&bucket_uuid);
+    let mut added = Vec::with_capacity(file_paths.len());
     for file_path in file_paths {
         let path = Path::new(&file_path);
         let file_name = path
@@ -550,9 +712,22 @@ pub async fn upload_native_files(
             .unwrap_or("file")
             .to_string();
 
-        let data = tokio::fs::read(&file_path)
+        // Streamed into the bucket a chunk at a time, so files of any size
+        //  upload with bounded memory
+        let data = tokio::fs::File::open(&file_path)
             .await
             .map_err(|e| format!("Failed to read file '{}': {}", file_path, e))?;
+        let size = data
+            .metadata()
+            .await
+            .map_err(|e| format!("Failed to read file '{}': {}", file_path, e))?
+            .len();
+        if size > limit {
+            return Err(format!(
+                "'{}' is larger than the upload limit of {} bytes",
+                file_name, limit
+            ));
+        }
 
         let dest_path = if mount_path.ends_with('/') {
             format!("{}{}", mount_path, file_name)
@@ -561,20 +736,161 @@ pub async fn upload_native_files(
         };
 
         mount
-            .add(&PathBuf::from(&dest_path), Cursor::new(data))
+            .add_reader(&PathBuf::from(&dest_path), data)
             .await
             .map_err(|e| format!("Failed to add '{}': {}", file_name, e))?;
+        added.push(dest_path);
     }
 
-    service
+    let link = service
         .peer()
         .save_mount(&mount, false)
         .await
         .map_err(|e| e.to_string())?;
+    for path in added {
+        service
+            .audit(
+                AuditEvent::new(Actor::Desktop, "bucket.add")
+                    .bucket(bucket_uuid)
+                    .path(path)
+                    .version(&link),
+            )
+            .await;
+    }
 
     Ok(())
 }
 
+/// Progress of a [`download_paths`] call, emitted as each chunk or file is written
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadProgress {
+    pub bucket_id: Uuid,
+    /// Bucket path of the file being written
+    pub path: String,
+    pub files_done: usize,
+    pub files_total: usize,
+    pub bytes_done: u64,
+}
+
+/// Result of a [`download_paths`] call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadResult {
+    pub files: usize,
+    pub bytes: u64,
+}
+
+/// Download files and directories (recursively) to a folder on disk, the
+/// counterpart of `upload_native_files`
+///
+/// Each path is written under `dest_dir` by its own name, directories with
+/// their contents below them. Chunked files are written a chunk at a time,
+/// and a [`DOWNLOAD_PROGRESS_EVENT`] is emitted after each chunk and file.
+#[tauri::command]
+pub async fn download_paths(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    bucket_id: String,
+    paths: Vec<String>,
+    dest_dir: String,
+) -> Result<DownloadResult, String> {
+    let service = get_service(&state).await?;
+    let bucket_uuid = parse_bucket_id(&bucket_id)?;
+
+    let mount = service
+        .peer()
+        .mount_for_read(bucket_uuid)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // Resolve every file first so progress has a total
+    let dest_dir = PathBuf::from(dest_dir);
+    let mut files = Vec::new();
+    for path in &paths {
+        let path = PathBuf::from(path);
+        let name = path
+            .file_name()
+            .ok_or_else(|| format!("Cannot download '{}'", path.display()))?;
+        let node_link = mount.get(&path).await.map_err(|e| e.to_string())?;
+        if node_link.is_dir() {
+            let items = mount.ls_deep(&path).await.map_err(|e| e.to_string())?;
+            for (relative, node_link) in items {
+                if node_link.is_data() {
+                    files.push((path.join(&relative), dest_dir.join(name).join(relative)));
+                }
+            }
+        } else {
+            files.push((path.clone(), dest_dir.join(name)));
+        }
+    }
+
+    let mut progress = DownloadProgress {
+        bucket_id: bucket_uuid,
+        path: String::new(),
+        files_done: 0,
+        files_total: files.len(),
+        bytes_done: 0,
+    };
+    for (path, dest) in files {
+        // Bucket entries are plain names, but never write outside dest_dir
+        if dest
+            .components()
+            .any(|c| matches!(c, std::path::Component::ParentDir))
+        {
+            return Err(format!("Refusing to write '{}'", dest.display()));
+        }
+        progress.path = path.to_string_lossy().to_string();
+        if let Some(parent) = dest.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("Failed to create '{}': {}", parent.display(), e))?;
+        }
+        let mut file = tokio::fs::File::create(&dest)
+            .await
+            .map_err(|e| format!("Failed to create '{}': {}", dest.display(), e))?;
+
+        match mount.get(&path).await.map_err(|e| e.to_string())? {
+            NodeLink::Data(link, secret, data) if data.is_chunked() => {
+                let blobs = mount.blobs();
+                let list = ChunkList::load(&blobs, &link, &secret)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                for chunk in list.chunks() {
+                    let bytes = chunk.read(&blobs).await.map_err(|e| e.to_string())?;
+                    write_download(&mut file, &dest, &bytes).await?;
+                    progress.bytes_done += bytes.len() as u64;
+                    let _ = app.emit(DOWNLOAD_PROGRESS_EVENT, &progress);
+                }
+            }
+            _ => {
+                let bytes = mount.cat(&path).await.map_err(|e| e.to_string())?;
+                write_download(&mut file, &dest, &bytes).await?;
+                progress.bytes_done += bytes.len() as u64;
+            }
+        }
+        file.flush()
+            .await
+            .map_err(|e| format!("Failed to write '{}': {}", dest.display(), e))?;
+
+        progress.files_done += 1;
+        let _ = app.emit(DOWNLOAD_PROGRESS_EVENT, &progress);
+    }
+
+    Ok(DownloadResult {
+        files: progress.files_done,
+        bytes: progress.bytes_done,
+    })
+}
+
+async fn write_download(
+    file: &mut tokio::fs::File,
+    dest: &Path,
+    bytes: &[u8],
+) -> Result<(), String> {
+    file.write_all(bytes)
+        .await
+        .map_err(|e| format!("Failed to write '{}': {}", dest.display(), e))
+}
+
 /// Create a directory
 #[tauri::command]
 pub async fn mkdir(
@@ -596,11 +912,19 @@ pub async fn mkdir(
         .await
         .map_err(|e| e.to_string())?;
 
-    service
+    let link = service
         .peer()
         .save_mount(&mount, false)
         .await
         .map_err(|e| e.to_string())?;
+    service
+        .audit(
+            AuditEvent::new(Actor::Desktop, "bucket.mkdir")
+                .bucket(bucket_uuid)
+                .path(path)
+                .version(&link),
+        )
+        .await;
 
     Ok(())
 }
@@ -626,11 +950,19 @@ pub async fn delete_path(
         .await
         .map_err(|e| e.to_string())?;
 
-    service
+    let link = service
         .peer()
         .save_mount(&mount, false)
         .await
         .map_err(|e| e.to_string())?;
+    service
+        .audit(
+            AuditEvent::new(Actor::Desktop, "bucket.delete")
+                .bucket(bucket_uuid)
+                .path(path)
+                .version(&link),
+        )
+        .await;
 
     Ok(())
 }
@@ -704,12 +1036,14 @@ pub async fn ls_at_version(
                 format!("{}/{}", path.trim_end_matches('/'), name)
             };
 
+            // Old versions aren't being edited
             FileEntry {
                 path: full_path,
                 name,
                 is_dir,
                 mime_type,
                 link_hash,
+                editing: Vec::new(),
             }
         })
         .collect())
@@ -799,3 +1133,87 @@ pub async fn cat_at_version(
         size,
     })
 }
+
+/// Get a bucket's sync policy (the default one if none is set)
+#[tauri::command]
+pub async fn get_sync_policy(
+    state: State<'_, AppState>,
+    bucket_id: String,
+) -> Result<SyncPolicy, String> {
+    let service = get_service(&state).await?;
+    let bucket_uuid = parse_bucket_id(&bucket_id)?;
+    Ok(service.sync_policies().get(&bucket_uuid))
+}
+
+/// Replace a bucket's sync policy
+#[tauri::command]
+pub async fn set_sync_policy(
+    state: State<'_, AppState>,
+    bucket_id: String,
+    policy: SyncPolicy,
+) -> Result<(), String> {
+    let service = get_service(&state).await?;
+    let bucket_uuid = parse_bucket_id(&bucket_id)?;
+    policy.validate().map_err(|e| e.to_string())?;
+    service
+        .set_sync_policy(bucket_uuid, policy)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+    service
+        .audit(AuditEvent::new(Actor::Desktop, "sync.policy.set").bucket(bucket_uuid))
+        .await;
+    Ok(())
+}
+
+/// Sync a bucket now by pinging its peers, regardless of its schedule
+#[tauri::command]
+pub async fn sync_bucket_now(state: State<'_, AppState>, bucket_id: String) -> Result<(), String> {
+    let service = get_service(&state).await?;
+    let bucket_uuid = parse_bucket_id(&bucket_id)?;
+    if service.sync_policies().get(&bucket_uuid).paused {
+        return Err("Syncing this bucket is paused".to_string());
+    }
+    service
+        .peer()
+        .ping(bucket_uuid)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Page through the photos of a bucket, or of every bucket, newest capture
+/// date first (still uses HTTP — the daemon keeps the timeline index up to date)
+#[tauri::command]
+pub async fn photo_timeline(
+    state: State<'_, AppState>,
+    bucket_id: Option<String>,
+    offset: Option<u32>,
+    limit: Option<u32>,
+) -> Result<PhotosResponse, String> {
+    let base_url = get_daemon_url(&state).await?;
+    let url = format!("{}/api/v0/bucket/photos", base_url);
+
+    let bucket_id = bucket_id.as_deref().map(parse_bucket_id).transpose()?;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .post(&url)
+        .json(&PhotosRequest {
+            bucket_id,
+            offset,
+            limit,
+        })
+        .send()
+        .await
+        .map_err(|e| format!("Failed to connect to daemon: {}", e))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = ErrorBody::parse(status, &response.text().await.unwrap_or_default());
+        return Err(format!("Failed to list photos ({}): {}", status, body));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))
+}
@@ -0,0 +1,63 @@
+//! Crash report IPC commands
+//!
+//! Let the frontend manage the crash reporting opt-in and review, send or
+//! delete stored reports.
+
+use tauri::{AppHandle, State};
+
+use crate::crash::{CrashReport, CrashReporter};
+
+/// Whether crash reporting is enabled
+#[tauri::command]
+pub async fn get_crash_reporting_enabled(
+    reporter: State<'_, CrashReporter>,
+) -> Result<bool, String> {
+    Ok(reporter.is_enabled())
+}
+
+/// Opt in to or out of crash reporting
+#[tauri::command]
+pub async fn set_crash_reporting_enabled(
+    reporter: State<'_, CrashReporter>,
+    enabled: bool,
+) -> Result<(), String> {
+    reporter.set_enabled(enabled).map_err(|e| e.to_string())
+}
+
+/// List stored crash reports, newest first
+#[tauri::command]
+pub async fn list_crash_reports(
+    reporter: State<'_, CrashReporter>,
+) -> Result<Vec<CrashReport>, String> {
+    reporter.list().map_err(|e| e.to_string())
+}
+
+/// Delete a stored crash report
+#[tauri::command]
+pub async fn delete_crash_report(
+    reporter: State<'_, CrashReporter>,
+    id: String,
+) -> Result<(), String> {
+    reporter.delete(&id).map_err(|e| e.to_string())
+}
+
+/// Open a prefilled issue for a crash report in the browser and mark it sent.
+/// Returns the issue URL.
+#[tauri::command]
+pub async fn send_crash_report(
+    app: AppHandle,
+    reporter: State<'_, CrashReporter>,
+    id: String,
+) -> Result<String, String> {
+    let url = reporter.send(&id).map_err(|e| e.to_string())?;
+
+    // Shell::open is deprecated in favour of the opener plugin, which the app
+    // does not ship yet
+    #[allow(deprecated)]
+    {
+        use tauri_plugin_shell::ShellExt;
+        app.shell().open(&url, None).map_err(|e| e.to_string())?;
+    }
+
+    Ok(url)
+}
@@ -1,7 +1,15 @@
 //! Daemon status IPC commands
 
+use jax_daemon::audit::{Actor, AuditEvent};
+use jax_daemon::http_server::api::v0::logs::{run_query, LogQueryRequest};
+use jax_daemon::logs::LogEntry;
+use jax_daemon::sync_conditions::{Conditions, SyncDeferral};
+use jax_daemon::AppConfig;
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 use tauri::State;
+use uuid::Uuid;
 
 use crate::AppState;
 
@@ -50,6 +58,10 @@ pub struct ConfigInfo {
     pub db_path: String,
     pub config_path: String,
     pub blob_store: String,
+    /// Largest file accepted into buckets without their own upload limit
+    pub max_upload_bytes: u64,
+    /// Upload limits of single buckets, in place of `max_upload_bytes`
+    pub bucket_upload_limits: BTreeMap<Uuid, u64>,
 }
 
 /// Get configuration info
@@ -58,6 +70,7 @@ pub async fn get_config_info(state: State<'_, AppState>) -> Result<ConfigInfo, S
     let inner = state.inner.read().await;
     let daemon = inner.as_ref().ok_or("Daemon not started")?;
     let jax_dir = &daemon.jax_dir;
+    let upload_limits = daemon.service.upload_limits();
 
     Ok(ConfigInfo {
         jax_dir: jax_dir.display().to_string(),
@@ -69,5 +82,151 @@ pub async fn get_config_info(state: State<'_, AppState>) -> Result<ConfigInfo, S
             "legacy"
         }
         .to_string(),
+        max_upload_bytes: upload_limits.max_bytes,
+        bucket_upload_limits: upload_limits.buckets.clone(),
+    })
+}
+
+/// Query the daemon logs for the log viewer.
+///
+/// `since`/`until` accept RFC 3339 or relative times (`10m`, `2h`, `1d`).
+/// Poll with `since` set to the last timestamp seen to follow new entries.
+#[tauri::command]
+pub async fn get_logs(
+    state: State<'_, AppState>,
+    level: Option<String>,
+    since: Option<String>,
+    until: Option<String>,
+    contains: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<LogEntry>, String> {
+    let log_dir = {
+        let inner = state.inner.read().await;
+        let daemon = inner.as_ref().ok_or("Daemon not started")?;
+        daemon
+            .service
+            .log_dir()
+            .ok_or("Daemon is not logging to a directory")?
+            .to_path_buf()
+    };
+    let req = LogQueryRequest {
+        level,
+        since,
+        until,
+        contains,
+        limit,
+    };
+
+    let response = tokio::task::spawn_blocking(move || run_query(&log_dir, &req))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+    Ok(response.entries)
+}
+
+/// Export the node identity, config and bucket list as a passphrase-encrypted
+/// bundle at `path`, for `import_identity_bundle` on another machine.
+/// Returns the number of buckets in the bundle.
+#[tauri::command]
+pub async fn export_identity_bundle(
+    state: State<'_, AppState>,
+    passphrase: String,
+    path: String,
+) -> Result<usize, String> {
+    use jax_daemon::http_server::api::v0::identity::MIN_PASSPHRASE_LEN;
+    use jax_daemon::identity::IdentityBundle;
+
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(format!(
+            "Passphrase must be at least {} characters",
+            MIN_PASSPHRASE_LEN
+        ));
+    }
+
+    let bundle = {
+        let inner = state.inner.read().await;
+        let daemon = inner.as_ref().ok_or("Daemon not started")?;
+        IdentityBundle::collect(&daemon.service)
+            .await
+            .map_err(|e| e.to_string())?
+    };
+    let buckets = bundle.buckets.len();
+
+    tokio::task::spawn_blocking(move || {
+        let encrypted = bundle.encrypt(&passphrase).map_err(|e| e.to_string())?;
+        std::fs::write(&path, encrypted).map_err(|e| e.to_string())
     })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    Ok(buckets)
+}
+
+/// Whether syncing is paused globally
+#[tauri::command]
+pub async fn get_sync_paused(state: State<'_, AppState>) -> Result<bool, String> {
+    let inner = state.inner.read().await;
+    let daemon = inner.as_ref().ok_or("Daemon not started")?;
+    Ok(daemon.service.sync_status().is_paused())
+}
+
+/// Pause or resume all syncing; the switch is kept across restarts
+#[tauri::command]
+pub async fn set_sync_paused(state: State<'_, AppState>, paused: bool) -> Result<(), String> {
+    let inner = state.inner.read().await;
+    let daemon = inner.as_ref().ok_or("Daemon not started")?;
+    daemon
+        .service
+        .set_sync_paused(paused)
+        .await
+        .map_err(|e| e.to_string())?;
+    let operation = if paused { "sync.pause" } else { "sync.resume" };
+    daemon
+        .service
+        .audit(AuditEvent::new(Actor::Desktop, operation))
+        .await;
+    Ok(())
+}
+
+/// How syncing adapts to battery power and metered networks, and the
+/// conditions it sees now
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncDeferralInfo {
+    pub deferral: SyncDeferral,
+    pub conditions: Conditions,
+    /// Pin downloads held back until the network is unmetered
+    pub deferred_jobs: usize,
+}
+
+/// Get the sync deferral settings and current conditions
+#[tauri::command]
+pub async fn get_sync_deferral(state: State<'_, AppState>) -> Result<SyncDeferralInfo, String> {
+    let inner = state.inner.read().await;
+    let daemon = inner.as_ref().ok_or("Daemon not started")?;
+    let status = daemon.service.sync_status();
+    Ok(SyncDeferralInfo {
+        deferral: daemon.service.reloader().current().sync_deferral,
+        conditions: status.conditions().current(),
+        deferred_jobs: status.deferred_jobs(),
+    })
+}
+
+/// Save the sync deferral settings to the config file and apply them
+#[tauri::command]
+pub async fn set_sync_deferral(
+    state: State<'_, AppState>,
+    deferral: SyncDeferral,
+) -> Result<(), String> {
+    let inner = state.inner.read().await;
+    let daemon = inner.as_ref().ok_or("Daemon not started")?;
+    let reloader = daemon.service.reloader();
+    let path = reloader
+        .config_path()
+        .ok_or("Daemon was not started from a config file")?;
+
+    let mut config = AppConfig::read(path).map_err(|e| e.to_string())?;
+    config.sync_deferral = deferral;
+    config.write(path).map_err(|e| e.to_string())?;
+    reloader.reload().map_err(|e| e.to_string())?;
+    Ok(())
 }
@@ -0,0 +1,45 @@
+//! Edit IPC commands
+//!
+//! Open bucket files in their default app and write saves back to the bucket.
+
+use tauri::{AppHandle, State};
+use uuid::Uuid;
+
+use crate::edit::{self, EditSession, EditSessions};
+
+/// Open a bucket file with the OS default app; each save in that app is
+/// written back to the bucket as a new version
+#[tauri::command]
+pub async fn open_with_default_app(
+    app: AppHandle,
+    bucket_id: String,
+    path: String,
+) -> Result<EditSession, String> {
+    let bucket_id = bucket_id
+        .parse::<Uuid>()
+        .map_err(|e| format!("Invalid bucket ID: {}", e))?;
+    edit::open_with_default_app(&app, bucket_id, &path).await
+}
+
+/// Files currently open for editing
+#[tauri::command]
+pub async fn list_edit_sessions(
+    sessions: State<'_, EditSessions>,
+) -> Result<Vec<EditSession>, String> {
+    Ok(sessions.list())
+}
+
+/// Stop writing saves of an open file back to the bucket
+#[tauri::command]
+pub async fn stop_editing(
+    sessions: State<'_, EditSessions>,
+    session_id: String,
+) -> Result<(), String> {
+    let session_id = session_id
+        .parse::<Uuid>()
+        .map_err(|e| format!("Invalid session ID: {}", e))?;
+    sessions
+        .remove(&session_id)
+        .map(|_| ())
+        .ok_or_else(|| format!("No edit session {}", session_id))
+}
@@ -0,0 +1,90 @@
+//! Social recovery IPC commands
+//!
+//! Thin wrappers over the daemon's escrow API (see
+//! `jax_daemon::http_server::api::v0::escrow`), audited as the desktop app.
+
+use jax_daemon::audit::Actor;
+use jax_daemon::http_server::api::v0::escrow::{
+    approve_release, held, recover_bucket, set_up_recovery, EscrowApproveRequest,
+    EscrowHeldResponse, EscrowRecoverRequest, EscrowRecoverResponse, EscrowSetupRequest,
+    EscrowSetupResponse,
+};
+use jax_daemon::ServiceState;
+use tauri::State;
+use uuid::Uuid;
+
+use crate::AppState;
+
+async fn get_service(state: &State<'_, AppState>) -> Result<ServiceState, String> {
+    let inner = state.inner.read().await;
+    let inner = inner.as_ref().ok_or("Daemon not started")?;
+    Ok(inner.service.clone())
+}
+
+fn parse_bucket_id(bucket_id: &str) -> Result<Uuid, String> {
+    bucket_id
+        .parse()
+        .map_err(|e| format!("Invalid bucket ID: {}", e))
+}
+
+/// Hand shards of a new recovery identity of a bucket to trusted peers
+#[tauri::command]
+pub async fn set_up_recovery_escrow(
+    state: State<'_, AppState>,
+    bucket_id: String,
+    trustees: Vec<String>,
+    threshold: u8,
+) -> Result<EscrowSetupResponse, String> {
+    let service = get_service(&state).await?;
+    let req = EscrowSetupRequest {
+        bucket_id: parse_bucket_id(&bucket_id)?,
+        trustees,
+        threshold,
+    };
+    set_up_recovery(&service, Actor::Desktop, req)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Recovery shards held for other owners, and the requests to release them
+#[tauri::command]
+pub async fn list_held_shards(state: State<'_, AppState>) -> Result<EscrowHeldResponse, String> {
+    let service = get_service(&state).await?;
+    Ok(held(&service))
+}
+
+/// Release our shard of a bucket to a peer that asked for it
+#[tauri::command]
+pub async fn approve_shard_release(
+    state: State<'_, AppState>,
+    bucket_id: String,
+    requester: String,
+) -> Result<(), String> {
+    let service = get_service(&state).await?;
+    let req = EscrowApproveRequest {
+        bucket_id: parse_bucket_id(&bucket_id)?,
+        requester,
+    };
+    approve_release(&service, Actor::Desktop, req)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Take the recovery of a bucket one step further; call again until it
+/// reports `recovered`
+#[tauri::command]
+pub async fn recover_bucket_access(
+    state: State<'_, AppState>,
+    bucket_id: String,
+    trustees: Vec<String>,
+) -> Result<EscrowRecoverResponse, String> {
+    let service = get_service(&state).await?;
+    let req = EscrowRecoverRequest {
+        bucket_id: parse_bucket_id(&bucket_id)?,
+        trustees,
+    };
+    recover_bucket(&service, Actor::Desktop, req)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,55 @@
+//! Inbox IPC commands
+//!
+//! Add pasted or dropped content to the inbox bucket and choose which bucket
+//! that is.
+
+use tauri::{AppHandle, State};
+use uuid::Uuid;
+
+use crate::inbox::{self, QuickAddContent, QuickAddResult};
+use crate::AppState;
+
+/// Add clipboard data, bytes or files to the inbox bucket and save it
+#[tauri::command]
+pub async fn quick_add(app: AppHandle, content: QuickAddContent) -> Result<QuickAddResult, String> {
+    inbox::quick_add(&app, content).await
+}
+
+/// The designated inbox bucket, if one was chosen or created
+#[tauri::command]
+pub async fn get_inbox_bucket(state: State<'_, AppState>) -> Result<Option<Uuid>, String> {
+    let inner = state.inner.read().await;
+    let daemon = inner.as_ref().ok_or("Daemon not started")?;
+    daemon
+        .service
+        .database()
+        .inbox_bucket()
+        .await
+        .map_err(|e| format!("Database error: {}", e))
+}
+
+/// Designate the inbox bucket, or clear it so one is created on next use
+#[tauri::command]
+pub async fn set_inbox_bucket(
+    state: State<'_, AppState>,
+    bucket_id: Option<String>,
+) -> Result<(), String> {
+    let bucket_id = bucket_id
+        .map(|id| id.parse::<Uuid>())
+        .transpose()
+        .map_err(|e| format!("Invalid bucket ID: {}", e))?;
+    let inner = state.inner.read().await;
+    let daemon = inner.as_ref().ok_or("Daemon not started")?;
+    daemon
+        .service
+        .database()
+        .set_inbox_bucket(bucket_id)
+        .await
+        .map_err(|e| format!("Database error: {}", e))
+}
+
+/// Open the quick drop window
+#[tauri::command]
+pub async fn show_quick_drop(app: AppHandle) -> Result<(), String> {
+    inbox::show_quick_drop(&app)
+}
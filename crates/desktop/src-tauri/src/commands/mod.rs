@@ -4,5 +4,10 @@
 //! A few commands (create, share, ping) still use HTTP for complex API flows.
 
 pub mod bucket;
+pub mod crash;
 pub mod daemon;
+pub mod edit;
+pub mod escrow;
+pub mod inbox;
 pub mod mount;
+pub mod onboarding;
@@ -8,6 +8,9 @@ use tauri::State;
 #[cfg(feature = "fuse")]
 use uuid::Uuid;
 
+#[cfg(feature = "fuse")]
+use jax_daemon::audit::{Actor, AuditEvent};
+
 use crate::AppState;
 
 /// Get the platform-specific base mount directory
@@ -233,6 +236,14 @@ pub async fn create_mount(
         )
         .await
         .map_err(|e| format!("Failed to create mount: {}", e))?;
+    daemon
+        .service
+        .audit(
+            AuditEvent::new(Actor::Desktop, "mount.create")
+                .bucket(bucket_id)
+                .detail(mount.mount_point.clone()),
+        )
+        .await;
 
     Ok(fuse_mount_to_info(mount))
 }
@@ -337,10 +348,17 @@ pub async fn delete_mount(state: State<'_, AppState>, mount_id: String) -> Resul
         .as_ref()
         .ok_or("Mount manager not available")?;
 
-    manager
+    let deleted = manager
         .delete(&id)
         .await
-        .map_err(|e| format!("Failed to delete mount: {}", e))
+        .map_err(|e| format!("Failed to delete mount: {}", e))?;
+    if deleted {
+        daemon
+            .service
+            .audit(AuditEvent::new(Actor::Desktop, "mount.delete").detail(mount_id))
+            .await;
+    }
+    Ok(deleted)
 }
 
 #[tauri::command]
@@ -515,6 +533,14 @@ pub async fn mount_bucket(
         .create_mount(bucket_uuid, &mount_point_str, false, false, None, None)
         .await
         .map_err(|e| format!("Failed to create mount: {}", e))?;
+    daemon
+        .service
+        .audit(
+            AuditEvent::new(Actor::Desktop, "mount.create")
+                .bucket(bucket_uuid)
+                .detail(mount_point_str.clone()),
+        )
+        .await;
 
     let mount_id = mount.mount_id;
 
@@ -580,6 +606,14 @@ pub async fn unmount_bucket(state: State<'_, AppState>, bucket_id: String) -> Re
         .delete(&mount.mount_id)
         .await
         .map_err(|e| format!("Failed to delete mount: {}", e))?;
+    daemon
+        .service
+        .audit(
+            AuditEvent::new(Actor::Desktop, "mount.delete")
+                .bucket(bucket_uuid)
+                .detail(mount.mount_id.to_string()),
+        )
+        .await;
 
     Ok(true)
 }
@@ -0,0 +1,149 @@
+//! First-run onboarding IPC commands
+//!
+//! Let the frontend create the jax directory (`~/.jax`) with a generated or
+//! imported identity (a bare key or a full identity bundle) and chosen ports,
+//! then start the embedded daemon, so the app works without running `jax init`
+//! first.
+
+use std::net::TcpListener;
+
+use common::prelude::SecretKey;
+use jax_daemon::identity::IdentityBundle;
+use jax_daemon::state::{AppConfig, AppState as JaxAppState};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+/// Ports chosen in the onboarding wizard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortChoice {
+    pub api_port: u16,
+    pub gateway_port: u16,
+    /// None lets the peer node pick an ephemeral port
+    pub peer_port: Option<u16>,
+}
+
+/// Result of a successful onboarding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InitResult {
+    pub jax_dir: String,
+    pub node_id: String,
+}
+
+/// Whether the jax directory exists and holds a complete state
+#[tauri::command]
+pub async fn is_initialized() -> Result<bool, String> {
+    Ok(JaxAppState::load(None).is_ok())
+}
+
+/// Ports to prefill the wizard with: the defaults where they are free,
+/// otherwise a free port picked by the OS
+#[tauri::command]
+pub async fn suggest_ports() -> Result<PortChoice, String> {
+    let defaults = AppConfig::default();
+    Ok(PortChoice {
+        api_port: free_port_or_any(defaults.api_port)?,
+        gateway_port: free_port_or_any(defaults.gateway_port)?,
+        peer_port: None,
+    })
+}
+
+/// Create the jax directory with a new identity and start the daemon
+#[tauri::command]
+pub async fn init_identity(app: AppHandle, ports: PortChoice) -> Result<InitResult, String> {
+    initialize(&app, SecretKey::generate(), ports)
+}
+
+/// Create the jax directory with an existing identity (PEM-encoded secret
+/// key, as written to `key.pem`) and start the daemon
+#[tauri::command]
+pub async fn import_identity(
+    app: AppHandle,
+    key_pem: String,
+    ports: PortChoice,
+) -> Result<InitResult, String> {
+    let key =
+        SecretKey::from_pem(key_pem.trim()).map_err(|e| format!("Invalid secret key: {}", e))?;
+    initialize(&app, key, ports)
+}
+
+/// Restore an identity, config and bucket list from a bundle written by
+/// `jax identity export` (or the Settings page), then start the daemon.
+/// The bundled buckets sync from their peers once it is running.
+#[tauri::command]
+pub async fn import_identity_bundle(
+    app: AppHandle,
+    path: String,
+    passphrase: String,
+    ports: PortChoice,
+) -> Result<InitResult, String> {
+    let data = std::fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let mut bundle =
+        tokio::task::spawn_blocking(move || IdentityBundle::decrypt(&data, &passphrase))
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| e.to_string())?;
+
+    check_ports(&ports)?;
+    bundle.config.api_port = ports.api_port;
+    bundle.config.gateway_port = ports.gateway_port;
+    bundle.config.peer_port = ports.peer_port;
+
+    let node_id = bundle.node_id().map_err(|e| e.to_string())?.to_hex();
+    let state = bundle.import(None).map_err(|e| e.to_string())?;
+
+    tracing::info!("Imported identity into {}", state.jax_dir.display());
+    crate::start_daemon(app.clone());
+
+    Ok(InitResult {
+        jax_dir: state.jax_dir.display().to_string(),
+        node_id,
+    })
+}
+
+fn initialize(app: &AppHandle, key: SecretKey, ports: PortChoice) -> Result<InitResult, String> {
+    check_ports(&ports)?;
+
+    let node_id = key.public().to_hex();
+    let config = AppConfig {
+        api_port: ports.api_port,
+        gateway_port: ports.gateway_port,
+        peer_port: ports.peer_port,
+        ..AppConfig::default()
+    };
+    let state = JaxAppState::init_with_key(None, Some(config), key).map_err(|e| e.to_string())?;
+
+    tracing::info!("Initialized jax directory at {}", state.jax_dir.display());
+    crate::start_daemon(app.clone());
+
+    Ok(InitResult {
+        jax_dir: state.jax_dir.display().to_string(),
+        node_id,
+    })
+}
+
+/// Fail early, before anything is written, if a chosen port is taken
+fn check_ports(ports: &PortChoice) -> Result<(), String> {
+    if ports.api_port == ports.gateway_port {
+        return Err("API and gateway ports must differ".to_string());
+    }
+    for (name, port) in [("API", ports.api_port), ("gateway", ports.gateway_port)] {
+        if !port_is_free(port) {
+            return Err(format!("{} port {} is already in use", name, port));
+        }
+    }
+    Ok(())
+}
+
+fn port_is_free(port: u16) -> bool {
+    TcpListener::bind(("0.0.0.0", port)).is_ok()
+}
+
+fn free_port_or_any(preferred: u16) -> Result<u16, String> {
+    if port_is_free(preferred) {
+        return Ok(preferred);
+    }
+    TcpListener::bind(("0.0.0.0", 0))
+        .and_then(|listener| listener.local_addr())
+        .map(|addr| addr.port())
+        .map_err(|e| format!("Failed to find a free port: {}", e))
+}
@@ -0,0 +1,288 @@
+//! Opt-in crash reporting
+//!
+//! When enabled, panics (in any thread or daemon task) and daemon startup
+//! failures are written as JSON reports to `~/.jax/crashes`, together with the
+//! app version, OS and the tail of the daemon log. Nothing leaves the machine
+//! until the user sends a report, which opens a prefilled GitHub issue.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use time::format_description::well_known::Rfc3339;
+
+/// Directory inside the jax directory holding crash reports
+pub const CRASH_DIR_NAME: &str = "crashes";
+/// Stores whether the user opted in
+const SETTINGS_FILE_NAME: &str = "settings.json";
+/// Number of log entries captured with each report
+const LOG_TAIL_LINES: usize = 100;
+/// Where "send report" files the issue
+const ISSUES_URL: &str = "https://github.com/jax-protocol/jax-fs/issues/new";
+/// GitHub rejects very long prefilled issue URLs
+const MAX_ISSUE_BODY_CHARS: usize = 6000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrashKind {
+    Panic,
+    TaskFailure,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashReport {
+    pub id: String,
+    /// RFC 3339 timestamp
+    pub timestamp: String,
+    pub kind: CrashKind,
+    pub message: String,
+    /// Source location of a panic
+    pub location: Option<String>,
+    pub backtrace: Option<String>,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    /// Most recent daemon log lines, oldest first
+    pub log_tail: Vec<String>,
+    /// Whether the user has sent this report
+    #[serde(default)]
+    pub sent: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CrashSettings {
+    enabled: bool,
+}
+
+/// Writes and manages crash reports; cheap to clone
+#[derive(Debug, Clone)]
+pub struct CrashReporter {
+    crash_dir: PathBuf,
+    log_dir: PathBuf,
+    enabled: Arc<AtomicBool>,
+}
+
+impl CrashReporter {
+    /// Reporter for a jax directory, with the opt-in setting loaded from disk
+    pub fn new(jax_dir: &Path) -> Self {
+        let crash_dir = jax_dir.join(CRASH_DIR_NAME);
+        let settings: CrashSettings = fs::read(crash_dir.join(SETTINGS_FILE_NAME))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+
+        Self {
+            crash_dir,
+            log_dir: jax_dir.join(jax_daemon::state::LOGS_DIR_NAME),
+            enabled: Arc::new(AtomicBool::new(settings.enabled)),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Opt in or out; persisted across restarts
+    pub fn set_enabled(&self, enabled: bool) -> std::io::Result<()> {
+        fs::create_dir_all(&self.crash_dir)?;
+        let settings = serde_json::to_vec_pretty(&CrashSettings { enabled })?;
+        fs::write(self.crash_dir.join(SETTINGS_FILE_NAME), settings)?;
+        self.enabled.store(enabled, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Record panics from any thread, then defer to the previous hook
+    pub fn install_panic_hook(&self) {
+        let reporter = self.clone();
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let message = if let Some(s) = info.payload().downcast_ref::<&str>() {
+                s.to_string()
+            } else if let Some(s) = info.payload().downcast_ref::<String>() {
+                s.clone()
+            } else {
+                "unknown panic payload".to_string()
+            };
+            let location = info
+                .location()
+                .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+            let thread = std::thread::current()
+                .name()
+                .map(|name| format!("thread '{}' panicked: ", name))
+                .unwrap_or_default();
+
+            reporter.record(
+                CrashKind::Panic,
+                format!("{}{}", thread, message),
+                location,
+                Some(std::backtrace::Backtrace::force_capture().to_string()),
+            );
+            previous(info);
+        }));
+    }
+
+    /// Record a daemon task that failed without panicking
+    pub fn record_task_failure(&self, task: &str, error: &str) {
+        self.record(
+            CrashKind::TaskFailure,
+            format!("{} failed: {}", task, error),
+            None,
+            None,
+        );
+    }
+
+    fn record(
+        &self,
+        kind: CrashKind,
+        message: String,
+        location: Option<String>,
+        backtrace: Option<String>,
+    ) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let report = CrashReport {
+            id: uuid::Uuid::new_v4().to_string(),
+            timestamp: time::OffsetDateTime::now_utc()
+                .format(&Rfc3339)
+                .unwrap_or_default(),
+            kind,
+            message,
+            location,
+            backtrace,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            log_tail: self.log_tail(),
+            sent: false,
+        };
+
+        // Runs inside the panic hook: report failures but never panic here
+        if let Err(e) = self.write(&report) {
+            eprintln!("Failed to write crash report: {}", e);
+        } else {
+            eprintln!(
+                "Crash report written to {}",
+                self.report_path(&report.id).display()
+            );
+        }
+    }
+
+    fn log_tail(&self) -> Vec<String> {
+        let filter = jax_daemon::logs::LogFilter::default();
+        jax_daemon::logs::query(&self.log_dir, &filter, LOG_TAIL_LINES)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|entry| {
+                format!(
+                    "{} {} {}: {}",
+                    entry.timestamp, entry.level, entry.target, entry.message
+                )
+            })
+            .collect()
+    }
+
+    fn report_path(&self, id: &str) -> PathBuf {
+        self.crash_dir.join(format!("{}.json", id))
+    }
+
+    fn write(&self, report: &CrashReport) -> std::io::Result<()> {
+        fs::create_dir_all(&self.crash_dir)?;
+        fs::write(
+            self.report_path(&report.id),
+            serde_json::to_vec_pretty(report)?,
+        )
+    }
+
+    /// All stored reports, newest first
+    pub fn list(&self) -> std::io::Result<Vec<CrashReport>> {
+        let entries = match fs::read_dir(&self.crash_dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        let mut reports = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.file_name().and_then(|n| n.to_str()) == Some(SETTINGS_FILE_NAME)
+                || path.extension().and_then(|e| e.to_str()) != Some("json")
+            {
+                continue;
+            }
+            if let Ok(report) = serde_json::from_slice::<CrashReport>(&fs::read(&path)?) {
+                reports.push(report);
+            }
+        }
+        // RFC 3339 UTC timestamps sort chronologically
+        reports.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        Ok(reports)
+    }
+
+    pub fn get(&self, id: &str) -> std::io::Result<CrashReport> {
+        let path = self.checked_path(id)?;
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    pub fn delete(&self, id: &str) -> std::io::Result<()> {
+        fs::remove_file(self.checked_path(id)?)
+    }
+
+    /// Build the issue URL for a report and mark it as sent
+    pub fn send(&self, id: &str) -> std::io::Result<String> {
+        let mut report = self.get(id)?;
+        let url = issue_url(&report, &self.report_path(id));
+        report.sent = true;
+        self.write(&report)?;
+        Ok(url)
+    }
+
+    /// Report ids come from the frontend; keep them inside the crash directory
+    fn checked_path(&self, id: &str) -> std::io::Result<PathBuf> {
+        if uuid::Uuid::parse_str(id).is_err() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("invalid crash report id: {}", id),
+            ));
+        }
+        Ok(self.report_path(id))
+    }
+}
+
+/// Prefilled GitHub issue for a report
+fn issue_url(report: &CrashReport, path: &Path) -> String {
+    let mut body = format!(
+        "**What happened**\n\n<!-- What were you doing when Jax crashed? -->\n\n\
+         **Crash**\n\n```\n{}\n{}\n```\n\n\
+         **Environment**\n\n- Jax desktop {}\n- {} ({})\n- {}\n",
+        report.message,
+        report.location.as_deref().unwrap_or(""),
+        report.app_version,
+        report.os,
+        report.arch,
+        report.timestamp,
+    );
+    if !report.log_tail.is_empty() {
+        let start = report.log_tail.len().saturating_sub(20);
+        body.push_str(&format!(
+            "\n**Recent logs**\n\n```\n{}\n```\n",
+            report.log_tail[start..].join("\n")
+        ));
+    }
+    body.push_str(&format!(
+        "\nThe full report (with backtrace) is at `{}`; please attach it.\n",
+        path.display()
+    ));
+    if body.chars().count() > MAX_ISSUE_BODY_CHARS {
+        body = body.chars().take(MAX_ISSUE_BODY_CHARS).collect();
+        body.push_str("\n…(truncated)");
+    }
+
+    let title = format!("Crash: {}", report.message.lines().next().unwrap_or(""));
+    reqwest::Url::parse_with_params(ISSUES_URL, &[("title", title), ("body", body)])
+        .map(|url| url.to_string())
+        .unwrap_or_else(|_| ISSUES_URL.to_string())
+}
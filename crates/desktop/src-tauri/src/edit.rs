@@ -0,0 +1,360 @@
+//! Edit bucket files in other apps
+//!
+//! Opening a file writes it to a temp directory and opens it with the OS
+//! default app. The temp copy is then polled, and each save is written back
+//! to the bucket as a new version. If the file changed in the bucket since it
+//! was opened (or since the last write back), the edit is saved next to it as
+//! a conflict copy rather than over the other change.
+//!
+//! While a file is open, other owners are told it is being edited (see
+//! [`common::peer::editing`]), so they can hold off until it is closed.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash as _, Hasher};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use common::linked_data::Link;
+use common::peer::editing::DEFAULT_EDITING_TTL;
+use jax_daemon::audit::{Actor, AuditEvent};
+use jax_daemon::ServiceState;
+
+use crate::AppState;
+
+/// How often open files are checked for saves
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// How often the mark telling other owners a file is being edited is
+/// renewed, well within its TTL
+const EDITING_RENEW_INTERVAL: Duration = Duration::from_secs(30);
+/// Directory under the system temp dir that files are opened from
+const TEMP_DIR_NAME: &str = "jax-edit";
+/// Event emitted when a save is written back to the bucket
+pub const EDIT_SAVED_EVENT: &str = "edit-saved";
+
+/// A bucket file opened in another app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditSession {
+    pub id: Uuid,
+    pub bucket_id: Uuid,
+    /// Bucket path of the file
+    pub path: String,
+    /// Where the file was written to and opened from
+    pub local_path: PathBuf,
+}
+
+/// A save written back to the bucket
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditSaved {
+    pub session_id: Uuid,
+    pub bucket_id: Uuid,
+    /// Bucket path the save was written to: the file's own, or a conflict copy
+    pub path: String,
+    pub conflict: bool,
+    pub link_hash: String,
+}
+
+/// Open edit sessions, managed by Tauri
+#[derive(Default)]
+pub struct EditSessions(Mutex<HashMap<Uuid, EditSession>>);
+
+impl EditSessions {
+    pub fn list(&self) -> Vec<EditSession> {
+        let sessions = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        sessions.values().cloned().collect()
+    }
+
+    fn contains(&self, id: &Uuid) -> bool {
+        let sessions = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        sessions.contains_key(id)
+    }
+
+    fn insert(&self, session: EditSession) {
+        let mut sessions = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        sessions.insert(session.id, session);
+    }
+
+    /// Stop watching a session; its temp copy is left for the app that may
+    /// still have it open
+    pub fn remove(&self, id: &Uuid) -> Option<EditSession> {
+        let mut sessions = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        sessions.remove(id)
+    }
+}
+
+/// Write a bucket file to a temp directory, open it with the default app and
+/// watch it for saves
+pub async fn open_with_default_app(
+    app: &AppHandle,
+    bucket_id: Uuid,
+    path: &str,
+) -> Result<EditSession, String> {
+    let service = service(app).await?;
+    let mount = service
+        .peer()
+        .mount(bucket_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    let bucket_path = PathBuf::from(path);
+    let node_link = mount.get(&bucket_path).await.map_err(|e| e.to_string())?;
+    if node_link.is_dir() {
+        return Err(format!("'{}' is a directory", path));
+    }
+    let name = bucket_path
+        .file_name()
+        .ok_or_else(|| format!("Cannot open '{}'", path))?;
+    let content = mount.cat(&bucket_path).await.map_err(|e| e.to_string())?;
+
+    // A directory per session keeps the file's own name, which apps show
+    let id = Uuid::new_v4();
+    let dir = std::env::temp_dir()
+        .join(TEMP_DIR_NAME)
+        .join(id.to_string());
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .map_err(|e| format!("Failed to create '{}': {}", dir.display(), e))?;
+    let local_path = dir.join(name);
+    tokio::fs::write(&local_path, &content)
+        .await
+        .map_err(|e| format!("Failed to write '{}': {}", local_path.display(), e))?;
+
+    let session = EditSession {
+        id,
+        bucket_id,
+        path: path.to_string(),
+        local_path: local_path.clone(),
+    };
+    app.state::<EditSessions>().insert(session.clone());
+
+    // Shell::open is deprecated in favour of the opener plugin, which the app
+    // does not ship yet
+    #[allow(deprecated)]
+    {
+        use tauri_plugin_shell::ShellExt;
+        app.shell()
+            .open(local_path.to_string_lossy().to_string(), None)
+            .map_err(|e| e.to_string())?;
+    }
+
+    let watched = session.clone();
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        watch(app, watched, node_link.link().clone(), digest(&content)).await;
+    });
+    Ok(session)
+}
+
+/// Poll a session's temp copy and write each save back until the session is
+/// stopped, keeping the file marked as being edited meanwhile
+async fn watch(app: AppHandle, session: EditSession, mut base: Link, mut written: u64) {
+    mark_editing(&app, &session).await;
+    let mut marked = Instant::now();
+    let mut seen = modified(&session.local_path).await;
+    // A change is only written back once it has stopped changing for a poll,
+    // so a save in progress isn't picked up half written
+    let mut pending = None;
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        if !app.state::<EditSessions>().contains(&session.id) {
+            clear_editing(&app, &session).await;
+            return;
+        }
+        if marked.elapsed() >= EDITING_RENEW_INTERVAL {
+            mark_editing(&app, &session).await;
+            marked = Instant::now();
+        }
+
+        let now = modified(&session.local_path).await;
+        if now == seen {
+            continue;
+        }
+        if pending != Some(now) {
+            pending = Some(now);
+            continue;
+        }
+        pending = None;
+        seen = now;
+
+        let content = match tokio::fs::read(&session.local_path).await {
+            Ok(content) => content,
+            Err(e) => {
+                tracing::warn!("Failed to read {}: {}", session.local_path.display(), e);
+                continue;
+            }
+        };
+        // Apps often rewrite a file without changing it
+        if digest(&content) == written {
+            continue;
+        }
+        match write_back(&app, &session, &base, content.clone()).await {
+            Ok((saved, link)) => {
+                tracing::info!(
+                    "Wrote {} back to bucket {} at {}",
+                    session.local_path.display(),
+                    session.bucket_id,
+                    saved.path
+                );
+                base = link;
+                written = digest(&content);
+                let _ = app.emit(EDIT_SAVED_EVENT, &saved);
+            }
+            Err(e) => tracing::warn!(
+                "Failed to write {} back to bucket {}: {}",
+                session.path,
+                session.bucket_id,
+                e
+            ),
+        }
+    }
+}
+
+/// Save an edit as a new version, over the file if it is unchanged since
+/// `base` and as a conflict copy next to it otherwise. Returns what was saved
+/// and the link to compare the next save against.
+async fn write_back(
+    app: &AppHandle,
+    session: &EditSession,
+    base: &Link,
+    content: Vec<u8>,
+) -> Result<(EditSaved, Link), String> {
+    let service = service(app).await?;
+    let mut mount = service
+        .peer()
+        .mount(session.bucket_id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let bucket_path = PathBuf::from(&session.path);
+    let current = mount
+        .get(&bucket_path)
+        .await
+        .ok()
+        .map(|node_link| node_link.link().clone());
+    let conflict = current.as_ref().is_some_and(|link| link != base);
+    let dest = if conflict {
+        conflict_path(&bucket_path, OffsetDateTime::now_utc())
+    } else {
+        bucket_path.clone()
+    };
+
+    mount
+        .add(&dest, Cursor::new(content))
+        .await
+        .map_err(|e| e.to_string())?;
+    let link = service
+        .peer()
+        .save_mount(&mount, false)
+        .await
+        .map_err(|e| e.to_string())?;
+    service
+        .audit(
+            AuditEvent::new(Actor::Desktop, "bucket.edit")
+                .bucket(session.bucket_id)
+                .path(dest.to_string_lossy())
+                .version(&link),
+        )
+        .await;
+
+    // Compare the next save against what the file now holds: our write, or
+    // the other change we didn't overwrite
+    let next_base = match current {
+        Some(current) if conflict => current,
+        _ => mount
+            .get(&bucket_path)
+            .await
+            .map_err(|e| e.to_string())?
+            .link()
+            .clone(),
+    };
+    let saved = EditSaved {
+        session_id: session.id,
+        bucket_id: session.bucket_id,
+        path: dest.to_string_lossy().to_string(),
+        conflict,
+        link_hash: link.hash().to_string(),
+    };
+    Ok((saved, next_base))
+}
+
+/// Mark a session's file as being edited by us, or renew the mark. The mark
+/// is only a hint to other owners, so failing to set it is just logged.
+async fn mark_editing(app: &AppHandle, session: &EditSession) {
+    let result = match service(app).await {
+        Ok(service) => service
+            .peer()
+            .mark_editing(session.bucket_id, &session.path, DEFAULT_EDITING_TTL)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        tracing::debug!("Failed to mark {} as being edited: {}", session.path, e);
+    }
+}
+
+/// Clear the mark of a session that was stopped; it expires anyway if this
+/// fails
+async fn clear_editing(app: &AppHandle, session: &EditSession) {
+    let result = match service(app).await {
+        Ok(service) => service
+            .peer()
+            .clear_editing(session.bucket_id, &session.path)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        tracing::debug!("Failed to clear edit mark on {}: {}", session.path, e);
+    }
+}
+
+/// `/dir/stem (edited YYYYMMDD-HHMMSS).ext`
+fn conflict_path(path: &Path, at: OffsetDateTime) -> PathBuf {
+    let stamp = format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}",
+        at.year(),
+        at.month() as u8,
+        at.day(),
+        at.hour(),
+        at.minute(),
+        at.second()
+    );
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let name = match path.extension() {
+        Some(ext) => format!("{} (edited {}).{}", stem, stamp, ext.to_string_lossy()),
+        None => format!("{} (edited {})", stem, stamp),
+    };
+    path.with_file_name(name)
+}
+
+async fn modified(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = tokio::fs::metadata(path).await.ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+fn digest(content: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+async fn service(app: &AppHandle) -> Result<ServiceState, String> {
+    let state = app.state::<AppState>();
+    let inner = state.inner.read().await;
+    inner
+        .as_ref()
+        .map(|daemon| daemon.service.clone())
+        .ok_or_else(|| "Daemon not started".to_string())
+}
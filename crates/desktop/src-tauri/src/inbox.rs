@@ -0,0 +1,312 @@
+//! Quick-drop inbox
+//!
+//! Pasted text and dropped files go straight into a designated inbox bucket:
+//! each drop is saved as a new version right away, which notifies the
+//! bucket's peers like any other save. The inbox is chosen in the settings
+//! (`inbox_bucket`), and an "Inbox" bucket is created on first use if none is.
+//!
+//! Content reaches the inbox through the `quick_add` IPC command, the global
+//! [`SHORTCUT`] (pastes the clipboard text), and the tray's "Quick Drop"
+//! window, which files can be dropped on.
+
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use common::mount::Mount;
+use jax_daemon::audit::{Actor, AuditEvent};
+use jax_daemon::http_server::error::ErrorBody;
+use jax_daemon::ServiceState;
+
+use crate::AppState;
+
+/// Global shortcut that adds the clipboard text to the inbox
+pub const SHORTCUT: &str = "CommandOrControl+Alt+J";
+/// Label of the quick drop window
+pub const QUICK_DROP_WINDOW: &str = "quick-drop";
+/// Event telling the frontend what was added to the inbox
+pub const INBOX_ADDED_EVENT: &str = "inbox-added";
+/// Name of the bucket created when no inbox is set
+const DEFAULT_INBOX_NAME: &str = "Inbox";
+
+/// Content to add to the inbox
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum QuickAddContent {
+    /// Text, saved as a `.txt` file
+    Text { text: String },
+    /// Raw bytes such as a pasted image, saved under `name` if given
+    Bytes {
+        data: Vec<u8>,
+        #[serde(default)]
+        name: Option<String>,
+    },
+    /// Files on disk, saved under their own names
+    Files { paths: Vec<String> },
+}
+
+/// What a quick add did
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuickAddResult {
+    pub bucket_id: Uuid,
+    /// Paths the content was saved at
+    pub paths: Vec<String>,
+    pub link_hash: String,
+}
+
+/// Add content to the inbox bucket and save it
+pub async fn quick_add(
+    app: &AppHandle,
+    content: QuickAddContent,
+) -> Result<QuickAddResult, String> {
+    let (service, api_port) = {
+        let state = app.state::<AppState>();
+        let inner = state.inner.read().await;
+        let daemon = inner.as_ref().ok_or("Daemon not started")?;
+        (daemon.service.clone(), daemon.api_port)
+    };
+    let bucket_id = inbox_bucket(&service, api_port).await?;
+
+    let mut mount = service
+        .peer()
+        .mount(bucket_id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let now = OffsetDateTime::now_utc();
+    let mut paths = Vec::new();
+    match content {
+        QuickAddContent::Text { text } => {
+            let path = free_path(&mount, &format!("{}.txt", stamped("clip", now))).await;
+            add(&mut mount, &path, text.into_bytes()).await?;
+            paths.push(path);
+        }
+        QuickAddContent::Bytes { data, name } => {
+            let name = name
+                .as_deref()
+                .and_then(file_name)
+                .unwrap_or_else(|| stamped("drop", now));
+            let path = free_path(&mount, &name).await;
+            add(&mut mount, &path, data).await?;
+            paths.push(path);
+        }
+        QuickAddContent::Files { paths: files } => {
+            for file in files {
+                let name = file_name(&file).unwrap_or_else(|| stamped("drop", now));
+                let data = tokio::fs::read(&file)
+                    .await
+                    .map_err(|e| format!("Failed to read file '{}': {}", file, e))?;
+                let path = free_path(&mount, &name).await;
+                add(&mut mount, &path, data).await?;
+                paths.push(path);
+            }
+        }
+    }
+    if paths.is_empty() {
+        return Err("Nothing to add".to_string());
+    }
+
+    let link = service
+        .peer()
+        .save_mount(&mount, false)
+        .await
+        .map_err(|e| e.to_string())?;
+    for path in &paths {
+        service
+            .audit(
+                AuditEvent::new(Actor::Desktop, "bucket.add")
+                    .bucket(bucket_id)
+                    .path(path.to_string_lossy())
+                    .version(&link),
+            )
+            .await;
+    }
+
+    let result = QuickAddResult {
+        bucket_id,
+        paths: paths
+            .iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect(),
+        link_hash: link.hash().to_string(),
+    };
+    let _ = app.emit(INBOX_ADDED_EVENT, &result);
+    Ok(result)
+}
+
+/// Add the clipboard text to the inbox
+pub async fn paste_clipboard(app: &AppHandle) -> Result<QuickAddResult, String> {
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+    let text = app
+        .clipboard()
+        .read_text()
+        .map_err(|e| format!("Clipboard has no text: {}", e))?;
+    if text.is_empty() {
+        return Err("Clipboard is empty".to_string());
+    }
+    quick_add(app, QuickAddContent::Text { text }).await
+}
+
+/// Register the global shortcut that pastes into the inbox
+pub fn setup_shortcut(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
+    use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+    app.global_shortcut()
+        .on_shortcut(SHORTCUT, |app, _shortcut, event| {
+            if event.state() != ShortcutState::Pressed {
+                return;
+            }
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = paste_clipboard(&app).await {
+                    tracing::warn!("Paste to inbox failed: {}", e);
+                }
+            });
+        })?;
+    Ok(())
+}
+
+/// Show the small always-on-top window that files can be dropped on
+pub fn show_quick_drop(app: &AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(QUICK_DROP_WINDOW) {
+        let _ = window.show();
+        let _ = window.set_focus();
+        return Ok(());
+    }
+
+    let window =
+        WebviewWindowBuilder::new(app, QUICK_DROP_WINDOW, WebviewUrl::App("quick-drop".into()))
+            .title("Quick Drop")
+            .inner_size(280.0, 200.0)
+            .resizable(false)
+            .always_on_top(true)
+            .build()
+            .map_err(|e| e.to_string())?;
+
+    let app = app.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) = event {
+            let paths = paths
+                .iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect();
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = quick_add(&app, QuickAddContent::Files { paths }).await {
+                    tracing::warn!("Quick drop failed: {}", e);
+                }
+            });
+        }
+    });
+    Ok(())
+}
+
+/// The inbox bucket, creating one if none is set or the set one is gone
+async fn inbox_bucket(service: &ServiceState, api_port: u16) -> Result<Uuid, String> {
+    let db = service.database();
+    if let Some(bucket_id) = db
+        .inbox_bucket()
+        .await
+        .map_err(|e| format!("Database error: {}", e))?
+    {
+        let exists = db
+            .get_bucket_info(&bucket_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+            .is_some();
+        if exists {
+            return Ok(bucket_id);
+        }
+        tracing::warn!("Inbox bucket {} no longer exists", bucket_id);
+    }
+
+    // Creating a bucket needs the API flow (init and first save)
+    #[derive(Serialize)]
+    struct CreateRequest {
+        name: String,
+    }
+
+    #[derive(Deserialize)]
+    struct CreateResponse {
+        bucket_id: Uuid,
+    }
+
+    let response = reqwest::Client::new()
+        .post(format!("http://localhost:{}/api/v0/bucket", api_port))
+        .json(&CreateRequest {
+            name: DEFAULT_INBOX_NAME.to_string(),
+        })
+        .send()
+        .await
+        .map_err(|e| format!("Failed to connect to daemon: {}", e))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = ErrorBody::parse(status, &response.text().await.unwrap_or_default());
+        return Err(format!("Failed to create inbox ({}): {}", status, body));
+    }
+    let bucket_id = response
+        .json::<CreateResponse>()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))?
+        .bucket_id;
+
+    db.set_inbox_bucket(Some(bucket_id))
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+    tracing::info!("Created inbox bucket {}", bucket_id);
+    Ok(bucket_id)
+}
+
+async fn add(mount: &mut Mount, path: &Path, data: Vec<u8>) -> Result<(), String> {
+    mount
+        .add(path, Cursor::new(data))
+        .await
+        .map_err(|e| format!("Failed to add '{}': {}", path.display(), e))
+}
+
+/// `/name`, or `/stem (2).ext` and so on if that is taken
+async fn free_path(mount: &Mount, name: &str) -> PathBuf {
+    let path = Path::new("/").join(name);
+    if mount.get(&path).await.is_err() {
+        return path;
+    }
+    let (stem, ext) = match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, format!(".{}", ext)),
+        _ => (name, String::new()),
+    };
+    let mut n = 2;
+    loop {
+        let path = Path::new("/").join(format!("{} ({}){}", stem, n, ext));
+        if mount.get(&path).await.is_err() {
+            return path;
+        }
+        n += 1;
+    }
+}
+
+/// The last component of a path, if it is a usable file name
+fn file_name(path: &str) -> Option<String> {
+    Path::new(path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+}
+
+/// `prefix-YYYYMMDD-HHMMSS`
+fn stamped(prefix: &str, at: OffsetDateTime) -> String {
+    format!(
+        "{}-{:04}{:02}{:02}-{:02}{:02}{:02}",
+        prefix,
+        at.year(),
+        at.month() as u8,
+        at.day(),
+        at.hour(),
+        at.minute(),
+        at.second()
+    )
+}
@@ -5,6 +5,9 @@
 //! ServiceState directly (no HTTP proxying).
 
 mod commands;
+mod crash;
+mod edit;
+mod inbox;
 mod tray;
 
 use std::path::PathBuf;
@@ -14,6 +17,8 @@ use tokio::sync::RwLock;
 
 use jax_daemon::ServiceState;
 
+use crate::crash::CrashReporter;
+
 /// Inner daemon state, populated once the daemon has started.
 pub struct DaemonInner {
     pub service: ServiceState,
@@ -39,17 +44,65 @@ impl Default for AppState {
 /// Run the Tauri application
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    // Initialize tracing subscriber for logging
-    tracing_subscriber::fmt()
-        .with_env_filter(
+    use tracing_subscriber::layer::SubscriberExt;
+    use tracing_subscriber::util::SubscriberInitExt;
+
+    // Initialize tracing subscriber for logging; the JSON files under
+    // ~/.jax/logs back the log viewer
+    let jax_dir = jax_daemon::state::AppState::jax_dir(None).ok();
+    let log_dir = jax_dir
+        .as_ref()
+        .map(|dir| dir.join(jax_daemon::state::LOGS_DIR_NAME));
+    let (json_layer, _log_guard) = match &log_dir {
+        Some(dir) => {
+            let (layer, guard) = jax_daemon::logs::json_file_layer(dir);
+            (Some(layer), Some(guard))
+        }
+        None => (None, None),
+    };
+
+    // Export traces if the config names an OTLP collector
+    let otlp_endpoint = jax_dir
+        .as_ref()
+        .map(|dir| dir.join(jax_daemon::state::CONFIG_FILE_NAME))
+        .and_then(|path| jax_daemon::AppConfig::read(&path).ok())
+        .and_then(|config| config.otlp_endpoint);
+    let (otlp_layer, _tracer_guard) = match otlp_endpoint
+        .as_deref()
+        .map(jax_daemon::telemetry::otlp_layer)
+    {
+        Some(Ok((layer, guard))) => (Some(layer), Some(guard)),
+        Some(Err(e)) => {
+            eprintln!("Warning: Failed to set up trace export: {}", e);
+            (None, None)
+        }
+        None => (None, None),
+    };
+
+    tracing_subscriber::registry()
+        .with(
             tracing_subscriber::EnvFilter::from_default_env()
                 .add_directive(tracing::Level::INFO.into()),
         )
+        .with(otlp_layer)
+        .with(tracing_subscriber::fmt::layer())
+        .with(json_layer)
         .init();
 
+    // Crash reports are only written once the user opts in; without a home
+    // directory they go to the temp directory
+    let crash_reporter = CrashReporter::new(
+        &jax_dir
+            .clone()
+            .unwrap_or_else(|| std::env::temp_dir().join(jax_daemon::state::APP_NAME)),
+    );
+    crash_reporter.install_panic_hook();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_autostart::init(
             tauri_plugin_autostart::MacosLauncher::LaunchAgent,
             None,
@@ -65,18 +118,25 @@ pub fn run() {
             // Initialize app state
             let state = AppState::default();
             app.manage(state);
+            app.manage(crash_reporter);
+            app.manage(edit::EditSessions::default());
 
             // Setup system tray
             tray::setup_tray(app)?;
 
-            // Spawn daemon in background
-            let app_handle = app.handle().clone();
-            tauri::async_runtime::spawn(async move {
-                if let Err(e) = spawn_daemon(&app_handle).await {
-                    tracing::error!("Failed to spawn daemon: {}", e);
-                    eprintln!("DAEMON ERROR: {}", e);
-                }
-            });
+            // Another app may already hold the shortcut; the tray and the
+            // quick drop window still work without it
+            if let Err(e) = inbox::setup_shortcut(app) {
+                tracing::warn!("Failed to register {}: {}", inbox::SHORTCUT, e);
+            }
+
+            // Spawn daemon in background; on first run the onboarding wizard
+            // initializes the jax directory and starts it instead
+            if jax_daemon::state::AppState::load(None).is_ok() {
+                start_daemon(app.handle().clone());
+            } else {
+                tracing::info!("Jax directory not initialized, waiting for onboarding");
+            }
 
             Ok(())
         })
@@ -92,10 +152,14 @@ pub fn run() {
             commands::bucket::rename_path,
             commands::bucket::move_path,
             commands::bucket::share_bucket,
+            commands::bucket::share_bucket_ephemeral,
             commands::bucket::is_published,
             commands::bucket::publish_bucket,
+            commands::bucket::rotate_public_link,
+            commands::bucket::unpublish_bucket,
             commands::bucket::ping_peer,
             commands::bucket::upload_native_files,
+            commands::bucket::download_paths,
             commands::bucket::mkdir,
             commands::bucket::delete_path,
             // History commands
@@ -104,10 +168,47 @@ pub fn run() {
             commands::bucket::cat_at_version,
             // Share commands
             commands::bucket::get_bucket_shares,
+            // Sync schedule commands
+            commands::bucket::get_sync_policy,
+            commands::bucket::set_sync_policy,
+            commands::bucket::sync_bucket_now,
+            commands::bucket::photo_timeline,
+            // Inbox commands
+            commands::inbox::quick_add,
+            commands::inbox::get_inbox_bucket,
+            commands::inbox::set_inbox_bucket,
+            commands::inbox::show_quick_drop,
+            // Edit in other apps
+            commands::edit::open_with_default_app,
+            commands::edit::list_edit_sessions,
+            commands::edit::stop_editing,
+            // Social recovery commands
+            commands::escrow::set_up_recovery_escrow,
+            commands::escrow::list_held_shards,
+            commands::escrow::approve_shard_release,
+            commands::escrow::recover_bucket_access,
             // Daemon commands
             commands::daemon::get_status,
             commands::daemon::get_identity,
             commands::daemon::get_config_info,
+            commands::daemon::get_logs,
+            commands::daemon::export_identity_bundle,
+            commands::daemon::get_sync_paused,
+            commands::daemon::set_sync_paused,
+            commands::daemon::get_sync_deferral,
+            commands::daemon::set_sync_deferral,
+            // Onboarding commands
+            commands::onboarding::is_initialized,
+            commands::onboarding::suggest_ports,
+            commands::onboarding::init_identity,
+            commands::onboarding::import_identity,
+            commands::onboarding::import_identity_bundle,
+            // Crash report commands
+            commands::crash::get_crash_reporting_enabled,
+            commands::crash::set_crash_reporting_enabled,
+            commands::crash::list_crash_reports,
+            commands::crash::delete_crash_report,
+            commands::crash::send_crash_report,
             // Mount commands
             commands::mount::list_mounts,
             commands::mount::create_mount,
@@ -126,14 +227,27 @@ pub fn run() {
         .expect("error while running tauri application");
 }
 
-/// Spawn the jax daemon in the background
+/// Start the jax daemon in the background, recording a failure to start
+pub(crate) fn start_daemon(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = spawn_daemon(&app_handle).await {
+            tracing::error!("Failed to spawn daemon: {}", e);
+            eprintln!("DAEMON ERROR: {}", e);
+            app_handle
+                .state::<CrashReporter>()
+                .record_task_failure("daemon", &e);
+        }
+    });
+}
+
+/// Run the jax daemon until it shuts down
 async fn spawn_daemon(app_handle: &tauri::AppHandle) -> Result<(), String> {
     use jax_daemon::state::AppState as JaxAppState;
     use jax_daemon::{start_service, ServiceConfig};
 
     // Load jax state from default location (~/.jax)
-    let jax_state = JaxAppState::load(None)
-        .map_err(|e| format!("Failed to load jax state (run 'jax init' first): {}", e))?;
+    let jax_state =
+        JaxAppState::load(None).map_err(|e| format!("Failed to load jax state: {}", e))?;
 
     // Load the secret key
     let secret_key = jax_state
@@ -155,15 +269,34 @@ async fn spawn_daemon(app_handle: &tauri::AppHandle) -> Result<(), String> {
     // Build service config
     let config = ServiceConfig {
         node_listen_addr,
-        node_secret: Some(secret_key),
+        node_secret: Some(secret_key.clone()),
         blob_store: jax_state.config.blob_store.clone(),
+        blob_locations: jax_state.config.blob_locations.clone(),
+        cdn_targets: jax_state.config.cdn_targets.clone(),
         jax_dir: jax_state.jax_dir.clone(),
         api_port,
         gateway_port,
         sqlite_path: Some(jax_state.db_path),
+        database_key: jax_state.config.database_key(&secret_key),
+        config_path: Some(jax_state.config_path.clone()),
+        sync_interval: jax_state.config.sync_interval(),
+        periodic_sync: true,
+        online_cache_max_bytes: jax_state.config.online_cache_max_bytes,
+        sync_deferral: jax_state.config.sync_deferral,
+        protocol_timeouts: jax_state.config.protocol_timeouts.timeouts(),
+        connection_limits: jax_state.config.protocol_limits.limits(),
         log_level: tracing::Level::INFO,
-        log_dir: None,
+        log_dir: Some(jax_state.jax_dir.join(jax_daemon::state::LOGS_DIR_NAME)),
+        otlp_endpoint: jax_state.config.otlp_endpoint.clone(),
         gateway_url: None,
+        gateway_tls: None,
+        reverse_proxy: None,
+        publish_scanners: jax_state.config.publish_scanners.clone(),
+        publish_builders: jax_state.config.publish_builders.clone(),
+        upload_limits: jax_state.config.upload_limits.clone(),
+        import_dirs: jax_state.config.import_dirs.clone(),
+        read_replica: false,
+        cluster: None,
     };
 
     tracing::info!(
@@ -1,57 +1,137 @@
 //! System tray setup and handlers
+//!
+//! The tray icon follows the daemon's sync status (fed by
+//! `ServiceState::sync_status`) with a coloured badge, and the menu offers
+//! quick actions: pause syncing, recent buckets, open the gateway, copy the
+//! peer ID, and paste or drop content into the inbox.
+
+use std::sync::Mutex;
+use std::time::Duration;
 
 use tauri::{
     image::Image,
-    menu::{Menu, MenuItem},
-    tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    App, Manager,
+    menu::{Menu, MenuItem, PredefinedMenuItem, Submenu},
+    tray::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent},
+    App, AppHandle, Emitter, Manager,
 };
+use uuid::Uuid;
+
+use jax_daemon::audit::{Actor, AuditEvent};
+use jax_daemon::SyncActivity;
 
 use crate::AppState;
 
-/// Holds a reference to the tray status menu item so we can update its text.
+/// Number of buckets listed under "Recent Buckets"
+const RECENT_BUCKETS: u32 = 5;
+/// How often the status is refreshed when nothing else changes it
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+/// Event asking the frontend to show a bucket
+pub const OPEN_BUCKET_EVENT: &str = "tray-open-bucket";
+
+/// Status shown by the tray icon
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TrayStatus {
+    /// Daemon not running or not connected to the network
+    Offline,
+    Idle,
+    Syncing,
+    Paused,
+    Error(String),
+}
+
+impl TrayStatus {
+    fn label(&self) -> String {
+        match self {
+            TrayStatus::Offline => "Status: Offline".to_string(),
+            TrayStatus::Idle => "Status: Up to date".to_string(),
+            TrayStatus::Syncing => "Status: Syncing...".to_string(),
+            TrayStatus::Paused => "Status: Syncing paused".to_string(),
+            TrayStatus::Error(e) => format!("Status: Sync error ({})", truncate(e, 48)),
+        }
+    }
+}
+
+/// Icon variants, built once from the base tray icon
+struct TrayIcons {
+    idle: Image<'static>,
+    syncing: Image<'static>,
+    paused: Image<'static>,
+    error: Image<'static>,
+    offline: Image<'static>,
+}
+
+/// Handles to the tray parts that change at runtime.
 pub struct TrayState {
-    pub status_item: MenuItem<tauri::Wry>,
+    tray: TrayIcon,
+    status_item: MenuItem<tauri::Wry>,
+    pause_item: MenuItem<tauri::Wry>,
+    recent_menu: Submenu<tauri::Wry>,
+    icons: TrayIcons,
+    /// Last status and bucket list shown, to avoid redundant updates
+    shown: Mutex<(Option<TrayStatus>, Vec<(Uuid, String)>)>,
 }
 
 /// Setup the system tray
 pub fn setup_tray(app: &App) -> Result<(), Box<dyn std::error::Error>> {
     // Create menu items
-    let open = MenuItem::with_id(app, "open", "Open Jax", true, None::<&str>)?;
     let status = MenuItem::with_id(app, "status", "Status: Starting...", false, None::<&str>)?;
+    let open = MenuItem::with_id(app, "open", "Open Jax", true, None::<&str>)?;
+    let recent = Submenu::with_id(app, "recent", "Recent Buckets", false)?;
+    let gateway = MenuItem::with_id(app, "gateway", "Open Gateway", true, None::<&str>)?;
+    let copy_id = MenuItem::with_id(app, "copy_peer_id", "Copy Peer ID", true, None::<&str>)?;
+    let paste_inbox = MenuItem::with_id(
+        app,
+        "paste_inbox",
+        "Paste to Inbox",
+        true,
+        Some(crate::inbox::SHORTCUT),
+    )?;
+    let quick_drop = MenuItem::with_id(app, "quick_drop", "Quick Drop...", true, None::<&str>)?;
+    let pause = MenuItem::with_id(app, "toggle_sync", "Pause Syncing", false, None::<&str>)?;
     let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
 
-    // Store the status item so we can update it later
-    app.manage(TrayState {
-        status_item: status.clone(),
-    });
-
     // Build menu
-    let menu = Menu::with_items(app, &[&open, &status, &quit])?;
+    let menu = Menu::with_items(
+        app,
+        &[
+            &status,
+            &PredefinedMenuItem::separator(app)?,
+            &open,
+            &recent,
+            &gateway,
+            &copy_id,
+            &pause,
+            &PredefinedMenuItem::separator(app)?,
+            &paste_inbox,
+            &quick_drop,
+            &PredefinedMenuItem::separator(app)?,
+            &quit,
+        ],
+    )?;
 
     // Load tray icon embedded at compile time
     let icon = Image::from_bytes(include_bytes!("../icons/tray-icon.png"))?;
+    let icons = TrayIcons {
+        syncing: badged(&icon, [0x3b, 0x82, 0xf6]),
+        paused: badged(&icon, [0xf5, 0x9e, 0x0b]),
+        error: badged(&icon, [0xef, 0x44, 0x44]),
+        offline: badged(&icon, [0x9c, 0xa3, 0xaf]),
+        idle: icon,
+    };
 
     // Build tray icon
-    let _tray = TrayIconBuilder::with_id("main")
-        .icon(icon)
-        .icon_as_template(true)
+    let tray = TrayIconBuilder::with_id("main")
+        .icon(icons.offline.clone())
         .menu(&menu)
         .show_menu_on_left_click(false)
-        .on_menu_event(move |app, event| match event.id.as_ref() {
-            "open" => {
-                if let Some(window) = app.get_webview_window("main") {
-                    let _ = window.show();
-                    let _ = window.set_focus();
+        .on_menu_event(move |app, event| {
+            let app = app.clone();
+            let id = event.id.as_ref().to_string();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = handle_menu_event(&app, &id).await {
+                    tracing::warn!("Tray action {} failed: {}", id, e);
                 }
-            }
-            "status" => {
-                // Status is informational, nothing to do
-            }
-            "quit" => {
-                app.exit(0);
-            }
-            _ => {}
+            });
         })
         .on_tray_icon_event(|tray, event| {
             if let TrayIconEvent::Click {
@@ -60,42 +140,236 @@ pub fn setup_tray(app: &App) -> Result<(), Box<dyn std::error::Error>> {
                 ..
             } = event
             {
-                let app = tray.app_handle();
-                if let Some(window) = app.get_webview_window("main") {
-                    let _ = window.show();
-                    let _ = window.set_focus();
-                }
+                show_window(tray.app_handle());
             }
         })
         .build(app)?;
 
-    // Spawn task to update status periodically
+    // Store the parts we update later
+    app.manage(TrayState {
+        tray,
+        status_item: status,
+        pause_item: pause,
+        recent_menu: recent,
+        icons,
+        shown: Mutex::new((None, Vec::new())),
+    });
+
+    // Update on sync status changes, and periodically for connectivity and
+    // the bucket list
     let app_handle = app.handle().clone();
     tauri::async_runtime::spawn(async move {
         loop {
-            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
-            update_tray_status(&app_handle).await;
+            update_tray(&app_handle).await;
+
+            let status_rx = {
+                let state = app_handle.state::<AppState>();
+                let inner = state.inner.read().await;
+                inner.as_ref().map(|daemon| {
+                    let status = daemon.service.sync_status();
+                    (status.subscribe(), status.subscribe_paused())
+                })
+            };
+            match status_rx {
+                Some((mut activity_rx, mut paused_rx)) => {
+                    let changed = async {
+                        tokio::select! {
+                            _ = activity_rx.changed() => {}
+                            _ = paused_rx.changed() => {}
+                        }
+                    };
+                    let _ = tokio::time::timeout(REFRESH_INTERVAL, changed).await;
+                }
+                None => tokio::time::sleep(REFRESH_INTERVAL).await,
+            }
         }
     });
 
     Ok(())
 }
 
-/// Update the tray status menu item
-async fn update_tray_status(app: &tauri::AppHandle) {
+async fn handle_menu_event(app: &AppHandle, id: &str) -> Result<(), String> {
+    match id {
+        "open" => show_window(app),
+        "quit" => app.exit(0),
+        "gateway" => {
+            let port = with_daemon(app, |daemon| daemon.gateway_port).await?;
+            // Shell::open is deprecated in favour of the opener plugin, which
+            // the app does not ship yet
+            #[allow(deprecated)]
+            {
+                use tauri_plugin_shell::ShellExt;
+                app.shell()
+                    .open(format!("http://localhost:{}", port), None)
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+        "copy_peer_id" => {
+            use tauri_plugin_clipboard_manager::ClipboardExt;
+            let peer_id = with_daemon(app, |daemon| daemon.service.peer().id().to_string()).await?;
+            app.clipboard()
+                .write_text(peer_id)
+                .map_err(|e| e.to_string())?;
+        }
+        "paste_inbox" => {
+            crate::inbox::paste_clipboard(app).await?;
+        }
+        "quick_drop" => crate::inbox::show_quick_drop(app)?,
+        "toggle_sync" => {
+            let service = with_daemon(app, |daemon| daemon.service.clone()).await?;
+            let paused = !service.sync_status().is_paused();
+            service
+                .set_sync_paused(paused)
+                .await
+                .map_err(|e| e.to_string())?;
+            let operation = if paused { "sync.pause" } else { "sync.resume" };
+            service
+                .audit(AuditEvent::new(Actor::Desktop, operation))
+                .await;
+            update_tray(app).await;
+        }
+        other => {
+            if let Some(bucket_id) = other.strip_prefix("bucket:") {
+                show_window(app);
+                app.emit(OPEN_BUCKET_EVENT, bucket_id)
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn show_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+async fn with_daemon<T>(
+    app: &AppHandle,
+    f: impl FnOnce(&crate::DaemonInner) -> T,
+) -> Result<T, String> {
     let state = app.state::<AppState>();
     let inner = state.inner.read().await;
+    inner
+        .as_ref()
+        .map(f)
+        .ok_or_else(|| "Daemon not started".to_string())
+}
 
-    let status_text = match inner.as_ref() {
-        Some(daemon) => format!(
-            "Status: Running (API:{}, GW:{})",
-            daemon.api_port, daemon.gateway_port
-        ),
-        None => "Status: Starting...".to_string(),
+/// Refresh the tray icon, status text, pause item and recent buckets
+async fn update_tray(app: &AppHandle) {
+    let (status, buckets, running, paused) = {
+        let state = app.state::<AppState>();
+        let inner = state.inner.read().await;
+        match inner.as_ref() {
+            Some(daemon) => {
+                let sync = daemon.service.sync_status();
+                let status = if !daemon.service.is_online() {
+                    TrayStatus::Offline
+                } else if sync.is_paused() {
+                    TrayStatus::Paused
+                } else {
+                    match sync.activity() {
+                        SyncActivity::Idle => TrayStatus::Idle,
+                        SyncActivity::Syncing => TrayStatus::Syncing,
+                        SyncActivity::Error(e) => TrayStatus::Error(e),
+                    }
+                };
+                let buckets = daemon
+                    .service
+                    .database()
+                    .list_buckets(None, Some(RECENT_BUCKETS))
+                    .await
+                    .map(|buckets| buckets.into_iter().map(|b| (b.id, b.name)).collect())
+                    .unwrap_or_default();
+                (status, buckets, true, sync.is_paused())
+            }
+            None => (TrayStatus::Offline, Vec::new(), false, false),
+        }
     };
 
-    tracing::debug!("Tray status: {}", status_text);
-
     let tray_state = app.state::<TrayState>();
-    let _ = tray_state.status_item.set_text(&status_text);
+    let mut shown = tray_state.shown.lock().unwrap_or_else(|e| e.into_inner());
+
+    if shown.0.as_ref() != Some(&status) {
+        tracing::debug!("Tray status: {:?}", status);
+        let icons = &tray_state.icons;
+        let icon = match status {
+            TrayStatus::Offline => &icons.offline,
+            TrayStatus::Idle => &icons.idle,
+            TrayStatus::Syncing => &icons.syncing,
+            TrayStatus::Paused => &icons.paused,
+            TrayStatus::Error(_) => &icons.error,
+        };
+        let _ = tray_state.tray.set_icon(Some(icon.clone()));
+        // Only the plain icon can be drawn as a monochrome template on macOS
+        let _ = tray_state
+            .tray
+            .set_icon_as_template(status == TrayStatus::Idle);
+        let _ = tray_state.tray.set_tooltip(Some(status.label()));
+        let _ = tray_state.status_item.set_text(status.label());
+        shown.0 = Some(status);
+    }
+    let _ = tray_state.pause_item.set_text(if paused {
+        "Resume Syncing"
+    } else {
+        "Pause Syncing"
+    });
+    let _ = tray_state.pause_item.set_enabled(running);
+
+    if shown.1 != buckets {
+        if let Err(e) = set_recent_buckets(app, &tray_state.recent_menu, &buckets) {
+            tracing::warn!("Failed to update recent buckets menu: {}", e);
+        }
+        shown.1 = buckets;
+    }
+}
+
+fn set_recent_buckets(
+    app: &AppHandle,
+    menu: &Submenu<tauri::Wry>,
+    buckets: &[(Uuid, String)],
+) -> tauri::Result<()> {
+    for item in menu.items()? {
+        menu.remove(&item)?;
+    }
+    for (id, name) in buckets {
+        let item = MenuItem::with_id(app, format!("bucket:{}", id), name, true, None::<&str>)?;
+        menu.append(&item)?;
+    }
+    menu.set_enabled(!buckets.is_empty())
+}
+
+/// Copy of `base` with a status dot in the bottom-right corner
+fn badged(base: &Image<'_>, rgb: [u8; 3]) -> Image<'static> {
+    let (width, height) = (base.width(), base.height());
+    let mut rgba = base.rgba().to_vec();
+
+    let radius = width.min(height) as f32 * 0.22;
+    let (cx, cy) = (width as f32 - radius - 1.0, height as f32 - radius - 1.0);
+    for y in 0..height {
+        for x in 0..width {
+            let (dx, dy) = (x as f32 + 0.5 - cx, y as f32 + 0.5 - cy);
+            let distance = (dx * dx + dy * dy).sqrt();
+            let i = ((y * width + x) * 4) as usize;
+            if distance <= radius {
+                rgba[i..i + 4].copy_from_slice(&[rgb[0], rgb[1], rgb[2], 0xff]);
+            } else if distance <= radius + 1.5 {
+                // Clear a ring around the dot so it stands out from the glyph
+                rgba[i + 3] = 0;
+            }
+        }
+    }
+
+    Image::new_owned(rgba, width, height)
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        s.to_string()
+    } else {
+        format!("{}...", s.chars().take(max).collect::<String>())
+    }
 }
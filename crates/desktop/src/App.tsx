@@ -1,5 +1,7 @@
-import { Router, Route, A, useLocation } from '@solidjs/router';
-import { Component } from 'solid-js';
+import { Router, Route, A, useLocation, useNavigate } from '@solidjs/router';
+import { listen } from '@tauri-apps/api/event';
+import { Component, createSignal, onCleanup, onMount, Show } from 'solid-js';
+import { isInitialized } from './lib/api';
 import Home from './pages/Home';
 import Buckets from './pages/Buckets';
 import Explorer from './pages/Explorer';
@@ -8,9 +10,18 @@ import Editor from './pages/Editor';
 import History from './pages/History';
 import Mounts from './pages/Mounts';
 import Settings from './pages/Settings';
+import Onboarding from './pages/Onboarding';
+import QuickDrop from './pages/QuickDrop';
 
 const Layout: Component<{ children?: any }> = (props) => {
   const location = useLocation();
+  const navigate = useNavigate();
+
+  // "Recent Buckets" in the tray menu
+  const unlisten = listen<string>('tray-open-bucket', (event) => {
+    navigate(`/buckets/${event.payload}`);
+  });
+  onCleanup(() => unlisten.then((fn) => fn()));
 
   const navLink = (href: string, label: string, icon: string) => {
     const active = () => location.pathname === href || location.pathname.startsWith(href + '/');
@@ -39,6 +50,11 @@ const Layout: Component<{ children?: any }> = (props) => {
     );
   };
 
+  // The quick drop window shows its page alone
+  if (location.pathname === '/quick-drop') {
+    return <>{props.children}</>;
+  }
+
   return (
     <div style={{ display: 'flex', 'min-height': '100vh' }}>
       <nav style={{
@@ -82,6 +98,27 @@ const Layout: Component<{ children?: any }> = (props) => {
 };
 
 const App: Component = () => {
+  // null until known; the wizard replaces the app on first run
+  const [initialized, setInitialized] = createSignal<boolean | null>(null);
+
+  onMount(async () => {
+    try {
+      setInitialized(await isInitialized());
+    } catch (_e) {
+      setInitialized(true);
+    }
+  });
+
+  return (
+    <Show when={initialized() !== null}>
+      <Show when={initialized()} fallback={<Onboarding onComplete={() => setInitialized(true)} />}>
+        <Routes />
+      </Show>
+    </Show>
+  );
+};
+
+const Routes: Component = () => {
   return (
     <Router root={Layout}>
       <Route path="/" component={Home} />
@@ -92,6 +129,7 @@ const App: Component = () => {
       <Route path="/buckets/:bucketId/history" component={History} />
       <Route path="/mounts" component={Mounts} />
       <Route path="/settings" component={Settings} />
+      <Route path="/quick-drop" component={QuickDrop} />
     </Router>
   );
 };
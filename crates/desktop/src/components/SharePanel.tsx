@@ -1,5 +1,16 @@
 import { Component, createSignal, onMount, Show, For } from 'solid-js';
-import { getBucketShares, shareBucket, pingPeer, ShareInfo } from '../lib/api';
+import {
+  getBucketShares,
+  shareBucket,
+  shareBucketEphemeral,
+  pingPeer,
+  ShareInfo,
+  getSyncPolicy,
+  setSyncPolicy,
+  syncBucketNow,
+} from '../lib/api';
+
+type ScheduleMode = 'default' | 'interval' | 'manual' | 'paused';
 
 interface SharePanelProps {
   bucketId: string;
@@ -18,11 +29,78 @@ const SharePanel: Component<SharePanelProps> = (props) => {
   const [sharing, setSharing] = createSignal(false);
   const [shareSuccess, setShareSuccess] = createSignal<string | null>(null);
 
+  // Share via temporary identity
+  const [ephemeralTtl, setEphemeralTtl] = createSignal(String(7 * 24 * 60 * 60));
+  const [ephemeralTicket, setEphemeralTicket] = createSignal<string | null>(null);
+  const [sharingEphemeral, setSharingEphemeral] = createSignal(false);
+
   // Ping
   const [pingKey, setPingKey] = createSignal<string | null>(null);
   const [pingResult, setPingResult] = createSignal<string | null>(null);
   const [pinging, setPinging] = createSignal(false);
 
+  // Sync schedule
+  const [scheduleMode, setScheduleMode] = createSignal<ScheduleMode>('default');
+  const [intervalMins, setIntervalMins] = createSignal('15');
+  const [activeHours, setActiveHours] = createSignal('');
+  const [onlineOnly, setOnlineOnly] = createSignal(false);
+  const [historyDepth, setHistoryDepth] = createSignal<number | null>(null);
+  const [scheduleResult, setScheduleResult] = createSignal<string | null>(null);
+
+  const loadSchedule = async () => {
+    try {
+      const policy = await getSyncPolicy(props.bucketId);
+      setScheduleMode(
+        policy.paused ? 'paused'
+          : policy.manual_only ? 'manual'
+            : policy.interval_secs !== null ? 'interval'
+              : 'default'
+      );
+      if (policy.interval_secs !== null) {
+        setIntervalMins(String(Math.max(1, Math.round(policy.interval_secs / 60))));
+      }
+      setActiveHours(policy.active_hours ?? '');
+      setOnlineOnly(policy.online_only);
+      setHistoryDepth(policy.history_depth);
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const saveSchedule = async () => {
+    setError(null);
+    setScheduleResult(null);
+    const mins = Number(intervalMins());
+    if (scheduleMode() === 'interval' && (!Number.isInteger(mins) || mins < 1)) {
+      setError('Interval must be a whole number of minutes');
+      return;
+    }
+    try {
+      await setSyncPolicy(props.bucketId, {
+        interval_secs: scheduleMode() === 'interval' ? mins * 60 : null,
+        manual_only: scheduleMode() === 'manual',
+        paused: scheduleMode() === 'paused',
+        active_hours: activeHours().trim() || null,
+        online_only: onlineOnly(),
+        history_depth: historyDepth(),
+      });
+      setScheduleResult('Schedule saved');
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const handleSyncNow = async () => {
+    setError(null);
+    setScheduleResult(null);
+    try {
+      await syncBucketNow(props.bucketId);
+      setScheduleResult('Syncing with peers');
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
   const loadShares = async () => {
     setLoading(true);
     setError(null);
@@ -37,13 +115,19 @@ const SharePanel: Component<SharePanelProps> = (props) => {
   };
 
   onMount(() => {
-    if (props.open) loadShares();
+    if (props.open) {
+      loadShares();
+      loadSchedule();
+    }
   });
 
   // Reload when panel opens
   const prevOpen = { value: props.open };
   const checkOpen = () => {
-    if (props.open && !prevOpen.value) loadShares();
+    if (props.open && !prevOpen.value) {
+      loadShares();
+      loadSchedule();
+    }
     prevOpen.value = props.open;
   };
 
@@ -65,6 +149,20 @@ const SharePanel: Component<SharePanelProps> = (props) => {
     }
   };
 
+  const handleShareEphemeral = async () => {
+    setSharingEphemeral(true);
+    setError(null);
+    setShareSuccess(null);
+    setEphemeralTicket(null);
+    try {
+      setEphemeralTicket(await shareBucketEphemeral(props.bucketId, Number(ephemeralTtl())));
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setSharingEphemeral(false);
+    }
+  };
+
   const handlePing = async (publicKey: string) => {
     setPingKey(publicKey);
     setPinging(true);
@@ -104,7 +202,7 @@ const SharePanel: Component<SharePanelProps> = (props) => {
           'align-items': 'center',
           'margin-bottom': '1.5rem',
         }}>
-          <h3 style={{ 'font-size': '1rem', 'font-weight': '600' }}>Sharing</h3>
+          <h3 style={{ 'font-size': '1rem', 'font-weight': '600' }}>Sharing &amp; Sync</h3>
           <button
             onClick={props.onClose}
             style={{
@@ -249,6 +347,72 @@ const SharePanel: Component<SharePanelProps> = (props) => {
           </Show>
         </div>
 
+        {/* Sync schedule */}
+        <div style={{ 'margin-bottom': '1.5rem' }}>
+          <div style={labelStyle()}>Sync Schedule</div>
+          <div style={{ display: 'flex', 'flex-direction': 'column', gap: '0.5rem' }}>
+            <select
+              value={scheduleMode()}
+              onChange={(e) => setScheduleMode(e.currentTarget.value as ScheduleMode)}
+              style={inputStyle()}
+            >
+              <option value="default">Default interval</option>
+              <option value="interval">Custom interval</option>
+              <option value="manual">Manual only</option>
+              <option value="paused">Paused</option>
+            </select>
+            <Show when={scheduleMode() === 'interval'}>
+              <div style={{ display: 'flex', gap: '0.5rem', 'align-items': 'center' }}>
+                <label style={{ 'font-size': '0.8125rem', color: 'var(--muted-fg)' }}>Every</label>
+                <input
+                  type="number"
+                  min="1"
+                  value={intervalMins()}
+                  onInput={(e) => setIntervalMins(e.currentTarget.value)}
+                  style={{ ...inputStyle(), width: '5rem' }}
+                />
+                <label style={{ 'font-size': '0.8125rem', color: 'var(--muted-fg)' }}>minutes</label>
+              </div>
+            </Show>
+            <Show when={scheduleMode() === 'default' || scheduleMode() === 'interval'}>
+              <input
+                type="text"
+                placeholder="Active hours, e.g. 08:00-18:00 (any time if empty)"
+                value={activeHours()}
+                onInput={(e) => setActiveHours(e.currentTarget.value)}
+                style={inputStyle()}
+              />
+            </Show>
+            <label style={{ display: 'flex', 'align-items': 'center', gap: '0.5rem', cursor: 'pointer' }}>
+              <input
+                type="checkbox"
+                checked={onlineOnly()}
+                onChange={(e) => setOnlineOnly(e.target.checked)}
+              />
+              <span style={{ 'font-size': '0.875rem' }}>
+                Online-only (download files when opened)
+              </span>
+            </label>
+            <div style={{ display: 'flex', gap: '0.5rem' }}>
+              <button onClick={saveSchedule} style={smallButtonStyle()}>
+                Save
+              </button>
+              <button
+                onClick={handleSyncNow}
+                disabled={scheduleMode() === 'paused'}
+                style={smallButtonStyle()}
+              >
+                Sync now
+              </button>
+            </div>
+            <Show when={scheduleResult()}>
+              <div style={{ 'font-size': '0.75rem', color: 'var(--muted-fg)' }}>
+                {scheduleResult()}
+              </div>
+            </Show>
+          </div>
+        </div>
+
         {/* Add peer form */}
         <div>
           <div style={labelStyle()}>Add Peer</div>
@@ -301,6 +465,52 @@ const SharePanel: Component<SharePanelProps> = (props) => {
             </button>
           </div>
         </div>
+
+        {/* Share via temporary identity */}
+        <div style={{ 'margin-top': '1.5rem' }}>
+          <div style={labelStyle()}>Share via Temporary Identity</div>
+          <p style={{ color: 'var(--muted-fg)', 'font-size': '0.75rem', 'margin-bottom': '0.5rem' }}>
+            Read-only access for a throwaway key, so the recipient isn't recorded in the bucket.
+            Send them the ticket; they claim it with <code>jax bucket claim</code>.
+          </p>
+          <div style={{ display: 'flex', gap: '0.5rem', 'align-items': 'center' }}>
+            <label style={{ 'font-size': '0.8125rem', color: 'var(--muted-fg)' }}>Expires in:</label>
+            <select
+              value={ephemeralTtl()}
+              onChange={(e) => setEphemeralTtl(e.currentTarget.value)}
+              style={inputStyle()}
+            >
+              <option value={String(60 * 60)}>1 hour</option>
+              <option value={String(24 * 60 * 60)}>1 day</option>
+              <option value={String(7 * 24 * 60 * 60)}>1 week</option>
+              <option value={String(30 * 24 * 60 * 60)}>30 days</option>
+            </select>
+            <button
+              onClick={handleShareEphemeral}
+              disabled={sharingEphemeral()}
+              style={smallButtonStyle()}
+            >
+              {sharingEphemeral() ? 'Sharing...' : 'Create ticket'}
+            </button>
+          </div>
+          <Show when={ephemeralTicket()}>
+            <textarea
+              readOnly
+              value={ephemeralTicket()!}
+              onFocus={(e) => e.currentTarget.select()}
+              style={{
+                ...inputStyle(),
+                width: '100%',
+                'margin-top': '0.5rem',
+                'font-family': 'monospace',
+                'font-size': '0.6875rem',
+                'word-break': 'break-all',
+                resize: 'none',
+                height: '5rem',
+              }}
+            />
+          </Show>
+        </div>
       </div>
     </Show>
   );
@@ -330,4 +540,17 @@ function inputStyle(): Record<string, string> {
   };
 }
 
+function smallButtonStyle(): Record<string, string> {
+  return {
+    padding: '0.375rem 0.75rem',
+    'border-radius': '8px',
+    border: '1px solid var(--border)',
+    background: 'var(--bg)',
+    color: 'var(--fg)',
+    cursor: 'pointer',
+    'font-size': '0.8125rem',
+    'font-family': 'inherit',
+  };
+}
+
 export default SharePanel;
@@ -18,6 +18,8 @@ export interface FileEntry {
   is_dir: boolean;
   mime_type: string;
   link_hash: string;
+  /** Public keys of owners editing the file right now */
+  editing: string[];
 }
 
 export interface CatResult {
@@ -87,22 +89,138 @@ export async function shareBucket(bucketId: string, peerPublicKey: string, role:
   return invoke('share_bucket', { bucketId, peerPublicKey, role });
 }
 
+/** Share a bucket with a temporary identity, returning the ticket for the recipient */
+export async function shareBucketEphemeral(bucketId: string, ttlSecs: number): Promise<string> {
+  return invoke('share_bucket_ephemeral', { bucketId, ttlSecs });
+}
+
 export async function isPublished(bucketId: string): Promise<boolean> {
   return invoke('is_published', { bucketId });
 }
 
-export async function publishBucket(bucketId: string): Promise<void> {
+/** Publish a bucket, returning the gateway path it is served at */
+export async function publishBucket(bucketId: string): Promise<string> {
   return invoke('publish_bucket', { bucketId });
 }
 
+/** Revoke the current public link and publish at a new gateway path */
+export async function rotatePublicLink(bucketId: string): Promise<string> {
+  return invoke('rotate_public_link', { bucketId });
+}
+
+export async function unpublishBucket(bucketId: string): Promise<void> {
+  return invoke('unpublish_bucket', { bucketId });
+}
+
 export async function pingPeer(bucketId: string, peerPublicKey: string): Promise<string> {
   return invoke('ping_peer', { bucketId, peerPublicKey });
 }
 
+// Sync schedule
+export interface SyncPolicy {
+  interval_secs: number | null;
+  manual_only: boolean;
+  paused: boolean;
+  /** Daily local time window, e.g. "08:00-18:00" */
+  active_hours: string | null;
+  /** Fetch file content when opened instead of after every sync */
+  online_only: boolean;
+  /** Keep the content of only this many recent versions */
+  history_depth: number | null;
+}
+
+export async function getSyncPolicy(bucketId: string): Promise<SyncPolicy> {
+  return invoke('get_sync_policy', { bucketId });
+}
+
+export async function setSyncPolicy(bucketId: string, policy: SyncPolicy): Promise<void> {
+  return invoke('set_sync_policy', { bucketId, policy });
+}
+
+export async function syncBucketNow(bucketId: string): Promise<void> {
+  return invoke('sync_bucket_now', { bucketId });
+}
+
+export interface PhotoInfo {
+  bucket_id: string;
+  path: string;
+  mime_type: string;
+  /** Seconds since the Unix epoch, absent if the photo has no capture date */
+  captured_at?: number;
+  width?: number;
+  height?: number;
+}
+
+export interface PhotoTimelinePage {
+  items: PhotoInfo[];
+  /** Offset of the next page, absent on the last page */
+  next_offset?: number;
+  skipped_buckets?: string[];
+}
+
+/** Photos of a bucket, or of every bucket, newest capture date first */
+export async function photoTimeline(
+  bucketId: string | null,
+  offset?: number,
+  limit?: number,
+): Promise<PhotoTimelinePage> {
+  return invoke('photo_timeline', { bucketId, offset, limit });
+}
+
 export async function uploadNativeFiles(bucketId: string, mountPath: string, filePaths: string[]): Promise<void> {
   return invoke('upload_native_files', { bucketId, mountPath, filePaths });
 }
 
+/** Emitted as the 'download-progress' event while downloadPaths runs */
+export interface DownloadProgress {
+  bucket_id: string;
+  path: string;
+  files_done: number;
+  files_total: number;
+  bytes_done: number;
+}
+
+export interface DownloadResult {
+  files: number;
+  bytes: number;
+}
+
+/** Write files and directories (recursively) to a folder on disk */
+export async function downloadPaths(bucketId: string, paths: string[], destDir: string): Promise<DownloadResult> {
+  return invoke('download_paths', { bucketId, paths, destDir });
+}
+
+/** A bucket file opened in its default app */
+export interface EditSession {
+  id: string;
+  bucket_id: string;
+  path: string;
+  local_path: string;
+}
+
+/** Emitted as the 'edit-saved' event when a save is written back */
+export interface EditSaved {
+  session_id: string;
+  bucket_id: string;
+  /** The file's own path, or a conflict copy's if it changed in the bucket */
+  path: string;
+  conflict: boolean;
+  link_hash: string;
+}
+
+/** Open a file with the OS default app, writing its saves back to the bucket */
+export async function openWithDefaultApp(bucketId: string, path: string): Promise<EditSession> {
+  return invoke('open_with_default_app', { bucketId, path });
+}
+
+export async function listEditSessions(): Promise<EditSession[]> {
+  return invoke('list_edit_sessions');
+}
+
+export async function stopEditing(sessionId: string): Promise<void> {
+  return invoke('stop_editing', { sessionId });
+}
+
 export async function mkdir(bucketId: string, path: string): Promise<void> {
   return invoke('mkdir', { bucketId, path });
 }
@@ -142,12 +260,51 @@ export interface ConfigInfo {
   db_path: string;
   config_path: string;
   blob_store: string;
+  /** Largest file accepted into buckets without their own upload limit */
+  max_upload_bytes: number;
+  /** Upload limits of single buckets by id, in place of `max_upload_bytes` */
+  bucket_upload_limits: Record<string, number>;
 }
 
 export async function getConfigInfo(): Promise<ConfigInfo> {
   return invoke('get_config_info');
 }
 
+// Crash reports
+export interface CrashReport {
+  id: string;
+  timestamp: string;
+  kind: 'panic' | 'task_failure';
+  message: string;
+  location: string | null;
+  backtrace: string | null;
+  app_version: string;
+  os: string;
+  arch: string;
+  log_tail: string[];
+  sent: boolean;
+}
+
+export async function getCrashReportingEnabled(): Promise<boolean> {
+  return invoke('get_crash_reporting_enabled');
+}
+
+export async function setCrashReportingEnabled(enabled: boolean): Promise<void> {
+  return invoke('set_crash_reporting_enabled', { enabled });
+}
+
+export async function listCrashReports(): Promise<CrashReport[]> {
+  return invoke('list_crash_reports');
+}
+
+export async function deleteCrashReport(id: string): Promise<void> {
+  return invoke('delete_crash_report', { id });
+}
+
+export async function sendCrashReport(id: string): Promise<string> {
+  return invoke('send_crash_report', { id });
+}
+
 // Mount types
 export interface MountInfo {
   mount_id: string;
@@ -227,3 +384,166 @@ export async function unmountBucket(bucketId: string): Promise<boolean> {
 export async function isBucketMounted(bucketId: string): Promise<MountInfo | null> {
   return invoke('is_bucket_mounted', { bucketId });
 }
+
+// First-run onboarding
+export interface PortChoice {
+  api_port: number;
+  gateway_port: number;
+  peer_port: number | null;
+}
+
+export interface InitResult {
+  jax_dir: string;
+  node_id: string;
+}
+
+export async function isInitialized(): Promise<boolean> {
+  return invoke('is_initialized');
+}
+
+export async function suggestPorts(): Promise<PortChoice> {
+  return invoke('suggest_ports');
+}
+
+export async function initIdentity(ports: PortChoice): Promise<InitResult> {
+  return invoke('init_identity', { ports });
+}
+
+export async function importIdentity(keyPem: string, ports: PortChoice): Promise<InitResult> {
+  return invoke('import_identity', { keyPem, ports });
+}
+
+export async function importIdentityBundle(path: string, passphrase: string, ports: PortChoice): Promise<InitResult> {
+  return invoke('import_identity_bundle', { path, passphrase, ports });
+}
+
+// Identity bundles
+export async function exportIdentityBundle(passphrase: string, path: string): Promise<number> {
+  return invoke('export_identity_bundle', { passphrase, path });
+}
+
+// Social recovery
+export interface EscrowSetup {
+  bucket_id: string;
+  recovery_key: string;
+  threshold: number;
+  trustees: string[];
+  new_bucket_link: string;
+}
+
+export interface HeldShard {
+  bucket_id: string;
+  owner: string;
+  recovery_key: string;
+  threshold: number;
+}
+
+export interface ReleaseRequest {
+  bucket_id: string;
+  requester: string;
+  approved: boolean;
+}
+
+export interface HeldShards {
+  shards: HeldShard[];
+  requests: ReleaseRequest[];
+}
+
+export type RecoveryState = 'waiting' | 'syncing' | 'recovered';
+
+export interface RecoveryProgress {
+  bucket_id: string;
+  state: RecoveryState;
+  released: string[];
+  pending: string[];
+  threshold?: number;
+  new_bucket_link?: string;
+}
+
+export async function setUpRecoveryEscrow(
+  bucketId: string,
+  trustees: string[],
+  threshold: number,
+): Promise<EscrowSetup> {
+  return invoke('set_up_recovery_escrow', { bucketId, trustees, threshold });
+}
+
+export async function listHeldShards(): Promise<HeldShards> {
+  return invoke('list_held_shards');
+}
+
+export async function approveShardRelease(bucketId: string, requester: string): Promise<void> {
+  return invoke('approve_shard_release', { bucketId, requester });
+}
+
+export async function recoverBucketAccess(bucketId: string, trustees: string[]): Promise<RecoveryProgress> {
+  return invoke('recover_bucket_access', { bucketId, trustees });
+}
+
+// Sync pause
+export async function getSyncPaused(): Promise<boolean> {
+  return invoke('get_sync_paused');
+}
+
+export async function setSyncPaused(paused: boolean): Promise<void> {
+  return invoke('set_sync_paused', { paused });
+}
+
+// Sync deferral on battery and metered networks
+export type MeteredOverride = 'auto' | 'always' | 'never';
+
+export interface SyncDeferral {
+  on_battery: boolean;
+  battery_interval_multiplier: number;
+  defer_downloads_on_metered: boolean;
+  metered: MeteredOverride;
+}
+
+export interface SyncConditions {
+  on_battery: boolean;
+  battery_percent?: number;
+  metered: boolean;
+}
+
+export interface SyncDeferralInfo {
+  deferral: SyncDeferral;
+  conditions: SyncConditions;
+  deferred_jobs: number;
+}
+
+export async function getSyncDeferral(): Promise<SyncDeferralInfo> {
+  return invoke('get_sync_deferral');
+}
+
+export async function setSyncDeferral(deferral: SyncDeferral): Promise<void> {
+  return invoke('set_sync_deferral', { deferral });
+}
+
+// Inbox
+export type QuickAddContent =
+  | { kind: 'text'; text: string }
+  | { kind: 'bytes'; data: number[]; name?: string }
+  | { kind: 'files'; paths: string[] };
+
+export interface QuickAddResult {
+  bucket_id: string;
+  paths: string[];
+  link_hash: string;
+}
+
+/** Add pasted or dropped content to the inbox bucket, creating it if needed */
+export async function quickAdd(content: QuickAddContent): Promise<QuickAddResult> {
+  return invoke('quick_add', { content });
+}
+
+export async function getInboxBucket(): Promise<string | null> {
+  return invoke('get_inbox_bucket');
+}
+
+export async function setInboxBucket(bucketId: string | null): Promise<void> {
+  return invoke('set_inbox_bucket', { bucketId });
+}
+
+export async function showQuickDrop(): Promise<void> {
+  return invoke('show_quick_drop');
+}
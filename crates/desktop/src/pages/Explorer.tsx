@@ -1,6 +1,7 @@
-import { Component, createSignal, onMount, For, Show, createMemo } from 'solid-js';
+import { Component, createSignal, onMount, onCleanup, For, Show, createMemo } from 'solid-js';
+import { listen } from '@tauri-apps/api/event';
 import { useParams, useSearchParams, useNavigate } from '@solidjs/router';
-import { ls, lsAtVersion, mkdir, deletePath, renamePath, uploadNativeFiles, addFile, publishBucket, isPublished as checkPublished, FileEntry } from '../lib/api';
+import { ls, lsAtVersion, mkdir, deletePath, renamePath, uploadNativeFiles, downloadPaths, DownloadProgress, openWithDefaultApp, EditSaved, addFile, publishBucket, rotatePublicLink, unpublishBucket, isPublished as checkPublished, FileEntry } from '../lib/api';
 import { pathToBreadcrumbs } from '../lib/utils';
 import Breadcrumb from '../components/Breadcrumb';
 import ConfirmDialog from '../components/ConfirmDialog';
@@ -34,6 +35,9 @@ const Explorer: Component = () => {
   // Delete confirmation state
   const [deleteTarget, setDeleteTarget] = createSignal<FileEntry | null>(null);
 
+  // Download progress (e.g. "3/10 files") and edits saved back
+  const [status, setStatus] = createSignal<string | null>(null);
+
   // Share panel
   const [showSharePanel, setShowSharePanel] = createSignal(false);
 
@@ -116,6 +120,64 @@ const Explorer: Component = () => {
     }
   };
 
+  // Other owners start and stop editing files, so keep their marks fresh
+  //  without reloading the listing
+  const editingPoll = setInterval(async () => {
+    if (versionHash()) return;
+    try {
+      const fresh = await ls(params.bucketId, currentPath());
+      const editing = new Map(fresh.map((e) => [e.path, e.editing]));
+      setEntries((prev) => prev.map((e) => ({ ...e, editing: editing.get(e.path) ?? [] })));
+    } catch {
+      // Non-critical, ignore
+    }
+  }, 15000);
+  onCleanup(() => clearInterval(editingPoll));
+
+  const unlistenDownload = listen<DownloadProgress>('download-progress', (event) => {
+    const p = event.payload;
+    if (p.bucket_id !== params.bucketId) return;
+    const mb = (p.bytes_done / (1024 * 1024)).toFixed(1);
+    setStatus(`Downloading ${p.files_done}/${p.files_total} files (${mb} MB)...`);
+  });
+  onCleanup(() => unlistenDownload.then((fn) => fn()));
+
+  const unlistenEdit = listen<EditSaved>('edit-saved', (event) => {
+    const saved = event.payload;
+    if (saved.bucket_id !== params.bucketId) return;
+    setStatus(saved.conflict
+      ? `File changed in the bucket since it was opened; saved your edit as ${saved.path}`
+      : `Saved ${saved.path}`);
+    refresh();
+  });
+  onCleanup(() => unlistenEdit.then((fn) => fn()));
+
+  const handleOpen = async (entry: FileEntry) => {
+    try {
+      setError(null);
+      await openWithDefaultApp(params.bucketId, entry.path);
+      setStatus(`Opened ${entry.name}; saves are written back to the bucket`);
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const handleDownload = async (entry: FileEntry) => {
+    try {
+      const { open } = await import('@tauri-apps/plugin-dialog');
+      const selected = await open({ directory: true, multiple: false, title: 'Download To' });
+      if (!selected) return;
+
+      setError(null);
+      setStatus('Downloading...');
+      const result = await downloadPaths(params.bucketId, [entry.path], selected as string);
+      setStatus(`Downloaded ${result.files} ${result.files === 1 ? 'file' : 'files'}`);
+    } catch (e) {
+      setStatus(null);
+      setError(String(e));
+    }
+  };
+
   const handleNewFolder = async () => {
     const name = newFolderName().trim();
     if (!name) return;
@@ -204,6 +266,34 @@ const Explorer: Component = () => {
     }
   };
 
+  const handleRotate = async () => {
+    if (!confirm('Revoke the current public link? Anyone using it will lose access.')) return;
+    try {
+      setPublishing(true);
+      setError(null);
+      await rotatePublicLink(params.bucketId);
+      fetchPublishedStatus();
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setPublishing(false);
+    }
+  };
+
+  const handleUnpublish = async () => {
+    if (!confirm('Stop publishing this bucket? Its public link will stop working.')) return;
+    try {
+      setPublishing(true);
+      setError(null);
+      await unpublishBucket(params.bucketId);
+      fetchPublishedStatus();
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setPublishing(false);
+    }
+  };
+
   const breadcrumbs = () => pathToBreadcrumbs(currentPath());
 
   return (
@@ -383,6 +473,42 @@ const Explorer: Component = () => {
               {publishing() ? 'Publishing...' : 'Publish'}
             </button>
           </Show>
+          <Show when={isPublished()}>
+            <button
+              onClick={handleRotate}
+              disabled={publishing()}
+              style={{
+                padding: '0.5rem 0.75rem',
+                'border-radius': '8px',
+                border: '1px solid var(--border)',
+                background: 'var(--muted)',
+                color: 'var(--fg)',
+                cursor: publishing() ? 'not-allowed' : 'pointer',
+                'font-size': '0.8125rem',
+                'font-weight': '500',
+                'font-family': 'inherit',
+              }}
+            >
+              Rotate link
+            </button>
+            <button
+              onClick={handleUnpublish}
+              disabled={publishing()}
+              style={{
+                padding: '0.5rem 0.75rem',
+                'border-radius': '8px',
+                border: '1px solid var(--border)',
+                background: 'var(--muted)',
+                color: 'var(--fg)',
+                cursor: publishing() ? 'not-allowed' : 'pointer',
+                'font-size': '0.8125rem',
+                'font-weight': '500',
+                'font-family': 'inherit',
+              }}
+            >
+              Unpublish
+            </button>
+          </Show>
         </Show>
       </div>
 
@@ -516,6 +642,20 @@ const Explorer: Component = () => {
         </div>
       </Show>
 
+      {/* Status display */}
+      <Show when={status()}>
+        <div style={{
+          padding: '0.5rem 1rem',
+          'border-radius': '8px',
+          'margin-bottom': '1rem',
+          background: 'var(--muted)',
+          color: 'var(--muted-fg)',
+          'font-size': '0.8125rem',
+        }}>
+          {status()}
+        </div>
+      </Show>
+
       {/* Error display */}
       <Show when={error()}>
         <div style={{
@@ -562,7 +702,7 @@ const Explorer: Component = () => {
           {/* Table header */}
           <div style={{
             display: 'grid',
-            'grid-template-columns': '1fr 120px 160px',
+            'grid-template-columns': '1fr 120px 310px',
             padding: '0.625rem 1rem',
             background: 'var(--muted)',
             'border-bottom': '1px solid var(--border)',
@@ -582,7 +722,7 @@ const Explorer: Component = () => {
             {(entry) => (
               <div style={{
                 display: 'grid',
-                'grid-template-columns': '1fr 120px 160px',
+                'grid-template-columns': '1fr 120px 310px',
                 padding: '0.625rem 1rem',
                 'border-bottom': '1px solid var(--border)',
                 'align-items': 'center',
@@ -645,6 +785,22 @@ const Explorer: Component = () => {
                       }}
                     />
                   </Show>
+                  <Show when={entry.editing.length > 0}>
+                    <span
+                      title={`Being edited by ${entry.editing.join(', ')}`}
+                      style={{
+                        'flex-shrink': '0',
+                        'font-size': '0.6875rem',
+                        'font-weight': '500',
+                        padding: '0.125rem 0.5rem',
+                        'border-radius': '9999px',
+                        background: 'hsl(38 92% 50% / 0.12)',
+                        color: '#d97706',
+                      }}
+                    >
+                      Editing
+                    </span>
+                  </Show>
                 </div>
 
                 {/* Type badge */}
@@ -672,7 +828,19 @@ const Explorer: Component = () => {
                     </button>
                   </Show>
                   <Show when={!isHistoryView()}>
+                    <button
+                      onClick={() => handleDownload(entry)}
+                      style={actionBtnStyle()}
+                    >
+                      Download
+                    </button>
                     <Show when={!entry.is_dir}>
+                      <button
+                        onClick={() => handleOpen(entry)}
+                        style={actionBtnStyle()}
+                      >
+                        Open
+                      </button>
                       <button
                         onClick={() => navigate(`/buckets/${params.bucketId}/edit?path=${encodeURIComponent(entry.path)}`)}
                         style={actionBtnStyle()}
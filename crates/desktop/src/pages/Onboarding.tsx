@@ -0,0 +1,241 @@
+import { Component, createSignal, onMount, Show } from 'solid-js';
+import { suggestPorts, initIdentity, importIdentity, importIdentityBundle, InitResult } from '../lib/api';
+
+type IdentityMode = 'generate' | 'import' | 'restore';
+
+const Onboarding: Component<{ onComplete: (result: InitResult) => void }> = (props) => {
+  const [mode, setMode] = createSignal<IdentityMode>('generate');
+  const [keyPem, setKeyPem] = createSignal('');
+  const [bundlePath, setBundlePath] = createSignal('');
+  const [passphrase, setPassphrase] = createSignal('');
+  const [apiPort, setApiPort] = createSignal('5001');
+  const [gatewayPort, setGatewayPort] = createSignal('8080');
+  const [peerPort, setPeerPort] = createSignal('');
+  const [submitting, setSubmitting] = createSignal(false);
+  const [error, setError] = createSignal<string | null>(null);
+
+  onMount(async () => {
+    try {
+      const ports = await suggestPorts();
+      setApiPort(String(ports.api_port));
+      setGatewayPort(String(ports.gateway_port));
+    } catch (_e) {
+      // Keep the defaults
+    }
+  });
+
+  const parsePort = (value: string, name: string): number => {
+    const port = Number(value);
+    if (!Number.isInteger(port) || port < 1 || port > 65535) {
+      throw new Error(`${name} port must be between 1 and 65535`);
+    }
+    return port;
+  };
+
+  const selectBundle = async () => {
+    const { open } = await import('@tauri-apps/plugin-dialog');
+    const path = await open({ multiple: false, directory: false });
+    if (typeof path === 'string') setBundlePath(path);
+  };
+
+  const ready = () => {
+    if (mode() === 'import') return keyPem().trim() !== '';
+    if (mode() === 'restore') return bundlePath() !== '' && passphrase() !== '';
+    return true;
+  };
+
+  const submit = async () => {
+    setError(null);
+    setSubmitting(true);
+    try {
+      const ports = {
+        api_port: parsePort(apiPort(), 'API'),
+        gateway_port: parsePort(gatewayPort(), 'Gateway'),
+        peer_port: peerPort().trim() ? parsePort(peerPort(), 'Peer') : null,
+      };
+      const result = mode() === 'restore'
+        ? await importIdentityBundle(bundlePath(), passphrase(), ports)
+        : mode() === 'import'
+          ? await importIdentity(keyPem(), ports)
+          : await initIdentity(ports);
+      props.onComplete(result);
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String(e));
+    } finally {
+      setSubmitting(false);
+    }
+  };
+
+  return (
+    <div style={{ display: 'flex', 'justify-content': 'center', padding: '3rem 1rem' }}>
+      <div style={{ width: '480px', 'max-width': '100%' }}>
+        <h2 style={{ 'font-size': '1.5rem', 'font-weight': '700', 'margin-bottom': '0.5rem' }}>
+          Welcome to jax
+        </h2>
+        <p style={{ color: 'var(--muted-fg)', 'font-size': '0.875rem', 'margin-bottom': '1.5rem' }}>
+          Set up this device's identity and the ports the node listens on.
+        </p>
+
+        <Show when={error()}>
+          <div style={errorStyle()}>{error()}</div>
+        </Show>
+
+        <div style={{ ...cardStyle(), 'margin-bottom': '1rem' }}>
+          <label style={labelStyle()}>Identity</label>
+          <div style={{ display: 'flex', gap: '0.5rem', 'margin-bottom': '0.75rem' }}>
+            <button
+              onClick={() => setMode('generate')}
+              style={mode() === 'generate' ? primaryButtonStyle() : secondaryButtonStyle()}
+            >
+              Generate new key
+            </button>
+            <button
+              onClick={() => setMode('import')}
+              style={mode() === 'import' ? primaryButtonStyle() : secondaryButtonStyle()}
+            >
+              Import key
+            </button>
+            <button
+              onClick={() => setMode('restore')}
+              style={mode() === 'restore' ? primaryButtonStyle() : secondaryButtonStyle()}
+            >
+              Restore backup
+            </button>
+          </div>
+          <Show when={mode() === 'import'}>
+            <textarea
+              value={keyPem()}
+              onInput={(e) => setKeyPem(e.currentTarget.value)}
+              placeholder="Paste the contents of key.pem"
+              rows={6}
+              style={{ ...inputStyle(), 'font-family': 'monospace', 'font-size': '0.75rem' }}
+            />
+          </Show>
+          <Show when={mode() === 'restore'}>
+            <div style={{ display: 'flex', gap: '0.5rem', 'margin-bottom': '0.5rem' }}>
+              <input
+                value={bundlePath()}
+                readOnly
+                placeholder="Identity bundle from jax identity export"
+                style={{ ...inputStyle(), flex: '1' }}
+              />
+              <button onClick={selectBundle} style={secondaryButtonStyle()}>
+                Browse
+              </button>
+            </div>
+            <input
+              type="password"
+              value={passphrase()}
+              onInput={(e) => setPassphrase(e.currentTarget.value)}
+              placeholder="Passphrase"
+              style={inputStyle()}
+            />
+          </Show>
+        </div>
+
+        <div style={{ ...cardStyle(), 'margin-bottom': '1.5rem' }}>
+          <div style={{ display: 'flex', gap: '0.75rem' }}>
+            <div style={{ flex: '1' }}>
+              <label style={labelStyle()}>API port</label>
+              <input value={apiPort()} onInput={(e) => setApiPort(e.currentTarget.value)} style={inputStyle()} />
+            </div>
+            <div style={{ flex: '1' }}>
+              <label style={labelStyle()}>Gateway port</label>
+              <input value={gatewayPort()} onInput={(e) => setGatewayPort(e.currentTarget.value)} style={inputStyle()} />
+            </div>
+            <div style={{ flex: '1' }}>
+              <label style={labelStyle()}>Peer port</label>
+              <input
+                value={peerPort()}
+                onInput={(e) => setPeerPort(e.currentTarget.value)}
+                placeholder="auto"
+                style={inputStyle()}
+              />
+            </div>
+          </div>
+        </div>
+
+        <button
+          onClick={submit}
+          disabled={submitting() || !ready()}
+          style={primaryButtonStyle()}
+        >
+          {submitting() ? 'Setting up...' : 'Get started'}
+        </button>
+      </div>
+    </div>
+  );
+};
+
+function cardStyle(): Record<string, string> {
+  return {
+    background: 'var(--muted)',
+    border: '1px solid var(--border)',
+    'border-radius': 'var(--radius)',
+    padding: '1rem',
+  };
+}
+
+function errorStyle(): Record<string, string> {
+  return {
+    background: 'hsl(0 84% 60% / 0.08)',
+    border: '1px solid hsl(0 84% 60% / 0.3)',
+    padding: '0.75rem 1rem',
+    'border-radius': '8px',
+    'margin-bottom': '1rem',
+    color: 'var(--accent-red)',
+    'font-size': '0.875rem',
+  };
+}
+
+function labelStyle(): Record<string, string> {
+  return {
+    display: 'block',
+    'font-size': '0.75rem',
+    'font-weight': '500',
+    'margin-bottom': '0.375rem',
+    color: 'var(--muted-fg)',
+  };
+}
+
+function inputStyle(): Record<string, string> {
+  return {
+    width: '100%',
+    padding: '0.5rem 0.75rem',
+    border: '1px solid var(--border)',
+    'border-radius': '6px',
+    'font-size': '0.875rem',
+    'font-family': 'inherit',
+    background: 'var(--bg)',
+    color: 'var(--fg)',
+  };
+}
+
+function primaryButtonStyle(): Record<string, string> {
+  return {
+    background: 'var(--fg)',
+    color: 'var(--bg)',
+    border: 'none',
+    padding: '0.5rem 1rem',
+    'border-radius': '8px',
+    cursor: 'pointer',
+    'font-size': '0.875rem',
+    'font-weight': '500',
+    'font-family': 'inherit',
+  };
+}
+
+function secondaryButtonStyle(): Record<string, string> {
+  return {
+    background: 'var(--bg)',
+    color: 'var(--fg)',
+    border: '1px solid var(--border)',
+    padding: '0.5rem 1rem',
+    'border-radius': '8px',
+    cursor: 'pointer',
+    'font-size': '0.875rem',
+    'font-family': 'inherit',
+  };
+}
+
+export default Onboarding;
@@ -0,0 +1,69 @@
+import { Component, createSignal, onCleanup } from 'solid-js';
+import { listen } from '@tauri-apps/api/event';
+import { quickAdd, QuickAddResult } from '../lib/api';
+
+/**
+ * Small always-on-top window opened from the tray. Files dropped on it are
+ * added to the inbox by the backend; pasting adds the clipboard contents.
+ */
+const QuickDrop: Component = () => {
+  const [status, setStatus] = createSignal('Drop files or paste to add them to your inbox');
+
+  const added = (result: QuickAddResult) => {
+    const names = result.paths.map((p) => p.split('/').pop()).join(', ');
+    setStatus(`Added ${names}`);
+  };
+
+  const unlisten = listen<QuickAddResult>('inbox-added', (event) => added(event.payload));
+  onCleanup(() => unlisten.then((fn) => fn()));
+
+  const onPaste = async (e: ClipboardEvent) => {
+    e.preventDefault();
+    try {
+      const file = e.clipboardData?.files[0];
+      if (file) {
+        const data = Array.from(new Uint8Array(await file.arrayBuffer()));
+        await quickAdd({ kind: 'bytes', data, name: file.name || undefined });
+        return;
+      }
+      const text = e.clipboardData?.getData('text/plain');
+      if (text) {
+        await quickAdd({ kind: 'text', text });
+      }
+    } catch (err) {
+      setStatus(String(err));
+    }
+  };
+
+  document.addEventListener('paste', onPaste);
+  onCleanup(() => document.removeEventListener('paste', onPaste));
+
+  return (
+    <div style={{
+      height: '100vh',
+      display: 'flex',
+      'align-items': 'center',
+      'justify-content': 'center',
+      padding: '1rem',
+      'box-sizing': 'border-box',
+    }}>
+      <div style={{
+        flex: '1',
+        height: '100%',
+        border: '2px dashed var(--border)',
+        'border-radius': '12px',
+        display: 'flex',
+        'align-items': 'center',
+        'justify-content': 'center',
+        'text-align': 'center',
+        padding: '0.75rem',
+        'font-size': '0.8125rem',
+        color: 'var(--muted-fg)',
+      }}>
+        {status()}
+      </div>
+    </div>
+  );
+};
+
+export default QuickDrop;
@@ -1,5 +1,32 @@
-import { Component, createSignal, onMount, Show } from 'solid-js';
-import { getConfigInfo, ConfigInfo } from '../lib/api';
+import { Component, createSignal, For, onMount, Show } from 'solid-js';
+import {
+  getConfigInfo,
+  ConfigInfo,
+  CrashReport,
+  getCrashReportingEnabled,
+  setCrashReportingEnabled,
+  listCrashReports,
+  deleteCrashReport,
+  sendCrashReport,
+  exportIdentityBundle,
+  getSyncPaused,
+  setSyncPaused,
+  getSyncDeferral,
+  setSyncDeferral,
+  MeteredOverride,
+  SyncDeferralInfo,
+  BucketInfo,
+  listBuckets,
+  getInboxBucket,
+  setInboxBucket,
+  showQuickDrop,
+  HeldShards,
+  listHeldShards,
+  approveShardRelease,
+  setUpRecoveryEscrow,
+  recoverBucketAccess,
+} from '../lib/api';
+import { formatFileSize } from '../lib/utils';
 
 type ThemeOption = 'system' | 'light' | 'dark';
 
@@ -16,6 +43,42 @@ const Settings: Component = () => {
   const [configLoading, setConfigLoading] = createSignal(true);
   const [error, setError] = createSignal<string | null>(null);
 
+  // Crash reports
+  const [crashReporting, setCrashReporting] = createSignal(false);
+  const [crashReports, setCrashReports] = createSignal<CrashReport[]>([]);
+
+  // Global sync pause
+  const [syncPaused, setSyncPausedSignal] = createSignal(false);
+
+  // Sync deferral on battery and metered networks
+  const [syncDeferral, setSyncDeferralSignal] = createSignal<SyncDeferralInfo | null>(null);
+
+  // Inbox bucket for pasted and dropped content
+  const [buckets, setBuckets] = createSignal<BucketInfo[]>([]);
+  const [inboxBucket, setInboxBucketSignal] = createSignal<string | null>(null);
+
+  // Identity export
+  const [exportPassphrase, setExportPassphrase] = createSignal('');
+  const [exportResult, setExportResult] = createSignal<string | null>(null);
+
+  // Social recovery
+  const [heldShards, setHeldShards] = createSignal<HeldShards | null>(null);
+  const [escrowBucket, setEscrowBucket] = createSignal('');
+  const [escrowTrustees, setEscrowTrustees] = createSignal('');
+  const [escrowThreshold, setEscrowThreshold] = createSignal(2);
+  const [recoverBucket, setRecoverBucket] = createSignal('');
+  const [recoverTrustees, setRecoverTrustees] = createSignal('');
+  const [recoveryResult, setRecoveryResult] = createSignal<string | null>(null);
+
+  const loadCrashReports = async () => {
+    try {
+      setCrashReporting(await getCrashReportingEnabled());
+      setCrashReports(await listCrashReports());
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
   onMount(async () => {
     // Load auto-launch state
     try {
@@ -34,6 +97,18 @@ const Settings: Component = () => {
       setTheme(saved);
     }
 
+    await loadCrashReports();
+
+    try {
+      setSyncPausedSignal(await getSyncPaused());
+      setSyncDeferralSignal(await getSyncDeferral());
+      setBuckets(await listBuckets());
+      setInboxBucketSignal(await getInboxBucket());
+      setHeldShards(await listHeldShards());
+    } catch (_e) {
+      // Daemon not started yet
+    }
+
     // Load config info
     try {
       const info = await getConfigInfo();
@@ -61,6 +136,133 @@ const Settings: Component = () => {
     }
   };
 
+  const toggleCrashReporting = async () => {
+    try {
+      await setCrashReportingEnabled(!crashReporting());
+      setCrashReporting(!crashReporting());
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const toggleSyncPaused = async () => {
+    try {
+      await setSyncPaused(!syncPaused());
+      setSyncPausedSignal(!syncPaused());
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const updateSyncDeferral = async (change: Partial<SyncDeferralInfo['deferral']>) => {
+    const info = syncDeferral();
+    if (!info) return;
+    try {
+      await setSyncDeferral({ ...info.deferral, ...change });
+      setSyncDeferralSignal(await getSyncDeferral());
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const describeConditions = (info: SyncDeferralInfo) => {
+    const { on_battery, battery_percent, metered } = info.conditions;
+    const power = on_battery
+      ? `On battery${battery_percent !== undefined ? ` (${battery_percent}%)` : ''}`
+      : 'On mains power';
+    const network = metered ? 'metered network' : 'unmetered network';
+    const deferred = info.deferred_jobs > 0 ? `, ${info.deferred_jobs} downloads waiting` : '';
+    return `${power}, ${network}${deferred}`;
+  };
+
+  const changeInboxBucket = async (bucketId: string) => {
+    try {
+      await setInboxBucket(bucketId || null);
+      setInboxBucketSignal(bucketId || null);
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const sendReport = async (id: string) => {
+    try {
+      await sendCrashReport(id);
+      await loadCrashReports();
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const deleteReport = async (id: string) => {
+    try {
+      await deleteCrashReport(id);
+      await loadCrashReports();
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const exportIdentity = async () => {
+    setExportResult(null);
+    try {
+      const { save } = await import('@tauri-apps/plugin-dialog');
+      const path = await save({ defaultPath: 'jax-identity.jaxid' });
+      if (!path) return;
+      const buckets = await exportIdentityBundle(exportPassphrase(), path);
+      setExportPassphrase('');
+      setExportResult(`Exported identity with ${buckets} bucket(s) to ${path}`);
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const parseKeys = (text: string) => text.split(/[\s,]+/).filter((key) => key.length > 0);
+
+  const setUpRecovery = async () => {
+    setRecoveryResult(null);
+    try {
+      const setup = await setUpRecoveryEscrow(
+        escrowBucket(),
+        parseKeys(escrowTrustees()),
+        escrowThreshold(),
+      );
+      setRecoveryResult(
+        `Any ${setup.threshold} of ${setup.trustees.length} trustees can now recover the bucket`,
+      );
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const approveRelease = async (bucketId: string, requester: string) => {
+    try {
+      await approveShardRelease(bucketId, requester);
+      setHeldShards(await listHeldShards());
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const recover = async () => {
+    setRecoveryResult(null);
+    try {
+      const progress = await recoverBucketAccess(recoverBucket().trim(), parseKeys(recoverTrustees()));
+      if (progress.state === 'waiting') {
+        setRecoveryResult(
+          `${progress.released.length} of ${progress.threshold ?? '?'} shards released; ` +
+            `${progress.pending.length} trustee(s) still have to approve. Try again once they have.`,
+        );
+      } else if (progress.state === 'syncing') {
+        setRecoveryResult('The bucket is syncing. Try again in a moment to finish recovering it.');
+      } else {
+        setRecoveryResult('Recovered: this node is now an owner of the bucket.');
+        setBuckets(await listBuckets());
+      }
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
   const applyTheme = (value: ThemeOption) => {
     setTheme(value);
     if (value === 'system') {
@@ -115,6 +317,193 @@ const Settings: Component = () => {
         </div>
       </div>
 
+      {/* Syncing */}
+      <div style={cardStyle()}>
+        <h3 style={sectionHeaderStyle()}>Syncing</h3>
+
+        <div style={settingRowStyle()}>
+          <div>
+            <div style={{ 'font-size': '0.875rem', 'font-weight': '500' }}>Pause Syncing</div>
+            <div style={{ 'font-size': '0.75rem', color: 'var(--muted-fg)' }}>
+              Stop syncing with peers, e.g. on a metered network. Stays paused after a restart.
+            </div>
+          </div>
+          <button
+            onClick={toggleSyncPaused}
+            style={toggleStyle(syncPaused())}
+          >
+            <span style={toggleKnobStyle(syncPaused())} />
+          </button>
+        </div>
+
+        <Show when={syncDeferral()}>
+          {(info) => (
+            <>
+              <div style={settingRowStyle()}>
+                <div>
+                  <div style={{ 'font-size': '0.875rem', 'font-weight': '500' }}>
+                    Sync Less on Battery
+                  </div>
+                  <div style={{ 'font-size': '0.75rem', color: 'var(--muted-fg)' }}>
+                    Check peers for changes {info().deferral.battery_interval_multiplier}x less
+                    often while on battery. {describeConditions(info())}.
+                  </div>
+                </div>
+                <button
+                  onClick={() => updateSyncDeferral({ on_battery: !info().deferral.on_battery })}
+                  style={toggleStyle(info().deferral.on_battery)}
+                >
+                  <span style={toggleKnobStyle(info().deferral.on_battery)} />
+                </button>
+              </div>
+
+              <div style={settingRowStyle()}>
+                <div>
+                  <div style={{ 'font-size': '0.875rem', 'font-weight': '500' }}>
+                    Wait for Unmetered Networks
+                  </div>
+                  <div style={{ 'font-size': '0.75rem', color: 'var(--muted-fg)' }}>
+                    Hold back file downloads on hotspots and capped plans. Changes to buckets
+                    still sync.
+                  </div>
+                </div>
+                <button
+                  onClick={() =>
+                    updateSyncDeferral({
+                      defer_downloads_on_metered: !info().deferral.defer_downloads_on_metered,
+                    })
+                  }
+                  style={toggleStyle(info().deferral.defer_downloads_on_metered)}
+                >
+                  <span style={toggleKnobStyle(info().deferral.defer_downloads_on_metered)} />
+                </button>
+              </div>
+
+              <div style={settingRowStyle()}>
+                <div>
+                  <div style={{ 'font-size': '0.875rem', 'font-weight': '500' }}>
+                    Metered Network
+                  </div>
+                  <div style={{ 'font-size': '0.75rem', color: 'var(--muted-fg)' }}>
+                    Detect it automatically, or say whether this network is metered.
+                  </div>
+                </div>
+                <select
+                  value={info().deferral.metered}
+                  onChange={(e) =>
+                    updateSyncDeferral({ metered: e.currentTarget.value as MeteredOverride })
+                  }
+                  style={{
+                    padding: '0.375rem 0.625rem',
+                    border: '1px solid var(--border)',
+                    'border-radius': '6px',
+                    'font-size': '0.8125rem',
+                    'font-family': 'inherit',
+                    background: 'var(--bg)',
+                    color: 'var(--fg)',
+                  }}
+                >
+                  <option value="auto">Detect</option>
+                  <option value="always">Always metered</option>
+                  <option value="never">Never metered</option>
+                </select>
+              </div>
+            </>
+          )}
+        </Show>
+      </div>
+
+      {/* Inbox */}
+      <div style={cardStyle()}>
+        <h3 style={sectionHeaderStyle()}>Inbox</h3>
+
+        <div style={settingRowStyle()}>
+          <div>
+            <div style={{ 'font-size': '0.875rem', 'font-weight': '500' }}>Inbox Bucket</div>
+            <div style={{ 'font-size': '0.75rem', color: 'var(--muted-fg)' }}>
+              Where Ctrl/Cmd+Alt+J pastes the clipboard and Quick Drop saves files. An "Inbox"
+              bucket is created the first time if none is chosen.
+            </div>
+          </div>
+          <div style={{ display: 'flex', gap: '0.5rem' }}>
+            <select
+              value={inboxBucket() ?? ''}
+              onChange={(e) => changeInboxBucket(e.currentTarget.value)}
+              style={{
+                padding: '0.375rem 0.625rem',
+                border: '1px solid var(--border)',
+                'border-radius': '6px',
+                'font-size': '0.8125rem',
+                'font-family': 'inherit',
+                background: 'var(--bg)',
+                color: 'var(--fg)',
+              }}
+            >
+              <option value="">Create when needed</option>
+              <For each={buckets()}>
+                {(bucket) => <option value={bucket.bucket_id}>{bucket.name}</option>}
+              </For>
+            </select>
+            <button onClick={() => showQuickDrop()} style={smallButtonStyle()}>
+              Quick Drop
+            </button>
+          </div>
+        </div>
+      </div>
+
+      {/* Crash Reports */}
+      <div style={cardStyle()}>
+        <h3 style={sectionHeaderStyle()}>Crash Reports</h3>
+
+        <div style={settingRowStyle()}>
+          <div>
+            <div style={{ 'font-size': '0.875rem', 'font-weight': '500' }}>Save Crash Reports</div>
+            <div style={{ 'font-size': '0.75rem', color: 'var(--muted-fg)' }}>
+              Keep a local report when Jax crashes. Nothing is sent unless you choose to.
+            </div>
+          </div>
+          <button
+            onClick={toggleCrashReporting}
+            style={toggleStyle(crashReporting())}
+          >
+            <span style={toggleKnobStyle(crashReporting())} />
+          </button>
+        </div>
+
+        <Show when={crashReports().length > 0}>
+          <div style={{ display: 'flex', 'flex-direction': 'column', gap: '0.5rem', 'margin-top': '1rem' }}>
+            <For each={crashReports()}>
+              {(report) => (
+                <div style={settingRowStyle()}>
+                  <div style={{ 'min-width': '0' }}>
+                    <div style={{
+                      'font-size': '0.8125rem',
+                      'white-space': 'nowrap',
+                      overflow: 'hidden',
+                      'text-overflow': 'ellipsis',
+                    }}>
+                      {report.message}
+                    </div>
+                    <div style={{ 'font-size': '0.75rem', color: 'var(--muted-fg)' }}>
+                      {new Date(report.timestamp).toLocaleString()}
+                      {report.sent ? ' · sent' : ''}
+                    </div>
+                  </div>
+                  <div style={{ display: 'flex', gap: '0.5rem', 'flex-shrink': '0' }}>
+                    <button onClick={() => sendReport(report.id)} style={smallButtonStyle()}>
+                      Send report
+                    </button>
+                    <button onClick={() => deleteReport(report.id)} style={smallButtonStyle()}>
+                      Delete
+                    </button>
+                  </div>
+                </div>
+              )}
+            </For>
+          </div>
+        </Show>
+      </div>
+
       {/* Appearance */}
       <div style={cardStyle()}>
         <h3 style={sectionHeaderStyle()}>Appearance</h3>
@@ -146,6 +535,162 @@ const Settings: Component = () => {
         </div>
       </div>
 
+      {/* Identity */}
+      <div style={cardStyle()}>
+        <h3 style={sectionHeaderStyle()}>Identity</h3>
+
+        <div style={{ 'font-size': '0.75rem', color: 'var(--muted-fg)', 'margin-bottom': '0.75rem' }}>
+          Export this node's key, config and bucket list as an encrypted file to move it to
+          another machine. Anyone with the file and passphrase can access your buckets.
+        </div>
+        <div style={{ display: 'flex', gap: '0.5rem' }}>
+          <input
+            type="password"
+            value={exportPassphrase()}
+            onInput={(e) => setExportPassphrase(e.currentTarget.value)}
+            placeholder="Passphrase (8+ characters)"
+            style={{
+              flex: '1',
+              padding: '0.375rem 0.625rem',
+              border: '1px solid var(--border)',
+              'border-radius': '6px',
+              'font-size': '0.8125rem',
+              'font-family': 'inherit',
+              background: 'var(--bg)',
+              color: 'var(--fg)',
+            }}
+          />
+          <button
+            onClick={exportIdentity}
+            disabled={exportPassphrase().length < 8}
+            style={smallButtonStyle()}
+          >
+            Export identity
+          </button>
+        </div>
+        <Show when={exportResult()}>
+          <div style={{ 'font-size': '0.75rem', color: 'var(--muted-fg)', 'margin-top': '0.5rem' }}>
+            {exportResult()}
+          </div>
+        </Show>
+      </div>
+
+      {/* Social Recovery */}
+      <div style={cardStyle()}>
+        <h3 style={sectionHeaderStyle()}>Recovery</h3>
+
+        <div style={{ 'font-size': '0.75rem', color: 'var(--muted-fg)', 'margin-bottom': '0.75rem' }}>
+          Let trusted peers recover a bucket if you lose this node's key. Each trustee holds a
+          shard; enough of them together can add a new node of yours as an owner.
+        </div>
+        <div style={{ display: 'flex', 'flex-direction': 'column', gap: '0.5rem' }}>
+          <select
+            value={escrowBucket()}
+            onChange={(e) => setEscrowBucket(e.currentTarget.value)}
+            style={inputStyle()}
+          >
+            <option value="">Choose a bucket</option>
+            <For each={buckets()}>
+              {(bucket) => <option value={bucket.bucket_id}>{bucket.name}</option>}
+            </For>
+          </select>
+          <textarea
+            value={escrowTrustees()}
+            onInput={(e) => setEscrowTrustees(e.currentTarget.value)}
+            placeholder="Public keys of trustees, one per line"
+            rows={3}
+            style={inputStyle()}
+          />
+          <div style={{ display: 'flex', gap: '0.5rem', 'align-items': 'center' }}>
+            <span style={{ 'font-size': '0.8125rem' }}>Trustees needed</span>
+            <input
+              type="number"
+              min="1"
+              value={escrowThreshold()}
+              onInput={(e) => setEscrowThreshold(Number(e.currentTarget.value))}
+              style={{ ...inputStyle(), width: '4rem' }}
+            />
+            <button
+              onClick={setUpRecovery}
+              disabled={!escrowBucket() || parseKeys(escrowTrustees()).length === 0}
+              style={smallButtonStyle()}
+            >
+              Set up recovery
+            </button>
+          </div>
+        </div>
+
+        <div style={{ 'font-size': '0.875rem', 'font-weight': '500', margin: '1rem 0 0.5rem' }}>
+          Recover a bucket
+        </div>
+        <div style={{ display: 'flex', 'flex-direction': 'column', gap: '0.5rem' }}>
+          <input
+            value={recoverBucket()}
+            onInput={(e) => setRecoverBucket(e.currentTarget.value)}
+            placeholder="Bucket ID"
+            style={inputStyle()}
+          />
+          <textarea
+            value={recoverTrustees()}
+            onInput={(e) => setRecoverTrustees(e.currentTarget.value)}
+            placeholder="Public keys of its trustees, one per line"
+            rows={3}
+            style={inputStyle()}
+          />
+          <button
+            onClick={recover}
+            disabled={!recoverBucket().trim() || parseKeys(recoverTrustees()).length === 0}
+            style={{ ...smallButtonStyle(), 'align-self': 'flex-start' }}
+          >
+            Recover
+          </button>
+        </div>
+        <Show when={recoveryResult()}>
+          <div style={{ 'font-size': '0.75rem', color: 'var(--muted-fg)', 'margin-top': '0.5rem' }}>
+            {recoveryResult()}
+          </div>
+        </Show>
+
+        <Show when={heldShards() && heldShards()!.shards.length > 0}>
+          <div style={{ 'font-size': '0.875rem', 'font-weight': '500', margin: '1rem 0 0.5rem' }}>
+            Shards held for others
+          </div>
+          <div style={{ 'font-size': '0.75rem', color: 'var(--muted-fg)', 'margin-bottom': '0.5rem' }}>
+            Only approve a request after checking with the owner, e.g. by phone, that the
+            requesting node is theirs.
+          </div>
+          <For each={heldShards()!.shards}>
+            {(shard) => (
+              <div style={{ 'margin-bottom': '0.5rem' }}>
+                <div style={{ 'font-size': '0.75rem', 'font-family': 'monospace', 'word-break': 'break-all' }}>
+                  {shard.bucket_id} (owner {shard.owner.slice(0, 12)}…)
+                </div>
+                <For each={heldShards()!.requests.filter((r) => r.bucket_id === shard.bucket_id)}>
+                  {(request) => (
+                    <div style={{ ...settingRowStyle(), 'margin-top': '0.25rem' }}>
+                      <span style={{ 'font-size': '0.75rem', 'font-family': 'monospace' }}>
+                        {request.requester.slice(0, 16)}…
+                      </span>
+                      <Show
+                        when={!request.approved}
+                        fallback={<span style={{ 'font-size': '0.75rem' }}>Approved</span>}
+                      >
+                        <button
+                          onClick={() => approveRelease(request.bucket_id, request.requester)}
+                          style={smallButtonStyle()}
+                        >
+                          Approve
+                        </button>
+                      </Show>
+                    </div>
+                  )}
+                </For>
+              </div>
+            )}
+          </For>
+        </Show>
+      </div>
+
       {/* Local Configuration */}
       <div style={cardStyle()}>
         <h3 style={sectionHeaderStyle()}>Local Configuration</h3>
@@ -160,6 +705,7 @@ const Settings: Component = () => {
             <ConfigRow label="Database" value={configInfo()!.db_path} />
             <ConfigRow label="Config file" value={configInfo()!.config_path} />
             <ConfigRow label="Blob store" value={configInfo()!.blob_store} />
+            <ConfigRow label="Upload limit" value={formatFileSize(configInfo()!.max_upload_bytes)} />
           </div>
         </Show>
       </div>
@@ -215,6 +761,31 @@ function settingRowStyle(): Record<string, string> {
   };
 }
 
+function inputStyle(): Record<string, string> {
+  return {
+    padding: '0.375rem 0.625rem',
+    border: '1px solid var(--border)',
+    'border-radius': '6px',
+    'font-size': '0.8125rem',
+    'font-family': 'inherit',
+    background: 'var(--bg)',
+    color: 'var(--fg)',
+  };
+}
+
+function smallButtonStyle(): Record<string, string> {
+  return {
+    padding: '0.25rem 0.625rem',
+    'border-radius': '6px',
+    border: '1px solid var(--border)',
+    background: 'var(--bg)',
+    color: 'var(--fg)',
+    cursor: 'pointer',
+    'font-size': '0.75rem',
+    'font-family': 'inherit',
+  };
+}
+
 function toggleStyle(on: boolean): Record<string, string> {
   return {
     width: '44px',
@@ -0,0 +1,195 @@
+//! UniFFI bindings of the jax lite node
+//!
+//! Native iOS and Android apps embed a [`LiteNode`] through [`JaxNode`], a
+//! facade exported with UniFFI proc-macros. The Swift and Kotlin bindings in
+//! `bindings/` are generated from the built library (see the README), so
+//! the apps call typed methods and get typed records and errors back.
+
+use std::sync::{Arc, RwLock};
+
+use jax_daemon::lite::{LiteBucket, LiteEntry, LiteError, LiteNode, LiteSyncReport};
+
+uniffi::setup_scaffolding!();
+
+/// A bucket the node has
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct Bucket {
+    pub id: String,
+    pub name: String,
+    /// Hash of the bucket's current version
+    pub version: String,
+}
+
+/// A file or directory in a bucket
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct Entry {
+    pub name: String,
+    pub path: String,
+    pub is_dir: bool,
+    pub mime_type: Option<String>,
+}
+
+/// What a background sync got done
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct SyncReport {
+    /// Buckets whose peers were pinged
+    pub buckets_pinged: u32,
+    /// Jobs still queued when the budget ran out
+    pub jobs_remaining: u32,
+    /// Whether the queue drained within the budget
+    pub finished: bool,
+}
+
+/// Why a call failed
+#[derive(Debug, thiserror::Error, uniffi::Error)]
+pub enum JaxError {
+    #[error("{message}")]
+    Failed { message: String },
+    #[error("Node was shut down")]
+    ShutDown,
+}
+
+impl From<LiteError> for JaxError {
+    fn from(e: LiteError) -> Self {
+        JaxError::Failed {
+            message: e.to_string(),
+        }
+    }
+}
+
+/// A running node
+///
+/// Calls block, so apps make them off the main thread; they may be made
+/// from several threads at once.
+#[derive(uniffi::Object)]
+pub struct JaxNode {
+    /// None once shut down
+    node: RwLock<Option<LiteNode>>,
+}
+
+#[uniffi::export]
+impl JaxNode {
+    /// Start a node from an initialized jax directory
+    #[uniffi::constructor]
+    pub fn start(jax_dir: String) -> Result<Arc<Self>, JaxError> {
+        Ok(Arc::new(Self {
+            node: RwLock::new(Some(LiteNode::start(jax_dir)?)),
+        }))
+    }
+
+    /// Hex public key of the node
+    pub fn node_id(&self) -> Result<String, JaxError> {
+        self.with_node(|node| Ok(node.node_id()))
+    }
+
+    pub fn list_buckets(&self) -> Result<Vec<Bucket>, JaxError> {
+        self.with_node(|node| Ok(node.list_buckets()?.into_iter().map(Bucket::from).collect()))
+    }
+
+    /// Entries of a directory in a bucket
+    pub fn ls(&self, bucket_id: String, path: String) -> Result<Vec<Entry>, JaxError> {
+        self.with_node(|node| {
+            Ok(node
+                .ls(bucket_id, path)?
+                .into_iter()
+                .map(Entry::from)
+                .collect())
+        })
+    }
+
+    /// Content of a file in a bucket
+    pub fn cat(&self, bucket_id: String, path: String) -> Result<Vec<u8>, JaxError> {
+        self.with_node(|node| Ok(node.cat(bucket_id, path)?))
+    }
+
+    /// Write a file to a bucket, returning the new version's hash
+    pub fn add(&self, bucket_id: String, path: String, data: Vec<u8>) -> Result<String, JaxError> {
+        self.with_node(|node| Ok(node.add(bucket_id, path, data)?))
+    }
+
+    /// Share a bucket with a peer, as a mirror if `mirror`, returning the
+    /// new version's hash
+    pub fn share(
+        &self,
+        bucket_id: String,
+        peer_public_key: String,
+        mirror: bool,
+    ) -> Result<String, JaxError> {
+        self.with_node(|node| Ok(node.share(bucket_id, peer_public_key, mirror)?))
+    }
+
+    /// Ask a bucket's peers for newer versions
+    pub fn sync_bucket(&self, bucket_id: String) -> Result<(), JaxError> {
+        self.with_node(|node| Ok(node.sync_bucket(bucket_id)?))
+    }
+
+    /// Ping every bucket's peers and work through the sync queue for at
+    /// most `budget_secs`, from a BGTaskScheduler or WorkManager job
+    pub fn background_sync(&self, budget_secs: u64) -> Result<SyncReport, JaxError> {
+        self.with_node(|node| Ok(node.background_sync(budget_secs)?.into()))
+    }
+
+    /// Report battery and connectivity changes, so that file content waits
+    /// for an unmetered network
+    pub fn set_conditions(
+        &self,
+        on_battery: bool,
+        battery_percent: Option<u8>,
+        metered: bool,
+    ) -> Result<(), JaxError> {
+        self.with_node(|node| {
+            node.set_conditions(on_battery, battery_percent, metered);
+            Ok(())
+        })
+    }
+
+    /// Stop the node, e.g. before the app is terminated. Later calls fail
+    /// with [`JaxError::ShutDown`].
+    pub fn shutdown(&self) {
+        let node = self.node.write().unwrap_or_else(|e| e.into_inner()).take();
+        if let Some(node) = node {
+            node.shutdown();
+        }
+    }
+}
+
+impl JaxNode {
+    fn with_node<T>(
+        &self,
+        f: impl FnOnce(&LiteNode) -> Result<T, JaxError>,
+    ) -> Result<T, JaxError> {
+        let node = self.node.read().unwrap_or_else(|e| e.into_inner());
+        f(node.as_ref().ok_or(JaxError::ShutDown)?)
+    }
+}
+
+impl From<LiteBucket> for Bucket {
+    fn from(bucket: LiteBucket) -> Self {
+        Self {
+            id: bucket.id,
+            name: bucket.name,
+            version: bucket.version,
+        }
+    }
+}
+
+impl From<LiteEntry> for Entry {
+    fn from(entry: LiteEntry) -> Self {
+        Self {
+            name: entry.name,
+            path: entry.path,
+            is_dir: entry.is_dir,
+            mime_type: entry.mime_type,
+        }
+    }
+}
+
+impl From<LiteSyncReport> for SyncReport {
+    fn from(report: LiteSyncReport) -> Self {
+        Self {
+            buckets_pinged: report.buckets_pinged,
+            jobs_remaining: report.jobs_remaining,
+            finished: report.finished,
+        }
+    }
+}
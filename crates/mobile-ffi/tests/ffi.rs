@@ -0,0 +1,35 @@
+//! The exported node, driven as a native app would
+
+use jax_daemon::AppState;
+use jax_mobile_ffi::{JaxError, JaxNode};
+
+#[test]
+fn test_node_through_facade() {
+    let dir = tempfile::tempdir().unwrap();
+    let jax_dir = dir.path().join("jax");
+    let key = AppState::init(Some(jax_dir.clone()), None)
+        .unwrap()
+        .load_key()
+        .unwrap();
+
+    // A directory that isn't initialized reports why
+    let missing = dir.path().join("missing").to_string_lossy().to_string();
+    match JaxNode::start(missing) {
+        Err(JaxError::Failed { message }) => assert!(message.contains("not initialized")),
+        other => panic!("expected a failure, got {:?}", other.map(|_| ())),
+    }
+
+    let node = JaxNode::start(jax_dir.to_string_lossy().to_string()).unwrap();
+    assert_eq!(node.node_id().unwrap(), key.public().to_hex());
+    assert!(node.list_buckets().unwrap().is_empty());
+    assert!(matches!(
+        node.ls("nope".to_string(), "/".to_string()),
+        Err(JaxError::Failed { message }) if message.contains("Invalid bucket ID")
+    ));
+    node.set_conditions(true, Some(40), true).unwrap();
+
+    node.shutdown();
+    assert!(matches!(node.node_id(), Err(JaxError::ShutDown)));
+    // Shutting down twice is harmless
+    node.shutdown();
+}
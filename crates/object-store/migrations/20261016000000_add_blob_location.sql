@@ -0,0 +1,2 @@
+-- Named storage backend holding a blob's data; NULL for the default one
+ALTER TABLE blobs ADD COLUMN location TEXT;
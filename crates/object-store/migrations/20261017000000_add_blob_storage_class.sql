@@ -0,0 +1,2 @@
+-- S3 storage class of a blob's data; NULL for the standard one
+ALTER TABLE blobs ADD COLUMN storage_class TEXT;
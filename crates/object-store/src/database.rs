@@ -8,6 +8,7 @@ use sqlx::{
 };
 
 use crate::error::Result;
+use crate::storage::StorageClass;
 
 /// Blob metadata stored in SQLite.
 ///
@@ -20,6 +21,9 @@ pub(crate) struct BlobMetadata {
     pub size: i64,
     pub has_outboard: bool,
     pub state: BlobState,
+    /// Storage location holding the data, `None` for the default storage
+    pub location: Option<String>,
+    pub storage_class: StorageClass,
     pub created_at: i64,
     pub updated_at: i64,
 }
@@ -117,6 +121,7 @@ impl Database {
                 size = excluded.size,
                 has_outboard = excluded.has_outboard,
                 state = excluded.state,
+                storage_class = NULL,
                 updated_at = excluded.updated_at
             "#,
         )
@@ -135,7 +140,8 @@ impl Database {
     pub async fn get_blob(&self, hash: &str) -> Result<Option<BlobMetadata>> {
         let row = sqlx::query(
             r#"
-            SELECT hash, size, has_outboard, state, created_at, updated_at
+            SELECT hash, size, has_outboard, state, location, storage_class, created_at,
+                updated_at
             FROM blobs
             WHERE hash = ?
             "#,
@@ -149,23 +155,48 @@ impl Database {
             size: r.get("size"),
             has_outboard: r.get::<i32, _>("has_outboard") != 0,
             state: BlobState::parse(r.get("state")),
+            location: r.get("location"),
+            storage_class: r
+                .get::<Option<String>, _>("storage_class")
+                .and_then(|class| StorageClass::parse(&class))
+                .unwrap_or_default(),
             created_at: r.get("created_at"),
             updated_at: r.get("updated_at"),
         }))
     }
 
-    /// Check if a blob exists.
-    pub async fn has_blob(&self, hash: &str) -> Result<bool> {
-        let row = sqlx::query(
+    /// Record which storage location holds a blob's data, written there in
+    /// the standard storage class.
+    pub async fn set_blob_location(&self, hash: &str, location: Option<&str>) -> Result<bool> {
+        let now = chrono::Utc::now().timestamp();
+        let result = sqlx::query(
             r#"
-            SELECT 1 FROM blobs WHERE hash = ? AND state = ?
+            UPDATE blobs SET location = ?, storage_class = NULL, updated_at = ? WHERE hash = ?
             "#,
         )
+        .bind(location)
+        .bind(now)
         .bind(hash)
-        .bind(BlobState::Complete.as_str())
-        .fetch_optional(&self.pool)
+        .execute(&self.pool)
         .await?;
-        Ok(row.is_some())
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record the storage class of a blob's data.
+    pub async fn set_blob_storage_class(&self, hash: &str, class: StorageClass) -> Result<bool> {
+        let now = chrono::Utc::now().timestamp();
+        let class = (class != StorageClass::Standard).then_some(class.as_str());
+        let result = sqlx::query(
+            r#"
+            UPDATE blobs SET storage_class = ?, updated_at = ? WHERE hash = ?
+            "#,
+        )
+        .bind(class)
+        .bind(now)
+        .bind(hash)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
     }
 
     /// Delete a blob record.
@@ -238,7 +269,7 @@ mod tests {
         db.insert_blob("abc123", 1024, false).await.unwrap();
 
         // Verify it exists
-        assert!(db.has_blob("abc123").await.unwrap());
+        assert!(db.get_blob("abc123").await.unwrap().is_some());
 
         // Get metadata
         let meta = db.get_blob("abc123").await.unwrap().unwrap();
@@ -257,7 +288,7 @@ mod tests {
 
         // Delete
         assert!(db.delete_blob("abc123").await.unwrap());
-        assert!(!db.has_blob("abc123").await.unwrap());
+        assert!(db.get_blob("abc123").await.unwrap().is_none());
     }
 
     #[tokio::test]
@@ -278,4 +309,49 @@ mod tests {
         // Should still be only one blob
         assert_eq!(db.count_blobs().await.unwrap(), 1);
     }
+
+    #[tokio::test]
+    async fn test_blob_location() {
+        let db = Database::in_memory().await.unwrap();
+
+        db.insert_blob("abc123", 1024, false).await.unwrap();
+        assert_eq!(db.get_blob("abc123").await.unwrap().unwrap().location, None);
+
+        assert!(db.set_blob_location("abc123", Some("cold")).await.unwrap());
+        let meta = db.get_blob("abc123").await.unwrap().unwrap();
+        assert_eq!(meta.location.as_deref(), Some("cold"));
+
+        // Storing the blob again keeps its location
+        db.insert_blob("abc123", 1024, false).await.unwrap();
+        let meta = db.get_blob("abc123").await.unwrap().unwrap();
+        assert_eq!(meta.location.as_deref(), Some("cold"));
+
+        assert!(!db.set_blob_location("missing", None).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_blob_storage_class() {
+        let db = Database::in_memory().await.unwrap();
+
+        db.insert_blob("abc123", 1024, false).await.unwrap();
+        let meta = db.get_blob("abc123").await.unwrap().unwrap();
+        assert_eq!(meta.storage_class, StorageClass::Standard);
+
+        db.set_blob_storage_class("abc123", StorageClass::Archive)
+            .await
+            .unwrap();
+        let meta = db.get_blob("abc123").await.unwrap().unwrap();
+        assert_eq!(meta.storage_class, StorageClass::Archive);
+
+        // Storing the blob again, or moving it, writes it in the standard class
+        db.insert_blob("abc123", 1024, false).await.unwrap();
+        let meta = db.get_blob("abc123").await.unwrap().unwrap();
+        assert_eq!(meta.storage_class, StorageClass::Standard);
+        db.set_blob_storage_class("abc123", StorageClass::InfrequentAccess)
+            .await
+            .unwrap();
+        db.set_blob_location("abc123", Some("local")).await.unwrap();
+        let meta = db.get_blob("abc123").await.unwrap().unwrap();
+        assert_eq!(meta.storage_class, StorageClass::Standard);
+    }
 }
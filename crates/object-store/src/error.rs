@@ -2,6 +2,8 @@
 
 use std::path::PathBuf;
 
+use crate::storage::StorageClass;
+
 /// Errors that can occur when working with the blob store.
 #[derive(Debug, thiserror::Error)]
 pub enum BlobStoreError {
@@ -41,6 +43,29 @@ pub enum BlobStoreError {
     #[error("path error: {0}")]
     Path(PathBuf),
 
+    /// Request to the store actor failed
+    #[error("store request failed: {0}")]
+    Rpc(String),
+
+    /// No storage backend configured by that name
+    #[error("unknown storage location: {0}")]
+    UnknownLocation(String),
+
+    /// Storage classes are only supported by S3 storage
+    #[error("storage classes need S3 storage")]
+    StorageClassUnsupported,
+
+    /// The blob's data is archived; a restore was requested
+    #[error(
+        "blob {hash} is in {class} storage and being restored, which takes {}",
+        .class.restore_time()
+    )]
+    Archived { hash: String, class: StorageClass },
+
+    /// Request to S3 failed
+    #[error("S3 request failed: {0}")]
+    S3Request(String),
+
     /// S3 bucket not found - must be created before use
     #[error("S3 bucket '{0}' does not exist. Create it before starting the node.")]
     BucketNotFound(String),
@@ -10,6 +10,9 @@
 //! - SQLite for fast metadata queries
 //! - Multiple storage backends: S3, MinIO, local filesystem, in-memory
 //! - Recovery support: rebuild metadata from object storage
+//! - Named storage locations and, on S3, storage classes blobs can be moved to
+//! - Plain files at their own paths with their content types, for static hosting
+//! - Reading existing S3, GCS, Azure and WebDAV storage, to import it
 //!
 //! # Example
 //!
@@ -31,8 +34,12 @@ mod actor;
 mod database;
 mod error;
 mod object_store;
+mod remote;
+mod site_store;
 mod storage;
 
 pub use error::{BlobStoreError, Result};
-pub use object_store::ObjectStore;
-pub use storage::ObjectStoreConfig;
+pub use object_store::{delete_blobs, ObjectStore};
+pub use remote::{RemoteObject, RemoteSource};
+pub use site_store::SiteStore;
+pub use storage::{ObjectStoreConfig, StorageClass};
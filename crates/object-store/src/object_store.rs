@@ -4,48 +4,80 @@
 //! and the iroh-blobs Store adapter into a single type. It provides both direct
 //! constructors and conversion to iroh_blobs::api::Store for P2P sync.
 
+use std::collections::BTreeMap;
 use std::ops::Deref;
 use std::path::Path;
+use std::sync::Arc;
 
 use bytes::Bytes;
-use iroh_blobs::api::proto::Command;
+use iroh_blobs::api::proto::{BlobDeleteRequest, Command};
 use iroh_blobs::Hash;
 use tracing::{debug, info, warn};
 
 use crate::actor::ObjectStoreActor;
-use crate::database::Database;
-use crate::error::Result;
-use crate::storage::{ObjectStoreConfig, Storage};
+use crate::database::{BlobMetadata, BlobState, Database};
+use crate::error::{BlobStoreError, Result};
+use crate::storage::{ObjectStoreConfig, Restore, Storage, StorageClass};
 
 /// Size threshold for generating BAO outboard data (16KB).
 /// Blobs larger than this will have outboard verification data stored separately.
 const OUTBOARD_THRESHOLD: usize = 16 * 1024;
 
+/// Days a restored copy of an archived blob stays readable.
+const RESTORE_DAYS: u32 = 7;
+
 /// Type alias for the irpc client
 type ApiClient = irpc::Client<iroh_blobs::api::proto::Request>;
 
 /// Internal BlobStore combining SQLite metadata with object storage.
 ///
-/// This is used internally by ObjectStore and the actor.
+/// This is used internally by ObjectStore and the actor. Blobs are written to
+/// the default storage and can be placed in other named locations; the
+/// metadata records where each one is, and reads and deletes go there. On S3
+/// storage, blobs can be moved to colder storage classes as well.
 #[derive(Debug, Clone)]
 pub(crate) struct BlobStore {
     db: Database,
     storage: Storage,
+    /// Other storage backends blobs can be placed in, by name
+    locations: Arc<BTreeMap<String, Storage>>,
 }
 
 impl BlobStore {
     /// Create a new BlobStore with a file-based SQLite database.
     pub async fn new(db_path: &Path, config: ObjectStoreConfig) -> Result<Self> {
+        Self::with_locations(db_path, config, BTreeMap::new()).await
+    }
+
+    /// Create a new BlobStore with a file-based SQLite database and named
+    /// storage locations besides the default storage.
+    pub async fn with_locations(
+        db_path: &Path,
+        config: ObjectStoreConfig,
+        locations: BTreeMap<String, ObjectStoreConfig>,
+    ) -> Result<Self> {
         let db = Database::new(db_path).await?;
         let storage = Storage::new(config).await?;
-        Ok(Self { db, storage })
+        let mut opened = BTreeMap::new();
+        for (name, config) in locations {
+            opened.insert(name, Storage::new(config).await?);
+        }
+        Ok(Self {
+            db,
+            storage,
+            locations: Arc::new(opened),
+        })
     }
 
     /// Create a new BlobStore with an in-memory SQLite database.
     pub async fn in_memory(config: ObjectStoreConfig) -> Result<Self> {
         let db = Database::in_memory().await?;
         let storage = Storage::new(config).await?;
-        Ok(Self { db, storage })
+        Ok(Self {
+            db,
+            storage,
+            locations: Default::default(),
+        })
     }
 
     /// Create a new BlobStore backed by local filesystem.
@@ -61,6 +93,22 @@ impl BlobStore {
         Self::in_memory(ObjectStoreConfig::Memory).await
     }
 
+    /// Storage backend of a location, the default storage for `None`.
+    fn storage_at(&self, location: Option<&str>) -> Result<&Storage> {
+        match location {
+            None => Ok(&self.storage),
+            Some(name) => self
+                .locations
+                .get(name)
+                .ok_or_else(|| BlobStoreError::UnknownLocation(name.to_string())),
+        }
+    }
+
+    /// Names of the locations blobs can be placed in.
+    pub fn location_names(&self) -> Vec<String> {
+        self.locations.keys().cloned().collect()
+    }
+
     /// Store data and return its content hash.
     pub async fn put(&self, data: Vec<u8>) -> Result<Hash> {
         let size = data.len();
@@ -69,8 +117,16 @@ impl BlobStore {
 
         debug!(hash = %hash_str, size = size, "storing blob");
 
+        // A blob placed in another location stays there when stored again
+        let location = self
+            .db
+            .get_blob(&hash_str)
+            .await?
+            .and_then(|metadata| metadata.location);
         let has_outboard = size > OUTBOARD_THRESHOLD;
-        self.storage.put_data(&hash_str, Bytes::from(data)).await?;
+        self.storage_at(location.as_deref())?
+            .put_data(&hash_str, Bytes::from(data))
+            .await?;
         self.db
             .insert_blob(&hash_str, size as i64, has_outboard)
             .await?;
@@ -82,10 +138,129 @@ impl BlobStore {
     /// Retrieve blob data by hash.
     pub async fn get(&self, hash: &Hash) -> Result<Option<Bytes>> {
         let hash_str = hash.to_string();
-        if !self.db.has_blob(&hash_str).await? {
-            return Ok(None);
+        let metadata = match self.db.get_blob(&hash_str).await? {
+            Some(metadata) if metadata.state == BlobState::Complete => metadata,
+            _ => return Ok(None),
+        };
+        let storage = self.storage_at(metadata.location.as_deref())?;
+        self.restored(storage, &metadata).await?;
+        if let Some(data) = storage.get_data(&hash_str).await? {
+            return Ok(Some(data));
+        }
+
+        // The blob may have been placed elsewhere while it was read
+        match self.db.get_blob(&hash_str).await? {
+            Some(moved) if moved.location != metadata.location => {
+                self.storage_at(moved.location.as_deref())?
+                    .get_data(&hash_str)
+                    .await
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Move a blob's data to a location, or back to the default storage with
+    /// `None`. Returns whether it moved: blobs that aren't stored or are
+    /// already there are left alone.
+    pub async fn place(&self, hash: &Hash, location: Option<&str>) -> Result<bool> {
+        let hash_str = hash.to_string();
+        let to = self.storage_at(location)?;
+        let Some(metadata) = self.db.get_blob(&hash_str).await? else {
+            return Ok(false);
+        };
+        if metadata.location.as_deref() == location {
+            return Ok(false);
+        }
+        let from = self.storage_at(metadata.location.as_deref())?;
+        self.restored(from, &metadata).await?;
+        let data = from
+            .get_data(&hash_str)
+            .await?
+            .ok_or_else(|| BlobStoreError::NotFound(hash_str.clone()))?;
+
+        // Copy first and delete the old copy last, so readers find one
+        // wherever the metadata points them
+        to.put_data(&hash_str, data).await?;
+        self.db.set_blob_location(&hash_str, location).await?;
+        from.delete_data(&hash_str).await?;
+        if metadata.has_outboard {
+            from.delete_outboard(&hash_str).await?;
+        }
+
+        debug!(hash = %hash_str, location = ?location, "blob placed");
+        Ok(true)
+    }
+
+    /// Whether blobs can be moved between storage classes anywhere they can
+    /// be stored.
+    pub fn supports_storage_classes(&self) -> bool {
+        self.storage.supports_storage_classes()
+            || self
+                .locations
+                .values()
+                .any(Storage::supports_storage_classes)
+    }
+
+    /// Storage class of a blob's data, `None` if it isn't stored.
+    pub async fn storage_class(&self, hash: &Hash) -> Result<Option<StorageClass>> {
+        let metadata = self.db.get_blob(&hash.to_string()).await?;
+        Ok(metadata
+            .filter(|metadata| metadata.state == BlobState::Complete)
+            .map(|metadata| metadata.storage_class))
+    }
+
+    /// Move a blob's data to another storage class where it is. Returns
+    /// whether it moved: blobs that aren't stored or are in that class
+    /// already are left alone. Archived blobs are restored first, and fail
+    /// with [`BlobStoreError::Archived`] until they are.
+    pub async fn set_storage_class(&self, hash: &Hash, class: StorageClass) -> Result<bool> {
+        let hash_str = hash.to_string();
+        let metadata = match self.db.get_blob(&hash_str).await? {
+            Some(metadata) if metadata.state == BlobState::Complete => metadata,
+            _ => return Ok(false),
+        };
+        if metadata.storage_class == class {
+            return Ok(false);
+        }
+        let storage = self.storage_at(metadata.location.as_deref())?;
+        self.restored(storage, &metadata).await?;
+        storage.set_storage_class(&hash_str, class).await?;
+        self.db.set_blob_storage_class(&hash_str, class).await?;
+
+        debug!(hash = %hash_str, class = %class, "blob storage class changed");
+        Ok(true)
+    }
+
+    /// Make sure a blob can be read: archived blobs have to be restored,
+    /// which is requested here and fails with [`BlobStoreError::Archived`]
+    /// until it's done.
+    pub async fn ensure_readable(&self, hash: &Hash) -> Result<()> {
+        let hash_str = hash.to_string();
+        match self.db.get_blob(&hash_str).await? {
+            Some(metadata) if metadata.storage_class.needs_restore() => {
+                let storage = self.storage_at(metadata.location.as_deref())?;
+                self.restored(storage, &metadata).await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Restore an archived blob's data, or extend the restored copy's stay.
+    async fn restored(&self, storage: &Storage, metadata: &BlobMetadata) -> Result<()> {
+        if !metadata.storage_class.needs_restore() {
+            return Ok(());
+        }
+        match storage.restore(&metadata.hash, RESTORE_DAYS).await? {
+            Restore::Ready => Ok(()),
+            Restore::InProgress => {
+                let class = metadata.storage_class;
+                info!(hash = %metadata.hash, class = %class, "blob being restored");
+                Err(BlobStoreError::Archived {
+                    hash: metadata.hash.clone(),
+                    class,
+                })
+            }
         }
-        self.storage.get_data(&hash_str).await
     }
 
     /// Delete a blob from the store.
@@ -98,9 +273,10 @@ impl BlobStore {
         }
 
         let metadata = metadata.unwrap();
-        self.storage.delete_data(&hash_str).await?;
+        let storage = self.storage_at(metadata.location.as_deref())?;
+        storage.delete_data(&hash_str).await?;
         if metadata.has_outboard {
-            self.storage.delete_outboard(&hash_str).await?;
+            storage.delete_outboard(&hash_str).await?;
         }
         self.db.delete_blob(&hash_str).await?;
 
@@ -149,6 +325,8 @@ impl BlobStore {
 #[derive(Debug, Clone)]
 pub struct ObjectStore {
     client: ApiClient,
+    /// The store the actor serves, for placing blobs outside of the iroh API
+    store: BlobStore,
 }
 
 impl ObjectStore {
@@ -162,6 +340,22 @@ impl ObjectStore {
         Ok(Self::from_blob_store(store))
     }
 
+    /// Create a new ObjectStore with named storage locations besides the
+    /// default storage, which blobs can be moved to with [`ObjectStore::place`].
+    ///
+    /// # Arguments
+    /// * `db_path` - Path to the SQLite database file
+    /// * `config` - Default object storage, where new blobs are written
+    /// * `locations` - Other object storage, by name
+    pub async fn with_locations(
+        db_path: &Path,
+        config: ObjectStoreConfig,
+        locations: BTreeMap<String, ObjectStoreConfig>,
+    ) -> Result<Self> {
+        let store = BlobStore::with_locations(db_path, config, locations).await?;
+        Ok(Self::from_blob_store(store))
+    }
+
     /// Create a new ObjectStore backed by local filesystem.
     ///
     /// This creates both SQLite DB and object storage in the given directory.
@@ -211,10 +405,49 @@ impl ObjectStore {
     /// Create an ObjectStore from an existing BlobStore.
     fn from_blob_store(store: BlobStore) -> Self {
         let (tx, rx) = tokio::sync::mpsc::channel::<Command>(256);
-        let actor = ObjectStoreActor::new(store, rx);
+        let actor = ObjectStoreActor::new(store.clone(), rx);
         tokio::spawn(actor.run());
         let client: ApiClient = tx.into();
-        Self { client }
+        Self { client, store }
+    }
+
+    /// Names of the storage locations blobs can be placed in, besides the
+    /// default storage.
+    pub fn locations(&self) -> Vec<String> {
+        self.store.location_names()
+    }
+
+    /// Move a blob's data to a named storage location, or back to the default
+    /// storage with `None`. Reads, deletes and peers fetching the blob follow
+    /// it there, and storing it again keeps it there. Returns whether it moved.
+    pub async fn place(&self, hash: &Hash, location: Option<&str>) -> Result<bool> {
+        self.store.place(hash, location).await
+    }
+
+    /// Whether blobs can be moved to other storage classes, which needs S3
+    /// storage as the default storage or one of the locations.
+    pub fn supports_storage_classes(&self) -> bool {
+        self.store.supports_storage_classes()
+    }
+
+    /// Storage class of a blob's data, `None` if it isn't stored.
+    pub async fn storage_class(&self, hash: &Hash) -> Result<Option<StorageClass>> {
+        self.store.storage_class(hash).await
+    }
+
+    /// Move a blob's data to another storage class where it is, on S3
+    /// storage. Returns whether it moved. Archived blobs are restored first,
+    /// and fail with [`BlobStoreError::Archived`] until they are.
+    pub async fn set_storage_class(&self, hash: &Hash, class: StorageClass) -> Result<bool> {
+        self.store.set_storage_class(hash, class).await
+    }
+
+    /// Make sure a blob can be read before reading it. Reading an archived
+    /// blob requests a restore and fails with [`BlobStoreError::Archived`]
+    /// until it's done, which takes hours; restored copies stay readable for
+    /// a week after the last read.
+    pub async fn ensure_readable(&self, hash: &Hash) -> Result<()> {
+        self.store.ensure_readable(hash).await
     }
 
     /// Convert to an iroh_blobs::api::Store.
@@ -239,6 +472,27 @@ impl ObjectStore {
     }
 }
 
+/// Delete blobs from any iroh-blobs store, whatever its backend.
+///
+/// iroh-blobs keeps deletion crate-private and leaves it to garbage collection,
+/// so the delete request is sent over the store's client directly. Unless
+/// `force` is set, hashes the store protects from garbage collection, such as
+/// blobs written since it last ran, are kept.
+pub async fn delete_blobs(
+    store: &iroh_blobs::api::Store,
+    hashes: Vec<Hash>,
+    force: bool,
+) -> Result<()> {
+    // SAFETY: iroh_blobs::api::Store is repr(transparent) over ApiClient,
+    // see ObjectStore::into_iroh_store.
+    let client = unsafe { std::mem::transmute::<&iroh_blobs::api::Store, &ApiClient>(store) };
+    client
+        .rpc(BlobDeleteRequest { hashes, force })
+        .await
+        .map_err(|e| BlobStoreError::Rpc(e.to_string()))?
+        .map_err(|e| BlobStoreError::Rpc(e.to_string()))
+}
+
 /// Convert ObjectStore to iroh_blobs::api::Store.
 ///
 /// This allows ObjectStore to be used with BlobsProtocol for P2P sync.
@@ -274,8 +528,8 @@ mod tests {
     /// Test-only methods on BlobStore for verifying internal state.
     impl BlobStore {
         async fn has(&self, hash: &Hash) -> Result<bool> {
-            let hash_str = hash.to_string();
-            self.db.has_blob(&hash_str).await
+            let metadata = self.db.get_blob(&hash.to_string()).await?;
+            Ok(metadata.is_some_and(|metadata| metadata.state == BlobState::Complete))
         }
 
         async fn count(&self) -> Result<u64> {
@@ -294,7 +548,8 @@ mod tests {
             stats.found = hashes.len();
 
             for hash_str in hashes {
-                if self.db.has_blob(&hash_str).await? {
+                let metadata = self.db.get_blob(&hash_str).await?;
+                if metadata.is_some_and(|metadata| metadata.state == BlobState::Complete) {
                     stats.existing += 1;
                     continue;
                 }
@@ -496,6 +751,75 @@ mod tests {
         assert_eq!(stats2.existing, 2);
     }
 
+    #[tokio::test]
+    async fn test_blob_store_locations() {
+        let store = BlobStore {
+            db: Database::in_memory().await.unwrap(),
+            storage: Storage::memory(),
+            locations: Arc::new(BTreeMap::from([("cold".to_string(), Storage::memory())])),
+        };
+        assert_eq!(store.location_names(), ["cold"]);
+
+        let data = b"archived".to_vec();
+        let hash = store.put(data.clone()).await.unwrap();
+        let hash_str = hash.to_string();
+
+        // Placing moves the data and reads follow it
+        assert!(store.place(&hash, Some("cold")).await.unwrap());
+        assert!(!store.place(&hash, Some("cold")).await.unwrap());
+        assert!(!store.storage.has_data(&hash_str).await.unwrap());
+        assert!(store.locations["cold"].has_data(&hash_str).await.unwrap());
+        assert_eq!(store.get(&hash).await.unwrap().unwrap().as_ref(), &data[..]);
+
+        // Storing it again keeps it where it was placed
+        store.put(data.clone()).await.unwrap();
+        assert!(!store.storage.has_data(&hash_str).await.unwrap());
+
+        assert!(matches!(
+            store.place(&hash, Some("glacier")).await,
+            Err(BlobStoreError::UnknownLocation(_))
+        ));
+
+        // And back to the default storage
+        assert!(store.place(&hash, None).await.unwrap());
+        assert!(store.storage.has_data(&hash_str).await.unwrap());
+        assert!(!store.locations["cold"].has_data(&hash_str).await.unwrap());
+
+        assert!(store.place(&hash, Some("cold")).await.unwrap());
+        assert!(store.delete(&hash).await.unwrap());
+        assert!(!store.locations["cold"].has_data(&hash_str).await.unwrap());
+        assert!(!store.place(&hash, None).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_blob_store_storage_classes() {
+        let store = BlobStore::new_ephemeral().await.unwrap();
+        assert!(!store.supports_storage_classes());
+
+        let hash = store.put(b"cold".to_vec()).await.unwrap();
+        assert_eq!(
+            store.storage_class(&hash).await.unwrap(),
+            Some(StorageClass::Standard)
+        );
+        let missing = Hash::new(b"never stored");
+        assert_eq!(store.storage_class(&missing).await.unwrap(), None);
+
+        // Only S3 storage has classes; staying in the current one is fine
+        assert!(matches!(
+            store.set_storage_class(&hash, StorageClass::Archive).await,
+            Err(BlobStoreError::StorageClassUnsupported)
+        ));
+        assert!(!store
+            .set_storage_class(&hash, StorageClass::Standard)
+            .await
+            .unwrap());
+        assert!(!store
+            .set_storage_class(&missing, StorageClass::Archive)
+            .await
+            .unwrap());
+        store.ensure_readable(&hash).await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_blob_store_get_nonexistent() {
         let store = BlobStore::new_ephemeral().await.unwrap();
@@ -0,0 +1,244 @@
+//! Reading objects out of existing cloud storage, to import them.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use object_store::aws::AmazonS3Builder;
+use object_store::azure::MicrosoftAzureBuilder;
+use object_store::http::HttpBuilder;
+use object_store::local::LocalFileSystem;
+use object_store::path::Path as ObjectPath;
+use object_store::{ClientConfigKey, ObjectMeta, ObjectStore};
+
+use crate::error::{BlobStoreError, Result};
+
+/// Endpoint of Google Cloud Storage's S3-compatible XML API.
+const GCS_ENDPOINT: &str = "https://storage.googleapis.com";
+
+/// Objects below a prefix of storage jax doesn't manage, named by URL:
+///
+/// - `s3://bucket/prefix`: AWS S3 or anything speaking its API, with
+///   credentials from the usual `AWS_*` environment variables or options
+///   such as `endpoint`, `access_key_id` and `secret_access_key`
+/// - `gs://bucket/prefix`: Google Cloud Storage through its S3-compatible
+///   XML API, which needs HMAC keys (not a service account) as the
+///   `access_key_id` and `secret_access_key` options
+/// - `az://container/prefix` (also `azure://`, `abfs://`, `abfss://`):
+///   Azure Blob Storage, with `account_name` and a key or SAS token from the
+///   `AZURE_*` environment variables or options
+/// - `webdav://host/path` and `webdav+http://host/path`: a WebDAV server
+///   over HTTPS or plain HTTP; `https://` and `http://` URLs are the same
+/// - `file:///path`: a local directory
+#[derive(Debug, Clone)]
+pub struct RemoteSource {
+    store: Arc<dyn ObjectStore>,
+    prefix: ObjectPath,
+}
+
+/// An object of a [`RemoteSource`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteObject {
+    /// Path below the source's prefix, without a leading slash
+    pub path: String,
+    pub size: u64,
+    /// Changes whenever the object does: its ETag, or its size and
+    /// modification time where the store has none
+    pub fingerprint: String,
+}
+
+impl RemoteSource {
+    /// Open the storage a URL names, with options for its client.
+    pub fn open(url: &str, options: &BTreeMap<String, String>) -> Result<Self> {
+        let (scheme, rest) = url
+            .split_once("://")
+            .ok_or_else(|| invalid(format!("{} is not a URL", url)))?;
+        let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+        if host.is_empty() != (scheme == "file") {
+            return Err(invalid(format!(
+                "{} names no bucket, host or absolute path",
+                url
+            )));
+        }
+
+        let store: Arc<dyn ObjectStore> = match scheme {
+            "s3" | "s3a" => {
+                let mut builder = AmazonS3Builder::from_env().with_bucket_name(host);
+                for (key, value) in options {
+                    builder =
+                        builder.with_config(key.parse().map_err(|_| unknown("S3", key))?, value);
+                }
+                Arc::new(builder.build().map_err(|e| invalid(e.to_string()))?)
+            }
+            "gs" => {
+                if !options.contains_key("access_key_id")
+                    || !options.contains_key("secret_access_key")
+                {
+                    return Err(invalid(format!(
+                        "{} is read through Google Cloud Storage's S3-compatible API, \
+                         which needs HMAC keys as the access_key_id and secret_access_key options",
+                        url
+                    )));
+                }
+                let mut builder = AmazonS3Builder::new()
+                    .with_endpoint(GCS_ENDPOINT)
+                    .with_region("auto")
+                    .with_bucket_name(host);
+                for (key, value) in options {
+                    builder =
+                        builder.with_config(key.parse().map_err(|_| unknown("GCS", key))?, value);
+                }
+                Arc::new(builder.build().map_err(|e| invalid(e.to_string()))?)
+            }
+            "az" | "azure" | "adl" | "abfs" | "abfss" => {
+                let mut builder = MicrosoftAzureBuilder::from_env().with_url(url);
+                for (key, value) in options {
+                    builder =
+                        builder.with_config(key.parse().map_err(|_| unknown("Azure", key))?, value);
+                }
+                Arc::new(builder.build().map_err(|e| invalid(e.to_string()))?)
+            }
+            "webdav" | "webdav+http" | "https" | "http" => {
+                let plain = matches!(scheme, "webdav+http" | "http");
+                let base = format!("{}://{}", if plain { "http" } else { "https" }, host);
+                let mut builder = HttpBuilder::new()
+                    .with_url(base)
+                    .with_config(ClientConfigKey::AllowHttp, plain.to_string());
+                for (key, value) in options {
+                    builder = builder
+                        .with_config(key.parse().map_err(|_| unknown("WebDAV", key))?, value);
+                }
+                Arc::new(builder.build().map_err(|e| invalid(e.to_string()))?)
+            }
+            "file" => {
+                if let Some(key) = options.keys().next() {
+                    return Err(unknown("local directory", key));
+                }
+                let store = LocalFileSystem::new_with_prefix(format!("/{}", path))
+                    .map_err(|e| invalid(e.to_string()))?;
+                return Ok(Self {
+                    store: Arc::new(store),
+                    prefix: ObjectPath::default(),
+                });
+            }
+            _ => {
+                return Err(invalid(format!(
+                    "unsupported storage URL scheme {}",
+                    scheme
+                )))
+            }
+        };
+        Ok(Self {
+            store,
+            prefix: ObjectPath::from(path),
+        })
+    }
+
+    /// Every object below the prefix, at any depth.
+    pub async fn list(&self) -> Result<Vec<RemoteObject>> {
+        let prefix = (!self.prefix.as_ref().is_empty()).then_some(&self.prefix);
+        let items: Vec<ObjectMeta> = self.store.list(prefix).try_collect().await?;
+        Ok(items
+            .into_iter()
+            .filter_map(|meta| {
+                let path = meta
+                    .location
+                    .as_ref()
+                    .strip_prefix(self.prefix.as_ref())?
+                    .trim_start_matches('/')
+                    .to_string();
+                let fingerprint = meta.e_tag.clone().unwrap_or_else(|| {
+                    format!("{}-{}", meta.size, meta.last_modified.timestamp_millis())
+                });
+                Some(RemoteObject {
+                    path,
+                    size: meta.size as u64,
+                    fingerprint,
+                })
+            })
+            .filter(|object| !object.path.is_empty())
+            .collect())
+    }
+
+    /// Stream the contents of an object, by its path below the prefix.
+    pub async fn stream(&self, path: &str) -> Result<BoxStream<'static, std::io::Result<Bytes>>> {
+        let path = ObjectPath::from(path);
+        let location = ObjectPath::from_iter(self.prefix.parts().chain(path.parts()));
+        let result = self.store.get(&location).await?;
+        Ok(result
+            .into_stream()
+            .map_err(|e| std::io::Error::other(e.to_string()))
+            .boxed())
+    }
+}
+
+fn invalid(message: String) -> BlobStoreError {
+    BlobStoreError::InvalidConfig(message)
+}
+
+fn unknown(store: &str, key: &str) -> BlobStoreError {
+    invalid(format!("unknown {} option {}", store, key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_local_source() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("docs/img")).unwrap();
+        std::fs::write(temp_dir.path().join("docs/readme.md"), "# hi").unwrap();
+        std::fs::write(temp_dir.path().join("docs/img/logo.png"), "png").unwrap();
+
+        let url = format!("file://{}", temp_dir.path().display());
+        let source = RemoteSource::open(&url, &BTreeMap::new()).unwrap();
+        let mut objects = source.list().await.unwrap();
+        objects.sort_by(|a, b| a.path.cmp(&b.path));
+        let paths: Vec<_> = objects.iter().map(|object| object.path.as_str()).collect();
+        assert_eq!(paths, ["docs/img/logo.png", "docs/readme.md"]);
+        assert_eq!(objects[1].size, 4);
+
+        let data: Vec<Bytes> = source
+            .stream("docs/readme.md")
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(data.concat(), b"# hi");
+
+        // Rewriting a file changes its fingerprint
+        std::fs::write(temp_dir.path().join("docs/readme.md"), "# hello").unwrap();
+        let changed = source.list().await.unwrap();
+        let readme = changed.iter().find(|o| o.path == "docs/readme.md").unwrap();
+        assert_ne!(readme.fingerprint, objects[1].fingerprint);
+    }
+
+    #[test]
+    fn test_source_urls() {
+        let options = BTreeMap::new();
+        assert!(RemoteSource::open("s3://bucket/some/prefix", &options).is_ok());
+        assert!(RemoteSource::open("webdav://dav.example.com/files", &options).is_ok());
+        assert!(RemoteSource::open("ftp://example.com/files", &options).is_err());
+        assert!(RemoteSource::open("not a url", &options).is_err());
+        assert!(RemoteSource::open("s3:///prefix", &options).is_err());
+        assert!(RemoteSource::open("file://relative/path", &options).is_err());
+        assert!(matches!(
+            RemoteSource::open("gs://site/www", &options),
+            Err(BlobStoreError::InvalidConfig(message)) if message.contains("HMAC")
+        ));
+        let hmac = BTreeMap::from(
+            ["access_key_id", "secret_access_key"].map(|key| (key.to_string(), "x".to_string())),
+        );
+        assert!(RemoteSource::open("gs://site/www", &hmac).is_ok());
+
+        let options = BTreeMap::from([("no_such_option".to_string(), "x".to_string())]);
+        assert!(matches!(
+            RemoteSource::open("s3://bucket", &options),
+            Err(BlobStoreError::InvalidConfig(message)) if message.contains("no_such_option")
+        ));
+    }
+}
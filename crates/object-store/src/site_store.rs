@@ -0,0 +1,116 @@
+//! Plain objects at chosen paths, for layouts read by tools other than jax.
+
+use bytes::Bytes;
+
+use crate::error::Result;
+use crate::storage::{ObjectStoreConfig, Storage};
+
+/// Object storage holding files at their own paths rather than as blobs, e.g.
+/// a static website an S3 bucket serves to a CDN.
+///
+/// On S3, objects keep the content type and cache control they were put
+/// with. Local directories don't; the web server serving one goes by file
+/// extension.
+#[derive(Debug, Clone)]
+pub struct SiteStore {
+    storage: Storage,
+}
+
+impl SiteStore {
+    /// Open object storage. S3 buckets must exist already.
+    pub async fn new(config: ObjectStoreConfig) -> Result<Self> {
+        Ok(Self {
+            storage: Storage::new(config).await?,
+        })
+    }
+
+    /// Put a file at a path, replacing any there.
+    pub async fn put(
+        &self,
+        path: &str,
+        data: impl Into<Bytes>,
+        content_type: &str,
+        cache_control: &str,
+    ) -> Result<()> {
+        self.storage
+            .put_object(path, data.into(), content_type, cache_control)
+            .await
+    }
+
+    /// Get the file at a path.
+    pub async fn get(&self, path: &str) -> Result<Option<Bytes>> {
+        self.storage.get_object(path).await
+    }
+
+    /// Delete the file at a path, if there is one.
+    pub async fn delete(&self, path: &str) -> Result<()> {
+        self.storage.delete_object(path).await
+    }
+
+    /// Paths of the files below a directory, at any depth.
+    pub async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        self.storage.list_objects(prefix).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_site_store() {
+        let store = SiteStore::new(ObjectStoreConfig::Memory).await.unwrap();
+        store
+            .put(
+                "site/index.html",
+                Bytes::from("<h1>hi</h1>"),
+                "text/html",
+                "no-cache",
+            )
+            .await
+            .unwrap();
+        store
+            .put(
+                "site/css/main.css",
+                Bytes::from("h1 {}"),
+                "text/css",
+                "no-cache",
+            )
+            .await
+            .unwrap();
+        store
+            .put("other.txt", Bytes::from("x"), "text/plain", "no-cache")
+            .await
+            .unwrap();
+
+        let mut paths = store.list("site").await.unwrap();
+        paths.sort();
+        assert_eq!(paths, ["site/css/main.css", "site/index.html"]);
+        assert_eq!(
+            store.get("site/index.html").await.unwrap().unwrap(),
+            Bytes::from("<h1>hi</h1>")
+        );
+
+        store.delete("site/index.html").await.unwrap();
+        store.delete("site/index.html").await.unwrap();
+        assert!(store.get("site/index.html").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_local_site_store() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store = SiteStore::new(ObjectStoreConfig::Local {
+            path: temp_dir.path().to_path_buf(),
+        })
+        .await
+        .unwrap();
+        store
+            .put("a/b.txt", Bytes::from("hello"), "text/plain", "no-cache")
+            .await
+            .unwrap();
+        assert_eq!(
+            std::fs::read(temp_dir.path().join("a/b.txt")).unwrap(),
+            b"hello"
+        );
+    }
+}
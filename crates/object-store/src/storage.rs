@@ -1,14 +1,15 @@
 //! Object storage backend abstraction (S3/MinIO/local filesystem/memory).
 
+use std::fmt;
 use std::path::PathBuf;
 use std::sync::Arc;
 
 use bytes::Bytes;
-use object_store::aws::AmazonS3Builder;
+use object_store::aws::{AmazonS3Builder, AwsAuthorizer, AwsCredential};
 use object_store::local::LocalFileSystem;
 use object_store::memory::InMemory;
 use object_store::path::Path as ObjectPath;
-use object_store::ObjectStore;
+use object_store::{Attribute, Attributes, ObjectStore, PutOptions};
 use serde::{Deserialize, Serialize};
 
 use crate::error::{BlobStoreError, Result};
@@ -42,15 +43,100 @@ pub enum ObjectStoreConfig {
     },
 }
 
+/// S3 storage class of a blob's data.
+///
+/// Blobs are written in the standard class. Colder classes cost less to keep
+/// and more to read; archive classes can't be read until the object is
+/// restored, which takes hours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageClass {
+    /// S3 Standard
+    #[default]
+    Standard,
+    /// S3 Standard-IA: readable right away
+    InfrequentAccess,
+    /// S3 Glacier Flexible Retrieval: restored before reading
+    Archive,
+    /// S3 Glacier Deep Archive: restored before reading, slower still
+    DeepArchive,
+}
+
+impl StorageClass {
+    /// Name stored in the metadata database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StorageClass::Standard => "standard",
+            StorageClass::InfrequentAccess => "infrequent_access",
+            StorageClass::Archive => "archive",
+            StorageClass::DeepArchive => "deep_archive",
+        }
+    }
+
+    /// Parse a name stored in the metadata database.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "standard" => Some(StorageClass::Standard),
+            "infrequent_access" => Some(StorageClass::InfrequentAccess),
+            "archive" => Some(StorageClass::Archive),
+            "deep_archive" => Some(StorageClass::DeepArchive),
+            _ => None,
+        }
+    }
+
+    /// Value of the `x-amz-storage-class` header.
+    fn s3_name(&self) -> &'static str {
+        match self {
+            StorageClass::Standard => "STANDARD",
+            StorageClass::InfrequentAccess => "STANDARD_IA",
+            StorageClass::Archive => "GLACIER",
+            StorageClass::DeepArchive => "DEEP_ARCHIVE",
+        }
+    }
+
+    /// Whether objects of this class have to be restored before reading.
+    pub fn needs_restore(&self) -> bool {
+        matches!(self, StorageClass::Archive | StorageClass::DeepArchive)
+    }
+
+    /// How long restoring an object of this class takes, with the standard
+    /// retrieval tier.
+    pub fn restore_time(&self) -> &'static str {
+        match self {
+            StorageClass::Standard | StorageClass::InfrequentAccess => "no time",
+            StorageClass::Archive => "3-5 hours",
+            StorageClass::DeepArchive => "up to 12 hours",
+        }
+    }
+}
+
+impl fmt::Display for StorageClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Where restoring an archived object stands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Restore {
+    /// A restored copy can be read
+    Ready,
+    /// The restore was requested and hasn't finished yet
+    InProgress,
+}
+
 /// Wrapper around different object storage backends.
 #[derive(Debug, Clone)]
 pub(crate) struct Storage {
     inner: Arc<dyn ObjectStore>,
+    /// Requests object_store doesn't make, for S3 storage
+    s3: Option<Arc<S3Objects>>,
 }
 
 impl Storage {
     /// Create a new storage backend from configuration.
     pub async fn new(config: ObjectStoreConfig) -> Result<Self> {
+        let mut s3 = None;
         let inner: Arc<dyn ObjectStore> = match &config {
             ObjectStoreConfig::Memory => Arc::new(InMemory::new()),
 
@@ -77,6 +163,17 @@ impl Storage {
                     .with_bucket_name(bucket)
                     .with_region(region.as_deref().unwrap_or("us-east-1"))
                     .with_allow_http(endpoint.starts_with("http://"));
+                s3 = Some(Arc::new(S3Objects {
+                    client: reqwest::Client::new(),
+                    bucket_url: format!("{}/{}", endpoint.trim_end_matches('/'), bucket),
+                    bucket: bucket.clone(),
+                    region: region.clone().unwrap_or_else(|| "us-east-1".to_string()),
+                    credential: AwsCredential {
+                        key_id: access_key.clone(),
+                        secret_key: secret_key.clone(),
+                        token: None,
+                    },
+                }));
 
                 let store: Arc<dyn ObjectStore> = Arc::new(
                     builder
@@ -112,7 +209,7 @@ impl Storage {
             }
         };
 
-        Ok(Self { inner })
+        Ok(Self { inner, s3 })
     }
 
     /// Build the object path for blob data.
@@ -166,6 +263,173 @@ impl Storage {
             Err(e) => Err(e.into()),
         }
     }
+
+    /// Whether blob data can be moved between storage classes, which only
+    /// S3 storage can.
+    pub fn supports_storage_classes(&self) -> bool {
+        self.s3.is_some()
+    }
+
+    /// Change the storage class of blob data. Archived data has to be
+    /// restored first.
+    pub async fn set_storage_class(&self, hash: &str, class: StorageClass) -> Result<()> {
+        let s3 = self
+            .s3
+            .as_ref()
+            .ok_or(BlobStoreError::StorageClassUnsupported)?;
+        s3.set_storage_class(&Self::data_path(hash), class).await
+    }
+
+    /// Restore archived blob data for `days`, or extend the restored copy's
+    /// stay if it's there already.
+    pub async fn restore(&self, hash: &str, days: u32) -> Result<Restore> {
+        let s3 = self
+            .s3
+            .as_ref()
+            .ok_or(BlobStoreError::StorageClassUnsupported)?;
+        s3.restore(&Self::data_path(hash), days).await
+    }
+
+    /// Put an object at any path, with the headers it is served with. Only
+    /// S3 keeps them; a directory's web server goes by file extension.
+    pub async fn put_object(
+        &self,
+        path: &str,
+        data: Bytes,
+        content_type: &str,
+        cache_control: &str,
+    ) -> Result<()> {
+        let mut attributes = Attributes::new();
+        if self.s3.is_some() {
+            attributes.insert(Attribute::ContentType, content_type.to_string().into());
+            attributes.insert(Attribute::CacheControl, cache_control.to_string().into());
+        }
+        let options = PutOptions {
+            attributes,
+            ..Default::default()
+        };
+        self.inner
+            .put_opts(&ObjectPath::from(path), data.into(), options)
+            .await?;
+        Ok(())
+    }
+
+    /// Get an object at any path.
+    pub async fn get_object(&self, path: &str) -> Result<Option<Bytes>> {
+        match self.inner.get(&ObjectPath::from(path)).await {
+            Ok(result) => Ok(Some(result.bytes().await?)),
+            Err(object_store::Error::NotFound { .. }) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Delete an object at any path, if it's there.
+    pub async fn delete_object(&self, path: &str) -> Result<()> {
+        match self.inner.delete(&ObjectPath::from(path)).await {
+            Ok(()) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Paths of the objects below a prefix, at any depth.
+    pub async fn list_objects(&self, prefix: &str) -> Result<Vec<String>> {
+        use futures::TryStreamExt;
+
+        let prefix = ObjectPath::from(prefix);
+        let items: Vec<_> = self.inner.list(Some(&prefix)).try_collect().await?;
+        Ok(items
+            .into_iter()
+            .map(|meta| meta.location.to_string())
+            .collect())
+    }
+}
+
+/// An S3 bucket, for the requests object_store doesn't make: copying an
+/// object onto itself in another storage class and restoring archived
+/// objects. Requests are path-style, as object_store makes them for
+/// custom endpoints.
+#[derive(Debug)]
+struct S3Objects {
+    client: reqwest::Client,
+    /// Endpoint URL followed by the bucket name
+    bucket_url: String,
+    bucket: String,
+    region: String,
+    credential: AwsCredential,
+}
+
+impl S3Objects {
+    async fn set_storage_class(&self, path: &ObjectPath, class: StorageClass) -> Result<()> {
+        let request = self
+            .client
+            .put(format!("{}/{}", self.bucket_url, path))
+            .header("x-amz-copy-source", format!("{}/{}", self.bucket, path))
+            .header("x-amz-metadata-directive", "COPY")
+            .header("x-amz-storage-class", class.s3_name());
+        let (status, body) = self.send(request).await?;
+        if !status.is_success() {
+            return Err(Self::error(status, &body));
+        }
+        Ok(())
+    }
+
+    async fn restore(&self, path: &ObjectPath, days: u32) -> Result<Restore> {
+        let body = format!(
+            "<RestoreRequest><Days>{}</Days>\
+             <GlacierJobParameters><Tier>Standard</Tier></GlacierJobParameters>\
+             </RestoreRequest>",
+            days
+        );
+        let request = self
+            .client
+            .post(format!("{}/{}?restore", self.bucket_url, path))
+            .body(body);
+        let (status, body) = self.send(request).await?;
+        match status.as_u16() {
+            // Restored already; the copy stays for `days` from now
+            200 => Ok(Restore::Ready),
+            202 => Ok(Restore::InProgress),
+            409 if error_code(&body) == Some("RestoreAlreadyInProgress") => Ok(Restore::InProgress),
+            _ => Err(Self::error(status, &body)),
+        }
+    }
+
+    /// Sign and send a request, returning its status and body.
+    async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<(reqwest::StatusCode, String)> {
+        let mut request = request
+            .build()
+            .map_err(|e| BlobStoreError::S3Request(e.to_string()))?;
+        AwsAuthorizer::new(&self.credential, "s3", &self.region).authorize(&mut request, None);
+        let response = self
+            .client
+            .execute(request)
+            .await
+            .map_err(|e| BlobStoreError::S3Request(e.to_string()))?;
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| BlobStoreError::S3Request(e.to_string()))?;
+        Ok((status, body))
+    }
+
+    fn error(status: reqwest::StatusCode, body: &str) -> BlobStoreError {
+        BlobStoreError::S3Request(format!(
+            "{}: {}",
+            status,
+            error_code(body).unwrap_or("no error code")
+        ))
+    }
+}
+
+/// The `<Code>` of an S3 error response.
+fn error_code(body: &str) -> Option<&str> {
+    let start = body.find("<Code>")? + "<Code>".len();
+    let end = start + body[start..].find("</Code>")?;
+    Some(&body[start..end])
 }
 
 #[cfg(test)]
@@ -174,6 +438,7 @@ impl Storage {
     pub fn memory() -> Self {
         Self {
             inner: Arc::new(InMemory::new()),
+            s3: None,
         }
     }
 
@@ -278,6 +543,42 @@ mod tests {
         assert!(file_path.exists());
     }
 
+    #[test]
+    fn test_storage_class_names() {
+        for class in [
+            StorageClass::Standard,
+            StorageClass::InfrequentAccess,
+            StorageClass::Archive,
+            StorageClass::DeepArchive,
+        ] {
+            assert_eq!(StorageClass::parse(class.as_str()), Some(class));
+        }
+        assert_eq!(StorageClass::parse("glacier"), None);
+        assert_eq!(StorageClass::InfrequentAccess.s3_name(), "STANDARD_IA");
+        assert!(!StorageClass::InfrequentAccess.needs_restore());
+        assert!(StorageClass::DeepArchive.needs_restore());
+    }
+
+    #[test]
+    fn test_s3_error_code() {
+        let body = "<?xml version=\"1.0\"?><Error><Code>RestoreAlreadyInProgress</Code>\
+                    <Message>Object restore is already in progress</Message></Error>";
+        assert_eq!(error_code(body), Some("RestoreAlreadyInProgress"));
+        assert_eq!(error_code(""), None);
+    }
+
+    #[tokio::test]
+    async fn test_storage_classes_need_s3() {
+        let storage = Storage::memory();
+        assert!(!storage.supports_storage_classes());
+        assert!(matches!(
+            storage
+                .set_storage_class("abc123", StorageClass::Archive)
+                .await,
+            Err(BlobStoreError::StorageClassUnsupported)
+        ));
+    }
+
     #[tokio::test]
     async fn test_outboard_storage() {
         let storage = Storage::memory();